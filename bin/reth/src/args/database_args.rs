@@ -1,6 +1,8 @@
 //! clap [Args](clap::Args) for database configuration
 
+use crate::args::utils::parse_byte_size;
 use clap::Args;
+use reth_db::{DatabaseEnvConfig, DatabaseSyncMode};
 use reth_interfaces::db::LogLevel;
 
 /// Parameters for database configuration
@@ -10,6 +12,55 @@ pub struct DatabaseArgs {
     /// Database logging level. Levels higher than "notice" require a debug build.
     #[arg(long = "db.log-level", value_enum)]
     pub log_level: Option<LogLevel>,
+
+    /// Maximum size of the database file, e.g. `4TB`. Overrides the value of the config file.
+    #[arg(long = "db.max-size", value_name = "SIZE", value_parser = parse_byte_size)]
+    pub max_size: Option<usize>,
+
+    /// Number of bytes the database file grows by when it's full, e.g. `4GB`. Overrides the value
+    /// of the config file.
+    #[arg(long = "db.growth-step", value_name = "SIZE", value_parser = parse_byte_size)]
+    pub growth_step: Option<usize>,
+
+    /// Maximum number of concurrent read transactions. Overrides the value of the config file.
+    #[arg(long = "db.max-readers", value_name = "COUNT")]
+    pub max_readers: Option<u64>,
+
+    /// How the database is flushed to disk on commit, one of "durable", "no-meta-sync",
+    /// "safe-no-sync" or "utterly-no-sync". Modes other than "durable" trade durability for write
+    /// performance. Overrides the value of the config file.
+    #[arg(long = "db.sync-mode", value_name = "MODE")]
+    pub sync_mode: Option<DatabaseSyncMode>,
+
+    /// Enables OS read-ahead, which speeds up linear scans but slows down random access.
+    #[arg(long = "db.read-ahead")]
+    pub read_ahead: bool,
+}
+
+impl DatabaseArgs {
+    /// Returns the database configuration of the arguments, applied to the defaults.
+    pub fn database_config(&self) -> DatabaseEnvConfig {
+        self.apply(DatabaseEnvConfig::default())
+    }
+
+    /// Applies the arguments that are set to the database configuration, usually loaded from the
+    /// config file.
+    pub fn apply(&self, mut config: DatabaseEnvConfig) -> DatabaseEnvConfig {
+        if let Some(max_size) = self.max_size {
+            config.max_size = max_size;
+        }
+        if let Some(growth_step) = self.growth_step {
+            config.growth_step = growth_step;
+        }
+        if let Some(max_readers) = self.max_readers {
+            config.max_readers = max_readers;
+        }
+        if let Some(sync_mode) = self.sync_mode {
+            config.sync_mode = sync_mode;
+        }
+        config.read_ahead |= self.read_ahead;
+        config
+    }
 }
 
 #[cfg(test)]
@@ -29,5 +80,31 @@ mod tests {
         let default_args = DatabaseArgs::default();
         let args = CommandParser::<DatabaseArgs>::parse_from(["reth"]).args;
         assert_eq!(args, default_args);
+        assert_eq!(args.apply(DatabaseEnvConfig::default()), DatabaseEnvConfig::default());
+    }
+
+    #[test]
+    fn test_parse_database_env_args() {
+        let args = CommandParser::<DatabaseArgs>::parse_from([
+            "reth",
+            "--db.max-size",
+            "1TB",
+            "--db.max-readers",
+            "1024",
+            "--db.sync-mode",
+            "safe-no-sync",
+            "--db.read-ahead",
+        ])
+        .args;
+
+        let config = args.apply(DatabaseEnvConfig::default());
+        assert_eq!(config.max_size, 1 << 40);
+        assert_eq!(config.growth_step, DatabaseEnvConfig::default().growth_step);
+        assert_eq!(config.max_readers, 1024);
+        assert_eq!(config.sync_mode, DatabaseSyncMode::SafeNoSync);
+        assert!(config.read_ahead);
+
+        assert!(CommandParser::<DatabaseArgs>::try_parse_from(["reth", "--db.sync-mode", "fast"])
+            .is_err());
     }
 }
@@ -1,12 +1,12 @@
 //! clap [Args](clap::Args) for Dev testnet configuration
 
-use std::time::Duration;
+use std::{path::PathBuf, time::Duration};
 
 use clap::Args;
 use humantime::parse_duration;
 
 /// Parameters for Dev testnet configuration
-#[derive(Debug, Args, PartialEq, Default, Clone, Copy)]
+#[derive(Debug, Args, PartialEq, Default, Clone)]
 #[clap(next_help_heading = "Dev testnet")]
 pub struct DevArgs {
     /// Start the node in dev mode
@@ -39,6 +39,14 @@ pub struct DevArgs {
         verbatim_doc_comment
     )]
     pub block_time: Option<Duration>,
+
+    /// The path to the secret key of the signer that seals the blocks, if the chain uses the
+    /// Clique proof-of-authority consensus.
+    ///
+    /// The node seals the blocks of the signer with this key, also without `--dev`, and serves
+    /// them to its peers. A new key is generated if the file doesn't exist.
+    #[arg(long = "dev.signer-key", value_name = "PATH", help_heading = "Dev testnet")]
+    pub signer_key: Option<PathBuf>,
 }
 
 #[cfg(test)]
@@ -56,13 +64,27 @@ mod tests {
     #[test]
     fn test_parse_dev_args() {
         let args = CommandParser::<DevArgs>::parse_from(["reth"]).args;
-        assert_eq!(args, DevArgs { dev: false, block_max_transactions: None, block_time: None });
+        assert_eq!(
+            args,
+            DevArgs {
+                dev: false,
+                block_max_transactions: None,
+                block_time: None,
+                signer_key: None
+            }
+        );
 
         let args = CommandParser::<DevArgs>::parse_from(["reth", "--dev"]).args;
-        assert_eq!(args, DevArgs { dev: true, block_max_transactions: None, block_time: None });
+        assert_eq!(
+            args,
+            DevArgs { dev: true, block_max_transactions: None, block_time: None, signer_key: None }
+        );
 
         let args = CommandParser::<DevArgs>::parse_from(["reth", "--auto-mine"]).args;
-        assert_eq!(args, DevArgs { dev: true, block_max_transactions: None, block_time: None });
+        assert_eq!(
+            args,
+            DevArgs { dev: true, block_max_transactions: None, block_time: None, signer_key: None }
+        );
 
         let args = CommandParser::<DevArgs>::parse_from([
             "reth",
@@ -71,7 +93,15 @@ mod tests {
             "2",
         ])
         .args;
-        assert_eq!(args, DevArgs { dev: true, block_max_transactions: Some(2), block_time: None });
+        assert_eq!(
+            args,
+            DevArgs {
+                dev: true,
+                block_max_transactions: Some(2),
+                block_time: None,
+                signer_key: None
+            }
+        );
 
         let args =
             CommandParser::<DevArgs>::parse_from(["reth", "--dev", "--dev.block-time", "1s"]).args;
@@ -80,11 +110,19 @@ mod tests {
             DevArgs {
                 dev: true,
                 block_max_transactions: None,
-                block_time: Some(std::time::Duration::from_secs(1))
+                block_time: Some(std::time::Duration::from_secs(1)),
+                signer_key: None,
             }
         );
     }
 
+    #[test]
+    fn test_parse_dev_signer_key() {
+        let args =
+            CommandParser::<DevArgs>::parse_from(["reth", "--dev", "--dev.signer-key", "key"]).args;
+        assert_eq!(args.signer_key, Some(PathBuf::from("key")));
+    }
+
     #[test]
     fn test_parse_dev_args_conflicts() {
         let args = CommandParser::<DevArgs>::try_parse_from([
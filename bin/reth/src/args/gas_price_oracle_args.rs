@@ -25,13 +25,19 @@ pub struct GasPriceOracleArgs {
     /// The percentile of gas prices to use for the estimate
     #[arg(long = "gpo.percentile", default_value_t = DEFAULT_GAS_PRICE_PERCENTILE)]
     pub percentile: u32,
+
+    /// Gas price to recommend if there are no recent transactions to sample, e.g. on
+    /// low-activity chains [default: 1 gwei]
+    #[arg(long = "gpo.default")]
+    pub default: Option<u64>,
 }
 
 impl GasPriceOracleArgs {
     /// Returns a [GasPriceOracleConfig] from the arguments.
     pub fn gas_price_oracle_config(&self) -> GasPriceOracleConfig {
-        let Self { blocks, ignore_price, max_price, percentile } = self;
+        let Self { blocks, ignore_price, max_price, percentile, default } = self;
         GasPriceOracleConfig {
+            default: default.map(U256::from),
             max_price: Some(U256::from(*max_price)),
             ignore_price: Some(U256::from(*ignore_price)),
             percentile: *percentile,
@@ -48,6 +54,7 @@ impl Default for GasPriceOracleArgs {
             ignore_price: DEFAULT_IGNORE_GAS_PRICE.to(),
             max_price: DEFAULT_MAX_GAS_PRICE.to(),
             percentile: DEFAULT_GAS_PRICE_PERCENTILE,
+            default: None,
         }
     }
 }
@@ -73,10 +80,18 @@ mod tests {
                 ignore_price: DEFAULT_IGNORE_GAS_PRICE.to(),
                 max_price: DEFAULT_MAX_GAS_PRICE.to(),
                 percentile: DEFAULT_GAS_PRICE_PERCENTILE,
+                default: None,
             }
         );
     }
 
+    #[test]
+    fn test_parse_gpo_default() {
+        let args =
+            CommandParser::<GasPriceOracleArgs>::parse_from(["reth", "--gpo.default", "100"]).args;
+        assert_eq!(args.gas_price_oracle_config().default, Some(U256::from(100)));
+    }
+
     #[test]
     fn gpo_args_default_sanity_test() {
         let default_args = GasPriceOracleArgs::default();
@@ -51,6 +51,22 @@ pub use dev_args::DevArgs;
 mod pruning_args;
 pub use pruning_args::PruningArgs;
 
+/// SnapshotArgs for offloading snapshots to object storage
+mod snapshot_args;
+pub use snapshot_args::SnapshotArgs;
+
+/// TreeArgs for configuring the blockchain tree
+mod tree_args;
+pub use tree_args::TreeArgs;
+
+/// StateCacheArgs for configuring the cache of the latest state
+mod state_cache_args;
+pub use state_cache_args::StateCacheArgs;
+
+/// OverrideArgs for overriding the hardfork activation of the chain
+mod override_args;
+pub use override_args::OverrideArgs;
+
 /// RollupArgs for configuring the op-reth rollup
 #[cfg(feature = "optimism")]
 mod rollup_args;
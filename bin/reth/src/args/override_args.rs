@@ -0,0 +1,121 @@
+//! clap [Args](clap::Args) for hardfork activation overrides
+
+use clap::Args;
+use reth_primitives::{ChainSpec, ForkCondition, Hardfork};
+use std::sync::Arc;
+
+/// The timestamp-based hardforks, in activation order.
+const TIMESTAMP_HARDFORKS: [Hardfork; 2] = [Hardfork::Shanghai, Hardfork::Cancun];
+
+/// Parameters for overriding the hardfork activation of the chain
+#[derive(Debug, Args, PartialEq, Eq, Default, Clone, Copy)]
+#[clap(next_help_heading = "Hardfork overrides")]
+pub struct OverrideArgs {
+    /// Overrides the activation timestamp of the Shanghai hardfork.
+    #[arg(long = "override.shanghai", value_name = "TIMESTAMP")]
+    pub shanghai: Option<u64>,
+
+    /// Overrides the activation timestamp of the Cancun hardfork.
+    #[arg(long = "override.cancun", value_name = "TIMESTAMP")]
+    pub cancun: Option<u64>,
+}
+
+impl OverrideArgs {
+    /// Returns true if no hardfork activation is overridden.
+    pub fn is_empty(&self) -> bool {
+        self.shanghai.is_none() && self.cancun.is_none()
+    }
+
+    /// Applies the overrides to the chain spec.
+    ///
+    /// Returns an error if the overridden hardforks activate out of order, or before the merge.
+    pub fn apply(&self, chain: Arc<ChainSpec>) -> eyre::Result<Arc<ChainSpec>> {
+        if self.is_empty() {
+            return Ok(chain)
+        }
+
+        let mut chain = (*chain).clone();
+        for (fork, timestamp) in
+            [(Hardfork::Shanghai, self.shanghai), (Hardfork::Cancun, self.cancun)]
+        {
+            if let Some(timestamp) = timestamp {
+                chain.set_fork(fork, ForkCondition::Timestamp(timestamp));
+            }
+        }
+        // the genesis header depends on the hardforks that are active at genesis
+        chain.genesis_hash = None;
+
+        validate_timestamp_hardforks(&chain)?;
+        Ok(Arc::new(chain))
+    }
+}
+
+/// Validates that the timestamp-based hardforks of the chain activate after the merge, and in
+/// order.
+fn validate_timestamp_hardforks(chain: &ChainSpec) -> eyre::Result<()> {
+    if chain.fork(Hardfork::Paris).ttd().is_none() &&
+        TIMESTAMP_HARDFORKS.iter().any(|fork| chain.fork(*fork).as_timestamp().is_some())
+    {
+        eyre::bail!("timestamp-based hardforks require the {} hardfork", Hardfork::Paris)
+    }
+
+    for forks in TIMESTAMP_HARDFORKS.windows(2) {
+        let (previous, fork) = (forks[0], forks[1]);
+        let Some(timestamp) = chain.fork(fork).as_timestamp() else { continue };
+        match chain.fork(previous).as_timestamp() {
+            Some(previous_timestamp) if previous_timestamp <= timestamp => {}
+            _ => eyre::bail!(
+                "the {fork} hardfork at timestamp {timestamp} activates before the {previous} hardfork"
+            ),
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+    use reth_primitives::{DEV, MAINNET};
+
+    /// A helper type to parse Args more easily
+    #[derive(Parser)]
+    struct CommandParser<T: Args> {
+        #[clap(flatten)]
+        args: T,
+    }
+
+    #[test]
+    fn override_args_default_sanity_test() {
+        let args = CommandParser::<OverrideArgs>::parse_from(["reth"]).args;
+        assert_eq!(args, OverrideArgs::default());
+        assert!(args.is_empty());
+        assert!(Arc::ptr_eq(&args.apply(MAINNET.clone()).unwrap(), &MAINNET));
+    }
+
+    #[test]
+    fn override_cancun() {
+        let args =
+            CommandParser::<OverrideArgs>::parse_from(["reth", "--override.cancun", "1700000000"])
+                .args;
+        assert_eq!(args.cancun, Some(1700000000));
+
+        let chain = args.apply(MAINNET.clone()).unwrap();
+        assert_eq!(chain.fork(Hardfork::Cancun), ForkCondition::Timestamp(1700000000));
+        assert_eq!(chain.fork_timestamps.cancun, Some(1700000000));
+        assert!(chain.is_cancun_active_at_timestamp(1700000000));
+        // the overrides don't change the genesis block of mainnet
+        assert_eq!(chain.genesis_hash(), MAINNET.genesis_hash());
+    }
+
+    #[test]
+    fn reject_out_of_order_overrides() {
+        let args = OverrideArgs { shanghai: Some(10), cancun: Some(5) };
+        assert!(args.apply(DEV.clone()).is_err());
+
+        // shanghai activates at timestamp 0 on the dev chain
+        let args = OverrideArgs { cancun: Some(5), ..Default::default() };
+        assert!(args.apply(DEV.clone()).is_ok());
+    }
+}
@@ -6,10 +6,19 @@ use clap::{
     builder::{RangedU64ValueParser, TypedValueParser},
     Arg, Args, Command,
 };
-use reth_primitives::constants::{
-    ETHEREUM_BLOCK_GAS_LIMIT, MAXIMUM_EXTRA_DATA_SIZE, SLOT_DURATION,
+use eyre::Context;
+use humantime::parse_duration;
+use reth_payload_relay::{
+    BeaconChainConfig, BuilderSigner, RelayConfig, Url, DEFAULT_SUBMISSION_INTERVAL,
 };
-use std::{borrow::Cow, ffi::OsStr, time::Duration};
+use reth_primitives::{
+    constants::{
+        eip4844::MAX_BLOBS_PER_BLOCK, ETHEREUM_BLOCK_GAS_LIMIT, MAXIMUM_EXTRA_DATA_SIZE,
+        SLOT_DURATION,
+    },
+    ChainSpec, B256,
+};
+use std::{borrow::Cow, ffi::OsStr, path::PathBuf, str::FromStr, time::Duration};
 
 /// Parameters for configuring the Payload Builder
 #[derive(Debug, Args, PartialEq)]
@@ -35,6 +44,38 @@ pub struct PayloadBuilderArgs {
     #[arg(long = "builder.max-tasks", default_value = "3", value_parser = RangedU64ValueParser::<usize>::new().range(1..))]
     pub max_payload_tasks: usize,
 
+    /// Maximum number of blobs to include in built blocks.
+    #[arg(long = "builder.max-blobs", default_value_t = MAX_BLOBS_PER_BLOCK as u64, value_parser = RangedU64ValueParser::<u64>::new().range(..=MAX_BLOBS_PER_BLOCK as u64))]
+    pub max_blobs_per_block: u64,
+
+    /// Wait for the payload build in progress when the consensus layer requests the payload,
+    /// instead of resolving with the best payload built so far.
+    ///
+    /// By default, an empty payload is returned if no payload has been built yet when the payload
+    /// is requested.
+    #[arg(long = "builder.wait-for-pending-build")]
+    pub wait_for_pending_build: bool,
+
+    /// Comma separated URLs of MEV-Boost relays to submit the built payloads to.
+    ///
+    /// The payloads are submitted for the proposers that are registered with the relays, and
+    /// signed with the key of `--builder.relay.secret-key`.
+    #[arg(
+        long = "builder.relays",
+        value_name = "URL",
+        value_delimiter = ',',
+        requires = "relay_secret_key"
+    )]
+    pub relays: Vec<Url>,
+
+    /// Path to a file with the hex encoded BLS secret key that signs the bids submitted to relays.
+    #[arg(long = "builder.relay.secret-key", value_name = "PATH")]
+    pub relay_secret_key: Option<PathBuf>,
+
+    /// The interval at which the best payload is submitted to the relays, if it improved.
+    #[arg(long = "builder.relay.interval", value_parser = parse_duration, default_value = "500ms", value_name = "DURATION")]
+    pub relay_interval: Duration,
+
     /// By default the pending block equals the latest block
     /// to save resources and not leak txs from the tx-pool,
     /// this flag enables computing of the pending block
@@ -56,12 +97,45 @@ impl Default for PayloadBuilderArgs {
             interval: Duration::from_secs(1),
             deadline: SLOT_DURATION,
             max_payload_tasks: 3,
+            max_blobs_per_block: MAX_BLOBS_PER_BLOCK as u64,
+            wait_for_pending_build: false,
+            relays: Vec::new(),
+            relay_secret_key: None,
+            relay_interval: DEFAULT_SUBMISSION_INTERVAL,
             #[cfg(feature = "optimism")]
             compute_pending_block: false,
         }
     }
 }
 
+impl PayloadBuilderArgs {
+    /// Returns the configuration of the relay submissions and the signer of the bids, if relays
+    /// are configured.
+    pub fn relay_config(
+        &self,
+        chain_spec: &ChainSpec,
+    ) -> eyre::Result<Option<(RelayConfig, BuilderSigner)>> {
+        if self.relays.is_empty() {
+            return Ok(None)
+        }
+        let beacon_chain = BeaconChainConfig::for_chain(chain_spec.chain).ok_or_else(|| {
+            eyre::eyre!("relay submissions are not supported for chain {}", chain_spec.chain)
+        })?;
+        let path = self.relay_secret_key.as_ref().ok_or_else(|| {
+            eyre::eyre!("--builder.relay.secret-key is required for relay submissions")
+        })?;
+        let secret_key = std::fs::read_to_string(path)
+            .wrap_err_with(|| format!("failed to read relay secret key: {}", path.display()))?;
+        let secret_key = B256::from_str(secret_key.trim())
+            .wrap_err_with(|| format!("invalid relay secret key: {}", path.display()))?;
+        let signer = BuilderSigner::new(secret_key, beacon_chain.genesis_fork_version)?;
+
+        let config = RelayConfig::new(self.relays.clone(), beacon_chain)
+            .with_submission_interval(self.relay_interval);
+        Ok(Some((config, signer)))
+    }
+}
+
 impl PayloadBuilderConfig for PayloadBuilderArgs {
     fn extradata(&self) -> Cow<'_, str> {
         self.extradata.as_str().into()
@@ -83,6 +157,14 @@ impl PayloadBuilderConfig for PayloadBuilderArgs {
         self.max_payload_tasks
     }
 
+    fn max_blobs_per_block(&self) -> u64 {
+        self.max_blobs_per_block
+    }
+
+    fn wait_for_pending_build(&self) -> bool {
+        self.wait_for_pending_build
+    }
+
     #[cfg(feature = "optimism")]
     fn compute_pending_block(&self) -> bool {
         self.compute_pending_block
@@ -136,6 +218,30 @@ mod tests {
         assert_eq!(args.max_payload_tasks, 1)
     }
 
+    #[test]
+    fn test_args_with_relays() {
+        let args = CommandParser::<PayloadBuilderArgs>::parse_from([
+            "reth",
+            "--builder.relays",
+            "https://relay1.example.com,https://relay2.example.com",
+            "--builder.relay.secret-key",
+            "builder.key",
+            "--builder.relay.interval",
+            "1s",
+        ])
+        .args;
+        assert_eq!(args.relays.len(), 2);
+        assert_eq!(args.relay_interval, Duration::from_secs(1));
+
+        // the secret key is required
+        assert!(CommandParser::<PayloadBuilderArgs>::try_parse_from([
+            "reth",
+            "--builder.relays",
+            "https://relay.example.com"
+        ])
+        .is_err());
+    }
+
     #[test]
     fn test_args_with_invalid_max_tasks() {
         assert!(CommandParser::<PayloadBuilderArgs>::try_parse_from([
@@ -146,6 +252,16 @@ mod tests {
         .is_err());
     }
 
+    #[test]
+    fn test_args_with_invalid_max_blobs() {
+        assert!(CommandParser::<PayloadBuilderArgs>::try_parse_from([
+            "reth",
+            "--builder.max-blobs",
+            "7"
+        ])
+        .is_err());
+    }
+
     #[test]
     fn test_default_extradata() {
         let extradata = default_extradata();
@@ -21,18 +21,20 @@ use reth_network_api::{NetworkInfo, Peers};
 use reth_node_api::EngineTypes;
 use reth_provider::{
     AccountReader, BlockReaderIdExt, CanonStateSubscriptions, ChainSpecProvider, ChangeSetReader,
-    EvmEnvProvider, HeaderProvider, StateProviderFactory,
+    EvmEnvProvider, HeaderProvider, LogIndexReader, PruneCheckpointReader, StageCheckpointReader,
+    StateProviderFactory, TransactionAddressIndexReader,
 };
 use reth_rpc::{
     eth::{cache::EthStateCacheConfig, gas_oracle::GasPriceOracleConfig, RPC_DEFAULT_GAS_CAP},
-    JwtError, JwtSecret,
+    JwtAuthValidator, JwtError, JwtSecret, JWT_MAX_IAT_DIFF,
 };
 use reth_rpc_builder::{
     auth::{AuthServerConfig, AuthServerHandle},
     constants,
     error::RpcError,
-    EthConfig, IpcServerBuilder, RethRpcModule, RpcModuleBuilder, RpcModuleConfig,
-    RpcModuleSelection, RpcServerConfig, RpcServerHandle, ServerBuilder, TransportRpcModuleConfig,
+    BatchRequestConfig, EthConfig, IpcServerBuilder, RateLimitConfig, RethRpcModule,
+    RpcModuleBuilder, RpcModuleConfig, RpcModuleSelection, RpcServerConfig, RpcServerHandle,
+    ServerBuilder, TransportRpcModuleConfig,
 };
 use reth_rpc_engine_api::{EngineApi, EngineApiServer};
 use reth_tasks::TaskSpawner;
@@ -40,9 +42,11 @@ use reth_transaction_pool::TransactionPool;
 use std::{
     ffi::OsStr,
     net::{IpAddr, Ipv4Addr, SocketAddr},
+    num::NonZeroU32,
     path::PathBuf,
+    time::Duration,
 };
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
 
 /// Default max number of subscriptions per connection.
 pub(crate) const RPC_DEFAULT_MAX_SUBS_PER_CONN: u32 = 1024;
@@ -79,6 +83,19 @@ pub struct RpcServerArgs {
     #[arg(long = "http.corsdomain")]
     pub http_corsdomain: Option<String>,
 
+    /// Maximum number of HTTP server connections, overrides `--rpc-max-connections`.
+    #[arg(long = "http.max-connections", value_name = "COUNT")]
+    pub http_max_connections: Option<MaxU32>,
+
+    /// Maximum gas limit for `eth_call` and call tracing RPC methods of the HTTP server,
+    /// overrides `--rpc-gas-cap`.
+    #[arg(
+        long = "http.gas-cap",
+        value_name = "GAS_CAP",
+        value_parser = RangedU64ValueParser::<u64>::new().range(1..)
+    )]
+    pub http_gas_cap: Option<u64>,
+
     /// Enable the WS-RPC server
     #[arg(long)]
     pub ws: bool,
@@ -99,6 +116,23 @@ pub struct RpcServerArgs {
     #[arg(long = "ws.api", value_parser = RpcModuleSelectionValueParser::default())]
     pub ws_api: Option<RpcModuleSelection>,
 
+    /// Maximum number of WS server connections, overrides `--rpc-max-connections`.
+    ///
+    /// If the WS server shares the port of the HTTP server, the HTTP limit applies.
+    #[arg(long = "ws.max-connections", value_name = "COUNT")]
+    pub ws_max_connections: Option<MaxU32>,
+
+    /// Maximum gas limit for `eth_call` and call tracing RPC methods of the WS server,
+    /// overrides `--rpc-gas-cap`.
+    ///
+    /// If the WS server shares the port of the HTTP server, both gas caps must be equal.
+    #[arg(
+        long = "ws.gas-cap",
+        value_name = "GAS_CAP",
+        value_parser = RangedU64ValueParser::<u64>::new().range(1..)
+    )]
+    pub ws_gas_cap: Option<u64>,
+
     /// Disable the IPC-RPC  server
     #[arg(long)]
     pub ipcdisable: bool,
@@ -107,6 +141,23 @@ pub struct RpcServerArgs {
     #[arg(long, default_value_t = constants::DEFAULT_IPC_ENDPOINT.to_string())]
     pub ipcpath: String,
 
+    /// Rpc Modules to be configured for the IPC server
+    #[arg(long = "ipc.api", value_parser = RpcModuleSelectionValueParser::default())]
+    pub ipc_api: Option<RpcModuleSelection>,
+
+    /// Maximum number of IPC server connections, overrides `--rpc-max-connections`.
+    #[arg(long = "ipc.max-connections", value_name = "COUNT")]
+    pub ipc_max_connections: Option<MaxU32>,
+
+    /// Maximum gas limit for `eth_call` and call tracing RPC methods of the IPC server,
+    /// overrides `--rpc-gas-cap`.
+    #[arg(
+        long = "ipc.gas-cap",
+        value_name = "GAS_CAP",
+        value_parser = RangedU64ValueParser::<u64>::new().range(1..)
+    )]
+    pub ipc_gas_cap: Option<u64>,
+
     /// Auth server address to listen on
     #[arg(long = "authrpc.addr", default_value_t = IpAddr::V4(Ipv4Addr::LOCALHOST))]
     pub auth_addr: IpAddr,
@@ -115,6 +166,22 @@ pub struct RpcServerArgs {
     #[arg(long = "authrpc.port", default_value_t = constants::DEFAULT_AUTH_PORT)]
     pub auth_port: u16,
 
+    /// Rpc Modules to be configured for the auth server, in addition to the `engine_` namespace.
+    ///
+    /// If the `eth` namespace is selected, it replaces the subset of `eth_` methods that is
+    /// always available on the auth server.
+    #[arg(long = "authrpc.api", value_parser = RpcModuleSelectionValueParser::default())]
+    pub auth_api: Option<RpcModuleSelection>,
+
+    /// Maximum gas limit for `eth_call` and call tracing RPC methods of the auth server,
+    /// overrides `--rpc-gas-cap`.
+    #[arg(
+        long = "authrpc.gas-cap",
+        value_name = "GAS_CAP",
+        value_parser = RangedU64ValueParser::<u64>::new().range(1..)
+    )]
+    pub auth_gas_cap: Option<u64>,
+
     /// Path to a JWT secret to use for the authenticated engine-API RPC server.
     ///
     /// This will enforce JWT authentication for all requests coming from the consensus layer.
@@ -124,6 +191,29 @@ pub struct RpcServerArgs {
     #[arg(long = "authrpc.jwtsecret", value_name = "PATH", global = true, required = false)]
     pub auth_jwtsecret: Option<PathBuf>,
 
+    /// Paths to additional JWT secrets that are accepted by the authenticated engine-API RPC
+    /// server, e.g. while the secret of `--authrpc.jwtsecret` is rotated.
+    ///
+    /// All secrets are reloaded from their files on `SIGHUP`. The secret of
+    /// `--authrpc.jwtsecret` is only reloaded if its path is provided.
+    #[arg(
+        long = "authrpc.jwtsecret.additional",
+        value_name = "PATH",
+        value_delimiter = ',',
+        global = true,
+        required = false
+    )]
+    pub auth_additional_jwtsecrets: Vec<PathBuf>,
+
+    /// Maximum difference in seconds between the `iat` (issued-at) claim of a JWT of the
+    /// authenticated engine-API RPC server and the current time.
+    #[arg(
+        long = "authrpc.jwt.iat-leeway",
+        value_name = "SECONDS",
+        default_value_t = JWT_MAX_IAT_DIFF.as_secs()
+    )]
+    pub auth_jwt_iat_leeway: u64,
+
     /// Hex encoded JWT secret to authenticate the regular RPC server(s), see `--http.api` and
     /// `--ws.api`.
     ///
@@ -148,6 +238,20 @@ pub struct RpcServerArgs {
     #[arg(long, value_name = "COUNT", default_value_t = RPC_DEFAULT_MAX_CONNECTIONS.into())]
     pub rpc_max_connections: MaxU32,
 
+    /// Maximum number of calls per second of all HTTP, WS and IPC connections. (0 = no limit)
+    ///
+    /// Every call of a batch request counts towards the limit.
+    #[arg(long, value_name = "COUNT", default_value_t = 0)]
+    pub rpc_max_requests_per_second: u32,
+
+    /// Maximum number of calls per second of a single HTTP, WS or IPC connection. (0 = no limit)
+    #[arg(long, value_name = "COUNT", default_value_t = 0)]
+    pub rpc_max_connection_requests_per_second: u32,
+
+    /// Maximum number of requests in a batch request for both HTTP and WS. (0 = no limit)
+    #[arg(long, value_name = "COUNT", default_value_t = 0)]
+    pub rpc_max_batch_size: u32,
+
     /// Maximum number of concurrent tracing requests.
     #[arg(long, value_name = "COUNT", default_value_t = constants::DEFAULT_MAX_TRACING_REQUESTS)]
     pub rpc_max_tracing_requests: u32,
@@ -237,7 +341,15 @@ impl RpcServerArgs {
         Engine: EngineApiServer<EngineT>,
         Conf: RethNodeCommandConfig,
     {
-        let auth_config = self.auth_server_config(jwt_secret)?;
+        let auth_config = self.auth_server_config(jwt_secret.clone())?;
+        #[cfg(unix)]
+        if let Some(validator) = auth_config.jwt_validator() {
+            self.spawn_jwt_secrets_reload(
+                validator.clone(),
+                jwt_secret,
+                components.task_executor(),
+            )?;
+        }
 
         let module_config = self.transport_rpc_module_config();
         debug!(target: "reth::cli", http=?module_config.http(), ws=?module_config.ws(), "Using RPC module config");
@@ -248,6 +360,7 @@ impl RpcServerArgs {
             .with_network(components.network())
             .with_events(components.events())
             .with_executor(components.task_executor())
+            .with_evm_config(conf.evm_config())
             .build_with_auth_server(module_config, engine_api);
 
         let rpc_components = RethRpcComponents {
@@ -308,8 +421,12 @@ impl RpcServerArgs {
             + HeaderProvider
             + StateProviderFactory
             + EvmEnvProvider
+            + StageCheckpointReader
             + ChainSpecProvider
             + ChangeSetReader
+            + LogIndexReader
+            + TransactionAddressIndexReader
+            + PruneCheckpointReader
             + Clone
             + Unpin
             + 'static,
@@ -330,6 +447,54 @@ impl RpcServerArgs {
         .await
     }
 
+    /// Returns the secrets accepted by the auth server: the given secret of `--authrpc.jwtsecret`
+    /// and the secrets of `--authrpc.jwtsecret.additional`.
+    fn auth_jwt_secrets(&self, jwt_secret: JwtSecret) -> Result<Vec<JwtSecret>, JwtError> {
+        let mut secrets = vec![jwt_secret];
+        for path in &self.auth_additional_jwtsecrets {
+            debug!(target: "reth::cli", ?path, "Reading additional JWT auth secret file");
+            secrets.push(JwtSecret::from_file(path)?);
+        }
+        Ok(secrets)
+    }
+
+    /// Spawns a task that reloads the secrets of the given validator of the auth server on
+    /// `SIGHUP`.
+    ///
+    /// If no path is configured for `--authrpc.jwtsecret`, the given secret is kept. If a secret
+    /// can't be loaded, the current secrets are kept.
+    #[cfg(unix)]
+    fn spawn_jwt_secrets_reload(
+        &self,
+        validator: JwtAuthValidator,
+        jwt_secret: JwtSecret,
+        executor: impl TaskSpawner,
+    ) -> std::io::Result<()> {
+        use tokio::signal::unix::{signal, SignalKind};
+
+        let mut hangup = signal(SignalKind::hangup())?;
+        let args = self.clone();
+        executor.spawn(Box::pin(async move {
+            while hangup.recv().await.is_some() {
+                let secret = match args.auth_jwtsecret.as_ref() {
+                    Some(path) => JwtSecret::from_file(path),
+                    None => Ok(jwt_secret.clone()),
+                };
+                match secret.and_then(|secret| args.auth_jwt_secrets(secret)) {
+                    Ok(secrets) => {
+                        let count = secrets.len();
+                        info!(target: "reth::cli", count, "Reloaded JWT auth secrets");
+                        validator.set_secrets(secrets);
+                    }
+                    Err(err) => {
+                        warn!(target: "reth::cli", %err, "Failed to reload JWT auth secrets");
+                    }
+                }
+            }
+        }));
+        Ok(())
+    }
+
     /// Create Engine API server.
     pub async fn start_auth_server<Provider, Pool, Network, Tasks, EngineT>(
         &self,
@@ -344,8 +509,12 @@ impl RpcServerArgs {
         Provider: BlockReaderIdExt
             + ChainSpecProvider
             + EvmEnvProvider
+            + StageCheckpointReader
             + HeaderProvider
             + StateProviderFactory
+            + LogIndexReader
+            + TransactionAddressIndexReader
+            + PruneCheckpointReader
             + Clone
             + Unpin
             + 'static,
@@ -395,6 +564,7 @@ impl RethRpcConfig for RpcServerArgs {
             max_receipts: self.rpc_state_cache.max_receipts,
             max_envs: self.rpc_state_cache.max_envs,
             max_concurrent_db_requests: self.rpc_state_cache.max_concurrent_db_requests,
+            max_traced_blocks: self.rpc_state_cache.max_traced_blocks,
         }
     }
 
@@ -431,14 +601,40 @@ impl RethRpcConfig for RpcServerArgs {
         }
 
         if self.is_ipc_enabled() {
-            config = config.with_ipc(RpcModuleSelection::default_ipc_modules());
+            config = config.with_ipc(
+                self.ipc_api
+                    .clone()
+                    .unwrap_or_else(|| RpcModuleSelection::default_ipc_modules().into()),
+            );
+        }
+
+        if let Some(auth_api) = self.auth_api.clone() {
+            config = config.with_auth(auth_api);
+        }
+
+        if let Some(gas_cap) = self.http_gas_cap {
+            config = config.with_http_gas_cap(gas_cap);
+        }
+        if let Some(gas_cap) = self.ws_gas_cap {
+            config = config.with_ws_gas_cap(gas_cap);
+        }
+        if let Some(gas_cap) = self.ipc_gas_cap {
+            config = config.with_ipc_gas_cap(gas_cap);
+        }
+        if let Some(gas_cap) = self.auth_gas_cap {
+            config = config.with_auth_gas_cap(gas_cap);
         }
 
         config
     }
 
     fn http_ws_server_builder(&self) -> ServerBuilder {
+        let batch_config = match self.rpc_max_batch_size {
+            0 => BatchRequestConfig::Unlimited,
+            max => BatchRequestConfig::Limit(max),
+        };
         ServerBuilder::new()
+            .set_batch_request_config(batch_config)
             .max_connections(self.rpc_max_connections.get())
             .max_request_body_size(self.rpc_max_request_size_bytes())
             .max_response_body_size(self.rpc_max_response_size_bytes())
@@ -450,24 +646,35 @@ impl RethRpcConfig for RpcServerArgs {
             .max_subscriptions_per_connection(self.rpc_max_subscriptions_per_connection.get())
             .max_request_body_size(self.rpc_max_request_size_bytes())
             .max_response_body_size(self.rpc_max_response_size_bytes())
-            .max_connections(self.rpc_max_connections.get())
+            .max_connections(self.ipc_max_connections.unwrap_or(self.rpc_max_connections).get())
     }
 
     fn rpc_server_config(&self) -> RpcServerConfig {
-        let mut config = RpcServerConfig::default().with_jwt_secret(self.rpc_secret_key());
+        let mut config = RpcServerConfig::default()
+            .with_jwt_secret(self.rpc_secret_key())
+            .with_rate_limit(RateLimitConfig {
+                global: NonZeroU32::new(self.rpc_max_requests_per_second),
+                per_connection: NonZeroU32::new(self.rpc_max_connection_requests_per_second),
+            });
 
         if self.http {
             let socket_address = SocketAddr::new(self.http_addr, self.http_port);
             config = config
                 .with_http_address(socket_address)
-                .with_http(self.http_ws_server_builder())
+                .with_http(self.http_ws_server_builder().max_connections(
+                    self.http_max_connections.unwrap_or(self.rpc_max_connections).get(),
+                ))
                 .with_http_cors(self.http_corsdomain.clone())
                 .with_ws_cors(self.ws_allowed_origins.clone());
         }
 
         if self.ws {
             let socket_address = SocketAddr::new(self.ws_addr, self.ws_port);
-            config = config.with_ws_address(socket_address).with_ws(self.http_ws_server_builder());
+            config = config.with_ws_address(socket_address).with_ws(
+                self.http_ws_server_builder().max_connections(
+                    self.ws_max_connections.unwrap_or(self.rpc_max_connections).get(),
+                ),
+            );
         }
 
         if self.is_ipc_enabled() {
@@ -481,7 +688,16 @@ impl RethRpcConfig for RpcServerArgs {
     fn auth_server_config(&self, jwt_secret: JwtSecret) -> Result<AuthServerConfig, RpcError> {
         let address = SocketAddr::new(self.auth_addr, self.auth_port);
 
-        Ok(AuthServerConfig::builder(jwt_secret).socket_addr(address).build())
+        let secrets = self.auth_jwt_secrets(jwt_secret.clone()).map_err(|err| {
+            RpcError::Custom(format!("failed to load additional JWT secrets: {err}"))
+        })?;
+        let validator = JwtAuthValidator::with_secrets(secrets)
+            .with_max_iat_diff(Duration::from_secs(self.auth_jwt_iat_leeway));
+
+        Ok(AuthServerConfig::builder(jwt_secret)
+            .socket_addr(address)
+            .jwt_validator(validator)
+            .build())
     }
 
     fn auth_jwt_secret(&self, default_jwt_path: PathBuf) -> Result<JwtSecret, JwtError> {
@@ -507,21 +723,35 @@ impl Default for RpcServerArgs {
             http_port: constants::DEFAULT_HTTP_RPC_PORT,
             http_api: None,
             http_corsdomain: None,
+            http_max_connections: None,
+            http_gas_cap: None,
             ws: false,
             ws_addr: Ipv4Addr::LOCALHOST.into(),
             ws_port: constants::DEFAULT_WS_RPC_PORT,
             ws_allowed_origins: None,
             ws_api: None,
+            ws_max_connections: None,
+            ws_gas_cap: None,
             ipcdisable: false,
             ipcpath: constants::DEFAULT_IPC_ENDPOINT.to_string(),
+            ipc_api: None,
+            ipc_max_connections: None,
+            ipc_gas_cap: None,
             auth_addr: Ipv4Addr::LOCALHOST.into(),
             auth_port: constants::DEFAULT_AUTH_PORT,
+            auth_api: None,
+            auth_gas_cap: None,
             auth_jwtsecret: None,
+            auth_additional_jwtsecrets: Vec::new(),
+            auth_jwt_iat_leeway: JWT_MAX_IAT_DIFF.as_secs(),
             rpc_jwtsecret: None,
             rpc_max_request_size: RPC_DEFAULT_MAX_REQUEST_SIZE_MB.into(),
             rpc_max_response_size: RPC_DEFAULT_MAX_RESPONSE_SIZE_MB.into(),
             rpc_max_subscriptions_per_connection: RPC_DEFAULT_MAX_SUBS_PER_CONN.into(),
             rpc_max_connections: RPC_DEFAULT_MAX_CONNECTIONS.into(),
+            rpc_max_requests_per_second: 0,
+            rpc_max_connection_requests_per_second: 0,
+            rpc_max_batch_size: 0,
             rpc_max_tracing_requests: constants::DEFAULT_MAX_TRACING_REQUESTS,
             rpc_max_blocks_per_filter: constants::DEFAULT_MAX_BLOCKS_PER_FILTER.into(),
             rpc_max_logs_per_response: (constants::DEFAULT_MAX_LOGS_PER_RESPONSE as u64).into(),
@@ -687,6 +917,66 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_ipc_rpc_module_config() {
+        let args = CommandParser::<RpcServerArgs>::parse_from(["reth"]).args;
+        let config = args.transport_rpc_module_config();
+        assert_eq!(
+            config.ipc().cloned().unwrap().into_selection(),
+            RpcModuleSelection::default_ipc_modules()
+        );
+
+        let args =
+            CommandParser::<RpcServerArgs>::parse_from(["reth", "--ipc.api", "eth,debug"]).args;
+        let config = args.transport_rpc_module_config();
+        let expected = vec![RethRpcModule::Eth, RethRpcModule::Debug];
+        assert_eq!(config.ipc().cloned().unwrap().into_selection(), expected);
+    }
+
+    #[test]
+    fn test_auth_rpc_module_config() {
+        let args = CommandParser::<RpcServerArgs>::parse_from(["reth"]).args;
+        let config = args.transport_rpc_module_config();
+        assert_eq!(config.auth(), None);
+
+        let args =
+            CommandParser::<RpcServerArgs>::parse_from(["reth", "--authrpc.api", "eth,web3"]).args;
+        let config = args.transport_rpc_module_config();
+        let expected = vec![RethRpcModule::Eth, RethRpcModule::Web3];
+        assert_eq!(config.auth().cloned().unwrap().into_selection(), expected);
+    }
+
+    #[test]
+    fn test_transport_gas_caps() {
+        let args = CommandParser::<RpcServerArgs>::parse_from(["reth"]).args;
+        let config = args.transport_rpc_module_config();
+        assert_eq!(config.http_gas_cap(), None);
+        assert_eq!(config.ws_gas_cap(), None);
+        assert_eq!(config.ipc_gas_cap(), None);
+        assert_eq!(config.auth_gas_cap(), None);
+
+        let args = CommandParser::<RpcServerArgs>::parse_from([
+            "reth",
+            "--http.gas-cap",
+            "1000",
+            "--ws.gas-cap",
+            "2000",
+            "--ipc.gas-cap",
+            "3000",
+            "--authrpc.gas-cap",
+            "4000",
+        ])
+        .args;
+        let config = args.transport_rpc_module_config();
+        assert_eq!(config.http_gas_cap(), Some(1000));
+        assert_eq!(config.ws_gas_cap(), Some(2000));
+        assert_eq!(config.ipc_gas_cap(), Some(3000));
+        assert_eq!(config.auth_gas_cap(), Some(4000));
+
+        assert!(CommandParser::<RpcServerArgs>::try_parse_from(["reth", "--http.gas-cap", "0"])
+            .is_err());
+    }
+
     #[test]
     fn test_rpc_server_config() {
         let args = CommandParser::<RpcServerArgs>::parse_from([
@@ -1,7 +1,7 @@
 use clap::Args;
 use reth_rpc::eth::cache::{
     DEFAULT_BLOCK_CACHE_MAX_LEN, DEFAULT_CONCURRENT_DB_REQUESTS, DEFAULT_ENV_CACHE_MAX_LEN,
-    DEFAULT_RECEIPT_CACHE_MAX_LEN,
+    DEFAULT_RECEIPT_CACHE_MAX_LEN, DEFAULT_TRACE_CACHE_MAX_LEN,
 };
 
 /// Parameters to configure RPC state cache.
@@ -36,6 +36,15 @@ pub struct RpcStateCacheArgs {
         default_value_t = DEFAULT_CONCURRENT_DB_REQUESTS,
     )]
     pub max_concurrent_db_requests: usize,
+
+    /// Max number of blocks with cached `debug_traceTransaction` and `trace_block` results.
+    ///
+    /// The trace cache is disabled if 0.
+    #[arg(
+        long = "rpc-cache.max-traced-blocks",
+        default_value_t = DEFAULT_TRACE_CACHE_MAX_LEN,
+    )]
+    pub max_traced_blocks: u32,
 }
 
 impl Default for RpcStateCacheArgs {
@@ -45,6 +54,7 @@ impl Default for RpcStateCacheArgs {
             max_receipts: DEFAULT_RECEIPT_CACHE_MAX_LEN,
             max_envs: DEFAULT_ENV_CACHE_MAX_LEN,
             max_concurrent_db_requests: DEFAULT_CONCURRENT_DB_REQUESTS,
+            max_traced_blocks: DEFAULT_TRACE_CACHE_MAX_LEN,
         }
     }
 }
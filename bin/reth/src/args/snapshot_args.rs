@@ -0,0 +1,150 @@
+//! clap [Args](clap::Args) for offloading snapshots to object storage
+
+use crate::args::utils::parse_byte_size;
+use clap::Args;
+use reth_provider::providers::{
+    FsSnapshotStore, RemoteSnapshots, S3Config, S3SnapshotStore, SnapshotStore,
+};
+use std::{path::PathBuf, sync::Arc};
+
+/// Default maximum size of the local cache of offloaded snapshots, 64 gigabytes.
+const DEFAULT_CACHE_SIZE: usize = 64 * 1024 * 1024 * 1024;
+
+/// Parameters for offloading snapshots to object storage
+#[derive(Debug, Args, PartialEq)]
+#[clap(next_help_heading = "Snapshots")]
+pub struct SnapshotArgs {
+    /// The endpoint of an S3-compatible object storage that ancient snapshot segments are
+    /// offloaded to, e.g. `https://s3.us-east-1.amazonaws.com`.
+    ///
+    /// The credentials are read from the `AWS_ACCESS_KEY_ID` and `AWS_SECRET_ACCESS_KEY`
+    /// environment variables.
+    #[arg(long = "snapshots.s3.endpoint", value_name = "URL", requires = "s3_bucket")]
+    pub s3_endpoint: Option<String>,
+
+    /// The bucket of the object storage the snapshot segments are offloaded to.
+    #[arg(long = "snapshots.s3.bucket", value_name = "BUCKET")]
+    pub s3_bucket: Option<String>,
+
+    /// The region of the bucket.
+    #[arg(long = "snapshots.s3.region", value_name = "REGION", default_value = "us-east-1")]
+    pub s3_region: String,
+
+    /// The prefix of the keys of the offloaded snapshot segments in the bucket, e.g. `mainnet/`.
+    #[arg(long = "snapshots.s3.prefix", value_name = "PREFIX", default_value = "")]
+    pub s3_prefix: String,
+
+    /// A directory that ancient snapshot segments are offloaded to, e.g. a mounted network
+    /// filesystem.
+    #[arg(long = "snapshots.remote-dir", value_name = "PATH", conflicts_with = "s3_endpoint")]
+    pub remote_dir: Option<PathBuf>,
+
+    /// Maximum size of the local cache of the offloaded snapshot segments that were fetched, e.g.
+    /// `64GB`.
+    #[arg(
+        long = "snapshots.cache-size",
+        value_name = "SIZE",
+        value_parser = parse_byte_size,
+        default_value = "64GB"
+    )]
+    pub cache_size: usize,
+
+    /// Offloads the local snapshot segments that end at least this many blocks below the highest
+    /// snapshotted block, on startup and whenever new segments are snapshotted. If not set,
+    /// offloaded segments are only fetched.
+    #[arg(long = "snapshots.keep-local-blocks", value_name = "BLOCKS")]
+    pub keep_local_blocks: Option<u64>,
+}
+
+impl Default for SnapshotArgs {
+    fn default() -> Self {
+        Self {
+            s3_endpoint: None,
+            s3_bucket: None,
+            s3_region: "us-east-1".to_string(),
+            s3_prefix: String::new(),
+            remote_dir: None,
+            cache_size: DEFAULT_CACHE_SIZE,
+            keep_local_blocks: None,
+        }
+    }
+}
+
+impl SnapshotArgs {
+    /// Returns the remote store of the offloaded snapshot segments, caching fetched segments in
+    /// the given directory, or [None] if no store is configured.
+    pub fn remote_snapshots(&self, cache_path: PathBuf) -> eyre::Result<Option<RemoteSnapshots>> {
+        let store: Arc<dyn SnapshotStore> = if let Some(endpoint) = &self.s3_endpoint {
+            let credential = |var: &str| {
+                std::env::var(var)
+                    .map_err(|_| eyre::eyre!("missing S3 credentials, {var} is not set"))
+            };
+            let config = S3Config {
+                endpoint: endpoint.clone(),
+                region: self.s3_region.clone(),
+                bucket: self.s3_bucket.clone().unwrap_or_default(),
+                prefix: self.s3_prefix.clone(),
+                access_key_id: credential("AWS_ACCESS_KEY_ID")?,
+                secret_access_key: credential("AWS_SECRET_ACCESS_KEY")?,
+            };
+            Arc::new(S3SnapshotStore::new(config)?)
+        } else if let Some(remote_dir) = &self.remote_dir {
+            Arc::new(FsSnapshotStore::new(remote_dir))
+        } else {
+            return Ok(None)
+        };
+
+        let mut remote = RemoteSnapshots::new(store, cache_path, self.cache_size as u64)?;
+        if let Some(blocks) = self.keep_local_blocks {
+            remote = remote.with_keep_local_blocks(blocks);
+        }
+        Ok(Some(remote))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    /// A helper type to parse Args more easily
+    #[derive(Parser)]
+    struct CommandParser<T: Args> {
+        #[clap(flatten)]
+        args: T,
+    }
+
+    #[test]
+    fn snapshot_args_default_sanity_check() {
+        let default_args = SnapshotArgs::default();
+        let args = CommandParser::<SnapshotArgs>::parse_from(["reth"]).args;
+        assert_eq!(args, default_args);
+    }
+
+    #[test]
+    fn parse_snapshot_args() {
+        let args = CommandParser::<SnapshotArgs>::parse_from([
+            "reth",
+            "--snapshots.s3.endpoint",
+            "http://localhost:9000",
+            "--snapshots.s3.bucket",
+            "snapshots",
+            "--snapshots.cache-size",
+            "1GB",
+            "--snapshots.keep-local-blocks",
+            "1000000",
+        ])
+        .args;
+        assert_eq!(args.s3_bucket.as_deref(), Some("snapshots"));
+        assert_eq!(args.cache_size, 1024 * 1024 * 1024);
+        assert_eq!(args.keep_local_blocks, Some(1_000_000));
+
+        // the bucket is required
+        assert!(CommandParser::<SnapshotArgs>::try_parse_from([
+            "reth",
+            "--snapshots.s3.endpoint",
+            "http://localhost:9000",
+        ])
+        .is_err());
+    }
+}
@@ -0,0 +1,94 @@
+//! State cache arguments
+
+use clap::Args;
+use reth_provider::providers::StateCacheConfig;
+
+/// Parameters for configuring the cache of the latest state, used for block execution and payload
+/// building
+#[derive(Debug, Clone, Args, PartialEq, Eq)]
+#[clap(next_help_heading = "State Cache")]
+pub struct StateCacheArgs {
+    /// The maximum number of accounts in the cache.
+    #[arg(
+        long = "state-cache.max-accounts",
+        default_value_t = StateCacheConfig::default().max_accounts
+    )]
+    pub max_accounts: u32,
+
+    /// The maximum number of accounts with cached storage slots.
+    #[arg(
+        long = "state-cache.max-storage-accounts",
+        default_value_t = StateCacheConfig::default().max_storage_accounts
+    )]
+    pub max_storage_accounts: u32,
+
+    /// The maximum number of cached storage slots per account.
+    #[arg(
+        long = "state-cache.max-storage-slots",
+        default_value_t = StateCacheConfig::default().max_storage_slots_per_account
+    )]
+    pub max_storage_slots: u32,
+
+    /// The maximum number of bytecodes in the cache.
+    #[arg(
+        long = "state-cache.max-bytecodes",
+        default_value_t = StateCacheConfig::default().max_bytecodes
+    )]
+    pub max_bytecodes: u32,
+}
+
+impl Default for StateCacheArgs {
+    fn default() -> Self {
+        let config = StateCacheConfig::default();
+        Self {
+            max_accounts: config.max_accounts,
+            max_storage_accounts: config.max_storage_accounts,
+            max_storage_slots: config.max_storage_slots_per_account,
+            max_bytecodes: config.max_bytecodes,
+        }
+    }
+}
+
+impl StateCacheArgs {
+    /// Returns the [StateCacheConfig] for the configured limits.
+    pub fn state_cache_config(&self) -> StateCacheConfig {
+        StateCacheConfig {
+            max_accounts: self.max_accounts,
+            max_storage_accounts: self.max_storage_accounts,
+            max_storage_slots_per_account: self.max_storage_slots,
+            max_bytecodes: self.max_bytecodes,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    /// A helper type to parse Args more easily
+    #[derive(Parser)]
+    struct CommandParser<T: Args> {
+        #[clap(flatten)]
+        args: T,
+    }
+
+    #[test]
+    fn state_cache_args_default_sanity_test() {
+        let default_args = StateCacheArgs::default();
+        let args = CommandParser::<StateCacheArgs>::parse_from(["reth"]).args;
+        assert_eq!(args, default_args);
+        assert_eq!(args.state_cache_config(), StateCacheConfig::default());
+    }
+
+    #[test]
+    fn state_cache_config() {
+        let args = CommandParser::<StateCacheArgs>::parse_from([
+            "reth",
+            "--state-cache.max-storage-slots",
+            "100",
+        ])
+        .args;
+        assert_eq!(args.state_cache_config().max_storage_slots_per_account, 100);
+    }
+}
@@ -0,0 +1,101 @@
+//! Blockchain tree arguments
+
+use clap::{builder::RangedU64ValueParser, Args};
+use reth_blockchain_tree::BlockchainTreeConfig;
+
+/// Parameters for configuring the blockchain tree
+#[derive(Debug, Args, PartialEq)]
+#[clap(next_help_heading = "Blockchain tree")]
+pub struct TreeArgs {
+    /// The maximum depth of reorgs that are handled by the blockchain tree.
+    ///
+    /// Blocks below this depth are considered final and are pruned from the tree.
+    #[arg(long = "tree.max-reorg-depth", default_value_t = 64, value_name = "BLOCKS", value_parser = RangedU64ValueParser::<u64>::new().range(1..))]
+    pub max_reorg_depth: u64,
+
+    /// The maximum number of disconnected blocks that are buffered until their parent is known.
+    #[arg(long = "tree.max-buffered-blocks", default_value_t = 200, value_name = "BLOCKS")]
+    pub max_buffered_blocks: usize,
+
+    /// The maximum number of blocks in all chains of the tree, after which blocks that fork off a
+    /// historical block are rejected.
+    ///
+    /// Blocks that extend the canonical head are bounded by `--tree.max-tip-siblings` instead.
+    #[arg(long = "tree.max-blocks", default_value_t = 1024, value_name = "BLOCKS")]
+    pub max_blocks: usize,
+
+    /// The maximum number of blocks that extend the canonical head, after which further blocks
+    /// with the canonical head as parent are rejected.
+    #[arg(long = "tree.max-tip-siblings", default_value_t = 16, value_name = "BLOCKS")]
+    pub max_tip_siblings: usize,
+}
+
+impl Default for TreeArgs {
+    fn default() -> Self {
+        let config = BlockchainTreeConfig::default();
+        Self {
+            max_reorg_depth: config.max_reorg_depth(),
+            max_buffered_blocks: config.max_unconnected_blocks(),
+            max_blocks: config.max_blocks_in_tree(),
+            max_tip_siblings: config.max_tip_siblings(),
+        }
+    }
+}
+
+impl TreeArgs {
+    /// Returns the [BlockchainTreeConfig] for the configured limits.
+    pub fn tree_config(&self) -> BlockchainTreeConfig {
+        let default = BlockchainTreeConfig::default();
+        BlockchainTreeConfig::new(
+            self.max_reorg_depth,
+            self.max_reorg_depth + 1,
+            default.num_of_additional_canonical_block_hashes(),
+            self.max_buffered_blocks,
+        )
+        .with_max_blocks_in_tree(self.max_blocks)
+        .with_max_tip_siblings(self.max_tip_siblings)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    /// A helper type to parse Args more easily
+    #[derive(Parser)]
+    struct CommandParser<T: Args> {
+        #[clap(flatten)]
+        args: T,
+    }
+
+    #[test]
+    fn tree_args_default_sanity_test() {
+        let default_args = TreeArgs::default();
+        let args = CommandParser::<TreeArgs>::parse_from(["reth"]).args;
+        assert_eq!(args, default_args);
+    }
+
+    #[test]
+    fn tree_config() {
+        let args = CommandParser::<TreeArgs>::parse_from([
+            "reth",
+            "--tree.max-reorg-depth",
+            "1024",
+            "--tree.max-buffered-blocks",
+            "500",
+            "--tree.max-tip-siblings",
+            "4",
+        ])
+        .args;
+        let config = args.tree_config();
+        assert_eq!(config.max_reorg_depth(), 1024);
+        assert_eq!(config.max_blocks_in_chain(), 1025);
+        assert_eq!(config.max_unconnected_blocks(), 500);
+        assert_eq!(config.num_of_canonical_hashes(), 1024);
+        assert_eq!(config.max_tip_siblings(), 4);
+
+        assert!(CommandParser::<TreeArgs>::try_parse_from(["reth", "--tree.max-reorg-depth", "0"])
+            .is_err());
+    }
+}
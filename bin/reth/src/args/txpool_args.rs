@@ -4,8 +4,8 @@ use crate::cli::config::RethTransactionPoolConfig;
 use clap::Args;
 use reth_primitives::Address;
 use reth_transaction_pool::{
-    LocalTransactionConfig, PoolConfig, PriceBumpConfig, SubPoolLimit, DEFAULT_PRICE_BUMP,
-    REPLACE_BLOB_PRICE_BUMP, TXPOOL_MAX_ACCOUNT_SLOTS_PER_SENDER,
+    LocalTransactionConfig, PoolConfig, PriceBumpConfig, SubPoolLimit, DEFAULT_MAX_BUNDLES,
+    DEFAULT_PRICE_BUMP, REPLACE_BLOB_PRICE_BUMP, TXPOOL_MAX_ACCOUNT_SLOTS_PER_SENDER,
     TXPOOL_SUBPOOL_MAX_SIZE_MB_DEFAULT, TXPOOL_SUBPOOL_MAX_TXS_DEFAULT,
 };
 /// Parameters for debugging purposes
@@ -33,6 +33,13 @@ pub struct TxPoolArgs {
     #[arg(long = "txpool.queued_max_size", default_value_t = TXPOOL_SUBPOOL_MAX_SIZE_MB_DEFAULT)]
     pub queued_max_size: usize,
 
+    /// Max number of transaction in the blob sub-pool
+    #[arg(long = "blobpool.max_count", default_value_t = TXPOOL_SUBPOOL_MAX_TXS_DEFAULT)]
+    pub blob_max_count: usize,
+    /// Max size of the blob sub-pool in megabytes.
+    #[arg(long = "blobpool.max_size", default_value_t = TXPOOL_SUBPOOL_MAX_SIZE_MB_DEFAULT)]
+    pub blob_max_size: usize,
+
     /// Max number of executable transaction slots guaranteed per account
     #[arg(long = "txpool.max_account_slots", default_value_t = TXPOOL_MAX_ACCOUNT_SLOTS_PER_SENDER)]
     pub max_account_slots: usize,
@@ -44,12 +51,25 @@ pub struct TxPoolArgs {
     /// Price bump percentage to replace an already existing blob transaction
     #[arg(long = "blobpool.pricebump", default_value_t = REPLACE_BLOB_PRICE_BUMP)]
     pub blob_transaction_price_bump: u128,
+
+    /// Max number of bundles submitted via `eth_sendBundle` kept in the bundle pool
+    #[arg(long = "txpool.max_bundles", default_value_t = DEFAULT_MAX_BUNDLES)]
+    pub max_bundles: usize,
+
     /// Flag to disable local transaction exemptions.
     #[arg(long = "txpool.nolocals")]
     pub no_locals: bool,
     /// Flag to allow certain addresses as local
     #[arg(long = "txpool.locals")]
     pub locals: Vec<Address>,
+    /// Flag to toggle local transaction propagation.
+    ///
+    /// If set, transactions submitted via RPC are never gossiped to peers.
+    #[arg(long = "txpool.no_local_transaction_propagation")]
+    pub no_local_transaction_propagation: bool,
+    /// Flag to also persist pending transactions received from the network across restarts.
+    #[arg(long = "txpool.persist_pending")]
+    pub persist_pending: bool,
 }
 
 impl Default for TxPoolArgs {
@@ -61,11 +81,16 @@ impl Default for TxPoolArgs {
             basefee_max_size: TXPOOL_SUBPOOL_MAX_SIZE_MB_DEFAULT,
             queued_max_count: TXPOOL_SUBPOOL_MAX_TXS_DEFAULT,
             queued_max_size: TXPOOL_SUBPOOL_MAX_SIZE_MB_DEFAULT,
+            blob_max_count: TXPOOL_SUBPOOL_MAX_TXS_DEFAULT,
+            blob_max_size: TXPOOL_SUBPOOL_MAX_SIZE_MB_DEFAULT,
             max_account_slots: TXPOOL_MAX_ACCOUNT_SLOTS_PER_SENDER,
             price_bump: DEFAULT_PRICE_BUMP,
             blob_transaction_price_bump: REPLACE_BLOB_PRICE_BUMP,
+            max_bundles: DEFAULT_MAX_BUNDLES,
             no_locals: false,
             locals: Default::default(),
+            no_local_transaction_propagation: false,
+            persist_pending: false,
         }
     }
 }
@@ -77,6 +102,7 @@ impl RethTransactionPoolConfig for TxPoolArgs {
             local_transactions_config: LocalTransactionConfig {
                 no_exemptions: self.no_locals,
                 local_addresses: self.locals.clone().into_iter().collect(),
+                propagate_local_transactions: !self.no_local_transaction_propagation,
             },
             pending_limit: SubPoolLimit {
                 max_txs: self.pending_max_count,
@@ -91,14 +117,15 @@ impl RethTransactionPoolConfig for TxPoolArgs {
                 max_size: self.queued_max_size * 1024 * 1024,
             },
             blob_limit: SubPoolLimit {
-                max_txs: self.queued_max_count,
-                max_size: self.queued_max_size * 1024 * 1024,
+                max_txs: self.blob_max_count,
+                max_size: self.blob_max_size * 1024 * 1024,
             },
             max_account_slots: self.max_account_slots,
             price_bumps: PriceBumpConfig {
                 default_price_bump: self.price_bump,
                 replace_blob_tx_price_bump: self.blob_transaction_price_bump,
             },
+            max_bundles: self.max_bundles,
         }
     }
 }
@@ -121,4 +148,27 @@ mod tests {
         let args = CommandParser::<TxPoolArgs>::parse_from(["reth"]).args;
         assert_eq!(args, default_args);
     }
+
+    #[test]
+    fn txpool_args_limits() {
+        let args = CommandParser::<TxPoolArgs>::parse_from([
+            "reth",
+            "--txpool.pending_max_count",
+            "50000",
+            "--txpool.queued_max_size",
+            "100",
+            "--txpool.max_account_slots",
+            "64",
+            "--txpool.pricebump",
+            "25",
+        ])
+        .args;
+
+        let config = args.pool_config();
+        assert_eq!(config.pending_limit.max_txs, 50000);
+        assert_eq!(config.queued_limit.max_size, 100 * 1024 * 1024);
+        assert_eq!(config.max_account_slots, 64);
+        assert_eq!(config.price_bumps.default_price_bump, 25);
+        assert_eq!(config.price_bumps.replace_blob_tx_price_bump, REPLACE_BLOB_PRICE_BUMP);
+    }
 }
@@ -1,9 +1,10 @@
 //! Clap parser utilities
 
-use reth_primitives::{fs, AllGenesisFormats, BlockHashOrNumber, ChainSpec, B256};
+use reth_primitives::{fs, AllGenesisFormats, BlockHashOrNumber, ChainSpec, Genesis, B256};
 use std::{
+    io::BufReader,
     net::{IpAddr, Ipv4Addr, SocketAddr, ToSocketAddrs},
-    path::PathBuf,
+    path::{Path, PathBuf},
     str::FromStr,
     sync::Arc,
     time::Duration,
@@ -30,6 +31,8 @@ pub fn parse_duration_from_secs(arg: &str) -> eyre::Result<Duration, std::num::P
 
 /// Clap value parser for [ChainSpec]s that takes either a built-in chainspec or the path
 /// to a custom one.
+///
+/// The custom chainspec file can be either a serialized [ChainSpec] or a geth-style genesis file.
 pub fn chain_spec_value_parser(s: &str) -> eyre::Result<Arc<ChainSpec>, eyre::Error> {
     Ok(match s {
         #[cfg(not(feature = "optimism"))]
@@ -48,13 +51,24 @@ pub fn chain_spec_value_parser(s: &str) -> eyre::Result<Arc<ChainSpec>, eyre::Er
         "base_sepolia" | "base-sepolia" => BASE_SEPOLIA.clone(),
         #[cfg(feature = "optimism")]
         "base" => BASE_MAINNET.clone(),
-        _ => {
-            let raw = fs::read_to_string(PathBuf::from(shellexpand::full(s)?.into_owned()))?;
-            serde_json::from_str(&raw)?
-        }
+        _ => Arc::new(parse_chain_spec_file(&PathBuf::from(shellexpand::full(s)?.into_owned()))?),
     })
 }
 
+/// Parses a custom chainspec file, which is either a serialized [ChainSpec] or a geth-style
+/// genesis file, without reading the whole file into memory first.
+///
+/// [AllGenesisFormats] is untagged, so it buffers the whole document before deserializing it.
+/// Instead, the formats are tried one after the other, directly from the file.
+fn parse_chain_spec_file(path: &Path) -> eyre::Result<ChainSpec> {
+    let reader = || fs::open(path).map(BufReader::new);
+    if let Ok(chain_spec) = serde_json::from_reader::<_, ChainSpec>(reader()?) {
+        return Ok(chain_spec)
+    }
+    let genesis: Genesis = serde_json::from_reader(reader()?)?;
+    Ok(genesis.into())
+}
+
 /// The help info for the --chain flag
 pub fn chain_help() -> String {
     format!("The chain this node is running.\nPossible values are either a built-in chain or the path to a chain specification file.\n\nBuilt-in chains:\n    {}", SUPPORTED_CHAINS.join(", "))
@@ -158,12 +172,32 @@ pub fn parse_socket_address(value: &str) -> eyre::Result<SocketAddr, SocketAddre
         .ok_or_else(|| SocketAddressParsingError::Parse(value.to_string()))
 }
 
+/// Parses a size in bytes from a `str`, either a plain number of bytes or a number with one of
+/// the binary `KB`, `MB`, `GB` or `TB` suffixes, e.g. `512MB`.
+pub fn parse_byte_size(value: &str) -> eyre::Result<usize> {
+    let value = value.trim();
+    let split = value.find(|c: char| !c.is_ascii_digit()).unwrap_or(value.len());
+    let (number, unit) = value.split_at(split);
+    let multiplier: usize = match unit.trim().to_ascii_uppercase().as_str() {
+        "" | "B" => 1,
+        "KB" => 1 << 10,
+        "MB" => 1 << 20,
+        "GB" => 1 << 30,
+        "TB" => 1 << 40,
+        unit => eyre::bail!("unknown size unit: {unit}"),
+    };
+    number
+        .parse::<usize>()?
+        .checked_mul(multiplier)
+        .ok_or_else(|| eyre::eyre!("size is too large: {value}"))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use proptest::prelude::Rng;
     use reth_primitives::{
-        hex, Address, ChainConfig, ChainSpecBuilder, Genesis, GenesisAccount, U256,
+        hex, Address, ChainConfig, ChainSpecBuilder, Genesis, GenesisAccount, Hardfork, U256,
     };
     use secp256k1::rand::thread_rng;
     use std::collections::HashMap;
@@ -266,6 +300,33 @@ mod tests {
         assert_eq!(custom_genesis_from_spec.chain(), chain_from_struct.chain());
     }
 
+    #[test]
+    fn parse_chain_spec_from_genesis_file() {
+        let genesis = r#"{
+    "difficulty": "0x0",
+    "gasLimit": "0x1388",
+    "alloc": {
+        "0x6Be02d1d3665660d22FF9624b7BE0551ee1Ac91b": { "balance": "0x21" }
+    },
+    "config": {
+        "chainId": 2600,
+        "londonBlock": 0,
+        "terminalTotalDifficulty": 0,
+        "shanghaiTime": 10
+    }
+}"#;
+        let file = tempfile::NamedTempFile::new().unwrap();
+        fs::write(file.path(), genesis).unwrap();
+
+        let path = file.path().to_str().unwrap();
+        let chain_spec = chain_spec_value_parser(path).unwrap();
+        assert_eq!(chain_spec.chain, 2600.into());
+        assert_eq!(chain_spec.genesis().alloc.len(), 1);
+        assert!(chain_spec.fork(Hardfork::London).active_at_block(0));
+        assert!(chain_spec.fork(Hardfork::Shanghai).active_at_timestamp(10));
+        assert_eq!(chain_spec.genesis(), genesis_value_parser(path).unwrap().genesis());
+    }
+
     #[test]
     fn parse_socket_addresses() {
         for value in ["localhost:9000", ":9000", "9000"] {
@@ -289,4 +350,14 @@ mod tests {
             assert_eq!(socket_addr.port(), port);
         }
     }
+
+    #[test]
+    fn parse_byte_sizes() {
+        assert_eq!(parse_byte_size("1024").unwrap(), 1024);
+        assert_eq!(parse_byte_size("512MB").unwrap(), 512 * 1024 * 1024);
+        assert_eq!(parse_byte_size("4 gb").unwrap(), 4 * 1024 * 1024 * 1024);
+        assert_eq!(parse_byte_size("4TB").unwrap(), 4 << 40);
+        assert!(parse_byte_size("TB").is_err());
+        assert!(parse_byte_size("4PB").is_err());
+    }
 }
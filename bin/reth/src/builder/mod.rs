@@ -3,7 +3,7 @@ use super::cli::{components::RethRpcServerHandles, ext::DefaultRethNodeCommandCo
 use crate::{
     args::{
         get_secret_key, DatabaseArgs, DebugArgs, DevArgs, NetworkArgs, PayloadBuilderArgs,
-        PruningArgs, RpcServerArgs, TxPoolArgs,
+        PruningArgs, RpcServerArgs, SnapshotArgs, StateCacheArgs, TreeArgs, TxPoolArgs,
     },
     cli::{
         components::RethNodeComponentsImpl,
@@ -18,7 +18,7 @@ use crate::{
     dirs::{ChainPath, DataDirPath, MaybePlatformPath},
     init::init_genesis,
     prometheus_exporter,
-    utils::{get_single_header, write_peers_to_file},
+    utils::{get_single_header, write_bans_to_file, write_peers_to_file},
     version::SHORT_VERSION,
 };
 use eyre::Context;
@@ -35,6 +35,7 @@ use reth_beacon_consensus::{
 use reth_blockchain_tree::{
     config::BlockchainTreeConfig, externals::TreeExternals, BlockchainTree, ShareableBlockchainTree,
 };
+use reth_clique_consensus::{CliqueConsensus, CliqueSealer, CliqueSigner};
 use reth_config::{
     config::{PruneConfig, StageConfig},
     Config,
@@ -42,11 +43,13 @@ use reth_config::{
 use reth_db::{
     database::Database,
     database_metrics::{DatabaseMetadata, DatabaseMetrics},
+    DatabaseEnvConfig,
 };
 use reth_downloaders::{
     bodies::bodies::BodiesDownloaderBuilder,
     headers::reverse_headers::ReverseHeadersDownloaderBuilder,
 };
+use reth_exex::{ExExContext, ExExHandle, ExExManager};
 use reth_interfaces::{
     blockchain_tree::BlockchainTreeEngine,
     consensus::Consensus,
@@ -68,13 +71,14 @@ use reth_primitives::{
     constants::eip4844::{LoadKzgSettingsError, MAINNET_KZG_TRUSTED_SETUP},
     kzg::KzgSettings,
     stage::StageId,
-    BlockHashOrNumber, BlockNumber, ChainSpec, DisplayHardforks, Head, SealedHeader, TxHash, B256,
-    MAINNET,
+    BlockHashOrNumber, BlockNumber, ChainSpec, DisplayHardforks, Head, PruneSegment, SealedHeader,
+    TxHash, B256, MAINNET,
 };
 use reth_provider::{
-    providers::BlockchainProvider, BlockHashReader, BlockReader,
-    BlockchainTreePendingStateProvider, CanonStateSubscriptions, HeaderProvider, HeaderSyncMode,
-    ProviderFactory, StageCheckpointReader,
+    providers::{BlockchainProvider, SnapshotProvider, StateCache},
+    BlockHashReader, BlockReader, BlockReaderIdExt, BlockchainTreePendingStateProvider,
+    CanonStateSubscriptions, HeaderProvider, HeaderSyncMode, ProviderFactory,
+    PruneCheckpointReader, StageCheckpointReader,
 };
 use reth_prune::PrunerBuilder;
 use reth_revm::EvmProcessorFactory;
@@ -83,23 +87,19 @@ use reth_stages::{
     prelude::*,
     stages::{
         AccountHashingStage, ExecutionStage, ExecutionStageThresholds, IndexAccountHistoryStage,
-        IndexStorageHistoryStage, MerkleStage, SenderRecoveryStage, StorageHashingStage,
-        TotalDifficultyStage, TransactionLookupStage,
+        IndexLogsStage, IndexStorageHistoryStage, IndexTransactionAddressesStage, MerkleStage,
+        SenderRecoveryStage, StorageHashingStage, TotalDifficultyStage, TransactionLookupStage,
     },
     MetricEvent,
 };
 use reth_tasks::{TaskExecutor, TaskManager};
 use reth_transaction_pool::{
-    blobstore::InMemoryBlobStore, EthTransactionPool, TransactionPool,
-    TransactionValidationTaskExecutor,
+    blobstore::{DiskFileBlobStore, DiskFileBlobStoreConfig},
+    EthTransactionPool, TransactionPool, TransactionValidationTaskExecutor,
 };
 use revm_inspectors::stack::Hook;
 use secp256k1::SecretKey;
-use std::{
-    net::{SocketAddr, SocketAddrV4},
-    path::PathBuf,
-    sync::Arc,
-};
+use std::{net::SocketAddr, path::PathBuf, sync::Arc};
 use tokio::{
     runtime::Handle,
     sync::{
@@ -241,6 +241,15 @@ pub struct NodeConfig {
     /// All pruning related arguments
     pub pruning: PruningArgs,
 
+    /// All snapshot offloading related arguments with --snapshots prefix
+    pub snapshots: SnapshotArgs,
+
+    /// All blockchain tree related arguments with --tree prefix
+    pub tree: TreeArgs,
+
+    /// All state cache related arguments with --state-cache prefix
+    pub state_cache: StateCacheArgs,
+
     /// Rollup related arguments
     #[cfg(feature = "optimism")]
     pub rollup: crate::args::RollupArgs,
@@ -264,6 +273,9 @@ impl NodeConfig {
             db: DatabaseArgs::default(),
             dev: DevArgs::default(),
             pruning: PruningArgs::default(),
+            snapshots: SnapshotArgs::default(),
+            tree: TreeArgs::default(),
+            state_cache: StateCacheArgs::default(),
             #[cfg(feature = "optimism")]
             rollup: crate::args::RollupArgs::default(),
         }
@@ -275,6 +287,13 @@ impl NodeConfig {
         self
     }
 
+    /// Set the database backend for the node, e.g. [DatabaseBuilder::memory] for platforms that
+    /// can't run MDBX
+    pub fn with_database(mut self, database: DatabaseBuilder) -> Self {
+        self.database = database;
+        self
+    }
+
     /// Set the config file for the node
     pub fn with_config(mut self, config: impl Into<PathBuf>) -> Self {
         self.config = Some(config.into());
@@ -359,6 +378,24 @@ impl NodeConfig {
         self
     }
 
+    /// Set the snapshot offloading args for the node
+    pub fn with_snapshots(mut self, snapshots: SnapshotArgs) -> Self {
+        self.snapshots = snapshots;
+        self
+    }
+
+    /// Set the blockchain tree args for the node
+    pub fn with_tree(mut self, tree: TreeArgs) -> Self {
+        self.tree = tree;
+        self
+    }
+
+    /// Set the state cache args for the node
+    pub fn with_state_cache(mut self, state_cache: StateCacheArgs) -> Self {
+        self.state_cache = state_cache;
+        self
+    }
+
     /// Set the node instance number
     pub fn with_instance_number(mut self, instance: u16) -> Self {
         self.instance = instance;
@@ -400,7 +437,8 @@ impl NodeConfig {
         info!(target: "reth::cli", "reth {} starting", SHORT_VERSION);
 
         let database = std::mem::take(&mut self.database);
-        let db_instance = database.init_db(self.db.log_level, self.chain.chain)?;
+        let db_config = self.db.apply(self.load_db_config(&database)?);
+        let db_instance = database.init_db(self.db.log_level, db_config, self.chain.chain)?;
 
         match db_instance {
             DatabaseInstance::Real { db, data_dir } => {
@@ -411,9 +449,29 @@ impl NodeConfig {
                 let builder = NodeBuilderWithDatabase { config: self, db, data_dir };
                 builder.launch::<E>(ext, executor).await
             }
+            DatabaseInstance::Memory { db, data_dir } => {
+                let builder = NodeBuilderWithDatabase { config: self, db, data_dir };
+                builder.launch::<E>(ext, executor).await
+            }
         }
     }
 
+    /// Loads the database configuration from the config file, before the database is opened.
+    ///
+    /// Test databases always use the default configuration, unless a config file is given.
+    fn load_db_config(&self, database: &DatabaseBuilder) -> eyre::Result<DatabaseEnvConfig> {
+        let config_path = match (&self.config, database) {
+            (Some(config_path), _) => config_path.clone(),
+            (None, DatabaseBuilder::Real(path) | DatabaseBuilder::Memory(path)) => {
+                path.unwrap_or_chain_default(self.chain.chain).config_path()
+            }
+            (None, DatabaseBuilder::Test) => return Ok(DatabaseEnvConfig::default()),
+        };
+        let config = confy::load_path::<Config>(&config_path)
+            .wrap_err_with(|| format!("Could not load config file {:?}", config_path))?;
+        Ok(config.db)
+    }
+
     /// Get the network secret from the given data dir
     pub fn network_secret(&self, data_dir: &ChainPath<DataDirPath>) -> eyre::Result<SecretKey> {
         let network_secret_path =
@@ -500,7 +558,7 @@ impl NodeConfig {
             head,
             secret_key,
             default_peers_path.clone(),
-        );
+        )?;
 
         let client = network_config.client.clone();
         let builder = NetworkManager::builder(network_config).await?;
@@ -515,16 +573,14 @@ impl NodeConfig {
         prune_config: Option<PruneConfig>,
         sync_metrics_tx: UnboundedSender<MetricEvent>,
         tree_config: BlockchainTreeConfig,
+        executor_factory: EvmProcessorFactory,
     ) -> eyre::Result<BlockchainTree<DB, EvmProcessorFactory>>
     where
         DB: Database + Unpin + Clone + 'static,
     {
         // configure blockchain tree
-        let tree_externals = TreeExternals::new(
-            provider_factory.clone(),
-            consensus.clone(),
-            EvmProcessorFactory::new(self.chain.clone()),
-        );
+        let tree_externals =
+            TreeExternals::new(provider_factory.clone(), consensus.clone(), executor_factory);
         let tree = BlockchainTree::new(
             tree_externals,
             tree_config,
@@ -542,7 +598,7 @@ impl NodeConfig {
         head: Head,
         executor: &TaskExecutor,
         data_dir: &ChainPath<DataDirPath>,
-    ) -> eyre::Result<EthTransactionPool<BlockchainProvider<DB, Tree>, InMemoryBlobStore>>
+    ) -> eyre::Result<EthTransactionPool<BlockchainProvider<DB, Tree>, DiskFileBlobStore>>
     where
         DB: Database + Unpin + Clone + 'static,
         Tree: BlockchainTreeEngine
@@ -551,10 +607,12 @@ impl NodeConfig {
             + Clone
             + 'static,
     {
-        let blob_store = InMemoryBlobStore::default();
+        let blob_store =
+            DiskFileBlobStore::open(data_dir.blobstore_path(), DiskFileBlobStoreConfig::default())?;
         let validator = TransactionValidationTaskExecutor::eth_builder(Arc::clone(&self.chain))
             .with_head_timestamp(head.timestamp)
             .kzg_settings(self.kzg_settings()?)
+            .set_local_transactions_config(self.txpool.pool_config().local_transactions_config)
             .with_additional_tasks(1)
             .build_with_tasks(blockchain_db.clone(), executor.clone(), blob_store.clone());
 
@@ -568,8 +626,13 @@ impl NodeConfig {
             let pool = transaction_pool.clone();
             let chain_events = blockchain_db.canonical_state_stream();
             let client = blockchain_db.clone();
-            let transactions_backup_config =
-                reth_transaction_pool::maintain::LocalTransactionBackupConfig::with_local_txs_backup(transactions_path);
+            let mut transactions_backup_config =
+                reth_transaction_pool::maintain::LocalTransactionBackupConfig::with_local_txs_backup(transactions_path)
+                    .with_backup_interval(reth_transaction_pool::maintain::DEFAULT_TXS_BACKUP_INTERVAL);
+            if self.txpool.persist_pending {
+                transactions_backup_config = transactions_backup_config
+                    .with_pending_txs_backup(data_dir.txpool_pending_transactions_path());
+            }
 
             executor.spawn_critical_with_graceful_shutdown_signal(
                 "local transactions backup task",
@@ -611,6 +674,47 @@ impl NodeConfig {
         }
     }
 
+    /// Returns the [Consensus] instance to use with the given provider.
+    ///
+    /// This is a [CliqueConsensus] instance if the genesis of the chain has a Clique configuration,
+    /// so the blocks sealed by the node are validated like the blocks of other signers, see
+    /// [Self::consensus] otherwise.
+    pub fn consensus_with_provider<Provider>(&self, provider: Provider) -> Arc<dyn Consensus>
+    where
+        Provider: HeaderProvider + 'static,
+    {
+        if self.chain.genesis.config.clique.is_some() {
+            Arc::new(CliqueConsensus::new(Arc::clone(&self.chain), provider))
+        } else {
+            self.consensus()
+        }
+    }
+
+    /// Returns true if the node seals its own blocks.
+    ///
+    /// This is the case in dev mode, and for a Clique chain if the `--dev.signer-key` is set.
+    pub fn seals_blocks(&self) -> bool {
+        self.dev.dev ||
+            (self.chain.genesis.config.clique.is_some() && self.dev.signer_key.is_some())
+    }
+
+    /// Returns the [CliqueSealer] of the `--dev.signer-key` for the latest block of the provider.
+    fn clique_sealer<Provider>(&self, provider: Provider) -> eyre::Result<CliqueSealer>
+    where
+        Provider: BlockReaderIdExt,
+    {
+        let Some(path) = &self.dev.signer_key else {
+            eyre::bail!("--dev.signer-key is required to seal the blocks of a Clique chain")
+        };
+        let signer = CliqueSigner::new(get_secret_key(path)?);
+        info!(target: "reth::cli", signer=%signer.address(), "Sealing blocks with Clique signer");
+
+        let latest_header =
+            provider.latest_header()?.unwrap_or_else(|| self.chain.sealed_genesis_header());
+        let consensus = CliqueConsensus::new(Arc::clone(&self.chain), provider);
+        Ok(consensus.sealer(signer, &latest_header)?)
+    }
+
     /// Constructs a [Pipeline] that's wired to the network
     #[allow(clippy::too_many_arguments)]
     async fn build_networked_pipeline<DB, Client>(
@@ -623,6 +727,7 @@ impl NodeConfig {
         metrics_tx: reth_stages::MetricEventsSender,
         prune_config: Option<PruneConfig>,
         max_block: Option<BlockNumber>,
+        executor_factory: EvmProcessorFactory,
     ) -> eyre::Result<Pipeline<DB>>
     where
         DB: Database + Unpin + Clone + 'static,
@@ -648,6 +753,7 @@ impl NodeConfig {
                 self.debug.continuous,
                 metrics_tx,
                 prune_config,
+                executor_factory,
             )
             .await?;
 
@@ -706,19 +812,23 @@ impl NodeConfig {
         C: BlockReader + HeaderProvider + Clone + Unpin + 'static,
         Pool: TransactionPool + Unpin + 'static,
     {
-        let (handle, network, txpool, eth) =
-            builder.transactions(pool).request_handler(client).split_with_handle();
+        let (handle, network, txpool, eth) = builder
+            .transactions_with_config(pool, self.network.transactions_manager_config())
+            .request_handler(client)
+            .split_with_handle();
 
         task_executor.spawn_critical("p2p txpool", txpool);
         task_executor.spawn_critical("p2p eth request handler", eth);
 
         let default_peers_path = data_dir.known_peers_path();
         let known_peers_file = self.network.persistent_peers_file(default_peers_path);
+        let bans_file = self.network.persistent_peers_file(data_dir.banned_peers_path());
         task_executor.spawn_critical_with_graceful_shutdown_signal(
             "p2p network task",
             |shutdown| {
                 network.run_until_graceful_shutdown(shutdown, |network| {
-                    write_peers_to_file(network, known_peers_file)
+                    write_peers_to_file(network, known_peers_file);
+                    write_bans_to_file(network, bans_file);
                 })
             },
         );
@@ -813,22 +923,34 @@ impl NodeConfig {
         head: Head,
         secret_key: SecretKey,
         default_peers_path: PathBuf,
-    ) -> NetworkConfig<ProviderFactory<DB>> {
+    ) -> eyre::Result<NetworkConfig<ProviderFactory<DB>>> {
+        // the receipts of the blocks up to the prune checkpoints are gone, so the blocks can't be
+        // served to `eth/69` peers anymore
+        let mut earliest_block = 0;
+        for segment in [PruneSegment::Receipts, PruneSegment::ContractLogs] {
+            let checkpoint = provider_factory.get_prune_checkpoint(segment)?;
+            if let Some(pruned) = checkpoint.and_then(|checkpoint| checkpoint.block_number) {
+                earliest_block = earliest_block.max(pruned + 1);
+            }
+        }
+        let prune_config =
+            self.pruning.prune_config(Arc::clone(&self.chain))?.or_else(|| config.prune.clone());
+
         let cfg_builder = self
             .network
             .network_config(config, self.chain.clone(), secret_key, default_peers_path)
             .with_task_executor(Box::new(executor))
             .set_head(head)
-            .listener_addr(SocketAddr::V4(SocketAddrV4::new(
-                self.network.addr,
+            .earliest_block(earliest_block)
+            .receipts_prune_mode(prune_config.and_then(|config| config.segments.receipts))
+            .listener_addr(
                 // set discovery port based on instance number
-                self.network.port + self.instance - 1,
-            )))
-            .discovery_addr(SocketAddr::V4(SocketAddrV4::new(
-                self.network.addr,
+                self.network.listener_addr(self.network.port + self.instance - 1),
+            )
+            .discovery_addr(
                 // set discovery port based on instance number
-                self.network.port + self.instance - 1,
-            )));
+                self.network.listener_addr(self.network.port + self.instance - 1),
+            );
 
         // When `sequencer_endpoint` is configured, the node will forward all transactions to a
         // Sequencer node for execution and inclusion on L1, and disable its own txpool
@@ -838,7 +960,7 @@ impl NodeConfig {
             .sequencer_endpoint(self.rollup.sequencer_http.clone())
             .disable_tx_gossip(self.rollup.disable_txpool_gossip);
 
-        cfg_builder.build(provider_factory)
+        Ok(cfg_builder.build(provider_factory))
     }
 
     #[allow(clippy::too_many_arguments)]
@@ -853,6 +975,7 @@ impl NodeConfig {
         continuous: bool,
         metrics_tx: reth_stages::MetricEventsSender,
         prune_config: Option<PruneConfig>,
+        executor_factory: EvmProcessorFactory,
     ) -> eyre::Result<Pipeline<DB>>
     where
         DB: Database + Clone + 'static,
@@ -868,8 +991,6 @@ impl NodeConfig {
 
         let (tip_tx, tip_rx) = watch::channel(B256::ZERO);
         use revm_inspectors::stack::InspectorStackConfig;
-        let factory = reth_revm::EvmProcessorFactory::new(self.chain.clone());
-
         let stack_config = InspectorStackConfig {
             use_printer_tracer: self.debug.print_inspector,
             hook: if let Some(hook_block) = self.debug.hook_block {
@@ -883,7 +1004,7 @@ impl NodeConfig {
             },
         };
 
-        let factory = factory.with_stack_config(stack_config);
+        let factory = executor_factory.with_stack_config(stack_config);
 
         let prune_modes = prune_config.map(|prune| prune.segments).unwrap_or_default();
 
@@ -905,9 +1026,11 @@ impl NodeConfig {
                     TotalDifficultyStage::new(consensus)
                         .with_commit_threshold(stage_config.total_difficulty.commit_threshold),
                 )
-                .set(SenderRecoveryStage {
-                    commit_threshold: stage_config.sender_recovery.commit_threshold,
-                })
+                .set(
+                    SenderRecoveryStage::new(stage_config.sender_recovery.commit_threshold)
+                        .with_parallelism(stage_config.sender_recovery.parallelism)
+                        .with_batch_size(stage_config.sender_recovery.batch_size),
+                )
                 .set(
                     ExecutionStage::new(
                         factory,
@@ -915,6 +1038,7 @@ impl NodeConfig {
                             max_blocks: stage_config.execution.max_blocks,
                             max_changes: stage_config.execution.max_changes,
                             max_cumulative_gas: stage_config.execution.max_cumulative_gas,
+                            max_duration: stage_config.execution.max_duration,
                         },
                         stage_config
                             .merkle
@@ -945,7 +1069,21 @@ impl NodeConfig {
                 .set(IndexStorageHistoryStage::new(
                     stage_config.index_storage_history.commit_threshold,
                     prune_modes.storage_history,
-                )),
+                ))
+                .add_before(
+                    IndexLogsStage::new(stage_config.index_logs.commit_threshold),
+                    StageId::Finish,
+                )
+                .disable_if(StageId::IndexLogs, || !stage_config.index_logs.enabled)
+                .add_before(
+                    IndexTransactionAddressesStage::new(
+                        stage_config.index_transaction_addresses.commit_threshold,
+                    ),
+                    StageId::Finish,
+                )
+                .disable_if(StageId::IndexTransactionAddresses, || {
+                    !stage_config.index_transaction_addresses.enabled
+                }),
             )
             .build(provider_factory);
 
@@ -976,6 +1114,9 @@ impl Default for NodeConfig {
             db: DatabaseArgs::default(),
             dev: DevArgs::default(),
             pruning: PruningArgs::default(),
+            snapshots: SnapshotArgs::default(),
+            tree: TreeArgs::default(),
+            state_cache: StateCacheArgs::default(),
             #[cfg(feature = "optimism")]
             rollup: crate::args::RollupArgs::default(),
         }
@@ -1022,10 +1163,39 @@ impl<DB: Database + DatabaseMetrics + DatabaseMetadata + 'static> NodeBuilderWit
             self.config.chain.snapshot_block_interval,
         )?;
 
-        provider_factory = provider_factory.with_snapshots(
-            self.data_dir.snapshots_path(),
-            snapshotter.highest_snapshot_receiver(),
-        )?;
+        let mut snapshot_provider = SnapshotProvider::new(self.data_dir.snapshots_path())?
+            .with_highest_tracker(Some(snapshotter.highest_snapshot_receiver()));
+        let cache_path = self.data_dir.snapshots_path().join("cache");
+        if let Some(remote) = self.config.snapshots.remote_snapshots(cache_path)? {
+            snapshot_provider = snapshot_provider.with_remote(remote)?;
+        }
+        let snapshot_provider = Arc::new(snapshot_provider);
+        provider_factory = provider_factory.with_snapshot_provider(snapshot_provider.clone());
+
+        if snapshot_provider.remote().and_then(|remote| remote.keep_local_blocks()).is_some() {
+            let mut highest_snapshots = snapshotter.highest_snapshot_receiver();
+            executor.spawn_blocking(Box::pin(async move {
+                // offload on startup, and whenever the snapshotter created new segments
+                loop {
+                    match snapshot_provider.offload_snapshots() {
+                        Ok(0) => {}
+                        Ok(offloaded) => {
+                            info!(target: "reth::cli", offloaded, "Offloaded ancient snapshots")
+                        }
+                        Err(err) => {
+                            error!(target: "reth::cli", %err, "Failed to offload snapshots")
+                        }
+                    }
+                    if highest_snapshots.changed().await.is_err() {
+                        break
+                    }
+                }
+            }));
+        }
+
+        // cache the latest state for block execution and payload building
+        let state_cache = StateCache::new(self.config.state_cache.state_cache_config());
+        provider_factory = provider_factory.with_state_cache(state_cache.clone());
 
         self.config.start_metrics_endpoint(prometheus_handle, Arc::clone(&self.db)).await?;
 
@@ -1035,7 +1205,20 @@ impl<DB: Database + DatabaseMetrics + DatabaseMetadata + 'static> NodeBuilderWit
 
         info!(target: "reth::cli", "{}", DisplayHardforks::new(self.config.chain.hardforks()));
 
-        let consensus = self.config.consensus();
+        let consensus =
+            match ext.consensus(Arc::clone(&self.config.chain), provider_factory.clone()) {
+                Some(consensus) => consensus,
+                None => self.config.consensus_with_provider(provider_factory.clone()),
+            };
+        let evm_config = ext.evm_config();
+
+        // configure the executor of the blockchain tree and the execution stage
+        let mut executor_factory = EvmProcessorFactory::new(self.config.chain.clone())
+            .with_parallel_execution(config.stages.execution.parallel)
+            .with_evm_config(evm_config);
+        if let Some(inspector_factory) = ext.execution_inspector_factory() {
+            executor_factory = executor_factory.with_inspector_factory(inspector_factory);
+        }
 
         debug!(target: "reth::cli", "Spawning stages metrics listener task");
         let (sync_metrics_tx, sync_metrics_rx) = unbounded_channel();
@@ -1049,15 +1232,20 @@ impl<DB: Database + DatabaseMetrics + DatabaseMetadata + 'static> NodeBuilderWit
             .or(config.prune.clone());
 
         // configure blockchain tree
-        let tree_config = BlockchainTreeConfig::default();
+        let tree_config = self.config.tree.tree_config();
         let tree = self.config.build_blockchain_tree(
             provider_factory.clone(),
             consensus.clone(),
             prune_config.clone(),
             sync_metrics_tx.clone(),
             tree_config,
+            executor_factory.clone(),
         )?;
         let canon_state_notification_sender = tree.canon_state_notification_sender();
+        executor.spawn_critical(
+            "state cache",
+            state_cache.update_on_notifications(canon_state_notification_sender.subscribe()),
+        );
         let blockchain_tree = ShareableBlockchainTree::new(tree);
         debug!(target: "reth::cli", "configured blockchain tree");
 
@@ -1113,13 +1301,37 @@ impl<DB: Database + DatabaseMetrics + DatabaseMetadata + 'static> NodeBuilderWit
 
         ext.on_components_initialized(&components)?;
 
+        // launch the execution extensions
+        let exexs = ext.install_exexs(&components)?;
+        let mut exex_handles = Vec::with_capacity(exexs.len());
+        for (id, exex) in exexs {
+            let (handle, notifications, events) = ExExHandle::new(id.clone());
+            let ctx = ExExContext { head, components: components.clone(), notifications, events };
+            executor.spawn_critical("exex", async move {
+                if let Err(err) = exex.launch(ctx).await {
+                    error!(target: "reth::cli", %id, %err, "ExEx failed");
+                }
+            });
+            exex_handles.push(handle);
+        }
+        if !exex_handles.is_empty() {
+            info!(target: "reth::cli", exexs = exex_handles.len(), "Execution extensions launched");
+        }
+        let (exex_manager, finished_exex_height) =
+            ExExManager::new(exex_handles, head.number, provider_factory.clone());
+        executor.spawn_critical(
+            "exex manager",
+            exex_manager.run(blockchain_db.subscribe_to_canonical_state()),
+        );
+
         debug!(target: "reth::cli", "Spawning payload builder service");
 
         // TODO: stateful node builder should handle this in with_payload_builder
         // Optimism's payload builder is implemented on the OptimismPayloadBuilder type.
         #[cfg(feature = "optimism")]
-        let payload_builder = reth_optimism_payload_builder::OptimismPayloadBuilder::default()
-            .set_compute_pending_block(self.config.builder.compute_pending_block);
+        let payload_builder = <reth_optimism_payload_builder::OptimismPayloadBuilder>::default()
+            .set_compute_pending_block(self.config.builder.compute_pending_block)
+            .with_evm_config(evm_config);
 
         #[cfg(feature = "optimism")]
         let payload_builder: PayloadBuilderHandle<OptimismEngineTypes> =
@@ -1127,12 +1339,32 @@ impl<DB: Database + DatabaseMetrics + DatabaseMetadata + 'static> NodeBuilderWit
 
         // The default payload builder is implemented on the unit type.
         #[cfg(not(feature = "optimism"))]
-        let payload_builder = reth_ethereum_payload_builder::EthereumPayloadBuilder::default();
+        let payload_builder =
+            reth_ethereum_payload_builder::EthereumPayloadBuilder::new(evm_config);
 
         #[cfg(not(feature = "optimism"))]
         let payload_builder: PayloadBuilderHandle<EthEngineTypes> =
             ext.spawn_payload_builder_service(&self.config.builder, &components, payload_builder)?;
 
+        #[cfg(not(feature = "optimism"))]
+        if let Some((relay_config, signer)) =
+            self.config.builder.relay_config(&self.config.chain)?
+        {
+            info!(
+                target: "reth::cli",
+                relays = relay_config.relays.len(),
+                builder = %signer.public_key(),
+                "Submitting payloads to relays"
+            );
+            let submitter = reth_payload_relay::RelaySubmitter::new(
+                relay_config,
+                signer,
+                payload_builder.clone().into(),
+                Box::new(executor.clone()),
+            );
+            executor.spawn_critical("relay submitter", submitter.run());
+        }
+
         let (consensus_engine_tx, mut consensus_engine_rx) = unbounded_channel();
         if let Some(store_path) = self.config.debug.engine_api_store.clone() {
             let (engine_intercept_tx, engine_intercept_rx) = unbounded_channel();
@@ -1146,20 +1378,26 @@ impl<DB: Database + DatabaseMetrics + DatabaseMetadata + 'static> NodeBuilderWit
         let max_block = self.config.max_block(&network_client, provider_factory.clone()).await?;
 
         // Configure the pipeline
-        let (mut pipeline, client) = if self.config.dev.dev {
-            info!(target: "reth::cli", "Starting Reth in dev mode");
+        let (mut pipeline, client) = if self.config.seals_blocks() {
+            if self.config.dev.dev {
+                info!(target: "reth::cli", "Starting Reth in dev mode");
+            }
             let mining_mode =
                 self.config.mining_mode(transaction_pool.pending_transactions_listener());
 
-            let (_, client, mut task) = AutoSealBuilder::new(
+            let mut auto_seal = AutoSealBuilder::new(
                 Arc::clone(&self.config.chain),
                 blockchain_db.clone(),
                 transaction_pool.clone(),
                 consensus_engine_tx.clone(),
                 canon_state_notification_sender,
                 mining_mode,
-            )
-            .build();
+            );
+            if self.config.chain.genesis.config.clique.is_some() {
+                auto_seal =
+                    auto_seal.with_clique_sealer(self.config.clique_sealer(blockchain_db.clone())?);
+            }
+            let (_, client, mut task) = auto_seal.build();
 
             let mut pipeline = self
                 .config
@@ -1172,6 +1410,7 @@ impl<DB: Database + DatabaseMetrics + DatabaseMetadata + 'static> NodeBuilderWit
                     sync_metrics_tx,
                     prune_config.clone(),
                     max_block,
+                    executor_factory.clone(),
                 )
                 .await?;
 
@@ -1193,6 +1432,7 @@ impl<DB: Database + DatabaseMetrics + DatabaseMetadata + 'static> NodeBuilderWit
                     sync_metrics_tx,
                     prune_config.clone(),
                     max_block,
+                    executor_factory.clone(),
                 )
                 .await?;
 
@@ -1201,6 +1441,10 @@ impl<DB: Database + DatabaseMetrics + DatabaseMetadata + 'static> NodeBuilderWit
 
         let pipeline_events = pipeline.events();
 
+        debug!(target: "reth::cli", "Spawning pipeline progress metrics task");
+        let progress_metrics = reth_stages::PipelineProgressMetrics::new(pipeline.progress());
+        executor.spawn_critical("pipeline progress metrics task", progress_metrics);
+
         let initial_target = self.config.initial_pipeline_target(genesis_hash);
         let mut hooks = EngineHooks::new();
 
@@ -1208,7 +1452,8 @@ impl<DB: Database + DatabaseMetrics + DatabaseMetadata + 'static> NodeBuilderWit
             let mut pruner = PrunerBuilder::new(prune_config.clone())
                 .max_reorg_depth(tree_config.max_reorg_depth() as usize)
                 .prune_delete_limit(self.config.chain.prune_delete_limit)
-                .build(provider_factory, snapshotter.highest_snapshot_receiver());
+                .build(provider_factory, snapshotter.highest_snapshot_receiver())
+                .with_finished_exex_height(finished_exex_height);
 
             let events = pruner.events();
             hooks.add(PruneHook::new(pruner, Box::new(executor.clone())));
@@ -1235,6 +1480,7 @@ impl<DB: Database + DatabaseMetrics + DatabaseMetadata + 'static> NodeBuilderWit
             consensus_engine_rx,
             hooks,
         )?;
+        let beacon_consensus_engine = beacon_consensus_engine.with_consensus(consensus);
         info!(target: "reth::cli", "Consensus engine initialized");
 
         let events = stream_select!(
@@ -1337,6 +1583,17 @@ impl<DB: Database + DatabaseMetrics + DatabaseMetadata + 'static> NodeBuilderWit
 
         // Update the config with the command line arguments
         config.peers.connect_trusted_nodes_only = self.config.network.trusted_only;
+        if let Some(trusted_peers_file) = &self.config.network.trusted_peers_file {
+            config.peers.trusted_nodes_file = Some(trusted_peers_file.clone());
+        }
+
+        // restore the bans of the previous run
+        let bans_file =
+            self.config.network.persistent_peers_file(self.data_dir.banned_peers_path());
+        match config.peers.clone().with_bans_from_file(bans_file) {
+            Ok(peers) => config.peers = peers,
+            Err(err) => warn!(target: "reth::cli", %err, "Failed to load banned peers"),
+        }
 
         if !self.config.network.trusted_peers.is_empty() {
             info!(target: "reth::cli", "Adding trusted nodes");
@@ -5,7 +5,8 @@ use reth_network_api::{NetworkInfo, Peers};
 use reth_primitives::ChainSpec;
 use reth_provider::{
     AccountReader, BlockReaderIdExt, CanonStateSubscriptions, ChainSpecProvider, ChangeSetReader,
-    EvmEnvProvider, StateProviderFactory,
+    EvmEnvProvider, LogIndexReader, PruneCheckpointReader, StageCheckpointReader,
+    StateProviderFactory, TransactionAddressIndexReader,
 };
 use reth_rpc_builder::{
     auth::{AuthRpcModule, AuthServerHandle},
@@ -21,8 +22,12 @@ pub trait FullProvider:
     + AccountReader
     + StateProviderFactory
     + EvmEnvProvider
+    + StageCheckpointReader
     + ChainSpecProvider
     + ChangeSetReader
+    + LogIndexReader
+    + TransactionAddressIndexReader
+    + PruneCheckpointReader
     + Clone
     + Unpin
     + 'static
@@ -34,8 +39,12 @@ impl<T> FullProvider for T where
         + AccountReader
         + StateProviderFactory
         + EvmEnvProvider
+        + StageCheckpointReader
         + ChainSpecProvider
         + ChangeSetReader
+        + LogIndexReader
+        + TransactionAddressIndexReader
+        + PruneCheckpointReader
         + Clone
         + Unpin
         + 'static
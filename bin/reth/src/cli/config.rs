@@ -106,6 +106,12 @@ pub trait PayloadBuilderConfig {
     /// Maximum number of tasks to spawn for building a payload.
     fn max_payload_tasks(&self) -> usize;
 
+    /// Maximum number of blobs to include in built blocks.
+    fn max_blobs_per_block(&self) -> u64;
+
+    /// Whether to wait for the payload build in progress when the payload is requested.
+    fn wait_for_pending_build(&self) -> bool;
+
     /// Returns whether or not to construct the pending block.
     #[cfg(feature = "optimism")]
     fn compute_pending_block(&self) -> bool;
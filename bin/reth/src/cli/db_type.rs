@@ -2,22 +2,26 @@
 
 use crate::dirs::{ChainPath, DataDirPath, MaybePlatformPath};
 use alloy_chains::Chain;
+use eyre::WrapErr;
 use reth_db::{
-    init_db,
+    init_db_with_config,
     test_utils::{create_test_rw_db, TempDatabase},
-    DatabaseEnv,
+    DatabaseEnv, DatabaseEnvConfig, MemoryDatabase,
 };
 use reth_interfaces::db::LogLevel;
 use std::{str::FromStr, sync::Arc};
 
-/// A type that represents either a _real_ (represented by a path), or _test_ database, which will
-/// use a [TempDatabase].
+/// A type that represents either a _real_ (represented by a path), _test_ database, which will
+/// use a [TempDatabase], or _memory_ database, which will use a [MemoryDatabase].
 #[derive(Debug)]
 pub enum DatabaseBuilder {
     /// The real database type, with a specified data dir
     Real(MaybePlatformPath<DataDirPath>),
     /// The test database type
     Test,
+    /// The in-memory database type, persisted to the db path of the specified data dir without
+    /// memory-mapped files
+    Memory(MaybePlatformPath<DataDirPath>),
 }
 
 impl DatabaseBuilder {
@@ -26,17 +30,28 @@ impl DatabaseBuilder {
         Self::Test
     }
 
+    /// Creates a _memory_ database, which is persisted to the db path of the given data dir
+    pub fn memory(datadir: MaybePlatformPath<DataDirPath>) -> Self {
+        Self::Memory(datadir)
+    }
+
     /// Initializes and returns the [DatabaseInstance] depending on the current database type.
     ///
     /// If the [DatabaseBuilder] is test, then the [ChainPath] constructed will be derived from the
-    /// db path of the [TempDatabase] and the given chain. The [LogLevel] will not be used.
+    /// db path of the [TempDatabase] and the given chain. The [LogLevel] and [DatabaseEnvConfig]
+    /// will not be used.
     ///
     /// If the [DatabaseBuilder] is real, then the db will be initialized using the given log level
-    /// and the [ChainPath] will be derived from the given path and chain. This database path is
-    /// then passed into [init_db].
+    /// and config, and the [ChainPath] will be derived from the given path and chain. This database
+    /// path is then passed into [init_db_with_config].
+    ///
+    /// If the [DatabaseBuilder] is memory, then the [ChainPath] will be derived from the given path
+    /// and chain, and the [MemoryDatabase] is loaded from its db path, or created if it doesn't
+    /// exist yet. The [LogLevel] and [DatabaseEnvConfig] will not be used.
     pub fn init_db(
         self,
         log_level: Option<LogLevel>,
+        config: DatabaseEnvConfig,
         chain: Chain,
     ) -> eyre::Result<DatabaseInstance> {
         match self {
@@ -54,9 +69,21 @@ impl DatabaseBuilder {
                 let db_path = data_dir.db_path();
 
                 tracing::info!(target: "reth::cli", path = ?db_path, "Opening database");
-                let db = Arc::new(init_db(db_path.clone(), log_level)?.with_metrics());
+                let db = Arc::new(
+                    init_db_with_config(db_path.clone(), log_level, config)?.with_metrics(),
+                );
                 Ok(DatabaseInstance::Real { db, data_dir })
             }
+            DatabaseBuilder::Memory(path) => {
+                let data_dir = path.unwrap_or_chain_default(chain);
+                let db_path = data_dir.db_path();
+
+                tracing::info!(target: "reth::cli", path = ?db_path, "Opening in-memory database");
+                let db = MemoryDatabase::open(&db_path).wrap_err_with(|| {
+                    format!("Could not open in-memory database at path: {}", db_path.display())
+                })?;
+                Ok(DatabaseInstance::Memory { db: Arc::new(db), data_dir })
+            }
         }
     }
 }
@@ -86,6 +113,13 @@ pub enum DatabaseInstance {
         /// The data dir
         data_dir: ChainPath<DataDirPath>,
     },
+    /// The in-memory database
+    Memory {
+        /// The database
+        db: Arc<MemoryDatabase>,
+        /// The data dir
+        data_dir: ChainPath<DataDirPath>,
+    },
 }
 
 impl DatabaseInstance {
@@ -94,6 +128,7 @@ impl DatabaseInstance {
         match self {
             Self::Test { data_dir, .. } => data_dir,
             Self::Real { data_dir, .. } => data_dir,
+            Self::Memory { data_dir, .. } => data_dir,
         }
     }
 }
@@ -102,6 +137,12 @@ impl DatabaseInstance {
 mod tests {
     use super::*;
     use alloy_chains::Chain;
+    use reth_db::{
+        database::Database,
+        tables,
+        transaction::{DbTx, DbTxMut},
+    };
+    use reth_primitives::B256;
 
     #[test]
     fn test_database_db_dir() {
@@ -111,7 +152,7 @@ mod tests {
         let expected_db_path = tempdir.path().join("db");
         let datadir_path = MaybePlatformPath::<DataDirPath>::from(tempdir.path().to_path_buf());
         let db = DatabaseBuilder::Real(datadir_path);
-        let db = db.init_db(None, Chain::mainnet()).unwrap();
+        let db = db.init_db(None, DatabaseEnvConfig::default(), Chain::mainnet()).unwrap();
 
         // ensure that the datadir path is correct
         assert_eq!(db.data_dir().data_dir_path(), expected_datadir_path);
@@ -119,4 +160,34 @@ mod tests {
         // ensure that the db path is correct
         assert_eq!(db.data_dir().db_path(), expected_db_path);
     }
+
+    #[test]
+    fn test_memory_database_data_dir() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let datadir_path = MaybePlatformPath::<DataDirPath>::from(tempdir.path().to_path_buf());
+        let db = DatabaseBuilder::memory(datadir_path)
+            .init_db(None, DatabaseEnvConfig::default(), Chain::mainnet())
+            .unwrap();
+
+        let DatabaseInstance::Memory { db, data_dir } = db else {
+            panic!("expected an in-memory database")
+        };
+        assert_eq!(data_dir.data_dir_path(), tempdir.path());
+        assert!(data_dir.db_path().exists());
+
+        let tx = db.tx_mut().unwrap();
+        tx.put::<tables::CanonicalHeaders>(1, B256::ZERO).unwrap();
+        tx.commit().unwrap();
+        drop(db);
+
+        // the database is restored from the db path
+        let datadir_path = MaybePlatformPath::<DataDirPath>::from(tempdir.path().to_path_buf());
+        let db = DatabaseBuilder::memory(datadir_path)
+            .init_db(None, DatabaseEnvConfig::default(), Chain::mainnet())
+            .unwrap();
+        let DatabaseInstance::Memory { db, .. } = db else {
+            panic!("expected an in-memory database")
+        };
+        assert_eq!(db.tx().unwrap().get::<tables::CanonicalHeaders>(1), Ok(Some(B256::ZERO)));
+    }
 }
@@ -6,13 +6,18 @@ use crate::cli::{
 };
 use clap::Args;
 use reth_basic_payload_builder::{
-    BasicPayloadJobGenerator, BasicPayloadJobGeneratorConfig, PayloadBuilder,
+    BasicPayloadJobGenerator, BasicPayloadJobGeneratorConfig, PayloadBuilder, PayloadResolvePolicy,
 };
+use reth_db::database::Database;
+use reth_exex::LaunchExEx;
+use reth_interfaces::consensus::Consensus;
 use reth_node_api::EngineTypes;
 use reth_payload_builder::{PayloadBuilderHandle, PayloadBuilderService};
-use reth_provider::CanonStateSubscriptions;
+use reth_primitives::ChainSpec;
+use reth_provider::{CanonStateSubscriptions, ProviderFactory};
+use reth_revm::{execution_inspector::ExecutionInspectorFactory, EvmConfig};
 use reth_tasks::TaskSpawner;
-use std::{fmt, marker::PhantomData};
+use std::{fmt, marker::PhantomData, sync::Arc};
 
 /// A trait that allows for extending parts of the CLI with additional functionality.
 ///
@@ -39,11 +44,44 @@ impl RethCliExt for () {
 ///
 /// 1. [configure_network](RethNodeCommandConfig::configure_network)
 /// 2. [on_components_initialized](RethNodeCommandConfig::on_components_initialized)
-/// 3. [spawn_payload_builder_service](RethNodeCommandConfig::spawn_payload_builder_service)
-/// 4. [extend_rpc_modules](RethNodeCommandConfig::extend_rpc_modules)
-/// 5. [on_rpc_server_started](RethNodeCommandConfig::on_rpc_server_started)
-/// 6. [on_node_started](RethNodeCommandConfig::on_node_started)
+/// 3. [install_exexs](RethNodeCommandConfig::install_exexs)
+/// 4. [spawn_payload_builder_service](RethNodeCommandConfig::spawn_payload_builder_service)
+/// 5. [extend_rpc_modules](RethNodeCommandConfig::extend_rpc_modules)
+/// 6. [on_rpc_server_started](RethNodeCommandConfig::on_rpc_server_started)
+/// 7. [on_node_started](RethNodeCommandConfig::on_node_started)
 pub trait RethNodeCommandConfig: fmt::Debug {
+    /// Returns the [EvmConfig] that is used for block execution, payload building and RPC calls.
+    ///
+    /// This allows chains with additional precompiles to register them, see
+    /// [EvmConfig::with_precompiles].
+    fn evm_config(&self) -> EvmConfig {
+        EvmConfig::default()
+    }
+
+    /// Returns the [ExecutionInspectorFactory] that creates the inspectors that are attached to
+    /// the executed blocks of the execution stage and the blockchain tree, if any.
+    ///
+    /// By default, blocks are executed without inspectors.
+    fn execution_inspector_factory(&self) -> Option<Arc<dyn ExecutionInspectorFactory>> {
+        None
+    }
+
+    /// Returns the [Consensus] that validates the headers and blocks of the pipeline, the
+    /// blockchain tree and the engine, if it replaces the consensus of the chain.
+    ///
+    /// By default, this is the [BeaconConsensus](reth_beacon_consensus::BeaconConsensus), or the
+    /// Clique or auto seal consensus if configured, see
+    /// [NodeConfig::consensus_with_provider](crate::builder::NodeConfig::consensus_with_provider).
+    fn consensus<DB: Database + 'static>(
+        &self,
+        chain_spec: Arc<ChainSpec>,
+        provider_factory: ProviderFactory<DB>,
+    ) -> Option<Arc<dyn Consensus>> {
+        let _ = chain_spec;
+        let _ = provider_factory;
+        None
+    }
+
     /// Invoked with the network configuration before the network is configured.
     ///
     /// This allows additional configuration of the network before it is launched.
@@ -72,6 +110,20 @@ pub trait RethNodeCommandConfig: fmt::Debug {
         Ok(())
     }
 
+    /// Returns the execution extensions (ExExes) to launch, by their identifier.
+    ///
+    /// Every ExEx is spawned as a critical task that is notified about all changes of the
+    /// canonical chain. Data above the lowest height all ExExes have finished processing is not
+    /// pruned.
+    #[allow(clippy::type_complexity)]
+    fn install_exexs<Reth: RethNodeComponents>(
+        &mut self,
+        components: &Reth,
+    ) -> eyre::Result<Vec<(String, Box<dyn LaunchExEx<Reth>>)>> {
+        let _ = components;
+        Ok(Vec::new())
+    }
+
     /// Event hook called once the node has been launched.
     ///
     /// This is called last after the node has been launched.
@@ -151,7 +203,13 @@ pub trait RethNodeCommandConfig: fmt::Debug {
             .deadline(conf.deadline())
             .max_payload_tasks(conf.max_payload_tasks())
             .extradata(conf.extradata_rlp_bytes())
-            .max_gas_limit(conf.max_gas_limit());
+            .max_gas_limit(conf.max_gas_limit())
+            .max_blobs_per_block(conf.max_blobs_per_block())
+            .resolve_policy(if conf.wait_for_pending_build() {
+                PayloadResolvePolicy::WaitForPendingBuild
+            } else {
+                PayloadResolvePolicy::Immediate
+            });
 
         // no extradata for optimism
         #[cfg(feature = "optimism")]
@@ -248,6 +306,22 @@ impl<T> NoArgs<T> {
 }
 
 impl<T: RethNodeCommandConfig> RethNodeCommandConfig for NoArgs<T> {
+    fn evm_config(&self) -> EvmConfig {
+        self.inner().map(|conf| conf.evm_config()).unwrap_or_default()
+    }
+
+    fn execution_inspector_factory(&self) -> Option<Arc<dyn ExecutionInspectorFactory>> {
+        self.inner().and_then(|conf| conf.execution_inspector_factory())
+    }
+
+    fn consensus<DB: Database + 'static>(
+        &self,
+        chain_spec: Arc<ChainSpec>,
+        provider_factory: ProviderFactory<DB>,
+    ) -> Option<Arc<dyn Consensus>> {
+        self.inner().and_then(|conf| conf.consensus(chain_spec, provider_factory))
+    }
+
     fn configure_network<Conf, Reth>(
         &mut self,
         config: &mut Conf,
@@ -275,6 +349,18 @@ impl<T: RethNodeCommandConfig> RethNodeCommandConfig for NoArgs<T> {
         }
     }
 
+    #[allow(clippy::type_complexity)]
+    fn install_exexs<Reth: RethNodeComponents>(
+        &mut self,
+        components: &Reth,
+    ) -> eyre::Result<Vec<(String, Box<dyn LaunchExEx<Reth>>)>> {
+        if let Some(conf) = self.inner_mut() {
+            conf.install_exexs(components)
+        } else {
+            Ok(Vec::new())
+        }
+    }
+
     fn on_node_started<Reth: RethNodeComponents>(&mut self, components: &Reth) -> eyre::Result<()> {
         if let Some(conf) = self.inner_mut() {
             conf.on_node_started(components)
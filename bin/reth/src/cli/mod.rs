@@ -7,7 +7,8 @@ use crate::{
     },
     cli::ext::RethCliExt,
     commands::{
-        config_cmd, db, debug_cmd, import, init_cmd, node, p2p, recover, stage, test_vectors,
+        config_cmd, db, debug_cmd, dump_state, export_era, import, import_era, init_cmd,
+        init_state, node, p2p, recover, stage, test_vectors,
     },
     runner::CliRunner,
     version::{LONG_VERSION, SHORT_VERSION},
@@ -78,7 +79,11 @@ impl<Ext: RethCliExt> Cli<Ext> {
         match self.command {
             Commands::Node(command) => runner.run_command_until_exit(|ctx| command.execute(ctx)),
             Commands::Init(command) => runner.run_blocking_until_ctrl_c(command.execute()),
+            Commands::InitState(command) => runner.run_blocking_until_ctrl_c(command.execute()),
             Commands::Import(command) => runner.run_blocking_until_ctrl_c(command.execute()),
+            Commands::ImportEra(command) => runner.run_blocking_until_ctrl_c(command.execute()),
+            Commands::ExportEra(command) => runner.run_blocking_until_ctrl_c(command.execute()),
+            Commands::DumpState(command) => runner.run_blocking_until_ctrl_c(command.execute()),
             Commands::Db(command) => runner.run_blocking_until_ctrl_c(command.execute()),
             Commands::Stage(command) => runner.run_blocking_until_ctrl_c(command.execute()),
             Commands::P2P(command) => runner.run_until_ctrl_c(command.execute()),
@@ -123,9 +128,21 @@ pub enum Commands<Ext: RethCliExt = ()> {
     /// Initialize the database from a genesis file.
     #[command(name = "init")]
     Init(init_cmd::InitCommand),
+    /// Initialize the database from a state snapshot.
+    #[command(name = "init-state")]
+    InitState(init_state::InitStateCommand),
     /// This syncs RLP encoded blocks from a file.
     #[command(name = "import")]
     Import(import::ImportCommand),
+    /// This syncs pre-merge blocks from era1 archive files.
+    #[command(name = "import-era")]
+    ImportEra(import_era::ImportEraCommand),
+    /// Exports pre-merge blocks to era1 archive files.
+    #[command(name = "export-era")]
+    ExportEra(export_era::ExportEraCommand),
+    /// Exports the state at a block to a state snapshot.
+    #[command(name = "dump-state")]
+    DumpState(dump_state::DumpStateCommand),
     /// Database debugging utilities
     #[command(name = "db")]
     Db(db::Command),
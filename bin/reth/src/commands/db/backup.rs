@@ -0,0 +1,110 @@
+use clap::Parser;
+use eyre::WrapErr;
+use human_bytes::human_bytes;
+use reth_db::{version::db_version_file_path, DatabaseEnv};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::mpsc,
+    time::Duration,
+};
+use tracing::info;
+
+/// The name of the MDBX data file.
+const MDBX_DATA_FILE_NAME: &str = "mdbx.dat";
+
+/// The interval at which the backup progress is reported.
+const PROGRESS_INTERVAL: Duration = Duration::from_secs(5);
+
+/// The arguments for the `reth db backup` command
+#[derive(Parser, Debug)]
+pub struct Command {
+    /// The directory to write the backup to, it must not exist yet.
+    ///
+    /// The backup is laid out like a data dir, so it can be used with `--datadir` directly.
+    #[arg(value_name = "PATH", verbatim_doc_comment)]
+    path: PathBuf,
+
+    /// Omits free pages from the backup, which makes it smaller but takes more CPU time.
+    #[arg(long)]
+    compact: bool,
+}
+
+impl Command {
+    /// Execute `db backup` command
+    pub fn execute(
+        self,
+        db: &DatabaseEnv,
+        db_path: &Path,
+        snapshots_path: &Path,
+    ) -> eyre::Result<()> {
+        if self.path.exists() {
+            eyre::bail!("Backup destination {:?} already exists", self.path)
+        }
+
+        let backup_db_path = self.path.join("db");
+        fs::create_dir_all(&backup_db_path)
+            .wrap_err_with(|| format!("Could not create backup directory {backup_db_path:?}"))?;
+
+        let info = db.info()?;
+        let total_size = (info.last_pgno() + 1) * db.stat()?.page_size() as usize;
+        let backup_file = backup_db_path.join(MDBX_DATA_FILE_NAME);
+        info!(target: "reth::cli", path = ?self.path, size = %human_bytes(total_size as f64), "Starting database backup");
+
+        // the copy is made from a read transaction, so the node can keep running meanwhile
+        let backup_file = backup_file.as_path();
+        let compact = self.compact;
+        let (tx, rx) = mpsc::channel::<()>();
+        let copy = std::thread::scope(|scope| {
+            let handle = scope.spawn(move || {
+                // the sender is dropped once the copy is done, or if the thread panics
+                let _tx = tx;
+                db.copy(backup_file, compact)
+            });
+
+            while let Err(mpsc::RecvTimeoutError::Timeout) = rx.recv_timeout(PROGRESS_INTERVAL) {
+                let copied = fs::metadata(backup_file).map(|m| m.len()).unwrap_or(0);
+                info!(
+                    target: "reth::cli",
+                    copied = %human_bytes(copied as f64),
+                    progress = %format!("{:.2}%", copied as f64 / total_size as f64 * 100.0),
+                    "Backing up database"
+                );
+            }
+
+            handle.join()
+        });
+        copy.map_err(|panic| {
+            let reason = panic
+                .downcast_ref::<&str>()
+                .copied()
+                .or_else(|| panic.downcast_ref::<String>().map(String::as_str))
+                .unwrap_or("unknown");
+            eyre::eyre!("Database backup thread panicked: {reason}")
+        })?
+        .wrap_err("Could not copy database")?;
+
+        let version_file = db_version_file_path(db_path);
+        if version_file.exists() {
+            fs::copy(&version_file, db_version_file_path(&backup_db_path))?;
+        }
+
+        // snapshots are immutable once written, so they can be copied as they are
+        if snapshots_path.exists() {
+            let backup_snapshots_path = self.path.join("snapshots");
+            fs::create_dir_all(&backup_snapshots_path)?;
+            for entry in fs::read_dir(snapshots_path)? {
+                let path = entry?.path();
+                if path.is_file() {
+                    fs::copy(
+                        &path,
+                        backup_snapshots_path.join(path.file_name().expect("is file")),
+                    )?;
+                }
+            }
+        }
+
+        info!(target: "reth::cli", path = ?self.path, "Database backup finished");
+        Ok(())
+    }
+}
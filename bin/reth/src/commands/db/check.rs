@@ -0,0 +1,505 @@
+use clap::Parser;
+use reth_db::{
+    cursor::{DbCursorRO, DbCursorRW},
+    database::Database,
+    models::{sharded_key::ShardedKey, storage_sharded_key::StorageShardedKey},
+    snapshot::iter_snapshots,
+    table::Table,
+    tables,
+    transaction::{DbTx, DbTxMut},
+    BlockNumberList, Tables,
+};
+use reth_primitives::{
+    snapshot::SnapshotSegment, Address, BlockHash, BlockNumber, GotExpected, PruneSegment, TxNumber,
+};
+use std::path::Path;
+use thiserror::Error;
+use tracing::info;
+
+/// The arguments for the `reth db check` command
+#[derive(Parser, Debug)]
+pub struct Command {
+    /// Repairs the inconsistencies that can be fixed without resyncing.
+    #[arg(long)]
+    pub repair: bool,
+}
+
+impl Command {
+    /// Execute `db check` command
+    pub fn execute<DB: Database>(self, db: &DB, snapshots_path: &Path) -> eyre::Result<()> {
+        let snapshotted_headers = highest_snapshot_block(snapshots_path, SnapshotSegment::Headers)?;
+        let inconsistencies = if self.repair {
+            let tx = db.tx_mut()?;
+            let inconsistencies = check(&tx, snapshotted_headers)?;
+
+            let mut repaired = 0;
+            for inconsistency in &inconsistencies {
+                if repair(&tx, inconsistency)? {
+                    repaired += 1;
+                }
+            }
+            tx.commit()?;
+
+            info!(target: "reth::cli", repaired, "Repaired inconsistencies");
+            inconsistencies
+        } else {
+            check(&db.tx()?, snapshotted_headers)?
+        };
+
+        if inconsistencies.is_empty() {
+            println!("No inconsistencies found");
+            return Ok(())
+        }
+
+        for inconsistency in &inconsistencies {
+            let status = if !inconsistency.is_repairable() {
+                "unrepairable"
+            } else if self.repair {
+                "repaired"
+            } else {
+                "repairable"
+            };
+            println!("[{status}] {inconsistency}");
+        }
+        println!("Found {} inconsistencies", inconsistencies.len());
+
+        Ok(())
+    }
+}
+
+/// An inconsistency between database tables.
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum Inconsistency {
+    /// There is a gap in the canonical chain.
+    #[error("canonical hashes are missing for blocks #{from}..#{to}")]
+    CanonicalGap {
+        /// The first missing block number.
+        from: BlockNumber,
+        /// The last missing block number.
+        to: BlockNumber,
+    },
+    /// A canonical block has no header.
+    #[error("header of canonical block #{0} is missing")]
+    MissingHeader(BlockNumber),
+    /// A canonical block has no total difficulty.
+    #[error("total difficulty of canonical block #{0} is missing")]
+    MissingTotalDifficulty(BlockNumber),
+    /// The hash of a canonical block is not mapped to its number.
+    #[error("hash {hash} of canonical block #{number} is not mapped to its number")]
+    MissingHeaderNumber {
+        /// The number of the block.
+        number: BlockNumber,
+        /// The canonical hash of the block.
+        hash: BlockHash,
+    },
+    /// There is a gap in the block body indices.
+    #[error("body indices are missing for blocks #{from}..#{to}")]
+    BodyIndicesGap {
+        /// The first missing block number.
+        from: BlockNumber,
+        /// The last missing block number.
+        to: BlockNumber,
+    },
+    /// The transactions of a block do not start right after the transactions of its parent.
+    #[error("first transaction of block #{number} does not follow its parent: {diff}")]
+    NonContiguousTransactions {
+        /// The number of the block.
+        number: BlockNumber,
+        /// The first transaction number of the block and the expected one.
+        diff: GotExpected<TxNumber>,
+    },
+    /// A transaction table contains entries beyond the last transaction of the last block.
+    #[error("table {table} contains entries after the last transaction #{next_tx_num}")]
+    DanglingTransactionData {
+        /// The table with dangling entries.
+        table: Tables,
+        /// The first transaction number that does not belong to a block.
+        next_tx_num: TxNumber,
+    },
+    /// The blocks of a history shard are out of the shard's range.
+    #[error("history shard {key} in table {table} contains blocks outside of its range")]
+    InvalidHistoryShard {
+        /// The table of the shard.
+        table: Tables,
+        /// The key of the shard.
+        key: String,
+    },
+    /// The last account history shard is not keyed by [`u64::MAX`].
+    #[error("last account history shard of {0:?} is not keyed by u64::MAX")]
+    UnterminatedAccountHistory(ShardedKey<Address>),
+    /// The last storage history shard is not keyed by [`u64::MAX`].
+    #[error("last storage history shard of {0:?} is not keyed by u64::MAX")]
+    UnterminatedStorageHistory(StorageShardedKey),
+}
+
+impl Inconsistency {
+    /// Returns `true` if the inconsistency can be repaired from the remaining data.
+    pub fn is_repairable(&self) -> bool {
+        matches!(
+            self,
+            Inconsistency::MissingHeaderNumber { .. } |
+                Inconsistency::DanglingTransactionData { .. } |
+                Inconsistency::UnterminatedAccountHistory(_) |
+                Inconsistency::UnterminatedStorageHistory(_)
+        )
+    }
+}
+
+/// Returns the highest block of the snapshots of the segment in the given directory.
+fn highest_snapshot_block(
+    snapshots_path: &Path,
+    segment: SnapshotSegment,
+) -> eyre::Result<Option<BlockNumber>> {
+    if !snapshots_path.exists() {
+        return Ok(None)
+    }
+    let snapshots = iter_snapshots(snapshots_path)?;
+    Ok(snapshots
+        .get(&segment)
+        .and_then(|ranges| ranges.iter().map(|(block_range, _)| *block_range.end()).max()))
+}
+
+/// Checks the invariants between the tables and returns all inconsistencies.
+///
+/// The headers up to `snapshotted_headers` are served from the snapshots, so they're expected to
+/// be missing from the database.
+pub(crate) fn check<TX: DbTx>(
+    tx: &TX,
+    snapshotted_headers: Option<BlockNumber>,
+) -> eyre::Result<Vec<Inconsistency>> {
+    // the pruner removes the headers from the database once they're snapshotted, the checkpoint
+    // also covers the snapshots that were offloaded
+    let pruned_headers = tx
+        .get::<tables::PruneCheckpoints>(PruneSegment::Headers)?
+        .and_then(|checkpoint| checkpoint.block_number);
+    let first_header = pruned_headers.max(snapshotted_headers).map_or(0, |number| number + 1);
+
+    let mut inconsistencies = Vec::new();
+    check_canonical_chain(tx, first_header, &mut inconsistencies)?;
+    check_body_indices(tx, &mut inconsistencies)?;
+    check_account_history(tx, &mut inconsistencies)?;
+    check_storage_history(tx, &mut inconsistencies)?;
+    Ok(inconsistencies)
+}
+
+/// Checks that the canonical chain has no gaps and that every canonical block has a header, a
+/// total difficulty and a hash to number mapping.
+///
+/// The headers of the blocks before `first_header` were pruned or moved to snapshots, so only
+/// their hash to number mapping is checked.
+fn check_canonical_chain<TX: DbTx>(
+    tx: &TX,
+    first_header: BlockNumber,
+    inconsistencies: &mut Vec<Inconsistency>,
+) -> eyre::Result<()> {
+    let mut expected = first_header;
+    for entry in tx.cursor_read::<tables::CanonicalHeaders>()?.walk(None)? {
+        let (number, hash) = entry?;
+        if number > expected {
+            inconsistencies.push(Inconsistency::CanonicalGap { from: expected, to: number - 1 });
+        }
+        expected = expected.max(number + 1);
+
+        if number >= first_header {
+            if tx.get::<tables::Headers>(number)?.is_none() {
+                inconsistencies.push(Inconsistency::MissingHeader(number));
+            }
+            if tx.get::<tables::HeaderTD>(number)?.is_none() {
+                inconsistencies.push(Inconsistency::MissingTotalDifficulty(number));
+            }
+        }
+        if tx.get::<tables::HeaderNumbers>(hash)? != Some(number) {
+            inconsistencies.push(Inconsistency::MissingHeaderNumber { number, hash });
+        }
+    }
+    Ok(())
+}
+
+/// Checks that the block body indices have no gaps, that the transactions of consecutive blocks
+/// are contiguous and that there is no transaction data beyond the last block.
+fn check_body_indices<TX: DbTx>(
+    tx: &TX,
+    inconsistencies: &mut Vec<Inconsistency>,
+) -> eyre::Result<()> {
+    let mut expected: Option<(BlockNumber, TxNumber)> = None;
+    for entry in tx.cursor_read::<tables::BlockBodyIndices>()?.walk(None)? {
+        let (number, indices) = entry?;
+        let (expected_number, expected_tx_num) = expected.unwrap_or((0, 0));
+        if number != expected_number {
+            inconsistencies
+                .push(Inconsistency::BodyIndicesGap { from: expected_number, to: number - 1 });
+        } else if indices.first_tx_num != expected_tx_num {
+            inconsistencies.push(Inconsistency::NonContiguousTransactions {
+                number,
+                diff: GotExpected::new(indices.first_tx_num, expected_tx_num),
+            });
+        }
+        expected = Some((number + 1, indices.next_tx_num()));
+    }
+
+    let next_tx_num = expected.map(|(_, next_tx_num)| next_tx_num).unwrap_or_default();
+    check_dangling::<TX, tables::Transactions>(tx, next_tx_num, inconsistencies)?;
+    check_dangling::<TX, tables::TransactionBlock>(tx, next_tx_num, inconsistencies)?;
+    check_dangling::<TX, tables::TxSenders>(tx, next_tx_num, inconsistencies)?;
+    check_dangling::<TX, tables::Receipts>(tx, next_tx_num, inconsistencies)?;
+    Ok(())
+}
+
+/// Checks that the given transaction table has no entries at or after `next_tx_num`.
+fn check_dangling<TX: DbTx, T: Table<Key = TxNumber>>(
+    tx: &TX,
+    next_tx_num: TxNumber,
+    inconsistencies: &mut Vec<Inconsistency>,
+) -> eyre::Result<()> {
+    if tx.cursor_read::<T>()?.last()?.is_some_and(|(tx_num, _)| tx_num >= next_tx_num) {
+        inconsistencies.push(Inconsistency::DanglingTransactionData {
+            table: T::NAME.parse().expect("table exists"),
+            next_tx_num,
+        });
+    }
+    Ok(())
+}
+
+/// Returns `true` if all blocks of the shard are above the previous shard and not above its
+/// highest block number.
+fn is_shard_in_range(
+    list: &BlockNumberList,
+    previous_highest: Option<BlockNumber>,
+    highest_block_number: BlockNumber,
+) -> bool {
+    let mut blocks = list.iter(0).map(|block| block as BlockNumber);
+    let first = blocks.next();
+    let last = blocks.last().or(first);
+    first.map_or(true, |first| previous_highest.map_or(true, |previous| first > previous)) &&
+        last.map_or(true, |last| last <= highest_block_number)
+}
+
+/// Checks that the account history shards are ordered and that the last shard of every account is
+/// keyed by [`u64::MAX`].
+fn check_account_history<TX: DbTx>(
+    tx: &TX,
+    inconsistencies: &mut Vec<Inconsistency>,
+) -> eyre::Result<()> {
+    let mut previous: Option<ShardedKey<Address>> = None;
+    for entry in tx.cursor_read::<tables::AccountHistory>()?.walk(None)? {
+        let (key, list) = entry?;
+
+        let previous_highest = match previous.take() {
+            Some(previous) if previous.key == key.key => Some(previous.highest_block_number),
+            Some(previous) => {
+                if previous.highest_block_number != u64::MAX {
+                    inconsistencies.push(Inconsistency::UnterminatedAccountHistory(previous));
+                }
+                None
+            }
+            None => None,
+        };
+
+        if !is_shard_in_range(&list, previous_highest, key.highest_block_number) {
+            inconsistencies.push(Inconsistency::InvalidHistoryShard {
+                table: Tables::AccountHistory,
+                key: format!("{key:?}"),
+            });
+        }
+        previous = Some(key);
+    }
+
+    if let Some(previous) = previous.filter(|key| key.highest_block_number != u64::MAX) {
+        inconsistencies.push(Inconsistency::UnterminatedAccountHistory(previous));
+    }
+    Ok(())
+}
+
+/// Checks that the storage history shards are ordered and that the last shard of every storage
+/// slot is keyed by [`u64::MAX`].
+fn check_storage_history<TX: DbTx>(
+    tx: &TX,
+    inconsistencies: &mut Vec<Inconsistency>,
+) -> eyre::Result<()> {
+    let mut previous: Option<StorageShardedKey> = None;
+    for entry in tx.cursor_read::<tables::StorageHistory>()?.walk(None)? {
+        let (key, list) = entry?;
+
+        let previous_highest = match previous.take() {
+            Some(previous)
+                if previous.address == key.address &&
+                    previous.sharded_key.key == key.sharded_key.key =>
+            {
+                Some(previous.sharded_key.highest_block_number)
+            }
+            Some(previous) => {
+                if previous.sharded_key.highest_block_number != u64::MAX {
+                    inconsistencies.push(Inconsistency::UnterminatedStorageHistory(previous));
+                }
+                None
+            }
+            None => None,
+        };
+
+        if !is_shard_in_range(&list, previous_highest, key.sharded_key.highest_block_number) {
+            inconsistencies.push(Inconsistency::InvalidHistoryShard {
+                table: Tables::StorageHistory,
+                key: format!("{key:?}"),
+            });
+        }
+        previous = Some(key);
+    }
+
+    if let Some(previous) = previous.filter(|key| key.sharded_key.highest_block_number != u64::MAX)
+    {
+        inconsistencies.push(Inconsistency::UnterminatedStorageHistory(previous));
+    }
+    Ok(())
+}
+
+/// Repairs the given inconsistency, returns `false` if it can't be repaired.
+pub(crate) fn repair<TX: DbTxMut + DbTx>(
+    tx: &TX,
+    inconsistency: &Inconsistency,
+) -> eyre::Result<bool> {
+    match inconsistency {
+        Inconsistency::MissingHeaderNumber { number, hash } => {
+            tx.put::<tables::HeaderNumbers>(*hash, *number)?;
+        }
+        Inconsistency::DanglingTransactionData { table, next_tx_num } => match table {
+            Tables::Transactions => delete_from::<TX, tables::Transactions>(tx, *next_tx_num)?,
+            Tables::TransactionBlock => {
+                delete_from::<TX, tables::TransactionBlock>(tx, *next_tx_num)?
+            }
+            Tables::TxSenders => delete_from::<TX, tables::TxSenders>(tx, *next_tx_num)?,
+            Tables::Receipts => delete_from::<TX, tables::Receipts>(tx, *next_tx_num)?,
+            _ => return Ok(false),
+        },
+        Inconsistency::UnterminatedAccountHistory(key) => {
+            if let Some(list) = tx.get::<tables::AccountHistory>(key.clone())? {
+                tx.delete::<tables::AccountHistory>(key.clone(), None)?;
+                tx.put::<tables::AccountHistory>(ShardedKey::new(key.key, u64::MAX), list)?;
+            }
+        }
+        Inconsistency::UnterminatedStorageHistory(key) => {
+            if let Some(list) = tx.get::<tables::StorageHistory>(key.clone())? {
+                tx.delete::<tables::StorageHistory>(key.clone(), None)?;
+                tx.put::<tables::StorageHistory>(
+                    StorageShardedKey::new(key.address, key.sharded_key.key, u64::MAX),
+                    list,
+                )?;
+            }
+        }
+        _ => return Ok(false),
+    }
+    Ok(true)
+}
+
+/// Deletes all entries of the given table starting at `tx_num`.
+fn delete_from<TX: DbTxMut, T: Table<Key = TxNumber>>(
+    tx: &TX,
+    tx_num: TxNumber,
+) -> eyre::Result<()> {
+    let mut cursor = tx.cursor_write::<T>()?;
+    let mut walker = cursor.walk(Some(tx_num))?;
+    while walker.next().transpose()?.is_some() {
+        walker.delete_current()?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_db::{models::StoredBlockBodyIndices, test_utils::create_test_rw_db};
+    use reth_primitives::{Header, PruneCheckpoint, PruneMode};
+
+    #[test]
+    fn check_and_repair() {
+        let db = create_test_rw_db();
+
+        let tx = db.tx_mut().unwrap();
+        for number in 0..3 {
+            let header = Header { number, ..Default::default() };
+            let hash = header.hash_slow();
+            tx.put::<tables::CanonicalHeaders>(number, hash).unwrap();
+            tx.put::<tables::Headers>(number, header).unwrap();
+            tx.put::<tables::HeaderTD>(number, Default::default()).unwrap();
+            // the hash of the last block is not mapped to its number
+            if number < 2 {
+                tx.put::<tables::HeaderNumbers>(hash, number).unwrap();
+            }
+            tx.put::<tables::BlockBodyIndices>(
+                number,
+                StoredBlockBodyIndices { first_tx_num: number, tx_count: 1 },
+            )
+            .unwrap();
+        }
+        // sender of a transaction that does not belong to a block
+        tx.put::<tables::TxSenders>(3, Address::ZERO).unwrap();
+        // history that is not terminated by a `u64::MAX` shard
+        tx.put::<tables::AccountHistory>(
+            ShardedKey::new(Address::ZERO, 10),
+            BlockNumberList::new([1, 10]).unwrap(),
+        )
+        .unwrap();
+        tx.commit().unwrap();
+
+        let inconsistencies = check(&db.tx().unwrap(), None).unwrap();
+        let hash = Header { number: 2, ..Default::default() }.hash_slow();
+        assert_eq!(
+            inconsistencies,
+            vec![
+                Inconsistency::MissingHeaderNumber { number: 2, hash },
+                Inconsistency::DanglingTransactionData { table: Tables::TxSenders, next_tx_num: 3 },
+                Inconsistency::UnterminatedAccountHistory(ShardedKey::new(Address::ZERO, 10)),
+            ]
+        );
+        assert!(inconsistencies.iter().all(Inconsistency::is_repairable));
+
+        let tx = db.tx_mut().unwrap();
+        for inconsistency in &inconsistencies {
+            assert!(repair(&tx, inconsistency).unwrap());
+        }
+        tx.commit().unwrap();
+
+        assert_eq!(check(&db.tx().unwrap(), None).unwrap(), vec![]);
+    }
+
+    #[test]
+    fn skip_pruned_and_snapshotted_headers() {
+        let db = create_test_rw_db();
+
+        // the headers of the blocks before #5 were moved to snapshots and pruned
+        let tx = db.tx_mut().unwrap();
+        for number in 5..8 {
+            let header = Header { number, ..Default::default() };
+            let hash = header.hash_slow();
+            tx.put::<tables::CanonicalHeaders>(number, hash).unwrap();
+            tx.put::<tables::Headers>(number, header).unwrap();
+            tx.put::<tables::HeaderTD>(number, Default::default()).unwrap();
+            tx.put::<tables::HeaderNumbers>(hash, number).unwrap();
+        }
+        tx.commit().unwrap();
+
+        assert_eq!(
+            check(&db.tx().unwrap(), None).unwrap(),
+            vec![Inconsistency::CanonicalGap { from: 0, to: 4 }]
+        );
+        // the snapshots cover the missing headers
+        assert_eq!(check(&db.tx().unwrap(), Some(4)).unwrap(), vec![]);
+        assert_eq!(
+            check(&db.tx().unwrap(), Some(3)).unwrap(),
+            vec![Inconsistency::CanonicalGap { from: 4, to: 4 }]
+        );
+
+        // the pruned headers are expected to be missing, even if the snapshots were offloaded
+        let tx = db.tx_mut().unwrap();
+        tx.put::<tables::PruneCheckpoints>(
+            PruneSegment::Headers,
+            PruneCheckpoint {
+                block_number: Some(4),
+                tx_number: None,
+                prune_mode: PruneMode::Before(5),
+            },
+        )
+        .unwrap();
+        tx.commit().unwrap();
+        assert_eq!(check(&db.tx().unwrap(), None).unwrap(), vec![]);
+    }
+}
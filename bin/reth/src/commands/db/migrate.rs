@@ -0,0 +1,42 @@
+use clap::Parser;
+use reth_db::{migration::Migrator, DatabaseEnv};
+use std::path::Path;
+
+/// The arguments for the `reth db migrate` command
+#[derive(Parser, Debug)]
+pub struct Command {
+    /// Applies the pending migrations and rolls them back afterwards, leaving the database
+    /// unchanged
+    #[arg(long)]
+    pub dry_run: bool,
+}
+
+impl Command {
+    /// Execute `db migrate` command
+    pub fn execute(self, db: &DatabaseEnv, db_path: &Path) -> eyre::Result<()> {
+        let migrator = Migrator::new(db, db_path).with_dry_run(self.dry_run);
+
+        let pending = migrator.pending()?;
+        if pending.is_empty() {
+            println!("Database is up to date");
+            return Ok(())
+        }
+
+        for migration in pending {
+            println!("Pending migration to v{}: {}", migration.version, migration.description);
+        }
+
+        let outcome = migrator.run()?;
+        if self.dry_run {
+            println!(
+                "Dry run applied {} migration(s) successfully, database remains at v{}",
+                outcome.applied.len(),
+                outcome.to_version
+            );
+        } else {
+            println!("Migrated database from v{} to v{}", outcome.from_version, outcome.to_version);
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,200 @@
+use crate::utils::DbTool;
+use clap::Parser;
+use comfy_table::{Cell, Row, Table as ComfyTable};
+use eyre::WrapErr;
+use human_bytes::human_bytes;
+use reth_db::{database::Database, mdbx, DatabaseEnv, Tables};
+use reth_primitives::snapshot::SnapshotSegment;
+use serde::Serialize;
+use std::{collections::BTreeMap, fs, path::Path};
+
+/// The arguments for the `reth db stats` command
+#[derive(Parser, Debug)]
+pub struct Command {
+    /// Prints the stats as JSON instead of a table.
+    #[arg(long)]
+    json: bool,
+}
+
+/// The stats of a single database table.
+#[derive(Debug, Serialize)]
+struct TableStats {
+    name: &'static str,
+    entries: usize,
+    branch_pages: usize,
+    leaf_pages: usize,
+    overflow_pages: usize,
+    size: usize,
+}
+
+/// The stats of all snapshot files of a single segment.
+#[derive(Debug, Serialize)]
+struct SnapshotStats {
+    segment: SnapshotSegment,
+    files: usize,
+    size: u64,
+}
+
+/// The stats of the database and the snapshots.
+#[derive(Debug, Serialize)]
+struct Stats {
+    tables: Vec<TableStats>,
+    total_size: usize,
+    freelist_pages: usize,
+    freelist_size: usize,
+    snapshots: Vec<SnapshotStats>,
+}
+
+impl Command {
+    /// Execute `db stats` command
+    pub fn execute(
+        self,
+        tool: &DbTool<'_, DatabaseEnv>,
+        snapshots_path: &Path,
+    ) -> eyre::Result<()> {
+        let mut stats = tool.db.view(|tx| {
+            let mut tables = Tables::ALL.iter().map(|table| table.name()).collect::<Vec<_>>();
+            tables.sort();
+
+            let mut stats = Vec::with_capacity(tables.len());
+            for table in tables {
+                let table_db = tx.inner.open_db(Some(table)).wrap_err("Could not open db.")?;
+
+                let table_stats = tx
+                    .inner
+                    .db_stat(&table_db)
+                    .wrap_err(format!("Could not find table: {table}"))?;
+
+                // Defaults to 16KB right now but we should
+                // re-evaluate depending on the DB we end up using
+                // (e.g. REDB does not have these options as configurable intentionally)
+                let page_size = table_stats.page_size() as usize;
+                let leaf_pages = table_stats.leaf_pages();
+                let branch_pages = table_stats.branch_pages();
+                let overflow_pages = table_stats.overflow_pages();
+                let num_pages = leaf_pages + branch_pages + overflow_pages;
+
+                stats.push(TableStats {
+                    name: table,
+                    entries: table_stats.entries(),
+                    branch_pages,
+                    leaf_pages,
+                    overflow_pages,
+                    size: page_size * num_pages,
+                });
+            }
+
+            let freelist_pages = tx.inner.env().freelist()?;
+            let freelist_size = freelist_pages *
+                tx.inner.db_stat(&mdbx::Database::freelist_db())?.page_size() as usize;
+
+            Ok::<_, eyre::Report>(Stats {
+                total_size: stats.iter().map(|table| table.size).sum(),
+                tables: stats,
+                freelist_pages,
+                freelist_size,
+                snapshots: Vec::new(),
+            })
+        })??;
+        stats.snapshots = snapshot_stats(snapshots_path)?;
+
+        if self.json {
+            println!("{}", serde_json::to_string_pretty(&stats)?);
+        } else {
+            print_tables(&stats);
+        }
+
+        Ok(())
+    }
+}
+
+/// Collects the number of files and their total size for each snapshot segment.
+fn snapshot_stats(snapshots_path: &Path) -> eyre::Result<Vec<SnapshotStats>> {
+    let mut segments = BTreeMap::<SnapshotSegment, (usize, u64)>::new();
+    if snapshots_path.exists() {
+        for entry in fs::read_dir(snapshots_path)? {
+            let entry = entry?;
+            let Some(stem) = entry.path().file_stem().map(ToOwned::to_owned) else { continue };
+            if let Some((segment, _, _)) = SnapshotSegment::parse_filename(&stem) {
+                let (files, size) = segments.entry(segment).or_default();
+                *files += 1;
+                *size += entry.metadata()?.len();
+            }
+        }
+    }
+
+    Ok(segments
+        .into_iter()
+        .map(|(segment, (files, size))| SnapshotStats { segment, files, size })
+        .collect())
+}
+
+/// Prints the stats as tables.
+fn print_tables(stats: &Stats) {
+    let mut stats_table = ComfyTable::new();
+    stats_table.load_preset(comfy_table::presets::ASCII_MARKDOWN);
+    stats_table.set_header([
+        "Table Name",
+        "# Entries",
+        "Branch Pages",
+        "Leaf Pages",
+        "Overflow Pages",
+        "Total Size",
+    ]);
+
+    for table in &stats.tables {
+        let mut row = Row::new();
+        row.add_cell(Cell::new(table.name))
+            .add_cell(Cell::new(table.entries))
+            .add_cell(Cell::new(table.branch_pages))
+            .add_cell(Cell::new(table.leaf_pages))
+            .add_cell(Cell::new(table.overflow_pages))
+            .add_cell(Cell::new(human_bytes(table.size as f64)));
+        stats_table.add_row(row);
+    }
+
+    let max_widths = stats_table.column_max_content_widths();
+
+    let mut seperator = Row::new();
+    for width in max_widths {
+        seperator.add_cell(Cell::new("-".repeat(width as usize)));
+    }
+    stats_table.add_row(seperator);
+
+    let mut row = Row::new();
+    row.add_cell(Cell::new("Total DB size"))
+        .add_cell(Cell::new(""))
+        .add_cell(Cell::new(""))
+        .add_cell(Cell::new(""))
+        .add_cell(Cell::new(""))
+        .add_cell(Cell::new(human_bytes(stats.total_size as f64)));
+    stats_table.add_row(row);
+
+    let mut row = Row::new();
+    row.add_cell(Cell::new("Freelist size"))
+        .add_cell(Cell::new(stats.freelist_pages))
+        .add_cell(Cell::new(""))
+        .add_cell(Cell::new(""))
+        .add_cell(Cell::new(""))
+        .add_cell(Cell::new(human_bytes(stats.freelist_size as f64)));
+    stats_table.add_row(row);
+
+    println!("{stats_table}");
+
+    if stats.snapshots.is_empty() {
+        return
+    }
+
+    let mut snapshots_table = ComfyTable::new();
+    snapshots_table.load_preset(comfy_table::presets::ASCII_MARKDOWN);
+    snapshots_table.set_header(["Snapshot Segment", "# Files", "Total Size"]);
+    for snapshot in &stats.snapshots {
+        let mut row = Row::new();
+        row.add_cell(Cell::new(snapshot.segment))
+            .add_cell(Cell::new(snapshot.files))
+            .add_cell(Cell::new(human_bytes(snapshot.size as f64)));
+        snapshots_table.add_row(row);
+    }
+
+    println!("{snapshots_table}");
+}
@@ -18,7 +18,7 @@ use reth_beacon_consensus::BeaconConsensus;
 use reth_blockchain_tree::{
     BlockchainTree, BlockchainTreeConfig, ShareableBlockchainTree, TreeExternals,
 };
-use reth_db::{init_db, DatabaseEnv};
+use reth_db::{init_db_with_config, DatabaseEnv};
 use reth_interfaces::{consensus::Consensus, RethResult};
 use reth_node_api::PayloadBuilderAttributes;
 use reth_payload_builder::database::CachedReads;
@@ -150,7 +150,8 @@ impl Command {
         fs::create_dir_all(&db_path)?;
 
         // initialize the database
-        let db = Arc::new(init_db(db_path, self.db.log_level)?);
+        let db =
+            Arc::new(init_db_with_config(db_path, self.db.log_level, self.db.database_config())?);
         let provider_factory = ProviderFactory::new(Arc::clone(&db), Arc::clone(&self.chain));
 
         let consensus: Arc<dyn Consensus> = Arc::new(BeaconConsensus::new(Arc::clone(&self.chain)));
@@ -278,11 +279,11 @@ impl Command {
         );
 
         #[cfg(feature = "optimism")]
-        let payload_builder = reth_optimism_payload_builder::OptimismPayloadBuilder::default()
+        let payload_builder = <reth_optimism_payload_builder::OptimismPayloadBuilder>::default()
             .compute_pending_block();
 
         #[cfg(not(feature = "optimism"))]
-        let payload_builder = reth_ethereum_payload_builder::EthereumPayloadBuilder::default();
+        let payload_builder = <reth_ethereum_payload_builder::EthereumPayloadBuilder>::default();
 
         match payload_builder.try_build(args)? {
             BuildOutcome::Better { payload, .. } => {
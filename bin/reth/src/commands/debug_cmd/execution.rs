@@ -16,7 +16,7 @@ use clap::Parser;
 use futures::{stream::select as stream_select, StreamExt};
 use reth_beacon_consensus::BeaconConsensus;
 use reth_config::Config;
-use reth_db::{database::Database, init_db, DatabaseEnv};
+use reth_db::{database::Database, init_db_with_config, DatabaseEnv};
 use reth_downloaders::{
     bodies::bodies::BodiesDownloaderBuilder,
     headers::reverse_headers::ReverseHeadersDownloaderBuilder,
@@ -127,15 +127,18 @@ impl Command {
                     TotalDifficultyStage::new(consensus)
                         .with_commit_threshold(stage_conf.total_difficulty.commit_threshold),
                 )
-                .set(SenderRecoveryStage {
-                    commit_threshold: stage_conf.sender_recovery.commit_threshold,
-                })
+                .set(
+                    SenderRecoveryStage::new(stage_conf.sender_recovery.commit_threshold)
+                        .with_parallelism(stage_conf.sender_recovery.parallelism)
+                        .with_batch_size(stage_conf.sender_recovery.batch_size),
+                )
                 .set(ExecutionStage::new(
                     factory,
                     ExecutionStageThresholds {
                         max_blocks: None,
                         max_changes: None,
                         max_cumulative_gas: None,
+                        max_duration: None,
                     },
                     stage_conf
                         .merkle
@@ -163,7 +166,7 @@ impl Command {
             .network
             .network_config(config, self.chain.clone(), secret_key, default_peers_path)
             .with_task_executor(Box::new(task_executor))
-            .listener_addr(SocketAddr::V4(SocketAddrV4::new(self.network.addr, self.network.port)))
+            .listener_addr(self.network.listener_addr(self.network.port))
             .discovery_addr(SocketAddr::V4(SocketAddrV4::new(
                 self.network.discovery.addr,
                 self.network.discovery.port,
@@ -202,7 +205,8 @@ impl Command {
         let data_dir = self.datadir.unwrap_or_chain_default(self.chain.chain);
         let db_path = data_dir.db_path();
         fs::create_dir_all(&db_path)?;
-        let db = Arc::new(init_db(db_path, self.db.log_level)?);
+        let db =
+            Arc::new(init_db_with_config(db_path, self.db.log_level, self.db.database_config())?);
         let provider_factory = ProviderFactory::new(db.clone(), self.chain.clone());
 
         debug!(target: "reth::cli", chain=%self.chain.chain, genesis=?self.chain.genesis_hash(), "Initializing genesis");
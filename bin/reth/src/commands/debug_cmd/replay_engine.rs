@@ -17,7 +17,7 @@ use reth_blockchain_tree::{
     BlockchainTree, BlockchainTreeConfig, ShareableBlockchainTree, TreeExternals,
 };
 use reth_config::Config;
-use reth_db::{init_db, DatabaseEnv};
+use reth_db::{init_db_with_config, DatabaseEnv};
 use reth_interfaces::consensus::Consensus;
 use reth_network::NetworkHandle;
 use reth_network_api::NetworkInfo;
@@ -110,7 +110,7 @@ impl Command {
             .network
             .network_config(config, self.chain.clone(), secret_key, default_peers_path)
             .with_task_executor(Box::new(task_executor))
-            .listener_addr(SocketAddr::V4(SocketAddrV4::new(self.network.addr, self.network.port)))
+            .listener_addr(self.network.listener_addr(self.network.port))
             .discovery_addr(SocketAddr::V4(SocketAddrV4::new(
                 self.network.discovery.addr,
                 self.network.discovery.port,
@@ -133,7 +133,8 @@ impl Command {
         fs::create_dir_all(&db_path)?;
 
         // Initialize the database
-        let db = Arc::new(init_db(db_path, self.db.log_level)?);
+        let db =
+            Arc::new(init_db_with_config(db_path, self.db.log_level, self.db.database_config())?);
         let provider_factory = ProviderFactory::new(db.clone(), self.chain.clone());
 
         let consensus: Arc<dyn Consensus> = Arc::new(BeaconConsensus::new(Arc::clone(&self.chain)));
@@ -166,11 +167,11 @@ impl Command {
 
         // Set up payload builder
         #[cfg(not(feature = "optimism"))]
-        let payload_builder = reth_ethereum_payload_builder::EthereumPayloadBuilder::default();
+        let payload_builder = <reth_ethereum_payload_builder::EthereumPayloadBuilder>::default();
 
         // Optimism's payload builder is implemented on the OptimismPayloadBuilder type.
         #[cfg(feature = "optimism")]
-        let payload_builder = reth_optimism_payload_builder::OptimismPayloadBuilder::default();
+        let payload_builder = <reth_optimism_payload_builder::OptimismPayloadBuilder>::default();
 
         let payload_generator = BasicPayloadJobGenerator::with_builder(
             blockchain_db.clone(),
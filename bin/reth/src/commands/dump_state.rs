@@ -0,0 +1,422 @@
+//! Command that exports the state at a block to a state snapshot.
+
+use crate::{
+    args::{
+        utils::{chain_help, genesis_value_parser, SUPPORTED_CHAINS},
+        DatabaseArgs,
+    },
+    dirs::{DataDirPath, MaybePlatformPath},
+    version::SHORT_VERSION,
+};
+use clap::Parser;
+use eyre::Context;
+use reth_db::{
+    cursor::{DbCursorRO, DbDupCursorRO},
+    models::{storage_sharded_key::StorageShardedKey, ShardedKey},
+    open_db_read_only, tables,
+    transaction::DbTx,
+};
+use reth_primitives::{
+    stage::StageId, Address, BlockNumber, Bytes, ChainSpec, Header, PruneSegment, B256, U256,
+};
+use reth_provider::{
+    AccountReader, HeaderProvider, HistoricalStateProviderRef, ProviderFactory,
+    PruneCheckpointReader, StageCheckpointReader, StateProvider,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashSet,
+    fs::File,
+    io::{BufWriter, Write},
+    path::PathBuf,
+    sync::Arc,
+};
+use tracing::info;
+
+/// The number of ancestor headers of the block that are part of a state snapshot.
+///
+/// The `BLOCKHASH` opcode can look up the hashes of the last 256 blocks, which the node can't
+/// recover on its own since the history before the snapshot is not available.
+pub(crate) const ANCESTOR_HEADERS: u64 = 256;
+
+/// An entry of a state snapshot.
+///
+/// A snapshot is a stream of entries, one JSON object per line: the [StateEntry::Header] of the
+/// block the state belongs to first, followed by the [StateEntry::AncestorHeader]s of the
+/// [ANCESTOR_HEADERS] preceding blocks in descending order, then every [StateEntry::Account] in
+/// ascending order of addresses, each followed by its [StateEntry::Storage] slots in ascending
+/// order of keys. The [StateEntry::Bytecode] of a contract precedes the first account with the
+/// code.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum StateEntry {
+    /// The header of the block the state belongs to.
+    #[serde(rename_all = "camelCase")]
+    Header {
+        /// The block header.
+        header: Header,
+        /// Total difficulty of the chain at the block.
+        total_difficulty: U256,
+    },
+    /// The header of a block preceding the block the state belongs to.
+    AncestorHeader {
+        /// The block header.
+        header: Header,
+    },
+    /// A contract bytecode, referenced by its hash from the accounts.
+    Bytecode {
+        /// The raw bytecode.
+        code: Bytes,
+    },
+    /// An account.
+    #[serde(rename_all = "camelCase")]
+    Account {
+        /// The address of the account.
+        address: Address,
+        /// The nonce of the account.
+        nonce: u64,
+        /// The balance of the account.
+        balance: U256,
+        /// The hash of the bytecode of the account, if it's a contract.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        code_hash: Option<B256>,
+    },
+    /// A non-zero storage slot of the preceding account.
+    Storage {
+        /// The storage key.
+        key: B256,
+        /// The storage value.
+        value: U256,
+    },
+}
+
+/// Exports the state at a block to a state snapshot.
+///
+/// The snapshot contains all accounts, storage slots and bytecodes at the block, and can be used
+/// to bootstrap a node with `reth init-state`, or to compare the state with other clients.
+#[derive(Debug, Parser)]
+pub struct DumpStateCommand {
+    /// The path to the data dir for all reth files and subdirectories.
+    ///
+    /// Defaults to the OS-specific data directory:
+    ///
+    /// - Linux: `$XDG_DATA_HOME/reth/` or `$HOME/.local/share/reth/`
+    /// - Windows: `{FOLDERID_RoamingAppData}/reth/`
+    /// - macOS: `$HOME/Library/Application Support/reth/`
+    #[arg(long, value_name = "DATA_DIR", verbatim_doc_comment, default_value_t)]
+    datadir: MaybePlatformPath<DataDirPath>,
+
+    /// The chain this node is running.
+    ///
+    /// Possible values are either a built-in chain or the path to a chain specification file.
+    #[arg(
+        long,
+        value_name = "CHAIN_OR_PATH",
+        long_help = chain_help(),
+        default_value = SUPPORTED_CHAINS[0],
+        value_parser = genesis_value_parser
+    )]
+    chain: Arc<ChainSpec>,
+
+    #[clap(flatten)]
+    db: DatabaseArgs,
+
+    /// The block to export the state at.
+    ///
+    /// Defaults to the last executed block. The state at older blocks is reconstructed from the
+    /// changesets, so they must not be pruned.
+    #[arg(long, value_name = "BLOCK_NUMBER")]
+    block: Option<BlockNumber>,
+
+    /// The file to write the state snapshot to.
+    #[arg(value_name = "OUTPUT_FILE", verbatim_doc_comment)]
+    path: PathBuf,
+}
+
+impl DumpStateCommand {
+    /// Execute `dump-state` command
+    pub async fn execute(self) -> eyre::Result<()> {
+        info!(target: "reth::cli", "reth {} starting", SHORT_VERSION);
+
+        // add network name to data dir
+        let data_dir = self.datadir.unwrap_or_chain_default(self.chain.chain);
+        let db_path = data_dir.db_path();
+
+        info!(target: "reth::cli", path = ?db_path, "Opening database");
+        let db = open_db_read_only(&db_path, self.db.log_level)?;
+        let provider_factory = ProviderFactory::new(&db, self.chain.clone());
+        let provider = provider_factory.provider()?;
+
+        let tip = provider
+            .get_stage_checkpoint(StageId::Execution)?
+            .map(|checkpoint| checkpoint.block_number)
+            .unwrap_or_default();
+        let block = self.block.unwrap_or(tip);
+        if block > tip {
+            eyre::bail!("block #{block} is not executed yet, the last executed block is #{tip}")
+        }
+
+        // the state at the block is looked up in the history of the blocks after it
+        for stage in [StageId::IndexAccountHistory, StageId::IndexStorageHistory] {
+            let indexed = provider
+                .get_stage_checkpoint(stage)?
+                .map(|checkpoint| checkpoint.block_number)
+                .unwrap_or_default();
+            if block < tip && indexed < tip {
+                eyre::bail!(
+                    "the state at block #{block} is not available, {stage} is at block \
+                     #{indexed} instead of the last executed block #{tip}"
+                )
+            }
+        }
+        for segment in [PruneSegment::AccountHistory, PruneSegment::StorageHistory] {
+            let pruned = provider
+                .get_prune_checkpoint(segment)?
+                .and_then(|checkpoint| checkpoint.block_number);
+            if pruned.map_or(false, |pruned| pruned > block) {
+                eyre::bail!("the state at block #{block} is not available, {segment} is pruned")
+            }
+        }
+
+        let header = provider
+            .header_by_number(block)?
+            .ok_or_else(|| eyre::eyre!("header for block #{block} not found"))?;
+        let total_difficulty = provider
+            .header_td_by_number(block)?
+            .ok_or_else(|| eyre::eyre!("total difficulty for block #{block} not found"))?;
+
+        let file = File::create(&self.path)
+            .wrap_err_with(|| format!("Could not create state snapshot {:?}", self.path))?;
+        let mut writer = BufWriter::new(file);
+
+        info!(target: "reth::cli", block, ?header.state_root, path = ?self.path, "Exporting state");
+        write_entry(&mut writer, &StateEntry::Header { header, total_difficulty })?;
+        for number in (block.saturating_sub(ANCESTOR_HEADERS)..block).rev() {
+            let header = provider
+                .header_by_number(number)?
+                .ok_or_else(|| eyre::eyre!("header for block #{number} not found"))?;
+            write_entry(&mut writer, &StateEntry::AncestorHeader { header })?;
+        }
+        let accounts = dump_state(provider.tx_ref(), block, &mut writer)?;
+        writer.flush()?;
+
+        info!(target: "reth::cli", accounts, "Exported state");
+        Ok(())
+    }
+}
+
+/// Writes the bytecodes, accounts and storage at the given block to the writer.
+///
+/// The state at the block is looked up in the history of the accounts and storage slots that
+/// changed after it, so the memory doesn't grow with the number of blocks after the block. The
+/// history has to be indexed up to the last executed block.
+///
+/// Returns the number of accounts written.
+pub(crate) fn dump_state<TX: DbTx, W: Write>(
+    tx: &TX,
+    block: BlockNumber,
+    writer: &mut W,
+) -> eyre::Result<usize> {
+    // the changesets of the blocks after the block hold the values at the block
+    let state = HistoricalStateProviderRef::new(tx, block + 1);
+
+    let mut written = 0;
+    let mut written_bytecodes = HashSet::new();
+    let mut bytecodes = tx.cursor_read::<tables::Bytecodes>()?;
+    let mut plain_storage = tx.cursor_dup_read::<tables::PlainStorageState>()?;
+    let mut storage_history = tx.cursor_read::<tables::StorageHistory>()?;
+
+    // the accounts at the block are the accounts in the plain state, and the accounts that
+    // changed after the block
+    let mut plain_accounts = tx.cursor_read::<tables::PlainAccountState>()?;
+    let mut account_history = tx.cursor_read::<tables::AccountHistory>()?;
+    let mut next_plain = plain_accounts.first()?.map(|(address, _)| address);
+    let mut next_history = account_history.first()?.map(|(key, _)| key.key);
+    while let Some(address) = next_key(next_plain, next_history) {
+        if next_plain == Some(address) {
+            next_plain = plain_accounts.next()?.map(|(address, _)| address);
+        }
+        if next_history == Some(address) {
+            // skip the other shards of the address
+            let mut entry = account_history.seek(ShardedKey::new(address, u64::MAX))?;
+            if entry.as_ref().map_or(false, |(key, _)| key.key == address) {
+                entry = account_history.next()?;
+            }
+            next_history = entry.map(|(key, _)| key.key);
+        }
+
+        let Some(account) = state.basic_account(address)? else { continue };
+
+        // a bytecode is written before the first account with the code
+        if let Some(code_hash) = account.bytecode_hash {
+            if written_bytecodes.insert(code_hash) {
+                let (_, bytecode) = bytecodes
+                    .seek_exact(code_hash)?
+                    .ok_or_else(|| eyre::eyre!("bytecode {code_hash} of {address} not found"))?;
+                write_entry(writer, &StateEntry::Bytecode { code: bytecode.original_bytes() })?;
+            }
+        }
+        write_entry(
+            writer,
+            &StateEntry::Account {
+                address,
+                nonce: account.nonce,
+                balance: account.balance,
+                code_hash: account.bytecode_hash,
+            },
+        )?;
+
+        // the slots at the block are the slots in the plain state, and the slots that changed
+        // after the block
+        let mut plain_slots = plain_storage.walk_dup(Some(address), None)?;
+        let mut next_plain_slot = plain_slots.next().transpose()?.map(|(_, slot)| slot.key);
+        let mut next_history_slot = storage_history
+            .seek(StorageShardedKey::new(address, B256::ZERO, 0))?
+            .filter(|(key, _)| key.address == address)
+            .map(|(key, _)| key.sharded_key.key);
+        while let Some(key) = next_key(next_plain_slot, next_history_slot) {
+            if next_plain_slot == Some(key) {
+                next_plain_slot = plain_slots.next().transpose()?.map(|(_, slot)| slot.key);
+            }
+            if next_history_slot == Some(key) {
+                // skip the other shards of the slot
+                let mut entry =
+                    storage_history.seek(StorageShardedKey::new(address, key, u64::MAX))?;
+                if entry.as_ref().map_or(false, |(shard, _)| {
+                    shard.address == address && shard.sharded_key.key == key
+                }) {
+                    entry = storage_history.next()?;
+                }
+                next_history_slot = entry
+                    .filter(|(shard, _)| shard.address == address)
+                    .map(|(shard, _)| shard.sharded_key.key);
+            }
+
+            let value = state.storage(address, key)?.unwrap_or_default();
+            if value != U256::ZERO {
+                write_entry(writer, &StateEntry::Storage { key, value })?;
+            }
+        }
+
+        written += 1;
+        if written % 1_000_000 == 0 {
+            info!(target: "reth::cli", written, %address, "Exported accounts");
+        }
+    }
+
+    Ok(written)
+}
+
+/// Returns the lower of the next keys of two sorted walks that are merged.
+fn next_key<K: Ord>(a: Option<K>, b: Option<K>) -> Option<K> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(a.min(b)),
+        (a, b) => a.or(b),
+    }
+}
+
+fn write_entry<W: Write>(writer: &mut W, entry: &StateEntry) -> eyre::Result<()> {
+    serde_json::to_writer(&mut *writer, entry)?;
+    writer.write_all(b"\n")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_db::{
+        database::Database,
+        models::{AccountBeforeTx, BlockNumberAddress},
+        test_utils::create_test_rw_db,
+        transaction::DbTxMut,
+    };
+    use reth_primitives::{Account, Bytecode, IntegerList, StorageEntry};
+
+    #[test]
+    fn parse_dump_state_command_args() {
+        let args: DumpStateCommand =
+            DumpStateCommand::parse_from(["reth", "--block", "100", "state.jsonl"]);
+        assert_eq!(args.block, Some(100));
+        assert_eq!(args.path, PathBuf::from("state.jsonl"));
+    }
+
+    #[test]
+    fn state_entry_serde_roundtrip() {
+        let entry = StateEntry::Account {
+            address: Address::repeat_byte(1),
+            nonce: 1,
+            balance: U256::from(2),
+            code_hash: None,
+        };
+        let json = serde_json::to_string(&entry).unwrap();
+        assert!(json.starts_with(r#"{"type":"account""#));
+        assert_eq!(serde_json::from_str::<StateEntry>(&json).unwrap(), entry);
+    }
+
+    #[test]
+    fn dump_state_at_older_block() {
+        let (changed, removed, created) =
+            (Address::with_last_byte(1), Address::with_last_byte(2), Address::with_last_byte(3));
+        let (slot, new_slot) = (B256::with_last_byte(1), B256::with_last_byte(2));
+        let code = Bytecode::new_raw(Bytes::from_static(&[0x60, 0x00]));
+        let account = |nonce| Account { nonce, ..Default::default() };
+        let storage = |key, value| StorageEntry { key, value: U256::from(value) };
+        let history = || IntegerList::new([1]).unwrap();
+
+        // block #1 changes an account and its storage, removes an account and creates a contract
+        let db = create_test_rw_db();
+        let tx = db.tx_mut().unwrap();
+        tx.put::<tables::PlainAccountState>(changed, account(2)).unwrap();
+        tx.put::<tables::PlainAccountState>(
+            created,
+            Account { bytecode_hash: Some(code.hash_slow()), ..Default::default() },
+        )
+        .unwrap();
+        tx.put::<tables::Bytecodes>(code.hash_slow(), code).unwrap();
+        tx.put::<tables::PlainStorageState>(changed, storage(slot, 5)).unwrap();
+        tx.put::<tables::PlainStorageState>(changed, storage(new_slot, 6)).unwrap();
+        for (address, info) in
+            [(changed, Some(account(1))), (removed, Some(account(5))), (created, None)]
+        {
+            tx.put::<tables::AccountChangeSet>(1, AccountBeforeTx { address, info }).unwrap();
+            tx.put::<tables::AccountHistory>(ShardedKey::new(address, u64::MAX), history())
+                .unwrap();
+        }
+        for (address, key, value) in
+            [(changed, slot, 4), (changed, new_slot, 0), (removed, slot, 9)]
+        {
+            tx.put::<tables::StorageChangeSet>(
+                BlockNumberAddress((1, address)),
+                storage(key, value),
+            )
+            .unwrap();
+            tx.put::<tables::StorageHistory>(
+                StorageShardedKey::new(address, key, u64::MAX),
+                history(),
+            )
+            .unwrap();
+        }
+        tx.commit().unwrap();
+
+        let mut snapshot = Vec::new();
+        let tx = db.tx().unwrap();
+        assert_eq!(dump_state(&tx, 0, &mut snapshot).unwrap(), 2);
+        let entries = snapshot
+            .split(|b| *b == b'\n')
+            .filter(|line| !line.is_empty())
+            .map(|line| serde_json::from_slice::<StateEntry>(line).unwrap())
+            .collect::<Vec<_>>();
+        let account = |address, nonce| StateEntry::Account {
+            address,
+            nonce,
+            balance: U256::ZERO,
+            code_hash: None,
+        };
+        let storage = |key, value| StateEntry::Storage { key, value: U256::from(value) };
+        // the code of the contract created after the block isn't dumped
+        assert_eq!(
+            entries,
+            vec![account(changed, 1), storage(slot, 4), account(removed, 5), storage(slot, 9)]
+        );
+    }
+}
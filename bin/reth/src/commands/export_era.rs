@@ -0,0 +1,180 @@
+//! Command that exports pre-merge history to era1 archive files.
+
+use crate::{
+    args::{
+        utils::{chain_help, genesis_value_parser, SUPPORTED_CHAINS},
+        DatabaseArgs,
+    },
+    dirs::{DataDirPath, MaybePlatformPath},
+    version::SHORT_VERSION,
+};
+use clap::Parser;
+use eyre::Context;
+use reth_db::{database::Database, open_db_read_only};
+use reth_downloaders::era::{Era1Block, Era1File, MAX_ERA1_BLOCKS};
+use reth_primitives::{BlockBody, BlockHashOrNumber, BlockNumber, ChainSpec, Receipt};
+use reth_provider::{
+    BlockNumReader, BlockReader, DatabaseProviderRO, HeaderProvider, ProviderFactory,
+    ReceiptProvider,
+};
+use std::{path::PathBuf, sync::Arc};
+use tracing::info;
+
+/// Exports pre-merge blocks from the database to era1 archive files.
+#[derive(Debug, Parser)]
+pub struct ExportEraCommand {
+    /// The path to the data dir for all reth files and subdirectories.
+    ///
+    /// Defaults to the OS-specific data directory:
+    ///
+    /// - Linux: `$XDG_DATA_HOME/reth/` or `$HOME/.local/share/reth/`
+    /// - Windows: `{FOLDERID_RoamingAppData}/reth/`
+    /// - macOS: `$HOME/Library/Application Support/reth/`
+    #[arg(long, value_name = "DATA_DIR", verbatim_doc_comment, default_value_t)]
+    datadir: MaybePlatformPath<DataDirPath>,
+
+    /// The chain this node is running.
+    ///
+    /// Possible values are either a built-in chain or the path to a chain specification file.
+    #[arg(
+        long,
+        value_name = "CHAIN_OR_PATH",
+        long_help = chain_help(),
+        default_value = SUPPORTED_CHAINS[0],
+        value_parser = genesis_value_parser
+    )]
+    chain: Arc<ChainSpec>,
+
+    #[clap(flatten)]
+    db: DatabaseArgs,
+
+    /// The first epoch to export. Each epoch contains 8192 blocks.
+    #[arg(long, value_name = "EPOCH", default_value_t = 0)]
+    first_epoch: u64,
+
+    /// The maximum number of epochs to export.
+    ///
+    /// Defaults to all complete epochs before the merge that are available in the database.
+    #[arg(long, value_name = "COUNT")]
+    count: Option<u64>,
+
+    /// The directory to write the era1 files to.
+    #[arg(value_name = "OUTPUT_DIR", verbatim_doc_comment)]
+    path: PathBuf,
+}
+
+impl ExportEraCommand {
+    /// Execute `export-era` command
+    pub async fn execute(self) -> eyre::Result<()> {
+        info!(target: "reth::cli", "reth {} starting", SHORT_VERSION);
+
+        // add network name to data dir
+        let data_dir = self.datadir.unwrap_or_chain_default(self.chain.chain);
+        let db_path = data_dir.db_path();
+
+        info!(target: "reth::cli", path = ?db_path, "Opening database");
+        let db = open_db_read_only(&db_path, self.db.log_level)?;
+        let provider_factory = ProviderFactory::new(&db, self.chain.clone());
+        let provider = provider_factory.provider()?;
+
+        std::fs::create_dir_all(&self.path)
+            .wrap_err_with(|| format!("Could not create output directory {:?}", self.path))?;
+
+        let network = self.chain.chain.to_string();
+        let last_block = provider.last_block_number()?;
+
+        let mut epoch = self.first_epoch;
+        let last_epoch = self.count.map(|count| self.first_epoch.saturating_add(count));
+        while last_epoch.map_or(true, |last| epoch < last) {
+            let start = epoch * MAX_ERA1_BLOCKS as u64;
+            let end = start + MAX_ERA1_BLOCKS as u64 - 1;
+            if start > last_block {
+                break
+            }
+
+            let (blocks, merged) = export_blocks(&provider, start, end.min(last_block))?;
+            if blocks.is_empty() {
+                break
+            }
+
+            // only the epoch that contains the merge is allowed to be incomplete
+            if !merged && end > last_block {
+                info!(target: "reth::cli", epoch, last_block, "Epoch is not complete yet, stopping");
+                break
+            }
+
+            let file = Era1File::new(blocks)?;
+            let path = self.path.join(file.file_name(&network));
+            file.write(&path).wrap_err_with(|| format!("Could not write era1 file {:?}", path))?;
+            info!(target: "reth::cli", epoch, ?path, "Exported era1 file");
+
+            if merged {
+                info!(target: "reth::cli", "Reached the merge, stopping");
+                break
+            }
+            epoch += 1;
+        }
+
+        info!(target: "reth::cli", "Finishing up");
+        Ok(())
+    }
+}
+
+/// Reads the pre-merge blocks in the given range from the database.
+///
+/// Returns the blocks and whether the merge was reached within the range, in which case all blocks
+/// before the first proof-of-stake block are returned.
+fn export_blocks<DB: Database>(
+    provider: &DatabaseProviderRO<DB>,
+    start: BlockNumber,
+    end: BlockNumber,
+) -> eyre::Result<(Vec<Era1Block>, bool)> {
+    let mut blocks = Vec::with_capacity((end - start + 1) as usize);
+    for number in start..=end {
+        let header = provider
+            .header_by_number(number)?
+            .ok_or_else(|| eyre::eyre!("header for block #{number} not found"))?;
+
+        // proof-of-stake blocks have no difficulty, era1 files stop at the last proof-of-work block
+        if number > 0 && header.difficulty.is_zero() {
+            return Ok((blocks, true))
+        }
+
+        let block = provider
+            .block(BlockHashOrNumber::Number(number))?
+            .ok_or_else(|| eyre::eyre!("body for block #{number} not found"))?;
+        let receipts = provider
+            .receipts_by_block(BlockHashOrNumber::Number(number))?
+            .ok_or_else(|| eyre::eyre!("receipts for block #{number} are not available"))?;
+        let total_difficulty = provider
+            .header_td_by_number(number)?
+            .ok_or_else(|| eyre::eyre!("total difficulty for block #{number} not found"))?;
+
+        blocks.push(Era1Block {
+            header,
+            body: BlockBody {
+                transactions: block.body,
+                ommers: block.ommers,
+                withdrawals: block.withdrawals,
+            },
+            receipts: receipts.into_iter().map(Receipt::with_bloom).collect(),
+            total_difficulty,
+        });
+    }
+
+    Ok((blocks, false))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_export_era_command_args() {
+        let args: ExportEraCommand =
+            ExportEraCommand::parse_from(["reth", "--first-epoch", "2", "--count", "3", "out"]);
+        assert_eq!(args.first_epoch, 2);
+        assert_eq!(args.count, Some(3));
+        assert_eq!(args.path, PathBuf::from("out"));
+    }
+}
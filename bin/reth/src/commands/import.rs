@@ -15,9 +15,10 @@ use eyre::Context;
 use futures::{Stream, StreamExt};
 use reth_beacon_consensus::BeaconConsensus;
 use reth_config::Config;
-use reth_db::{database::Database, init_db};
+use reth_db::{database::Database, init_db_with_config};
 use reth_downloaders::{
-    bodies::bodies::BodiesDownloaderBuilder, file_client::FileClient,
+    bodies::bodies::BodiesDownloaderBuilder,
+    file_client::{ChunkedFileReader, FileClient},
     headers::reverse_headers::ReverseHeadersDownloaderBuilder,
 };
 use reth_interfaces::consensus::Consensus;
@@ -31,6 +32,9 @@ use std::{path::PathBuf, sync::Arc};
 use tokio::sync::watch;
 use tracing::{debug, info};
 
+/// The default number of blocks that are imported at once.
+const DEFAULT_IMPORT_CHUNK_LEN: u64 = 10_000;
+
 /// Syncs RLP encoded blocks from a file.
 #[derive(Debug, Parser)]
 pub struct ImportCommand {
@@ -63,10 +67,15 @@ pub struct ImportCommand {
     #[clap(flatten)]
     db: DatabaseArgs,
 
+    /// The number of blocks to read from the file and import at once.
+    #[arg(long, value_name = "CHUNK_LEN", default_value_t = DEFAULT_IMPORT_CHUNK_LEN)]
+    chunk_len: u64,
+
     /// The path to a block file for import.
     ///
     /// The online stages (headers and bodies) are replaced by a file import, after which the
-    /// remaining stages are executed.
+    /// remaining stages are executed. The file is imported in chunks, chunks that were already
+    /// imported are skipped, so an interrupted import can be resumed by running it again.
     #[arg(value_name = "IMPORT_PATH", verbatim_doc_comment)]
     path: PathBuf,
 }
@@ -86,7 +95,8 @@ impl ImportCommand {
         let db_path = data_dir.db_path();
 
         info!(target: "reth::cli", path = ?db_path, "Opening database");
-        let db = Arc::new(init_db(db_path, self.db.log_level)?);
+        let db =
+            Arc::new(init_db_with_config(db_path, self.db.log_level, self.db.apply(config.db))?);
         info!(target: "reth::cli", "Database opened");
         let provider_factory = ProviderFactory::new(db.clone(), self.chain.clone());
 
@@ -97,109 +107,56 @@ impl ImportCommand {
         let consensus = Arc::new(BeaconConsensus::new(self.chain.clone()));
         info!(target: "reth::cli", "Consensus engine initialized");
 
-        // create a new FileClient
-        info!(target: "reth::cli", "Importing chain file");
-        let file_client = Arc::new(FileClient::new(&self.path).await?);
-
-        // override the tip
-        let tip = file_client.tip().expect("file client has no tip");
-        info!(target: "reth::cli", "Chain file imported");
-
-        let (mut pipeline, events) = self
-            .build_import_pipeline(config, provider_factory.clone(), &consensus, file_client)
+        // stream the chain file chunk by chunk
+        info!(target: "reth::cli", path = ?self.path, "Importing chain file");
+        let mut reader = ChunkedFileReader::new(&self.path, self.chunk_len).await?;
+
+        while let Some(file_client) = reader.next_chunk().await? {
+            let latest_block_number = provider_factory
+                .provider()?
+                .get_stage_checkpoint(StageId::Finish)?
+                .map(|ch| ch.block_number);
+
+            let max_block = file_client.max_block().unwrap_or(0);
+            if latest_block_number.is_some_and(|latest| latest >= max_block) {
+                debug!(target: "reth::cli", max_block, "Chunk already imported, skipping");
+                continue
+            }
+
+            // override the tip
+            let tip = file_client.tip().expect("file client has no tip");
+            info!(target: "reth::cli", max_block, "Chain file chunk read");
+
+            let (mut pipeline, events) = build_import_pipeline(
+                self.chain.clone(),
+                config.clone(),
+                provider_factory.clone(),
+                &consensus,
+                Arc::new(file_client),
+            )
             .await?;
 
-        // override the tip
-        pipeline.set_tip(tip);
-        debug!(target: "reth::cli", ?tip, "Tip manually set");
-
-        let provider = provider_factory.provider()?;
-
-        let latest_block_number =
-            provider.get_stage_checkpoint(StageId::Finish)?.map(|ch| ch.block_number);
-        tokio::spawn(handle_events(None, latest_block_number, events, db.clone()));
-
-        // Run pipeline
-        info!(target: "reth::cli", "Starting sync pipeline");
-        tokio::select! {
-            res = pipeline.run() => res?,
-            _ = tokio::signal::ctrl_c() => {},
-        };
+            // override the tip
+            pipeline.set_tip(tip);
+            debug!(target: "reth::cli", ?tip, "Tip manually set");
+
+            tokio::spawn(handle_events(None, latest_block_number, events, db.clone()));
+
+            // Run pipeline
+            info!(target: "reth::cli", "Starting sync pipeline");
+            tokio::select! {
+                res = pipeline.run() => res?,
+                _ = tokio::signal::ctrl_c() => {
+                    info!(target: "reth::cli", "Import interrupted");
+                    return Ok(())
+                },
+            };
+        }
 
         info!(target: "reth::cli", "Finishing up");
         Ok(())
     }
 
-    async fn build_import_pipeline<DB, C>(
-        &self,
-        config: Config,
-        provider_factory: ProviderFactory<DB>,
-        consensus: &Arc<C>,
-        file_client: Arc<FileClient>,
-    ) -> eyre::Result<(Pipeline<DB>, impl Stream<Item = NodeEvent>)>
-    where
-        DB: Database + Clone + Unpin + 'static,
-        C: Consensus + 'static,
-    {
-        if !file_client.has_canonical_blocks() {
-            eyre::bail!("unable to import non canonical blocks");
-        }
-
-        let header_downloader = ReverseHeadersDownloaderBuilder::new(config.stages.headers)
-            .build(file_client.clone(), consensus.clone())
-            .into_task();
-
-        let body_downloader = BodiesDownloaderBuilder::new(config.stages.bodies)
-            .build(file_client.clone(), consensus.clone(), provider_factory.clone())
-            .into_task();
-
-        let (tip_tx, tip_rx) = watch::channel(B256::ZERO);
-        let factory = reth_revm::EvmProcessorFactory::new(self.chain.clone());
-
-        let max_block = file_client.max_block().unwrap_or(0);
-        let mut pipeline = Pipeline::builder()
-            .with_tip_sender(tip_tx)
-            // we want to sync all blocks the file client provides or 0 if empty
-            .with_max_block(max_block)
-            .add_stages(
-                DefaultStages::new(
-                    provider_factory.clone(),
-                    HeaderSyncMode::Tip(tip_rx),
-                    consensus.clone(),
-                    header_downloader,
-                    body_downloader,
-                    factory.clone(),
-                )
-                .set(
-                    TotalDifficultyStage::new(consensus.clone())
-                        .with_commit_threshold(config.stages.total_difficulty.commit_threshold),
-                )
-                .set(SenderRecoveryStage {
-                    commit_threshold: config.stages.sender_recovery.commit_threshold,
-                })
-                .set(ExecutionStage::new(
-                    factory,
-                    ExecutionStageThresholds {
-                        max_blocks: config.stages.execution.max_blocks,
-                        max_changes: config.stages.execution.max_changes,
-                        max_cumulative_gas: config.stages.execution.max_cumulative_gas,
-                    },
-                    config
-                        .stages
-                        .merkle
-                        .clean_threshold
-                        .max(config.stages.account_hashing.clean_threshold)
-                        .max(config.stages.storage_hashing.clean_threshold),
-                    config.prune.map(|prune| prune.segments).unwrap_or_default(),
-                )),
-            )
-            .build(provider_factory);
-
-        let events = pipeline.events().map(Into::into);
-
-        Ok((pipeline, events))
-    }
-
     /// Loads the reth config
     fn load_config(&self, config_path: PathBuf) -> eyre::Result<Config> {
         confy::load_path::<Config>(config_path.clone())
@@ -207,6 +164,81 @@ impl ImportCommand {
     }
 }
 
+/// Builds a pipeline that syncs the blocks of the given [FileClient] and runs the remaining
+/// stages on top of them.
+pub(crate) async fn build_import_pipeline<DB, C>(
+    chain: Arc<ChainSpec>,
+    config: Config,
+    provider_factory: ProviderFactory<DB>,
+    consensus: &Arc<C>,
+    file_client: Arc<FileClient>,
+) -> eyre::Result<(Pipeline<DB>, impl Stream<Item = NodeEvent>)>
+where
+    DB: Database + Clone + Unpin + 'static,
+    C: Consensus + 'static,
+{
+    if !file_client.has_canonical_blocks() {
+        eyre::bail!("unable to import non canonical blocks");
+    }
+
+    let header_downloader = ReverseHeadersDownloaderBuilder::new(config.stages.headers)
+        .build(file_client.clone(), consensus.clone())
+        .into_task();
+
+    let body_downloader = BodiesDownloaderBuilder::new(config.stages.bodies)
+        .build(file_client.clone(), consensus.clone(), provider_factory.clone())
+        .into_task();
+
+    let (tip_tx, tip_rx) = watch::channel(B256::ZERO);
+    let factory = reth_revm::EvmProcessorFactory::new(chain);
+
+    let max_block = file_client.max_block().unwrap_or(0);
+    let mut pipeline = Pipeline::builder()
+        .with_tip_sender(tip_tx)
+        // we want to sync all blocks the file client provides or 0 if empty
+        .with_max_block(max_block)
+        .add_stages(
+            DefaultStages::new(
+                provider_factory.clone(),
+                HeaderSyncMode::Tip(tip_rx),
+                consensus.clone(),
+                header_downloader,
+                body_downloader,
+                factory.clone(),
+            )
+            .set(
+                TotalDifficultyStage::new(consensus.clone())
+                    .with_commit_threshold(config.stages.total_difficulty.commit_threshold),
+            )
+            .set(
+                SenderRecoveryStage::new(config.stages.sender_recovery.commit_threshold)
+                    .with_parallelism(config.stages.sender_recovery.parallelism)
+                    .with_batch_size(config.stages.sender_recovery.batch_size),
+            )
+            .set(ExecutionStage::new(
+                factory,
+                ExecutionStageThresholds {
+                    max_blocks: config.stages.execution.max_blocks,
+                    max_changes: config.stages.execution.max_changes,
+                    max_cumulative_gas: config.stages.execution.max_cumulative_gas,
+                    max_duration: config.stages.execution.max_duration,
+                },
+                config
+                    .stages
+                    .merkle
+                    .clean_threshold
+                    .max(config.stages.account_hashing.clean_threshold)
+                    .max(config.stages.storage_hashing.clean_threshold),
+                config.prune.map(|prune| prune.segments).unwrap_or_default(),
+            )),
+        )
+        .build(provider_factory);
+
+    let events = pipeline.events().map(Into::into);
+
+    Ok((pipeline, events))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,246 @@
+//! Command that imports pre-merge history from era1 archive files.
+
+use crate::{
+    args::{
+        utils::{chain_help, genesis_value_parser, SUPPORTED_CHAINS},
+        DatabaseArgs,
+    },
+    commands::{import::build_import_pipeline, node::events::handle_events},
+    dirs::{DataDirPath, MaybePlatformPath},
+    init::init_genesis,
+    version::SHORT_VERSION,
+};
+use clap::Parser;
+use eyre::Context;
+use reth_beacon_consensus::BeaconConsensus;
+use reth_config::Config;
+use reth_db::init_db_with_config;
+use reth_downloaders::{
+    era::{read_accumulator_roots, Era1Block, Era1File, ERA1_FILE_EXTENSION},
+    file_client::FileClient,
+};
+use reth_primitives::{stage::StageId, ChainSpec, B256};
+use reth_provider::{BlockHashReader, ProviderFactory, StageCheckpointReader};
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use tracing::{debug, info};
+
+/// Syncs pre-merge blocks from era1 archive files.
+#[derive(Debug, Parser)]
+pub struct ImportEraCommand {
+    /// The path to the configuration file to use.
+    #[arg(long, value_name = "FILE", verbatim_doc_comment)]
+    config: Option<PathBuf>,
+
+    /// The path to the data dir for all reth files and subdirectories.
+    ///
+    /// Defaults to the OS-specific data directory:
+    ///
+    /// - Linux: `$XDG_DATA_HOME/reth/` or `$HOME/.local/share/reth/`
+    /// - Windows: `{FOLDERID_RoamingAppData}/reth/`
+    /// - macOS: `$HOME/Library/Application Support/reth/`
+    #[arg(long, value_name = "DATA_DIR", verbatim_doc_comment, default_value_t)]
+    datadir: MaybePlatformPath<DataDirPath>,
+
+    /// The chain this node is running.
+    ///
+    /// Possible values are either a built-in chain or the path to a chain specification file.
+    #[arg(
+        long,
+        value_name = "CHAIN_OR_PATH",
+        long_help = chain_help(),
+        default_value = SUPPORTED_CHAINS[0],
+        value_parser = genesis_value_parser
+    )]
+    chain: Arc<ChainSpec>,
+
+    #[clap(flatten)]
+    db: DatabaseArgs,
+
+    /// The path to the list of canonical epoch accumulator roots of the chain.
+    ///
+    /// The file contains one hex encoded root per line, ordered by epoch, and is published
+    /// alongside the era1 archives. The accumulator root of every imported file must match the
+    /// root of its epoch.
+    #[arg(long, value_name = "FILE", verbatim_doc_comment)]
+    accumulators: PathBuf,
+
+    /// The path to an era1 file or to a directory containing era1 files.
+    ///
+    /// Files are imported in the order of their names. Each file is validated against the
+    /// canonical accumulator root of its epoch and must build on the last block of the previous
+    /// file before its blocks replace the online stages (headers and bodies), after which the
+    /// remaining stages are executed.
+    #[arg(value_name = "IMPORT_PATH", verbatim_doc_comment)]
+    path: PathBuf,
+}
+
+impl ImportEraCommand {
+    /// Execute `import-era` command
+    pub async fn execute(self) -> eyre::Result<()> {
+        info!(target: "reth::cli", "reth {} starting", SHORT_VERSION);
+
+        // add network name to data dir
+        let data_dir = self.datadir.unwrap_or_chain_default(self.chain.chain);
+        let config_path = self.config.clone().unwrap_or(data_dir.config_path());
+
+        let config: Config = confy::load_path::<Config>(config_path.clone())
+            .wrap_err_with(|| format!("Could not load config file {:?}", config_path))?;
+        info!(target: "reth::cli", path = ?config_path, "Configuration loaded");
+
+        let db_path = data_dir.db_path();
+
+        info!(target: "reth::cli", path = ?db_path, "Opening database");
+        let db =
+            Arc::new(init_db_with_config(db_path, self.db.log_level, self.db.database_config())?);
+        info!(target: "reth::cli", "Database opened");
+        let provider_factory = ProviderFactory::new(db.clone(), self.chain.clone());
+
+        debug!(target: "reth::cli", chain=%self.chain.chain, genesis=?self.chain.genesis_hash(), "Initializing genesis");
+
+        init_genesis(db.clone(), self.chain.clone())?;
+
+        let consensus = Arc::new(BeaconConsensus::new(self.chain.clone()));
+        info!(target: "reth::cli", "Consensus engine initialized");
+
+        let roots = read_accumulator_roots(&self.accumulators).wrap_err_with(|| {
+            format!("Could not read accumulator roots {:?}", self.accumulators)
+        })?;
+
+        let files = era1_files(&self.path)?;
+        info!(target: "reth::cli", files = files.len(), "Found era1 files");
+
+        // the hash of the last block of the previous file
+        let mut previous: Option<B256> = None;
+        for path in files {
+            let latest_block_number = provider_factory
+                .provider()?
+                .get_stage_checkpoint(StageId::Finish)?
+                .map(|ch| ch.block_number);
+
+            info!(target: "reth::cli", ?path, "Reading era1 file");
+            let file = Era1File::read(&path)
+                .wrap_err_with(|| format!("Could not read era1 file {:?}", path))?;
+            file.verify()
+                .and_then(|_| file.verify_epoch(&roots))
+                .wrap_err_with(|| format!("Invalid era1 file {:?}", path))?;
+
+            // the first file builds on the genesis parent or on a block that is already stored
+            let parent_hash = match (previous, file.start_block().checked_sub(1)) {
+                (Some(hash), _) => hash,
+                (None, None) => B256::ZERO,
+                (None, Some(parent)) => provider_factory.block_hash(parent)?.ok_or_else(|| {
+                    eyre::eyre!("Parent block #{parent} of era1 file {:?} is missing", path)
+                })?,
+            };
+            file.verify_parent(parent_hash)
+                .wrap_err_with(|| format!("Era1 file {:?} does not extend the chain", path))?;
+            previous = file.blocks.last().map(|block| block.header.hash_slow());
+
+            let last_block = file.blocks.last().map(|block| block.header.number).unwrap_or(0);
+            if latest_block_number.is_some_and(|latest| latest >= last_block) {
+                info!(target: "reth::cli", ?path, last_block, "Era1 file already imported, skipping");
+                continue
+            }
+
+            let file_client = Arc::new(FileClient::from_blocks(
+                file.blocks.into_iter().map(Era1Block::into_block),
+            ));
+            let tip = file_client.tip().expect("era1 file has no blocks");
+
+            let (mut pipeline, events) = build_import_pipeline(
+                self.chain.clone(),
+                config.clone(),
+                provider_factory.clone(),
+                &consensus,
+                file_client,
+            )
+            .await?;
+
+            // override the tip
+            pipeline.set_tip(tip);
+            debug!(target: "reth::cli", ?tip, "Tip manually set");
+
+            tokio::spawn(handle_events(None, latest_block_number, events, db.clone()));
+
+            // Run pipeline
+            info!(target: "reth::cli", ?path, "Starting sync pipeline");
+            tokio::select! {
+                res = pipeline.run() => res?,
+                _ = tokio::signal::ctrl_c() => {
+                    info!(target: "reth::cli", "Import interrupted");
+                    return Ok(())
+                },
+            };
+        }
+
+        info!(target: "reth::cli", "Finishing up");
+        Ok(())
+    }
+}
+
+/// Returns the era1 files at the given path, sorted by their names.
+///
+/// If the path is a file it is returned as is, otherwise all files with the era1 extension in the
+/// directory are returned.
+fn era1_files(path: &Path) -> eyre::Result<Vec<PathBuf>> {
+    if path.is_file() {
+        return Ok(vec![path.to_path_buf()])
+    }
+
+    let mut files = Vec::new();
+    for entry in std::fs::read_dir(path)
+        .wrap_err_with(|| format!("Could not read era1 directory {:?}", path))?
+    {
+        let path = entry?.path();
+        if path.is_file() && path.extension().is_some_and(|ext| ext == ERA1_FILE_EXTENSION) {
+            files.push(path);
+        }
+    }
+    files.sort();
+
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_common_import_era_command_chain_args() {
+        for chain in SUPPORTED_CHAINS {
+            let args: ImportEraCommand = ImportEraCommand::parse_from([
+                "reth",
+                "--chain",
+                chain,
+                "--accumulators",
+                "accumulators.txt",
+                ".",
+            ]);
+            assert_eq!(
+                Ok(args.chain.chain),
+                chain.parse::<reth_primitives::Chain>(),
+                "failed to parse chain {chain}"
+            );
+        }
+    }
+
+    #[test]
+    fn era1_files_sorted() {
+        let dir = tempfile::tempdir().unwrap();
+        for name in ["mainnet-00001-5ec1ffb8.era1", "mainnet-00000-5ec1ffb8.era1", "other.txt"] {
+            std::fs::write(dir.path().join(name), []).unwrap();
+        }
+
+        let files = era1_files(dir.path()).unwrap();
+        assert_eq!(
+            files,
+            vec![
+                dir.path().join("mainnet-00000-5ec1ffb8.era1"),
+                dir.path().join("mainnet-00001-5ec1ffb8.era1"),
+            ]
+        );
+    }
+}
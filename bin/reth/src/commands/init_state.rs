@@ -0,0 +1,522 @@
+//! Command that initializes the node from a state snapshot.
+
+use crate::{
+    args::{
+        utils::{chain_help, genesis_value_parser, SUPPORTED_CHAINS},
+        DatabaseArgs,
+    },
+    commands::dump_state::{StateEntry, ANCESTOR_HEADERS},
+    dirs::{DataDirPath, MaybePlatformPath},
+    init::insert_genesis_header,
+};
+use alloy_rlp::Decodable;
+use clap::Parser;
+use eyre::Context;
+use reth_db::{
+    cursor::DbCursorRO,
+    database::Database,
+    init_db_with_config,
+    models::StoredBlockBodyIndices,
+    tables,
+    transaction::{DbTx, DbTxMut},
+    DatabaseError,
+};
+use reth_primitives::{
+    hex, keccak256,
+    stage::{StageCheckpoint, StageId},
+    Account, Address, Bytecode, ChainSpec, Header, SealedHeader, StorageEntry, U256,
+};
+use reth_provider::{ProviderFactory, StageCheckpointWriter};
+use reth_trie::StateRoot;
+use std::{
+    fs::File,
+    io::{BufRead, BufReader},
+    path::PathBuf,
+    sync::Arc,
+};
+use tracing::{info, warn};
+
+/// Number of snapshot entries written to the database in a single transaction.
+const COMMIT_THRESHOLD: usize = 1_000_000;
+
+/// Initializes the database from a state snapshot created with `reth dump-state`.
+///
+/// The state is only accepted if it belongs to the trusted header and its root matches the state
+/// root of the header, otherwise the database is left empty. The node then syncs from the block of
+/// the snapshot onwards, the ancestor headers of the snapshot provide the block hashes of the
+/// preceding blocks.
+#[derive(Debug, Parser)]
+pub struct InitStateCommand {
+    /// The path to the data dir for all reth files and subdirectories.
+    ///
+    /// Defaults to the OS-specific data directory:
+    ///
+    /// - Linux: `$XDG_DATA_HOME/reth/` or `$HOME/.local/share/reth/`
+    /// - Windows: `{FOLDERID_RoamingAppData}/reth/`
+    /// - macOS: `$HOME/Library/Application Support/reth/`
+    #[arg(long, value_name = "DATA_DIR", verbatim_doc_comment, default_value_t)]
+    datadir: MaybePlatformPath<DataDirPath>,
+
+    /// The chain this node is running.
+    ///
+    /// Possible values are either a built-in chain or the path to a chain specification file.
+    #[arg(
+        long,
+        value_name = "CHAIN_OR_PATH",
+        long_help = chain_help(),
+        default_value = SUPPORTED_CHAINS[0],
+        value_parser = genesis_value_parser
+    )]
+    chain: Arc<ChainSpec>,
+
+    #[clap(flatten)]
+    db: DatabaseArgs,
+
+    /// A file with the trusted header of the block of the snapshot, hex encoded in RLP, e.g. as
+    /// returned by `debug_getRawHeader`.
+    #[arg(long, value_name = "HEADER_FILE")]
+    header: PathBuf,
+
+    /// The total difficulty of the chain at the block of the trusted header.
+    ///
+    /// Defaults to the final difficulty of the chain for blocks after the merge, and is required
+    /// for blocks before it.
+    #[arg(long, value_name = "TOTAL_DIFFICULTY")]
+    total_difficulty: Option<U256>,
+
+    /// The state snapshot to initialize the database from.
+    #[arg(value_name = "STATE_FILE", verbatim_doc_comment)]
+    path: PathBuf,
+}
+
+impl InitStateCommand {
+    /// Execute the `init-state` command
+    pub async fn execute(self) -> eyre::Result<()> {
+        info!(target: "reth::cli", "reth init-state starting");
+
+        let header = std::fs::read_to_string(&self.header)
+            .wrap_err_with(|| format!("Could not read trusted header {:?}", self.header))?;
+        let header = Header::decode(&mut hex::decode(header.trim())?.as_slice())?.seal_slow();
+
+        // add network name to data dir
+        let data_dir = self.datadir.unwrap_or_chain_default(self.chain.chain);
+        let db_path = data_dir.db_path();
+        info!(target: "reth::cli", path = ?db_path, "Opening database");
+        let db =
+            Arc::new(init_db_with_config(&db_path, self.db.log_level, self.db.database_config())?);
+        info!(target: "reth::cli", "Database opened");
+
+        let file = File::open(&self.path)
+            .wrap_err_with(|| format!("Could not open state snapshot {:?}", self.path))?;
+        let factory = ProviderFactory::new(db, self.chain.clone());
+
+        let total_difficulty = self
+            .total_difficulty
+            .or_else(|| {
+                self.chain
+                    .paris_block_and_final_difficulty
+                    .filter(|(paris_block, _)| header.number >= *paris_block)
+                    .map(|(_, final_difficulty)| final_difficulty)
+            })
+            .ok_or_else(|| {
+                eyre::eyre!("--total-difficulty is required for blocks before the merge")
+            })?;
+
+        info!(target: "reth::cli", number = header.number, hash = ?header.hash, "Importing state");
+        let accounts =
+            init_state(&factory, self.chain, &header, total_difficulty, BufReader::new(file))?;
+
+        info!(target: "reth::cli", accounts, "State imported");
+        Ok(())
+    }
+}
+
+/// Initializes the empty database with the state snapshot belonging to the trusted header.
+///
+/// The state is committed in batches, and the headers with the stage checkpoints only once its
+/// root is verified. If the import fails, the committed state is removed again, so the database
+/// stays empty.
+///
+/// Returns the number of imported accounts.
+pub(crate) fn init_state<DB: Database, R: BufRead>(
+    factory: &ProviderFactory<DB>,
+    chain: Arc<ChainSpec>,
+    trusted: &SealedHeader,
+    total_difficulty: U256,
+    reader: R,
+) -> eyre::Result<usize> {
+    let provider_rw = factory.provider_rw()?;
+    if provider_rw.tx_ref().cursor_read::<tables::CanonicalHeaders>()?.first()?.is_some() {
+        eyre::bail!("the database is not empty")
+    }
+    // the state in a database without headers is left over from an interrupted import
+    clear_state::<DB>(provider_rw.tx_ref())?;
+    provider_rw.commit()?;
+
+    import_state(factory, chain, trusted, total_difficulty, reader).or_else(|err| {
+        let provider_rw = factory.provider_rw()?;
+        clear_state::<DB>(provider_rw.tx_ref())?;
+        provider_rw.commit()?;
+        Err(err)
+    })
+}
+
+fn import_state<DB: Database, R: BufRead>(
+    factory: &ProviderFactory<DB>,
+    chain: Arc<ChainSpec>,
+    trusted: &SealedHeader,
+    total_difficulty: U256,
+    reader: R,
+) -> eyre::Result<usize> {
+    let mut lines = reader.lines();
+    let mut next_entry = || -> eyre::Result<Option<StateEntry>> {
+        let Some(line) = lines.next().transpose()? else { return Ok(None) };
+        Ok(Some(serde_json::from_str(&line)?))
+    };
+
+    let Some(StateEntry::Header { header, total_difficulty: snapshot_total_difficulty }) =
+        next_entry()?
+    else {
+        eyre::bail!("state snapshot does not start with a header")
+    };
+    let header = header.seal_slow();
+    if header.hash != trusted.hash {
+        eyre::bail!(
+            "state snapshot belongs to block #{} ({}), not to the trusted header {}",
+            header.number,
+            header.hash,
+            trusted.hash
+        )
+    }
+    if snapshot_total_difficulty != total_difficulty {
+        warn!(
+            target: "reth::cli",
+            %snapshot_total_difficulty,
+            %total_difficulty,
+            "Ignoring the total difficulty of the state snapshot"
+        );
+    }
+
+    let mut provider_rw = factory.provider_rw()?;
+    let mut ancestors: Vec<SealedHeader> = Vec::new();
+    let mut accounts = 0;
+    let mut entries = 0;
+    let mut account: Option<Address> = None;
+    while let Some(entry) = next_entry()? {
+        let tx = provider_rw.tx_ref();
+        match entry {
+            StateEntry::Header { .. } => eyre::bail!("state snapshot contains multiple headers"),
+            StateEntry::AncestorHeader { header: ancestor } => {
+                if entries != ancestors.len() {
+                    eyre::bail!("state snapshot contains ancestor headers after the state")
+                }
+                // the ancestors are linked to the trusted header by their hashes
+                let ancestor = ancestor.seal_slow();
+                let parent_hash = ancestors.last().unwrap_or(&header).parent_hash;
+                if ancestor.hash != parent_hash {
+                    eyre::bail!(
+                        "ancestor header #{} ({}) is not the parent {parent_hash}",
+                        ancestor.number,
+                        ancestor.hash
+                    )
+                }
+                ancestors.push(ancestor);
+            }
+            StateEntry::Bytecode { code } => {
+                let bytecode = Bytecode::new_raw(code);
+                tx.put::<tables::Bytecodes>(bytecode.hash_slow(), bytecode)?;
+            }
+            StateEntry::Account { address, nonce, balance, code_hash } => {
+                let info = Account { nonce, balance, bytecode_hash: code_hash };
+                tx.put::<tables::PlainAccountState>(address, info)?;
+                tx.put::<tables::HashedAccount>(keccak256(address), info)?;
+                account = Some(address);
+
+                accounts += 1;
+                if accounts % 1_000_000 == 0 {
+                    info!(target: "reth::cli", accounts, %address, "Imported accounts");
+                }
+            }
+            StateEntry::Storage { key, value } => {
+                let Some(address) = account else {
+                    eyre::bail!("state snapshot contains storage before the first account")
+                };
+                tx.put::<tables::PlainStorageState>(address, StorageEntry { key, value })?;
+                tx.put::<tables::HashedStorage>(
+                    keccak256(address),
+                    StorageEntry { key: keccak256(key), value },
+                )?;
+            }
+        }
+
+        entries += 1;
+        if entries % COMMIT_THRESHOLD == 0 {
+            provider_rw.commit()?;
+            provider_rw = factory.provider_rw()?;
+        }
+    }
+
+    // the node can't execute the following blocks without the hashes of the preceding blocks
+    let expected_ancestors = header.number.min(ANCESTOR_HEADERS) as usize;
+    if ancestors.len() != expected_ancestors {
+        eyre::bail!(
+            "state snapshot contains {} instead of {expected_ancestors} ancestor headers",
+            ancestors.len()
+        )
+    }
+    let genesis_hash = chain.genesis_hash();
+    if ancestors
+        .last()
+        .map_or(false, |ancestor| ancestor.number == 0 && ancestor.hash != genesis_hash)
+    {
+        eyre::bail!("state snapshot does not belong to the genesis {genesis_hash}")
+    }
+
+    info!(target: "reth::cli", "Computing state root");
+    let (root, updates) = StateRoot::from_tx(provider_rw.tx_ref()).root_with_updates()?;
+    if root != header.state_root {
+        eyre::bail!("state root mismatch: expected {}, got {root}", header.state_root)
+    }
+    updates.flush(provider_rw.tx_ref())?;
+
+    // the genesis header is expected to be present by the node
+    if header.number != 0 {
+        insert_genesis_header::<DB>(provider_rw.tx_ref(), chain)?;
+    }
+    let tx = provider_rw.tx_ref();
+    tx.put::<tables::CanonicalHeaders>(header.number, header.hash)?;
+    tx.put::<tables::HeaderNumbers>(header.hash, header.number)?;
+    tx.put::<tables::BlockBodyIndices>(header.number, StoredBlockBodyIndices::default())?;
+    tx.put::<tables::HeaderTD>(header.number, total_difficulty.into())?;
+    tx.put::<tables::Headers>(header.number, header.header.clone())?;
+    // the genesis header is already inserted
+    for ancestor in ancestors.into_iter().filter(|ancestor| ancestor.number != 0) {
+        tx.put::<tables::CanonicalHeaders>(ancestor.number, ancestor.hash)?;
+        tx.put::<tables::HeaderNumbers>(ancestor.hash, ancestor.number)?;
+        tx.put::<tables::Headers>(ancestor.number, ancestor.header)?;
+    }
+
+    // the history before the snapshot is not available, so all stages start at its block
+    for stage in StageId::ALL {
+        provider_rw.save_stage_checkpoint(stage, StageCheckpoint::new(header.number))?;
+    }
+    provider_rw.commit()?;
+
+    Ok(accounts)
+}
+
+/// Removes the imported state from the tables that are committed before the headers.
+fn clear_state<DB: Database>(tx: &<DB as Database>::TXMut) -> Result<(), DatabaseError> {
+    tx.clear::<tables::Bytecodes>()?;
+    tx.clear::<tables::PlainAccountState>()?;
+    tx.clear::<tables::PlainStorageState>()?;
+    tx.clear::<tables::HashedAccount>()?;
+    tx.clear::<tables::HashedStorage>()?;
+    tx.clear::<tables::AccountsTrie>()?;
+    tx.clear::<tables::StoragesTrie>()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{commands::dump_state::dump_state, init::init_genesis};
+    use reth_db::{
+        test_utils::{create_test_rw_db, TempDatabase},
+        DatabaseEnv,
+    };
+    use reth_primitives::{Bytes, Chain, Genesis, GenesisAccount, B256, U256};
+    use std::collections::HashMap;
+
+    #[test]
+    fn parse_init_state_command_args() {
+        let args: InitStateCommand =
+            InitStateCommand::parse_from(["reth", "--header", "header.rlp", "state.jsonl"]);
+        assert_eq!(args.header, PathBuf::from("header.rlp"));
+        assert_eq!(args.path, PathBuf::from("state.jsonl"));
+    }
+
+    #[test]
+    fn dump_and_init_state() {
+        let chain_spec = Arc::new(ChainSpec {
+            chain: Chain::from_id(1),
+            genesis: Genesis {
+                alloc: HashMap::from([
+                    (
+                        Address::with_last_byte(1),
+                        GenesisAccount {
+                            code: Some(Bytes::from_static(&[0x60, 0x00])),
+                            storage: Some(HashMap::from([
+                                (B256::with_last_byte(1), B256::with_last_byte(2)),
+                                (B256::with_last_byte(3), B256::with_last_byte(4)),
+                            ])),
+                            ..Default::default()
+                        },
+                    ),
+                    (
+                        Address::with_last_byte(2),
+                        GenesisAccount { balance: U256::from(1), ..Default::default() },
+                    ),
+                ]),
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+
+        let source = create_test_rw_db();
+        init_genesis(source.clone(), chain_spec.clone()).unwrap();
+
+        let header = chain_spec.sealed_genesis_header();
+        let mut snapshot = Vec::new();
+        let entry =
+            StateEntry::Header { header: header.header.clone(), total_difficulty: U256::ZERO };
+        serde_json::to_writer(&mut snapshot, &entry).unwrap();
+        snapshot.push(b'\n');
+        let tx = source.tx().unwrap();
+        assert_eq!(dump_state(&tx, 0, &mut snapshot).unwrap(), 2);
+
+        let target = create_test_rw_db();
+        let factory = ProviderFactory::new(target.clone(), chain_spec.clone());
+        // a snapshot with a different state leaves the database empty
+        let mut tampered = snapshot.clone();
+        let last_line = tampered[..tampered.len() - 1].iter().rposition(|b| *b == b'\n').unwrap();
+        tampered.truncate(last_line + 1);
+        let entry = StateEntry::Account {
+            address: Address::with_last_byte(2),
+            nonce: 0,
+            balance: U256::from(2),
+            code_hash: None,
+        };
+        serde_json::to_writer(&mut tampered, &entry).unwrap();
+        tampered.push(b'\n');
+        let err =
+            init_state(&factory, chain_spec.clone(), &header, U256::ZERO, tampered.as_slice())
+                .unwrap_err();
+        assert!(err.to_string().starts_with("state root mismatch"));
+        let target_tx = target.tx().unwrap();
+        assert_eq!(target_tx.entries::<tables::PlainAccountState>().unwrap(), 0);
+        assert_eq!(target_tx.entries::<tables::CanonicalHeaders>().unwrap(), 0);
+        drop(target_tx);
+
+        // the total difficulty is taken from the trusted input
+        let total_difficulty = U256::from(7);
+        assert_eq!(
+            init_state(
+                &factory,
+                chain_spec.clone(),
+                &header,
+                total_difficulty,
+                snapshot.as_slice()
+            )
+            .unwrap(),
+            2
+        );
+
+        let target_tx = target.tx().unwrap();
+        let accounts = |tx: &<Arc<TempDatabase<DatabaseEnv>> as Database>::TX| {
+            tx.cursor_read::<tables::PlainAccountState>()
+                .unwrap()
+                .walk(None)
+                .unwrap()
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap()
+        };
+        assert_eq!(accounts(&tx), accounts(&target_tx));
+        let storage = |tx: &<Arc<TempDatabase<DatabaseEnv>> as Database>::TX| {
+            tx.cursor_read::<tables::PlainStorageState>()
+                .unwrap()
+                .walk(None)
+                .unwrap()
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap()
+        };
+        assert_eq!(storage(&tx).len(), 2);
+        assert_eq!(storage(&tx), storage(&target_tx));
+        assert_eq!(
+            target_tx.cursor_read::<tables::CanonicalHeaders>().unwrap().last().unwrap(),
+            Some((0, header.hash))
+        );
+        assert_eq!(target_tx.get::<tables::HeaderTD>(0).unwrap(), Some(total_difficulty.into()));
+
+        // the snapshot has to belong to the trusted header
+        let other = create_test_rw_db();
+        let factory = ProviderFactory::new(other, chain_spec.clone());
+        let trusted = Header { number: 1, ..Default::default() }.seal_slow();
+        assert!(
+            init_state(&factory, chain_spec, &trusted, U256::ZERO, snapshot.as_slice()).is_err()
+        );
+    }
+
+    #[test]
+    fn init_state_with_ancestor_headers() {
+        let chain_spec = Arc::new(ChainSpec {
+            chain: Chain::from_id(1),
+            genesis: Genesis {
+                alloc: HashMap::from([(
+                    Address::with_last_byte(1),
+                    GenesisAccount { balance: U256::from(1), ..Default::default() },
+                )]),
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+        let source = create_test_rw_db();
+        init_genesis(source.clone(), chain_spec.clone()).unwrap();
+
+        // block #1 doesn't change the state
+        let genesis = chain_spec.sealed_genesis_header();
+        let header = Header {
+            number: 1,
+            parent_hash: genesis.hash,
+            state_root: genesis.state_root,
+            ..Default::default()
+        }
+        .seal_slow();
+        let snapshot = |ancestors: &[StateEntry]| {
+            let mut snapshot = Vec::new();
+            let entry =
+                StateEntry::Header { header: header.header.clone(), total_difficulty: U256::ZERO };
+            for entry in std::iter::once(&entry).chain(ancestors) {
+                serde_json::to_writer(&mut snapshot, entry).unwrap();
+                snapshot.push(b'\n');
+            }
+            dump_state(&source.tx().unwrap(), 0, &mut snapshot).unwrap();
+            snapshot
+        };
+
+        // the block hashes of the preceding blocks are required
+        let target = create_test_rw_db();
+        let factory = ProviderFactory::new(target.clone(), chain_spec.clone());
+        let err = init_state(&factory, chain_spec.clone(), &header, U256::ZERO, &snapshot(&[])[..])
+            .unwrap_err();
+        assert!(err.to_string().contains("ancestor headers"));
+
+        let unrelated = StateEntry::AncestorHeader { header: Header::default() };
+        assert!(init_state(
+            &factory,
+            chain_spec.clone(),
+            &header,
+            U256::ZERO,
+            &snapshot(&[unrelated])[..]
+        )
+        .is_err());
+
+        let ancestor = StateEntry::AncestorHeader { header: genesis.header.clone() };
+        assert_eq!(
+            init_state(&factory, chain_spec, &header, U256::ZERO, &snapshot(&[ancestor])[..])
+                .unwrap(),
+            1
+        );
+        let target_tx = target.tx().unwrap();
+        assert_eq!(
+            target_tx
+                .cursor_read::<tables::CanonicalHeaders>()
+                .unwrap()
+                .walk(None)
+                .unwrap()
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap(),
+            vec![(0, genesis.hash), (1, header.hash)]
+        );
+    }
+}
@@ -3,8 +3,12 @@
 pub mod config_cmd;
 pub mod db;
 pub mod debug_cmd;
+pub mod dump_state;
+pub mod export_era;
 pub mod import;
+pub mod import_era;
 pub mod init_cmd;
+pub mod init_state;
 pub mod node;
 pub mod p2p;
 pub mod recover;
@@ -8,17 +8,17 @@ use reth_interfaces::consensus::ForkchoiceState;
 use reth_network::{NetworkEvent, NetworkHandle};
 use reth_network_api::PeersInfo;
 use reth_primitives::{
-    stage::{EntitiesCheckpoint, StageCheckpoint, StageId},
+    stage::{StageCheckpoint, StageId},
     BlockNumber,
 };
 use reth_prune::PrunerEvent;
-use reth_stages::{ExecOutput, PipelineEvent};
+use reth_stages::{Eta, ExecOutput, PipelineEvent};
 use std::{
     fmt::{Display, Formatter},
     future::Future,
     pin::Pin,
     task::{Context, Poll},
-    time::{Duration, Instant},
+    time::Duration,
 };
 use tokio::time::Interval;
 use tracing::{info, warn};
@@ -66,9 +66,7 @@ impl<DB> NodeState<DB> {
                     target,
                 };
 
-                let stage_progress = OptionalField(
-                    checkpoint.entities().and_then(|entities| entities.fmt_percentage()),
-                );
+                let stage_progress = fmt_stage_progress(&checkpoint);
 
                 if let Some(stage_eta) = current_stage.eta.fmt_for_stage(stage_id) {
                     info!(
@@ -107,9 +105,7 @@ impl<DB> NodeState<DB> {
                     current_stage.eta.update(checkpoint);
 
                     let target = OptionalField(current_stage.target);
-                    let stage_progress = OptionalField(
-                        checkpoint.entities().and_then(|entities| entities.fmt_percentage()),
-                    );
+                    let stage_progress = fmt_stage_progress(&checkpoint);
 
                     let message =
                         if done { "Stage finished executing" } else { "Stage committed progress" };
@@ -228,6 +224,26 @@ impl<T: Display> Display for OptionalField<T> {
     }
 }
 
+/// Formats the progress of the stage checkpoint as percentage.
+///
+/// The progress of the execution stage is measured in gas, and followed by the number of executed
+/// transactions, e.g. `41.35% (1200/3000 transactions)`.
+fn fmt_stage_progress(checkpoint: &StageCheckpoint) -> OptionalField<String> {
+    let progress = checkpoint.entities().and_then(|entities| entities.fmt_percentage());
+    let transactions = checkpoint
+        .execution_stage_checkpoint()
+        .map(|checkpoint| checkpoint.transactions)
+        .filter(|transactions| transactions.total > 0);
+
+    OptionalField(match (progress, transactions) {
+        (Some(progress), Some(transactions)) => Some(format!(
+            "{progress} ({}/{} transactions)",
+            transactions.processed, transactions.total
+        )),
+        (progress, _) => progress,
+    })
+}
+
 /// The stage currently being executed.
 struct CurrentStage {
     stage_id: StageId,
@@ -328,9 +344,7 @@ where
             if let Some(CurrentStage { stage_id, eta, checkpoint, target }) =
                 &this.state.current_stage
             {
-                let stage_progress = OptionalField(
-                    checkpoint.entities().and_then(|entities| entities.fmt_percentage()),
-                );
+                let stage_progress = fmt_stage_progress(checkpoint);
 
                 if let Some(stage_eta) = eta.fmt_for_stage(*stage_id) {
                     info!(
@@ -397,91 +411,3 @@ where
         Poll::Pending
     }
 }
-
-/// A container calculating the estimated time that a stage will complete in, based on stage
-/// checkpoints reported by the pipeline.
-///
-/// One `Eta` is only valid for a single stage.
-#[derive(Default, Copy, Clone)]
-struct Eta {
-    /// The last stage checkpoint
-    last_checkpoint: EntitiesCheckpoint,
-    /// The last time the stage reported its checkpoint
-    last_checkpoint_time: Option<Instant>,
-    /// The current ETA
-    eta: Option<Duration>,
-}
-
-impl Eta {
-    /// Update the ETA given the checkpoint, if possible.
-    fn update(&mut self, checkpoint: StageCheckpoint) {
-        let Some(current) = checkpoint.entities() else { return };
-
-        if let Some(last_checkpoint_time) = &self.last_checkpoint_time {
-            let processed_since_last = current.processed - self.last_checkpoint.processed;
-            let elapsed = last_checkpoint_time.elapsed();
-            let per_second = processed_since_last as f64 / elapsed.as_secs_f64();
-
-            self.eta = Duration::try_from_secs_f64(
-                ((current.total - current.processed) as f64) / per_second,
-            )
-            .ok();
-        }
-
-        self.last_checkpoint = current;
-        self.last_checkpoint_time = Some(Instant::now());
-    }
-
-    /// Format ETA for a given stage.
-    ///
-    /// NOTE: Currently ETA is enabled only for the stages that have predictable progress.
-    /// It's not the case for network-dependent ([StageId::Headers] and [StageId::Bodies]) and
-    /// [StageId::Execution] stages.
-    fn fmt_for_stage(&self, stage: StageId) -> Option<String> {
-        if matches!(stage, StageId::Headers | StageId::Bodies | StageId::Execution) {
-            None
-        } else {
-            Some(self.to_string())
-        }
-    }
-}
-
-impl Display for Eta {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        if let Some((eta, last_checkpoint_time)) = self.eta.zip(self.last_checkpoint_time) {
-            let remaining = eta.checked_sub(last_checkpoint_time.elapsed());
-
-            if let Some(remaining) = remaining {
-                return write!(
-                    f,
-                    "{}",
-                    humantime::format_duration(Duration::from_secs(remaining.as_secs()))
-                )
-            }
-        }
-
-        write!(f, "unknown")
-    }
-}
-
-#[cfg(test)]
-mod tests {
-    use crate::commands::node::events::Eta;
-    use std::time::{Duration, Instant};
-
-    #[test]
-    fn eta_display_no_milliseconds() {
-        let eta = Eta {
-            last_checkpoint_time: Some(Instant::now()),
-            eta: Some(Duration::from_millis(
-                13 * 60 * 1000 + // Minutes
-                    37 * 1000 + // Seconds
-                    999, // Milliseconds
-            )),
-            ..Default::default()
-        }
-        .to_string();
-
-        assert_eq!(eta, "13m 37s");
-    }
-}
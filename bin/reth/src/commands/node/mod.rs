@@ -5,8 +5,8 @@
 use crate::{
     args::{
         utils::{chain_help, genesis_value_parser, parse_socket_address, SUPPORTED_CHAINS},
-        DatabaseArgs, DebugArgs, DevArgs, NetworkArgs, PayloadBuilderArgs, PruningArgs,
-        RpcServerArgs, TxPoolArgs,
+        DatabaseArgs, DebugArgs, DevArgs, NetworkArgs, OverrideArgs, PayloadBuilderArgs,
+        PruningArgs, RpcServerArgs, SnapshotArgs, StateCacheArgs, TreeArgs, TxPoolArgs,
     },
     builder::NodeConfig,
     cli::{db_type::DatabaseBuilder, ext::RethCliExt},
@@ -112,6 +112,22 @@ pub struct NodeCommand<Ext: RethCliExt = ()> {
     #[clap(flatten)]
     pub pruning: PruningArgs,
 
+    /// All snapshot offloading related arguments with --snapshots prefix
+    #[clap(flatten)]
+    pub snapshots: SnapshotArgs,
+
+    /// All blockchain tree related arguments with --tree prefix
+    #[clap(flatten)]
+    pub tree: TreeArgs,
+
+    /// All state cache related arguments with --state-cache prefix
+    #[clap(flatten)]
+    pub state_cache: StateCacheArgs,
+
+    /// All hardfork activation overrides with --override prefix
+    #[clap(flatten)]
+    pub overrides: OverrideArgs,
+
     /// Rollup related arguments
     #[cfg(feature = "optimism")]
     #[clap(flatten)]
@@ -141,6 +157,10 @@ impl<Ext: RethCliExt> NodeCommand<Ext> {
             db,
             dev,
             pruning,
+            snapshots,
+            tree,
+            state_cache,
+            overrides,
             #[cfg(feature = "optimism")]
             rollup,
             ..
@@ -160,6 +180,10 @@ impl<Ext: RethCliExt> NodeCommand<Ext> {
             db,
             dev,
             pruning,
+            snapshots,
+            tree,
+            state_cache,
+            overrides,
             #[cfg(feature = "optimism")]
             rollup,
             ext,
@@ -183,11 +207,18 @@ impl<Ext: RethCliExt> NodeCommand<Ext> {
             db,
             dev,
             pruning,
+            snapshots,
+            tree,
+            state_cache,
+            overrides,
             #[cfg(feature = "optimism")]
             rollup,
             ext,
         } = self;
 
+        // adjust the hardfork activation of the chain before anything depends on it
+        let chain = overrides.apply(chain)?;
+
         // set up real database
         let database = DatabaseBuilder::Real(datadir);
 
@@ -207,6 +238,9 @@ impl<Ext: RethCliExt> NodeCommand<Ext> {
             db,
             dev,
             pruning,
+            snapshots,
+            tree,
+            state_cache,
             #[cfg(feature = "optimism")]
             rollup,
         };
@@ -275,7 +309,7 @@ mod tests {
         ])
         .unwrap();
         assert_eq!(cmd.network.discovery.addr, Ipv4Addr::LOCALHOST);
-        assert_eq!(cmd.network.addr, Ipv4Addr::LOCALHOST);
+        assert_eq!(cmd.network.addr, vec![IpAddr::V4(Ipv4Addr::LOCALHOST)]);
     }
 
     #[test]
@@ -0,0 +1,338 @@
+//! Crawler that records the peers of the network without syncing.
+
+use enr::Enr;
+use futures::StreamExt;
+use reth_network::{
+    DisconnectReason, DiscoveredEvent, DiscoveryEvent, NetworkEvent, NetworkEvents, NetworkHandle,
+};
+use reth_network_api::{PeerKind, Peers};
+use reth_primitives::{Bytes, Chain, ForkId, PeerId, B256, U256};
+use secp256k1::SecretKey;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    fs::File,
+    io::{BufWriter, Write},
+    net::SocketAddr,
+    path::Path,
+    time::Duration,
+};
+use tokio::time::Instant;
+use tracing::info;
+
+/// Interval in which the progress of the crawl is reported.
+const PROGRESS_INTERVAL: Duration = Duration::from_secs(10);
+
+/// A record of a crawl.
+///
+/// A crawl is a stream of records, one JSON object per line. A [CrawlRecord::Discovered] is
+/// written for every peer found by discovery, and again whenever its ENR or the fork id of its ENR
+/// is retrieved. A [CrawlRecord::Handshake] is written for the first completed handshake with
+/// every peer.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum CrawlRecord {
+    /// A peer found by discovery.
+    #[serde(rename_all = "camelCase")]
+    Discovered {
+        /// The identifier of the peer.
+        peer_id: PeerId,
+        /// The address the peer listens on.
+        address: SocketAddr,
+        /// The EIP-2124 fork id advertised in the ENR of the peer, if retrieved.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        fork_id: Option<ForkId>,
+        /// The ENR of the peer, if retrieved.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        enr: Option<CrawlEnr>,
+    },
+    /// A completed handshake with a peer.
+    #[serde(rename_all = "camelCase")]
+    Handshake {
+        /// The identifier of the peer.
+        peer_id: PeerId,
+        /// The address of the session with the peer.
+        remote_addr: SocketAddr,
+        /// The client version announced by the peer.
+        client_version: String,
+        /// The capabilities announced by the peer, e.g. `eth/68`.
+        capabilities: Vec<String>,
+        /// The negotiated `eth` version.
+        eth_version: u8,
+        /// The chain of the peer.
+        chain: Chain,
+        /// The genesis hash of the peer.
+        genesis: B256,
+        /// The best block hash of the peer.
+        best_hash: B256,
+        /// The total difficulty of the best chain of the peer.
+        total_difficulty: U256,
+        /// The fork id of the peer.
+        fork_id: ForkId,
+    },
+}
+
+/// The ENR of a peer, see [EIP-778](https://eips.ethereum.org/EIPS/eip-778).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CrawlEnr {
+    /// The sequence number of the ENR.
+    pub seq: u64,
+    /// The key-value pairs of the ENR, the values are RLP encoded.
+    pub pairs: BTreeMap<String, Bytes>,
+}
+
+impl From<&Enr<SecretKey>> for CrawlEnr {
+    fn from(enr: &Enr<SecretKey>) -> Self {
+        let pairs = enr
+            .iter()
+            .map(|(key, value)| {
+                (String::from_utf8_lossy(key).into_owned(), Bytes::copy_from_slice(value))
+            })
+            .collect();
+        Self { seq: enr.seq(), pairs }
+    }
+}
+
+/// What is known of a discovered peer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct DiscoveredPeer {
+    address: SocketAddr,
+    fork_id: Option<ForkId>,
+    enr: Option<CrawlEnr>,
+}
+
+/// Records the peers of the network to a writer.
+#[derive(Debug)]
+pub(crate) struct Crawler<W> {
+    writer: W,
+    /// The address, ENR and ENR fork id of all discovered peers.
+    discovered: HashMap<PeerId, DiscoveredPeer>,
+    /// All peers a handshake was completed with.
+    handshakes: HashSet<PeerId>,
+}
+
+impl<W: Write> Crawler<W> {
+    /// Creates a new crawler that writes its records to the writer.
+    pub(crate) fn new(writer: W) -> Self {
+        Self { writer, discovered: HashMap::new(), handshakes: HashSet::new() }
+    }
+
+    /// Records the peers found by discovery.
+    pub(crate) fn on_discovery_event(&mut self, event: DiscoveryEvent) -> eyre::Result<()> {
+        let (peer_id, peer) = match event {
+            DiscoveryEvent::NewNode(DiscoveredEvent::EventQueued {
+                peer_id,
+                socket_addr,
+                fork_id,
+            }) => {
+                let known = self.discovered.get(&peer_id);
+                let peer = DiscoveredPeer {
+                    address: socket_addr,
+                    fork_id: fork_id.or(known.and_then(|peer| peer.fork_id)),
+                    enr: known.and_then(|peer| peer.enr.clone()),
+                };
+                (peer_id, peer)
+            }
+            // the address of the peer is only known once it's discovered
+            DiscoveryEvent::EnrForkId(peer_id, fork_id) => {
+                let Some(known) = self.discovered.get(&peer_id) else { return Ok(()) };
+                (peer_id, DiscoveredPeer { fork_id: Some(fork_id), ..known.clone() })
+            }
+            DiscoveryEvent::Enr(peer_id, enr) => {
+                let Some(known) = self.discovered.get(&peer_id) else { return Ok(()) };
+                (peer_id, DiscoveredPeer { enr: Some(CrawlEnr::from(&enr)), ..known.clone() })
+            }
+        };
+
+        if self.discovered.get(&peer_id) == Some(&peer) {
+            return Ok(())
+        }
+        let record = CrawlRecord::Discovered {
+            peer_id,
+            address: peer.address,
+            fork_id: peer.fork_id,
+            enr: peer.enr.clone(),
+        };
+        self.discovered.insert(peer_id, peer);
+        self.write(&record)
+    }
+
+    /// Records the peers a handshake was completed with.
+    ///
+    /// Returns the peer of an established session, which can be removed to make room for other
+    /// peers.
+    pub(crate) fn on_network_event(&mut self, event: NetworkEvent) -> eyre::Result<Option<PeerId>> {
+        let NetworkEvent::SessionEstablished {
+            peer_id,
+            remote_addr,
+            client_version,
+            capabilities,
+            status,
+            version,
+            ..
+        } = event
+        else {
+            return Ok(None)
+        };
+
+        if self.handshakes.insert(peer_id) {
+            self.write(&CrawlRecord::Handshake {
+                peer_id,
+                remote_addr,
+                client_version: client_version.to_string(),
+                capabilities: capabilities.capabilities().iter().map(ToString::to_string).collect(),
+                eth_version: version as u8,
+                chain: status.chain,
+                genesis: status.genesis,
+                best_hash: status.blockhash,
+                total_difficulty: status.total_difficulty,
+                fork_id: status.forkid,
+            })?;
+        }
+        Ok(Some(peer_id))
+    }
+
+    /// Returns the number of discovered peers.
+    pub(crate) fn num_discovered(&self) -> usize {
+        self.discovered.len()
+    }
+
+    /// Returns the number of peers a handshake was completed with.
+    pub(crate) fn num_handshakes(&self) -> usize {
+        self.handshakes.len()
+    }
+
+    /// Flushes the records to the writer.
+    pub(crate) fn flush(&mut self) -> eyre::Result<()> {
+        Ok(self.writer.flush()?)
+    }
+
+    fn write(&mut self, record: &CrawlRecord) -> eyre::Result<()> {
+        serde_json::to_writer(&mut self.writer, record)?;
+        self.writer.write_all(b"\n")?;
+        Ok(())
+    }
+}
+
+/// Crawls the network, writing the records to the file at the given path.
+///
+/// Every peer is disconnected and removed from the peer set right after the handshake, so the
+/// outbound slots are free for other peers and the peer is not dialed again. Runs until the given
+/// duration elapsed, or forever.
+pub(crate) async fn crawl(
+    network: NetworkHandle,
+    path: &Path,
+    duration: Option<Duration>,
+) -> eyre::Result<()> {
+    let file = File::create(path)?;
+    let mut crawler = Crawler::new(BufWriter::new(file));
+
+    let mut discovery = network.discovery_listener();
+    let mut events = network.event_listener();
+    let mut progress = tokio::time::interval(PROGRESS_INTERVAL);
+    let deadline = duration.map(|duration| Instant::now() + duration);
+    let timeout = async {
+        match deadline {
+            Some(deadline) => tokio::time::sleep_until(deadline).await,
+            None => std::future::pending().await,
+        }
+    };
+    tokio::pin!(timeout);
+
+    info!(target: "reth::cli", path = ?path, "Crawling the network");
+    loop {
+        tokio::select! {
+            Some(event) = discovery.next() => crawler.on_discovery_event(event)?,
+            Some(event) = events.next() => {
+                if let Some(peer_id) = crawler.on_network_event(event)? {
+                    network.disconnect_peer_with_reason(peer_id, DisconnectReason::ClientQuitting);
+                    // a gracefully disconnected peer would be dialed again
+                    network.remove_peer(peer_id, PeerKind::Basic);
+                }
+            }
+            _ = progress.tick() => {
+                crawler.flush()?;
+                info!(
+                    target: "reth::cli",
+                    discovered = crawler.num_discovered(),
+                    handshakes = crawler.num_handshakes(),
+                    "Crawling the network"
+                );
+            }
+            _ = &mut timeout => break,
+        }
+    }
+
+    crawler.flush()?;
+    info!(
+        target: "reth::cli",
+        discovered = crawler.num_discovered(),
+        handshakes = crawler.num_handshakes(),
+        "Crawl finished"
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use enr::EnrBuilder;
+    use reth_primitives::ForkHash;
+    use secp256k1::rand::thread_rng;
+
+    #[test]
+    fn records_discovered_peers() {
+        let mut crawler = Crawler::new(Vec::new());
+        let peer_id = PeerId::random();
+        let address = SocketAddr::from(([127, 0, 0, 1], 30303));
+        let fork_id = ForkId { hash: ForkHash([1, 2, 3, 4]), next: 0 };
+
+        let discovered = || {
+            DiscoveryEvent::NewNode(DiscoveredEvent::EventQueued {
+                peer_id,
+                socket_addr: address,
+                fork_id: None,
+            })
+        };
+        crawler.on_discovery_event(discovered()).unwrap();
+        // the same peer is only recorded again once its fork id is known
+        crawler.on_discovery_event(discovered()).unwrap();
+        crawler.on_discovery_event(DiscoveryEvent::EnrForkId(peer_id, fork_id)).unwrap();
+        crawler.on_discovery_event(discovered()).unwrap();
+        // the fork id of an unknown peer is ignored
+        crawler.on_discovery_event(DiscoveryEvent::EnrForkId(PeerId::random(), fork_id)).unwrap();
+
+        let enr = EnrBuilder::new("v4")
+            .ip4([127, 0, 0, 1].into())
+            .tcp4(30303)
+            .build(&SecretKey::new(&mut thread_rng()))
+            .unwrap();
+        crawler.on_discovery_event(DiscoveryEvent::Enr(peer_id, enr.clone())).unwrap();
+        // the same ENR is only recorded once
+        crawler.on_discovery_event(DiscoveryEvent::Enr(peer_id, enr.clone())).unwrap();
+        crawler.on_discovery_event(discovered()).unwrap();
+        assert_eq!(crawler.num_discovered(), 1);
+
+        let records = String::from_utf8(crawler.writer)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str::<CrawlRecord>(line).unwrap())
+            .collect::<Vec<_>>();
+        let crawl_enr = CrawlEnr::from(&enr);
+        assert_eq!(crawl_enr.seq, enr.seq());
+        assert_eq!(crawl_enr.pairs.keys().collect::<Vec<_>>(), ["id", "ip", "secp256k1", "tcp"]);
+        assert_eq!(
+            records,
+            vec![
+                CrawlRecord::Discovered { peer_id, address, fork_id: None, enr: None },
+                CrawlRecord::Discovered { peer_id, address, fork_id: Some(fork_id), enr: None },
+                CrawlRecord::Discovered {
+                    peer_id,
+                    address,
+                    fork_id: Some(fork_id),
+                    enr: Some(crawl_enr)
+                },
+            ]
+        );
+    }
+}
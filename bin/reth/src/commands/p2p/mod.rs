@@ -17,7 +17,9 @@ use reth_discv4::NatResolver;
 use reth_interfaces::p2p::bodies::client::BodiesClient;
 use reth_primitives::{BlockHashOrNumber, ChainSpec, NodeRecord};
 use reth_provider::ProviderFactory;
-use std::{path::PathBuf, sync::Arc};
+use std::{path::PathBuf, sync::Arc, time::Duration};
+
+pub mod crawl;
 
 /// `reth p2p` command
 #[derive(Debug, Parser)]
@@ -95,6 +97,20 @@ pub enum Subcommands {
         #[arg(value_parser = hash_or_num_value_parser)]
         id: BlockHashOrNumber,
     },
+    /// Crawl the network, recording the discovered peers and their handshakes without syncing
+    Crawl {
+        /// The file to write the records of the crawl to, one JSON object per line
+        #[arg(value_name = "OUTPUT_FILE")]
+        output: PathBuf,
+
+        /// How long to crawl, e.g. `1h`. Crawls until interrupted if not set
+        #[arg(long, value_parser = humantime::parse_duration)]
+        duration: Option<Duration>,
+
+        /// Maximum number of concurrent outbound connections
+        #[arg(long, default_value_t = 100)]
+        max_outbound: usize,
+    },
 }
 impl Command {
     /// Execute `p2p` command
@@ -118,6 +134,13 @@ impl Command {
 
         config.peers.connect_trusted_nodes_only = self.trusted_only;
 
+        if let Subcommands::Crawl { max_outbound, .. } = self.command {
+            config.peers = config
+                .peers
+                .with_max_outbound(max_outbound)
+                .with_max_concurrent_dials(max_outbound);
+        }
+
         let default_secret_key_path = data_dir.p2p_secret_path();
         let secret_key_path = self.p2p_secret_key.clone().unwrap_or(default_secret_key_path);
         let p2p_secret_key = get_secret_key(&secret_key_path)?;
@@ -176,6 +199,9 @@ impl Command {
                 let body = result.into_iter().next().unwrap();
                 println!("Successfully downloaded body: {body:?}")
             }
+            Subcommands::Crawl { ref output, duration, .. } => {
+                crawl::crawl(network, output, duration).await?;
+            }
         }
 
         Ok(())
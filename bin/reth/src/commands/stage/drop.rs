@@ -10,7 +10,7 @@ use crate::{
     utils::DbTool,
 };
 use clap::Parser;
-use reth_db::{database::Database, open_db, tables, transaction::DbTxMut, DatabaseEnv};
+use reth_db::{database::Database, open_db_with_config, tables, transaction::DbTxMut, DatabaseEnv};
 use reth_primitives::{fs, stage::StageId, ChainSpec};
 use std::sync::Arc;
 use tracing::info;
@@ -54,7 +54,8 @@ impl Command {
         let db_path = data_dir.db_path();
         fs::create_dir_all(&db_path)?;
 
-        let db = open_db(db_path.as_ref(), self.db.log_level)?;
+        let db =
+            open_db_with_config(db_path.as_ref(), self.db.log_level, self.db.database_config())?;
 
         let tool = DbTool::new(&db, self.chain.clone())?;
 
@@ -87,7 +88,7 @@ impl Command {
                         StageId::Execution.to_string(),
                         Default::default(),
                     )?;
-                    insert_genesis_state::<DatabaseEnv>(tx, self.chain.genesis())?;
+                    insert_genesis_state::<DatabaseEnv>(tx, &self.chain.genesis().alloc)?;
                 }
                 StageEnum::AccountHashing => {
                     tx.clear::<tables::HashedAccount>()?;
@@ -73,6 +73,7 @@ async fn unwind_and_copy<DB: Database>(
             max_blocks: Some(u64::MAX),
             max_changes: None,
             max_cumulative_gas: None,
+            max_duration: None,
         },
         MERKLE_STAGE_DEFAULT_CLEAN_THRESHOLD,
         PruneModes::all(),
@@ -119,10 +120,8 @@ async fn dry_run<DB: Database>(
     let factory = ProviderFactory::new(&output_db, chain);
     let provider = factory.provider_rw()?;
 
-    let mut stage = MerkleStage::Execution {
-        // Forces updating the root instead of calculating from scratch
-        clean_threshold: u64::MAX,
-    };
+    // Forces updating the root instead of calculating from scratch
+    let mut stage = MerkleStage::new_execution(u64::MAX);
 
     loop {
         let input = reth_stages::ExecInput {
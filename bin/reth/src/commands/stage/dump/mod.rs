@@ -6,8 +6,8 @@ use crate::{
 };
 use clap::Parser;
 use reth_db::{
-    cursor::DbCursorRO, database::Database, init_db, table::TableImporter, tables,
-    transaction::DbTx, DatabaseEnv,
+    cursor::DbCursorRO, database::Database, init_db, init_db_with_config, table::TableImporter,
+    tables, transaction::DbTx, DatabaseEnv,
 };
 use reth_primitives::ChainSpec;
 use std::{path::PathBuf, sync::Arc};
@@ -100,7 +100,8 @@ impl Command {
         let data_dir = self.datadir.unwrap_or_chain_default(self.chain.chain);
         let db_path = data_dir.db_path();
         info!(target: "reth::cli", path = ?db_path, "Opening database");
-        let db = Arc::new(init_db(db_path, self.db.log_level)?);
+        let db =
+            Arc::new(init_db_with_config(db_path, self.db.log_level, self.db.database_config())?);
         info!(target: "reth::cli", "Database opened");
 
         let tool = DbTool::new(&db, self.chain.clone())?;
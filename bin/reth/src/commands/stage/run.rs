@@ -15,19 +15,19 @@ use crate::{
 use clap::Parser;
 use reth_beacon_consensus::BeaconConsensus;
 use reth_config::Config;
-use reth_db::init_db;
+use reth_db::init_db_with_config;
 use reth_downloaders::bodies::bodies::BodiesDownloaderBuilder;
 use reth_primitives::ChainSpec;
-use reth_provider::{ProviderFactory, StageCheckpointReader};
+use reth_provider::{ProviderFactory, StageCheckpointReader, StageCheckpointWriter};
 use reth_stages::{
     stages::{
         AccountHashingStage, BodyStage, ExecutionStage, ExecutionStageThresholds,
         IndexAccountHistoryStage, IndexStorageHistoryStage, MerkleStage, SenderRecoveryStage,
-        StorageHashingStage, TransactionLookupStage,
+        StorageHashingStage, TotalDifficultyStage, TransactionLookupStage,
     },
     ExecInput, Stage, StageExt, UnwindInput,
 };
-use std::{any::Any, net::SocketAddr, path::PathBuf, sync::Arc};
+use std::{any::Any, net::SocketAddr, path::PathBuf, sync::Arc, time::Instant};
 use tracing::*;
 
 /// `reth stage` command
@@ -122,7 +122,8 @@ impl Command {
         let db_path = data_dir.db_path();
 
         info!(target: "reth::cli", path = ?db_path, "Opening database");
-        let db = Arc::new(init_db(db_path, self.db.log_level)?);
+        let db =
+            Arc::new(init_db_with_config(db_path, self.db.log_level, self.db.apply(config.db))?);
         info!(target: "reth::cli", "Database opened");
 
         let factory = ProviderFactory::new(Arc::clone(&db), self.chain.clone());
@@ -148,6 +149,9 @@ impl Command {
 
                     let mut config = config;
                     config.peers.connect_trusted_nodes_only = self.network.trusted_only;
+                    if let Some(trusted_peers_file) = &self.network.trusted_peers_file {
+                        config.peers.trusted_nodes_file = Some(trusted_peers_file.clone());
+                    }
                     if !self.network.trusted_peers.is_empty() {
                         self.network.trusted_peers.iter().for_each(|peer| {
                             config.peers.trusted_nodes.insert(*peer);
@@ -183,6 +187,9 @@ impl Command {
                         BodiesDownloaderBuilder::default()
                             .with_stream_batch_size(batch_size as usize)
                             .with_request_limit(config.stages.bodies.downloader_request_limit)
+                            .with_max_response_size_bytes(
+                                config.stages.bodies.downloader_max_response_size_bytes,
+                            )
                             .with_max_buffered_blocks_size_bytes(
                                 config.stages.bodies.downloader_max_buffered_blocks_size_bytes,
                             )
@@ -194,7 +201,23 @@ impl Command {
                     );
                     (Box::new(stage), None)
                 }
-                StageEnum::Senders => (Box::new(SenderRecoveryStage::new(batch_size)), None),
+                StageEnum::TotalDifficulty => {
+                    let consensus = Arc::new(BeaconConsensus::new(self.chain.clone()));
+                    (
+                        Box::new(
+                            TotalDifficultyStage::new(consensus).with_commit_threshold(batch_size),
+                        ),
+                        None,
+                    )
+                }
+                StageEnum::Senders => (
+                    Box::new(
+                        SenderRecoveryStage::new(batch_size)
+                            .with_parallelism(config.stages.sender_recovery.parallelism)
+                            .with_batch_size(config.stages.sender_recovery.batch_size),
+                    ),
+                    None,
+                ),
                 StageEnum::Execution => {
                     let factory = reth_revm::EvmProcessorFactory::new(self.chain.clone());
                     (
@@ -204,6 +227,7 @@ impl Command {
                                 max_blocks: Some(batch_size),
                                 max_changes: None,
                                 max_cumulative_gas: None,
+                                max_duration: None,
                             },
                             config.stages.merkle.clean_threshold,
                             config.prune.map(|prune| prune.segments).unwrap_or_default(),
@@ -226,13 +250,21 @@ impl Command {
                 ),
                 StageEnum::AccountHistory => (Box::<IndexAccountHistoryStage>::default(), None),
                 StageEnum::StorageHistory => (Box::<IndexStorageHistoryStage>::default(), None),
-                _ => return Ok(()),
+                StageEnum::Headers => {
+                    eyre::bail!("The headers stage requires a sync target and can't be run on its own, use `reth node` instead")
+                }
+                StageEnum::Hashing => {
+                    eyre::bail!(
+                        "Run the `account-hashing` and `storage-hashing` stages separately instead"
+                    )
+                }
             };
         if let Some(unwind_stage) = &unwind_stage {
             assert!(exec_stage.type_id() == unwind_stage.type_id());
         }
 
-        let checkpoint = provider_rw.get_stage_checkpoint(exec_stage.id())?.unwrap_or_default();
+        let stage_id = exec_stage.id();
+        let checkpoint = provider_rw.get_stage_checkpoint(stage_id)?.unwrap_or_default();
 
         let unwind_stage = unwind_stage.as_mut().unwrap_or(&mut exec_stage);
 
@@ -246,8 +278,10 @@ impl Command {
             while unwind.checkpoint.block_number > self.from {
                 let unwind_output = unwind_stage.unwind(&provider_rw, unwind)?;
                 unwind.checkpoint = unwind_output.checkpoint;
+                info!(target: "reth::cli", stage = %stage_id, checkpoint = unwind.checkpoint.block_number, "Stage unwound");
 
                 if self.commit {
+                    provider_rw.save_stage_checkpoint(stage_id, unwind.checkpoint)?;
                     provider_rw.commit()?;
                     provider_rw = factory.provider_rw()?;
                 }
@@ -259,13 +293,16 @@ impl Command {
             checkpoint: Some(checkpoint.with_block_number(self.from)),
         };
 
+        let start = Instant::now();
         loop {
             exec_stage.execute_ready(input).await?;
             let output = exec_stage.execute(&provider_rw, input)?;
 
             input.checkpoint = Some(output.checkpoint);
+            info!(target: "reth::cli", stage = %stage_id, checkpoint = output.checkpoint.block_number, done = output.done, "Stage executed");
 
             if self.commit {
+                provider_rw.save_stage_checkpoint(stage_id, output.checkpoint)?;
                 provider_rw.commit()?;
                 provider_rw = factory.provider_rw()?;
             }
@@ -274,6 +311,7 @@ impl Command {
                 break
             }
         }
+        info!(target: "reth::cli", stage = %stage_id, elapsed = ?start.elapsed(), committed = self.commit, "Finished stage run");
 
         Ok(())
     }
@@ -3,15 +3,29 @@
 use crate::{
     args::{
         utils::{chain_help, genesis_value_parser, SUPPORTED_CHAINS},
-        DatabaseArgs,
+        DatabaseArgs, StageEnum,
     },
     dirs::{DataDirPath, MaybePlatformPath},
 };
 use clap::{Parser, Subcommand};
-use reth_db::{cursor::DbCursorRO, database::Database, open_db, tables, transaction::DbTx};
-use reth_primitives::{BlockHashOrNumber, ChainSpec};
-use reth_provider::{BlockExecutionWriter, ProviderFactory};
-use std::{ops::RangeInclusive, sync::Arc};
+use eyre::WrapErr;
+use reth_beacon_consensus::BeaconConsensus;
+use reth_config::Config;
+use reth_db::{
+    cursor::DbCursorRO, database::Database, open_db_with_config, tables, transaction::DbTx,
+};
+use reth_downloaders::{
+    bodies::bodies::BodiesDownloaderBuilder, file_client::FileClient,
+    headers::reverse_headers::ReverseHeadersDownloaderBuilder,
+};
+use reth_primitives::{stage::StageId, BlockHashOrNumber, ChainSpec};
+use reth_provider::{HeaderSyncMode, ProviderFactory};
+use reth_stages::{
+    prelude::*,
+    stages::{ExecutionStage, ExecutionStageThresholds, SenderRecoveryStage, TotalDifficultyStage},
+};
+use std::{ops::RangeInclusive, path::PathBuf, sync::Arc};
+use tracing::info;
 
 /// `reth stage unwind` command
 #[derive(Debug, Parser)]
@@ -39,9 +53,20 @@ pub struct Command {
     )]
     chain: Arc<ChainSpec>,
 
+    /// The path to the configuration file to use.
+    #[arg(long, value_name = "FILE", verbatim_doc_comment, global = true)]
+    config: Option<PathBuf>,
+
     #[clap(flatten)]
     db: DatabaseArgs,
 
+    /// Only unwinds the given stage and the stages that are executed after it.
+    ///
+    /// The data of the stages before it, e.g. downloaded headers and bodies when unwinding the
+    /// execution stage, is kept.
+    #[arg(long, value_enum, global = true)]
+    stage: Option<StageEnum>,
+
     #[clap(subcommand)]
     command: Subcommands,
 }
@@ -56,27 +81,132 @@ impl Command {
             eyre::bail!("Database {db_path:?} does not exist.")
         }
 
-        let db = open_db(db_path.as_ref(), self.db.log_level)?;
+        let config_path = self.config.clone().unwrap_or(data_dir.config_path());
+        let config: Config = confy::load_path::<Config>(config_path.clone())
+            .wrap_err_with(|| format!("Could not load config file {:?}", config_path))?;
+
+        let db = Arc::new(open_db_with_config(
+            db_path.as_ref(),
+            self.db.log_level,
+            self.db.database_config(),
+        )?);
 
         let range = self.command.unwind_range(&db)?;
 
         if *range.start() == 0 {
             eyre::bail!("Cannot unwind genesis block")
         }
+        let target = *range.start() - 1;
 
-        let factory = ProviderFactory::new(&db, self.chain.clone());
-        let provider = factory.provider_rw()?;
+        let provider_factory = ProviderFactory::new(db, self.chain.clone());
+        let mut pipeline = self.build_pipeline(config, provider_factory)?;
 
-        let blocks_and_execution = provider
-            .take_block_and_execution_range(&self.chain, range)
-            .map_err(|err| eyre::eyre!("Transaction error on unwind: {err:?}"))?;
+        info!(target: "reth::cli", target, "Unwinding pipeline");
+        pipeline.unwind(target, None)?;
 
-        provider.commit()?;
-
-        println!("Unwound {} blocks", blocks_and_execution.len());
+        println!("Unwound {} blocks", range.count());
 
         Ok(())
     }
+
+    /// Builds a pipeline with the default stages, which is only used to unwind them.
+    fn build_pipeline<DB>(
+        &self,
+        config: Config,
+        provider_factory: ProviderFactory<DB>,
+    ) -> eyre::Result<Pipeline<DB>>
+    where
+        DB: Database + Clone + Unpin + 'static,
+    {
+        let stages = self.build_stages(config, provider_factory.clone())?;
+        Ok(Pipeline::builder().add_stages(stages).build(provider_factory))
+    }
+
+    /// Builds the stages to unwind, which are all default stages or the ones selected by
+    /// `--stage`.
+    ///
+    /// The downloaders are backed by an empty [FileClient], so nothing is ever downloaded.
+    fn build_stages<DB>(
+        &self,
+        config: Config,
+        provider_factory: ProviderFactory<DB>,
+    ) -> eyre::Result<StageSetBuilder<DB>>
+    where
+        DB: Database + Clone + Unpin + 'static,
+    {
+        let consensus = Arc::new(BeaconConsensus::new(self.chain.clone()));
+        let client = Arc::new(FileClient::from_blocks(Vec::new()));
+
+        let header_downloader = ReverseHeadersDownloaderBuilder::new(config.stages.headers)
+            .build(client.clone(), consensus.clone());
+        let body_downloader = BodiesDownloaderBuilder::new(config.stages.bodies).build(
+            client,
+            consensus.clone(),
+            provider_factory.clone(),
+        );
+
+        let factory = reth_revm::EvmProcessorFactory::new(self.chain.clone());
+
+        let stages: StageSetBuilder<DB> = DefaultStages::new(
+            provider_factory.clone(),
+            HeaderSyncMode::Continuous,
+            consensus.clone(),
+            header_downloader,
+            body_downloader,
+            factory.clone(),
+        )
+        .set(
+            TotalDifficultyStage::new(consensus)
+                .with_commit_threshold(config.stages.total_difficulty.commit_threshold),
+        )
+        .set(
+            SenderRecoveryStage::new(config.stages.sender_recovery.commit_threshold)
+                .with_parallelism(config.stages.sender_recovery.parallelism)
+                .with_batch_size(config.stages.sender_recovery.batch_size),
+        )
+        .set(ExecutionStage::new(
+            factory,
+            ExecutionStageThresholds {
+                max_blocks: config.stages.execution.max_blocks,
+                max_changes: config.stages.execution.max_changes,
+                max_cumulative_gas: config.stages.execution.max_cumulative_gas,
+                max_duration: config.stages.execution.max_duration,
+            },
+            config
+                .stages
+                .merkle
+                .clean_threshold
+                .max(config.stages.account_hashing.clean_threshold)
+                .max(config.stages.storage_hashing.clean_threshold),
+            config.prune.map(|prune| prune.segments).unwrap_or_default(),
+        ));
+
+        Ok(match self.stage {
+            Some(stage) => stages.disable_all_before(first_stage_id(stage)),
+            None => stages,
+        })
+    }
+}
+
+/// Returns the id of the first pipeline stage that belongs to the given [StageEnum].
+///
+/// The hashing and merkle stages are unwound together, starting with the
+/// [StageId::MerkleUnwind] stage that reverts the trie before the hashed state is unwound.
+fn first_stage_id(stage: StageEnum) -> StageId {
+    match stage {
+        StageEnum::Headers => StageId::Headers,
+        StageEnum::TotalDifficulty => StageId::TotalDifficulty,
+        StageEnum::Bodies => StageId::Bodies,
+        StageEnum::Senders => StageId::SenderRecovery,
+        StageEnum::Execution => StageId::Execution,
+        StageEnum::AccountHashing |
+        StageEnum::StorageHashing |
+        StageEnum::Hashing |
+        StageEnum::Merkle => StageId::MerkleUnwind,
+        StageEnum::TxLookup => StageId::TransactionLookup,
+        StageEnum::StorageHistory => StageId::IndexStorageHistory,
+        StageEnum::AccountHistory => StageId::IndexAccountHistory,
+    }
 }
 
 /// `reth stage unwind` subcommand
@@ -115,6 +245,9 @@ impl Subcommands {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use reth_db::test_utils::create_test_rw_db;
+    use reth_primitives::MAINNET;
+    use reth_stages::Stage;
 
     #[test]
     fn parse_unwind() {
@@ -123,5 +256,36 @@ mod tests {
 
         let cmd = Command::parse_from(["reth", "--datadir", "dir", "num-blocks", "100"]);
         assert_eq!(cmd.command, Subcommands::NumBlocks { amount: 100 });
+
+        let cmd = Command::parse_from(["reth", "to-block", "100", "--stage", "execution"]);
+        assert_eq!(cmd.stage, Some(StageEnum::Execution));
+    }
+
+    #[test]
+    fn unwind_merkle_stages() {
+        let factory = ProviderFactory::new(create_test_rw_db(), MAINNET.clone());
+        let cmd = Command::parse_from(["reth", "to-block", "100", "--stage", "merkle"]);
+        let stages = cmd
+            .build_stages(Config::default(), factory)
+            .unwrap()
+            .build()
+            .into_iter()
+            .map(|stage| stage.id())
+            .collect::<Vec<_>>();
+
+        // the trie is unwound before the hashed state, and the stages before it are kept
+        assert_eq!(
+            stages,
+            vec![
+                StageId::MerkleUnwind,
+                StageId::AccountHashing,
+                StageId::StorageHashing,
+                StageId::MerkleExecute,
+                StageId::TransactionLookup,
+                StageId::IndexStorageHistory,
+                StageId::IndexAccountHistory,
+                StageId::Finish,
+            ]
+        );
     }
 }
@@ -301,6 +301,13 @@ impl<D> ChainPath<D> {
         self.0.join("known-peers.json").into()
     }
 
+    /// Returns the path to the banned peers file for this chain.
+    ///
+    /// `<DIR>/<CHAIN_ID>/banned-peers.json`
+    pub fn banned_peers_path(&self) -> PathBuf {
+        self.0.join("banned-peers.json").into()
+    }
+
     /// Returns the path to the blobstore directory for this chain where blobs of unfinalized
     /// transactions are stored.
     ///
@@ -316,6 +323,13 @@ impl<D> ChainPath<D> {
         self.0.join("txpool-transactions-backup.rlp").into()
     }
 
+    /// Returns the path to the pending transactions backup file
+    ///
+    /// `<DIR>/<CHAIN_ID>/txpool-pending-transactions-backup.rlp`
+    pub fn txpool_pending_transactions_path(&self) -> PathBuf {
+        self.0.join("txpool-pending-transactions-backup.rlp").into()
+    }
+
     /// Returns the path to the config file for this chain.
     ///
     /// `<DIR>/<CHAIN_ID>/reth.toml`
@@ -1,24 +1,21 @@
 //! Reth genesis initialization utility functions.
 
 use reth_db::{
-    cursor::DbCursorRO,
+    cursor::{DbCursorRO, DbCursorRW},
     database::Database,
+    models::{AccountBeforeTx, BlockNumberAddress},
     tables,
     transaction::{DbTx, DbTxMut},
 };
 use reth_interfaces::{db::DatabaseError, provider::ProviderResult};
 use reth_primitives::{
-    stage::StageId, Account, Bytecode, ChainSpec, Receipts, StorageEntry, B256, U256,
+    keccak256, stage::StageId, Account, Address, Bytecode, ChainSpec, Genesis, GenesisAccount,
+    StorageEntry, B256, U256,
 };
 use reth_provider::{
-    bundle_state::{BundleStateInit, RevertsInit},
-    BundleStateWithReceipts, DatabaseProviderRW, HashingWriter, HistoryWriter, OriginalValuesKnown,
-    ProviderError, ProviderFactory,
-};
-use std::{
-    collections::{BTreeMap, HashMap},
-    sync::Arc,
+    DatabaseProviderRW, HashingWriter, HistoryWriter, ProviderError, ProviderFactory,
 };
+use std::{collections::BTreeMap, sync::Arc};
 use tracing::debug;
 
 /// Database initialization error type.
@@ -45,7 +42,15 @@ impl From<DatabaseError> for InitDatabaseError {
     }
 }
 
+/// The number of genesis accounts and storage slots that are written in a single transaction.
+const GENESIS_COMMIT_THRESHOLD: usize = 100_000;
+
 /// Write the genesis block if it has not already been written
+///
+/// The genesis state is written in batches of accounts that are committed one after the other, so
+/// large allocations don't have to fit into a single transaction. The header is written last, a
+/// database without it is left over from an interrupted initialization and its state is
+/// overwritten.
 pub fn init_genesis<DB: Database>(
     db: Arc<DB>,
     chain: Arc<ChainSpec>,
@@ -70,19 +75,23 @@ pub fn init_genesis<DB: Database>(
     drop(tx);
     debug!("Writing genesis block.");
 
-    // use transaction to insert genesis header
     let factory = ProviderFactory::new(&db, chain.clone());
     let provider_rw = factory.provider_rw()?;
-    insert_genesis_hashes(&provider_rw, genesis)?;
-    insert_genesis_history(&provider_rw, genesis)?;
+    clear_genesis_state::<DB>(provider_rw.tx_ref())?;
     provider_rw.commit()?;
 
+    for batch in genesis_batches(genesis) {
+        let provider_rw = factory.provider_rw()?;
+        insert_genesis_hashes(&provider_rw, batch.iter().copied())?;
+        insert_genesis_history(&provider_rw, batch.iter().copied())?;
+        insert_genesis_state::<DB>(provider_rw.tx_ref(), batch.iter().copied())?;
+        provider_rw.commit()?;
+    }
+
     // Insert header
     let tx = db.tx_mut()?;
     insert_genesis_header::<DB>(&tx, chain.clone())?;
 
-    insert_genesis_state::<DB>(&tx, genesis)?;
-
     // insert sync stage
     for stage in StageId::ALL.iter() {
         tx.put::<tables::SyncStage>(stage.to_string(), Default::default())?;
@@ -92,88 +101,126 @@ pub fn init_genesis<DB: Database>(
     Ok(hash)
 }
 
-/// Inserts the genesis state into the database.
-pub fn insert_genesis_state<DB: Database>(
+/// Clears the tables that are written by [insert_genesis_state], [insert_genesis_hashes] and
+/// [insert_genesis_history].
+fn clear_genesis_state<DB: Database>(tx: &<DB as Database>::TXMut) -> Result<(), DatabaseError> {
+    tx.clear::<tables::Bytecodes>()?;
+    tx.clear::<tables::PlainAccountState>()?;
+    tx.clear::<tables::PlainStorageState>()?;
+    tx.clear::<tables::AccountChangeSet>()?;
+    tx.clear::<tables::StorageChangeSet>()?;
+    tx.clear::<tables::HashedAccount>()?;
+    tx.clear::<tables::HashedStorage>()?;
+    tx.clear::<tables::AccountHistory>()?;
+    tx.clear::<tables::StorageHistory>()?;
+    Ok(())
+}
+
+/// Splits the genesis allocation into batches of accounts in ascending order of their addresses,
+/// each with about [GENESIS_COMMIT_THRESHOLD] accounts and storage slots.
+///
+/// The storage of an account is never split.
+fn genesis_batches(genesis: &Genesis) -> Vec<Vec<(&Address, &GenesisAccount)>> {
+    let mut alloc = genesis.alloc.iter().collect::<Vec<_>>();
+    alloc.sort_unstable_by_key(|(address, _)| *address);
+
+    let mut batches = Vec::new();
+    let mut batch = Vec::new();
+    let mut entries = 0;
+    for (address, account) in alloc {
+        batch.push((address, account));
+        entries += 1 + account.storage.as_ref().map_or(0, |storage| storage.len());
+        if entries >= GENESIS_COMMIT_THRESHOLD {
+            batches.push(std::mem::take(&mut batch));
+            entries = 0;
+        }
+    }
+    if !batch.is_empty() {
+        batches.push(batch);
+    }
+    batches
+}
+
+/// Inserts the genesis state of the accounts into the database.
+///
+/// The accounts are written directly with cursors, so that large allocations are not copied into
+/// an intermediate bundle state. Writing them in ascending order of their addresses is the
+/// fastest, but not required.
+pub fn insert_genesis_state<'a, DB: Database>(
     tx: &<DB as Database>::TXMut,
-    genesis: &reth_primitives::Genesis,
+    alloc: impl IntoIterator<Item = (&'a Address, &'a GenesisAccount)>,
 ) -> ProviderResult<()> {
-    let mut state_init: BundleStateInit = HashMap::new();
-    let mut reverts_init = HashMap::new();
-    let mut contracts: HashMap<B256, Bytecode> = HashMap::new();
+    let mut accounts_cursor = tx.cursor_write::<tables::PlainAccountState>()?;
+    let mut storage_cursor = tx.cursor_dup_write::<tables::PlainStorageState>()?;
+    let mut bytecodes_cursor = tx.cursor_write::<tables::Bytecodes>()?;
+    let mut account_changeset_cursor = tx.cursor_dup_write::<tables::AccountChangeSet>()?;
+    let mut storage_changeset_cursor = tx.cursor_dup_write::<tables::StorageChangeSet>()?;
 
-    for (address, account) in &genesis.alloc {
+    for (address, account) in alloc {
         let bytecode_hash = if let Some(code) = &account.code {
             let bytecode = Bytecode::new_raw(code.clone());
             let hash = bytecode.hash_slow();
-            contracts.insert(hash, bytecode);
+            bytecodes_cursor.upsert(hash, bytecode)?;
             Some(hash)
         } else {
             None
         };
 
-        // get state
-        let storage = account
-            .storage
-            .as_ref()
-            .map(|m| {
-                m.iter()
-                    .map(|(key, value)| {
-                        let value = U256::from_be_bytes(value.0);
-                        (*key, (U256::ZERO, value))
-                    })
-                    .collect::<HashMap<_, _>>()
-            })
-            .unwrap_or_default();
-
-        reverts_init.insert(
-            *address,
-            (Some(None), storage.keys().map(|k| StorageEntry::new(*k, U256::ZERO)).collect()),
-        );
-
-        state_init.insert(
+        accounts_cursor.upsert(
             *address,
-            (
-                None,
-                Some(Account {
-                    nonce: account.nonce.unwrap_or_default(),
-                    balance: account.balance,
-                    bytecode_hash,
-                }),
-                storage,
-            ),
-        );
+            Account {
+                nonce: account.nonce.unwrap_or_default(),
+                balance: account.balance,
+                bytecode_hash,
+            },
+        )?;
+        // the accounts did not exist before the genesis block
+        account_changeset_cursor.upsert(0, AccountBeforeTx { address: *address, info: None })?;
+
+        if let Some(storage) = &account.storage {
+            let mut storage = storage.iter().collect::<Vec<_>>();
+            storage.sort_unstable_by_key(|(key, _)| *key);
+
+            for (key, value) in storage {
+                let value = U256::from_be_bytes(value.0);
+                storage_changeset_cursor.upsert(
+                    BlockNumberAddress((0, *address)),
+                    StorageEntry { key: *key, value: U256::ZERO },
+                )?;
+                if value != U256::ZERO {
+                    storage_cursor.upsert(*address, StorageEntry { key: *key, value })?;
+                }
+            }
+        }
     }
-    let all_reverts_init: RevertsInit = HashMap::from([(0, reverts_init)]);
-
-    let bundle = BundleStateWithReceipts::new_init(
-        state_init,
-        all_reverts_init,
-        contracts.into_iter().collect(),
-        Receipts::new(),
-        0,
-    );
-
-    bundle.write_to_db(tx, OriginalValuesKnown::Yes)?;
 
     Ok(())
 }
 
-/// Inserts hashes for the genesis state.
-pub fn insert_genesis_hashes<DB: Database>(
+/// Inserts hashes for the genesis state of the accounts.
+pub fn insert_genesis_hashes<'a, DB: Database>(
     provider: &DatabaseProviderRW<&DB>,
-    genesis: &reth_primitives::Genesis,
+    alloc: impl IntoIterator<Item = (&'a Address, &'a GenesisAccount)> + Clone,
 ) -> ProviderResult<()> {
     // insert and hash accounts to hashing table
-    let alloc_accounts =
-        genesis.alloc.clone().into_iter().map(|(addr, account)| (addr, Some(account.into())));
+    let alloc_accounts = alloc.clone().into_iter().map(|(addr, account)| {
+        let account = Account {
+            nonce: account.nonce.unwrap_or_default(),
+            balance: account.balance,
+            bytecode_hash: account.code.as_ref().map(keccak256),
+        };
+        (*addr, Some(account))
+    });
     provider.insert_account_for_hashing(alloc_accounts)?;
 
-    let alloc_storage = genesis.alloc.clone().into_iter().filter_map(|(addr, account)| {
+    let alloc_storage = alloc.into_iter().filter_map(|(addr, account)| {
         // only return Some if there is storage
-        account.storage.map(|storage| {
+        account.storage.as_ref().map(|storage| {
             (
-                addr,
-                storage.into_iter().map(|(key, value)| StorageEntry { key, value: value.into() }),
+                *addr,
+                storage
+                    .iter()
+                    .map(|(key, value)| StorageEntry { key: *key, value: (*value).into() }),
             )
         })
     });
@@ -182,18 +229,17 @@ pub fn insert_genesis_hashes<DB: Database>(
     Ok(())
 }
 
-/// Inserts history indices for genesis accounts and storage.
-pub fn insert_genesis_history<DB: Database>(
+/// Inserts history indices for the genesis accounts and storage.
+pub fn insert_genesis_history<'a, DB: Database>(
     provider: &DatabaseProviderRW<&DB>,
-    genesis: &reth_primitives::Genesis,
+    alloc: impl IntoIterator<Item = (&'a Address, &'a GenesisAccount)> + Clone,
 ) -> ProviderResult<()> {
     let account_transitions =
-        genesis.alloc.keys().map(|addr| (*addr, vec![0])).collect::<BTreeMap<_, _>>();
+        alloc.clone().into_iter().map(|(addr, _)| (*addr, vec![0])).collect::<BTreeMap<_, _>>();
     provider.insert_account_history_index(account_transitions)?;
 
-    let storage_transitions = genesis
-        .alloc
-        .iter()
+    let storage_transitions = alloc
+        .into_iter()
         .filter_map(|(addr, account)| account.storage.as_ref().map(|storage| (addr, storage)))
         .flat_map(|(addr, storage)| storage.iter().map(|(key, _)| ((*addr, *key), vec![0])))
         .collect::<BTreeMap<_, _>>();
@@ -229,8 +275,8 @@ mod tests {
         DatabaseEnv,
     };
     use reth_primitives::{
-        Address, Chain, ForkTimestamps, Genesis, GenesisAccount, IntegerList, GOERLI,
-        GOERLI_GENESIS_HASH, MAINNET, MAINNET_GENESIS_HASH, SEPOLIA, SEPOLIA_GENESIS_HASH,
+        Bytes, Chain, ForkTimestamps, IntegerList, GOERLI, GOERLI_GENESIS_HASH, MAINNET,
+        MAINNET_GENESIS_HASH, SEPOLIA, SEPOLIA_GENESIS_HASH,
     };
     use std::collections::HashMap;
 
@@ -342,4 +388,169 @@ mod tests {
             )],
         );
     }
+
+    #[test]
+    fn init_genesis_state() {
+        let address_with_code = Address::with_last_byte(1);
+        let address_with_storage = Address::with_last_byte(2);
+        let storage_key = B256::with_last_byte(1);
+        let storage_value = B256::with_last_byte(2);
+        let code = Bytes::from_static(&[0x60, 0x00]);
+
+        let chain_spec = Arc::new(ChainSpec {
+            chain: Chain::from_id(1),
+            genesis: Genesis {
+                alloc: HashMap::from([
+                    (
+                        address_with_storage,
+                        GenesisAccount {
+                            nonce: Some(1),
+                            storage: Some(HashMap::from([
+                                (storage_key, storage_value),
+                                (B256::with_last_byte(3), B256::ZERO),
+                            ])),
+                            ..Default::default()
+                        },
+                    ),
+                    (
+                        address_with_code,
+                        GenesisAccount { code: Some(code.clone()), ..Default::default() },
+                    ),
+                ]),
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+
+        let db = create_test_rw_db();
+        init_genesis(db.clone(), chain_spec).unwrap();
+
+        let tx = db.tx().expect("failed to init tx");
+
+        let code_hash = keccak256(&code);
+        assert_eq!(
+            collect_table_entries::<Arc<DatabaseEnv>, tables::PlainAccountState>(&tx)
+                .expect("failed to collect"),
+            vec![
+                (
+                    address_with_code,
+                    Account { bytecode_hash: Some(code_hash), ..Default::default() }
+                ),
+                (address_with_storage, Account { nonce: 1, ..Default::default() }),
+            ],
+        );
+        assert_eq!(
+            collect_table_entries::<Arc<DatabaseEnv>, tables::Bytecodes>(&tx)
+                .expect("failed to collect")
+                .into_iter()
+                .map(|(hash, _)| hash)
+                .collect::<Vec<_>>(),
+            vec![code_hash],
+        );
+
+        // zero storage values are not stored
+        assert_eq!(
+            collect_table_entries::<Arc<DatabaseEnv>, tables::PlainStorageState>(&tx)
+                .expect("failed to collect"),
+            vec![(
+                address_with_storage,
+                StorageEntry { key: storage_key, value: U256::from_be_bytes(storage_value.0) }
+            )],
+        );
+
+        assert_eq!(
+            collect_table_entries::<Arc<DatabaseEnv>, tables::AccountChangeSet>(&tx)
+                .expect("failed to collect"),
+            vec![
+                (0, AccountBeforeTx { address: address_with_code, info: None }),
+                (0, AccountBeforeTx { address: address_with_storage, info: None }),
+            ],
+        );
+        assert_eq!(
+            collect_table_entries::<Arc<DatabaseEnv>, tables::StorageChangeSet>(&tx)
+                .expect("failed to collect")
+                .len(),
+            2
+        );
+    }
+
+    #[test]
+    fn split_genesis_into_batches() {
+        let storage = (0..GENESIS_COMMIT_THRESHOLD as u64)
+            .map(|slot| (B256::from(U256::from(slot)), B256::with_last_byte(1)))
+            .collect::<HashMap<_, _>>();
+        let genesis = Genesis {
+            alloc: HashMap::from([
+                (Address::with_last_byte(3), GenesisAccount::default()),
+                (
+                    Address::with_last_byte(1),
+                    GenesisAccount { storage: Some(storage), ..Default::default() },
+                ),
+                (Address::with_last_byte(2), GenesisAccount::default()),
+            ]),
+            ..Default::default()
+        };
+
+        // the storage of an account is not split, and the accounts are sorted
+        let batches = genesis_batches(&genesis)
+            .into_iter()
+            .map(|batch| batch.into_iter().map(|(address, _)| *address).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            batches,
+            vec![
+                vec![Address::with_last_byte(1)],
+                vec![Address::with_last_byte(2), Address::with_last_byte(3)]
+            ]
+        );
+    }
+
+    #[test]
+    fn init_genesis_over_interrupted_init() {
+        let address = Address::with_last_byte(1);
+        let chain_spec = Arc::new(ChainSpec {
+            chain: Chain::from_id(1),
+            genesis: Genesis {
+                alloc: HashMap::from([(
+                    address,
+                    GenesisAccount { balance: U256::from(1), ..Default::default() },
+                )]),
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+
+        // the state of an interrupted init, without the header
+        let db = create_test_rw_db();
+        let factory = ProviderFactory::new(&db, chain_spec.clone());
+        let provider_rw = factory.provider_rw().unwrap();
+        let alloc = &chain_spec.genesis().alloc;
+        insert_genesis_history(&provider_rw, alloc).unwrap();
+        insert_genesis_state::<Arc<DatabaseEnv>>(provider_rw.tx_ref(), alloc).unwrap();
+        let leftover = Address::with_last_byte(2);
+        provider_rw
+            .tx_ref()
+            .put::<tables::PlainAccountState>(leftover, Account::default())
+            .unwrap();
+        provider_rw.commit().unwrap();
+
+        init_genesis(db.clone(), chain_spec).unwrap();
+
+        let tx = db.tx().expect("failed to init tx");
+        assert_eq!(
+            collect_table_entries::<Arc<DatabaseEnv>, tables::PlainAccountState>(&tx)
+                .expect("failed to collect"),
+            vec![(address, Account { balance: U256::from(1), ..Default::default() })],
+        );
+        assert_eq!(
+            collect_table_entries::<Arc<DatabaseEnv>, tables::AccountHistory>(&tx)
+                .expect("failed to collect"),
+            vec![(ShardedKey::new(address, u64::MAX), IntegerList::new([0]).unwrap())],
+        );
+        assert_eq!(
+            collect_table_entries::<Arc<DatabaseEnv>, tables::AccountChangeSet>(&tx)
+                .expect("failed to collect"),
+            vec![(0, AccountBeforeTx { address, info: None })],
+        );
+    }
 }
@@ -269,16 +269,17 @@ pub fn get_or_create_jwt_secret_from_path(path: &Path) -> Result<JwtSecret, JwtE
     }
 }
 
-/// Collect the peers from the [NetworkManager] and write them to the given `persistent_peers_file`,
-/// if configured.
+/// Collect the peers and their reputation and backoff state from the [NetworkManager] and write
+/// them to the given `persistent_peers_file`, if configured.
 pub fn write_peers_to_file<C>(network: &NetworkManager<C>, persistent_peers_file: Option<PathBuf>)
 where
     C: BlockReader + Unpin,
 {
     if let Some(file_path) = persistent_peers_file {
-        let known_peers = network.all_peers().collect::<Vec<_>>();
+        let known_peers = network.all_persisted_peers().collect::<Vec<_>>();
+        let num_peers = known_peers.len();
         if let Ok(known_peers) = serde_json::to_string_pretty(&known_peers) {
-            trace!(target: "reth::cli", peers_file =?file_path, num_peers=%known_peers.len(), "Saving current peers");
+            trace!(target: "reth::cli", peers_file =?file_path, %num_peers, "Saving current peers");
             let parent_dir = file_path.parent().map(fs::create_dir_all).transpose();
             match parent_dir.and_then(|_| fs::write(&file_path, known_peers)) {
                 Ok(_) => {
@@ -291,3 +292,27 @@ where
         }
     }
 }
+
+/// Collect the active bans of peers and IP addresses from the [NetworkManager] and write them to
+/// the given `bans_file`, if configured.
+pub fn write_bans_to_file<C>(network: &NetworkManager<C>, bans_file: Option<PathBuf>)
+where
+    C: BlockReader + Unpin,
+{
+    if let Some(file_path) = bans_file {
+        let bans = network.bans();
+        let num_bans = bans.peers.len() + bans.ips.len();
+        if let Ok(bans) = serde_json::to_string_pretty(&bans) {
+            trace!(target: "reth::cli", bans_file =?file_path, %num_bans, "Saving current bans");
+            let parent_dir = file_path.parent().map(fs::create_dir_all).transpose();
+            match parent_dir.and_then(|_| fs::write(&file_path, bans)) {
+                Ok(_) => {
+                    info!(target: "reth::cli", bans_file=?file_path, "Wrote banned peers to file");
+                }
+                Err(err) => {
+                    warn!(target: "reth::cli", ?err, bans_file=?file_path, "Failed to write banned peers to file");
+                }
+            }
+        }
+    }
+}
@@ -415,6 +415,9 @@ impl<DB: Database, EF: ExecutorFactory> BlockchainTree<DB, EF> {
             let canonical_chain = self.canonical_chain();
 
             if block.parent_hash == canonical_chain.tip().hash {
+                if let Err(err) = self.ensure_tip_sibling_capacity(block.parent_hash) {
+                    return Err(InsertBlockError::tree_error(err, block.block))
+                }
                 let chain = AppendableChain::new_canonical_head_fork(
                     block,
                     &parent_header,
@@ -431,6 +434,9 @@ impl<DB: Database, EF: ExecutorFactory> BlockchainTree<DB, EF> {
 
                 (status, chain)
             } else {
+                if let Err(err) = self.ensure_fork_capacity() {
+                    return Err(InsertBlockError::tree_error(err, block.block))
+                }
                 let chain = AppendableChain::new_canonical_fork(
                     block,
                     &parent_header,
@@ -474,6 +480,13 @@ impl<DB: Database, EF: ExecutorFactory> BlockchainTree<DB, EF> {
             Some(fork) => fork,
         };
 
+        // forks of historical blocks are only accepted while the tree has capacity
+        if canonical_fork.hash != self.block_indices().canonical_tip().hash {
+            if let Err(err) = self.ensure_fork_capacity() {
+                return Err(InsertBlockError::tree_error(err, block.block))
+            }
+        }
+
         // get chain that block needs to join to.
         let parent_chain = match self.state.chains.get_mut(&chain_id) {
             Some(parent_chain) => parent_chain,
@@ -589,6 +602,37 @@ impl<DB: Database, EF: ExecutorFactory> BlockchainTree<DB, EF> {
         (self.block_indices().canonical_hash(&fork.number) == Some(fork.hash)).then_some(fork)
     }
 
+    /// Returns an error if the tree holds the maximum number of blocks, see
+    /// [BlockchainTreeConfig::max_blocks_in_tree].
+    ///
+    /// This is checked before inserting a block that forks off a historical block, which are only
+    /// kept in memory to handle reorgs.
+    fn ensure_fork_capacity(&self) -> Result<(), BlockchainTreeError> {
+        let max_blocks = self.config.max_blocks_in_tree();
+        if self.state.block_count() >= max_blocks {
+            self.metrics.rejected_forks.increment(1);
+            return Err(BlockchainTreeError::TreeCapacityExceeded { max_blocks })
+        }
+        Ok(())
+    }
+
+    /// Returns an error if the canonical tip already has the maximum number of child blocks in the
+    /// tree, see [BlockchainTreeConfig::max_tip_siblings].
+    ///
+    /// Blocks that extend the canonical tip are not bounded by
+    /// [BlockchainTreeConfig::max_blocks_in_tree], so this keeps the number of competing blocks
+    /// for the next height bounded.
+    fn ensure_tip_sibling_capacity(&self, tip: BlockHash) -> Result<(), BlockchainTreeError> {
+        let max_siblings = self.config.max_tip_siblings();
+        let siblings =
+            self.block_indices().fork_to_child().get(&tip).map_or(0, |children| children.len());
+        if siblings >= max_siblings {
+            self.metrics.rejected_forks.increment(1);
+            return Err(BlockchainTreeError::TipSiblingsExceeded { max_siblings })
+        }
+        Ok(())
+    }
+
     /// Insert a chain into the tree.
     ///
     /// Inserts a chain into the tree and builds the block indices.
@@ -1281,6 +1325,7 @@ impl<DB: Database, EF: ExecutorFactory> BlockchainTree<DB, EF> {
         }
 
         self.metrics.sidechains.set(self.state.chains.len() as f64);
+        self.metrics.blocks.set(self.state.block_count() as f64);
         self.metrics.canonical_chain_height.set(height as f64);
         if let Some(metrics_tx) = self.sync_metrics_tx.as_mut() {
             let _ = metrics_tx.send(MetricEvent::SyncHeight { height });
@@ -1718,6 +1763,66 @@ mod tests {
         assert_eq!(tree.state.chains.get(&1.into()).unwrap().state().state().reverts.len(), 1);
     }
 
+    #[tokio::test]
+    async fn test_fork_capacity() {
+        let data = BlockChainTestData::default_with_numbers(11, 12);
+        let (block1, exec1) = data.blocks[0].clone();
+        let genesis = data.genesis;
+
+        let externals = setup_externals(vec![exec1]);
+
+        // last finalized block would be number 9.
+        setup_genesis(&externals.provider_factory, genesis);
+
+        // make tree that holds a single block
+        let config = BlockchainTreeConfig::new(1, 2, 3, 2)
+            .with_max_blocks_in_tree(1)
+            .with_max_tip_siblings(1);
+        let mut tree = BlockchainTree::new(externals, config, None).expect("failed to create tree");
+        // genesis block 10 is already canonical
+        tree.make_canonical(&B256::ZERO).unwrap();
+
+        // make genesis block 10 as finalized
+        tree.finalize_block(10);
+
+        assert_eq!(tree.ensure_fork_capacity(), Ok(()));
+        assert_eq!(tree.ensure_tip_sibling_capacity(block1.parent_hash), Ok(()));
+
+        assert_eq!(
+            tree.insert_block(block1.clone(), BlockValidationKind::Exhaustive).unwrap(),
+            InsertPayloadOk::Inserted(BlockStatus::Valid)
+        );
+
+        // the tree is full, forks off historical blocks are rejected
+        assert_eq!(
+            tree.ensure_fork_capacity(),
+            Err(BlockchainTreeError::TreeCapacityExceeded { max_blocks: 1 })
+        );
+
+        // a sibling of block1 extends the canonical tip, which already has the maximum number of
+        // children
+        let mut block1a = block1.clone();
+        block1a.hash = B256::new([0x34; 32]);
+
+        let err = tree.insert_block(block1a, BlockValidationKind::Exhaustive).unwrap_err();
+        assert_eq!(
+            err.kind().as_tree_error(),
+            Some(BlockchainTreeError::TipSiblingsExceeded { max_siblings: 1 })
+        );
+
+        // Trie state:
+        //      b1 (pending block)
+        //    /
+        //  /
+        // g1 (canonical blocks)
+        // |
+        TreeTester::default()
+            .with_chain_num(1)
+            .with_block_to_chain(HashMap::from([(block1.hash, 0.into())]))
+            .with_fork_to_child(HashMap::from([(block1.parent_hash, HashSet::from([block1.hash]))]))
+            .assert(&tree);
+    }
+
     #[tokio::test]
     async fn sanity_path() {
         let data = BlockChainTestData::default_with_numbers(11, 12);
@@ -19,6 +19,14 @@ pub struct BlockchainTreeConfig {
     /// be 256. It covers both number of blocks required for reorg, and number of blocks
     /// required for `BLOCKHASH` EVM opcode.
     num_of_additional_canonical_block_hashes: u64,
+    /// The maximum number of blocks in all chains of the tree, after which blocks that fork off a
+    /// historical block are rejected.
+    ///
+    /// Blocks that extend the canonical head are bounded by `max_tip_siblings` instead.
+    max_blocks_in_tree: usize,
+    /// The maximum number of blocks that extend the canonical head, after which further blocks
+    /// with the canonical head as parent are rejected.
+    max_tip_siblings: usize,
 }
 
 impl Default for BlockchainTreeConfig {
@@ -33,6 +41,11 @@ impl Default for BlockchainTreeConfig {
             num_of_additional_canonical_block_hashes: 256,
             // max unconnected blocks.
             max_unconnected_blocks: 200,
+            // Enough forks of the maximum length for any realistic number of competing
+            // proposals.
+            max_blocks_in_tree: 1024,
+            // Competing proposals for the next slot are rare, this leaves plenty of headroom.
+            max_tip_siblings: 16,
         }
     }
 }
@@ -53,9 +66,23 @@ impl BlockchainTreeConfig {
             max_reorg_depth,
             num_of_additional_canonical_block_hashes,
             max_unconnected_blocks,
+            ..Default::default()
         }
     }
 
+    /// Set the maximum number of blocks in all chains of the tree, after which blocks that fork
+    /// off a historical block are rejected.
+    pub fn with_max_blocks_in_tree(mut self, max_blocks_in_tree: usize) -> Self {
+        self.max_blocks_in_tree = max_blocks_in_tree;
+        self
+    }
+
+    /// Set the maximum number of blocks that extend the canonical head.
+    pub fn with_max_tip_siblings(mut self, max_tip_siblings: usize) -> Self {
+        self.max_tip_siblings = max_tip_siblings;
+        self
+    }
+
     /// Return the maximum reorg depth.
     pub fn max_reorg_depth(&self) -> u64 {
         self.max_reorg_depth
@@ -88,4 +115,15 @@ impl BlockchainTreeConfig {
     pub fn max_unconnected_blocks(&self) -> usize {
         self.max_unconnected_blocks
     }
+
+    /// Return the maximum number of blocks in all chains of the tree, after which blocks that fork
+    /// off a historical block are rejected.
+    pub fn max_blocks_in_tree(&self) -> usize {
+        self.max_blocks_in_tree
+    }
+
+    /// Return the maximum number of blocks that extend the canonical head.
+    pub fn max_tip_siblings(&self) -> usize {
+        self.max_tip_siblings
+    }
 }
@@ -11,6 +11,11 @@ use std::time::{Duration, Instant};
 pub struct TreeMetrics {
     /// Total number of sidechains (not including the canonical chain)
     pub sidechains: Gauge,
+    /// Total number of blocks in all sidechains
+    pub blocks: Gauge,
+    /// The number of blocks forking off a historical block that were rejected because the tree
+    /// was full
+    pub rejected_forks: Counter,
     /// The highest block number in the canonical chain
     pub canonical_chain_height: Gauge,
     /// The number of reorgs
@@ -82,6 +82,11 @@ impl TreeState {
         chain.receipts_by_block_hash(block_hash)
     }
 
+    /// Returns the number of blocks in all chains of the tree.
+    pub(crate) fn block_count(&self) -> usize {
+        self.chains.values().map(|chain| chain.blocks().len()).sum()
+    }
+
     /// Insert a chain into the tree.
     ///
     /// Inserts a chain into the tree and builds the block indices.
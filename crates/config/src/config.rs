@@ -1,11 +1,12 @@
 //! Configuration files.
 
+use reth_db::DatabaseEnvConfig;
 use reth_discv4::Discv4Config;
 use reth_network::{NetworkConfigBuilder, PeersConfig, SessionsConfig};
 use reth_primitives::PruneModes;
 use secp256k1::SecretKey;
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::{path::PathBuf, time::Duration};
 
 /// Configuration for the reth node.
 #[derive(Debug, Clone, Default, Deserialize, PartialEq, Serialize)]
@@ -21,6 +22,8 @@ pub struct Config {
     pub peers: PeersConfig,
     /// Configuration for peer sessions.
     pub sessions: SessionsConfig,
+    /// Configuration for the database environment.
+    pub db: DatabaseEnvConfig,
 }
 
 impl Config {
@@ -43,6 +46,7 @@ impl Config {
             .sessions_config(self.sessions.clone())
             .peer_config(peer_config)
             .discovery(discv4)
+            .nat(Some(nat_resolution_method))
     }
 }
 
@@ -72,6 +76,10 @@ pub struct StageConfig {
     pub index_account_history: IndexHistoryConfig,
     /// Index Storage History stage configuration.
     pub index_storage_history: IndexHistoryConfig,
+    /// Index Logs stage configuration.
+    pub index_logs: IndexLogsConfig,
+    /// Index Transaction Addresses stage configuration.
+    pub index_transaction_addresses: IndexTransactionAddressesConfig,
 }
 
 /// Header stage configuration.
@@ -91,6 +99,13 @@ pub struct HeadersConfig {
     pub downloader_max_buffered_responses: usize,
     /// The maximum number of headers to request from a peer at a time.
     pub downloader_request_limit: u64,
+    /// The number of distinct peers that have to agree on the header at the start of each
+    /// request, before out of order responses are checked against it.
+    ///
+    /// A value lower than 2 disables the cross-check.
+    ///
+    /// Default: 3
+    pub downloader_skeleton_peers: usize,
     /// The maximum number of headers to download before committing progress to the database.
     pub commit_threshold: u64,
 }
@@ -103,6 +118,7 @@ impl Default for HeadersConfig {
             downloader_max_concurrent_requests: 100,
             downloader_min_concurrent_requests: 5,
             downloader_max_buffered_responses: 100,
+            downloader_skeleton_peers: 3,
         }
     }
 }
@@ -130,6 +146,13 @@ pub struct BodiesConfig {
     ///
     /// Default: 200
     pub downloader_request_limit: u64,
+    /// The maximum estimated size of the bodies requested from a peer at once in bytes.
+    ///
+    /// The number of blocks per request is lowered below the request limit if the blocks are
+    /// large, since peers truncate responses above their own limit.
+    ///
+    /// Default: 2MB
+    pub downloader_max_response_size_bytes: usize,
     /// The maximum number of block bodies returned at once from the stream
     ///
     /// Default: 1_000
@@ -153,6 +176,7 @@ impl Default for BodiesConfig {
     fn default() -> Self {
         Self {
             downloader_request_limit: 200,
+            downloader_max_response_size_bytes: 2 * 1024 * 1024, // ~2MB
             downloader_stream_batch_size: 1_000,
             downloader_max_buffered_blocks_size_bytes: 2 * 1024 * 1024 * 1024, // ~2GB
             downloader_min_concurrent_requests: 5,
@@ -167,11 +191,16 @@ impl Default for BodiesConfig {
 pub struct SenderRecoveryConfig {
     /// The maximum number of transactions to process before committing progress to the database.
     pub commit_threshold: u64,
+    /// The number of threads used to recover senders, all available cores are used if unset.
+    pub parallelism: Option<usize>,
+    /// The number of transactions recovered by a single job, the transactions are divided evenly
+    /// across the threads if unset.
+    pub batch_size: Option<usize>,
 }
 
 impl Default for SenderRecoveryConfig {
     fn default() -> Self {
-        Self { commit_threshold: 5_000_000 }
+        Self { commit_threshold: 5_000_000, parallelism: None, batch_size: None }
     }
 }
 
@@ -185,6 +214,11 @@ pub struct ExecutionConfig {
     pub max_changes: Option<u64>,
     /// The maximum gas to process before the execution stage commits.
     pub max_cumulative_gas: Option<u64>,
+    /// The maximum time spent on executing blocks before the execution stage commits.
+    #[serde(with = "humantime_serde")]
+    pub max_duration: Option<Duration>,
+    /// Whether to execute the transactions of a block optimistically in parallel.
+    pub parallel: bool,
 }
 
 impl Default for ExecutionConfig {
@@ -194,6 +228,9 @@ impl Default for ExecutionConfig {
             max_changes: Some(5_000_000),
             // 50k full blocks of 30M gas
             max_cumulative_gas: Some(30_000_000 * 50_000),
+            // 10 minutes
+            max_duration: Some(Duration::from_secs(10 * 60)),
+            parallel: false,
         }
     }
 }
@@ -258,6 +295,43 @@ impl Default for IndexHistoryConfig {
     }
 }
 
+/// Index Logs stage configuration.
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Serialize)]
+#[serde(default)]
+pub struct IndexLogsConfig {
+    /// Whether to build the log index that is used to speed up `eth_getLogs`.
+    ///
+    /// Default: false
+    pub enabled: bool,
+    /// The maximum number of blocks to process before committing progress to the database.
+    pub commit_threshold: u64,
+}
+
+impl Default for IndexLogsConfig {
+    fn default() -> Self {
+        Self { enabled: false, commit_threshold: 100_000 }
+    }
+}
+
+/// Index Transaction Addresses stage configuration.
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Serialize)]
+#[serde(default)]
+pub struct IndexTransactionAddressesConfig {
+    /// Whether to build the transaction address index that is used by the `ots_` transaction
+    /// search methods.
+    ///
+    /// Default: false
+    pub enabled: bool,
+    /// The maximum number of blocks to process before committing progress to the database.
+    pub commit_threshold: u64,
+}
+
+impl Default for IndexTransactionAddressesConfig {
+    fn default() -> Self {
+        Self { enabled: false, commit_threshold: 100_000 }
+    }
+}
+
 /// Pruning configuration.
 #[derive(Debug, Clone, Deserialize, PartialEq, Serialize)]
 #[serde(default)]
@@ -278,6 +352,7 @@ impl Default for PruneConfig {
 #[cfg(test)]
 mod tests {
     use super::Config;
+    use reth_db::{DatabaseEnvConfig, DatabaseSyncMode};
 
     const EXTENSION: &str = "toml";
 
@@ -426,4 +501,20 @@ storage_history = { distance = 16384 }
 #";
         let _conf: Config = toml::from_str(alpha_0_0_11).unwrap();
     }
+
+    #[test]
+    fn test_db_config() {
+        let config = r"#
+[db]
+max_size = 1099511627776
+sync_mode = 'safe-no-sync'
+read_ahead = true
+#";
+        let config: Config = toml::from_str(config).unwrap();
+        assert_eq!(config.db.max_size, 1 << 40);
+        assert_eq!(config.db.sync_mode, DatabaseSyncMode::SafeNoSync);
+        assert!(config.db.read_ahead);
+        // unset values fall back to the defaults
+        assert_eq!(config.db.growth_step, DatabaseEnvConfig::default().growth_step);
+    }
 }
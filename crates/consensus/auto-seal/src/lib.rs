@@ -15,6 +15,7 @@
 #![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
 
 use reth_beacon_consensus::BeaconEngineMessage;
+use reth_clique_consensus::CliqueSealer;
 use reth_interfaces::{
     consensus::{Consensus, ConsensusError},
     executor::{BlockExecutionError, BlockValidationError},
@@ -38,7 +39,7 @@ use reth_transaction_pool::TransactionPool;
 use std::{
     collections::HashMap,
     sync::Arc,
-    time::{SystemTime, UNIX_EPOCH},
+    time::{Duration, SystemTime, UNIX_EPOCH},
 };
 use tokio::sync::{mpsc::UnboundedSender, RwLock, RwLockReadGuard, RwLockWriteGuard};
 use tracing::trace;
@@ -99,7 +100,8 @@ pub struct AutoSealBuilder<Client, Pool, Engine: EngineTypes> {
     consensus: AutoSealConsensus,
     pool: Pool,
     mode: MiningMode,
-    storage: Storage,
+    latest_header: SealedHeader,
+    sealer: Option<CliqueSealer>,
     to_engine: UnboundedSender<BeaconEngineMessage<Engine>>,
     canon_state_notification: CanonStateNotificationSender,
 }
@@ -128,7 +130,8 @@ where
             .unwrap_or_else(|| chain_spec.sealed_genesis_header());
 
         Self {
-            storage: Storage::new(latest_header),
+            latest_header,
+            sealer: None,
             client,
             consensus: AutoSealConsensus::new(chain_spec),
             pool,
@@ -144,11 +147,28 @@ where
         self
     }
 
+    /// Seals the blocks with the given [CliqueSealer].
+    ///
+    /// The sealer must be created for the latest block of the client.
+    pub fn with_clique_sealer(mut self, sealer: CliqueSealer) -> Self {
+        self.sealer = Some(sealer);
+        self
+    }
+
     /// Consumes the type and returns all components
     #[track_caller]
     pub fn build(self) -> (AutoSealConsensus, AutoSealClient, MiningTask<Client, Pool, Engine>) {
-        let Self { client, consensus, pool, mode, storage, to_engine, canon_state_notification } =
-            self;
+        let Self {
+            client,
+            consensus,
+            pool,
+            mode,
+            latest_header,
+            sealer,
+            to_engine,
+            canon_state_notification,
+        } = self;
+        let storage = Storage::new(latest_header, sealer);
         let auto_client = AutoSealClient::new(storage.clone());
         let task = MiningTask::new(
             Arc::clone(&consensus.chain_spec),
@@ -172,12 +192,13 @@ pub(crate) struct Storage {
 // == impl Storage ===
 
 impl Storage {
-    fn new(header: SealedHeader) -> Self {
+    fn new(header: SealedHeader, sealer: Option<CliqueSealer>) -> Self {
         let (header, best_hash) = header.split();
         let mut storage = StorageInner {
             best_hash,
             total_difficulty: header.difficulty,
             best_block: header.number,
+            sealer,
             ..Default::default()
         };
         storage.headers.insert(0, header);
@@ -211,6 +232,8 @@ pub(crate) struct StorageInner {
     pub(crate) best_hash: B256,
     /// The total difficulty of the chain until this block
     pub(crate) total_difficulty: U256,
+    /// Seals the blocks if the chain uses Clique
+    pub(crate) sealer: Option<CliqueSealer>,
 }
 
 // === impl StorageInner ===
@@ -367,6 +390,11 @@ impl StorageInner {
         Ok(header)
     }
 
+    /// Returns the time to wait before sealing the next block, see [CliqueSealer::delay].
+    pub(crate) fn sealing_delay(&self) -> Duration {
+        self.sealer.as_ref().map_or(Duration::ZERO, |sealer| sealer.delay(self.best_block + 1))
+    }
+
     /// Builds and executes a new block with the given transactions, on the provided [EVMProcessor].
     ///
     /// This returns the header of the executed block, as well as the poststate from execution.
@@ -376,7 +404,12 @@ impl StorageInner {
         client: &impl StateProviderFactory,
         chain_spec: Arc<ChainSpec>,
     ) -> Result<(SealedHeader, BundleStateWithReceipts), BlockExecutionError> {
-        let header = self.build_header_template(&transactions, chain_spec.clone());
+        let mut header = self.build_header_template(&transactions, chain_spec.clone());
+        if let Some(sealer) = &self.sealer {
+            sealer
+                .prepare_header(&mut header)
+                .map_err(|err| BlockExecutionError::Sealing(err.into()))?;
+        }
 
         let block = Block { header, body: transactions, ommers: vec![], withdrawals: None }
             .with_recovered_senders()
@@ -399,7 +432,7 @@ impl StorageInner {
         trace!(target: "consensus::auto", ?bundle_state, ?header, ?body, "executed block, calculating state root and completing header");
 
         // fill in the rest of the fields
-        let header = self.complete_header(
+        let mut header = self.complete_header(
             header,
             &bundle_state,
             client,
@@ -410,6 +443,12 @@ impl StorageInner {
 
         trace!(target: "consensus::auto", root=?header.state_root, ?body, "calculated root");
 
+        if let Some(sealer) = &mut self.sealer {
+            sealer
+                .seal_header(&mut header)
+                .map_err(|err| BlockExecutionError::Sealing(err.into()))?;
+        }
+
         // finally insert into storage
         self.insert_new_block(header.clone(), body);
 
@@ -14,7 +14,7 @@ use reth_interfaces::{
         error::{BlockchainTreeError, CanonicalError, InsertBlockError, InsertBlockErrorKind},
         BlockStatus, BlockchainTreeEngine, CanonicalOutcome, InsertPayloadOk,
     },
-    consensus::ForkchoiceState,
+    consensus::{Consensus, ForkchoiceState},
     executor::{BlockExecutionError, BlockValidationError},
     p2p::{bodies::client::BodiesClient, headers::client::HeadersClient},
     sync::{NetworkSyncUpdater, SyncState},
@@ -581,6 +581,14 @@ where
         Ok(None)
     }
 
+    /// Sets the [`Consensus`] that validates the blocks the engine downloads from the network.
+    ///
+    /// By default, this is the [`BeaconConsensus`](crate::BeaconConsensus) of the chain.
+    pub fn with_consensus(mut self, consensus: Arc<dyn Consensus>) -> Self {
+        self.sync.set_consensus(consensus);
+        self
+    }
+
     /// Returns a new [`BeaconConsensusEngineHandle`] that can be cloned and shared.
     ///
     /// The [`BeaconConsensusEngineHandle`] can be used to interact with this
@@ -1881,15 +1889,19 @@ mod tests {
         BeaconForkChoiceUpdateError,
     };
     use assert_matches::assert_matches;
-    use reth_interfaces::test_utils::generators::{self, Rng};
+    use reth_interfaces::test_utils::{
+        generators::{self, Rng},
+        TestConsensus, TestFullBlockClient,
+    };
     use reth_primitives::{
-        stage::StageCheckpoint, ChainSpec, ChainSpecBuilder, B256, MAINNET, U256,
+        stage::StageCheckpoint, BlockBody, ChainSpec, ChainSpecBuilder, SealedHeader, B256,
+        MAINNET, U256,
     };
     use reth_provider::{BlockWriter, ProviderFactory};
     use reth_rpc_types::engine::{ForkchoiceState, ForkchoiceUpdated, PayloadStatus};
     use reth_rpc_types_compat::engine::payload::try_block_to_payload_v1;
     use reth_stages::{ExecOutput, PipelineError, StageError};
-    use std::{collections::VecDeque, sync::Arc, time::Duration};
+    use std::{collections::VecDeque, future::poll_fn, sync::Arc, time::Duration};
     use tokio::sync::oneshot::error::TryRecvError;
 
     // Pipeline error is propagated.
@@ -2056,6 +2068,45 @@ mod tests {
         assert_matches!(rx.await, Ok(Ok(())));
     }
 
+    // Test that the blocks downloaded by the consensus engine are validated with the consensus
+    // set through `with_consensus`.
+    #[tokio::test]
+    async fn downloads_blocks_with_configured_consensus() {
+        let chain_spec = Arc::new(
+            ChainSpecBuilder::default()
+                .chain(MAINNET.chain)
+                .genesis(MAINNET.genesis.clone())
+                .paris_activated()
+                .build(),
+        );
+
+        // the headers have no base fee, so they are rejected by the beacon consensus
+        let client = TestFullBlockClient::default();
+        let mut header = SealedHeader::default();
+        let body = BlockBody::default();
+        for _ in 0..3 {
+            header.parent_hash = header.hash_slow();
+            header.number += 1;
+            header = header.header.seal_slow();
+            client.insert(header.clone(), body.clone());
+        }
+
+        let (consensus_engine, _env) =
+            TestConsensusEngineBuilder::new(chain_spec).with_client(client.clone()).build();
+        let mut consensus_engine =
+            consensus_engine.with_consensus(Arc::new(TestConsensus::default()));
+
+        let tip = client.highest_block().expect("there should be blocks here");
+        consensus_engine.sync.download_block_range(tip.hash, tip.number);
+
+        for number in 1..=tip.number {
+            let event = poll_fn(|cx| consensus_engine.sync.poll(cx)).await;
+            assert_matches!(event, EngineSyncEvent::FetchedFullBlock(block) => {
+                assert_eq!(block.number, number);
+            });
+        }
+    }
+
     fn insert_blocks<'a, DB: Database>(
         db: DB,
         chain: Arc<ChainSpec>,
@@ -3,10 +3,13 @@
 use crate::{engine::metrics::EngineSyncMetrics, BeaconConsensus};
 use futures::FutureExt;
 use reth_db::database::Database;
-use reth_interfaces::p2p::{
-    bodies::client::BodiesClient,
-    full_block::{FetchFullBlockFuture, FetchFullBlockRangeFuture, FullBlockClient},
-    headers::client::HeadersClient,
+use reth_interfaces::{
+    consensus::Consensus,
+    p2p::{
+        bodies::client::BodiesClient,
+        full_block::{FetchFullBlockFuture, FetchFullBlockRangeFuture, FullBlockClient},
+        headers::client::HeadersClient,
+    },
 };
 use reth_primitives::{BlockNumber, ChainSpec, SealedBlock, B256};
 use reth_stages::{ControlFlow, Pipeline, PipelineError, PipelineWithResult};
@@ -94,6 +97,11 @@ where
         // TODO: full block range metrics
     }
 
+    /// Sets the [Consensus] that validates the downloaded full blocks.
+    pub(crate) fn set_consensus(&mut self, consensus: Arc<dyn Consensus>) {
+        self.full_block_client.set_consensus(consensus);
+    }
+
     /// Sets the max block value for testing
     #[cfg(test)]
     pub(crate) fn set_max_block(&mut self, block: BlockNumber) {
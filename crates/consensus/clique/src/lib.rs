@@ -0,0 +1,531 @@
+//! Clique proof-of-authority consensus, see [EIP-225](https://eips.ethereum.org/EIPS/eip-225).
+//!
+//! Blocks are sealed by a set of authorized signers, which is changed by the votes of the signers.
+//! The signers are tracked in [Snapshot]s, which are computed from the ancestors of a block.
+//!
+//! Blocks after the merge are validated by the [BeaconConsensus].
+
+#![doc(
+    html_logo_url = "https://raw.githubusercontent.com/paradigmxyz/reth/main/assets/reth-docs.png",
+    html_favicon_url = "https://avatars0.githubusercontent.com/u/97369466?s=256",
+    issue_tracker_base_url = "https://github.com/paradigmxyz/reth/issues/"
+)]
+#![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
+
+use parking_lot::Mutex;
+use reth_beacon_consensus_core::BeaconConsensus;
+use reth_consensus_common::validation;
+use reth_interfaces::consensus::{CliqueError, Consensus, ConsensusError};
+use reth_primitives::{
+    constants::ALLOWED_FUTURE_BLOCK_TIME_SECONDS, revm::env::recover_header_signer, Address, Bytes,
+    ChainSpec, GotExpected, Hardfork, Header, SealedBlock, SealedHeader, B256,
+    EMPTY_OMMER_ROOT_HASH, U256,
+};
+use reth_provider::HeaderProvider;
+use schnellru::{ByLength, LruMap};
+use std::{fmt, sync::Arc, time::SystemTime};
+
+mod sealer;
+mod snapshot;
+
+pub use sealer::{CliqueSealer, CliqueSigner};
+pub use snapshot::Snapshot;
+
+/// The number of bytes of the extra data reserved for the vanity of the signer.
+pub const EXTRA_VANITY: usize = 32;
+
+/// The number of bytes of the extra data reserved for the signature of the signer.
+pub const EXTRA_SEAL: usize = 65;
+
+/// The nonce of a block that votes to authorize the beneficiary as signer.
+pub const NONCE_AUTH: u64 = u64::MAX;
+
+/// The nonce of a block that votes to deauthorize the beneficiary as signer.
+pub const NONCE_DROP: u64 = 0;
+
+/// The difficulty of a block sealed by the in-turn signer.
+pub const DIFF_IN_TURN: U256 = U256::from_limbs([2, 0, 0, 0]);
+
+/// The difficulty of a block sealed by an out-of-turn signer.
+pub const DIFF_NO_TURN: U256 = U256::from_limbs([1, 0, 0, 0]);
+
+/// The default number of blocks after which the votes are reset.
+pub const DEFAULT_EPOCH: u64 = 30_000;
+
+/// The number of bytes of an address in the list of signers.
+const ADDRESS_LEN: usize = std::mem::size_of::<Address>();
+
+/// The number of recent snapshots to keep in memory.
+const SNAPSHOT_CACHE_SIZE: u32 = 128;
+
+/// Clique proof-of-authority consensus.
+///
+/// The signers that are authorized to seal a block are computed from its ancestors, so
+/// [Consensus::validate_block] requires the ancestors of the block to be available from the
+/// provider.
+pub struct CliqueConsensus<Provider> {
+    chain_spec: Arc<ChainSpec>,
+    /// The consensus for the blocks after the merge.
+    beacon: BeaconConsensus,
+    /// The minimum number of seconds between blocks.
+    period: u64,
+    /// The number of blocks after which the votes are reset.
+    epoch: u64,
+    provider: Provider,
+    /// The snapshots of the recently validated blocks, by block hash.
+    snapshots: Mutex<LruMap<B256, Snapshot, ByLength>>,
+}
+
+impl<Provider> CliqueConsensus<Provider> {
+    /// Creates a new instance of [CliqueConsensus] with the Clique configuration of the genesis.
+    pub fn new(chain_spec: Arc<ChainSpec>, provider: Provider) -> Self {
+        let config = chain_spec.genesis.config.clique.as_ref();
+        let period = config.and_then(|config| config.period).unwrap_or_default();
+        let epoch = config.and_then(|config| config.epoch).filter(|epoch| *epoch > 0);
+        Self {
+            beacon: BeaconConsensus::new(Arc::clone(&chain_spec)),
+            chain_spec,
+            period,
+            epoch: epoch.unwrap_or(DEFAULT_EPOCH),
+            provider,
+            snapshots: Mutex::new(LruMap::new(ByLength::new(SNAPSHOT_CACHE_SIZE))),
+        }
+    }
+
+    /// Returns the minimum number of seconds between blocks.
+    pub fn period(&self) -> u64 {
+        self.period
+    }
+
+    /// Returns the number of blocks after which the votes are reset.
+    pub fn epoch(&self) -> u64 {
+        self.epoch
+    }
+}
+
+impl<Provider: HeaderProvider> CliqueConsensus<Provider> {
+    /// Returns the snapshot after the block with the given hash.
+    ///
+    /// This applies the ancestors of the block since the last checkpoint or cached snapshot.
+    pub fn snapshot(&self, mut hash: B256) -> Result<Snapshot, CliqueError> {
+        let mut headers = Vec::new();
+        let mut snapshot = loop {
+            if let Some(snapshot) = self.snapshots.lock().get(&hash) {
+                break snapshot.clone()
+            }
+
+            let header =
+                self.provider.header(&hash)?.ok_or(CliqueError::UnknownAncestor { hash })?;
+
+            // checkpoints contain the list of signers
+            if header.number % self.epoch == 0 {
+                let snapshot = Snapshot::new(header.number, hash, checkpoint_signers(&header)?);
+                break self.with_checkpoint_recents(snapshot, &header)?
+            }
+
+            let parent_hash = header.parent_hash;
+            headers.push(header.seal(hash));
+            hash = parent_hash;
+        };
+
+        for header in headers.into_iter().rev() {
+            let signer = recover_signer(&header)?;
+            snapshot.apply(&header, signer, self.epoch)?;
+        }
+
+        self.snapshots.lock().insert(snapshot.hash(), snapshot.clone());
+        Ok(snapshot)
+    }
+
+    /// Recovers the signers of the blocks up to the checkpoint of the snapshot, which are not
+    /// allowed to seal the blocks right after the checkpoint.
+    fn with_checkpoint_recents(
+        &self,
+        snapshot: Snapshot,
+        checkpoint: &Header,
+    ) -> Result<Snapshot, CliqueError> {
+        // genesis has no signer
+        if checkpoint.number == 0 {
+            return Ok(snapshot)
+        }
+
+        let mut recents = vec![(checkpoint.number, recover_signer(checkpoint)?)];
+        let mut parent_hash = checkpoint.parent_hash;
+        let oldest = checkpoint.number.saturating_sub(snapshot.signer_limit() - 1).max(1);
+        for number in (oldest..checkpoint.number).rev() {
+            let header = self
+                .provider
+                .header(&parent_hash)?
+                .ok_or(CliqueError::UnknownAncestor { hash: parent_hash })?;
+            recents.push((number, recover_signer(&header)?));
+            parent_hash = header.parent_hash;
+        }
+        Ok(snapshot.with_recents(recents))
+    }
+
+    /// Returns a new [CliqueSealer] for the children of the given block.
+    pub fn sealer(
+        &self,
+        signer: CliqueSigner,
+        parent: &SealedHeader,
+    ) -> Result<CliqueSealer, CliqueError> {
+        let snapshot = self.snapshot(parent.hash())?;
+        Ok(CliqueSealer::new(signer, snapshot, parent.timestamp, self.period, self.epoch))
+    }
+
+    /// Validates that the signer of the block is authorized, see [Snapshot::apply].
+    fn validate_signer(&self, header: &SealedHeader) -> Result<(), CliqueError> {
+        let mut snapshot = self.snapshot(header.parent_hash)?;
+
+        // checkpoints must list the signers of the parent
+        if header.number % self.epoch == 0 &&
+            !checkpoint_signers(header)?.into_iter().eq(snapshot.signers())
+        {
+            return Err(CliqueError::MismatchedCheckpointSigners)
+        }
+
+        let signer = recover_signer(header)?;
+        let expected_difficulty =
+            if snapshot.is_inturn(header.number, signer) { DIFF_IN_TURN } else { DIFF_NO_TURN };
+        snapshot.apply(header, signer, self.epoch)?;
+
+        if header.difficulty != expected_difficulty {
+            return Err(CliqueError::WrongDifficulty(GotExpected {
+                got: header.difficulty,
+                expected: expected_difficulty,
+            }))
+        }
+
+        self.snapshots.lock().insert(header.hash(), snapshot);
+        Ok(())
+    }
+}
+
+impl<Provider: HeaderProvider> Consensus for CliqueConsensus<Provider> {
+    fn validate_header(&self, header: &SealedHeader) -> Result<(), ConsensusError> {
+        if header.is_zero_difficulty() {
+            return self.beacon.validate_header(header)
+        }
+
+        validation::validate_header_standalone(header, &self.chain_spec)?;
+
+        // Check if timestamp is in future. Clock can drift but this can be consensus issue.
+        let present_timestamp =
+            SystemTime::now().duration_since(SystemTime::UNIX_EPOCH).unwrap().as_secs();
+        if header.timestamp > present_timestamp + ALLOWED_FUTURE_BLOCK_TIME_SECONDS {
+            return Err(ConsensusError::TimestampIsInFuture {
+                timestamp: header.timestamp,
+                present_timestamp,
+            })
+        }
+
+        validate_clique_header(header, self.epoch)?;
+        Ok(())
+    }
+
+    fn validate_header_against_parent(
+        &self,
+        header: &SealedHeader,
+        parent: &SealedHeader,
+    ) -> Result<(), ConsensusError> {
+        if header.is_zero_difficulty() {
+            return self.beacon.validate_header_against_parent(header, parent)
+        }
+
+        validation::validate_header_regarding_parent(parent, header, &self.chain_spec)?;
+
+        if parent.timestamp + self.period > header.timestamp {
+            return Err(CliqueError::InvalidPeriod {
+                parent_timestamp: parent.timestamp,
+                timestamp: header.timestamp,
+                period: self.period,
+            }
+            .into())
+        }
+
+        Ok(())
+    }
+
+    fn validate_header_with_total_difficulty(
+        &self,
+        header: &Header,
+        total_difficulty: U256,
+    ) -> Result<(), ConsensusError> {
+        // the extra data of clique blocks exceeds the limit of the beacon consensus
+        if self.chain_spec.fork(Hardfork::Paris).active_at_ttd(total_difficulty, header.difficulty)
+        {
+            return self.beacon.validate_header_with_total_difficulty(header, total_difficulty)
+        }
+        Ok(())
+    }
+
+    fn validate_block(&self, block: &SealedBlock) -> Result<(), ConsensusError> {
+        if block.header.is_zero_difficulty() {
+            return self.beacon.validate_block(block)
+        }
+
+        validation::validate_block_standalone(block, &self.chain_spec)?;
+        self.validate_signer(&block.header)?;
+        Ok(())
+    }
+}
+
+impl<Provider> fmt::Debug for CliqueConsensus<Provider> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CliqueConsensus")
+            .field("period", &self.period)
+            .field("epoch", &self.epoch)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Validates the Clique rules of the header that don't depend on its ancestors.
+pub fn validate_clique_header(header: &Header, epoch: u64) -> Result<(), CliqueError> {
+    let checkpoint = header.number % epoch == 0;
+
+    if checkpoint && header.beneficiary != Address::ZERO {
+        return Err(CliqueError::InvalidCheckpointBeneficiary)
+    }
+    if header.nonce != NONCE_AUTH && header.nonce != NONCE_DROP {
+        return Err(CliqueError::InvalidVote { nonce: header.nonce })
+    }
+    if checkpoint && header.nonce != NONCE_DROP {
+        return Err(CliqueError::InvalidCheckpointVote)
+    }
+
+    // checkpoints contain the list of signers between the vanity and the seal
+    let signers_len = checkpoint_signers_len(header)?;
+    if !checkpoint && signers_len != 0 {
+        return Err(CliqueError::ExtraSigners)
+    }
+    if checkpoint && signers_len % ADDRESS_LEN != 0 {
+        return Err(CliqueError::InvalidCheckpointSigners)
+    }
+
+    if header.mix_hash != B256::ZERO {
+        return Err(CliqueError::InvalidMixHash)
+    }
+    if header.ommers_hash != EMPTY_OMMER_ROOT_HASH {
+        return Err(CliqueError::InvalidOmmers)
+    }
+    if header.number > 0 && header.difficulty != DIFF_IN_TURN && header.difficulty != DIFF_NO_TURN {
+        return Err(CliqueError::InvalidDifficulty { difficulty: header.difficulty })
+    }
+
+    Ok(())
+}
+
+/// Returns the hash of the header without the seal, which is signed by the signer.
+pub fn seal_hash(header: &Header) -> Result<B256, CliqueError> {
+    let seal_start =
+        header.extra_data.len().checked_sub(EXTRA_SEAL).ok_or(CliqueError::MissingSignature)?;
+    let mut header = header.clone();
+    header.extra_data = Bytes::from(header.extra_data[..seal_start].to_vec());
+    Ok(header.hash_slow())
+}
+
+/// Recovers the signer of the header from the seal.
+pub fn recover_signer(header: &Header) -> Result<Address, CliqueError> {
+    if header.extra_data.len() < EXTRA_SEAL {
+        return Err(CliqueError::MissingSignature)
+    }
+    recover_header_signer(header).map_err(|_| CliqueError::InvalidSignature)
+}
+
+/// Returns the list of signers of a checkpoint header.
+pub fn checkpoint_signers(header: &Header) -> Result<Vec<Address>, CliqueError> {
+    let signers_len = checkpoint_signers_len(header)?;
+    if signers_len % ADDRESS_LEN != 0 {
+        return Err(CliqueError::InvalidCheckpointSigners)
+    }
+    let signers = &header.extra_data[EXTRA_VANITY..EXTRA_VANITY + signers_len];
+    Ok(signers.chunks_exact(ADDRESS_LEN).map(Address::from_slice).collect())
+}
+
+/// Returns the number of bytes between the vanity and the seal of the extra data.
+fn checkpoint_signers_len(header: &Header) -> Result<usize, CliqueError> {
+    let len = header.extra_data.len();
+    if len < EXTRA_VANITY {
+        return Err(CliqueError::MissingVanity)
+    }
+    if len < EXTRA_VANITY + EXTRA_SEAL {
+        return Err(CliqueError::MissingSignature)
+    }
+    Ok(len - EXTRA_VANITY - EXTRA_SEAL)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::{CliqueConfig, MAINNET};
+    use reth_provider::test_utils::MockEthProvider;
+    use secp256k1::{rand::thread_rng, SecretKey};
+
+    /// Returns a signed block of the signer with its parent.
+    fn signed_block(
+        signer: &CliqueSigner,
+        parent: &SealedHeader,
+        difficulty: U256,
+    ) -> SealedHeader {
+        let mut header = Header {
+            parent_hash: parent.hash(),
+            number: parent.number + 1,
+            timestamp: parent.timestamp + 1,
+            ommers_hash: EMPTY_OMMER_ROOT_HASH,
+            difficulty,
+            extra_data: vec![0; EXTRA_VANITY + EXTRA_SEAL].into(),
+            ..Default::default()
+        };
+        signer.sign(&mut header).unwrap();
+        header.seal_slow()
+    }
+
+    #[test]
+    fn validate_signers() {
+        let signer = CliqueSigner::new(SecretKey::new(&mut thread_rng()));
+
+        let mut extra_data = vec![0; EXTRA_VANITY];
+        extra_data.extend_from_slice(signer.address().as_slice());
+        extra_data.extend_from_slice(&[0; EXTRA_SEAL]);
+        let genesis = Header {
+            extra_data: extra_data.into(),
+            difficulty: DIFF_NO_TURN,
+            ..Default::default()
+        }
+        .seal_slow();
+
+        let provider = MockEthProvider::default();
+        provider.add_header(genesis.hash(), genesis.header.clone());
+        let consensus = CliqueConsensus::new(MAINNET.clone(), provider);
+        assert_eq!(
+            consensus.snapshot(genesis.hash()).unwrap().signers().collect::<Vec<_>>(),
+            [signer.address()]
+        );
+
+        // the only signer is always in turn
+        let block = signed_block(&signer, &genesis, DIFF_IN_TURN);
+        assert_eq!(validate_clique_header(&block, DEFAULT_EPOCH), Ok(()));
+        assert_eq!(consensus.validate_signer(&block), Ok(()));
+        assert_eq!(
+            consensus.validate_signer(&signed_block(&signer, &genesis, DIFF_NO_TURN)),
+            Err(CliqueError::WrongDifficulty(GotExpected {
+                got: DIFF_NO_TURN,
+                expected: DIFF_IN_TURN
+            }))
+        );
+
+        let unauthorized = CliqueSigner::new(SecretKey::new(&mut thread_rng()));
+        assert_eq!(
+            consensus.validate_signer(&signed_block(&unauthorized, &genesis, DIFF_IN_TURN)),
+            Err(CliqueError::UnauthorizedSigner { signer: unauthorized.address() })
+        );
+    }
+
+    /// Seals the child of the parent with the sealer of the signer, and adds it to the provider.
+    fn sealed_block(
+        consensus: &CliqueConsensus<MockEthProvider>,
+        signer: &CliqueSigner,
+        parent: &SealedHeader,
+    ) -> SealedHeader {
+        let mut sealer = consensus.sealer(signer.clone(), parent).unwrap();
+        let mut header = Header {
+            parent_hash: parent.hash(),
+            number: parent.number + 1,
+            timestamp: parent.timestamp + 1,
+            ..Default::default()
+        };
+        sealer.prepare_header(&mut header).unwrap();
+        sealer.seal_header(&mut header).unwrap();
+        let header = header.seal_slow();
+        consensus.provider.add_header(header.hash(), header.header.clone());
+        header
+    }
+
+    #[test]
+    fn validate_signers_after_checkpoint() {
+        let mut signers = (0..3)
+            .map(|_| CliqueSigner::new(SecretKey::new(&mut thread_rng())))
+            .collect::<Vec<_>>();
+        signers.sort_by_key(CliqueSigner::address);
+
+        let mut extra_data = vec![0; EXTRA_VANITY];
+        for signer in &signers {
+            extra_data.extend_from_slice(signer.address().as_slice());
+        }
+        extra_data.extend_from_slice(&[0; EXTRA_SEAL]);
+        let genesis = Header {
+            extra_data: extra_data.into(),
+            difficulty: DIFF_NO_TURN,
+            ..Default::default()
+        }
+        .seal_slow();
+
+        let mut chain_spec = (**MAINNET).clone();
+        chain_spec.genesis.config.clique = Some(CliqueConfig { period: Some(0), epoch: Some(4) });
+        let chain_spec = Arc::new(chain_spec);
+        let provider = MockEthProvider::default();
+        provider.add_header(genesis.hash(), genesis.header.clone());
+        let consensus = CliqueConsensus::new(Arc::clone(&chain_spec), provider.clone());
+
+        // the in-turn signers seal the blocks up to the checkpoint
+        let mut parent = genesis;
+        for number in 1..=4 {
+            let block = sealed_block(&consensus, &signers[number % signers.len()], &parent);
+            assert_eq!(consensus.validate_signer(&block), Ok(()));
+            parent = block;
+        }
+
+        // the checkpoint must list the signers
+        let mut checkpoint = parent.header.clone();
+        checkpoint.extra_data = vec![0; EXTRA_VANITY + EXTRA_SEAL].into();
+        signers[1].sign(&mut checkpoint).unwrap();
+        assert_eq!(
+            consensus.validate_signer(&checkpoint.seal_slow()),
+            Err(CliqueError::MismatchedCheckpointSigners)
+        );
+
+        // without cached snapshots, the signers of the blocks before the checkpoint are recovered
+        let consensus = CliqueConsensus::new(chain_spec, provider);
+        let snapshot = consensus.snapshot(parent.hash()).unwrap();
+        assert_eq!(snapshot.number(), 4);
+        assert!(snapshot.is_recent(5, signers[1].address()));
+        assert!(!snapshot.is_recent(5, signers[0].address()));
+
+        let recent = &signers[1];
+        assert_eq!(
+            consensus.validate_signer(&signed_block(recent, &parent, DIFF_NO_TURN)),
+            Err(CliqueError::RecentlySigned { signer: recent.address() })
+        );
+        assert_eq!(
+            consensus.validate_signer(&signed_block(&signers[0], &parent, DIFF_NO_TURN)),
+            Ok(())
+        );
+        assert_eq!(
+            consensus.validate_signer(&signed_block(&signers[2], &parent, DIFF_NO_TURN)),
+            Err(CliqueError::WrongDifficulty(GotExpected {
+                got: DIFF_NO_TURN,
+                expected: DIFF_IN_TURN
+            }))
+        );
+    }
+
+    #[test]
+    fn validate_extra_data() {
+        let header =
+            Header { number: 1, extra_data: vec![0; EXTRA_VANITY].into(), ..Default::default() };
+        assert_eq!(
+            validate_clique_header(&header, DEFAULT_EPOCH),
+            Err(CliqueError::MissingSignature)
+        );
+
+        let header = Header {
+            number: 1,
+            ommers_hash: EMPTY_OMMER_ROOT_HASH,
+            difficulty: DIFF_IN_TURN,
+            extra_data: vec![0; EXTRA_VANITY + ADDRESS_LEN + EXTRA_SEAL].into(),
+            ..Default::default()
+        };
+        assert_eq!(validate_clique_header(&header, DEFAULT_EPOCH), Err(CliqueError::ExtraSigners));
+        assert_eq!(validate_clique_header(&header, 1), Ok(()));
+    }
+}
@@ -0,0 +1,198 @@
+use crate::{
+    seal_hash, Snapshot, DIFF_IN_TURN, DIFF_NO_TURN, EXTRA_SEAL, EXTRA_VANITY, NONCE_DROP,
+};
+use rand::Rng;
+use reth_interfaces::consensus::CliqueError;
+use reth_primitives::{public_key_to_address, Address, Header, B256, EMPTY_OMMER_ROOT_HASH};
+use secp256k1::{Message, SecretKey, SECP256K1};
+use std::{fmt, time::Duration};
+
+/// The maximum delay per signer of the out-of-turn signers before they seal a block, so that the
+/// in-turn signer and the out-of-turn signers don't seal the same block at once.
+pub const WIGGLE_TIME: Duration = Duration::from_millis(500);
+
+/// The key of a Clique signer.
+#[derive(Clone)]
+pub struct CliqueSigner {
+    secret_key: SecretKey,
+    address: Address,
+}
+
+impl CliqueSigner {
+    /// Creates a new signer with the given secret key.
+    pub fn new(secret_key: SecretKey) -> Self {
+        let address = public_key_to_address(secret_key.public_key(SECP256K1));
+        Self { secret_key, address }
+    }
+
+    /// Returns the address of the signer.
+    pub fn address(&self) -> Address {
+        self.address
+    }
+
+    /// Signs the header and replaces the seal at the end of the extra data with the signature.
+    pub fn sign(&self, header: &mut Header) -> Result<(), CliqueError> {
+        let hash = seal_hash(header)?;
+        let message = Message::from_slice(hash.as_slice()).expect("hash is 32 bytes");
+        let (recovery_id, signature) =
+            SECP256K1.sign_ecdsa_recoverable(&message, &self.secret_key).serialize_compact();
+
+        let mut extra_data = header.extra_data[..header.extra_data.len() - EXTRA_SEAL].to_vec();
+        extra_data.extend_from_slice(&signature);
+        extra_data.push(recovery_id.to_i32() as u8);
+        header.extra_data = extra_data.into();
+        Ok(())
+    }
+}
+
+impl fmt::Debug for CliqueSigner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CliqueSigner").field("address", &self.address).finish_non_exhaustive()
+    }
+}
+
+/// Seals the blocks of a [CliqueSigner] on top of a chain.
+///
+/// Blocks are sealed in two steps, see [CliqueSealer::prepare_header] and
+/// [CliqueSealer::seal_header]. The sealer never votes.
+#[derive(Debug)]
+pub struct CliqueSealer {
+    signer: CliqueSigner,
+    /// The snapshot after the last sealed block.
+    snapshot: Snapshot,
+    /// The timestamp of the last sealed block.
+    timestamp: u64,
+    /// The minimum number of seconds between blocks.
+    period: u64,
+    /// The number of blocks after which the votes are reset.
+    epoch: u64,
+}
+
+impl CliqueSealer {
+    /// Creates a new sealer for the children of the block with the given snapshot and timestamp.
+    pub fn new(
+        signer: CliqueSigner,
+        snapshot: Snapshot,
+        timestamp: u64,
+        period: u64,
+        epoch: u64,
+    ) -> Self {
+        Self { signer, snapshot, timestamp, period, epoch }
+    }
+
+    /// Returns the address of the signer.
+    pub fn signer(&self) -> Address {
+        self.signer.address()
+    }
+
+    /// Returns the time to wait before sealing the block with the given number.
+    ///
+    /// The in-turn signer seals right away, out-of-turn signers wait for a random delay of up to
+    /// [WIGGLE_TIME] times the [Snapshot::signer_limit].
+    pub fn delay(&self, number: u64) -> Duration {
+        if self.snapshot.is_inturn(number, self.signer.address()) {
+            return Duration::ZERO
+        }
+        let wiggle = WIGGLE_TIME * self.snapshot.signer_limit() as u32;
+        rand::thread_rng().gen_range(Duration::ZERO..=wiggle)
+    }
+
+    /// Fills in the Clique fields of the header of the next block before its execution.
+    ///
+    /// The header is signed, so that the signer is recovered as coinbase of the block while it's
+    /// executed. The signature is invalidated by the fields that are filled in after execution, so
+    /// the header must be sealed with [CliqueSealer::seal_header] before it's inserted.
+    pub fn prepare_header(&self, header: &mut Header) -> Result<(), CliqueError> {
+        let signer = self.signer.address();
+        if !self.snapshot.is_signer(signer) {
+            return Err(CliqueError::UnauthorizedSigner { signer })
+        }
+        if self.snapshot.is_recent(header.number, signer) {
+            return Err(CliqueError::RecentlySigned { signer })
+        }
+
+        header.beneficiary = Address::ZERO;
+        header.nonce = NONCE_DROP;
+        header.mix_hash = B256::ZERO;
+        header.ommers_hash = EMPTY_OMMER_ROOT_HASH;
+        header.timestamp = header.timestamp.max(self.timestamp + self.period);
+        header.difficulty = if self.snapshot.is_inturn(header.number, signer) {
+            DIFF_IN_TURN
+        } else {
+            DIFF_NO_TURN
+        };
+
+        // keep the vanity, and list the signers in checkpoints
+        let mut extra_data = header.extra_data.to_vec();
+        extra_data.resize(EXTRA_VANITY, 0);
+        if header.number % self.epoch == 0 {
+            for signer in self.snapshot.signers() {
+                extra_data.extend_from_slice(signer.as_slice());
+            }
+        }
+        extra_data.resize(extra_data.len() + EXTRA_SEAL, 0);
+        header.extra_data = extra_data.into();
+
+        self.signer.sign(header)
+    }
+
+    /// Signs the completed header of the next block and applies it to the snapshot.
+    pub fn seal_header(&mut self, header: &mut Header) -> Result<(), CliqueError> {
+        self.signer.sign(header)?;
+        self.snapshot.apply(&header.clone().seal_slow(), self.signer.address(), self.epoch)?;
+        self.timestamp = header.timestamp;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{recover_signer, validate_clique_header, DEFAULT_EPOCH};
+    use secp256k1::rand::thread_rng;
+
+    #[test]
+    fn seal_blocks() {
+        let signer = CliqueSigner::new(SecretKey::new(&mut thread_rng()));
+        let other = Address::random();
+        let snapshot = Snapshot::new(0, B256::ZERO, [signer.address(), other]);
+        let mut sealer = CliqueSealer::new(signer.clone(), snapshot, 0, 5, 2);
+
+        // the signature of the prepared header recovers the signer
+        let mut header = Header { number: 1, timestamp: 1, ..Default::default() };
+        sealer.prepare_header(&mut header).unwrap();
+        assert_eq!(recover_signer(&header), Ok(signer.address()));
+        assert_eq!(header.timestamp, 5);
+        let inturn = sealer.snapshot.is_inturn(1, signer.address());
+        assert_eq!(header.difficulty, if inturn { DIFF_IN_TURN } else { DIFF_NO_TURN });
+
+        header.gas_used = 21_000;
+        sealer.seal_header(&mut header).unwrap();
+        assert_eq!(recover_signer(&header), Ok(signer.address()));
+        assert_eq!(validate_clique_header(&header, 2), Ok(()));
+
+        // the sealer signed the previous block
+        let mut header = Header { number: 2, ..Default::default() };
+        assert_eq!(
+            sealer.prepare_header(&mut header),
+            Err(CliqueError::RecentlySigned { signer: signer.address() })
+        );
+    }
+
+    #[test]
+    fn wiggle_out_of_turn() {
+        let signer = CliqueSigner::new(SecretKey::new(&mut thread_rng()));
+        let others = [Address::random(), Address::random()];
+        let snapshot = Snapshot::new(0, B256::ZERO, [signer.address(), others[0], others[1]]);
+        let sealer = CliqueSealer::new(signer.clone(), snapshot.clone(), 0, 5, DEFAULT_EPOCH);
+
+        for number in 1..=3 {
+            let delay = sealer.delay(number);
+            if snapshot.is_inturn(number, signer.address()) {
+                assert_eq!(delay, Duration::ZERO);
+            } else {
+                assert!(delay <= WIGGLE_TIME * 2);
+            }
+        }
+    }
+}
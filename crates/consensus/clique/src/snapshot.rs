@@ -0,0 +1,296 @@
+use crate::NONCE_AUTH;
+use reth_interfaces::consensus::CliqueError;
+use reth_primitives::{Address, BlockNumber, SealedHeader, B256};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+/// The state of the signer voting at a block.
+///
+/// See [EIP-225](https://eips.ethereum.org/EIPS/eip-225#vote-tallying) for the voting rules.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Snapshot {
+    /// The number of the block.
+    number: BlockNumber,
+    /// The hash of the block.
+    hash: B256,
+    /// The authorized signers, in ascending order.
+    signers: BTreeSet<Address>,
+    /// The signers of the recent blocks, by block number.
+    recents: BTreeMap<BlockNumber, Address>,
+    /// The votes cast since the last checkpoint, in chronological order.
+    votes: Vec<Vote>,
+    /// The current tally of the votes, by address.
+    tally: HashMap<Address, Tally>,
+}
+
+/// A vote of a signer to authorize or deauthorize an address.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Vote {
+    signer: Address,
+    address: Address,
+    authorize: bool,
+}
+
+/// The tally of the votes for an address.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Tally {
+    authorize: bool,
+    votes: usize,
+}
+
+impl Snapshot {
+    /// Creates a new snapshot at a checkpoint block with the given signers.
+    pub fn new(
+        number: BlockNumber,
+        hash: B256,
+        signers: impl IntoIterator<Item = Address>,
+    ) -> Self {
+        Self {
+            number,
+            hash,
+            signers: signers.into_iter().collect(),
+            recents: BTreeMap::new(),
+            votes: Vec::new(),
+            tally: HashMap::new(),
+        }
+    }
+
+    /// Sets the signers of the recent blocks, by block number.
+    ///
+    /// The recent signers are not part of the signers listed in checkpoints, so a snapshot created
+    /// at a checkpoint after genesis needs the signers of the blocks up to the checkpoint, see
+    /// [Snapshot::signer_limit].
+    pub fn with_recents(
+        mut self,
+        recents: impl IntoIterator<Item = (BlockNumber, Address)>,
+    ) -> Self {
+        self.recents = recents.into_iter().collect();
+        self
+    }
+
+    /// Returns the number of the block of the snapshot.
+    pub fn number(&self) -> BlockNumber {
+        self.number
+    }
+
+    /// Returns the hash of the block of the snapshot.
+    pub fn hash(&self) -> B256 {
+        self.hash
+    }
+
+    /// Returns the authorized signers, in ascending order.
+    pub fn signers(&self) -> impl Iterator<Item = Address> + '_ {
+        self.signers.iter().copied()
+    }
+
+    /// Returns true if the address is an authorized signer.
+    pub fn is_signer(&self, signer: Address) -> bool {
+        self.signers.contains(&signer)
+    }
+
+    /// Returns true if it's the turn of the signer to seal the block with the given number.
+    pub fn is_inturn(&self, number: BlockNumber, signer: Address) -> bool {
+        if self.signers.is_empty() {
+            return false
+        }
+        let index = (number % self.signers.len() as u64) as usize;
+        self.signers.iter().nth(index) == Some(&signer)
+    }
+
+    /// Returns true if the signer sealed one of the recent blocks, and is not allowed to seal the
+    /// block with the given number.
+    pub fn is_recent(&self, number: BlockNumber, signer: Address) -> bool {
+        let limit = self.signer_limit();
+        self.recents
+            .iter()
+            .any(|(seen, recent)| *recent == signer && (number < limit || *seen > number - limit))
+    }
+
+    /// Applies the block with the given signer on top of the snapshot.
+    ///
+    /// The block must be the child of the block of the snapshot. Votes are reset at the checkpoint
+    /// blocks of every `epoch`.
+    pub fn apply(
+        &mut self,
+        header: &SealedHeader,
+        signer: Address,
+        epoch: u64,
+    ) -> Result<(), CliqueError> {
+        let number = header.number;
+
+        // votes are discarded at every checkpoint
+        if number % epoch == 0 {
+            self.votes.clear();
+            self.tally.clear();
+        }
+
+        // the oldest signer is allowed to sign again
+        let limit = self.signer_limit();
+        if number >= limit {
+            self.recents.remove(&(number - limit));
+        }
+
+        if !self.signers.contains(&signer) {
+            return Err(CliqueError::UnauthorizedSigner { signer })
+        }
+        if self.recents.values().any(|recent| *recent == signer) {
+            return Err(CliqueError::RecentlySigned { signer })
+        }
+        self.recents.insert(number, signer);
+
+        // a new vote of the signer for the address replaces the previous one
+        let address = header.beneficiary;
+        if let Some(index) =
+            self.votes.iter().position(|vote| vote.signer == signer && vote.address == address)
+        {
+            let vote = self.votes.remove(index);
+            self.uncast(vote.address, vote.authorize);
+        }
+
+        let authorize = header.nonce == NONCE_AUTH;
+        if self.cast(address, authorize) {
+            self.votes.push(Vote { signer, address, authorize });
+        }
+
+        // apply the vote if it has the majority of the signers
+        let passed = self
+            .tally
+            .get(&address)
+            .filter(|tally| tally.votes > self.signers.len() / 2)
+            .map(|tally| tally.authorize);
+        if let Some(authorize) = passed {
+            if authorize {
+                self.signers.insert(address);
+            } else {
+                self.signers.remove(&address);
+
+                // the signer limit shrunk, so the oldest signer is allowed to sign again
+                let limit = self.signer_limit();
+                if number >= limit {
+                    self.recents.remove(&(number - limit));
+                }
+
+                // discard the votes of the deauthorized signer
+                let (discarded, votes) = std::mem::take(&mut self.votes)
+                    .into_iter()
+                    .partition::<Vec<_>, _>(|vote| vote.signer == address);
+                self.votes = votes;
+                for vote in discarded {
+                    self.uncast(vote.address, vote.authorize);
+                }
+            }
+
+            // discard the votes for the address, the vote passed
+            self.votes.retain(|vote| vote.address != address);
+            self.tally.remove(&address);
+        }
+
+        self.number = number;
+        self.hash = header.hash();
+
+        Ok(())
+    }
+
+    /// Returns the number of consecutive blocks in which a signer can only seal once.
+    pub fn signer_limit(&self) -> u64 {
+        self.signers.len() as u64 / 2 + 1
+    }
+
+    /// Adds a vote to the tally, returns false if the vote is meaningless.
+    fn cast(&mut self, address: Address, authorize: bool) -> bool {
+        // only votes that change the signers count
+        if self.signers.contains(&address) == authorize {
+            return false
+        }
+        let tally = self.tally.entry(address).or_insert(Tally { authorize, votes: 0 });
+        if tally.authorize != authorize {
+            return false
+        }
+        tally.votes += 1;
+        true
+    }
+
+    /// Removes a previously cast vote from the tally.
+    fn uncast(&mut self, address: Address, authorize: bool) {
+        if let Some(tally) = self.tally.get_mut(&address) {
+            if tally.authorize == authorize {
+                tally.votes -= 1;
+                if tally.votes == 0 {
+                    self.tally.remove(&address);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::NONCE_DROP;
+    use reth_primitives::Header;
+
+    const EPOCH: u64 = 30_000;
+
+    fn vote(snapshot: &mut Snapshot, signer: Address, address: Address, authorize: bool) {
+        let header = Header {
+            number: snapshot.number() + 1,
+            beneficiary: address,
+            nonce: if authorize { NONCE_AUTH } else { NONCE_DROP },
+            ..Default::default()
+        };
+        snapshot.apply(&header.seal_slow(), signer, EPOCH).unwrap();
+    }
+
+    #[test]
+    fn authorize_and_deauthorize_signer() {
+        let (a, b, c) = (Address::repeat_byte(1), Address::repeat_byte(2), Address::random());
+        let mut snapshot = Snapshot::new(0, B256::ZERO, [a, b]);
+
+        // a single vote is not the majority of two signers
+        vote(&mut snapshot, a, c, true);
+        assert!(!snapshot.is_signer(c));
+        vote(&mut snapshot, b, c, true);
+        assert!(snapshot.is_signer(c));
+        assert_eq!(snapshot.signers().count(), 3);
+
+        // two of three signers drop c
+        vote(&mut snapshot, a, c, false);
+        assert!(snapshot.is_signer(c));
+        vote(&mut snapshot, b, c, false);
+        assert!(!snapshot.is_signer(c));
+        assert_eq!(snapshot.number(), 4);
+    }
+
+    #[test]
+    fn reject_unauthorized_and_recent_signers() {
+        let (a, b) = (Address::repeat_byte(1), Address::repeat_byte(2));
+        let mut snapshot = Snapshot::new(0, B256::ZERO, [a, b]);
+        let header = Header { number: 1, ..Default::default() }.seal_slow();
+
+        let unauthorized = Address::random();
+        assert_eq!(
+            snapshot.clone().apply(&header, unauthorized, EPOCH),
+            Err(CliqueError::UnauthorizedSigner { signer: unauthorized })
+        );
+
+        snapshot.apply(&header, a, EPOCH).unwrap();
+        assert!(snapshot.is_recent(2, a));
+        let header = Header { number: 2, ..Default::default() }.seal_slow();
+        assert_eq!(
+            snapshot.clone().apply(&header, a, EPOCH),
+            Err(CliqueError::RecentlySigned { signer: a })
+        );
+        snapshot.apply(&header, b, EPOCH).unwrap();
+
+        // a can sign again after b
+        assert!(!snapshot.is_recent(3, a));
+    }
+
+    #[test]
+    fn inturn_signer() {
+        let (a, b) = (Address::repeat_byte(1), Address::repeat_byte(2));
+        let snapshot = Snapshot::new(0, B256::ZERO, [b, a]);
+        assert!(snapshot.is_inturn(2, a));
+        assert!(snapshot.is_inturn(3, b));
+        assert!(!snapshot.is_inturn(3, a));
+    }
+}
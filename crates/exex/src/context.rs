@@ -0,0 +1,43 @@
+use crate::{ExExEvent, ExExNotification};
+use reth_primitives::Head;
+use std::{future::Future, pin::Pin};
+use tokio::sync::mpsc::{Receiver, UnboundedSender};
+
+/// The future of a running ExEx.
+///
+/// The ExEx is spawned as a critical task of the node, so the node shuts down if it panics.
+pub type BoxExEx = Pin<Box<dyn Future<Output = eyre::Result<()>> + Send>>;
+
+/// Everything an ExEx needs to run.
+#[derive(Debug)]
+pub struct ExExContext<Node> {
+    /// The head of the chain when the ExEx was launched.
+    pub head: Head,
+    /// The components of the node, e.g. the provider and the transaction pool.
+    pub components: Node,
+    /// The notifications about changes of the canonical chain.
+    ///
+    /// Notifications that don't fit into the channel are buffered for every ExEx. Once the
+    /// buffer is full, the committed blocks are read from the database instead.
+    pub notifications: Receiver<ExExNotification>,
+    /// The channel to send [ExExEvent]s to the node.
+    pub events: UnboundedSender<ExExEvent>,
+}
+
+/// Launches an ExEx with its [ExExContext].
+///
+/// This is implemented for all closures that take the context and return the future of the ExEx.
+pub trait LaunchExEx<Node>: Send {
+    /// Consumes the launcher and returns the future of the ExEx.
+    fn launch(self: Box<Self>, ctx: ExExContext<Node>) -> BoxExEx;
+}
+
+impl<Node, F, Fut> LaunchExEx<Node> for F
+where
+    F: FnOnce(ExExContext<Node>) -> Fut + Send,
+    Fut: Future<Output = eyre::Result<()>> + Send + 'static,
+{
+    fn launch(self: Box<Self>, ctx: ExExContext<Node>) -> BoxExEx {
+        Box::pin((*self)(ctx))
+    }
+}
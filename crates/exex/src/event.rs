@@ -0,0 +1,10 @@
+use reth_primitives::BlockNumber;
+
+/// Events emitted by an ExEx.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExExEvent {
+    /// The ExEx has finished processing all blocks up to and including the given height.
+    ///
+    /// Data of the blocks above the lowest finished height of all ExExes is not pruned.
+    FinishedHeight(BlockNumber),
+}
@@ -0,0 +1,28 @@
+//! Execution extensions (ExEx).
+//!
+//! An execution extension is a long-running task that is notified about every change of the
+//! canonical chain, together with the full outcome of the executed blocks, i.e. the receipts and
+//! state changes. It can be used to build indexers, bridges or rollups on top of the node.
+//!
+//! Every ExEx reports the highest block it has finished processing with
+//! [ExExEvent::FinishedHeight]. The [ExExManager] tracks the lowest finished height of all ExExes,
+//! and the pruner doesn't prune any data above it, so an ExEx can always read the blocks it has yet
+//! to process from the database. An ExEx that falls behind, or misses notifications, is notified
+//! about the committed blocks it missed from the database.
+
+#![doc(
+    html_logo_url = "https://raw.githubusercontent.com/paradigmxyz/reth/main/assets/reth-docs.png",
+    html_favicon_url = "https://avatars0.githubusercontent.com/u/97369466?s=256",
+    issue_tracker_base_url = "https://github.com/paradigmxyz/reth/issues/"
+)]
+#![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
+
+mod context;
+mod event;
+mod manager;
+mod notification;
+
+pub use context::{BoxExEx, ExExContext, LaunchExEx};
+pub use event::ExExEvent;
+pub use manager::{ExExBackfill, ExExHandle, ExExManager, FinishedExExHeight};
+pub use notification::ExExNotification;
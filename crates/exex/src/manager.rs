@@ -0,0 +1,493 @@
+use crate::{ExExEvent, ExExNotification};
+use futures_util::{stream::SelectAll, Stream, StreamExt};
+use reth_db::database::Database;
+use reth_interfaces::provider::ProviderResult;
+use reth_primitives::BlockNumber;
+use reth_provider::{CanonStateNotification, CanonStateNotifications, Chain, ProviderFactory};
+use std::{
+    collections::VecDeque,
+    future::{poll_fn, Future},
+    ops::RangeInclusive,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+use tokio::sync::{
+    mpsc::{self, Receiver, UnboundedSender},
+    oneshot, watch,
+};
+use tokio_stream::wrappers::{
+    errors::BroadcastStreamRecvError, BroadcastStream, UnboundedReceiverStream,
+};
+use tokio_util::sync::PollSender;
+use tracing::{debug, error, warn};
+
+/// The default capacity of the notification channel of an ExEx.
+const DEFAULT_NOTIFICATION_CHANNEL_SIZE: usize = 1024;
+
+/// The maximum number of notifications buffered for an ExEx whose channel is full, before it's
+/// backfilled from the database instead.
+const MAX_BUFFERED_NOTIFICATIONS: usize = 1024;
+
+/// The maximum number of blocks of a notification that is backfilled from the database.
+const BACKFILL_BATCH_SIZE: u64 = 100;
+
+/// The lowest height all ExExes have finished processing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FinishedExExHeight {
+    /// No ExExes are installed, so there is no restriction.
+    NoExExs,
+    /// Not all ExExes have reported a finished height yet.
+    NotReady,
+    /// All ExExes have finished processing the blocks up to and including this height.
+    Height(BlockNumber),
+}
+
+impl FinishedExExHeight {
+    /// Returns true if not all ExExes have reported a finished height yet.
+    pub const fn is_not_ready(&self) -> bool {
+        matches!(self, Self::NotReady)
+    }
+}
+
+/// Reads committed blocks and their execution outcome, to backfill the ExExes that missed
+/// notifications.
+///
+/// The blocks are read on the blocking pool.
+pub trait ExExBackfill: Clone + Send + Sync + 'static {
+    /// Returns the canonical blocks in the range and their execution outcome.
+    fn committed_chain(&self, range: RangeInclusive<BlockNumber>) -> ProviderResult<Chain>;
+}
+
+impl<DB: Database + Clone + 'static> ExExBackfill for ProviderFactory<DB> {
+    fn committed_chain(&self, range: RangeInclusive<BlockNumber>) -> ProviderResult<Chain> {
+        self.provider()?.get_committed_chain(range)
+    }
+}
+
+/// A batch of committed blocks that is read from the database.
+#[derive(Debug)]
+struct PendingBackfill {
+    /// The last block of the batch.
+    end: BlockNumber,
+    /// Receives the blocks once they are read.
+    rx: oneshot::Receiver<ProviderResult<Chain>>,
+}
+
+/// The manager's side of the channels of a single ExEx.
+#[derive(Debug)]
+pub struct ExExHandle {
+    /// The identifier of the ExEx.
+    id: String,
+    /// Sends the notifications to the ExEx.
+    notifications: PollSender<ExExNotification>,
+    /// The notifications that didn't fit into the channel yet.
+    buffer: VecDeque<ExExNotification>,
+    /// The number of the next block the ExEx is notified about, after the buffered notifications.
+    next_block: BlockNumber,
+    /// Whether the ExEx missed notifications, so the blocks from `next_block` on are backfilled
+    /// from the database.
+    backfill: bool,
+    /// The batch of blocks from `next_block` on that is currently read from the database.
+    pending_backfill: Option<PendingBackfill>,
+    /// Receives the events of the ExEx.
+    events: Option<mpsc::UnboundedReceiver<ExExEvent>>,
+    /// The highest height the ExEx has finished processing.
+    finished_height: Option<BlockNumber>,
+}
+
+impl ExExHandle {
+    /// Creates a new handle for the ExEx with the given identifier.
+    ///
+    /// Returns the handle, and the notification receiver and event sender for the
+    /// [ExExContext](crate::ExExContext) of the ExEx.
+    pub fn new(id: String) -> (Self, Receiver<ExExNotification>, UnboundedSender<ExExEvent>) {
+        let (notifications_tx, notifications_rx) = mpsc::channel(DEFAULT_NOTIFICATION_CHANNEL_SIZE);
+        let (events_tx, events_rx) = mpsc::unbounded_channel();
+        let handle = Self {
+            id,
+            notifications: PollSender::new(notifications_tx),
+            buffer: VecDeque::new(),
+            next_block: 0,
+            backfill: false,
+            pending_backfill: None,
+            events: Some(events_rx),
+            finished_height: None,
+        };
+        (handle, notifications_rx, events_tx)
+    }
+
+    /// Returns the identifier of the ExEx.
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Queues the notification for the ExEx.
+    ///
+    /// Once the buffer is full, the ExEx is backfilled from the database instead: committed blocks
+    /// are read from the database, and only the reverted blocks the ExEx was notified about are
+    /// queued.
+    fn push(&mut self, notification: ExExNotification) {
+        if !self.backfill && self.buffer.len() >= MAX_BUFFERED_NOTIFICATIONS {
+            warn!(
+                target: "exex::manager",
+                id = %self.id,
+                next_block = %self.next_block,
+                "ExEx is lagging behind, backfilling from the database"
+            );
+            self.backfill = true;
+        }
+
+        if !self.backfill {
+            self.next_block = next_block(&notification);
+            self.buffer.push_back(notification);
+            return
+        }
+
+        if let Some(old) = notification.reverted_chain() {
+            // the batch that is read may contain reverted blocks, so it's read again
+            self.pending_backfill = None;
+            let first = old.first().number;
+            if first < self.next_block {
+                self.buffer.push_back(ExExNotification::ChainReverted {
+                    old: truncate_chain(old, self.next_block),
+                });
+                self.next_block = first;
+            }
+        }
+    }
+
+    /// Sends the buffered and backfilled notifications until the channel is full.
+    fn poll_flush<P: ExExBackfill>(
+        &mut self,
+        cx: &mut Context<'_>,
+        provider: &P,
+        tip: BlockNumber,
+    ) {
+        loop {
+            if self.notifications.is_closed() {
+                self.buffer.clear();
+                self.backfill = false;
+                self.pending_backfill = None;
+                return
+            }
+
+            if self.buffer.is_empty() && (!self.backfill || self.next_block > tip) {
+                if self.backfill {
+                    debug!(target: "exex::manager", id = %self.id, %tip, "ExEx backfilled");
+                    self.backfill = false;
+                }
+                return
+            }
+
+            match self.notifications.poll_reserve(cx) {
+                Poll::Ready(Ok(())) => {}
+                Poll::Ready(Err(_)) => {
+                    warn!(
+                        target: "exex::manager",
+                        id = %self.id,
+                        "ExEx notification channel closed"
+                    );
+                    continue
+                }
+                Poll::Pending => return,
+            }
+
+            let notification = match self.buffer.pop_front() {
+                Some(notification) => notification,
+                None => {
+                    let pending = self.pending_backfill.get_or_insert_with(|| {
+                        let end = tip.min(self.next_block + BACKFILL_BATCH_SIZE - 1);
+                        let range = self.next_block..=end;
+                        let provider = provider.clone();
+                        let (tx, rx) = oneshot::channel();
+                        tokio::task::spawn_blocking(move || {
+                            let _ = tx.send(provider.committed_chain(range));
+                        });
+                        PendingBackfill { end, rx }
+                    });
+                    let Poll::Ready(result) = Pin::new(&mut pending.rx).poll(cx) else {
+                        // the reserved slot is kept until the blocks are read
+                        return
+                    };
+                    let end = pending.end;
+                    self.pending_backfill = None;
+                    // the task only drops the sender if reading the blocks panicked
+                    match result.map_err(eyre::Report::from).and_then(|chain| Ok(chain?)) {
+                        Ok(chain) => {
+                            self.next_block = end + 1;
+                            ExExNotification::ChainCommitted { new: Arc::new(chain) }
+                        }
+                        Err(err) => {
+                            // retried on the next notification
+                            error!(
+                                target: "exex::manager",
+                                id = %self.id,
+                                next_block = %self.next_block,
+                                %err,
+                                "Failed to backfill ExEx"
+                            );
+                            self.notifications.abort_send();
+                            return
+                        }
+                    }
+                }
+            };
+            let _ = self.notifications.send_item(notification);
+        }
+    }
+}
+
+/// Forwards the changes of the canonical chain to all ExExes and tracks the lowest height they
+/// have finished processing.
+///
+/// Every ExEx has its own buffer, so a slow ExEx doesn't hold back the others. An ExEx that
+/// falls further behind, or misses notifications because the manager lagged behind the canonical
+/// state notifications, is backfilled from the database.
+#[derive(Debug)]
+pub struct ExExManager<P> {
+    handles: Vec<ExExHandle>,
+    /// Reads the blocks to backfill.
+    provider: P,
+    /// The tip of the canonical chain, as of the last notification.
+    tip: BlockNumber,
+    /// Whether notifications were skipped since the last notification, so the tip is unknown.
+    lagged: bool,
+    finished_height: watch::Sender<FinishedExExHeight>,
+}
+
+impl<P: ExExBackfill> ExExManager<P> {
+    /// Creates a new manager for the given ExExes, which were launched at the given head.
+    ///
+    /// Returns the manager and the receiver of the lowest finished height of all ExExes, which is
+    /// used to restrict the pruner.
+    pub fn new(
+        mut handles: Vec<ExExHandle>,
+        head: BlockNumber,
+        provider: P,
+    ) -> (Self, watch::Receiver<FinishedExExHeight>) {
+        for handle in &mut handles {
+            handle.next_block = head + 1;
+        }
+        let initial = if handles.is_empty() {
+            FinishedExExHeight::NoExExs
+        } else {
+            FinishedExExHeight::NotReady
+        };
+        let (finished_height, finished_height_rx) = watch::channel(initial);
+        (Self { handles, provider, tip: head, lagged: false, finished_height }, finished_height_rx)
+    }
+
+    /// Returns the lowest height all ExExes have finished processing.
+    fn compute_finished_height(&self) -> FinishedExExHeight {
+        if self.handles.is_empty() {
+            return FinishedExExHeight::NoExExs
+        }
+        self.handles
+            .iter()
+            .map(|handle| handle.finished_height)
+            .min()
+            .flatten()
+            .map_or(FinishedExExHeight::NotReady, FinishedExExHeight::Height)
+    }
+
+    /// Records the event of the ExEx at the given index.
+    fn on_event(&mut self, idx: usize, event: ExExEvent) {
+        let handle = &mut self.handles[idx];
+        match event {
+            ExExEvent::FinishedHeight(height) => {
+                debug!(target: "exex::manager", id = %handle.id, %height, "ExEx finished height");
+                handle.finished_height = Some(height);
+            }
+        }
+        let finished_height = self.compute_finished_height();
+        self.finished_height.send_if_modified(|current| {
+            let modified = *current != finished_height;
+            *current = finished_height;
+            modified
+        });
+    }
+
+    /// Queues the notification for all ExExes.
+    fn on_notification(&mut self, notification: ExExNotification) {
+        self.tip = next_block(&notification) - 1;
+        self.lagged = false;
+        for handle in &mut self.handles {
+            handle.push(notification.clone());
+        }
+    }
+
+    /// Records the events and forwards the notifications, until the canonical state notifications
+    /// end.
+    fn poll_run(
+        &mut self,
+        cx: &mut Context<'_>,
+        events: &mut SelectAll<impl Stream<Item = (usize, ExExEvent)> + Unpin>,
+        notifications: &mut BroadcastStream<CanonStateNotification>,
+    ) -> Poll<()> {
+        loop {
+            while let Poll::Ready(Some((idx, event))) = events.poll_next_unpin(cx) {
+                self.on_event(idx, event);
+            }
+
+            let received = match notifications.poll_next_unpin(cx) {
+                Poll::Ready(Some(Ok(notification))) => {
+                    self.on_notification(notification.into());
+                    true
+                }
+                Poll::Ready(Some(Err(BroadcastStreamRecvError::Lagged(skipped)))) => {
+                    // the committed blocks are read from the database, up to the tip of the next
+                    // notification
+                    warn!(
+                        target: "exex::manager",
+                        %skipped,
+                        "Lagged behind canonical state notifications, backfilling ExExes"
+                    );
+                    for handle in &mut self.handles {
+                        handle.backfill = true;
+                    }
+                    self.lagged = true;
+                    true
+                }
+                Poll::Ready(None) => return Poll::Ready(()),
+                Poll::Pending => false,
+            };
+
+            if !self.lagged {
+                for handle in &mut self.handles {
+                    handle.poll_flush(cx, &self.provider, self.tip);
+                }
+            }
+
+            if !received {
+                return Poll::Pending
+            }
+        }
+    }
+
+    /// Runs the manager until the canonical state notifications end.
+    pub async fn run(mut self, notifications: CanonStateNotifications) {
+        let mut events = SelectAll::new();
+        for (idx, handle) in self.handles.iter_mut().enumerate() {
+            if let Some(rx) = handle.events.take() {
+                events.push(UnboundedReceiverStream::new(rx).map(move |event| (idx, event)));
+            }
+        }
+        let mut notifications = BroadcastStream::new(notifications);
+
+        poll_fn(|cx| self.poll_run(cx, &mut events, &mut notifications)).await
+    }
+}
+
+/// Returns the number of the block after the canonical tip of the notification.
+fn next_block(notification: &ExExNotification) -> BlockNumber {
+    match (notification.committed_chain(), notification.reverted_chain()) {
+        (Some(new), _) => new.tip().number + 1,
+        (None, Some(old)) => old.first().number,
+        (None, None) => unreachable!("notifications commit or revert blocks"),
+    }
+}
+
+/// Returns the blocks of the chain below the given block number.
+fn truncate_chain(chain: Arc<Chain>, below: BlockNumber) -> Arc<Chain> {
+    if chain.tip().number < below {
+        return chain
+    }
+    let blocks = chain.blocks().range(..below).map(|(_, block)| block.clone());
+    let state = chain.state_at_block(below - 1).expect("block is part of the chain");
+    Arc::new(Chain::new(blocks, state, None))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::{Header, SealedBlock, SealedBlockWithSenders};
+    use reth_provider::BundleStateWithReceipts;
+    use tokio::sync::broadcast;
+
+    /// Backfills empty blocks.
+    #[derive(Clone)]
+    struct TestBackfill;
+
+    impl ExExBackfill for TestBackfill {
+        fn committed_chain(&self, range: RangeInclusive<BlockNumber>) -> ProviderResult<Chain> {
+            Ok(chain(range))
+        }
+    }
+
+    fn chain(range: RangeInclusive<BlockNumber>) -> Chain {
+        let blocks = range.map(|number| SealedBlockWithSenders {
+            block: SealedBlock {
+                header: Header { number, ..Default::default() }.seal_slow(),
+                ..Default::default()
+            },
+            senders: Vec::new(),
+        });
+        Chain::new(blocks, BundleStateWithReceipts::default(), None)
+    }
+
+    #[test]
+    fn finished_height() {
+        let (manager, rx) = ExExManager::new(Vec::new(), 0, TestBackfill);
+        assert_eq!(*rx.borrow(), FinishedExExHeight::NoExExs);
+        assert_eq!(manager.compute_finished_height(), FinishedExExHeight::NoExExs);
+
+        let (first, _, _) = ExExHandle::new("first".to_string());
+        let (second, _, _) = ExExHandle::new("second".to_string());
+        let (mut manager, rx) = ExExManager::new(vec![first, second], 0, TestBackfill);
+        assert!(rx.borrow().is_not_ready());
+
+        manager.on_event(0, ExExEvent::FinishedHeight(10));
+        assert!(rx.borrow().is_not_ready());
+
+        manager.on_event(1, ExExEvent::FinishedHeight(5));
+        assert_eq!(*rx.borrow(), FinishedExExHeight::Height(5));
+
+        manager.on_event(1, ExExEvent::FinishedHeight(20));
+        assert_eq!(*rx.borrow(), FinishedExExHeight::Height(10));
+    }
+
+    #[test]
+    fn backfill_full_buffer() {
+        let (mut handle, _notifications, _events) = ExExHandle::new("exex".to_string());
+        handle.next_block = 1;
+        let last = MAX_BUFFERED_NOTIFICATIONS as u64 + 1;
+        for number in 1..=last {
+            handle.push(ExExNotification::ChainCommitted { new: Arc::new(chain(number..=number)) });
+        }
+
+        // the last block didn't fit into the buffer
+        assert!(handle.backfill);
+        assert_eq!(handle.buffer.len(), MAX_BUFFERED_NOTIFICATIONS);
+        assert_eq!(handle.next_block, last);
+
+        // only the reverted block the ExEx is notified about is reverted
+        handle.push(ExExNotification::ChainReverted { old: Arc::new(chain(last - 1..=last)) });
+        assert_eq!(handle.next_block, last - 1);
+        let reverted = handle.buffer.back().and_then(|n| n.reverted_chain()).unwrap();
+        assert_eq!(reverted.blocks().keys().copied().collect::<Vec<_>>(), vec![last - 1]);
+    }
+
+    #[tokio::test]
+    async fn backfill_after_lag() {
+        let (handle, mut notifications, _events) = ExExHandle::new("exex".to_string());
+        let (manager, _) = ExExManager::new(vec![handle], 0, TestBackfill);
+
+        // the manager misses the first three blocks
+        let (tx, rx) = broadcast::channel(2);
+        for number in 1..=5 {
+            tx.send(CanonStateNotification::Commit { new: Arc::new(chain(number..=number)) })
+                .unwrap();
+        }
+        tokio::spawn(manager.run(rx));
+
+        // the missed blocks are read on the blocking pool
+        let mut blocks = Vec::new();
+        while blocks.len() < 5 {
+            let notification = notifications.recv().await.unwrap();
+            blocks.extend(notification.committed_chain().unwrap().blocks().keys().copied());
+        }
+        assert_eq!(blocks, (1..=5).collect::<Vec<_>>());
+        drop(tx);
+    }
+}
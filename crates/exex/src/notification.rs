@@ -0,0 +1,54 @@
+use reth_provider::{CanonStateNotification, Chain};
+use std::sync::Arc;
+
+/// A change of the canonical chain that is sent to every ExEx.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ExExNotification {
+    /// The chain was extended by the new blocks.
+    ChainCommitted {
+        /// The committed blocks and their execution outcome.
+        new: Arc<Chain>,
+    },
+    /// The chain was reorganized: the old blocks were reverted and the new blocks committed.
+    ChainReorged {
+        /// The reverted blocks and their execution outcome.
+        old: Arc<Chain>,
+        /// The committed blocks and their execution outcome.
+        new: Arc<Chain>,
+    },
+    /// The blocks were reverted, without committing any new blocks.
+    ChainReverted {
+        /// The reverted blocks and their execution outcome.
+        old: Arc<Chain>,
+    },
+}
+
+impl ExExNotification {
+    /// Returns the committed chain, if any.
+    pub fn committed_chain(&self) -> Option<Arc<Chain>> {
+        match self {
+            Self::ChainCommitted { new } | Self::ChainReorged { new, .. } => Some(new.clone()),
+            Self::ChainReverted { .. } => None,
+        }
+    }
+
+    /// Returns the reverted chain, if any.
+    pub fn reverted_chain(&self) -> Option<Arc<Chain>> {
+        match self {
+            Self::ChainReorged { old, .. } | Self::ChainReverted { old } => Some(old.clone()),
+            Self::ChainCommitted { .. } => None,
+        }
+    }
+}
+
+impl From<CanonStateNotification> for ExExNotification {
+    fn from(notification: CanonStateNotification) -> Self {
+        match notification {
+            CanonStateNotification::Commit { new } => Self::ChainCommitted { new },
+            CanonStateNotification::Reorg { old, new } if new.is_empty() => {
+                Self::ChainReverted { old }
+            }
+            CanonStateNotification::Reorg { old, new } => Self::ChainReorged { old, new },
+        }
+    }
+}
@@ -46,6 +46,19 @@ pub enum BlockchainTreeError {
         /// The block hash of the block that failed to buffer.
         block_hash: BlockHash,
     },
+    /// Thrown if the block forks off a historical block while the tree is full.
+    #[error("blockchain tree holds the maximum of {max_blocks} blocks")]
+    TreeCapacityExceeded {
+        /// The maximum number of blocks in the tree.
+        max_blocks: usize,
+    },
+    /// Thrown if the block extends the canonical tip while the tip has the maximum number of
+    /// child blocks in the tree.
+    #[error("canonical tip has the maximum of {max_siblings} child blocks in the tree")]
+    TipSiblingsExceeded {
+        /// The maximum number of child blocks of the canonical tip.
+        max_siblings: usize,
+    },
 }
 
 /// Result alias for `CanonicalError`
@@ -244,6 +257,7 @@ impl InsertBlockErrorKind {
                     BlockExecutionError::CanonicalRevert { .. } |
                     BlockExecutionError::CanonicalCommit { .. } |
                     BlockExecutionError::AppendChainDoesntConnect { .. } |
+                    BlockExecutionError::Sealing(_) |
                     BlockExecutionError::UnavailableForTest => false,
                     #[cfg(feature = "optimism")]
                     BlockExecutionError::OptimismBlockExecution(_) => false,
@@ -259,7 +273,9 @@ impl InsertBlockErrorKind {
                     BlockchainTreeError::CanonicalChain { .. } |
                     BlockchainTreeError::BlockNumberNotFoundInChain { .. } |
                     BlockchainTreeError::BlockHashNotFoundInChain { .. } |
-                    BlockchainTreeError::BlockBufferingFailed { .. } => false,
+                    BlockchainTreeError::BlockBufferingFailed { .. } |
+                    BlockchainTreeError::TreeCapacityExceeded { .. } => false,
+                    BlockchainTreeError::TipSiblingsExceeded { .. } => false,
                 }
             }
             InsertBlockErrorKind::Provider(_) | InsertBlockErrorKind::Internal(_) => {
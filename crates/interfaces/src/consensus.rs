@@ -1,6 +1,7 @@
+use crate::provider::ProviderError;
 use reth_primitives::{
-    BlockHash, BlockNumber, GotExpected, GotExpectedBoxed, Header, InvalidTransactionError,
-    SealedBlock, SealedHeader, B256, U256,
+    Address, BlockHash, BlockNumber, GotExpected, GotExpectedBoxed, Header,
+    InvalidTransactionError, SealedBlock, SealedHeader, B256, U256,
 };
 use std::fmt::Debug;
 
@@ -279,4 +280,107 @@ pub enum ConsensusError {
     /// Error for a transaction that violates consensus.
     #[error(transparent)]
     InvalidTransaction(#[from] InvalidTransactionError),
+
+    /// Error for a block that violates the Clique consensus rules.
+    #[error(transparent)]
+    Clique(#[from] CliqueError),
+}
+
+/// Errors of the Clique proof-of-authority consensus, see
+/// [EIP-225](https://eips.ethereum.org/EIPS/eip-225).
+#[derive(thiserror::Error, Debug, PartialEq, Eq, Clone)]
+pub enum CliqueError {
+    /// Error when the extra data is too short to contain the vanity.
+    #[error("extra data is missing the 32 byte vanity")]
+    MissingVanity,
+
+    /// Error when the extra data is too short to contain the seal.
+    #[error("extra data is missing the 65 byte signature")]
+    MissingSignature,
+
+    /// Error when a block that is not a checkpoint lists signers in the extra data.
+    #[error("non-checkpoint block contains a list of signers")]
+    ExtraSigners,
+
+    /// Error when the signers of a checkpoint block are not a list of addresses.
+    #[error("invalid list of signers in checkpoint block")]
+    InvalidCheckpointSigners,
+
+    /// Error when the signers of a checkpoint block don't match the signers of the snapshot.
+    #[error("mismatched list of signers in checkpoint block")]
+    MismatchedCheckpointSigners,
+
+    /// Error when a checkpoint block has a non-zero beneficiary.
+    #[error("beneficiary in checkpoint block is not zero")]
+    InvalidCheckpointBeneficiary,
+
+    /// Error when the nonce is not a valid vote.
+    #[error("vote nonce {nonce:#x} is neither authorize nor drop")]
+    InvalidVote {
+        /// The nonce of the block.
+        nonce: u64,
+    },
+
+    /// Error when a checkpoint block has a non-zero nonce.
+    #[error("vote nonce in checkpoint block is not zero")]
+    InvalidCheckpointVote,
+
+    /// Error when the mix hash is not zero.
+    #[error("mix hash is not zero")]
+    InvalidMixHash,
+
+    /// Error when the block has ommers.
+    #[error("ommers are not allowed")]
+    InvalidOmmers,
+
+    /// Error when the difficulty is neither in-turn nor out-of-turn.
+    #[error("invalid difficulty {difficulty}")]
+    InvalidDifficulty {
+        /// The difficulty of the block.
+        difficulty: U256,
+    },
+
+    /// Error when the difficulty doesn't match the turn of the signer.
+    #[error("mismatched difficulty for the turn of the signer: {0}")]
+    WrongDifficulty(GotExpected<U256>),
+
+    /// Error when the block is sealed earlier than the period after its parent.
+    #[error("block timestamp {timestamp} is less than {period}s after the parent timestamp {parent_timestamp}")]
+    InvalidPeriod {
+        /// The parent block's timestamp.
+        parent_timestamp: u64,
+        /// The block's timestamp.
+        timestamp: u64,
+        /// The minimum number of seconds between blocks.
+        period: u64,
+    },
+
+    /// Error when the signer can't be recovered from the seal.
+    #[error("invalid signature")]
+    InvalidSignature,
+
+    /// Error when the signer is not in the list of authorized signers.
+    #[error("unauthorized signer {signer}")]
+    UnauthorizedSigner {
+        /// The signer of the block.
+        signer: Address,
+    },
+
+    /// Error when the signer sealed one of the recent blocks.
+    #[error("signer {signer} signed recently")]
+    RecentlySigned {
+        /// The signer of the block.
+        signer: Address,
+    },
+
+    /// Error when an ancestor that is required to compute the signers is not known.
+    #[error("unknown ancestor {hash}")]
+    UnknownAncestor {
+        /// The hash of the unknown ancestor.
+        hash: B256,
+    },
+
+    /// Error when the ancestors required to compute the signers can't be read.
+    #[error(transparent)]
+    Provider(#[from] ProviderError),
 }
@@ -1,4 +1,4 @@
-use crate::provider::ProviderError;
+use crate::{consensus::ConsensusError, provider::ProviderError};
 use reth_primitives::{
     revm_primitives::EVMError, BlockNumHash, Bloom, GotExpected, GotExpectedBoxed,
     PruneSegmentError, B256,
@@ -110,6 +110,9 @@ pub enum BlockExecutionError {
         /// The fork on the other chain
         other_chain_fork: Box<BlockNumHash>,
     },
+    /// Error when the block can't be sealed
+    #[error("failed to seal block: {0}")]
+    Sealing(ConsensusError),
     /// Only used for TestExecutor
     ///
     /// Note: this is not feature gated for convenience.
@@ -152,6 +152,14 @@ pub enum DownloadError {
     /// Received headers with less than expected items.
     #[error("received less headers than expected: {0}")]
     HeadersResponseTooShort(GotExpected<u64>),
+    /// Received headers that don't match the skeleton agreed on by other peers.
+    #[error("headers response doesn't match the skeleton anchor at block {number}: {hash}")]
+    SkeletonAnchorMismatch {
+        /// Block number of the anchor.
+        number: BlockNumber,
+        /// The hash of the anchor in the response and the agreed hash.
+        hash: GotExpectedBoxed<B256>,
+    },
 
     /* ==================== BODIES ERRORS ==================== */
     /// Block validation failed
@@ -35,6 +35,11 @@ impl<Client> FullBlockClient<Client> {
         Self { client, consensus }
     }
 
+    /// Sets the [Consensus] that validates the downloaded blocks.
+    pub fn set_consensus(&mut self, consensus: Arc<dyn Consensus>) {
+        self.consensus = consensus;
+    }
+
     /// Returns a client with Test consensus
     #[cfg(any(test, feature = "test-utils"))]
     pub fn test_client(client: Client) -> Self {
@@ -103,6 +103,18 @@ impl BanList {
         self.banned_peers.contains_key(peer_id)
     }
 
+    /// Returns an iterator over the banned peers, and until when they are banned or `None` if
+    /// they are banned indefinitely.
+    pub fn banned_peers(&self) -> impl Iterator<Item = (PeerId, Option<Instant>)> + '_ {
+        self.banned_peers.iter().map(|(peer_id, until)| (*peer_id, *until))
+    }
+
+    /// Returns an iterator over the banned ips, and until when they are banned or `None` if they
+    /// are banned indefinitely.
+    pub fn banned_ips(&self) -> impl Iterator<Item = (IpAddr, Option<Instant>)> + '_ {
+        self.banned_ips.iter().map(|(ip, until)| (*ip, *until))
+    }
+
     /// Unbans the ip address
     pub fn unban_ip(&mut self, ip: &IpAddr) {
         self.banned_ips.remove(ip);
@@ -13,3 +13,7 @@ pub mod bandwidth_meter;
 pub mod stream;
 
 pub mod ratelimit;
+
+pub mod socks;
+
+pub mod token_bucket;
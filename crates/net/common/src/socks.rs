@@ -0,0 +1,309 @@
+//! A minimal SOCKS5 client for tunneling outgoing TCP connections through a proxy, see
+//! [RFC 1928](https://www.rfc-editor.org/rfc/rfc1928).
+
+use std::{
+    fmt,
+    io::{self, Error, ErrorKind},
+    net::SocketAddr,
+    str::FromStr,
+};
+use tokio::{
+    io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt},
+    net::TcpStream,
+};
+
+const SOCKS_VERSION: u8 = 5;
+const METHOD_NO_AUTH: u8 = 0;
+const METHOD_USERNAME_PASSWORD: u8 = 2;
+const METHOD_NOT_ACCEPTABLE: u8 = 0xff;
+/// The version of the username/password subnegotiation, see RFC 1929.
+const AUTH_VERSION: u8 = 1;
+const CMD_CONNECT: u8 = 1;
+const ATYP_IPV4: u8 = 1;
+const ATYP_DOMAIN: u8 = 3;
+const ATYP_IPV6: u8 = 4;
+
+/// A SOCKS5 proxy that outgoing TCP connections are tunneled through.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Socks5Proxy {
+    /// The address of the proxy server.
+    addr: SocketAddr,
+    /// The username and password to authenticate with, if required by the proxy.
+    credentials: Option<(String, String)>,
+}
+
+impl Socks5Proxy {
+    /// Creates a new proxy that doesn't require authentication.
+    pub fn new(addr: SocketAddr) -> Self {
+        Self { addr, credentials: None }
+    }
+
+    /// Sets the username and password used to authenticate with the proxy.
+    pub fn with_credentials(
+        mut self,
+        username: impl Into<String>,
+        password: impl Into<String>,
+    ) -> Self {
+        self.credentials = Some((username.into(), password.into()));
+        self
+    }
+
+    /// Returns the address of the proxy server.
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// Opens a TCP connection to the target through the proxy.
+    pub async fn connect(&self, target: SocketAddr) -> io::Result<TcpStream> {
+        let mut stream = TcpStream::connect(self.addr).await?;
+        self.handshake(&mut stream, target).await?;
+        Ok(stream)
+    }
+
+    /// Negotiates the authentication method and requests a connection to the target, after which
+    /// the stream is tunneled to the target.
+    async fn handshake<S>(&self, stream: &mut S, target: SocketAddr) -> io::Result<()>
+    where
+        S: AsyncRead + AsyncWrite + Unpin,
+    {
+        let methods: &[u8] = if self.credentials.is_some() {
+            &[METHOD_NO_AUTH, METHOD_USERNAME_PASSWORD]
+        } else {
+            &[METHOD_NO_AUTH]
+        };
+        let mut greeting = vec![SOCKS_VERSION, methods.len() as u8];
+        greeting.extend_from_slice(methods);
+        stream.write_all(&greeting).await?;
+
+        let mut reply = [0u8; 2];
+        stream.read_exact(&mut reply).await?;
+        if reply[0] != SOCKS_VERSION {
+            return Err(invalid_data("invalid SOCKS version"))
+        }
+        match (reply[1], &self.credentials) {
+            (METHOD_NO_AUTH, _) => {}
+            (METHOD_USERNAME_PASSWORD, Some((username, password))) => {
+                authenticate(stream, username, password).await?
+            }
+            (METHOD_NOT_ACCEPTABLE, _) => {
+                return Err(Error::new(
+                    ErrorKind::PermissionDenied,
+                    "SOCKS proxy rejected the authentication methods",
+                ))
+            }
+            _ => return Err(invalid_data("SOCKS proxy selected an unsupported method")),
+        }
+
+        let mut request = vec![SOCKS_VERSION, CMD_CONNECT, 0];
+        match target {
+            SocketAddr::V4(addr) => {
+                request.push(ATYP_IPV4);
+                request.extend_from_slice(&addr.ip().octets());
+            }
+            SocketAddr::V6(addr) => {
+                request.push(ATYP_IPV6);
+                request.extend_from_slice(&addr.ip().octets());
+            }
+        }
+        request.extend_from_slice(&target.port().to_be_bytes());
+        stream.write_all(&request).await?;
+
+        let mut reply = [0u8; 4];
+        stream.read_exact(&mut reply).await?;
+        if reply[0] != SOCKS_VERSION {
+            return Err(invalid_data("invalid SOCKS version"))
+        }
+        if reply[1] != 0 {
+            return Err(Error::new(ErrorKind::ConnectionRefused, connect_error(reply[1])))
+        }
+
+        // skip the address the proxy bound to connect to the target
+        let len = match reply[3] {
+            ATYP_IPV4 => 4,
+            ATYP_IPV6 => 16,
+            ATYP_DOMAIN => stream.read_u8().await? as usize,
+            _ => return Err(invalid_data("invalid SOCKS address type")),
+        };
+        let mut bound = vec![0u8; len + 2];
+        stream.read_exact(&mut bound).await?;
+
+        Ok(())
+    }
+}
+
+impl fmt::Debug for Socks5Proxy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // don't leak the password
+        f.debug_struct("Socks5Proxy")
+            .field("addr", &self.addr)
+            .field("username", &self.credentials.as_ref().map(|(username, _)| username))
+            .finish_non_exhaustive()
+    }
+}
+
+impl fmt::Display for Socks5Proxy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.credentials {
+            Some((username, _)) => write!(f, "socks5://{username}@{}", self.addr),
+            None => write!(f, "socks5://{}", self.addr),
+        }
+    }
+}
+
+/// Parses a proxy from `socks5://[<username>:<password>@]<ip>:<port>`, the scheme is optional.
+impl FromStr for Socks5Proxy {
+    type Err = Socks5ProxyParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.strip_prefix("socks5://").unwrap_or(s);
+        let (credentials, addr) = match s.rsplit_once('@') {
+            Some((credentials, addr)) => {
+                let (username, password) =
+                    credentials.split_once(':').ok_or(Socks5ProxyParseError::Credentials)?;
+                (Some((username.to_string(), password.to_string())), addr)
+            }
+            None => (None, s),
+        };
+        let addr = addr.parse().map_err(|_| Socks5ProxyParseError::Address(addr.to_string()))?;
+        Ok(Self { addr, credentials })
+    }
+}
+
+/// Error when parsing a [Socks5Proxy].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Socks5ProxyParseError {
+    /// The address of the proxy is not a socket address.
+    Address(String),
+    /// The credentials are not formatted as `<username>:<password>`.
+    Credentials,
+}
+
+impl fmt::Display for Socks5ProxyParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Socks5ProxyParseError::Address(addr) => write!(f, "invalid proxy address: {addr}"),
+            Socks5ProxyParseError::Credentials => {
+                f.write_str("proxy credentials must be formatted as <username>:<password>")
+            }
+        }
+    }
+}
+
+impl std::error::Error for Socks5ProxyParseError {}
+
+/// Authenticates with the username and password, see
+/// [RFC 1929](https://www.rfc-editor.org/rfc/rfc1929).
+async fn authenticate<S>(stream: &mut S, username: &str, password: &str) -> io::Result<()>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let (username, password) = (username.as_bytes(), password.as_bytes());
+    if username.len() > u8::MAX as usize || password.len() > u8::MAX as usize {
+        return Err(Error::new(ErrorKind::InvalidInput, "SOCKS credentials are too long"))
+    }
+    let mut request = vec![AUTH_VERSION, username.len() as u8];
+    request.extend_from_slice(username);
+    request.push(password.len() as u8);
+    request.extend_from_slice(password);
+    stream.write_all(&request).await?;
+
+    let mut reply = [0u8; 2];
+    stream.read_exact(&mut reply).await?;
+    if reply[1] != 0 {
+        return Err(Error::new(ErrorKind::PermissionDenied, "SOCKS authentication failed"))
+    }
+    Ok(())
+}
+
+fn invalid_data(msg: &str) -> Error {
+    Error::new(ErrorKind::InvalidData, msg.to_string())
+}
+
+/// Describes the reply code of a failed connect request.
+fn connect_error(code: u8) -> String {
+    let reason = match code {
+        1 => "general failure",
+        2 => "connection not allowed by ruleset",
+        3 => "network unreachable",
+        4 => "host unreachable",
+        5 => "connection refused",
+        6 => "TTL expired",
+        7 => "command not supported",
+        8 => "address type not supported",
+        _ => "unknown error",
+    };
+    format!("SOCKS proxy failed to connect: {reason}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::{IpAddr, Ipv4Addr};
+
+    #[test]
+    fn parse_proxy() {
+        let proxy: Socks5Proxy = "socks5://127.0.0.1:9050".parse().unwrap();
+        assert_eq!(proxy, Socks5Proxy::new("127.0.0.1:9050".parse().unwrap()));
+        assert_eq!(proxy.to_string(), "socks5://127.0.0.1:9050");
+
+        let proxy: Socks5Proxy = "user:p@ss@[::1]:1080".parse().unwrap();
+        assert_eq!(
+            proxy,
+            Socks5Proxy::new("[::1]:1080".parse().unwrap()).with_credentials("user", "p@ss")
+        );
+        assert!(!format!("{proxy:?}").contains("p@ss"));
+
+        assert!("localhost:1080".parse::<Socks5Proxy>().is_err());
+        assert_eq!(
+            "user@127.0.0.1:1080".parse::<Socks5Proxy>(),
+            Err(Socks5ProxyParseError::Credentials)
+        );
+    }
+
+    #[tokio::test]
+    async fn handshake_with_credentials() {
+        let proxy =
+            Socks5Proxy::new("127.0.0.1:1080".parse().unwrap()).with_credentials("user", "pw");
+        let target = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(1, 2, 3, 4)), 30303);
+        let (mut client, mut server) = tokio::io::duplex(64);
+
+        let server = async move {
+            let mut greeting = [0u8; 4];
+            server.read_exact(&mut greeting).await.unwrap();
+            assert_eq!(greeting, [5, 2, METHOD_NO_AUTH, METHOD_USERNAME_PASSWORD]);
+            server.write_all(&[5, METHOD_USERNAME_PASSWORD]).await.unwrap();
+
+            let mut auth = [0u8; 8];
+            server.read_exact(&mut auth).await.unwrap();
+            assert_eq!(&auth, b"\x01\x04user\x02pw");
+            server.write_all(&[1, 0]).await.unwrap();
+
+            let mut request = [0u8; 10];
+            server.read_exact(&mut request).await.unwrap();
+            assert_eq!(request, [5, CMD_CONNECT, 0, ATYP_IPV4, 1, 2, 3, 4, 0x76, 0x5f]);
+            server.write_all(&[5, 0, 0, ATYP_DOMAIN, 3, b'f', b'o', b'o', 0, 80]).await.unwrap();
+        };
+
+        let (res, _) = tokio::join!(proxy.handshake(&mut client, target), server);
+        res.unwrap();
+    }
+
+    #[tokio::test]
+    async fn handshake_connect_refused() {
+        let proxy = Socks5Proxy::new("127.0.0.1:1080".parse().unwrap());
+        let target = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(1, 2, 3, 4)), 30303);
+        let (mut client, mut server) = tokio::io::duplex(64);
+
+        let server = async move {
+            let mut greeting = [0u8; 3];
+            server.read_exact(&mut greeting).await.unwrap();
+            server.write_all(&[5, METHOD_NO_AUTH]).await.unwrap();
+            let mut request = [0u8; 10];
+            server.read_exact(&mut request).await.unwrap();
+            server.write_all(&[5, 5, 0, ATYP_IPV4]).await.unwrap();
+        };
+
+        let (res, _) = tokio::join!(proxy.handshake(&mut client, target), server);
+        assert_eq!(res.unwrap_err().kind(), ErrorKind::ConnectionRefused);
+    }
+}
@@ -0,0 +1,114 @@
+//! A token bucket that limits the rate of messages, requests or bytes.
+
+use std::time::{Duration, Instant};
+
+/// A token bucket that refills at a constant rate of tokens per second.
+///
+/// The bucket can hold at most one second worth of tokens and is allowed to go into debt: with
+/// [TokenBucket::consume], a message is admitted as long as the bucket is not empty, even if it is
+/// larger than the remaining tokens. This ensures that messages which exceed the per second limit
+/// can still be sent, while the average rate is preserved. [TokenBucket::try_acquire] only takes a
+/// token if a whole one is available.
+#[derive(Debug)]
+pub struct TokenBucket {
+    /// Tokens added per second.
+    rate: f64,
+    /// Currently available tokens, negative if in debt.
+    tokens: f64,
+    /// Last time the bucket was refilled.
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    /// Creates a new full bucket that refills with `rate` tokens per second.
+    pub fn new(rate: u64) -> Self {
+        let rate = rate.max(1) as f64;
+        Self { rate, tokens: rate, last_refill: Instant::now() }
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.last_refill).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.rate).min(self.rate);
+        self.last_refill = now;
+    }
+
+    /// Returns the time until the bucket admits the next message.
+    pub fn wait_time(&mut self, now: Instant) -> Duration {
+        self.refill(now);
+        if self.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-self.tokens / self.rate)
+        }
+    }
+
+    /// Removes the given number of tokens from the bucket.
+    pub fn consume(&mut self, tokens: usize) {
+        self.tokens -= tokens as f64;
+    }
+
+    /// Takes a single token if one is available at the given time.
+    pub fn try_acquire(&mut self, now: Instant) -> bool {
+        self.refill(now);
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Returns true if the bucket is refilled completely at the given time, meaning it has not
+    /// limited anything during the last second.
+    pub fn is_full(&mut self, now: Instant) -> bool {
+        self.refill(now);
+        self.tokens >= self.rate
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bucket_goes_into_debt() {
+        let mut bucket = TokenBucket::new(100);
+        let now = bucket.last_refill;
+        assert_eq!(bucket.wait_time(now), Duration::ZERO);
+
+        // admitted even though it exceeds the limit
+        bucket.consume(150);
+        assert_eq!(bucket.wait_time(now), Duration::from_millis(500));
+
+        // refilled after waiting
+        assert_eq!(bucket.wait_time(now + Duration::from_millis(500)), Duration::ZERO);
+
+        // refills up to one second worth of tokens
+        let mut bucket = TokenBucket::new(100);
+        let now = bucket.last_refill + Duration::from_secs(10);
+        bucket.refill(now);
+        assert_eq!(bucket.tokens, 100.0);
+    }
+
+    #[test]
+    fn acquire_whole_tokens() {
+        let mut bucket = TokenBucket::new(2);
+        let now = bucket.last_refill;
+        assert!(bucket.try_acquire(now));
+        assert!(bucket.try_acquire(now));
+        assert!(!bucket.try_acquire(now));
+        assert!(!bucket.is_full(now));
+
+        // half a second refills one token
+        let now = now + Duration::from_millis(500);
+        assert!(bucket.try_acquire(now));
+        assert!(!bucket.try_acquire(now));
+
+        // the bucket never holds more tokens than the rate
+        let now = now + Duration::from_secs(10);
+        assert!(bucket.is_full(now));
+        assert!(bucket.try_acquire(now));
+        assert!(bucket.try_acquire(now));
+        assert!(!bucket.try_acquire(now));
+    }
+}
@@ -12,6 +12,7 @@ use reth_primitives::{
 };
 use std::{
     collections::{HashMap, HashSet},
+    net::SocketAddr,
     time::Duration,
 };
 
@@ -71,6 +72,13 @@ pub struct Discv4Config {
     pub resolve_external_ip_interval: Option<Duration>,
     /// The duration after which we consider a bond expired.
     pub bond_expiration: Duration,
+    /// An additional UDP address of the other IP version to bind to, to discover peers over both
+    /// IPv4 and IPv6.
+    ///
+    /// If the port is `0`, the port of the primary socket is used. The port is advertised in the
+    /// local ENR, but the IP is not, since it's usually unspecified. It can be advertised with the
+    /// `ip` or `ip6` EIP-868 pair.
+    pub additional_discovery_addr: Option<SocketAddr>,
 }
 
 impl Discv4Config {
@@ -143,6 +151,7 @@ impl Default for Discv4Config {
             external_ip_resolver: Some(Default::default()),
             // By default retry public IP using a 5min interval
             resolve_external_ip_interval: Some(Duration::from_secs(60 * 5)),
+            additional_discovery_addr: None,
         }
     }
 }
@@ -308,6 +317,17 @@ impl Discv4ConfigBuilder {
         self
     }
 
+    /// Sets an additional UDP address of the other IP version to bind to.
+    ///
+    /// If the port is `0`, the port of the primary socket is used.
+    pub fn additional_discovery_addr(
+        &mut self,
+        additional_discovery_addr: Option<SocketAddr>,
+    ) -> &mut Self {
+        self.config.additional_discovery_addr = additional_discovery_addr;
+        self
+    }
+
     /// Returns the configured [`Discv4Config`]
     pub fn build(&self) -> Discv4Config {
         self.config.clone()
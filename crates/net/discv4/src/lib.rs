@@ -44,6 +44,7 @@ use reth_primitives::{
     hex, ForkId, PeerId, B256,
 };
 use secp256k1::SecretKey;
+use socket2::{Domain, Protocol, Socket, Type};
 use std::{
     cell::RefCell,
     collections::{btree_map, hash_map::Entry, BTreeMap, HashMap, VecDeque},
@@ -149,6 +150,10 @@ pub struct Discv4 {
     ///
     /// This includes the currently tracked external IP address of the node.
     node_record: Arc<Mutex<NodeRecord>>,
+    /// Tracks the local ENR for EIP-868.
+    ///
+    /// This is the ENR the service hands out to peers that request it.
+    local_enr: Arc<Mutex<Enr<SecretKey>>>,
 }
 
 // === impl Discv4 ===
@@ -184,6 +189,11 @@ impl Discv4 {
                 "127.0.0.1:3030".parse().unwrap(),
                 PeerId::random(),
             ))),
+            local_enr: Arc::new(Mutex::new(
+                EnrBuilder::new("v4")
+                    .build(&SecretKey::new(&mut secp256k1::rand::thread_rng()))
+                    .expect("v4 is set; qed"),
+            )),
         }
     }
 
@@ -226,12 +236,39 @@ impl Discv4 {
         secret_key: SecretKey,
         config: Discv4Config,
     ) -> io::Result<(Self, Discv4Service)> {
-        let socket = UdpSocket::bind(local_address).await?;
+        let additional_addr = config
+            .additional_discovery_addr
+            .filter(|addr| addr.is_ipv4() != local_address.is_ipv4());
+        let socket = if additional_addr.is_some() {
+            bind_udp_socket(local_address)?
+        } else {
+            UdpSocket::bind(local_address).await?
+        };
         let local_addr = socket.local_addr()?;
         local_node_record.udp_port = local_addr.port();
         trace!(target: "discv4",  ?local_addr,"opened UDP socket");
 
-        let service = Discv4Service::new(socket, local_addr, local_node_record, secret_key, config);
+        let additional_socket = match additional_addr {
+            Some(mut addr) => {
+                if addr.port() == 0 {
+                    addr.set_port(local_addr.port());
+                }
+                let socket = bind_udp_socket(addr)?;
+                let additional_local_addr = socket.local_addr()?;
+                trace!(target: "discv4", ?additional_local_addr, "opened additional UDP socket");
+                Some(socket)
+            }
+            None => None,
+        };
+
+        let service = Discv4Service::new(
+            socket,
+            additional_socket,
+            local_addr,
+            local_node_record,
+            secret_key,
+            config,
+        );
         let discv4 = service.handle();
         Ok((discv4, service))
     }
@@ -248,6 +285,14 @@ impl Discv4 {
         *self.node_record.lock()
     }
 
+    /// Returns the EIP-868 ENR of the local node.
+    ///
+    /// This is the ENR the service hands out to peers, including the currently tracked external
+    /// IP address and the configured EIP-868 pairs.
+    pub fn local_enr(&self) -> Enr<SecretKey> {
+        self.local_enr.lock().clone()
+    }
+
     /// Returns the currently tracked external IP of the node.
     pub fn external_ip(&self) -> IpAddr {
         self.node_record.lock().address
@@ -352,6 +397,15 @@ impl Discv4 {
         self.send_to_service(cmd);
     }
 
+    /// Sets the udp port advertised in our [`NodeRecord`] and ENR, e.g. the port that is mapped to
+    /// the socket on the gateway.
+    ///
+    /// This doesn't change the port of the socket.
+    pub fn set_udp_port(&self, port: u16) {
+        let cmd = Discv4Command::SetUdpPort(port);
+        self.send_to_service(cmd);
+    }
+
     /// Sets the pair in the EIP-868 [`Enr`] of the node.
     ///
     /// If the key already exists, this will update it.
@@ -408,6 +462,8 @@ pub struct Discv4Service {
     local_node_record: NodeRecord,
     /// Keeps track of the node record of the local node.
     shared_node_record: Arc<Mutex<NodeRecord>>,
+    /// Keeps track of the EIP-868 ENR of the local node.
+    shared_eip_868_enr: Arc<Mutex<Enr<SecretKey>>>,
     /// The secret key used to sign payloads
     secret_key: SecretKey,
     /// The UDP socket for sending and receiving messages.
@@ -467,9 +523,11 @@ pub struct Discv4Service {
 }
 
 impl Discv4Service {
-    /// Create a new instance for a bound [`UdpSocket`].
+    /// Create a new instance for a bound [`UdpSocket`], and an optional additional socket of the
+    /// other IP version.
     pub(crate) fn new(
         socket: UdpSocket,
+        additional_socket: Option<UdpSocket>,
         local_address: SocketAddr,
         local_node_record: NodeRecord,
         secret_key: SecretKey,
@@ -480,11 +538,18 @@ impl Discv4Service {
         let (egress_tx, egress_rx) = mpsc::channel(config.udp_egress_message_buffer);
         let mut tasks = JoinSet::<()>::new();
 
+        let additional_socket = additional_socket.map(Arc::new);
+        let additional_local_address =
+            additional_socket.as_ref().and_then(|socket| socket.local_addr().ok());
+        if let Some(udp) = additional_socket.clone() {
+            tasks.spawn(receive_loop(udp, ingress_tx.clone(), local_node_record.id));
+        }
+
         let udp = Arc::clone(&socket);
         tasks.spawn(receive_loop(udp, ingress_tx, local_node_record.id));
 
         let udp = Arc::clone(&socket);
-        tasks.spawn(send_loop(udp, egress_rx));
+        tasks.spawn(send_loop(udp, additional_socket, egress_rx));
 
         let kbuckets = KBucketsTable::new(
             NodeKey::from(&local_node_record).into(),
@@ -525,6 +590,15 @@ impl Discv4Service {
                 builder.udp6(local_node_record.udp_port);
                 builder.tcp6(local_node_record.tcp_port);
             }
+            // the IP of the additional socket is not advertised, see
+            // `Discv4Config::additional_discovery_addr`
+            if let Some(addr) = additional_local_address {
+                if addr.is_ipv4() {
+                    builder.udp4(addr.port());
+                } else {
+                    builder.udp6(addr.port());
+                }
+            }
 
             for (key, val) in config.additional_eip868_rlp_pairs.iter() {
                 builder.add_value_rlp(key, val.clone());
@@ -536,12 +610,14 @@ impl Discv4Service {
         let (to_service, commands_rx) = mpsc::unbounded_channel();
 
         let shared_node_record = Arc::new(Mutex::new(local_node_record));
+        let shared_eip_868_enr = Arc::new(Mutex::new(local_eip_868_enr.clone()));
 
         Discv4Service {
             local_address,
             local_eip_868_enr,
             local_node_record,
             shared_node_record,
+            shared_eip_868_enr,
             _socket: socket,
             kbuckets,
             secret_key,
@@ -574,6 +650,7 @@ impl Discv4Service {
             local_addr: self.local_address,
             to_service: self.to_service.clone(),
             node_record: self.shared_node_record.clone(),
+            local_enr: self.shared_eip_868_enr.clone(),
         }
     }
 
@@ -596,10 +673,16 @@ impl Discv4Service {
             let _ = self.local_eip_868_enr.set_ip(external_ip, &self.secret_key);
             let mut lock = self.shared_node_record.lock();
             *lock = self.local_node_record;
+            self.update_shared_enr();
             debug!(target: "discv4", enr=?self.local_eip_868_enr, "Updated local ENR");
         }
     }
 
+    /// Shares the updated EIP-868 ENR with the frontend handles.
+    fn update_shared_enr(&self) {
+        *self.shared_eip_868_enr.lock() = self.local_eip_868_enr.clone();
+    }
+
     /// Returns the [PeerId] that identifies this node
     pub fn local_peer_id(&self) -> &PeerId {
         &self.local_node_record.id
@@ -1233,6 +1316,7 @@ impl Discv4Service {
                     (Some(new), None) => self.notify(DiscoveryUpdate::EnrForkId(record, new)),
                     _ => {}
                 }
+                self.notify(DiscoveryUpdate::Enr(record, msg.enr.into_inner()));
             }
         }
     }
@@ -1590,6 +1674,7 @@ impl Discv4Service {
                         debug!(target: "discv4", key=%String::from_utf8_lossy(&key), "Update EIP-868 extension pair");
 
                         let _ = self.local_eip_868_enr.insert_raw_rlp(key, rlp, &self.secret_key);
+                        self.update_shared_enr();
                     }
                     Discv4Command::SetTcpPort(port) => {
                         debug!(target: "discv4", %port, "Update tcp port");
@@ -1599,6 +1684,19 @@ impl Discv4Service {
                         } else {
                             let _ = self.local_eip_868_enr.set_tcp6(port, &self.secret_key);
                         }
+                        *self.shared_node_record.lock() = self.local_node_record;
+                        self.update_shared_enr();
+                    }
+                    Discv4Command::SetUdpPort(port) => {
+                        debug!(target: "discv4", %port, "Update udp port");
+                        self.local_node_record.udp_port = port;
+                        if self.local_node_record.address.is_ipv4() {
+                            let _ = self.local_eip_868_enr.set_udp4(port, &self.secret_key);
+                        } else {
+                            let _ = self.local_eip_868_enr.set_udp6(port, &self.secret_key);
+                        }
+                        *self.shared_node_record.lock() = self.local_node_record;
+                        self.update_shared_enr();
                     }
 
                     Discv4Command::Terminated => {
@@ -1707,10 +1805,35 @@ pub enum Discv4Event {
     Terminated,
 }
 
+/// Binds a [`UdpSocket`] to the address, so that the unspecified addresses of both IP versions can
+/// be bound to the same port.
+///
+/// IPv6 sockets don't receive IPv4 datagrams.
+fn bind_udp_socket(addr: SocketAddr) -> io::Result<UdpSocket> {
+    let socket = Socket::new(Domain::for_address(addr), Type::DGRAM, Some(Protocol::UDP))?;
+    if addr.is_ipv6() {
+        socket.set_only_v6(true)?;
+    }
+    socket.set_nonblocking(true)?;
+    socket.bind(&addr.into())?;
+    UdpSocket::from_std(socket.into())
+}
+
 /// Continuously reads new messages from the channel and writes them to the socket
-pub(crate) async fn send_loop(udp: Arc<UdpSocket>, rx: EgressReceiver) {
+///
+/// Messages to addresses of the IP version of the additional socket are sent from that socket.
+pub(crate) async fn send_loop(
+    udp: Arc<UdpSocket>,
+    additional: Option<Arc<UdpSocket>>,
+    rx: EgressReceiver,
+) {
+    let is_ipv4 = udp.local_addr().map_or(true, |addr| addr.is_ipv4());
     let mut stream = ReceiverStream::new(rx);
     while let Some((payload, to)) = stream.next().await {
+        let udp = match additional.as_ref() {
+            Some(additional) if to.is_ipv4() != is_ipv4 => additional,
+            _ => &udp,
+        };
         match udp.send_to(&payload, to).await {
             Ok(size) => {
                 trace!(target: "discv4",  ?to, ?size,"sent payload");
@@ -1767,6 +1890,7 @@ pub(crate) async fn receive_loop(udp: Arc<UdpSocket>, tx: IngressSender, local_i
 enum Discv4Command {
     Add(NodeRecord),
     SetTcpPort(u16),
+    SetUdpPort(u16),
     SetEIP868RLPPair { key: Vec<u8>, rlp: Bytes },
     Ban(PeerId, IpAddr),
     BanPeer(PeerId),
@@ -2102,6 +2226,8 @@ pub enum DiscoveryUpdate {
     DiscoveredAtCapacity(NodeRecord),
     /// Received a [`ForkId`] via EIP-868 for the given [`NodeRecord`].
     EnrForkId(NodeRecord, ForkId),
+    /// Received the [`Enr`] via EIP-868 for the given [`NodeRecord`].
+    Enr(NodeRecord, Enr<SecretKey>),
     /// Node that was removed from the table
     Removed(PeerId),
     /// A series of updates
@@ -2155,6 +2281,35 @@ mod tests {
         assert_eq!(expected, decoded);
     }
 
+    #[tokio::test]
+    async fn test_handle_local_enr() {
+        let fork: ForkId = ForkId { hash: ForkHash([220, 233, 108, 45]), next: 0u64 };
+        let mut disc_conf = Discv4Config::default();
+        disc_conf.add_eip868_pair("eth", EnrForkIdEntry::from(fork));
+        let (discv4, mut service) = create_discv4_with_config(disc_conf).await;
+
+        // the handle returns the ENR of the service, including the EIP-868 pairs
+        assert_eq!(discv4.local_enr(), service.local_eip_868_enr);
+        assert!(discv4.local_enr().get_raw_rlp(b"eth").is_some());
+
+        let external_ip = IpAddr::from(Ipv4Addr::new(10, 0, 0, 1));
+        service.set_external_ip_addr(external_ip);
+        assert_eq!(discv4.local_enr().ip4(), Some(Ipv4Addr::new(10, 0, 0, 1)));
+        assert_eq!(discv4.local_enr().seq(), service.local_eip_868_enr.seq());
+    }
+
+    #[tokio::test]
+    async fn test_additional_discovery_addr() {
+        let mut disc_conf = Discv4Config::default();
+        disc_conf.additional_discovery_addr = "[::]:0".parse().ok();
+        let (discv4, service) = create_discv4_with_config(disc_conf).await;
+
+        // the additional socket is bound to the port of the primary socket
+        let port = service.local_addr().port();
+        assert_eq!(discv4.local_enr().udp4(), Some(port));
+        assert_eq!(discv4.local_enr().udp6(), Some(port));
+    }
+
     #[test]
     fn test_enr_forkid_entry_decode() {
         let raw: [u8; 8] = [0xc7, 0xc6, 0x84, 0xdc, 0xe9, 0x6c, 0x2d, 0x80];
@@ -2477,6 +2632,22 @@ mod tests {
         let _ = discv4.lookup_self().await;
     }
 
+    #[tokio::test]
+    async fn test_set_advertised_ports() {
+        let (discv4, service) = create_discv4().await;
+        let _handle = service.spawn();
+
+        discv4.set_udp_port(30305);
+        discv4.set_tcp_port(30306);
+        // the commands are handled in order, so the ports are set once the lookup returns
+        let _ = discv4.lookup_self().await;
+
+        let record = discv4.node_record();
+        assert_eq!((record.udp_port, record.tcp_port), (30305, 30306));
+        assert_eq!(discv4.local_enr().udp4(), Some(30305));
+        assert_eq!(discv4.local_enr().tcp4(), Some(30306));
+    }
+
     // sends a PING packet with wrong 'to' field and expects a PONG response.
     #[tokio::test(flavor = "multi_thread")]
     async fn test_check_wrong_to() {
@@ -223,6 +223,11 @@ impl<K: EnrKey> EnrWrapper<K> {
     pub fn new(enr: Enr<K>) -> Self {
         EnrWrapper(enr)
     }
+
+    /// Returns the wrapped [`Enr`].
+    pub fn into_inner(self) -> Enr<K> {
+        self.0
+    }
 }
 
 impl<K> Encodable for EnrWrapper<K>
@@ -42,6 +42,10 @@ pub struct BodiesDownloader<B: BodiesClient, Provider> {
     provider: Provider,
     /// The maximum number of non-empty blocks per one request
     request_limit: u64,
+    /// The maximum estimated size of the bodies requested at once in bytes.
+    max_response_size_bytes: usize,
+    /// Moving average of the size of non-empty blocks in bytes, once a block was received.
+    avg_block_size_bytes: Option<usize>,
     /// The maximum number of block bodies returned at once from the stream
     stream_batch_size: usize,
     /// The allowed range for number of concurrent requests.
@@ -77,7 +81,7 @@ where
         };
         // as the range is inclusive, we need to add 1 to the end.
         let items_left = (self.download_range.end() + 1).saturating_sub(start_at);
-        let limit = items_left.min(self.request_limit);
+        let limit = items_left.min(self.request_limit());
         self.query_headers(start_at..=*self.download_range.end(), limit)
     }
 
@@ -126,6 +130,20 @@ where
         Ok(Some(headers).filter(|h| !h.is_empty()))
     }
 
+    /// Returns the maximum number of non-empty blocks per one request.
+    ///
+    /// This is the configured request limit, lowered so that the estimated size of the response
+    /// doesn't exceed the maximum response size, which peers would truncate anyway.
+    fn request_limit(&self) -> u64 {
+        match self.avg_block_size_bytes {
+            Some(avg) if avg > 0 => {
+                let limit = (self.max_response_size_bytes / avg).max(1) as u64;
+                self.request_limit.min(limit)
+            }
+            _ => self.request_limit,
+        }
+    }
+
     /// Get the next expected block number for queueing.
     fn next_expected_block_number(&self) -> BlockNumber {
         match self.latest_queued_block_number {
@@ -215,6 +233,19 @@ where
         let size = response.iter().map(BlockResponse::size).sum::<usize>() +
             response.capacity() * mem::size_of::<BlockResponse>();
 
+        // update the estimated size of non-empty blocks
+        let (full_blocks, full_size) = response
+            .iter()
+            .filter(|block| matches!(block, BlockResponse::Full(_)))
+            .fold((0, 0), |(count, size), block| (count + 1, size + block.size()));
+        if full_blocks > 0 {
+            let sample = full_size / full_blocks;
+            self.avg_block_size_bytes = Some(match self.avg_block_size_bytes {
+                Some(avg) => (avg * 3 + sample) / 4,
+                None => sample,
+            });
+        }
+
         let response = OrderedBodiesResponse { resp: response, size };
         let response_len = response.len();
 
@@ -485,6 +516,8 @@ impl Ord for OrderedBodiesResponse {
 pub struct BodiesDownloaderBuilder {
     /// The batch size of non-empty blocks per one request
     pub request_limit: u64,
+    /// The maximum estimated size of the bodies requested at once in bytes.
+    pub max_response_size_bytes: usize,
     /// The maximum number of block bodies returned at once from the stream
     pub stream_batch_size: usize,
     /// Maximum number of bytes of received bodies to buffer internally.
@@ -500,6 +533,7 @@ impl BodiesDownloaderBuilder {
         BodiesDownloaderBuilder::default()
             .with_stream_batch_size(config.downloader_stream_batch_size)
             .with_request_limit(config.downloader_request_limit)
+            .with_max_response_size_bytes(config.downloader_max_response_size_bytes)
             .with_max_buffered_blocks_size_bytes(config.downloader_max_buffered_blocks_size_bytes)
             .with_concurrent_requests_range(
                 config.downloader_min_concurrent_requests..=
@@ -512,6 +546,7 @@ impl Default for BodiesDownloaderBuilder {
     fn default() -> Self {
         Self {
             request_limit: 200,
+            max_response_size_bytes: 2 * 1024 * 1024, // ~2MB
             stream_batch_size: 1_000,
             max_buffered_blocks_size_bytes: 2 * 1024 * 1024 * 1024, // ~2GB
             concurrent_requests_range: 5..=100,
@@ -526,6 +561,12 @@ impl BodiesDownloaderBuilder {
         self
     }
 
+    /// Set the maximum estimated size of the bodies requested at once on the downloader.
+    pub fn with_max_response_size_bytes(mut self, max_response_size_bytes: usize) -> Self {
+        self.max_response_size_bytes = max_response_size_bytes;
+        self
+    }
+
     /// Set stream batch size on the downloader.
     pub fn with_stream_batch_size(mut self, stream_batch_size: usize) -> Self {
         self.stream_batch_size = stream_batch_size;
@@ -563,6 +604,7 @@ impl BodiesDownloaderBuilder {
     {
         let Self {
             request_limit,
+            max_response_size_bytes,
             stream_batch_size,
             concurrent_requests_range,
             max_buffered_blocks_size_bytes,
@@ -574,6 +616,8 @@ impl BodiesDownloaderBuilder {
             consensus,
             provider,
             request_limit,
+            max_response_size_bytes,
+            avg_block_size_bytes: None,
             stream_batch_size,
             max_buffered_blocks_size_bytes,
             concurrent_requests_range,
@@ -798,4 +842,30 @@ mod tests {
             Some(Ok(res)) => assert_eq!(res, zip_blocks(headers.iter().take(100), &mut bodies))
         );
     }
+
+    // Check that the request limit is lowered so that responses don't exceed the maximum size
+    #[tokio::test]
+    async fn limits_request_size() {
+        let db = create_test_rw_db();
+        let mut rng = generators::rng();
+        let block = generators::random_block(&mut rng, 1, None, Some(10), Some(0));
+        let block_size = BlockResponse::Full(block.clone()).size();
+
+        let mut downloader = BodiesDownloaderBuilder::default()
+            .with_request_limit(100)
+            .with_max_response_size_bytes(block_size * 5)
+            .build(
+                Arc::new(TestBodiesClient::default()),
+                Arc::new(TestConsensus::default()),
+                ProviderFactory::new(db, MAINNET.clone()),
+            );
+        assert_eq!(downloader.request_limit(), 100);
+
+        // empty blocks don't count towards the estimated size
+        downloader.buffer_bodies_response(vec![BlockResponse::Empty(block.header.clone())]);
+        assert_eq!(downloader.request_limit(), 100);
+
+        downloader.buffer_bodies_response(vec![BlockResponse::Full(block)]);
+        assert_eq!(downloader.request_limit(), 5);
+    }
 }
@@ -0,0 +1,571 @@
+//! Reading and writing of [era1](https://github.com/ethereum/go-ethereum/pull/26621) archive files.
+//!
+//! An era1 file is an [e2store](https://github.com/status-im/nimbus-eth2/blob/stable/docs/e2store.md)
+//! file containing up to [`MAX_ERA1_BLOCKS`] consecutive pre-merge blocks:
+//!
+//! ```text
+//! era1 := Version | block-tuple* | other-entries* | Accumulator | BlockIndex
+//! block-tuple := CompressedHeader | CompressedBody | CompressedReceipts | TotalDifficulty
+//! ```
+//!
+//! Headers, bodies and receipts are snappy-framed RLP, the accumulator is the SSZ hash tree root
+//! of the `(block_hash, total_difficulty)` records of all blocks of the file.
+
+use alloy_rlp::{Decodable, Encodable};
+use reth_primitives::{
+    hex, Block, BlockBody, BlockNumber, GotExpected, Header, ReceiptWithBloom, B256, U256,
+};
+use sha2::{Digest, Sha256};
+use std::{
+    io::{Read, Write},
+    path::Path,
+};
+use thiserror::Error;
+
+/// The maximum number of blocks in a single era1 file.
+pub const MAX_ERA1_BLOCKS: usize = 8192;
+
+/// The file extension of era1 files.
+pub const ERA1_FILE_EXTENSION: &str = "era1";
+
+const VERSION: u16 = 0x3265;
+const COMPRESSED_HEADER: u16 = 0x03;
+const COMPRESSED_BODY: u16 = 0x04;
+const COMPRESSED_RECEIPTS: u16 = 0x05;
+const TOTAL_DIFFICULTY: u16 = 0x06;
+const ACCUMULATOR: u16 = 0x07;
+const BLOCK_INDEX: u16 = 0x3266;
+
+/// The size of an e2store entry header: type (2 bytes), length (4 bytes), reserved (2 bytes).
+const ENTRY_HEADER_SIZE: usize = 8;
+
+/// An error that can occur when reading, writing or verifying era1 files.
+#[derive(Debug, Error)]
+pub enum Era1Error {
+    /// An error occurred when reading or writing the file.
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    /// An error occurred when decoding the rlp of a block.
+    #[error(transparent)]
+    Rlp(#[from] alloy_rlp::Error),
+    /// The file ended in the middle of an entry.
+    #[error("unexpected end of era1 file")]
+    UnexpectedEof,
+    /// An entry of an unexpected type was found.
+    #[error("unexpected entry type {got:#06x}, expected {expected:#06x}")]
+    UnexpectedEntry {
+        /// The expected entry type.
+        expected: u16,
+        /// The entry type found in the file.
+        got: u16,
+    },
+    /// An entry has an invalid length.
+    #[error("invalid length {len} of entry type {entry:#06x}")]
+    InvalidEntryLength {
+        /// The entry type.
+        entry: u16,
+        /// The length of the entry.
+        len: usize,
+    },
+    /// The file contains no blocks or more than [`MAX_ERA1_BLOCKS`] blocks.
+    #[error("invalid number of blocks in era1 file: {0}")]
+    InvalidBlockCount(usize),
+    /// The blocks of the file are not consecutive.
+    #[error("block #{got} does not follow block #{previous}")]
+    NonConsecutiveBlocks {
+        /// The number of the previous block.
+        previous: BlockNumber,
+        /// The number of the block following it.
+        got: BlockNumber,
+    },
+    /// The block index does not match the blocks of the file.
+    #[error("block index does not match the blocks of the file")]
+    BlockIndexMismatch,
+    /// The total difficulty of a block does not match the difficulty of its header.
+    #[error("total difficulty mismatch for block #{number}: {diff}")]
+    TotalDifficultyMismatch {
+        /// The number of the block.
+        number: BlockNumber,
+        /// The mismatching total difficulty.
+        diff: GotExpected<U256>,
+    },
+    /// The accumulator root does not match the blocks of the file.
+    #[error("accumulator root mismatch: {0}")]
+    AccumulatorMismatch(GotExpected<B256>),
+    /// The file does not start at the first block of an epoch.
+    #[error("era1 file starts at block #{0}, which is not the first block of an epoch")]
+    UnalignedEpoch(BlockNumber),
+    /// There is no canonical accumulator root for the epoch of the file.
+    #[error("no canonical accumulator root for epoch {0}")]
+    UnknownEpoch(u64),
+    /// The accumulator root of the file does not match the canonical root of its epoch.
+    #[error("accumulator root mismatch for epoch {epoch}: {root}")]
+    EpochAccumulatorMismatch {
+        /// The epoch of the file.
+        epoch: u64,
+        /// The mismatching accumulator root.
+        root: GotExpected<B256>,
+    },
+    /// The first block of the file does not build on the last block of the previous file.
+    #[error("parent hash mismatch for block #{number}: {hash}")]
+    ParentHashMismatch {
+        /// The number of the first block of the file.
+        number: BlockNumber,
+        /// The mismatching parent hash.
+        hash: GotExpected<B256>,
+    },
+    /// A line of an accumulator list is not a valid root.
+    #[error("invalid accumulator root on line {0}")]
+    InvalidAccumulatorRoot(usize),
+}
+
+/// A block stored in an era1 file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Era1Block {
+    /// The header of the block.
+    pub header: Header,
+    /// The body of the block.
+    pub body: BlockBody,
+    /// The receipts of the block.
+    pub receipts: Vec<ReceiptWithBloom>,
+    /// The total difficulty of the chain up to and including the block.
+    pub total_difficulty: U256,
+}
+
+impl Era1Block {
+    /// Returns the block without the receipts and the total difficulty.
+    pub fn into_block(self) -> Block {
+        Block {
+            header: self.header,
+            body: self.body.transactions,
+            ommers: self.body.ommers,
+            withdrawals: self.body.withdrawals,
+        }
+    }
+}
+
+/// The contents of an era1 file.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Era1File {
+    /// The blocks of the file, ordered by block number.
+    pub blocks: Vec<Era1Block>,
+    /// The accumulator root stored in the file.
+    pub accumulator: B256,
+}
+
+impl Era1File {
+    /// Creates a new era1 file from the given blocks, computing the accumulator root.
+    pub fn new(blocks: Vec<Era1Block>) -> Result<Self, Era1Error> {
+        if blocks.is_empty() || blocks.len() > MAX_ERA1_BLOCKS {
+            return Err(Era1Error::InvalidBlockCount(blocks.len()))
+        }
+        let accumulator = accumulator_root(&blocks);
+        Ok(Self { blocks, accumulator })
+    }
+
+    /// Reads the era1 file at the given path.
+    ///
+    /// The contents are not verified, see [`Era1File::verify`].
+    pub fn read(path: impl AsRef<Path>) -> Result<Self, Era1Error> {
+        let data = std::fs::read(path)?;
+        Self::decode(&data)
+    }
+
+    /// Decodes an era1 file.
+    pub fn decode(data: &[u8]) -> Result<Self, Era1Error> {
+        let mut buf = data;
+
+        let (entry, version) = read_entry(&mut buf)?;
+        expect_entry(VERSION, entry)?;
+        if !version.is_empty() {
+            return Err(Era1Error::InvalidEntryLength { entry, len: version.len() })
+        }
+
+        let mut blocks = Vec::new();
+        let accumulator = loop {
+            let (entry, data) = read_entry(&mut buf)?;
+            match entry {
+                COMPRESSED_HEADER => {
+                    let header = Header::decode(&mut decompress(data)?.as_slice())?;
+
+                    let (entry, data) = read_entry(&mut buf)?;
+                    expect_entry(COMPRESSED_BODY, entry)?;
+                    let body = BlockBody::decode(&mut decompress(data)?.as_slice())?;
+
+                    let (entry, data) = read_entry(&mut buf)?;
+                    expect_entry(COMPRESSED_RECEIPTS, entry)?;
+                    let receipts =
+                        Vec::<ReceiptWithBloom>::decode(&mut decompress(data)?.as_slice())?;
+
+                    let (entry, data) = read_entry(&mut buf)?;
+                    expect_entry(TOTAL_DIFFICULTY, entry)?;
+                    let total_difficulty =
+                        U256::from_le_bytes::<32>(data.try_into().map_err(|_| {
+                            Era1Error::InvalidEntryLength { entry, len: data.len() }
+                        })?);
+
+                    blocks.push(Era1Block { header, body, receipts, total_difficulty });
+                }
+                ACCUMULATOR => {
+                    break B256::try_from(data)
+                        .map_err(|_| Era1Error::InvalidEntryLength { entry, len: data.len() })?
+                }
+                // other entries are allowed between the blocks and the accumulator
+                _ => {}
+            }
+        };
+
+        if blocks.is_empty() || blocks.len() > MAX_ERA1_BLOCKS {
+            return Err(Era1Error::InvalidBlockCount(blocks.len()))
+        }
+
+        let (entry, index) = read_entry(&mut buf)?;
+        expect_entry(BLOCK_INDEX, entry)?;
+        // starting number, one offset per block and the block count
+        if index.len() != (blocks.len() + 2) * 8 {
+            return Err(Era1Error::InvalidEntryLength { entry, len: index.len() })
+        }
+        let starting_number = u64::from_le_bytes(index[..8].try_into().expect("8 bytes"));
+        let count = u64::from_le_bytes(index[index.len() - 8..].try_into().expect("8 bytes"));
+        if starting_number != blocks[0].header.number || count != blocks.len() as u64 {
+            return Err(Era1Error::BlockIndexMismatch)
+        }
+
+        Ok(Self { blocks, accumulator })
+    }
+
+    /// Verifies that the blocks of the file are consecutive, that the total difficulties match
+    /// the difficulties of the headers and that the accumulator root matches the blocks.
+    pub fn verify(&self) -> Result<(), Era1Error> {
+        let Some(first) = self.blocks.first() else { return Err(Era1Error::InvalidBlockCount(0)) };
+
+        // the total difficulty of the genesis block is its difficulty
+        if first.header.number == 0 && first.total_difficulty != first.header.difficulty {
+            return Err(Era1Error::TotalDifficultyMismatch {
+                number: 0,
+                diff: GotExpected {
+                    got: first.total_difficulty,
+                    expected: first.header.difficulty,
+                },
+            })
+        }
+
+        for window in self.blocks.windows(2) {
+            let (previous, block) = (&window[0], &window[1]);
+            if block.header.number != previous.header.number + 1 {
+                return Err(Era1Error::NonConsecutiveBlocks {
+                    previous: previous.header.number,
+                    got: block.header.number,
+                })
+            }
+
+            let expected = previous.total_difficulty + block.header.difficulty;
+            if block.total_difficulty != expected {
+                return Err(Era1Error::TotalDifficultyMismatch {
+                    number: block.header.number,
+                    diff: GotExpected { got: block.total_difficulty, expected },
+                })
+            }
+        }
+
+        let root = accumulator_root(&self.blocks);
+        if root != self.accumulator {
+            return Err(Era1Error::AccumulatorMismatch(GotExpected {
+                got: root,
+                expected: self.accumulator,
+            }))
+        }
+
+        Ok(())
+    }
+
+    /// Verifies that the accumulator root of the file matches the canonical root of its epoch.
+    ///
+    /// `roots` is the list of canonical accumulator roots indexed by epoch, see
+    /// [`read_accumulator_roots`].
+    pub fn verify_epoch(&self, roots: &[B256]) -> Result<(), Era1Error> {
+        let start = self.start_block();
+        if start % MAX_ERA1_BLOCKS as u64 != 0 {
+            return Err(Era1Error::UnalignedEpoch(start))
+        }
+
+        let epoch = self.epoch();
+        let expected = *roots.get(epoch as usize).ok_or(Era1Error::UnknownEpoch(epoch))?;
+        if self.accumulator != expected {
+            return Err(Era1Error::EpochAccumulatorMismatch {
+                epoch,
+                root: GotExpected { got: self.accumulator, expected },
+            })
+        }
+
+        Ok(())
+    }
+
+    /// Verifies that the first block of the file is the child of the block with the given hash,
+    /// the last block of the previous file.
+    pub fn verify_parent(&self, parent_hash: B256) -> Result<(), Era1Error> {
+        let Some(first) = self.blocks.first() else { return Err(Era1Error::InvalidBlockCount(0)) };
+        if first.header.parent_hash != parent_hash {
+            return Err(Era1Error::ParentHashMismatch {
+                number: first.header.number,
+                hash: GotExpected { got: first.header.parent_hash, expected: parent_hash },
+            })
+        }
+        Ok(())
+    }
+
+    /// Returns the number of the first block of the file.
+    pub fn start_block(&self) -> BlockNumber {
+        self.blocks.first().map_or(0, |block| block.header.number)
+    }
+
+    /// Returns the epoch of the file.
+    pub fn epoch(&self) -> u64 {
+        self.start_block() / MAX_ERA1_BLOCKS as u64
+    }
+
+    /// Encodes the era1 file.
+    pub fn encode(&self) -> Result<Vec<u8>, Era1Error> {
+        let mut out = Vec::new();
+        write_entry(&mut out, VERSION, &[]);
+
+        let mut offsets = Vec::with_capacity(self.blocks.len());
+        for block in &self.blocks {
+            offsets.push(out.len() as i64);
+
+            let mut rlp = Vec::new();
+            block.header.encode(&mut rlp);
+            write_entry(&mut out, COMPRESSED_HEADER, &compress(&rlp)?);
+
+            rlp.clear();
+            block.body.encode(&mut rlp);
+            write_entry(&mut out, COMPRESSED_BODY, &compress(&rlp)?);
+
+            rlp.clear();
+            block.receipts.encode(&mut rlp);
+            write_entry(&mut out, COMPRESSED_RECEIPTS, &compress(&rlp)?);
+
+            write_entry(&mut out, TOTAL_DIFFICULTY, &block.total_difficulty.to_le_bytes::<32>());
+        }
+
+        write_entry(&mut out, ACCUMULATOR, self.accumulator.as_slice());
+
+        // block offsets are relative to the start of the block index entry
+        let index_start = out.len() as i64;
+        let mut index = Vec::with_capacity((self.blocks.len() + 2) * 8);
+        index.extend_from_slice(&self.start_block().to_le_bytes());
+        for offset in offsets {
+            index.extend_from_slice(&(offset - index_start).to_le_bytes());
+        }
+        index.extend_from_slice(&(self.blocks.len() as u64).to_le_bytes());
+        write_entry(&mut out, BLOCK_INDEX, &index);
+
+        Ok(out)
+    }
+
+    /// Writes the era1 file to the given path.
+    pub fn write(&self, path: impl AsRef<Path>) -> Result<(), Era1Error> {
+        Ok(std::fs::write(path, self.encode()?)?)
+    }
+
+    /// Returns the standard name of the file: `<network>-<epoch>-<short accumulator root>.era1`.
+    pub fn file_name(&self, network: &str) -> String {
+        format!(
+            "{network}-{:05}-{}.{ERA1_FILE_EXTENSION}",
+            self.epoch(),
+            hex::encode(&self.accumulator[..4])
+        )
+    }
+}
+
+/// Reads a list of canonical accumulator roots, one hex encoded root per line, ordered by epoch.
+///
+/// This is the format of the list of pre-merge epoch accumulators that the era1 archives are
+/// published with. Empty lines are ignored.
+pub fn read_accumulator_roots(path: impl AsRef<Path>) -> Result<Vec<B256>, Era1Error> {
+    parse_accumulator_roots(&std::fs::read_to_string(path)?)
+}
+
+fn parse_accumulator_roots(list: &str) -> Result<Vec<B256>, Era1Error> {
+    list.lines()
+        .enumerate()
+        .map(|(idx, line)| (idx, line.trim()))
+        .filter(|(_, line)| !line.is_empty())
+        .map(|(idx, line)| line.parse().map_err(|_| Era1Error::InvalidAccumulatorRoot(idx + 1)))
+        .collect()
+}
+
+/// Computes the accumulator root of the given blocks, the SSZ hash tree root of the list of
+/// `(block_hash, total_difficulty)` header records with a limit of [`MAX_ERA1_BLOCKS`].
+pub fn accumulator_root(blocks: &[Era1Block]) -> B256 {
+    let mut layer = blocks
+        .iter()
+        .map(|block| {
+            sha256_pair(&block.header.hash_slow(), &block.total_difficulty.to_le_bytes::<32>())
+        })
+        .collect::<Vec<_>>();
+
+    // merkleize the records, padding each layer with the zero hash of its depth
+    let mut zero_hash = B256::ZERO;
+    for _ in 0..MAX_ERA1_BLOCKS.trailing_zeros() {
+        if layer.len() % 2 == 1 {
+            layer.push(zero_hash);
+        }
+        layer = layer.chunks(2).map(|pair| sha256_pair(&pair[0], &pair[1])).collect();
+        zero_hash = sha256_pair(&zero_hash, &zero_hash);
+    }
+    let root = layer.first().copied().unwrap_or(zero_hash);
+
+    // mix in the length of the list
+    sha256_pair(&root, &U256::from(blocks.len()).to_le_bytes::<32>())
+}
+
+fn sha256_pair(left: &[u8], right: &[u8]) -> B256 {
+    let mut hasher = Sha256::new();
+    hasher.update(left);
+    hasher.update(right);
+    B256::from_slice(&hasher.finalize())
+}
+
+fn read_entry<'a>(buf: &mut &'a [u8]) -> Result<(u16, &'a [u8]), Era1Error> {
+    if buf.len() < ENTRY_HEADER_SIZE {
+        return Err(Era1Error::UnexpectedEof)
+    }
+    let entry = u16::from_le_bytes([buf[0], buf[1]]);
+    let len = u32::from_le_bytes([buf[2], buf[3], buf[4], buf[5]]) as usize;
+    let rest = &buf[ENTRY_HEADER_SIZE..];
+    if rest.len() < len {
+        return Err(Era1Error::UnexpectedEof)
+    }
+    let (data, rest) = rest.split_at(len);
+    *buf = rest;
+    Ok((entry, data))
+}
+
+fn write_entry(out: &mut Vec<u8>, entry: u16, data: &[u8]) {
+    out.extend_from_slice(&entry.to_le_bytes());
+    out.extend_from_slice(&(data.len() as u32).to_le_bytes());
+    out.extend_from_slice(&[0, 0]);
+    out.extend_from_slice(data);
+}
+
+fn expect_entry(expected: u16, got: u16) -> Result<(), Era1Error> {
+    if expected != got {
+        return Err(Era1Error::UnexpectedEntry { expected, got })
+    }
+    Ok(())
+}
+
+fn decompress(data: &[u8]) -> Result<Vec<u8>, Era1Error> {
+    let mut out = Vec::new();
+    snap::read::FrameDecoder::new(data).read_to_end(&mut out)?;
+    Ok(out)
+}
+
+fn compress(data: &[u8]) -> Result<Vec<u8>, Era1Error> {
+    let mut encoder = snap::write::FrameEncoder::new(Vec::new());
+    encoder.write_all(data)?;
+    encoder
+        .into_inner()
+        .map_err(|err| Era1Error::Io(std::io::Error::new(err.error().kind(), err.to_string())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::{Receipt, TxType};
+
+    fn era1_file(start: BlockNumber, count: u64) -> Era1File {
+        let mut total_difficulty = U256::ZERO;
+        let blocks = (start..start + count)
+            .map(|number| {
+                let header =
+                    Header { number, difficulty: U256::from(number + 1), ..Default::default() };
+                total_difficulty += header.difficulty;
+                let receipt = Receipt {
+                    tx_type: TxType::Legacy,
+                    success: true,
+                    cumulative_gas_used: number,
+                    ..Default::default()
+                };
+                Era1Block {
+                    header,
+                    body: BlockBody::default(),
+                    receipts: vec![receipt.with_bloom()],
+                    total_difficulty,
+                }
+            })
+            .collect();
+        Era1File::new(blocks).unwrap()
+    }
+
+    #[test]
+    fn era1_roundtrip() {
+        let file = era1_file(0, 10);
+        file.verify().unwrap();
+
+        let decoded = Era1File::decode(&file.encode().unwrap()).unwrap();
+        assert_eq!(decoded, file);
+        decoded.verify().unwrap();
+        assert!(file.file_name("mainnet").starts_with("mainnet-00000-"));
+    }
+
+    #[test]
+    fn detects_invalid_accumulator() {
+        let mut file = era1_file(0, 3);
+        file.accumulator = B256::ZERO;
+        assert!(matches!(file.verify(), Err(Era1Error::AccumulatorMismatch(_))));
+    }
+
+    #[test]
+    fn detects_invalid_total_difficulty() {
+        let mut file = era1_file(0, 3);
+        file.blocks[2].total_difficulty += U256::from(1);
+        assert!(matches!(file.verify(), Err(Era1Error::TotalDifficultyMismatch { number: 2, .. })));
+    }
+
+    #[test]
+    fn verifies_epoch_accumulator() {
+        let first = era1_file(0, 3);
+        let second = era1_file(MAX_ERA1_BLOCKS as u64, 3);
+        let roots =
+            parse_accumulator_roots(&format!("{}\n\n{}\n", first.accumulator, second.accumulator))
+                .unwrap();
+
+        first.verify_epoch(&roots).unwrap();
+        second.verify_epoch(&roots).unwrap();
+        assert!(matches!(
+            first.verify_epoch(&[B256::ZERO]),
+            Err(Era1Error::EpochAccumulatorMismatch { epoch: 0, .. })
+        ));
+        assert!(matches!(first.verify_epoch(&[]), Err(Era1Error::UnknownEpoch(0))));
+        assert!(matches!(era1_file(1, 3).verify_epoch(&roots), Err(Era1Error::UnalignedEpoch(1))));
+        assert!(matches!(
+            parse_accumulator_roots("0x1234"),
+            Err(Era1Error::InvalidAccumulatorRoot(1))
+        ));
+    }
+
+    #[test]
+    fn verifies_parent() {
+        let mut file = era1_file(10, 3);
+        let parent = B256::with_last_byte(1);
+        file.blocks[0].header.parent_hash = parent;
+
+        file.verify_parent(parent).unwrap();
+        assert!(matches!(
+            file.verify_parent(B256::ZERO),
+            Err(Era1Error::ParentHashMismatch { number: 10, .. })
+        ));
+    }
+
+    #[test]
+    fn empty_accumulator_root() {
+        // hash tree root of an empty list of header records
+        let mut zero_hash = B256::ZERO;
+        for _ in 0..13 {
+            zero_hash = sha256_pair(&zero_hash, &zero_hash);
+        }
+        assert_eq!(accumulator_root(&[]), sha256_pair(&zero_hash, &[0; 32]));
+    }
+}
@@ -8,7 +8,8 @@ use reth_interfaces::p2p::{
     priority::Priority,
 };
 use reth_primitives::{
-    BlockBody, BlockHash, BlockHashOrNumber, BlockNumber, Header, HeadersDirection, PeerId, B256,
+    Block, BlockBody, BlockHash, BlockHashOrNumber, BlockNumber, Header, HeadersDirection, PeerId,
+    B256,
 };
 use std::{self, collections::HashMap, path::Path};
 use thiserror::Error;
@@ -27,7 +28,8 @@ use tracing::{trace, warn};
 /// Blocks are assumed to have populated transactions, so reading headers will also buffer
 /// transactions in memory for use in the bodies stage.
 ///
-/// This reads the entire file into memory, so it is not suitable for large files.
+/// This reads the entire file into memory, so it is not suitable for large files. Use a
+/// [`ChunkedFileReader`] to import large files chunk by chunk instead.
 #[derive(Debug)]
 pub struct FileClient {
     /// The buffered headers retrieved when fetching new bodies.
@@ -69,15 +71,25 @@ impl FileClient {
         let mut reader = vec![];
         file.read_to_end(&mut reader).await.unwrap();
 
-        let mut headers = HashMap::new();
-        let mut hash_to_number = HashMap::new();
-        let mut bodies = HashMap::new();
+        let mut blocks = Vec::new();
 
         // use with_capacity to make sure the internal buffer contains the entire file
         let mut stream = FramedRead::with_capacity(&reader[..], BlockFileCodec, file_len as usize);
 
         while let Some(block_res) = stream.next().await {
-            let block = block_res?;
+            blocks.push(block_res?);
+        }
+
+        Ok(Self::from_blocks(blocks))
+    }
+
+    /// Initialize the [`FileClient`] with the given blocks.
+    pub fn from_blocks(blocks: impl IntoIterator<Item = Block>) -> Self {
+        let mut headers = HashMap::new();
+        let mut hash_to_number = HashMap::new();
+        let mut bodies = HashMap::new();
+
+        for block in blocks {
             let block_hash = block.header.hash_slow();
 
             // add to the internal maps
@@ -95,12 +107,12 @@ impl FileClient {
 
         trace!(blocks = headers.len(), "Initialized file client");
 
-        Ok(Self { headers, hash_to_number, bodies })
+        Self { headers, hash_to_number, bodies }
     }
 
     /// Get the tip hash of the chain.
     pub fn tip(&self) -> Option<B256> {
-        self.headers.get(&(self.headers.len() as u64)).map(|h| h.hash_slow())
+        self.max_block().and_then(|number| self.headers.get(&number)).map(|h| h.hash_slow())
     }
 
     /// Returns the highest block number of this client has or `None` if empty
@@ -142,6 +154,50 @@ impl FileClient {
     }
 }
 
+/// Reads blocks from a file in chunks of a fixed number of blocks.
+///
+/// Each chunk is returned as a separate [`FileClient`], so only a single chunk of the file has to
+/// be held in memory at once.
+#[derive(Debug)]
+pub struct ChunkedFileReader {
+    /// The stream of blocks decoded from the file.
+    stream: FramedRead<File, BlockFileCodec>,
+    /// The maximum number of blocks per chunk.
+    chunk_len: u64,
+}
+
+impl ChunkedFileReader {
+    /// Opens the file at the given path for reading chunks of at most `chunk_len` blocks.
+    pub async fn new<P: AsRef<Path>>(path: P, chunk_len: u64) -> Result<Self, FileClientError> {
+        let file = File::open(path).await?;
+        Ok(Self::from_file(file, chunk_len))
+    }
+
+    /// Reads chunks of at most `chunk_len` blocks from the given file.
+    pub(crate) fn from_file(file: File, chunk_len: u64) -> Self {
+        Self { stream: FramedRead::new(file, BlockFileCodec), chunk_len: chunk_len.max(1) }
+    }
+
+    /// Returns a [`FileClient`] for the next chunk of blocks, or `None` if the end of the file has
+    /// been reached.
+    pub async fn next_chunk(&mut self) -> Result<Option<FileClient>, FileClientError> {
+        let mut blocks = Vec::new();
+        while (blocks.len() as u64) < self.chunk_len {
+            match self.stream.next().await {
+                Some(block) => blocks.push(block?),
+                None => break,
+            }
+        }
+
+        if blocks.is_empty() {
+            return Ok(None)
+        }
+
+        trace!(target: "downloaders::file", blocks = blocks.len(), "Read chunk of blocks");
+        Ok(Some(FileClient::from_blocks(blocks)))
+    }
+}
+
 impl HeadersClient for FileClient {
     type Output = HeadersFut;
 
@@ -359,4 +415,20 @@ mod tests {
             Some(Ok(res)) => assert_eq!(res, zip_blocks(headers.iter(), &mut bodies))
         );
     }
+
+    #[tokio::test]
+    async fn test_read_file_in_chunks() {
+        // Generate some random blocks
+        let (file, headers, _) = generate_bodies_file(0..=19).await;
+
+        let mut reader = ChunkedFileReader::from_file(file, 8);
+
+        let mut chunks = Vec::new();
+        while let Some(client) = reader.next_chunk().await.unwrap() {
+            assert!(client.has_canonical_blocks());
+            chunks.push(client.max_block());
+        }
+        assert_eq!(chunks, vec![Some(7), Some(15), Some(19)]);
+        assert_eq!(chunks.last().copied().flatten(), headers.last().map(|h| h.number));
+    }
 }
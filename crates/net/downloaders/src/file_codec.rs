@@ -10,17 +10,9 @@ use tokio_util::codec::{Decoder, Encoder};
 
 /// Codec for reading raw block bodies from a file.
 ///
-/// If using with [`FramedRead`](tokio_util::codec::FramedRead), the user should make sure the
-/// framed reader has capacity for the entire block file. Otherwise, the decoder will return
-/// [`InputTooShort`](alloy_rlp::Error::InputTooShort), because RLP headers can only be
-/// decoded if the internal buffer is large enough to contain the entire block body.
-///
-/// Without ensuring the framed reader has capacity for the entire file, a block body is likely to
-/// fall across two read buffers, the decoder will not be able to decode the header, which will
-/// cause it to fail.
-///
-/// It's recommended to use [`with_capacity`](tokio_util::codec::FramedRead::with_capacity) to set
-/// the capacity of the framed reader to the size of the file.
+/// If the buffer does not contain an entire block yet, the decoder asks for more data, so a
+/// [`FramedRead`](tokio_util::codec::FramedRead) can stream blocks from a file of any size.
+#[derive(Debug)]
 pub(crate) struct BlockFileCodec;
 
 impl Decoder for BlockFileCodec {
@@ -32,7 +24,12 @@ impl Decoder for BlockFileCodec {
             return Ok(None)
         }
         let buf_slice = &mut src.as_ref();
-        let body = Block::decode(buf_slice)?;
+        let body = match Block::decode(buf_slice) {
+            Ok(body) => body,
+            // the block is not fully buffered yet
+            Err(alloy_rlp::Error::InputTooShort) => return Ok(None),
+            Err(err) => return Err(err.into()),
+        };
         src.advance(src.len() - buf_slice.len());
         Ok(Some(body))
     }
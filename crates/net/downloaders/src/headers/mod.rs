@@ -4,5 +4,7 @@ pub mod reverse_headers;
 /// A downloader implementation that spawns a downloader to a task
 pub mod task;
 
+mod skeleton;
+
 #[cfg(any(test, feature = "test-utils"))]
 pub mod test_utils;
@@ -1,6 +1,9 @@
 //! A headers downloader that can handle multiple requests concurrently.
 
-use super::task::TaskDownloader;
+use super::{
+    skeleton::{Skeleton, VoteOutcome},
+    task::TaskDownloader,
+};
 use crate::metrics::HeaderDownloaderMetrics;
 use futures::{stream::Stream, FutureExt};
 use futures_util::{stream::FuturesUnordered, StreamExt};
@@ -32,7 +35,7 @@ use std::{
     task::{ready, Context, Poll},
 };
 use thiserror::Error;
-use tracing::{error, trace};
+use tracing::{debug, error, trace};
 
 /// A heuristic that is used to determine the number of requests that should be prepared for a peer.
 /// This should ensure that there are always requests lined up for peers to handle while the
@@ -64,6 +67,11 @@ impl From<HeadersResponseError> for ReverseHeadersDownloaderError {
 /// **Note:** This downloader downloads in reverse, see also [HeadersDirection::Falling], this means
 /// the batches of headers that this downloader yields will start at the chain tip and move towards
 /// the local head: falling block numbers.
+///
+/// If enough peers are connected, the header at the start of each request is additionally
+/// requested from several peers. Responses that arrive out of order are checked against these
+/// anchors before they are buffered, so that a single peer can't stall the download with responses
+/// that would only fail validation once the chain validated from the sync target reaches them.
 #[must_use = "Stream does nothing unless polled"]
 #[allow(missing_debug_implementations)]
 pub struct ReverseHeadersDownloader<H: HeadersClient> {
@@ -100,6 +108,10 @@ pub struct ReverseHeadersDownloader<H: HeadersClient> {
     in_progress_queue: FuturesUnordered<HeadersRequestFuture<H::Output>>,
     /// Buffered, unvalidated responses
     buffered_responses: BinaryHeap<OrderedHeadersResponse>,
+    /// The anchors of the requests, cross-checked against multiple peers.
+    skeleton: Skeleton,
+    /// Requests for the anchors of the skeleton in progress
+    skeleton_requests: FuturesUnordered<HeadersRequestFuture<H::Output>>,
     /// Buffered, _sorted_ and validated headers ready to be returned.
     ///
     /// Note: headers are sorted from high to low
@@ -304,14 +316,169 @@ where
             }
         }
 
+        // the validated chain decides over the anchors
+        let mut detached = None;
+        if self.skeleton.is_enabled() {
+            for header in &validated {
+                let Some(confirmation) = self.skeleton.confirm(header.number, header.hash()) else {
+                    continue
+                };
+                for peer_id in confirmation.dissenters {
+                    self.client.report_bad_message(peer_id);
+                }
+                if confirmation.detached {
+                    detached = Some(header.number);
+                }
+            }
+        }
+
         // update tracked block info (falling block number)
         self.next_chain_tip_block_number =
             validated.last().expect("exists").number.saturating_sub(1);
         self.queued_validated_headers.extend(validated);
 
+        if let Some(number) = detached {
+            self.on_skeleton_detached(number);
+        }
+
         Ok(())
     }
 
+    /// Discards the skeleton after the peers agreed on an anchor that is not part of the validated
+    /// chain.
+    ///
+    /// The buffered responses were only checked against the detached skeleton, so they are
+    /// requested again, together with new anchors.
+    fn on_skeleton_detached(&mut self, number: BlockNumber) {
+        debug!(target: "downloaders::headers", number, "Skeleton detached from the validated chain");
+        self.metrics.detached_skeletons.increment(1);
+        self.skeleton.clear();
+        self.skeleton_requests.clear();
+
+        let buffered = std::mem::take(&mut self.buffered_responses);
+        self.metrics.buffered_responses.set(0.);
+        for response in buffered.into_vec() {
+            self.request_anchor(response.block_number());
+            self.submit_request(response.request, Priority::High);
+        }
+    }
+
+    /// Checks that the headers match the agreed anchors at their start and below their end.
+    ///
+    /// Caution: this expects the `headers` to be non-empty and sorted with _falling_ block numbers
+    fn validate_skeleton(&self, headers: &[Header]) -> DownloadResult<()> {
+        let highest = &headers[0];
+        if let Some(anchor) = self.skeleton.agreed(highest.number) {
+            let hash = highest.hash_slow();
+            if hash != anchor {
+                return Err(DownloadError::SkeletonAnchorMismatch {
+                    number: highest.number,
+                    hash: GotExpected { got: hash, expected: anchor }.into(),
+                })
+            }
+        }
+
+        let lowest = headers.last().expect("is not empty");
+        if let Some(number) = lowest.number.checked_sub(1) {
+            if let Some(anchor) = self.skeleton.agreed(number) {
+                if lowest.parent_hash != anchor {
+                    return Err(DownloadError::SkeletonAnchorMismatch {
+                        number,
+                        hash: GotExpected { got: lowest.parent_hash, expected: anchor }.into(),
+                    })
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Requests the anchor at the given block number from multiple peers, if the skeleton is
+    /// enabled and enough peers are connected.
+    fn request_anchor(&mut self, number: BlockNumber) {
+        if !self.skeleton.is_enabled() ||
+            self.client.num_connected_peers() < self.skeleton.quorum() ||
+            !self.skeleton.insert(number)
+        {
+            return
+        }
+        for _ in 0..self.skeleton.quorum() {
+            self.submit_anchor_request(number);
+        }
+    }
+
+    /// Requests a vote on the anchor at the given block number.
+    fn submit_anchor_request(&mut self, number: BlockNumber) {
+        self.skeleton.on_vote_requested(number);
+        let request =
+            HeadersRequest { start: number.into(), limit: 1, direction: HeadersDirection::Falling };
+        self.skeleton_requests.push(self.request_fut(request, Priority::Normal));
+    }
+
+    /// Handles the response to a request for an anchor.
+    fn on_anchor_outcome(&mut self, response: HeadersRequestOutcome) {
+        let number = response.block_number();
+        let vote = match response.outcome {
+            Ok(res) => {
+                let (peer_id, headers) = res.split();
+                match headers.as_slice() {
+                    [header] if header.number == number => Some((peer_id, header.hash_slow())),
+                    _ => {
+                        let error = DownloadError::HeadersResponseStartBlockMismatch(GotExpected {
+                            got: headers.first().map(|header| header.number).unwrap_or_default(),
+                            expected: number,
+                        });
+                        self.penalize_peer(Some(peer_id), &error);
+                        None
+                    }
+                }
+            }
+            Err(_) => None,
+        };
+
+        let outcome = match vote {
+            Some((peer_id, hash)) => self.skeleton.on_vote(number, peer_id, hash),
+            None => self.skeleton.on_failed_vote(number),
+        };
+        match outcome {
+            VoteOutcome::Pending => {}
+            VoteOutcome::Retry => self.submit_anchor_request(number),
+            VoteOutcome::Agreed(hash) => self.on_anchor_agreed(number, hash),
+            VoteOutcome::Unresolved => {
+                debug!(target: "downloaders::headers", number, "Peers didn't agree on the skeleton anchor");
+                self.metrics.unresolved_anchors.increment(1);
+            }
+        }
+    }
+
+    /// Checks the buffered responses adjacent to a newly agreed anchor, and requests the ones that
+    /// don't match it again.
+    fn on_anchor_agreed(&mut self, number: BlockNumber, hash: B256) {
+        trace!(target: "downloaders::headers", number, ?hash, "Skeleton anchor agreed");
+
+        let mut rejected = Vec::new();
+        let buffered = std::mem::take(&mut self.buffered_responses).into_vec();
+        let mut retained = Vec::with_capacity(buffered.len());
+        for response in buffered {
+            let adjacent = response.block_number() == number ||
+                response.headers.last().map_or(false, |lowest| lowest.number == number + 1);
+            match adjacent.then(|| self.validate_skeleton(&response.headers)) {
+                Some(Err(error)) => rejected.push(HeadersResponseError {
+                    request: response.request,
+                    peer_id: Some(response.peer_id),
+                    error,
+                }),
+                _ => retained.push(response),
+            }
+        }
+        self.buffered_responses = retained.into();
+
+        for error in rejected {
+            self.metrics.buffered_responses.decrement(1.);
+            self.on_headers_error(Box::new(error));
+        }
+    }
+
     /// Updates the state based on the given `target_block_number`
     ///
     /// There are three different outcomes:
@@ -506,6 +673,13 @@ where
                         .map(Err::<(), ReverseHeadersDownloaderError>)
                         .transpose()?;
                 } else if highest.number > self.existing_local_block_number() {
+                    // check the response against the skeleton before it takes up buffer space
+                    if let Err(error) = self.validate_skeleton(&headers) {
+                        return Err(
+                            HeadersResponseError { request, peer_id: Some(peer_id), error }.into()
+                        )
+                    }
+
                     self.metrics.buffered_responses.increment(1.);
                     // can't validate yet
                     self.buffered_responses.push(OrderedHeadersResponse {
@@ -611,6 +785,8 @@ where
         self.queued_validated_headers = Vec::new();
         self.buffered_responses = BinaryHeap::new();
         self.in_progress_queue.clear();
+        self.skeleton.clear();
+        self.skeleton_requests.clear();
 
         self.metrics.in_flight_requests.set(0.);
         self.metrics.buffered_responses.set(0.);
@@ -811,6 +987,11 @@ where
         // 3. return batch, if enough validated
         // 4. return Pending if 2.) did not submit a new request, else continue
         loop {
+            // poll anchor requests first, so responses can be checked against the anchors
+            while let Poll::Ready(Some(outcome)) = this.skeleton_requests.poll_next_unpin(cx) {
+                this.on_anchor_outcome(outcome);
+            }
+
             // poll requests
             while let Poll::Ready(Some(outcome)) = this.in_progress_queue.poll_next_unpin(cx) {
                 this.metrics.in_flight_requests.decrement(1.);
@@ -848,6 +1029,11 @@ where
                         "Requesting headers {request:?}"
                     );
                     progress = true;
+                    // the first request in line attaches to the validated chain right away
+                    let start = request.start.as_number().expect("is number");
+                    if start != this.next_chain_tip_block_number {
+                        this.request_anchor(start);
+                    }
                     this.submit_request(request, Priority::Normal);
                 } else {
                     // no more requests
@@ -1083,6 +1269,8 @@ pub struct ReverseHeadersDownloaderBuilder {
     max_concurrent_requests: usize,
     /// How many responses to buffer
     max_buffered_responses: usize,
+    /// Number of peers that have to agree on the anchors of the skeleton
+    skeleton_peers: usize,
 }
 
 impl ReverseHeadersDownloaderBuilder {
@@ -1094,6 +1282,7 @@ impl ReverseHeadersDownloaderBuilder {
             .min_concurrent_requests(config.downloader_min_concurrent_requests)
             .max_concurrent_requests(config.downloader_max_concurrent_requests)
             .max_buffered_responses(config.downloader_max_buffered_responses)
+            .skeleton_peers(config.downloader_skeleton_peers)
             .stream_batch_size(config.commit_threshold as usize)
     }
 }
@@ -1108,6 +1297,7 @@ impl Default for ReverseHeadersDownloaderBuilder {
             max_concurrent_requests: 100,
             min_concurrent_requests: 5,
             max_buffered_responses: 100,
+            skeleton_peers: 3,
         }
     }
 }
@@ -1160,6 +1350,15 @@ impl ReverseHeadersDownloaderBuilder {
         self
     }
 
+    /// Set the number of distinct peers that have to agree on the anchors of the skeleton.
+    ///
+    /// The skeleton is only used if at least this many peers are connected. A value lower than 2
+    /// disables the skeleton.
+    pub fn skeleton_peers(mut self, skeleton_peers: usize) -> Self {
+        self.skeleton_peers = skeleton_peers;
+        self
+    }
+
     /// Build [ReverseHeadersDownloader] with provided consensus
     /// and header client implementations
     pub fn build<H>(self, client: H, consensus: Arc<dyn Consensus>) -> ReverseHeadersDownloader<H>
@@ -1172,6 +1371,7 @@ impl ReverseHeadersDownloaderBuilder {
             min_concurrent_requests,
             max_concurrent_requests,
             max_buffered_responses,
+            skeleton_peers,
         } = self;
         ReverseHeadersDownloader {
             consensus,
@@ -1191,6 +1391,8 @@ impl ReverseHeadersDownloaderBuilder {
             sync_target_request: None,
             in_progress_queue: Default::default(),
             buffered_responses: Default::default(),
+            skeleton: Skeleton::new(skeleton_peers),
+            skeleton_requests: Default::default(),
             queued_validated_headers: Default::default(),
             metrics: Default::default(),
         }
@@ -1222,8 +1424,65 @@ mod tests {
 
     use crate::headers::test_utils::child_header;
     use assert_matches::assert_matches;
-    use reth_interfaces::test_utils::{TestConsensus, TestHeadersClient};
+    use reth_interfaces::{
+        p2p::download::DownloadClient,
+        test_utils::{TestConsensus, TestHeadersClient},
+    };
     use reth_primitives::SealedHeader;
+    use std::sync::{atomic::AtomicUsize, Mutex};
+
+    /// A client that serves a chain of headers from several peers in turn, one of which responds
+    /// with the headers of a fork.
+    #[derive(Debug)]
+    struct SkeletonTestClient {
+        chain: Vec<SealedHeader>,
+        fork: Vec<SealedHeader>,
+        peers: Vec<PeerId>,
+        malicious: PeerId,
+        next_peer: AtomicUsize,
+        reported: Mutex<Vec<PeerId>>,
+    }
+
+    impl DownloadClient for SkeletonTestClient {
+        fn report_bad_message(&self, peer_id: PeerId) {
+            self.reported.lock().unwrap().push(peer_id);
+        }
+
+        fn num_connected_peers(&self) -> usize {
+            self.peers.len()
+        }
+    }
+
+    impl HeadersClient for SkeletonTestClient {
+        type Output = futures::future::Ready<PeerRequestResult<Vec<Header>>>;
+
+        fn get_headers_with_priority(
+            &self,
+            request: HeadersRequest,
+            _priority: Priority,
+        ) -> Self::Output {
+            let next_peer = self.next_peer.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+            let peer_id = self.peers[next_peer % self.peers.len()];
+            let chain = if peer_id == self.malicious { &self.fork } else { &self.chain };
+
+            let start = match request.start {
+                BlockHashOrNumber::Hash(hash) => {
+                    chain.iter().find(|header| header.hash() == hash).map(|header| header.number)
+                }
+                BlockHashOrNumber::Number(number) => Some(number),
+            };
+            let headers = start
+                .map(|start| {
+                    (0..request.limit)
+                        .filter_map(|i| start.checked_sub(i))
+                        .filter_map(|number| chain.get(number as usize))
+                        .map(|header| header.header.clone())
+                        .collect()
+                })
+                .unwrap_or_default();
+            futures::future::ready(Ok((peer_id, headers).into()))
+        }
+    }
 
     /// Tests that `replace_number` works the same way as Option::replace
     #[test]
@@ -1516,4 +1775,43 @@ mod tests {
 
         assert!(downloader.next().await.is_none());
     }
+
+    #[tokio::test]
+    async fn download_with_skeleton() {
+        reth_tracing::init_test_tracing();
+
+        let mut chain = vec![SealedHeader::default()];
+        let mut fork = vec![SealedHeader::default()];
+        for _ in 0..30 {
+            chain.push(child_header(chain.last().unwrap()));
+            let mut header = child_header(fork.last().unwrap()).unseal();
+            header.timestamp += 1;
+            fork.push(header.seal_slow());
+        }
+
+        let malicious = PeerId::random();
+        let client = Arc::new(SkeletonTestClient {
+            chain: chain.clone(),
+            fork,
+            peers: vec![PeerId::random(), malicious, PeerId::random(), PeerId::random()],
+            malicious,
+            next_peer: Default::default(),
+            reported: Default::default(),
+        });
+        let mut downloader = ReverseHeadersDownloaderBuilder::default()
+            .stream_batch_size(30)
+            .request_limit(5)
+            .skeleton_peers(2)
+            .build(Arc::clone(&client), Arc::new(TestConsensus::default()));
+        downloader.update_local_head(chain[0].clone());
+        downloader.update_sync_target(SyncTarget::Tip(chain[30].hash()));
+
+        let headers = downloader.next().await.unwrap();
+        assert_eq!(headers, Ok(chain[1..].iter().rev().cloned().collect::<Vec<_>>()));
+
+        // only the peer serving the fork was penalized
+        let reported = client.reported.lock().unwrap();
+        assert!(reported.contains(&malicious));
+        assert!(reported.iter().all(|peer_id| *peer_id == malicious));
+    }
 }
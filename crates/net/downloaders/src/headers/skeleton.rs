@@ -0,0 +1,273 @@
+//! The skeleton of a reverse headers download, cross-checked against multiple peers.
+
+use reth_primitives::{BlockNumber, PeerId, B256};
+use std::collections::HashMap;
+
+/// The anchors of a reverse headers download.
+///
+/// Anchors are the headers at the start of the requests that fill the gap between the sync target
+/// and the local head. The hash of an anchor is requested from several peers and only accepted if
+/// enough distinct peers agree on it. Responses that arrive out of order can then be checked
+/// against the anchors at both of their ends before they're buffered, so that a single peer can't
+/// fill the buffer with headers that fail validation later.
+///
+/// The chain that is validated from the sync target is always authoritative: once it reaches an
+/// anchor, the anchor is confirmed or, if the peers agreed on another hash, the skeleton is
+/// detached from the chain.
+#[derive(Debug)]
+pub(crate) struct Skeleton {
+    /// Number of distinct peers that have to agree on the hash of an anchor.
+    quorum: usize,
+    /// The anchors by block number.
+    anchors: HashMap<BlockNumber, Anchor>,
+}
+
+impl Skeleton {
+    /// Creates a new skeleton that requires `quorum` peers to agree on an anchor.
+    ///
+    /// The skeleton is disabled if the quorum is lower than 2.
+    pub(crate) fn new(quorum: usize) -> Self {
+        Self { quorum, anchors: HashMap::new() }
+    }
+
+    /// Returns true if anchors are cross-checked.
+    pub(crate) fn is_enabled(&self) -> bool {
+        self.quorum > 1
+    }
+
+    /// Returns the number of distinct peers that have to agree on an anchor.
+    pub(crate) fn quorum(&self) -> usize {
+        self.quorum
+    }
+
+    /// Maximum number of votes requested for an anchor before it's given up.
+    fn max_attempts(&self) -> usize {
+        self.quorum * 2
+    }
+
+    /// Adds an anchor at the given block number.
+    ///
+    /// Returns false if the anchor already exists.
+    pub(crate) fn insert(&mut self, number: BlockNumber) -> bool {
+        if self.anchors.contains_key(&number) {
+            return false
+        }
+        self.anchors.insert(number, Anchor::default());
+        true
+    }
+
+    /// Returns the agreed hash of the anchor at the given block number.
+    pub(crate) fn agreed(&self, number: BlockNumber) -> Option<B256> {
+        match self.anchors.get(&number)?.state {
+            AnchorState::Agreed(hash) => Some(hash),
+            _ => None,
+        }
+    }
+
+    /// Records that a vote for the anchor was requested.
+    pub(crate) fn on_vote_requested(&mut self, number: BlockNumber) {
+        if let Some(anchor) = self.anchors.get_mut(&number) {
+            anchor.attempts += 1;
+            anchor.outstanding += 1;
+        }
+    }
+
+    /// Records the hash of the anchor returned by a peer.
+    pub(crate) fn on_vote(
+        &mut self,
+        number: BlockNumber,
+        peer_id: PeerId,
+        hash: B256,
+    ) -> VoteOutcome {
+        let (quorum, max_attempts) = (self.quorum, self.max_attempts());
+        let Some(anchor) = self.anchors.get_mut(&number) else { return VoteOutcome::Pending };
+        anchor.outstanding = anchor.outstanding.saturating_sub(1);
+        if anchor.state != AnchorState::Voting {
+            return VoteOutcome::Pending
+        }
+
+        // only the first vote of each peer counts
+        anchor.votes.entry(peer_id).or_insert(hash);
+        if anchor.votes.values().filter(|vote| **vote == hash).count() >= quorum {
+            anchor.state = AnchorState::Agreed(hash);
+            return VoteOutcome::Agreed(hash)
+        }
+        anchor.next_outcome(max_attempts)
+    }
+
+    /// Records that a requested vote for the anchor failed.
+    pub(crate) fn on_failed_vote(&mut self, number: BlockNumber) -> VoteOutcome {
+        let max_attempts = self.max_attempts();
+        let Some(anchor) = self.anchors.get_mut(&number) else { return VoteOutcome::Pending };
+        anchor.outstanding = anchor.outstanding.saturating_sub(1);
+        if anchor.state != AnchorState::Voting {
+            return VoteOutcome::Pending
+        }
+        anchor.next_outcome(max_attempts)
+    }
+
+    /// Confirms the hash of a header of the validated chain.
+    ///
+    /// Returns [None] if there's no anchor at the block number, otherwise removes the anchor and
+    /// returns the peers that voted for another hash.
+    pub(crate) fn confirm(&mut self, number: BlockNumber, hash: B256) -> Option<Confirmation> {
+        let anchor = self.anchors.remove(&number)?;
+        let dissenters = anchor
+            .votes
+            .into_iter()
+            .filter(|(_, vote)| *vote != hash)
+            .map(|(peer_id, _)| peer_id)
+            .collect();
+        let detached = matches!(anchor.state, AnchorState::Agreed(agreed) if agreed != hash);
+        Some(Confirmation { dissenters, detached })
+    }
+
+    /// Removes all anchors.
+    pub(crate) fn clear(&mut self) {
+        self.anchors.clear();
+    }
+}
+
+/// The outcome of a vote on an anchor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum VoteOutcome {
+    /// The anchor is undecided, or already decided, and no further vote has to be requested.
+    Pending,
+    /// The anchor is undecided and another vote has to be requested.
+    Retry,
+    /// The peers agreed on the hash of the anchor.
+    Agreed(B256),
+    /// The peers didn't agree on the anchor within the maximum number of votes.
+    ///
+    /// Responses at the anchor are then only checked by the validation of the chain.
+    Unresolved,
+}
+
+/// The result of [Skeleton::confirm].
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct Confirmation {
+    /// The peers that voted for another hash than the one of the validated chain.
+    pub(crate) dissenters: Vec<PeerId>,
+    /// Whether the peers agreed on another hash, in which case the skeleton below the anchor
+    /// doesn't attach to the validated chain.
+    pub(crate) detached: bool,
+}
+
+/// An anchor of the [Skeleton].
+#[derive(Debug, Default)]
+struct Anchor {
+    /// The hash returned by each peer.
+    votes: HashMap<PeerId, B256>,
+    /// Number of requested votes.
+    attempts: usize,
+    /// Number of requested votes without response.
+    outstanding: usize,
+    /// Whether the anchor is decided.
+    state: AnchorState,
+}
+
+impl Anchor {
+    /// Returns the outcome of an undecided vote.
+    fn next_outcome(&mut self, max_attempts: usize) -> VoteOutcome {
+        if self.outstanding > 0 {
+            VoteOutcome::Pending
+        } else if self.attempts < max_attempts {
+            VoteOutcome::Retry
+        } else {
+            self.state = AnchorState::Unresolved;
+            VoteOutcome::Unresolved
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+enum AnchorState {
+    #[default]
+    Voting,
+    Agreed(B256),
+    Unresolved,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request_votes(skeleton: &mut Skeleton, number: BlockNumber, votes: usize) {
+        for _ in 0..votes {
+            skeleton.on_vote_requested(number);
+        }
+    }
+
+    #[test]
+    fn agrees_on_quorum() {
+        let mut skeleton = Skeleton::new(2);
+        assert!(skeleton.insert(10));
+        assert!(!skeleton.insert(10));
+        request_votes(&mut skeleton, 10, 2);
+
+        let (peer1, peer2) = (PeerId::random(), PeerId::random());
+        let hash = B256::random();
+        assert_eq!(skeleton.on_vote(10, peer1, hash), VoteOutcome::Pending);
+        // a second vote of the same peer doesn't count
+        request_votes(&mut skeleton, 10, 1);
+        assert_eq!(skeleton.on_vote(10, peer1, hash), VoteOutcome::Pending);
+        assert_eq!(skeleton.agreed(10), None);
+        assert_eq!(skeleton.on_vote(10, peer2, hash), VoteOutcome::Agreed(hash));
+        assert_eq!(skeleton.agreed(10), Some(hash));
+
+        let confirmation = skeleton.confirm(10, hash).unwrap();
+        assert_eq!(confirmation, Confirmation { dissenters: vec![], detached: false });
+        assert_eq!(skeleton.confirm(10, hash), None);
+    }
+
+    #[test]
+    fn contested_anchor() {
+        let mut skeleton = Skeleton::new(2);
+        skeleton.insert(10);
+        request_votes(&mut skeleton, 10, 2);
+
+        let (honest1, honest2, malicious) = (PeerId::random(), PeerId::random(), PeerId::random());
+        let (hash, bad_hash) = (B256::random(), B256::random());
+        assert_eq!(skeleton.on_vote(10, honest1, hash), VoteOutcome::Pending);
+        assert_eq!(skeleton.on_vote(10, malicious, bad_hash), VoteOutcome::Retry);
+        request_votes(&mut skeleton, 10, 1);
+        assert_eq!(skeleton.on_vote(10, honest2, hash), VoteOutcome::Agreed(hash));
+
+        let confirmation = skeleton.confirm(10, hash).unwrap();
+        assert_eq!(confirmation, Confirmation { dissenters: vec![malicious], detached: false });
+    }
+
+    #[test]
+    fn unresolved_anchor() {
+        let mut skeleton = Skeleton::new(2);
+        skeleton.insert(10);
+        request_votes(&mut skeleton, 10, 2);
+        assert_eq!(skeleton.on_failed_vote(10), VoteOutcome::Pending);
+        assert_eq!(skeleton.on_vote(10, PeerId::random(), B256::random()), VoteOutcome::Retry);
+        request_votes(&mut skeleton, 10, 2);
+        assert_eq!(skeleton.on_failed_vote(10), VoteOutcome::Pending);
+        assert_eq!(skeleton.on_vote(10, PeerId::random(), B256::random()), VoteOutcome::Unresolved);
+
+        // late votes are ignored
+        assert_eq!(skeleton.on_vote(10, PeerId::random(), B256::random()), VoteOutcome::Pending);
+        assert_eq!(skeleton.agreed(10), None);
+    }
+
+    #[test]
+    fn detached_skeleton() {
+        let mut skeleton = Skeleton::new(2);
+        skeleton.insert(10);
+        request_votes(&mut skeleton, 10, 2);
+
+        let (peer1, peer2) = (PeerId::random(), PeerId::random());
+        let bad_hash = B256::random();
+        skeleton.on_vote(10, peer1, bad_hash);
+        assert_eq!(skeleton.on_vote(10, peer2, bad_hash), VoteOutcome::Agreed(bad_hash));
+
+        let mut confirmation = skeleton.confirm(10, B256::random()).unwrap();
+        confirmation.dissenters.sort();
+        let mut dissenters = vec![peer1, peer2];
+        dissenters.sort();
+        assert_eq!(confirmation, Confirmation { dissenters, detached: true });
+    }
+}
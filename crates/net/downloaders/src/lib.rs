@@ -31,5 +31,8 @@ pub mod file_client;
 /// Enables decoding and encoding `Block` types within file contexts.
 pub mod file_codec;
 
+/// Reading and writing of era1 archive files.
+pub mod era;
+
 #[cfg(any(test, feature = "test-utils"))]
 pub mod test_utils;
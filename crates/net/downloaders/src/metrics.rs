@@ -120,6 +120,10 @@ pub struct HeaderDownloaderMetrics {
     pub validation_errors: Counter,
     /// Number of unexpected errors while requesting items
     pub unexpected_errors: Counter,
+    /// Number of skeleton anchors the peers didn't agree on
+    pub unresolved_anchors: Counter,
+    /// Number of times the skeleton didn't attach to the validated chain and was discarded
+    pub detached_skeletons: Counter,
 }
 
 impl HeaderDownloaderMetrics {
@@ -82,6 +82,11 @@ impl Capability {
         Self::eth(EthVersion::Eth68)
     }
 
+    /// Returns the [EthVersion::Eth69] capability.
+    pub const fn eth_69() -> Self {
+        Self::eth(EthVersion::Eth69)
+    }
+
     /// Whether this is eth v66 protocol.
     #[inline]
     pub fn is_eth_v66(&self) -> bool {
@@ -100,10 +105,25 @@ impl Capability {
         self.name == "eth" && self.version == 68
     }
 
+    /// Whether this is eth v69.
+    #[inline]
+    pub fn is_eth_v69(&self) -> bool {
+        self.name == "eth" && self.version == 69
+    }
+
     /// Whether this is any eth version.
     #[inline]
     pub fn is_eth(&self) -> bool {
-        self.is_eth_v66() || self.is_eth_v67() || self.is_eth_v68()
+        self.eth_version().is_some()
+    }
+
+    /// Returns the eth version if this is a supported eth version.
+    #[inline]
+    pub fn eth_version(&self) -> Option<EthVersion> {
+        if self.name != "eth" {
+            return None
+        }
+        EthVersion::try_from(u8::try_from(self.version).ok()?).ok()
     }
 }
 
@@ -153,6 +173,7 @@ pub struct Capabilities {
     eth_66: bool,
     eth_67: bool,
     eth_68: bool,
+    eth_69: bool,
 }
 
 impl Capabilities {
@@ -171,7 +192,7 @@ impl Capabilities {
     /// Whether the peer supports `eth` sub-protocol.
     #[inline]
     pub fn supports_eth(&self) -> bool {
-        self.eth_68 || self.eth_67 || self.eth_66
+        self.eth_69 || self.eth_68 || self.eth_67 || self.eth_66
     }
 
     /// Whether this peer supports eth v66 protocol.
@@ -191,6 +212,12 @@ impl Capabilities {
     pub fn supports_eth_v68(&self) -> bool {
         self.eth_68
     }
+
+    /// Whether this peer supports eth v69 protocol.
+    #[inline]
+    pub fn supports_eth_v69(&self) -> bool {
+        self.eth_69
+    }
 }
 
 impl From<Vec<Capability>> for Capabilities {
@@ -199,6 +226,7 @@ impl From<Vec<Capability>> for Capabilities {
             eth_66: value.iter().any(Capability::is_eth_v66),
             eth_67: value.iter().any(Capability::is_eth_v67),
             eth_68: value.iter().any(Capability::is_eth_v68),
+            eth_69: value.iter().any(Capability::is_eth_v69),
             inner: value,
         }
     }
@@ -218,6 +246,7 @@ impl Decodable for Capabilities {
             eth_66: inner.iter().any(Capability::is_eth_v66),
             eth_67: inner.iter().any(Capability::is_eth_v67),
             eth_68: inner.iter().any(Capability::is_eth_v68),
+            eth_69: inner.iter().any(Capability::is_eth_v69),
             inner,
         })
     }
@@ -345,7 +374,7 @@ impl SharedCapability {
     /// Returns the number of protocol messages supported by this capability.
     pub fn num_messages(&self) -> u8 {
         match self {
-            SharedCapability::Eth { version: _version, .. } => EthMessageID::max() + 1,
+            SharedCapability::Eth { version, .. } => EthMessageID::max(*version) + 1,
             SharedCapability::UnknownCapability { messages, .. } => *messages,
         }
     }
@@ -620,6 +649,7 @@ mod tests {
             Capability::new_static("eth", 66),
             Capability::new_static("eth", 67),
             Capability::new_static("eth", 68),
+            Capability::new_static("eth", 69),
         ]
         .into();
 
@@ -627,6 +657,7 @@ mod tests {
         assert!(capabilities.supports_eth_v66());
         assert!(capabilities.supports_eth_v67());
         assert!(capabilities.supports_eth_v68());
+        assert!(capabilities.supports_eth_v69());
     }
 
     #[test]
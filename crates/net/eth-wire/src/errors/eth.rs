@@ -31,6 +31,14 @@ pub enum EthStreamError {
     #[error("message size ({0}) exceeds max length (10MB)")]
     /// Received a message whose size exceeds the standard limit.
     MessageTooBig(usize),
+    #[error("invalid block range update: earliest {earliest}, latest {latest}")]
+    /// Received a `BlockRangeUpdate` whose earliest block is after the latest block.
+    InvalidBlockRangeUpdate {
+        /// The earliest block the peer can serve.
+        earliest: u64,
+        /// The latest block the peer can serve.
+        latest: u64,
+    },
     #[error("TransactionHashes invalid len of fields: hashes_len={hashes_len} types_len={types_len} sizes_len={sizes_len}")]
     /// Received malformed transaction hashes message with discrepancies in field lengths.
     TransactionHashesInvalidLenOfFields {
@@ -111,4 +119,12 @@ pub enum EthHandshakeError {
         /// The maximum allowed bit length for the total difficulty.
         maximum: usize,
     },
+    #[error("invalid block range in status message: earliest {earliest}, latest {latest}")]
+    /// The earliest block of the `eth/69` status is after the latest block.
+    InvalidBlockRange {
+        /// The earliest block the peer can serve.
+        earliest: u64,
+        /// The latest block the peer can serve.
+        latest: u64,
+    },
 }
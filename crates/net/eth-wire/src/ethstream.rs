@@ -1,8 +1,8 @@
 use crate::{
     errors::{EthHandshakeError, EthStreamError},
-    message::{EthBroadcastMessage, ProtocolBroadcastMessage},
-    types::{EthMessage, ProtocolMessage, Status},
-    CanDisconnect, DisconnectReason, EthVersion,
+    message::{EthBroadcastMessage, ProtocolBroadcastMessage, RequestPair},
+    types::{EthMessage, ProtocolMessage, Status, StatusEth69},
+    BlockRangeUpdate, CanDisconnect, DisconnectReason, EthVersion, Receipts, Receipts69,
 };
 use alloy_rlp::Encodable;
 use futures::{ready, Sink, SinkExt, StreamExt};
@@ -51,9 +51,15 @@ where
     /// Consumes the [`UnauthedEthStream`] and returns an [`EthStream`] after the `Status`
     /// handshake is completed successfully. This also returns the `Status` message sent by the
     /// remote peer.
+    ///
+    /// If `eth/69` is negotiated, the given range of blocks is announced in the status message
+    /// instead of the total difficulty, otherwise it's ignored. Since the `eth/69` status doesn't
+    /// contain the total difficulty, the returned `Status` of an `eth/69` peer has a total
+    /// difficulty of zero and the latest block of the peer's range as best block.
     pub async fn handshake(
         mut self,
         status: Status,
+        block_range: BlockRangeUpdate,
         fork_filter: ForkFilter,
     ) -> Result<(EthStream<S>, Status), EthStreamError> {
         trace!(
@@ -61,10 +67,17 @@ where
             "sending eth status to peer"
         );
 
+        let version = EthVersion::try_from(status.version)?;
+        let our_status = if version >= EthVersion::Eth69 {
+            EthMessage::Status69(StatusEth69::new(status, block_range))
+        } else {
+            EthMessage::Status(status)
+        };
+
         // we need to encode and decode here on our own because we don't have an `EthStream` yet
         // The max length for a status with TTD is: <msg id = 1 byte> + <rlp(status) = 88 byte>
         let mut our_status_bytes = BytesMut::with_capacity(1 + 88);
-        ProtocolMessage::from(EthMessage::Status(status)).encode(&mut our_status_bytes);
+        ProtocolMessage::from(our_status).encode(&mut our_status_bytes);
         let our_status_bytes = our_status_bytes.freeze();
         self.inner.send(our_status_bytes).await?;
 
@@ -83,7 +96,6 @@ where
             return Err(EthStreamError::MessageTooBig(their_msg.len()))
         }
 
-        let version = EthVersion::try_from(status.version)?;
         let msg = match ProtocolMessage::decode_message(version, &mut their_msg.as_ref()) {
             Ok(m) => m,
             Err(err) => {
@@ -151,6 +163,57 @@ where
 
                 Ok((stream, resp))
             }
+            EthMessage::Status69(resp) => {
+                trace!(
+                    status=?resp,
+                    "validating incoming eth/69 status from peer"
+                );
+                if status.genesis != resp.genesis {
+                    self.inner.disconnect(DisconnectReason::ProtocolBreach).await?;
+                    return Err(EthHandshakeError::MismatchedGenesis(
+                        GotExpected { expected: status.genesis, got: resp.genesis }.into(),
+                    )
+                    .into())
+                }
+
+                if status.version != resp.version {
+                    self.inner.disconnect(DisconnectReason::ProtocolBreach).await?;
+                    return Err(EthHandshakeError::MismatchedProtocolVersion(GotExpected {
+                        got: resp.version,
+                        expected: status.version,
+                    })
+                    .into())
+                }
+
+                if status.chain != resp.chain {
+                    self.inner.disconnect(DisconnectReason::ProtocolBreach).await?;
+                    return Err(EthHandshakeError::MismatchedChain(GotExpected {
+                        got: resp.chain,
+                        expected: status.chain,
+                    })
+                    .into())
+                }
+
+                if !resp.block_range().is_valid() {
+                    self.inner.disconnect(DisconnectReason::ProtocolBreach).await?;
+                    return Err(EthHandshakeError::InvalidBlockRange {
+                        earliest: resp.earliest,
+                        latest: resp.latest,
+                    }
+                    .into())
+                }
+
+                if let Err(err) =
+                    fork_filter.validate(resp.forkid).map_err(EthHandshakeError::InvalidFork)
+                {
+                    self.inner.disconnect(DisconnectReason::ProtocolBreach).await?;
+                    return Err(err.into())
+                }
+
+                let stream = EthStream::new(version, self.inner);
+
+                Ok((stream, resp.into()))
+            }
             _ => {
                 self.inner.disconnect(DisconnectReason::ProtocolBreach).await?;
                 Err(EthStreamError::EthHandshakeError(
@@ -262,13 +325,20 @@ where
             }
         };
 
-        if matches!(msg.message, EthMessage::Status(_)) {
-            return Poll::Ready(Some(Err(EthStreamError::EthHandshakeError(
-                EthHandshakeError::StatusNotInHandshake,
-            ))))
+        match msg.message {
+            EthMessage::Status(_) | EthMessage::Status69(_) => Poll::Ready(Some(Err(
+                EthStreamError::EthHandshakeError(EthHandshakeError::StatusNotInHandshake),
+            ))),
+            // the bloom filters are computed here so that the receipts are the same regardless of
+            // the version
+            EthMessage::Receipts69(resp) => {
+                Poll::Ready(Some(Ok(EthMessage::Receipts(RequestPair {
+                    request_id: resp.request_id,
+                    message: Receipts::from(resp.message),
+                }))))
+            }
+            message => Poll::Ready(Some(Ok(message))),
         }
-
-        Poll::Ready(Some(Ok(msg.message)))
     }
 }
 
@@ -284,7 +354,7 @@ where
     }
 
     fn start_send(self: Pin<&mut Self>, item: EthMessage) -> Result<(), Self::Error> {
-        if matches!(item, EthMessage::Status(_)) {
+        if matches!(item, EthMessage::Status(_) | EthMessage::Status69(_)) {
             // TODO: to disconnect here we would need to do something similar to P2PStream's
             // start_disconnect, which would ideally be a part of the CanDisconnect trait, or at
             // least similar.
@@ -297,6 +367,16 @@ where
             return Err(EthStreamError::EthHandshakeError(EthHandshakeError::StatusNotInHandshake))
         }
 
+        let item = match item {
+            EthMessage::Receipts(resp) if self.version >= EthVersion::Eth69 => {
+                EthMessage::Receipts69(RequestPair {
+                    request_id: resp.request_id,
+                    message: Receipts69::from(resp.message),
+                })
+            }
+            item => item,
+        };
+
         let mut bytes = BytesMut::new();
         ProtocolMessage::from(item).encode(&mut bytes);
         let bytes = bytes.freeze();
@@ -333,7 +413,7 @@ mod tests {
         errors::{EthHandshakeError, EthStreamError},
         p2pstream::{ProtocolVersion, UnauthedP2PStream},
         types::{broadcast::BlockHashNumber, EthMessage, EthVersion, Status},
-        EthStream, HelloMessageWithProtocols, PassthroughCodec,
+        BlockRangeUpdate, EthStream, HelloMessageWithProtocols, PassthroughCodec,
     };
     use alloy_chains::NamedChain;
     use futures::{SinkExt, StreamExt};
@@ -369,7 +449,7 @@ mod tests {
             let (incoming, _) = listener.accept().await.unwrap();
             let stream = PassthroughCodec::default().framed(incoming);
             let (_, their_status) = UnauthedEthStream::new(stream)
-                .handshake(status_clone, fork_filter_clone)
+                .handshake(status_clone, BlockRangeUpdate::default(), fork_filter_clone)
                 .await
                 .unwrap();
 
@@ -381,8 +461,10 @@ mod tests {
         let sink = PassthroughCodec::default().framed(outgoing);
 
         // try to connect
-        let (_, their_status) =
-            UnauthedEthStream::new(sink).handshake(status, fork_filter).await.unwrap();
+        let (_, their_status) = UnauthedEthStream::new(sink)
+            .handshake(status, BlockRangeUpdate::default(), fork_filter)
+            .await
+            .unwrap();
 
         // their status is a clone of our status, these should be equal
         assert_eq!(their_status, status);
@@ -416,7 +498,7 @@ mod tests {
             let (incoming, _) = listener.accept().await.unwrap();
             let stream = PassthroughCodec::default().framed(incoming);
             let (_, their_status) = UnauthedEthStream::new(stream)
-                .handshake(status_clone, fork_filter_clone)
+                .handshake(status_clone, BlockRangeUpdate::default(), fork_filter_clone)
                 .await
                 .unwrap();
 
@@ -428,8 +510,10 @@ mod tests {
         let sink = PassthroughCodec::default().framed(outgoing);
 
         // try to connect
-        let (_, their_status) =
-            UnauthedEthStream::new(sink).handshake(status, fork_filter).await.unwrap();
+        let (_, their_status) = UnauthedEthStream::new(sink)
+            .handshake(status, BlockRangeUpdate::default(), fork_filter)
+            .await
+            .unwrap();
 
         // their status is a clone of our status, these should be equal
         assert_eq!(their_status, status);
@@ -462,8 +546,9 @@ mod tests {
             // roughly based off of the design of tokio::net::TcpListener
             let (incoming, _) = listener.accept().await.unwrap();
             let stream = PassthroughCodec::default().framed(incoming);
-            let handshake_res =
-                UnauthedEthStream::new(stream).handshake(status_clone, fork_filter_clone).await;
+            let handshake_res = UnauthedEthStream::new(stream)
+                .handshake(status_clone, BlockRangeUpdate::default(), fork_filter_clone)
+                .await;
 
             // make sure the handshake fails due to td too high
             assert!(matches!(
@@ -478,7 +563,9 @@ mod tests {
         let sink = PassthroughCodec::default().framed(outgoing);
 
         // try to connect
-        let handshake_res = UnauthedEthStream::new(sink).handshake(status, fork_filter).await;
+        let handshake_res = UnauthedEthStream::new(sink)
+            .handshake(status, BlockRangeUpdate::default(), fork_filter)
+            .await;
 
         // this handshake should also fail due to td too high
         assert!(matches!(
@@ -613,7 +700,7 @@ mod tests {
             let unauthed_stream = UnauthedP2PStream::new(stream);
             let (p2p_stream, _) = unauthed_stream.handshake(server_hello).await.unwrap();
             let (mut eth_stream, _) = UnauthedEthStream::new(p2p_stream)
-                .handshake(status_copy, fork_filter_clone)
+                .handshake(status_copy, BlockRangeUpdate::default(), fork_filter_clone)
                 .await
                 .unwrap();
 
@@ -641,8 +728,10 @@ mod tests {
         let unauthed_stream = UnauthedP2PStream::new(sink);
         let (p2p_stream, _) = unauthed_stream.handshake(client_hello).await.unwrap();
 
-        let (mut client_stream, _) =
-            UnauthedEthStream::new(p2p_stream).handshake(status, fork_filter).await.unwrap();
+        let (mut client_stream, _) = UnauthedEthStream::new(p2p_stream)
+            .handshake(status, BlockRangeUpdate::default(), fork_filter)
+            .await
+            .unwrap();
 
         client_stream.send(test_msg).await.unwrap();
 
@@ -19,7 +19,8 @@ use std::{
 use crate::{
     capability::{Capability, SharedCapabilities, SharedCapability, UnsupportedCapabilityError},
     errors::{EthStreamError, P2PStreamError},
-    CanDisconnect, DisconnectReason, EthStream, P2PStream, Status, UnauthedEthStream,
+    BlockRangeUpdate, CanDisconnect, DisconnectReason, EthStream, P2PStream, Status,
+    UnauthedEthStream,
 };
 use bytes::{Bytes, BytesMut};
 use futures::{pin_mut, Sink, SinkExt, Stream, StreamExt, TryStream, TryStreamExt};
@@ -200,9 +201,13 @@ impl<St> RlpxProtocolMultiplexer<St> {
 
     /// Converts this multiplexer into a [RlpxSatelliteStream] with eth protocol as the given
     /// primary protocol.
+    ///
+    /// The given range of blocks is announced if `eth/69` is negotiated, see
+    /// [UnauthedEthStream::handshake].
     pub async fn into_eth_satellite_stream(
         self,
         status: Status,
+        block_range: BlockRangeUpdate,
         fork_filter: ForkFilter,
     ) -> Result<(RlpxSatelliteStream<St, EthStream<ProtocolProxy>>, Status), EthStreamError>
     where
@@ -212,7 +217,7 @@ impl<St> RlpxProtocolMultiplexer<St> {
         self.into_satellite_stream_with_tuple_handshake(
             &Capability::eth(eth_cap),
             move |proxy| async move {
-                UnauthedEthStream::new(proxy).handshake(status, fork_filter).await
+                UnauthedEthStream::new(proxy).handshake(status, block_range, fork_filter).await
             },
         )
         .await
@@ -664,7 +669,7 @@ mod tests {
                 UnauthedP2PStream::new(stream).handshake(server_hello).await.unwrap();
 
             let (_eth_stream, _) = UnauthedEthStream::new(p2p_stream)
-                .handshake(other_status, other_fork_filter)
+                .handshake(other_status, BlockRangeUpdate::default(), other_fork_filter)
                 .await
                 .unwrap();
 
@@ -679,7 +684,9 @@ mod tests {
             .into_satellite_stream_with_handshake(
                 eth.capability().as_ref(),
                 move |proxy| async move {
-                    UnauthedEthStream::new(proxy).handshake(status, fork_filter).await
+                    UnauthedEthStream::new(proxy)
+                        .handshake(status, BlockRangeUpdate::default(), fork_filter)
+                        .await
                 },
             )
             .await
@@ -702,7 +709,11 @@ mod tests {
             let (conn, _) = UnauthedP2PStream::new(stream).handshake(server_hello).await.unwrap();
 
             let (mut st, _their_status) = RlpxProtocolMultiplexer::new(conn)
-                .into_eth_satellite_stream(other_status, other_fork_filter)
+                .into_eth_satellite_stream(
+                    other_status,
+                    BlockRangeUpdate::default(),
+                    other_fork_filter,
+                )
                 .await
                 .unwrap();
 
@@ -733,7 +744,7 @@ mod tests {
 
         let conn = connect_passthrough(local_addr, test_hello().0).await;
         let (mut st, _their_status) = RlpxProtocolMultiplexer::new(conn)
-            .into_eth_satellite_stream(status, fork_filter)
+            .into_eth_satellite_stream(status, BlockRangeUpdate::default(), fork_filter)
             .await
             .unwrap();
 
@@ -372,8 +372,8 @@ mod tests {
         capability::{Capability, SharedCapabilities},
         muxdemux::MuxDemuxStream,
         protocol::Protocol,
-        EthVersion, HelloMessageWithProtocols, Status, StatusBuilder, StreamClone,
-        UnauthedEthStream, UnauthedP2PStream,
+        BlockRangeUpdate, EthVersion, HelloMessageWithProtocols, Status, StatusBuilder,
+        StreamClone, UnauthedEthStream, UnauthedP2PStream,
     };
 
     const ETH_68_CAP: Capability = Capability::eth(EthVersion::Eth68);
@@ -498,8 +498,10 @@ mod tests {
             MuxDemuxStream::try_new(p2p_stream, main_cap.capability().into_owned(), shared_caps)
                 .expect("should start mxdmx stream");
 
-        let (mut main_stream, _) =
-            UnauthedEthStream::new(proxy_server).handshake(status, fork_filter).await.unwrap();
+        let (mut main_stream, _) = UnauthedEthStream::new(proxy_server)
+            .handshake(status, BlockRangeUpdate::default(), fork_filter)
+            .await
+            .unwrap();
 
         let protocol_proxy =
             main_stream.inner_mut().try_clone_stream(&CUSTOM_CAP).expect("should clone stream");
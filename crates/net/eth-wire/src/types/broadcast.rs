@@ -35,6 +35,28 @@ impl NewBlockHashes {
     }
 }
 
+/// Announces the range of blocks a peer can serve, introduced in `eth/69`.
+///
+/// This is sent by a peer whenever its latest block moved forward by a number of blocks.
+#[derive_arbitrary(rlp)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, RlpEncodable, RlpDecodable, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct BlockRangeUpdate {
+    /// The earliest block the peer can serve.
+    pub earliest: u64,
+    /// The latest block the peer can serve.
+    pub latest: u64,
+    /// The hash of the latest block.
+    pub latest_hash: B256,
+}
+
+impl BlockRangeUpdate {
+    /// Returns true if the range is well-formed, i.e. the earliest block is not after the latest.
+    pub fn is_valid(&self) -> bool {
+        self.earliest <= self.latest
+    }
+}
+
 /// A block hash _and_ a block number.
 #[derive_arbitrary(rlp)]
 #[derive(Clone, Debug, PartialEq, Eq, RlpEncodable, RlpDecodable, Default)]
@@ -131,7 +153,7 @@ impl NewPooledTransactionHashes {
                 matches!(version, EthVersion::Eth67 | EthVersion::Eth66)
             }
             NewPooledTransactionHashes::Eth68(_) => {
-                matches!(version, EthVersion::Eth68)
+                matches!(version, EthVersion::Eth68 | EthVersion::Eth69)
             }
         }
     }
@@ -1,9 +1,10 @@
 #![allow(missing_docs)]
 
 use super::{
-    broadcast::NewBlockHashes, BlockBodies, BlockHeaders, GetBlockBodies, GetBlockHeaders,
-    GetNodeData, GetPooledTransactions, GetReceipts, NewBlock, NewPooledTransactionHashes66,
-    NewPooledTransactionHashes68, NodeData, PooledTransactions, Receipts, Status, Transactions,
+    broadcast::NewBlockHashes, BlockBodies, BlockHeaders, BlockRangeUpdate, GetBlockBodies,
+    GetBlockHeaders, GetNodeData, GetPooledTransactions, GetReceipts, NewBlock,
+    NewPooledTransactionHashes66, NewPooledTransactionHashes68, NodeData, PooledTransactions,
+    Receipts, Receipts69, Status, StatusEth69, Transactions,
 };
 use crate::{errors::EthStreamError, EthVersion, SharedTransactions};
 use alloy_rlp::{length_of_length, Decodable, Encodable, Header};
@@ -31,11 +32,25 @@ impl ProtocolMessage {
         let message_type = EthMessageID::decode(buf)?;
 
         let message = match message_type {
-            EthMessageID::Status => EthMessage::Status(Status::decode(buf)?),
+            EthMessageID::Status => {
+                if version >= EthVersion::Eth69 {
+                    EthMessage::Status69(StatusEth69::decode(buf)?)
+                } else {
+                    EthMessage::Status(Status::decode(buf)?)
+                }
+            }
             EthMessageID::NewBlockHashes => {
+                if version >= EthVersion::Eth69 {
+                    return Err(EthStreamError::EthInvalidMessageError(version, message_type))
+                }
                 EthMessage::NewBlockHashes(NewBlockHashes::decode(buf)?)
             }
-            EthMessageID::NewBlock => EthMessage::NewBlock(Box::new(NewBlock::decode(buf)?)),
+            EthMessageID::NewBlock => {
+                if version >= EthVersion::Eth69 {
+                    return Err(EthStreamError::EthInvalidMessageError(version, message_type))
+                }
+                EthMessage::NewBlock(Box::new(NewBlock::decode(buf)?))
+            }
             EthMessageID::Transactions => EthMessage::Transactions(Transactions::decode(buf)?),
             EthMessageID::NewPooledTransactionHashes => {
                 if version >= EthVersion::Eth68 {
@@ -97,8 +112,17 @@ impl ProtocolMessage {
                 EthMessage::GetReceipts(request_pair)
             }
             EthMessageID::Receipts => {
-                let request_pair = RequestPair::<Receipts>::decode(buf)?;
-                EthMessage::Receipts(request_pair)
+                if version >= EthVersion::Eth69 {
+                    EthMessage::Receipts69(RequestPair::<Receipts69>::decode(buf)?)
+                } else {
+                    EthMessage::Receipts(RequestPair::<Receipts>::decode(buf)?)
+                }
+            }
+            EthMessageID::BlockRangeUpdate => {
+                if version < EthVersion::Eth69 {
+                    return Err(EthStreamError::EthInvalidMessageError(version, message_type))
+                }
+                EthMessage::BlockRangeUpdate(BlockRangeUpdate::decode(buf)?)
             }
         };
         Ok(ProtocolMessage { message_type, message })
@@ -148,7 +172,7 @@ impl From<EthBroadcastMessage> for ProtocolBroadcastMessage {
     }
 }
 
-/// Represents a message in the eth wire protocol, versions 66, 67 and 68.
+/// Represents a message in the eth wire protocol, versions 66, 67, 68 and 69.
 ///
 /// The ethereum wire protocol is a set of messages that are broadcast to the network in two
 /// styles:
@@ -165,17 +189,24 @@ impl From<EthBroadcastMessage> for ProtocolBroadcastMessage {
 /// The `eth/68` changes only NewPooledTransactionHashes to include `types` and `sized`. For
 /// it, NewPooledTransactionHashes is renamed as [`NewPooledTransactionHashes66`] and
 /// [`NewPooledTransactionHashes68`] is defined.
+///
+/// The `eth/69` removes the total difficulty from the [`Status`] (see [`StatusEth69`]), removes
+/// [`NewBlock`] and [`NewBlockHashes`], drops the bloom filter from the [`Receipts`] (see
+/// [`Receipts69`]) and introduces [`BlockRangeUpdate`].
 #[derive(Clone, Debug, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum EthMessage {
     /// Status is required for the protocol handshake
     Status(Status),
+    /// The `eth/69` status message
+    Status69(StatusEth69),
     /// The following messages are broadcast to the network
     NewBlockHashes(NewBlockHashes),
     NewBlock(Box<NewBlock>),
     Transactions(Transactions),
     NewPooledTransactionHashes66(NewPooledTransactionHashes66),
     NewPooledTransactionHashes68(NewPooledTransactionHashes68),
+    BlockRangeUpdate(BlockRangeUpdate),
 
     // The following messages are request-response message pairs
     GetBlockHeaders(RequestPair<GetBlockHeaders>),
@@ -188,13 +219,14 @@ pub enum EthMessage {
     NodeData(RequestPair<NodeData>),
     GetReceipts(RequestPair<GetReceipts>),
     Receipts(RequestPair<Receipts>),
+    Receipts69(RequestPair<Receipts69>),
 }
 
 impl EthMessage {
     /// Returns the message's ID.
     pub fn message_id(&self) -> EthMessageID {
         match self {
-            EthMessage::Status(_) => EthMessageID::Status,
+            EthMessage::Status(_) | EthMessage::Status69(_) => EthMessageID::Status,
             EthMessage::NewBlockHashes(_) => EthMessageID::NewBlockHashes,
             EthMessage::NewBlock(_) => EthMessageID::NewBlock,
             EthMessage::Transactions(_) => EthMessageID::Transactions,
@@ -209,7 +241,8 @@ impl EthMessage {
             EthMessage::GetNodeData(_) => EthMessageID::GetNodeData,
             EthMessage::NodeData(_) => EthMessageID::NodeData,
             EthMessage::GetReceipts(_) => EthMessageID::GetReceipts,
-            EthMessage::Receipts(_) => EthMessageID::Receipts,
+            EthMessage::Receipts(_) | EthMessage::Receipts69(_) => EthMessageID::Receipts,
+            EthMessage::BlockRangeUpdate(_) => EthMessageID::BlockRangeUpdate,
         }
     }
 }
@@ -218,6 +251,7 @@ impl Encodable for EthMessage {
     fn encode(&self, out: &mut dyn BufMut) {
         match self {
             EthMessage::Status(status) => status.encode(out),
+            EthMessage::Status69(status) => status.encode(out),
             EthMessage::NewBlockHashes(new_block_hashes) => new_block_hashes.encode(out),
             EthMessage::NewBlock(new_block) => new_block.encode(out),
             EthMessage::Transactions(transactions) => transactions.encode(out),
@@ -233,11 +267,14 @@ impl Encodable for EthMessage {
             EthMessage::NodeData(data) => data.encode(out),
             EthMessage::GetReceipts(request) => request.encode(out),
             EthMessage::Receipts(receipts) => receipts.encode(out),
+            EthMessage::Receipts69(receipts) => receipts.encode(out),
+            EthMessage::BlockRangeUpdate(block_range) => block_range.encode(out),
         }
     }
     fn length(&self) -> usize {
         match self {
             EthMessage::Status(status) => status.length(),
+            EthMessage::Status69(status) => status.length(),
             EthMessage::NewBlockHashes(new_block_hashes) => new_block_hashes.length(),
             EthMessage::NewBlock(new_block) => new_block.length(),
             EthMessage::Transactions(transactions) => transactions.length(),
@@ -253,6 +290,8 @@ impl Encodable for EthMessage {
             EthMessage::NodeData(data) => data.length(),
             EthMessage::GetReceipts(request) => request.length(),
             EthMessage::Receipts(receipts) => receipts.length(),
+            EthMessage::Receipts69(receipts) => receipts.length(),
+            EthMessage::BlockRangeUpdate(block_range) => block_range.length(),
         }
     }
 }
@@ -318,12 +357,17 @@ pub enum EthMessageID {
     NodeData = 0x0e,
     GetReceipts = 0x0f,
     Receipts = 0x10,
+    BlockRangeUpdate = 0x11,
 }
 
 impl EthMessageID {
-    /// Returns the max value.
-    pub const fn max() -> u8 {
-        Self::Receipts as u8
+    /// Returns the max message ID of the given version.
+    pub const fn max(version: EthVersion) -> u8 {
+        if version as u8 >= EthVersion::Eth69 as u8 {
+            Self::BlockRangeUpdate as u8
+        } else {
+            Self::Receipts as u8
+        }
     }
 }
 
@@ -355,6 +399,7 @@ impl Decodable for EthMessageID {
             0x0e => EthMessageID::NodeData,
             0x0f => EthMessageID::GetReceipts,
             0x10 => EthMessageID::Receipts,
+            0x11 => EthMessageID::BlockRangeUpdate,
             _ => return Err(alloy_rlp::Error::Custom("Invalid message ID")),
         };
         buf.advance(1);
@@ -382,6 +427,7 @@ impl TryFrom<usize> for EthMessageID {
             0x0e => Ok(EthMessageID::NodeData),
             0x0f => Ok(EthMessageID::GetReceipts),
             0x10 => Ok(EthMessageID::Receipts),
+            0x11 => Ok(EthMessageID::BlockRangeUpdate),
             _ => Err("Invalid message ID"),
         }
     }
@@ -437,8 +483,8 @@ where
 #[cfg(test)]
 mod tests {
     use crate::{
-        errors::EthStreamError, types::message::RequestPair, EthMessage, EthMessageID, GetNodeData,
-        NodeData, ProtocolMessage,
+        errors::EthStreamError, types::message::RequestPair, BlockRangeUpdate, EthMessage,
+        EthMessageID, EthVersion, GetNodeData, NewBlockHashes, NodeData, ProtocolMessage,
     };
     use alloy_rlp::{Decodable, Encodable};
     use reth_primitives::hex;
@@ -468,6 +514,30 @@ mod tests {
         assert!(matches!(msg, Err(EthStreamError::EthInvalidMessageError(..))));
     }
 
+    #[test]
+    fn test_eth69_messages() {
+        let new_block_hashes = EthMessage::NewBlockHashes(NewBlockHashes(vec![]));
+        let buf = encode(ProtocolMessage::from(new_block_hashes.clone()));
+        let msg = ProtocolMessage::decode_message(EthVersion::Eth69, &mut &buf[..]);
+        assert!(matches!(msg, Err(EthStreamError::EthInvalidMessageError(..))));
+        let msg = ProtocolMessage::decode_message(EthVersion::Eth68, &mut &buf[..]).unwrap();
+        assert_eq!(msg.message, new_block_hashes);
+
+        let block_range = EthMessage::BlockRangeUpdate(BlockRangeUpdate {
+            earliest: 0,
+            latest: 1337,
+            latest_hash: Default::default(),
+        });
+        let buf = encode(ProtocolMessage::from(block_range.clone()));
+        let msg = ProtocolMessage::decode_message(EthVersion::Eth68, &mut &buf[..]);
+        assert!(matches!(msg, Err(EthStreamError::EthInvalidMessageError(..))));
+        let msg = ProtocolMessage::decode_message(EthVersion::Eth69, &mut &buf[..]).unwrap();
+        assert_eq!(msg.message, block_range);
+
+        assert_eq!(EthMessageID::max(EthVersion::Eth68), EthMessageID::Receipts as u8);
+        assert_eq!(EthMessageID::max(EthVersion::Eth69), EthMessageID::BlockRangeUpdate as u8);
+    }
+
     #[test]
     fn request_pair_encode() {
         let request_pair = RequestPair { request_id: 1337, message: vec![5u8] };
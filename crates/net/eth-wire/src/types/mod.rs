@@ -1,7 +1,7 @@
 //! Types for the eth wire protocol.
 
 mod status;
-pub use status::Status;
+pub use status::{Status, StatusEth69};
 
 pub mod version;
 pub use version::EthVersion;
@@ -23,3 +23,6 @@ pub use state::*;
 
 pub mod receipts;
 pub use receipts::*;
+
+pub mod snap;
+pub use snap::{SnapMessageId, SnapProtocolMessage};
@@ -1,8 +1,8 @@
 //! Implements the `GetReceipts` and `Receipts` message types.
 
-use alloy_rlp::{RlpDecodableWrapper, RlpEncodableWrapper};
+use alloy_rlp::{Decodable, Encodable, Header, RlpDecodableWrapper, RlpEncodableWrapper};
 use reth_codecs::derive_arbitrary;
-use reth_primitives::{ReceiptWithBloom, B256};
+use reth_primitives::{bytes::BufMut, Log, Receipt, ReceiptWithBloom, TxType, B256};
 
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
@@ -32,11 +32,160 @@ pub struct Receipts(
     pub Vec<Vec<ReceiptWithBloom>>,
 );
 
+/// The `eth/69` response to [`GetReceipts`].
+///
+/// Since `eth/69` the bloom filter is no longer sent, because it can be computed from the logs.
+/// Each receipt is encoded as the list `[tx-type, post-state-or-status, cumulative-gas, logs]`,
+/// regardless of the transaction type.
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Receipts69(
+    /// Each receipt list should correspond to a block hash in the request.
+    pub Vec<Vec<Receipt>>,
+);
+
+impl Receipts69 {
+    fn encode_receipt(receipt: &Receipt, out: &mut dyn BufMut) {
+        Self::receipt_header(receipt).encode(out);
+        u8::from(receipt.tx_type).encode(out);
+        receipt.success.encode(out);
+        receipt.cumulative_gas_used.encode(out);
+        receipt.logs.encode(out);
+    }
+
+    fn receipt_header(receipt: &Receipt) -> Header {
+        let payload_length = u8::from(receipt.tx_type).length() +
+            receipt.success.length() +
+            receipt.cumulative_gas_used.length() +
+            receipt.logs.length();
+        Header { list: true, payload_length }
+    }
+
+    fn receipt_length(receipt: &Receipt) -> usize {
+        let header = Self::receipt_header(receipt);
+        header.length() + header.payload_length
+    }
+
+    fn decode_receipt(buf: &mut &[u8]) -> alloy_rlp::Result<Receipt> {
+        let mut fields = decode_list_payload(buf)?;
+        let tx_type = match u8::decode(&mut fields)? {
+            0x00 => TxType::Legacy,
+            0x01 => TxType::EIP2930,
+            0x02 => TxType::EIP1559,
+            0x03 => TxType::EIP4844,
+            #[cfg(feature = "optimism")]
+            0x7E => TxType::DEPOSIT,
+            _ => return Err(alloy_rlp::Error::Custom("invalid receipt type")),
+        };
+        let receipt = Receipt {
+            tx_type,
+            success: bool::decode(&mut fields)?,
+            cumulative_gas_used: u64::decode(&mut fields)?,
+            logs: Vec::<Log>::decode(&mut fields)?,
+            #[cfg(feature = "optimism")]
+            deposit_nonce: None,
+            #[cfg(feature = "optimism")]
+            deposit_receipt_version: None,
+        };
+        if !fields.is_empty() {
+            return Err(alloy_rlp::Error::Custom("unexpected fields in receipt"))
+        }
+        Ok(receipt)
+    }
+
+    fn block_header(receipts: &[Receipt]) -> Header {
+        let payload_length = receipts.iter().map(Self::receipt_length).sum();
+        Header { list: true, payload_length }
+    }
+
+    fn payload_length(&self) -> usize {
+        self.0
+            .iter()
+            .map(|receipts| {
+                let header = Self::block_header(receipts);
+                header.length() + header.payload_length
+            })
+            .sum()
+    }
+}
+
+impl Encodable for Receipts69 {
+    fn encode(&self, out: &mut dyn BufMut) {
+        Header { list: true, payload_length: self.payload_length() }.encode(out);
+        for receipts in &self.0 {
+            Self::block_header(receipts).encode(out);
+            for receipt in receipts {
+                Self::encode_receipt(receipt, out);
+            }
+        }
+    }
+
+    fn length(&self) -> usize {
+        let payload_length = self.payload_length();
+        Header { list: true, payload_length }.length() + payload_length
+    }
+}
+
+impl Decodable for Receipts69 {
+    fn decode(buf: &mut &[u8]) -> alloy_rlp::Result<Self> {
+        let mut payload = decode_list_payload(buf)?;
+        let mut blocks = Vec::new();
+        while !payload.is_empty() {
+            let mut block = decode_list_payload(&mut payload)?;
+            let mut receipts = Vec::new();
+            while !block.is_empty() {
+                receipts.push(Self::decode_receipt(&mut block)?);
+            }
+            blocks.push(receipts);
+        }
+        Ok(Self(blocks))
+    }
+}
+
+/// Decodes a list header and returns its payload, advancing the buffer past the list.
+fn decode_list_payload<'a>(buf: &mut &'a [u8]) -> alloy_rlp::Result<&'a [u8]> {
+    let header = Header::decode(buf)?;
+    if !header.list {
+        return Err(alloy_rlp::Error::UnexpectedString)
+    }
+    if buf.len() < header.payload_length {
+        return Err(alloy_rlp::Error::InputTooShort)
+    }
+    let (payload, rest) = buf.split_at(header.payload_length);
+    *buf = rest;
+    Ok(payload)
+}
+
+impl From<Receipts> for Receipts69 {
+    fn from(receipts: Receipts) -> Self {
+        Self(
+            receipts
+                .0
+                .into_iter()
+                .map(|receipts| receipts.into_iter().map(ReceiptWithBloom::into_receipt).collect())
+                .collect(),
+        )
+    }
+}
+
+impl From<Receipts69> for Receipts {
+    /// Computes the bloom filter of every receipt.
+    fn from(receipts: Receipts69) -> Self {
+        Self(
+            receipts
+                .0
+                .into_iter()
+                .map(|receipts| receipts.into_iter().map(ReceiptWithBloom::from).collect())
+                .collect(),
+        )
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{
         types::{message::RequestPair, GetReceipts},
-        Receipts,
+        Receipts, Receipts69,
     };
     use alloy_rlp::{Decodable, Encodable};
     use reth_primitives::{hex, Log, Receipt, ReceiptWithBloom, TxType};
@@ -66,6 +215,39 @@ mod tests {
         assert!(receipts == decoded);
     }
 
+    #[test]
+    fn roundtrip_receipts69() {
+        let receipt = Receipt {
+            tx_type: TxType::EIP4844,
+            success: true,
+            cumulative_gas_used: 21_000,
+            logs: vec![Log {
+                address: hex!("0000000000000000000000000000000000000011").into(),
+                topics: vec![hex!(
+                    "000000000000000000000000000000000000000000000000000000000000dead"
+                )
+                .into()],
+                data: hex!("0100ff")[..].into(),
+            }],
+            #[cfg(feature = "optimism")]
+            deposit_nonce: None,
+            #[cfg(feature = "optimism")]
+            deposit_receipt_version: None,
+        };
+        let receipts = Receipts69(vec![vec![receipt.clone()], vec![]]);
+
+        let mut out = vec![];
+        receipts.encode(&mut out);
+        assert_eq!(out.len(), receipts.length());
+        let decoded = Receipts69::decode(&mut out.as_slice()).unwrap();
+        assert_eq!(decoded, receipts);
+
+        // the bloom filter is computed from the logs
+        let with_bloom = Receipts::from(decoded);
+        assert_eq!(with_bloom.0[0][0], ReceiptWithBloom::from(receipt));
+        assert_eq!(Receipts69::from(with_bloom), receipts);
+    }
+
     #[test]
     // Test vector from: https://eips.ethereum.org/EIPS/eip-2481
     fn encode_get_receipts() {
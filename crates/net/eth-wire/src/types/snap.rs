@@ -0,0 +1,400 @@
+//! Implements the `snap/1` protocol messages.
+//!
+//! See also <https://github.com/ethereum/devp2p/blob/master/caps/snap.md>
+
+use crate::{capability::Capability, protocol::Protocol};
+use alloy_rlp::{Decodable, Encodable, RlpDecodable, RlpEncodable};
+use reth_codecs::derive_arbitrary;
+use reth_primitives::{
+    constants::EMPTY_ROOT_HASH, Account, BufMut, Bytes, BytesMut, B256, KECCAK_EMPTY, U256,
+};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Requests an unknown number of accounts from a given account trie, starting at the specified
+/// account hash and capped by the maximum allowed response size in bytes.
+#[derive_arbitrary(rlp)]
+#[derive(Clone, Debug, PartialEq, Eq, RlpEncodable, RlpDecodable, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct GetAccountRange {
+    /// Request ID to match up responses with.
+    pub request_id: u64,
+    /// Root hash of the account trie to serve.
+    pub root_hash: B256,
+    /// Account hash of the first to retrieve.
+    pub starting_hash: B256,
+    /// Account hash after which to stop serving data.
+    pub limit_hash: B256,
+    /// Soft limit at which to stop returning data.
+    pub response_bytes: u64,
+}
+
+/// The response to [`GetAccountRange`], containing a consecutive range of accounts and the merkle
+/// proofs for the range boundaries.
+#[derive_arbitrary(rlp)]
+#[derive(Clone, Debug, PartialEq, Eq, RlpEncodable, RlpDecodable, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct AccountRange {
+    /// ID of the request this is a response for.
+    pub request_id: u64,
+    /// List of consecutive accounts from the trie.
+    pub accounts: Vec<AccountData>,
+    /// List of trie nodes proving the account range.
+    pub proof: Vec<Bytes>,
+}
+
+/// A single account in an [`AccountRange`] response.
+#[derive_arbitrary(rlp)]
+#[derive(Clone, Debug, PartialEq, Eq, RlpEncodable, RlpDecodable, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct AccountData {
+    /// Hash of the account address.
+    pub hash: B256,
+    /// The account in slim format.
+    pub body: SlimAccount,
+}
+
+/// The slim account format used by the `snap` protocol.
+///
+/// This is equivalent to the account in the state trie, except that the empty storage root and
+/// the empty code hash are replaced with empty byte strings.
+#[derive_arbitrary(rlp)]
+#[derive(Clone, Debug, PartialEq, Eq, RlpEncodable, RlpDecodable, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SlimAccount {
+    /// The account nonce.
+    pub nonce: u64,
+    /// The account balance.
+    pub balance: U256,
+    /// The storage root, empty if the account has no storage.
+    pub storage_root: Bytes,
+    /// The code hash, empty if the account has no code.
+    pub code_hash: Bytes,
+}
+
+impl SlimAccount {
+    /// Creates the slim representation of the account with the given storage root.
+    pub fn new(account: Account, storage_root: B256) -> Self {
+        let storage_root = if storage_root == EMPTY_ROOT_HASH {
+            Bytes::new()
+        } else {
+            Bytes::copy_from_slice(storage_root.as_slice())
+        };
+        let code_hash = match account.bytecode_hash {
+            Some(code_hash) if code_hash != KECCAK_EMPTY => {
+                Bytes::copy_from_slice(code_hash.as_slice())
+            }
+            _ => Bytes::new(),
+        };
+        Self { nonce: account.nonce, balance: account.balance, storage_root, code_hash }
+    }
+}
+
+/// Requests the storage slots of multiple accounts' storage tries.
+///
+/// If `starting_hash` and `limit_hash` are set, only the first account is served from that range,
+/// all other accounts are served in full.
+#[derive_arbitrary(rlp)]
+#[derive(Clone, Debug, PartialEq, Eq, RlpEncodable, RlpDecodable, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct GetStorageRanges {
+    /// Request ID to match up responses with.
+    pub request_id: u64,
+    /// Root hash of the account trie to serve.
+    pub root_hash: B256,
+    /// Account hashes of the storage tries to serve.
+    pub account_hashes: Vec<B256>,
+    /// Storage slot hash of the first to retrieve, empty for the start of the trie.
+    pub starting_hash: Bytes,
+    /// Storage slot hash after which to stop serving, empty for the end of the trie.
+    pub limit_hash: Bytes,
+    /// Soft limit at which to stop returning data.
+    pub response_bytes: u64,
+}
+
+/// The response to [`GetStorageRanges`].
+#[derive_arbitrary(rlp)]
+#[derive(Clone, Debug, PartialEq, Eq, RlpEncodable, RlpDecodable, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct StorageRanges {
+    /// ID of the request this is a response for.
+    pub request_id: u64,
+    /// List of list of consecutive slots from the tries, one list per requested account.
+    pub slots: Vec<Vec<StorageData>>,
+    /// List of trie nodes proving the last slot range, if it is incomplete.
+    pub proof: Vec<Bytes>,
+}
+
+/// A single storage slot in a [`StorageRanges`] response.
+#[derive_arbitrary(rlp)]
+#[derive(Clone, Debug, PartialEq, Eq, RlpEncodable, RlpDecodable, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct StorageData {
+    /// Hash of the storage slot.
+    pub hash: B256,
+    /// The RLP encoded slot value.
+    pub data: Bytes,
+}
+
+/// Requests a number of contract byte codes by hash.
+#[derive_arbitrary(rlp)]
+#[derive(Clone, Debug, PartialEq, Eq, RlpEncodable, RlpDecodable, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct GetByteCodes {
+    /// Request ID to match up responses with.
+    pub request_id: u64,
+    /// Code hashes to retrieve the code for.
+    pub hashes: Vec<B256>,
+    /// Soft limit at which to stop returning data.
+    pub response_bytes: u64,
+}
+
+/// The response to [`GetByteCodes`].
+#[derive_arbitrary(rlp)]
+#[derive(Clone, Debug, PartialEq, Eq, RlpEncodable, RlpDecodable, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ByteCodes {
+    /// ID of the request this is a response for.
+    pub request_id: u64,
+    /// The requested bytecodes in order.
+    pub codes: Vec<Bytes>,
+}
+
+/// Requests a number of state (either account or storage) Merkle trie nodes by path.
+#[derive_arbitrary(rlp)]
+#[derive(Clone, Debug, PartialEq, Eq, RlpEncodable, RlpDecodable, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct GetTrieNodes {
+    /// Request ID to match up responses with.
+    pub request_id: u64,
+    /// Root hash of the account trie to serve.
+    pub root_hash: B256,
+    /// Trie paths to retrieve the nodes for, grouped by account.
+    ///
+    /// The first element of each group is the compact encoded path in the account trie, all
+    /// following elements are compact encoded paths in the storage trie of that account.
+    pub paths: Vec<Vec<Bytes>>,
+    /// Soft limit at which to stop returning data.
+    pub response_bytes: u64,
+}
+
+/// The response to [`GetTrieNodes`].
+#[derive_arbitrary(rlp)]
+#[derive(Clone, Debug, PartialEq, Eq, RlpEncodable, RlpDecodable, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TrieNodes {
+    /// ID of the request this is a response for.
+    pub request_id: u64,
+    /// The requested trie nodes in order.
+    pub nodes: Vec<Bytes>,
+}
+
+/// Represents message IDs for `snap` protocol messages.
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum SnapMessageId {
+    /// [`GetAccountRange`] message.
+    GetAccountRange = 0x00,
+    /// [`AccountRange`] message.
+    AccountRange = 0x01,
+    /// [`GetStorageRanges`] message.
+    GetStorageRanges = 0x02,
+    /// [`StorageRanges`] message.
+    StorageRanges = 0x03,
+    /// [`GetByteCodes`] message.
+    GetByteCodes = 0x04,
+    /// [`ByteCodes`] message.
+    ByteCodes = 0x05,
+    /// [`GetTrieNodes`] message.
+    GetTrieNodes = 0x06,
+    /// [`TrieNodes`] message.
+    TrieNodes = 0x07,
+}
+
+impl SnapMessageId {
+    /// Returns the max value.
+    pub const fn max() -> u8 {
+        Self::TrieNodes as u8
+    }
+}
+
+impl TryFrom<u8> for SnapMessageId {
+    type Error = alloy_rlp::Error;
+
+    fn try_from(id: u8) -> Result<Self, Self::Error> {
+        let id = match id {
+            0x00 => SnapMessageId::GetAccountRange,
+            0x01 => SnapMessageId::AccountRange,
+            0x02 => SnapMessageId::GetStorageRanges,
+            0x03 => SnapMessageId::StorageRanges,
+            0x04 => SnapMessageId::GetByteCodes,
+            0x05 => SnapMessageId::ByteCodes,
+            0x06 => SnapMessageId::GetTrieNodes,
+            0x07 => SnapMessageId::TrieNodes,
+            _ => return Err(alloy_rlp::Error::Custom("Invalid snap message ID")),
+        };
+        Ok(id)
+    }
+}
+
+/// All messages of the `snap/1` protocol.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum SnapProtocolMessage {
+    /// Represents a [`GetAccountRange`] request.
+    GetAccountRange(GetAccountRange),
+    /// Represents an [`AccountRange`] response.
+    AccountRange(AccountRange),
+    /// Represents a [`GetStorageRanges`] request.
+    GetStorageRanges(GetStorageRanges),
+    /// Represents a [`StorageRanges`] response.
+    StorageRanges(StorageRanges),
+    /// Represents a [`GetByteCodes`] request.
+    GetByteCodes(GetByteCodes),
+    /// Represents a [`ByteCodes`] response.
+    ByteCodes(ByteCodes),
+    /// Represents a [`GetTrieNodes`] request.
+    GetTrieNodes(GetTrieNodes),
+    /// Represents a [`TrieNodes`] response.
+    TrieNodes(TrieNodes),
+}
+
+// === impl SnapProtocolMessage ===
+
+impl SnapProtocolMessage {
+    /// Returns the capability for the `snap/1` protocol.
+    pub const fn capability() -> Capability {
+        Capability::new_static("snap", 1)
+    }
+
+    /// Returns the `snap/1` protocol.
+    pub const fn protocol() -> Protocol {
+        Protocol::new(Self::capability(), SnapMessageId::max() + 1)
+    }
+
+    /// Returns the message's ID.
+    pub fn message_id(&self) -> SnapMessageId {
+        match self {
+            SnapProtocolMessage::GetAccountRange(_) => SnapMessageId::GetAccountRange,
+            SnapProtocolMessage::AccountRange(_) => SnapMessageId::AccountRange,
+            SnapProtocolMessage::GetStorageRanges(_) => SnapMessageId::GetStorageRanges,
+            SnapProtocolMessage::StorageRanges(_) => SnapMessageId::StorageRanges,
+            SnapProtocolMessage::GetByteCodes(_) => SnapMessageId::GetByteCodes,
+            SnapProtocolMessage::ByteCodes(_) => SnapMessageId::ByteCodes,
+            SnapProtocolMessage::GetTrieNodes(_) => SnapMessageId::GetTrieNodes,
+            SnapProtocolMessage::TrieNodes(_) => SnapMessageId::TrieNodes,
+        }
+    }
+
+    /// Returns the request id of the message.
+    pub fn request_id(&self) -> u64 {
+        match self {
+            SnapProtocolMessage::GetAccountRange(msg) => msg.request_id,
+            SnapProtocolMessage::AccountRange(msg) => msg.request_id,
+            SnapProtocolMessage::GetStorageRanges(msg) => msg.request_id,
+            SnapProtocolMessage::StorageRanges(msg) => msg.request_id,
+            SnapProtocolMessage::GetByteCodes(msg) => msg.request_id,
+            SnapProtocolMessage::ByteCodes(msg) => msg.request_id,
+            SnapProtocolMessage::GetTrieNodes(msg) => msg.request_id,
+            SnapProtocolMessage::TrieNodes(msg) => msg.request_id,
+        }
+    }
+
+    /// Encodes the message, prefixed with its (relative) message ID.
+    pub fn encoded(&self) -> BytesMut {
+        let mut buf = BytesMut::new();
+        buf.put_u8(self.message_id() as u8);
+        match self {
+            SnapProtocolMessage::GetAccountRange(msg) => msg.encode(&mut buf),
+            SnapProtocolMessage::AccountRange(msg) => msg.encode(&mut buf),
+            SnapProtocolMessage::GetStorageRanges(msg) => msg.encode(&mut buf),
+            SnapProtocolMessage::StorageRanges(msg) => msg.encode(&mut buf),
+            SnapProtocolMessage::GetByteCodes(msg) => msg.encode(&mut buf),
+            SnapProtocolMessage::ByteCodes(msg) => msg.encode(&mut buf),
+            SnapProtocolMessage::GetTrieNodes(msg) => msg.encode(&mut buf),
+            SnapProtocolMessage::TrieNodes(msg) => msg.encode(&mut buf),
+        }
+        buf
+    }
+
+    /// Decodes a message that is prefixed with its (relative) message ID.
+    pub fn decode_message(buf: &mut &[u8]) -> alloy_rlp::Result<Self> {
+        let (&id, rest) = buf.split_first().ok_or(alloy_rlp::Error::InputTooShort)?;
+        *buf = rest;
+        let msg = match SnapMessageId::try_from(id)? {
+            SnapMessageId::GetAccountRange => {
+                SnapProtocolMessage::GetAccountRange(GetAccountRange::decode(buf)?)
+            }
+            SnapMessageId::AccountRange => {
+                SnapProtocolMessage::AccountRange(AccountRange::decode(buf)?)
+            }
+            SnapMessageId::GetStorageRanges => {
+                SnapProtocolMessage::GetStorageRanges(GetStorageRanges::decode(buf)?)
+            }
+            SnapMessageId::StorageRanges => {
+                SnapProtocolMessage::StorageRanges(StorageRanges::decode(buf)?)
+            }
+            SnapMessageId::GetByteCodes => {
+                SnapProtocolMessage::GetByteCodes(GetByteCodes::decode(buf)?)
+            }
+            SnapMessageId::ByteCodes => SnapProtocolMessage::ByteCodes(ByteCodes::decode(buf)?),
+            SnapMessageId::GetTrieNodes => {
+                SnapProtocolMessage::GetTrieNodes(GetTrieNodes::decode(buf)?)
+            }
+            SnapMessageId::TrieNodes => SnapProtocolMessage::TrieNodes(TrieNodes::decode(buf)?),
+        };
+        Ok(msg)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn snap_message_roundtrip() {
+        let messages = vec![
+            SnapProtocolMessage::GetAccountRange(GetAccountRange {
+                request_id: 1,
+                root_hash: B256::random(),
+                starting_hash: B256::ZERO,
+                limit_hash: B256::repeat_byte(0xff),
+                response_bytes: 512 * 1024,
+            }),
+            SnapProtocolMessage::AccountRange(AccountRange {
+                request_id: 1,
+                accounts: vec![AccountData {
+                    hash: B256::random(),
+                    body: SlimAccount::new(
+                        Account { nonce: 1, balance: U256::from(2), bytecode_hash: None },
+                        EMPTY_ROOT_HASH,
+                    ),
+                }],
+                proof: vec![Bytes::from_static(&[0xc0])],
+            }),
+            SnapProtocolMessage::GetByteCodes(GetByteCodes {
+                request_id: 2,
+                hashes: vec![B256::random()],
+                response_bytes: 1024,
+            }),
+            SnapProtocolMessage::TrieNodes(TrieNodes { request_id: 3, nodes: vec![] }),
+        ];
+
+        for msg in messages {
+            let encoded = msg.encoded();
+            assert_eq!(encoded[0], msg.message_id() as u8);
+            let decoded = SnapProtocolMessage::decode_message(&mut &encoded[..]).unwrap();
+            assert_eq!(decoded, msg);
+        }
+    }
+
+    #[test]
+    fn slim_account_omits_empty_fields() {
+        let account = Account { nonce: 0, balance: U256::ZERO, bytecode_hash: Some(KECCAK_EMPTY) };
+        let slim = SlimAccount::new(account, EMPTY_ROOT_HASH);
+        assert!(slim.storage_root.is_empty());
+        assert!(slim.code_hash.is_empty());
+    }
+}
@@ -1,4 +1,4 @@
-use crate::{EthVersion, StatusBuilder};
+use crate::{BlockRangeUpdate, EthVersion, StatusBuilder};
 use alloy_chains::{Chain, NamedChain};
 use alloy_rlp::{RlpDecodable, RlpEncodable};
 use reth_codecs::derive_arbitrary;
@@ -131,6 +131,77 @@ impl Debug for Status {
     }
 }
 
+/// The `eth/69` status message.
+///
+/// Compared to [`Status`], the total difficulty is removed and the best block hash is replaced by
+/// the range of blocks the peer can serve, see also [`BlockRangeUpdate`].
+#[derive_arbitrary(rlp)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, RlpEncodable, RlpDecodable)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct StatusEth69 {
+    /// The current protocol version, 69 or higher.
+    pub version: u8,
+
+    /// The chain id, as introduced in
+    /// [EIP155](https://eips.ethereum.org/EIPS/eip-155#list-of-chain-ids).
+    pub chain: Chain,
+
+    /// The genesis hash of the peer's chain.
+    pub genesis: B256,
+
+    /// The fork identifier as defined by
+    /// [EIP-2124](https://github.com/ethereum/EIPs/blob/master/EIPS/eip-2124.md).
+    pub forkid: ForkId,
+
+    /// The earliest block the peer can serve.
+    pub earliest: u64,
+
+    /// The latest block the peer can serve.
+    pub latest: u64,
+
+    /// The hash of the latest block.
+    pub latest_hash: B256,
+}
+
+impl StatusEth69 {
+    /// Creates the `eth/69` status message from the [`Status`] and the range of available blocks.
+    pub fn new(status: Status, block_range: BlockRangeUpdate) -> Self {
+        Self {
+            version: status.version,
+            chain: status.chain,
+            genesis: status.genesis,
+            forkid: status.forkid,
+            earliest: block_range.earliest,
+            latest: block_range.latest,
+            latest_hash: block_range.latest_hash,
+        }
+    }
+
+    /// Returns the range of blocks the peer can serve.
+    pub fn block_range(&self) -> BlockRangeUpdate {
+        BlockRangeUpdate {
+            earliest: self.earliest,
+            latest: self.latest,
+            latest_hash: self.latest_hash,
+        }
+    }
+}
+
+impl From<StatusEth69> for Status {
+    /// Converts the `eth/69` status, the total difficulty is unknown and set to zero and the
+    /// latest block is used as best block.
+    fn from(status: StatusEth69) -> Self {
+        Status {
+            version: status.version,
+            chain: status.chain,
+            total_difficulty: U256::ZERO,
+            blockhash: status.latest_hash,
+            genesis: status.genesis,
+            forkid: status.forkid,
+        }
+    }
+}
+
 // <https://etherscan.io/block/0>
 impl Default for Status {
     fn default() -> Self {
@@ -150,7 +221,7 @@ impl Default for Status {
 
 #[cfg(test)]
 mod tests {
-    use crate::types::{EthVersion, Status};
+    use crate::types::{BlockRangeUpdate, EthVersion, Status, StatusEth69};
     use alloy_chains::{Chain, NamedChain};
     use alloy_rlp::{Decodable, Encodable};
     use rand::Rng;
@@ -328,4 +399,23 @@ mod tests {
         assert_eq!(status.blockhash, head_hash);
         assert_eq!(status.genesis, genesis_hash);
     }
+
+    #[test]
+    fn roundtrip_eth69_status() {
+        let status = Status { version: EthVersion::Eth69 as u8, ..Default::default() };
+        let block_range =
+            BlockRangeUpdate { earliest: 10, latest: 100, latest_hash: B256::random() };
+        let status69 = StatusEth69::new(status, block_range);
+        assert_eq!(status69.block_range(), block_range);
+
+        let mut encoded = Vec::new();
+        status69.encode(&mut encoded);
+        assert_eq!(StatusEth69::decode(&mut &encoded[..]).unwrap(), status69);
+
+        let converted = Status::from(status69);
+        assert_eq!(converted.total_difficulty, U256::ZERO);
+        assert_eq!(converted.blockhash, block_range.latest_hash);
+        assert_eq!(converted.genesis, status.genesis);
+        assert_eq!(converted.forkid, status.forkid);
+    }
 }
@@ -20,11 +20,14 @@ pub enum EthVersion {
 
     /// The `eth` protocol version 68.
     Eth68 = 68,
+
+    /// The `eth` protocol version 69.
+    Eth69 = 69,
 }
 
 impl EthVersion {
     /// The latest known eth version
-    pub const LATEST: EthVersion = EthVersion::Eth68;
+    pub const LATEST: EthVersion = EthVersion::Eth69;
 
     /// Returns the total number of messages the protocol version supports.
     pub const fn total_messages(&self) -> u8 {
@@ -34,6 +37,10 @@ impl EthVersion {
                 // eth/67,68 are eth/66 minus GetNodeData and NodeData messages
                 13
             }
+            EthVersion::Eth69 => {
+                // eth/69 is eth/68 minus NewBlockHashes and NewBlock plus BlockRangeUpdate
+                12
+            }
         }
     }
 }
@@ -56,6 +63,7 @@ impl TryFrom<&str> for EthVersion {
             "66" => Ok(EthVersion::Eth66),
             "67" => Ok(EthVersion::Eth67),
             "68" => Ok(EthVersion::Eth68),
+            "69" => Ok(EthVersion::Eth69),
             _ => Err(ParseVersionError(s.to_string())),
         }
     }
@@ -79,6 +87,7 @@ impl TryFrom<u8> for EthVersion {
             66 => Ok(EthVersion::Eth66),
             67 => Ok(EthVersion::Eth67),
             68 => Ok(EthVersion::Eth68),
+            69 => Ok(EthVersion::Eth69),
             _ => Err(ParseVersionError(u.to_string())),
         }
     }
@@ -107,6 +116,7 @@ impl From<EthVersion> for &'static str {
             EthVersion::Eth66 => "66",
             EthVersion::Eth67 => "67",
             EthVersion::Eth68 => "68",
+            EthVersion::Eth69 => "69",
         }
     }
 }
@@ -121,7 +131,8 @@ mod tests {
         assert_eq!(EthVersion::Eth66, EthVersion::try_from("66").unwrap());
         assert_eq!(EthVersion::Eth67, EthVersion::try_from("67").unwrap());
         assert_eq!(EthVersion::Eth68, EthVersion::try_from("68").unwrap());
-        assert_eq!(Err(ParseVersionError("69".to_string())), EthVersion::try_from("69"));
+        assert_eq!(EthVersion::Eth69, EthVersion::try_from("69").unwrap());
+        assert_eq!(Err(ParseVersionError("70".to_string())), EthVersion::try_from("70"));
     }
 
     #[test]
@@ -129,6 +140,7 @@ mod tests {
         assert_eq!(EthVersion::Eth66, "66".parse().unwrap());
         assert_eq!(EthVersion::Eth67, "67".parse().unwrap());
         assert_eq!(EthVersion::Eth68, "68".parse().unwrap());
-        assert_eq!(Err(ParseVersionError("69".to_string())), "69".parse::<EthVersion>());
+        assert_eq!(EthVersion::Eth69, "69".parse().unwrap());
+        assert_eq!(Err(ParseVersionError("70".to_string())), "70".parse::<EthVersion>());
     }
 }
@@ -50,10 +50,10 @@ pub mod fuzz_rlp {
     use alloy_rlp::{RlpDecodableWrapper, RlpEncodableWrapper};
     use reth_codecs::derive_arbitrary;
     use reth_eth_wire::{
-        BlockBodies, BlockHeaders, DisconnectReason, GetBlockBodies, GetBlockHeaders, GetNodeData,
-        GetPooledTransactions, GetReceipts, HelloMessage, NewBlock, NewBlockHashes,
-        NewPooledTransactionHashes66, NewPooledTransactionHashes68, NodeData, P2PMessage,
-        PooledTransactions, Receipts, Status, Transactions,
+        BlockBodies, BlockHeaders, BlockRangeUpdate, DisconnectReason, GetBlockBodies,
+        GetBlockHeaders, GetNodeData, GetPooledTransactions, GetReceipts, HelloMessage, NewBlock,
+        NewBlockHashes, NewPooledTransactionHashes66, NewPooledTransactionHashes68, NodeData,
+        P2PMessage, PooledTransactions, Receipts, Status, Transactions,
     };
     use reth_primitives::{BlockHashOrNumber, TransactionSigned};
     use serde::{Deserialize, Serialize};
@@ -107,6 +107,7 @@ pub mod fuzz_rlp {
 
     // eth subprotocol messages
     fuzz_type_and_name!(Status, fuzz_Status);
+    fuzz_type_and_name!(BlockRangeUpdate, fuzz_BlockRangeUpdate);
     fuzz_type_and_name!(NewBlockHashes, fuzz_NewBlockHashes);
     fuzz_type_and_name!(Transactions, fuzz_Transactions);
 
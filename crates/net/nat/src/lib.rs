@@ -1,4 +1,4 @@
-//! Helpers for resolving the external IP.
+//! Helpers for resolving the external IP and mapping ports on the gateway.
 //!
 //! ## Feature Flags
 //!
@@ -16,13 +16,16 @@ use pin_project_lite::pin_project;
 use std::{
     fmt,
     future::{poll_fn, Future},
-    net::{AddrParseError, IpAddr},
+    net::{AddrParseError, IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
     pin::Pin,
     str::FromStr,
     task::{ready, Context, Poll},
     time::Duration,
 };
-use tracing::debug;
+use tokio::net::UdpSocket;
+use tracing::{debug, trace};
+
+mod natpmp;
 
 #[cfg(feature = "serde")]
 use serde_with::{DeserializeFromStr, SerializeDisplay};
@@ -36,6 +39,8 @@ pub enum NatResolver {
     Any,
     /// Resolve via Upnp
     Upnp,
+    /// Resolve via NAT-PMP, from the default gateway
+    NatPmp,
     /// Resolve external IP via [public_ip::Resolver]
     PublicIp,
     /// Use the given [IpAddr]
@@ -51,6 +56,39 @@ impl NatResolver {
     pub async fn external_addr(self) -> Option<IpAddr> {
         external_addr_with(self).await
     }
+
+    /// Returns true if the resolver maps ports on the gateway, see [NatResolver::map_port].
+    ///
+    /// Only [NatResolver::Upnp] and [NatResolver::NatPmp] map ports, [NatResolver::Any] only
+    /// resolves the external IP.
+    pub fn supports_port_mapping(&self) -> bool {
+        matches!(self, NatResolver::Upnp | NatResolver::NatPmp)
+    }
+
+    /// Attempts to map the local port on the gateway for the given lease duration (best effort).
+    ///
+    /// The gateway may map another external port than the local port, see
+    /// [PortMapping::external_port].
+    pub async fn map_port(
+        self,
+        protocol: PortMappingProtocol,
+        port: u16,
+        lease: Duration,
+    ) -> Option<PortMapping> {
+        if port == 0 {
+            return None
+        }
+        let mapping = match self {
+            NatResolver::Upnp => map_port_upnp(protocol, port, lease).await,
+            NatResolver::NatPmp => map_port_natpmp(protocol, port, lease).await,
+            NatResolver::Any |
+            NatResolver::PublicIp |
+            NatResolver::ExternalIp(_) |
+            NatResolver::None => None,
+        };
+        // a gateway that can't map the port responds with the external port 0
+        mapping.filter(|mapping| mapping.external_port != 0)
+    }
 }
 
 impl fmt::Display for NatResolver {
@@ -58,6 +96,7 @@ impl fmt::Display for NatResolver {
         match self {
             NatResolver::Any => f.write_str("any"),
             NatResolver::Upnp => f.write_str("upnp"),
+            NatResolver::NatPmp => f.write_str("pmp"),
             NatResolver::PublicIp => f.write_str("publicip"),
             NatResolver::ExternalIp(ip) => write!(f, "extip:{ip}"),
             NatResolver::None => f.write_str("none"),
@@ -83,6 +122,7 @@ impl FromStr for NatResolver {
         let r = match s {
             "any" => NatResolver::Any,
             "upnp" => NatResolver::Upnp,
+            "pmp" | "natpmp" => NatResolver::NatPmp,
             "none" => NatResolver::None,
             "publicip" | "public-ip" => NatResolver::PublicIp,
             s => {
@@ -190,6 +230,7 @@ pub async fn external_addr_with(resolver: NatResolver) -> Option<IpAddr> {
             .await
         }
         NatResolver::Upnp => resolve_external_ip_upnp().await,
+        NatResolver::NatPmp => resolve_external_ip_natpmp().await,
         NatResolver::PublicIp => resolve_external_ip().await,
         NatResolver::ExternalIp(ip) => Some(ip),
         NatResolver::None => None,
@@ -257,14 +298,160 @@ async fn resolve_external_ip_upnp() -> Option<IpAddr> {
         .ok()
 }
 
+async fn resolve_external_ip_natpmp() -> Option<IpAddr> {
+    let Some(gateway) = natpmp::default_gateway() else {
+        debug!(target: "net::nat", "Failed to resolve external IP via NAT-PMP: failed to find gateway");
+        return None
+    };
+    natpmp::external_ip(gateway)
+        .await
+        .map_err(|err| {
+            debug!(target: "net::nat", ?err, %gateway, "Failed to resolve external IP via NAT-PMP");
+            err
+        })
+        .ok()
+        .map(IpAddr::V4)
+}
+
 async fn resolve_external_ip() -> Option<IpAddr> {
     public_ip::addr().await
 }
 
+/// The default lease duration of the port mappings created by [maintain_port_mappings].
+pub const DEFAULT_PORT_MAPPING_LEASE: Duration = Duration::from_secs(20 * 60);
+
+/// The interval in which failed port mappings are retried by [maintain_port_mappings].
+const PORT_MAPPING_RETRY_INTERVAL: Duration = Duration::from_secs(60);
+
+/// The transport protocol of a port mapping.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum PortMappingProtocol {
+    /// TCP, used by RLPx
+    Tcp,
+    /// UDP, used by discovery
+    Udp,
+}
+
+impl From<PortMappingProtocol> for igd::PortMappingProtocol {
+    fn from(protocol: PortMappingProtocol) -> Self {
+        match protocol {
+            PortMappingProtocol::Tcp => igd::PortMappingProtocol::TCP,
+            PortMappingProtocol::Udp => igd::PortMappingProtocol::UDP,
+        }
+    }
+}
+
+/// A port mapped on the gateway.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct PortMapping {
+    /// The port on the gateway that is forwarded to the local port.
+    pub external_port: u16,
+    /// The duration after which the gateway removes the mapping.
+    pub lifetime: Duration,
+}
+
+/// Maps the given local ports on the gateway with the resolver, and renews the mappings before
+/// their lease expires.
+///
+/// `on_mapped` is called with the protocol, the local port and the external port whenever the
+/// external port of a mapping changes, so that it can be advertised. Until a port is mapped, its
+/// external port is assumed to be the local port. Port `0` is never mapped.
+///
+/// Failed mappings are retried periodically. This future never completes.
+pub async fn maintain_port_mappings(
+    resolver: NatResolver,
+    mut ports: Vec<(PortMappingProtocol, u16)>,
+    lease: Duration,
+    mut on_mapped: impl FnMut(PortMappingProtocol, u16, u16) + Send,
+) {
+    ports.retain(|(_, port)| *port != 0);
+    if ports.is_empty() || !resolver.supports_port_mapping() {
+        return std::future::pending().await
+    }
+
+    let mut external_ports = ports.iter().map(|(_, port)| *port).collect::<Vec<_>>();
+    loop {
+        let mut renew_in = lease / 2;
+        for ((protocol, port), external_port) in ports.iter().copied().zip(&mut external_ports) {
+            match resolver.map_port(protocol, port, lease).await {
+                Some(mapping) => {
+                    trace!(target: "net::nat", ?protocol, port, ?mapping, "Mapped port on gateway");
+                    renew_in = renew_in.min(mapping.lifetime / 2);
+                    if mapping.external_port != *external_port {
+                        *external_port = mapping.external_port;
+                        on_mapped(protocol, port, mapping.external_port);
+                    }
+                }
+                None => {
+                    debug!(target: "net::nat", ?protocol, port, %resolver, "Failed to map port on gateway");
+                    renew_in = renew_in.min(PORT_MAPPING_RETRY_INTERVAL);
+                }
+            }
+        }
+        // never spin if the gateway grants very short leases
+        tokio::time::sleep(renew_in.max(Duration::from_secs(1))).await;
+    }
+}
+
+async fn map_port_upnp(
+    protocol: PortMappingProtocol,
+    port: u16,
+    lease: Duration,
+) -> Option<PortMapping> {
+    let gateway = search_gateway(Default::default())
+        .await
+        .map_err(|err| {
+            debug!(target: "net::nat", ?err, "Failed to map port via UPnP: failed to find gateway");
+            err
+        })
+        .ok()?;
+
+    // the gateway forwards to the address of the interface that reaches it
+    let gateway_addr = SocketAddr::from(gateway.addr);
+    let local_ip = local_ip_towards(gateway_addr).await?;
+    let lease_secs = u32::try_from(lease.as_secs()).unwrap_or(u32::MAX);
+    gateway
+        .add_port(protocol.into(), port, SocketAddr::new(local_ip, port), lease_secs, "reth")
+        .await
+        .map_err(|err| {
+            debug!(target: "net::nat", ?err, ?protocol, port, "Failed to map port via UPnP");
+            err
+        })
+        .ok()?;
+
+    Some(PortMapping { external_port: port, lifetime: lease })
+}
+
+async fn map_port_natpmp(
+    protocol: PortMappingProtocol,
+    port: u16,
+    lease: Duration,
+) -> Option<PortMapping> {
+    let Some(gateway) = natpmp::default_gateway() else {
+        debug!(target: "net::nat", "Failed to map port via NAT-PMP: failed to find gateway");
+        return None
+    };
+    natpmp::map_port(gateway, protocol, port, lease)
+        .await
+        .map_err(|err| {
+            debug!(target: "net::nat", ?err, %gateway, ?protocol, port, "Failed to map port via NAT-PMP");
+            err
+        })
+        .ok()
+}
+
+/// Returns the local IP of the interface that is used to reach the given address.
+async fn local_ip_towards(addr: SocketAddr) -> Option<IpAddr> {
+    let unspecified: IpAddr =
+        if addr.is_ipv4() { Ipv4Addr::UNSPECIFIED.into() } else { Ipv6Addr::UNSPECIFIED.into() };
+    let socket = UdpSocket::bind((unspecified, 0)).await.ok()?;
+    socket.connect(addr).await.ok()?;
+    socket.local_addr().ok().map(|addr| addr.ip())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::net::Ipv4Addr;
 
     #[tokio::test]
     #[ignore]
@@ -286,10 +473,22 @@ mod tests {
         dbg!(ip);
     }
 
+    #[tokio::test]
+    async fn never_map_port_zero() {
+        let lease = DEFAULT_PORT_MAPPING_LEASE;
+        assert_eq!(NatResolver::NatPmp.map_port(PortMappingProtocol::Tcp, 0, lease).await, None);
+        assert_eq!(NatResolver::Any.map_port(PortMappingProtocol::Tcp, 30303, lease).await, None);
+    }
+
     #[test]
     fn test_from_str() {
         assert_eq!(NatResolver::Any, "any".parse().unwrap());
         assert_eq!(NatResolver::None, "none".parse().unwrap());
+        assert_eq!(NatResolver::NatPmp, "pmp".parse().unwrap());
+        assert_eq!(NatResolver::NatPmp.to_string().as_str(), "pmp");
+        assert!(NatResolver::Upnp.supports_port_mapping());
+        assert!(!NatResolver::Any.supports_port_mapping());
+        assert!(!NatResolver::PublicIp.supports_port_mapping());
 
         let ip = NatResolver::ExternalIp(IpAddr::V4(Ipv4Addr::UNSPECIFIED));
         let s = "extip:0.0.0.0";
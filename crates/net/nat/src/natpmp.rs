@@ -0,0 +1,180 @@
+//! A minimal NAT-PMP client, see [RFC 6886](https://www.rfc-editor.org/rfc/rfc6886).
+
+use crate::{PortMapping, PortMappingProtocol};
+use std::{io, net::Ipv4Addr, time::Duration};
+use tokio::net::UdpSocket;
+
+/// The port NAT-PMP gateways listen on.
+const NATPMP_PORT: u16 = 5351;
+/// The number of times a request is sent before giving up.
+const MAX_ATTEMPTS: u32 = 4;
+/// The timeout of the first attempt, doubled with every retransmission.
+const INITIAL_TIMEOUT: Duration = Duration::from_millis(250);
+
+const OPCODE_EXTERNAL_ADDR: u8 = 0;
+const OPCODE_MAP_UDP: u8 = 1;
+const OPCODE_MAP_TCP: u8 = 2;
+/// Set in the opcode of all responses.
+const OPCODE_RESPONSE: u8 = 128;
+
+/// Requests the external IPv4 address of the gateway.
+pub(crate) async fn external_ip(gateway: Ipv4Addr) -> io::Result<Ipv4Addr> {
+    let response = send_request(gateway, &[0, OPCODE_EXTERNAL_ADDR]).await?;
+    decode_external_ip_response(&response)
+}
+
+/// Requests a mapping of the local port on the gateway, for the given lifetime.
+pub(crate) async fn map_port(
+    gateway: Ipv4Addr,
+    protocol: PortMappingProtocol,
+    port: u16,
+    lifetime: Duration,
+) -> io::Result<PortMapping> {
+    let response = send_request(gateway, &encode_map_request(protocol, port, lifetime)).await?;
+    decode_map_response(protocol, &response)
+}
+
+/// Returns the IPv4 address of the default gateway, read from the routing table of the kernel.
+///
+/// Only supported on Linux.
+pub(crate) fn default_gateway() -> Option<Ipv4Addr> {
+    #[cfg(target_os = "linux")]
+    {
+        let routes = std::fs::read_to_string("/proc/net/route").ok()?;
+        parse_default_gateway(&routes)
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        None
+    }
+}
+
+/// Parses the gateway of the default route from the contents of `/proc/net/route`.
+///
+/// Addresses are formatted as hex of the native representation of the network order bytes.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_default_gateway(routes: &str) -> Option<Ipv4Addr> {
+    routes.lines().skip(1).find_map(|line| {
+        let mut fields = line.split_whitespace().skip(1);
+        let (destination, gateway) = (fields.next()?, fields.next()?);
+        if destination != "00000000" {
+            return None
+        }
+        let gateway = u32::from_str_radix(gateway, 16).ok()?;
+        (gateway != 0).then(|| Ipv4Addr::from(gateway.to_ne_bytes()))
+    })
+}
+
+/// Sends the request to the gateway and waits for the response, retransmitting the request with
+/// an exponential backoff.
+async fn send_request(gateway: Ipv4Addr, request: &[u8]) -> io::Result<Vec<u8>> {
+    let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0)).await?;
+    socket.connect((gateway, NATPMP_PORT)).await?;
+
+    let mut buf = [0u8; 16];
+    let mut timeout = INITIAL_TIMEOUT;
+    for _ in 0..MAX_ATTEMPTS {
+        socket.send(request).await?;
+        match tokio::time::timeout(timeout, socket.recv(&mut buf)).await {
+            Ok(len) => return Ok(buf[..len?].to_vec()),
+            Err(_) => timeout *= 2,
+        }
+    }
+
+    Err(io::Error::new(io::ErrorKind::TimedOut, "NAT-PMP gateway did not respond"))
+}
+
+fn encode_map_request(protocol: PortMappingProtocol, port: u16, lifetime: Duration) -> [u8; 12] {
+    let opcode = match protocol {
+        PortMappingProtocol::Udp => OPCODE_MAP_UDP,
+        PortMappingProtocol::Tcp => OPCODE_MAP_TCP,
+    };
+    let lifetime = u32::try_from(lifetime.as_secs()).unwrap_or(u32::MAX);
+
+    let mut request = [0u8; 12];
+    request[1] = opcode;
+    // the external port is only a suggestion, the gateway may map another one
+    request[4..6].copy_from_slice(&port.to_be_bytes());
+    request[6..8].copy_from_slice(&port.to_be_bytes());
+    request[8..12].copy_from_slice(&lifetime.to_be_bytes());
+    request
+}
+
+fn decode_external_ip_response(response: &[u8]) -> io::Result<Ipv4Addr> {
+    decode_response_header(response, OPCODE_EXTERNAL_ADDR, 12)?;
+    Ok(Ipv4Addr::new(response[8], response[9], response[10], response[11]))
+}
+
+fn decode_map_response(protocol: PortMappingProtocol, response: &[u8]) -> io::Result<PortMapping> {
+    let opcode = match protocol {
+        PortMappingProtocol::Udp => OPCODE_MAP_UDP,
+        PortMappingProtocol::Tcp => OPCODE_MAP_TCP,
+    };
+    decode_response_header(response, opcode, 16)?;
+    let external_port = u16::from_be_bytes([response[10], response[11]]);
+    let lifetime = u32::from_be_bytes([response[12], response[13], response[14], response[15]]);
+    Ok(PortMapping { external_port, lifetime: Duration::from_secs(lifetime as u64) })
+}
+
+/// Validates the version, opcode and length of the response, and that the request succeeded.
+fn decode_response_header(response: &[u8], opcode: u8, len: usize) -> io::Result<()> {
+    if response.len() < len || response[0] != 0 || response[1] != OPCODE_RESPONSE | opcode {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "invalid NAT-PMP response"))
+    }
+    let result = u16::from_be_bytes([response[2], response[3]]);
+    if result != 0 {
+        return Err(io::Error::new(
+            io::ErrorKind::Other,
+            format!("NAT-PMP request failed with result code {result}"),
+        ))
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encode_decode_map_port() {
+        let request =
+            encode_map_request(PortMappingProtocol::Tcp, 30303, Duration::from_secs(7200));
+        assert_eq!(request, [0, 2, 0, 0, 0x76, 0x5f, 0x76, 0x5f, 0, 0, 0x1c, 0x20]);
+
+        let response = [0, 130, 0, 0, 0, 0, 0, 1, 0x76, 0x5f, 0x76, 0x60, 0, 0, 0x0e, 0x10];
+        let mapping = decode_map_response(PortMappingProtocol::Tcp, &response).unwrap();
+        assert_eq!(
+            mapping,
+            PortMapping { external_port: 30304, lifetime: Duration::from_secs(3600) }
+        );
+
+        // response to a UDP mapping request
+        assert!(decode_map_response(PortMappingProtocol::Udp, &response).is_err());
+    }
+
+    #[test]
+    fn decode_external_ip() {
+        let response = [0, 128, 0, 0, 0, 0, 0, 1, 203, 0, 113, 7];
+        assert_eq!(decode_external_ip_response(&response).unwrap(), Ipv4Addr::new(203, 0, 113, 7));
+
+        // unsupported opcode
+        let response = [0, 128, 0, 5, 0, 0, 0, 1, 0, 0, 0, 0];
+        assert!(decode_external_ip_response(&response).is_err());
+        assert!(decode_external_ip_response(&response[..8]).is_err());
+    }
+
+    #[test]
+    #[cfg(target_endian = "little")]
+    fn parse_gateway_from_routes() {
+        let routes = "\
+Iface\tDestination\tGateway \tFlags\tRefCnt\tUse\tMetric\tMask\t\tMTU\tWindow\tIRTT
+eth0\t0000A8C0\t00000000\t0001\t0\t0\t100\t00FFFFFF\t0\t0\t0
+eth0\t00000000\t0101A8C0\t0003\t0\t0\t100\t00000000\t0\t0\t0
+";
+        assert_eq!(parse_default_gateway(routes), Some(Ipv4Addr::new(192, 168, 1, 1)));
+        assert_eq!(
+            parse_default_gateway(routes.lines().take(2).collect::<Vec<_>>().join("\n").as_str()),
+            None
+        );
+    }
+}
@@ -14,10 +14,16 @@
 #![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
 
 use async_trait::async_trait;
+use enr::Enr;
 use reth_eth_wire::{DisconnectReason, EthVersion, Status};
 use reth_primitives::{NodeRecord, PeerId};
 use reth_rpc_types::NetworkStatus;
-use std::{net::SocketAddr, sync::Arc, time::Instant};
+use secp256k1::SecretKey;
+use std::{
+    net::SocketAddr,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 pub use error::NetworkError;
 pub use reputation::{Reputation, ReputationChangeKind};
@@ -63,6 +69,9 @@ pub trait PeersInfo: Send + Sync {
 
     /// Returns the Ethereum Node Record of the node.
     fn local_node_record(&self) -> NodeRecord;
+
+    /// Returns the signed [EIP-778](https://eips.ethereum.org/EIPS/eip-778) record of the node.
+    fn local_enr(&self) -> Enr<SecretKey>;
 }
 
 /// Provides an API for managing the peers of the network.
@@ -157,6 +166,13 @@ pub struct PeerInfo {
     pub session_established: Instant,
 }
 
+impl PeerInfo {
+    /// Returns how long the session to that peer has been established.
+    pub fn connected_duration(&self) -> Duration {
+        self.session_established.elapsed()
+    }
+}
+
 /// The direction of the connection.
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum Direction {
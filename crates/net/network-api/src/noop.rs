@@ -9,10 +9,12 @@ use crate::{
 };
 use alloy_chains::Chain;
 use async_trait::async_trait;
+use enr::{Enr, EnrBuilder};
 use reth_discv4::DEFAULT_DISCOVERY_PORT;
 use reth_eth_wire::{DisconnectReason, ProtocolVersion};
 use reth_primitives::{NodeRecord, PeerId};
 use reth_rpc_types::{EthProtocolInfo, NetworkStatus};
+use secp256k1::{rand::thread_rng, SecretKey};
 use std::net::{IpAddr, SocketAddr};
 
 /// A type that implements all network trait that does nothing.
@@ -67,6 +69,10 @@ impl PeersInfo for NoopNetwork {
     fn local_node_record(&self) -> NodeRecord {
         NodeRecord::new(self.local_addr(), PeerId::random())
     }
+
+    fn local_enr(&self) -> Enr<SecretKey> {
+        EnrBuilder::new("v4").build(&SecretKey::new(&mut thread_rng())).expect("v4 is set; qed")
+    }
 }
 
 #[async_trait]
@@ -30,6 +30,8 @@ pub enum ReputationChangeKind {
     FailedToConnect,
     /// Connection dropped by peer.
     Dropped,
+    /// Peer exceeded the configured limits for requests we serve.
+    ExceededRequestLimits,
     /// Reset the reputation to the default value.
     Reset,
     /// Apply a reputation change by value
@@ -1,8 +1,14 @@
 //! Builder support for configuring the entire setup.
 
 use crate::{
-    eth_requests::EthRequestHandler, transactions::TransactionsManager, NetworkHandle,
-    NetworkManager,
+    eth_requests::{EthRequestHandler, EthRequestLimits},
+    protocol::IntoRlpxSubProtocol,
+    snap_requests::{SnapProtocolHandler, SnapRequestHandler, SNAP_REQUEST_CHANNEL_CAPACITY},
+    transactions::{
+        SqrtPeersFullPropagation, TransactionGossipValidator, TransactionPropagationPolicy,
+        TransactionsManager, TransactionsManagerConfig,
+    },
+    NetworkHandle, NetworkManager,
 };
 use reth_transaction_pool::TransactionPool;
 use tokio::sync::mpsc;
@@ -13,10 +19,11 @@ pub(crate) const ETH_REQUEST_CHANNEL_CAPACITY: usize = 256;
 
 /// A builder that can configure all components of the network.
 #[allow(missing_debug_implementations)]
-pub struct NetworkBuilder<C, Tx, Eth> {
+pub struct NetworkBuilder<C, Tx, Eth, Snap = ()> {
     pub(crate) network: NetworkManager<C>,
     pub(crate) transactions: Tx,
     pub(crate) request_handler: Eth,
+    pub(crate) snap_request_handler: Snap,
 }
 
 // === impl NetworkBuilder ===
@@ -24,10 +31,19 @@ pub struct NetworkBuilder<C, Tx, Eth> {
 impl<C, Tx, Eth> NetworkBuilder<C, Tx, Eth> {
     /// Consumes the type and returns all fields.
     pub fn split(self) -> (NetworkManager<C>, Tx, Eth) {
-        let NetworkBuilder { network, transactions, request_handler } = self;
+        let NetworkBuilder { network, transactions, request_handler, .. } = self;
         (network, transactions, request_handler)
     }
 
+    /// Consumes the type and returns all fields and also return a [`NetworkHandle`].
+    pub fn split_with_handle(self) -> (NetworkHandle, NetworkManager<C>, Tx, Eth) {
+        let NetworkBuilder { network, transactions, request_handler, .. } = self;
+        let handle = network.handle().clone();
+        (handle, network, transactions, request_handler)
+    }
+}
+
+impl<C, Tx, Eth, Snap> NetworkBuilder<C, Tx, Eth, Snap> {
     /// Returns the network manager.
     pub fn network(&self) -> &NetworkManager<C> {
         &self.network
@@ -43,36 +59,116 @@ impl<C, Tx, Eth> NetworkBuilder<C, Tx, Eth> {
         self.network.handle().clone()
     }
 
-    /// Consumes the type and returns all fields and also return a [`NetworkHandle`].
-    pub fn split_with_handle(self) -> (NetworkHandle, NetworkManager<C>, Tx, Eth) {
-        let NetworkBuilder { network, transactions, request_handler } = self;
+    /// Consumes the type and returns all fields, including the `snap` request handler, and also
+    /// return a [`NetworkHandle`].
+    pub fn split_with_snap_handler(self) -> (NetworkHandle, NetworkManager<C>, Tx, Eth, Snap) {
+        let NetworkBuilder { network, transactions, request_handler, snap_request_handler } = self;
         let handle = network.handle().clone();
-        (handle, network, transactions, request_handler)
+        (handle, network, transactions, request_handler, snap_request_handler)
+    }
+
+    /// Adds an additional RLPx sub-protocol that is announced in the `Hello` message of all new
+    /// connections.
+    ///
+    /// If the protocol is shared with the remote, the protocol's connection handler receives the
+    /// protocol's own message stream on top of the RLPx connection.
+    pub fn add_rlpx_sub_protocol(mut self, protocol: impl IntoRlpxSubProtocol) -> Self {
+        self.network.add_rlpx_sub_protocol(protocol);
+        self
     }
 
     /// Creates a new [`TransactionsManager`] and wires it to the network.
     pub fn transactions<Pool: TransactionPool>(
         self,
         pool: Pool,
-    ) -> NetworkBuilder<C, TransactionsManager<Pool>, Eth> {
-        let NetworkBuilder { mut network, request_handler, .. } = self;
+    ) -> NetworkBuilder<C, TransactionsManager<Pool>, Eth, Snap> {
+        self.transactions_with_policy(pool, SqrtPeersFullPropagation)
+    }
+
+    /// Creates a new [`TransactionsManager`] that propagates transactions according to the given
+    /// [`TransactionPropagationPolicy`] and wires it to the network.
+    pub fn transactions_with_policy<Pool: TransactionPool>(
+        self,
+        pool: Pool,
+        policy: impl TransactionPropagationPolicy,
+    ) -> NetworkBuilder<C, TransactionsManager<Pool>, Eth, Snap> {
+        let NetworkBuilder { mut network, request_handler, snap_request_handler, .. } = self;
+        let (tx, rx) = mpsc::unbounded_channel();
+        network.set_transactions(tx);
+        let handle = network.handle().clone();
+        let transactions = TransactionsManager::with_policy(handle, pool, rx, policy);
+        NetworkBuilder { network, request_handler, transactions, snap_request_handler }
+    }
+
+    /// Creates a new [`TransactionsManager`] with the given [`TransactionsManagerConfig`] and
+    /// wires it to the network.
+    pub fn transactions_with_config<Pool: TransactionPool>(
+        self,
+        pool: Pool,
+        config: TransactionsManagerConfig,
+    ) -> NetworkBuilder<C, TransactionsManager<Pool>, Eth, Snap> {
+        let NetworkBuilder { mut network, request_handler, snap_request_handler, .. } = self;
         let (tx, rx) = mpsc::unbounded_channel();
         network.set_transactions(tx);
         let handle = network.handle().clone();
-        let transactions = TransactionsManager::new(handle, pool, rx);
-        NetworkBuilder { network, request_handler, transactions }
+        let transactions =
+            TransactionsManager::with_config(handle, pool, rx, SqrtPeersFullPropagation, config);
+        NetworkBuilder { network, request_handler, transactions, snap_request_handler }
     }
 
     /// Creates a new [`EthRequestHandler`] and wires it to the network.
     pub fn request_handler<Client>(
         self,
         client: Client,
-    ) -> NetworkBuilder<C, Tx, EthRequestHandler<Client>> {
-        let NetworkBuilder { mut network, transactions, .. } = self;
+    ) -> NetworkBuilder<C, Tx, EthRequestHandler<Client>, Snap> {
+        self.request_handler_with_limits(client, Default::default())
+    }
+
+    /// Creates a new [`EthRequestHandler`] that applies the given [`EthRequestLimits`] to every
+    /// peer and wires it to the network.
+    pub fn request_handler_with_limits<Client>(
+        self,
+        client: Client,
+        limits: EthRequestLimits,
+    ) -> NetworkBuilder<C, Tx, EthRequestHandler<Client>, Snap> {
+        let NetworkBuilder { mut network, transactions, snap_request_handler, .. } = self;
         let (tx, rx) = mpsc::channel(ETH_REQUEST_CHANNEL_CAPACITY);
         network.set_eth_request_handler(tx);
         let peers = network.handle().peers_handle().clone();
-        let request_handler = EthRequestHandler::new(client, peers, rx);
-        NetworkBuilder { network, request_handler, transactions }
+        let request_handler = EthRequestHandler::with_limits(client, peers, rx, limits);
+        NetworkBuilder { network, request_handler, transactions, snap_request_handler }
+    }
+
+    /// Creates a new [`SnapRequestHandler`] and registers the `snap` protocol with the network.
+    ///
+    /// The handler serves all `snap` requests and must be spawned, see
+    /// [`NetworkBuilder::split_with_snap_handler`].
+    ///
+    /// Note: `GetTrieNodes` requests are not served, so peers can not heal their state from this
+    /// node, which is why the node does not register the `snap` protocol by default.
+    pub fn snap_request_handler<Client>(
+        self,
+        client: Client,
+    ) -> NetworkBuilder<C, Tx, Eth, SnapRequestHandler<Client>> {
+        let NetworkBuilder { mut network, transactions, request_handler, .. } = self;
+        let (tx, rx) = mpsc::channel(SNAP_REQUEST_CHANNEL_CAPACITY);
+        network.add_rlpx_sub_protocol(SnapProtocolHandler::new(tx));
+        let snap_request_handler = SnapRequestHandler::new(client, rx);
+        NetworkBuilder { network, transactions, request_handler, snap_request_handler }
+    }
+}
+
+impl<C, Pool, Eth, Snap> NetworkBuilder<C, TransactionsManager<Pool>, Eth, Snap>
+where
+    Pool: TransactionPool,
+{
+    /// Sets the [`TransactionGossipValidator`] that validates all transactions received from
+    /// peers before they're imported into the pool.
+    pub fn transaction_gossip_validator(
+        mut self,
+        validator: impl TransactionGossipValidator,
+    ) -> Self {
+        self.transactions.set_gossip_validator(validator);
+        self
     }
 }
@@ -80,14 +80,11 @@ impl<T: Hash + Eq> LruCache<T> {
     }
 
     /// Returns number of elements currently in cache.
-    #[cfg(test)]
-    #[allow(dead_code)]
     pub fn len(&self) -> usize {
         self.inner.len()
     }
 
     /// Returns `true` if there are currently no elements in the cache.
-    #[cfg(test)]
     #[allow(dead_code)]
     pub fn is_empty(&self) -> bool {
         self.inner.is_empty()
@@ -1,6 +1,7 @@
 //! Network config support
 
 use crate::{
+    discovery::Discv5Config,
     error::NetworkError,
     import::{BlockImport, ProofOfStakeBlockImport},
     peers::PeersConfig,
@@ -10,9 +11,13 @@ use crate::{
 use reth_discv4::{Discv4Config, Discv4ConfigBuilder, DEFAULT_DISCOVERY_ADDRESS};
 use reth_dns_discovery::DnsDiscoveryConfig;
 use reth_ecies::util::pk2id;
-use reth_eth_wire::{HelloMessage, HelloMessageWithProtocols, Status};
+use reth_eth_wire::{
+    BlockRangeUpdate, EthVersion, HelloMessage, HelloMessageWithProtocols, Status,
+};
+use reth_net_nat::NatResolver;
 use reth_primitives::{
-    mainnet_nodes, sepolia_nodes, ChainSpec, ForkFilter, Head, NodeRecord, PeerId, MAINNET,
+    mainnet_nodes, sepolia_nodes, BlockNumber, ChainSpec, ForkFilter, Head, NodeRecord, PeerId,
+    PruneMode, MAINNET,
 };
 use reth_provider::{BlockReader, HeaderProvider};
 use reth_tasks::{TaskSpawner, TokioTaskExecutor};
@@ -43,10 +48,22 @@ pub struct NetworkConfig<C> {
     pub dns_discovery_config: Option<DnsDiscoveryConfig>,
     /// How to set up discovery.
     pub discovery_v4_config: Option<Discv4Config>,
+    /// How to set up discovery via discv5.
+    pub discovery_v5_config: Option<Discv5Config>,
     /// Address to use for discovery
     pub discovery_addr: SocketAddr,
     /// Address to listen for incoming connections
     pub listener_addr: SocketAddr,
+    /// Additional addresses to listen for incoming connections, e.g. an IPv6 address next to an
+    /// IPv4 [`NetworkConfig::listener_addr`].
+    ///
+    /// Addresses with port `0` use the port of the primary listener.
+    pub additional_listener_addrs: Vec<SocketAddr>,
+    /// An additional address of the other IP version for discovery, e.g. an IPv6 address next to
+    /// an IPv4 [`NetworkConfig::discovery_addr`].
+    ///
+    /// If the port is `0`, the port of the primary discovery address is used.
+    pub additional_discovery_addr: Option<SocketAddr>,
     /// How to instantiate peer manager.
     pub peers_config: PeersConfig,
     /// How to configure the [SessionManager](crate::session::SessionManager).
@@ -68,12 +85,22 @@ pub struct NetworkConfig<C> {
     pub executor: Box<dyn TaskSpawner>,
     /// The `Status` message to send to peers at the beginning.
     pub status: Status,
+    /// The range of blocks that is announced to `eth/69` peers at the beginning.
+    pub block_range: BlockRangeUpdate,
+    /// The prune mode of the receipts, which moves the earliest block of the announced range
+    /// forward as the head advances.
+    pub receipts_prune_mode: Option<PruneMode>,
     /// Sets the hello message for the p2p handshake in RLPx
     pub hello_message: HelloMessageWithProtocols,
     /// Additional protocols to announce and handle in RLPx
     pub extra_protocols: RlpxSubProtocols,
     /// Whether to disable transaction gossip
     pub tx_gossip_disabled: bool,
+    /// Whether discovered peers are only dialed once their EIP-2124 fork id was retrieved from
+    /// their ENR.
+    pub enforce_enr_fork_id: bool,
+    /// The resolver used to map the listener and discovery ports on the gateway, if any.
+    pub nat: Option<NatResolver>,
     /// Optimism Network Config
     #[cfg(feature = "optimism")]
     pub optimism_network_config: OptimismNetworkConfig,
@@ -142,12 +169,19 @@ pub struct NetworkConfigBuilder {
     dns_discovery_config: Option<DnsDiscoveryConfig>,
     /// How to set up discovery.
     discovery_v4_builder: Option<Discv4ConfigBuilder>,
+    /// How to set up discovery via discv5.
+    #[serde(skip)]
+    discovery_v5_config: Option<Discv5Config>,
     /// All boot nodes to start network discovery with.
     boot_nodes: HashSet<NodeRecord>,
     /// Address to use for discovery
     discovery_addr: Option<SocketAddr>,
     /// Listener for incoming connections
     listener_addr: Option<SocketAddr>,
+    /// Additional addresses of the listener for incoming connections
+    additional_listener_addrs: Vec<SocketAddr>,
+    /// Additional address of the other IP version for discovery
+    additional_discovery_addr: Option<SocketAddr>,
     /// How to instantiate peer manager.
     peers_config: Option<PeersConfig>,
     /// How to configure the sessions manager
@@ -161,13 +195,32 @@ pub struct NetworkConfigBuilder {
     executor: Option<Box<dyn TaskSpawner>>,
     /// Sets the hello message for the p2p handshake in RLPx
     hello_message: Option<HelloMessageWithProtocols>,
+    /// The client version that replaces the one of the hello message.
+    client_version: Option<String>,
+    /// The eth versions that are removed from the capabilities of the hello message.
+    #[serde(skip)]
+    disabled_eth_versions: Vec<EthVersion>,
+    /// The lowest eth version that is negotiated with peers.
+    #[serde(skip)]
+    min_eth_version: Option<EthVersion>,
+    /// The highest eth version that is negotiated with peers.
+    #[serde(skip)]
+    max_eth_version: Option<EthVersion>,
     /// The executor to use for spawning tasks.
     #[serde(skip)]
     extra_protocols: RlpxSubProtocols,
     /// Head used to start set for the fork filter and status.
     head: Option<Head>,
+    /// The earliest block whose header, body and receipts are available.
+    earliest_block: BlockNumber,
+    /// The prune mode of the receipts.
+    receipts_prune_mode: Option<PruneMode>,
     /// Whether tx gossip is disabled
     tx_gossip_disabled: bool,
+    /// Whether discovered peers are only dialed once their fork id is known.
+    enforce_enr_fork_id: bool,
+    /// The resolver used to map the ports on the gateway.
+    nat: Option<NatResolver>,
     /// The block importer type
     #[serde(skip)]
     block_import: Option<Box<dyn BlockImport>>,
@@ -194,18 +247,29 @@ impl NetworkConfigBuilder {
             secret_key,
             dns_discovery_config: Some(Default::default()),
             discovery_v4_builder: Some(Default::default()),
+            discovery_v5_config: None,
             boot_nodes: Default::default(),
             discovery_addr: None,
             listener_addr: None,
+            additional_listener_addrs: Vec::new(),
+            additional_discovery_addr: None,
             peers_config: None,
             sessions_config: None,
             chain_spec: MAINNET.clone(),
             network_mode: Default::default(),
             executor: None,
             hello_message: None,
+            client_version: None,
+            disabled_eth_versions: Vec::new(),
+            min_eth_version: None,
+            max_eth_version: None,
             extra_protocols: Default::default(),
             head: None,
+            earliest_block: 0,
+            receipts_prune_mode: None,
             tx_gossip_disabled: false,
+            enforce_enr_fork_id: false,
+            nat: None,
             block_import: None,
             #[cfg(feature = "optimism")]
             optimism_network_config: OptimismNetworkConfigBuilder::default(),
@@ -239,6 +303,23 @@ impl NetworkConfigBuilder {
         self
     }
 
+    /// Sets the earliest block whose header, body and receipts are available, e.g. the first
+    /// block after the pruned receipts.
+    ///
+    /// Together with the head, this is the range of blocks announced to `eth/69` peers. Defaults
+    /// to genesis.
+    pub fn earliest_block(mut self, earliest_block: BlockNumber) -> Self {
+        self.earliest_block = earliest_block;
+        self
+    }
+
+    /// Sets the prune mode of the receipts, so that the earliest block announced to `eth/69` peers
+    /// follows the pruning as the head advances.
+    pub fn receipts_prune_mode(mut self, receipts_prune_mode: Option<PruneMode>) -> Self {
+        self.receipts_prune_mode = receipts_prune_mode;
+        self
+    }
+
     /// Sets the `HelloMessage` to send when connecting to peers.
     ///
     /// ```
@@ -254,6 +335,40 @@ impl NetworkConfigBuilder {
         self
     }
 
+    /// Sets the client version that identifies the node in the `HelloMessage`, e.g.
+    /// `"reth/v0.1.0/x86_64-unknown-linux-gnu"`.
+    ///
+    /// This takes precedence over the client version of the
+    /// [NetworkConfigBuilder::hello_message].
+    pub fn client_version(mut self, client_version: impl Into<String>) -> Self {
+        self.client_version = Some(client_version.into());
+        self
+    }
+
+    /// Stops advertising the given eth version in the `HelloMessage`, e.g. to disable eth/66.
+    ///
+    /// Note: at least one eth version must remain, otherwise launching the network fails.
+    pub fn disable_eth_version(mut self, version: EthVersion) -> Self {
+        if !self.disabled_eth_versions.contains(&version) {
+            self.disabled_eth_versions.push(version);
+        }
+        self
+    }
+
+    /// Stops advertising eth versions lower than the given version in the `HelloMessage`, so that
+    /// sessions with peers that only support older versions are rejected.
+    pub fn min_eth_version(mut self, version: EthVersion) -> Self {
+        self.min_eth_version = Some(version);
+        self
+    }
+
+    /// Stops advertising eth versions higher than the given version in the `HelloMessage`, e.g. to
+    /// stay on eth/68 with all peers.
+    pub fn max_eth_version(mut self, version: EthVersion) -> Self {
+        self.max_eth_version = Some(version);
+        self
+    }
+
     /// Set a custom peer config for how peers are handled
     pub fn peer_config(mut self, config: PeersConfig) -> Self {
         self.peers_config = Some(config);
@@ -293,6 +408,19 @@ impl NetworkConfigBuilder {
         self
     }
 
+    /// Sets additional socket addresses the network will listen on, e.g. to listen on both IPv4
+    /// and IPv6 addresses.
+    ///
+    /// Addresses with port `0` use the port of the [NetworkConfigBuilder::listener_addr]. The first
+    /// address of the other IP version than the discovery address is advertised in the local ENR.
+    pub fn additional_listener_addrs(
+        mut self,
+        addrs: impl IntoIterator<Item = SocketAddr>,
+    ) -> Self {
+        self.additional_listener_addrs = addrs.into_iter().collect();
+        self
+    }
+
     /// Sets the port of the address the network will listen on.
     ///
     /// By default, this is [DEFAULT_DISCOVERY_PORT](reth_discv4::DEFAULT_DISCOVERY_PORT)
@@ -315,12 +443,31 @@ impl NetworkConfigBuilder {
         self
     }
 
+    /// Sets an additional socket address of the other IP version the discovery network will
+    /// listen on, e.g. to discover peers over both IPv4 and IPv6.
+    ///
+    /// Discv4 and discv5 each bind an additional socket. If the port is `0`, discv4 uses the port
+    /// of the [NetworkConfigBuilder::discovery_addr]. Discv5 always uses the port of its primary
+    /// address.
+    pub fn additional_discovery_addr(mut self, addr: Option<SocketAddr>) -> Self {
+        self.additional_discovery_addr = addr;
+        self
+    }
+
     /// Sets the discv4 config to use.
     pub fn discovery(mut self, builder: Discv4ConfigBuilder) -> Self {
         self.discovery_v4_builder = Some(builder);
         self
     }
 
+    /// Sets the discv5 config to use.
+    ///
+    /// Discv5 is disabled by default, if set, it runs alongside discv4 on its own UDP socket.
+    pub fn discovery_v5(mut self, config: Discv5Config) -> Self {
+        self.discovery_v5_config = Some(config);
+        self
+    }
+
     /// Sets the dns discovery config to use.
     pub fn dns_discovery(mut self, config: DnsDiscoveryConfig) -> Self {
         self.dns_discovery_config = Some(config);
@@ -351,7 +498,7 @@ impl NetworkConfigBuilder {
 
     /// Disables all discovery.
     pub fn disable_discovery(self) -> Self {
-        self.disable_discv4_discovery().disable_dns_discovery()
+        self.disable_discv4_discovery().disable_discv5_discovery().disable_dns_discovery()
     }
 
     /// Disables all discovery if the given condition is true.
@@ -369,6 +516,12 @@ impl NetworkConfigBuilder {
         self
     }
 
+    /// Disable the Discv5 discovery.
+    pub fn disable_discv5_discovery(mut self) -> Self {
+        self.discovery_v5_config = None;
+        self
+    }
+
     /// Disable the DNS discovery if the given condition is true.
     pub fn disable_dns_discovery_if(self, disable: bool) -> Self {
         if disable {
@@ -399,6 +552,25 @@ impl NetworkConfigBuilder {
         self
     }
 
+    /// Sets whether discovered peers are only dialed once their EIP-2124 fork id was retrieved
+    /// from their ENR via [EIP-868](https://eips.ethereum.org/EIPS/eip-868).
+    ///
+    /// This avoids dialing peers of incompatible chains, but ignores the discovered peers that
+    /// don't advertise a fork id. Requires EIP-868 support in discovery.
+    pub fn enforce_enr_fork_id(mut self, enforce_enr_fork_id: bool) -> Self {
+        self.enforce_enr_fork_id = enforce_enr_fork_id;
+        self
+    }
+
+    /// Sets the resolver used to map the listener and discovery ports on the gateway.
+    ///
+    /// The ports are only mapped if the resolver supports it, see
+    /// [NatResolver::supports_port_mapping].
+    pub fn nat(mut self, nat: Option<NatResolver>) -> Self {
+        self.nat = nat;
+        self
+    }
+
     /// Sets the block import type.
     pub fn block_import(mut self, block_import: Box<dyn BlockImport>) -> Self {
         self.block_import = Some(block_import);
@@ -424,18 +596,29 @@ impl NetworkConfigBuilder {
             secret_key,
             mut dns_discovery_config,
             discovery_v4_builder,
+            discovery_v5_config,
             boot_nodes,
             discovery_addr,
             listener_addr,
+            additional_listener_addrs,
+            additional_discovery_addr,
             peers_config,
             sessions_config,
             chain_spec,
             network_mode,
             executor,
             hello_message,
+            client_version,
+            disabled_eth_versions,
+            min_eth_version,
+            max_eth_version,
             extra_protocols,
             head,
+            earliest_block,
+            receipts_prune_mode,
             tx_gossip_disabled,
+            enforce_enr_fork_id,
+            nat,
             block_import,
             #[cfg(feature = "optimism")]
                 optimism_network_config: OptimismNetworkConfigBuilder { sequencer_endpoint },
@@ -446,6 +629,15 @@ impl NetworkConfigBuilder {
         let mut hello_message =
             hello_message.unwrap_or_else(|| HelloMessage::builder(peer_id).build());
         hello_message.port = listener_addr.port();
+        if let Some(client_version) = client_version {
+            hello_message.client_version = client_version;
+        }
+        hello_message.protocols.retain(|protocol| {
+            let Some(version) = protocol.cap.eth_version() else { return true };
+            !disabled_eth_versions.contains(&version) &&
+                min_eth_version.map_or(true, |min| version >= min) &&
+                max_eth_version.map_or(true, |max| version <= max)
+        });
 
         let head = head.unwrap_or(Head {
             hash: chain_spec.genesis_hash(),
@@ -457,6 +649,11 @@ impl NetworkConfigBuilder {
 
         // set the status
         let status = Status::spec_builder(&chain_spec, &head).build();
+        let block_range = BlockRangeUpdate {
+            earliest: earliest_block.min(head.number),
+            latest: head.number,
+            latest_hash: head.hash,
+        };
 
         // set a fork filter based on the chain spec and head
         let fork_filter = chain_spec.fork_filter(head);
@@ -478,8 +675,11 @@ impl NetworkConfigBuilder {
             boot_nodes,
             dns_discovery_config,
             discovery_v4_config: discovery_v4_builder.map(|builder| builder.build()),
+            discovery_v5_config,
             discovery_addr: discovery_addr.unwrap_or(DEFAULT_DISCOVERY_ADDRESS),
             listener_addr,
+            additional_listener_addrs,
+            additional_discovery_addr,
             peers_config: peers_config.unwrap_or_default(),
             sessions_config: sessions_config.unwrap_or_default(),
             chain_spec,
@@ -487,10 +687,14 @@ impl NetworkConfigBuilder {
             network_mode,
             executor: executor.unwrap_or_else(|| Box::<TokioTaskExecutor>::default()),
             status,
+            block_range,
+            receipts_prune_mode,
             hello_message,
             extra_protocols,
             fork_filter,
             tx_gossip_disabled,
+            enforce_enr_fork_id,
+            nat,
             #[cfg(feature = "optimism")]
             optimism_network_config: OptimismNetworkConfig { sequencer_endpoint },
         }
@@ -526,7 +730,7 @@ mod tests {
     use super::*;
     use rand::thread_rng;
     use reth_dns_discovery::tree::LinkEntry;
-    use reth_primitives::{Chain, ForkHash};
+    use reth_primitives::{Chain, ForkHash, B256};
     use reth_provider::test_utils::NoopProvider;
     use std::collections::BTreeMap;
 
@@ -547,6 +751,49 @@ mod tests {
         assert_eq!(bootstrap_nodes.len(), 1);
     }
 
+    #[test]
+    fn test_hello_message_customization() {
+        let config = builder()
+            .client_version("my-chain/v1.0.0")
+            .disable_eth_version(EthVersion::Eth66)
+            .build(NoopProvider::default());
+        assert_eq!(config.hello_message.client_version, "my-chain/v1.0.0");
+        assert!(!config.hello_message.contains_protocol(&EthVersion::Eth66.into()));
+        assert!(config.hello_message.contains_protocol(&EthVersion::Eth67.into()));
+        assert!(config.hello_message.contains_protocol(&EthVersion::Eth68.into()));
+        assert!(config.hello_message.contains_protocol(&EthVersion::Eth69.into()));
+    }
+
+    #[test]
+    fn test_eth_version_range() {
+        let config = builder()
+            .min_eth_version(EthVersion::Eth67)
+            .max_eth_version(EthVersion::Eth68)
+            .build(NoopProvider::default());
+        assert!(!config.hello_message.contains_protocol(&EthVersion::Eth66.into()));
+        assert!(config.hello_message.contains_protocol(&EthVersion::Eth67.into()));
+        assert!(config.hello_message.contains_protocol(&EthVersion::Eth68.into()));
+        assert!(!config.hello_message.contains_protocol(&EthVersion::Eth69.into()));
+    }
+
+    #[test]
+    fn test_block_range_from_head() {
+        // the range starts at genesis by default
+        let config = builder().build(NoopProvider::default());
+        let genesis_hash = MAINNET.genesis_hash();
+        assert_eq!(
+            config.block_range,
+            BlockRangeUpdate { earliest: 0, latest: 0, latest_hash: genesis_hash }
+        );
+
+        let head = Head { number: 100, hash: B256::random(), ..Default::default() };
+        let config = builder().set_head(head).earliest_block(10).build(NoopProvider::default());
+        assert_eq!(
+            config.block_range,
+            BlockRangeUpdate { earliest: 10, latest: 100, latest_hash: head.hash }
+        );
+    }
+
     #[test]
     fn test_network_fork_filter_default() {
         let mut chain_spec = Arc::clone(&MAINNET);
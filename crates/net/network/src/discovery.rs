@@ -4,22 +4,190 @@ use crate::{
     error::{NetworkError, ServiceKind},
     manager::DiscoveredEvent,
 };
+use alloy_rlp::Decodable;
+use discv5::{
+    enr::{CombinedKey, EnrBuilder, EnrPublicKey},
+    ListenConfig,
+};
+use enr::Enr;
 use futures::StreamExt;
 use reth_discv4::{DiscoveryUpdate, Discv4, Discv4Config, EnrForkIdEntry};
 use reth_dns_discovery::{
     DnsDiscoveryConfig, DnsDiscoveryHandle, DnsDiscoveryService, DnsNodeRecordUpdate, DnsResolver,
 };
+use reth_ecies::util::pk2id;
 use reth_primitives::{ForkId, NodeRecord, PeerId};
 use secp256k1::SecretKey;
 use std::{
     collections::{hash_map::Entry, HashMap, VecDeque},
-    net::{IpAddr, SocketAddr},
+    fmt,
+    net::{IpAddr, Ipv4Addr, SocketAddr},
     pin::Pin,
     sync::Arc,
     task::{ready, Context, Poll},
 };
 use tokio::{sync::mpsc, task::JoinHandle};
 use tokio_stream::{wrappers::ReceiverStream, Stream};
+use tracing::trace;
+
+/// The default port for discv5 via UDP.
+///
+/// Discv5 can't share a socket with discv4, this is the port commonly used by consensus clients.
+pub const DEFAULT_DISCOVERY_V5_PORT: u16 = 9000;
+
+/// The default address for discv5 via UDP: "0.0.0.0:9000"
+pub const DEFAULT_DISCOVERY_V5_ADDRESS: SocketAddr =
+    SocketAddr::new(IpAddr::V4(Ipv4Addr::UNSPECIFIED), DEFAULT_DISCOVERY_V5_PORT);
+
+/// Configuration for the discv5 service.
+#[derive(Debug, Clone)]
+pub struct Discv5Config {
+    /// The UDP address the discv5 service binds to.
+    pub discovery_addr: SocketAddr,
+    /// An additional UDP address of the other IP version the discv5 service binds to, to discover
+    /// peers over both IPv4 and IPv6.
+    pub additional_discovery_addr: Option<SocketAddr>,
+    /// The TCP port that is advertised in the local ENR.
+    ///
+    /// If not set, the port of the [`Discv5Config::discovery_addr`] is used.
+    pub tcp_port: Option<u16>,
+    /// Nodes to bootstrap the discv5 routing table with.
+    pub bootstrap_nodes: Vec<discv5::Enr>,
+    /// The config of the discv5 service itself.
+    pub config: discv5::Config,
+}
+
+// === impl Discv5Config ===
+
+impl Discv5Config {
+    /// Creates a new config that binds to the given address.
+    pub fn new(discovery_addr: SocketAddr) -> Self {
+        Self {
+            discovery_addr,
+            additional_discovery_addr: None,
+            tcp_port: None,
+            bootstrap_nodes: Vec::new(),
+            config: discv5::ConfigBuilder::new(discovery_addr.into()).build(),
+        }
+    }
+
+    /// Sets the TCP port that is advertised in the local ENR.
+    pub fn with_tcp_port(mut self, port: u16) -> Self {
+        self.tcp_port = Some(port);
+        self
+    }
+
+    /// Binds the discv5 service to an additional address of the other IP version than
+    /// [`Discv5Config::discovery_addr`], and advertises both addresses in the local ENR.
+    ///
+    /// This replaces the listen config of [`Discv5Config::config`]. Has no effect if the address
+    /// has the same IP version as [`Discv5Config::discovery_addr`], since discv5 binds to at
+    /// most one address per IP version.
+    pub fn with_additional_discovery_addr(mut self, addr: SocketAddr) -> Self {
+        let listen_config = match (self.discovery_addr, addr) {
+            (SocketAddr::V4(ipv4), SocketAddr::V6(ipv6)) |
+            (SocketAddr::V6(ipv6), SocketAddr::V4(ipv4)) => {
+                ListenConfig::from_two_sockets(Some(ipv4), Some(ipv6))
+            }
+            _ => return self,
+        };
+        self.additional_discovery_addr = Some(addr);
+        self.config = discv5::ConfigBuilder::new(listen_config).build();
+        self
+    }
+
+    /// Sets the nodes to bootstrap the routing table with.
+    pub fn with_bootstrap_nodes(mut self, nodes: impl IntoIterator<Item = discv5::Enr>) -> Self {
+        self.bootstrap_nodes = nodes.into_iter().collect();
+        self
+    }
+
+    /// Sets the config of the discv5 service.
+    ///
+    /// Note: the listen addresses of the given config must match [`Discv5Config::discovery_addr`]
+    /// and [`Discv5Config::additional_discovery_addr`].
+    pub fn with_discv5_config(mut self, config: discv5::Config) -> Self {
+        self.config = config;
+        self
+    }
+}
+
+impl Default for Discv5Config {
+    fn default() -> Self {
+        Self::new(DEFAULT_DISCOVERY_V5_ADDRESS)
+    }
+}
+
+/// Wrapper around the running discv5 service.
+struct Discv5Handle(discv5::Discv5);
+
+impl fmt::Debug for Discv5Handle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Discv5Handle").field("local_enr", &self.0.local_enr()).finish()
+    }
+}
+
+/// Converts a discv5 [`Enr`](discv5::Enr) into a [`NodeRecord`].
+///
+/// Returns `None` if the record is not signed with a secp256k1 key or does not advertise an IPv4
+/// address with a TCP port.
+fn enr_to_node_record(enr: &discv5::Enr) -> Option<NodeRecord> {
+    let public_key = secp256k1::PublicKey::from_slice(&enr.public_key().encode()).ok()?;
+    let address = enr.ip4()?;
+    let tcp_port = enr.tcp4()?;
+    let udp_port = enr.udp4().unwrap_or(tcp_port);
+    Some(NodeRecord { address: address.into(), tcp_port, udp_port, id: pk2id(&public_key) })
+}
+
+/// Returns the [`ForkId`] of the `eth` entry of a discv5 [`Enr`](discv5::Enr), see EIP-868.
+fn enr_fork_id(enr: &discv5::Enr) -> Option<ForkId> {
+    let mut rlp = enr.get_raw_rlp(b"eth")?;
+    EnrForkIdEntry::decode(&mut rlp).ok().map(|entry| entry.fork_id)
+}
+
+/// Spawns the discv5 service and returns the handle and the event stream.
+async fn start_discv5(
+    sk: &SecretKey,
+    config: Discv5Config,
+) -> Result<(Discv5Handle, ReceiverStream<discv5::Event>), NetworkError> {
+    let Discv5Config {
+        discovery_addr,
+        additional_discovery_addr,
+        tcp_port,
+        bootstrap_nodes,
+        config,
+    } = config;
+    let err = |err: String| NetworkError::Discv5(discovery_addr, err);
+
+    let mut key_bytes = sk.secret_bytes();
+    let key =
+        CombinedKey::secp256k1_from_bytes(&mut key_bytes).map_err(|e| err(format!("{e:?}")))?;
+
+    let mut builder = EnrBuilder::new("v4");
+    let tcp_port = tcp_port.unwrap_or(discovery_addr.port());
+    for addr in std::iter::once(discovery_addr).chain(additional_discovery_addr) {
+        if !addr.ip().is_unspecified() {
+            builder.ip(addr.ip());
+        }
+        match addr {
+            SocketAddr::V4(addr) => builder.udp4(addr.port()).tcp4(tcp_port),
+            SocketAddr::V6(addr) => builder.udp6(addr.port()).tcp6(tcp_port),
+        };
+    }
+    let local_enr = builder.build(&key).map_err(|e| err(format!("{e:?}")))?;
+
+    let mut discv5 =
+        discv5::Discv5::new(local_enr, key, config).map_err(|e| err(format!("{e:?}")))?;
+    for enr in bootstrap_nodes {
+        if let Err(e) = discv5.add_enr(enr) {
+            trace!(target: "net::discovery", %e, "failed to add discv5 bootstrap node");
+        }
+    }
+    discv5.start().await.map_err(|e| err(format!("{e:?}")))?;
+    let events = discv5.event_stream().await.map_err(|e| err(format!("{e:?}")))?;
+
+    Ok((Discv5Handle(discv5), ReceiverStream::new(events)))
+}
 
 /// An abstraction over the configured discovery protocol.
 ///
@@ -39,6 +207,10 @@ pub struct Discovery {
     discv4_updates: Option<ReceiverStream<DiscoveryUpdate>>,
     /// The handle to the spawned discv4 service
     _discv4_service: Option<JoinHandle<()>>,
+    /// The running discv5 service.
+    _discv5: Option<Discv5Handle>,
+    /// All events emitted by the discv5 service.
+    discv5_updates: Option<ReceiverStream<discv5::Event>>,
     /// Handler to interact with the DNS discovery service
     _dns_discovery: Option<DnsDiscoveryHandle>,
     /// Updates from the DNS discovery service.
@@ -49,6 +221,8 @@ pub struct Discovery {
     queued_events: VecDeque<DiscoveryEvent>,
     /// List of listeners subscribed to discovery events.
     discovery_listeners: Vec<mpsc::UnboundedSender<DiscoveryEvent>>,
+    /// Whether nodes are only reported once their [`ForkId`] is known.
+    enforce_enr_fork_id: bool,
 }
 
 impl Discovery {
@@ -56,10 +230,14 @@ impl Discovery {
     ///
     /// This will spawn the [`reth_discv4::Discv4Service`] onto a new task and establish a listener
     /// channel to receive all discovered nodes.
+    ///
+    /// If a [`Discv5Config`] is provided, discv5 is started on its own UDP socket alongside
+    /// discv4 and the nodes found by both protocols are merged.
     pub async fn new(
         discovery_addr: SocketAddr,
         sk: SecretKey,
         discv4_config: Option<Discv4Config>,
+        discv5_config: Option<Discv5Config>,
         dns_discovery_config: Option<DnsDiscoveryConfig>,
     ) -> Result<Self, NetworkError> {
         // setup discv4
@@ -77,6 +255,14 @@ impl Discovery {
             (None, None, None)
         };
 
+        // setup discv5
+        let (_discv5, discv5_updates) = if let Some(discv5_config) = discv5_config {
+            let (discv5, updates) = start_discv5(&sk, discv5_config).await?;
+            (Some(discv5), Some(updates))
+        } else {
+            (None, None)
+        };
+
         // setup DNS discovery
         let (_dns_discovery, dns_discovery_updates, _dns_disc_service) =
             if let Some(dns_config) = dns_discovery_config {
@@ -97,14 +283,30 @@ impl Discovery {
             discv4,
             discv4_updates,
             _discv4_service,
+            _discv5,
+            discv5_updates,
             discovered_nodes: Default::default(),
             queued_events: Default::default(),
             _dns_disc_service,
             _dns_discovery,
             dns_discovery_updates,
+            enforce_enr_fork_id: false,
         })
     }
 
+    /// Sets whether discovered nodes are only reported once their [`ForkId`] was retrieved from
+    /// their ENR, see [EIP-868](https://eips.ethereum.org/EIPS/eip-868).
+    ///
+    /// Nodes that don't advertise a fork id are never reported.
+    pub(crate) fn set_enforce_enr_fork_id(&mut self, enforce_enr_fork_id: bool) {
+        self.enforce_enr_fork_id = enforce_enr_fork_id;
+    }
+
+    /// Returns the frontend of the discv4 service, if discv4 is enabled.
+    pub(crate) fn discv4(&self) -> Option<Discv4> {
+        self.discv4.clone()
+    }
+
     /// Registers a listener for receiving [DiscoveryEvent] updates.
     pub(crate) fn add_listener(&mut self, tx: mpsc::UnboundedSender<DiscoveryEvent>) {
         self.discovery_listeners.push(tx);
@@ -152,6 +354,10 @@ impl Discovery {
 
     /// Processes an incoming [NodeRecord] update from a discovery service
     fn on_node_record_update(&mut self, record: NodeRecord, fork_id: Option<ForkId>) {
+        if self.enforce_enr_fork_id && fork_id.is_none() {
+            // the node is reported once its fork id was retrieved
+            return
+        }
         let id = record.id;
         let addr = record.tcp_addr();
         match self.discovered_nodes.entry(id) {
@@ -171,8 +377,14 @@ impl Discovery {
                 self.on_node_record_update(record, None);
             }
             DiscoveryUpdate::EnrForkId(node, fork_id) => {
+                if self.enforce_enr_fork_id {
+                    self.on_node_record_update(node, Some(fork_id));
+                }
                 self.queued_events.push_back(DiscoveryEvent::EnrForkId(node.id, fork_id))
             }
+            DiscoveryUpdate::Enr(node, enr) => {
+                self.queued_events.push_back(DiscoveryEvent::Enr(node.id, enr))
+            }
             DiscoveryUpdate::Removed(node) => {
                 self.discovered_nodes.remove(&node);
             }
@@ -187,6 +399,17 @@ impl Discovery {
         }
     }
 
+    fn on_discv5_event(&mut self, event: discv5::Event) {
+        match event {
+            discv5::Event::Discovered(enr) | discv5::Event::SessionEstablished(enr, _) => {
+                if let Some(record) = enr_to_node_record(&enr) {
+                    self.on_node_record_update(record, enr_fork_id(&enr));
+                }
+            }
+            _ => {}
+        }
+    }
+
     pub(crate) fn poll(&mut self, cx: &mut Context<'_>) -> Poll<DiscoveryEvent> {
         loop {
             // Drain all buffered events first
@@ -202,6 +425,12 @@ impl Discovery {
                 self.on_discv4_update(update)
             }
 
+            while let Some(Poll::Ready(Some(event))) =
+                self.discv5_updates.as_mut().map(|updates| updates.poll_next_unpin(cx))
+            {
+                self.on_discv5_event(event)
+            }
+
             while let Some(Poll::Ready(Some(update))) =
                 self.dns_discovery_updates.as_mut().map(|updates| updates.poll_next_unpin(cx))
             {
@@ -245,10 +474,13 @@ impl Discovery {
             discv4_updates: Default::default(),
             queued_events: Default::default(),
             _discv4_service: Default::default(),
+            _discv5: None,
+            discv5_updates: None,
             _dns_discovery: None,
             dns_discovery_updates: None,
             _dns_disc_service: None,
             discovery_listeners: Default::default(),
+            enforce_enr_fork_id: false,
         }
     }
 }
@@ -260,23 +492,134 @@ pub enum DiscoveryEvent {
     NewNode(DiscoveredEvent),
     /// Retrieved a [`ForkId`] from the peer via ENR request, See <https://eips.ethereum.org/EIPS/eip-868>
     EnrForkId(PeerId, ForkId),
+    /// Retrieved the ENR of the peer via ENR request, See <https://eips.ethereum.org/EIPS/eip-868>
+    Enr(PeerId, Enr<SecretKey>),
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use rand::thread_rng;
+    use reth_primitives::ForkHash;
     use secp256k1::SECP256K1;
-    use std::net::{Ipv4Addr, SocketAddrV4};
+    use std::net::{Ipv4Addr, Ipv6Addr, SocketAddrV4, SocketAddrV6};
 
     #[tokio::test(flavor = "multi_thread")]
     async fn test_discovery_setup() {
         let mut rng = thread_rng();
         let (secret_key, _) = SECP256K1.generate_keypair(&mut rng);
         let discovery_addr = SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::UNSPECIFIED, 0));
-        let _discovery =
-            Discovery::new(discovery_addr, secret_key, Default::default(), Default::default())
-                .await
-                .unwrap();
+        let _discovery = Discovery::new(
+            discovery_addr,
+            secret_key,
+            Default::default(),
+            None,
+            Default::default(),
+        )
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_discv4_and_discv5_setup() {
+        let mut rng = thread_rng();
+        let (secret_key, _) = SECP256K1.generate_keypair(&mut rng);
+        let discovery_addr = SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::UNSPECIFIED, 0));
+        let discv5_addr = SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 0));
+        let discovery = Discovery::new(
+            discovery_addr,
+            secret_key,
+            Some(Default::default()),
+            Some(Discv5Config::new(discv5_addr)),
+            None,
+        )
+        .await
+        .unwrap();
+        assert!(discovery.discv4.is_some());
+        assert!(discovery.discv5_updates.is_some());
+    }
+
+    #[test]
+    fn test_discv5_additional_discovery_addr() {
+        let ipv6_addr = SocketAddr::V6(SocketAddrV6::new(Ipv6Addr::UNSPECIFIED, 9000, 0, 0));
+        let config = Discv5Config::default().with_additional_discovery_addr(ipv6_addr);
+        assert_eq!(config.additional_discovery_addr, Some(ipv6_addr));
+
+        // discv5 binds to at most one address per IP version
+        let ipv4_addr = SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 9001));
+        let config = Discv5Config::default().with_additional_discovery_addr(ipv4_addr);
+        assert_eq!(config.additional_discovery_addr, None);
+    }
+
+    #[test]
+    fn test_enr_to_node_record() {
+        let mut rng = thread_rng();
+        let (secret_key, public_key) = SECP256K1.generate_keypair(&mut rng);
+        let key = CombinedKey::secp256k1_from_bytes(&mut secret_key.secret_bytes()).unwrap();
+        let enr = EnrBuilder::new("v4")
+            .ip4(Ipv4Addr::LOCALHOST)
+            .udp4(30303)
+            .tcp4(30304)
+            .build(&key)
+            .unwrap();
+
+        let record = enr_to_node_record(&enr).unwrap();
+        assert_eq!(record.id, pk2id(&public_key));
+        assert_eq!(record.address, IpAddr::V4(Ipv4Addr::LOCALHOST));
+        assert_eq!(record.tcp_port, 30304);
+        assert_eq!(record.udp_port, 30303);
+
+        // nodes that can't be dialed via TCP are ignored
+        let enr = EnrBuilder::new("v4").ip4(Ipv4Addr::LOCALHOST).udp4(30303).build(&key).unwrap();
+        assert!(enr_to_node_record(&enr).is_none());
+    }
+
+    #[test]
+    fn test_enr_fork_id() {
+        let mut rng = thread_rng();
+        let (secret_key, _) = SECP256K1.generate_keypair(&mut rng);
+        let key = CombinedKey::secp256k1_from_bytes(&mut secret_key.secret_bytes()).unwrap();
+        let fork_id = ForkId { hash: ForkHash([0xde, 0xad, 0xbe, 0xef]), next: 0 };
+        let enr = EnrBuilder::new("v4")
+            .ip4(Ipv4Addr::LOCALHOST)
+            .tcp4(30303)
+            .add_value_rlp("eth", alloy_rlp::encode(EnrForkIdEntry::from(fork_id)).into())
+            .build(&key)
+            .unwrap();
+        assert_eq!(enr_fork_id(&enr), Some(fork_id));
+
+        let enr = EnrBuilder::new("v4").ip4(Ipv4Addr::LOCALHOST).tcp4(30303).build(&key).unwrap();
+        assert_eq!(enr_fork_id(&enr), None);
+    }
+
+    #[test]
+    fn test_enforce_enr_fork_id() {
+        let mut discovery = Discovery::noop();
+        discovery.set_enforce_enr_fork_id(true);
+        let record = NodeRecord {
+            address: IpAddr::V4(Ipv4Addr::LOCALHOST),
+            tcp_port: 30303,
+            udp_port: 30303,
+            id: PeerId::random(),
+        };
+
+        // the node is not reported until its fork id is known
+        discovery.on_discv4_update(DiscoveryUpdate::Added(record));
+        assert!(discovery.queued_events.is_empty());
+
+        let fork_id = ForkId { hash: ForkHash([0xde, 0xad, 0xbe, 0xef]), next: 0 };
+        discovery.on_discv4_update(DiscoveryUpdate::EnrForkId(record, fork_id));
+        assert!(matches!(
+            discovery.queued_events.pop_front(),
+            Some(DiscoveryEvent::NewNode(DiscoveredEvent::EventQueued {
+                peer_id,
+                fork_id: Some(id),
+                ..
+            })) if peer_id == record.id && id == fork_id
+        ));
+        assert!(matches!(
+            discovery.queued_events.pop_front(),
+            Some(DiscoveryEvent::EnrForkId(peer_id, id)) if peer_id == record.id && id == fork_id
+        ));
     }
 }
@@ -43,6 +43,12 @@ pub enum NetworkError {
     /// IO error when creating the discovery service
     #[error("failed to launch discovery service: {0}")]
     Discovery(io::Error),
+    /// Error when launching the discv5 service failed.
+    #[error("failed to launch discv5 service on {0}: {1}")]
+    Discv5(SocketAddr, String),
+    /// Error when the capabilities of the configured hello message aren't handled by the node.
+    #[error("invalid hello message: {0}")]
+    InvalidHelloMessage(String),
     /// Error when setting up the DNS resolver failed
     ///
     /// See also [DnsResolver](reth_dns_discovery::DnsResolver::from_system_conf)
@@ -224,6 +230,7 @@ impl SessionError for PendingSessionHandshakeError {
         match self {
             PendingSessionHandshakeError::Eth(eth) => eth.merits_discovery_ban(),
             PendingSessionHandshakeError::Ecies(_) => true,
+            PendingSessionHandshakeError::Timeout => false,
         }
     }
 
@@ -231,6 +238,7 @@ impl SessionError for PendingSessionHandshakeError {
         match self {
             PendingSessionHandshakeError::Eth(eth) => eth.is_fatal_protocol_error(),
             PendingSessionHandshakeError::Ecies(_) => true,
+            PendingSessionHandshakeError::Timeout => false,
         }
     }
 
@@ -238,6 +246,7 @@ impl SessionError for PendingSessionHandshakeError {
         match self {
             PendingSessionHandshakeError::Eth(eth) => eth.should_backoff(),
             PendingSessionHandshakeError::Ecies(_) => Some(BackoffKind::Low),
+            PendingSessionHandshakeError::Timeout => Some(BackoffKind::Medium),
         }
     }
 }
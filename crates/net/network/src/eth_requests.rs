@@ -1,23 +1,29 @@
 //! Blocks/Headers management for the p2p network.
 
 use crate::{metrics::EthRequestHandlerMetrics, peers::PeersHandle};
+use alloy_rlp::Encodable;
 use futures::StreamExt;
 use reth_eth_wire::{
     BlockBodies, BlockHeaders, GetBlockBodies, GetBlockHeaders, GetNodeData, GetReceipts, NodeData,
     Receipts,
 };
 use reth_interfaces::p2p::error::RequestResult;
+use reth_net_common::token_bucket::TokenBucket;
+use reth_network_api::ReputationChangeKind;
 use reth_primitives::{BlockBody, BlockHashOrNumber, Header, HeadersDirection, PeerId};
 use reth_provider::{BlockReader, HeaderProvider, ReceiptProvider};
 use std::{
     borrow::Borrow,
+    collections::{HashMap, VecDeque},
     future::Future,
     hash::Hash,
     pin::Pin,
     task::{Context, Poll},
+    time::{Duration, Instant},
 };
 use tokio::sync::{mpsc::Receiver, oneshot};
 use tokio_stream::wrappers::ReceiverStream;
+use tracing::trace;
 
 // Limits: <https://github.com/ethereum/go-ethereum/blob/b0d44338bbcefee044f1f635a84487cbbd8f0538/eth/protocols/eth/handler.go#L34-L56>
 
@@ -50,6 +56,140 @@ const SOFT_RESPONSE_LIMIT: usize = 2 * 1024 * 1024;
 /// Estimated size in bytes of an RLP encoded header.
 const APPROX_HEADER_SIZE: usize = 500;
 
+/// Default maximum number of requests of a single peer that are queued for processing.
+///
+/// This is a quarter of the capacity of the channel between the network and the handler, so that
+/// a single peer can't occupy all of it.
+pub const DEFAULT_MAX_IN_FLIGHT_REQUESTS_PER_PEER: usize = 64;
+
+/// How long the request state of a peer without any queued requests is kept around.
+const IDLE_PEER_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Limits for the requests served to a single peer.
+///
+/// Requests that exceed any of the limits are not answered and the peer's reputation is penalized
+/// with [`ReputationChangeKind::ExceededRequestLimits`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct EthRequestLimits {
+    /// Maximum number of requests per second a peer is allowed to send.
+    pub max_requests_per_sec: Option<u64>,
+    /// Maximum number of requests of a peer that are queued for processing.
+    pub max_in_flight: Option<usize>,
+    /// Maximum number of response bytes served to a peer within
+    /// [`EthRequestLimits::response_bytes_window`].
+    pub max_response_bytes_per_window: Option<u64>,
+    /// The window [`EthRequestLimits::max_response_bytes_per_window`] applies to.
+    #[cfg_attr(feature = "serde", serde(with = "humantime_serde"))]
+    pub response_bytes_window: Duration,
+}
+
+impl EthRequestLimits {
+    /// Sets the maximum number of requests per second a peer is allowed to send.
+    pub fn with_max_requests_per_sec(mut self, limit: u64) -> Self {
+        self.max_requests_per_sec = Some(limit);
+        self
+    }
+
+    /// Sets the maximum number of requests of a peer that are queued for processing.
+    pub fn with_max_in_flight(mut self, limit: usize) -> Self {
+        self.max_in_flight = Some(limit);
+        self
+    }
+
+    /// Sets the maximum number of response bytes served to a peer within the given window.
+    pub fn with_max_response_bytes_per_window(mut self, limit: u64, window: Duration) -> Self {
+        self.max_response_bytes_per_window = Some(limit);
+        self.response_bytes_window = window;
+        self
+    }
+}
+
+impl Default for EthRequestLimits {
+    fn default() -> Self {
+        Self {
+            max_requests_per_sec: None,
+            max_in_flight: Some(DEFAULT_MAX_IN_FLIGHT_REQUESTS_PER_PEER),
+            max_response_bytes_per_window: None,
+            response_bytes_window: Duration::from_secs(1),
+        }
+    }
+}
+
+/// Tracks the requests of a single peer.
+#[derive(Debug)]
+struct PeerRequests {
+    /// Requests waiting to be processed.
+    queued: VecDeque<IncomingEthRequest>,
+    /// Limits the rate of incoming requests, if configured.
+    rate: Option<TokenBucket>,
+    /// Start of the current response bytes window.
+    window_start: Instant,
+    /// Response bytes served in the current window.
+    window_bytes: u64,
+    /// When the last request was received.
+    last_request: Instant,
+}
+
+// === impl PeerRequests ===
+
+impl PeerRequests {
+    fn new(limits: &EthRequestLimits, now: Instant) -> Self {
+        Self {
+            queued: VecDeque::new(),
+            rate: limits.max_requests_per_sec.map(TokenBucket::new),
+            window_start: now,
+            window_bytes: 0,
+            last_request: now,
+        }
+    }
+
+    /// Queues the request if it's within the rate and in-flight limits.
+    ///
+    /// Returns the request if it exceeds any of the limits.
+    fn try_queue(
+        &mut self,
+        request: IncomingEthRequest,
+        limits: &EthRequestLimits,
+        now: Instant,
+    ) -> Result<(), IncomingEthRequest> {
+        self.last_request = now;
+        if limits.max_in_flight.is_some_and(|max| self.queued.len() >= max) {
+            return Err(request)
+        }
+        if let Some(rate) = self.rate.as_mut() {
+            if !rate.wait_time(now).is_zero() {
+                return Err(request)
+            }
+            rate.consume(1);
+        }
+        self.queued.push_back(request);
+        Ok(())
+    }
+
+    /// Returns true if the peer hasn't exhausted its response bytes in the current window.
+    fn can_serve(&mut self, limits: &EthRequestLimits, now: Instant) -> bool {
+        let Some(max_bytes) = limits.max_response_bytes_per_window else { return true };
+        if now.saturating_duration_since(self.window_start) >= limits.response_bytes_window {
+            self.window_start = now;
+            self.window_bytes = 0;
+        }
+        self.window_bytes < max_bytes
+    }
+
+    /// Records the size of a response served to the peer.
+    fn on_served(&mut self, bytes: usize) {
+        self.window_bytes = self.window_bytes.saturating_add(bytes as u64);
+    }
+
+    /// Returns true if the peer has no queued requests and hasn't sent any for a while.
+    fn is_idle(&self, now: Instant) -> bool {
+        self.queued.is_empty() &&
+            now.saturating_duration_since(self.last_request) >= IDLE_PEER_TIMEOUT
+    }
+}
+
 /// Manages eth related requests on top of the p2p network.
 ///
 /// This can be spawned to another task and is supposed to be run as background service.
@@ -59,11 +199,13 @@ pub struct EthRequestHandler<C> {
     /// The client type that can interact with the chain.
     client: C,
     /// Used for reporting peers.
-    // TODO use to report spammers
-    #[allow(dead_code)]
     peers: PeersHandle,
     /// Incoming request from the [NetworkManager](crate::NetworkManager).
     incoming_requests: ReceiverStream<IncomingEthRequest>,
+    /// Limits applied to the requests of every peer.
+    limits: EthRequestLimits,
+    /// Queued requests and limit state of all peers that recently sent requests.
+    peer_requests: HashMap<PeerId, PeerRequests>,
     /// Metrics for the eth request handler.
     metrics: EthRequestHandlerMetrics,
 }
@@ -72,8 +214,52 @@ pub struct EthRequestHandler<C> {
 impl<C> EthRequestHandler<C> {
     /// Create a new instance
     pub fn new(client: C, peers: PeersHandle, incoming: Receiver<IncomingEthRequest>) -> Self {
+        Self::with_limits(client, peers, incoming, Default::default())
+    }
+
+    /// Create a new instance that applies the given per peer [`EthRequestLimits`].
+    pub fn with_limits(
+        client: C,
+        peers: PeersHandle,
+        incoming: Receiver<IncomingEthRequest>,
+        limits: EthRequestLimits,
+    ) -> Self {
         let metrics = Default::default();
-        Self { client, peers, incoming_requests: ReceiverStream::new(incoming), metrics }
+        Self {
+            client,
+            peers,
+            incoming_requests: ReceiverStream::new(incoming),
+            limits,
+            peer_requests: Default::default(),
+            metrics,
+        }
+    }
+
+    /// Queues an incoming request or rejects it if the peer exceeds its limits.
+    fn on_incoming_request(&mut self, request: IncomingEthRequest, now: Instant) {
+        let peer_id = *request.peer_id();
+        let peer = self
+            .peer_requests
+            .entry(peer_id)
+            .or_insert_with(|| PeerRequests::new(&self.limits, now));
+        if let Err(request) = peer.try_queue(request, &self.limits, now) {
+            self.reject_request(request);
+        }
+    }
+
+    /// Drops the request without responding and penalizes the peer.
+    fn reject_request(&mut self, request: IncomingEthRequest) {
+        let peer_id = *request.peer_id();
+        trace!(target: "net::eth", ?peer_id, "peer exceeded request limits");
+        self.metrics.rejected_requests.increment(1);
+        self.peers.reputation_change(peer_id, ReputationChangeKind::ExceededRequestLimits);
+    }
+
+    /// Records the size of a response served to the peer.
+    fn on_response_served(&mut self, peer_id: &PeerId, bytes: usize) {
+        if let Some(peer) = self.peer_requests.get_mut(peer_id) {
+            peer.on_served(bytes);
+        }
     }
 }
 
@@ -148,18 +334,19 @@ where
 
     fn on_headers_request(
         &mut self,
-        _peer_id: PeerId,
+        peer_id: PeerId,
         request: GetBlockHeaders,
         response: oneshot::Sender<RequestResult<BlockHeaders>>,
     ) {
         self.metrics.received_headers_requests.increment(1);
-        let headers = self.get_headers_response(request);
-        let _ = response.send(Ok(BlockHeaders(headers)));
+        let headers = BlockHeaders(self.get_headers_response(request));
+        self.on_response_served(&peer_id, headers.length());
+        let _ = response.send(Ok(headers));
     }
 
     fn on_bodies_request(
         &mut self,
-        _peer_id: PeerId,
+        peer_id: PeerId,
         request: GetBlockBodies,
         response: oneshot::Sender<RequestResult<BlockBodies>>,
     ) {
@@ -192,12 +379,14 @@ where
             }
         }
 
-        let _ = response.send(Ok(BlockBodies(bodies)));
+        let bodies = BlockBodies(bodies);
+        self.on_response_served(&peer_id, bodies.length());
+        let _ = response.send(Ok(bodies));
     }
 
     fn on_receipts_request(
         &mut self,
-        _peer_id: PeerId,
+        peer_id: PeerId,
         request: GetReceipts,
         response: oneshot::Sender<RequestResult<Receipts>>,
     ) {
@@ -230,7 +419,9 @@ where
             }
         }
 
-        let _ = response.send(Ok(Receipts(receipts)));
+        let receipts = Receipts(receipts);
+        self.on_response_served(&peer_id, receipts.length());
+        let _ = response.send(Ok(receipts));
     }
 }
 
@@ -247,10 +438,41 @@ where
         let this = self.get_mut();
 
         loop {
-            match this.incoming_requests.poll_next_unpin(cx) {
-                Poll::Pending => return Poll::Pending,
-                Poll::Ready(None) => return Poll::Ready(()),
-                Poll::Ready(Some(incoming)) => match incoming {
+            // drain the channel first, so that the requests of a single peer can't occupy all of
+            // its capacity
+            let mut is_terminated = false;
+            let now = Instant::now();
+            loop {
+                match this.incoming_requests.poll_next_unpin(cx) {
+                    Poll::Pending => break,
+                    Poll::Ready(None) => {
+                        is_terminated = true;
+                        break
+                    }
+                    Poll::Ready(Some(incoming)) => this.on_incoming_request(incoming, now),
+                }
+            }
+
+            // serve one request of every peer per round
+            let mut served_any = false;
+            let peers = this
+                .peer_requests
+                .iter()
+                .filter(|(_, peer)| !peer.queued.is_empty())
+                .map(|(peer_id, _)| *peer_id)
+                .collect::<Vec<_>>();
+            for peer_id in peers {
+                let Some(peer) = this.peer_requests.get_mut(&peer_id) else { continue };
+                let can_serve = peer.can_serve(&this.limits, now);
+                let Some(incoming) = peer.queued.pop_front() else { continue };
+                served_any = true;
+
+                if !can_serve {
+                    this.reject_request(incoming);
+                    continue
+                }
+
+                match incoming {
                     IncomingEthRequest::GetBlockHeaders { peer_id, request, response } => {
                         this.on_headers_request(peer_id, request, response)
                     }
@@ -261,7 +483,16 @@ where
                     IncomingEthRequest::GetReceipts { peer_id, request, response } => {
                         this.on_receipts_request(peer_id, request, response)
                     }
-                },
+                }
+            }
+
+            if !served_any {
+                this.peer_requests.retain(|_, peer| !peer.is_idle(now));
+
+                if is_terminated {
+                    return Poll::Ready(())
+                }
+                return Poll::Pending
             }
         }
     }
@@ -330,3 +561,73 @@ pub enum IncomingEthRequest {
         response: oneshot::Sender<RequestResult<Receipts>>,
     },
 }
+
+// === impl IncomingEthRequest ===
+
+impl IncomingEthRequest {
+    /// Returns the id of the peer that sent the request.
+    pub fn peer_id(&self) -> &PeerId {
+        match self {
+            IncomingEthRequest::GetBlockHeaders { peer_id, .. } |
+            IncomingEthRequest::GetBlockBodies { peer_id, .. } |
+            IncomingEthRequest::GetNodeData { peer_id, .. } |
+            IncomingEthRequest::GetReceipts { peer_id, .. } => peer_id,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::B256;
+
+    fn bodies_request(peer_id: PeerId) -> IncomingEthRequest {
+        let (response, _) = oneshot::channel();
+        IncomingEthRequest::GetBlockBodies {
+            peer_id,
+            request: GetBlockBodies(vec![B256::ZERO]),
+            response,
+        }
+    }
+
+    #[test]
+    fn test_in_flight_and_rate_limits() {
+        let peer_id = PeerId::random();
+        let now = Instant::now();
+
+        let limits = EthRequestLimits::default().with_max_in_flight(2);
+        let mut peer = PeerRequests::new(&limits, now);
+        assert!(peer.try_queue(bodies_request(peer_id), &limits, now).is_ok());
+        assert!(peer.try_queue(bodies_request(peer_id), &limits, now).is_ok());
+        assert!(peer.try_queue(bodies_request(peer_id), &limits, now).is_err());
+        peer.queued.pop_front();
+        assert!(peer.try_queue(bodies_request(peer_id), &limits, now).is_ok());
+
+        let limits = EthRequestLimits::default().with_max_requests_per_sec(2);
+        let mut peer = PeerRequests::new(&limits, now);
+        for _ in 0..3 {
+            assert!(peer.try_queue(bodies_request(peer_id), &limits, now).is_ok());
+        }
+        // the bucket is in debt now
+        assert!(peer.try_queue(bodies_request(peer_id), &limits, now).is_err());
+        let later = now + Duration::from_secs(1);
+        assert!(peer.try_queue(bodies_request(peer_id), &limits, later).is_ok());
+    }
+
+    #[test]
+    fn test_response_bytes_limit() {
+        let now = Instant::now();
+        let window = Duration::from_secs(1);
+        let limits = EthRequestLimits::default().with_max_response_bytes_per_window(100, window);
+        let mut peer = PeerRequests::new(&limits, now);
+
+        assert!(peer.can_serve(&limits, now));
+        peer.on_served(150);
+        assert!(!peer.can_serve(&limits, now));
+
+        // a new window starts
+        assert!(peer.can_serve(&limits, now + window));
+        assert!(!peer.is_idle(now));
+        assert!(peer.is_idle(now + IDLE_PEER_TIMEOUT));
+    }
+}
@@ -1,6 +1,10 @@
 //! Fetch data from the network.
 
-use crate::{message::BlockRequest, peers::PeersHandle};
+use crate::{
+    message::BlockRequest,
+    metrics::{FetchMetrics, FetchPeerMetrics},
+    peers::PeersHandle,
+};
 use futures::StreamExt;
 use reth_eth_wire::{GetBlockBodies, GetBlockHeaders};
 use reth_interfaces::p2p::{
@@ -11,12 +15,16 @@ use reth_interfaces::p2p::{
 use reth_network_api::ReputationChangeKind;
 use reth_primitives::{BlockBody, Header, PeerId, B256};
 use std::{
+    cmp::Reverse,
     collections::{HashMap, VecDeque},
+    fmt,
+    str::FromStr,
     sync::{
         atomic::{AtomicU64, AtomicUsize, Ordering},
         Arc,
     },
     task::{Context, Poll},
+    time::Instant,
 };
 use tokio::sync::{mpsc, mpsc::UnboundedSender, oneshot};
 use tokio_stream::wrappers::UnboundedReceiverStream;
@@ -24,6 +32,41 @@ use tokio_stream::wrappers::UnboundedReceiverStream;
 mod client;
 pub use client::FetchClient;
 
+/// How the peers that serve download requests are selected.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum PeerSelection {
+    /// Prefer the idle peers with the lowest request timeout, which tracks their latency.
+    #[default]
+    Latency,
+    /// Prefer the idle peers with the highest measured download throughput of block bodies.
+    ///
+    /// Peers without measured throughput are tried first, ties are broken by latency.
+    Throughput,
+}
+
+impl FromStr for PeerSelection {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "latency" => Ok(PeerSelection::Latency),
+            "throughput" => Ok(PeerSelection::Throughput),
+            _ => Err(format!("invalid peer selection {s}, expected latency or throughput")),
+        }
+    }
+}
+
+impl fmt::Display for PeerSelection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PeerSelection::Latency => f.write_str("latency"),
+            PeerSelection::Throughput => f.write_str("throughput"),
+        }
+    }
+}
+
 /// Manages data fetching operations.
 ///
 /// This type is hooked into the staged sync pipeline and delegates download request to available
@@ -50,6 +93,10 @@ pub struct StateFetcher {
     download_requests_rx: UnboundedReceiverStream<DownloadRequest>,
     /// Sender for download requests, used to detach a [`FetchClient`]
     download_requests_tx: UnboundedSender<DownloadRequest>,
+    /// How the peers for requests are selected.
+    peer_selection: PeerSelection,
+    /// Metrics for the responses of peers.
+    metrics: FetchMetrics,
 }
 
 // === impl StateSyncer ===
@@ -66,9 +113,17 @@ impl StateFetcher {
             queued_requests: Default::default(),
             download_requests_rx: UnboundedReceiverStream::new(download_requests_rx),
             download_requests_tx,
+            peer_selection: Default::default(),
+            metrics: Default::default(),
         }
     }
 
+    /// Sets how the peers for requests are selected.
+    pub(crate) fn with_peer_selection(mut self, peer_selection: PeerSelection) -> Self {
+        self.peer_selection = peer_selection;
+        self
+    }
+
     /// Invoked when connected to a new peer.
     pub(crate) fn new_active_peer(
         &mut self,
@@ -77,8 +132,18 @@ impl StateFetcher {
         best_number: u64,
         timeout: Arc<AtomicU64>,
     ) {
-        self.peers
-            .insert(peer_id, Peer { state: PeerState::Idle, best_hash, best_number, timeout });
+        let metrics = FetchPeerMetrics::new_with_labels(&[("peer_id", peer_id.to_string())]);
+        self.peers.insert(
+            peer_id,
+            Peer {
+                state: PeerState::Idle,
+                best_hash,
+                best_number,
+                timeout,
+                throughput: None,
+                metrics,
+            },
+        );
     }
 
     /// Removes the peer from the peer list, after which it is no longer available for future
@@ -88,7 +153,10 @@ impl StateFetcher {
     ///
     /// This cancels also inflight request and sends an error to the receiver.
     pub(crate) fn on_session_closed(&mut self, peer: &PeerId) {
-        self.peers.remove(peer);
+        if let Some(removed) = self.peers.remove(peer) {
+            // the series of a disconnected peer can't be removed, so it's reset instead
+            removed.metrics.bodies_peer_throughput.set(0.0);
+        }
         if let Some(req) = self.inflight_headers_requests.remove(peer) {
             let _ = req.response.send(Err(RequestError::ConnectionDropped));
         }
@@ -119,14 +187,18 @@ impl StateFetcher {
     }
 
     /// Returns the _next_ idle peer that's ready to accept a request,
-    /// prioritizing those with the lowest timeout/latency.
+    /// prioritizing those with the lowest timeout/latency, or the highest throughput, depending on
+    /// the [PeerSelection].
     /// Once a peer has been yielded, it will be moved to the end of the map
     fn next_peer(&mut self) -> Option<PeerId> {
-        self.peers
-            .iter()
-            .filter(|(_, peer)| peer.state.is_idle())
-            .min_by_key(|(_, peer)| peer.timeout())
-            .map(|(id, _)| *id)
+        let idle = self.peers.iter().filter(|(_, peer)| peer.state.is_idle());
+        let next = match self.peer_selection {
+            PeerSelection::Latency => idle.min_by_key(|(_, peer)| peer.timeout()),
+            PeerSelection::Throughput => {
+                idle.min_by_key(|(_, peer)| (peer.throughput.map(Reverse), peer.timeout()))
+            }
+        };
+        next.map(|(id, _)| *id)
     }
 
     /// Returns the next action to return
@@ -196,7 +268,8 @@ impl StateFetcher {
 
         match req {
             DownloadRequest::GetBlockHeaders { request, response, .. } => {
-                let inflight = Request { request: request.clone(), response };
+                let inflight =
+                    Request { request: request.clone(), response, started_at: Instant::now() };
                 self.inflight_headers_requests.insert(peer_id, inflight);
                 let HeadersRequest { start, limit, direction } = request;
                 BlockRequest::GetBlockHeaders(GetBlockHeaders {
@@ -207,7 +280,8 @@ impl StateFetcher {
                 })
             }
             DownloadRequest::GetBlockBodies { request, response, .. } => {
-                let inflight = Request { request: request.clone(), response };
+                let inflight =
+                    Request { request: request.clone(), response, started_at: Instant::now() };
                 self.inflight_bodies_requests.insert(peer_id, inflight);
                 BlockRequest::GetBlockBodies(GetBlockBodies(request))
             }
@@ -268,10 +342,21 @@ impl StateFetcher {
         peer_id: PeerId,
         res: RequestResult<Vec<BlockBody>>,
     ) -> Option<BlockResponseOutcome> {
+        let mut throughput = None;
         if let Some(resp) = self.inflight_bodies_requests.remove(&peer_id) {
+            if let (Ok(bodies), false) = (&res, resp.request.is_empty()) {
+                let bytes = bodies.iter().map(BlockBody::size).sum::<usize>() as u64;
+                // at millisecond resolution, so instant responses don't skew the average
+                let elapsed = resp.started_at.elapsed().as_secs_f64().max(0.001);
+                throughput = Some((bytes as f64 / elapsed) as u64);
+                self.metrics.bodies_received_bytes.increment(bytes);
+            }
             let _ = resp.response.send(res.map(|b| (peer_id, b).into()));
         }
         if let Some(peer) = self.peers.get_mut(&peer_id) {
+            if let Some(throughput) = throughput {
+                peer.on_throughput(throughput);
+            }
             if peer.state.on_request_finished() {
                 return self.followup_request(peer_id)
             }
@@ -307,12 +392,27 @@ struct Peer {
     best_number: u64,
     /// Tracks the current timeout value we use for the peer.
     timeout: Arc<AtomicU64>,
+    /// Moving average of the download throughput of block bodies in bytes per second, once a
+    /// bodies request was answered.
+    throughput: Option<u64>,
+    /// Metrics for the responses of the peer.
+    metrics: FetchPeerMetrics,
 }
 
 impl Peer {
     fn timeout(&self) -> u64 {
         self.timeout.load(Ordering::Relaxed)
     }
+
+    /// Updates the moving average of the throughput with a new measurement.
+    fn on_throughput(&mut self, throughput: u64) {
+        let avg = match self.throughput {
+            Some(avg) => (avg * 3 + throughput) / 4,
+            None => throughput,
+        };
+        self.throughput = Some(avg);
+        self.metrics.bodies_peer_throughput.set(avg as f64);
+    }
 }
 
 /// Tracks the state of an individual peer
@@ -359,6 +459,8 @@ struct Request<Req, Resp> {
     #[allow(dead_code)]
     request: Req,
     response: oneshot::Sender<Resp>,
+    /// When the request was sent.
+    started_at: Instant,
 }
 
 /// Requests that can be sent to the Syncer from a [`FetchClient`]
@@ -500,6 +602,43 @@ mod tests {
         assert_eq!(fetcher.next_peer(), Some(peer2));
     }
 
+    #[tokio::test]
+    async fn test_peer_throughput_prioritization() {
+        let manager = PeersManager::new(PeersConfig::default());
+        let mut fetcher = StateFetcher::new(manager.handle(), Default::default())
+            .with_peer_selection(PeerSelection::Throughput);
+        let peer1 = B512::random();
+        let peer2 = B512::random();
+        let peer3 = B512::random();
+
+        fetcher.new_active_peer(peer1, B256::random(), 1, Arc::new(AtomicU64::new(30)));
+        fetcher.new_active_peer(peer2, B256::random(), 2, Arc::new(AtomicU64::new(300)));
+        fetcher.new_active_peer(peer3, B256::random(), 3, Arc::new(AtomicU64::new(50)));
+
+        // without measurements, the peer with the lowest timeout is selected
+        assert_eq!(fetcher.next_peer(), Some(peer1));
+
+        // unmeasured peers are tried before measured ones
+        fetcher.peers.get_mut(&peer1).unwrap().on_throughput(1_000);
+        assert_eq!(fetcher.next_peer(), Some(peer3));
+        fetcher.peers.get_mut(&peer3).unwrap().on_throughput(100);
+        assert_eq!(fetcher.next_peer(), Some(peer2));
+
+        // then the peer with the highest throughput is selected, regardless of its timeout
+        fetcher.peers.get_mut(&peer2).unwrap().on_throughput(10_000);
+        assert_eq!(fetcher.next_peer(), Some(peer2));
+        fetcher.on_pending_disconnect(&peer2);
+        assert_eq!(fetcher.next_peer(), Some(peer1));
+    }
+
+    #[test]
+    fn test_parse_peer_selection() {
+        for selection in [PeerSelection::Latency, PeerSelection::Throughput] {
+            assert_eq!(selection.to_string().parse::<PeerSelection>().unwrap(), selection);
+        }
+        assert!("fastest".parse::<PeerSelection>().is_err());
+    }
+
     #[tokio::test]
     async fn test_on_block_headers_response() {
         let manager = PeersManager::new(PeersConfig::default());
@@ -545,6 +684,7 @@ mod tests {
                     direction: Default::default(),
                 },
                 response: tx,
+                started_at: Instant::now(),
             };
             let mut header = SealedHeader::default().unseal();
             header.number = 0u64;
@@ -130,22 +130,29 @@ mod network;
 pub mod peers;
 pub mod protocol;
 mod session;
+pub mod snap_requests;
 mod state;
 mod swarm;
 pub mod transactions;
 
 pub use builder::NetworkBuilder;
 pub use config::{NetworkConfig, NetworkConfigBuilder};
-pub use discovery::{Discovery, DiscoveryEvent};
-pub use fetch::FetchClient;
-pub use manager::{NetworkEvent, NetworkManager};
+pub use discovery::{
+    Discovery, DiscoveryEvent, Discv5Config, DEFAULT_DISCOVERY_V5_ADDRESS,
+    DEFAULT_DISCOVERY_V5_PORT,
+};
+pub use fetch::{FetchClient, PeerSelection};
+pub use manager::{
+    DiscoveredEvent, NetworkEvent, NetworkManager, PeerSessionEvent,
+    SESSION_EVENTS_CHANNEL_CAPACITY,
+};
 pub use message::PeerRequest;
 pub use network::{NetworkEvents, NetworkHandle, NetworkProtocols};
 pub use peers::PeersConfig;
 pub use session::{
-    ActiveSessionHandle, ActiveSessionMessage, Direction, PeerInfo, PendingSessionEvent,
-    PendingSessionHandle, PendingSessionHandshakeError, SessionCommand, SessionEvent, SessionId,
-    SessionLimits, SessionManager, SessionsConfig,
+    ActiveSessionHandle, ActiveSessionMessage, BandwidthLimits, Direction, PeerInfo,
+    PendingSessionEvent, PendingSessionHandle, PendingSessionHandshakeError, SessionCommand,
+    SessionEvent, SessionId, SessionLimits, SessionManager, SessionsConfig,
 };
 
-pub use reth_eth_wire::{DisconnectReason, HelloMessageWithProtocols};
+pub use reth_eth_wire::{DisconnectReason, EthVersion, HelloMessageWithProtocols};
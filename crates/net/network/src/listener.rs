@@ -1,7 +1,7 @@
 //! Contains connection-oriented interfaces.
 
-use futures::{ready, Stream};
-
+use futures::Stream;
+use socket2::{Domain, Protocol, Socket, Type};
 use std::{
     io,
     net::SocketAddr,
@@ -22,6 +22,9 @@ pub struct ConnectionListener {
     /// The active tcp listener for incoming connections.
     #[pin]
     incoming: TcpListenerStream,
+    /// Listeners on additional addresses, e.g. an IPv6 address next to the IPv4 address of the
+    /// primary listener.
+    additional: Vec<(SocketAddr, TcpListenerStream)>,
 }
 
 impl ConnectionListener {
@@ -32,32 +35,86 @@ impl ConnectionListener {
         Ok(Self::new(listener, local_addr))
     }
 
+    /// Creates a new listener that listens for incoming connections on the given address and all
+    /// additional addresses.
+    ///
+    /// Additional addresses with port `0` use the port of the primary listener. If both IPv4 and
+    /// IPv6 addresses are given, IPv6 sockets only accept IPv6 connections, so that the
+    /// unspecified addresses `0.0.0.0` and `::` can be bound to the same port.
+    pub async fn bind_all(
+        addr: SocketAddr,
+        additional: impl IntoIterator<Item = SocketAddr>,
+    ) -> io::Result<Self> {
+        let mut additional = additional.into_iter().collect::<Vec<_>>();
+        if additional.is_empty() {
+            return Self::bind(addr).await
+        }
+
+        let only_v6 = additional.iter().any(|other| other.is_ipv4() != addr.is_ipv4());
+        let listener = bind_tcp_listener(addr, only_v6)?;
+        let local_address = listener.local_addr()?;
+        let mut this = Self::new(listener, local_address);
+
+        for addr in additional.iter_mut() {
+            if addr.port() == 0 {
+                addr.set_port(local_address.port());
+            }
+            let listener = bind_tcp_listener(*addr, only_v6)?;
+            this.additional.push((listener.local_addr()?, TcpListenerStream { inner: listener }));
+        }
+        Ok(this)
+    }
+
     /// Creates a new connection listener stream.
     pub(crate) fn new(listener: TcpListener, local_address: SocketAddr) -> Self {
-        Self { local_address, incoming: TcpListenerStream { inner: listener } }
+        Self {
+            local_address,
+            incoming: TcpListenerStream { inner: listener },
+            additional: Vec::new(),
+        }
     }
 
     /// Polls the type to make progress.
     pub fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<ListenerEvent> {
         let this = self.project();
-        match ready!(this.incoming.poll_next(cx)) {
-            Some(Ok((stream, remote_addr))) => {
-                if let Err(err) = stream.set_nodelay(true) {
-                    tracing::warn!(target: "net", "set nodelay failed: {:?}", err);
-                }
-                Poll::Ready(ListenerEvent::Incoming { stream, remote_addr })
-            }
-            Some(Err(err)) => Poll::Ready(ListenerEvent::Error(err)),
-            None => {
-                Poll::Ready(ListenerEvent::ListenerClosed { local_address: *this.local_address })
+        if let Poll::Ready(event) = this.incoming.poll_next(cx) {
+            return Poll::Ready(ListenerEvent::new(event, *this.local_address))
+        }
+        for (local_address, incoming) in this.additional.iter_mut() {
+            if let Poll::Ready(event) = Pin::new(incoming).poll_next(cx) {
+                return Poll::Ready(ListenerEvent::new(event, *local_address))
             }
         }
+        Poll::Pending
     }
 
     /// Returns the socket address this listener listens on.
+    ///
+    /// This is the primary address if the listener listens on multiple addresses.
     pub fn local_address(&self) -> SocketAddr {
         self.local_address
     }
+
+    /// Returns all socket addresses this listener listens on, starting with the primary address.
+    pub fn local_addresses(&self) -> impl Iterator<Item = SocketAddr> + '_ {
+        std::iter::once(self.local_address).chain(self.additional.iter().map(|(addr, _)| *addr))
+    }
+}
+
+/// Binds a [`TcpListener`] to the address, configured like [`TcpListener::bind`].
+///
+/// If `only_v6` is set, an IPv6 listener doesn't accept IPv4 connections.
+fn bind_tcp_listener(addr: SocketAddr, only_v6: bool) -> io::Result<TcpListener> {
+    let socket = Socket::new(Domain::for_address(addr), Type::STREAM, Some(Protocol::TCP))?;
+    if addr.is_ipv6() && only_v6 {
+        socket.set_only_v6(true)?;
+    }
+    #[cfg(unix)]
+    socket.set_reuse_address(true)?;
+    socket.set_nonblocking(true)?;
+    socket.bind(&addr.into())?;
+    socket.listen(1024)?;
+    TcpListener::from_std(socket.into())
 }
 
 /// Event type produced by the [`TcpListenerStream`].
@@ -82,6 +139,22 @@ pub enum ListenerEvent {
     Error(io::Error),
 }
 
+impl ListenerEvent {
+    /// Converts the next item of the listener bound to the local address into an event.
+    fn new(item: Option<io::Result<(TcpStream, SocketAddr)>>, local_address: SocketAddr) -> Self {
+        match item {
+            Some(Ok((stream, remote_addr))) => {
+                if let Err(err) = stream.set_nodelay(true) {
+                    tracing::warn!(target: "net", "set nodelay failed: {:?}", err);
+                }
+                ListenerEvent::Incoming { stream, remote_addr }
+            }
+            Some(Err(err)) => ListenerEvent::Error(err),
+            None => ListenerEvent::ListenerClosed { local_address },
+        }
+    }
+}
+
 /// A stream of incoming [`TcpStream`]s.
 #[derive(Debug)]
 struct TcpListenerStream {
@@ -105,7 +178,7 @@ impl Stream for TcpListenerStream {
 mod tests {
     use super::*;
     use futures::pin_mut;
-    use std::net::{Ipv4Addr, SocketAddrV4};
+    use std::net::{Ipv4Addr, Ipv6Addr, SocketAddrV4, SocketAddrV6};
     use tokio::macros::support::poll_fn;
 
     #[tokio::test(flavor = "multi_thread")]
@@ -128,4 +201,30 @@ mod tests {
 
         let _ = TcpStream::connect(local_addr).await.unwrap();
     }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_dual_stack_listener() {
+        let listener = ConnectionListener::bind_all(
+            SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 0)),
+            [SocketAddr::V6(SocketAddrV6::new(Ipv6Addr::LOCALHOST, 0, 0, 0))],
+        )
+        .await
+        .unwrap();
+        let local_addrs = listener.local_addresses().collect::<Vec<_>>();
+        assert_eq!(local_addrs.len(), 2);
+        // the additional listener uses the port of the primary listener
+        assert_eq!(local_addrs[0].port(), local_addrs[1].port());
+        assert!(local_addrs[1].is_ipv6());
+
+        let ipv6_addr = local_addrs[1];
+        tokio::task::spawn(async move {
+            let _ = TcpStream::connect(ipv6_addr).await.unwrap();
+        });
+
+        pin_mut!(listener);
+        match poll_fn(|cx| listener.as_mut().poll(cx)).await {
+            ListenerEvent::Incoming { remote_addr, .. } => assert!(remote_addr.is_ipv6()),
+            _ => panic!("unexpected event"),
+        }
+    }
 }
@@ -23,9 +23,11 @@ use crate::{
     import::{BlockImport, BlockImportOutcome, BlockValidation},
     listener::ConnectionListener,
     message::{NewBlockMessage, PeerMessage, PeerRequest, PeerRequestSender},
-    metrics::{DisconnectMetrics, NetworkMetrics, NETWORK_POOL_TRANSACTIONS_SCOPE},
+    metrics::{
+        DisconnectMetrics, HandshakeFailureMetrics, NetworkMetrics, NETWORK_POOL_TRANSACTIONS_SCOPE,
+    },
     network::{NetworkHandle, NetworkHandleMessage},
-    peers::{PeersHandle, PeersManager},
+    peers::{Bans, PeersHandle, PeersManager, PersistedPeer},
     protocol::IntoRlpxSubProtocol,
     session::SessionManager,
     state::NetworkState,
@@ -37,11 +39,14 @@ use futures::{pin_mut, Future, StreamExt};
 use parking_lot::Mutex;
 use reth_eth_wire::{
     capability::{Capabilities, CapabilityMessage},
-    DisconnectReason, EthVersion, Status,
+    DisconnectReason, EthVersion, HelloMessageWithProtocols, Status,
 };
 use reth_metrics::common::mpsc::UnboundedMeteredSender;
 use reth_net_common::bandwidth_meter::BandwidthMeter;
-use reth_network_api::ReputationChangeKind;
+use reth_net_nat::{
+    maintain_port_mappings, NatResolver, PortMappingProtocol, DEFAULT_PORT_MAPPING_LEASE,
+};
+use reth_network_api::{PeerInfo, ReputationChangeKind};
 use reth_primitives::{ForkId, NodeRecord, PeerId, B256};
 use reth_provider::{BlockNumReader, BlockReader};
 use reth_rpc_types::{EthProtocolInfo, NetworkStatus};
@@ -49,7 +54,8 @@ use reth_tasks::shutdown::GracefulShutdown;
 use reth_tokio_util::EventListeners;
 use secp256k1::SecretKey;
 use std::{
-    net::SocketAddr,
+    collections::HashMap,
+    net::{Ipv4Addr, Ipv6Addr, SocketAddr},
     pin::Pin,
     sync::{
         atomic::{AtomicU64, AtomicUsize, Ordering},
@@ -57,10 +63,16 @@ use std::{
     },
     task::{Context, Poll},
 };
-use tokio::sync::mpsc::{self, error::TrySendError};
+use tokio::sync::{
+    broadcast,
+    mpsc::{self, error::TrySendError},
+};
 use tokio_stream::wrappers::UnboundedReceiverStream;
 use tracing::{debug, error, trace, warn};
 
+/// The capacity of the broadcast channel for [`PeerSessionEvent`]s.
+pub const SESSION_EVENTS_CHANNEL_CAPACITY: usize = 1024;
+
 #[cfg_attr(doc, aquamarine::aquamarine)]
 /// Manages the _entire_ state of the network.
 ///
@@ -82,6 +94,11 @@ pub struct NetworkManager<C> {
     block_import: Box<dyn BlockImport>,
     /// All listeners for high level network events.
     event_listeners: EventListeners<NetworkEvent>,
+    /// Broadcasts established and closed sessions to all subscribed listeners.
+    session_events: broadcast::Sender<PeerSessionEvent>,
+    /// The [`PeerInfo`] of all active sessions, included in the [`PeerSessionEvent::Closed`]
+    /// event.
+    active_session_infos: HashMap<PeerId, PeerInfo>,
     /// Sender half to send events to the
     /// [`TransactionsManager`](crate::transactions::TransactionsManager) task, if configured.
     to_transactions_manager: Option<UnboundedMeteredSender<NetworkTransactionEvent>>,
@@ -108,6 +125,8 @@ pub struct NetworkManager<C> {
     metrics: NetworkMetrics,
     /// Disconnect metrics for the Network
     disconnect_metrics: DisconnectMetrics,
+    /// Metrics for the failed handshakes of pending sessions
+    handshake_failure_metrics: HandshakeFailureMetrics,
 }
 
 // === impl NetworkManager ===
@@ -162,8 +181,11 @@ where
             client,
             secret_key,
             mut discovery_v4_config,
+            discovery_v5_config,
             discovery_addr,
             listener_addr,
+            additional_listener_addrs,
+            additional_discovery_addr,
             peers_config,
             sessions_config,
             chain_spec,
@@ -173,34 +195,117 @@ where
             executor,
             hello_message,
             status,
+            block_range,
+            receipts_prune_mode,
             fork_filter,
             dns_discovery_config,
             extra_protocols,
             tx_gossip_disabled,
+            enforce_enr_fork_id,
+            nat,
             #[cfg(feature = "optimism")]
                 optimism_network_config: crate::config::OptimismNetworkConfig { sequencer_endpoint },
         } = config;
 
+        validate_hello_message(&hello_message)?;
+
+        let peer_selection = peers_config.peer_selection;
         let peers_manager = PeersManager::new(peers_config);
         let peers_handle = peers_manager.handle();
 
-        let incoming = ConnectionListener::bind(listener_addr).await.map_err(|err| {
-            NetworkError::from_io_error(err, ServiceKind::Listener(listener_addr))
-        })?;
+        let incoming =
+            ConnectionListener::bind_all(listener_addr, additional_listener_addrs).await.map_err(
+                |err| NetworkError::from_io_error(err, ServiceKind::Listener(listener_addr)),
+            )?;
         let listener_address = Arc::new(Mutex::new(incoming.local_address()));
 
+        // the first listener address of the other IP version is advertised in the ENR next to the
+        // discovery address, so that peers that only support this IP version can reach the node
+        let dual_stack_addr = incoming
+            .local_addresses()
+            .find(|addr| addr.is_ipv4() != discovery_addr.is_ipv4())
+            .and_then(resolve_unspecified_ip);
+
         discovery_v4_config = discovery_v4_config.map(|mut disc_config| {
             // merge configured boot nodes
             disc_config.bootstrap_nodes.extend(boot_nodes.clone());
             disc_config.add_eip868_pair("eth", status.forkid);
+            if disc_config.additional_discovery_addr.is_none() {
+                disc_config.additional_discovery_addr = additional_discovery_addr;
+            }
+            match dual_stack_addr {
+                Some(SocketAddr::V4(addr)) => {
+                    disc_config.add_eip868_pair("ip", addr.ip().octets());
+                    disc_config.add_eip868_pair("tcp", addr.port());
+                }
+                Some(SocketAddr::V6(addr)) => {
+                    disc_config.add_eip868_pair("ip6", addr.ip().octets());
+                    disc_config.add_eip868_pair("tcp6", addr.port());
+                }
+                None => {}
+            }
             disc_config
         });
 
-        let discovery =
-            Discovery::new(discovery_addr, secret_key, discovery_v4_config, dns_discovery_config)
-                .await?;
+        // advertise the actual listener port, the configured port could be `0`
+        let listener_port = listener_address.lock().port();
+        let discovery_v5_config = discovery_v5_config.map(|mut disc_config| {
+            let tcp_port = disc_config.tcp_port.unwrap_or(listener_port);
+            if let Some(addr) = additional_discovery_addr
+                .filter(|_| disc_config.additional_discovery_addr.is_none())
+            {
+                // discv4 and discv5 can't share a port of the same IP version
+                let port = disc_config.discovery_addr.port();
+                disc_config =
+                    disc_config.with_additional_discovery_addr(SocketAddr::new(addr.ip(), port));
+            }
+            disc_config.with_tcp_port(tcp_port)
+        });
+
+        let discv5_port =
+            discovery_v5_config.as_ref().map(|disc_config| disc_config.discovery_addr.port());
+
+        let mut discovery = Discovery::new(
+            discovery_addr,
+            secret_key,
+            discovery_v4_config,
+            discovery_v5_config,
+            dns_discovery_config,
+        )
+        .await?;
+        discovery.set_enforce_enr_fork_id(enforce_enr_fork_id);
         // need to retrieve the addr here since provided port could be `0`
         let local_peer_id = discovery.local_id();
+        let discv4 = discovery.discv4();
+
+        // map the listener and discovery ports on the gateway, and advertise the external ports
+        if let Some(nat) = nat.filter(NatResolver::supports_port_mapping) {
+            let discv4_port = discv4.as_ref().map(|discv4| discv4.local_addr().port());
+            let mut ports = vec![(PortMappingProtocol::Tcp, listener_port)];
+            for port in discv4_port.into_iter().chain(discv5_port) {
+                if !ports.contains(&(PortMappingProtocol::Udp, port)) {
+                    ports.push((PortMappingProtocol::Udp, port));
+                }
+            }
+            let discv4 = discv4.clone();
+            let on_mapped = move |protocol: PortMappingProtocol, port: u16, external_port: u16| {
+                // discv5 learns its external UDP address from the endpoints its peers observe
+                let Some(discv4) = &discv4 else { return };
+                match protocol {
+                    PortMappingProtocol::Tcp => discv4.set_tcp_port(external_port),
+                    PortMappingProtocol::Udp if Some(port) == discv4_port => {
+                        discv4.set_udp_port(external_port)
+                    }
+                    PortMappingProtocol::Udp => {}
+                }
+            };
+            executor.spawn(Box::pin(maintain_port_mappings(
+                nat,
+                ports,
+                DEFAULT_PORT_MAPPING_LEASE,
+                on_mapped,
+            )));
+        }
 
         let num_active_peers = Arc::new(AtomicUsize::new(0));
         let bandwidth_meter: BandwidthMeter = BandwidthMeter::default();
@@ -210,6 +315,8 @@ where
             sessions_config,
             executor,
             status,
+            block_range,
+            receipts_prune_mode,
             hello_message,
             fork_filter,
             extra_protocols,
@@ -222,12 +329,15 @@ where
             peers_manager,
             chain_spec.genesis_hash(),
             Arc::clone(&num_active_peers),
+            peer_selection,
         );
 
         let swarm = Swarm::new(incoming, sessions, state, NetworkConnectionState::default());
 
         let (to_manager_tx, from_handle_rx) = mpsc::unbounded_channel();
 
+        let (session_events, _) = broadcast::channel(SESSION_EVENTS_CHANNEL_CAPACITY);
+
         let handle = NetworkHandle::new(
             Arc::clone(&num_active_peers),
             listener_address,
@@ -239,6 +349,8 @@ where
             bandwidth_meter,
             Arc::new(AtomicU64::new(chain_spec.chain.id())),
             tx_gossip_disabled,
+            session_events.clone(),
+            discv4,
             #[cfg(feature = "optimism")]
             sequencer_endpoint,
         );
@@ -249,11 +361,14 @@ where
             from_handle_rx: UnboundedReceiverStream::new(from_handle_rx),
             block_import,
             event_listeners: Default::default(),
+            session_events,
+            active_session_infos: Default::default(),
             to_transactions_manager: None,
             to_eth_request_handler: None,
             num_active_peers,
             metrics: Default::default(),
             disconnect_metrics: Default::default(),
+            handshake_failure_metrics: Default::default(),
         })
     }
 
@@ -293,7 +408,12 @@ where
 
     /// Create a [`NetworkBuilder`] to configure all components of the network
     pub fn into_builder(self) -> NetworkBuilder<C, (), ()> {
-        NetworkBuilder { network: self, transactions: (), request_handler: () }
+        NetworkBuilder {
+            network: self,
+            transactions: (),
+            request_handler: (),
+            snap_request_handler: (),
+        }
     }
 
     /// Returns the [`SocketAddr`] that listens for incoming connections.
@@ -321,6 +441,22 @@ where
         self.swarm.state().peers().iter_peers()
     }
 
+    /// Returns an iterator over the state of all peers in the peer set that should be persisted
+    /// across restarts.
+    ///
+    /// See also [`PeersConfig::with_basic_nodes_from_file`](crate::PeersConfig).
+    pub fn all_persisted_peers(&self) -> impl Iterator<Item = PersistedPeer> + '_ {
+        self.swarm.state().peers().iter_persisted_peers()
+    }
+
+    /// Returns the active bans of peers and IP addresses, which should be persisted across
+    /// restarts.
+    ///
+    /// See also [`PeersConfig::with_bans_from_file`](crate::PeersConfig).
+    pub fn bans(&self) -> Bans {
+        self.swarm.state().peers().bans()
+    }
+
     /// Returns a new [`PeersHandle`] that can be cloned and shared.
     ///
     /// The [`PeersHandle`] can be used to interact with the network's peer set.
@@ -487,6 +623,10 @@ where
                     this.block_import.on_new_block(peer_id, block);
                 });
             }
+            PeerMessage::BlockRangeUpdated(range) => {
+                // the latest block of the range is the peer's best block
+                self.swarm.state_mut().update_peer_block(&peer_id, range.latest_hash, range.latest);
+            }
             PeerMessage::PooledTransactions(msg) => {
                 self.notify_tx_manager(NetworkTransactionEvent::IncomingPooledTransactionHashes {
                     peer_id,
@@ -731,6 +871,11 @@ where
                                     .peers_mut()
                                     .on_incoming_session_established(peer_id, remote_addr);
                             }
+                            if let Some(info) = this.swarm.sessions().get_peer_info_by_id(peer_id) {
+                                this.active_session_infos.insert(peer_id, info.clone());
+                                let _ =
+                                    this.session_events.send(PeerSessionEvent::Established(info));
+                            }
                             this.event_listeners.notify(NetworkEvent::SessionEstablished {
                                 peer_id,
                                 remote_addr,
@@ -800,6 +945,11 @@ where
                                 this.swarm.state().peers().num_backed_off_peers().saturating_sub(1)
                                     as f64,
                             );
+                            if let Some(info) = this.active_session_infos.remove(&peer_id) {
+                                let _ = this
+                                    .session_events
+                                    .send(PeerSessionEvent::Closed { info, reason });
+                            }
                             this.event_listeners
                                 .notify(NetworkEvent::SessionClosed { peer_id, reason });
                         }
@@ -817,6 +967,7 @@ where
                                     .peers_mut()
                                     .on_incoming_pending_session_dropped(remote_addr, err);
                                 this.metrics.pending_session_failures.increment(1);
+                                this.handshake_failure_metrics.increment(err);
                                 if let Some(reason) = err.as_disconnected() {
                                     this.disconnect_metrics.increment(reason);
                                 }
@@ -824,7 +975,9 @@ where
                                 this.swarm
                                     .state_mut()
                                     .peers_mut()
-                                    .on_incoming_pending_session_gracefully_closed();
+                                    .on_incoming_pending_session_gracefully_closed(
+                                        remote_addr.ip(),
+                                    );
                             }
                             this.metrics.closed_sessions.increment(1);
                             this.metrics
@@ -855,6 +1008,7 @@ where
                                     err,
                                 );
                                 this.metrics.pending_session_failures.increment(1);
+                                this.handshake_failure_metrics.increment(err);
                                 if let Some(reason) = err.as_disconnected() {
                                     this.disconnect_metrics.increment(reason);
                                 }
@@ -926,6 +1080,49 @@ where
     }
 }
 
+/// Validates that all capabilities of the hello message are handled by the node.
+///
+/// The eth protocol is handled by the sessions, all other protocols are only announced via the
+/// handlers of the [RlpxSubProtocols](crate::protocol::RlpxSubProtocols).
+fn validate_hello_message(hello: &HelloMessageWithProtocols) -> Result<(), NetworkError> {
+    if !hello.protocols.iter().any(|protocol| protocol.cap.is_eth()) {
+        return Err(NetworkError::InvalidHelloMessage(
+            "no supported eth version is advertised".to_string(),
+        ))
+    }
+    if let Some(protocol) = hello.protocols.iter().find(|protocol| !protocol.cap.is_eth()) {
+        return Err(NetworkError::InvalidHelloMessage(format!(
+            "capability {} is not supported, protocols other than eth must be added as RLPx sub-protocols",
+            protocol.cap
+        )))
+    }
+    Ok(())
+}
+
+/// Replaces an unspecified IP of the address with the local IP of the default route of its IP
+/// version, which is the IP that peers of this IP version see if there's no NAT.
+///
+/// Returns `None` if there's no route, e.g. because the host has no IPv6 connectivity.
+fn resolve_unspecified_ip(mut addr: SocketAddr) -> Option<SocketAddr> {
+    if !addr.ip().is_unspecified() {
+        return Some(addr)
+    }
+    // connecting a UDP socket only selects the route, no datagram is sent
+    let remote: SocketAddr = if addr.is_ipv4() {
+        (Ipv4Addr::new(192, 0, 2, 1), 9).into()
+    } else {
+        (Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 1), 9).into()
+    };
+    let socket = std::net::UdpSocket::bind(SocketAddr::new(addr.ip(), 0)).ok()?;
+    socket.connect(remote).ok()?;
+    let ip = socket.local_addr().ok()?.ip();
+    if ip.is_unspecified() || ip.is_loopback() {
+        return None
+    }
+    addr.set_ip(ip);
+    Some(addr)
+}
+
 /// (Non-exhaustive) Events emitted by the network that are of interest for subscribers.
 ///
 /// This includes any event types that may be relevant to tasks, for metrics, keep track of peers
@@ -962,7 +1159,43 @@ pub enum NetworkEvent {
     PeerRemoved(PeerId),
 }
 
+/// Events emitted when a session to a peer is established or closed.
+///
+/// See [`NetworkHandle::session_event_listener`].
+#[derive(Debug, Clone)]
+pub enum PeerSessionEvent {
+    /// Established a new session with the peer.
+    Established(PeerInfo),
+    /// Closed the session with the peer.
+    Closed {
+        /// Info about the session that was closed.
+        info: PeerInfo,
+        /// Why the disconnect was triggered
+        reason: Option<DisconnectReason>,
+    },
+}
+
+// === impl PeerSessionEvent ===
+
+impl PeerSessionEvent {
+    /// Returns the [`PeerInfo`] of the session.
+    pub fn peer_info(&self) -> &PeerInfo {
+        match self {
+            PeerSessionEvent::Established(info) | PeerSessionEvent::Closed { info, .. } => info,
+        }
+    }
+}
+
+/// Events emitted when a new node is discovered.
 #[derive(Debug, Clone)]
 pub enum DiscoveredEvent {
-    EventQueued { peer_id: PeerId, socket_addr: SocketAddr, fork_id: Option<ForkId> },
+    /// The discovered node was queued to be added to the peer set.
+    EventQueued {
+        /// The identifier of the discovered node.
+        peer_id: PeerId,
+        /// The address the node listens on.
+        socket_addr: SocketAddr,
+        /// The fork id of the node, if already retrieved from its ENR.
+        fork_id: Option<ForkId>,
+    },
 }
@@ -5,10 +5,10 @@
 
 use futures::FutureExt;
 use reth_eth_wire::{
-    capability::RawCapabilityMessage, message::RequestPair, BlockBodies, BlockHeaders, EthMessage,
-    GetBlockBodies, GetBlockHeaders, GetNodeData, GetPooledTransactions, GetReceipts, NewBlock,
-    NewBlockHashes, NewPooledTransactionHashes, NodeData, PooledTransactions, Receipts,
-    SharedTransactions, Transactions,
+    capability::RawCapabilityMessage, message::RequestPair, BlockBodies, BlockHeaders,
+    BlockRangeUpdate, EthMessage, GetBlockBodies, GetBlockHeaders, GetNodeData,
+    GetPooledTransactions, GetReceipts, NewBlock, NewBlockHashes, NewPooledTransactionHashes,
+    NodeData, PooledTransactions, Receipts, SharedTransactions, Transactions,
 };
 use reth_interfaces::p2p::error::{RequestError, RequestResult};
 use reth_primitives::{
@@ -53,6 +53,8 @@ pub enum PeerMessage {
     SendTransactions(SharedTransactions),
     /// Send new pooled transactions
     PooledTransactions(NewPooledTransactionHashes),
+    /// The range of blocks the peer can serve, only exchanged with `eth/69` peers.
+    BlockRangeUpdated(BlockRangeUpdate),
     /// All `eth` request variants.
     EthRequest(PeerRequest),
     /// Other than eth namespace message
@@ -1,4 +1,5 @@
-use reth_eth_wire::DisconnectReason;
+use crate::session::PendingSessionHandshakeError;
+use reth_eth_wire::{errors::EthStreamError, DisconnectReason};
 use reth_metrics::{
     metrics::{Counter, Gauge},
     Metrics,
@@ -51,6 +52,39 @@ pub struct NetworkMetrics {
 pub struct SessionManagerMetrics {
     /// Number of dials that resulted in a peer being added to the peerset
     pub(crate) total_dial_successes: Counter,
+    /// Number of incoming connections that were rejected because they exceeded the rate limit
+    pub(crate) total_rate_limited_incoming_connections: Counter,
+}
+
+/// Metrics for failed handshakes of pending sessions, by cause
+#[derive(Metrics)]
+#[metrics(scope = "network.handshake_failures")]
+pub struct HandshakeFailureMetrics {
+    /// Number of pending sessions that did not complete the handshakes in time
+    pub(crate) timeout: Counter,
+
+    /// Number of pending sessions that failed the ECIES handshake
+    pub(crate) ecies: Counter,
+
+    /// Number of pending sessions that failed the p2p `Hello` handshake
+    pub(crate) p2p: Counter,
+
+    /// Number of pending sessions that failed the `eth` `Status` handshake
+    pub(crate) eth: Counter,
+}
+
+impl HandshakeFailureMetrics {
+    /// Increments the counter for the cause of the failed handshake
+    pub(crate) fn increment(&self, err: &PendingSessionHandshakeError) {
+        match err {
+            PendingSessionHandshakeError::Timeout => self.timeout.increment(1),
+            PendingSessionHandshakeError::Ecies(_) => self.ecies.increment(1),
+            PendingSessionHandshakeError::Eth(EthStreamError::P2PStreamError(_)) => {
+                self.p2p.increment(1)
+            }
+            PendingSessionHandshakeError::Eth(_) => self.eth.increment(1),
+        }
+    }
 }
 
 /// Metrics for the TransactionsManager
@@ -69,8 +103,22 @@ pub struct TransactionsManagerMetrics {
     pub(crate) pending_pool_imports: Gauge,
     /// Currently active outgoing GetPooledTransactions requests.
     pub(crate) inflight_transaction_requests: Gauge,
+    /// Number of announced transaction hashes waiting to be requested from peers.
+    pub(crate) buffered_transaction_hashes: Gauge,
     /// How often we failed to send a request to the peer because the channel was full.
     pub(crate) egress_peer_channel_full: Counter,
+    /// Total number of transactions that were not announced to a peer, because the peer has
+    /// already seen them.
+    pub(crate) duplicate_announcements_avoided: Counter,
+    /// Number of transactions that are remembered as seen, summed over all peers.
+    pub(crate) seen_transactions: Gauge,
+    /// Approximate memory used by the caches of seen transactions of all peers, in bytes.
+    pub(crate) seen_transactions_memory_bytes: Gauge,
+    /// Total number of seen transactions that were evicted from the cache of a peer.
+    pub(crate) seen_transactions_evictions: Counter,
+    /// Total number of transactions received from peers that were dropped by the gossip
+    /// validator.
+    pub(crate) filtered_gossip_transactions: Counter,
 }
 
 /// Metrics for Disconnection types
@@ -142,6 +190,22 @@ impl DisconnectMetrics {
     }
 }
 
+/// Metrics for the responses of peers to download requests, handled by the StateFetcher
+#[derive(Metrics)]
+#[metrics(scope = "network.fetch")]
+pub struct FetchMetrics {
+    /// Total number of bytes of received block bodies
+    pub(crate) bodies_received_bytes: Counter,
+}
+
+/// Metrics for the responses of a single peer to download requests, labeled by the peer id
+#[derive(Metrics)]
+#[metrics(scope = "network.fetch")]
+pub struct FetchPeerMetrics {
+    /// Moving average of the download throughput of block bodies of the peer in bytes per second
+    pub(crate) bodies_peer_throughput: Gauge,
+}
+
 /// Metrics for the EthRequestHandler
 #[derive(Metrics)]
 #[metrics(scope = "network")]
@@ -151,4 +215,24 @@ pub struct EthRequestHandlerMetrics {
 
     /// Number of received bodies requests
     pub(crate) received_bodies_requests: Counter,
+
+    /// Number of requests rejected because the peer exceeded the request limits
+    pub(crate) rejected_requests: Counter,
+}
+
+/// Metrics for the SnapRequestHandler
+#[derive(Metrics)]
+#[metrics(scope = "network")]
+pub struct SnapRequestHandlerMetrics {
+    /// Number of received account range requests
+    pub(crate) received_account_range_requests: Counter,
+
+    /// Number of received storage ranges requests
+    pub(crate) received_storage_ranges_requests: Counter,
+
+    /// Number of received bytecodes requests
+    pub(crate) received_byte_codes_requests: Counter,
+
+    /// Number of received trie nodes requests
+    pub(crate) received_trie_nodes_requests: Counter,
 }
@@ -1,9 +1,16 @@
 use crate::{
-    config::NetworkMode, discovery::DiscoveryEvent, manager::NetworkEvent, message::PeerRequest,
-    peers::PeersHandle, protocol::RlpxSubProtocol, FetchClient,
+    config::NetworkMode,
+    discovery::DiscoveryEvent,
+    manager::{NetworkEvent, PeerSessionEvent},
+    message::PeerRequest,
+    peers::PeersHandle,
+    protocol::RlpxSubProtocol,
+    FetchClient,
 };
 use async_trait::async_trait;
+use enr::{Enr, EnrBuilder};
 use parking_lot::Mutex;
+use reth_discv4::Discv4;
 use reth_eth_wire::{DisconnectReason, NewBlock, NewPooledTransactionHashes, SharedTransactions};
 use reth_interfaces::sync::{NetworkSyncUpdater, SyncState, SyncStateProvider};
 use reth_net_common::bandwidth_meter::BandwidthMeter;
@@ -21,8 +28,8 @@ use std::{
         Arc,
     },
 };
-use tokio::sync::{mpsc, mpsc::UnboundedSender, oneshot};
-use tokio_stream::wrappers::UnboundedReceiverStream;
+use tokio::sync::{broadcast, mpsc, mpsc::UnboundedSender, oneshot};
+use tokio_stream::wrappers::{BroadcastStream, UnboundedReceiverStream};
 
 /// A _shareable_ network frontend. Used to interact with the network.
 ///
@@ -49,6 +56,8 @@ impl NetworkHandle {
         bandwidth_meter: BandwidthMeter,
         chain_id: Arc<AtomicU64>,
         tx_gossip_disabled: bool,
+        session_events: broadcast::Sender<PeerSessionEvent>,
+        discv4: Option<Discv4>,
         #[cfg(feature = "optimism")] sequencer_endpoint: Option<String>,
     ) -> Self {
         let inner = NetworkInner {
@@ -64,6 +73,8 @@ impl NetworkHandle {
             initial_sync_done: Arc::new(AtomicBool::new(false)),
             chain_id,
             tx_gossip_disabled,
+            session_events,
+            discv4,
             #[cfg(feature = "optimism")]
             sequencer_endpoint,
         };
@@ -95,6 +106,31 @@ impl NetworkHandle {
         rx.await
     }
 
+    /// Returns the [`PeerInfo`] of the active session to the given peer, if any.
+    ///
+    /// This includes the client version, negotiated eth version, capabilities, direction, remote
+    /// address and the time the session was established.
+    pub async fn get_peer_info(&self, peer_id: PeerId) -> Result<Option<PeerInfo>, NetworkError> {
+        self.get_peer_by_id(peer_id).await
+    }
+
+    /// Returns the [`PeerInfo`] of all active sessions.
+    pub async fn get_all_peer_info(&self) -> Result<Vec<PeerInfo>, NetworkError> {
+        self.get_all_peers().await
+    }
+
+    /// Returns a new stream of [`PeerSessionEvent`]s for all sessions that are established or
+    /// closed after this call.
+    ///
+    /// Unlike [`NetworkEvents::event_listener`] this subscribes directly to a broadcast channel
+    /// without a roundtrip to the [`NetworkManager`](crate::NetworkManager). Listeners that fall
+    /// behind by more than
+    /// [`SESSION_EVENTS_CHANNEL_CAPACITY`](crate::SESSION_EVENTS_CHANNEL_CAPACITY) events
+    /// receive a lag error and miss the oldest events.
+    pub fn session_event_listener(&self) -> BroadcastStream<PeerSessionEvent> {
+        BroadcastStream::new(self.inner.session_events.subscribe())
+    }
+
     /// Returns the mode of the network, either pow, or pos
     pub fn mode(&self) -> &NetworkMode {
         &self.inner.network_mode
@@ -205,6 +241,25 @@ impl PeersInfo for NetworkHandle {
 
         NodeRecord::new(socket_addr, id)
     }
+
+    fn local_enr(&self) -> Enr<SecretKey> {
+        // the ENR discv4 hands out to peers
+        if let Some(discv4) = &self.inner.discv4 {
+            return discv4.local_enr()
+        }
+
+        let local_node_record = self.local_node_record();
+        let mut builder = EnrBuilder::new("v4");
+        builder.ip(local_node_record.address);
+        if local_node_record.address.is_ipv4() {
+            builder.udp4(local_node_record.udp_port);
+            builder.tcp4(local_node_record.tcp_port);
+        } else {
+            builder.udp6(local_node_record.udp_port);
+            builder.tcp6(local_node_record.tcp_port);
+        }
+        builder.build(&self.inner.secret_key).expect("v4 is set; qed")
+    }
 }
 
 #[async_trait]
@@ -354,6 +409,10 @@ struct NetworkInner {
     chain_id: Arc<AtomicU64>,
     /// Whether to disable transaction gossip
     tx_gossip_disabled: bool,
+    /// Sender half of the broadcast channel for session events.
+    session_events: broadcast::Sender<PeerSessionEvent>,
+    /// The frontend of the discv4 service, if discv4 is enabled.
+    discv4: Option<Discv4>,
     /// The sequencer HTTP Endpoint
     #[cfg(feature = "optimism")]
     sequencer_endpoint: Option<String>,
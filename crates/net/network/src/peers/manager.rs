@@ -1,9 +1,11 @@
 use crate::{
     error::{BackoffKind, SessionError},
+    fetch::PeerSelection,
     peers::{
         reputation::{is_banned_reputation, DEFAULT_REPUTATION},
-        ReputationChangeWeights, DEFAULT_MAX_CONCURRENT_DIALS, DEFAULT_MAX_PEERS_INBOUND,
-        DEFAULT_MAX_PEERS_OUTBOUND,
+        ReputationChangeWeights, DEFAULT_INBOUND_SUBNET_PREFIX, DEFAULT_MAX_CONCURRENT_DIALS,
+        DEFAULT_MAX_PEERS_INBOUND, DEFAULT_MAX_PEERS_INBOUND_PER_IP,
+        DEFAULT_MAX_PEERS_INBOUND_PER_SUBNET, DEFAULT_MAX_PEERS_OUTBOUND,
     },
     session::{Direction, PendingSessionHandshakeError},
 };
@@ -17,9 +19,9 @@ use std::{
     fmt::Display,
     io::{self, ErrorKind},
     net::{IpAddr, SocketAddr},
-    path::Path,
+    path::{Path, PathBuf},
     task::{Context, Poll},
-    time::Duration,
+    time::{Duration, SystemTime},
 };
 use thiserror::Error;
 use tokio::{
@@ -27,7 +29,10 @@ use tokio::{
     time::{Instant, Interval},
 };
 use tokio_stream::wrappers::UnboundedReceiverStream;
-use tracing::{info, trace};
+use tracing::{info, trace, warn};
+
+/// The interval at which the trusted nodes file is checked for changes.
+const TRUSTED_NODES_FILE_RELOAD_INTERVAL: Duration = Duration::from_secs(5);
 
 /// A communication channel to the [`PeersManager`] to apply manual changes to the peer set.
 #[derive(Clone, Debug)]
@@ -73,6 +78,38 @@ impl PeersHandle {
 
         rx.await.unwrap_or_default()
     }
+
+    /// Bans the peer for the given duration, or indefinitely if `None`.
+    ///
+    /// If currently connected this will disconnect the session.
+    pub fn ban_peer(&self, peer_id: PeerId, duration: Option<Duration>) {
+        self.send(PeerCommand::BanPeer(peer_id, duration));
+    }
+
+    /// Lifts the ban of the peer.
+    pub fn unban_peer(&self, peer_id: PeerId) {
+        self.send(PeerCommand::UnbanPeer(peer_id));
+    }
+
+    /// Bans the IP address for the given duration, or indefinitely if `None`.
+    ///
+    /// Sessions of peers with this address are disconnected. Non-global addresses are not banned.
+    pub fn ban_ip(&self, ip: IpAddr, duration: Option<Duration>) {
+        self.send(PeerCommand::BanIp(ip, duration));
+    }
+
+    /// Lifts the ban of the IP address.
+    pub fn unban_ip(&self, ip: IpAddr) {
+        self.send(PeerCommand::UnbanIp(ip));
+    }
+
+    /// Returns all active bans, including the temporary bans of peers with a bad reputation.
+    pub async fn bans(&self) -> Bans {
+        let (tx, rx) = oneshot::channel();
+        self.send(PeerCommand::GetBans(tx));
+
+        rx.await.unwrap_or_default()
+    }
 }
 
 /// Maintains the state of _all_ the peers known to the network.
@@ -110,6 +147,10 @@ pub struct PeersManager {
     backoff_durations: PeerBackoffDurations,
     /// If non-trusted peers should be connected to
     connect_trusted_nodes_only: bool,
+    /// The file with additional trusted nodes, reloaded when it changes.
+    trusted_nodes_file: Option<TrustedNodesFile>,
+    /// Tracks the inbound connections per IP address and subnet.
+    inbound_connections: InboundConnections,
     /// Timestamp of the last time [Self::tick] was called.
     last_tick: Instant,
     /// Maximum number of backoff attempts before we give up on a peer and dropping.
@@ -123,13 +164,17 @@ impl PeersManager {
             refill_slots_interval,
             connection_info,
             reputation_weights,
-            ban_list,
+            mut ban_list,
             ban_duration,
             backoff_durations,
             trusted_nodes,
             connect_trusted_nodes_only,
+            trusted_nodes_file,
             basic_nodes,
+            persisted_peers,
             max_backoff_count,
+            // used by the fetcher
+            peer_selection: _,
         } = config;
         let (manager_tx, handle_rx) = mpsc::unbounded_channel();
         let now = Instant::now();
@@ -137,6 +182,11 @@ impl PeersManager {
         // We use half of the interval to decrease the max duration to `150%` in worst case
         let unban_interval = ban_duration.min(backoff_durations.low) / 2;
 
+        // the trusted nodes of the config are never removed on reloads of the trusted nodes file
+        let trusted_nodes_file = trusted_nodes_file.map(|path| {
+            TrustedNodesFile::new(path, trusted_nodes.iter().map(|node| node.id).collect())
+        });
+
         let mut peers = HashMap::with_capacity(trusted_nodes.len() + basic_nodes.len());
 
         for NodeRecord { address, tcp_port, udp_port: _, id } in trusted_nodes {
@@ -147,7 +197,33 @@ impl PeersManager {
             peers.entry(id).or_insert_with(|| Peer::new(SocketAddr::from((address, tcp_port))));
         }
 
-        Self {
+        // restore the state of peers from a previous run
+        let mut backed_off_peers = HashMap::new();
+        let system_now = SystemTime::now();
+        for persisted in persisted_peers {
+            let NodeRecord { address, tcp_port, udp_port: _, id } = persisted.record;
+            let peer =
+                peers.entry(id).or_insert_with(|| Peer::new(SocketAddr::from((address, tcp_port))));
+            peer.reputation = persisted.reputation;
+            peer.severe_backoff_counter = persisted.severe_backoff_counter;
+
+            if peer.is_banned() {
+                // the ban list is not persisted, so we ban the peer again for the configured
+                // duration, after which its reputation is reset
+                ban_list.ban_peer_until(id, std::time::Instant::now() + ban_duration);
+            }
+
+            let remaining = persisted
+                .backoff_until
+                .and_then(|until| until.duration_since(system_now).ok())
+                .filter(|remaining| !remaining.is_zero());
+            if let Some(remaining) = remaining {
+                peer.backed_off = true;
+                backed_off_peers.insert(id, std::time::Instant::now() + remaining);
+            }
+        }
+
+        let mut manager = Self {
             peers,
             manager_tx,
             handle_rx: UnboundedReceiverStream::new(handle_rx),
@@ -160,13 +236,18 @@ impl PeersManager {
             release_interval: tokio::time::interval_at(now + unban_interval, unban_interval),
             connection_info,
             ban_list,
-            backed_off_peers: Default::default(),
+            backed_off_peers,
             ban_duration,
             backoff_durations,
             connect_trusted_nodes_only,
+            trusted_nodes_file,
+            inbound_connections: Default::default(),
             last_tick: Instant::now(),
             max_backoff_count,
-        }
+        };
+        // load the trusted nodes file before the first connections are established
+        manager.reload_trusted_nodes_file();
+        manager
     }
 
     /// Returns a new [`PeersHandle`] that can send commands to this type.
@@ -185,6 +266,45 @@ impl PeersManager {
         self.peers.iter().map(|(peer_id, v)| NodeRecord::new(v.addr, *peer_id))
     }
 
+    /// Returns an iterator over the state of all peers that should be persisted across restarts.
+    pub(crate) fn iter_persisted_peers(&self) -> impl Iterator<Item = PersistedPeer> + '_ {
+        let now = std::time::Instant::now();
+        let system_now = SystemTime::now();
+        self.peers.iter().map(move |(peer_id, peer)| {
+            let backoff_until = self
+                .backed_off_peers
+                .get(peer_id)
+                .map(|until| system_now + until.saturating_duration_since(now));
+            PersistedPeer {
+                record: NodeRecord::new(peer.addr, *peer_id),
+                reputation: peer.reputation,
+                severe_backoff_counter: peer.severe_backoff_counter,
+                backoff_until,
+            }
+        })
+    }
+
+    /// Returns the active bans, see also [`PeersConfig::with_bans_from_file`].
+    pub(crate) fn bans(&self) -> Bans {
+        let now = std::time::Instant::now();
+        let system_now = SystemTime::now();
+        let until = move |until: Option<std::time::Instant>| {
+            until.map(|until| system_now + until.saturating_duration_since(now))
+        };
+        Bans {
+            peers: self
+                .ban_list
+                .banned_peers()
+                .map(|(peer_id, banned_until)| BannedPeer { peer_id, until: until(banned_until) })
+                .collect(),
+            ips: self
+                .ban_list
+                .banned_ips()
+                .map(|(ip, banned_until)| BannedIp { ip, until: until(banned_until) })
+                .collect(),
+        }
+    }
+
     /// Returns an iterator over all peer ids for peers with the given kind
     pub(crate) fn peers_by_kind(&self, kind: PeerKind) -> impl Iterator<Item = PeerId> + '_ {
         self.peers.iter().filter_map(move |(peer_id, peer)| (peer.kind == kind).then_some(*peer_id))
@@ -211,7 +331,10 @@ impl PeersManager {
     /// Invoked when a new _incoming_ tcp connection is accepted.
     ///
     /// returns an error if the inbound ip address is on the ban list or
-    /// we have reached our limit for max inbound connections
+    /// we have reached our limit for max inbound connections, in total or from the ip address or
+    /// its subnet.
+    ///
+    /// An ip address that exceeds its limit is banned for the low backoff duration.
     pub(crate) fn on_incoming_pending_session(
         &mut self,
         addr: IpAddr,
@@ -222,20 +345,37 @@ impl PeersManager {
         if !self.connection_info.has_in_capacity() {
             return Err(InboundConnectionError::ExceedsLimit(self.connection_info.max_inbound))
         }
+        // connections from the local host, for example of local test networks, are not limited
+        if !addr.is_loopback() {
+            let max_inbound_per_ip = self.connection_info.max_inbound_per_ip;
+            if self.inbound_connections.num_per_ip(&addr) >= max_inbound_per_ip {
+                self.ban_list
+                    .ban_ip_until(addr, std::time::Instant::now() + self.backoff_durations.low);
+                return Err(InboundConnectionError::ExceedsIpLimit(max_inbound_per_ip))
+            }
+            let max_inbound_per_subnet = self.connection_info.max_inbound_per_subnet;
+            let subnet = subnet(addr, self.connection_info.inbound_subnet_prefix);
+            if self.inbound_connections.num_per_subnet(&subnet) >= max_inbound_per_subnet {
+                return Err(InboundConnectionError::ExceedsSubnetLimit(max_inbound_per_subnet))
+            }
+        }
         // keep track of new connection
         self.connection_info.inc_in();
+        self.inbound_connections.inc(addr, self.connection_info.inbound_subnet_prefix);
         Ok(())
     }
 
     /// Invoked when a previous call to [Self::on_incoming_pending_session] succeeded but it was
     /// rejected.
-    pub(crate) fn on_incoming_pending_session_rejected_internally(&mut self) {
+    pub(crate) fn on_incoming_pending_session_rejected_internally(&mut self, addr: IpAddr) {
         self.connection_info.decr_in();
+        self.inbound_connections.decr(addr, self.connection_info.inbound_subnet_prefix);
     }
 
     /// Invoked when a pending session was closed.
-    pub(crate) fn on_incoming_pending_session_gracefully_closed(&mut self) {
-        self.connection_info.decr_in()
+    pub(crate) fn on_incoming_pending_session_gracefully_closed(&mut self, addr: IpAddr) {
+        self.connection_info.decr_in();
+        self.inbound_connections.decr(addr, self.connection_info.inbound_subnet_prefix);
     }
 
     /// Invoked when a pending session was closed.
@@ -253,7 +393,8 @@ impl PeersManager {
             }
         }
 
-        self.connection_info.decr_in()
+        self.connection_info.decr_in();
+        self.inbound_connections.decr(remote_addr.ip(), self.connection_info.inbound_subnet_prefix);
     }
 
     /// Called when a new _incoming_ active session was established to the given peer.
@@ -263,6 +404,9 @@ impl PeersManager {
     /// If the reputation of the peer is below the `BANNED_REPUTATION` threshold, a disconnect will
     /// be scheduled.
     pub(crate) fn on_incoming_session_established(&mut self, peer_id: PeerId, addr: SocketAddr) {
+        // the connection is tracked until the session is closed
+        self.inbound_connections.sessions.insert(peer_id, addr.ip());
+
         // we only need to check the peer id here as the ip address will have been checked at
         // on_inbound_pending_session. We also check if the peer is in the backoff list here.
         if self.ban_list.is_banned_peer(&peer_id) {
@@ -291,6 +435,16 @@ impl PeersManager {
                 self.queued_actions.push_back(PeerAction::PeerAdded(peer_id));
             }
         }
+
+        // only trusted peers are allowed to connect in trusted nodes only mode
+        if self.connect_trusted_nodes_only &&
+            !self.peers.get(&peer_id).map_or(false, |peer| peer.is_trusted())
+        {
+            self.queued_actions.push_back(PeerAction::Disconnect {
+                peer_id,
+                reason: Some(DisconnectReason::UselessPeer),
+            });
+        }
     }
 
     /// Bans the peer temporarily with the configured ban timeout
@@ -320,6 +474,51 @@ impl PeersManager {
         self.queued_actions.push_back(PeerAction::UnBanPeer { peer_id });
     }
 
+    /// Bans the peer for the given duration or indefinitely, and disconnects it if connected.
+    fn ban_peer_manually(&mut self, peer_id: PeerId, duration: Option<Duration>) {
+        trace!(target: "net::peers", ?peer_id, ?duration, "banning peer");
+        let until = duration.map(|duration| std::time::Instant::now() + duration);
+        self.ban_list.ban_peer_with(peer_id, until);
+        if self.peers.get(&peer_id).map_or(false, |peer| peer.state.is_connected()) {
+            self.queued_actions.push_back(PeerAction::Disconnect {
+                peer_id,
+                reason: Some(DisconnectReason::DisconnectRequested),
+            });
+        }
+        self.queued_actions.push_back(PeerAction::BanPeer { peer_id });
+    }
+
+    /// Lifts the ban of the peer, and resets its reputation if it was banned because of it.
+    fn unban_peer_manually(&mut self, peer_id: PeerId) {
+        trace!(target: "net::peers", ?peer_id, "unbanning peer");
+        if let Some(peer) = self.peers.get_mut(&peer_id) {
+            if peer.is_banned() {
+                peer.unban();
+            }
+        }
+        self.unban_peer(peer_id);
+    }
+
+    /// Bans the IP address for the given duration or indefinitely, and disconnects all connected
+    /// peers with this address.
+    fn ban_ip_manually(&mut self, ip: IpAddr, duration: Option<Duration>) {
+        trace!(target: "net::peers", ?ip, ?duration, "banning ip");
+        let until = duration.map(|duration| std::time::Instant::now() + duration);
+        self.ban_list.ban_ip_with(ip, until);
+        if !self.ban_list.is_banned_ip(&ip) {
+            // non-global addresses are never banned
+            return
+        }
+        for (peer_id, peer) in self.peers.iter() {
+            if peer.addr.ip() == ip && peer.state.is_connected() {
+                self.queued_actions.push_back(PeerAction::Disconnect {
+                    peer_id: *peer_id,
+                    reason: Some(DisconnectReason::DisconnectRequested),
+                });
+            }
+        }
+    }
+
     /// Tick function to update reputation of all connected peers.
     /// Peers are rewarded with reputation increases for the time they are connected since the last
     /// tick. This is to prevent peers from being disconnected eventually due to slashed
@@ -402,6 +601,7 @@ impl PeersManager {
 
     /// Gracefully disconnected an active session
     pub(crate) fn on_active_session_gracefully_closed(&mut self, peer_id: PeerId) {
+        self.release_inbound_session(&peer_id);
         match self.peers.entry(peer_id) {
             Entry::Occupied(mut entry) => {
                 self.connection_info.decr_state(entry.get().state);
@@ -435,9 +635,17 @@ impl PeersManager {
         peer_id: &PeerId,
         err: &EthStreamError,
     ) {
+        self.release_inbound_session(peer_id);
         self.on_connection_failure(remote_addr, peer_id, err, ReputationChangeKind::Dropped)
     }
 
+    /// Stops tracking the connection of the session, if it was an inbound session.
+    fn release_inbound_session(&mut self, peer_id: &PeerId) {
+        if let Some(ip) = self.inbound_connections.sessions.remove(peer_id) {
+            self.inbound_connections.decr(ip, self.connection_info.inbound_subnet_prefix);
+        }
+    }
+
     /// Called when an attempt to create an _outgoing_ pending session failed while setting up a tcp
     /// connection.
     pub(crate) fn on_outgoing_connection_failure(
@@ -529,9 +737,14 @@ impl PeersManager {
     ///
     /// If the session was an outgoing connection, this means that the peer initiated a connection
     /// to us at the same time and this connection is already established.
-    pub(crate) fn on_already_connected(&mut self, direction: Direction) {
+    pub(crate) fn on_already_connected(&mut self, direction: Direction, remote_addr: SocketAddr) {
         match direction {
-            Direction::Incoming => {}
+            Direction::Incoming => {
+                // the pending session is closed, the connection was counted when it was accepted
+                self.connection_info.decr_in();
+                self.inbound_connections
+                    .decr(remote_addr.ip(), self.connection_info.inbound_subnet_prefix);
+            }
             Direction::Outgoing(_) => {
                 // need to decrement the outgoing counter
                 self.connection_info.decr_out();
@@ -560,7 +773,6 @@ impl PeersManager {
     /// Called for a newly discovered trusted peer.
     ///
     /// If the peer already exists, then the address and kind will be updated.
-    #[allow(dead_code)]
     pub(crate) fn add_trusted_peer(&mut self, peer_id: PeerId, addr: SocketAddr) {
         self.add_peer_kind(peer_id, PeerKind::Trusted, addr, None)
     }
@@ -642,6 +854,26 @@ impl PeersManager {
         peer.kind = PeerKind::Basic;
     }
 
+    /// Reloads the trusted nodes file if it changed since it was last loaded.
+    ///
+    /// Nodes that were added to the file are added as trusted peers, nodes that were removed from
+    /// the file are removed from the set and disconnected.
+    fn reload_trusted_nodes_file(&mut self) {
+        let Some((added, removed)) =
+            self.trusted_nodes_file.as_mut().and_then(|file| file.reload())
+        else {
+            return
+        };
+
+        for NodeRecord { address, tcp_port, udp_port: _, id } in added {
+            self.add_trusted_peer(id, SocketAddr::from((address, tcp_port)));
+        }
+        for id in removed {
+            self.remove_peer_from_trusted_set(id);
+            self.remove_peer(id);
+        }
+    }
+
     /// Returns the idle peer with the highest reputation.
     ///
     /// Peers that are `trusted`, see [PeerKind], are prioritized as long as they're not currently
@@ -745,6 +977,15 @@ impl PeersManager {
                     PeerCommand::GetPeers(tx) => {
                         let _ = tx.send(self.iter_peers().collect());
                     }
+                    PeerCommand::BanPeer(peer_id, duration) => {
+                        self.ban_peer_manually(peer_id, duration)
+                    }
+                    PeerCommand::UnbanPeer(peer_id) => self.unban_peer_manually(peer_id),
+                    PeerCommand::BanIp(ip, duration) => self.ban_ip_manually(ip, duration),
+                    PeerCommand::UnbanIp(ip) => self.ban_list.unban_ip(&ip),
+                    PeerCommand::GetBans(tx) => {
+                        let _ = tx.send(self.bans());
+                    }
                 }
             }
 
@@ -774,6 +1015,14 @@ impl PeersManager {
                 })
             }
 
+            let reload_trusted_nodes_file = self
+                .trusted_nodes_file
+                .as_mut()
+                .map_or(false, |file| file.reload_interval.poll_tick(cx).is_ready());
+            if reload_trusted_nodes_file {
+                self.reload_trusted_nodes_file();
+            }
+
             while self.refill_slots_interval.poll_tick(cx).is_ready() {
                 self.fill_outbound_slots();
             }
@@ -808,6 +1057,13 @@ pub struct ConnectionInfo {
     /// Maximum allowed concurrent outbound dials.
     #[cfg_attr(feature = "serde", serde(default))]
     max_concurrent_outbound_dials: usize,
+    /// Maximum allowed inbound connections from a single IP address.
+    max_inbound_per_ip: usize,
+    /// Maximum allowed inbound connections from a single subnet.
+    max_inbound_per_subnet: usize,
+    /// The prefix length of the IPv4 subnets of `max_inbound_per_subnet`, IPv6 subnets are always
+    /// `/64`.
+    inbound_subnet_prefix: u8,
 }
 
 // === impl ConnectionInfo ===
@@ -856,7 +1112,58 @@ impl Default for ConnectionInfo {
             max_outbound: DEFAULT_MAX_PEERS_OUTBOUND,
             max_inbound: DEFAULT_MAX_PEERS_INBOUND,
             max_concurrent_outbound_dials: DEFAULT_MAX_CONCURRENT_DIALS,
+            max_inbound_per_ip: DEFAULT_MAX_PEERS_INBOUND_PER_IP,
+            max_inbound_per_subnet: DEFAULT_MAX_PEERS_INBOUND_PER_SUBNET,
+            inbound_subnet_prefix: DEFAULT_INBOUND_SUBNET_PREFIX,
+        }
+    }
+}
+
+/// Tracks the number of inbound connections, pending and active, per IP address and subnet.
+#[derive(Debug, Default)]
+struct InboundConnections {
+    per_ip: HashMap<IpAddr, usize>,
+    per_subnet: HashMap<IpAddr, usize>,
+    /// The IP addresses of the active inbound sessions.
+    sessions: HashMap<PeerId, IpAddr>,
+}
+
+impl InboundConnections {
+    fn num_per_ip(&self, ip: &IpAddr) -> usize {
+        self.per_ip.get(ip).copied().unwrap_or_default()
+    }
+
+    fn num_per_subnet(&self, subnet: &IpAddr) -> usize {
+        self.per_subnet.get(subnet).copied().unwrap_or_default()
+    }
+
+    fn inc(&mut self, ip: IpAddr, subnet_prefix: u8) {
+        *self.per_ip.entry(ip).or_default() += 1;
+        *self.per_subnet.entry(subnet(ip, subnet_prefix)).or_default() += 1;
+    }
+
+    fn decr(&mut self, ip: IpAddr, subnet_prefix: u8) {
+        fn decr_entry(counts: &mut HashMap<IpAddr, usize>, key: IpAddr) {
+            if let Entry::Occupied(mut entry) = counts.entry(key) {
+                *entry.get_mut() -= 1;
+                if *entry.get() == 0 {
+                    entry.remove();
+                }
+            }
         }
+        decr_entry(&mut self.per_ip, ip);
+        decr_entry(&mut self.per_subnet, subnet(ip, subnet_prefix));
+    }
+}
+
+/// Returns the subnet of the IP address, with the given prefix length for IPv4 and `/64` for IPv6.
+fn subnet(ip: IpAddr, ipv4_prefix: u8) -> IpAddr {
+    match ip {
+        IpAddr::V4(ip) => {
+            let mask = u32::MAX.checked_shl(32 - ipv4_prefix.min(32) as u32).unwrap_or_default();
+            IpAddr::V4((u32::from(ip) & mask).into())
+        }
+        IpAddr::V6(ip) => IpAddr::V6((u128::from(ip) & (u128::MAX << 64)).into()),
     }
 }
 
@@ -1040,6 +1347,16 @@ pub(crate) enum PeerCommand {
     GetPeer(PeerId, oneshot::Sender<Option<Peer>>),
     /// Get node information on all peers
     GetPeers(oneshot::Sender<Vec<NodeRecord>>),
+    /// Ban a peer for the given duration, or indefinitely
+    BanPeer(PeerId, Option<Duration>),
+    /// Lift the ban of a peer
+    UnbanPeer(PeerId),
+    /// Ban an IP address for the given duration, or indefinitely
+    BanIp(IpAddr, Option<Duration>),
+    /// Lift the ban of an IP address
+    UnbanIp(IpAddr),
+    /// Get all active bans
+    GetBans(oneshot::Sender<Bans>),
 }
 
 /// Actions the peer manager can trigger.
@@ -1104,7 +1421,14 @@ pub struct PeersConfig {
     /// Trusted nodes to connect to.
     pub trusted_nodes: HashSet<NodeRecord>,
     /// Connect to trusted nodes only?
+    ///
+    /// If enabled, incoming connections of peers that are not trusted are rejected as well.
     pub connect_trusted_nodes_only: bool,
+    /// A JSON file with the enode URLs of additional trusted nodes.
+    ///
+    /// The file is reloaded when it's modified, so that trusted nodes can be added and removed
+    /// without a restart.
+    pub trusted_nodes_file: Option<PathBuf>,
     /// Maximum number of backoff attempts before we give up on a peer and dropping.
     ///
     /// The max time spent of a peer before it's removed from the set is determined by the
@@ -1118,6 +1442,9 @@ pub struct PeersConfig {
     /// Basic nodes to connect to.
     #[cfg_attr(feature = "serde", serde(skip))]
     pub basic_nodes: HashSet<NodeRecord>,
+    /// The state of peers from a previous run, such as their reputation and backoff.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub persisted_peers: Vec<PersistedPeer>,
     /// How long to ban bad peers.
     #[cfg_attr(feature = "serde", serde(with = "humantime_serde"))]
     pub ban_duration: Duration,
@@ -1133,6 +1460,8 @@ pub struct PeersConfig {
     ///
     /// The backoff duration increases with number of backoff attempts.
     pub backoff_durations: PeerBackoffDurations,
+    /// How the connected peers that serve download requests are selected.
+    pub peer_selection: PeerSelection,
 }
 
 impl Default for PeersConfig {
@@ -1147,8 +1476,11 @@ impl Default for PeersConfig {
             backoff_durations: Default::default(),
             trusted_nodes: Default::default(),
             connect_trusted_nodes_only: false,
+            trusted_nodes_file: None,
             basic_nodes: Default::default(),
+            persisted_peers: Default::default(),
             max_backoff_count: 5,
+            peer_selection: Default::default(),
         }
     }
 }
@@ -1160,6 +1492,12 @@ impl PeersConfig {
         self
     }
 
+    /// Configure how the peers that serve download requests are selected
+    pub fn with_peer_selection(mut self, peer_selection: PeerSelection) -> Self {
+        self.peer_selection = peer_selection;
+        self
+    }
+
     /// Configure how long to ban bad peers
     pub fn with_ban_duration(mut self, ban_duration: Duration) -> Self {
         self.ban_duration = ban_duration;
@@ -1184,6 +1522,24 @@ impl PeersConfig {
         self
     }
 
+    /// Maximum allowed inbound connections from a single IP address.
+    pub fn with_max_inbound_per_ip(mut self, max_inbound_per_ip: usize) -> Self {
+        self.connection_info.max_inbound_per_ip = max_inbound_per_ip;
+        self
+    }
+
+    /// Maximum allowed inbound connections from a single subnet, with the given prefix length for
+    /// IPv4 subnets. IPv6 subnets are always `/64`.
+    pub fn with_max_inbound_per_subnet(
+        mut self,
+        max_inbound_per_subnet: usize,
+        ipv4_prefix: u8,
+    ) -> Self {
+        self.connection_info.max_inbound_per_subnet = max_inbound_per_subnet;
+        self.connection_info.inbound_subnet_prefix = ipv4_prefix;
+        self
+    }
+
     /// Maximum allowed inbound connections with optional update.
     pub fn with_max_inbound_opt(mut self, max_inbound: Option<usize>) -> Self {
         if let Some(max_inbound) = max_inbound {
@@ -1230,6 +1586,12 @@ impl PeersConfig {
         self
     }
 
+    /// Peers with their state from a previous run.
+    pub fn with_persisted_peers(mut self, peers: Vec<PersistedPeer>) -> Self {
+        self.persisted_peers = peers;
+        self
+    }
+
     /// Configures the max allowed backoff count.
     pub fn with_max_backoff_count(mut self, max_backoff_count: u32) -> Self {
         self.max_backoff_count = max_backoff_count;
@@ -1248,9 +1610,60 @@ impl PeersConfig {
         self
     }
 
+    /// Sets the file with additional trusted nodes, which is reloaded when it's modified.
+    pub fn with_trusted_nodes_file(mut self, trusted_nodes_file: Option<PathBuf>) -> Self {
+        self.trusted_nodes_file = trusted_nodes_file;
+        self
+    }
+
+    /// Adds the given bans to the ban list, except for bans that already expired.
+    pub fn with_bans(mut self, bans: Bans) -> Self {
+        let now = std::time::Instant::now();
+        let system_now = SystemTime::now();
+        // the expiry of the ban, or `None` if the ban already expired
+        let until = |until: Option<SystemTime>| match until {
+            Some(until) => {
+                until.duration_since(system_now).ok().map(|remaining| Some(now + remaining))
+            }
+            None => Some(None),
+        };
+        for BannedPeer { peer_id, until: banned_until } in bans.peers {
+            if let Some(until) = until(banned_until) {
+                self.ban_list.ban_peer_with(peer_id, until);
+            }
+        }
+        for BannedIp { ip, until: banned_until } in bans.ips {
+            if let Some(until) = until(banned_until) {
+                self.ban_list.ban_ip_with(ip, until);
+            }
+        }
+        self
+    }
+
+    /// Read the bans of a previous run from file. Ignored if None or if the file doesn't exist.
+    ///
+    /// See also [`PeersHandle::bans`].
+    pub fn with_bans_from_file(
+        self,
+        optional_file: Option<impl AsRef<Path>>,
+    ) -> Result<Self, io::Error> {
+        let Some(file_path) = optional_file else { return Ok(self) };
+        let reader = match std::fs::File::open(file_path.as_ref()) {
+            Ok(file) => io::BufReader::new(file),
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(self),
+            Err(e) => Err(e)?,
+        };
+        info!(target: "net::peers", file = %file_path.as_ref().display(), "Loading saved bans");
+        let bans: Bans = serde_json::from_reader(reader)?;
+        Ok(self.with_bans(bans))
+    }
+
     /// Read from file nodes available at launch. Ignored if None.
+    ///
+    /// The file can either contain a list of [`NodeRecord`]s or a list of [`PersistedPeer`]s, in
+    /// which case the state of the peers is restored as well.
     pub fn with_basic_nodes_from_file(
-        self,
+        mut self,
         optional_file: Option<impl AsRef<Path>>,
     ) -> Result<Self, io::Error> {
         let Some(file_path) = optional_file else { return Ok(self) };
@@ -1260,11 +1673,142 @@ impl PeersConfig {
             Err(e) => Err(e)?,
         };
         info!(target: "net::peers", file = %file_path.as_ref().display(), "Loading saved peers");
-        let nodes: HashSet<NodeRecord> = serde_json::from_reader(reader)?;
-        Ok(self.with_basic_nodes(nodes))
+        let entries: Vec<PersistedPeerEntry> = serde_json::from_reader(reader)?;
+        for entry in entries {
+            match entry {
+                PersistedPeerEntry::Record(record) => {
+                    self.basic_nodes.insert(record);
+                }
+                PersistedPeerEntry::Peer(peer) => self.persisted_peers.push(peer),
+            }
+        }
+        Ok(self)
+    }
+}
+
+/// A file with the enode URLs of trusted nodes, which is reloaded when it's modified.
+#[derive(Debug)]
+struct TrustedNodesFile {
+    path: PathBuf,
+    /// The modification time of the file when it was last loaded.
+    modified: Option<SystemTime>,
+    /// The nodes of the file when it was last loaded.
+    nodes: HashSet<NodeRecord>,
+    /// Trusted nodes of the config, which are never removed.
+    configured: HashSet<PeerId>,
+    /// The interval at which the file is checked for changes.
+    reload_interval: Interval,
+}
+
+impl TrustedNodesFile {
+    fn new(path: PathBuf, configured: HashSet<PeerId>) -> Self {
+        let reload_interval = tokio::time::interval_at(
+            Instant::now() + TRUSTED_NODES_FILE_RELOAD_INTERVAL,
+            TRUSTED_NODES_FILE_RELOAD_INTERVAL,
+        );
+        Self { path, modified: None, nodes: HashSet::new(), configured, reload_interval }
+    }
+
+    /// Reloads the file if it was modified since it was last loaded.
+    ///
+    /// Returns the added nodes and the ids of the removed nodes. A missing file contains no
+    /// nodes, the nodes are kept if the file can't be parsed.
+    fn reload(&mut self) -> Option<(Vec<NodeRecord>, Vec<PeerId>)> {
+        let modified = std::fs::metadata(&self.path).and_then(|metadata| metadata.modified()).ok();
+        if modified.is_some() && modified == self.modified {
+            return None
+        }
+        self.modified = modified;
+
+        let nodes = match std::fs::read(&self.path) {
+            Ok(contents) => match serde_json::from_slice::<HashSet<NodeRecord>>(&contents) {
+                Ok(nodes) => nodes,
+                Err(err) => {
+                    warn!(target: "net::peers", %err, file = %self.path.display(), "Failed to parse trusted nodes file");
+                    return None
+                }
+            },
+            Err(err) if err.kind() == ErrorKind::NotFound => HashSet::new(),
+            Err(err) => {
+                warn!(target: "net::peers", %err, file = %self.path.display(), "Failed to read trusted nodes file");
+                return None
+            }
+        };
+        if nodes == self.nodes {
+            return None
+        }
+        info!(target: "net::peers", file = %self.path.display(), nodes = nodes.len(), "Loaded trusted nodes file");
+
+        let added = nodes.difference(&self.nodes).copied().collect();
+        let removed = self
+            .nodes
+            .difference(&nodes)
+            .map(|node| node.id)
+            .filter(|id| !self.configured.contains(id) && !nodes.iter().any(|node| node.id == *id))
+            .collect();
+        self.nodes = nodes;
+        Some((added, removed))
     }
 }
 
+/// The state of a peer that is persisted across restarts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PersistedPeer {
+    /// Where to reach the peer.
+    pub record: NodeRecord,
+    /// Reputation of the peer.
+    pub reputation: i32,
+    /// Number of times the peer was backed off due to a severe [BackoffKind].
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub severe_backoff_counter: u32,
+    /// Until when the peer is backed off, if it is.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub backoff_until: Option<SystemTime>,
+}
+
+/// The active bans of peers and IP addresses, which can be persisted across restarts.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Bans {
+    /// The banned peers.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub peers: Vec<BannedPeer>,
+    /// The banned IP addresses.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub ips: Vec<BannedIp>,
+}
+
+/// A banned peer, see [`Bans`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BannedPeer {
+    /// The ID of the peer.
+    pub peer_id: PeerId,
+    /// Until when the peer is banned, or `None` if it's banned indefinitely.
+    pub until: Option<SystemTime>,
+}
+
+/// A banned IP address, see [`Bans`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BannedIp {
+    /// The IP address.
+    pub ip: IpAddr,
+    /// Until when the address is banned, or `None` if it's banned indefinitely.
+    pub until: Option<SystemTime>,
+}
+
+/// An entry of a persisted peers file.
+///
+/// Older files only contain the [`NodeRecord`] of each peer.
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize), serde(untagged))]
+enum PersistedPeerEntry {
+    Record(NodeRecord),
+    Peer(PersistedPeer),
+}
+
 /// The durations to use when a backoff should be applied to a peer.
 ///
 /// See also [`BackoffKind`].
@@ -1325,6 +1869,8 @@ impl Default for PeerBackoffDurations {
 #[derive(Debug, Error)]
 pub enum InboundConnectionError {
     ExceedsLimit(usize),
+    ExceedsIpLimit(usize),
+    ExceedsSubnetLimit(usize),
     IpBanned,
 }
 
@@ -1342,7 +1888,7 @@ mod tests {
         peers::{
             manager::{ConnectionInfo, PeerBackoffDurations, PeerConnectionState},
             reputation::DEFAULT_REPUTATION,
-            PeerAction,
+            BannedPeer, Bans, PeerAction, PersistedPeer,
         },
         session::PendingSessionHandshakeError,
         PeersConfig,
@@ -1362,7 +1908,7 @@ mod tests {
         net::{IpAddr, Ipv4Addr, SocketAddr},
         pin::Pin,
         task::{Context, Poll},
-        time::Duration,
+        time::{Duration, SystemTime},
     };
 
     struct PeerActionFuture<'a> {
@@ -1807,7 +2353,7 @@ mod tests {
 
         assert!(peers.on_incoming_pending_session(socket_addr.ip()).is_ok());
         assert_eq!(peers.connection_info.num_inbound, 1);
-        peers.on_incoming_pending_session_rejected_internally();
+        peers.on_incoming_pending_session_rejected_internally(socket_addr.ip());
         assert_eq!(peers.connection_info.num_inbound, 0);
     }
 
@@ -1818,7 +2364,7 @@ mod tests {
 
         assert!(peers.on_incoming_pending_session(socket_addr.ip()).is_ok());
         assert_eq!(peers.connection_info.num_inbound, 1);
-        peers.on_incoming_pending_session_gracefully_closed();
+        peers.on_incoming_pending_session_gracefully_closed(socket_addr.ip());
         assert_eq!(peers.connection_info.num_inbound, 0);
     }
 
@@ -2025,7 +2571,9 @@ mod tests {
             Ok(_) => panic!(),
             Err(err) => match err {
                 super::InboundConnectionError::IpBanned {} => {}
-                super::InboundConnectionError::ExceedsLimit { .. } => {
+                super::InboundConnectionError::ExceedsLimit { .. } |
+                super::InboundConnectionError::ExceedsIpLimit { .. } |
+                super::InboundConnectionError::ExceedsSubnetLimit { .. } => {
                     panic!()
                 }
             },
@@ -2165,6 +2713,120 @@ mod tests {
         .await;
     }
 
+    #[tokio::test]
+    async fn test_inbound_limits_per_ip_and_subnet() {
+        let config =
+            PeersConfig::default().with_max_inbound_per_ip(2).with_max_inbound_per_subnet(3, 24);
+        let mut peers = PeersManager::new(config);
+        let ip = IpAddr::V4(Ipv4Addr::new(1, 1, 1, 2));
+
+        assert!(peers.on_incoming_pending_session(ip).is_ok());
+        assert!(peers.on_incoming_pending_session(ip).is_ok());
+        assert!(matches!(
+            peers.on_incoming_pending_session(ip),
+            Err(InboundConnectionError::ExceedsIpLimit(2))
+        ));
+        // the ip is banned for exceeding its limit
+        assert!(matches!(
+            peers.on_incoming_pending_session(ip),
+            Err(InboundConnectionError::IpBanned)
+        ));
+
+        // the subnet has room for one more connection
+        assert!(peers.on_incoming_pending_session(IpAddr::V4(Ipv4Addr::new(1, 1, 1, 3))).is_ok());
+        assert!(matches!(
+            peers.on_incoming_pending_session(IpAddr::V4(Ipv4Addr::new(1, 1, 1, 4))),
+            Err(InboundConnectionError::ExceedsSubnetLimit(3))
+        ));
+        assert!(peers.on_incoming_pending_session(IpAddr::V4(Ipv4Addr::new(1, 1, 2, 4))).is_ok());
+
+        // closed connections release the limit of the subnet
+        peers.on_incoming_pending_session_gracefully_closed(ip);
+        assert!(peers.on_incoming_pending_session(IpAddr::V4(Ipv4Addr::new(1, 1, 1, 4))).is_ok());
+
+        // connections from the local host are not limited
+        for _ in 0..3 {
+            assert!(peers.on_incoming_pending_session(IpAddr::V4(Ipv4Addr::LOCALHOST)).is_ok());
+        }
+    }
+
+    #[test]
+    fn test_subnet() {
+        let ip = IpAddr::V4(Ipv4Addr::new(192, 168, 17, 42));
+        assert_eq!(subnet(ip, 24), IpAddr::V4(Ipv4Addr::new(192, 168, 17, 0)));
+        assert_eq!(subnet(ip, 16), IpAddr::V4(Ipv4Addr::new(192, 168, 0, 0)));
+        assert_eq!(subnet(ip, 0), IpAddr::V4(Ipv4Addr::UNSPECIFIED));
+        assert_eq!(subnet(ip, 32), ip);
+
+        let ip: IpAddr = "2001:db8:1:2:3:4:5:6".parse().unwrap();
+        assert_eq!(subnet(ip, 24), "2001:db8:1:2::".parse::<IpAddr>().unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_reject_untrusted_incoming() {
+        let trusted_peer = PeerId::random();
+        let config = PeersConfig::default()
+            .with_trusted_nodes(HashSet::from([NodeRecord {
+                address: IpAddr::V4(Ipv4Addr::new(127, 0, 1, 2)),
+                tcp_port: 8008,
+                udp_port: 8008,
+                id: trusted_peer,
+            }]))
+            .with_connect_trusted_nodes_only(true);
+        let mut peers = PeersManager::new(config);
+        let socket_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 1, 3)), 8008);
+
+        let untrusted_peer = PeerId::random();
+        assert!(peers.on_incoming_pending_session(socket_addr.ip()).is_ok());
+        peers.on_incoming_session_established(untrusted_peer, socket_addr);
+        assert!(
+            matches!(event!(peers), PeerAction::PeerAdded(peer_id) if peer_id == untrusted_peer)
+        );
+        match event!(peers) {
+            PeerAction::Disconnect { peer_id, reason } => {
+                assert_eq!(peer_id, untrusted_peer);
+                assert_eq!(reason, Some(DisconnectReason::UselessPeer));
+            }
+            _ => unreachable!(),
+        }
+
+        // the slot is released once the session is closed
+        peers.on_active_session_gracefully_closed(untrusted_peer);
+        assert_eq!(peers.connection_info.num_inbound, 0);
+        assert!(!peers.peers.contains_key(&untrusted_peer));
+    }
+
+    #[tokio::test]
+    async fn test_reload_trusted_nodes_file() {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        let node = |port| NodeRecord {
+            address: IpAddr::V4(Ipv4Addr::new(127, 0, 1, 2)),
+            tcp_port: port,
+            udp_port: port,
+            id: PeerId::random(),
+        };
+        let (first, second) = (node(8008), node(8009));
+        std::fs::write(file.path(), serde_json::to_vec(&[first]).unwrap()).unwrap();
+
+        let config =
+            PeersConfig::default().with_trusted_nodes_file(Some(file.path().to_path_buf()));
+        let mut peers = PeersManager::new(config);
+        assert!(peers.peers.get(&first.id).unwrap().is_trusted());
+
+        std::fs::write(file.path(), serde_json::to_vec(&[second]).unwrap()).unwrap();
+        // the modification time might not have changed within the resolution of the file system
+        peers.trusted_nodes_file.as_mut().unwrap().modified = None;
+        peers.reload_trusted_nodes_file();
+        assert!(!peers.peers.contains_key(&first.id));
+        assert!(peers.peers.get(&second.id).unwrap().is_trusted());
+
+        // an invalid file keeps the trusted nodes
+        std::fs::write(file.path(), "invalid").unwrap();
+        peers.trusted_nodes_file.as_mut().unwrap().modified = None;
+        peers.reload_trusted_nodes_file();
+        assert!(peers.peers.get(&second.id).unwrap().is_trusted());
+    }
+
     #[tokio::test]
     async fn test_tick() {
         let ip = IpAddr::V4(Ipv4Addr::new(127, 0, 1, 2));
@@ -2254,4 +2916,130 @@ mod tests {
             .count();
         assert_eq!(dials, peer_manager.connection_info.max_concurrent_outbound_dials);
     }
+
+    #[tokio::test]
+    async fn test_restore_persisted_peers() {
+        let socket_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 1, 2)), 8008);
+        let backed_off = PeerId::random();
+        let banned = PeerId::random();
+        let persisted = vec![
+            PersistedPeer {
+                record: NodeRecord::new(socket_addr, backed_off),
+                reputation: DEFAULT_REPUTATION - 100,
+                severe_backoff_counter: 2,
+                backoff_until: Some(SystemTime::now() + Duration::from_secs(60)),
+            },
+            PersistedPeer {
+                record: NodeRecord::new(socket_addr, banned),
+                reputation: i32::MIN,
+                severe_backoff_counter: 0,
+                backoff_until: Some(SystemTime::now() - Duration::from_secs(60)),
+            },
+        ];
+        let config = PeersConfig::default().with_persisted_peers(persisted);
+        let mut peers = PeersManager::new(config);
+
+        let peer = peers.peers.get(&backed_off).unwrap();
+        assert_eq!(peer.reputation, DEFAULT_REPUTATION - 100);
+        assert_eq!(peer.severe_backoff_counter, 2);
+        assert!(peer.is_backed_off());
+        assert!(peers.backed_off_peers.contains_key(&backed_off));
+
+        let peer = peers.peers.get(&banned).unwrap();
+        assert!(peer.is_banned());
+        assert!(!peer.is_backed_off());
+        assert!(peers.ban_list.is_banned_peer(&banned));
+
+        // neither peer is dialed
+        peers.fill_outbound_slots();
+        assert!(peers.queued_actions.is_empty());
+
+        let mut persisted = peers.iter_persisted_peers().collect::<Vec<_>>();
+        persisted.sort_by_key(|peer| peer.reputation);
+        assert_eq!(persisted.len(), 2);
+        assert_eq!(persisted[0].record.id, banned);
+        assert_eq!(persisted[0].backoff_until, None);
+        assert_eq!(persisted[1].record.id, backed_off);
+        assert!(persisted[1].backoff_until.is_some());
+    }
+
+    #[test]
+    fn test_load_persisted_peers_file() {
+        let socket_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 1, 2)), 8008);
+        let record = NodeRecord::new(socket_addr, PeerId::random());
+        let persisted = PersistedPeer {
+            record: NodeRecord::new(socket_addr, PeerId::random()),
+            reputation: DEFAULT_REPUTATION,
+            severe_backoff_counter: 1,
+            backoff_until: None,
+        };
+
+        // files can contain both plain records and persisted peers
+        let contents = format!(
+            "[{}, {}]",
+            serde_json::to_string(&record).unwrap(),
+            serde_json::to_string(&persisted).unwrap()
+        );
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("peers.json");
+        std::fs::write(&file, contents).unwrap();
+
+        let config = PeersConfig::default().with_basic_nodes_from_file(Some(&file)).unwrap();
+        assert_eq!(config.basic_nodes, HashSet::from([record]));
+        assert_eq!(config.persisted_peers, vec![persisted]);
+    }
+
+    #[tokio::test]
+    async fn test_manual_bans() {
+        let peer = PeerId::random();
+        let ip = IpAddr::V4(Ipv4Addr::new(1, 1, 1, 1));
+        let mut peers = PeersManager::default();
+        peers.add_peer(peer, SocketAddr::new(ip, 8008), None);
+        peers.peers.get_mut(&peer).unwrap().state = PeerConnectionState::Out;
+        peers.queued_actions.clear();
+
+        // the connected peer is disconnected
+        peers.ban_peer_manually(peer, None);
+        assert!(matches!(
+            peers.queued_actions.pop_front(),
+            Some(PeerAction::Disconnect { peer_id, .. }) if peer_id == peer
+        ));
+        assert!(matches!(
+            peers.queued_actions.pop_front(),
+            Some(PeerAction::BanPeer { peer_id }) if peer_id == peer
+        ));
+
+        peers.ban_ip_manually(ip, Some(Duration::from_secs(60 * 60)));
+        assert!(matches!(
+            peers.queued_actions.pop_front(),
+            Some(PeerAction::Disconnect { peer_id, .. }) if peer_id == peer
+        ));
+        // non-global addresses are not banned
+        let private_ip = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1));
+        peers.ban_ip_manually(private_ip, None);
+        assert!(!peers.ban_list.is_banned_ip(&private_ip));
+
+        let bans = peers.bans();
+        assert_eq!(bans.peers, vec![BannedPeer { peer_id: peer, until: None }]);
+        assert_eq!(bans.ips.len(), 1);
+        assert_eq!(bans.ips[0].ip, ip);
+        assert!(bans.ips[0].until.unwrap() > SystemTime::now());
+
+        // bans are restored from a previous run, unless they expired
+        let mut bans: Bans = serde_json::from_str(&serde_json::to_string(&bans).unwrap()).unwrap();
+        let expired = PeerId::random();
+        bans.peers.push(BannedPeer {
+            peer_id: expired,
+            until: Some(SystemTime::now() - Duration::from_secs(1)),
+        });
+        let config = PeersConfig::default().with_bans(bans);
+        assert!(config.ban_list.is_banned_peer(&peer));
+        assert!(config.ban_list.is_banned_ip(&ip));
+        assert!(!config.ban_list.is_banned_peer(&expired));
+
+        peers.unban_peer_manually(peer);
+        peers.ban_list.unban_ip(&ip);
+        assert!(!peers.ban_list.is_banned(&peer, &ip));
+        assert_eq!(peers.bans(), Bans::default());
+    }
 }
@@ -4,7 +4,10 @@ mod manager;
 mod reputation;
 
 pub(crate) use manager::InboundConnectionError;
-pub use manager::{ConnectionInfo, Peer, PeerAction, PeersConfig, PeersHandle, PeersManager};
+pub use manager::{
+    BannedIp, BannedPeer, Bans, ConnectionInfo, Peer, PeerAction, PeersConfig, PeersHandle,
+    PeersManager, PersistedPeer,
+};
 pub use reputation::ReputationChangeWeights;
 pub use reth_network_api::PeerKind;
 
@@ -14,5 +17,14 @@ pub(crate) const DEFAULT_MAX_PEERS_OUTBOUND: usize = 100;
 /// Maximum number of available slots for inbound sessions.
 pub(crate) const DEFAULT_MAX_PEERS_INBOUND: usize = 30;
 
+/// Maximum number of available slots for inbound sessions from a single IP address.
+pub(crate) const DEFAULT_MAX_PEERS_INBOUND_PER_IP: usize = 5;
+
+/// Maximum number of available slots for inbound sessions from a single subnet.
+pub(crate) const DEFAULT_MAX_PEERS_INBOUND_PER_SUBNET: usize = 10;
+
+/// The default prefix length of the IPv4 subnets of the inbound session limits.
+pub(crate) const DEFAULT_INBOUND_SUBNET_PREFIX: u8 = 24;
+
 /// Maximum number of available slots concurrent outgoing dials.
 pub(crate) const DEFAULT_MAX_CONCURRENT_DIALS: usize = 10;
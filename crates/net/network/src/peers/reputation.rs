@@ -30,6 +30,9 @@ const BAD_MESSAGE_REPUTATION_CHANGE: i32 = 16 * REPUTATION_UNIT;
 /// apply any changes to the peer's reputation, effectively ignoring it.
 const ALREADY_SEEN_TRANSACTION_REPUTATION_CHANGE: i32 = 0;
 
+/// The reputation change to apply to a peer that sent more requests than we're willing to serve.
+const EXCEEDED_REQUEST_LIMITS_REPUTATION_CHANGE: i32 = 2 * REPUTATION_UNIT;
+
 /// The reputation change to apply to a peer which violates protocol rules: minimal reputation
 const BAD_PROTOCOL_REPUTATION_CHANGE: i32 = i32::MIN;
 
@@ -42,6 +45,7 @@ pub(crate) fn is_banned_reputation(reputation: i32) -> bool {
 /// How the [`ReputationChangeKind`] are weighted.
 #[derive(Debug, Clone, PartialEq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 pub struct ReputationChangeWeights {
     /// Weight for [`ReputationChangeKind::BadMessage`]
     pub bad_message: Reputation,
@@ -59,6 +63,8 @@ pub struct ReputationChangeWeights {
     pub failed_to_connect: Reputation,
     /// Weight for [`ReputationChangeKind::Dropped`]
     pub dropped: Reputation,
+    /// Weight for [`ReputationChangeKind::ExceededRequestLimits`]
+    pub exceeded_request_limits: Reputation,
 }
 
 // === impl ReputationChangeWeights ===
@@ -76,6 +82,7 @@ impl ReputationChangeWeights {
             ReputationChangeKind::BadProtocol => self.bad_protocol.into(),
             ReputationChangeKind::FailedToConnect => self.failed_to_connect.into(),
             ReputationChangeKind::Dropped => self.dropped.into(),
+            ReputationChangeKind::ExceededRequestLimits => self.exceeded_request_limits.into(),
             ReputationChangeKind::Reset => DEFAULT_REPUTATION.into(),
             ReputationChangeKind::Other(val) => val.into(),
         }
@@ -93,6 +100,7 @@ impl Default for ReputationChangeWeights {
             bad_protocol: BAD_PROTOCOL_REPUTATION_CHANGE,
             failed_to_connect: FAILED_TO_CONNECT_REPUTATION_CHANGE,
             dropped: REMOTE_DISCONNECT_REPUTATION_CHANGE,
+            exceeded_request_limits: EXCEEDED_REQUEST_LIMITS_REPUTATION_CHANGE,
         }
     }
 }
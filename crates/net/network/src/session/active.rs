@@ -6,9 +6,11 @@ use crate::{
         config::INITIAL_REQUEST_TIMEOUT,
         conn::EthRlpxConnection,
         handle::{ActiveSessionMessage, SessionCommand},
+        throttle::OutboundThrottle,
         SessionId,
     },
 };
+use alloy_rlp::Encodable;
 use core::sync::atomic::Ordering;
 use fnv::FnvHashMap;
 use futures::{stream::Fuse, SinkExt, StreamExt};
@@ -16,7 +18,7 @@ use reth_eth_wire::{
     capability::Capabilities,
     errors::{EthHandshakeError, EthStreamError, P2PStreamError},
     message::{EthBroadcastMessage, RequestPair},
-    DisconnectP2P, DisconnectReason, EthMessage,
+    DisconnectP2P, DisconnectReason, EthMessage, EthVersion, Receipts,
 };
 use reth_interfaces::p2p::error::RequestError;
 use reth_metrics::common::mpsc::MeteredPollSender;
@@ -96,6 +98,8 @@ pub(crate) struct ActiveSession {
     pub(crate) protocol_breach_request_timeout: Duration,
     /// Used to reserve a slot to guarantee that the termination message is delivered
     pub(crate) terminate_message: Option<(PollSender<ActiveSessionMessage>, ActiveSessionMessage)>,
+    /// Enforces the outbound bandwidth limits of this session.
+    pub(crate) outbound_throttle: OutboundThrottle,
 }
 
 impl ActiveSession {
@@ -171,10 +175,14 @@ impl ActiveSession {
         }
 
         match msg {
-            message @ EthMessage::Status(_) => OnIncomingMessageOutcome::BadMessage {
-                error: EthStreamError::EthHandshakeError(EthHandshakeError::StatusNotInHandshake),
-                message,
-            },
+            message @ (EthMessage::Status(_) | EthMessage::Status69(_)) => {
+                OnIncomingMessageOutcome::BadMessage {
+                    error: EthStreamError::EthHandshakeError(
+                        EthHandshakeError::StatusNotInHandshake,
+                    ),
+                    message,
+                }
+            }
             EthMessage::NewBlockHashes(msg) => {
                 self.try_emit_broadcast(PeerMessage::NewBlockHashes(msg)).into()
             }
@@ -202,6 +210,18 @@ impl ActiveSession {
                 }
                 self.try_emit_broadcast(PeerMessage::PooledTransactions(msg.into())).into()
             }
+            EthMessage::BlockRangeUpdate(msg) => {
+                if !msg.is_valid() {
+                    return OnIncomingMessageOutcome::BadMessage {
+                        error: EthStreamError::InvalidBlockRangeUpdate {
+                            earliest: msg.earliest,
+                            latest: msg.latest,
+                        },
+                        message: EthMessage::BlockRangeUpdate(msg),
+                    }
+                }
+                self.try_emit_broadcast(PeerMessage::BlockRangeUpdated(msg)).into()
+            }
             EthMessage::GetBlockHeaders(req) => {
                 on_request!(req, BlockHeaders, GetBlockHeaders)
             }
@@ -232,6 +252,14 @@ impl ActiveSession {
             EthMessage::Receipts(resp) => {
                 on_response!(resp, GetReceipts)
             }
+            EthMessage::Receipts69(resp) => {
+                // this is converted by the stream already, but is handled for completeness
+                let resp = RequestPair {
+                    request_id: resp.request_id,
+                    message: Receipts::from(resp.message),
+                };
+                on_response!(resp, GetReceipts)
+            }
         }
     }
 
@@ -251,11 +279,21 @@ impl ActiveSession {
     /// Handle a message received from the internal network
     fn on_internal_peer_message(&mut self, msg: PeerMessage) {
         match msg {
+            // block announcements were removed in eth/69
             PeerMessage::NewBlockHashes(msg) => {
-                self.queued_outgoing.push_back(EthMessage::NewBlockHashes(msg).into());
+                if self.conn.version() < EthVersion::Eth69 {
+                    self.queued_outgoing.push_back(EthMessage::NewBlockHashes(msg).into());
+                }
             }
             PeerMessage::NewBlock(msg) => {
-                self.queued_outgoing.push_back(EthBroadcastMessage::NewBlock(msg.block).into());
+                if self.conn.version() < EthVersion::Eth69 {
+                    self.queued_outgoing.push_back(EthBroadcastMessage::NewBlock(msg.block).into());
+                }
+            }
+            PeerMessage::BlockRangeUpdated(msg) => {
+                if self.conn.version() >= EthVersion::Eth69 {
+                    self.queued_outgoing.push_back(EthMessage::BlockRangeUpdate(msg).into());
+                }
             }
             PeerMessage::PooledTransactions(msg) => {
                 if msg.is_valid_for_version(self.conn.version()) {
@@ -550,6 +588,16 @@ impl Future for ActiveSession {
 
             // Send messages by advancing the sink and queuing in buffered messages
             while this.conn.poll_ready_unpin(cx).is_ready() {
+                // ensure the next message is within the bandwidth limits, if it's not we're woken
+                // up once the limits allow sending it
+                if let Some(msg) = this.queued_outgoing.front() {
+                    if !this.outbound_throttle.is_unlimited() &&
+                        this.outbound_throttle.poll_acquire(cx, msg.length()).is_pending()
+                    {
+                        break
+                    }
+                }
+
                 if let Some(msg) = this.queued_outgoing.pop_front() {
                     progress = true;
                     let res = match msg {
@@ -735,6 +783,18 @@ pub(crate) enum OutgoingMessage {
     Broadcast(EthBroadcastMessage),
 }
 
+// === impl OutgoingMessage ===
+
+impl OutgoingMessage {
+    /// Returns the encoded length of the message.
+    fn length(&self) -> usize {
+        match self {
+            OutgoingMessage::Eth(msg) => msg.length(),
+            OutgoingMessage::Broadcast(msg) => msg.length(),
+        }
+    }
+}
+
 impl From<EthMessage> for OutgoingMessage {
     fn from(value: EthMessage) -> Self {
         OutgoingMessage::Eth(value)
@@ -761,14 +821,16 @@ fn calculate_new_timeout(current_timeout: Duration, estimated_rtt: Duration) ->
 mod tests {
     use super::*;
     use crate::session::{
-        config::{INITIAL_REQUEST_TIMEOUT, PROTOCOL_BREACH_REQUEST_TIMEOUT},
+        config::{
+            INITIAL_REQUEST_TIMEOUT, PENDING_SESSION_TIMEOUT, PROTOCOL_BREACH_REQUEST_TIMEOUT,
+        },
         handle::PendingSessionEvent,
-        start_pending_incoming_session,
+        start_pending_incoming_session, Direction,
     };
     use reth_ecies::{stream::ECIESStream, util::pk2id};
     use reth_eth_wire::{
-        EthStream, GetBlockBodies, HelloMessageWithProtocols, P2PStream, Status, StatusBuilder,
-        UnauthedEthStream, UnauthedP2PStream,
+        BlockRangeUpdate, EthStream, GetBlockBodies, HelloMessageWithProtocols, P2PStream, Status,
+        StatusBuilder, UnauthedEthStream, UnauthedP2PStream,
     };
     use reth_net_common::bandwidth_meter::{BandwidthMeter, MeteredStream};
     use reth_primitives::{ForkFilter, Hardfork, MAINNET};
@@ -815,7 +877,7 @@ mod tests {
             F: FnOnce(EthStream<P2PStream<ECIESStream<TcpStream>>>) -> O + Send + 'static,
             O: Future<Output = ()> + Send + Sync,
         {
-            let status = self.status;
+            let mut status = self.status;
             let fork_filter = self.fork_filter.clone();
             let local_peer_id = self.local_peer_id;
             let mut hello = self.hello.clone();
@@ -826,9 +888,10 @@ mod tests {
                 let sink = ECIESStream::connect(outgoing, key, local_peer_id).await.unwrap();
 
                 let (p2p_stream, _) = UnauthedP2PStream::new(sink).handshake(hello).await.unwrap();
+                status.set_eth_version(p2p_stream.shared_capabilities().eth_version().unwrap());
 
                 let (client_stream, _) = UnauthedEthStream::new(p2p_stream)
-                    .handshake(status, fork_filter)
+                    .handshake(status, BlockRangeUpdate::default(), fork_filter)
                     .await
                     .unwrap();
                 f(client_stream).await
@@ -852,8 +915,10 @@ mod tests {
                 self.secret_key,
                 self.hello.clone(),
                 self.status,
+                BlockRangeUpdate::default(),
                 self.fork_filter.clone(),
                 Default::default(),
+                PENDING_SESSION_TIMEOUT,
             ));
 
             let mut stream = ReceiverStream::new(pending_sessions_rx);
@@ -898,6 +963,7 @@ mod tests {
                         )),
                         protocol_breach_request_timeout: PROTOCOL_BREACH_REQUEST_TIMEOUT,
                         terminate_message: None,
+                        outbound_throttle: Default::default(),
                     }
                 }
                 ev => {
@@ -984,6 +1050,43 @@ mod tests {
         rx.await.unwrap();
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_pending_session_timeout() {
+        let builder = SessionBuilder::default();
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let local_addr = listener.local_addr().unwrap();
+
+        // a client that connects but never starts the handshake
+        let _client = TcpStream::connect(local_addr).await.unwrap();
+        let (incoming, remote_addr) = listener.accept().await.unwrap();
+
+        let (_disconnect_tx, disconnect_rx) = oneshot::channel();
+        let (pending_sessions_tx, mut pending_sessions_rx) = mpsc::channel(1);
+        tokio::task::spawn(start_pending_incoming_session(
+            disconnect_rx,
+            SessionId(0),
+            MeteredStream::new_with_meter(incoming, builder.bandwidth_meter.clone()),
+            pending_sessions_tx,
+            remote_addr,
+            builder.secret_key,
+            builder.hello.clone(),
+            builder.status,
+            BlockRangeUpdate::default(),
+            builder.fork_filter.clone(),
+            Default::default(),
+            Duration::from_millis(100),
+        ));
+
+        match pending_sessions_rx.recv().await.unwrap() {
+            PendingSessionEvent::HandshakeTimeout { session_id, direction, .. } => {
+                assert_eq!(session_id, SessionId(0));
+                assert_eq!(direction, Direction::Incoming);
+            }
+            ev => unreachable!("{ev:?}"),
+        }
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn test_send_many_messages() {
         reth_tracing::init_test_tracing();
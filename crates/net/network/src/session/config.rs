@@ -2,8 +2,9 @@
 
 use crate::{
     peers::{DEFAULT_MAX_PEERS_INBOUND, DEFAULT_MAX_PEERS_OUTBOUND},
-    session::{Direction, ExceedsSessionLimit},
+    session::{BandwidthLimits, Direction, ExceedsSessionLimit},
 };
+use reth_net_common::socks::Socks5Proxy;
 use std::time::Duration;
 
 /// Default request timeout for a single request.
@@ -16,6 +17,11 @@ pub const INITIAL_REQUEST_TIMEOUT: Duration = Duration::from_secs(20);
 /// This is the time a peer has to answer a response.
 pub const PROTOCOL_BREACH_REQUEST_TIMEOUT: Duration = Duration::from_secs(2 * 60);
 
+/// Default timeout for the handshakes of a pending session.
+///
+/// This is the time a peer has to complete the `ECIES`, `Hello` and `Status` handshakes.
+pub const PENDING_SESSION_TIMEOUT: Duration = Duration::from_secs(20);
+
 /// Configuration options when creating a [SessionManager](crate::session::SessionManager).
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -38,6 +44,19 @@ pub struct SessionsConfig {
     /// `PROTOCOL_BREACH_REQUEST_TIMEOUT`) this is considered a protocol violation and results in a
     /// dropped session.
     pub protocol_breach_request_timeout: Duration,
+    /// The maximum time a pending session has to complete all handshakes, before it's dropped.
+    pub pending_session_timeout: Duration,
+    /// The maximum number of incoming connections accepted per second.
+    ///
+    /// By default, no limit will be enforced.
+    pub max_incoming_connections_per_sec: Option<u64>,
+    /// Outbound bandwidth limits to enforce.
+    ///
+    /// By default, no limits will be enforced.
+    pub bandwidth_limits: BandwidthLimits,
+    /// The SOCKS5 proxy that outgoing connections are tunneled through, if any.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub proxy: Option<Socks5Proxy>,
 }
 
 impl Default for SessionsConfig {
@@ -56,6 +75,10 @@ impl Default for SessionsConfig {
             limits: Default::default(),
             initial_internal_request_timeout: INITIAL_REQUEST_TIMEOUT,
             protocol_breach_request_timeout: PROTOCOL_BREACH_REQUEST_TIMEOUT,
+            pending_session_timeout: PENDING_SESSION_TIMEOUT,
+            max_incoming_connections_per_sec: None,
+            bandwidth_limits: Default::default(),
+            proxy: None,
         }
     }
 }
@@ -70,6 +93,42 @@ impl SessionsConfig {
         self.session_event_buffer = n;
         self
     }
+
+    /// Sets the [`SessionLimits`] to enforce, for example the maximum number of pending incoming
+    /// sessions that are handshaking concurrently.
+    pub fn with_limits(mut self, limits: SessionLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Sets the maximum time a pending session has to complete the `ECIES`, `Hello` and `Status`
+    /// handshakes.
+    pub fn with_pending_session_timeout(mut self, timeout: Duration) -> Self {
+        self.pending_session_timeout = timeout;
+        self
+    }
+
+    /// Sets the maximum number of incoming connections accepted per second.
+    ///
+    /// Connections that exceed the rate are closed before the handshake starts.
+    pub fn with_max_incoming_connections_per_sec(mut self, limit: u64) -> Self {
+        self.max_incoming_connections_per_sec = Some(limit);
+        self
+    }
+
+    /// Sets the SOCKS5 proxy that all outgoing connections are tunneled through.
+    ///
+    /// Incoming connections and discovery are not affected.
+    pub fn with_proxy(mut self, proxy: Option<Socks5Proxy>) -> Self {
+        self.proxy = proxy;
+        self
+    }
+
+    /// Sets the outbound [`BandwidthLimits`] for all sessions.
+    pub fn with_bandwidth_limits(mut self, limits: BandwidthLimits) -> Self {
+        self.bandwidth_limits = limits;
+        self
+    }
 }
 
 /// Limits for sessions.
@@ -212,6 +212,15 @@ pub enum PendingSessionEvent {
         /// The direction of the session, either `Inbound` or `Outgoing`
         direction: Direction,
     },
+    /// Thrown when the session did not complete the handshakes within the configured timeout.
+    HandshakeTimeout {
+        /// The remote node's socket address
+        remote_addr: SocketAddr,
+        /// The internal identifier for the disconnected session
+        session_id: SessionId,
+        /// The direction of the session, either `Inbound` or `Outgoing`
+        direction: Direction,
+    },
 }
 
 /// Commands that can be sent to the spawned session.
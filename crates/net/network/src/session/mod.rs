@@ -3,23 +3,30 @@
 use crate::{
     message::PeerMessage,
     metrics::SessionManagerMetrics,
-    session::{active::ActiveSession, config::SessionCounter},
+    session::{
+        active::ActiveSession,
+        config::SessionCounter,
+        throttle::{OutboundThrottle, SharedTokenBucket},
+    },
 };
 use fnv::FnvHashMap;
 use futures::{future::Either, io, FutureExt, StreamExt};
+use parking_lot::Mutex;
 use reth_ecies::{stream::ECIESStream, ECIESError};
 use reth_eth_wire::{
     capability::{Capabilities, CapabilityMessage},
     errors::EthStreamError,
-    DisconnectReason, EthVersion, HelloMessageWithProtocols, Status, UnauthedEthStream,
-    UnauthedP2PStream,
+    BlockRangeUpdate, DisconnectReason, EthVersion, HelloMessageWithProtocols, Status,
+    UnauthedEthStream, UnauthedP2PStream,
 };
 use reth_metrics::common::mpsc::MeteredPollSender;
 use reth_net_common::{
     bandwidth_meter::{BandwidthMeter, MeteredStream},
+    socks::Socks5Proxy,
     stream::HasRemoteAddr,
+    token_bucket::TokenBucket,
 };
-use reth_primitives::{ForkFilter, ForkId, ForkTransition, Head, PeerId};
+use reth_primitives::{ForkFilter, ForkId, ForkTransition, Head, PeerId, PruneMode, PruneSegment};
 use reth_tasks::TaskSpawner;
 use secp256k1::SecretKey;
 use std::{
@@ -43,6 +50,7 @@ mod active;
 mod config;
 mod conn;
 mod handle;
+mod throttle;
 pub use crate::message::PeerRequestSender;
 use crate::protocol::{IntoRlpxSubProtocol, RlpxSubProtocolHandlers, RlpxSubProtocols};
 pub use config::{SessionLimits, SessionsConfig};
@@ -52,6 +60,11 @@ pub use handle::{
 };
 use reth_eth_wire::multiplex::RlpxProtocolMultiplexer;
 pub use reth_network_api::{Direction, PeerInfo};
+pub use throttle::BandwidthLimits;
+
+/// The number of blocks either end of the range of available blocks has to move before the new
+/// range is announced to `eth/69` peers.
+const BLOCK_RANGE_UPDATE_INTERVAL: u64 = 32;
 
 /// Internal identifier for active sessions.
 #[derive(Debug, Clone, Copy, PartialOrd, PartialEq, Eq, Hash)]
@@ -71,16 +84,32 @@ pub struct SessionManager {
     /// If an [ActiveSession] does not receive a response at all within this duration then it is
     /// considered a protocol violation and the session will initiate a drop.
     protocol_breach_request_timeout: Duration,
+    /// The maximum time a pending session has to complete all handshakes.
+    pending_session_timeout: Duration,
+    /// Limits the rate at which incoming connections are accepted, if configured.
+    incoming_connections_rate: Option<TokenBucket>,
+    /// The SOCKS5 proxy that outgoing connections are tunneled through, if configured.
+    proxy: Option<Socks5Proxy>,
     /// The secret key used for authenticating sessions.
     secret_key: SecretKey,
     /// The `Status` message to send to peers.
     status: Status,
+    /// The range of blocks that is announced to `eth/69` peers.
+    block_range: BlockRangeUpdate,
+    /// The range of blocks that was last announced to the active `eth/69` peers.
+    announced_block_range: BlockRangeUpdate,
+    /// The prune mode of the receipts, which moves the earliest available block.
+    receipts_prune_mode: Option<PruneMode>,
     /// THe `HelloMessage` message to send to peers.
     hello_message: HelloMessageWithProtocols,
     /// The [`ForkFilter`] used to validate the peer's `Status` message.
     fork_filter: ForkFilter,
     /// Size of the command buffer per session.
     session_command_buffer: usize,
+    /// Outbound bandwidth limits enforced on every session.
+    bandwidth_limits: BandwidthLimits,
+    /// The outbound bandwidth limit shared by all sessions, if configured.
+    total_outbound_bandwidth: Option<SharedTokenBucket>,
     /// The executor for spawned tasks.
     executor: Box<dyn TaskSpawner>,
     /// All pending session that are currently handshaking, exchanging `Hello`s.
@@ -122,6 +151,8 @@ impl SessionManager {
         config: SessionsConfig,
         executor: Box<dyn TaskSpawner>,
         status: Status,
+        block_range: BlockRangeUpdate,
+        receipts_prune_mode: Option<PruneMode>,
         hello_message: HelloMessageWithProtocols,
         fork_filter: ForkFilter,
         extra_protocols: RlpxSubProtocols,
@@ -136,11 +167,24 @@ impl SessionManager {
             counter: SessionCounter::new(config.limits),
             initial_internal_request_timeout: config.initial_internal_request_timeout,
             protocol_breach_request_timeout: config.protocol_breach_request_timeout,
+            pending_session_timeout: config.pending_session_timeout,
+            incoming_connections_rate: config
+                .max_incoming_connections_per_sec
+                .map(TokenBucket::new),
+            proxy: config.proxy,
             secret_key,
             status,
+            block_range,
+            announced_block_range: block_range,
+            receipts_prune_mode,
             hello_message,
             fork_filter,
             session_command_buffer: config.session_command_buffer,
+            total_outbound_bandwidth: config
+                .bandwidth_limits
+                .max_outbound_bytes_total
+                .map(|rate| Arc::new(Mutex::new(TokenBucket::new(rate)))),
+            bandwidth_limits: config.bandwidth_limits,
             executor,
             pending_sessions: Default::default(),
             active_sessions: Default::default(),
@@ -200,14 +244,66 @@ impl SessionManager {
     ///
     /// If the updated activated another fork, this will return a [ForkTransition] and updates the
     /// active [ForkId]. See also [ForkFilter::set_head].
+    ///
+    /// The range of available blocks ends at the new head, and starts after the receipts that are
+    /// pruned at the new head. The new range is announced to all `eth/69` peers, if either end
+    /// moved by at least [BLOCK_RANGE_UPDATE_INTERVAL] blocks since the last announcement.
     pub(crate) fn on_status_update(&mut self, head: Head) -> Option<ForkTransition> {
         self.status.blockhash = head.hash;
         self.status.total_difficulty = head.total_difficulty;
         let transition = self.fork_filter.set_head(head);
         self.status.forkid = self.fork_filter.current();
+
+        let pruned = self
+            .receipts_prune_mode
+            .and_then(|mode| mode.prune_target_block(head.number, PruneSegment::Receipts).ok())
+            .flatten();
+        let mut earliest = self.block_range.earliest;
+        if let Some((pruned, _)) = pruned {
+            earliest = earliest.max(pruned + 1);
+        }
+        self.block_range = BlockRangeUpdate {
+            earliest: earliest.min(head.number),
+            latest: head.number,
+            latest_hash: head.hash,
+        };
+
+        let announced = self.announced_block_range;
+        if self.block_range.latest.abs_diff(announced.latest) >= BLOCK_RANGE_UPDATE_INTERVAL ||
+            self.block_range.earliest.abs_diff(announced.earliest) >= BLOCK_RANGE_UPDATE_INTERVAL
+        {
+            self.announce_block_range();
+        }
+
         transition
     }
 
+    /// Sends the current range of available blocks to all active `eth/69` sessions.
+    fn announce_block_range(&mut self) {
+        let block_range = self.block_range;
+        self.announced_block_range = block_range;
+        for session in self.active_sessions.values() {
+            if session.version >= EthVersion::Eth69 {
+                let msg = PeerMessage::BlockRangeUpdated(block_range);
+                let _ = session.commands_to_session.try_send(SessionCommand::Message(msg));
+            }
+        }
+    }
+
+    /// Returns true if another incoming connection can be accepted without exceeding the
+    /// configured rate of incoming connections.
+    ///
+    /// If this returns true, the connection is counted towards the rate.
+    pub(crate) fn try_admit_incoming_connection(&mut self) -> bool {
+        let Some(rate) = self.incoming_connections_rate.as_mut() else { return true };
+        if !rate.wait_time(Instant::now()).is_zero() {
+            self.metrics.total_rate_limited_incoming_connections.increment(1);
+            return false
+        }
+        rate.consume(1);
+        true
+    }
+
     /// An incoming TCP connection was received. This starts the authentication process to turn this
     /// stream into an active peer session.
     ///
@@ -234,6 +330,7 @@ impl SessionManager {
         let secret_key = self.secret_key;
         let hello_message = self.hello_message.clone();
         let status = self.status;
+        let block_range = self.block_range;
         let fork_filter = self.fork_filter.clone();
         let extra_handlers = self.extra_protocols.on_incoming(remote_addr);
         self.spawn(start_pending_incoming_session(
@@ -245,8 +342,10 @@ impl SessionManager {
             secret_key,
             hello_message,
             status,
+            block_range,
             fork_filter,
             extra_handlers,
+            self.pending_session_timeout,
         ));
 
         let handle = PendingSessionHandle {
@@ -269,8 +368,10 @@ impl SessionManager {
             let hello_message = self.hello_message.clone();
             let fork_filter = self.fork_filter.clone();
             let status = self.status;
+            let block_range = self.block_range;
             let band_with_meter = self.bandwidth_meter.clone();
             let extra_handlers = self.extra_protocols.on_outgoing(remote_addr, remote_peer_id);
+            let proxy = self.proxy.clone();
             self.spawn(start_pending_outbound_session(
                 disconnect_rx,
                 pending_events,
@@ -280,9 +381,12 @@ impl SessionManager {
                 secret_key,
                 hello_message,
                 status,
+                block_range,
                 fork_filter,
                 band_with_meter,
                 extra_handlers,
+                self.pending_session_timeout,
+                proxy,
             ));
 
             let handle = PendingSessionHandle {
@@ -485,6 +589,10 @@ impl SessionManager {
                     internal_request_timeout: Arc::clone(&timeout),
                     protocol_breach_request_timeout: self.protocol_breach_request_timeout,
                     terminate_message: None,
+                    outbound_throttle: OutboundThrottle::new(
+                        &self.bandwidth_limits,
+                        self.total_outbound_bandwidth.clone(),
+                    ),
                 };
 
                 self.spawn(session);
@@ -591,6 +699,30 @@ impl SessionManager {
                     }
                 }
             }
+            PendingSessionEvent::HandshakeTimeout { remote_addr, session_id, direction } => {
+                trace!(
+                    target: "net::session",
+                    ?session_id,
+                    ?remote_addr,
+                    "pending session timed out"
+                );
+                self.remove_pending_session(&session_id);
+                match direction {
+                    Direction::Incoming => {
+                        Poll::Ready(SessionEvent::IncomingPendingSessionClosed {
+                            remote_addr,
+                            error: Some(PendingSessionHandshakeError::Timeout),
+                        })
+                    }
+                    Direction::Outgoing(peer_id) => {
+                        Poll::Ready(SessionEvent::OutgoingPendingSessionClosed {
+                            remote_addr,
+                            peer_id,
+                            error: Some(PendingSessionHandshakeError::Timeout),
+                        })
+                    }
+                }
+            }
         }
     }
 
@@ -736,6 +868,8 @@ pub enum PendingSessionHandshakeError {
     Eth(EthStreamError),
     /// The pending session failed due to an error while establishing the ECIES stream
     Ecies(ECIESError),
+    /// The pending session did not complete the handshakes in time
+    Timeout,
 }
 
 impl PendingSessionHandshakeError {
@@ -767,8 +901,10 @@ pub(crate) async fn start_pending_incoming_session(
     secret_key: SecretKey,
     hello: HelloMessageWithProtocols,
     status: Status,
+    block_range: BlockRangeUpdate,
     fork_filter: ForkFilter,
     extra_handlers: RlpxSubProtocolHandlers,
+    timeout: Duration,
 ) {
     authenticate(
         disconnect_rx,
@@ -780,13 +916,17 @@ pub(crate) async fn start_pending_incoming_session(
         Direction::Incoming,
         hello,
         status,
+        block_range,
         fork_filter,
         extra_handlers,
+        timeout,
     )
     .await
 }
 
-/// Starts the authentication process for a connection initiated by a remote peer.
+/// Starts the authentication process for a connection initiated by the local node.
+///
+/// If a proxy is configured, the connection is tunneled through the proxy.
 #[instrument(skip_all, fields(%remote_addr, peer_id), target = "net")]
 #[allow(clippy::too_many_arguments)]
 async fn start_pending_outbound_session(
@@ -798,11 +938,18 @@ async fn start_pending_outbound_session(
     secret_key: SecretKey,
     hello: HelloMessageWithProtocols,
     status: Status,
+    block_range: BlockRangeUpdate,
     fork_filter: ForkFilter,
     bandwidth_meter: BandwidthMeter,
     extra_handlers: RlpxSubProtocolHandlers,
+    timeout: Duration,
+    proxy: Option<Socks5Proxy>,
 ) {
-    let stream = match TcpStream::connect(remote_addr).await {
+    let stream = match proxy {
+        Some(proxy) => proxy.connect(remote_addr).await,
+        None => TcpStream::connect(remote_addr).await,
+    };
+    let stream = match stream {
         Ok(stream) => {
             if let Err(err) = stream.set_nodelay(true) {
                 tracing::warn!(target: "net::session", "set nodelay failed: {:?}", err);
@@ -831,13 +978,17 @@ async fn start_pending_outbound_session(
         Direction::Outgoing(remote_peer_id),
         hello,
         status,
+        block_range,
         fork_filter,
         extra_handlers,
+        timeout,
     )
     .await
 }
 
 /// Authenticates a session
+///
+/// The session is dropped if the handshakes don't complete within the given timeout.
 #[allow(clippy::too_many_arguments)]
 async fn authenticate(
     disconnect_rx: oneshot::Receiver<()>,
@@ -849,39 +1000,50 @@ async fn authenticate(
     direction: Direction,
     hello: HelloMessageWithProtocols,
     status: Status,
+    block_range: BlockRangeUpdate,
     fork_filter: ForkFilter,
     extra_handlers: RlpxSubProtocolHandlers,
+    timeout: Duration,
 ) {
     let local_addr = stream.inner().local_addr().ok();
-    let stream = match get_eciess_stream(stream, secret_key, direction).await {
-        Ok(stream) => stream,
-        Err(error) => {
-            let _ = events
-                .send(PendingSessionEvent::EciesAuthError {
+    let auth = async move {
+        let stream = match get_eciess_stream(stream, secret_key, direction).await {
+            Ok(stream) => stream,
+            Err(error) => {
+                return PendingSessionEvent::EciesAuthError {
                     remote_addr,
                     session_id,
                     error,
                     direction,
-                })
-                .await;
-            return
-        }
-    };
+                }
+            }
+        };
 
-    let unauthed = UnauthedP2PStream::new(stream);
+        let unauthed = UnauthedP2PStream::new(stream);
 
-    let auth = authenticate_stream(
-        unauthed,
-        session_id,
-        remote_addr,
-        local_addr,
-        direction,
-        hello,
-        status,
-        fork_filter,
-        extra_handlers,
-    )
-    .boxed();
+        authenticate_stream(
+            unauthed,
+            session_id,
+            remote_addr,
+            local_addr,
+            direction,
+            hello,
+            status,
+            block_range,
+            fork_filter,
+            extra_handlers,
+        )
+        .await
+    };
+    let auth = tokio::time::timeout(timeout, auth)
+        .map(move |res| {
+            res.unwrap_or_else(|_| PendingSessionEvent::HandshakeTimeout {
+                remote_addr,
+                session_id,
+                direction,
+            })
+        })
+        .boxed();
 
     match futures::future::select(disconnect_rx, auth).await {
         Either::Left((_, _)) => {
@@ -930,6 +1092,7 @@ async fn authenticate_stream(
     direction: Direction,
     mut hello: HelloMessageWithProtocols,
     mut status: Status,
+    block_range: BlockRangeUpdate,
     fork_filter: ForkFilter,
     mut extra_handlers: RlpxSubProtocolHandlers,
 ) -> PendingSessionEvent {
@@ -962,28 +1125,29 @@ async fn authenticate_stream(
         }
     };
 
+    // Before trying status handshake, set up the version to negotiated shared version
+    status.set_eth_version(eth_version);
+
     let (conn, their_status) = if p2p_stream.shared_capabilities().len() == 1 {
         // if the hello handshake was successful we can try status handshake
-        //
-        // Before trying status handshake, set up the version to negotiated shared version
-        status.set_eth_version(eth_version);
         let eth_unauthed = UnauthedEthStream::new(p2p_stream);
-        let (eth_stream, their_status) = match eth_unauthed.handshake(status, fork_filter).await {
-            Ok(stream_res) => stream_res,
-            Err(err) => {
-                return PendingSessionEvent::Disconnected {
-                    remote_addr,
-                    session_id,
-                    direction,
-                    error: Some(err),
+        let (eth_stream, their_status) =
+            match eth_unauthed.handshake(status, block_range, fork_filter).await {
+                Ok(stream_res) => stream_res,
+                Err(err) => {
+                    return PendingSessionEvent::Disconnected {
+                        remote_addr,
+                        session_id,
+                        direction,
+                        error: Some(err),
+                    }
                 }
-            }
-        };
+            };
         (eth_stream.into(), their_status)
     } else {
         // Multiplex the stream with the extra protocols
         let (mut multiplex_stream, their_status) = RlpxProtocolMultiplexer::new(p2p_stream)
-            .into_eth_satellite_stream(status, fork_filter)
+            .into_eth_satellite_stream(status, block_range, fork_filter)
             .await
             .unwrap();
 
@@ -1013,3 +1177,51 @@ async fn authenticate_stream(
         client_id: their_hello.client_version,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_ecies::util::pk2id;
+    use reth_eth_wire::HelloMessage;
+    use reth_primitives::{B256, MAINNET, MINIMUM_PRUNING_DISTANCE};
+    use reth_tasks::TokioTaskExecutor;
+    use secp256k1::SECP256K1;
+
+    #[test]
+    fn update_block_range_on_status_update() {
+        let secret_key = SecretKey::new(&mut rand::thread_rng());
+        let hello = HelloMessage::builder(pk2id(&secret_key.public_key(SECP256K1))).build();
+        let head = Head { number: 20_000, hash: B256::random(), ..Default::default() };
+        let block_range =
+            BlockRangeUpdate { earliest: 10_000, latest: 20_000, latest_hash: head.hash };
+        let mut sessions = SessionManager::new(
+            secret_key,
+            Default::default(),
+            Box::<TokioTaskExecutor>::default(),
+            Status::spec_builder(&MAINNET, &head).build(),
+            block_range,
+            Some(PruneMode::Distance(MINIMUM_PRUNING_DISTANCE)),
+            hello,
+            MAINNET.fork_filter(head),
+            Default::default(),
+            Default::default(),
+        );
+
+        // the receipts are pruned up to block 9_937, the earliest block stays
+        let head = Head { number: 20_001, hash: B256::random(), ..head };
+        sessions.on_status_update(head);
+        assert_eq!(
+            sessions.block_range,
+            BlockRangeUpdate { earliest: 10_000, latest: 20_001, latest_hash: head.hash }
+        );
+        assert_eq!(sessions.announced_block_range, block_range);
+
+        // both ends move with the head, and the new range is announced
+        let head = Head { number: 20_100, hash: B256::random(), ..head };
+        sessions.on_status_update(head);
+        let block_range =
+            BlockRangeUpdate { earliest: 10_037, latest: 20_100, latest_hash: head.hash };
+        assert_eq!(sessions.block_range, block_range);
+        assert_eq!(sessions.announced_block_range, block_range);
+    }
+}
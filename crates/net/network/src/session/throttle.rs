@@ -0,0 +1,120 @@
+//! Outbound bandwidth throttling for active sessions.
+
+use parking_lot::Mutex;
+use reth_net_common::token_bucket::TokenBucket;
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+    time::{Duration, Instant},
+};
+use tokio::time::Sleep;
+
+/// Limits for the number of bytes per second we send to peers.
+///
+/// By default, no limits will be enforced.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct BandwidthLimits {
+    /// Maximum number of bytes per second sent to a single peer.
+    pub max_outbound_bytes_per_peer: Option<u64>,
+    /// Maximum number of bytes per second sent to all peers combined.
+    pub max_outbound_bytes_total: Option<u64>,
+}
+
+impl BandwidthLimits {
+    /// Sets the maximum number of bytes per second sent to a single peer.
+    pub fn with_max_outbound_bytes_per_peer(mut self, limit: u64) -> Self {
+        self.max_outbound_bytes_per_peer = Some(limit);
+        self
+    }
+
+    /// Sets the maximum number of bytes per second sent to all peers combined.
+    pub fn with_max_outbound_bytes_total(mut self, limit: u64) -> Self {
+        self.max_outbound_bytes_total = Some(limit);
+        self
+    }
+}
+
+/// A [`TokenBucket`] that is shared by all sessions.
+pub(crate) type SharedTokenBucket = Arc<Mutex<TokenBucket>>;
+
+/// Throttles the outbound traffic of a single session.
+#[derive(Debug, Default)]
+pub(crate) struct OutboundThrottle {
+    /// The limit for this session.
+    peer: Option<TokenBucket>,
+    /// The limit shared by all sessions.
+    total: Option<SharedTokenBucket>,
+    /// Delay until the buckets admit the next message.
+    delay: Option<Pin<Box<Sleep>>>,
+}
+
+impl OutboundThrottle {
+    /// Creates a new throttle for a session.
+    pub(crate) fn new(limits: &BandwidthLimits, total: Option<SharedTokenBucket>) -> Self {
+        Self { peer: limits.max_outbound_bytes_per_peer.map(TokenBucket::new), total, delay: None }
+    }
+
+    /// Returns true if no limits are enforced.
+    pub(crate) fn is_unlimited(&self) -> bool {
+        self.peer.is_none() && self.total.is_none()
+    }
+
+    /// Polls whether a message of the given size can be sent now.
+    ///
+    /// If this returns `Ready`, the size is consumed from all limits. Otherwise the current task is
+    /// woken up once the message can be sent.
+    pub(crate) fn poll_acquire(&mut self, cx: &mut Context<'_>, bytes: usize) -> Poll<()> {
+        loop {
+            if let Some(delay) = self.delay.as_mut() {
+                if delay.as_mut().poll(cx).is_pending() {
+                    return Poll::Pending
+                }
+                self.delay = None;
+            }
+
+            let now = Instant::now();
+            let mut wait = Duration::ZERO;
+            if let Some(peer) = self.peer.as_mut() {
+                wait = wait.max(peer.wait_time(now));
+            }
+            if let Some(total) = self.total.as_ref() {
+                wait = wait.max(total.lock().wait_time(now));
+            }
+
+            if wait.is_zero() {
+                break
+            }
+            self.delay = Some(Box::pin(tokio::time::sleep(wait)));
+        }
+
+        if let Some(peer) = self.peer.as_mut() {
+            peer.consume(bytes);
+        }
+        if let Some(total) = self.total.as_ref() {
+            total.lock().consume(bytes);
+        }
+        Poll::Ready(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn throttle_delays_messages() {
+        let limits = BandwidthLimits::default().with_max_outbound_bytes_per_peer(1000);
+        let mut throttle = OutboundThrottle::new(&limits, None);
+        assert!(!throttle.is_unlimited());
+
+        let start = Instant::now();
+        // puts the bucket 100 bytes into debt
+        std::future::poll_fn(|cx| throttle.poll_acquire(cx, 1100)).await;
+        std::future::poll_fn(|cx| throttle.poll_acquire(cx, 10)).await;
+        assert!(start.elapsed() >= Duration::from_millis(100));
+    }
+}
@@ -0,0 +1,633 @@
+//! Serving of the `snap` protocol on top of the p2p network.
+//!
+//! See also <https://github.com/ethereum/devp2p/blob/master/caps/snap.md>
+
+use crate::{
+    metrics::SnapRequestHandlerMetrics,
+    protocol::{ConnectionHandler, OnNotSupported, ProtocolHandler},
+};
+use alloy_rlp::Encodable;
+use futures::{stream::FuturesUnordered, Stream, StreamExt};
+use reth_eth_wire::{
+    capability::SharedCapabilities,
+    multiplex::ProtocolConnection,
+    protocol::Protocol,
+    snap::{
+        AccountData, AccountRange, ByteCodes, GetAccountRange, GetByteCodes, GetStorageRanges,
+        GetTrieNodes, SlimAccount, StorageData, StorageRanges, TrieNodes,
+    },
+    SnapProtocolMessage,
+};
+use reth_network_api::Direction;
+use reth_primitives::{BytesMut, PeerId, B256, KECCAK_EMPTY};
+use reth_provider::SnapStateReader;
+use std::{
+    future::Future,
+    net::SocketAddr,
+    pin::Pin,
+    task::{Context, Poll},
+};
+use tokio::{
+    sync::{
+        mpsc::{self, error::TrySendError, Receiver},
+        oneshot,
+    },
+    task::JoinHandle,
+};
+use tokio_stream::wrappers::ReceiverStream;
+use tracing::trace;
+
+// Limits: <https://github.com/ethereum/go-ethereum/blob/v1.13.5/eth/protocols/snap/handler.go#L34-L53>
+
+/// Maximum size of replies to data retrievals.
+const SOFT_RESPONSE_LIMIT: usize = 2 * 1024 * 1024;
+
+/// Maximum number of accounts to serve.
+///
+/// Used to limit lookups.
+const MAX_ACCOUNTS_SERVE: usize = 16 * 1024;
+
+/// Maximum number of storage slots to serve for a single account.
+///
+/// Used to limit lookups.
+const MAX_STORAGE_SLOTS_SERVE: usize = 16 * 1024;
+
+/// Maximum number of bytecodes to serve.
+///
+/// Used to limit lookups.
+const MAX_CODE_LOOKUPS: usize = 1024;
+
+/// Maximum number of `snap` requests that are served concurrently.
+///
+/// Serving a request is blocking database work, so requests are served on the blocking pool and
+/// not read from the channel while this many are in flight.
+const MAX_CONCURRENT_SNAP_REQUESTS: usize = 16;
+
+/// We set the max channel capacity of the [`SnapRequestHandler`] to 256, same as for the
+/// [`EthRequestHandler`](crate::eth_requests::EthRequestHandler).
+pub(crate) const SNAP_REQUEST_CHANNEL_CAPACITY: usize = 256;
+
+/// Manages `snap` requests on top of the p2p network.
+///
+/// Requests are only served for the state root of the latest persisted block, requests for any
+/// other root are answered with an empty response, as permitted by the protocol.
+///
+/// Note: reth does not store the RLP encoded trie nodes, hence `GetTrieNodes` requests are always
+/// answered with an empty response. Because of this the node does not enable the handler by
+/// default, see [`NetworkBuilder::snap_request_handler`](crate::NetworkBuilder).
+///
+/// This can be spawned to another task and is supposed to be run as background service.
+#[derive(Debug)]
+#[must_use = "Manager does nothing unless polled."]
+pub struct SnapRequestHandler<C> {
+    /// The client type that can interact with the chain.
+    client: C,
+    /// Incoming requests from the `snap` protocol connections.
+    incoming_requests: ReceiverStream<IncomingSnapRequest>,
+    /// Requests that are currently served on the blocking pool.
+    inflight_requests: FuturesUnordered<JoinHandle<()>>,
+    /// Metrics for the snap request handler.
+    metrics: SnapRequestHandlerMetrics,
+}
+
+// === impl SnapRequestHandler ===
+
+impl<C> SnapRequestHandler<C> {
+    /// Create a new instance
+    pub fn new(client: C, incoming: Receiver<IncomingSnapRequest>) -> Self {
+        let metrics = Default::default();
+        Self {
+            client,
+            incoming_requests: ReceiverStream::new(incoming),
+            inflight_requests: Default::default(),
+            metrics,
+        }
+    }
+}
+
+impl<C> SnapRequestHandler<C>
+where
+    C: SnapStateReader + Clone + 'static,
+{
+    /// Serves the request on the blocking pool, `GetTrieNodes` requests are answered right away.
+    fn on_request(&mut self, incoming: IncomingSnapRequest) {
+        let client = self.client.clone();
+        let task = match incoming {
+            IncomingSnapRequest::GetAccountRange { request, response, .. } => {
+                self.metrics.received_account_range_requests.increment(1);
+                tokio::task::spawn_blocking(move || {
+                    let _ = response.send(get_account_range_response(&client, &request));
+                })
+            }
+            IncomingSnapRequest::GetStorageRanges { request, response, .. } => {
+                self.metrics.received_storage_ranges_requests.increment(1);
+                tokio::task::spawn_blocking(move || {
+                    let _ = response.send(get_storage_ranges_response(&client, &request));
+                })
+            }
+            IncomingSnapRequest::GetByteCodes { request, response, .. } => {
+                self.metrics.received_byte_codes_requests.increment(1);
+                tokio::task::spawn_blocking(move || {
+                    let _ = response.send(get_byte_codes_response(&client, &request));
+                })
+            }
+            IncomingSnapRequest::GetTrieNodes { peer_id, request, response } => {
+                self.metrics.received_trie_nodes_requests.increment(1);
+                trace!(target: "net::snap", ?peer_id, "trie nodes are not served");
+                let _ =
+                    response.send(TrieNodes { request_id: request.request_id, nodes: Vec::new() });
+                return
+            }
+        };
+        self.inflight_requests.push(task);
+    }
+}
+
+/// An endless future.
+///
+/// This should be spawned or used as part of `tokio::select!`.
+impl<C> Future for SnapRequestHandler<C>
+where
+    C: SnapStateReader + Clone + Unpin + 'static,
+{
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+
+        loop {
+            // drain the served requests
+            while let Poll::Ready(Some(_)) = this.inflight_requests.poll_next_unpin(cx) {}
+
+            // apply backpressure to the connections until a served request finishes
+            if this.inflight_requests.len() >= MAX_CONCURRENT_SNAP_REQUESTS {
+                return Poll::Pending
+            }
+
+            match this.incoming_requests.poll_next_unpin(cx) {
+                Poll::Pending => return Poll::Pending,
+                Poll::Ready(None) => return Poll::Ready(()),
+                Poll::Ready(Some(incoming)) => this.on_request(incoming),
+            }
+        }
+    }
+}
+
+/// Returns true if the root is the state root we can serve.
+fn is_servable_root<C: SnapStateReader>(client: &C, root_hash: B256) -> bool {
+    client.snap_state_root().map(|root| root == root_hash).unwrap_or_default()
+}
+
+fn get_account_range_response<C: SnapStateReader>(
+    client: &C,
+    request: &GetAccountRange,
+) -> AccountRange {
+    // an empty response without a proof signals that the state is not available
+    let unavailable = AccountRange { request_id: request.request_id, ..Default::default() };
+    if !is_servable_root(client, request.root_hash) {
+        return unavailable
+    }
+
+    let limit = (request.response_bytes as usize).min(SOFT_RESPONSE_LIMIT);
+    let Ok(accounts) = client.hashed_account_range(request.starting_hash, MAX_ACCOUNTS_SERVE)
+    else {
+        return unavailable
+    };
+
+    let mut response = unavailable.clone();
+    let mut total_bytes = 0;
+    for (hash, account) in accounts {
+        // a partial range without a proof would be rejected by the peer
+        let Ok(storage_root) = client.hashed_storage_root(hash) else { return unavailable };
+        let account = AccountData { hash, body: SlimAccount::new(account, storage_root) };
+        total_bytes += account.length();
+        response.accounts.push(account);
+
+        // the first account after the limit is included to prove the end of the range
+        if hash >= request.limit_hash || total_bytes > limit {
+            break
+        }
+    }
+
+    let mut targets = vec![request.starting_hash];
+    if let Some(last) = response.accounts.last() {
+        targets.push(last.hash);
+    }
+    match client.hashed_account_multiproof(&targets) {
+        Ok(proof) => response.proof = proof,
+        Err(_) => return unavailable,
+    }
+
+    response
+}
+
+fn get_storage_ranges_response<C: SnapStateReader>(
+    client: &C,
+    request: &GetStorageRanges,
+) -> StorageRanges {
+    let mut response = StorageRanges { request_id: request.request_id, ..Default::default() };
+    if !is_servable_root(client, request.root_hash) {
+        return response
+    }
+
+    let limit = (request.response_bytes as usize).min(SOFT_RESPONSE_LIMIT);
+    let mut total_bytes = 0;
+
+    for (idx, hashed_address) in request.account_hashes.iter().copied().enumerate() {
+        if total_bytes > limit {
+            break
+        }
+
+        // the requested range only applies to the first account
+        let (start, end) = if idx == 0 {
+            (range_bound(&request.starting_hash), range_bound(&request.limit_hash))
+        } else {
+            (None, None)
+        };
+        let origin = start.unwrap_or_default();
+        let end = end.unwrap_or(B256::repeat_byte(0xff));
+
+        let Ok(storage) =
+            client.hashed_storage_range(hashed_address, origin, MAX_STORAGE_SLOTS_SERVE)
+        else {
+            break
+        };
+        let mut complete = storage.len() < MAX_STORAGE_SLOTS_SERVE;
+
+        let mut slots = Vec::new();
+        for (hash, value) in storage {
+            let slot = StorageData { hash, data: alloy_rlp::encode(value).into() };
+            total_bytes += slot.length();
+            slots.push(slot);
+
+            if hash >= end {
+                complete = true;
+                break
+            }
+            if total_bytes > limit {
+                complete = false;
+                break
+            }
+        }
+
+        // a proof is only required if the served range is partial
+        let partial = !complete || start.is_some();
+        let last = slots.last().map(|slot| slot.hash);
+        response.slots.push(slots);
+
+        if partial {
+            let mut targets = vec![origin];
+            targets.extend(last);
+            match client.hashed_storage_multiproof(hashed_address, &targets) {
+                Ok(proof) => response.proof = proof,
+                Err(_) => {
+                    response.slots.pop();
+                }
+            }
+            break
+        }
+    }
+
+    response
+}
+
+fn get_byte_codes_response<C: SnapStateReader>(client: &C, request: &GetByteCodes) -> ByteCodes {
+    let mut response = ByteCodes { request_id: request.request_id, ..Default::default() };
+    let limit = (request.response_bytes as usize).min(SOFT_RESPONSE_LIMIT);
+    let mut total_bytes = 0;
+    for hash in request.hashes.iter().take(MAX_CODE_LOOKUPS) {
+        if *hash == KECCAK_EMPTY {
+            // the empty code is not stored, but can be served trivially
+            response.codes.push(Default::default());
+            continue
+        }
+        if let Some(bytecode) = client.code_by_hash(*hash).unwrap_or_default() {
+            let code = bytecode.original_bytes();
+            total_bytes += code.len();
+            response.codes.push(code);
+
+            if total_bytes > limit {
+                break
+            }
+        }
+    }
+
+    response
+}
+
+/// Interprets an optional range bound of a [`GetStorageRanges`] request.
+///
+/// Returns `None` for an empty bound, shorter bounds are left aligned.
+fn range_bound(bound: &[u8]) -> Option<B256> {
+    if bound.is_empty() {
+        return None
+    }
+    let mut hash = B256::ZERO;
+    let len = bound.len().min(32);
+    hash[..len].copy_from_slice(&bound[..len]);
+    Some(hash)
+}
+
+/// All `snap` requests delegated by the protocol connections.
+#[derive(Debug)]
+pub enum IncomingSnapRequest {
+    /// Request a range of accounts.
+    ///
+    /// The response should be sent through the channel.
+    GetAccountRange {
+        /// The ID of the peer that sent the request.
+        peer_id: PeerId,
+        /// The requested account range.
+        request: GetAccountRange,
+        /// The channel sender for the response containing the accounts.
+        response: oneshot::Sender<AccountRange>,
+    },
+    /// Request ranges of storage slots.
+    ///
+    /// The response should be sent through the channel.
+    GetStorageRanges {
+        /// The ID of the peer that sent the request.
+        peer_id: PeerId,
+        /// The requested storage ranges.
+        request: GetStorageRanges,
+        /// The channel sender for the response containing the storage slots.
+        response: oneshot::Sender<StorageRanges>,
+    },
+    /// Request contract bytecodes.
+    ///
+    /// The response should be sent through the channel.
+    GetByteCodes {
+        /// The ID of the peer that sent the request.
+        peer_id: PeerId,
+        /// The requested code hashes.
+        request: GetByteCodes,
+        /// The channel sender for the response containing the bytecodes.
+        response: oneshot::Sender<ByteCodes>,
+    },
+    /// Request trie nodes.
+    ///
+    /// The response should be sent through the channel.
+    GetTrieNodes {
+        /// The ID of the peer that sent the request.
+        peer_id: PeerId,
+        /// The requested trie node paths.
+        request: GetTrieNodes,
+        /// The channel sender for the response containing the trie nodes.
+        response: oneshot::Sender<TrieNodes>,
+    },
+}
+
+/// The [`ProtocolHandler`] that announces the `snap/1` capability and forwards all requests to the
+/// [`SnapRequestHandler`].
+#[derive(Debug, Clone)]
+pub struct SnapProtocolHandler {
+    to_request_handler: mpsc::Sender<IncomingSnapRequest>,
+}
+
+impl SnapProtocolHandler {
+    /// Creates a new protocol handler that forwards all requests to the given channel.
+    pub fn new(to_request_handler: mpsc::Sender<IncomingSnapRequest>) -> Self {
+        Self { to_request_handler }
+    }
+}
+
+impl ProtocolHandler for SnapProtocolHandler {
+    type ConnectionHandler = SnapConnectionHandler;
+
+    fn on_incoming(&self, _socket_addr: SocketAddr) -> Option<Self::ConnectionHandler> {
+        Some(SnapConnectionHandler { to_request_handler: self.to_request_handler.clone() })
+    }
+
+    fn on_outgoing(
+        &self,
+        _socket_addr: SocketAddr,
+        _peer_id: PeerId,
+    ) -> Option<Self::ConnectionHandler> {
+        Some(SnapConnectionHandler { to_request_handler: self.to_request_handler.clone() })
+    }
+}
+
+/// The [`ConnectionHandler`] for a single `snap` connection.
+#[derive(Debug)]
+pub struct SnapConnectionHandler {
+    to_request_handler: mpsc::Sender<IncomingSnapRequest>,
+}
+
+impl ConnectionHandler for SnapConnectionHandler {
+    type Connection = SnapConnection;
+
+    fn protocol(&self) -> Protocol {
+        SnapProtocolMessage::protocol()
+    }
+
+    fn on_unsupported_by_peer(
+        self,
+        _supported: &SharedCapabilities,
+        _direction: Direction,
+        _peer_id: PeerId,
+    ) -> OnNotSupported {
+        OnNotSupported::KeepAlive
+    }
+
+    fn into_connection(
+        self,
+        _direction: Direction,
+        peer_id: PeerId,
+        conn: ProtocolConnection,
+    ) -> Self::Connection {
+        SnapConnection {
+            peer_id,
+            conn,
+            to_request_handler: self.to_request_handler,
+            pending_responses: Default::default(),
+        }
+    }
+}
+
+/// A pending response to a `snap` request.
+type PendingSnapResponse =
+    Pin<Box<dyn Future<Output = Result<SnapProtocolMessage, oneshot::error::RecvError>> + Send>>;
+
+/// A `snap` connection to a single peer.
+///
+/// Decodes incoming requests, delegates them to the [`SnapRequestHandler`] and yields the encoded
+/// responses.
+#[must_use = "Streams do nothing unless polled."]
+pub struct SnapConnection {
+    /// The remote peer.
+    peer_id: PeerId,
+    /// The connection to the remote peer.
+    conn: ProtocolConnection,
+    /// Sender half for requests to the [`SnapRequestHandler`].
+    to_request_handler: mpsc::Sender<IncomingSnapRequest>,
+    /// Responses that are still being processed by the [`SnapRequestHandler`].
+    pending_responses: FuturesUnordered<PendingSnapResponse>,
+}
+
+impl SnapConnection {
+    /// Delegates the request to the [`SnapRequestHandler`].
+    ///
+    /// Returns false if the message is not a request.
+    fn on_request(&mut self, msg: SnapProtocolMessage) -> bool {
+        let peer_id = self.peer_id;
+        let (request, rx): (_, PendingSnapResponse) = match msg {
+            SnapProtocolMessage::GetAccountRange(request) => {
+                let (response, rx) = oneshot::channel();
+                (
+                    IncomingSnapRequest::GetAccountRange { peer_id, request, response },
+                    Box::pin(async move { rx.await.map(SnapProtocolMessage::AccountRange) }),
+                )
+            }
+            SnapProtocolMessage::GetStorageRanges(request) => {
+                let (response, rx) = oneshot::channel();
+                (
+                    IncomingSnapRequest::GetStorageRanges { peer_id, request, response },
+                    Box::pin(async move { rx.await.map(SnapProtocolMessage::StorageRanges) }),
+                )
+            }
+            SnapProtocolMessage::GetByteCodes(request) => {
+                let (response, rx) = oneshot::channel();
+                (
+                    IncomingSnapRequest::GetByteCodes { peer_id, request, response },
+                    Box::pin(async move { rx.await.map(SnapProtocolMessage::ByteCodes) }),
+                )
+            }
+            SnapProtocolMessage::GetTrieNodes(request) => {
+                let (response, rx) = oneshot::channel();
+                (
+                    IncomingSnapRequest::GetTrieNodes { peer_id, request, response },
+                    Box::pin(async move { rx.await.map(SnapProtocolMessage::TrieNodes) }),
+                )
+            }
+            _ => return false,
+        };
+
+        match self.to_request_handler.try_send(request) {
+            Ok(()) => self.pending_responses.push(rx),
+            Err(TrySendError::Full(_)) => {
+                // the handler is busy, the peer will time out the request
+                trace!(target: "net::snap", peer_id=?self.peer_id, "snap request handler is full, dropping request");
+            }
+            Err(TrySendError::Closed(_)) => {}
+        }
+        true
+    }
+}
+
+impl Stream for SnapConnection {
+    type Item = BytesMut;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+
+        loop {
+            if let Poll::Ready(Some(res)) = this.pending_responses.poll_next_unpin(cx) {
+                if let Ok(response) = res {
+                    return Poll::Ready(Some(response.encoded()))
+                }
+                continue
+            }
+
+            let Some(msg) = futures::ready!(this.conn.poll_next_unpin(cx)) else {
+                // the connection was closed
+                return Poll::Ready(None)
+            };
+
+            let Ok(msg) = SnapProtocolMessage::decode_message(&mut &msg[..]) else {
+                trace!(target: "net::snap", peer_id=?this.peer_id, "failed to decode snap message, disconnecting");
+                return Poll::Ready(None)
+            };
+
+            if !this.on_request(msg) {
+                // we only serve requests, responses are unexpected
+                trace!(target: "net::snap", peer_id=?this.peer_id, "received unexpected snap response");
+            }
+        }
+    }
+}
+
+impl std::fmt::Debug for SnapConnection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SnapConnection")
+            .field("peer_id", &self.peer_id)
+            .field("pending_responses", &self.pending_responses.len())
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_interfaces::provider::{ProviderError, ProviderResult};
+    use reth_primitives::{Account, Bytecode, Bytes, U256};
+
+    /// Serves a single account whose storage root can not be computed.
+    #[derive(Debug, Clone)]
+    struct BrokenStorageRoot;
+
+    impl SnapStateReader for BrokenStorageRoot {
+        fn snap_state_root(&self) -> ProviderResult<B256> {
+            Ok(B256::ZERO)
+        }
+
+        fn hashed_account_range(
+            &self,
+            _start: B256,
+            _limit: usize,
+        ) -> ProviderResult<Vec<(B256, Account)>> {
+            Ok(vec![(B256::repeat_byte(0x01), Account::default())])
+        }
+
+        fn hashed_storage_range(
+            &self,
+            _hashed_address: B256,
+            _start: B256,
+            _limit: usize,
+        ) -> ProviderResult<Vec<(B256, U256)>> {
+            Ok(Vec::new())
+        }
+
+        fn hashed_storage_root(&self, _hashed_address: B256) -> ProviderResult<B256> {
+            Err(ProviderError::StateRootNotAvailableForHistoricalBlock)
+        }
+
+        fn code_by_hash(&self, _code_hash: B256) -> ProviderResult<Option<Bytecode>> {
+            Ok(None)
+        }
+
+        fn hashed_account_multiproof(&self, _targets: &[B256]) -> ProviderResult<Vec<Bytes>> {
+            Ok(vec![Bytes::from_static(&[0x80])])
+        }
+
+        fn hashed_storage_multiproof(
+            &self,
+            _hashed_address: B256,
+            _targets: &[B256],
+        ) -> ProviderResult<Vec<Bytes>> {
+            Ok(Vec::new())
+        }
+    }
+
+    #[test]
+    fn account_range_unavailable_on_state_error() {
+        let request = GetAccountRange {
+            request_id: 1,
+            root_hash: B256::ZERO,
+            starting_hash: B256::ZERO,
+            limit_hash: B256::repeat_byte(0xff),
+            response_bytes: SOFT_RESPONSE_LIMIT as u64,
+        };
+        let response = get_account_range_response(&BrokenStorageRoot, &request);
+        assert_eq!(response, AccountRange { request_id: 1, ..Default::default() });
+    }
+
+    #[test]
+    fn storage_range_bounds() {
+        assert_eq!(range_bound(&[]), None);
+        assert_eq!(range_bound(&[0xff; 32]), Some(B256::repeat_byte(0xff)));
+
+        let mut expected = B256::ZERO;
+        expected[0] = 0x01;
+        assert_eq!(range_bound(&[0x01]), Some(expected));
+    }
+}
@@ -3,7 +3,7 @@
 use crate::{
     cache::LruCache,
     discovery::{Discovery, DiscoveryEvent},
-    fetch::{BlockResponseOutcome, FetchAction, StateFetcher},
+    fetch::{BlockResponseOutcome, FetchAction, PeerSelection, StateFetcher},
     manager::DiscoveredEvent,
     message::{
         BlockRequest, NewBlockMessage, PeerRequest, PeerRequestSender, PeerResponse,
@@ -81,8 +81,10 @@ where
         peers_manager: PeersManager,
         genesis_hash: B256,
         num_active_peers: Arc<AtomicUsize>,
+        peer_selection: PeerSelection,
     ) -> Self {
-        let state_fetcher = StateFetcher::new(peers_manager.handle(), num_active_peers);
+        let state_fetcher = StateFetcher::new(peers_manager.handle(), num_active_peers)
+            .with_peer_selection(peer_selection);
         Self {
             active_peers: Default::default(),
             peers_manager,
@@ -306,6 +308,8 @@ where
                 self.queued_messages
                     .push_back(StateAction::DiscoveredEnrForkId { peer_id, fork_id });
             }
+            // only the fork id of the ENR is relevant to the peers
+            DiscoveryEvent::Enr(..) => {}
         }
     }
 
@@ -147,7 +147,7 @@ where
             }
             SessionEvent::AlreadyConnected { peer_id, remote_addr, direction } => {
                 trace!( target: "net", ?peer_id, ?remote_addr, ?direction, "already connected");
-                self.state.peers_mut().on_already_connected(direction);
+                self.state.peers_mut().on_already_connected(direction, remote_addr);
                 None
             }
             SessionEvent::ValidMessage { peer_id, message } => {
@@ -194,6 +194,11 @@ where
                 if self.is_shutting_down() {
                     return None
                 }
+                // drop the connection if we accept incoming connections too fast
+                if !self.sessions.try_admit_incoming_connection() {
+                    trace!(target: "net", ?remote_addr, "Exceeded incoming connection rate limit");
+                    return None
+                }
                 // ensure we can handle an incoming connection from this address
                 if let Err(err) =
                     self.state_mut().peers_mut().on_incoming_pending_session(remote_addr.ip())
@@ -209,6 +214,14 @@ where
                                 DisconnectReason::TooManyPeers,
                             );
                         }
+                        InboundConnectionError::ExceedsIpLimit(limit) |
+                        InboundConnectionError::ExceedsSubnetLimit(limit) => {
+                            trace!(target: "net", %limit, ?remote_addr, ?err, "Exceeded incoming connection limit of the address; disconnecting");
+                            self.sessions.disconnect_incoming_connection(
+                                stream,
+                                DisconnectReason::TooManyPeers,
+                            );
+                        }
                     }
                     return None
                 }
@@ -222,7 +235,7 @@ where
                         trace!(target: "net", ?err, "Incoming connection rejected, capacity already reached.");
                         self.state_mut()
                             .peers_mut()
-                            .on_incoming_pending_session_rejected_internally();
+                            .on_incoming_pending_session_rejected_internally(remote_addr.ip());
                     }
                 }
             }
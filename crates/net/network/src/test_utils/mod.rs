@@ -7,4 +7,4 @@ pub use init::{
     enr_to_peer_id, unused_port, unused_tcp_addr, unused_tcp_and_udp_port, unused_tcp_udp,
     unused_udp_addr, unused_udp_port, GETH_TIMEOUT,
 };
-pub use testnet::{NetworkEventStream, Peer, PeerConfig, Testnet};
+pub use testnet::{NetworkEventStream, Peer, PeerConfig, Testnet, TestnetHandle, Topology};
@@ -13,7 +13,7 @@ use futures::{FutureExt, StreamExt};
 use pin_project::pin_project;
 use reth_eth_wire::{protocol::Protocol, DisconnectReason, HelloMessageWithProtocols};
 use reth_network_api::{NetworkInfo, Peers};
-use reth_primitives::{PeerId, MAINNET};
+use reth_primitives::{PeerId, TxHash, MAINNET};
 use reth_provider::{
     test_utils::NoopProvider, BlockReader, BlockReaderIdExt, HeaderProvider, StateProviderFactory,
 };
@@ -25,11 +25,13 @@ use reth_transaction_pool::{
 };
 use secp256k1::SecretKey;
 use std::{
+    collections::BTreeSet,
     fmt,
     future::Future,
     net::{Ipv4Addr, SocketAddr, SocketAddrV4},
     pin::Pin,
     task::{Context, Poll},
+    time::Duration,
 };
 use tokio::{
     sync::{
@@ -290,28 +292,160 @@ impl<C, Pool> TestnetHandle<C, Pool> {
 
     /// Connects all peers with each other
     pub async fn connect_peers(&self) {
-        if self.peers.len() < 2 {
-            return
-        }
+        self.connect_topology(Topology::Full).await
+    }
 
-        let mut streams = Vec::with_capacity(self.peers.len());
-        let mut num_sessions = Vec::with_capacity(self.peers.len());
-        for (idx, handle) in self.peers.iter().enumerate().take(self.peers.len() - 1) {
-            streams.push(NetworkEventStream::new(handle.event_listener()));
-            let mut num = 0;
-            for idx in (idx + 1)..self.peers.len() {
-                let neighbour = &self.peers[idx];
-                handle.network.add_peer(*neighbour.peer_id(), neighbour.local_addr());
-                num += 1;
-            }
-            num_sessions.push(num);
+    /// Connects the peers according to the given [`Topology`].
+    ///
+    /// Discovery is disabled on the peers of the [`Testnet`], so instead the lower indexed peer of
+    /// each connection is told about the other one, as if it had discovered it. Waits until all
+    /// sessions are established on both ends.
+    ///
+    /// # Panics
+    /// If the topology refers to a peer that doesn't exist.
+    pub async fn connect_topology(&self, topology: Topology) {
+        let edges = topology.edges(self.peers.len());
+
+        // the listeners are created before connecting, so no established session is missed
+        let mut num_sessions = vec![0; self.peers.len()];
+        for &(a, b) in &edges {
+            num_sessions[a] += 1;
+            num_sessions[b] += 1;
         }
+        let streams = self
+            .peers
+            .iter()
+            .map(|handle| NetworkEventStream::new(handle.event_listener()))
+            .collect::<Vec<_>>();
+
+        for (a, b) in edges {
+            let neighbour = &self.peers[b];
+            self.peers[a].network.add_peer(*neighbour.peer_id(), neighbour.local_addr());
+        }
+
         let fut = streams
             .into_iter()
             .zip(num_sessions)
             .map(|(mut stream, num)| async move { stream.take_session_established(num).await });
         futures::future::join_all(fut).await;
     }
+
+    /// Connects the two peers at the given indices and waits until the session is established on
+    /// both ends.
+    pub async fn connect(&self, a: usize, b: usize) {
+        self.connect_topology(Topology::Custom(vec![(a, b)])).await
+    }
+
+    /// Disconnects the two peers at the given indices and waits until the session is closed on
+    /// both ends.
+    pub async fn disconnect(&self, a: usize, b: usize) {
+        let (handle_a, handle_b) = (&self.peers[a], &self.peers[b]);
+        let mut stream_a = NetworkEventStream::new(handle_a.event_listener());
+        let mut stream_b = NetworkEventStream::new(handle_b.event_listener());
+        handle_a.network.disconnect_peer(*handle_b.peer_id());
+        futures::future::join(
+            stream_a.session_closed(handle_b.peer_id()),
+            stream_b.session_closed(handle_a.peer_id()),
+        )
+        .await;
+    }
+}
+
+impl<C, Pool> TestnetHandle<C, Pool>
+where
+    Pool: TransactionPool,
+{
+    /// Waits until the transaction with the given hash is in the pool of every peer that has one,
+    /// e.g. after it was inserted into one of the pools and gossiped to the others.
+    ///
+    /// Returns the indices of the peers that didn't receive the transaction within the given
+    /// timeout.
+    pub async fn wait_for_transaction(&self, hash: TxHash, timeout: Duration) -> Vec<usize> {
+        let fut = self.peers.iter().enumerate().map(|(idx, peer)| async move {
+            let received = match peer.pool() {
+                Some(pool) => {
+                    tokio::time::timeout(timeout, pool_contains(pool, hash)).await.is_ok()
+                }
+                None => true,
+            };
+            (!received).then_some(idx)
+        });
+        futures::future::join_all(fut).await.into_iter().flatten().collect()
+    }
+
+    /// Asserts that the transaction with the given hash reaches the pool of every peer that has
+    /// one within the given timeout.
+    ///
+    /// # Panics
+    /// If a peer didn't receive the transaction in time.
+    pub async fn assert_transaction_propagated(&self, hash: TxHash, timeout: Duration) {
+        let missing = self.wait_for_transaction(hash, timeout).await;
+        assert!(missing.is_empty(), "transaction {hash} was not propagated to peers {missing:?}");
+    }
+}
+
+/// Resolves once the transaction with the given hash is in the pool.
+async fn pool_contains<Pool: TransactionPool>(pool: &Pool, hash: TxHash) {
+    // subscribe first, so a transaction inserted in the meantime isn't missed
+    let mut listener = pool.new_transactions_listener();
+    if pool.contains(&hash) {
+        return
+    }
+    while let Some(event) = listener.recv().await {
+        if *event.transaction.hash() == hash {
+            return
+        }
+    }
+    // the pool was dropped
+    std::future::pending::<()>().await
+}
+
+/// The connections between the peers of a [`Testnet`], see [`TestnetHandle::connect_topology`].
+///
+/// Peers are referred to by their index in the [`Testnet`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Topology {
+    /// Every peer is connected to every other peer.
+    Full,
+    /// Every peer is connected to its successor, and the last peer to the first one.
+    Ring,
+    /// Every peer is connected to its successor.
+    Line,
+    /// Every peer is connected to the peer at the given index, and only to it.
+    Star(usize),
+    /// The given connections, in any order.
+    Custom(Vec<(usize, usize)>),
+}
+
+impl Topology {
+    /// Returns the distinct connections of the topology for the given number of peers, the lower
+    /// index first.
+    ///
+    /// # Panics
+    /// If a connection refers to a peer that doesn't exist, or connects a peer to itself.
+    pub fn edges(&self, num_peers: usize) -> Vec<(usize, usize)> {
+        let edges: Vec<(usize, usize)> = match self {
+            Topology::Full => {
+                (0..num_peers).flat_map(|a| ((a + 1)..num_peers).map(move |b| (a, b))).collect()
+            }
+            Topology::Ring if num_peers > 2 => {
+                (0..num_peers).map(|a| (a, (a + 1) % num_peers)).collect()
+            }
+            Topology::Ring | Topology::Line => (1..num_peers).map(|b| (b - 1, b)).collect(),
+            Topology::Star(center) => {
+                (0..num_peers).filter(|peer| peer != center).map(|peer| (*center, peer)).collect()
+            }
+            Topology::Custom(edges) => edges.clone(),
+        };
+
+        let mut distinct = BTreeSet::new();
+        for (a, b) in edges {
+            assert!(a < num_peers && b < num_peers, "peer out of bounds: ({a}, {b})");
+            assert_ne!(a, b, "peer {a} can't connect to itself");
+            distinct.insert((a.min(b), a.max(b)));
+        }
+        distinct.into_iter().collect()
+    }
 }
 
 /// A peer in the [`Testnet`].
@@ -590,6 +724,17 @@ impl NetworkEventStream {
         None
     }
 
+    /// Awaits the event for the closed session with the given peer, returning the disconnect
+    /// reason.
+    pub async fn session_closed(&mut self, peer_id: &PeerId) -> Option<Option<DisconnectReason>> {
+        while let Some((closed, reason)) = self.next_session_closed().await {
+            if closed == *peer_id {
+                return Some(reason)
+            }
+        }
+        None
+    }
+
     /// Awaits the next event for an established session
     pub async fn next_session_established(&mut self) -> Option<PeerId> {
         while let Some(ev) = self.inner.next().await {
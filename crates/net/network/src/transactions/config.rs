@@ -0,0 +1,61 @@
+//! Configuration of the [`TransactionsManager`](super::TransactionsManager).
+
+use std::{num::NonZeroUsize, time::Duration};
+
+/// Default number of transactions to keep track of for a single peer.
+pub const DEFAULT_MAX_SEEN_TRANSACTIONS_PER_PEER: usize = 1024 * 10;
+
+/// Default number of recently rejected transactions that are remembered.
+pub const DEFAULT_MAX_REJECTED_TRANSACTIONS: u32 = 1024 * 32;
+
+/// Default duration for which a rejected transaction is remembered.
+pub const DEFAULT_REJECTED_TRANSACTIONS_TTL: Duration = Duration::from_secs(5 * 60);
+
+/// Configuration of the [`TransactionsManager`](super::TransactionsManager).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct TransactionsManagerConfig {
+    /// Maximum number of transaction hashes that are remembered as seen by a single peer.
+    ///
+    /// Transactions that a peer has seen, because it announced them or they were sent to it, are
+    /// not announced to the peer again. Once the limit is reached, the oldest hashes are evicted.
+    /// A larger limit avoids more duplicate announcements, at the cost of memory per peer.
+    pub max_seen_transactions_per_peer: NonZeroUsize,
+    /// Maximum number of recently rejected transactions that are remembered.
+    ///
+    /// Transactions that were rejected by the pool or the gossip validator are neither requested
+    /// nor validated again while they're remembered.
+    pub max_rejected_transactions: u32,
+    /// How long a rejected transaction is remembered.
+    #[cfg_attr(feature = "serde", serde(with = "humantime_serde"))]
+    pub rejected_transactions_ttl: Duration,
+}
+
+impl TransactionsManagerConfig {
+    /// Sets the maximum number of transaction hashes that are remembered as seen by a peer.
+    pub fn with_max_seen_transactions_per_peer(mut self, limit: NonZeroUsize) -> Self {
+        self.max_seen_transactions_per_peer = limit;
+        self
+    }
+
+    /// Sets how many rejected transactions are remembered, and for how long.
+    pub fn with_rejected_transactions(mut self, limit: u32, ttl: Duration) -> Self {
+        self.max_rejected_transactions = limit;
+        self.rejected_transactions_ttl = ttl;
+        self
+    }
+}
+
+impl Default for TransactionsManagerConfig {
+    fn default() -> Self {
+        Self {
+            max_seen_transactions_per_peer: NonZeroUsize::new(
+                DEFAULT_MAX_SEEN_TRANSACTIONS_PER_PEER,
+            )
+            .expect("is non zero"),
+            max_rejected_transactions: DEFAULT_MAX_REJECTED_TRANSACTIONS,
+            rejected_transactions_ttl: DEFAULT_REJECTED_TRANSACTIONS_TTL,
+        }
+    }
+}
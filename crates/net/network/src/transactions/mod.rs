@@ -23,22 +23,40 @@ use reth_primitives::{
     B256,
 };
 use reth_transaction_pool::{
-    error::PoolResult, GetPooledTransactionLimit, PoolTransaction, PropagateKind,
-    PropagatedTransactions, TransactionPool, ValidPoolTransaction,
+    error::{PoolErrorKind, PoolResult},
+    GetPooledTransactionLimit, PoolTransaction, PropagateKind, PropagatedTransactions,
+    TransactionPool, ValidPoolTransaction,
 };
 use std::{
-    collections::{hash_map::Entry, HashMap, HashSet},
-    num::NonZeroUsize,
+    collections::{hash_map::Entry, HashMap, HashSet, VecDeque},
     pin::Pin,
     sync::Arc,
     task::{Context, Poll},
 };
-use tokio::sync::{mpsc, mpsc::error::TrySendError, oneshot, oneshot::error::RecvError};
+use tokio::sync::{mpsc, oneshot, oneshot::error::RecvError};
 use tokio_stream::wrappers::{ReceiverStream, UnboundedReceiverStream};
 use tracing::{debug, trace};
 
-/// Cache limit of transactions to keep track of for a single peer.
-const PEER_TRANSACTION_CACHE_LIMIT: usize = 1024 * 10;
+mod config;
+mod policy;
+mod rejected;
+mod validation;
+
+pub use config::{
+    TransactionsManagerConfig, DEFAULT_MAX_REJECTED_TRANSACTIONS,
+    DEFAULT_MAX_SEEN_TRANSACTIONS_PER_PEER, DEFAULT_REJECTED_TRANSACTIONS_TTL,
+};
+pub use policy::{
+    HashesOnlyPropagation, NoPropagation, PropagationMode, PropagationPeer,
+    SqrtPeersFullPropagation, TransactionPropagationPolicy,
+};
+use rejected::RejectedTransactions;
+pub use validation::{
+    AcceptAllGossip, GossipPeer, GossipValidationOutcome, TransactionGossipValidator,
+};
+
+/// Approximate size in bytes of a single entry in the cache of transactions seen by a peer.
+const APPROX_SEEN_TRANSACTION_ENTRY_SIZE: usize = 64;
 
 /// Soft limit for NewPooledTransactions
 const NEW_POOLED_TRANSACTION_HASHES_SOFT_LIMIT: usize = 4096;
@@ -51,13 +69,23 @@ const MAX_FULL_TRANSACTIONS_PACKET_SIZE: usize = 100 * 1024;
 /// <https://github.com/ethereum/devp2p/blob/master/caps/eth.md#newpooledtransactionhashes-0x08>
 const GET_POOLED_TRANSACTION_SOFT_LIMIT_NUM_HASHES: usize = 256;
 
+/// Softlimit for the response size of a GetPooledTransactions message (2MB)
+const GET_POOLED_TRANSACTION_SOFT_LIMIT_RESPONSE_SIZE: usize = 2 * 1024 * 1024;
+
 /// Softlimit for the response size of a GetPooledTransactions message (2MB)
 const GET_POOLED_TRANSACTION_SOFT_LIMIT_SIZE: GetPooledTransactionLimit =
-    GetPooledTransactionLimit::SizeSoftLimit(2 * 1024 * 1024);
+    GetPooledTransactionLimit::SizeSoftLimit(GET_POOLED_TRANSACTION_SOFT_LIMIT_RESPONSE_SIZE);
 
 /// How many peers we keep track of for each missing transaction.
 const MAX_ALTERNATIVE_PEERS_PER_TX: usize = 3;
 
+/// The maximum number of announced hashes that are buffered for a single peer until they're
+/// requested from it.
+const MAX_BUFFERED_HASHES_PER_PEER: usize = 4096;
+
+/// The number of pending pool imports at which no more transactions are requested from peers.
+const MAX_PENDING_POOL_IMPORTS: usize = 4096;
+
 /// The future for inserting a function into the pool
 pub type PoolImportFuture = Pin<Box<dyn Future<Output = PoolResult<TxHash>> + Send + 'static>>;
 
@@ -187,6 +215,8 @@ pub struct TransactionsManager<Pool> {
     transactions_by_peers: HashMap<TxHash, Vec<PeerId>>,
     /// Transactions that are currently imported into the `Pool`
     pool_imports: FuturesUnordered<PoolImportFuture>,
+    /// Transactions that were recently rejected, these are neither fetched nor imported again.
+    rejected_transactions: RejectedTransactions,
     /// All the connected peers.
     peers: HashMap<PeerId, Peer>,
     /// Send half for the command channel.
@@ -197,18 +227,51 @@ pub struct TransactionsManager<Pool> {
     pending_transactions: ReceiverStream<TxHash>,
     /// Incoming events from the [`NetworkManager`](crate::NetworkManager).
     transaction_events: UnboundedMeteredReceiver<NetworkTransactionEvent>,
+    /// Decides how transactions are propagated to peers.
+    propagation_policy: Box<dyn TransactionPropagationPolicy>,
+    /// Validates transactions received from peers before they're imported into the pool.
+    gossip_validator: Box<dyn TransactionGossipValidator>,
+    /// Configuration of the manager.
+    config: TransactionsManagerConfig,
     /// TransactionsManager metrics
     metrics: TransactionsManagerMetrics,
 }
 
 impl<Pool: TransactionPool> TransactionsManager<Pool> {
-    /// Sets up a new instance.
+    /// Sets up a new instance that propagates transactions according to the default
+    /// [`SqrtPeersFullPropagation`] policy.
     ///
     /// Note: This expects an existing [`NetworkManager`](crate::NetworkManager) instance.
     pub fn new(
         network: NetworkHandle,
         pool: Pool,
         from_network: mpsc::UnboundedReceiver<NetworkTransactionEvent>,
+    ) -> Self {
+        Self::with_policy(network, pool, from_network, SqrtPeersFullPropagation)
+    }
+
+    /// Sets up a new instance with the given [`TransactionPropagationPolicy`].
+    ///
+    /// Note: This expects an existing [`NetworkManager`](crate::NetworkManager) instance.
+    pub fn with_policy(
+        network: NetworkHandle,
+        pool: Pool,
+        from_network: mpsc::UnboundedReceiver<NetworkTransactionEvent>,
+        propagation_policy: impl TransactionPropagationPolicy,
+    ) -> Self {
+        Self::with_config(network, pool, from_network, propagation_policy, Default::default())
+    }
+
+    /// Sets up a new instance with the given [`TransactionPropagationPolicy`] and
+    /// [`TransactionsManagerConfig`].
+    ///
+    /// Note: This expects an existing [`NetworkManager`](crate::NetworkManager) instance.
+    pub fn with_config(
+        network: NetworkHandle,
+        pool: Pool,
+        from_network: mpsc::UnboundedReceiver<NetworkTransactionEvent>,
+        propagation_policy: impl TransactionPropagationPolicy,
+        config: TransactionsManagerConfig,
     ) -> Self {
         let network_events = network.event_listener();
         let (command_tx, command_rx) = mpsc::unbounded_channel();
@@ -224,6 +287,10 @@ impl<Pool: TransactionPool> TransactionsManager<Pool> {
             transaction_fetcher: Default::default(),
             transactions_by_peers: Default::default(),
             pool_imports: Default::default(),
+            rejected_transactions: RejectedTransactions::new(
+                config.max_rejected_transactions,
+                config.rejected_transactions_ttl,
+            ),
             peers: Default::default(),
             command_tx,
             command_rx: UnboundedReceiverStream::new(command_rx),
@@ -232,6 +299,9 @@ impl<Pool: TransactionPool> TransactionsManager<Pool> {
                 from_network,
                 NETWORK_POOL_TRANSACTIONS_SCOPE,
             ),
+            propagation_policy: Box::new(propagation_policy),
+            gossip_validator: Box::new(AcceptAllGossip),
+            config,
             metrics: Default::default(),
         }
     }
@@ -247,6 +317,17 @@ where
     pub fn handle(&self) -> TransactionsHandle {
         TransactionsHandle { manager_tx: self.command_tx.clone() }
     }
+
+    /// Replaces the [`TransactionPropagationPolicy`] that is used for all future propagation.
+    pub fn set_propagation_policy(&mut self, policy: impl TransactionPropagationPolicy) {
+        self.propagation_policy = Box::new(policy);
+    }
+
+    /// Replaces the [`TransactionGossipValidator`] that validates all future transactions received
+    /// from peers.
+    pub fn set_gossip_validator(&mut self, validator: impl TransactionGossipValidator) {
+        self.gossip_validator = Box::new(validator);
+    }
 }
 
 impl<Pool> TransactionsManager<Pool>
@@ -263,6 +344,37 @@ where
         self.metrics
             .inflight_transaction_requests
             .set(self.transaction_fetcher.inflight_requests.len() as f64);
+        self.metrics
+            .buffered_transaction_hashes
+            .set(self.transaction_fetcher.num_buffered_hashes() as f64);
+    }
+
+    #[inline]
+    fn update_peer_metrics(&self) {
+        let num_seen = self.peers.values().map(|peer| peer.transactions.len()).sum::<usize>();
+        self.metrics.seen_transactions.set(num_seen as f64);
+        self.metrics
+            .seen_transactions_memory_bytes
+            .set((num_seen * APPROX_SEEN_TRANSACTION_ENTRY_SIZE) as f64);
+    }
+
+    /// Returns true if the pool imports can't keep up with the transactions received from peers.
+    #[inline]
+    fn is_pool_import_queue_saturated(&self) -> bool {
+        self.pool_imports.len() >= MAX_PENDING_POOL_IMPORTS
+    }
+
+    /// Requests the buffered announced hashes from all idle peers, unless the pool imports are
+    /// falling behind, in which case the hashes stay buffered until the queue was drained.
+    fn request_buffered_hashes(&mut self) {
+        if self.is_pool_import_queue_saturated() {
+            trace!(target: "net::tx", pending_imports=%self.pool_imports.len(), "pool import queue saturated, not requesting transactions");
+            return
+        }
+        let num_full = self.transaction_fetcher.schedule_requests(&self.peers);
+        if num_full > 0 {
+            self.metrics.egress_peer_channel_full.increment(num_full as u64);
+        }
     }
 
     /// Request handler for an incoming request for transactions
@@ -337,70 +449,81 @@ where
             return propagated
         }
 
-        // send full transactions to a fraction fo the connected peers (square root of the total
-        // number of connected peers)
-        let max_num_full = (self.peers.len() as f64).sqrt() as usize + 1;
+        let num_peers = self.peers.len();
 
         // Note: Assuming ~random~ order due to random state of the peers map hasher
         for (peer_idx, (peer_id, peer)) in self.peers.iter_mut().enumerate() {
+            let propagation_peer = PropagationPeer {
+                peer_id,
+                version: peer.version,
+                client_version: &peer.client_version,
+                idx: peer_idx,
+                num_peers,
+            };
+
             // filter all transactions unknown to the peer
             let mut hashes = PooledTransactionsHashesBuilder::new(peer.version);
             let mut full_transactions = FullTransactionsBuilder::default();
 
-            // Iterate through the transactions to propagate and fill the hashes and full
-            // transaction lists, before deciding whether or not to send full transactions to the
-            // peer.
+            // Iterate through the transactions to propagate and let the policy decide whether the
+            // peer receives the full transaction, only the hash or nothing at all.
             for tx in to_propagate.iter() {
-                if peer.transactions.insert(tx.hash()) {
-                    hashes.push(tx);
+                let mode =
+                    self.propagation_policy.propagation_mode(propagation_peer, &tx.transaction);
+                if !mode.is_propagate() {
+                    continue
+                }
+                if !peer.mark_seen(tx.hash(), &self.metrics) {
+                    // the peer already knows the transaction
+                    self.metrics.duplicate_announcements_avoided.increment(1);
+                    continue
+                }
 
-                    // Do not send full 4844 transaction hashes to peers.
-                    //
-                    //  Nodes MUST NOT automatically broadcast blob transactions to their peers.
-                    //  Instead, those transactions are only announced using
-                    //  `NewPooledTransactionHashes` messages, and can then be manually requested
-                    //  via `GetPooledTransactions`.
-                    //
-                    // From: <https://eips.ethereum.org/EIPS/eip-4844#networking>
-                    if !tx.transaction.is_eip4844() {
-                        full_transactions.push(tx);
-                    }
+                // Do not send full 4844 transaction hashes to peers.
+                //
+                //  Nodes MUST NOT automatically broadcast blob transactions to their peers.
+                //  Instead, those transactions are only announced using
+                //  `NewPooledTransactionHashes` messages, and can then be manually requested
+                //  via `GetPooledTransactions`.
+                //
+                // From: <https://eips.ethereum.org/EIPS/eip-4844#networking>
+                let send_full = mode == PropagationMode::Full && !tx.transaction.is_eip4844();
+
+                // if the transaction does not fit into the full transactions message anymore, fall
+                // back to announcing its hash
+                if !send_full || !full_transactions.push(tx) {
+                    hashes.push(tx);
                 }
             }
+
             let mut new_pooled_hashes = hashes.build();
 
             if !new_pooled_hashes.is_empty() {
-                // determine whether to send full tx objects or hashes. If there are no full
-                // transactions, try to send hashes.
-                if peer_idx > max_num_full || full_transactions.is_empty() {
-                    // enforce tx soft limit per message for the (unlikely) event the number of
-                    // hashes exceeds it
-                    new_pooled_hashes.truncate(NEW_POOLED_TRANSACTION_HASHES_SOFT_LIMIT);
-
-                    for hash in new_pooled_hashes.iter_hashes().copied() {
-                        propagated.0.entry(hash).or_default().push(PropagateKind::Hash(*peer_id));
-                    }
+                // enforce tx soft limit per message for the (unlikely) event the number of
+                // hashes exceeds it
+                new_pooled_hashes.truncate(NEW_POOLED_TRANSACTION_HASHES_SOFT_LIMIT);
+
+                for hash in new_pooled_hashes.iter_hashes().copied() {
+                    propagated.0.entry(hash).or_default().push(PropagateKind::Hash(*peer_id));
+                }
 
-                    trace!(target: "net::tx", ?peer_id, num_txs=?new_pooled_hashes.len(), "Propagating tx hashes to peer");
+                trace!(target: "net::tx", ?peer_id, num_txs=?new_pooled_hashes.len(), "Propagating tx hashes to peer");
 
-                    // send hashes of transactions
-                    self.network.send_transactions_hashes(*peer_id, new_pooled_hashes);
-                } else {
-                    let new_full_transactions = full_transactions.build();
-
-                    for tx in new_full_transactions.iter() {
-                        propagated
-                            .0
-                            .entry(tx.hash())
-                            .or_default()
-                            .push(PropagateKind::Full(*peer_id));
-                    }
+                // send hashes of transactions
+                self.network.send_transactions_hashes(*peer_id, new_pooled_hashes);
+            }
 
-                    trace!(target: "net::tx", ?peer_id, num_txs=?new_full_transactions.len(), "Propagating full transactions to peer");
+            if !full_transactions.is_empty() {
+                let new_full_transactions = full_transactions.build();
 
-                    // send full transactions
-                    self.network.send_transactions(*peer_id, new_full_transactions);
+                for tx in new_full_transactions.iter() {
+                    propagated.0.entry(tx.hash()).or_default().push(PropagateKind::Full(*peer_id));
                 }
+
+                trace!(target: "net::tx", ?peer_id, num_txs=?new_full_transactions.len(), "Propagating full transactions to peer");
+
+                // send full transactions
+                self.network.send_transactions(*peer_id, new_full_transactions);
             }
         }
 
@@ -435,8 +558,10 @@ where
 
         // Iterate through the transactions to propagate and fill the hashes and full transaction
         for tx in to_propagate {
-            if peer.transactions.insert(tx.hash()) {
+            if peer.mark_seen(tx.hash(), &self.metrics) {
                 full_transactions.push(&tx);
+            } else {
+                self.metrics.duplicate_announcements_avoided.increment(1);
             }
         }
 
@@ -481,8 +606,10 @@ where
             let mut hashes = PooledTransactionsHashesBuilder::new(peer.version);
 
             for tx in to_propagate {
-                if peer.transactions.insert(tx.hash()) {
+                if peer.mark_seen(tx.hash(), &self.metrics) {
                     hashes.push(&tx);
+                } else {
+                    self.metrics.duplicate_announcements_avoided.increment(1);
                 }
             }
 
@@ -527,37 +654,35 @@ where
         let mut num_already_seen = 0;
 
         if let Some(peer) = self.peers.get_mut(&peer_id) {
+            // the announced sizes are used to fill the requests up to the response soft limit
+            let sizes = match &msg {
+                NewPooledTransactionHashes::Eth68(msg) => {
+                    msg.hashes.iter().copied().zip(msg.sizes.iter().copied()).collect()
+                }
+                NewPooledTransactionHashes::Eth66(_) => HashMap::new(),
+            };
             let mut hashes = msg.into_hashes();
             // keep track of the transactions the peer knows
             for tx in hashes.iter().copied() {
-                if !peer.transactions.insert(tx) {
+                if !peer.mark_seen(tx, &self.metrics) {
                     num_already_seen += 1;
                 }
             }
 
-            self.pool.retain_unknown(&mut hashes);
-
-            if hashes.is_empty() {
-                // nothing to request
-                return
-            }
-
-            // enforce recommended soft limit, however the peer may enforce an arbitrary limit on
-            // the response (2MB)
-            hashes.truncate(GET_POOLED_TRANSACTION_SOFT_LIMIT_NUM_HASHES);
-
-            // request the missing transactions
-            let request_sent =
-                self.transaction_fetcher.request_transactions_from_peer(hashes, peer);
-            if !request_sent {
-                self.metrics.egress_peer_channel_full.increment(1);
-                return
-            }
-
             if num_already_seen > 0 {
                 self.metrics.messages_with_already_seen_hashes.increment(1);
                 trace!(target: "net::tx", num_hashes=%num_already_seen, ?peer_id, client=?peer.client_version, "Peer sent already seen hashes");
             }
+
+            self.pool.retain_unknown(&mut hashes);
+            hashes.retain(|hash| !self.rejected_transactions.contains(hash));
+
+            // buffer the missing transactions, they're requested in batches
+            self.transaction_fetcher.on_announced_hashes(
+                peer_id,
+                hashes.into_iter().map(|hash| (hash, sizes.get(&hash).copied())),
+            );
+            self.request_buffered_hashes();
         }
 
         if num_already_seen > 0 {
@@ -643,6 +768,7 @@ where
             NetworkEvent::SessionClosed { peer_id, .. } => {
                 // remove the peer
                 self.peers.remove(&peer_id);
+                self.transaction_fetcher.on_peer_disconnected(&peer_id);
             }
             NetworkEvent::SessionEstablished {
                 peer_id, client_version, messages, version, ..
@@ -651,14 +777,13 @@ where
                 self.peers.insert(
                     peer_id,
                     Peer {
-                        transactions: LruCache::new(
-                            NonZeroUsize::new(PEER_TRANSACTION_CACHE_LIMIT).unwrap(),
-                        ),
+                        transactions: LruCache::new(self.config.max_seen_transactions_per_peer),
                         request_tx: messages,
                         version,
-                        client_version,
+                        client_version: client_version.clone(),
                     },
                 );
+                self.transaction_fetcher.on_peer_connected(peer_id);
 
                 // Send a `NewPooledTransactionHashes` to the peer with up to
                 // `NEW_POOLED_TRANSACTION_HASHES_SOFT_LIMIT` transactions in the
@@ -667,6 +792,7 @@ where
                     if self.network.tx_gossip_disabled() {
                         return
                     }
+                    let num_peers = self.peers.len();
                     let peer = self.peers.get_mut(&peer_id).expect("is present; qed");
 
                     let mut msg_builder = PooledTransactionsHashesBuilder::new(version);
@@ -678,12 +804,31 @@ where
                         return
                     }
 
-                    for pooled_tx in pooled_txs.into_iter() {
-                        peer.transactions.insert(*pooled_tx.hash());
-                        msg_builder.push_pooled(pooled_tx);
+                    let propagation_peer = PropagationPeer {
+                        peer_id: &peer_id,
+                        version,
+                        client_version: &client_version,
+                        idx: num_peers - 1,
+                        num_peers,
+                    };
+
+                    for pooled_tx in pooled_txs.into_iter().map(PropagateTransaction::new) {
+                        // the policy may exclude transactions from being announced to this peer
+                        if !self
+                            .propagation_policy
+                            .propagation_mode(propagation_peer, &pooled_tx.transaction)
+                            .is_propagate()
+                        {
+                            continue
+                        }
+                        peer.mark_seen(pooled_tx.hash(), &self.metrics);
+                        msg_builder.push(&pooled_tx);
                     }
 
                     let msg = msg_builder.build();
+                    if msg.is_empty() {
+                        return
+                    }
                     self.network.send_transactions_hashes(peer_id, msg);
                 }
             }
@@ -709,9 +854,18 @@ where
         // tracks the quality of the given transactions
         let mut has_bad_transactions = false;
         let mut num_already_seen = 0;
+        let mut rejected = None;
 
         if let Some(peer) = self.peers.get_mut(&peer_id) {
             for tx in transactions {
+                // skip the recovery and validation of recently rejected transactions
+                if let Some(is_bad) = self.rejected_transactions.get(tx.hash()) {
+                    if is_bad {
+                        rejected.get_or_insert(ReputationChangeKind::BadTransactions);
+                    }
+                    continue
+                }
+
                 // recover transaction
                 let tx = if let Ok(tx) = tx.try_into_ecrecovered() {
                     tx
@@ -724,10 +878,32 @@ where
                 // If we received the transactions as the response to our GetPooledTransactions
                 // requests (based on received `NewPooledTransactionHashes`) then we already
                 // recorded the hashes in [`Self::on_new_pooled_transaction_hashes`]
-                if source.is_broadcast() && !peer.transactions.insert(*tx.hash()) {
+                if source.is_broadcast() && !peer.mark_seen(*tx.hash(), &self.metrics) {
                     num_already_seen += 1;
                 }
 
+                let gossip_peer = GossipPeer {
+                    peer_id: &peer_id,
+                    version: peer.version,
+                    client_version: &peer.client_version,
+                    is_broadcast: source.is_broadcast(),
+                };
+                match self.gossip_validator.validate_gossip(gossip_peer, &tx) {
+                    GossipValidationOutcome::Accept => {}
+                    GossipValidationOutcome::Ignore => {
+                        self.metrics.filtered_gossip_transactions.increment(1);
+                        self.rejected_transactions.insert(*tx.hash(), false);
+                        continue
+                    }
+                    GossipValidationOutcome::Reject(kind) => {
+                        trace!(target: "net::tx", hash=?tx.hash(), ?peer_id, "Rejected transaction from peer");
+                        self.metrics.filtered_gossip_transactions.increment(1);
+                        self.rejected_transactions.insert(*tx.hash(), true);
+                        rejected.get_or_insert(kind);
+                        continue
+                    }
+                }
+
                 match self.transactions_by_peers.entry(*tx.hash()) {
                     Entry::Occupied(mut entry) => {
                         // transaction was already inserted
@@ -755,6 +931,11 @@ where
             }
         }
 
+        // the peer is penalized once per message
+        if let Some(kind) = rejected {
+            self.report_peer(peer_id, kind);
+        }
+
         if has_bad_transactions || num_already_seen > 0 {
             self.report_already_seen(peer_id);
         }
@@ -858,14 +1039,25 @@ where
                     // rules)
                     if err.is_bad_transaction() && !this.network.is_syncing() {
                         debug!(target: "net::tx", ?err, "bad pool transaction import");
+                        this.rejected_transactions.insert(err.hash, true);
                         this.on_bad_import(err.hash);
                         continue
                     }
+                    // remember the rejection, unless it's not caused by the transaction itself
+                    if !matches!(err.kind, PoolErrorKind::AlreadyImported | PoolErrorKind::Other(_))
+                    {
+                        this.rejected_transactions.insert(err.hash, false);
+                    }
                     this.on_good_import(err.hash);
                 }
             }
         }
 
+        // request more transactions if the imports made room in the import queue, or the
+        // requests to the peers completed
+        this.request_buffered_hashes();
+
+        this.update_request_metrics();
         this.update_import_metrics();
 
         // handle and propagate new transactions
@@ -877,6 +1069,8 @@ where
             this.on_new_transactions(new_txs);
         }
 
+        this.update_peer_metrics();
+
         // all channels are fully drained and import futures pending
 
         Poll::Pending
@@ -916,14 +1110,17 @@ struct FullTransactionsBuilder {
 
 impl FullTransactionsBuilder {
     /// Append a transaction to the list if it doesn't exceed the maximum size.
-    fn push(&mut self, transaction: &PropagateTransaction) {
+    ///
+    /// Returns `false` if the transaction was not added.
+    fn push(&mut self, transaction: &PropagateTransaction) -> bool {
         let new_size = self.total_size + transaction.size;
         if new_size > MAX_FULL_TRANSACTIONS_PACKET_SIZE {
-            return
+            return false
         }
 
         self.total_size = new_size;
         self.transactions.push(Arc::clone(&transaction.transaction));
+        true
     }
 
     /// Returns whether or not any transactions are in the [FullTransactionsBuilder].
@@ -947,18 +1144,6 @@ enum PooledTransactionsHashesBuilder {
 // === impl PooledTransactionsHashesBuilder ===
 
 impl PooledTransactionsHashesBuilder {
-    /// Push a transaction from the pool to the list.
-    fn push_pooled<T: PoolTransaction>(&mut self, pooled_tx: Arc<ValidPoolTransaction<T>>) {
-        match self {
-            PooledTransactionsHashesBuilder::Eth66(msg) => msg.0.push(*pooled_tx.hash()),
-            PooledTransactionsHashesBuilder::Eth68(msg) => {
-                msg.hashes.push(*pooled_tx.hash());
-                msg.sizes.push(pooled_tx.encoded_length());
-                msg.types.push(pooled_tx.transaction.tx_type());
-            }
-        }
-    }
-
     fn push(&mut self, tx: &PropagateTransaction) {
         match self {
             PooledTransactionsHashesBuilder::Eth66(msg) => msg.0.push(tx.hash()),
@@ -976,7 +1161,9 @@ impl PooledTransactionsHashesBuilder {
             EthVersion::Eth66 | EthVersion::Eth67 => {
                 PooledTransactionsHashesBuilder::Eth66(Default::default())
             }
-            EthVersion::Eth68 => PooledTransactionsHashesBuilder::Eth68(Default::default()),
+            EthVersion::Eth68 | EthVersion::Eth69 => {
+                PooledTransactionsHashesBuilder::Eth68(Default::default())
+            }
         }
     }
 
@@ -1070,29 +1257,99 @@ struct Peer {
     client_version: Arc<str>,
 }
 
+// === impl Peer ===
+
+impl Peer {
+    /// Marks the transaction as seen by the peer, and records if this evicted the oldest seen
+    /// transaction.
+    ///
+    /// Returns false if the peer has already seen the transaction.
+    fn mark_seen(&mut self, hash: TxHash, metrics: &TransactionsManagerMetrics) -> bool {
+        let (is_new, evicted) = self.transactions.insert_and_get_evicted(hash);
+        if evicted.is_some() {
+            metrics.seen_transactions_evictions.increment(1);
+        }
+        is_new
+    }
+}
+
 /// The type responsible for fetching missing transactions from peers.
 ///
-/// This will keep track of unique transaction hashes that are currently being fetched and submits
-/// new requests on announced hashes.
+/// This will keep track of unique transaction hashes that are currently being fetched or are
+/// buffered until they're fetched. The hashes announced by a peer are requested from it in
+/// batches within the soft limits of a `GetPooledTransactions` request, with at most one inflight
+/// request per peer. Hashes that a peer failed to deliver are handed over to the next peer that
+/// announced them.
 #[derive(Debug, Default)]
 struct TransactionFetcher {
     /// All currently active requests for pooled transactions.
     inflight_requests: FuturesUnordered<GetPooledTxRequestFut>,
-    /// Set that tracks all hashes that are currently being fetched.
-    inflight_hash_to_fallback_peers: HashMap<TxHash, Vec<PeerId>>,
+    /// All hashes that are currently being fetched or buffered, and the peers that announced them.
+    unknown_hashes: HashMap<TxHash, UnknownTransaction>,
+    /// The hashes that are waiting to be requested from each connected peer, in announcement
+    /// order.
+    buffered_hashes: HashMap<PeerId, VecDeque<TxHash>>,
+    /// Peers that currently have an inflight request.
+    busy_peers: HashSet<PeerId>,
 }
 
 // === impl TransactionFetcher ===
 
 impl TransactionFetcher {
+    /// Starts tracking the buffered hashes of the peer.
+    fn on_peer_connected(&mut self, peer_id: PeerId) {
+        self.buffered_hashes.entry(peer_id).or_default();
+    }
+
+    /// Hands the buffered hashes of the peer over to the other peers that announced them.
+    ///
+    /// The hashes of an inflight request are handed over once the request failed.
+    fn on_peer_disconnected(&mut self, peer_id: &PeerId) {
+        self.busy_peers.remove(peer_id);
+        if let Some(buffered) = self.buffered_hashes.remove(peer_id) {
+            self.reschedule_hashes(peer_id, buffered);
+        }
+    }
+
+    /// Returns the number of hashes that are waiting to be requested.
+    fn num_buffered_hashes(&self) -> usize {
+        self.buffered_hashes.values().map(VecDeque::len).sum()
+    }
+
     /// Removes the specified hashes from inflight tracking.
     #[inline]
     fn remove_inflight_hashes<'a, I>(&mut self, hashes: I)
     where
         I: IntoIterator<Item = &'a TxHash>,
     {
-        for &hash in hashes {
-            self.inflight_hash_to_fallback_peers.remove(&hash);
+        for hash in hashes {
+            self.unknown_hashes.remove(hash);
+        }
+    }
+
+    /// Hands the hashes that `peer_id` was responsible for over to the next connected peer that
+    /// announced them, or stops tracking them if there is none.
+    fn reschedule_hashes(&mut self, peer_id: &PeerId, hashes: impl IntoIterator<Item = TxHash>) {
+        for hash in hashes {
+            let Entry::Occupied(mut entry) = self.unknown_hashes.entry(hash) else { continue };
+            let unknown = entry.get_mut();
+            if unknown.peers.first() != Some(peer_id) {
+                // already handed over to another peer
+                continue
+            }
+
+            let buffered_hashes = &self.buffered_hashes;
+            unknown.peers.retain(|peer| peer != peer_id && buffered_hashes.contains_key(peer));
+            unknown.inflight = false;
+            match unknown.peers.first() {
+                Some(next) => {
+                    // not subject to the buffer limit, since the peer announced the hash as well
+                    self.buffered_hashes.get_mut(next).expect("is connected").push_back(hash);
+                }
+                None => {
+                    entry.remove();
+                }
+            }
         }
     }
 
@@ -1101,13 +1358,14 @@ impl TransactionFetcher {
         if let Poll::Ready(Some(GetPooledTxResponse { peer_id, requested_hashes, result })) =
             self.inflight_requests.poll_next_unpin(cx)
         {
+            self.busy_peers.remove(&peer_id);
             return match result {
                 Ok(Ok(transactions)) => {
                     // clear received hashes
                     self.remove_inflight_hashes(transactions.hashes());
 
-                    // TODO: re-request missing hashes, for now clear all of them
-                    self.remove_inflight_hashes(requested_hashes.iter());
+                    // request the missing hashes from other peers
+                    self.reschedule_hashes(&peer_id, requested_hashes);
 
                     Poll::Ready(FetchEvent::TransactionsFetched {
                         peer_id,
@@ -1115,13 +1373,11 @@ impl TransactionFetcher {
                     })
                 }
                 Ok(Err(req_err)) => {
-                    // TODO: re-request missing hashes
-                    self.remove_inflight_hashes(&requested_hashes);
+                    self.reschedule_hashes(&peer_id, requested_hashes);
                     Poll::Ready(FetchEvent::FetchError { peer_id, error: req_err })
                 }
                 Err(_) => {
-                    // TODO: re-request missing hashes
-                    self.remove_inflight_hashes(&requested_hashes);
+                    self.reschedule_hashes(&peer_id, requested_hashes);
                     // request channel closed/dropped
                     Poll::Ready(FetchEvent::FetchError {
                         peer_id,
@@ -1144,73 +1400,134 @@ impl TransactionFetcher {
         self.remove_inflight_hashes(hashes)
     }
 
-    /// Requests the missing transactions from the announced hashes of the peer
+    /// Buffers the missing transactions from the announced hashes of the peer, with their
+    /// announced sizes if known.
     ///
-    /// This filters all announced hashes that are already in flight, and requests the missing,
-    /// while marking the given peer as an alternative peer for the hashes that are already in
-    /// flight.
-    fn request_transactions_from_peer(
+    /// Hashes that are already being fetched or buffered for another peer are not buffered again,
+    /// instead the given peer is marked as an alternative peer for them. Hashes that exceed the
+    /// buffer limit of the peer are dropped.
+    fn on_announced_hashes(
         &mut self,
-        mut announced_hashes: Vec<TxHash>,
-        peer: &Peer,
-    ) -> bool {
-        let peer_id: PeerId = peer.request_tx.peer_id;
-        // 1. filter out inflight hashes, and register the peer as fallback for all inflight hashes
-        announced_hashes.retain(|&hash| {
-            match self.inflight_hash_to_fallback_peers.entry(hash) {
+        peer_id: PeerId,
+        hashes: impl IntoIterator<Item = (TxHash, Option<usize>)>,
+    ) {
+        let Some(buffered) = self.buffered_hashes.get_mut(&peer_id) else { return };
+        let mut num_dropped = 0;
+        for (hash, size) in hashes {
+            match self.unknown_hashes.entry(hash) {
                 Entry::Vacant(entry) => {
-                    // the hash is not in inflight hashes, insert it and retain in the vector
-                    entry.insert(vec![peer_id]);
-                    true
+                    if buffered.len() >= MAX_BUFFERED_HASHES_PER_PEER {
+                        num_dropped += 1;
+                        continue
+                    }
+                    entry.insert(UnknownTransaction {
+                        peers: vec![peer_id],
+                        size,
+                        inflight: false,
+                    });
+                    buffered.push_back(hash);
                 }
                 Entry::Occupied(mut entry) => {
-                    // the hash is already in inflight, add this peer as a backup if not more than 3
+                    // the hash is already tracked, add this peer as a backup if not more than 3
                     // backups already
-                    let backups = entry.get_mut();
-                    if backups.len() < MAX_ALTERNATIVE_PEERS_PER_TX {
-                        backups.push(peer_id);
+                    let peers = &mut entry.get_mut().peers;
+                    if peers.len() <= MAX_ALTERNATIVE_PEERS_PER_TX && !peers.contains(&peer_id) {
+                        peers.push(peer_id);
                     }
-                    false
                 }
             }
-        });
+        }
 
-        // 2. request all missing from peer
-        if announced_hashes.is_empty() {
-            // nothing to request
-            return false
+        if num_dropped > 0 {
+            trace!(target: "net::tx", %num_dropped, ?peer_id, "Dropped announced hashes exceeding the buffer limit");
         }
+    }
 
-        let (response, rx) = oneshot::channel();
-        let req: PeerRequest = PeerRequest::GetPooledTransactions {
-            request: GetPooledTransactions(announced_hashes.clone()),
-            response,
-        };
+    /// Requests the buffered hashes from all peers without an inflight request.
+    ///
+    /// A request contains at most [`GET_POOLED_TRANSACTION_SOFT_LIMIT_NUM_HASHES`] hashes, and
+    /// the announced sizes of the requested transactions add up to at most
+    /// [`GET_POOLED_TRANSACTION_SOFT_LIMIT_RESPONSE_SIZE`].
+    ///
+    /// Returns the number of peers whose request channel was full, their hashes stay buffered.
+    fn schedule_requests(&mut self, peers: &HashMap<PeerId, Peer>) -> usize {
+        let mut num_full = 0;
+        for (peer_id, buffered) in self.buffered_hashes.iter_mut() {
+            if buffered.is_empty() || self.busy_peers.contains(peer_id) {
+                continue
+            }
+            let Some(peer) = peers.get(peer_id) else { continue };
+
+            let mut hashes = Vec::new();
+            let mut size = 0;
+            while let Some(hash) = buffered.front() {
+                let Some(unknown) = self.unknown_hashes.get(hash) else {
+                    // received in the meantime
+                    buffered.pop_front();
+                    continue
+                };
+                if unknown.inflight || unknown.peers.first() != Some(peer_id) {
+                    // requested from another peer in the meantime
+                    buffered.pop_front();
+                    continue
+                }
 
-        // try to send the request to the peer
-        if let Err(err) = peer.request_tx.try_send(req) {
-            // peer channel is full
-            match err {
-                TrySendError::Full(req) | TrySendError::Closed(req) => {
-                    // need to do some cleanup so
-                    let req = req.into_get_pooled_transactions().expect("is get pooled tx");
-
-                    // we know that the peer is the only entry in the map, so we can remove all
-                    for hash in req.0.into_iter() {
-                        self.inflight_hash_to_fallback_peers.remove(&hash);
-                    }
+                let tx_size = unknown.size.unwrap_or_default();
+                if hashes.len() >= GET_POOLED_TRANSACTION_SOFT_LIMIT_NUM_HASHES ||
+                    (!hashes.is_empty() &&
+                        size + tx_size > GET_POOLED_TRANSACTION_SOFT_LIMIT_RESPONSE_SIZE)
+                {
+                    break
                 }
+                size += tx_size;
+                hashes.extend(buffered.pop_front());
             }
-            return false
-        } else {
-            //create a new request for it, from that peer
-            self.inflight_requests.push(GetPooledTxRequestFut::new(peer_id, announced_hashes, rx))
+
+            if hashes.is_empty() {
+                continue
+            }
+
+            let (response, rx) = oneshot::channel();
+            let req: PeerRequest = PeerRequest::GetPooledTransactions {
+                request: GetPooledTransactions(hashes.clone()),
+                response,
+            };
+
+            // try to send the request to the peer
+            if peer.request_tx.try_send(req).is_err() {
+                // the peer channel is full, retry later
+                for hash in hashes.into_iter().rev() {
+                    buffered.push_front(hash);
+                }
+                num_full += 1;
+                continue
+            }
+
+            for hash in hashes.iter() {
+                if let Some(unknown) = self.unknown_hashes.get_mut(hash) {
+                    unknown.inflight = true;
+                }
+            }
+            self.busy_peers.insert(*peer_id);
+            self.inflight_requests.push(GetPooledTxRequestFut::new(*peer_id, hashes, rx))
         }
 
-        true
+        num_full
     }
 }
 
+/// A transaction that is announced but not yet received.
+#[derive(Debug)]
+struct UnknownTransaction {
+    /// The peers that announced the transaction, the first one is the peer the transaction is
+    /// requested from.
+    peers: Vec<PeerId>,
+    /// The size of the transaction, if announced.
+    size: Option<usize>,
+    /// Whether the transaction is part of an inflight request.
+    inflight: bool,
+}
+
 /// Represents possible events from fetching transactions.
 #[derive(Debug)]
 enum FetchEvent {
@@ -1290,12 +1607,96 @@ mod tests {
     use alloy_rlp::Decodable;
     use reth_interfaces::sync::{NetworkSyncUpdater, SyncState};
     use reth_network_api::NetworkInfo;
-    use reth_primitives::hex;
+    use reth_primitives::{hex, PooledTransactionsElementEcRecovered};
     use reth_provider::test_utils::NoopProvider;
 
     use reth_transaction_pool::test_utils::{testing_pool, MockTransaction};
     use secp256k1::SecretKey;
     use std::future::poll_fn;
+
+    fn new_mock_peer(peer_id: PeerId) -> (Peer, mpsc::Receiver<PeerRequest>) {
+        let (tx, rx) = mpsc::channel(1);
+        let peer = Peer {
+            transactions: LruCache::new(
+                TransactionsManagerConfig::default().max_seen_transactions_per_peer,
+            ),
+            request_tx: PeerRequestSender::new(peer_id, tx),
+            version: EthVersion::Eth68,
+            client_version: Arc::from(""),
+        };
+        (peer, rx)
+    }
+
+    fn requested_hashes(rx: &mut mpsc::Receiver<PeerRequest>) -> Option<Vec<TxHash>> {
+        rx.try_recv().ok().and_then(PeerRequest::into_get_pooled_transactions).map(|req| req.0)
+    }
+
+    #[test]
+    fn test_peer_mark_seen() {
+        let (mut peer, _rx) = new_mock_peer(PeerId::random());
+        let metrics = TransactionsManagerMetrics::default();
+
+        let hash = TxHash::random();
+        assert!(peer.mark_seen(hash, &metrics));
+        assert!(!peer.mark_seen(hash, &metrics));
+        assert_eq!(peer.transactions.len(), 1);
+    }
+
+    #[test]
+    fn test_fetcher_batches_and_dedupes_hashes() {
+        let (peer_a, peer_b) = (PeerId::random(), PeerId::random());
+        let (a, mut rx_a) = new_mock_peer(peer_a);
+        let (b, mut rx_b) = new_mock_peer(peer_b);
+        let peers = HashMap::from([(peer_a, a), (peer_b, b)]);
+
+        let mut fetcher = TransactionFetcher::default();
+        fetcher.on_peer_connected(peer_a);
+        fetcher.on_peer_connected(peer_b);
+
+        let hashes = (0..300).map(|_| TxHash::random()).collect::<Vec<_>>();
+        fetcher.on_announced_hashes(peer_a, hashes.iter().map(|hash| (*hash, None)));
+        // the hashes are only requested from the first peer that announced them
+        fetcher.on_announced_hashes(peer_b, hashes.iter().map(|hash| (*hash, None)));
+        assert_eq!(fetcher.num_buffered_hashes(), 300);
+
+        assert_eq!(fetcher.schedule_requests(&peers), 0);
+        assert_eq!(
+            requested_hashes(&mut rx_a).unwrap(),
+            hashes[..GET_POOLED_TRANSACTION_SOFT_LIMIT_NUM_HASHES]
+        );
+        assert!(requested_hashes(&mut rx_b).is_none());
+
+        // the remaining hashes are requested once the inflight request completed
+        fetcher.schedule_requests(&peers);
+        assert!(requested_hashes(&mut rx_a).is_none());
+
+        // the buffered hashes are handed over to the other peer
+        fetcher.on_peer_disconnected(&peer_a);
+        fetcher.schedule_requests(&peers);
+        assert_eq!(
+            requested_hashes(&mut rx_b).unwrap(),
+            hashes[GET_POOLED_TRANSACTION_SOFT_LIMIT_NUM_HASHES..]
+        );
+        assert_eq!(fetcher.num_buffered_hashes(), 0);
+    }
+
+    #[test]
+    fn test_fetcher_respects_response_size_limit() {
+        let peer_id = PeerId::random();
+        let (peer, mut rx) = new_mock_peer(peer_id);
+        let peers = HashMap::from([(peer_id, peer)]);
+
+        let mut fetcher = TransactionFetcher::default();
+        fetcher.on_peer_connected(peer_id);
+
+        let hashes = (0..3).map(|_| TxHash::random()).collect::<Vec<_>>();
+        let size = GET_POOLED_TRANSACTION_SOFT_LIMIT_RESPONSE_SIZE / 2;
+        fetcher.on_announced_hashes(peer_id, hashes.iter().map(|hash| (*hash, Some(size))));
+
+        fetcher.schedule_requests(&peers);
+        assert_eq!(requested_hashes(&mut rx).unwrap(), hashes[..2]);
+        assert_eq!(fetcher.num_buffered_hashes(), 1);
+    }
     #[tokio::test(flavor = "multi_thread")]
     async fn test_ignored_tx_broadcasts_while_initially_syncing() {
         reth_tracing::init_test_tracing();
@@ -1554,6 +1955,100 @@ mod tests {
         handle.terminate().await;
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_gossip_validator_rejects_transactions() {
+        #[derive(Debug)]
+        struct RejectAll;
+
+        impl TransactionGossipValidator for RejectAll {
+            fn validate_gossip(
+                &self,
+                _peer: GossipPeer<'_>,
+                _transaction: &PooledTransactionsElementEcRecovered,
+            ) -> GossipValidationOutcome {
+                GossipValidationOutcome::Reject(ReputationChangeKind::BadTransactions)
+            }
+        }
+
+        reth_tracing::init_test_tracing();
+        let net = Testnet::create(3).await;
+
+        let mut handles = net.handles();
+        let handle0 = handles.next().unwrap();
+        let handle1 = handles.next().unwrap();
+
+        drop(handles);
+        let handle = net.spawn();
+
+        let listener0 = handle0.event_listener();
+
+        handle0.add_peer(*handle1.peer_id(), handle1.local_addr());
+        let secret_key = SecretKey::new(&mut rand::thread_rng());
+
+        let client = NoopProvider::default();
+        let pool = testing_pool();
+        let config = NetworkConfigBuilder::new(secret_key)
+            .disable_discovery()
+            .listener_port(0)
+            .build(client);
+        let (network_handle, network, mut transactions, _) = NetworkManager::new(config)
+            .await
+            .unwrap()
+            .into_builder()
+            .transactions(pool.clone())
+            .transaction_gossip_validator(RejectAll)
+            .split_with_handle();
+        tokio::task::spawn(network);
+
+        network_handle.update_sync_state(SyncState::Idle);
+
+        // wait for all initiator connections
+        let mut established = listener0.take(2);
+        while let Some(ev) = established.next().await {
+            match ev {
+                NetworkEvent::SessionEstablished {
+                    peer_id,
+                    remote_addr,
+                    client_version,
+                    capabilities,
+                    messages,
+                    status,
+                    version,
+                } => transactions.on_network_event(NetworkEvent::SessionEstablished {
+                    peer_id,
+                    remote_addr,
+                    client_version,
+                    capabilities,
+                    messages,
+                    status,
+                    version,
+                }),
+                NetworkEvent::PeerAdded(_peer_id) => continue,
+                ev => {
+                    panic!("unexpected event {ev:?}")
+                }
+            }
+        }
+        // random tx: <https://etherscan.io/getRawTx?tx=0x9448608d36e721ef403c53b00546068a6474d6cbab6816c3926de449898e7bce>
+        let input = hex!("02f871018302a90f808504890aef60826b6c94ddf4c5025d1a5742cf12f74eec246d4432c295e487e09c3bbcc12b2b80c080a0f21a4eacd0bf8fea9c5105c543be5a1d8c796516875710fafafdf16d16d8ee23a001280915021bb446d1973501a67f93d2b38894a514b976e7b46dc2fe54598d76");
+        let signed_tx = TransactionSigned::decode(&mut &input[..]).unwrap();
+        transactions.on_network_tx_event(NetworkTransactionEvent::IncomingTransactions {
+            peer_id: *handle1.peer_id(),
+            msg: Transactions(vec![signed_tx.clone()]),
+        });
+        // the transaction was never handed to the pool
+        assert!(!transactions.transactions_by_peers.contains_key(&signed_tx.hash()));
+
+        poll_fn(|cx| {
+            let _ = transactions.poll_unpin(cx);
+            Poll::Ready(())
+        })
+        .await;
+
+        assert!(pool.is_empty());
+        handle.terminate().await;
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn test_on_get_pooled_transactions_network() {
         reth_tracing::init_test_tracing();
@@ -0,0 +1,162 @@
+//! Policies that control how transactions are propagated to connected peers.
+
+use reth_eth_wire::EthVersion;
+use reth_primitives::{PeerId, TransactionSigned};
+use std::{fmt, sync::Arc};
+
+/// How a single transaction should be announced to a single peer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PropagationMode {
+    /// Send the full transaction object via a `Transactions` message.
+    ///
+    /// Note: EIP-4844 transactions are never broadcast in full, if the policy returns this mode
+    /// for a blob transaction it is announced as [PropagationMode::Hash] instead.
+    Full,
+    /// Only announce the hash via a `NewPooledTransactionHashes` message.
+    Hash,
+    /// Do not propagate the transaction to the peer at all.
+    Skip,
+}
+
+// === impl PropagationMode ===
+
+impl PropagationMode {
+    /// Returns true if the transaction should be announced to the peer in any form.
+    pub fn is_propagate(&self) -> bool {
+        !matches!(self, PropagationMode::Skip)
+    }
+}
+
+/// Context about the peer a transaction is about to be propagated to.
+#[derive(Debug, Clone, Copy)]
+pub struct PropagationPeer<'a> {
+    /// The id of the peer.
+    pub peer_id: &'a PeerId,
+    /// The negotiated version of the session.
+    pub version: EthVersion,
+    /// The peer's client version.
+    pub client_version: &'a str,
+    /// Position of this peer in the current propagation round.
+    ///
+    /// Note: the order of peers is not stable across rounds.
+    pub idx: usize,
+    /// Number of connected peers in the current propagation round.
+    pub num_peers: usize,
+}
+
+/// A policy that decides how transactions are propagated to connected peers.
+///
+/// The [`TransactionsManager`](crate::transactions::TransactionsManager) consults the policy for
+/// every (peer, transaction) pair that is not yet known to the peer. This makes it possible to, for
+/// example, announce only hashes to all peers, send full bodies to a fixed subset or keep private
+/// transactions out of the public gossip entirely.
+pub trait TransactionPropagationPolicy: fmt::Debug + Send + Sync + Unpin + 'static {
+    /// Returns how the transaction should be propagated to the given peer.
+    fn propagation_mode(
+        &self,
+        peer: PropagationPeer<'_>,
+        transaction: &Arc<TransactionSigned>,
+    ) -> PropagationMode;
+}
+
+impl<T: TransactionPropagationPolicy + ?Sized> TransactionPropagationPolicy for Box<T> {
+    fn propagation_mode(
+        &self,
+        peer: PropagationPeer<'_>,
+        transaction: &Arc<TransactionSigned>,
+    ) -> PropagationMode {
+        (**self).propagation_mode(peer, transaction)
+    }
+}
+
+/// The default propagation policy.
+///
+/// Sends full transactions to a fraction of the connected peers (square root of the total number of
+/// connected peers) and only the hashes to all other peers.
+#[derive(Debug, Clone, Copy, Default)]
+#[non_exhaustive]
+pub struct SqrtPeersFullPropagation;
+
+impl TransactionPropagationPolicy for SqrtPeersFullPropagation {
+    fn propagation_mode(
+        &self,
+        peer: PropagationPeer<'_>,
+        _transaction: &Arc<TransactionSigned>,
+    ) -> PropagationMode {
+        let max_num_full = (peer.num_peers as f64).sqrt() as usize + 1;
+        if peer.idx > max_num_full {
+            PropagationMode::Hash
+        } else {
+            PropagationMode::Full
+        }
+    }
+}
+
+/// Only announces transaction hashes, full transactions are never broadcast.
+#[derive(Debug, Clone, Copy, Default)]
+#[non_exhaustive]
+pub struct HashesOnlyPropagation;
+
+impl TransactionPropagationPolicy for HashesOnlyPropagation {
+    fn propagation_mode(
+        &self,
+        _peer: PropagationPeer<'_>,
+        _transaction: &Arc<TransactionSigned>,
+    ) -> PropagationMode {
+        PropagationMode::Hash
+    }
+}
+
+/// Never propagates any transactions.
+///
+/// This is useful for private mempool setups where transactions must not leak to the public
+/// network. Note: requests for pooled transactions from peers are still served.
+#[derive(Debug, Clone, Copy, Default)]
+#[non_exhaustive]
+pub struct NoPropagation;
+
+impl TransactionPropagationPolicy for NoPropagation {
+    fn propagation_mode(
+        &self,
+        _peer: PropagationPeer<'_>,
+        _transaction: &Arc<TransactionSigned>,
+    ) -> PropagationMode {
+        PropagationMode::Skip
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn peer(peer_id: &PeerId, idx: usize, num_peers: usize) -> PropagationPeer<'_> {
+        PropagationPeer { peer_id, version: EthVersion::Eth68, client_version: "", idx, num_peers }
+    }
+
+    #[test]
+    fn sqrt_policy_matches_default_fanout() {
+        let tx = Arc::new(TransactionSigned::default());
+        let peer_id = PeerId::random();
+        let policy = SqrtPeersFullPropagation;
+
+        // sqrt(16) + 1 = 5
+        for idx in 0..=5 {
+            assert_eq!(
+                policy.propagation_mode(peer(&peer_id, idx, 16), &tx),
+                PropagationMode::Full
+            );
+        }
+        for idx in 6..16 {
+            assert_eq!(
+                policy.propagation_mode(peer(&peer_id, idx, 16), &tx),
+                PropagationMode::Hash
+            );
+        }
+
+        assert_eq!(
+            HashesOnlyPropagation.propagation_mode(peer(&peer_id, 0, 16), &tx),
+            PropagationMode::Hash
+        );
+        assert!(!NoPropagation.propagation_mode(peer(&peer_id, 0, 16), &tx).is_propagate());
+    }
+}
@@ -0,0 +1,120 @@
+//! A time-bounded cache of transactions that were recently rejected.
+
+use reth_metrics::{
+    metrics::{Counter, Gauge},
+    Metrics,
+};
+use reth_primitives::TxHash;
+use schnellru::{ByLength, LruMap};
+use std::{
+    fmt,
+    time::{Duration, Instant},
+};
+
+/// Keeps track of transactions that were recently rejected by the pool or the gossip validator.
+///
+/// Transactions in the cache are neither requested from peers nor validated again until their
+/// entry expires, so that spam waves of the same junk transactions don't repeatedly go through
+/// the full validation.
+pub(crate) struct RejectedTransactions {
+    /// Maps the hash of a rejected transaction to the time and reason of the rejection.
+    transactions: LruMap<TxHash, RejectedEntry>,
+    /// How long a rejection is remembered.
+    ttl: Duration,
+    /// Metrics for the cache.
+    metrics: RejectedTransactionsMetrics,
+}
+
+impl RejectedTransactions {
+    pub(crate) fn new(max_length: u32, ttl: Duration) -> Self {
+        Self {
+            transactions: LruMap::new(ByLength::new(max_length)),
+            ttl,
+            metrics: Default::default(),
+        }
+    }
+
+    /// Records that the transaction was rejected.
+    ///
+    /// `is_bad` marks transactions that are invalid regardless of the state of the pool, the peers
+    /// that send them are penalized.
+    pub(crate) fn insert(&mut self, hash: TxHash, is_bad: bool) {
+        self.transactions.insert(hash, RejectedEntry { rejected_at: Instant::now(), is_bad });
+        self.metrics.inserts.increment(1);
+        self.metrics.count.set(self.transactions.len() as f64);
+    }
+
+    /// Returns whether the transaction is bad, if it was rejected within the TTL.
+    ///
+    /// Expired entries are removed.
+    pub(crate) fn get(&mut self, hash: &TxHash) -> Option<bool> {
+        {
+            let entry = self.transactions.get(hash)?;
+            if entry.rejected_at.elapsed() < self.ttl {
+                self.metrics.hits.increment(1);
+                return Some(entry.is_bad)
+            }
+        }
+        // if we get here, the entry has expired
+        self.transactions.remove(hash);
+        self.metrics.expired.increment(1);
+        self.metrics.count.set(self.transactions.len() as f64);
+        None
+    }
+
+    /// Returns true if the transaction was rejected within the TTL.
+    pub(crate) fn contains(&mut self, hash: &TxHash) -> bool {
+        self.get(hash).is_some()
+    }
+}
+
+impl fmt::Debug for RejectedTransactions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RejectedTransactions")
+            .field("len", &self.transactions.len())
+            .field("ttl", &self.ttl)
+            .finish_non_exhaustive()
+    }
+}
+
+struct RejectedEntry {
+    /// When the transaction was rejected.
+    rejected_at: Instant,
+    /// Whether the transaction is invalid regardless of the state of the pool.
+    is_bad: bool,
+}
+
+/// Metrics for the cache of rejected transactions.
+#[derive(Metrics)]
+#[metrics(scope = "network.transactions.rejected")]
+struct RejectedTransactionsMetrics {
+    /// The number of rejected transactions in the cache.
+    count: Gauge,
+    /// The total number of rejected transactions that were inserted.
+    inserts: Counter,
+    /// The total number of transactions that were skipped, because they were recently rejected.
+    hits: Counter,
+    /// The total number of entries that were removed, because they expired.
+    expired: Counter,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rejected_transactions_expire() {
+        let mut cache = RejectedTransactions::new(10, Duration::from_millis(50));
+        let (bad, underpriced) = (TxHash::random(), TxHash::random());
+        cache.insert(bad, true);
+        cache.insert(underpriced, false);
+
+        assert_eq!(cache.get(&bad), Some(true));
+        assert_eq!(cache.get(&underpriced), Some(false));
+        assert!(!cache.contains(&TxHash::random()));
+
+        std::thread::sleep(Duration::from_millis(60));
+        assert!(!cache.contains(&bad));
+        assert_eq!(cache.transactions.len(), 1);
+    }
+}
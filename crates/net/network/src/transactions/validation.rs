@@ -0,0 +1,80 @@
+//! Hooks that validate transactions received from peers before they're imported into the pool.
+
+use reth_eth_wire::EthVersion;
+use reth_network_api::ReputationChangeKind;
+use reth_primitives::{PeerId, PooledTransactionsElementEcRecovered};
+use std::fmt;
+
+/// Context about the peer a transaction was received from.
+#[derive(Debug, Clone, Copy)]
+pub struct GossipPeer<'a> {
+    /// The id of the peer.
+    pub peer_id: &'a PeerId,
+    /// The negotiated version of the session.
+    pub version: EthVersion,
+    /// The peer's client version.
+    pub client_version: &'a str,
+    /// Whether the peer broadcast the transaction, or sent it as a response to our request.
+    pub is_broadcast: bool,
+}
+
+/// The outcome of validating a transaction received from a peer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GossipValidationOutcome {
+    /// Import the transaction into the pool.
+    Accept,
+    /// Drop the transaction without penalizing the peer.
+    Ignore,
+    /// Drop the transaction and apply the reputation change to the peer.
+    Reject(ReputationChangeKind),
+}
+
+// === impl GossipValidationOutcome ===
+
+impl GossipValidationOutcome {
+    /// Returns true if the transaction should be imported into the pool.
+    pub fn is_accept(&self) -> bool {
+        matches!(self, GossipValidationOutcome::Accept)
+    }
+}
+
+/// Validates transactions received from peers before they're imported into the pool.
+///
+/// The [`TransactionsManager`](crate::transactions::TransactionsManager) consults the validator
+/// for every recovered transaction that a peer broadcast or sent as a response, before it's handed
+/// to the pool. Unlike the validation of the pool, the validator knows which peer sent the
+/// transaction, so it can, for example, reject certain transaction types or enforce chain-specific
+/// rules and penalize the peer that sent them.
+pub trait TransactionGossipValidator: fmt::Debug + Send + Sync + Unpin + 'static {
+    /// Returns whether the transaction received from the given peer should be imported.
+    fn validate_gossip(
+        &self,
+        peer: GossipPeer<'_>,
+        transaction: &PooledTransactionsElementEcRecovered,
+    ) -> GossipValidationOutcome;
+}
+
+impl<T: TransactionGossipValidator + ?Sized> TransactionGossipValidator for Box<T> {
+    fn validate_gossip(
+        &self,
+        peer: GossipPeer<'_>,
+        transaction: &PooledTransactionsElementEcRecovered,
+    ) -> GossipValidationOutcome {
+        (**self).validate_gossip(peer, transaction)
+    }
+}
+
+/// The default validator, which accepts all transactions.
+#[derive(Debug, Clone, Copy, Default)]
+#[non_exhaustive]
+pub struct AcceptAllGossip;
+
+impl TransactionGossipValidator for AcceptAllGossip {
+    fn validate_gossip(
+        &self,
+        _peer: GossipPeer<'_>,
+        _transaction: &PooledTransactionsElementEcRecovered,
+    ) -> GossipValidationOutcome {
+        GossipValidationOutcome::Accept
+    }
+}
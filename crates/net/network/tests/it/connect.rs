@@ -11,7 +11,7 @@ use reth_interfaces::{
 };
 use reth_net_common::ban_list::BanList;
 use reth_network::{
-    test_utils::{enr_to_peer_id, NetworkEventStream, PeerConfig, Testnet, GETH_TIMEOUT},
+    test_utils::{enr_to_peer_id, NetworkEventStream, PeerConfig, Testnet, Topology, GETH_TIMEOUT},
     NetworkConfigBuilder, NetworkEvent, NetworkEvents, NetworkManager, PeersConfig,
 };
 use reth_network_api::{NetworkInfo, Peers, PeersInfo};
@@ -81,6 +81,39 @@ async fn test_establish_connections() {
     }
 }
 
+#[test]
+fn test_topology_edges() {
+    assert_eq!(Topology::Full.edges(3), vec![(0, 1), (0, 2), (1, 2)]);
+    assert_eq!(Topology::Ring.edges(3), vec![(0, 1), (0, 2), (1, 2)]);
+    assert_eq!(Topology::Ring.edges(2), vec![(0, 1)]);
+    assert_eq!(Topology::Line.edges(4), vec![(0, 1), (1, 2), (2, 3)]);
+    assert_eq!(Topology::Star(1).edges(3), vec![(0, 1), (1, 2)]);
+    assert_eq!(Topology::Custom(vec![(2, 0), (0, 2), (1, 2)]).edges(3), vec![(0, 2), (1, 2)]);
+    assert!(Topology::Full.edges(1).is_empty());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_connect_topology() {
+    reth_tracing::init_test_tracing();
+
+    let net = Testnet::create(4).await;
+    let handle = net.spawn();
+    handle.connect_topology(Topology::Ring).await;
+
+    // a session with the new neighbour is established on both ends
+    handle.connect(0, 2).await;
+    let peers = handle.peers();
+    for (idx, num) in [3, 2, 3, 2].into_iter().enumerate() {
+        assert_eq!(peers[idx].network().num_connected_peers(), num);
+    }
+
+    handle.disconnect(0, 2).await;
+
+    let net = handle.terminate().await;
+    assert_eq!(net.peers()[1].num_peers(), 2);
+    assert_eq!(net.peers()[3].num_peers(), 2);
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn test_already_connected() {
     reth_tracing::init_test_tracing();
@@ -193,6 +226,35 @@ async fn test_get_peer_by_id() {
     assert!(peer.is_none());
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn test_get_peer_info() {
+    reth_tracing::init_test_tracing();
+    let net = Testnet::create(2).await;
+
+    let mut handles = net.handles();
+    let handle0 = handles.next().unwrap();
+    let handle1 = handles.next().unwrap();
+
+    drop(handles);
+    let _handle = net.spawn();
+
+    let mut listener0 = NetworkEventStream::new(handle0.event_listener());
+
+    handle0.add_peer(*handle1.peer_id(), handle1.local_addr());
+    let _ = listener0.next_session_established().await.unwrap();
+
+    let info = handle0.get_peer_info(*handle1.peer_id()).await.unwrap().unwrap();
+    assert_eq!(info.remote_id, *handle1.peer_id());
+    assert_eq!(info.remote_addr, handle1.local_addr());
+    assert!(info.direction.is_outgoing());
+    assert!(info.capabilities.supports_eth());
+    assert!(info.connected_duration() < Duration::from_secs(60));
+
+    let all = handle0.get_all_peer_info().await.unwrap();
+    assert_eq!(all.len(), 1);
+    assert_eq!(all[0].remote_id, *handle1.peer_id());
+}
+
 #[tokio::test(flavor = "multi_thread")]
 #[ignore]
 async fn test_connect_with_boot_nodes() {
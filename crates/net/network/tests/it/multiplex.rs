@@ -9,13 +9,15 @@ use reth_eth_wire::{
 use reth_network::{
     protocol::{ConnectionHandler, OnNotSupported, ProtocolHandler},
     test_utils::Testnet,
+    NetworkConfigBuilder, NetworkManager,
 };
-use reth_network_api::Direction;
+use reth_network_api::{Direction, NetworkInfo, Peers};
 use reth_primitives::BytesMut;
-use reth_provider::test_utils::MockEthProvider;
+use reth_provider::test_utils::{MockEthProvider, NoopProvider};
 use reth_rpc_types::PeerId;
+use secp256k1::SecretKey;
 use std::{
-    net::SocketAddr,
+    net::{Ipv4Addr, SocketAddr, SocketAddrV4},
     pin::Pin,
     task::{ready, Context, Poll},
 };
@@ -327,3 +329,38 @@ async fn test_proto_multiplex() {
     let response = rx.await.unwrap();
     assert_eq!(response, "hello from peer1!");
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_proto_multiplex_with_builder() {
+    reth_tracing::init_test_tracing();
+
+    let mut handles = Vec::new();
+    let mut events = Vec::new();
+    for _ in 0..2 {
+        let secret_key = SecretKey::new(&mut rand::thread_rng());
+        let config = NetworkConfigBuilder::new(secret_key)
+            .listener_addr(SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 0)))
+            .disable_discovery()
+            .build(NoopProvider::default());
+
+        let (tx, rx) = mpsc::unbounded_channel();
+        let (handle, network, _, requests) = NetworkManager::builder(config)
+            .await
+            .unwrap()
+            .add_rlpx_sub_protocol(PingPongProtoHandler { state: ProtocolState { events: tx } })
+            .request_handler(NoopProvider::default())
+            .split_with_handle();
+        tokio::task::spawn(network);
+        tokio::task::spawn(requests);
+
+        handles.push(handle);
+        events.push(rx);
+    }
+
+    handles[0].add_peer(*handles[1].peer_id(), handles[1].local_addr());
+
+    for (idx, rx) in events.iter_mut().enumerate() {
+        let ProtocolEvent::Established { peer_id, .. } = rx.recv().await.unwrap();
+        assert_eq!(peer_id, *handles[1 - idx].peer_id());
+    }
+}
@@ -4,7 +4,7 @@ use futures::StreamExt;
 use reth_eth_wire::EthVersion;
 use reth_network::{
     test_utils::{PeerConfig, Testnet},
-    NetworkEvent, NetworkEvents,
+    NetworkEvent, NetworkEvents, PeerSessionEvent,
 };
 use reth_network_api::{NetworkInfo, Peers};
 use reth_provider::test_utils::NoopProvider;
@@ -33,7 +33,7 @@ async fn test_session_established_with_highest_version() {
             }
             NetworkEvent::SessionEstablished { peer_id, status, .. } => {
                 assert_eq!(handle1.peer_id(), &peer_id);
-                assert_eq!(status.version, EthVersion::Eth68 as u8);
+                assert_eq!(status.version, EthVersion::Eth69 as u8);
             }
             ev => {
                 panic!("unexpected event {ev:?}")
@@ -81,3 +81,39 @@ async fn test_session_established_with_different_capability() {
 
     handle.terminate().await;
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_session_event_listener() {
+    reth_tracing::init_test_tracing();
+
+    let net = Testnet::create(2).await;
+
+    let mut handles = net.handles();
+    let handle0 = handles.next().unwrap();
+    let handle1 = handles.next().unwrap();
+    drop(handles);
+
+    let handle = net.spawn();
+
+    let mut events = handle0.session_event_listener();
+    handle0.add_peer(*handle1.peer_id(), handle1.local_addr());
+
+    match events.next().await.unwrap().unwrap() {
+        PeerSessionEvent::Established(info) => {
+            assert_eq!(info.remote_id, *handle1.peer_id());
+            assert_eq!(info.eth_version, EthVersion::Eth69);
+        }
+        ev => panic!("unexpected event: {ev:?}"),
+    }
+
+    handle0.disconnect_peer(*handle1.peer_id());
+
+    match events.next().await.unwrap().unwrap() {
+        PeerSessionEvent::Closed { info, .. } => {
+            assert_eq!(info.remote_id, *handle1.peer_id());
+        }
+        ev => panic!("unexpected event: {ev:?}"),
+    }
+
+    handle.terminate().await;
+}
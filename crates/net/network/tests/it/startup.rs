@@ -1,9 +1,13 @@
 use reth_discv4::Discv4Config;
+use reth_eth_wire::{
+    capability::Capability, protocol::Protocol, EthVersion, HelloMessageWithProtocols,
+};
 use reth_network::{
     error::{NetworkError, ServiceKind},
+    test_utils::enr_to_peer_id,
     Discovery, NetworkConfigBuilder, NetworkManager,
 };
-use reth_network_api::NetworkInfo;
+use reth_network_api::{NetworkInfo, PeersInfo};
 use reth_provider::test_utils::NoopProvider;
 use secp256k1::SecretKey;
 use std::{
@@ -32,6 +36,26 @@ async fn test_is_default_syncing() {
     assert!(!network.handle().is_syncing());
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn test_local_enr() {
+    let secret_key = SecretKey::new(&mut rand::thread_rng());
+    let config = NetworkConfigBuilder::new(secret_key)
+        .disable_discovery()
+        .listener_port(0)
+        .build(NoopProvider::default());
+    let network = NetworkManager::new(config).await.unwrap();
+    let handle = network.handle();
+
+    let record = handle.local_node_record();
+    let enr = handle.local_enr();
+    assert_eq!(enr.ip4(), Some(Ipv4Addr::LOCALHOST));
+    assert_eq!(enr.tcp4(), Some(record.tcp_port));
+    assert_eq!(enr.udp4(), Some(record.udp_port));
+
+    let enr = enr.to_base64().parse().unwrap();
+    assert_eq!(enr_to_peer_id(enr), record.id);
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn test_listener_addr_in_use() {
     let secret_key = SecretKey::new(&mut rand::thread_rng());
@@ -59,8 +83,36 @@ async fn test_discovery_addr_in_use() {
     let any_port_listener = TcpListener::bind(addr).await.unwrap();
     let port = any_port_listener.local_addr().unwrap().port();
     let addr = SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::UNSPECIFIED, port));
-    let _discovery = Discovery::new(addr, secret_key, Some(disc_config), None).await.unwrap();
+    let _discovery = Discovery::new(addr, secret_key, Some(disc_config), None, None).await.unwrap();
     let disc_config = Discv4Config::default();
-    let result = Discovery::new(addr, secret_key, Some(disc_config), None).await;
+    let result = Discovery::new(addr, secret_key, Some(disc_config), None, None).await;
     assert!(is_addr_in_use_kind(&result.err().unwrap(), ServiceKind::Discovery(addr)));
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_reject_hello_without_eth() {
+    let secret_key = SecretKey::new(&mut rand::thread_rng());
+    let config = NetworkConfigBuilder::new(secret_key)
+        .disable_discovery()
+        .listener_port(0)
+        .disable_eth_version(EthVersion::Eth66)
+        .disable_eth_version(EthVersion::Eth67)
+        .disable_eth_version(EthVersion::Eth68)
+        .disable_eth_version(EthVersion::Eth69)
+        .build(NoopProvider::default());
+    let err = NetworkManager::new(config).await.err().unwrap();
+    assert!(matches!(err, NetworkError::InvalidHelloMessage(_)), "{:?}", err);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_reject_unhandled_capability() {
+    let secret_key = SecretKey::new(&mut rand::thread_rng());
+    let builder = NetworkConfigBuilder::new(secret_key).disable_discovery().listener_port(0);
+    let hello = HelloMessageWithProtocols::builder(builder.get_peer_id())
+        .protocol(EthVersion::Eth68)
+        .protocol(Protocol::new(Capability::new_static("snap", 1), 8))
+        .build();
+    let config = builder.hello_message(hello).build(NoopProvider::default());
+    let err = NetworkManager::new(config).await.err().unwrap();
+    assert!(matches!(err, NetworkError::InvalidHelloMessage(_)), "{:?}", err);
+}
@@ -1,10 +1,11 @@
 //! Testing gossiping of transactions.
 
 use rand::thread_rng;
-use reth_network::test_utils::Testnet;
+use reth_network::test_utils::{Testnet, Topology};
 use reth_primitives::U256;
 use reth_provider::test_utils::{ExtendedAccount, MockEthProvider};
 use reth_transaction_pool::{test_utils::TransactionGenerator, PoolTransaction, TransactionPool};
+use std::time::Duration;
 #[tokio::test(flavor = "multi_thread")]
 async fn test_tx_gossip() {
     reth_tracing::init_test_tracing();
@@ -42,3 +43,27 @@ async fn test_tx_gossip() {
     let received = peer1_tx_listener.recv().await.unwrap();
     assert_eq!(received, hash);
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_tx_gossip_line() {
+    reth_tracing::init_test_tracing();
+
+    let provider = MockEthProvider::default();
+    let net = Testnet::create_with(3, provider.clone()).await;
+
+    let net = net.with_eth_pool();
+    let handle = net.spawn();
+    // peer0 and peer2 are only connected through peer1
+    handle.connect_topology(Topology::Line).await;
+
+    let mut gen = TransactionGenerator::new(thread_rng());
+    let tx = gen.gen_eip1559_pooled();
+    let sender = tx.sender();
+    provider.add_account(sender, ExtendedAccount::new(0, U256::from(100_000_000)));
+
+    let peer0_pool = handle.peers()[0].pool().unwrap();
+    let hash = peer0_pool.add_external_transaction(tx).await.unwrap();
+
+    // ensure the tx is relayed by peer1
+    handle.assert_transaction_propagated(hash, Duration::from_secs(10)).await;
+}
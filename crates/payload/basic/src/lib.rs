@@ -20,8 +20,9 @@ use reth_payload_builder::{
 use reth_primitives::{
     bytes::BytesMut,
     constants::{
+        eip4844::{DATA_GAS_PER_BLOB, MAX_BLOBS_PER_BLOCK},
         BEACON_NONCE, EMPTY_RECEIPTS, EMPTY_TRANSACTIONS, EMPTY_WITHDRAWALS,
-        ETHEREUM_BLOCK_GAS_LIMIT, RETH_CLIENT_VERSION, SLOT_DURATION,
+        ETHEREUM_BLOCK_GAS_LIMIT, MIN_GAS_LIMIT, RETH_CLIENT_VERSION, SLOT_DURATION,
     },
     proofs, Block, BlockNumberOrTag, Bytes, ChainSpec, Header, Receipts, SealedBlock, Withdrawal,
     B256, EMPTY_OMMER_ROOT_HASH, U256,
@@ -55,6 +56,11 @@ use tokio::{
 use tracing::{debug, trace, warn};
 
 mod metrics;
+mod selection;
+pub use selection::{
+    BestTransactionFilter, BestTransactionsFor, DefaultTransactionSelection, ExcludeSenders,
+    SenderGasQuota, TransactionSelection, TransactionSelectionPolicy,
+};
 
 /// The [`PayloadJobGenerator`] that creates [`BasicPayloadJob`]s.
 #[derive(Debug)]
@@ -178,12 +184,20 @@ where
             block.seal(attributes.parent())
         };
 
-        let config = PayloadConfig::new(
+        let mut config = PayloadConfig::new(
             Arc::new(parent_block),
             self.config.extradata.clone(),
             attributes,
             Arc::clone(&self.chain_spec),
-        );
+        )
+        .with_max_blobs_per_block(self.config.max_blobs_per_block);
+
+        // the gas limit of optimism blocks is set by the payload attributes
+        if !self.chain_spec.is_optimism() {
+            let gas_limit =
+                next_block_gas_limit(config.parent_block.gas_limit, self.config.max_gas_limit);
+            config.initialized_block_env.gas_limit = U256::from(gas_limit);
+        }
 
         let until = self.job_deadline(config.attributes.timestamp());
         let deadline = Box::pin(tokio::time::sleep_until(until));
@@ -197,6 +211,7 @@ where
             executor: self.executor.clone(),
             deadline,
             interval: tokio::time::interval(self.config.interval),
+            resolve_policy: self.config.resolve_policy,
             best_payload: None,
             pending_block: None,
             cached_reads,
@@ -238,6 +253,25 @@ pub struct PrecachedState {
     pub cached: CachedReads,
 }
 
+/// Determines how a [BasicPayloadJob] resolves while a payload build is in progress.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PayloadResolvePolicy {
+    /// Resolve immediately with the best payload built so far, unless the payload build in
+    /// progress finishes first.
+    ///
+    /// If no payload has been built yet, an empty payload is built and returned, which favors
+    /// latency over revenue.
+    #[default]
+    Immediate,
+    /// Wait for the payload build in progress and resolve with its payload, if it's better than
+    /// the best payload built so far.
+    ///
+    /// An empty payload is only returned if no payload has been built yet and the payload build in
+    /// progress fails. This favors revenue over latency, so the interval should be set so that a
+    /// single build finishes well within the time the consensus layer waits for the payload.
+    WaitForPendingBuild,
+}
+
 /// Restricts how many generator tasks can be executed at once.
 #[derive(Debug, Clone)]
 struct PayloadTaskGuard(Arc<Semaphore>);
@@ -257,6 +291,10 @@ pub struct BasicPayloadJobGeneratorConfig {
     extradata: Bytes,
     /// Target gas ceiling for built blocks, defaults to [ETHEREUM_BLOCK_GAS_LIMIT] gas.
     max_gas_limit: u64,
+    /// Maximum number of blobs to include in built blocks, defaults to [MAX_BLOBS_PER_BLOCK].
+    max_blobs_per_block: u64,
+    /// How the payload job resolves while a payload build is in progress.
+    resolve_policy: PayloadResolvePolicy,
     /// The interval at which the job should build a new payload after the last.
     interval: Duration,
     /// The deadline for when the payload builder job should resolve.
@@ -303,11 +341,38 @@ impl BasicPayloadJobGeneratorConfig {
 
     /// Sets the target gas ceiling for mined blocks.
     ///
+    /// The gas limit of a built block moves from the gas limit of its parent towards the target, by
+    /// the maximum change that is allowed per block.
+    ///
     /// Defaults to [ETHEREUM_BLOCK_GAS_LIMIT] gas.
     pub fn max_gas_limit(mut self, max_gas_limit: u64) -> Self {
         self.max_gas_limit = max_gas_limit;
         self
     }
+
+    /// Sets the maximum number of blobs to include in built blocks.
+    ///
+    /// Defaults to [MAX_BLOBS_PER_BLOCK].
+    ///
+    /// # Panics
+    ///
+    /// If `max_blobs_per_block` exceeds [MAX_BLOBS_PER_BLOCK].
+    pub fn max_blobs_per_block(mut self, max_blobs_per_block: u64) -> Self {
+        assert!(
+            max_blobs_per_block <= MAX_BLOBS_PER_BLOCK as u64,
+            "max_blobs_per_block must not exceed {MAX_BLOBS_PER_BLOCK}"
+        );
+        self.max_blobs_per_block = max_blobs_per_block;
+        self
+    }
+
+    /// Sets how the payload job resolves while a payload build is in progress.
+    ///
+    /// Defaults to [PayloadResolvePolicy::Immediate].
+    pub fn resolve_policy(mut self, resolve_policy: PayloadResolvePolicy) -> Self {
+        self.resolve_policy = resolve_policy;
+        self
+    }
 }
 
 impl Default for BasicPayloadJobGeneratorConfig {
@@ -317,6 +382,8 @@ impl Default for BasicPayloadJobGeneratorConfig {
         Self {
             extradata: extradata.freeze().into(),
             max_gas_limit: ETHEREUM_BLOCK_GAS_LIMIT,
+            max_blobs_per_block: MAX_BLOBS_PER_BLOCK as u64,
+            resolve_policy: PayloadResolvePolicy::default(),
             interval: Duration::from_secs(1),
             // 12s slot time
             deadline: SLOT_DURATION,
@@ -343,6 +410,8 @@ where
     deadline: Pin<Box<Sleep>>,
     /// The interval at which the job should build a new payload after the last.
     interval: Interval,
+    /// How the job resolves while a payload build is in progress.
+    resolve_policy: PayloadResolvePolicy,
     /// The best payload so far.
     best_payload: Option<Builder::BuiltPayload>,
     /// Receiver for the block that is currently being built.
@@ -488,6 +557,7 @@ where
         let best_payload = self.best_payload.take();
         let maybe_better = self.pending_block.take();
         let mut empty_payload = None;
+        let wait_for_better = self.resolve_policy == PayloadResolvePolicy::WaitForPendingBuild;
 
         if best_payload.is_none() {
             debug!(target: "payload_builder", id=%self.config.payload_id(), "no best payload yet to resolve, building empty payload");
@@ -507,7 +577,12 @@ where
             if let Some(payload) = self.builder.on_missing_payload(args) {
                 debug!(target: "payload_builder", id=%self.config.payload_id(), "resolving fallback payload as best payload");
                 return (
-                    ResolveBestPayload { best_payload: Some(payload), maybe_better, empty_payload },
+                    ResolveBestPayload {
+                        best_payload: Some(payload),
+                        maybe_better,
+                        wait_for_better,
+                        empty_payload,
+                    },
                     KeepPayloadJobAlive::Yes,
                 )
             }
@@ -526,7 +601,7 @@ where
             empty_payload = Some(rx);
         }
 
-        let fut = ResolveBestPayload { best_payload, maybe_better, empty_payload };
+        let fut = ResolveBestPayload { best_payload, maybe_better, wait_for_better, empty_payload };
 
         (fut, KeepPayloadJobAlive::No)
     }
@@ -541,12 +616,17 @@ where
 ///
 /// If no payload has been built so far, it will either return an empty payload or the result of the
 /// in progress build job, whatever finishes first.
+///
+/// With [PayloadResolvePolicy::WaitForPendingBuild], this always waits for the in progress build
+/// job before it returns the best or empty payload.
 #[derive(Debug)]
 pub struct ResolveBestPayload<Payload> {
     /// Best payload so far.
     best_payload: Option<Payload>,
     /// Regular payload job that's currently running that might produce a better payload.
     maybe_better: Option<PendingPayload<Payload>>,
+    /// Whether to wait for `maybe_better` before returning another payload.
+    wait_for_better: bool,
     /// The empty payload building job in progress.
     empty_payload: Option<oneshot::Receiver<Result<Payload, PayloadBuilderError>>>,
 }
@@ -562,12 +642,16 @@ where
 
         // check if there is a better payload before returning the best payload
         if let Some(fut) = Pin::new(&mut this.maybe_better).as_pin_mut() {
-            if let Poll::Ready(res) = fut.poll(cx) {
-                this.maybe_better = None;
-                if let Ok(BuildOutcome::Better { payload, .. }) = res {
-                    debug!(target: "payload_builder", "resolving better payload");
-                    return Poll::Ready(Ok(payload))
+            match fut.poll(cx) {
+                Poll::Ready(res) => {
+                    this.maybe_better = None;
+                    if let Ok(BuildOutcome::Better { payload, .. }) = res {
+                        debug!(target: "payload_builder", "resolving better payload");
+                        return Poll::Ready(Ok(payload))
+                    }
                 }
+                Poll::Pending if this.wait_for_better => return Poll::Pending,
+                Poll::Pending => {}
             }
         }
 
@@ -649,6 +733,8 @@ pub struct PayloadConfig<Attributes> {
     pub attributes: Attributes,
     /// The chain spec.
     pub chain_spec: Arc<ChainSpec>,
+    /// Maximum number of blobs to include in the payload.
+    pub max_blobs_per_block: u64,
 }
 
 impl<Attributes> PayloadConfig<Attributes> {
@@ -656,6 +742,19 @@ impl<Attributes> PayloadConfig<Attributes> {
     pub fn extra_data(&self) -> Bytes {
         self.extra_data.clone()
     }
+
+    /// Sets the maximum number of blobs to include in the payload.
+    ///
+    /// Defaults to [MAX_BLOBS_PER_BLOCK].
+    pub fn with_max_blobs_per_block(mut self, max_blobs_per_block: u64) -> Self {
+        self.max_blobs_per_block = max_blobs_per_block;
+        self
+    }
+
+    /// Returns the maximum blob gas of the payload.
+    pub fn max_blob_gas(&self) -> u64 {
+        self.max_blobs_per_block * DATA_GAS_PER_BLOB
+    }
 }
 
 impl<Attributes> PayloadConfig<Attributes>
@@ -680,6 +779,7 @@ where
             extra_data,
             attributes,
             chain_spec,
+            max_blobs_per_block: MAX_BLOBS_PER_BLOCK as u64,
         }
     }
 
@@ -996,6 +1096,21 @@ pub fn is_better_payload(best_payload: Option<&EthBuiltPayload>, new_fees: U256)
     }
 }
 
+/// Returns the gas limit of a block with the given parent gas limit, which moves towards the target
+/// gas limit by the maximum change that is allowed per block.
+///
+/// The target is clamped to [MIN_GAS_LIMIT], so the gas limit never drops below it.
+pub fn next_block_gas_limit(parent_gas_limit: u64, target_gas_limit: u64) -> u64 {
+    let target_gas_limit = target_gas_limit.max(MIN_GAS_LIMIT);
+    // the gas limit must change by less than 1/1024 of the parent gas limit
+    let max_change = (parent_gas_limit / 1024).saturating_sub(1);
+    if target_gas_limit > parent_gas_limit {
+        target_gas_limit.min(parent_gas_limit + max_change)
+    } else {
+        target_gas_limit.max(parent_gas_limit - max_change)
+    }
+}
+
 /// Returns the duration until the given unix timestamp in seconds.
 ///
 /// Returns `Duration::ZERO` if the given timestamp is in the past.
@@ -1004,3 +1119,25 @@ fn duration_until(unix_timestamp_secs: u64) -> Duration {
     let timestamp = Duration::from_secs(unix_timestamp_secs);
     timestamp.saturating_sub(unix_now)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gas_limit_moves_towards_target() {
+        let parent = 30_000_000;
+        assert_eq!(next_block_gas_limit(parent, parent), parent);
+        assert_eq!(next_block_gas_limit(parent, parent + 100), parent + 100);
+        assert_eq!(next_block_gas_limit(parent, 36_000_000), parent + parent / 1024 - 1);
+        assert_eq!(next_block_gas_limit(parent, 15_000_000), parent - parent / 1024 + 1);
+    }
+
+    #[test]
+    fn gas_limit_clamped_to_minimum() {
+        assert_eq!(next_block_gas_limit(MIN_GAS_LIMIT, 0), MIN_GAS_LIMIT);
+        assert_eq!(next_block_gas_limit(MIN_GAS_LIMIT + 1, 1000), MIN_GAS_LIMIT);
+        let parent = 2 * MIN_GAS_LIMIT;
+        assert_eq!(next_block_gas_limit(parent, 0), parent - parent / 1024 + 1);
+    }
+}
@@ -0,0 +1,381 @@
+//! Policies for the selection of pool transactions during payload building.
+
+use reth_primitives::Address;
+use reth_transaction_pool::{
+    BestTransactions, BestTransactionsAttributes, PoolTransaction, ValidPoolTransaction,
+};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+    sync::Arc,
+};
+
+/// The iterator over the best transactions of a pool with transactions of type `T`.
+pub type BestTransactionsFor<T> = Box<dyn BestTransactions<Item = Arc<ValidPoolTransaction<T>>>>;
+
+/// Controls which pool transactions are considered during payload building, and in which order.
+///
+/// The policy is object-safe, so a node can be configured with an
+/// `Arc<dyn TransactionSelectionPolicy<T>>`.
+pub trait TransactionSelectionPolicy<T: PoolTransaction>: fmt::Debug + Send + Sync {
+    /// Returns the transactions to consider for a payload with the given attributes, in order of
+    /// inclusion, selected from the best transactions of the pool.
+    fn select(
+        &self,
+        best: BestTransactionsFor<T>,
+        attributes: BestTransactionsAttributes,
+    ) -> Box<dyn TransactionSelection<T>>;
+
+    /// Returns true if the bundles that target the block are included before the transactions.
+    ///
+    /// Defaults to true.
+    fn include_bundles(&self) -> bool {
+        true
+    }
+}
+
+impl<T, P> TransactionSelectionPolicy<T> for Arc<P>
+where
+    T: PoolTransaction,
+    P: TransactionSelectionPolicy<T> + ?Sized,
+{
+    fn select(
+        &self,
+        best: BestTransactionsFor<T>,
+        attributes: BestTransactionsAttributes,
+    ) -> Box<dyn TransactionSelection<T>> {
+        (**self).select(best, attributes)
+    }
+
+    fn include_bundles(&self) -> bool {
+        (**self).include_bundles()
+    }
+}
+
+/// The transactions that are considered for a single payload, see
+/// [TransactionSelectionPolicy::select].
+///
+/// The payload builder tries to include the transactions in order, as long as they fit into the
+/// block and can be executed. Transactions that can't be included are marked as invalid, see
+/// [BestTransactions::mark_invalid].
+pub trait TransactionSelection<T: PoolTransaction>:
+    BestTransactions<Item = Arc<ValidPoolTransaction<T>>>
+{
+    /// Called by the payload builder once the transaction was included in the payload, with the
+    /// gas it used.
+    fn on_included(&mut self, transaction: &ValidPoolTransaction<T>, gas_used: u64) {
+        let _ = transaction;
+        let _ = gas_used;
+    }
+}
+
+impl<T: PoolTransaction> TransactionSelection<T> for BestTransactionsFor<T> {}
+
+/// The default [TransactionSelectionPolicy], which considers all transactions that satisfy the
+/// base fee, in the order of the pool.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[non_exhaustive]
+pub struct DefaultTransactionSelection;
+
+impl<T: PoolTransaction + 'static> TransactionSelectionPolicy<T> for DefaultTransactionSelection {
+    fn select(
+        &self,
+        best: BestTransactionsFor<T>,
+        _attributes: BestTransactionsAttributes,
+    ) -> Box<dyn TransactionSelection<T>> {
+        Box::new(best)
+    }
+}
+
+/// A [TransactionSelectionPolicy] that excludes the transactions of the given senders.
+#[derive(Debug, Clone, Default)]
+pub struct ExcludeSenders {
+    senders: Arc<HashSet<Address>>,
+}
+
+impl ExcludeSenders {
+    /// Creates a new policy that excludes the transactions of the given senders.
+    pub fn new(senders: impl IntoIterator<Item = Address>) -> Self {
+        Self { senders: Arc::new(senders.into_iter().collect()) }
+    }
+}
+
+impl<T: PoolTransaction + 'static> TransactionSelectionPolicy<T> for ExcludeSenders {
+    fn select(
+        &self,
+        best: BestTransactionsFor<T>,
+        _attributes: BestTransactionsAttributes,
+    ) -> Box<dyn TransactionSelection<T>> {
+        let senders = Arc::clone(&self.senders);
+        Box::new(BestTransactionFilter::new(best, move |tx: &Arc<ValidPoolTransaction<T>>| {
+            !senders.contains(&tx.sender())
+        }))
+    }
+}
+
+/// A [TransactionSelectionPolicy] that limits the total gas used by the transactions of a single
+/// sender in a payload.
+///
+/// A transaction is only considered if its gas limit fits into the remaining quota of its sender,
+/// and only the gas of included transactions counts against the quota.
+#[derive(Debug, Clone, Copy)]
+pub struct SenderGasQuota {
+    max_gas_per_sender: u64,
+}
+
+impl SenderGasQuota {
+    /// Creates a new policy that includes transactions of a sender that use at most
+    /// `max_gas_per_sender` in total.
+    pub fn new(max_gas_per_sender: u64) -> Self {
+        Self { max_gas_per_sender }
+    }
+}
+
+impl<T: PoolTransaction + 'static> TransactionSelectionPolicy<T> for SenderGasQuota {
+    fn select(
+        &self,
+        best: BestTransactionsFor<T>,
+        _attributes: BestTransactionsAttributes,
+    ) -> Box<dyn TransactionSelection<T>> {
+        Box::new(SenderGasQuotaSelection {
+            best,
+            max_gas_per_sender: self.max_gas_per_sender,
+            gas_per_sender: HashMap::new(),
+        })
+    }
+}
+
+/// The [TransactionSelection] of [SenderGasQuota].
+struct SenderGasQuotaSelection<T: PoolTransaction> {
+    best: BestTransactionsFor<T>,
+    max_gas_per_sender: u64,
+    /// The gas used by the included transactions of every sender.
+    gas_per_sender: HashMap<Address, u64>,
+}
+
+impl<T: PoolTransaction> Iterator for SenderGasQuotaSelection<T> {
+    type Item = Arc<ValidPoolTransaction<T>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let best = self.best.next()?;
+            let gas_used = self.gas_per_sender.get(&best.sender()).copied().unwrap_or_default();
+            if gas_used + best.gas_limit() <= self.max_gas_per_sender {
+                return Some(best)
+            }
+            // the descendants of the transaction can't be included either
+            self.best.mark_invalid(&best);
+        }
+    }
+}
+
+impl<T: PoolTransaction> BestTransactions for SenderGasQuotaSelection<T> {
+    fn mark_invalid(&mut self, transaction: &Self::Item) {
+        self.best.mark_invalid(transaction)
+    }
+
+    fn no_updates(&mut self) {
+        self.best.no_updates()
+    }
+
+    fn skip_blobs(&mut self) {
+        self.best.skip_blobs()
+    }
+
+    fn set_skip_blobs(&mut self, skip_blobs: bool) {
+        self.best.set_skip_blobs(skip_blobs)
+    }
+}
+
+impl<T: PoolTransaction> TransactionSelection<T> for SenderGasQuotaSelection<T> {
+    fn on_included(&mut self, transaction: &ValidPoolTransaction<T>, gas_used: u64) {
+        *self.gas_per_sender.entry(transaction.sender()).or_default() += gas_used;
+    }
+}
+
+/// A [BestTransactions] iterator that only yields the transactions that satisfy the predicate.
+///
+/// Rejected transactions are marked as invalid, so that their descendants are skipped as well.
+pub struct BestTransactionFilter<I, P> {
+    best: I,
+    predicate: P,
+}
+
+impl<I, P> BestTransactionFilter<I, P> {
+    /// Creates a new filter over the given best transactions.
+    pub fn new(best: I, predicate: P) -> Self {
+        Self { best, predicate }
+    }
+}
+
+impl<I, P> Iterator for BestTransactionFilter<I, P>
+where
+    I: BestTransactions,
+    P: FnMut(&I::Item) -> bool,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let best = self.best.next()?;
+            if (self.predicate)(&best) {
+                return Some(best)
+            }
+            self.best.mark_invalid(&best);
+        }
+    }
+}
+
+impl<I, P> BestTransactions for BestTransactionFilter<I, P>
+where
+    I: BestTransactions,
+    P: FnMut(&I::Item) -> bool + Send,
+{
+    fn mark_invalid(&mut self, transaction: &Self::Item) {
+        self.best.mark_invalid(transaction)
+    }
+
+    fn no_updates(&mut self) {
+        self.best.no_updates()
+    }
+
+    fn skip_blobs(&mut self) {
+        self.best.skip_blobs()
+    }
+
+    fn set_skip_blobs(&mut self, skip_blobs: bool) {
+        self.best.set_skip_blobs(skip_blobs)
+    }
+}
+
+impl<T, I, P> TransactionSelection<T> for BestTransactionFilter<I, P>
+where
+    T: PoolTransaction,
+    I: TransactionSelection<T>,
+    P: FnMut(&Arc<ValidPoolTransaction<T>>) -> bool + Send,
+{
+    fn on_included(&mut self, transaction: &ValidPoolTransaction<T>, gas_used: u64) {
+        self.best.on_included(transaction, gas_used)
+    }
+}
+
+impl<I: fmt::Debug, P> fmt::Debug for BestTransactionFilter<I, P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BestTransactionFilter").field("best", &self.best).finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_transaction_pool::test_utils::{MockTransaction, MockTransactionFactory};
+    use std::collections::VecDeque;
+
+    /// Yields the given transactions in order, and skips the descendants of invalid ones.
+    struct MockBestTransactions {
+        transactions: VecDeque<Arc<ValidPoolTransaction<MockTransaction>>>,
+        invalid: HashSet<Address>,
+    }
+
+    impl Iterator for MockBestTransactions {
+        type Item = Arc<ValidPoolTransaction<MockTransaction>>;
+
+        fn next(&mut self) -> Option<Self::Item> {
+            let invalid = &self.invalid;
+            while let Some(tx) = self.transactions.pop_front() {
+                if !invalid.contains(&tx.sender()) {
+                    return Some(tx)
+                }
+            }
+            None
+        }
+    }
+
+    impl BestTransactions for MockBestTransactions {
+        fn mark_invalid(&mut self, transaction: &Self::Item) {
+            self.invalid.insert(transaction.sender());
+        }
+
+        fn no_updates(&mut self) {}
+
+        fn skip_blobs(&mut self) {}
+
+        fn set_skip_blobs(&mut self, _skip_blobs: bool) {}
+    }
+
+    /// Returns the best transactions of two senders with three transactions each, alternating
+    /// between the senders, with a gas limit of 100 each.
+    fn best_transactions() -> BestTransactionsFor<MockTransaction> {
+        let mut factory = MockTransactionFactory::default();
+        let mut transactions = VecDeque::new();
+        let first = MockTransaction::eip1559().with_sender(Address::with_last_byte(1));
+        let second = MockTransaction::eip1559().with_sender(Address::with_last_byte(2));
+        for (first, second) in [first.clone(), first.next(), first.next().next()].into_iter().zip([
+            second.clone(),
+            second.next(),
+            second.next().next(),
+        ]) {
+            transactions.push_back(factory.validated_arc(first.with_gas_limit(100)));
+            transactions.push_back(factory.validated_arc(second.with_gas_limit(100)));
+        }
+        Box::new(MockBestTransactions { transactions, invalid: HashSet::new() })
+    }
+
+    fn senders(
+        selection: impl Iterator<Item = Arc<ValidPoolTransaction<MockTransaction>>>,
+    ) -> Vec<u8> {
+        selection.map(|tx| tx.sender().0[19]).collect()
+    }
+
+    #[test]
+    fn select_all_by_default() {
+        let selection = DefaultTransactionSelection
+            .select(best_transactions(), BestTransactionsAttributes::base_fee(0));
+        assert_eq!(senders(selection), vec![1, 2, 1, 2, 1, 2]);
+    }
+
+    #[test]
+    fn exclude_senders() {
+        let policy: Arc<dyn TransactionSelectionPolicy<MockTransaction>> =
+            Arc::new(ExcludeSenders::new([Address::with_last_byte(1)]));
+        let selection = policy.select(best_transactions(), BestTransactionsAttributes::base_fee(0));
+        assert_eq!(senders(selection), vec![2, 2, 2]);
+    }
+
+    #[test]
+    fn count_only_included_transactions_against_quota() {
+        let mut selection = SenderGasQuota::new(200)
+            .select(best_transactions(), BestTransactionsAttributes::base_fee(0));
+
+        // the first transaction of the first sender is not included, e.g. because its nonce is
+        // too low, so it doesn't count against the quota
+        let skipped = selection.next().unwrap();
+        assert_eq!(skipped.sender(), Address::with_last_byte(1));
+
+        let mut included = Vec::new();
+        while let Some(tx) = selection.next() {
+            if tx.sender() == Address::with_last_byte(1) {
+                selection.on_included(&tx, tx.gas_limit());
+            } else {
+                // only uses half of its gas limit
+                selection.on_included(&tx, tx.gas_limit() / 2);
+            }
+            included.push(tx.sender().0[19]);
+        }
+        // the first sender reaches the quota with two transactions, the second sender gets all
+        // three transactions since it used less gas
+        assert_eq!(included, vec![2, 1, 2, 1, 2]);
+    }
+
+    #[test]
+    fn skip_descendants_over_quota() {
+        let mut selection = SenderGasQuota::new(100)
+            .select(best_transactions(), BestTransactionsAttributes::base_fee(0));
+        let mut included = Vec::new();
+        while let Some(tx) = selection.next() {
+            selection.on_included(&tx, tx.gas_limit());
+            included.push(tx.sender().0[19]);
+        }
+        assert_eq!(included, vec![1, 2]);
+    }
+}
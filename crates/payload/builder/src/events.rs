@@ -0,0 +1,35 @@
+//! Events emitted by the [PayloadBuilderService](crate::PayloadBuilderService).
+
+use reth_node_api::EngineTypes;
+use std::fmt;
+use tokio::sync::broadcast;
+
+/// The capacity of the payload events channel.
+pub(crate) const PAYLOAD_EVENTS_CHANNEL_CAPACITY: usize = 16;
+
+/// An event emitted by the [PayloadBuilderService](crate::PayloadBuilderService).
+pub enum PayloadEvent<Engine: EngineTypes> {
+    /// A new payload job was started for the given attributes.
+    Attributes(Engine::PayloadBuilderAttributes),
+}
+
+impl<Engine: EngineTypes> Clone for PayloadEvent<Engine> {
+    fn clone(&self) -> Self {
+        match self {
+            PayloadEvent::Attributes(attributes) => PayloadEvent::Attributes(attributes.clone()),
+        }
+    }
+}
+
+impl<Engine: EngineTypes> fmt::Debug for PayloadEvent<Engine> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PayloadEvent::Attributes(attributes) => {
+                f.debug_tuple("Attributes").field(attributes).finish()
+            }
+        }
+    }
+}
+
+/// A receiver of [PayloadEvent]s.
+pub type PayloadEvents<Engine> = broadcast::Receiver<PayloadEvent<Engine>>;
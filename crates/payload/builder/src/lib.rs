@@ -102,6 +102,7 @@
 
 pub mod database;
 pub mod error;
+mod events;
 mod metrics;
 mod optimism;
 mod payload;
@@ -113,6 +114,7 @@ pub mod noop;
 #[cfg(any(test, feature = "test-utils"))]
 pub mod test_utils;
 
+pub use events::{PayloadEvent, PayloadEvents};
 pub use optimism::OptimismPayloadBuilderAttributes;
 pub use payload::{EthBuiltPayload, EthPayloadBuilderAttributes};
 pub use reth_rpc_types::engine::PayloadId;
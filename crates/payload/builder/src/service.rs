@@ -4,7 +4,10 @@
 //! Once a new payload is created, it is continuously updated.
 
 use crate::{
-    error::PayloadBuilderError, metrics::PayloadBuilderServiceMetrics, traits::PayloadJobGenerator,
+    error::PayloadBuilderError,
+    events::{PayloadEvent, PayloadEvents, PAYLOAD_EVENTS_CHANNEL_CAPACITY},
+    metrics::PayloadBuilderServiceMetrics,
+    traits::PayloadJobGenerator,
     KeepPayloadJobAlive, PayloadJob,
 };
 use futures_util::{future::FutureExt, Stream, StreamExt};
@@ -17,7 +20,7 @@ use std::{
     pin::Pin,
     task::{Context, Poll},
 };
-use tokio::sync::{mpsc, oneshot};
+use tokio::sync::{broadcast, mpsc, oneshot};
 use tokio_stream::wrappers::UnboundedReceiverStream;
 use tracing::{debug, info, trace, warn};
 
@@ -63,6 +66,11 @@ where
     ) -> Option<Result<Engine::PayloadBuilderAttributes, PayloadBuilderError>> {
         self.inner.payload_attributes(id).await
     }
+
+    /// Returns a receiver of the [PayloadEvent]s of the payload builder.
+    pub async fn subscribe(&self) -> Result<PayloadEvents<Engine>, oneshot::error::RecvError> {
+        self.inner.subscribe().await
+    }
 }
 
 impl<Engine> From<PayloadBuilderHandle<Engine>> for PayloadStore<Engine>
@@ -135,6 +143,13 @@ where
         rx.await.ok()?
     }
 
+    /// Returns a receiver of the [PayloadEvent]s of the service.
+    pub async fn subscribe(&self) -> Result<PayloadEvents<Engine>, oneshot::error::RecvError> {
+        let (tx, rx) = oneshot::channel();
+        let _ = self.to_service.send(PayloadServiceCommand::Subscribe(tx));
+        rx.await
+    }
+
     /// Sends a message to the service to start building a new payload for the given payload.
     ///
     /// This is the same as [PayloadBuilderHandle::new_payload] but does not wait for the result and
@@ -189,6 +204,8 @@ where
     metrics: PayloadBuilderServiceMetrics,
     /// Chain events notification stream
     chain_events: St,
+    /// Sender half of the [PayloadEvent]s channel.
+    payload_events: broadcast::Sender<PayloadEvent<Engine>>,
 }
 
 // === impl PayloadBuilderService ===
@@ -207,6 +224,7 @@ where
     /// additional logic when new state is committed. See also [PayloadJobGenerator::on_new_state].
     pub fn new(generator: Gen, chain_events: St) -> (Self, PayloadBuilderHandle<Engine>) {
         let (service_tx, command_rx) = mpsc::unbounded_channel();
+        let (payload_events, _) = broadcast::channel(PAYLOAD_EVENTS_CHANNEL_CAPACITY);
         let service = Self {
             generator,
             payload_jobs: Vec::new(),
@@ -214,6 +232,7 @@ where
             command_rx: UnboundedReceiverStream::new(command_rx),
             metrics: Default::default(),
             chain_events,
+            payload_events,
         };
 
         let handle = service.handle();
@@ -360,12 +379,17 @@ where
                         } else {
                             // no job for this payload yet, create one
                             let parent = attr.parent();
+                            let event = (this.payload_events.receiver_count() > 0)
+                                .then(|| PayloadEvent::Attributes(attr.clone()));
                             match this.generator.new_payload_job(attr) {
                                 Ok(job) => {
                                     info!(%id, %parent, "New payload job created");
                                     this.metrics.inc_initiated_jobs();
                                     new_job = true;
                                     this.payload_jobs.push((job, id));
+                                    if let Some(event) = event {
+                                        let _ = this.payload_events.send(event);
+                                    }
                                 }
                                 Err(err) => {
                                     this.metrics.inc_failed_jobs();
@@ -387,6 +411,9 @@ where
                     PayloadServiceCommand::Resolve(id, tx) => {
                         let _ = tx.send(this.resolve(id));
                     }
+                    PayloadServiceCommand::Subscribe(tx) => {
+                        let _ = tx.send(this.payload_events.subscribe());
+                    }
                 }
             }
 
@@ -419,6 +446,8 @@ pub enum PayloadServiceCommand<Engine: EngineTypes> {
     ),
     /// Resolve the payload and return the payload
     Resolve(PayloadId, oneshot::Sender<Option<PayloadFuture<Engine::BuiltPayload>>>),
+    /// Subscribe to the payload events
+    Subscribe(oneshot::Sender<PayloadEvents<Engine>>),
 }
 
 impl<Engine> fmt::Debug for PayloadServiceCommand<Engine>
@@ -437,6 +466,7 @@ where
                 f.debug_tuple("PayloadAttributes").field(&f0).field(&f1).finish()
             }
             PayloadServiceCommand::Resolve(f0, _f1) => f.debug_tuple("Resolve").field(&f0).finish(),
+            PayloadServiceCommand::Subscribe(f0) => f.debug_tuple("Subscribe").field(&f0).finish(),
         }
     }
 }
@@ -14,38 +14,63 @@ pub use builder::*;
 mod builder {
     use reth_basic_payload_builder::{
         commit_withdrawals, is_better_payload, pre_block_beacon_root_contract_call, BuildArguments,
-        BuildOutcome, PayloadBuilder, PayloadConfig, WithdrawalsOutcome,
+        BuildOutcome, DefaultTransactionSelection, PayloadBuilder, PayloadConfig,
+        TransactionSelectionPolicy, WithdrawalsOutcome,
     };
     use reth_payload_builder::{
         error::PayloadBuilderError, EthBuiltPayload, EthPayloadBuilderAttributes,
     };
     use reth_primitives::{
-        constants::{eip4844::MAX_DATA_GAS_PER_BLOCK, BEACON_NONCE},
+        constants::BEACON_NONCE,
         eip4844::calculate_excess_blob_gas,
         proofs,
         revm::{compat::into_reth_log, env::tx_env_with_recovered},
         Block, Header, IntoRecoveredTransaction, Receipt, Receipts, EMPTY_OMMER_ROOT_HASH, U256,
     };
     use reth_provider::{BundleStateWithReceipts, StateProviderFactory};
-    use reth_revm::database::StateProviderDatabase;
-    use reth_transaction_pool::TransactionPool;
+    use reth_revm::{database::StateProviderDatabase, EvmConfig};
+    use reth_transaction_pool::{BestTransactionsAttributes, TransactionPool};
     use revm::{
-        db::states::bundle_state::BundleRetention,
+        db::{states::bundle_state::BundleRetention, CacheDB},
         primitives::{EVMError, Env, InvalidTransaction, ResultAndState},
         DatabaseCommit, State,
     };
     use tracing::{debug, trace};
 
     /// Ethereum payload builder
+    ///
+    /// The pool transactions that are considered for a payload are selected by the
+    /// [TransactionSelectionPolicy], which defaults to [DefaultTransactionSelection].
     #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
     #[non_exhaustive]
-    pub struct EthereumPayloadBuilder;
+    pub struct EthereumPayloadBuilder<Selection = DefaultTransactionSelection> {
+        /// The configuration of the EVM that executes the transactions.
+        evm_config: EvmConfig,
+        /// The policy that selects the pool transactions of a payload.
+        selection: Selection,
+    }
+
+    impl EthereumPayloadBuilder {
+        /// Creates a new payload builder that executes transactions with the given EVM
+        /// configuration.
+        pub fn new(evm_config: EvmConfig) -> Self {
+            Self { evm_config, selection: DefaultTransactionSelection }
+        }
+    }
+
+    impl<Selection> EthereumPayloadBuilder<Selection> {
+        /// Sets the policy that selects the pool transactions of a payload.
+        pub fn with_selection_policy<S>(self, selection: S) -> EthereumPayloadBuilder<S> {
+            EthereumPayloadBuilder { evm_config: self.evm_config, selection }
+        }
+    }
 
     // Default implementation of [PayloadBuilder] for unit type
-    impl<Pool, Client> PayloadBuilder<Pool, Client> for EthereumPayloadBuilder
+    impl<Pool, Client, Selection> PayloadBuilder<Pool, Client> for EthereumPayloadBuilder<Selection>
     where
         Client: StateProviderFactory,
         Pool: TransactionPool,
+        Selection: TransactionSelectionPolicy<Pool::Transaction>,
     {
         type Attributes = EthPayloadBuilderAttributes;
         type BuiltPayload = EthBuiltPayload;
@@ -54,22 +79,27 @@ mod builder {
             &self,
             args: BuildArguments<Pool, Client, EthPayloadBuilderAttributes, EthBuiltPayload>,
         ) -> Result<BuildOutcome<EthBuiltPayload>, PayloadBuilderError> {
-            default_ethereum_payload_builder(args)
+            default_ethereum_payload_builder(args, &self.evm_config, &self.selection)
         }
     }
 
     /// Constructs an Ethereum transaction payload using the best transactions from the pool.
     ///
     /// Given build arguments including an Ethereum client, transaction pool,
-    /// and configuration, this function creates a transaction payload. Returns
+    /// and configuration, this function creates a transaction payload, executing the transactions
+    /// with the given EVM configuration. The pool transactions are selected by the given
+    /// [TransactionSelectionPolicy]. Returns
     /// a result indicating success with the payload or an error in case of failure.
     #[inline]
-    pub fn default_ethereum_payload_builder<Pool, Client>(
+    pub fn default_ethereum_payload_builder<Pool, Client, Selection>(
         args: BuildArguments<Pool, Client, EthPayloadBuilderAttributes, EthBuiltPayload>,
+        evm_config: &EvmConfig,
+        selection: &Selection,
     ) -> Result<BuildOutcome<EthBuiltPayload>, PayloadBuilderError>
     where
         Client: StateProviderFactory,
         Pool: TransactionPool,
+        Selection: TransactionSelectionPolicy<Pool::Transaction>,
     {
         let BuildArguments { client, pool, mut cached_reads, config, cancel, best_payload } = args;
 
@@ -80,6 +110,7 @@ mod builder {
             .with_bundle_update()
             .build();
         let extra_data = config.extra_data();
+        let max_blob_gas = config.max_blob_gas();
         let PayloadConfig {
             initialized_block_env,
             initialized_cfg,
@@ -96,7 +127,14 @@ mod builder {
         let base_fee = initialized_block_env.basefee.to::<u64>();
 
         let mut executed_txs = Vec::new();
-        let mut best_txs = pool.best_transactions_with_base_fee(base_fee);
+        let mut best_txs = selection.select(
+            pool.best_transactions_with_base_fee(base_fee),
+            BestTransactionsAttributes::base_fee(base_fee),
+        );
+        if max_blob_gas == 0 {
+            // blob transactions are disabled
+            best_txs.skip_blobs();
+        }
 
         let mut total_fees = U256::ZERO;
 
@@ -113,6 +151,96 @@ mod builder {
         )?;
 
         let mut receipts = Vec::new();
+
+        // include all bundles that target this block at the top of the block, each bundle is
+        // simulated first and only included if all of its transactions can be executed
+        let bundles = if selection.include_bundles() {
+            pool.bundles_for_block(block_number, attributes.timestamp)
+        } else {
+            Vec::new()
+        };
+        if !bundles.is_empty() {
+            // bundles are simulated on top of a separate database that mirrors all changes that
+            // are committed to the block's state
+            let mut sim_db = CacheDB::new(state.clone());
+            pre_block_beacon_root_contract_call(
+                &mut sim_db,
+                &chain_spec,
+                block_number,
+                &initialized_cfg,
+                &initialized_block_env,
+                &attributes,
+            )?;
+
+            'bundles: for bundle in bundles {
+                // check if the job was cancelled, if so we can exit early
+                if cancel.is_cancelled() {
+                    return Ok(BuildOutcome::Cancelled)
+                }
+
+                let mut evm = revm::EVM::new();
+                evm.database(sim_db.clone());
+                let mut bundle_gas_used = 0;
+                let mut results = Vec::with_capacity(bundle.transactions().len());
+
+                for tx in bundle.transactions() {
+                    if cumulative_gas_used + bundle_gas_used + tx.gas_limit() > block_gas_limit {
+                        trace!(target: "payload_builder", bundle=?bundle.hash(), "skipping bundle because it exceeds the block gas limit");
+                        continue 'bundles
+                    }
+
+                    evm.env = Env {
+                        cfg: initialized_cfg.clone(),
+                        block: initialized_block_env.clone(),
+                        tx: tx_env_with_recovered(tx),
+                    };
+
+                    let ResultAndState { result, state } = match evm_config.transact(&mut evm) {
+                        Ok(res) => res,
+                        Err(EVMError::Transaction(err)) => {
+                            trace!(target: "payload_builder", ?err, bundle=?bundle.hash(), tx=?tx.hash, "skipping bundle with invalid transaction");
+                            continue 'bundles
+                        }
+                        Err(err) => return Err(PayloadBuilderError::EvmExecutionError(err)),
+                    };
+
+                    if !result.is_success() && !bundle.may_revert(&tx.hash) {
+                        trace!(target: "payload_builder", bundle=?bundle.hash(), tx=?tx.hash, "skipping bundle with reverted transaction");
+                        continue 'bundles
+                    }
+
+                    bundle_gas_used += result.gas_used();
+                    evm.db.as_mut().expect("is set").commit(state.clone());
+                    results.push((result, state));
+                }
+
+                // the bundle is valid, apply it to the block
+                sim_db = evm.db.take().expect("is set");
+                for (tx, (result, state)) in bundle.transactions().iter().zip(results) {
+                    db.commit(state);
+
+                    let gas_used = result.gas_used();
+                    cumulative_gas_used += gas_used;
+
+                    receipts.push(Some(Receipt {
+                        tx_type: tx.tx_type(),
+                        success: result.is_success(),
+                        cumulative_gas_used,
+                        logs: result.logs().into_iter().map(into_reth_log).collect(),
+                    }));
+
+                    let miner_fee = tx
+                        .effective_tip_per_gas(Some(base_fee))
+                        .expect("fee is always valid; execution succeeded");
+                    total_fees += U256::from(miner_fee) * U256::from(gas_used);
+
+                    executed_txs.push(tx.clone().into_signed());
+                }
+                trace!(target: "payload_builder", bundle=?bundle.hash(), "included bundle");
+            }
+        }
+
+        // transactions of included bundles are skipped by the nonce check
         while let Some(pool_tx) = best_txs.next() {
             // ensure we still have capacity for this transaction
             if cumulative_gas_used + pool_tx.gas_limit() > block_gas_limit {
@@ -135,7 +263,7 @@ mod builder {
             // the EIP-4844 can still fit in the block
             if let Some(blob_tx) = tx.transaction.as_eip4844() {
                 let tx_blob_gas = blob_tx.blob_gas();
-                if sum_blob_gas_used + tx_blob_gas > MAX_DATA_GAS_PER_BLOCK {
+                if sum_blob_gas_used + tx_blob_gas > max_blob_gas {
                     // we can't fit this _blob_ transaction into the block, so we mark it as
                     // invalid, which removes its dependent transactions from
                     // the iterator. This is similar to the gas limit condition
@@ -156,7 +284,7 @@ mod builder {
             let mut evm = revm::EVM::with_env(env);
             evm.database(&mut db);
 
-            let ResultAndState { result, state } = match evm.transact() {
+            let ResultAndState { result, state } = match evm_config.transact(&mut evm) {
                 Ok(res) => res,
                 Err(err) => {
                     match err {
@@ -190,7 +318,7 @@ mod builder {
                 sum_blob_gas_used += tx_blob_gas;
 
                 // if we've reached the max data gas per block, we can skip blob txs entirely
-                if sum_blob_gas_used == MAX_DATA_GAS_PER_BLOCK {
+                if sum_blob_gas_used >= max_blob_gas {
                     best_txs.skip_blobs();
                 }
             }
@@ -214,6 +342,9 @@ mod builder {
                 .expect("fee is always valid; execution succeeded");
             total_fees += U256::from(miner_fee) * U256::from(gas_used);
 
+            // only included transactions count against the limits of the selection
+            best_txs.on_included(&pool_tx, gas_used);
+
             // append transaction to the list of executed transactions
             executed_txs.push(tx.into_signed());
         }
@@ -28,8 +28,8 @@ mod builder {
         EMPTY_OMMER_ROOT_HASH, U256,
     };
     use reth_provider::{BundleStateWithReceipts, StateProviderFactory};
-    use reth_revm::database::StateProviderDatabase;
-    use reth_transaction_pool::TransactionPool;
+    use reth_revm::{database::StateProviderDatabase, EvmConfig};
+    use reth_transaction_pool::{BestTransactionsAttributes, TransactionPool};
     use revm::{
         db::states::bundle_state::BundleRetention,
         primitives::{EVMError, Env, InvalidTransaction, ResultAndState},
@@ -38,15 +38,22 @@ mod builder {
     use tracing::{debug, trace};
 
     /// Optimism's payload builder
+    ///
+    /// The pool transactions that are considered for a payload are selected by the
+    /// [TransactionSelectionPolicy], which defaults to [DefaultTransactionSelection].
     #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
     #[non_exhaustive]
-    pub struct OptimismPayloadBuilder {
+    pub struct OptimismPayloadBuilder<Selection = DefaultTransactionSelection> {
         /// The rollup's compute pending block configuration option.
         // TODO(clabby): Implement this feature.
         compute_pending_block: bool,
+        /// The configuration of the EVM that executes the transactions.
+        evm_config: EvmConfig,
+        /// The policy that selects the pool transactions of a payload.
+        selection: Selection,
     }
 
-    impl OptimismPayloadBuilder {
+    impl<Selection> OptimismPayloadBuilder<Selection> {
         /// Sets the rollup's compute pending block configuration option.
         pub fn set_compute_pending_block(mut self, compute_pending_block: bool) -> Self {
             self.compute_pending_block = compute_pending_block;
@@ -58,17 +65,33 @@ mod builder {
             self.set_compute_pending_block(true)
         }
 
+        /// Sets the configuration of the EVM that executes the transactions.
+        pub fn with_evm_config(mut self, evm_config: EvmConfig) -> Self {
+            self.evm_config = evm_config;
+            self
+        }
+
         /// Returns the rollup's compute pending block configuration option.
         pub fn is_compute_pending_block(&self) -> bool {
             self.compute_pending_block
         }
+
+        /// Sets the policy that selects the pool transactions of a payload.
+        pub fn with_selection_policy<S>(self, selection: S) -> OptimismPayloadBuilder<S> {
+            OptimismPayloadBuilder {
+                compute_pending_block: self.compute_pending_block,
+                evm_config: self.evm_config,
+                selection,
+            }
+        }
     }
 
     /// Implementation of the [PayloadBuilder] trait for [OptimismPayloadBuilder].
-    impl<Pool, Client> PayloadBuilder<Pool, Client> for OptimismPayloadBuilder
+    impl<Pool, Client, Selection> PayloadBuilder<Pool, Client> for OptimismPayloadBuilder<Selection>
     where
         Client: StateProviderFactory,
         Pool: TransactionPool,
+        Selection: TransactionSelectionPolicy<Pool::Transaction>,
     {
         type Attributes = OptimismPayloadBuilderAttributes;
         type BuiltPayload = EthBuiltPayload;
@@ -77,7 +100,12 @@ mod builder {
             &self,
             args: BuildArguments<Pool, Client, OptimismPayloadBuilderAttributes, EthBuiltPayload>,
         ) -> Result<BuildOutcome<EthBuiltPayload>, PayloadBuilderError> {
-            optimism_payload_builder(args, self.compute_pending_block)
+            optimism_payload_builder(
+                args,
+                self.compute_pending_block,
+                &self.evm_config,
+                &self.selection,
+            )
         }
 
         fn on_missing_payload(
@@ -108,13 +136,16 @@ mod builder {
     /// and configuration, this function creates a transaction payload. Returns
     /// a result indicating success with the payload or an error in case of failure.
     #[inline]
-    pub(crate) fn optimism_payload_builder<Pool, Client>(
+    pub(crate) fn optimism_payload_builder<Pool, Client, Selection>(
         args: BuildArguments<Pool, Client, OptimismPayloadBuilderAttributes, EthBuiltPayload>,
         _compute_pending_block: bool,
+        evm_config: &EvmConfig,
+        selection: &Selection,
     ) -> Result<BuildOutcome<EthBuiltPayload>, PayloadBuilderError>
     where
         Client: StateProviderFactory,
         Pool: TransactionPool,
+        Selection: TransactionSelectionPolicy<Pool::Transaction>,
     {
         debug_assert!(
             args.config.initialized_cfg.optimism,
@@ -147,7 +178,10 @@ mod builder {
         let base_fee = initialized_block_env.basefee.to::<u64>();
 
         let mut executed_txs = Vec::new();
-        let mut best_txs = pool.best_transactions_with_base_fee(base_fee);
+        let mut best_txs = selection.select(
+            pool.best_transactions_with_base_fee(base_fee),
+            BestTransactionsAttributes::base_fee(base_fee),
+        );
 
         let mut total_fees = U256::ZERO;
 
@@ -211,7 +245,7 @@ mod builder {
             let mut evm = revm::EVM::with_env(env);
             evm.database(&mut db);
 
-            let ResultAndState { result, state } = match evm.transact() {
+            let ResultAndState { result, state } = match evm_config.transact(&mut evm) {
                 Ok(res) => res,
                 Err(err) => {
                     match err {
@@ -283,7 +317,7 @@ mod builder {
                 let mut evm = revm::EVM::with_env(env);
                 evm.database(&mut db);
 
-                let ResultAndState { result, state } = match evm.transact() {
+                let ResultAndState { result, state } = match evm_config.transact(&mut evm) {
                     Ok(res) => res,
                     Err(err) => {
                         match err {
@@ -333,6 +367,9 @@ mod builder {
                     .expect("fee is always valid; execution succeeded");
                 total_fees += U256::from(miner_fee) * U256::from(gas_used);
 
+                // only included transactions count against the limits of the selection
+                best_txs.on_included(&pool_tx, gas_used);
+
                 // append transaction to the list of executed transactions
                 executed_txs.push(tx.into_signed());
             }
@@ -0,0 +1,70 @@
+//! A client of the relay builder API.
+
+use crate::RelayError;
+use reqwest::{Response, Url};
+use reth_rpc_types::relay::Validator;
+use serde::{Deserialize, Serialize};
+
+/// The endpoint that returns the proposer duties of the current and the next epoch.
+const VALIDATORS_PATH: &str = "relay/v1/builder/validators";
+
+/// The endpoint for the submission of blocks.
+const BLOCKS_PATH: &str = "relay/v1/builder/blocks";
+
+/// The error response of a relay.
+#[derive(Debug, Deserialize)]
+struct RelayErrorResponse {
+    message: String,
+}
+
+/// A client of the builder API of a single relay.
+#[derive(Debug, Clone)]
+pub struct RelayClient {
+    /// The base url of the relay.
+    url: Url,
+    /// The HTTP client.
+    http: reqwest::Client,
+}
+
+impl RelayClient {
+    /// Creates a new client of the relay with the given base url.
+    pub fn new(url: Url) -> Self {
+        Self { url, http: reqwest::Client::new() }
+    }
+
+    /// Returns the base url of the relay.
+    pub fn url(&self) -> &Url {
+        &self.url
+    }
+
+    /// Returns the proposers of the current and the next epoch that are registered with the relay.
+    pub async fn validators(&self) -> Result<Vec<Validator>, RelayError> {
+        let response = self.http.get(self.endpoint(VALIDATORS_PATH)).send().await?;
+        Ok(Self::check(response).await?.json().await?)
+    }
+
+    /// Submits a signed bid to the relay.
+    pub async fn submit_block<T: Serialize + ?Sized>(&self, bid: &T) -> Result<(), RelayError> {
+        let response = self.http.post(self.endpoint(BLOCKS_PATH)).json(bid).send().await?;
+        Self::check(response).await?;
+        Ok(())
+    }
+
+    /// Returns the url of the given endpoint of the relay.
+    fn endpoint(&self, path: &str) -> String {
+        format!("{}/{path}", self.url.as_str().trim_end_matches('/'))
+    }
+
+    /// Returns the response if it is successful, or the error message of the relay otherwise.
+    async fn check(response: Response) -> Result<Response, RelayError> {
+        let status = response.status();
+        if status.is_success() {
+            return Ok(response)
+        }
+        let body = response.text().await?;
+        let message = serde_json::from_str::<RelayErrorResponse>(&body)
+            .map(|response| response.message)
+            .unwrap_or(body);
+        Err(RelayError::Rejected { status, message })
+    }
+}
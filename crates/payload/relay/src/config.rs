@@ -0,0 +1,94 @@
+//! Configuration of the relay integration.
+
+use reqwest::Url;
+use reth_primitives::{Chain, NamedChain};
+use std::time::Duration;
+
+/// The default interval at which the best payload of a job is submitted to the relays, if it
+/// improved.
+pub const DEFAULT_SUBMISSION_INTERVAL: Duration = Duration::from_millis(500);
+
+/// The parameters of the beacon chain that are required for the submission of bids.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BeaconChainConfig {
+    /// The timestamp of the beacon chain genesis.
+    pub genesis_time: u64,
+    /// The fork version of the beacon chain genesis, which is part of the builder signing domain.
+    pub genesis_fork_version: [u8; 4],
+    /// The duration of a slot in seconds.
+    pub seconds_per_slot: u64,
+}
+
+impl BeaconChainConfig {
+    /// The beacon chain of mainnet.
+    pub const MAINNET: Self = Self::new(1606824023, [0x00, 0x00, 0x00, 0x00]);
+    /// The beacon chain of goerli.
+    pub const GOERLI: Self = Self::new(1616508000, [0x00, 0x00, 0x10, 0x20]);
+    /// The beacon chain of sepolia.
+    pub const SEPOLIA: Self = Self::new(1655733600, [0x90, 0x00, 0x00, 0x69]);
+    /// The beacon chain of holesky.
+    pub const HOLESKY: Self = Self::new(1695902400, [0x01, 0x01, 0x70, 0x00]);
+
+    /// Creates a new config with the default slot duration of 12 seconds.
+    pub const fn new(genesis_time: u64, genesis_fork_version: [u8; 4]) -> Self {
+        Self { genesis_time, genesis_fork_version, seconds_per_slot: 12 }
+    }
+
+    /// Returns the beacon chain of the given execution chain, if it is known.
+    pub fn for_chain(chain: Chain) -> Option<Self> {
+        match chain.named()? {
+            NamedChain::Mainnet => Some(Self::MAINNET),
+            NamedChain::Goerli => Some(Self::GOERLI),
+            NamedChain::Sepolia => Some(Self::SEPOLIA),
+            NamedChain::Holesky => Some(Self::HOLESKY),
+            _ => None,
+        }
+    }
+
+    /// Returns the slot that starts at the given timestamp, or `None` if the timestamp is not the
+    /// start of a slot.
+    pub fn slot_at(&self, timestamp: u64) -> Option<u64> {
+        let elapsed = timestamp.checked_sub(self.genesis_time)?;
+        (elapsed % self.seconds_per_slot == 0).then_some(elapsed / self.seconds_per_slot)
+    }
+}
+
+/// The configuration of the [RelaySubmitter](crate::RelaySubmitter).
+#[derive(Debug, Clone)]
+pub struct RelayConfig {
+    /// The base urls of the relays to submit bids to.
+    pub relays: Vec<Url>,
+    /// The beacon chain the payloads are built for.
+    pub beacon_chain: BeaconChainConfig,
+    /// The interval at which the best payload of a job is submitted, if it improved.
+    pub submission_interval: Duration,
+}
+
+impl RelayConfig {
+    /// Creates a new config for the given relays and beacon chain.
+    pub fn new(relays: Vec<Url>, beacon_chain: BeaconChainConfig) -> Self {
+        Self { relays, beacon_chain, submission_interval: DEFAULT_SUBMISSION_INTERVAL }
+    }
+
+    /// Sets the interval at which the best payload of a job is submitted.
+    pub fn with_submission_interval(mut self, submission_interval: Duration) -> Self {
+        self.submission_interval = submission_interval;
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn slot_at_timestamp() {
+        let config = BeaconChainConfig::MAINNET;
+        assert_eq!(config.slot_at(config.genesis_time), Some(0));
+        assert_eq!(config.slot_at(config.genesis_time + 24), Some(2));
+        assert_eq!(config.slot_at(config.genesis_time + 25), None);
+        assert_eq!(config.slot_at(config.genesis_time - 12), None);
+        assert_eq!(BeaconChainConfig::for_chain(Chain::mainnet()), Some(config));
+        assert_eq!(BeaconChainConfig::for_chain(Chain::from_id(1337)), None);
+    }
+}
@@ -0,0 +1,27 @@
+//! Error types of the relay integration.
+
+use reqwest::StatusCode;
+
+/// Errors returned by a relay.
+#[derive(Debug, thiserror::Error)]
+pub enum RelayError {
+    /// The request to the relay failed.
+    #[error(transparent)]
+    Http(#[from] reqwest::Error),
+    /// The relay rejected the request.
+    #[error("relay responded with {status}: {message}")]
+    Rejected {
+        /// The status code of the response.
+        status: StatusCode,
+        /// The error message of the relay.
+        message: String,
+    },
+}
+
+/// Errors of the [BuilderSigner](crate::BuilderSigner).
+#[derive(Debug, thiserror::Error)]
+pub enum SignerError {
+    /// The secret key is not a valid BLS secret key.
+    #[error("invalid BLS secret key")]
+    InvalidSecretKey,
+}
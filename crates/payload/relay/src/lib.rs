@@ -0,0 +1,29 @@
+//! Submission of locally built payloads to MEV-Boost relays.
+//!
+//! With the [RelaySubmitter], reth acts as a block builder for the proposers that are registered
+//! with the configured relays: for every payload job of the payload builder, the best payload
+//! built so far is signed with the builder key and submitted to the relays via the builder API
+//! `submitBlock` endpoint, until the slot of the payload starts.
+//!
+//! See also <https://flashbots.github.io/relay-specs/>
+
+#![doc(
+    html_logo_url = "https://raw.githubusercontent.com/paradigmxyz/reth/main/assets/reth-docs.png",
+    html_favicon_url = "https://avatars0.githubusercontent.com/u/97369466?s=256",
+    issue_tracker_base_url = "https://github.com/paradigmxyz/reth/issues/"
+)]
+#![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
+
+mod client;
+mod config;
+mod error;
+mod metrics;
+mod signer;
+mod submitter;
+
+pub use client::RelayClient;
+pub use config::{BeaconChainConfig, RelayConfig, DEFAULT_SUBMISSION_INTERVAL};
+pub use error::{RelayError, SignerError};
+pub use reqwest::Url;
+pub use signer::BuilderSigner;
+pub use submitter::RelaySubmitter;
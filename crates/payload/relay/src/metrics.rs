@@ -0,0 +1,22 @@
+//! Relay submission metrics.
+
+use reth_metrics::{
+    metrics::{Counter, Gauge, Histogram},
+    Metrics,
+};
+
+/// Metrics of the bids submitted to a single relay.
+#[derive(Metrics, Clone)]
+#[metrics(scope = "payloads.relay")]
+pub(crate) struct RelayMetrics {
+    /// Total number of bids accepted by the relay
+    pub(crate) submitted_bids: Counter,
+    /// Total number of bids that failed or were rejected by the relay
+    pub(crate) failed_bids: Counter,
+    /// Value of the last submitted bid, in ETH
+    pub(crate) last_bid_value: Gauge,
+    /// Slot of the last submitted bid
+    pub(crate) last_bid_slot: Gauge,
+    /// Time it took to submit a bid, in seconds
+    pub(crate) submission_duration: Histogram,
+}
@@ -0,0 +1,163 @@
+//! Signing of builder bids.
+
+use crate::SignerError;
+use blst::min_pk::SecretKey;
+use reth_primitives::B256;
+use reth_rpc_types::{
+    beacon::{BlsPublicKey, BlsSignature},
+    relay::BidTrace,
+};
+use sha2::{Digest, Sha256};
+use std::fmt;
+
+/// The domain type of builder signatures, see `DOMAIN_APPLICATION_BUILDER` of the builder spec.
+const DOMAIN_APPLICATION_BUILDER: [u8; 4] = [0x00, 0x00, 0x00, 0x01];
+
+/// The domain separation tag of beacon chain BLS signatures.
+const BLS_DST: &[u8] = b"BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_POP_";
+
+/// Signs [BidTrace]s with the BLS key of the builder.
+#[derive(Clone)]
+pub struct BuilderSigner {
+    secret_key: SecretKey,
+    public_key: BlsPublicKey,
+    domain: B256,
+}
+
+impl BuilderSigner {
+    /// Creates a new signer with the given BLS secret key, for the beacon chain with the given
+    /// genesis fork version.
+    pub fn new(secret_key: B256, genesis_fork_version: [u8; 4]) -> Result<Self, SignerError> {
+        let secret_key = SecretKey::from_bytes(secret_key.as_slice())
+            .map_err(|_| SignerError::InvalidSecretKey)?;
+        let public_key = BlsPublicKey::from(secret_key.sk_to_pk().compress());
+        Ok(Self { secret_key, public_key, domain: builder_domain(genesis_fork_version) })
+    }
+
+    /// Returns the BLS public key of the builder.
+    pub fn public_key(&self) -> BlsPublicKey {
+        self.public_key
+    }
+
+    /// Signs the given bid.
+    pub fn sign(&self, bid: &BidTrace) -> BlsSignature {
+        let signing_root = hash_pair(bid_trace_root(bid), self.domain);
+        BlsSignature::from(self.secret_key.sign(signing_root.as_slice(), BLS_DST, &[]).compress())
+    }
+}
+
+impl fmt::Debug for BuilderSigner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BuilderSigner")
+            .field("public_key", &self.public_key)
+            .field("domain", &self.domain)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Returns the builder signing domain for the given genesis fork version.
+///
+/// Builder signatures are valid across forks, so the domain is computed with the genesis fork
+/// version and a zero genesis validators root.
+fn builder_domain(genesis_fork_version: [u8; 4]) -> B256 {
+    let mut fork_version = B256::ZERO;
+    fork_version[..4].copy_from_slice(&genesis_fork_version);
+    let fork_data_root = hash_pair(fork_version, B256::ZERO);
+
+    let mut domain = B256::ZERO;
+    domain[..4].copy_from_slice(&DOMAIN_APPLICATION_BUILDER);
+    domain[4..].copy_from_slice(&fork_data_root[..28]);
+    domain
+}
+
+/// Returns the SSZ hash tree root of the bid.
+fn bid_trace_root(bid: &BidTrace) -> B256 {
+    let mut leaves = [B256::ZERO; 16];
+    leaves[0] = uint_chunk(bid.slot);
+    leaves[1] = bid.parent_hash;
+    leaves[2] = bid.block_hash;
+    leaves[3] = public_key_root(&bid.builder_public_key);
+    leaves[4] = public_key_root(&bid.proposer_public_key);
+    leaves[5][..20].copy_from_slice(bid.proposer_fee_recipient.as_slice());
+    leaves[6] = uint_chunk(bid.gas_limit);
+    leaves[7] = uint_chunk(bid.gas_used);
+    leaves[8] = B256::from(bid.value.to_le_bytes::<32>());
+    merkleize(&mut leaves)
+}
+
+/// Returns the SSZ hash tree root of a public key, which spans two chunks.
+fn public_key_root(public_key: &BlsPublicKey) -> B256 {
+    let mut chunks = [B256::ZERO; 2];
+    chunks[0].copy_from_slice(&public_key[..32]);
+    chunks[1][..16].copy_from_slice(&public_key[32..]);
+    hash_pair(chunks[0], chunks[1])
+}
+
+/// Returns the SSZ chunk of an unsigned integer.
+fn uint_chunk(value: u64) -> B256 {
+    let mut chunk = B256::ZERO;
+    chunk[..8].copy_from_slice(&value.to_le_bytes());
+    chunk
+}
+
+/// Returns the merkle root of the given chunks, whose number must be a power of two.
+fn merkleize(chunks: &mut [B256]) -> B256 {
+    let mut len = chunks.len();
+    while len > 1 {
+        for i in 0..len / 2 {
+            chunks[i] = hash_pair(chunks[2 * i], chunks[2 * i + 1]);
+        }
+        len /= 2;
+    }
+    chunks[0]
+}
+
+fn hash_pair(left: B256, right: B256) -> B256 {
+    let mut hasher = Sha256::new();
+    hasher.update(left);
+    hasher.update(right);
+    B256::from_slice(&hasher.finalize())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use blst::{min_pk::PublicKey, BLST_ERROR};
+    use reth_primitives::{b256, U256};
+
+    #[test]
+    fn mainnet_builder_domain() {
+        assert_eq!(
+            builder_domain([0x00, 0x00, 0x00, 0x00]),
+            b256!("00000001f5a5fd42d16a20302798ef6ed309979b43003d2320d9f0e8ea9831a9")
+        );
+    }
+
+    #[test]
+    fn sign_bid() {
+        let secret_key = B256::from(SecretKey::key_gen(&[1; 32], &[]).unwrap().to_bytes());
+        let signer = BuilderSigner::new(secret_key, [0x00, 0x00, 0x00, 0x00]).unwrap();
+        let bid = BidTrace {
+            slot: 1,
+            builder_public_key: signer.public_key(),
+            gas_limit: 30_000_000,
+            value: U256::from(1),
+            ..Default::default()
+        };
+
+        let signature = blst::min_pk::Signature::from_bytes(signer.sign(&bid).as_slice()).unwrap();
+        let public_key = PublicKey::from_bytes(signer.public_key().as_slice()).unwrap();
+        let signing_root = hash_pair(bid_trace_root(&bid), signer.domain);
+        assert_eq!(
+            signature.verify(true, signing_root.as_slice(), BLS_DST, &[], &public_key, true),
+            BLST_ERROR::BLST_SUCCESS
+        );
+
+        // the signature commits to the bid
+        let other = BidTrace { value: U256::from(2), ..bid.clone() };
+        assert_ne!(bid_trace_root(&other), bid_trace_root(&bid));
+
+        // secret keys must be smaller than the curve order
+        assert!(BuilderSigner::new(B256::repeat_byte(0xff), [0x00, 0x00, 0x00, 0x00]).is_err());
+    }
+}
@@ -0,0 +1,311 @@
+//! A task that submits the payloads of the payload builder to relays.
+
+use crate::{metrics::RelayMetrics, BuilderSigner, RelayClient, RelayConfig};
+use futures_util::future::join_all;
+use reth_node_api::{BuiltPayload, EngineTypes, PayloadBuilderAttributes};
+use reth_payload_builder::{PayloadEvent, PayloadStore};
+use reth_primitives::B256;
+use reth_rpc_types::{
+    beacon::BlsSignature,
+    engine::ExecutionPayloadEnvelopeV3,
+    relay::{BidTrace, SignedBidSubmissionV2, SignedBidSubmissionV3, ValidatorRegistrationMessage},
+};
+use reth_rpc_types_compat::engine::payload::try_block_to_payload_v2;
+use reth_tasks::TaskSpawner;
+use serde::Serialize;
+use std::{
+    fmt,
+    sync::Arc,
+    time::{Instant, SystemTime, UNIX_EPOCH},
+};
+use tokio::sync::broadcast::error::RecvError;
+use tracing::{debug, trace, warn};
+
+/// A bid for the submission to a relay.
+#[derive(Debug, Clone, Serialize)]
+#[serde(untagged)]
+enum BidSubmission {
+    Capella(SignedBidSubmissionV2),
+    Deneb(SignedBidSubmissionV3),
+}
+
+impl BidSubmission {
+    /// Returns an unsigned submission with the execution payload of the given payload, or `None`
+    /// if the payload is pre-Shanghai.
+    fn unsigned<P: BuiltPayload>(payload: P) -> Option<Self> {
+        let block = payload.block();
+        if block.parent_beacon_block_root.is_some() {
+            let ExecutionPayloadEnvelopeV3 { execution_payload, blobs_bundle, .. } =
+                payload.into_v3_payload();
+            Some(Self::Deneb(SignedBidSubmissionV3 {
+                message: Default::default(),
+                execution_payload,
+                blobs_bundle,
+                signature: Default::default(),
+            }))
+        } else if block.withdrawals_root.is_some() {
+            Some(Self::Capella(SignedBidSubmissionV2 {
+                message: Default::default(),
+                execution_payload: try_block_to_payload_v2(block.clone()),
+                signature: Default::default(),
+            }))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the submission with the given signed bid.
+    fn signed(mut self, bid: BidTrace, signature: BlsSignature) -> Self {
+        match &mut self {
+            Self::Capella(submission) => {
+                submission.message = bid;
+                submission.signature = signature;
+            }
+            Self::Deneb(submission) => {
+                submission.message = bid;
+                submission.signature = signature;
+            }
+        }
+        self
+    }
+
+    /// Returns the bid of the submission.
+    fn bid(&self) -> &BidTrace {
+        match self {
+            Self::Capella(submission) => &submission.message,
+            Self::Deneb(submission) => &submission.message,
+        }
+    }
+}
+
+/// A relay with its metrics.
+#[derive(Debug)]
+struct Relay {
+    client: RelayClient,
+    metrics: RelayMetrics,
+}
+
+/// Submits the payloads built by the payload builder to MEV-Boost relays.
+///
+/// For every new payload job, the submitter looks up the proposer of the slot of the payload in
+/// the validator registrations of every relay. Until the slot starts, the best payload of the job
+/// is then signed and submitted to the relays whenever it changed, in the configured interval.
+///
+/// The value of a bid is the revenue of the fee recipient of the payload, so bids are only
+/// submitted to relays with a registered fee recipient that matches the fee recipient of the
+/// payload attributes.
+pub struct RelaySubmitter<Engine: EngineTypes> {
+    inner: Arc<RelaySubmitterInner<Engine>>,
+}
+
+struct RelaySubmitterInner<Engine: EngineTypes> {
+    config: RelayConfig,
+    relays: Vec<Relay>,
+    signer: BuilderSigner,
+    payload_store: PayloadStore<Engine>,
+    task_spawner: Box<dyn TaskSpawner>,
+}
+
+impl<Engine> RelaySubmitter<Engine>
+where
+    Engine: EngineTypes + 'static,
+{
+    /// Creates a new submitter of the payloads of the given payload store.
+    pub fn new(
+        config: RelayConfig,
+        signer: BuilderSigner,
+        payload_store: PayloadStore<Engine>,
+        task_spawner: Box<dyn TaskSpawner>,
+    ) -> Self {
+        let relays = config
+            .relays
+            .iter()
+            .map(|url| Relay {
+                client: RelayClient::new(url.clone()),
+                metrics: RelayMetrics::new_with_labels(&[("relay", url.to_string())]),
+            })
+            .collect();
+        let inner = RelaySubmitterInner { config, relays, signer, payload_store, task_spawner };
+        Self { inner: Arc::new(inner) }
+    }
+
+    /// Submits the payloads of all new payload jobs, until the payload builder is shut down.
+    pub async fn run(self) {
+        let Ok(mut events) = self.inner.payload_store.subscribe().await else {
+            warn!(target: "payload::relay", "Payload builder is not running");
+            return
+        };
+        loop {
+            match events.recv().await {
+                Ok(PayloadEvent::Attributes(attributes)) => {
+                    let this = self.clone();
+                    self.inner.task_spawner.spawn(Box::pin(async move {
+                        this.submit_bids(attributes).await;
+                    }));
+                }
+                Err(RecvError::Lagged(skipped)) => {
+                    debug!(target: "payload::relay", skipped, "Skipped payload jobs");
+                }
+                Err(RecvError::Closed) => return,
+            }
+        }
+    }
+
+    /// Submits the best payload of the job with the given attributes to the relays, until the
+    /// slot of the payload starts.
+    async fn submit_bids(&self, attributes: Engine::PayloadBuilderAttributes) {
+        let timestamp = attributes.timestamp();
+        let Some(slot) = self.inner.config.beacon_chain.slot_at(timestamp) else {
+            debug!(target: "payload::relay", timestamp, "Payload timestamp is not a slot start");
+            return
+        };
+
+        let proposers = join_all(self.inner.relays.iter().map(|relay| proposer(relay, slot))).await;
+        let proposers = self
+            .inner
+            .relays
+            .iter()
+            .zip(proposers)
+            .filter_map(|(relay, proposer)| Some((relay, proposer?)))
+            .filter(|(relay, proposer)| {
+                let matches = proposer.fee_recipient == attributes.suggested_fee_recipient();
+                if !matches {
+                    debug!(
+                        target: "payload::relay",
+                        relay = %relay.client.url(),
+                        slot,
+                        "Registered fee recipient does not match the payload"
+                    );
+                }
+                matches
+            })
+            .collect::<Vec<_>>();
+        if proposers.is_empty() {
+            trace!(target: "payload::relay", slot, "No registered proposer for the slot");
+            return
+        }
+
+        let id = attributes.payload_id();
+        let mut interval = tokio::time::interval(self.inner.config.submission_interval);
+        let mut last_submitted = None::<B256>;
+        while unix_timestamp() < timestamp {
+            interval.tick().await;
+
+            let payload = match self.inner.payload_store.best_payload(id).await {
+                Some(Ok(payload)) => payload,
+                Some(Err(err)) => {
+                    debug!(target: "payload::relay", %err, %id, "Failed to get the best payload");
+                    continue
+                }
+                // the job was resolved
+                None => return,
+            };
+            let block_hash = payload.block().hash();
+            if last_submitted == Some(block_hash) {
+                continue
+            }
+            last_submitted = Some(block_hash);
+
+            let block = payload.block();
+            let value = payload.fees();
+            let bid = |proposer: &ValidatorRegistrationMessage| BidTrace {
+                slot,
+                parent_hash: block.parent_hash,
+                block_hash,
+                builder_public_key: self.inner.signer.public_key(),
+                proposer_public_key: proposer.pubkey,
+                proposer_fee_recipient: proposer.fee_recipient,
+                gas_limit: block.gas_limit,
+                gas_used: block.gas_used,
+                value,
+            };
+            let bids = proposers
+                .iter()
+                .map(|(relay, proposer)| (*relay, bid(proposer)))
+                .collect::<Vec<_>>();
+            let Some(unsigned) = BidSubmission::unsigned(payload.clone()) else {
+                debug!(target: "payload::relay", %id, "Bids are only supported after Shanghai");
+                return
+            };
+
+            join_all(bids.into_iter().map(|(relay, bid)| {
+                let signature = self.inner.signer.sign(&bid);
+                submit(relay, slot, unsigned.clone().signed(bid, signature))
+            }))
+            .await;
+        }
+    }
+}
+
+impl<Engine: EngineTypes> Clone for RelaySubmitter<Engine> {
+    fn clone(&self) -> Self {
+        Self { inner: Arc::clone(&self.inner) }
+    }
+}
+
+impl<Engine: EngineTypes> fmt::Debug for RelaySubmitter<Engine> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RelaySubmitter")
+            .field("config", &self.inner.config)
+            .field("signer", &self.inner.signer)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Returns the proposer of the slot that is registered with the relay.
+async fn proposer(relay: &Relay, slot: u64) -> Option<ValidatorRegistrationMessage> {
+    match relay.client.validators().await {
+        Ok(validators) => validators
+            .into_iter()
+            .find(|validator| validator.slot == slot)
+            .map(|validator| validator.entry.message),
+        Err(err) => {
+            warn!(
+                target: "payload::relay",
+                relay = %relay.client.url(),
+                %err,
+                "Failed to get the registered proposers"
+            );
+            None
+        }
+    }
+}
+
+/// Submits the bid to the relay and records the metrics of the submission.
+async fn submit(relay: &Relay, slot: u64, submission: BidSubmission) {
+    let BidTrace { block_hash, value, .. } = *submission.bid();
+    let start = Instant::now();
+    let res = relay.client.submit_block(&submission).await;
+    relay.metrics.submission_duration.record(start.elapsed().as_secs_f64());
+    match res {
+        Ok(()) => {
+            trace!(
+                target: "payload::relay",
+                relay = %relay.client.url(),
+                slot,
+                %block_hash,
+                %value,
+                "Submitted bid"
+            );
+            relay.metrics.submitted_bids.increment(1);
+            relay.metrics.last_bid_value.set(f64::from(value) / 1e18);
+            relay.metrics.last_bid_slot.set(slot as f64);
+        }
+        Err(err) => {
+            debug!(
+                target: "payload::relay",
+                relay = %relay.client.url(),
+                slot,
+                %block_hash,
+                %err,
+                "Failed to submit bid"
+            );
+            relay.metrics.failed_bids.increment(1);
+        }
+    }
+}
+
+/// Returns the current unix timestamp in seconds.
+fn unix_timestamp() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs()
+}
@@ -643,6 +643,14 @@ impl ChainSpec {
         self.chain.is_optimism()
     }
 
+    /// Returns `true` if this chain used the Clique proof-of-authority consensus before the merge.
+    ///
+    /// This is the case for goerli and chains with a Clique configuration in the genesis.
+    #[inline]
+    pub fn is_clique(&self) -> bool {
+        self.chain == Chain::goerli() || self.genesis.config.clique.is_some()
+    }
+
     /// Get the genesis block specification.
     ///
     /// To get the header for the genesis block, use [`Self::genesis_header`] instead.
@@ -804,6 +812,12 @@ impl ChainSpec {
         self.hardforks.get(&fork).copied().unwrap_or(ForkCondition::Never)
     }
 
+    /// Sets the activation condition of the given fork, replacing the existing one.
+    pub fn set_fork(&mut self, fork: Hardfork, condition: ForkCondition) {
+        self.hardforks.insert(fork, condition);
+        self.fork_timestamps = ForkTimestamps::from_hardforks(&self.hardforks);
+    }
+
     /// Get an iterator of all hardforks with their respective activation conditions.
     pub fn forks_iter(&self) -> impl Iterator<Item = (Hardfork, ForkCondition)> + '_ {
         self.hardforks.iter().map(|(f, b)| (*f, *b))
@@ -1002,6 +1016,7 @@ impl From<Genesis> for ChainSpec {
             .collect::<BTreeMap<_, _>>();
 
         // Paris
+        let mut paris_block_and_final_difficulty = None;
         if let Some(ttd) = genesis.config.terminal_total_difficulty {
             hardforks.insert(
                 Hardfork::Paris,
@@ -1010,6 +1025,11 @@ impl From<Genesis> for ChainSpec {
                     fork_block: genesis.config.merge_netsplit_block,
                 },
             );
+
+            // the chain is merged at genesis if the genesis block reached the ttd
+            if genesis.difficulty >= ttd {
+                paris_block_and_final_difficulty = Some((0, genesis.difficulty));
+            }
         }
 
         // Time-based hardforks
@@ -1033,7 +1053,7 @@ impl From<Genesis> for ChainSpec {
             genesis_hash: None,
             fork_timestamps: ForkTimestamps::from_hardforks(&hardforks),
             hardforks,
-            paris_block_and_final_difficulty: None,
+            paris_block_and_final_difficulty,
             deposit_contract: None,
             ..Default::default()
         }
@@ -2838,6 +2858,19 @@ Post-merge hard forks (timestamp based):
         assert_eq!(genesis.config.cancun_time, Some(4661));
     }
 
+    #[test]
+    fn merged_genesis_paris_block() {
+        let s = r#"{"config":{"chainId":1337,"londonBlock":0,"terminalTotalDifficulty":0,"terminalTotalDifficultyPassed":true,"shanghaiTime":0},"difficulty":"0x0","gasLimit":"0x4c4b40","alloc":{}}"#;
+        let chainspec = ChainSpec::from(serde_json::from_str::<Genesis>(s).unwrap());
+        assert_eq!(chainspec.paris_block_and_final_difficulty, Some((0, U256::ZERO)));
+        assert_eq!(chainspec.final_paris_total_difficulty(0), Some(U256::ZERO));
+
+        // the ttd is only reached after genesis
+        let s = r#"{"config":{"chainId":1337,"londonBlock":0,"terminalTotalDifficulty":10},"difficulty":"0x1","gasLimit":"0x4c4b40","alloc":{}}"#;
+        let chainspec = ChainSpec::from(serde_json::from_str::<Genesis>(s).unwrap());
+        assert_eq!(chainspec.paris_block_and_final_difficulty, None);
+    }
+
     #[test]
     fn test_default_cancun_header_forkhash() {
         // set the gas limit from the hive test genesis according to the hash
@@ -1,7 +1,7 @@
 //! Wrapper for `std::fs` methods
 
 use std::{
-    fs::{self, ReadDir},
+    fs::{self, File, ReadDir},
     io,
     path::{Path, PathBuf},
 };
@@ -181,6 +181,12 @@ pub fn read_to_string(path: impl AsRef<Path>) -> Result<String> {
     fs::read_to_string(path).map_err(|err| FsPathError::read(err, path))
 }
 
+/// Wrapper for `std::fs::File::open`
+pub fn open(path: impl AsRef<Path>) -> Result<File> {
+    let path = path.as_ref();
+    File::open(path).map_err(|err| FsPathError::open(err, path))
+}
+
 /// Read the entire contents of a file into a bytes vector.
 ///
 /// Wrapper for `std::fs::read`
@@ -3,7 +3,7 @@ use crate::{
     recover_signer_unchecked,
     revm::config::revm_spec,
     revm_primitives::{AnalysisKind, BlockEnv, CfgEnv, Env, SpecId, TransactTo, TxEnv},
-    Address, Bytes, Chain, ChainSpec, Head, Header, Transaction, TransactionKind,
+    Address, Bytes, ChainSpec, Head, Header, Transaction, TransactionKind,
     TransactionSignedEcRecovered, B256, U256,
 };
 
@@ -91,10 +91,10 @@ pub fn fill_block_env_with_coinbase(
 
 /// Return the coinbase address for the given header and chain spec.
 pub fn block_coinbase(chain_spec: &ChainSpec, header: &Header, after_merge: bool) -> Address {
-    if chain_spec.chain == Chain::goerli() && !after_merge {
+    if chain_spec.is_clique() && !after_merge {
         recover_header_signer(header).unwrap_or_else(|err| {
             panic!(
-                "Failed to recover goerli Clique Consensus signer from header ({}, {}) using extradata {}: {:?}",
+                "Failed to recover Clique Consensus signer from header ({}, {}) using extradata {}: {:?}",
                 header.number, header.hash_slow(), header.extra_data, err
             )
         })
@@ -120,7 +120,8 @@ pub fn recover_header_signer(header: &Header) -> Result<Address, CliqueSignerRec
     // Fixed number of extra-data suffix bytes reserved for signer signature.
     // 65 bytes fixed as signatures are based on the standard secp256k1 curve.
     // Filled with zeros on genesis block.
-    let signature_start_byte = extra_data_len - 65;
+    let signature_start_byte =
+        extra_data_len.checked_sub(65).ok_or(CliqueSignerRecoveryError::InvalidExtraData)?;
     let signature: [u8; 65] = header.extra_data[signature_start_byte..]
         .try_into()
         .map_err(|_| CliqueSignerRecoveryError::InvalidExtraData)?;
@@ -134,6 +134,9 @@ pub struct ExecutionCheckpoint {
     pub block_range: CheckpointBlockRange,
     /// Progress measured in gas.
     pub progress: EntitiesCheckpoint,
+    /// Progress measured in transactions. The number of processed transactions is the number of
+    /// the next transaction to execute.
+    pub transactions: EntitiesCheckpoint,
 }
 
 /// Saves the progress of Headers stage.
@@ -437,6 +440,10 @@ mod tests {
                     processed: rng.gen::<u32>() as u64,
                     total: u32::MAX as u64 + rng.gen::<u64>(),
                 },
+                transactions: EntitiesCheckpoint {
+                    processed: rng.gen::<u32>() as u64,
+                    total: u32::MAX as u64 + rng.gen::<u64>(),
+                },
             }),
             StageUnitCheckpoint::Headers(HeadersCheckpoint {
                 block_range: CheckpointBlockRange { from: rng.gen(), to: rng.gen() },
@@ -27,6 +27,14 @@ pub enum StageId {
     IndexStorageHistory,
     /// Index account history stage in the process.
     IndexAccountHistory,
+    /// Index logs stage in the process.
+    ///
+    /// The log index is optional, so this stage is not part of [StageId::ALL].
+    IndexLogs,
+    /// Index transaction addresses stage in the process.
+    ///
+    /// The transaction address index is optional, so this stage is not part of [StageId::ALL].
+    IndexTransactionAddresses,
     /// Finish stage in the process.
     Finish,
     /// Other custom stage with a provided string identifier.
@@ -66,6 +74,8 @@ impl StageId {
             StageId::TransactionLookup => "TransactionLookup",
             StageId::IndexAccountHistory => "IndexAccountHistory",
             StageId::IndexStorageHistory => "IndexStorageHistory",
+            StageId::IndexLogs => "IndexLogs",
+            StageId::IndexTransactionAddresses => "IndexTransactionAddresses",
             StageId::Finish => "Finish",
             StageId::Other(s) => s,
         }
@@ -105,6 +115,8 @@ mod tests {
         assert_eq!(StageId::MerkleExecute.to_string(), "MerkleExecute");
         assert_eq!(StageId::IndexAccountHistory.to_string(), "IndexAccountHistory");
         assert_eq!(StageId::IndexStorageHistory.to_string(), "IndexStorageHistory");
+        assert_eq!(StageId::IndexLogs.to_string(), "IndexLogs");
+        assert_eq!(StageId::IndexTransactionAddresses.to_string(), "IndexTransactionAddresses");
         assert_eq!(StageId::TransactionLookup.to_string(), "TransactionLookup");
         assert_eq!(StageId::Finish.to_string(), "Finish");
 
@@ -6,11 +6,13 @@ use crate::{
     Metrics, PrunerError, PrunerEvent,
 };
 use reth_db::database::Database;
-use reth_primitives::{BlockNumber, PruneMode, PruneProgress, PruneSegment};
+use reth_exex::FinishedExExHeight;
+use reth_primitives::{BlockNumber, PruneMode, PruneProgress};
 use reth_provider::{ProviderFactory, PruneCheckpointReader};
 use reth_snapshot::HighestSnapshotsTracker;
 use reth_tokio_util::EventListeners;
 use std::{collections::BTreeMap, sync::Arc, time::Instant};
+use tokio::sync::watch;
 use tokio_stream::wrappers::UnboundedReceiverStream;
 use tracing::{debug, trace};
 
@@ -37,8 +39,12 @@ pub struct Pruner<DB> {
     /// Maximum number of blocks to be pruned per run, as an additional restriction to
     /// `previous_tip_block_number`.
     prune_max_blocks_per_run: usize,
-    #[allow(dead_code)]
+    /// The highest snapshotted blocks of each segment, data up to which is pruned from the
+    /// database.
     highest_snapshots_tracker: HighestSnapshotsTracker,
+    /// The lowest block height all execution extensions have finished processing. Data above it
+    /// is not pruned.
+    finished_exex_height: watch::Receiver<FinishedExExHeight>,
     metrics: Metrics,
     listeners: EventListeners<PrunerEvent>,
 }
@@ -61,11 +67,22 @@ impl<DB: Database> Pruner<DB> {
             delete_limit,
             prune_max_blocks_per_run,
             highest_snapshots_tracker,
+            finished_exex_height: watch::channel(FinishedExExHeight::NoExExs).1,
             metrics: Metrics::default(),
             listeners: Default::default(),
         }
     }
 
+    /// Sets the receiver of the lowest block height all execution extensions have finished
+    /// processing.
+    pub fn with_finished_exex_height(
+        mut self,
+        finished_exex_height: watch::Receiver<FinishedExExHeight>,
+    ) -> Self {
+        self.finished_exex_height = finished_exex_height;
+        self
+    }
+
     /// Listen for events on the prune.
     pub fn events(&mut self) -> UnboundedReceiverStream<PrunerEvent> {
         self.listeners.new_listener()
@@ -80,6 +97,15 @@ impl<DB: Database> Pruner<DB> {
             return Ok(PruneProgress::Finished)
         }
 
+        let tip_block_number = match *self.finished_exex_height.borrow() {
+            FinishedExExHeight::NoExExs => tip_block_number,
+            FinishedExExHeight::NotReady => {
+                debug!(target: "pruner", %tip_block_number, "ExExes are not ready, skipping");
+                return Ok(PruneProgress::Finished)
+            }
+            FinishedExExHeight::Height(height) => tip_block_number.min(height),
+        };
+
         trace!(target: "pruner", %tip_block_number, "Pruner started");
         let start = Instant::now();
 
@@ -88,7 +114,6 @@ impl<DB: Database> Pruner<DB> {
         let mut done = true;
         let mut stats = BTreeMap::new();
 
-        // TODO(alexey): prune snapshotted segments of data (headers, transactions)
         let highest_snapshots = *self.highest_snapshots_tracker.borrow();
 
         // Multiply `self.delete_limit` (number of rows to delete per block) by number of blocks
@@ -152,51 +177,32 @@ impl<DB: Database> Pruner<DB> {
         }
 
         if let Some(snapshots) = highest_snapshots {
-            if let (Some(to_block), true) = (snapshots.headers, delete_limit > 0) {
-                let prune_mode = PruneMode::Before(to_block + 1);
-                trace!(
-                    target: "pruner",
-                    prune_segment = ?PruneSegment::Headers,
-                    %to_block,
-                    ?prune_mode,
-                    "Got target block to prune"
-                );
-
-                let segment_start = Instant::now();
-                let segment = segments::Headers::new(prune_mode);
-                let previous_checkpoint = provider.get_prune_checkpoint(PruneSegment::Headers)?;
-                let output = segment
-                    .prune(&provider, PruneInput { previous_checkpoint, to_block, delete_limit })?;
-                if let Some(checkpoint) = output.checkpoint {
-                    segment
-                        .save_checkpoint(&provider, checkpoint.as_prune_checkpoint(prune_mode))?;
+            // Data that was moved to snapshots is served from there, so it's removed from the
+            // database up to the highest snapshotted block of each segment.
+            let snapshotted_segments: [(_, fn(PruneMode) -> Box<dyn Segment<DB>>); 3] = [
+                (snapshots.headers, |mode| Box::new(segments::Headers::new(mode))),
+                (snapshots.transactions, |mode| Box::new(segments::Transactions::new(mode))),
+                (snapshots.receipts, |mode| Box::new(segments::Receipts::new(mode))),
+            ];
+
+            for (highest_block, segment) in snapshotted_segments {
+                let Some(to_block) = highest_block else { continue };
+                if delete_limit == 0 {
+                    break
                 }
-                self.metrics
-                    .get_prune_segment_metrics(PruneSegment::Headers)
-                    .duration_seconds
-                    .record(segment_start.elapsed());
-
-                done = done && output.done;
-                delete_limit = delete_limit.saturating_sub(output.pruned);
-                stats.insert(
-                    PruneSegment::Headers,
-                    (PruneProgress::from_done(output.done), output.pruned),
-                );
-            }
 
-            if let (Some(to_block), true) = (snapshots.transactions, delete_limit > 0) {
                 let prune_mode = PruneMode::Before(to_block + 1);
+                let segment = segment(prune_mode);
                 trace!(
                     target: "pruner",
-                    prune_segment = ?PruneSegment::Transactions,
+                    prune_segment = ?segment.segment(),
                     %to_block,
                     ?prune_mode,
                     "Got target block to prune"
                 );
 
                 let segment_start = Instant::now();
-                let segment = segments::Transactions::new(prune_mode);
-                let previous_checkpoint = provider.get_prune_checkpoint(PruneSegment::Headers)?;
+                let previous_checkpoint = provider.get_prune_checkpoint(segment.segment())?;
                 let output = segment
                     .prune(&provider, PruneInput { previous_checkpoint, to_block, delete_limit })?;
                 if let Some(checkpoint) = output.checkpoint {
@@ -204,14 +210,14 @@ impl<DB: Database> Pruner<DB> {
                         .save_checkpoint(&provider, checkpoint.as_prune_checkpoint(prune_mode))?;
                 }
                 self.metrics
-                    .get_prune_segment_metrics(PruneSegment::Transactions)
+                    .get_prune_segment_metrics(segment.segment())
                     .duration_seconds
                     .record(segment_start.elapsed());
 
                 done = done && output.done;
                 delete_limit = delete_limit.saturating_sub(output.pruned);
                 stats.insert(
-                    PruneSegment::Transactions,
+                    segment.segment(),
                     (PruneProgress::from_done(output.done), output.pruned),
                 );
             }
@@ -264,9 +270,17 @@ impl<DB: Database> Pruner<DB> {
 #[cfg(test)]
 mod tests {
     use crate::Pruner;
-    use reth_db::test_utils::create_test_rw_db;
-    use reth_primitives::MAINNET;
-    use reth_provider::ProviderFactory;
+    use reth_db::{tables, test_utils::create_test_rw_db};
+    use reth_interfaces::test_utils::{
+        generators,
+        generators::{random_block_range, random_receipt},
+    };
+    use reth_primitives::{
+        snapshot::HighestSnapshots, PruneCheckpoint, PruneMode, PruneProgress, PruneSegment, B256,
+        MAINNET,
+    };
+    use reth_provider::{ProviderFactory, PruneCheckpointReader};
+    use reth_stages::test_utils::TestStageDB;
     use tokio::sync::watch;
 
     #[test]
@@ -289,4 +303,50 @@ mod tests {
         let third_block_number = second_block_number;
         assert!(!pruner.is_pruning_needed(third_block_number));
     }
+
+    #[test]
+    fn prune_snapshotted_receipts() {
+        let db = TestStageDB::default();
+        let mut rng = generators::rng();
+
+        let blocks = random_block_range(&mut rng, 1..=10, B256::ZERO, 2..3);
+        db.insert_blocks(blocks.iter(), None).expect("insert blocks");
+
+        let mut receipts = Vec::new();
+        for block in &blocks {
+            for transaction in &block.body {
+                receipts
+                    .push((receipts.len() as u64, random_receipt(&mut rng, transaction, Some(0))));
+            }
+        }
+        db.insert_receipts(receipts.clone()).expect("insert receipts");
+
+        let snapshotted_block = 5;
+        let (_snapshots_tx, snapshots_rx) = watch::channel(Some(HighestSnapshots {
+            receipts: Some(snapshotted_block),
+            ..Default::default()
+        }));
+        let mut pruner = Pruner::new(db.factory.clone(), vec![], 5, 100, 5, snapshots_rx);
+
+        assert_eq!(pruner.run(10).unwrap(), PruneProgress::Finished);
+
+        // receipts up to the highest snapshotted block are removed from the database
+        let snapshotted_txs = blocks
+            .iter()
+            .take(snapshotted_block as usize)
+            .map(|block| block.body.len())
+            .sum::<usize>();
+        let remaining = db.table::<tables::Receipts>().unwrap();
+        assert_eq!(remaining.len(), receipts.len() - snapshotted_txs);
+        assert!(remaining.iter().all(|(tx_number, _)| *tx_number >= snapshotted_txs as u64));
+
+        assert_eq!(
+            db.factory.provider().unwrap().get_prune_checkpoint(PruneSegment::Receipts).unwrap(),
+            Some(PruneCheckpoint {
+                block_number: Some(snapshotted_block),
+                tx_number: Some(snapshotted_txs as u64 - 1),
+                prune_mode: PruneMode::Before(snapshotted_block + 1),
+            })
+        );
+    }
 }
@@ -0,0 +1,207 @@
+use reth_primitives::Address;
+use revm::{
+    inspectors::NoOpInspector,
+    precompile::{PrecompileWithAddress, Precompiles, SpecId as PrecompilesSpecId},
+    primitives::{specification::*, EVMResult, Env, SpecId},
+    Database, EVMImpl, Inspector, Transact, EVM,
+};
+use std::fmt;
+
+/// Configuration of the EVM that is shared by block execution, payload building and RPC calls.
+///
+/// This allows chains with additional precompiled contracts to reuse the executor: the custom
+/// precompiles are installed on top of the precompiles of the active hardfork and take precedence
+/// over them.
+#[derive(Clone, Copy, Default)]
+pub struct EvmConfig {
+    /// The additional precompiles.
+    precompiles: &'static [PrecompileWithAddress],
+}
+
+impl EvmConfig {
+    /// Sets the additional precompiles, replacing any previously set precompiles.
+    pub fn with_precompiles(mut self, precompiles: &'static [PrecompileWithAddress]) -> Self {
+        self.precompiles = precompiles;
+        self
+    }
+
+    /// Returns true if no additional precompiles are registered.
+    pub fn is_default(&self) -> bool {
+        self.precompiles.is_empty()
+    }
+
+    /// Returns all precompiles that are active in the given spec.
+    pub fn precompiles(&self, spec_id: SpecId) -> Precompiles {
+        let mut precompiles = Precompiles::new(PrecompilesSpecId::from_spec_id(spec_id)).clone();
+        precompiles.extend(self.precompiles.iter().cloned());
+        precompiles
+    }
+
+    /// Returns the addresses of all precompiles that are active in the given spec.
+    pub fn precompile_addresses(&self, spec_id: SpecId) -> Vec<Address> {
+        let mut addresses = Precompiles::new(PrecompilesSpecId::from_spec_id(spec_id))
+            .addresses()
+            .into_iter()
+            .copied()
+            .map(Address::from)
+            .collect::<Vec<_>>();
+        for PrecompileWithAddress(address, _) in self.precompiles.iter() {
+            if !addresses.contains(address) {
+                addresses.push(*address);
+            }
+        }
+        addresses
+    }
+
+    /// Executes the transaction of the given EVM, without committing the state changes.
+    ///
+    /// This is the same as [EVM::transact], with the additional precompiles installed.
+    pub fn transact<DB: Database>(&self, evm: &mut EVM<DB>) -> EVMResult<DB::Error> {
+        if self.is_default() {
+            return evm.transact()
+        }
+        let db = evm.db.as_mut().expect("Database needs to be set");
+        let mut inspector = NoOpInspector;
+        self.evm_inner::<DB, false>(&mut evm.env, db, &mut inspector).transact()
+    }
+
+    /// Executes the transaction of the given EVM with the given inspector, without committing the
+    /// state changes.
+    ///
+    /// This is the same as [EVM::inspect], with the additional precompiles installed.
+    pub fn inspect<DB: Database, I: Inspector<DB>>(
+        &self,
+        evm: &mut EVM<DB>,
+        mut inspector: I,
+    ) -> EVMResult<DB::Error> {
+        if self.is_default() {
+            return evm.inspect(inspector)
+        }
+        let db = evm.db.as_mut().expect("Database needs to be set");
+        self.evm_inner::<DB, true>(&mut evm.env, db, &mut inspector).transact()
+    }
+
+    /// Executes the transaction of the given EVM with the given inspector trait object, without
+    /// committing the state changes.
+    pub fn inspect_dyn<DB: Database>(
+        &self,
+        evm: &mut EVM<DB>,
+        inspector: &mut dyn Inspector<DB>,
+    ) -> EVMResult<DB::Error> {
+        let db = evm.db.as_mut().expect("Database needs to be set");
+        self.evm_inner::<DB, true>(&mut evm.env, db, inspector).transact()
+    }
+
+    /// Creates the EVM for the spec of the given environment.
+    fn evm_inner<'a, DB: Database, const INSPECT: bool>(
+        &self,
+        env: &'a mut Env,
+        db: &'a mut DB,
+        inspector: &'a mut dyn Inspector<DB>,
+    ) -> Box<dyn Transact<DB::Error> + 'a> {
+        let spec_id = env.cfg.spec_id;
+        let precompiles = self.precompiles(spec_id);
+
+        macro_rules! create_evm {
+            ($spec:ident) => {
+                Box::new(EVMImpl::<'a, $spec, DB, INSPECT>::new_with_spec(
+                    db,
+                    env,
+                    inspector,
+                    precompiles,
+                ))
+            };
+        }
+
+        match spec_id {
+            SpecId::FRONTIER | SpecId::FRONTIER_THAWING => create_evm!(FrontierSpec),
+            SpecId::HOMESTEAD | SpecId::DAO_FORK => create_evm!(HomesteadSpec),
+            SpecId::TANGERINE => create_evm!(TangerineSpec),
+            SpecId::SPURIOUS_DRAGON => create_evm!(SpuriousDragonSpec),
+            SpecId::BYZANTIUM => create_evm!(ByzantiumSpec),
+            SpecId::PETERSBURG | SpecId::CONSTANTINOPLE => create_evm!(PetersburgSpec),
+            SpecId::ISTANBUL | SpecId::MUIR_GLACIER => create_evm!(IstanbulSpec),
+            SpecId::BERLIN => create_evm!(BerlinSpec),
+            SpecId::LONDON | SpecId::ARROW_GLACIER | SpecId::GRAY_GLACIER => {
+                create_evm!(LondonSpec)
+            }
+            SpecId::MERGE => create_evm!(MergeSpec),
+            SpecId::SHANGHAI => create_evm!(ShanghaiSpec),
+            SpecId::CANCUN => create_evm!(CancunSpec),
+            SpecId::LATEST => create_evm!(LatestSpec),
+            #[cfg(feature = "optimism")]
+            SpecId::BEDROCK => create_evm!(BedrockSpec),
+            #[cfg(feature = "optimism")]
+            SpecId::REGOLITH => create_evm!(RegolithSpec),
+            #[cfg(feature = "optimism")]
+            SpecId::CANYON => create_evm!(CanyonSpec),
+        }
+    }
+}
+
+// Precompiles can't be compared, so configs are equal if they share the same precompiles.
+impl PartialEq for EvmConfig {
+    fn eq(&self, other: &Self) -> bool {
+        (self.precompiles.is_empty() && other.precompiles.is_empty()) ||
+            std::ptr::eq(self.precompiles, other.precompiles)
+    }
+}
+
+impl Eq for EvmConfig {}
+
+impl fmt::Debug for EvmConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EvmConfig")
+            .field(
+                "precompiles",
+                &self.precompiles.iter().map(|precompile| precompile.0).collect::<Vec<_>>(),
+            )
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use revm::{
+        db::{CacheDB, EmptyDB},
+        precompile::{Precompile, PrecompileOutput, PrecompileResult},
+        primitives::{ExecutionResult, Output, TransactTo, U256},
+    };
+
+    fn echo(input: &[u8], _gas_limit: u64) -> PrecompileResult {
+        Ok(PrecompileOutput::without_logs(100, input.to_vec()))
+    }
+
+    static ECHO: [PrecompileWithAddress; 1] =
+        [PrecompileWithAddress(Address::with_last_byte(0xff), Precompile::Standard(echo))];
+
+    #[test]
+    fn custom_precompile() {
+        let address = ECHO[0].0;
+        let config = EvmConfig::default().with_precompiles(&ECHO);
+        assert!(config.precompile_addresses(SpecId::CANCUN).contains(&address));
+        assert!(!EvmConfig::default().precompile_addresses(SpecId::CANCUN).contains(&address));
+        assert_eq!(config, EvmConfig::default().with_precompiles(&ECHO));
+        assert_ne!(config, EvmConfig::default());
+
+        let mut evm = EVM::new();
+        evm.database(CacheDB::new(EmptyDB::default()));
+        evm.env.cfg.spec_id = SpecId::CANCUN;
+        evm.env.block.basefee = U256::ZERO;
+        evm.env.tx.gas_limit = 100_000;
+        evm.env.tx.gas_price = U256::ZERO;
+        evm.env.tx.transact_to = TransactTo::Call(address);
+        evm.env.tx.data = vec![1, 2, 3].into();
+
+        let result = config.transact(&mut evm).unwrap().result;
+        let ExecutionResult::Success { output: Output::Call(output), .. } = result else {
+            panic!("expected a successful call")
+        };
+        assert_eq!(output.as_ref(), &[1, 2, 3]);
+
+        // without the precompile, the call goes to an empty account
+        let result = EvmConfig::default().transact(&mut evm).unwrap().result;
+        assert_eq!(result.output().map(|output| output.len()), Some(0));
+    }
+}
@@ -0,0 +1,25 @@
+use reth_primitives::TransactionSigned;
+use reth_provider::ProviderError;
+use revm::{db::StateDBBox, primitives::Env, Inspector};
+use std::fmt;
+
+/// An inspector that is attached to the execution of a single transaction of a block.
+pub type ExecutionInspector<'a> = dyn Inspector<StateDBBox<'a, ProviderError>> + 'a;
+
+/// Creates the inspectors that are attached to block execution, e.g. in the execution stage and
+/// the blockchain tree.
+///
+/// This can be used to stream opcode or call level data of the executed blocks to an external
+/// service. If an executor has no factory, transactions are executed without any inspector.
+pub trait ExecutionInspectorFactory: fmt::Debug + Send + Sync {
+    /// Returns the inspector for the given transaction, which is executed in the given
+    /// environment.
+    ///
+    /// The block environment contains the number, timestamp and beneficiary of the executed block.
+    /// If `None` is returned, the transaction is executed without an inspector.
+    fn inspector<'a>(
+        &self,
+        env: &Env,
+        transaction: &TransactionSigned,
+    ) -> Option<Box<ExecutionInspector<'a>>>;
+}
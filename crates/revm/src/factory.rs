@@ -1,7 +1,9 @@
 use crate::{
     database::StateProviderDatabase,
+    execution_inspector::ExecutionInspectorFactory,
     processor::EVMProcessor,
     stack::{InspectorStack, InspectorStackConfig},
+    EvmConfig,
 };
 use reth_primitives::ChainSpec;
 use reth_provider::{ExecutorFactory, PrunableBlockExecutor, StateProvider};
@@ -12,12 +14,21 @@ use std::sync::Arc;
 pub struct EvmProcessorFactory {
     chain_spec: Arc<ChainSpec>,
     stack: Option<InspectorStack>,
+    evm_config: EvmConfig,
+    inspector_factory: Option<Arc<dyn ExecutionInspectorFactory>>,
+    parallel_execution: bool,
 }
 
 impl EvmProcessorFactory {
     /// Create new factory
     pub fn new(chain_spec: Arc<ChainSpec>) -> Self {
-        Self { chain_spec, stack: None }
+        Self {
+            chain_spec,
+            stack: None,
+            evm_config: EvmConfig::default(),
+            inspector_factory: None,
+            parallel_execution: false,
+        }
     }
 
     /// Sets the inspector stack for all generated executors.
@@ -31,6 +42,28 @@ impl EvmProcessorFactory {
         self.stack = Some(InspectorStack::new(config));
         self
     }
+
+    /// Sets the EVM configuration for all generated executors.
+    pub fn with_evm_config(mut self, evm_config: EvmConfig) -> Self {
+        self.evm_config = evm_config;
+        self
+    }
+
+    /// Sets the factory of the inspectors that are attached to the execution of all generated
+    /// executors.
+    pub fn with_inspector_factory(mut self, factory: Arc<dyn ExecutionInspectorFactory>) -> Self {
+        self.inspector_factory = Some(factory);
+        self
+    }
+
+    /// Enables optimistic parallel execution of the transactions of a block for all generated
+    /// executors.
+    ///
+    /// This has no effect on optimism.
+    pub fn with_parallel_execution(mut self, parallel_execution: bool) -> Self {
+        self.parallel_execution = parallel_execution;
+        self
+    }
 }
 
 impl ExecutorFactory for EvmProcessorFactory {
@@ -38,11 +71,24 @@ impl ExecutorFactory for EvmProcessorFactory {
         &'a self,
         sp: SP,
     ) -> Box<dyn PrunableBlockExecutor + 'a> {
-        let database_state = StateProviderDatabase::new(sp);
-        let mut evm = Box::new(EVMProcessor::new_with_db(self.chain_spec.clone(), database_state));
+        let mut evm = if self.parallel_execution && cfg!(not(feature = "optimism")) {
+            let sp = Arc::new(sp);
+            let database_state = StateProviderDatabase::new(sp.clone());
+            let mut evm =
+                Box::new(EVMProcessor::new_with_db(self.chain_spec.clone(), database_state));
+            evm.set_parallel_provider(sp);
+            evm
+        } else {
+            let database_state = StateProviderDatabase::new(sp);
+            Box::new(EVMProcessor::new_with_db(self.chain_spec.clone(), database_state))
+        };
         if let Some(ref stack) = self.stack {
             evm.set_stack(stack.clone());
         }
+        evm.set_evm_config(self.evm_config);
+        if let Some(ref factory) = self.inspector_factory {
+            evm.set_inspector_factory(factory.clone());
+        }
         evm
     }
 
@@ -7,15 +7,26 @@
 )]
 #![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
 
+/// EVM configuration shared by all EVM users.
+mod config;
+pub use config::EvmConfig;
+
 /// Contains glue code for integrating reth database into revm's [Database].
 pub mod database;
 
+/// Inspectors attached to block execution.
+pub mod execution_inspector;
+
 /// revm implementation of reth block and transaction executors.
 mod factory;
 
 /// new revm account state executor
 pub mod processor;
 
+/// Optimistic parallel execution of block transactions.
+#[cfg(not(feature = "optimism"))]
+mod parallel;
+
 /// State changes that are not related to transactions.
 pub mod state_change;
 
@@ -0,0 +1,327 @@
+//! Optimistic parallel execution of the transactions of a block.
+//!
+//! All transactions of a block are first executed in parallel on top of the state before the
+//! block, recording the accounts and storage slots every transaction reads. The results are then
+//! committed in order, and every transaction that read state written by a previous transaction of
+//! the same block is re-executed on top of the committed state.
+//!
+//! Since every transaction credits its fees to the block beneficiary, the beneficiary is treated
+//! separately: if a transaction does not observe the beneficiary, its reward is applied on top of
+//! the committed state instead of being a conflict.
+
+use crate::{database::StateProviderDatabase, EvmConfig};
+use reth_primitives::{revm::env::fill_tx_env, Address, TransactionSigned, B256, U256};
+use reth_provider::{ProviderError, StateProvider};
+use revm::{
+    db::DatabaseRef,
+    interpreter::{opcode, Interpreter},
+    primitives::{AccountInfo, Bytecode, EVMError, Env, ResultAndState, State as EvmState},
+    CacheState, Database, EVMData, Inspector, EVM,
+};
+use std::collections::{BTreeMap, HashSet};
+
+/// The minimum number of transactions of a block for it to be executed in parallel.
+pub(crate) const MIN_PARALLEL_TRANSACTIONS: usize = 4;
+
+/// A read-only view of the state before a block, shared by all workers.
+pub(crate) struct StateView<'v> {
+    /// The accounts and contracts changed or loaded by the executor so far.
+    cache: &'v CacheState,
+    /// The block hashes loaded by the executor so far.
+    block_hashes: &'v BTreeMap<u64, B256>,
+    /// The state the executor is based on.
+    db: StateProviderDatabase<&'v (dyn StateProvider + 'v)>,
+}
+
+impl<'v> StateView<'v> {
+    /// Creates a new view of the given executor state.
+    pub(crate) fn new(
+        cache: &'v CacheState,
+        block_hashes: &'v BTreeMap<u64, B256>,
+        provider: &'v (dyn StateProvider + 'v),
+    ) -> Self {
+        Self { cache, block_hashes, db: StateProviderDatabase::new(provider) }
+    }
+
+    /// Returns the account info of the given address.
+    pub(crate) fn basic(&self, address: Address) -> Result<Option<AccountInfo>, ProviderError> {
+        match self.cache.accounts.get(&address) {
+            Some(account) => Ok(account.account_info()),
+            None => self.db.basic_ref(address),
+        }
+    }
+
+    fn code_by_hash(&self, code_hash: B256) -> Result<Bytecode, ProviderError> {
+        match self.cache.contracts.get(&code_hash) {
+            Some(code) => Ok(code.clone()),
+            None => self.db.code_by_hash_ref(code_hash),
+        }
+    }
+
+    fn storage(&self, address: Address, index: U256) -> Result<U256, ProviderError> {
+        if let Some(account) = self.cache.accounts.get(&address) {
+            let Some(plain_account) = &account.account else { return Ok(U256::ZERO) };
+            if let Some(value) = plain_account.storage.get(&index) {
+                return Ok(*value)
+            }
+            if account.status.storage_known() {
+                return Ok(U256::ZERO)
+            }
+        }
+        self.db.storage_ref(address, index)
+    }
+
+    fn block_hash(&self, number: U256) -> Result<B256, ProviderError> {
+        if let Some(hash) = u64::try_from(number).ok().and_then(|n| self.block_hashes.get(&n)) {
+            return Ok(*hash)
+        }
+        self.db.block_hash_ref(number)
+    }
+}
+
+/// The accounts and storage slots read by a transaction.
+#[derive(Debug, Default)]
+struct ReadSet {
+    accounts: HashSet<Address>,
+    storage: HashSet<(Address, U256)>,
+}
+
+/// The accounts and storage slots written by the committed transactions of a block.
+#[derive(Debug, Default)]
+pub(crate) struct WriteSet {
+    accounts: HashSet<Address>,
+    storage: HashSet<(Address, U256)>,
+    /// Accounts whose storage was cleared, because they were created or destroyed.
+    cleared: HashSet<Address>,
+}
+
+impl WriteSet {
+    /// Adds the changes of a committed transaction.
+    pub(crate) fn extend(&mut self, state: &EvmState) {
+        for (address, account) in state {
+            if !account.is_touched() {
+                continue
+            }
+            self.accounts.insert(*address);
+            if account.is_created() || account.is_selfdestructed() {
+                self.cleared.insert(*address);
+            }
+            for (slot, value) in &account.storage {
+                if value.is_changed() {
+                    self.storage.insert((*address, *slot));
+                }
+            }
+        }
+    }
+
+    /// Returns true if any of the reads, except for the ignored account, was written.
+    fn conflicts(&self, reads: &ReadSet, ignored: Option<Address>) -> bool {
+        reads
+            .accounts
+            .iter()
+            .any(|address| Some(*address) != ignored && self.accounts.contains(address)) ||
+            reads
+                .storage
+                .iter()
+                .any(|key| self.storage.contains(key) || self.cleared.contains(&key.0))
+    }
+}
+
+/// A database that records the reads of a transaction from a [StateView].
+struct RecordingDatabase<'v> {
+    view: &'v StateView<'v>,
+    reads: ReadSet,
+}
+
+impl Database for RecordingDatabase<'_> {
+    type Error = ProviderError;
+
+    fn basic(&mut self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+        self.reads.accounts.insert(address);
+        self.view.basic(address)
+    }
+
+    fn code_by_hash(&mut self, code_hash: B256) -> Result<Bytecode, Self::Error> {
+        // code is immutable, so it can't conflict
+        self.view.code_by_hash(code_hash)
+    }
+
+    fn storage(&mut self, address: Address, index: U256) -> Result<U256, Self::Error> {
+        self.reads.storage.insert((address, index));
+        self.view.storage(address, index)
+    }
+
+    fn block_hash(&mut self, number: U256) -> Result<B256, Self::Error> {
+        // block hashes don't change within a block, so they can't conflict
+        self.view.block_hash(number)
+    }
+}
+
+/// An inspector that detects whether a transaction observes the block beneficiary, other than by
+/// crediting it with the fees.
+///
+/// Anything that depends on the balance or the existence of the beneficiary counts as observing
+/// it, i.e. running its code or passing it to an opcode that accesses another account.
+struct BeneficiaryInspector {
+    beneficiary: Address,
+    observed: bool,
+}
+
+impl<DB: Database> Inspector<DB> for BeneficiaryInspector {
+    fn step(&mut self, interp: &mut Interpreter<'_>, _data: &mut EVMData<'_, DB>) {
+        if self.observed {
+            return
+        }
+        if interp.contract.address == self.beneficiary {
+            self.observed = true;
+            return
+        }
+
+        let position = match interp.current_opcode() {
+            opcode::BALANCE |
+            opcode::EXTCODESIZE |
+            opcode::EXTCODECOPY |
+            opcode::EXTCODEHASH |
+            opcode::SELFDESTRUCT => 0,
+            opcode::CALL | opcode::CALLCODE | opcode::DELEGATECALL | opcode::STATICCALL => 1,
+            _ => return,
+        };
+        if let Ok(word) = interp.stack.peek(position) {
+            self.observed = Address::from_word(B256::from(word)) == self.beneficiary;
+        }
+    }
+}
+
+/// The result of executing a transaction on top of the state before the block.
+pub(crate) struct Speculation {
+    result: Result<ResultAndState, EVMError<ProviderError>>,
+    reads: ReadSet,
+    observed_beneficiary: bool,
+}
+
+impl Speculation {
+    /// Executes the transaction on top of the given view in the given block environment and EVM
+    /// configuration.
+    pub(crate) fn execute(
+        view: &StateView<'_>,
+        env: &Env,
+        evm_config: &EvmConfig,
+        transaction: &TransactionSigned,
+        sender: Address,
+    ) -> Self {
+        let beneficiary = env.block.coinbase;
+        let mut evm = EVM::new();
+        evm.env = env.clone();
+        fill_tx_env(&mut evm.env.tx, transaction, sender);
+        evm.database(RecordingDatabase { view, reads: ReadSet::default() });
+
+        let mut inspector = BeneficiaryInspector {
+            beneficiary,
+            observed: sender == beneficiary || transaction.to() == Some(beneficiary),
+        };
+        let result = evm_config.inspect(&mut evm, &mut inspector);
+        let db = evm.db.take().expect("database is set");
+
+        Self { result, reads: db.reads, observed_beneficiary: inspector.observed }
+    }
+
+    /// Returns the result of the transaction if it did not read any state written by the
+    /// previously committed transactions, otherwise it needs to be re-executed.
+    ///
+    /// If the beneficiary reward of the transaction is returned, the beneficiary must be removed
+    /// from the result and credited with the reward instead.
+    pub(crate) fn into_valid(
+        self,
+        written: &WriteSet,
+        beneficiary: Address,
+        beneficiary_before: Option<&AccountInfo>,
+    ) -> Option<(ResultAndState, Option<u128>)> {
+        let result = self.result.ok()?;
+        let reward = if self.observed_beneficiary {
+            None
+        } else {
+            beneficiary_reward(&result.state, beneficiary, beneficiary_before)
+        };
+        if written.conflicts(&self.reads, reward.is_some().then_some(beneficiary)) {
+            return None
+        }
+        Some((result, reward))
+    }
+}
+
+/// Returns the balance credited to the beneficiary by the transaction.
+///
+/// Returns `None` if nothing was credited, since touching an empty beneficiary would remove it.
+fn beneficiary_reward(
+    state: &EvmState,
+    beneficiary: Address,
+    beneficiary_before: Option<&AccountInfo>,
+) -> Option<u128> {
+    let balance_before = beneficiary_before.map(|info| info.balance).unwrap_or_default();
+    let balance_after = state.get(&beneficiary)?.info.balance;
+    let reward = u128::try_from(balance_after.checked_sub(balance_before)?).ok()?;
+    (reward > 0).then_some(reward)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use revm::primitives::{Account, AccountStatus, StorageSlot};
+    use std::collections::HashMap;
+
+    fn touched(storage: HashMap<U256, StorageSlot>) -> Account {
+        let mut account = Account { storage, ..Default::default() };
+        account.status = AccountStatus::Touched;
+        account
+    }
+
+    #[test]
+    fn write_set_conflicts() {
+        let (a, b, beneficiary) = (Address::random(), Address::random(), Address::random());
+        let slot = U256::from(1);
+
+        let mut written = WriteSet::default();
+        written.extend(&EvmState::from([
+            (
+                a,
+                touched(HashMap::from([(
+                    slot,
+                    StorageSlot::new_changed(U256::ZERO, U256::from(2)),
+                )])),
+            ),
+            (beneficiary, touched(HashMap::new())),
+            (b, Account::default()),
+        ]));
+
+        let reads = |accounts: &[Address], storage: &[(Address, U256)]| ReadSet {
+            accounts: accounts.iter().copied().collect(),
+            storage: storage.iter().copied().collect(),
+        };
+
+        // untouched accounts are not written
+        assert!(!written.conflicts(&reads(&[b], &[(b, slot)]), None));
+        // written accounts and slots conflict
+        assert!(written.conflicts(&reads(&[a], &[]), None));
+        assert!(written.conflicts(&reads(&[], &[(a, slot)]), None));
+        assert!(!written.conflicts(&reads(&[], &[(a, U256::from(2))]), None));
+        // the beneficiary only conflicts if it is not ignored
+        assert!(written.conflicts(&reads(&[beneficiary], &[]), None));
+        assert!(!written.conflicts(&reads(&[beneficiary], &[]), Some(beneficiary)));
+    }
+
+    #[test]
+    fn beneficiary_reward_difference() {
+        let beneficiary = Address::random();
+        let before = AccountInfo { balance: U256::from(10), ..Default::default() };
+        let mut account = touched(HashMap::new());
+        account.info.balance = U256::from(15);
+        let state = EvmState::from([(beneficiary, account)]);
+
+        assert_eq!(beneficiary_reward(&state, beneficiary, Some(&before)), Some(5));
+        assert_eq!(beneficiary_reward(&state, beneficiary, None), Some(15));
+        assert_eq!(beneficiary_reward(&state, Address::random(), Some(&before)), None);
+
+        let before = AccountInfo { balance: U256::from(15), ..Default::default() };
+        assert_eq!(beneficiary_reward(&state, beneficiary, Some(&before)), None);
+    }
+}
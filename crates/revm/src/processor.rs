@@ -1,8 +1,10 @@
 use crate::{
     database::StateProviderDatabase,
     eth_dao_fork::{DAO_HARDFORK_BENEFICIARY, DAO_HARDKFORK_ACCOUNTS},
+    execution_inspector::ExecutionInspectorFactory,
     stack::{InspectorStack, InspectorStackConfig},
     state_change::{apply_beacon_root_contract_call, post_block_balance_increments},
+    EvmConfig,
 };
 use reth_interfaces::executor::{BlockExecutionError, BlockValidationError};
 use reth_primitives::{
@@ -21,6 +23,10 @@ use revm::{
 };
 use std::{sync::Arc, time::Instant};
 
+#[cfg(not(feature = "optimism"))]
+use crate::parallel::{Speculation, StateView, WriteSet, MIN_PARALLEL_TRANSACTIONS};
+#[cfg(not(feature = "optimism"))]
+use rayon::prelude::*;
 #[cfg(not(feature = "optimism"))]
 use reth_primitives::revm::compat::into_reth_log;
 #[cfg(not(feature = "optimism"))]
@@ -55,6 +61,10 @@ pub struct EVMProcessor<'a> {
     pub(crate) evm: EVM<StateDBBox<'a, ProviderError>>,
     /// Hook and inspector stack that we want to invoke on that hook.
     stack: InspectorStack,
+    /// The configuration of the EVM, e.g. additional precompiles.
+    evm_config: EvmConfig,
+    /// Creates the inspectors that are attached to the executed transactions, if any.
+    inspector_factory: Option<Arc<dyn ExecutionInspectorFactory>>,
     /// The collection of receipts.
     /// Outer vector stores receipts for each block sequentially.
     /// The inner vector stores receipts ordered by transaction number.
@@ -74,6 +84,9 @@ pub struct EVMProcessor<'a> {
     pruning_address_filter: Option<(u64, Vec<Address>)>,
     /// Execution stats
     pub(crate) stats: BlockExecutorStats,
+    /// The state provider the database is based on, if transactions are executed in parallel.
+    #[cfg_attr(feature = "optimism", allow(dead_code))]
+    parallel: Option<Arc<dyn StateProvider + 'a>>,
 }
 
 impl<'a> EVMProcessor<'a> {
@@ -89,12 +102,15 @@ impl<'a> EVMProcessor<'a> {
             chain_spec,
             evm,
             stack: InspectorStack::new(InspectorStackConfig::default()),
+            evm_config: EvmConfig::default(),
+            inspector_factory: None,
             receipts: Receipts::new(),
             first_block: None,
             tip: None,
             prune_modes: PruneModes::none(),
             pruning_address_filter: None,
             stats: BlockExecutorStats::default(),
+            parallel: None,
         }
     }
 
@@ -122,12 +138,15 @@ impl<'a> EVMProcessor<'a> {
             chain_spec,
             evm,
             stack: InspectorStack::new(InspectorStackConfig::default()),
+            evm_config: EvmConfig::default(),
+            inspector_factory: None,
             receipts: Receipts::new(),
             first_block: None,
             tip: None,
             prune_modes: PruneModes::none(),
             pruning_address_filter: None,
             stats: BlockExecutorStats::default(),
+            parallel: None,
         }
     }
 
@@ -136,6 +155,29 @@ impl<'a> EVMProcessor<'a> {
         self.stack = stack;
     }
 
+    /// Enables optimistic parallel execution of the transactions of a block.
+    ///
+    /// The given provider must be the one the database of the executor is based on, it is used to
+    /// execute the transactions of a block in parallel on top of the state before the block.
+    ///
+    /// Blocks with transactions that should be inspected are always executed sequentially.
+    pub fn set_parallel_provider(&mut self, provider: Arc<dyn StateProvider + 'a>) {
+        self.parallel = Some(provider);
+    }
+
+    /// Configures the EVM of the executor.
+    pub fn set_evm_config(&mut self, evm_config: EvmConfig) {
+        self.evm_config = evm_config;
+    }
+
+    /// Configures the factory of the inspectors that are attached to the executed transactions.
+    ///
+    /// Transactions that are inspected by the [InspectorStack] are not passed to the factory.
+    /// Blocks are always executed sequentially if an inspector factory is configured.
+    pub fn set_inspector_factory(&mut self, factory: Arc<dyn ExecutionInspectorFactory>) {
+        self.inspector_factory = Some(factory);
+    }
+
     /// Configure the executor with the given block.
     pub fn set_first_block(&mut self, num: BlockNumber) {
         self.first_block = Some(num);
@@ -246,16 +288,23 @@ impl<'a> EVMProcessor<'a> {
         let hash = transaction.hash();
         let out = if self.stack.should_inspect(&self.evm.env, hash) {
             // execution with inspector.
-            let output = self.evm.inspect(&mut self.stack);
+            let output = self.evm_config.inspect(&mut self.evm, &mut self.stack);
             tracing::trace!(
                 target: "evm",
                 ?hash, ?output, ?transaction, env = ?self.evm.env,
                 "Executed transaction"
             );
             output
+        } else if let Some(mut inspector) = self
+            .inspector_factory
+            .as_ref()
+            .and_then(|factory| factory.inspector(&self.evm.env, transaction))
+        {
+            // execution with a custom inspector.
+            self.evm_config.inspect_dyn(&mut self.evm, inspector.as_mut())
         } else {
             // main execution.
-            self.evm.transact()
+            self.evm_config.transact(&mut self.evm)
         };
         out.map_err(|e| BlockValidationError::EVM { hash, error: e.into() }.into())
     }
@@ -422,6 +471,15 @@ impl<'a> BlockExecutor for EVMProcessor<'a> {
             return Ok((Vec::new(), 0))
         }
 
+        if let Some(provider) = self.parallel.clone() {
+            if block.body.len() >= MIN_PARALLEL_TRANSACTIONS &&
+                self.inspector_factory.is_none() &&
+                !block.body.iter().any(|tx| self.stack.should_inspect(&self.evm.env, tx.hash()))
+            {
+                return self.execute_transactions_parallel(block, provider.as_ref())
+            }
+        }
+
         let mut cumulative_gas_used = 0;
         let mut receipts = Vec::with_capacity(block.body.len());
         for (sender, transaction) in block.transactions_with_sender() {
@@ -486,6 +544,97 @@ impl<'a> BlockExecutor for EVMProcessor<'a> {
     }
 }
 
+#[cfg(not(feature = "optimism"))]
+impl<'a> EVMProcessor<'a> {
+    /// Executes the transactions of the block in parallel on top of the state before the block,
+    /// then commits them in order and re-executes every transaction that read state written by a
+    /// previous transaction of the block.
+    fn execute_transactions_parallel(
+        &mut self,
+        block: &BlockWithSenders,
+        provider: &(dyn StateProvider + 'a),
+    ) -> Result<(Vec<Receipt>, u64), BlockExecutionError> {
+        let time = Instant::now();
+        let beneficiary = block.header.beneficiary;
+        let (beneficiary_before, speculations) = {
+            let state = self.evm.db.as_ref().expect("Database inside EVM is always set");
+            let view = StateView::new(&state.cache, &state.block_hashes, provider);
+            let beneficiary_before =
+                view.basic(beneficiary).map_err(|_| BlockExecutionError::ProviderError)?;
+            let env = &self.evm.env;
+            let evm_config = &self.evm_config;
+            let speculations = block
+                .body
+                .par_iter()
+                .zip(block.senders.par_iter())
+                .map(|(transaction, sender)| {
+                    Speculation::execute(&view, env, evm_config, transaction, *sender)
+                })
+                .collect::<Vec<_>>();
+            (beneficiary_before, speculations)
+        };
+        self.stats.execution_duration += time.elapsed();
+
+        let mut written = WriteSet::default();
+        let mut cumulative_gas_used = 0;
+        let mut receipts = Vec::with_capacity(block.body.len());
+        for ((sender, transaction), speculation) in
+            block.transactions_with_sender().zip(speculations)
+        {
+            let time = Instant::now();
+            // The sum of the transaction’s gas limit, Tg, and the gas utilized in this block prior,
+            // must be no greater than the block’s gasLimit.
+            let block_available_gas = block.header.gas_limit - cumulative_gas_used;
+            if transaction.gas_limit() > block_available_gas {
+                return Err(BlockValidationError::TransactionGasLimitMoreThanAvailableBlockGas {
+                    transaction_gas_limit: transaction.gas_limit(),
+                    block_available_gas,
+                }
+                .into())
+            }
+            let (ResultAndState { result, mut state }, reward) = match speculation.into_valid(
+                &written,
+                beneficiary,
+                beneficiary_before.as_ref(),
+            ) {
+                Some(valid) => valid,
+                None => {
+                    trace!(target: "evm", hash = ?transaction.hash(), "Re-executing transaction");
+                    (self.transact(transaction, *sender)?, None)
+                }
+            };
+            self.stats.execution_duration += time.elapsed();
+            let time = Instant::now();
+
+            written.extend(&state);
+            if let Some(reward) = reward {
+                // the speculative beneficiary balance is stale, credit the reward instead
+                state.remove(&beneficiary);
+                self.db_mut().commit(state);
+                self.db_mut()
+                    .increment_balances([(beneficiary, reward)])
+                    .map_err(|_| BlockValidationError::IncrementBalanceFailed)?;
+            } else {
+                self.db_mut().commit(state);
+            }
+
+            self.stats.apply_state_duration += time.elapsed();
+
+            // append gas used
+            cumulative_gas_used += result.gas_used();
+
+            receipts.push(Receipt {
+                tx_type: transaction.tx_type(),
+                success: result.is_success(),
+                cumulative_gas_used,
+                logs: result.into_logs().into_iter().map(into_reth_log).collect(),
+            });
+        }
+
+        Ok((receipts, cumulative_gas_used))
+    }
+}
+
 impl<'a> PrunableBlockExecutor for EVMProcessor<'a> {
     fn set_tip(&mut self, tip: BlockNumber) {
         self.tip = Some(tip);
@@ -1006,4 +1155,282 @@ mod tests {
             .unwrap();
         assert_eq!(parent_beacon_block_root_storage, U256::from(0x69));
     }
+
+    #[cfg(not(feature = "optimism"))]
+    #[test]
+    fn inspector_factory_inspects_transactions() {
+        use crate::execution_inspector::ExecutionInspector;
+        use reth_primitives::{Signature, Transaction, TransactionKind, TxLegacy};
+        use revm::{
+            interpreter::{opcode, Interpreter},
+            primitives::Env,
+            EVMData, Inspector,
+        };
+        use std::sync::Mutex;
+
+        /// Records the executed opcodes.
+        struct OpcodeInspector(Arc<Mutex<Vec<u8>>>);
+
+        impl<DB: Database> Inspector<DB> for OpcodeInspector {
+            fn step(&mut self, interp: &mut Interpreter<'_>, _data: &mut EVMData<'_, DB>) {
+                self.0.lock().unwrap().push(interp.current_opcode());
+            }
+        }
+
+        /// Inspects the transactions to the given address.
+        #[derive(Debug)]
+        struct OpcodeInspectorFactory {
+            inspected: Address,
+            opcodes: Arc<Mutex<Vec<u8>>>,
+        }
+
+        impl ExecutionInspectorFactory for OpcodeInspectorFactory {
+            fn inspector<'a>(
+                &self,
+                _env: &Env,
+                transaction: &TransactionSigned,
+            ) -> Option<Box<ExecutionInspector<'a>>> {
+                (transaction.to() == Some(self.inspected)).then(|| {
+                    Box::new(OpcodeInspector(self.opcodes.clone())) as Box<ExecutionInspector<'a>>
+                })
+            }
+        }
+
+        let sender = Address::repeat_byte(0x01);
+        let recipient = Address::repeat_byte(0x02);
+        let counter = Address::repeat_byte(0xc0);
+
+        let mut db = StateProviderTest::default();
+        db.insert_account(
+            sender,
+            Account { balance: U256::from(10).pow(U256::from(18)), ..Default::default() },
+            None,
+            HashMap::new(),
+        );
+        // increments the value of the first slot
+        db.insert_account(
+            counter,
+            Account::default(),
+            Some(bytes!("600054600101600055")),
+            HashMap::from([(B256::ZERO, U256::from(1))]),
+        );
+
+        let transaction = |nonce: u64, to: Address| {
+            TransactionSigned::from_transaction_and_signature(
+                Transaction::Legacy(TxLegacy {
+                    chain_id: Some(1),
+                    nonce,
+                    gas_price: 2,
+                    gas_limit: 100_000,
+                    to: TransactionKind::Call(to),
+                    value: U256::ZERO.into(),
+                    input: Bytes::new(),
+                }),
+                Signature::default(),
+            )
+        };
+        let block = BlockWithSenders {
+            block: Block {
+                header: Header {
+                    number: 1,
+                    timestamp: 12,
+                    gas_limit: 30_000_000,
+                    base_fee_per_gas: Some(1),
+                    ..Default::default()
+                },
+                body: vec![transaction(0, recipient), transaction(1, counter)],
+                ommers: vec![],
+                withdrawals: None,
+            },
+            senders: vec![sender, sender],
+        };
+
+        let chain_spec = Arc::new(ChainSpecBuilder::from(&*MAINNET).shanghai_activated().build());
+        let mut executor =
+            EVMProcessor::new_with_db(chain_spec, StateProviderDatabase::new(db.clone()));
+        let opcodes = Arc::new(Mutex::new(Vec::new()));
+        executor.set_inspector_factory(Arc::new(OpcodeInspectorFactory {
+            inspected: counter,
+            opcodes: opcodes.clone(),
+        }));
+
+        let (receipts, _) = executor.execute_transactions(&block, U256::ZERO).unwrap();
+        assert!(receipts.iter().all(|receipt| receipt.success));
+
+        // only the call to the counter is inspected
+        let opcodes = opcodes.lock().unwrap();
+        assert_eq!(
+            opcodes[..6],
+            [
+                opcode::PUSH1,
+                opcode::SLOAD,
+                opcode::PUSH1,
+                opcode::ADD,
+                opcode::PUSH1,
+                opcode::SSTORE
+            ]
+        );
+        // the state changes of inspected transactions are committed
+        assert_eq!(executor.db_mut().storage(counter, U256::ZERO).unwrap(), U256::from(2));
+    }
+
+    #[cfg(not(feature = "optimism"))]
+    mod parallel {
+        use super::*;
+        use reth_primitives::{
+            proofs::{state_root_unhashed, storage_root_unhashed},
+            revm::compat::into_reth_acc,
+            Signature, Transaction, TransactionKind, TxLegacy,
+        };
+
+        /// Returns the state root of the state of the provider with the changes of the bundle
+        /// applied.
+        fn post_state_root(db: &StateProviderTest, bundle: &BundleStateWithReceipts) -> B256 {
+            let mut state = db
+                .accounts
+                .iter()
+                .map(|(address, (storage, account))| {
+                    let storage = storage
+                        .iter()
+                        .map(|(slot, value)| (U256::from_be_bytes(slot.0), *value))
+                        .collect::<HashMap<_, _>>();
+                    (*address, (*account, storage))
+                })
+                .collect::<HashMap<_, _>>();
+            for (address, account) in bundle.bundle_accounts_iter() {
+                let Some(info) = account.info.clone() else {
+                    state.remove(&address);
+                    continue
+                };
+                let (entry, storage) = state.entry(address).or_default();
+                if account.status.was_destroyed() {
+                    storage.clear();
+                }
+                *entry = into_reth_acc(info);
+                storage.extend(
+                    account.storage.iter().map(|(slot, value)| (*slot, value.present_value)),
+                );
+            }
+
+            state_root_unhashed(state.into_iter().map(|(address, (account, storage))| {
+                let storage = storage.into_iter().filter(|(_, value)| !value.is_zero());
+                let storage_root = storage_root_unhashed(
+                    storage.map(|(slot, value)| (B256::from(slot.to_be_bytes()), value)),
+                );
+                (address, (account, storage_root))
+            }))
+        }
+
+        #[test]
+        fn parallel_execution_matches_sequential() {
+            let beneficiary = Address::repeat_byte(0xbe);
+            let senders = [1, 2, 3, 4].map(Address::repeat_byte);
+            let recipients = [5, 6, 7].map(Address::repeat_byte);
+            let counter = Address::repeat_byte(0xc0);
+
+            let mut db = StateProviderTest::default();
+            for sender in senders {
+                let account =
+                    Account { balance: U256::from(10).pow(U256::from(18)), ..Default::default() };
+                db.insert_account(sender, account, None, HashMap::new());
+            }
+            // increments the value of the first slot
+            db.insert_account(
+                counter,
+                Account::default(),
+                Some(bytes!("600054600101600055")),
+                HashMap::from([(B256::ZERO, U256::from(1))]),
+            );
+
+            let transaction = |nonce: u64, to: Address, value: u64| {
+                TransactionSigned::from_transaction_and_signature(
+                    Transaction::Legacy(TxLegacy {
+                        chain_id: Some(1),
+                        nonce,
+                        gas_price: 2,
+                        gas_limit: 100_000,
+                        to: TransactionKind::Call(to),
+                        value: U256::from(value).into(),
+                        input: Bytes::new(),
+                    }),
+                    Signature::default(),
+                )
+            };
+            let block = |number: u64, transactions: Vec<(Address, TransactionSigned)>| {
+                let (block_senders, body) = transactions.into_iter().unzip();
+                BlockWithSenders {
+                    block: Block {
+                        header: Header {
+                            number,
+                            timestamp: number * 12,
+                            gas_limit: 30_000_000,
+                            base_fee_per_gas: Some(1),
+                            beneficiary,
+                            ..Default::default()
+                        },
+                        body,
+                        ommers: vec![],
+                        withdrawals: None,
+                    },
+                    senders: block_senders,
+                }
+            };
+            let blocks = [
+                // the transactions conflict on the recipient, the counter and the sender
+                block(
+                    1,
+                    vec![
+                        (senders[0], transaction(0, recipients[0], 1)),
+                        (senders[1], transaction(0, counter, 0)),
+                        (senders[2], transaction(0, counter, 0)),
+                        (senders[0], transaction(1, recipients[1], 1)),
+                        (senders[3], transaction(0, recipients[0], 1)),
+                    ],
+                ),
+                // a transaction observes the beneficiary
+                block(
+                    2,
+                    vec![
+                        (senders[1], transaction(1, counter, 0)),
+                        (senders[3], transaction(1, counter, 0)),
+                        (senders[2], transaction(1, beneficiary, 1)),
+                        (senders[0], transaction(2, recipients[2], 1)),
+                    ],
+                ),
+            ];
+
+            let chain_spec =
+                Arc::new(ChainSpecBuilder::from(&*MAINNET).shanghai_activated().build());
+            let execute = |parallel: bool| {
+                let provider = Arc::new(db.clone());
+                let mut executor = EVMProcessor::new_with_db(
+                    chain_spec.clone(),
+                    StateProviderDatabase::new(provider.clone()),
+                );
+                if parallel {
+                    executor.set_parallel_provider(provider);
+                }
+                let receipts = blocks
+                    .iter()
+                    .map(|block| {
+                        let (receipts, _) =
+                            executor.execute_transactions(block, U256::ZERO).unwrap();
+                        executor.db_mut().merge_transitions(BundleRetention::Reverts);
+                        receipts
+                    })
+                    .collect::<Vec<_>>();
+                (receipts, executor.take_output_state())
+            };
+
+            let (sequential_receipts, sequential_state) = execute(false);
+            let (parallel_receipts, parallel_state) = execute(true);
+            assert_eq!(parallel_receipts, sequential_receipts);
+            assert_eq!(
+                post_state_root(&db, &parallel_state),
+                post_state_root(&db, &sequential_state)
+            );
+            assert_eq!(parallel_state.storage(&counter, U256::ZERO), Some(U256::from(5)));
+            assert!(sequential_receipts.iter().flatten().all(|receipt| receipt.success));
+        }
+    }
 }
@@ -13,6 +13,8 @@ pub trait AdminApi {
 
     /// Disconnects from a remote node if the connection exists.
     ///
+    /// The node is also removed from the trusted peer set.
+    ///
     /// Returns true if the peer was successfully removed.
     #[method(name = "removePeer")]
     fn remove_peer(&self, record: NodeRecord) -> RpcResult<bool>;
@@ -5,7 +5,7 @@ use reth_rpc_types::{
         BlockTraceResult, GethDebugTracingCallOptions, GethDebugTracingOptions, GethTrace,
         TraceResult,
     },
-    Bundle, CallRequest, RichBlock, StateContext,
+    Bundle, CallRequest, ExecutionWitness, RichBlock, StateContext,
 };
 
 /// Debug rpc interface.
@@ -130,6 +130,15 @@ pub trait DebugApi {
         opts: Option<GethDebugTracingCallOptions>,
     ) -> RpcResult<Vec<Vec<GethTrace>>>;
 
+    /// Re-executes the block and returns the witness of its execution.
+    ///
+    /// The witness contains the trie nodes, bytecodes and key preimages of all state touched by the
+    /// block and the ancestor headers read by `BLOCKHASH`, which is enough to execute the block
+    /// statelessly on top of the parent state root.
+    #[method(name = "executionWitness")]
+    async fn debug_execution_witness(&self, block: BlockNumberOrTag)
+        -> RpcResult<ExecutionWitness>;
+
     /// Sets the logging backtrace location. When a backtrace location is set and a log message is
     /// emitted at that location,  the stack of the goroutine executing the log statement will
     /// be printed to stderr.
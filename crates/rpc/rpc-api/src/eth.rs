@@ -4,7 +4,9 @@ use reth_primitives::{
     Address, BlockId, BlockNumberOrTag, Bytes, B256, B64, U256, U64,
 };
 use reth_rpc_types::{
-    state::StateOverride, AccessListWithGasUsed, BlockOverrides, Bundle, CallRequest,
+    simulate::{SimulatePayload, SimulatedBlock},
+    state::StateOverride,
+    AccessListWithGasUsed, Account, BlockOverrides, Bundle, CallRequest,
     EIP1186AccountProofResponse, EthCallResponse, FeeHistory, Index, RichBlock, StateContext,
     SyncStatus, Transaction, TransactionReceipt, TransactionRequest, Work,
 };
@@ -162,6 +164,17 @@ pub trait EthApi {
         state_override: Option<StateOverride>,
     ) -> RpcResult<Vec<EthCallResponse>>;
 
+    /// Simulates a series of blocks with calls on top of the given block, with optional block and
+    /// state overrides for each block.
+    ///
+    /// Returns the return data, logs and gas usage of every call.
+    #[method(name = "simulateV1")]
+    async fn simulate_v1(
+        &self,
+        payload: SimulatePayload,
+        block_number: Option<BlockId>,
+    ) -> RpcResult<Vec<SimulatedBlock>>;
+
     /// Generates an access list for a transaction.
     ///
     /// This method creates an [EIP2930](https://eips.ethereum.org/EIPS/eip-2930) type accessList based on a given Transaction.
@@ -191,6 +204,7 @@ pub trait EthApi {
         request: CallRequest,
         block_number: Option<BlockId>,
         state_override: Option<StateOverride>,
+        block_overrides: Option<Box<BlockOverrides>>,
     ) -> RpcResult<U256>;
 
     /// Returns the current price per gas in wei.
@@ -277,4 +291,8 @@ pub trait EthApi {
         keys: Vec<JsonStorageKey>,
         block_number: Option<BlockId>,
     ) -> RpcResult<EIP1186AccountProofResponse>;
+
+    /// Returns the balance, nonce, code hash and storage root of the account at the given block.
+    #[method(name = "getAccount")]
+    async fn get_account(&self, address: Address, block: BlockId) -> RpcResult<Account>;
 }
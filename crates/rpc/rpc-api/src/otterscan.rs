@@ -1,5 +1,5 @@
 use jsonrpsee::{core::RpcResult, proc_macros::rpc};
-use reth_primitives::{Address, BlockId, BlockNumberOrTag, TxHash, B256};
+use reth_primitives::{Address, BlockId, BlockNumberOrTag, Bytes, TxHash, B256};
 use reth_rpc_types::{
     BlockDetails, ContractCreator, InternalOperation, OtsBlockTransactions, TraceEntry,
     Transaction, TransactionsWithReceipts,
@@ -24,13 +24,15 @@ pub trait Otterscan {
     async fn get_internal_operations(&self, tx_hash: TxHash) -> RpcResult<Vec<InternalOperation>>;
 
     /// Given a transaction hash, returns its raw revert reason.
+    ///
+    /// Returns empty bytes if the transaction did not revert.
     #[method(name = "getTransactionError")]
-    async fn get_transaction_error(&self, tx_hash: TxHash) -> RpcResult<String>;
+    async fn get_transaction_error(&self, tx_hash: TxHash) -> RpcResult<Bytes>;
 
     /// Extract all variations of calls, contract creation and self-destructs and returns a call
     /// tree.
     #[method(name = "traceTransaction")]
-    async fn trace_transaction(&self, tx_hash: TxHash) -> RpcResult<TraceEntry>;
+    async fn trace_transaction(&self, tx_hash: TxHash) -> RpcResult<Vec<TraceEntry>>;
 
     /// Tailor-made and expanded version of eth_getBlockByNumber for block details page in
     /// Otterscan.
@@ -14,9 +14,10 @@ use jsonrpsee::{
 use reth_network_api::{NetworkInfo, Peers};
 use reth_node_api::EngineTypes;
 use reth_provider::{
-    BlockReaderIdExt, ChainSpecProvider, EvmEnvProvider, HeaderProvider, ReceiptProviderIdExt,
-    StateProviderFactory,
+    BlockReaderIdExt, ChainSpecProvider, EvmEnvProvider, HeaderProvider, LogIndexReader,
+    PruneCheckpointReader, ReceiptProviderIdExt, StageCheckpointReader, StateProviderFactory,
 };
+use reth_revm::EvmConfig;
 use reth_rpc::{
     eth::{
         cache::EthStateCache, gas_oracle::GasPriceOracle, EthFilterConfig, FeeHistoryCache,
@@ -48,6 +49,9 @@ where
     Provider: BlockReaderIdExt
         + ChainSpecProvider
         + EvmEnvProvider
+        + StageCheckpointReader
+        + LogIndexReader
+        + PruneCheckpointReader
         + HeaderProvider
         + ReceiptProviderIdExt
         + StateProviderFactory
@@ -78,6 +82,7 @@ where
         Box::new(executor.clone()),
         BlockingTaskPool::build().expect("failed to build tracing pool"),
         fee_history_cache,
+        EvmConfig::default(),
     );
     let config = EthFilterConfig::default()
         .max_logs_per_response(DEFAULT_MAX_LOGS_PER_RESPONSE)
@@ -99,6 +104,9 @@ where
     Provider: BlockReaderIdExt
         + ChainSpecProvider
         + EvmEnvProvider
+        + StageCheckpointReader
+        + LogIndexReader
+        + PruneCheckpointReader
         + HeaderProvider
         + StateProviderFactory
         + Clone
@@ -139,6 +147,8 @@ pub struct AuthServerConfig {
     pub(crate) socket_addr: SocketAddr,
     /// The secret for the auth layer of the server.
     pub(crate) secret: JwtSecret,
+    /// The validator of the auth layer, if it accepts more than `secret`.
+    pub(crate) jwt_validator: Option<JwtAuthValidator>,
     /// Configs for JSON-RPC Http.
     pub(crate) server_config: ServerBuilder,
 }
@@ -156,13 +166,18 @@ impl AuthServerConfig {
         self.socket_addr
     }
 
+    /// Returns the configured validator for the auth layer, if any.
+    pub fn jwt_validator(&self) -> Option<&JwtAuthValidator> {
+        self.jwt_validator.as_ref()
+    }
+
     /// Convenience function to start a server in one step.
     pub async fn start(self, module: AuthRpcModule) -> Result<AuthServerHandle, RpcError> {
-        let Self { socket_addr, secret, server_config } = self;
+        let Self { socket_addr, secret, jwt_validator, server_config } = self;
 
         // Create auth middleware.
-        let middleware = tower::ServiceBuilder::new()
-            .layer(AuthLayer::new(JwtAuthValidator::new(secret.clone())));
+        let validator = jwt_validator.unwrap_or_else(|| JwtAuthValidator::new(secret.clone()));
+        let middleware = tower::ServiceBuilder::new().layer(AuthLayer::new(validator));
 
         // By default, both http and ws are enabled.
         let server =
@@ -182,6 +197,7 @@ impl AuthServerConfig {
 pub struct AuthServerConfigBuilder {
     socket_addr: Option<SocketAddr>,
     secret: JwtSecret,
+    jwt_validator: Option<JwtAuthValidator>,
     server_config: Option<ServerBuilder>,
 }
 
@@ -190,7 +206,7 @@ pub struct AuthServerConfigBuilder {
 impl AuthServerConfigBuilder {
     /// Create a new `AuthServerConfigBuilder` with the given `secret`.
     pub fn new(secret: JwtSecret) -> Self {
-        Self { socket_addr: None, secret, server_config: None }
+        Self { socket_addr: None, secret, jwt_validator: None, server_config: None }
     }
 
     /// Set the socket address for the server.
@@ -211,6 +227,17 @@ impl AuthServerConfigBuilder {
         self
     }
 
+    /// Set the validator for the auth layer of the server.
+    ///
+    /// This can be used to accept additional secrets, e.g. during a secret rotation, or to replace
+    /// the secrets without restarting the server, see [JwtAuthValidator::set_secrets]. The
+    /// validator should accept the secret of the builder, which is used by the clients of the
+    /// [AuthServerHandle].
+    pub fn jwt_validator(mut self, validator: JwtAuthValidator) -> Self {
+        self.jwt_validator = Some(validator);
+        self
+    }
+
     /// Configures the JSON-RPC server
     ///
     /// Note: this always configures an [EthSubscriptionIdProvider]
@@ -227,6 +254,7 @@ impl AuthServerConfigBuilder {
                 SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), constants::DEFAULT_AUTH_PORT)
             }),
             secret: self.secret,
+            jwt_validator: self.jwt_validator,
             server_config: self.server_config.unwrap_or_else(|| {
                 ServerBuilder::new()
                     // This needs to large enough to handle large eth_getLogs responses and maximum
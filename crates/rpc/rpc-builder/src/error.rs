@@ -95,4 +95,15 @@ pub enum WsHttpSamePortError {
         /// Ws modules.
         ws_modules: Vec<RethRpcModule>,
     },
+    /// Ws and http server configured on same port but with different gas caps.
+    #[error(
+        "different gas caps for HTTP and WS on the same port is currently not supported: \
+         HTTP: {http_gas_cap:?}, WS: {ws_gas_cap:?}"
+    )]
+    ConflictingGasCaps {
+        /// Http gas cap.
+        http_gas_cap: Option<u64>,
+        /// Ws gas cap.
+        ws_gas_cap: Option<u64>,
+    },
 }
@@ -3,7 +3,7 @@ use crate::constants::{
 };
 use reth_rpc::{
     eth::{
-        cache::{EthStateCache, EthStateCacheConfig},
+        cache::{EthStateCache, EthStateCacheConfig, TraceCache},
         gas_oracle::GasPriceOracleConfig,
         EthFilterConfig, FeeHistoryCacheConfig, RPC_DEFAULT_GAS_CAP,
     },
@@ -24,6 +24,8 @@ pub struct EthHandlers<Provider, Pool, Network, Events> {
     pub pubsub: EthPubSub<Provider, Pool, Events, Network>,
     /// The configured tracing call pool
     pub blocking_task_pool: BlockingTaskPool,
+    /// The cache for the results of tracing calls, shared by the `debug` and `trace` handlers
+    pub trace_cache: TraceCache,
 }
 
 /// Additional config values for the eth namespace
@@ -20,7 +20,8 @@
 //! use reth_network_api::{NetworkInfo, Peers};
 //! use reth_provider::{
 //!     AccountReader, BlockReaderIdExt, CanonStateSubscriptions, ChainSpecProvider,
-//!     ChangeSetReader, EvmEnvProvider, StateProviderFactory,
+//!     ChangeSetReader, EvmEnvProvider, LogIndexReader, PruneCheckpointReader,
+//!     StageCheckpointReader, StateProviderFactory, TransactionAddressIndexReader,
 //! };
 //! use reth_rpc_builder::{
 //!     RethRpcModule, RpcModuleBuilder, RpcServerConfig, ServerBuilder, TransportRpcModuleConfig,
@@ -37,8 +38,12 @@
 //!         + BlockReaderIdExt
 //!         + ChainSpecProvider
 //!         + ChangeSetReader
+//!         + LogIndexReader
+//!         + TransactionAddressIndexReader
+//!         + PruneCheckpointReader
 //!         + StateProviderFactory
 //!         + EvmEnvProvider
+//!         + StageCheckpointReader
 //!         + Clone
 //!         + Unpin
 //!         + 'static,
@@ -72,7 +77,8 @@
 //! use reth_node_api::EngineTypes;
 //! use reth_provider::{
 //!     AccountReader, BlockReaderIdExt, CanonStateSubscriptions, ChainSpecProvider,
-//!     ChangeSetReader, EvmEnvProvider, StateProviderFactory,
+//!     ChangeSetReader, EvmEnvProvider, LogIndexReader, PruneCheckpointReader,
+//!     StageCheckpointReader, StateProviderFactory, TransactionAddressIndexReader,
 //! };
 //! use reth_rpc::JwtSecret;
 //! use reth_rpc_api::EngineApiServer;
@@ -94,8 +100,12 @@
 //!         + BlockReaderIdExt
 //!         + ChainSpecProvider
 //!         + ChangeSetReader
+//!         + LogIndexReader
+//!         + TransactionAddressIndexReader
+//!         + PruneCheckpointReader
 //!         + StateProviderFactory
 //!         + EvmEnvProvider
+//!         + StageCheckpointReader
 //!         + Clone
 //!         + Unpin
 //!         + 'static,
@@ -145,7 +155,7 @@ use std::{
 };
 
 use hyper::{header::AUTHORIZATION, HeaderMap};
-pub use jsonrpsee::server::ServerBuilder;
+pub use jsonrpsee::server::{BatchRequestConfig, ServerBuilder};
 use jsonrpsee::{
     server::{IdProvider, Server, ServerHandle},
     Methods, RpcModule,
@@ -164,18 +174,21 @@ pub use reth_ipc::server::{Builder as IpcServerBuilder, Endpoint};
 use reth_network_api::{noop::NoopNetwork, NetworkInfo, Peers};
 use reth_provider::{
     AccountReader, BlockReader, BlockReaderIdExt, CanonStateSubscriptions, ChainSpecProvider,
-    ChangeSetReader, EvmEnvProvider, StateProviderFactory,
+    ChangeSetReader, EvmEnvProvider, LogIndexReader, PruneCheckpointReader, StageCheckpointReader,
+    StateProviderFactory, TransactionAddressIndexReader,
 };
+use reth_revm::EvmConfig;
+pub use reth_rpc::RateLimitConfig;
 use reth_rpc::{
     eth::{
-        cache::{cache_new_blocks_task, EthStateCache},
+        cache::{cache_new_blocks_task, trace_cache_reorg_task, EthStateCache, TraceCache},
         fee_history_cache_new_blocks_task,
         gas_oracle::GasPriceOracle,
         EthBundle, FeeHistoryCache,
     },
     AdminApi, AuthLayer, BlockingTaskGuard, BlockingTaskPool, Claims, DebugApi, EngineEthApi,
     EthApi, EthFilter, EthPubSub, EthSubscriptionIdProvider, JwtAuthValidator, JwtSecret, NetApi,
-    OtterscanApi, RPCApi, RethApi, TraceApi, TxPoolApi, Web3Api,
+    OtterscanApi, RPCApi, RateLimitLayer, RateLimiter, RethApi, TraceApi, TxPoolApi, Web3Api,
 };
 use reth_rpc_api::{servers::*, EngineApiServer};
 use reth_tasks::{TaskSpawner, TokioTaskExecutor};
@@ -221,8 +234,12 @@ where
         + AccountReader
         + StateProviderFactory
         + EvmEnvProvider
+        + StageCheckpointReader
         + ChainSpecProvider
         + ChangeSetReader
+        + LogIndexReader
+        + TransactionAddressIndexReader
+        + PruneCheckpointReader
         + Clone
         + Unpin
         + 'static,
@@ -254,6 +271,8 @@ pub struct RpcModuleBuilder<Provider, Pool, Network, Tasks, Events> {
     executor: Tasks,
     /// Provides access to chain events, such as new blocks, required by pubsub.
     events: Events,
+    /// The EVM configuration used for calls and tracing.
+    evm_config: EvmConfig,
 }
 
 // === impl RpcBuilder ===
@@ -269,7 +288,7 @@ impl<Provider, Pool, Network, Tasks, Events>
         executor: Tasks,
         events: Events,
     ) -> Self {
-        Self { provider, pool, network, executor, events }
+        Self { provider, pool, network, executor, events, evm_config: EvmConfig::default() }
     }
 
     /// Configure the provider instance.
@@ -277,8 +296,8 @@ impl<Provider, Pool, Network, Tasks, Events>
     where
         P: BlockReader + StateProviderFactory + EvmEnvProvider + 'static,
     {
-        let Self { pool, network, executor, events, .. } = self;
-        RpcModuleBuilder { provider, network, pool, executor, events }
+        let Self { pool, network, executor, events, evm_config, .. } = self;
+        RpcModuleBuilder { provider, network, pool, executor, events, evm_config }
     }
 
     /// Configure the transaction pool instance.
@@ -286,8 +305,8 @@ impl<Provider, Pool, Network, Tasks, Events>
     where
         P: TransactionPool + 'static,
     {
-        let Self { provider, network, executor, events, .. } = self;
-        RpcModuleBuilder { provider, network, pool, executor, events }
+        let Self { provider, network, executor, events, evm_config, .. } = self;
+        RpcModuleBuilder { provider, network, pool, executor, events, evm_config }
     }
 
     /// Configure a [NoopTransactionPool] instance.
@@ -298,13 +317,14 @@ impl<Provider, Pool, Network, Tasks, Events>
     pub fn with_noop_pool(
         self,
     ) -> RpcModuleBuilder<Provider, NoopTransactionPool, Network, Tasks, Events> {
-        let Self { provider, executor, events, network, .. } = self;
+        let Self { provider, executor, events, network, evm_config, .. } = self;
         RpcModuleBuilder {
             provider,
             executor,
             events,
             network,
             pool: NoopTransactionPool::default(),
+            evm_config,
         }
     }
 
@@ -313,8 +333,8 @@ impl<Provider, Pool, Network, Tasks, Events>
     where
         N: NetworkInfo + Peers + 'static,
     {
-        let Self { provider, pool, executor, events, .. } = self;
-        RpcModuleBuilder { provider, network, pool, executor, events }
+        let Self { provider, pool, executor, events, evm_config, .. } = self;
+        RpcModuleBuilder { provider, network, pool, executor, events, evm_config }
     }
 
     /// Configure a [NoopNetwork] instance.
@@ -323,8 +343,15 @@ impl<Provider, Pool, Network, Tasks, Events>
     /// This is only intended for allow easier setup of namespaces that depend on the [EthApi] which
     /// requires a [NetworkInfo] implementation.
     pub fn with_noop_network(self) -> RpcModuleBuilder<Provider, Pool, NoopNetwork, Tasks, Events> {
-        let Self { provider, pool, executor, events, .. } = self;
-        RpcModuleBuilder { provider, pool, executor, events, network: NoopNetwork::default() }
+        let Self { provider, pool, executor, events, evm_config, .. } = self;
+        RpcModuleBuilder {
+            provider,
+            pool,
+            executor,
+            events,
+            network: NoopNetwork::default(),
+            evm_config,
+        }
     }
 
     /// Configure the task executor to use for additional tasks.
@@ -335,8 +362,8 @@ impl<Provider, Pool, Network, Tasks, Events>
     where
         T: TaskSpawner + 'static,
     {
-        let Self { pool, network, provider, events, .. } = self;
-        RpcModuleBuilder { provider, network, pool, executor, events }
+        let Self { pool, network, provider, events, evm_config, .. } = self;
+        RpcModuleBuilder { provider, network, pool, executor, events, evm_config }
     }
 
     /// Configure [TokioTaskExecutor] as the task executor to use for additional tasks.
@@ -346,8 +373,15 @@ impl<Provider, Pool, Network, Tasks, Events>
     pub fn with_tokio_executor(
         self,
     ) -> RpcModuleBuilder<Provider, Pool, Network, TokioTaskExecutor, Events> {
-        let Self { pool, network, provider, events, .. } = self;
-        RpcModuleBuilder { provider, network, pool, events, executor: TokioTaskExecutor::default() }
+        let Self { pool, network, provider, events, evm_config, .. } = self;
+        RpcModuleBuilder {
+            provider,
+            network,
+            pool,
+            events,
+            executor: TokioTaskExecutor::default(),
+            evm_config,
+        }
     }
 
     /// Configure the event subscriber instance
@@ -355,8 +389,15 @@ impl<Provider, Pool, Network, Tasks, Events>
     where
         E: CanonStateSubscriptions + 'static,
     {
-        let Self { provider, pool, executor, network, .. } = self;
-        RpcModuleBuilder { provider, network, pool, executor, events }
+        let Self { provider, pool, executor, network, evm_config, .. } = self;
+        RpcModuleBuilder { provider, network, pool, executor, events, evm_config }
+    }
+
+    /// Configure the EVM configuration, including any additional precompiles, that is used for
+    /// calls and tracing.
+    pub fn with_evm_config(mut self, evm_config: EvmConfig) -> Self {
+        self.evm_config = evm_config;
+        self
     }
 }
 
@@ -367,8 +408,12 @@ where
         + AccountReader
         + StateProviderFactory
         + EvmEnvProvider
+        + StageCheckpointReader
         + ChainSpecProvider
         + ChangeSetReader
+        + LogIndexReader
+        + TransactionAddressIndexReader
+        + PruneCheckpointReader
         + Clone
         + Unpin
         + 'static,
@@ -396,9 +441,19 @@ where
     {
         let mut modules = TransportRpcModules::default();
 
-        let Self { provider, pool, network, executor, events } = self;
-
-        let TransportRpcModuleConfig { http, ws, ipc, config } = module_config.clone();
+        let Self { provider, pool, network, executor, events, evm_config } = self;
+
+        let TransportRpcModuleConfig {
+            http,
+            ws,
+            ipc,
+            auth,
+            http_gas_cap,
+            ws_gas_cap,
+            ipc_gas_cap,
+            auth_gas_cap,
+            config,
+        } = module_config.clone();
 
         let mut registry = RethModuleRegistry::new(
             provider,
@@ -407,14 +462,15 @@ where
             executor,
             events,
             config.unwrap_or_default(),
-        );
+        )
+        .with_evm_config(evm_config);
 
         modules.config = module_config;
-        modules.http = registry.maybe_module(http.as_ref());
-        modules.ws = registry.maybe_module(ws.as_ref());
-        modules.ipc = registry.maybe_module(ipc.as_ref());
+        modules.http = registry.maybe_module(http.as_ref(), http_gas_cap);
+        modules.ws = registry.maybe_module(ws.as_ref(), ws_gas_cap);
+        modules.ipc = registry.maybe_module(ipc.as_ref(), ipc_gas_cap);
 
-        let auth_module = registry.create_auth_module(engine);
+        let auth_module = registry.create_auth_module_with(engine, auth.as_ref(), auth_gas_cap);
 
         (modules, auth_module, registry)
     }
@@ -446,8 +502,9 @@ where
         self,
         config: RpcModuleConfig,
     ) -> RethModuleRegistry<Provider, Pool, Network, Tasks, Events> {
-        let Self { provider, pool, network, executor, events } = self;
+        let Self { provider, pool, network, executor, events, evm_config } = self;
         RethModuleRegistry::new(provider, pool, network, executor, events, config)
+            .with_evm_config(evm_config)
     }
 
     /// Configures all [RpcModule]s specific to the given [TransportRpcModuleConfig] which can be
@@ -457,10 +514,19 @@ where
     pub fn build(self, module_config: TransportRpcModuleConfig) -> TransportRpcModules<()> {
         let mut modules = TransportRpcModules::default();
 
-        let Self { provider, pool, network, executor, events } = self;
+        let Self { provider, pool, network, executor, events, evm_config } = self;
 
         if !module_config.is_empty() {
-            let TransportRpcModuleConfig { http, ws, ipc, config } = module_config.clone();
+            let TransportRpcModuleConfig {
+                http,
+                ws,
+                ipc,
+                http_gas_cap,
+                ws_gas_cap,
+                ipc_gas_cap,
+                config,
+                ..
+            } = module_config.clone();
 
             let mut registry = RethModuleRegistry::new(
                 provider,
@@ -469,12 +535,13 @@ where
                 executor,
                 events,
                 config.unwrap_or_default(),
-            );
+            )
+            .with_evm_config(evm_config);
 
             modules.config = module_config;
-            modules.http = registry.maybe_module(http.as_ref());
-            modules.ws = registry.maybe_module(ws.as_ref());
-            modules.ipc = registry.maybe_module(ipc.as_ref());
+            modules.http = registry.maybe_module(http.as_ref(), http_gas_cap);
+            modules.ws = registry.maybe_module(ws.as_ref(), ws_gas_cap);
+            modules.ipc = registry.maybe_module(ipc.as_ref(), ipc_gas_cap);
         }
 
         modules
@@ -653,8 +720,12 @@ impl RpcModuleSelection {
             + AccountReader
             + StateProviderFactory
             + EvmEnvProvider
+            + StageCheckpointReader
             + ChainSpecProvider
             + ChangeSetReader
+            + LogIndexReader
+            + TransactionAddressIndexReader
+            + PruneCheckpointReader
             + Clone
             + Unpin
             + 'static,
@@ -781,6 +852,14 @@ pub enum RethRpcModule {
     /// This is separate from [RethRpcModule::Eth] because it is a non standardized call that
     /// should be opt-in.
     EthCallBundle,
+    /// The full non-standard `eth_` bundle namespace: `eth_sendBundle`, `eth_callBundle` and
+    /// `eth_cancelBundle`
+    ///
+    /// Submitted bundles are stored in the transaction pool and included at the top of the block
+    /// by the payload builder.
+    ///
+    /// This includes [RethRpcModule::EthCallBundle].
+    EthBundle,
 }
 
 // === impl RethRpcModule ===
@@ -820,6 +899,7 @@ impl FromStr for RethRpcModule {
             "reth" => RethRpcModule::Reth,
             "ots" => RethRpcModule::Ots,
             "eth-call-bundle" | "eth_callBundle" => RethRpcModule::EthCallBundle,
+            "eth-bundle" | "eth_sendBundle" => RethRpcModule::EthBundle,
             _ => return Err(ParseError::VariantNotFound),
         })
     }
@@ -857,6 +937,8 @@ pub struct RethModuleRegistry<Provider, Pool, Network, Tasks, Events> {
     events: Events,
     /// Additional settings for handlers.
     config: RpcModuleConfig,
+    /// The EVM configuration used by the eth namespace handlers.
+    evm_config: EvmConfig,
     /// Holds a clone of all the eth namespace handlers
     eth: Option<EthHandlers<Provider, Pool, Network, Events>>,
     /// to put trace calls behind semaphore
@@ -888,10 +970,20 @@ impl<Provider, Pool, Network, Tasks, Events>
             modules: Default::default(),
             blocking_pool_guard: BlockingTaskGuard::new(config.eth.max_tracing_requests),
             config,
+            evm_config: EvmConfig::default(),
             events,
         }
     }
 
+    /// Configures the EVM configuration, including any additional precompiles, that is used by
+    /// the eth namespace handlers.
+    ///
+    /// This has no effect if the eth handlers were already created.
+    pub fn with_evm_config(mut self, evm_config: EvmConfig) -> Self {
+        self.evm_config = evm_config;
+        self
+    }
+
     /// Returns a reference to the pool
     pub fn pool(&self) -> &Pool {
         &self.pool
@@ -964,8 +1056,12 @@ where
         + AccountReader
         + StateProviderFactory
         + EvmEnvProvider
+        + StageCheckpointReader
         + ChainSpecProvider
         + ChangeSetReader
+        + LogIndexReader
+        + TransactionAddressIndexReader
+        + PruneCheckpointReader
         + Clone
         + Unpin
         + 'static,
@@ -1028,14 +1124,43 @@ where
         EngineT: EngineTypes,
         EngineApi: EngineApiServer<EngineT>,
     {
-        let eth_handlers = self.eth_handlers();
+        self.create_auth_module_with(engine_api, None, None)
+    }
+
+    /// Configures the auth module like [Self::create_auth_module], with the modules of the given
+    /// [RpcModuleSelection] installed in addition.
+    ///
+    /// If the selection includes the `eth` namespace, the full namespace replaces the subset of
+    /// `eth_` handlers. If a gas cap is given, the `eth_` handlers use it instead of the
+    /// configured [EthConfig::rpc_gas_cap].
+    ///
+    /// Note: This does _not_ register the `engine_` in this registry.
+    pub fn create_auth_module_with<EngineApi, EngineT>(
+        &mut self,
+        engine_api: EngineApi,
+        modules: Option<&RpcModuleSelection>,
+        gas_cap: Option<u64>,
+    ) -> AuthRpcModule
+    where
+        EngineT: EngineTypes,
+        EngineApi: EngineApiServer<EngineT>,
+    {
         let mut module = RpcModule::new(());
 
         module.merge(engine_api.into_rpc()).expect("No conflicting methods");
 
-        // also merge a subset of `eth_` handlers
-        let engine_eth = EngineEthApi::new(eth_handlers.api.clone(), eth_handlers.filter);
-        module.merge(engine_eth.into_rpc()).expect("No conflicting methods");
+        let has_eth = modules
+            .map_or(false, |modules| modules.iter_selection().any(|m| m == RethRpcModule::Eth));
+        if !has_eth {
+            // also merge a subset of `eth_` handlers
+            let eth_handlers = self.eth_handlers_with_gas_cap(gas_cap);
+            let engine_eth = EngineEthApi::new(eth_handlers.api, eth_handlers.filter);
+            module.merge(engine_eth.into_rpc()).expect("No conflicting methods");
+        }
+
+        if let Some(selected) = self.maybe_module(modules, gas_cap) {
+            module.merge(selected).expect("No conflicting methods");
+        }
 
         AuthRpcModule { inner: module }
     }
@@ -1066,10 +1191,18 @@ where
         self
     }
 
-    /// Helper function to create a [RpcModule] if it's not `None`
-    fn maybe_module(&mut self, config: Option<&RpcModuleSelection>) -> Option<RpcModule<()>> {
+    /// Helper function to create a [RpcModule] if it's not `None`, see also
+    /// [Self::module_for_with_gas_cap]
+    fn maybe_module(
+        &mut self,
+        config: Option<&RpcModuleSelection>,
+        gas_cap: Option<u64>,
+    ) -> Option<RpcModule<()>> {
         let config = config?;
-        let module = self.module_for(config);
+        let module = match gas_cap {
+            Some(gas_cap) => self.module_for_with_gas_cap(config, gas_cap),
+            None => self.module_for(config),
+        };
         Some(module)
     }
 
@@ -1084,6 +1217,35 @@ where
         module
     }
 
+    /// Populates a new [RpcModule] like [Self::module_for], but the `eth_` handlers of the modules
+    /// use the given gas cap instead of the configured [EthConfig::rpc_gas_cap].
+    ///
+    /// This is used to configure a different gas cap per transport. Unless the gas cap matches
+    /// the configured one, the API implementations are created anew and are not installed in this
+    /// registry.
+    ///
+    /// # Panics
+    ///
+    /// If called outside of the tokio runtime. See also [Self::eth_api]
+    pub fn module_for_with_gas_cap(
+        &mut self,
+        config: &RpcModuleSelection,
+        gas_cap: u64,
+    ) -> RpcModule<()> {
+        if gas_cap == self.config.eth.rpc_gas_cap {
+            return self.module_for(config)
+        }
+
+        let eth = self.eth_handlers_with_gas_cap(Some(gas_cap));
+        let namespaces: Vec<_> = config.iter_selection().collect();
+        let mut module = RpcModule::new(());
+        for namespace in namespaces.iter().copied() {
+            let methods = self.create_methods(namespace, &namespaces, &eth);
+            module.merge(methods).expect("No conflicts");
+        }
+        module
+    }
+
     /// Returns the [Methods] for the given [RethRpcModule]
     ///
     /// If this is the first time the namespace is requested, a new instance of API implementation
@@ -1096,13 +1258,7 @@ where
         &mut self,
         namespaces: impl Iterator<Item = RethRpcModule>,
     ) -> Vec<Methods> {
-        let EthHandlers {
-            api: eth_api,
-            filter: eth_filter,
-            pubsub: eth_pubsub,
-            cache: _,
-            blocking_task_pool: _,
-        } = self.with_eth(|eth| eth.clone());
+        let eth = self.with_eth(|eth| eth.clone());
 
         // Create a copy, so we can list out all the methods for rpc_ api
         let namespaces: Vec<_> = namespaces.collect();
@@ -1110,67 +1266,101 @@ where
             .iter()
             .copied()
             .map(|namespace| {
-                self.modules
-                    .entry(namespace)
-                    .or_insert_with(|| match namespace {
-                        RethRpcModule::Admin => {
-                            AdminApi::new(self.network.clone()).into_rpc().into()
-                        }
-                        RethRpcModule::Debug => DebugApi::new(
-                            self.provider.clone(),
-                            eth_api.clone(),
-                            Box::new(self.executor.clone()),
-                            self.blocking_pool_guard.clone(),
-                        )
-                        .into_rpc()
-                        .into(),
-                        RethRpcModule::Eth => {
-                            // merge all eth handlers
-                            let mut module = eth_api.clone().into_rpc();
-                            module.merge(eth_filter.clone().into_rpc()).expect("No conflicts");
-                            module.merge(eth_pubsub.clone().into_rpc()).expect("No conflicts");
-
-                            module.into()
-                        }
-                        RethRpcModule::Net => {
-                            NetApi::new(self.network.clone(), eth_api.clone()).into_rpc().into()
-                        }
-                        RethRpcModule::Trace => TraceApi::new(
-                            self.provider.clone(),
-                            eth_api.clone(),
-                            self.blocking_pool_guard.clone(),
-                        )
-                        .into_rpc()
-                        .into(),
-                        RethRpcModule::Web3 => Web3Api::new(self.network.clone()).into_rpc().into(),
-                        RethRpcModule::Txpool => {
-                            TxPoolApi::new(self.pool.clone()).into_rpc().into()
-                        }
-                        RethRpcModule::Rpc => RPCApi::new(
-                            namespaces
-                                .iter()
-                                .map(|module| (module.to_string(), "1.0".to_string()))
-                                .collect(),
-                        )
-                        .into_rpc()
-                        .into(),
-                        RethRpcModule::Ots => OtterscanApi::new(eth_api.clone()).into_rpc().into(),
-                        RethRpcModule::Reth => {
-                            RethApi::new(self.provider.clone(), Box::new(self.executor.clone()))
-                                .into_rpc()
-                                .into()
-                        }
-                        RethRpcModule::EthCallBundle => {
-                            EthBundle::new(eth_api.clone(), self.blocking_pool_guard.clone())
-                                .into_rpc()
-                                .into()
-                        }
-                    })
-                    .clone()
+                if let Some(methods) = self.modules.get(&namespace) {
+                    return methods.clone()
+                }
+                let methods = self.create_methods(namespace, &namespaces, &eth);
+                self.modules.insert(namespace, methods.clone());
+                methods
             })
             .collect::<Vec<_>>()
     }
 
+    /// Creates a new instance of the API implementation of the given [RethRpcModule], using the
+    /// given `eth_` handlers.
+    ///
+    /// `namespaces` are all the namespaces that are installed together with this one.
+    fn create_methods(
+        &self,
+        namespace: RethRpcModule,
+        namespaces: &[RethRpcModule],
+        eth: &EthHandlers<Provider, Pool, Network, Events>,
+    ) -> Methods {
+        let EthHandlers {
+            api: eth_api,
+            filter: eth_filter,
+            pubsub: eth_pubsub,
+            cache: _,
+            blocking_task_pool: _,
+            trace_cache,
+        } = eth;
+
+        match namespace {
+            RethRpcModule::Admin => AdminApi::new(self.network.clone()).into_rpc().into(),
+            RethRpcModule::Debug => DebugApi::new(
+                self.provider.clone(),
+                eth_api.clone(),
+                Box::new(self.executor.clone()),
+                self.blocking_pool_guard.clone(),
+                trace_cache.clone(),
+            )
+            .into_rpc()
+            .into(),
+            RethRpcModule::Eth => {
+                // merge all eth handlers
+                let mut module = eth_api.clone().into_rpc();
+                module.merge(eth_filter.clone().into_rpc()).expect("No conflicts");
+                module.merge(eth_pubsub.clone().into_rpc()).expect("No conflicts");
+
+                module.into()
+            }
+            RethRpcModule::Net => {
+                NetApi::new(self.network.clone(), eth_api.clone()).into_rpc().into()
+            }
+            RethRpcModule::Trace => TraceApi::new(
+                self.provider.clone(),
+                eth_api.clone(),
+                self.blocking_pool_guard.clone(),
+                trace_cache.clone(),
+            )
+            .into_rpc()
+            .into(),
+            RethRpcModule::Web3 => Web3Api::new(self.network.clone()).into_rpc().into(),
+            RethRpcModule::Txpool => TxPoolApi::new(self.pool.clone()).into_rpc().into(),
+            RethRpcModule::Rpc => RPCApi::new(
+                namespaces.iter().map(|module| (module.to_string(), "1.0".to_string())).collect(),
+            )
+            .into_rpc()
+            .into(),
+            RethRpcModule::Ots => {
+                OtterscanApi::new(self.provider.clone(), eth_api.clone()).into_rpc().into()
+            }
+            RethRpcModule::Reth => {
+                RethApi::new(self.provider.clone(), Box::new(self.executor.clone()))
+                    .into_rpc()
+                    .into()
+            }
+            RethRpcModule::EthCallBundle => {
+                if namespaces.contains(&RethRpcModule::EthBundle) {
+                    // `eth_callBundle` is already part of the full bundle namespace
+                    return Methods::new()
+                }
+                EthCallBundleApiServer::into_rpc(EthBundle::new(
+                    eth_api.clone(),
+                    self.pool.clone(),
+                    self.blocking_pool_guard.clone(),
+                ))
+                .into()
+            }
+            RethRpcModule::EthBundle => EthBundleApiServer::into_rpc(EthBundle::new(
+                eth_api.clone(),
+                self.pool.clone(),
+                self.blocking_pool_guard.clone(),
+            ))
+            .into(),
+        }
+    }
+
     /// Returns the [EthStateCache] frontend
     ///
     /// This will spawn exactly one [EthStateCache] service if this is the first time the cache is
@@ -1222,6 +1412,18 @@ where
                 }),
             );
 
+            let trace_cache = TraceCache::new(self.config.eth.cache.max_traced_blocks);
+            if trace_cache.is_enabled() {
+                let new_canonical_blocks = self.events.canonical_state_stream();
+                let tc = trace_cache.clone();
+                self.executor.spawn_critical(
+                    "remove reorged blocks from trace cache task",
+                    Box::pin(async move {
+                        trace_cache_reorg_task(tc, new_canonical_blocks).await;
+                    }),
+                );
+            }
+
             let executor = Box::new(self.executor.clone());
             let blocking_task_pool =
                 BlockingTaskPool::build().expect("failed to build tracing pool");
@@ -1235,6 +1437,7 @@ where
                 executor.clone(),
                 blocking_task_pool.clone(),
                 fee_history_cache,
+                self.evm_config,
             );
             let filter = EthFilter::new(
                 self.provider.clone(),
@@ -1252,7 +1455,7 @@ where
                 executor,
             );
 
-            let eth = EthHandlers { api, cache, filter, pubsub, blocking_task_pool };
+            let eth = EthHandlers { api, cache, filter, pubsub, blocking_task_pool, trace_cache };
             self.eth = Some(eth);
         }
         f(self.eth.as_ref().expect("exists; qed"))
@@ -1267,6 +1470,19 @@ where
         self.with_eth(|handlers| handlers.clone())
     }
 
+    /// Returns the configured [EthHandlers] like [Self::eth_handlers], with the [EthApi] using
+    /// the given gas cap instead of the configured [EthConfig::rpc_gas_cap], if any.
+    fn eth_handlers_with_gas_cap(
+        &mut self,
+        gas_cap: Option<u64>,
+    ) -> EthHandlers<Provider, Pool, Network, Events> {
+        let mut handlers = self.eth_handlers();
+        if let Some(gas_cap) = gas_cap {
+            handlers.api = handlers.api.with_gas_cap(gas_cap);
+        }
+        handlers
+    }
+
     /// Returns the configured [EthApi] or creates it if it does not exist yet
     ///
     /// Caution: This will spawn the necessary tasks required by the [EthApi]: [EthStateCache].
@@ -1285,7 +1501,12 @@ where
     /// If called outside of the tokio runtime. See also [Self::eth_api]
     pub fn trace_api(&mut self) -> TraceApi<Provider, EthApi<Provider, Pool, Network>> {
         let eth = self.eth_handlers();
-        TraceApi::new(self.provider.clone(), eth.api, self.blocking_pool_guard.clone())
+        TraceApi::new(
+            self.provider.clone(),
+            eth.api,
+            self.blocking_pool_guard.clone(),
+            eth.trace_cache,
+        )
     }
 
     /// Instantiates [EthBundle] Api
@@ -1293,9 +1514,9 @@ where
     /// # Panics
     ///
     /// If called outside of the tokio runtime. See also [Self::eth_api]
-    pub fn bundle_api(&mut self) -> EthBundle<EthApi<Provider, Pool, Network>> {
+    pub fn bundle_api(&mut self) -> EthBundle<EthApi<Provider, Pool, Network>, Pool> {
         let eth_api = self.eth_api();
-        EthBundle::new(eth_api, self.blocking_pool_guard.clone())
+        EthBundle::new(eth_api, self.pool.clone(), self.blocking_pool_guard.clone())
     }
 
     /// Instantiates OtterscanApi
@@ -1303,9 +1524,9 @@ where
     /// # Panics
     ///
     /// If called outside of the tokio runtime. See also [Self::eth_api]
-    pub fn otterscan_api(&mut self) -> OtterscanApi<EthApi<Provider, Pool, Network>> {
+    pub fn otterscan_api(&mut self) -> OtterscanApi<Provider, EthApi<Provider, Pool, Network>> {
         let eth_api = self.eth_api();
-        OtterscanApi::new(eth_api)
+        OtterscanApi::new(self.provider.clone(), eth_api)
     }
 
     /// Instantiates DebugApi
@@ -1314,12 +1535,13 @@ where
     ///
     /// If called outside of the tokio runtime. See also [Self::eth_api]
     pub fn debug_api(&mut self) -> DebugApi<Provider, EthApi<Provider, Pool, Network>> {
-        let eth_api = self.eth_api();
+        let eth = self.eth_handlers();
         DebugApi::new(
             self.provider.clone(),
-            eth_api,
+            eth.api,
             Box::new(self.executor.clone()),
             self.blocking_pool_guard.clone(),
+            eth.trace_cache,
         )
     }
 
@@ -1370,6 +1592,8 @@ pub struct RpcServerConfig {
     ipc_endpoint: Option<Endpoint>,
     /// JWT secret for authentication
     jwt_secret: Option<JwtSecret>,
+    /// Call rate limits of the http, ws and ipc servers
+    rate_limit: RateLimitConfig,
 }
 
 impl fmt::Debug for RpcServerConfig {
@@ -1383,6 +1607,7 @@ impl fmt::Debug for RpcServerConfig {
             .field("ipc_server_config", &self.ipc_server_config)
             .field("ipc_endpoint", &self.ipc_endpoint.as_ref().map(|endpoint| endpoint.path()))
             .field("jwt_secret", &self.jwt_secret)
+            .field("rate_limit", &self.rate_limit)
             .finish()
     }
 }
@@ -1441,6 +1666,15 @@ impl RpcServerConfig {
         self
     }
 
+    /// Configures the call rate limits of the http, ws and ipc servers.
+    ///
+    /// Every call counts towards the limits, including the calls of a batch request. The global
+    /// limit is shared by all servers. Calls that exceed a limit are answered with an error.
+    pub fn with_rate_limit(mut self, rate_limit: RateLimitConfig) -> Self {
+        self.rate_limit = rate_limit;
+        self
+    }
+
     /// Configures the [SocketAddr] of the http server
     ///
     /// Default is [Ipv4Addr::LOCALHOST] and [DEFAULT_HTTP_RPC_PORT]
@@ -1541,6 +1775,9 @@ impl RpcServerConfig {
             DEFAULT_HTTP_RPC_PORT,
         )));
         let jwt_secret = self.jwt_secret.clone();
+        // holds the per-connection buckets of http connections, the calls are limited by the
+        // methods of the server, see `RpcServer::start`
+        let rate_limit = RateLimitLayer::new(self.rate_limit);
 
         let ws_socket_addr = self
             .ws_addr
@@ -1581,6 +1818,7 @@ impl RpcServerConfig {
                 http_socket_addr,
                 cors,
                 secret,
+                rate_limit.clone(),
                 ServerKind::WsHttp(http_socket_addr),
                 modules
                     .http
@@ -1610,6 +1848,7 @@ impl RpcServerConfig {
                 ws_socket_addr,
                 self.ws_cors_domains.take(),
                 self.jwt_secret.clone(),
+                rate_limit.clone(),
                 ServerKind::WS(ws_socket_addr),
                 modules.ws.as_ref().map(RpcServerMetrics::new).unwrap_or_default(),
             )
@@ -1625,6 +1864,7 @@ impl RpcServerConfig {
                 http_socket_addr,
                 self.http_cors_domains.take(),
                 self.jwt_secret.clone(),
+                rate_limit,
                 ServerKind::Http(http_socket_addr),
                 modules.http.as_ref().map(RpcServerMetrics::new).unwrap_or_default(),
             )
@@ -1648,6 +1888,7 @@ impl RpcServerConfig {
     /// Note: The server ist not started and does nothing unless polled, See also [RpcServer::start]
     pub async fn build(mut self, modules: &TransportRpcModules) -> Result<RpcServer, RpcError> {
         let mut server = RpcServer::empty();
+        server.rate_limiter = RateLimiter::new(self.rate_limit);
         server.ws_http = self.build_ws_http(modules).await?;
 
         if let Some(builder) = self.ipc_server_config {
@@ -1682,6 +1923,16 @@ pub struct TransportRpcModuleConfig {
     ws: Option<RpcModuleSelection>,
     /// ipc module configuration
     ipc: Option<RpcModuleSelection>,
+    /// Modules installed on the auth server in addition to the `engine_` namespace
+    auth: Option<RpcModuleSelection>,
+    /// Gas cap of the `eth_` handlers of the http modules, overrides [EthConfig::rpc_gas_cap]
+    http_gas_cap: Option<u64>,
+    /// Gas cap of the `eth_` handlers of the ws modules, overrides [EthConfig::rpc_gas_cap]
+    ws_gas_cap: Option<u64>,
+    /// Gas cap of the `eth_` handlers of the ipc modules, overrides [EthConfig::rpc_gas_cap]
+    ipc_gas_cap: Option<u64>,
+    /// Gas cap of the `eth_` handlers of the auth module, overrides [EthConfig::rpc_gas_cap]
+    auth_gas_cap: Option<u64>,
     /// Config for the modules
     config: Option<RpcModuleConfig>,
 }
@@ -1722,6 +1973,39 @@ impl TransportRpcModuleConfig {
         self
     }
 
+    /// Sets the [RpcModuleSelection] that is installed on the auth server, in addition to the
+    /// `engine_` namespace.
+    ///
+    /// See also [RethModuleRegistry::create_auth_module_with]
+    pub fn with_auth(mut self, auth: impl Into<RpcModuleSelection>) -> Self {
+        self.auth = Some(auth.into());
+        self
+    }
+
+    /// Sets the gas cap of the `eth_` handlers for the http transport.
+    pub fn with_http_gas_cap(mut self, gas_cap: u64) -> Self {
+        self.http_gas_cap = Some(gas_cap);
+        self
+    }
+
+    /// Sets the gas cap of the `eth_` handlers for the ws transport.
+    pub fn with_ws_gas_cap(mut self, gas_cap: u64) -> Self {
+        self.ws_gas_cap = Some(gas_cap);
+        self
+    }
+
+    /// Sets the gas cap of the `eth_` handlers for the ipc transport.
+    pub fn with_ipc_gas_cap(mut self, gas_cap: u64) -> Self {
+        self.ipc_gas_cap = Some(gas_cap);
+        self
+    }
+
+    /// Sets the gas cap of the `eth_` handlers of the auth server.
+    pub fn with_auth_gas_cap(mut self, gas_cap: u64) -> Self {
+        self.auth_gas_cap = Some(gas_cap);
+        self
+    }
+
     /// Sets a custom [RpcModuleConfig] for the configured modules.
     pub fn with_config(mut self, config: RpcModuleConfig) -> Self {
         self.config = Some(config);
@@ -1748,9 +2032,40 @@ impl TransportRpcModuleConfig {
         self.ipc.as_ref()
     }
 
+    /// Returns the [RpcModuleSelection] that is installed on the auth server
+    pub fn auth(&self) -> Option<&RpcModuleSelection> {
+        self.auth.as_ref()
+    }
+
+    /// Returns the gas cap override for the http transport
+    pub fn http_gas_cap(&self) -> Option<u64> {
+        self.http_gas_cap
+    }
+
+    /// Returns the gas cap override for the ws transport
+    pub fn ws_gas_cap(&self) -> Option<u64> {
+        self.ws_gas_cap
+    }
+
+    /// Returns the gas cap override for the ipc transport
+    pub fn ipc_gas_cap(&self) -> Option<u64> {
+        self.ipc_gas_cap
+    }
+
+    /// Returns the gas cap override for the auth server
+    pub fn auth_gas_cap(&self) -> Option<u64> {
+        self.auth_gas_cap
+    }
+
     /// Ensures that both http and ws are configured and that they are configured to use the same
     /// port.
     fn ensure_ws_http_identical(&self) -> Result<(), WsHttpSamePortError> {
+        if self.http_gas_cap != self.ws_gas_cap {
+            return Err(WsHttpSamePortError::ConflictingGasCaps {
+                http_gas_cap: self.http_gas_cap,
+                ws_gas_cap: self.ws_gas_cap,
+            })
+        }
         if RpcModuleSelection::are_identical(self.http.as_ref(), self.ws.as_ref()) {
             Ok(())
         } else {
@@ -1876,8 +2191,8 @@ impl WsHttpServers {
     /// Starts the servers and returns the handles (http, ws)
     async fn start(
         self,
-        http_module: Option<RpcModule<()>>,
-        ws_module: Option<RpcModule<()>>,
+        http_module: Option<Methods>,
+        ws_module: Option<Methods>,
         config: &TransportRpcModuleConfig,
     ) -> Result<(Option<ServerHandle>, Option<ServerHandle>), RpcError> {
         let mut http_handle = None;
@@ -1919,16 +2234,26 @@ impl Default for WsHttpServers {
 }
 
 /// Http Servers Enum
+///
+/// All servers are rate limited, which is a no-op if no limits are configured.
 enum WsHttpServerKind {
     /// Http server
-    Plain(Server<Identity, RpcServerMetrics>),
+    Plain(Server<Stack<RateLimitLayer, Identity>, RpcServerMetrics>),
     /// Http server with cors
-    WithCors(Server<Stack<CorsLayer, Identity>, RpcServerMetrics>),
+    WithCors(Server<Stack<RateLimitLayer, Stack<CorsLayer, Identity>>, RpcServerMetrics>),
     /// Http server with auth
-    WithAuth(Server<Stack<AuthLayer<JwtAuthValidator>, Identity>, RpcServerMetrics>),
+    WithAuth(
+        Server<
+            Stack<AuthLayer<JwtAuthValidator>, Stack<RateLimitLayer, Identity>>,
+            RpcServerMetrics,
+        >,
+    ),
     /// Http server with cors and auth
     WithCorsAuth(
-        Server<Stack<AuthLayer<JwtAuthValidator>, Stack<CorsLayer, Identity>>, RpcServerMetrics>,
+        Server<
+            Stack<AuthLayer<JwtAuthValidator>, Stack<RateLimitLayer, Stack<CorsLayer, Identity>>>,
+            RpcServerMetrics,
+        >,
     ),
 }
 
@@ -1936,7 +2261,7 @@ enum WsHttpServerKind {
 
 impl WsHttpServerKind {
     /// Starts the server and returns the handle
-    async fn start(self, module: RpcModule<()>) -> ServerHandle {
+    async fn start(self, module: Methods) -> ServerHandle {
         match self {
             WsHttpServerKind::Plain(server) => server.start(module),
             WsHttpServerKind::WithCors(server) => server.start(module),
@@ -1953,6 +2278,7 @@ impl WsHttpServerKind {
         socket_addr: SocketAddr,
         cors_domains: Option<String>,
         jwt_secret: Option<JwtSecret>,
+        rate_limit: RateLimitLayer,
         server_kind: ServerKind,
         metrics: RpcServerMetrics,
     ) -> Result<(Self, SocketAddr), RpcError> {
@@ -1960,9 +2286,10 @@ impl WsHttpServerKind {
             let cors = cors.map_err(|err| RpcError::Custom(err.to_string()))?;
 
             if let Some(secret) = jwt_secret {
-                // stack cors and auth layers
+                // stack cors, rate limit and auth layers
                 let middleware = tower::ServiceBuilder::new()
                     .layer(cors)
+                    .layer(rate_limit)
                     .layer(AuthLayer::new(JwtAuthValidator::new(secret.clone())));
 
                 let server = builder
@@ -1975,7 +2302,7 @@ impl WsHttpServerKind {
                 let server = WsHttpServerKind::WithCorsAuth(server);
                 Ok((server, local_addr))
             } else {
-                let middleware = tower::ServiceBuilder::new().layer(cors);
+                let middleware = tower::ServiceBuilder::new().layer(cors).layer(rate_limit);
                 let server = builder
                     .set_middleware(middleware)
                     .set_logger(metrics)
@@ -1989,6 +2316,7 @@ impl WsHttpServerKind {
         } else if let Some(secret) = jwt_secret {
             // jwt auth layered service
             let middleware = tower::ServiceBuilder::new()
+                .layer(rate_limit)
                 .layer(AuthLayer::new(JwtAuthValidator::new(secret.clone())));
             let server = builder
                 .set_middleware(middleware)
@@ -2002,8 +2330,10 @@ impl WsHttpServerKind {
             let server = WsHttpServerKind::WithAuth(server);
             Ok((server, local_addr))
         } else {
-            // plain server without any middleware
+            // plain server with only the rate limit
+            let middleware = tower::ServiceBuilder::new().layer(rate_limit);
             let server = builder
+                .set_middleware(middleware)
                 .set_logger(metrics)
                 .build(socket_addr)
                 .await
@@ -2021,13 +2351,15 @@ pub struct RpcServer {
     ws_http: WsHttpServer,
     /// ipc server
     ipc: Option<IpcServer<Identity, RpcServerMetrics>>,
+    /// Limits the calls of all servers
+    rate_limiter: RateLimiter,
 }
 
 // === impl RpcServer ===
 
 impl RpcServer {
     fn empty() -> RpcServer {
-        RpcServer { ws_http: Default::default(), ipc: None }
+        RpcServer { ws_http: Default::default(), ipc: None, rate_limiter: Default::default() }
     }
 
     /// Returns the [`SocketAddr`] of the http server if started.
@@ -2056,8 +2388,12 @@ impl RpcServer {
     #[instrument(name = "start", skip_all, fields(http = ?self.http_local_addr(), ws = ?self.ws_local_addr(), ipc = ?self.ipc_endpoint().map(|ipc|ipc.path())), target = "rpc", level = "TRACE")]
     pub async fn start(self, modules: TransportRpcModules) -> Result<RpcServerHandle, RpcError> {
         trace!(target: "rpc", "staring RPC server");
-        let Self { ws_http, ipc: ipc_server } = self;
+        let Self { ws_http, ipc: ipc_server, rate_limiter } = self;
         let TransportRpcModules { config, http, ws, ipc } = modules;
+        // the methods of every server are limited separately, since the connection ids of the
+        // servers overlap, but they share the global limit
+        let http = http.map(|module| rate_limiter.rate_limit(module));
+        let ws = ws.map(|module| rate_limiter.rate_limit(module));
         let mut handle = RpcServerHandle {
             http_local_addr: ws_http.http_local_addr,
             ws_local_addr: ws_http.ws_local_addr,
@@ -2076,7 +2412,7 @@ impl RpcServer {
             ipc_server.and_then(|server| ipc.map(|module| (server, module)))
         {
             handle.ipc_endpoint = Some(server.endpoint().path().to_string());
-            handle.ipc = Some(server.start(module).await?);
+            handle.ipc = Some(server.start(rate_limiter.rate_limit(module)).await?);
         }
 
         Ok(handle)
@@ -2221,6 +2557,15 @@ mod tests {
         assert_eq!(selection, RethRpcModule::EthCallBundle);
     }
 
+    #[test]
+    fn parse_eth_bundle() {
+        let selection = "eth-bundle".parse::<RethRpcModule>().unwrap();
+        assert_eq!(selection, RethRpcModule::EthBundle);
+        let selection = "eth_sendBundle".parse::<RethRpcModule>().unwrap();
+        assert_eq!(selection, RethRpcModule::EthBundle);
+        assert_eq!(RethRpcModule::EthBundle.as_str(), "eth-bundle");
+    }
+
     #[test]
     fn parse_eth_call_bundle_selection() {
         let selection = "eth,admin,debug,eth-call-bundle".parse::<RpcModuleSelection>().unwrap();
@@ -2330,6 +2675,11 @@ mod tests {
                 ])),
                 ws: None,
                 ipc: None,
+                auth: None,
+                http_gas_cap: None,
+                ws_gas_cap: None,
+                ipc_gas_cap: None,
+                auth_gas_cap: None,
                 config: None,
             }
         )
@@ -2344,6 +2694,11 @@ mod tests {
                 http: Some(RpcModuleSelection::Selection(vec![])),
                 ws: None,
                 ipc: None,
+                auth: None,
+                http_gas_cap: None,
+                ws_gas_cap: None,
+                ipc_gas_cap: None,
+                auth_gas_cap: None,
                 config: None,
             }
         )
@@ -1,11 +1,12 @@
 //! Auth server tests
 
-use crate::utils::launch_auth;
+use crate::utils::{launch_auth, test_engine_api, test_rpc_builder};
 use jsonrpsee::core::client::{ClientT, SubscriptionClientT};
 use reth_node_builder::EthEngineTypes;
 use reth_primitives::{Block, U64};
 use reth_rpc::JwtSecret;
 use reth_rpc_api::clients::EngineApiClient;
+use reth_rpc_builder::{RethRpcModule, TransportRpcModuleConfig};
 use reth_rpc_types::engine::{ForkchoiceState, PayloadId, TransitionConfiguration};
 use reth_rpc_types_compat::engine::payload::{
     convert_block_to_payload_input_v2, try_block_to_payload_v1,
@@ -46,3 +47,26 @@ async fn test_auth_endpoints_ws() {
     let client = handle.ws_client().await;
     test_basic_engine_calls(&client).await
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_auth_module_selection() {
+    let (_, mut auth_module, _) = test_rpc_builder().build_with_auth_server::<_, EthEngineTypes>(
+        TransportRpcModuleConfig::default(),
+        test_engine_api(),
+    );
+    let methods = auth_module.module_mut().method_names().collect::<Vec<_>>();
+    assert!(methods.contains(&"engine_newPayloadV1"));
+    assert!(methods.contains(&"eth_call"));
+    assert!(!methods.contains(&"web3_clientVersion"));
+
+    let config = TransportRpcModuleConfig::default()
+        .with_auth([RethRpcModule::Eth, RethRpcModule::Web3])
+        .with_auth_gas_cap(1_000_000);
+    let (_, mut auth_module, _) =
+        test_rpc_builder().build_with_auth_server::<_, EthEngineTypes>(config, test_engine_api());
+    let methods = auth_module.module_mut().method_names().collect::<Vec<_>>();
+    assert!(methods.contains(&"engine_newPayloadV1"));
+    // the full `eth` namespace replaces the subset
+    assert!(methods.contains(&"eth_gasPrice"));
+    assert!(methods.contains(&"web3_clientVersion"));
+}
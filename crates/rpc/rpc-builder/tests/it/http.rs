@@ -17,7 +17,7 @@ use reth_primitives::{
 use reth_rpc_api::{
     clients::{AdminApiClient, EthApiClient},
     DebugApiClient, EthFilterApiClient, NetApiClient, OtterscanClient, TraceApiClient,
-    Web3ApiClient,
+    TxPoolApiClient, Web3ApiClient,
 };
 use reth_rpc_builder::RethRpcModule;
 use reth_rpc_types::{
@@ -163,7 +163,7 @@ where
     EthApiClient::create_access_list(client, call_request.clone(), Some(block_number.into()))
         .await
         .unwrap();
-    EthApiClient::estimate_gas(client, call_request.clone(), Some(block_number.into()), None)
+    EthApiClient::estimate_gas(client, call_request.clone(), Some(block_number.into()), None, None)
         .await
         .unwrap();
     EthApiClient::call(client, call_request.clone(), Some(block_number.into()), None, None)
@@ -176,6 +176,7 @@ where
     EthApiClient::gas_price(client).await.unwrap_err();
     EthApiClient::max_priority_fee_per_gas(client).await.unwrap_err();
     EthApiClient::get_proof(client, address, vec![], None).await.unwrap();
+    EthApiClient::get_account(client, address, block_number.into()).await.unwrap();
 
     // Unimplemented
     assert!(is_unimplemented(EthApiClient::author(client).await.err().unwrap()));
@@ -202,6 +203,10 @@ where
     DebugApiClient::raw_block(client, block_id).await.unwrap();
     DebugApiClient::raw_transaction(client, B256::default()).await.unwrap();
     DebugApiClient::raw_receipts(client, block_id).await.unwrap();
+    // the block does not exist
+    assert!(DebugApiClient::debug_execution_witness(client, BlockNumberOrTag::Latest)
+        .await
+        .is_err());
     assert!(is_unimplemented(DebugApiClient::bad_blocks(client).await.err().unwrap()));
 }
 
@@ -245,6 +250,23 @@ where
     TraceApiClient::trace_filter(client, trace_filter).await.unwrap();
 }
 
+async fn test_basic_txpool_calls<C>(client: &C)
+where
+    C: ClientT + SubscriptionClientT + Sync,
+{
+    let address = Address::default();
+
+    let status = TxPoolApiClient::txpool_status(client).await.unwrap();
+    assert_eq!(status.pending, Default::default());
+    assert_eq!(status.queued, Default::default());
+    let inspect = TxPoolApiClient::txpool_inspect(client).await.unwrap();
+    assert!(inspect.pending.is_empty() && inspect.queued.is_empty());
+    let content = TxPoolApiClient::txpool_content(client).await.unwrap();
+    assert!(content.pending.is_empty() && content.queued.is_empty());
+    let content_from = TxPoolApiClient::txpool_content_from(client, address).await.unwrap();
+    assert!(content_from.pending.is_empty() && content_from.queued.is_empty());
+}
+
 async fn test_basic_web3_calls<C>(client: &C)
 where
     C: ClientT + SubscriptionClientT + Sync,
@@ -270,47 +292,33 @@ where
 
     OtterscanClient::get_api_level(client).await.unwrap();
 
-    assert!(is_unimplemented(
-        OtterscanClient::get_internal_operations(client, tx_hash).await.err().unwrap()
-    ));
-    assert!(is_unimplemented(
-        OtterscanClient::get_transaction_error(client, tx_hash).await.err().unwrap()
-    ));
-    assert!(is_unimplemented(
-        OtterscanClient::trace_transaction(client, tx_hash).await.err().unwrap()
-    ));
+    // the transaction does not exist
+    assert!(OtterscanClient::get_internal_operations(client, tx_hash).await.is_err());
+    assert!(OtterscanClient::get_transaction_error(client, tx_hash).await.is_err());
+    assert!(OtterscanClient::trace_transaction(client, tx_hash).await.is_err());
 
     OtterscanClient::get_block_details(client, block_number).await.unwrap();
 
     OtterscanClient::get_block_details_by_hash(client, block_hash).await.unwrap();
 
-    assert!(is_unimplemented(
-        OtterscanClient::get_block_transactions(client, block_number, page_number, page_size,)
-            .await
-            .err()
-            .unwrap()
-    ));
-    assert!(is_unimplemented(
-        OtterscanClient::search_transactions_before(client, address, block_number, page_size,)
-            .await
-            .err()
-            .unwrap()
-    ));
-    assert!(is_unimplemented(
-        OtterscanClient::search_transactions_after(client, address, block_number, page_size,)
-            .await
-            .err()
-            .unwrap()
-    ));
-    assert!(is_unimplemented(
-        OtterscanClient::get_transaction_by_sender_and_nonce(client, sender, nonce,)
-            .await
-            .err()
-            .unwrap()
-    ));
-    assert!(is_unimplemented(
-        OtterscanClient::get_contract_creator(client, address).await.err().unwrap()
-    ));
+    // the block does not exist
+    assert!(OtterscanClient::get_block_transactions(client, block_number, page_number, page_size)
+        .await
+        .is_err());
+
+    // the transaction address index is not enabled
+    assert!(OtterscanClient::search_transactions_before(client, address, 0.into(), page_size)
+        .await
+        .is_err());
+    assert!(OtterscanClient::search_transactions_after(client, address, 0.into(), page_size)
+        .await
+        .is_err());
+
+    assert!(OtterscanClient::get_transaction_by_sender_and_nonce(client, sender, nonce)
+        .await
+        .unwrap()
+        .is_none());
+    assert!(OtterscanClient::get_contract_creator(client, address).await.unwrap().is_none());
 }
 
 #[tokio::test(flavor = "multi_thread")]
@@ -457,6 +465,24 @@ async fn test_call_trace_functions_http_and_ws() {
     test_basic_trace_calls(&client).await;
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn test_call_txpool_functions_http() {
+    reth_tracing::init_test_tracing();
+
+    let handle = launch_http(vec![RethRpcModule::Txpool]).await;
+    let client = handle.http_client().unwrap();
+    test_basic_txpool_calls(&client).await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_call_txpool_functions_ws() {
+    reth_tracing::init_test_tracing();
+
+    let handle = launch_ws(vec![RethRpcModule::Txpool]).await;
+    let client = handle.ws_client().await.unwrap();
+    test_basic_txpool_calls(&client).await;
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn test_call_web3_functions_http() {
     reth_tracing::init_test_tracing();
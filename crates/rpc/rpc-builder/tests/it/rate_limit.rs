@@ -0,0 +1,84 @@
+//! Rate limit tests
+
+use crate::utils::{test_address, test_rpc_builder};
+use jsonrpsee::core::{client::ClientT, error::Error, params::BatchRequestBuilder};
+use reth_rpc::RATE_LIMIT_EXCEEDED_CODE;
+use reth_rpc_api::Web3ApiClient;
+use reth_rpc_builder::{
+    RateLimitConfig, RethRpcModule, RpcServerConfig, RpcServerHandle, TransportRpcModuleConfig,
+};
+use std::num::NonZeroU32;
+
+fn is_rate_limited<T>(res: Result<T, Error>) -> bool {
+    matches!(res, Err(Error::Call(err)) if err.code() == RATE_LIMIT_EXCEEDED_CODE)
+}
+
+async fn launch_http_ws_rate_limited(rate_limit: RateLimitConfig) -> RpcServerHandle {
+    let builder = test_rpc_builder();
+    let server = builder.build(
+        TransportRpcModuleConfig::set_ws(vec![RethRpcModule::Web3])
+            .with_http(vec![RethRpcModule::Web3]),
+    );
+    server
+        .start_server(
+            RpcServerConfig::ws(Default::default())
+                .with_ws_address(test_address())
+                .with_http(Default::default())
+                .with_http_address(test_address())
+                .with_rate_limit(rate_limit),
+        )
+        .await
+        .unwrap()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_rate_limit_batch_calls() {
+    let handle = launch_http_ws_rate_limited(RateLimitConfig {
+        global: None,
+        per_connection: NonZeroU32::new(2),
+    })
+    .await;
+    let client = handle.http_client().unwrap();
+
+    let mut batch = BatchRequestBuilder::new();
+    for _ in 0..3 {
+        batch.insert("web3_clientVersion", Vec::<()>::new()).unwrap();
+    }
+    let res = client.batch_request::<String>(batch).await.unwrap();
+    assert_eq!(res.num_successful_calls(), 2);
+    assert_eq!(res.num_failed_calls(), 1);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_rate_limit_ws_calls() {
+    let handle = launch_http_ws_rate_limited(RateLimitConfig {
+        global: None,
+        per_connection: NonZeroU32::new(2),
+    })
+    .await;
+
+    let client = handle.ws_client().await.unwrap();
+    Web3ApiClient::client_version(&client).await.unwrap();
+    Web3ApiClient::client_version(&client).await.unwrap();
+    assert!(is_rate_limited(Web3ApiClient::client_version(&client).await));
+
+    // a new connection has its own limit
+    let client = handle.ws_client().await.unwrap();
+    Web3ApiClient::client_version(&client).await.unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_rate_limit_shared_by_servers() {
+    let handle = launch_http_ws_rate_limited(RateLimitConfig {
+        global: NonZeroU32::new(2),
+        per_connection: None,
+    })
+    .await;
+
+    let http = handle.http_client().unwrap();
+    let ws = handle.ws_client().await.unwrap();
+    Web3ApiClient::client_version(&http).await.unwrap();
+    Web3ApiClient::client_version(&ws).await.unwrap();
+    assert!(is_rate_limited(Web3ApiClient::client_version(&http).await));
+    assert!(is_rate_limited(Web3ApiClient::client_version(&ws).await));
+}
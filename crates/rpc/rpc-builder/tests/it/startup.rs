@@ -119,3 +119,27 @@ async fn test_launch_same_port_different_cors() {
         RpcError::WsHttpSamePortError(WsHttpSamePortError::ConflictingCorsDomains { .. })
     ));
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_launch_same_port_different_gas_caps() {
+    let builder = test_rpc_builder();
+    let server = builder.build(
+        TransportRpcModuleConfig::set_ws(vec![RethRpcModule::Eth])
+            .with_http(vec![RethRpcModule::Eth])
+            .with_http_gas_cap(1_000_000),
+    );
+    let addr = test_address();
+    let res = server
+        .start_server(
+            RpcServerConfig::ws(Default::default())
+                .with_ws_address(addr)
+                .with_http(Default::default())
+                .with_http_address(addr),
+        )
+        .await;
+    let err = res.unwrap_err();
+    assert!(matches!(
+        err,
+        RpcError::WsHttpSamePortError(WsHttpSamePortError::ConflictingGasCaps { .. })
+    ));
+}
@@ -21,19 +21,23 @@ pub fn test_address() -> SocketAddr {
     SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::UNSPECIFIED, 0))
 }
 
-/// Launches a new server for the auth module
-pub async fn launch_auth(secret: JwtSecret) -> AuthServerHandle {
-    let config = AuthServerConfig::builder(secret).socket_addr(test_address()).build();
+/// Returns an [EngineApi] with testing components.
+pub fn test_engine_api() -> EngineApi<NoopProvider, EthEngineTypes> {
     let (tx, _rx) = unbounded_channel();
     let beacon_engine_handle = BeaconConsensusEngineHandle::<EthEngineTypes>::new(tx);
-    let engine_api = EngineApi::new(
+    EngineApi::new(
         NoopProvider::default(),
         MAINNET.clone(),
         beacon_engine_handle,
         spawn_test_payload_service().into(),
         Box::<TokioTaskExecutor>::default(),
-    );
-    let module = AuthRpcModule::new(engine_api);
+    )
+}
+
+/// Launches a new server for the auth module
+pub async fn launch_auth(secret: JwtSecret) -> AuthServerHandle {
+    let config = AuthServerConfig::builder(secret).socket_addr(test_address()).build();
+    let module = AuthRpcModule::new(test_engine_api());
     module.start_server(config).await.unwrap()
 }
 
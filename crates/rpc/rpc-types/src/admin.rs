@@ -15,6 +15,9 @@ use std::{
 pub struct NodeInfo {
     /// Enode of the node in URL format.
     pub enode: NodeRecord,
+    /// ENR of the node in its base64 text format, see
+    /// [EIP-778](https://eips.ethereum.org/EIPS/eip-778).
+    pub enr: String,
     /// ID of the local node.
     pub id: PeerId,
     /// IP of the local node.
@@ -32,13 +35,14 @@ pub struct NodeInfo {
 
 impl NodeInfo {
     /// Creates a new instance of `NodeInfo`.
-    pub fn new(enr: NodeRecord, status: NetworkStatus) -> NodeInfo {
+    pub fn new(enode: NodeRecord, enr: String, status: NetworkStatus) -> NodeInfo {
         NodeInfo {
-            enode: enr,
-            id: enr.id,
-            ip: enr.address,
-            listen_addr: enr.tcp_addr(),
-            ports: Ports { discovery: enr.udp_port, listener: enr.tcp_port },
+            enode,
+            enr,
+            id: enode.id,
+            ip: enode.address,
+            listen_addr: enode.tcp_addr(),
+            ports: Ports { discovery: enode.udp_port, listener: enode.tcp_port },
             name: status.client_version,
             protocols: Protocols { eth: status.eth_protocol_info, other: Default::default() },
         }
@@ -95,7 +99,7 @@ mod tests {
 
     #[test]
     fn test_parse_node_info_roundtrip() {
-        let sample = r#"{"enode":"enode://44826a5d6a55f88a18298bca4773fca5749cdc3a5c9f308aa7d810e9b31123f3e7c5fba0b1d70aac5308426f47df2a128a6747040a3815cc7dd7167d03be320d@[::]:30303","id":"44826a5d6a55f88a18298bca4773fca5749cdc3a5c9f308aa7d810e9b31123f3e7c5fba0b1d70aac5308426f47df2a128a6747040a3815cc7dd7167d03be320d","ip":"::","listenAddr":"[::]:30303","name":"reth","ports":{"discovery":30303,"listener":30303},"protocols":{"eth":{"difficulty":17334254859343145000,"genesis":"0xd4e56740f876aef8c010b86a40d5f56745a118d0906a34e69aec8c0db1cb8fa3","head":"0xb83f73fbe6220c111136aefd27b160bf4a34085c65ba89f24246b3162257c36a","network":1}}}"#;
+        let sample = r#"{"enode":"enode://44826a5d6a55f88a18298bca4773fca5749cdc3a5c9f308aa7d810e9b31123f3e7c5fba0b1d70aac5308426f47df2a128a6747040a3815cc7dd7167d03be320d@[::]:30303","enr":"enr:-IS4QHCYrYZbAKWCBRlAy5zzaDZXJBGkcnh4MHcBFZntXNFrdvJjX04jRzjzCBOonrkTfj499SZuOh8R33Ls8RRcy5wBgmlkgnY0gmlwhH8AAAGJc2VjcDI1NmsxoQPKY0yuDUmstAHYpMa2_oxVtw0RW_QAdpzBQA8yWM0xOIN1ZHCCdl8","id":"44826a5d6a55f88a18298bca4773fca5749cdc3a5c9f308aa7d810e9b31123f3e7c5fba0b1d70aac5308426f47df2a128a6747040a3815cc7dd7167d03be320d","ip":"::","listenAddr":"[::]:30303","name":"reth","ports":{"discovery":30303,"listener":30303},"protocols":{"eth":{"difficulty":17334254859343145000,"genesis":"0xd4e56740f876aef8c010b86a40d5f56745a118d0906a34e69aec8c0db1cb8fa3","head":"0xb83f73fbe6220c111136aefd27b160bf4a34085c65ba89f24246b3162257c36a","network":1}}}"#;
 
         let info: NodeInfo = serde_json::from_str(sample).unwrap();
         let serialized = serde_json::to_string_pretty(&info).unwrap();
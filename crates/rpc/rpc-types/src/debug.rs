@@ -0,0 +1,25 @@
+use alloy_primitives::{Bytes, B256};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// The witness of a block execution, returned by `debug_executionWitness`.
+///
+/// Contains everything a stateless client needs to re-execute the block on top of the parent
+/// state root.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExecutionWitness {
+    /// The RLP encoded trie nodes proving all accounts and storage slots touched during execution
+    /// against the parent state root and the state root of the block, keyed by their hash.
+    ///
+    /// The nodes of the state of the block include the siblings of the branch nodes that collapse
+    /// when accounts or storage slots are removed.
+    pub state: HashMap<B256, Bytes>,
+    /// The bytecodes of all contracts touched during execution, keyed by their hash.
+    pub codes: HashMap<B256, Bytes>,
+    /// The preimages of the hashed account addresses and storage slots touched during execution,
+    /// keyed by their hash.
+    pub keys: HashMap<B256, Bytes>,
+    /// The RLP encoded headers of the parent block and its ancestors down to the oldest block
+    /// whose hash is read by `BLOCKHASH`, starting with the parent block.
+    pub headers: Vec<Bytes>,
+}
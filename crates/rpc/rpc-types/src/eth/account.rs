@@ -0,0 +1,31 @@
+//! Types for the `eth_getAccount` endpoint.
+
+use alloy_primitives::{B256, U256, U64};
+use serde::{Deserialize, Serialize};
+
+/// The state of an account at a given block.
+#[derive(Debug, Clone, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Account {
+    /// The balance of the account.
+    pub balance: U256,
+    /// The nonce of the account.
+    pub nonce: U64,
+    /// The hash of the code of the account.
+    pub code_hash: B256,
+    /// The root of the storage trie of the account.
+    pub storage_root: B256,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serde_account() {
+        let s = r#"{"balance":"0x1","nonce":"0x2","codeHash":"0xc5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470","storageRoot":"0x56e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421"}"#;
+        let account: Account = serde_json::from_str(s).unwrap();
+        assert_eq!(account.nonce, U64::from(2));
+        assert_eq!(serde_json::to_string(&account).unwrap(), s);
+    }
+}
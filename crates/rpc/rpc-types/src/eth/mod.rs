@@ -1,4 +1,7 @@
 //! Ethereum related types
 
+pub mod account;
 pub mod engine;
+pub mod simulate;
+pub mod syncing;
 pub mod transaction;
@@ -0,0 +1,126 @@
+//! Types for the `eth_simulateV1` endpoint.
+
+use alloy_primitives::{Address, Bytes, B256, U256, U64};
+use alloy_rpc_types::{state::StateOverride, BlockOverrides, CallRequest, Log};
+use serde::{Deserialize, Serialize};
+
+/// The maximum number of blocks that can be simulated in a single `eth_simulateV1` request.
+pub const MAX_SIMULATE_BLOCKS: usize = 256;
+
+/// The input of `eth_simulateV1`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulatePayload {
+    /// The blocks to simulate, executed in order on top of each other.
+    pub block_state_calls: Vec<SimulateBlock>,
+    /// Whether the transactions should be validated like regular transactions, e.g. checking the
+    /// nonce and the base fee.
+    #[serde(default)]
+    pub validation: bool,
+}
+
+/// A single block of an `eth_simulateV1` request.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulateBlock {
+    /// Overrides for the block environment.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_overrides: Option<BlockOverrides>,
+    /// Overrides for the state, applied before the first call of the block.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub state_overrides: Option<StateOverride>,
+    /// The calls to execute in this block.
+    #[serde(default)]
+    pub calls: Vec<CallRequest>,
+}
+
+/// A simulated block returned by `eth_simulateV1`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulatedBlock {
+    /// The hash of the simulated header.
+    pub hash: B256,
+    /// The hash of the parent block, the previous simulated block or the block the simulation is
+    /// executed on.
+    pub parent_hash: B256,
+    /// The number of the block.
+    pub number: U256,
+    /// The timestamp of the block.
+    pub timestamp: U256,
+    /// The gas limit of the block.
+    pub gas_limit: U256,
+    /// The gas used by all calls of the block.
+    pub gas_used: U256,
+    /// The fee recipient of the block.
+    pub fee_recipient: Address,
+    /// The base fee of the block.
+    pub base_fee_per_gas: U256,
+    /// The prevrandao of the block.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prev_randao: Option<B256>,
+    /// The results of the calls of the block.
+    pub calls: Vec<SimulateCallResult>,
+}
+
+/// The result of a single call of an `eth_simulateV1` request.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulateCallResult {
+    /// `1` if the call succeeded, `0` otherwise.
+    pub status: U64,
+    /// The data returned by the call, or the revert data.
+    pub return_data: Bytes,
+    /// The gas used by the call.
+    pub gas_used: U64,
+    /// The logs emitted by the call.
+    pub logs: Vec<Log>,
+    /// The error if the call failed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<SimulateError>,
+}
+
+/// The error of a failed call of an `eth_simulateV1` request.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SimulateError {
+    /// The error code.
+    pub code: i32,
+    /// The error message.
+    pub message: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deserialize_simulate_payload() {
+        let s = r#"{
+            "blockStateCalls": [
+                {
+                    "blockOverrides": { "number": "0x10" },
+                    "stateOverrides": {
+                        "0xc000000000000000000000000000000000000000": { "balance": "0x3e8" }
+                    },
+                    "calls": [
+                        {
+                            "from": "0xc000000000000000000000000000000000000000",
+                            "to": "0xc100000000000000000000000000000000000000",
+                            "value": "0x3e8"
+                        }
+                    ]
+                },
+                {}
+            ],
+            "validation": true
+        }"#;
+        let payload: SimulatePayload = serde_json::from_str(s).unwrap();
+        assert!(payload.validation);
+        assert_eq!(payload.block_state_calls.len(), 2);
+        assert_eq!(
+            payload.block_state_calls[0].block_overrides.as_ref().unwrap().number,
+            Some(U256::from(0x10))
+        );
+        assert_eq!(payload.block_state_calls[0].calls.len(), 1);
+        assert!(payload.block_state_calls[1].calls.is_empty());
+    }
+}
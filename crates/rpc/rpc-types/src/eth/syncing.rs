@@ -0,0 +1,100 @@
+//! Types for the `eth_syncing` endpoint.
+
+use alloy_primitives::{U256, U64};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+/// Syncing info, extended with the progress of the individual sync stages.
+#[derive(Debug, Clone, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncInfo {
+    /// Starting block
+    pub starting_block: U256,
+    /// Current block
+    pub current_block: U256,
+    /// Highest block seen so far
+    pub highest_block: U256,
+    /// Warp sync snapshot chunks total.
+    pub warp_chunks_amount: Option<U256>,
+    /// Warp sync snapshot chunks processed.
+    pub warp_chunks_processed: Option<U256>,
+    /// The progress of the individual sync stages, if known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stages: Option<Vec<SyncStage>>,
+}
+
+/// The progress of a single sync stage.
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncStage {
+    /// The name of the stage.
+    pub name: String,
+    /// The block the stage has synced up to.
+    pub block: U64,
+}
+
+/// Sync status, serialized as `false` if the node is not syncing.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum SyncStatus {
+    /// Info when syncing
+    Info(SyncInfo),
+    /// Not syncing
+    None,
+}
+
+impl Serialize for SyncStatus {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            SyncStatus::Info(info) => info.serialize(serializer),
+            SyncStatus::None => serializer.serialize_bool(false),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for SyncStatus {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Syncing {
+            Info(SyncInfo),
+            Bool(bool),
+        }
+
+        match Syncing::deserialize(deserializer)? {
+            Syncing::Info(info) => Ok(SyncStatus::Info(info)),
+            Syncing::Bool(false) => Ok(SyncStatus::None),
+            Syncing::Bool(true) => Err(de::Error::custom("invalid sync status `true`")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serde_sync_status() {
+        let status = SyncStatus::Info(SyncInfo {
+            starting_block: U256::from(1),
+            current_block: U256::from(5),
+            highest_block: U256::from(10),
+            stages: Some(vec![SyncStage { name: "Headers".to_string(), block: U64::from(10) }]),
+            ..Default::default()
+        });
+        let s = serde_json::to_string(&status).unwrap();
+        assert_eq!(
+            s,
+            r#"{"startingBlock":"0x1","currentBlock":"0x5","highestBlock":"0xa","warpChunksAmount":null,"warpChunksProcessed":null,"stages":[{"name":"Headers","block":"0xa"}]}"#
+        );
+        assert_eq!(serde_json::from_str::<SyncStatus>(&s).unwrap(), status);
+
+        assert_eq!(serde_json::to_string(&SyncStatus::None).unwrap(), "false");
+        assert_eq!(serde_json::from_str::<SyncStatus>("false").unwrap(), SyncStatus::None);
+        assert!(serde_json::from_str::<SyncStatus>("true").is_err());
+    }
+}
@@ -11,6 +11,7 @@
 
 mod admin;
 pub mod beacon;
+mod debug;
 mod eth;
 mod mev;
 mod net;
@@ -27,17 +28,22 @@ pub mod trace {
     pub use alloy_rpc_trace_types::*;
 }
 // Ethereum specific rpc types related to typed transaction requests and the engine API.
+// The syncing types shadow the ones from alloy, extending them with the sync stages.
 pub use eth::{
+    account::Account,
     engine,
     engine::{
         ExecutionPayload, ExecutionPayloadV1, ExecutionPayloadV2, ExecutionPayloadV3, PayloadError,
     },
+    simulate,
+    syncing::{SyncInfo, SyncStage, SyncStatus},
     transaction::{
         self, BlobTransactionSidecar, TransactionKind, TransactionRequest, TypedTransactionRequest,
     },
 };
 
 pub use admin::*;
+pub use debug::*;
 pub use mev::*;
 pub use net::*;
 pub use otterscan::*;
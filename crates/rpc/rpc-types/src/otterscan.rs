@@ -18,21 +18,33 @@ pub enum OperationType {
 /// Custom struct for otterscan `getInternalOperations` RPC response
 #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub struct InternalOperation {
-    r#type: OperationType,
-    from: Address,
-    to: Address,
-    value: u128,
+    /// The type of the operation
+    pub r#type: OperationType,
+    /// The address the value is sent from
+    pub from: Address,
+    /// The address the value is sent to
+    pub to: Address,
+    /// The transferred value
+    pub value: U256,
 }
 
 /// Custom struct for otterscan `traceTransaction` RPC response
 #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub struct TraceEntry {
-    r#type: String,
-    depth: u32,
-    from: Address,
-    to: Address,
-    value: u128,
-    input: Bytes,
+    /// The type of the call, e.g. `CALL`, `CREATE2` or `SELFDESTRUCT`
+    pub r#type: String,
+    /// The depth of the call in the call tree
+    pub depth: u32,
+    /// The caller
+    pub from: Address,
+    /// The callee
+    pub to: Address,
+    /// The value sent with the call
+    pub value: U256,
+    /// The call input
+    pub input: Bytes,
+    /// The call output
+    pub output: Bytes,
 }
 
 /// Internal issuance struct for `BlockDetails` struct
@@ -48,9 +60,11 @@ pub struct InternalIssuance {
 #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct OtsBlock {
+    /// The block
     #[serde(flatten)]
-    block: Block,
-    transaction_count: usize,
+    pub block: Block,
+    /// The number of transactions in the block
+    pub transaction_count: usize,
 }
 
 /// Custom struct for otterscan `getBlockDetails` RPC response
@@ -66,16 +80,20 @@ pub struct BlockDetails {
 #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct OtsTransactionReceipt {
+    /// The transaction receipt
     #[serde(flatten)]
-    receipt: TransactionReceipt,
-    timestamp: u64,
+    pub receipt: TransactionReceipt,
+    /// The timestamp of the block the transaction was included in
+    pub timestamp: u64,
 }
 
 /// Custom struct for otterscan `getBlockTransactions` RPC response
 #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub struct OtsBlockTransactions {
-    fullblock: OtsBlock,
-    receipts: Vec<OtsTransactionReceipt>,
+    /// The block, containing only the transactions of the requested page
+    pub fullblock: OtsBlock,
+    /// The receipts of the transactions of the requested page
+    pub receipts: Vec<OtsTransactionReceipt>,
 }
 
 /// Custom struct for otterscan `searchTransactionsAfter`and `searchTransactionsBefore` RPC
@@ -83,17 +101,23 @@ pub struct OtsBlockTransactions {
 #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct TransactionsWithReceipts {
-    txs: Vec<Transaction>,
-    receipts: Vec<OtsTransactionReceipt>,
-    first_page: bool,
-    last_page: bool,
+    /// The matching transactions
+    pub txs: Vec<Transaction>,
+    /// The receipts of the matching transactions
+    pub receipts: Vec<OtsTransactionReceipt>,
+    /// Whether this is the page containing the most recent transactions
+    pub first_page: bool,
+    /// Whether this is the page containing the oldest transactions
+    pub last_page: bool,
 }
 
 /// Custom struct for otterscan `getContractCreator` RPC responses
 #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub struct ContractCreator {
-    tx: Transaction,
-    creator: Address,
+    /// The transaction that created the contract
+    pub tx: Transaction,
+    /// The address that created the contract
+    pub creator: Address,
 }
 
 impl From<Block> for OtsBlock {
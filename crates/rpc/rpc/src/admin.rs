@@ -34,6 +34,8 @@ where
 
     /// Handler for `admin_removePeer`
     fn remove_peer(&self, record: NodeRecord) -> RpcResult<bool> {
+        // trusted peers are never removed, so the peer is first removed from the trusted set
+        self.network.remove_peer(record.id, PeerKind::Trusted);
         self.network.remove_peer(record.id, PeerKind::Basic);
         Ok(true)
     }
@@ -81,10 +83,11 @@ where
 
     /// Handler for `admin_nodeInfo`
     async fn node_info(&self) -> RpcResult<NodeInfo> {
-        let enr = self.network.local_node_record();
+        let enode = self.network.local_node_record();
+        let enr = self.network.local_enr().to_base64();
         let status = self.network.network_status().await.to_rpc_result()?;
 
-        Ok(NodeInfo::new(enr, status))
+        Ok(NodeInfo::new(enode, enr, status))
     }
 
     /// Handler for `admin_peerEvents`
@@ -1,10 +1,12 @@
 use crate::{
     eth::{
+        cache::TraceCache,
         error::{EthApiError, EthResult},
         revm_utils::{
-            clone_into_empty_db, inspect, inspect_and_return_db, prepare_call_env,
-            replay_transactions_until, transact, EvmOverrides,
+            clone_into_empty_db, prepare_call_env, replay_transactions_until, transact,
+            EvmOverrides,
         },
+        tracing_limits::TracingLimits,
         EthTransactions, TransactionSource,
     },
     result::{internal_rpc_err, ToRpcResult},
@@ -14,22 +16,28 @@ use alloy_rlp::{Decodable, Encodable};
 use async_trait::async_trait;
 use jsonrpsee::core::RpcResult;
 use reth_primitives::{
+    constants::BEACON_ROOTS_ADDRESS,
+    keccak256,
     revm::env::tx_env_with_recovered,
     revm_primitives::{
         db::{DatabaseCommit, DatabaseRef},
         BlockEnv, CfgEnv,
     },
-    Address, Block, BlockId, BlockNumberOrTag, Bytes, TransactionSignedEcRecovered, B256,
+    trie::AccountProof,
+    Address, Block, BlockId, BlockNumberOrTag, Bytes, ChainSpec, Receipt,
+    TransactionSignedEcRecovered, B256,
 };
 use reth_provider::{
-    BlockReaderIdExt, ChainSpecProvider, HeaderProvider, StateProviderBox, TransactionVariant,
+    BlockReaderIdExt, ChainSpecProvider, HeaderProvider, StateProvider, StateProviderBox,
 };
 use reth_revm::{
     database::{StateProviderDatabase, SubState},
+    state_change::apply_beacon_root_contract_call,
     tracing::{
         js::{JsDbRequest, JsInspector},
         FourByteInspector, TracingInspector, TracingInspectorConfig,
     },
+    EvmConfig,
 };
 use reth_rpc_api::DebugApiServer;
 use reth_rpc_types::{
@@ -37,14 +45,19 @@ use reth_rpc_types::{
         BlockTraceResult, FourByteFrame, GethDebugBuiltInTracerType, GethDebugTracerType,
         GethDebugTracingCallOptions, GethDebugTracingOptions, GethTrace, NoopFrame, TraceResult,
     },
-    BlockError, Bundle, CallRequest, RichBlock, StateContext,
+    BlockError, Bundle, CallRequest, ExecutionWitness, RichBlock, StateContext,
 };
 use reth_tasks::TaskSpawner;
 use revm::{
     db::{CacheDB, EmptyDB},
     primitives::Env,
 };
-use std::sync::Arc;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    future::Future,
+    sync::Arc,
+    time::Duration,
+};
 use tokio::sync::{mpsc, AcquireError, OwnedSemaphorePermit};
 use tokio_stream::{wrappers::ReceiverStream, StreamExt};
 
@@ -64,9 +77,15 @@ impl<Provider, Eth> DebugApi<Provider, Eth> {
         eth: Eth,
         task_spawner: Box<dyn TaskSpawner>,
         blocking_task_guard: BlockingTaskGuard,
+        trace_cache: TraceCache,
     ) -> Self {
-        let inner =
-            Arc::new(DebugApiInner { provider, eth_api: eth, task_spawner, blocking_task_guard });
+        let inner = Arc::new(DebugApiInner {
+            provider,
+            eth_api: eth,
+            task_spawner,
+            blocking_task_guard,
+            trace_cache,
+        });
         Self { inner }
     }
 }
@@ -83,6 +102,14 @@ where
         self.inner.blocking_task_guard.clone().acquire_owned().await
     }
 
+    /// Acquires a permit to execute a tracing call, which is held by the limits of the tracer
+    /// configured by the options.
+    async fn tracing_limits(&self, opts: &GethDebugTracingOptions) -> EthResult<TracingLimits> {
+        let permit =
+            self.acquire_trace_permit().await.map_err(|_| EthApiError::InternalEthError)?;
+        Ok(TracingLimits::new(opts, permit))
+    }
+
     /// Trace the entire block asynchronously
     async fn trace_block_with(
         &self,
@@ -201,6 +228,68 @@ where
         .await
     }
 
+    /// Re-executes the block on top of its parent and returns the witness of the execution.
+    ///
+    /// All accounts and storage slots touched by the EIP-4788 pre-block call and the transactions,
+    /// the block's beneficiary and the withdrawal recipients are proven against the state root of
+    /// the parent block and the state root of the block.
+    pub async fn debug_execution_witness(&self, block_id: BlockId) -> EthResult<ExecutionWitness> {
+        let block_hash = self
+            .inner
+            .provider
+            .block_hash_for_id(block_id)?
+            .ok_or_else(|| EthApiError::UnknownBlockNumber)?;
+
+        let ((cfg, block_env, _), block) = futures::try_join!(
+            self.inner.eth_api.evm_env_at(block_hash.into()),
+            self.inner.eth_api.block_by_id_with_senders(block_id),
+        )?;
+
+        let block = block.ok_or_else(|| EthApiError::UnknownBlockNumber)?;
+        let state_at = block.parent_hash;
+        let parent_number = block.number.checked_sub(1).ok_or(EthApiError::UnknownBlockNumber)?;
+        let chain_spec = self.inner.provider.chain_spec();
+        let parent_beacon_block_root = block.parent_beacon_block_root;
+
+        // accounts that are touched outside of transaction execution
+        let mut touched = BTreeMap::<Address, BTreeSet<B256>>::new();
+        touched.entry(block.beneficiary).or_default();
+        for withdrawal in block.withdrawals.iter().flatten() {
+            touched.entry(withdrawal.address).or_default();
+        }
+        let transactions = block.into_transactions_ecrecovered().collect::<Vec<_>>();
+        let evm_config = *self.inner.eth_api.evm_config();
+
+        let (mut witness, targets, lowest_block_hash) = self
+            .inner
+            .eth_api
+            .spawn_with_state_at_block(state_at.into(), move |state| {
+                execute_block_for_witness(
+                    state,
+                    &chain_spec,
+                    Env { cfg, block: block_env, ..Default::default() },
+                    parent_beacon_block_root,
+                    touched,
+                    transactions,
+                    &evm_config,
+                )
+            })
+            .await?;
+
+        // the same accounts and storage slots are proven against the state of the block, which
+        // covers the nodes that a stateless client needs to compute the new state root
+        let proofs = self
+            .inner
+            .eth_api
+            .spawn_with_state_at_block(block_hash.into(), move |state| Ok(state.proofs(&targets)?))
+            .await?;
+        add_proof_nodes(&mut witness, proofs);
+
+        witness.headers = witness_headers(&self.inner.provider, parent_number, lowest_block_hash)?;
+
+        Ok(witness)
+    }
+
     /// Trace the transaction according to the provided options.
     ///
     /// Ref: <https://geth.ethereum.org/docs/developers/evm-tracing/built-in-tracers>
@@ -208,12 +297,32 @@ where
         &self,
         tx_hash: B256,
         opts: GethDebugTracingOptions,
+    ) -> EthResult<GethTrace> {
+        let timeout = tracing_timeout(&opts)?;
+        let limits = self.tracing_limits(&opts).await?;
+        // stops the tracer once the request timed out or was dropped
+        let _cancel = limits.cancel_on_drop();
+        with_tracing_timeout(timeout, self.trace_transaction_with(tx_hash, opts, limits)).await
+    }
+
+    /// Traces the transaction according to the provided options within the given limits.
+    async fn trace_transaction_with(
+        &self,
+        tx_hash: B256,
+        opts: GethDebugTracingOptions,
+        limits: TracingLimits,
     ) -> EthResult<GethTrace> {
         let (transaction, block) = match self.inner.eth_api.transaction_and_block(tx_hash).await? {
             None => return Err(EthApiError::TransactionNotFound),
             Some(res) => res,
         };
-        let (cfg, block_env, _) = self.inner.eth_api.evm_env_at(block.hash.into()).await?;
+        let block_hash = block.hash;
+        if let Some(trace) =
+            self.inner.trace_cache.get_transaction_trace(block_hash, tx_hash, &opts)
+        {
+            return Ok(trace)
+        }
+        let (cfg, block_env, _) = self.inner.eth_api.evm_env_at(block_hash.into()).await?;
 
         // we need to get the state of the parent block because we're essentially replaying the
         // block the transaction is included in
@@ -221,7 +330,9 @@ where
         let block_txs = block.body;
 
         let this = self.clone();
-        self.inner
+        let cache_opts = self.inner.trace_cache.is_enabled().then(|| opts.clone());
+        let trace = self
+            .inner
             .eth_api
             .spawn_with_state_at_block(state_at, move |state| {
                 // configure env for the target transaction
@@ -235,12 +346,24 @@ where
                     block_env.clone(),
                     block_txs,
                     tx.hash,
+                    this.inner.eth_api.evm_config(),
                 )?;
 
                 let env = Env { cfg, block: block_env, tx: tx_env_with_recovered(&tx) };
-                this.trace_transaction(opts, env, state_at, &mut db).map(|(trace, _)| trace)
+                this.trace_transaction(opts, env, state_at, &mut db, &limits)
+                    .map(|(trace, _)| trace)
             })
-            .await
+            .await?;
+
+        if let Some(opts) = cache_opts {
+            self.inner.trace_cache.insert_transaction_trace(
+                block_hash,
+                tx_hash,
+                &opts,
+                trace.clone(),
+            );
+        }
+        Ok(trace)
     }
 
     /// The debug_traceCall method lets you run an `eth_call` within the context of the given block
@@ -250,12 +373,28 @@ where
         call: CallRequest,
         block_id: Option<BlockId>,
         opts: GethDebugTracingCallOptions,
+    ) -> EthResult<GethTrace> {
+        let timeout = tracing_timeout(&opts.tracing_options)?;
+        let limits = self.tracing_limits(&opts.tracing_options).await?;
+        // stops the tracer once the request timed out or was dropped
+        let _cancel = limits.cancel_on_drop();
+        with_tracing_timeout(timeout, self.trace_call_with(call, block_id, opts, limits)).await
+    }
+
+    /// Traces the call according to the provided options within the given limits.
+    async fn trace_call_with(
+        &self,
+        call: CallRequest,
+        block_id: Option<BlockId>,
+        opts: GethDebugTracingCallOptions,
+        limits: TracingLimits,
     ) -> EthResult<GethTrace> {
         let at = block_id.unwrap_or(BlockId::Number(BlockNumberOrTag::Latest));
         let GethDebugTracingCallOptions { tracing_options, state_overrides, block_overrides } =
             opts;
         let overrides = EvmOverrides::new(state_overrides, block_overrides.map(Box::new));
         let GethDebugTracingOptions { config, tracer, tracer_config, .. } = tracing_options;
+        let evm_config = *self.inner.eth_api.evm_config();
 
         if let Some(tracer) = tracer {
             return match tracer {
@@ -266,7 +405,7 @@ where
                             .inner
                             .eth_api
                             .spawn_with_call_at(call, at, overrides, move |db, env| {
-                                inspect(db, env, &mut inspector)?;
+                                limits.inspect(db, env, &mut inspector, &evm_config)?;
                                 Ok(inspector)
                             })
                             .await?;
@@ -286,7 +425,8 @@ where
                             .inner
                             .eth_api
                             .spawn_with_call_at(call, at, overrides, move |db, env| {
-                                let (res, _) = inspect(db, env, &mut inspector)?;
+                                let (res, _) =
+                                    limits.inspect(db, env, &mut inspector, &evm_config)?;
                                 let frame = inspector
                                     .into_geth_builder()
                                     .geth_call_traces(call_config, res.result.gas_used());
@@ -310,8 +450,12 @@ where
                             self.inner
                                 .eth_api
                                 .spawn_with_call_at(call, at, overrides, move |db, env| {
-                                    let (res, _, db) =
-                                        inspect_and_return_db(db, env, &mut inspector)?;
+                                    let (res, _, db) = limits.inspect_and_return_db(
+                                        db,
+                                        env,
+                                        &mut inspector,
+                                        &evm_config,
+                                    )?;
                                     let frame = inspector
                                         .into_geth_builder()
                                         .geth_prestate_traces(&res, prestate_config, &db)?;
@@ -346,7 +490,8 @@ where
                         .eth_api
                         .spawn_with_call_at(call, at, overrides, move |db, env| {
                             let mut inspector = JsInspector::new(code, config, to_db_service)?;
-                            let (res, _) = inspect(db, env.clone(), &mut inspector)?;
+                            let (res, _) =
+                                limits.inspect(db, env.clone(), &mut inspector, &evm_config)?;
                             Ok(inspector.json_result(res, &env)?)
                         })
                         .await?;
@@ -365,7 +510,7 @@ where
             .inner
             .eth_api
             .spawn_with_call_at(call, at, overrides, move |db, env| {
-                let (res, _) = inspect(db, env, &mut inspector)?;
+                let (res, _) = limits.inspect(db, env, &mut inspector, &evm_config)?;
                 Ok((res, inspector))
             })
             .await?;
@@ -401,6 +546,9 @@ where
         let block = block.ok_or_else(|| EthApiError::UnknownBlockNumber)?;
         let GethDebugTracingCallOptions { tracing_options, mut state_overrides, .. } = opts;
         let gas_limit = self.inner.eth_api.call_gas_limit();
+        let limits = self.tracing_limits(&tracing_options).await?;
+        // stops the tracer once the request was dropped
+        let _cancel = limits.cancel_on_drop();
 
         // we're essentially replaying the transactions in the block here, hence we need the state
         // that points to the beginning of the block, which is the state at the parent block
@@ -431,7 +579,7 @@ where
                     for tx in transactions {
                         let tx = tx_env_with_recovered(&tx);
                         let env = Env { cfg: cfg.clone(), block: block_env.clone(), tx };
-                        let (res, _) = transact(&mut db, env)?;
+                        let (res, _) = transact(&mut db, env, this.inner.eth_api.evm_config())?;
                         db.commit(res.state);
                     }
                 }
@@ -464,6 +612,7 @@ where
                             env,
                             target_block,
                             &mut db,
+                            &limits,
                         )?;
 
                         // If there is more transactions, commit the database
@@ -481,7 +630,8 @@ where
             .await
     }
 
-    /// Executes the configured transaction with the environment on the given database.
+    /// Executes the configured transaction with the environment on the given database, within the
+    /// given limits.
     ///
     /// Returns the trace frame and the state that got updated after executing the transaction.
     ///
@@ -494,6 +644,7 @@ where
         env: Env,
         at: BlockId,
         db: &mut SubState<StateProviderBox>,
+        limits: &TracingLimits,
     ) -> EthResult<(GethTrace, revm_primitives::State)> {
         let GethDebugTracingOptions { config, tracer, tracer_config, .. } = opts;
 
@@ -502,7 +653,12 @@ where
                 GethDebugTracerType::BuiltInTracer(tracer) => match tracer {
                     GethDebugBuiltInTracerType::FourByteTracer => {
                         let mut inspector = FourByteInspector::default();
-                        let (res, _) = inspect(db, env, &mut inspector)?;
+                        let (res, _) = limits.inspect(
+                            db,
+                            env,
+                            &mut inspector,
+                            self.inner.eth_api.evm_config(),
+                        )?;
                         return Ok((FourByteFrame::from(inspector).into(), res.state))
                     }
                     GethDebugBuiltInTracerType::CallTracer => {
@@ -515,7 +671,12 @@ where
                                 .set_record_logs(call_config.with_log.unwrap_or_default()),
                         );
 
-                        let (res, _) = inspect(db, env, &mut inspector)?;
+                        let (res, _) = limits.inspect(
+                            db,
+                            env,
+                            &mut inspector,
+                            self.inner.eth_api.evm_config(),
+                        )?;
 
                         let frame = inspector
                             .into_geth_builder()
@@ -534,7 +695,12 @@ where
                                 // which we need to record steps and statediff
                                 .set_steps_and_state_diffs(prestate_config.is_default_mode()),
                         );
-                        let (res, _) = inspect(&mut *db, env, &mut inspector)?;
+                        let (res, _) = limits.inspect(
+                            &mut *db,
+                            env,
+                            &mut inspector,
+                            self.inner.eth_api.evm_config(),
+                        )?;
 
                         let frame = inspector.into_geth_builder().geth_prestate_traces(
                             &res,
@@ -559,7 +725,8 @@ where
                     let to_db_service = self.spawn_js_trace_service(at, Some(js_db))?;
 
                     let mut inspector = JsInspector::new(code, config, to_db_service)?;
-                    let (res, env) = inspect(db, env, &mut inspector)?;
+                    let (res, env) =
+                        limits.inspect(db, env, &mut inspector, self.inner.eth_api.evm_config())?;
 
                     let state = res.state.clone();
                     let result = inspector.json_result(res, &env)?;
@@ -573,7 +740,7 @@ where
 
         let mut inspector = TracingInspector::new(inspector_config);
 
-        let (res, _) = inspect(db, env, &mut inspector)?;
+        let (res, _) = limits.inspect(db, env, &mut inspector, self.inner.eth_api.evm_config())?;
         let gas_used = res.result.gas_used();
         let return_value = res.result.into_output().unwrap_or_default();
         let frame = inspector.into_geth_builder().geth_traces(gas_used, return_value, config);
@@ -698,11 +865,8 @@ where
         let block = self.inner.provider.block_by_id(block_id).to_rpc_result()?;
 
         let mut res = Vec::new();
-        if let Some(mut block) = block {
-            // In RPC withdrawals are always present
-            if block.withdrawals.is_none() {
-                block.withdrawals = Some(vec![]);
-            }
+        if let Some(block) = block {
+            // encoded as stored, pre-shanghai blocks don't include withdrawals
             block.encode(&mut res);
         }
 
@@ -720,31 +884,17 @@ where
     }
 
     /// Handler for `debug_getRawTransactions`
-    /// Returns the bytes of the transaction for the given hash.
+    /// Returns the bytes of the transactions of the given block.
     async fn raw_transactions(&self, block_id: BlockId) -> RpcResult<Vec<Bytes>> {
-        let block = self
-            .inner
-            .provider
-            .block_with_senders_by_id(block_id, TransactionVariant::NoHash)
-            .to_rpc_result()?
-            .unwrap_or_default();
-        Ok(block.into_transactions_ecrecovered().map(|tx| tx.envelope_encoded()).collect())
+        let block = self.inner.provider.block_by_id(block_id).to_rpc_result()?.unwrap_or_default();
+        Ok(block.body.iter().map(|tx| tx.envelope_encoded()).collect())
     }
 
     /// Handler for `debug_getRawReceipts`
     async fn raw_receipts(&self, block_id: BlockId) -> RpcResult<Vec<Bytes>> {
         let receipts =
             self.inner.provider.receipts_by_block_id(block_id).to_rpc_result()?.unwrap_or_default();
-        let mut all_receipts = Vec::with_capacity(receipts.len());
-
-        for receipt in receipts {
-            let mut buf = Vec::new();
-            let receipt = receipt.with_bloom();
-            receipt.encode(&mut buf);
-            all_receipts.push(buf.into());
-        }
-
-        Ok(all_receipts)
+        Ok(receipts.into_iter().map(encode_raw_receipt).collect())
     }
 
     /// Handler for `debug_getBadBlocks`
@@ -791,13 +941,21 @@ where
         Ok(DebugApi::debug_trace_block(self, block.into(), opts.unwrap_or_default()).await?)
     }
 
+    /// Handler for `debug_executionWitness`
+    async fn debug_execution_witness(
+        &self,
+        block: BlockNumberOrTag,
+    ) -> RpcResult<ExecutionWitness> {
+        let _permit = self.acquire_trace_permit().await;
+        Ok(DebugApi::debug_execution_witness(self, block.into()).await?)
+    }
+
     /// Handler for `debug_traceTransaction`
     async fn debug_trace_transaction(
         &self,
         tx_hash: B256,
         opts: Option<GethDebugTracingOptions>,
     ) -> RpcResult<GethTrace> {
-        let _permit = self.acquire_trace_permit().await;
         Ok(DebugApi::debug_trace_transaction(self, tx_hash, opts.unwrap_or_default()).await?)
     }
 
@@ -808,7 +966,6 @@ where
         block_number: Option<BlockId>,
         opts: Option<GethDebugTracingCallOptions>,
     ) -> RpcResult<GethTrace> {
-        let _permit = self.acquire_trace_permit().await;
         Ok(DebugApi::debug_trace_call(self, request, block_number, opts.unwrap_or_default())
             .await?)
     }
@@ -819,7 +976,6 @@ where
         state_context: Option<StateContext>,
         opts: Option<GethDebugTracingCallOptions>,
     ) -> RpcResult<Vec<Vec<GethTrace>>> {
-        let _permit = self.acquire_trace_permit().await;
         Ok(DebugApi::debug_trace_call_many(self, bundles, state_context, opts).await?)
     }
 
@@ -1056,4 +1212,492 @@ struct DebugApiInner<Provider, Eth> {
     blocking_task_guard: BlockingTaskGuard,
     /// The type that can spawn tasks which would otherwise block.
     task_spawner: Box<dyn TaskSpawner>,
+    /// Cache for the results of `debug_traceTransaction`
+    trace_cache: TraceCache,
+}
+
+/// The default timeout for JS tracers, if none was provided, same as geth.
+const DEFAULT_JS_TRACER_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Returns the timeout that applies to the tracing request.
+///
+/// If the request does not specify a timeout, JS tracers are limited to
+/// [DEFAULT_JS_TRACER_TIMEOUT], while all other tracers are not limited.
+fn tracing_timeout(opts: &GethDebugTracingOptions) -> EthResult<Option<Duration>> {
+    if let Some(timeout) = opts.timeout.as_deref() {
+        return parse_go_duration(timeout)
+            .map(Some)
+            .ok_or_else(|| EthApiError::InvalidParams(format!("invalid tracer timeout: {timeout}")))
+    }
+    if matches!(opts.tracer, Some(GethDebugTracerType::JsTracer(_))) {
+        return Ok(Some(DEFAULT_JS_TRACER_TIMEOUT))
+    }
+    Ok(None)
+}
+
+/// Awaits the tracing future, returning [EthApiError::ExecutionTimedOut] if it takes longer than
+/// the given timeout.
+async fn with_tracing_timeout<F, T>(timeout: Option<Duration>, fut: F) -> EthResult<T>
+where
+    F: Future<Output = EthResult<T>>,
+{
+    match timeout {
+        Some(timeout) => tokio::time::timeout(timeout, fut)
+            .await
+            .map_err(|_| EthApiError::ExecutionTimedOut(timeout))?,
+        None => fut.await,
+    }
+}
+
+/// Parses a duration in the format of Go's `time.ParseDuration`, e.g. `300ms`, `1.5s` or `1m30s`.
+fn parse_go_duration(s: &str) -> Option<Duration> {
+    let mut rest = s.trim();
+    if rest == "0" {
+        return Some(Duration::ZERO)
+    }
+    if rest.is_empty() {
+        return None
+    }
+
+    let mut total = 0f64;
+    while !rest.is_empty() {
+        let num_len = rest.find(|c: char| !(c.is_ascii_digit() || c == '.')).unwrap_or(rest.len());
+        let value: f64 = rest[..num_len].parse().ok()?;
+        rest = &rest[num_len..];
+        let unit_len = rest.find(|c: char| c.is_ascii_digit() || c == '.').unwrap_or(rest.len());
+        let nanos_per_unit = match &rest[..unit_len] {
+            "ns" => 1f64,
+            "us" | "µs" => 1e3,
+            "ms" => 1e6,
+            "s" => 1e9,
+            "m" => 60e9,
+            "h" => 3600e9,
+            _ => return None,
+        };
+        rest = &rest[unit_len..];
+        total += value * nanos_per_unit;
+    }
+
+    Some(Duration::from_nanos(total as u64))
+}
+
+/// Returns the EIP-2718 encoding of the receipt.
+///
+/// Unlike the network encoding, typed receipts are not wrapped in an RLP string header.
+fn encode_raw_receipt(receipt: Receipt) -> Bytes {
+    let mut buf = Vec::new();
+    receipt.with_bloom().encode_inner(&mut buf, false);
+    buf.into()
+}
+
+/// Executes the transactions of a block on top of the state of its parent and returns the witness
+/// of the parent state.
+///
+/// `touched` contains the accounts and storage slots that are touched outside of transaction
+/// execution. Also returns all touched accounts and storage slots, and the lowest block whose
+/// hash is read by `BLOCKHASH`.
+fn execute_block_for_witness(
+    state: StateProviderBox,
+    chain_spec: &ChainSpec,
+    env: Env,
+    parent_beacon_block_root: Option<B256>,
+    mut touched: BTreeMap<Address, BTreeSet<B256>>,
+    transactions: Vec<TransactionSignedEcRecovered>,
+    evm_config: &EvmConfig,
+) -> EthResult<(ExecutionWitness, Vec<(Address, Vec<B256>)>, Option<u64>)> {
+    let mut db = CacheDB::new(StateProviderDatabase::new(state));
+    let mut witness = ExecutionWitness::default();
+
+    // apply the EIP-4788 pre-block call as the executor does
+    let (timestamp, number) =
+        (env.block.timestamp.saturating_to(), env.block.number.saturating_to());
+    let mut evm = revm::EVM::with_env(env.clone());
+    evm.database(&mut db);
+    apply_beacon_root_contract_call(
+        chain_spec,
+        timestamp,
+        number,
+        parent_beacon_block_root,
+        &mut evm,
+    )
+    .map_err(|err| EthApiError::Internal(err.into()))?;
+    if let Some(account) = db.accounts.get(&BEACON_ROOTS_ADDRESS) {
+        touched
+            .entry(BEACON_ROOTS_ADDRESS)
+            .or_default()
+            .extend(account.storage.keys().map(|slot| B256::from(slot.to_be_bytes())));
+        if let Some(code) = account.info.code.as_ref() {
+            let code = code.original_bytes();
+            if !code.is_empty() {
+                witness.codes.insert(account.info.code_hash, code);
+            }
+        }
+    }
+
+    for tx in transactions {
+        let env = Env { tx: tx_env_with_recovered(&tx), ..env.clone() };
+        let (res, _) = transact(&mut db, env, evm_config)?;
+
+        for (address, account) in res.state.iter() {
+            touched
+                .entry(*address)
+                .or_default()
+                .extend(account.storage.keys().map(|slot| B256::from(slot.to_be_bytes())));
+
+            if let Some(code) = account.info.code.as_ref() {
+                let code = code.original_bytes();
+                if !code.is_empty() {
+                    witness.codes.insert(account.info.code_hash, code);
+                }
+            }
+        }
+
+        db.commit(res.state);
+    }
+
+    let targets = touched
+        .into_iter()
+        .map(|(address, slots)| (address, slots.into_iter().collect::<Vec<_>>()))
+        .collect::<Vec<_>>();
+    for (address, slots) in &targets {
+        witness.keys.insert(keccak256(address), address.to_vec().into());
+        for slot in slots {
+            witness.keys.insert(keccak256(slot), slot.to_vec().into());
+        }
+    }
+
+    // the state of the parent block is reverted once for all proofs
+    let proofs = db.db.state().proofs(&targets)?;
+    add_proof_nodes(&mut witness, proofs);
+
+    let lowest_block_hash = db.block_hashes.keys().map(|number| number.saturating_to()).min();
+
+    Ok((witness, targets, lowest_block_hash))
+}
+
+/// Adds the account and storage proof nodes to the state of the witness.
+fn add_proof_nodes(witness: &mut ExecutionWitness, proofs: Vec<AccountProof>) {
+    for proof in proofs {
+        let storage_nodes = proof.storage_proofs.into_iter().flat_map(|proof| proof.proof);
+        for node in proof.proof.into_iter().chain(storage_nodes) {
+            witness.state.insert(keccak256(&node), node);
+        }
+    }
+}
+
+/// Returns the RLP encoded headers from the parent block down to the lowest block whose hash is
+/// read by `BLOCKHASH`, so that the hashes can be verified against the parent hash of the block.
+fn witness_headers(
+    provider: &impl HeaderProvider,
+    parent_number: u64,
+    lowest_block_hash: Option<u64>,
+) -> EthResult<Vec<Bytes>> {
+    let lowest = lowest_block_hash.map_or(parent_number, |number| number.min(parent_number));
+    let headers = provider.headers_range(lowest..=parent_number)?;
+    if headers.len() as u64 != parent_number - lowest + 1 {
+        return Err(EthApiError::UnknownBlockNumber)
+    }
+    Ok(headers.into_iter().rev().map(|header| alloy_rlp::encode(header).into()).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_db::{tables, transaction::DbTxMut};
+    use reth_primitives::{
+        trie::EMPTY_ROOT_HASH, Account, Bytecode, Header, Signature, StorageEntry, Transaction,
+        TransactionKind, TransactionSigned, TxLegacy, TxType, KECCAK_EMPTY, MAINNET, U256,
+    };
+    use reth_provider::test_utils::create_test_provider_factory;
+    use reth_trie::StateRoot;
+    use revm::primitives::AccountInfo;
+    use std::collections::HashMap;
+
+    /// Returns the encoded items of an RLP list.
+    fn rlp_list(mut buf: &[u8]) -> Vec<&[u8]> {
+        let header = alloy_rlp::Header::decode(&mut buf).unwrap();
+        assert!(header.list);
+        let mut payload = &buf[..header.payload_length];
+        let mut items = Vec::new();
+        while !payload.is_empty() {
+            let mut rest = payload;
+            let item = alloy_rlp::Header::decode(&mut rest).unwrap();
+            let (encoded, remaining) =
+                payload.split_at(payload.len() - rest.len() + item.payload_length);
+            items.push(encoded);
+            payload = remaining;
+        }
+        items
+    }
+
+    /// Returns the payload of an RLP string.
+    fn rlp_string(mut buf: &[u8]) -> &[u8] {
+        let header = alloy_rlp::Header::decode(&mut buf).unwrap();
+        assert!(!header.list);
+        &buf[..header.payload_length]
+    }
+
+    /// A database that only reads the state and the block hashes of an [ExecutionWitness].
+    ///
+    /// Panics if the witness is missing anything the execution reads.
+    struct WitnessDatabase {
+        witness: ExecutionWitness,
+        state_root: B256,
+        block_hashes: HashMap<u64, B256>,
+    }
+
+    impl WitnessDatabase {
+        /// Verifies the headers of the witness against the parent hash of the block.
+        fn new(witness: ExecutionWitness, parent_hash: B256) -> Self {
+            let mut block_hashes = HashMap::new();
+            let mut state_root = None;
+            let mut hash = parent_hash;
+            for encoded in &witness.headers {
+                assert_eq!(keccak256(encoded), hash);
+                let header = Header::decode(&mut encoded.as_ref()).unwrap();
+                state_root.get_or_insert(header.state_root);
+                block_hashes.insert(header.number, hash);
+                hash = header.parent_hash;
+            }
+            Self { state_root: state_root.expect("missing parent header"), witness, block_hashes }
+        }
+
+        /// Returns the value of the key in the trie with the given root.
+        fn trie_value(&self, root: B256, key: B256) -> Option<Vec<u8>> {
+            if root == EMPTY_ROOT_HASH {
+                return None
+            }
+            let path = key.iter().flat_map(|byte| [byte >> 4, byte & 0x0f]).collect::<Vec<_>>();
+            let mut path = path.as_slice();
+            let mut node = self.witness.state.get(&root).expect("missing trie node").to_vec();
+            loop {
+                let items = rlp_list(&node);
+                let child = match items.len() {
+                    17 => {
+                        let (nibble, rest) = path.split_first()?;
+                        path = rest;
+                        items[*nibble as usize]
+                    }
+                    2 => {
+                        let encoded_path = rlp_string(items[0]);
+                        let mut node_path = Vec::new();
+                        if encoded_path[0] & 0x10 != 0 {
+                            node_path.push(encoded_path[0] & 0x0f);
+                        }
+                        node_path.extend(
+                            encoded_path[1..].iter().flat_map(|byte| [byte >> 4, byte & 0x0f]),
+                        );
+                        // leaf
+                        if encoded_path[0] & 0x20 != 0 {
+                            return (path == node_path.as_slice())
+                                .then(|| rlp_string(items[1]).to_vec())
+                        }
+                        path = path.strip_prefix(node_path.as_slice())?;
+                        items[1]
+                    }
+                    _ => panic!("invalid trie node"),
+                };
+                // nodes shorter than 32 bytes are embedded in their parent
+                let next = if child[0] >= alloy_rlp::EMPTY_LIST_CODE {
+                    child.to_vec()
+                } else {
+                    let hash = rlp_string(child);
+                    if hash.is_empty() {
+                        return None
+                    }
+                    self.witness
+                        .state
+                        .get(&B256::from_slice(hash))
+                        .expect("missing trie node")
+                        .to_vec()
+                };
+                node = next;
+            }
+        }
+
+        /// Returns the account and its storage root.
+        fn account(&self, address: Address) -> Option<(AccountInfo, B256)> {
+            let value = self.trie_value(self.state_root, keccak256(address))?;
+            let fields = rlp_list(&value);
+            let info = AccountInfo {
+                nonce: u64::decode(&mut &fields[0][..]).unwrap(),
+                balance: U256::decode(&mut &fields[1][..]).unwrap(),
+                code_hash: B256::decode(&mut &fields[3][..]).unwrap(),
+                code: None,
+            };
+            Some((info, B256::decode(&mut &fields[2][..]).unwrap()))
+        }
+    }
+
+    impl DatabaseRef for WitnessDatabase {
+        type Error = EthApiError;
+
+        fn basic_ref(&self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+            Ok(self.account(address).map(|(info, _)| info))
+        }
+
+        fn code_by_hash_ref(
+            &self,
+            code_hash: B256,
+        ) -> Result<revm::primitives::Bytecode, Self::Error> {
+            if code_hash == KECCAK_EMPTY {
+                return Ok(Default::default())
+            }
+            let code = self.witness.codes.get(&code_hash).expect("missing code");
+            assert_eq!(keccak256(code), code_hash);
+            Ok(revm::primitives::Bytecode::new_raw(code.clone()))
+        }
+
+        fn storage_ref(&self, address: Address, index: U256) -> Result<U256, Self::Error> {
+            let Some((_, storage_root)) = self.account(address) else { return Ok(U256::ZERO) };
+            let value = self.trie_value(storage_root, keccak256(B256::from(index.to_be_bytes())));
+            Ok(value.map(|value| U256::decode(&mut value.as_slice()).unwrap()).unwrap_or_default())
+        }
+
+        fn block_hash_ref(&self, number: U256) -> Result<B256, Self::Error> {
+            Ok(*self.block_hashes.get(&number.saturating_to()).expect("missing header"))
+        }
+    }
+
+    #[test]
+    fn execute_block_from_witness() {
+        let factory = create_test_provider_factory();
+        let (sender, contract, beneficiary) =
+            (Address::repeat_byte(1), Address::repeat_byte(2), Address::repeat_byte(3));
+        // stores the hash of block 1 in slot 0 and clears slot 1
+        let code = Bytes::from_static(&[
+            0x60, 0x01, 0x40, 0x60, 0x00, 0x55, 0x60, 0x00, 0x60, 0x01, 0x55, 0x00,
+        ]);
+        let code_hash = keccak256(&code);
+
+        let provider = factory.provider_rw().unwrap();
+        let tx = provider.tx_ref();
+        let accounts = [
+            (sender, Account { nonce: 0, balance: U256::from(1_000_000), bytecode_hash: None }),
+            (contract, Account { nonce: 1, balance: U256::ZERO, bytecode_hash: Some(code_hash) }),
+        ];
+        for (address, account) in accounts {
+            tx.put::<tables::PlainAccountState>(address, account).unwrap();
+            tx.put::<tables::HashedAccount>(keccak256(address), account).unwrap();
+        }
+        tx.put::<tables::Bytecodes>(code_hash, Bytecode::new_raw(code)).unwrap();
+        for (slot, value) in [(B256::with_last_byte(1), 5), (B256::with_last_byte(2), 7)] {
+            let value = U256::from(value);
+            tx.put::<tables::PlainStorageState>(contract, StorageEntry { key: slot, value })
+                .unwrap();
+            tx.put::<tables::HashedStorage>(
+                keccak256(contract),
+                StorageEntry { key: keccak256(slot), value },
+            )
+            .unwrap();
+        }
+        let (state_root, updates) = StateRoot::from_tx(tx).root_with_updates().unwrap();
+        updates.flush(tx).unwrap();
+
+        // the ancestors of block 4
+        let mut parent_hash = B256::ZERO;
+        let mut hashes = Vec::new();
+        for number in 0..4 {
+            let header = Header { number, parent_hash, state_root, ..Default::default() };
+            let header = header.seal_slow();
+            tx.put::<tables::CanonicalHeaders>(number, header.hash).unwrap();
+            tx.put::<tables::HeaderNumbers>(header.hash, number).unwrap();
+            tx.put::<tables::Headers>(number, header.header).unwrap();
+            parent_hash = header.hash;
+            hashes.push(header.hash);
+        }
+        provider.commit().unwrap();
+
+        let transaction = Transaction::Legacy(TxLegacy {
+            gas_limit: 100_000,
+            to: TransactionKind::Call(contract),
+            ..Default::default()
+        });
+        let transaction =
+            TransactionSigned::from_transaction_and_signature(transaction, Signature::default())
+                .with_signer(sender);
+        let mut env = Env::default();
+        env.block = BlockEnv {
+            number: U256::from(4),
+            coinbase: beneficiary,
+            gas_limit: U256::from(30_000_000),
+            ..Default::default()
+        };
+        let evm_config = EvmConfig::default();
+
+        let mut touched = BTreeMap::new();
+        touched.entry(beneficiary).or_default();
+        let (mut witness, _, lowest_block_hash) = execute_block_for_witness(
+            factory.latest().unwrap(),
+            &MAINNET,
+            env.clone(),
+            None,
+            touched,
+            vec![transaction.clone()],
+            &evm_config,
+        )
+        .unwrap();
+        assert_eq!(lowest_block_hash, Some(1));
+        witness.headers = witness_headers(&factory, 3, lowest_block_hash).unwrap();
+        assert_eq!(witness.headers.len(), 3);
+
+        let env = Env { tx: tx_env_with_recovered(&transaction), ..env };
+        let (expected, _) = transact(
+            CacheDB::new(StateProviderDatabase::new(factory.latest().unwrap())),
+            env.clone(),
+            &evm_config,
+        )
+        .unwrap();
+        let (res, _) =
+            transact(CacheDB::new(WitnessDatabase::new(witness, parent_hash)), env, &evm_config)
+                .unwrap();
+
+        assert!(res.result.is_success());
+        assert_eq!(res.result, expected.result);
+        assert_eq!(res.state, expected.state);
+        assert_eq!(
+            res.state[&contract].storage[&U256::ZERO].present_value,
+            U256::from_be_bytes(hashes[1].0)
+        );
+    }
+
+    #[test]
+    fn raw_receipt_encoding() {
+        let receipt = Receipt {
+            tx_type: TxType::EIP1559,
+            success: true,
+            cumulative_gas_used: 21_000,
+            logs: vec![],
+            #[cfg(feature = "optimism")]
+            deposit_nonce: None,
+            #[cfg(feature = "optimism")]
+            deposit_receipt_version: None,
+        };
+        let raw = encode_raw_receipt(receipt.clone());
+        assert_eq!(raw[0], 0x02);
+
+        // the network encoding wraps the typed receipt in a string header
+        let mut network = Vec::new();
+        receipt.clone().with_bloom().encode(&mut network);
+        let buf = &mut network.as_slice();
+        let header = alloy_rlp::Header::decode(buf).unwrap();
+        assert!(!header.list);
+        assert_eq!(*buf, &raw[..]);
+
+        let legacy = Receipt { tx_type: TxType::Legacy, ..receipt };
+        let mut expected = Vec::new();
+        legacy.clone().with_bloom().encode(&mut expected);
+        assert_eq!(encode_raw_receipt(legacy), Bytes::from(expected));
+    }
+
+    #[test]
+    fn parse_tracer_timeout() {
+        assert_eq!(parse_go_duration("300ms"), Some(Duration::from_millis(300)));
+        assert_eq!(parse_go_duration("1.5s"), Some(Duration::from_millis(1500)));
+        assert_eq!(parse_go_duration("1m30s"), Some(Duration::from_secs(90)));
+        assert_eq!(parse_go_duration("0"), Some(Duration::ZERO));
+        assert_eq!(parse_go_duration("10"), None);
+        assert_eq!(parse_go_duration("5 seconds"), None);
+        assert_eq!(parse_go_duration(""), None);
+    }
 }
@@ -4,22 +4,29 @@ use crate::{
     eth::{
         error::{ensure_success, EthApiError, EthResult, RevertError, RpcInvalidTransactionError},
         revm_utils::{
-            apply_state_overrides, build_call_evm_env, caller_gas_allowance,
-            cap_tx_gas_limit_with_caller_allowance, get_precompiles, inspect, prepare_call_env,
-            transact, EvmOverrides,
+            apply_block_overrides, apply_state_overrides, build_call_evm_env, caller_gas_allowance,
+            cap_tx_gas_limit_with_caller_allowance, inspect, prepare_call_env, transact,
+            EvmOverrides,
         },
         EthTransactions,
     },
     EthApi,
 };
 use reth_network_api::NetworkInfo;
-use reth_primitives::{revm::env::tx_env_with_recovered, BlockId, BlockNumberOrTag, Bytes, U256};
+use reth_primitives::{
+    revm::env::tx_env_with_recovered, BlockId, BlockNumberOrTag, Bytes, Header, U256, U64,
+};
 use reth_provider::{
     BlockReaderIdExt, ChainSpecProvider, EvmEnvProvider, StateProvider, StateProviderFactory,
 };
-use reth_revm::{access_list::AccessListInspector, database::StateProviderDatabase};
+use reth_revm::{access_list::AccessListInspector, database::StateProviderDatabase, EvmConfig};
 use reth_rpc_types::{
-    state::StateOverride, AccessListWithGasUsed, Bundle, CallRequest, EthCallResponse, StateContext,
+    simulate::{
+        SimulateBlock, SimulateCallResult, SimulateError, SimulatePayload, SimulatedBlock,
+        MAX_SIMULATE_BLOCKS,
+    },
+    state::StateOverride,
+    AccessListWithGasUsed, Bundle, CallRequest, EthCallResponse, Log, StateContext,
 };
 use reth_transaction_pool::TransactionPool;
 use revm::{
@@ -32,6 +39,8 @@ use tracing::trace;
 // Gas per transaction not creating a contract.
 const MIN_TRANSACTION_GAS: u64 = 21_000u64;
 const MIN_CREATE_GAS: u64 = 53_000u64;
+// Seconds between simulated blocks if the timestamp is not overridden.
+const SIMULATE_BLOCK_TIME: u64 = 12;
 
 impl<Provider, Pool, Network> EthApi<Provider, Pool, Network>
 where
@@ -45,13 +54,13 @@ where
         &self,
         request: CallRequest,
         at: BlockId,
-        state_override: Option<StateOverride>,
+        overrides: EvmOverrides,
     ) -> EthResult<U256> {
         let (cfg, block_env, at) = self.evm_env_at(at).await?;
 
         self.on_blocking_task(|this| async move {
             let state = this.state_at(at)?;
-            this.estimate_gas_with(cfg, block_env, request, state, state_override)
+            this.estimate_gas_with(cfg, block_env, request, state, overrides)
         })
         .await
     }
@@ -98,7 +107,7 @@ where
         )?;
 
         let Some(block) = block else { return Err(EthApiError::UnknownBlockNumber) };
-        let gas_limit = self.inner.gas_cap;
+        let gas_limit = self.gas_cap;
 
         // we're essentially replaying the transactions in the block here, hence we need the state
         // that points to the beginning of the block, which is the state at the parent block
@@ -112,6 +121,7 @@ where
             replay_block_txs = false;
         }
 
+        let evm_config = self.inner.evm_config;
         self.spawn_with_state_at_block(at.into(), move |state| {
             let mut results = Vec::with_capacity(transactions.len());
             let mut db = CacheDB::new(StateProviderDatabase::new(state));
@@ -123,7 +133,7 @@ where
                 for tx in transactions {
                     let tx = tx_env_with_recovered(&tx);
                     let env = Env { cfg: cfg.clone(), block: block_env.clone(), tx };
-                    let (res, _) = transact(&mut db, env)?;
+                    let (res, _) = transact(&mut db, env, &evm_config)?;
                     db.commit(res.state);
                 }
             }
@@ -144,7 +154,7 @@ where
                     &mut db,
                     overrides,
                 )?;
-                let (res, _) = transact(&mut db, env)?;
+                let (res, _) = transact(&mut db, env, &evm_config)?;
 
                 match ensure_success(res.result) {
                     Ok(output) => {
@@ -166,16 +176,207 @@ where
         .await
     }
 
+    /// Simulates the blocks of an `eth_simulateV1` request on top of the given block.
+    ///
+    /// Every simulated block is a child of the previous one and all calls are executed in order,
+    /// each on top of the state changes of the previous call. The hashes of the simulated blocks
+    /// are available to `BLOCKHASH` in the following blocks.
+    pub async fn simulate_v1(
+        &self,
+        payload: SimulatePayload,
+        block_number: Option<BlockId>,
+    ) -> EthResult<Vec<SimulatedBlock>> {
+        let SimulatePayload { block_state_calls, validation } = payload;
+        if block_state_calls.is_empty() {
+            return Err(EthApiError::InvalidParams(String::from("blockStateCalls are empty.")))
+        }
+        if block_state_calls.len() > MAX_SIMULATE_BLOCKS {
+            return Err(EthApiError::InvalidParams(format!(
+                "too many blocks, at most {MAX_SIMULATE_BLOCKS} blocks can be simulated"
+            )))
+        }
+
+        let at = block_number.unwrap_or(BlockId::Number(BlockNumberOrTag::Latest));
+        let (cfg, mut block_env, at) = self.evm_env_at(at).await?;
+        let gas_cap = self.gas_cap;
+        let evm_config = self.inner.evm_config;
+
+        self.spawn_with_state_at_block(at, move |state| {
+            let mut db = CacheDB::new(StateProviderDatabase::new(state));
+            let mut blocks = Vec::with_capacity(block_state_calls.len());
+
+            for SimulateBlock { block_overrides, state_overrides, calls } in block_state_calls {
+                let parent_number = block_env.number;
+                let parent_timestamp = block_env.timestamp;
+                block_env.number += U256::from(1);
+                block_env.timestamp += U256::from(SIMULATE_BLOCK_TIME);
+                if !validation {
+                    block_env.basefee = U256::ZERO;
+                }
+
+                if let Some(mut block_overrides) = block_overrides {
+                    if let Some(block_hashes) = block_overrides.block_hash.take() {
+                        db.block_hashes.extend(
+                            block_hashes.into_iter().map(|(num, hash)| (U256::from(num), hash)),
+                        )
+                    }
+                    apply_block_overrides(block_overrides, &mut block_env);
+                }
+                if block_env.number <= parent_number {
+                    return Err(EthApiError::InvalidParams(format!(
+                        "block number {} is not greater than the parent block number {}",
+                        block_env.number, parent_number
+                    )))
+                }
+                if block_env.timestamp <= parent_timestamp {
+                    return Err(EthApiError::InvalidParams(format!(
+                        "block timestamp {} is not greater than the parent block timestamp {}",
+                        block_env.timestamp, parent_timestamp
+                    )))
+                }
+
+                if let Some(state_overrides) = state_overrides {
+                    apply_state_overrides(state_overrides, &mut db)?;
+                }
+
+                // the previous simulated block or the block the simulation is executed on
+                let parent_hash = db.block_hash_ref(parent_number)?;
+
+                let block_gas_limit = block_env.gas_limit.saturating_to::<u64>();
+                let mut block_gas_used = 0u64;
+                let mut log_index = 0usize;
+                let mut results = Vec::with_capacity(calls.len());
+
+                for (transaction_index, call) in calls.into_iter().enumerate() {
+                    let remaining_gas = block_gas_limit.saturating_sub(block_gas_used);
+                    let mut env = prepare_call_env(
+                        cfg.clone(),
+                        block_env.clone(),
+                        call,
+                        gas_cap.min(remaining_gas),
+                        &mut db,
+                        EvmOverrides::default(),
+                    )?;
+                    if validation {
+                        env.cfg.disable_base_fee = false;
+                        env.cfg.disable_eip3607 = false;
+                    }
+
+                    let (res, env) = transact(&mut db, env, &evm_config)?;
+                    let gas_used = res.result.gas_used();
+                    block_gas_used = block_gas_used.saturating_add(gas_used);
+                    if validation && block_gas_used > block_gas_limit {
+                        return Err(EthApiError::InvalidParams(format!(
+                            "block gas limit {block_gas_limit} reached"
+                        )))
+                    }
+
+                    let result = match res.result {
+                        ExecutionResult::Success { logs, output, .. } => {
+                            let logs = logs
+                                .into_iter()
+                                .map(|log| {
+                                    let log = Log {
+                                        address: log.address,
+                                        topics: log.topics,
+                                        data: log.data,
+                                        block_hash: None,
+                                        block_number: Some(block_env.number),
+                                        transaction_hash: None,
+                                        transaction_index: Some(U256::from(transaction_index)),
+                                        log_index: Some(U256::from(log_index)),
+                                        removed: false,
+                                    };
+                                    log_index += 1;
+                                    log
+                                })
+                                .collect();
+                            SimulateCallResult {
+                                status: U64::from(1),
+                                return_data: output.into_data(),
+                                gas_used: U64::from(gas_used),
+                                logs,
+                                error: None,
+                            }
+                        }
+                        ExecutionResult::Revert { output, .. } => SimulateCallResult {
+                            status: U64::ZERO,
+                            return_data: output.clone(),
+                            gas_used: U64::from(gas_used),
+                            logs: Vec::new(),
+                            error: Some(SimulateError {
+                                code: 3,
+                                message: RevertError::new(output).to_string(),
+                            }),
+                        },
+                        ExecutionResult::Halt { reason, .. } => SimulateCallResult {
+                            status: U64::ZERO,
+                            return_data: Bytes::default(),
+                            gas_used: U64::from(gas_used),
+                            logs: Vec::new(),
+                            error: Some(SimulateError {
+                                code: -32015,
+                                message: RpcInvalidTransactionError::halt(reason, env.tx.gas_limit)
+                                    .to_string(),
+                            }),
+                        },
+                    };
+                    results.push(result);
+
+                    // the next call is executed on top of the changes of this call
+                    db.commit(res.state);
+                }
+
+                let header = Header {
+                    parent_hash,
+                    beneficiary: block_env.coinbase,
+                    difficulty: block_env.difficulty,
+                    number: block_env.number.saturating_to(),
+                    gas_limit: block_gas_limit,
+                    gas_used: block_gas_used,
+                    timestamp: block_env.timestamp.saturating_to(),
+                    mix_hash: block_env.prevrandao.unwrap_or_default(),
+                    base_fee_per_gas: Some(block_env.basefee.saturating_to()),
+                    ..Default::default()
+                };
+                let hash = header.hash_slow();
+                // the following blocks can access the hash of this block with BLOCKHASH
+                db.block_hashes.insert(block_env.number, hash);
+                for log in results.iter_mut().flat_map(|result| result.logs.iter_mut()) {
+                    log.block_hash = Some(hash);
+                }
+
+                blocks.push(SimulatedBlock {
+                    hash,
+                    parent_hash,
+                    number: block_env.number,
+                    timestamp: block_env.timestamp,
+                    gas_limit: block_env.gas_limit,
+                    gas_used: U256::from(block_gas_used),
+                    fee_recipient: block_env.coinbase,
+                    base_fee_per_gas: block_env.basefee,
+                    prev_randao: block_env.prevrandao,
+                    calls: results,
+                });
+            }
+
+            Ok(blocks)
+        })
+        .await
+    }
+
     /// Estimates the gas usage of the `request` with the state.
     ///
-    /// This will execute the [CallRequest] and find the best gas limit via binary search
+    /// This will execute the [CallRequest] and find the best gas limit via binary search.
+    ///
+    /// The given [EvmOverrides] are applied to the block env and the state before the estimation.
     pub fn estimate_gas_with<S>(
         &self,
         mut cfg: CfgEnv,
-        block: BlockEnv,
+        mut block: BlockEnv,
         request: CallRequest,
         state: S,
-        state_override: Option<StateOverride>,
+        overrides: EvmOverrides,
     ) -> EthResult<U256>
     where
         S: StateProvider,
@@ -189,6 +390,13 @@ where
         // <https://github.com/ethereum/go-ethereum/blob/ee8e83fa5f6cb261dad2ed0a7bbcde4930c41e6c/internal/ethapi/api.go#L985>
         cfg.disable_base_fee = true;
 
+        // apply block overrides before the gas limit of the block is used
+        let mut block_hashes = None;
+        if let Some(mut block_overrides) = overrides.block {
+            block_hashes = block_overrides.block_hash.take();
+            apply_block_overrides(*block_overrides, &mut block);
+        }
+
         // keep a copy of gas related request values
         let request_gas = request.gas;
         let request_gas_price = request.gas_price;
@@ -202,7 +410,13 @@ where
         let mut env = build_call_evm_env(cfg, block, request)?;
         let mut db = CacheDB::new(StateProviderDatabase::new(state));
 
-        if let Some(state_override) = state_override {
+        if let Some(block_hashes) = block_hashes {
+            // override block hashes
+            db.block_hashes
+                .extend(block_hashes.into_iter().map(|(num, hash)| (U256::from(num), hash)))
+        }
+
+        if let Some(state_override) = overrides.state {
             // apply state overrides
             apply_state_overrides(state_override, &mut db)?;
         }
@@ -243,7 +457,7 @@ where
         trace!(target: "rpc::eth::estimate", ?env, "Starting gas estimation");
 
         // transact with the highest __possible__ gas limit
-        let ethres = transact(&mut db, env.clone());
+        let ethres = transact(&mut db, env.clone(), &self.inner.evm_config);
 
         // Exceptional case: init used too much gas, we need to increase the gas limit and try
         // again
@@ -252,7 +466,7 @@ where
             // if price or limit was included in the request then we can execute the request
             // again with the block's gas limit to check if revert is gas related or not
             if request_gas.is_some() || request_gas_price.is_some() {
-                return Err(map_out_of_gas_err(env_gas_limit, env, &mut db))
+                return Err(map_out_of_gas_err(env_gas_limit, env, &mut db, &self.inner.evm_config))
             }
         }
 
@@ -270,7 +484,7 @@ where
                 // if price or limit was included in the request then we can execute the request
                 // again with the block's gas limit to check if revert is gas related or not
                 return if request_gas.is_some() || request_gas_price.is_some() {
-                    Err(map_out_of_gas_err(env_gas_limit, env, &mut db))
+                    Err(map_out_of_gas_err(env_gas_limit, env, &mut db, &self.inner.evm_config))
                 } else {
                     // the transaction did revert
                     Err(RpcInvalidTransactionError::Revert(RevertError::new(output)).into())
@@ -299,7 +513,7 @@ where
         while (highest_gas_limit - lowest_gas_limit) > 1 {
             let mut env = env.clone();
             env.tx.gas_limit = mid_gas_limit;
-            let ethres = transact(&mut db, env);
+            let ethres = transact(&mut db, env, &self.inner.evm_config);
 
             // Exceptional case: init used too much gas, we need to increase the gas limit and try
             // again
@@ -399,9 +613,9 @@ where
         // can consume the list since we're not using the request anymore
         let initial = request.access_list.take().unwrap_or_default();
 
-        let precompiles = get_precompiles(env.cfg.spec_id);
+        let precompiles = self.inner.evm_config.precompile_addresses(env.cfg.spec_id);
         let mut inspector = AccessListInspector::new(initial, from, to, precompiles);
-        let (result, env) = inspect(&mut db, env, &mut inspector)?;
+        let (result, env) = inspect(&mut db, env, &mut inspector, &self.inner.evm_config)?;
 
         match result.result {
             ExecutionResult::Halt { reason, .. } => Err(match reason {
@@ -418,7 +632,13 @@ where
 
         // calculate the gas used using the access list
         request.access_list = Some(access_list.clone());
-        let gas_used = self.estimate_gas_with(env.cfg, env.block, request, db.db.state(), None)?;
+        let gas_used = self.estimate_gas_with(
+            env.cfg,
+            env.block,
+            request,
+            db.db.state(),
+            EvmOverrides::default(),
+        )?;
 
         Ok(AccessListWithGasUsed { access_list, gas_used })
     }
@@ -431,13 +651,14 @@ fn map_out_of_gas_err<S>(
     env_gas_limit: U256,
     mut env: Env,
     mut db: &mut CacheDB<StateProviderDatabase<S>>,
+    evm_config: &EvmConfig,
 ) -> EthApiError
 where
     S: StateProvider,
 {
     let req_gas_limit = env.tx.gas_limit;
     env.tx.gas_limit = env_gas_limit.try_into().unwrap_or(u64::MAX);
-    let (res, _) = match transact(&mut db, env) {
+    let (res, _) = match transact(&mut db, env, evm_config) {
         Ok(res) => res,
         Err(err) => return err,
     };
@@ -454,3 +675,145 @@ where
         ExecutionResult::Halt { reason, .. } => RpcInvalidTransactionError::EvmHalt(reason).into(),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        eth::{
+            cache::EthStateCache, gas_oracle::GasPriceOracle, FeeHistoryCache,
+            FeeHistoryCacheConfig,
+        },
+        BlockingTaskPool,
+    };
+    use reth_primitives::{constants::ETHEREUM_BLOCK_GAS_LIMIT, Block, B256};
+    use reth_provider::test_utils::MockEthProvider;
+    use reth_transaction_pool::test_utils::{testing_pool, TestPool};
+    use serde_json::json;
+
+    // increments storage slot 0 and returns the new value
+    const COUNTER: &str = "0x6000546001018060005560005260206000f3";
+    // returns the hash of block 1
+    const BLOCKHASH_1: &str = "0x60014060005260206000f3";
+
+    fn build_test_eth_api() -> EthApi<MockEthProvider, TestPool, ()> {
+        let provider = MockEthProvider::default();
+        let genesis = Header::default();
+        let genesis_hash = genesis.hash_slow();
+        provider.add_header(genesis_hash, genesis.clone());
+        provider.add_block(genesis_hash, Block { header: genesis, ..Default::default() });
+
+        let cache = EthStateCache::spawn(provider.clone(), Default::default());
+        EthApi::new(
+            provider.clone(),
+            testing_pool(),
+            (),
+            cache.clone(),
+            GasPriceOracle::new(provider, Default::default(), cache.clone()),
+            ETHEREUM_BLOCK_GAS_LIMIT,
+            BlockingTaskPool::build().expect("failed to build tracing pool"),
+            FeeHistoryCache::new(cache, FeeHistoryCacheConfig::default()),
+        )
+    }
+
+    fn payload(payload: serde_json::Value) -> SimulatePayload {
+        serde_json::from_value(payload).unwrap()
+    }
+
+    fn return_word(result: &SimulateCallResult) -> U256 {
+        assert_eq!(result.status, U64::from(1), "call failed: {:?}", result.error);
+        U256::from_be_slice(&result.return_data)
+    }
+
+    #[tokio::test]
+    async fn simulate_carries_state_between_blocks() {
+        let eth_api = build_test_eth_api();
+        let counter = "0xc100000000000000000000000000000000000000";
+        let call = json!({ "to": counter });
+
+        let blocks = eth_api
+            .simulate_v1(
+                payload(json!({
+                    "blockStateCalls": [
+                        {
+                            "stateOverrides": { counter: { "code": COUNTER } },
+                            "calls": [call]
+                        },
+                        { "calls": [call, call] }
+                    ]
+                })),
+                None,
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(blocks.len(), 2);
+        assert_eq!(blocks[0].number, U256::from(1));
+        assert_eq!(blocks[1].number, U256::from(2));
+        assert_eq!(blocks[1].timestamp, blocks[0].timestamp + U256::from(SIMULATE_BLOCK_TIME));
+
+        let counts =
+            blocks.iter().flat_map(|block| block.calls.iter().map(return_word)).collect::<Vec<_>>();
+        assert_eq!(counts, vec![U256::from(1), U256::from(2), U256::from(3)]);
+    }
+
+    #[tokio::test]
+    async fn simulate_exposes_block_hashes() {
+        let eth_api = build_test_eth_api();
+        let contract = "0xc200000000000000000000000000000000000000";
+
+        let blocks = eth_api
+            .simulate_v1(
+                payload(json!({
+                    "blockStateCalls": [
+                        { "stateOverrides": { contract: { "code": BLOCKHASH_1 } } },
+                        { "calls": [{ "to": contract }] }
+                    ]
+                })),
+                None,
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(blocks[0].parent_hash, Header::default().hash_slow());
+        assert_eq!(blocks[1].parent_hash, blocks[0].hash);
+        assert_ne!(blocks[0].hash, B256::ZERO);
+        assert_eq!(return_word(&blocks[1].calls[0]), U256::from_be_bytes(blocks[0].hash.0));
+    }
+
+    #[tokio::test]
+    async fn simulate_validation() {
+        let eth_api = build_test_eth_api();
+        let request = |validation: bool| {
+            payload(json!({
+                "blockStateCalls": [{
+                    "blockOverrides": { "baseFee": "0xa" },
+                    "calls": [{ "to": "0xc300000000000000000000000000000000000000" }]
+                }],
+                "validation": validation
+            }))
+        };
+
+        // without validation the gas price may be below the base fee
+        let blocks = eth_api.simulate_v1(request(false), None).await.unwrap();
+        assert_eq!(blocks[0].calls[0].status, U64::from(1));
+        assert!(eth_api.simulate_v1(request(true), None).await.is_err());
+
+        // block numbers must increase
+        let res = eth_api
+            .simulate_v1(
+                payload(json!({
+                    "blockStateCalls": [
+                        { "blockOverrides": { "number": "0x5" } },
+                        { "blockOverrides": { "number": "0x5" } }
+                    ]
+                })),
+                None,
+            )
+            .await;
+        assert!(matches!(res, Err(EthApiError::InvalidParams(_))));
+
+        let res = eth_api.simulate_v1(payload(json!({ "blockStateCalls": [] })), None).await;
+        assert!(matches!(res, Err(EthApiError::InvalidParams(_))));
+    }
+}
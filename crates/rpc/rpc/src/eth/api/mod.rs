@@ -17,13 +17,16 @@ use reth_interfaces::RethResult;
 use reth_network_api::NetworkInfo;
 use reth_primitives::{
     revm_primitives::{BlockEnv, CfgEnv},
+    stage::StageId,
     Address, BlockId, BlockNumberOrTag, ChainInfo, SealedBlockWithSenders, B256, U256, U64,
 };
 
 use reth_provider::{
-    BlockReaderIdExt, ChainSpecProvider, EvmEnvProvider, StateProviderBox, StateProviderFactory,
+    BlockReaderIdExt, BundleStateProvider, ChainSpecProvider, EvmEnvProvider,
+    StageCheckpointReader, StateProviderBox, StateProviderFactory,
 };
-use reth_rpc_types::{SyncInfo, SyncStatus};
+use reth_revm::EvmConfig;
+use reth_rpc_types::{SyncInfo, SyncStage, SyncStatus};
 use reth_tasks::{TaskSpawner, TokioTaskExecutor};
 use reth_transaction_pool::TransactionPool;
 use std::{
@@ -85,6 +88,11 @@ pub trait EthApiSpec: EthTransactions + Send + Sync {
 pub struct EthApi<Provider, Pool, Network> {
     /// All nested fields bundled together.
     inner: Arc<EthApiInner<Provider, Pool, Network>>,
+    /// Maximum gas limit for `eth_call` and call tracing RPC methods.
+    ///
+    /// This is kept outside of the shared state, so that handles with different gas caps can be
+    /// served on different transports, see [EthApi::with_gas_cap].
+    gas_cap: u64,
 }
 
 impl<Provider, Pool, Network> EthApi<Provider, Pool, Network>
@@ -113,6 +121,7 @@ where
             Box::<TokioTaskExecutor>::default(),
             blocking_task_pool,
             fee_history_cache,
+            EvmConfig::default(),
         )
     }
 
@@ -128,6 +137,7 @@ where
         task_spawner: Box<dyn TaskSpawner>,
         blocking_task_pool: BlockingTaskPool,
         fee_history_cache: FeeHistoryCache,
+        evm_config: EvmConfig,
     ) -> Self {
         // get the block number of the latest block
         let latest_block = provider
@@ -144,17 +154,24 @@ where
             signers: Default::default(),
             eth_cache,
             gas_oracle,
-            gas_cap,
             starting_block: U256::from(latest_block),
             task_spawner,
             pending_block: Default::default(),
             blocking_task_pool,
             fee_history_cache,
+            evm_config,
             #[cfg(feature = "optimism")]
             http_client: reqwest::Client::new(),
         };
 
-        Self { inner: Arc::new(inner) }
+        Self { inner: Arc::new(inner), gas_cap }
+    }
+
+    /// Returns a handle that shares all state with this instance but uses the given gas limit cap
+    /// for `eth_call` and call tracing RPC methods.
+    pub fn with_gas_cap(mut self, gas_cap: impl Into<GasCap>) -> Self {
+        self.gas_cap = gas_cap.into().into();
+        self
     }
 
     /// Executes the future on a new blocking task.
@@ -189,7 +206,7 @@ where
 
     /// Returns the configured gas limit cap for `eth_call` and tracing related calls
     pub fn gas_cap(&self) -> u64 {
-        self.inner.gas_cap
+        self.gas_cap
     }
 
     /// Returns the inner `Provider`
@@ -222,11 +239,42 @@ where
 {
     /// Returns the state at the given [BlockId] enum.
     ///
+    /// If this is [BlockNumberOrTag::Pending] and the CL did not provide a pending block, this is
+    /// the state after the locally built pending block, if it is still up to date.
+    ///
     /// Note: if not [BlockNumberOrTag::Pending] then this will only return canonical state. See also <https://github.com/paradigmxyz/reth/issues/4515>
     pub fn state_at_block_id(&self, at: BlockId) -> EthResult<StateProviderBox> {
+        if at.is_pending() {
+            if let Some(state) = self.local_pending_state()? {
+                return Ok(state)
+            }
+        }
         Ok(self.provider().state_by_block_id(at)?)
     }
 
+    /// Returns the state after the locally built pending block, if it is still up to date and the
+    /// CL did not provide a pending block.
+    fn local_pending_state(&self) -> EthResult<Option<StateProviderBox>> {
+        let Ok(lock) = self.inner.pending_block.try_lock() else {
+            tracing::debug!(target: "rpc", "Pending block is being rebuilt, using provider state");
+            return Ok(None)
+        };
+        let Some(pending_block) = lock.as_ref() else { return Ok(None) };
+        if Instant::now() > pending_block.expires_at ||
+            self.provider().pending_block_num_hash()?.is_some()
+        {
+            return Ok(None)
+        }
+
+        let latest = self.provider().latest_header()?.ok_or(EthApiError::UnknownBlockNumber)?;
+        if latest.hash != pending_block.block.parent_hash {
+            return Ok(None)
+        }
+
+        let parent = self.state_at_hash(latest.hash)?;
+        Ok(Some(Box::new(BundleStateProvider::new(parent, pending_block.state_data()))))
+    }
+
     /// Returns the state at the given [BlockId] enum or the latest.
     ///
     /// Convenience function to interprets `None` as `BlockId::Number(BlockNumberOrTag::Latest)`
@@ -310,11 +358,15 @@ where
             let mut lock = this.inner.pending_block.lock().await;
             let now = Instant::now();
 
+            let pool_size = this.pool().pool_size();
+            let pool_pending = (pool_size.pending, pool_size.pending_size);
+
             // check if the block is still good
             if let Some(pending_block) = lock.as_ref() {
                 // this is guaranteed to be the `latest` header
                 if pending.block_env.number.to::<u64>() == pending_block.block.number &&
                     pending.origin.header().hash == pending_block.block.parent_hash &&
+                    pool_pending == pending_block.pool_pending &&
                     now <= pending_block.expires_at
                 {
                     return Ok(Some(pending_block.block.clone()))
@@ -327,17 +379,20 @@ where
             }
 
             // we rebuild the block
-            let pending_block = match pending.build_block(this.provider(), this.pool()) {
-                Ok(block) => block,
-                Err(err) => {
-                    tracing::debug!(target: "rpc", "Failed to build pending block: {:?}", err);
-                    return Ok(None)
-                }
-            };
+            let (pending_block, state) =
+                match pending.build_block(this.provider(), this.pool(), &this.inner.evm_config) {
+                    Ok(built) => built,
+                    Err(err) => {
+                        tracing::debug!(target: "rpc", "Failed to build pending block: {:?}", err);
+                        return Ok(None)
+                    }
+                };
 
             let now = Instant::now();
             *lock = Some(PendingBlock {
                 block: pending_block.clone(),
+                state: Arc::new(state),
+                pool_pending,
                 expires_at: now + Duration::from_secs(3),
             });
 
@@ -355,7 +410,7 @@ impl<Provider, Pool, Events> std::fmt::Debug for EthApi<Provider, Pool, Events>
 
 impl<Provider, Pool, Events> Clone for EthApi<Provider, Pool, Events> {
     fn clone(&self) -> Self {
-        Self { inner: Arc::clone(&self.inner) }
+        Self { inner: Arc::clone(&self.inner), gas_cap: self.gas_cap }
     }
 }
 
@@ -405,6 +460,7 @@ where
                 highest_block: current_block,
                 warp_chunks_amount: None,
                 warp_chunks_processed: None,
+                stages: None,
             })
         } else {
             SyncStatus::None
@@ -413,6 +469,45 @@ where
     }
 }
 
+impl<Provider, Pool, Network> EthApi<Provider, Pool, Network>
+where
+    Pool: TransactionPool + Clone + 'static,
+    Provider: BlockReaderIdExt
+        + ChainSpecProvider
+        + StateProviderFactory
+        + EvmEnvProvider
+        + StageCheckpointReader
+        + 'static,
+    Network: NetworkInfo + 'static,
+{
+    /// Returns the [SyncStatus] of the network, extended with the progress of the sync stages.
+    ///
+    /// The highest block is the target of the headers stage, and the checkpoints of all stages are
+    /// included as the per-stage progress.
+    pub fn sync_status_with_stages(&self) -> RethResult<SyncStatus> {
+        let mut status = EthApiSpec::sync_status(self)?;
+        if let SyncStatus::Info(info) = &mut status {
+            let mut stages = Vec::with_capacity(StageId::ALL.len());
+            for stage_id in StageId::ALL {
+                let Some(checkpoint) = self.provider().get_stage_checkpoint(stage_id)? else {
+                    continue
+                };
+                // the headers stage knows the block it is syncing to
+                if let Some(headers) = checkpoint.headers_stage_checkpoint() {
+                    info.highest_block = info.highest_block.max(U256::from(headers.block_range.to));
+                }
+                info.highest_block = info.highest_block.max(U256::from(checkpoint.block_number));
+                stages.push(SyncStage {
+                    name: stage_id.to_string(),
+                    block: U64::from(checkpoint.block_number),
+                });
+            }
+            info.stages = Some(stages);
+        }
+        Ok(status)
+    }
+}
+
 /// The default gas limit for eth_call and adjacent calls.
 ///
 /// This is different from the default to regular 30M block gas limit
@@ -456,8 +551,6 @@ struct EthApiInner<Provider, Pool, Network> {
     eth_cache: EthStateCache,
     /// The async gas oracle frontend for gas price suggestions
     gas_oracle: GasPriceOracle<Provider>,
-    /// Maximum gas limit for `eth_call` and call tracing RPC methods.
-    gas_cap: u64,
     /// The block number at which the node started
     starting_block: U256,
     /// The type that can spawn tasks which would otherwise block.
@@ -468,6 +561,8 @@ struct EthApiInner<Provider, Pool, Network> {
     blocking_task_pool: BlockingTaskPool,
     /// Cache for block fees history
     fee_history_cache: FeeHistoryCache,
+    /// The EVM configuration, including any additional precompiles.
+    evm_config: EvmConfig,
     /// An http client for communicating with sequencers.
     #[cfg(feature = "optimism")]
     http_client: reqwest::Client,
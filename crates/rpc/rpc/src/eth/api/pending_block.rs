@@ -8,17 +8,21 @@ use reth_primitives::{
     revm_primitives::{
         BlockEnv, CfgEnv, EVMError, Env, InvalidTransaction, ResultAndState, SpecId,
     },
-    Block, BlockId, BlockNumberOrTag, ChainSpec, Header, IntoRecoveredTransaction, Receipt,
-    Receipts, SealedBlockWithSenders, SealedHeader, B256, EMPTY_OMMER_ROOT_HASH, U256,
+    Block, BlockHash, BlockId, BlockNumHash, BlockNumber, BlockNumberOrTag, ChainSpec, Header,
+    IntoRecoveredTransaction, Receipt, Receipts, SealedBlockWithSenders, SealedHeader, B256,
+    EMPTY_OMMER_ROOT_HASH, U256,
+};
+use reth_provider::{
+    BundleStateDataProvider, BundleStateWithReceipts, ChainSpecProvider, StateProviderFactory,
 };
-use reth_provider::{BundleStateWithReceipts, ChainSpecProvider, StateProviderFactory};
 use reth_revm::{
     database::StateProviderDatabase,
     state_change::{apply_beacon_root_contract_call, post_block_withdrawals_balance_increments},
+    EvmConfig,
 };
 use reth_transaction_pool::TransactionPool;
 use revm::{db::states::bundle_state::BundleRetention, Database, DatabaseCommit, State};
-use std::time::Instant;
+use std::{sync::Arc, time::Instant};
 
 /// Configured [BlockEnv] and [CfgEnv] for a pending block
 #[derive(Debug, Clone)]
@@ -34,6 +38,8 @@ pub(crate) struct PendingBlockEnv {
 impl PendingBlockEnv {
     /// Builds a pending block using the given client and pool.
     ///
+    /// Returns the block and the state changes of its transactions on top of its parent.
+    ///
     /// If the origin is the actual pending block, the block is built with withdrawals.
     ///
     /// After Cancun, if the origin is the actual pending block, the block includes the EIP-4788 pre
@@ -42,7 +48,8 @@ impl PendingBlockEnv {
         self,
         client: &Client,
         pool: &Pool,
-    ) -> EthResult<SealedBlockWithSenders>
+        evm_config: &EvmConfig,
+    ) -> EthResult<(SealedBlockWithSenders, BundleStateWithReceipts)>
     where
         Client: StateProviderFactory + ChainSpecProvider,
         Pool: TransactionPool,
@@ -133,7 +140,7 @@ impl PendingBlockEnv {
             let mut evm = revm::EVM::with_env(env);
             evm.database(&mut db);
 
-            let ResultAndState { result, state } = match evm.transact() {
+            let ResultAndState { result, state } = match evm_config.transact(&mut evm) {
                 Ok(res) => res,
                 Err(err) => {
                     match err {
@@ -257,7 +264,7 @@ impl PendingBlockEnv {
 
         // seal the block
         let block = Block { header, body: executed_txs, ommers: vec![], withdrawals };
-        Ok(SealedBlockWithSenders { block: block.seal_slow(), senders })
+        Ok((SealedBlockWithSenders { block: block.seal_slow(), senders }, bundle))
     }
 }
 
@@ -357,6 +364,42 @@ impl PendingBlockEnvOrigin {
 pub(crate) struct PendingBlock {
     /// The cached pending block
     pub(crate) block: SealedBlockWithSenders,
+    /// The state changes of the pending block on top of its parent
+    pub(crate) state: Arc<BundleStateWithReceipts>,
+    /// The number and size of the pending transactions of the pool when the block was built
+    pub(crate) pool_pending: (usize, usize),
     /// Timestamp when the pending block is considered outdated
     pub(crate) expires_at: Instant,
 }
+
+impl PendingBlock {
+    /// Returns the state of the pending block, to be layered on top of the state of its parent.
+    pub(crate) fn state_data(&self) -> PendingBlockState {
+        PendingBlockState {
+            state: self.state.clone(),
+            parent: BlockNumHash::new(self.block.number - 1, self.block.parent_hash),
+        }
+    }
+}
+
+/// The state after the locally built pending block, see [PendingBlock::state_data].
+#[derive(Debug, Clone)]
+pub(crate) struct PendingBlockState {
+    state: Arc<BundleStateWithReceipts>,
+    parent: BlockNumHash,
+}
+
+impl BundleStateDataProvider for PendingBlockState {
+    fn state(&self) -> &BundleStateWithReceipts {
+        &self.state
+    }
+
+    fn block_hash(&self, block_number: BlockNumber) -> Option<BlockHash> {
+        // all ancestors are canonical and served by the state provider of the parent
+        (block_number == self.parent.number).then_some(self.parent.hash)
+    }
+
+    fn canonical_fork(&self) -> BlockNumHash {
+        self.parent
+    }
+}
@@ -5,7 +5,6 @@ use super::EthApiSpec;
 use crate::{
     eth::{
         api::{EthApi, EthTransactions},
-        error::EthApiError,
         revm_utils::EvmOverrides,
     },
     result::{internal_rpc_err, ToRpcResult},
@@ -18,11 +17,13 @@ use reth_primitives::{
 };
 use reth_provider::{
     BlockIdReader, BlockReader, BlockReaderIdExt, ChainSpecProvider, EvmEnvProvider,
-    HeaderProvider, StateProviderFactory,
+    HeaderProvider, StageCheckpointReader, StateProviderFactory,
 };
 use reth_rpc_api::EthApiServer;
 use reth_rpc_types::{
-    state::StateOverride, AccessListWithGasUsed, BlockOverrides, Bundle, CallRequest,
+    simulate::{SimulatePayload, SimulatedBlock},
+    state::StateOverride,
+    AccessListWithGasUsed, Account, BlockOverrides, Bundle, CallRequest,
     EIP1186AccountProofResponse, EthCallResponse, FeeHistory, Index, RichBlock, StateContext,
     SyncStatus, TransactionReceipt, TransactionRequest, Work,
 };
@@ -42,6 +43,7 @@ where
         + HeaderProvider
         + StateProviderFactory
         + EvmEnvProvider
+        + StageCheckpointReader
         + 'static,
     Network: NetworkInfo + Send + Sync + 'static,
 {
@@ -54,7 +56,7 @@ where
     /// Handler for: `eth_syncing`
     fn syncing(&self) -> Result<SyncStatus> {
         trace!(target: "rpc::eth", "Serving eth_syncing");
-        EthApiSpec::sync_status(self).to_rpc_result()
+        self.sync_status_with_stages().to_rpc_result()
     }
 
     /// Handler for: `eth_coinbase`
@@ -249,6 +251,16 @@ where
         Ok(EthApi::call_many(self, bundle, state_context, state_override).await?)
     }
 
+    /// Handler for: `eth_simulateV1`
+    async fn simulate_v1(
+        &self,
+        payload: SimulatePayload,
+        block_number: Option<BlockId>,
+    ) -> Result<Vec<SimulatedBlock>> {
+        trace!(target: "rpc::eth", ?block_number, "Serving eth_simulateV1");
+        Ok(EthApi::simulate_v1(self, payload, block_number).await?)
+    }
+
     /// Handler for: `eth_createAccessList`
     async fn create_access_list(
         &self,
@@ -267,13 +279,14 @@ where
         request: CallRequest,
         block_number: Option<BlockId>,
         state_override: Option<StateOverride>,
+        block_overrides: Option<Box<BlockOverrides>>,
     ) -> Result<U256> {
-        trace!(target: "rpc::eth", ?request, ?block_number, "Serving eth_estimateGas");
+        trace!(target: "rpc::eth", ?request, ?block_number, ?state_override, ?block_overrides, "Serving eth_estimateGas");
         Ok(self
             .estimate_gas_at(
                 request,
                 block_number.unwrap_or(BlockId::Number(BlockNumberOrTag::Latest)),
-                state_override,
+                EvmOverrides::new(state_override, block_overrides),
             )
             .await?)
     }
@@ -379,14 +392,13 @@ where
         block_number: Option<BlockId>,
     ) -> Result<EIP1186AccountProofResponse> {
         trace!(target: "rpc::eth", ?address, ?keys, ?block_number, "Serving eth_getProof");
-        let res = EthApi::get_proof(self, address, keys, block_number).await;
+        Ok(EthApi::get_proof(self, address, keys, block_number).await?)
+    }
 
-        Ok(res.map_err(|e| match e {
-            EthApiError::InvalidBlockRange => {
-                internal_rpc_err("eth_getProof is unimplemented for historical blocks")
-            }
-            _ => e.into(),
-        })?)
+    /// Handler for: `eth_getAccount`
+    async fn get_account(&self, address: Address, block: BlockId) -> Result<Account> {
+        trace!(target: "rpc::eth", ?address, ?block, "Serving eth_getAccount");
+        Ok(EthApi::get_account(self, address, block).await?)
     }
 }
 
@@ -408,7 +420,8 @@ mod tests {
     };
     use reth_provider::{
         test_utils::{MockEthProvider, NoopProvider},
-        BlockReader, BlockReaderIdExt, ChainSpecProvider, EvmEnvProvider, StateProviderFactory,
+        BlockReader, BlockReaderIdExt, ChainSpecProvider, EvmEnvProvider, StageCheckpointReader,
+        StateProviderFactory,
     };
     use reth_rpc_api::EthApiServer;
     use reth_rpc_types::FeeHistory;
@@ -419,6 +432,7 @@ mod tests {
             + BlockReader
             + ChainSpecProvider
             + EvmEnvProvider
+            + StageCheckpointReader
             + StateProviderFactory
             + Unpin
             + Clone
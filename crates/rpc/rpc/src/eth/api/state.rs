@@ -5,12 +5,13 @@ use crate::{
     EthApi,
 };
 use reth_primitives::{
-    serde_helper::JsonStorageKey, Address, BlockId, BlockNumberOrTag, Bytes, B256, U256,
+    constants::EMPTY_ROOT_HASH, serde_helper::JsonStorageKey, Address, BlockId, BlockNumberOrTag,
+    Bytes, B256, KECCAK_EMPTY, U256, U64,
 };
 use reth_provider::{
     BlockReaderIdExt, ChainSpecProvider, EvmEnvProvider, StateProvider, StateProviderFactory,
 };
-use reth_rpc_types::EIP1186AccountProofResponse;
+use reth_rpc_types::{Account, EIP1186AccountProofResponse};
 use reth_rpc_types_compat::proof::from_primitive_account_proof;
 use reth_transaction_pool::{PoolTransaction, TransactionPool};
 
@@ -89,24 +90,8 @@ where
         keys: Vec<JsonStorageKey>,
         block_id: Option<BlockId>,
     ) -> EthResult<EIP1186AccountProofResponse> {
-        let chain_info = self.provider().chain_info()?;
         let block_id = block_id.unwrap_or(BlockId::Number(BlockNumberOrTag::Latest));
 
-        // if we are trying to create a proof for the latest block, but have a BlockId as input
-        // that is not BlockNumberOrTag::Latest, then we need to figure out whether or not the
-        // BlockId corresponds to the latest block
-        let is_latest_block = match block_id {
-            BlockId::Number(BlockNumberOrTag::Number(num)) => num == chain_info.best_number,
-            BlockId::Hash(hash) => hash == chain_info.best_hash.into(),
-            BlockId::Number(BlockNumberOrTag::Latest) => true,
-            _ => false,
-        };
-
-        // TODO: remove when HistoricalStateProviderRef::proof is implemented
-        if !is_latest_block {
-            return Err(EthApiError::InvalidBlockRange)
-        }
-
         let this = self.clone();
         self.inner
             .blocking_task_pool
@@ -119,6 +104,39 @@ where
             .await
             .map_err(|_| EthApiError::InternalBlockingTaskError)?
     }
+
+    /// Returns the balance, nonce, code hash and storage root of the account at the given block.
+    ///
+    /// Accounts that don't exist are returned as empty accounts.
+    pub(crate) async fn get_account(
+        &self,
+        address: Address,
+        block_id: BlockId,
+    ) -> EthResult<Account> {
+        let this = self.clone();
+        self.inner
+            .blocking_task_pool
+            .spawn(move || {
+                let state = this.state_at_block_id(block_id)?;
+                let Some(account) = state.basic_account(address)? else {
+                    return Ok(Account {
+                        code_hash: KECCAK_EMPTY,
+                        storage_root: EMPTY_ROOT_HASH,
+                        ..Default::default()
+                    })
+                };
+                // the storage root of the account is computed from the storage trie
+                let storage_root = state.proof(address, &[])?.storage_root;
+                Ok(Account {
+                    balance: account.balance,
+                    nonce: U64::from(account.nonce),
+                    code_hash: account.get_bytecode_hash(),
+                    storage_root,
+                })
+            })
+            .await
+            .map_err(|_| EthApiError::InternalBlockingTaskError)?
+    }
 }
 
 #[cfg(test)]
@@ -126,15 +144,25 @@ mod tests {
     use super::*;
     use crate::{
         eth::{
-            cache::EthStateCache, gas_oracle::GasPriceOracle, FeeHistoryCache,
-            FeeHistoryCacheConfig,
+            api::pending_block::PendingBlock, cache::EthStateCache, gas_oracle::GasPriceOracle,
+            FeeHistoryCache, FeeHistoryCacheConfig,
         },
         BlockingTaskPool,
     };
-    use reth_primitives::{constants::ETHEREUM_BLOCK_GAS_LIMIT, StorageKey, StorageValue};
-    use reth_provider::test_utils::{ExtendedAccount, MockEthProvider, NoopProvider};
+    use reth_primitives::{
+        constants::ETHEREUM_BLOCK_GAS_LIMIT, Block, Header, Receipts, SealedBlockWithSenders,
+        StorageKey, StorageValue,
+    };
+    use reth_provider::{
+        test_utils::{ExtendedAccount, MockEthProvider, NoopProvider},
+        BundleStateWithReceipts,
+    };
     use reth_transaction_pool::test_utils::testing_pool;
-    use std::collections::HashMap;
+    use std::{
+        collections::HashMap,
+        sync::Arc,
+        time::{Duration, Instant},
+    };
 
     #[tokio::test]
     async fn test_storage() {
@@ -180,4 +208,72 @@ mod tests {
         let storage = eth_api.storage_at(address, storage_key.into(), None).unwrap();
         assert_eq!(storage, storage_value.to_be_bytes());
     }
+
+    #[tokio::test]
+    async fn test_pending_state_from_local_pending_block() {
+        let mock_provider = MockEthProvider::default();
+        let parent = Header { number: 1, ..Default::default() };
+        let parent_hash = parent.hash_slow();
+        mock_provider.add_header(parent_hash, parent);
+        let address = Address::random();
+        mock_provider.add_account(address, ExtendedAccount::new(0, U256::from(1)));
+
+        let cache = EthStateCache::spawn(mock_provider.clone(), Default::default());
+        let eth_api = EthApi::new(
+            mock_provider.clone(),
+            testing_pool(),
+            (),
+            cache.clone(),
+            GasPriceOracle::new(mock_provider.clone(), Default::default(), cache.clone()),
+            ETHEREUM_BLOCK_GAS_LIMIT,
+            BlockingTaskPool::build().expect("failed to build tracing pool"),
+            FeeHistoryCache::new(cache.clone(), FeeHistoryCacheConfig::default()),
+        );
+        let pending = Some(BlockNumberOrTag::Pending.into());
+
+        // without a local pending block, the pending state of the provider is used
+        assert_eq!(eth_api.balance(address, pending).unwrap(), U256::from(1));
+
+        let block = Block {
+            header: Header { number: 2, parent_hash, ..Default::default() },
+            ..Default::default()
+        };
+        let state = BundleStateWithReceipts::new_init(
+            HashMap::from([(
+                address,
+                (
+                    Some(reth_primitives::Account { balance: U256::from(1), ..Default::default() }),
+                    Some(reth_primitives::Account {
+                        balance: U256::from(100),
+                        ..Default::default()
+                    }),
+                    HashMap::new(),
+                ),
+            )]),
+            HashMap::new(),
+            vec![],
+            Receipts::new(),
+            2,
+        );
+        *eth_api.inner.pending_block.lock().await = Some(PendingBlock {
+            block: SealedBlockWithSenders { block: block.seal_slow(), senders: vec![] },
+            state: Arc::new(state),
+            pool_pending: (0, 0),
+            expires_at: Instant::now() + Duration::from_secs(60),
+        });
+
+        // the pending state is the state after the local pending block
+        assert_eq!(eth_api.balance(address, pending).unwrap(), U256::from(100));
+        assert_eq!(eth_api.balance(address, None).unwrap(), U256::from(1));
+
+        // while the pending block is being rebuilt, the pending state of the provider is used
+        {
+            let _rebuilding = eth_api.inner.pending_block.lock().await;
+            assert_eq!(eth_api.balance(address, pending).unwrap(), U256::from(1));
+        }
+
+        // the local pending block is outdated once its parent is no longer the latest block
+        mock_provider.add_header(B256::random(), Header { number: 2, ..Default::default() });
+        assert_eq!(eth_api.balance(address, pending).unwrap(), U256::from(1));
+    }
 }
@@ -29,6 +29,7 @@ use reth_provider::{
 use reth_revm::{
     database::StateProviderDatabase,
     tracing::{TracingInspector, TracingInspectorConfig},
+    EvmConfig,
 };
 use reth_rpc_types::{
     CallRequest, Index, Log, Transaction, TransactionInfo, TransactionReceipt, TransactionRequest,
@@ -67,6 +68,9 @@ pub trait EthTransactions: Send + Sync {
     /// Returns default gas limit to use for `eth_call` and tracing RPC methods.
     fn call_gas_limit(&self) -> u64;
 
+    /// Returns the EVM configuration to use for `eth_call` and tracing RPC methods.
+    fn evm_config(&self) -> &EvmConfig;
+
     /// Returns the state at the given [BlockId]
     fn state_at(&self, at: BlockId) -> EthResult<StateProviderBox>;
 
@@ -315,7 +319,11 @@ where
     Network: NetworkInfo + Send + Sync + 'static,
 {
     fn call_gas_limit(&self) -> u64 {
-        self.inner.gas_cap
+        self.gas_cap
+    }
+
+    fn evm_config(&self) -> &EvmConfig {
+        &self.inner.evm_config
     }
 
     fn state_at(&self, at: BlockId) -> EthResult<StateProviderBox> {
@@ -345,6 +353,10 @@ where
     async fn evm_env_at(&self, at: BlockId) -> EthResult<(CfgEnv, BlockEnv, BlockId)> {
         if at.is_pending() {
             let PendingBlockEnv { cfg, block_env, origin } = self.pending_block_env_and_cfg()?;
+            if !origin.is_actual_pending() && self.local_pending_block().await?.is_some() {
+                // execute on top of the locally built pending block
+                return Ok((cfg, block_env, BlockNumberOrTag::Pending.into()))
+            }
             Ok((cfg, block_env, origin.state_block_id()))
         } else {
             //  Use cached values if there is no pending block
@@ -553,7 +565,7 @@ where
                     max_fee_per_blob_gas: None,
                 },
                 BlockId::Number(BlockNumberOrTag::Pending),
-                None,
+                EvmOverrides::default(),
             )
             .await?;
         let gas_limit = estimated_gas;
@@ -643,8 +655,11 @@ where
         at: BlockId,
         overrides: EvmOverrides,
     ) -> EthResult<(ResultAndState, Env)> {
-        self.spawn_with_call_at(request, at, overrides, move |mut db, env| transact(&mut db, env))
-            .await
+        let evm_config = *self.evm_config();
+        self.spawn_with_call_at(request, at, overrides, move |mut db, env| {
+            transact(&mut db, env, &evm_config)
+        })
+        .await
     }
 
     async fn spawn_inspect_call_at<I>(
@@ -657,8 +672,11 @@ where
     where
         I: Inspector<StateCacheDB> + Send + 'static,
     {
-        self.spawn_with_call_at(request, at, overrides, move |db, env| inspect(db, env, inspector))
-            .await
+        let evm_config = *self.evm_config();
+        self.spawn_with_call_at(request, at, overrides, move |db, env| {
+            inspect(db, env, inspector, &evm_config)
+        })
+        .await
     }
 
     fn trace_at<F, R>(
@@ -675,7 +693,7 @@ where
             let db = CacheDB::new(StateProviderDatabase::new(state));
 
             let mut inspector = TracingInspector::new(config);
-            let (res, _) = inspect(db, env, &mut inspector)?;
+            let (res, _) = inspect(db, env, &mut inspector, self.evm_config())?;
 
             f(inspector, res)
         })
@@ -692,10 +710,11 @@ where
         F: FnOnce(TracingInspector, ResultAndState, StateCacheDB) -> EthResult<R> + Send + 'static,
         R: Send + 'static,
     {
+        let evm_config = *self.evm_config();
         self.spawn_with_state_at_block(at, move |state| {
             let db = CacheDB::new(StateProviderDatabase::new(state));
             let mut inspector = TracingInspector::new(config);
-            let (res, _, db) = inspect_and_return_db(db, env, &mut inspector)?;
+            let (res, _, db) = inspect_and_return_db(db, env, &mut inspector, &evm_config)?;
 
             f(inspector, res, db)
         })
@@ -744,17 +763,25 @@ where
         // block the transaction is included in
         let parent_block = block.parent_hash;
         let block_txs = block.body;
+        let evm_config = *self.evm_config();
 
         self.spawn_with_state_at_block(parent_block.into(), move |state| {
             let mut db = CacheDB::new(StateProviderDatabase::new(state));
 
             // replay all transactions prior to the targeted transaction
-            replay_transactions_until(&mut db, cfg.clone(), block_env.clone(), block_txs, tx.hash)?;
+            replay_transactions_until(
+                &mut db,
+                cfg.clone(),
+                block_env.clone(),
+                block_txs,
+                tx.hash,
+                &evm_config,
+            )?;
 
             let env = Env { cfg, block: block_env, tx: tx_env_with_recovered(&tx) };
 
             let mut inspector = TracingInspector::new(config);
-            let (res, _, db) = inspect_and_return_db(db, env, &mut inspector)?;
+            let (res, _, db) = inspect_and_return_db(db, env, &mut inspector, &evm_config)?;
             f(tx_info, inspector, res, db)
         })
         .await
@@ -847,7 +874,7 @@ where
                 let env = Env { cfg: cfg.clone(), block: block_env.clone(), tx };
 
                 let mut inspector = TracingInspector::new(config);
-                let (res, _) = inspect(&mut db, env, &mut inspector)?;
+                let (res, _) = inspect(&mut db, env, &mut inspector, this.evm_config())?;
                 let ResultAndState { result, state } = res;
                 results.push(f(tx_info, inspector, result, &state, &db)?);
 
@@ -13,11 +13,16 @@ use jsonrpsee::core::RpcResult;
 use reth_primitives::{
     keccak256,
     revm_primitives::db::{DatabaseCommit, DatabaseRef},
-    U256,
+    Bytes, B256, U256,
 };
 use reth_revm::database::StateProviderDatabase;
-use reth_rpc_api::EthCallBundleApiServer;
-use reth_rpc_types::{EthCallBundle, EthCallBundleResponse, EthCallBundleTransactionResult};
+use reth_rpc_api::{EthBundleApiServer, EthCallBundleApiServer};
+use reth_rpc_types::{
+    CancelBundleRequest, CancelPrivateTransactionRequest, EthBundleHash, EthCallBundle,
+    EthCallBundleResponse, EthCallBundleTransactionResult, EthSendBundle,
+    PrivateTransactionRequest,
+};
+use reth_transaction_pool::{BundleError, MevBundle, TransactionPool};
 use revm::{
     db::CacheDB,
     primitives::{Env, ResultAndState, TxEnv},
@@ -25,19 +30,79 @@ use revm::{
 use std::sync::Arc;
 
 /// `Eth` bundle implementation.
-pub struct EthBundle<Eth> {
+pub struct EthBundle<Eth, Pool> {
     /// All nested fields bundled together.
-    inner: Arc<EthBundleInner<Eth>>,
+    inner: Arc<EthBundleInner<Eth, Pool>>,
 }
 
-impl<Eth> EthBundle<Eth> {
+impl<Eth, Pool> EthBundle<Eth, Pool> {
     /// Create a new `EthBundle` instance.
-    pub fn new(eth_api: Eth, blocking_task_guard: BlockingTaskGuard) -> Self {
-        Self { inner: Arc::new(EthBundleInner { eth_api, blocking_task_guard }) }
+    pub fn new(eth_api: Eth, pool: Pool, blocking_task_guard: BlockingTaskGuard) -> Self {
+        Self { inner: Arc::new(EthBundleInner { eth_api, pool, blocking_task_guard }) }
+    }
+}
+
+impl<Eth, Pool> EthBundle<Eth, Pool>
+where
+    Eth: EthTransactions + 'static,
+    Pool: TransactionPool + 'static,
+{
+    /// Submits a bundle to the bundle pool, so that it can be included at the top of the block
+    /// with the bundle's block number by the payload builder.
+    pub fn send_bundle(&self, bundle: EthSendBundle) -> EthResult<EthBundleHash> {
+        let EthSendBundle {
+            txs,
+            block_number,
+            min_timestamp,
+            max_timestamp,
+            reverting_tx_hashes,
+            replacement_uuid,
+        } = bundle;
+        if txs.is_empty() {
+            return Err(EthApiError::InvalidParams(
+                EthBundleError::EmptyBundleTransactions.to_string(),
+            ))
+        }
+        if block_number.to::<u64>() == 0 {
+            return Err(EthApiError::InvalidParams(
+                EthBundleError::BundleMissingBlockNumber.to_string(),
+            ))
+        }
+
+        let transactions = txs
+            .into_iter()
+            .map(|tx| recover_raw_transaction(tx).map(|tx| tx.into_ecrecovered_transaction()))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let bundle = MevBundle::new(transactions, block_number.to())
+            .with_min_timestamp(min_timestamp)
+            .with_max_timestamp(max_timestamp)
+            .with_reverting_tx_hashes(reverting_tx_hashes)
+            .with_replacement_uuid(replacement_uuid);
+
+        let bundle_hash = self.inner.pool.add_bundle(bundle).map_err(|err| match err {
+            BundleError::Unsupported => EthApiError::Unsupported("bundles are not supported"),
+            err => EthApiError::InvalidParams(err.to_string()),
+        })?;
+        Ok(EthBundleHash { bundle_hash })
+    }
+
+    /// Removes a bundle from the bundle pool.
+    ///
+    /// The given value is either the hash of the bundle or its replacement uuid.
+    pub fn cancel_bundle(&self, request: CancelBundleRequest) -> EthResult<()> {
+        let CancelBundleRequest { bundle_hash } = request;
+        if let Ok(hash) = bundle_hash.parse::<B256>() {
+            if self.inner.pool.remove_bundle(hash).is_some() {
+                return Ok(())
+            }
+        }
+        self.inner.pool.remove_bundle_by_replacement_uuid(&bundle_hash);
+        Ok(())
     }
 }
 
-impl<Eth> EthBundle<Eth>
+impl<Eth, Pool> EthBundle<Eth, Pool>
 where
     Eth: EthTransactions + 'static,
 {
@@ -73,6 +138,7 @@ where
         let state_block_number = block_env.number;
         // use the block number of the request
         block_env.number = U256::from(block_number);
+        let evm_config = *self.inner.eth_api.evm_config();
 
         self.inner
             .eth_api
@@ -104,7 +170,7 @@ where
                         .effective_tip_per_gas(basefee)
                         .ok_or_else(|| RpcInvalidTransactionError::FeeCapTooLow)?;
                     tx.try_fill_tx_env(&mut evm.env.tx)?;
-                    let ResultAndState { result, state } = evm.transact()?;
+                    let ResultAndState { result, state } = evm_config.transact(&mut evm)?;
 
                     let gas_used = result.gas_used();
                     total_gas_used += gas_used;
@@ -178,32 +244,72 @@ where
 }
 
 #[async_trait::async_trait]
-impl<Eth> EthCallBundleApiServer for EthBundle<Eth>
+impl<Eth, Pool> EthCallBundleApiServer for EthBundle<Eth, Pool>
 where
     Eth: EthTransactions + 'static,
+    Pool: Send + Sync + 'static,
 {
     async fn call_bundle(&self, request: EthCallBundle) -> RpcResult<EthCallBundleResponse> {
         Ok(EthBundle::call_bundle(self, request).await?)
     }
 }
 
+#[async_trait::async_trait]
+impl<Eth, Pool> EthBundleApiServer for EthBundle<Eth, Pool>
+where
+    Eth: EthTransactions + 'static,
+    Pool: TransactionPool + 'static,
+{
+    async fn send_bundle(&self, bundle: EthSendBundle) -> RpcResult<EthBundleHash> {
+        Ok(EthBundle::send_bundle(self, bundle)?)
+    }
+
+    async fn call_bundle(&self, request: EthCallBundle) -> RpcResult<EthCallBundleResponse> {
+        Ok(EthBundle::call_bundle(self, request).await?)
+    }
+
+    async fn cancel_bundle(&self, request: CancelBundleRequest) -> RpcResult<()> {
+        Ok(EthBundle::cancel_bundle(self, request)?)
+    }
+
+    async fn send_private_transaction(
+        &self,
+        _request: PrivateTransactionRequest,
+    ) -> RpcResult<B256> {
+        Err(EthApiError::Unsupported("eth_sendPrivateTransaction is not supported").into())
+    }
+
+    async fn send_private_raw_transaction(&self, _bytes: Bytes) -> RpcResult<B256> {
+        Err(EthApiError::Unsupported("eth_sendPrivateRawTransaction is not supported").into())
+    }
+
+    async fn cancel_private_transaction(
+        &self,
+        _request: CancelPrivateTransactionRequest,
+    ) -> RpcResult<bool> {
+        Err(EthApiError::Unsupported("eth_cancelPrivateTransaction is not supported").into())
+    }
+}
+
 /// Container type for  `EthBundle` internals
 #[derive(Debug)]
-struct EthBundleInner<Eth> {
+struct EthBundleInner<Eth, Pool> {
     /// Access to commonly used code of the `eth` namespace
     eth_api: Eth,
+    /// The pool that stores submitted bundles
+    pool: Pool,
     // restrict the number of concurrent tracing calls.
     #[allow(dead_code)]
     blocking_task_guard: BlockingTaskGuard,
 }
 
-impl<Eth> std::fmt::Debug for EthBundle<Eth> {
+impl<Eth, Pool> std::fmt::Debug for EthBundle<Eth, Pool> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("EthBundle").finish_non_exhaustive()
     }
 }
 
-impl<Eth> Clone for EthBundle<Eth> {
+impl<Eth, Pool> Clone for EthBundle<Eth, Pool> {
     fn clone(&self) -> Self {
         Self { inner: Arc::clone(&self.inner) }
     }
@@ -24,6 +24,9 @@ pub const DEFAULT_ENV_CACHE_MAX_LEN: u32 = 1000;
 /// Default number of concurrent database requests.
 pub const DEFAULT_CONCURRENT_DB_REQUESTS: usize = 512;
 
+/// Default cache size for the trace cache: 0 blocks, which disables the cache.
+pub const DEFAULT_TRACE_CACHE_MAX_LEN: u32 = 0;
+
 /// Settings for the [EthStateCache](crate::eth::cache::EthStateCache).
 #[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -44,6 +47,12 @@ pub struct EthStateCacheConfig {
     ///
     /// Default is 512.
     pub max_concurrent_db_requests: usize,
+    /// Max number of blocks with cached tracing results, see
+    /// [TraceCache](crate::eth::cache::TraceCache).
+    ///
+    /// Default is 0, which disables the trace cache.
+    #[serde(default)]
+    pub max_traced_blocks: u32,
 }
 
 impl Default for EthStateCacheConfig {
@@ -53,6 +62,7 @@ impl Default for EthStateCacheConfig {
             max_receipts: DEFAULT_RECEIPT_CACHE_MAX_LEN,
             max_envs: DEFAULT_ENV_CACHE_MAX_LEN,
             max_concurrent_db_requests: DEFAULT_CONCURRENT_DB_REQUESTS,
+            max_traced_blocks: DEFAULT_TRACE_CACHE_MAX_LEN,
         }
     }
 }
@@ -32,6 +32,9 @@ mod metrics;
 mod multi_consumer;
 pub use multi_consumer::MultiConsumerLruCache;
 
+mod trace;
+pub use trace::{trace_cache_reorg_task, TraceCache};
+
 /// The type that can send the response to a requested [Block]
 type BlockTransactionsResponseSender =
     oneshot::Sender<ProviderResult<Option<Vec<TransactionSigned>>>>;
@@ -115,8 +118,13 @@ impl EthStateCache {
         Provider: StateProviderFactory + BlockReader + EvmEnvProvider + Clone + Unpin + 'static,
         Tasks: TaskSpawner + Clone + 'static,
     {
-        let EthStateCacheConfig { max_blocks, max_receipts, max_envs, max_concurrent_db_requests } =
-            config;
+        let EthStateCacheConfig {
+            max_blocks,
+            max_receipts,
+            max_envs,
+            max_concurrent_db_requests,
+            ..
+        } = config;
         let (this, service) = Self::create(
             provider,
             executor.clone(),
@@ -0,0 +1,196 @@
+//! Cache for the results of tracing calls.
+
+use futures::{Stream, StreamExt};
+use parking_lot::Mutex;
+use reth_primitives::B256;
+use reth_provider::CanonStateNotification;
+use reth_rpc_types::trace::{
+    geth::{GethDebugTracingOptions, GethTrace},
+    parity::LocalizedTransactionTrace,
+};
+use schnellru::{ByLength, LruMap};
+use std::sync::Arc;
+
+/// The maximum number of `debug_traceTransaction` results cached per block.
+///
+/// Every combination of transaction and tracing options is a separate entry, so without a limit
+/// a single block could grow the cache without bound.
+pub const MAX_TRANSACTION_TRACES_PER_BLOCK: u32 = 512;
+
+/// The cached tracing results of a single block.
+#[derive(Debug)]
+struct BlockTraces {
+    /// The result of `trace_block`.
+    block_traces: Option<Vec<LocalizedTransactionTrace>>,
+    /// The most recently used results of `debug_traceTransaction`, by transaction hash and
+    /// serialized tracing options.
+    transaction_traces: LruMap<(B256, String), GethTrace, ByLength>,
+}
+
+impl Default for BlockTraces {
+    fn default() -> Self {
+        Self {
+            block_traces: None,
+            transaction_traces: LruMap::new(ByLength::new(MAX_TRANSACTION_TRACES_PER_BLOCK)),
+        }
+    }
+}
+
+/// A LRU cache for the results of `debug_traceTransaction` and `trace_block`.
+///
+/// Results are cached per block hash, so the results of a block that was reorged out are never
+/// returned for the block that replaced it. The entries of reverted blocks are removed by
+/// [trace_cache_reorg_task].
+///
+/// A cache with a capacity of 0 blocks is disabled and caches nothing.
+#[derive(Debug, Clone, Default)]
+pub struct TraceCache {
+    blocks: Option<Arc<Mutex<LruMap<B256, BlockTraces, ByLength>>>>,
+}
+
+impl TraceCache {
+    /// Creates a new cache for the tracing results of up to `max_blocks` blocks.
+    pub fn new(max_blocks: u32) -> Self {
+        let blocks =
+            (max_blocks > 0).then(|| Arc::new(Mutex::new(LruMap::new(ByLength::new(max_blocks)))));
+        Self { blocks }
+    }
+
+    /// Returns true if the cache is enabled.
+    pub fn is_enabled(&self) -> bool {
+        self.blocks.is_some()
+    }
+
+    /// Returns the cached `debug_traceTransaction` result of the transaction in the given block.
+    pub fn get_transaction_trace(
+        &self,
+        block_hash: B256,
+        tx_hash: B256,
+        opts: &GethDebugTracingOptions,
+    ) -> Option<GethTrace> {
+        let blocks = self.blocks.as_ref()?;
+        let key = (tx_hash, serde_json::to_string(opts).ok()?);
+        blocks.lock().get(&block_hash)?.transaction_traces.get(&key).cloned()
+    }
+
+    /// Caches the `debug_traceTransaction` result of the transaction in the given block.
+    ///
+    /// At most [MAX_TRANSACTION_TRACES_PER_BLOCK] results are kept per block, the least recently
+    /// used result is evicted first.
+    pub fn insert_transaction_trace(
+        &self,
+        block_hash: B256,
+        tx_hash: B256,
+        opts: &GethDebugTracingOptions,
+        trace: GethTrace,
+    ) {
+        let Some(blocks) = self.blocks.as_ref() else { return };
+        let Ok(opts) = serde_json::to_string(opts) else { return };
+        if let Some(block) = blocks.lock().get_or_insert(block_hash, BlockTraces::default) {
+            block.transaction_traces.insert((tx_hash, opts), trace);
+        }
+    }
+
+    /// Returns the cached `trace_block` result of the given block.
+    pub fn get_block_traces(&self, block_hash: B256) -> Option<Vec<LocalizedTransactionTrace>> {
+        self.blocks.as_ref()?.lock().get(&block_hash)?.block_traces.clone()
+    }
+
+    /// Caches the `trace_block` result of the given block.
+    pub fn insert_block_traces(&self, block_hash: B256, traces: Vec<LocalizedTransactionTrace>) {
+        let Some(blocks) = self.blocks.as_ref() else { return };
+        if let Some(block) = blocks.lock().get_or_insert(block_hash, BlockTraces::default) {
+            block.block_traces = Some(traces);
+        }
+    }
+
+    /// Removes the cached results of the given blocks.
+    pub fn remove_blocks(&self, block_hashes: impl IntoIterator<Item = B256>) {
+        let Some(blocks) = self.blocks.as_ref() else { return };
+        let mut blocks = blocks.lock();
+        for block_hash in block_hashes {
+            blocks.remove(&block_hash);
+        }
+    }
+}
+
+/// Awaits for canonical state notifications and removes the cached tracing results of the
+/// reverted blocks.
+pub async fn trace_cache_reorg_task<St>(trace_cache: TraceCache, mut events: St)
+where
+    St: Stream<Item = CanonStateNotification> + Unpin + 'static,
+{
+    while let Some(event) = events.next().await {
+        if let Some(reverted) = event.reverted() {
+            trace_cache.remove_blocks(reverted.blocks().values().map(|block| block.hash()));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_rpc_types::trace::geth::{GethDebugBuiltInTracerType, GethDebugTracerType, NoopFrame};
+
+    #[test]
+    fn cache_by_block_and_options() {
+        let cache = TraceCache::new(2);
+        let (block_hash, tx_hash) = (B256::random(), B256::random());
+        let noop = GethDebugTracingOptions {
+            tracer: Some(GethDebugTracerType::BuiltInTracer(
+                GethDebugBuiltInTracerType::NoopTracer,
+            )),
+            ..Default::default()
+        };
+
+        cache.insert_transaction_trace(
+            block_hash,
+            tx_hash,
+            &noop,
+            GethTrace::NoopTracer(NoopFrame::default()),
+        );
+        cache.insert_block_traces(block_hash, Vec::new());
+
+        assert!(cache.get_transaction_trace(block_hash, tx_hash, &noop).is_some());
+        assert!(cache
+            .get_transaction_trace(block_hash, tx_hash, &GethDebugTracingOptions::default())
+            .is_none());
+        assert!(cache.get_transaction_trace(B256::random(), tx_hash, &noop).is_none());
+        assert_eq!(cache.get_block_traces(block_hash), Some(Vec::new()));
+
+        cache.remove_blocks([block_hash]);
+        assert!(cache.get_transaction_trace(block_hash, tx_hash, &noop).is_none());
+        assert!(cache.get_block_traces(block_hash).is_none());
+    }
+
+    #[test]
+    fn bounded_transaction_traces_per_block() {
+        let cache = TraceCache::new(1);
+        let block_hash = B256::random();
+        let opts = GethDebugTracingOptions::default();
+        let tx_hashes =
+            (0..=MAX_TRANSACTION_TRACES_PER_BLOCK).map(|_| B256::random()).collect::<Vec<_>>();
+
+        for tx_hash in &tx_hashes {
+            cache.insert_transaction_trace(
+                block_hash,
+                *tx_hash,
+                &opts,
+                GethTrace::NoopTracer(NoopFrame::default()),
+            );
+        }
+
+        // the first result was evicted
+        assert!(cache.get_transaction_trace(block_hash, tx_hashes[0], &opts).is_none());
+        assert!(cache.get_transaction_trace(block_hash, tx_hashes[1], &opts).is_some());
+    }
+
+    #[test]
+    fn disabled_cache() {
+        let cache = TraceCache::new(0);
+        let block_hash = B256::random();
+        cache.insert_block_traces(block_hash, Vec::new());
+        assert!(!cache.is_enabled());
+        assert!(cache.get_block_traces(block_hash).is_none());
+    }
+}
@@ -7,7 +7,7 @@ use jsonrpsee::{
     types::{error::CALL_EXECUTION_FAILED_CODE, ErrorObject},
 };
 use reth_interfaces::RethError;
-use reth_primitives::{revm_primitives::InvalidHeader, Address, Bytes, U256};
+use reth_primitives::{revm_primitives::InvalidHeader, Address, BlockNumber, Bytes, U256};
 use reth_revm::tracing::js::JsInspectorError;
 use reth_rpc_types::{error::EthRpcErrorCode, BlockError, CallInputError};
 use reth_transaction_pool::error::{
@@ -20,6 +20,10 @@ use std::time::Duration;
 /// Result alias
 pub type EthResult<T> = Result<T, EthApiError>;
 
+/// Error code returned when the requested history has been pruned, see also
+/// <https://eips.ethereum.org/EIPS/eip-4444>.
+pub const PRUNED_HISTORY_UNAVAILABLE_CODE: i32 = 4444;
+
 /// Errors that can occur when interacting with the `eth_` namespace
 #[derive(Debug, thiserror::Error)]
 pub enum EthApiError {
@@ -42,6 +46,9 @@ pub enum EthApiError {
     /// finalized, <https://github.com/ethereum/execution-apis/blob/6d17705a875e52c26826124c2a8a15ed542aeca2/src/schemas/block.yaml#L109>
     #[error("unknown block")]
     UnknownSafeOrFinalizedBlock,
+    /// Thrown when the requested historical data has been pruned by a full node.
+    #[error("pruned history unavailable: state at block #{0} is pruned")]
+    PrunedHistoryUnavailable(BlockNumber),
     /// Thrown when an unknown block or transaction index is encountered
     #[error("unknown block or tx index")]
     UnknownBlockOrTxIndex,
@@ -100,6 +107,9 @@ pub enum EthApiError {
     /// Error thrown when a (tracing) call exceeds the configured timeout
     #[error("execution aborted (timeout = {0:?})")]
     ExecutionTimedOut(Duration),
+    /// Error thrown when the steps recorded by a tracer exceed the memory limit
+    #[error("execution aborted (tracer memory limit = {0} bytes)")]
+    TracerMemoryLimitExceeded(usize),
     /// Internal Error thrown by the javascript tracer
     #[error("{0}")]
     InternalJsTracerError(String),
@@ -157,11 +167,15 @@ impl From<EthApiError> for ErrorObject<'static> {
             EthApiError::UnknownSafeOrFinalizedBlock => {
                 rpc_error_with_code(EthRpcErrorCode::UnknownBlock.code(), error.to_string())
             }
+            err @ EthApiError::PrunedHistoryUnavailable(_) => {
+                rpc_error_with_code(PRUNED_HISTORY_UNAVAILABLE_CODE, err.to_string())
+            }
             EthApiError::Unsupported(msg) => internal_rpc_err(msg),
             EthApiError::InternalJsTracerError(msg) => internal_rpc_err(msg),
             EthApiError::InvalidParams(msg) => invalid_params_rpc_err(msg),
             EthApiError::InvalidRewardPercentiles => internal_rpc_err(error.to_string()),
-            err @ EthApiError::ExecutionTimedOut(_) => {
+            err @ EthApiError::ExecutionTimedOut(_) |
+            err @ EthApiError::TracerMemoryLimitExceeded(_) => {
                 rpc_error_with_code(CALL_EXECUTION_FAILED_CODE, err.to_string())
             }
             err @ EthApiError::InternalBlockingTaskError => internal_rpc_err(err.to_string()),
@@ -217,6 +231,9 @@ impl From<reth_interfaces::provider::ProviderError> for EthApiError {
             ProviderError::FinalizedBlockNotFound | ProviderError::SafeBlockNotFound => {
                 EthApiError::UnknownSafeOrFinalizedBlock
             }
+            ProviderError::StateAtBlockPruned(block_number) => {
+                EthApiError::PrunedHistoryUnavailable(block_number)
+            }
             err => EthApiError::Internal(err.into()),
         }
     }
@@ -714,4 +731,14 @@ mod tests {
         let err = EthApiError::ExecutionTimedOut(Duration::from_secs(10));
         assert_eq!(err.to_string(), "execution aborted (timeout = 10s)");
     }
+
+    #[test]
+    fn pruned_state_error() {
+        let err =
+            EthApiError::from(reth_interfaces::provider::ProviderError::StateAtBlockPruned(1));
+        assert!(matches!(err, EthApiError::PrunedHistoryUnavailable(1)));
+
+        let err: ErrorObject<'static> = err.into();
+        assert_eq!(err.code(), PRUNED_HISTORY_UNAVAILABLE_CODE);
+    }
 }
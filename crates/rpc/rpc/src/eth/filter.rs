@@ -1,7 +1,7 @@
 use super::cache::EthStateCache;
 use crate::{
     eth::{
-        error::EthApiError,
+        error::{EthApiError, PRUNED_HISTORY_UNAVAILABLE_CODE},
         logs_utils::{self, append_matching_block_logs},
     },
     result::{rpc_error_with_code, ToRpcResult},
@@ -11,17 +11,21 @@ use core::fmt;
 
 use async_trait::async_trait;
 use jsonrpsee::{core::RpcResult, server::IdProvider};
-use reth_primitives::{IntoRecoveredTransaction, TxHash};
-use reth_provider::{BlockIdReader, BlockReader, EvmEnvProvider, ProviderError};
+use reth_primitives::{IntoRecoveredTransaction, PruneSegment, TxHash};
+use reth_provider::{
+    BlockIdReader, BlockReader, EvmEnvProvider, LogIndexReader, ProviderError,
+    PruneCheckpointReader,
+};
 use reth_rpc_api::EthFilterApiServer;
 use reth_rpc_types::{
-    BlockNumHash, Filter, FilterBlockOption, FilterChanges, FilterId, FilteredParams, Log,
-    PendingTransactionFilterKind,
+    BlockNumHash, Filter, FilterBlockOption, FilterChanges, FilterId, FilterSet, FilteredParams,
+    Log, PendingTransactionFilterKind, ValueOrArray,
 };
 use reth_tasks::TaskSpawner;
 use reth_transaction_pool::{NewSubpoolTransactionStream, PoolTransaction, TransactionPool};
 use std::{
-    collections::HashMap,
+    collections::{BTreeSet, HashMap},
+    hash::Hash,
     iter::StepBy,
     ops::RangeInclusive,
     sync::Arc,
@@ -124,7 +128,12 @@ where
 
 impl<Provider, Pool> EthFilter<Provider, Pool>
 where
-    Provider: BlockReader + BlockIdReader + EvmEnvProvider + 'static,
+    Provider: BlockReader
+        + BlockIdReader
+        + EvmEnvProvider
+        + LogIndexReader
+        + PruneCheckpointReader
+        + 'static,
     Pool: TransactionPool + 'static,
     <Pool as TransactionPool>::Transaction: 'static,
 {
@@ -223,7 +232,12 @@ where
 #[async_trait]
 impl<Provider, Pool> EthFilterApiServer for EthFilter<Provider, Pool>
 where
-    Provider: BlockReader + BlockIdReader + EvmEnvProvider + 'static,
+    Provider: BlockReader
+        + BlockIdReader
+        + EvmEnvProvider
+        + LogIndexReader
+        + PruneCheckpointReader
+        + 'static,
     Pool: TransactionPool + 'static,
 {
     /// Handler for `eth_newFilter`
@@ -342,7 +356,12 @@ struct EthFilterInner<Provider, Pool> {
 
 impl<Provider, Pool> EthFilterInner<Provider, Pool>
 where
-    Provider: BlockReader + BlockIdReader + EvmEnvProvider + 'static,
+    Provider: BlockReader
+        + BlockIdReader
+        + EvmEnvProvider
+        + LogIndexReader
+        + PruneCheckpointReader
+        + 'static,
     Pool: TransactionPool + 'static,
 {
     /// Returns logs matching given filter object.
@@ -408,6 +427,7 @@ where
     /// Returns an error if:
     ///  - underlying database error
     ///  - amount of matches exceeds configured limit
+    ///  - receipts in the range are pruned
     async fn get_logs_in_block_range(
         &self,
         filter: &Filter,
@@ -419,9 +439,34 @@ where
         if to_block - from_block > self.max_blocks_per_filter {
             return Err(FilterError::QueryExceedsMaxBlocks(self.max_blocks_per_filter))
         }
+        self.ensure_receipts_available(filter, from_block)?;
 
         let mut all_logs = Vec::new();
         let filter_params = FilteredParams::new(Some(filter.clone()));
+        let is_multi_block_range = from_block != to_block;
+
+        // blocks that are covered by the log index only need to be checked if the index has an
+        // entry for them
+        let mut from_block = from_block;
+        if let Some((last_indexed, blocks)) = self.indexed_blocks(filter, from_block, to_block)? {
+            for block_number in blocks {
+                let block_hash = self
+                    .provider
+                    .block_hash(block_number)?
+                    .ok_or(ProviderError::BlockNotFound(block_number.into()))?;
+                self.append_block_logs(
+                    &mut all_logs,
+                    &filter_params,
+                    BlockNumHash::new(block_number, block_hash),
+                    is_multi_block_range,
+                )
+                .await?;
+            }
+            from_block = last_indexed + 1;
+        }
+        if from_block > to_block {
+            return Ok(all_logs)
+        }
 
         // derive bloom filters from filter input
         let address_filter = FilteredParams::address_filter(&filter.address);
@@ -449,31 +494,127 @@ where
                             .ok_or(ProviderError::BlockNotFound(header.number.into()))?,
                     };
 
-                    if let Some(receipts) = self.eth_cache.get_receipts(block_hash).await? {
-                        append_matching_block_logs(
-                            &mut all_logs,
-                            &self.provider,
-                            &filter_params,
-                            BlockNumHash::new(header.number, block_hash),
-                            &receipts,
-                            false,
-                        )?;
-
-                        // size check but only if range is multiple blocks, so we always return all
-                        // logs of a single block
-                        let is_multi_block_range = from_block != to_block;
-                        if is_multi_block_range && all_logs.len() > self.max_logs_per_response {
-                            return Err(FilterError::QueryExceedsMaxResults(
-                                self.max_logs_per_response,
-                            ))
-                        }
-                    }
+                    self.append_block_logs(
+                        &mut all_logs,
+                        &filter_params,
+                        BlockNumHash::new(header.number, block_hash),
+                        is_multi_block_range,
+                    )
+                    .await?;
                 }
             }
         }
 
         Ok(all_logs)
     }
+
+    /// Returns an error if receipts in the range starting at `from_block` are pruned, since the
+    /// logs in them would be missing from the result.
+    ///
+    /// With [PruneSegment::ContractLogs], the receipts with logs of the retained addresses are
+    /// kept, so only filters that don't restrict the addresses are rejected.
+    fn ensure_receipts_available(
+        &self,
+        filter: &Filter,
+        from_block: u64,
+    ) -> Result<(), FilterError> {
+        let mut segments = vec![PruneSegment::Receipts];
+        if filter_set_values(&filter.address).is_none() {
+            segments.push(PruneSegment::ContractLogs);
+        }
+        for segment in segments {
+            let pruned = self
+                .provider
+                .get_prune_checkpoint(segment)?
+                .and_then(|checkpoint| checkpoint.block_number);
+            if let Some(pruned) = pruned.filter(|pruned| from_block <= *pruned) {
+                return Err(FilterError::ReceiptsPruned(pruned))
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the blocks in the range that are covered by the log index and may contain logs
+    /// matching the filter, together with the last block of the range covered by the index.
+    ///
+    /// Returns `None` if the log index is not maintained, doesn't cover the start of the range or
+    /// the filter doesn't restrict the addresses or topics.
+    fn indexed_blocks(
+        &self,
+        filter: &Filter,
+        from_block: u64,
+        to_block: u64,
+    ) -> Result<Option<(u64, BTreeSet<u64>)>, FilterError> {
+        let Some(checkpoint) = self.provider.log_index_checkpoint()? else { return Ok(None) };
+        if checkpoint < from_block {
+            return Ok(None)
+        }
+        let range = from_block..=to_block.min(checkpoint);
+
+        // the blocks matching all of the address and topic sets
+        let mut blocks: Option<BTreeSet<u64>> = None;
+        let mut intersect = |matching: BTreeSet<u64>| {
+            blocks = Some(match blocks.take() {
+                Some(blocks) => blocks.intersection(&matching).copied().collect(),
+                None => matching,
+            });
+        };
+
+        if let Some(addresses) = filter_set_values(&filter.address) {
+            let mut matching = BTreeSet::new();
+            for address in addresses {
+                matching.extend(self.provider.blocks_with_log_address(address, range.clone())?);
+            }
+            intersect(matching);
+        }
+        for topics in filter.topics.iter() {
+            if let Some(topics) = filter_set_values(topics) {
+                let mut matching = BTreeSet::new();
+                for topic in topics {
+                    matching.extend(self.provider.blocks_with_log_topic(topic, range.clone())?);
+                }
+                intersect(matching);
+            }
+        }
+
+        Ok(blocks.map(|blocks| (*range.end(), blocks)))
+    }
+
+    /// Appends the logs of the block that match the filter.
+    ///
+    /// Returns an error if the logs exceed the configured limit and the filter spans multiple
+    /// blocks, so all logs of a single block are always returned.
+    async fn append_block_logs(
+        &self,
+        all_logs: &mut Vec<Log>,
+        filter_params: &FilteredParams,
+        block: BlockNumHash,
+        is_multi_block_range: bool,
+    ) -> Result<(), FilterError> {
+        if let Some(receipts) = self.eth_cache.get_receipts(block.hash).await? {
+            append_matching_block_logs(
+                all_logs,
+                &self.provider,
+                filter_params,
+                block,
+                &receipts,
+                false,
+            )?;
+
+            if is_multi_block_range && all_logs.len() > self.max_logs_per_response {
+                return Err(FilterError::QueryExceedsMaxResults(self.max_logs_per_response))
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Returns the values of the filter set, or `None` if it matches any value.
+fn filter_set_values<T: Clone + Eq + Hash>(set: &FilterSet<T>) -> Option<Vec<T>> {
+    match set.to_value_or_array()? {
+        ValueOrArray::Value(value) => Some(vec![value]),
+        ValueOrArray::Array(values) => Some(values),
+    }
 }
 
 /// Config for the filter
@@ -648,6 +789,8 @@ pub enum FilterError {
     QueryExceedsMaxBlocks(u64),
     #[error("query exceeds max results {0}")]
     QueryExceedsMaxResults(usize),
+    #[error("pruned history unavailable: receipts up to block #{0} are pruned")]
+    ReceiptsPruned(u64),
     #[error(transparent)]
     EthAPIError(#[from] EthApiError),
     /// Error thrown when a spawned task failed to deliver a response.
@@ -673,6 +816,9 @@ impl From<FilterError> for jsonrpsee::types::error::ErrorObject<'static> {
             err @ FilterError::QueryExceedsMaxResults(_) => {
                 rpc_error_with_code(jsonrpsee::types::error::INVALID_PARAMS_CODE, err.to_string())
             }
+            err @ FilterError::ReceiptsPruned(_) => {
+                rpc_error_with_code(PRUNED_HISTORY_UNAVAILABLE_CODE, err.to_string())
+            }
         }
     }
 }
@@ -50,6 +50,9 @@ pub struct GasPriceOracleConfig {
     pub max_block_history: u64,
 
     /// The default gas price to use if there are no blocks to use
+    ///
+    /// This is also the price that is suggested for blocks without transactions until a populated
+    /// block is sampled. Defaults to 1 gwei.
     pub default: Option<U256>,
 
     /// The maximum gas price to use for the estimate
@@ -108,8 +111,12 @@ where
 
         // this is the number of blocks that we will cache the values for
         let cached_values = (oracle_config.blocks * 5).max(oracle_config.max_block_history as u32);
+        let mut last_price = GasPriceOracleResult::default();
+        if let Some(price) = oracle_config.default {
+            last_price.price = price;
+        }
         let inner = Mutex::new(GasPriceOracleInner {
-            last_price: Default::default(),
+            last_price,
             lowest_effective_tip_cache: EffectiveTipLruCache(LruMap::new(ByLength::new(
                 cached_values,
             ))),
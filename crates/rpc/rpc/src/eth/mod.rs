@@ -11,6 +11,7 @@ mod logs_utils;
 mod pubsub;
 pub mod revm_utils;
 mod signer;
+pub(crate) mod tracing_limits;
 pub(crate) mod utils;
 
 pub use api::{
@@ -304,8 +304,9 @@ where
     /// Returns a stream that yields all logs that match the given filter.
     fn log_stream(&self, filter: FilteredParams) -> impl Stream<Item = Log> {
         BroadcastStream::new(self.chain_events.subscribe_to_canonical_state())
-            .map(move |canon_state| {
-                canon_state.expect("new block subscription never ends; qed").block_receipts()
+            .filter_map(|canon_state| {
+                // a lagging subscriber misses the skipped notifications instead of failing
+                futures::future::ready(canon_state.ok().map(|state| state.block_receipts()))
             })
             .flat_map(futures::stream::iter)
             .flat_map(move |(block_receipts, removed)| {
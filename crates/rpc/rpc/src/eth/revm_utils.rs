@@ -5,14 +5,14 @@ use reth_primitives::{
     revm::env::{fill_tx_env, fill_tx_env_with_recovered},
     Address, TransactionSigned, TransactionSignedEcRecovered, TxHash, B256, U256,
 };
+use reth_revm::EvmConfig;
 use reth_rpc_types::{
     state::{AccountOverride, StateOverride},
     BlockOverrides, CallRequest,
 };
 use revm::{
     db::{CacheDB, EmptyDB},
-    precompile::{Precompiles, SpecId as PrecompilesSpecId},
-    primitives::{BlockEnv, CfgEnv, Env, ResultAndState, SpecId, TransactTo, TxEnv},
+    primitives::{BlockEnv, CfgEnv, Env, ResultAndState, TransactTo, TxEnv},
     Database, Inspector,
 };
 use revm_primitives::{
@@ -107,27 +107,29 @@ impl FillableTransaction for TransactionSigned {
     }
 }
 
-/// Returns the addresses of the precompiles corresponding to the SpecId.
-#[inline]
-pub(crate) fn get_precompiles(spec_id: SpecId) -> impl IntoIterator<Item = Address> {
-    let spec = PrecompilesSpecId::from_spec_id(spec_id);
-    Precompiles::new(spec).addresses().into_iter().copied().map(Address::from)
-}
-
 /// Executes the [Env] against the given [Database] without committing state changes.
-pub(crate) fn transact<DB>(db: DB, env: Env) -> EthResult<(ResultAndState, Env)>
+pub(crate) fn transact<DB>(
+    db: DB,
+    env: Env,
+    evm_config: &EvmConfig,
+) -> EthResult<(ResultAndState, Env)>
 where
     DB: Database,
     <DB as Database>::Error: Into<EthApiError>,
 {
     let mut evm = revm::EVM::with_env(env);
     evm.database(db);
-    let res = evm.transact()?;
+    let res = evm_config.transact(&mut evm)?;
     Ok((res, evm.env))
 }
 
 /// Executes the [Env] against the given [Database] without committing state changes.
-pub(crate) fn inspect<DB, I>(db: DB, env: Env, inspector: I) -> EthResult<(ResultAndState, Env)>
+pub(crate) fn inspect<DB, I>(
+    db: DB,
+    env: Env,
+    inspector: I,
+    evm_config: &EvmConfig,
+) -> EthResult<(ResultAndState, Env)>
 where
     DB: Database,
     <DB as Database>::Error: Into<EthApiError>,
@@ -135,7 +137,7 @@ where
 {
     let mut evm = revm::EVM::with_env(env);
     evm.database(db);
-    let res = evm.inspect(inspector)?;
+    let res = evm_config.inspect(&mut evm, inspector)?;
     Ok((res, evm.env))
 }
 
@@ -147,6 +149,7 @@ pub(crate) fn inspect_and_return_db<DB, I>(
     db: DB,
     env: Env,
     inspector: I,
+    evm_config: &EvmConfig,
 ) -> EthResult<(ResultAndState, Env, DB)>
 where
     DB: Database,
@@ -155,7 +158,7 @@ where
 {
     let mut evm = revm::EVM::with_env(env);
     evm.database(db);
-    let res = evm.inspect(inspector)?;
+    let res = evm_config.inspect(&mut evm, inspector)?;
     let db = evm.take_db();
     Ok((res, evm.env, db))
 }
@@ -172,6 +175,7 @@ pub(crate) fn replay_transactions_until<DB, I, Tx>(
     block_env: BlockEnv,
     transactions: I,
     target_tx_hash: B256,
+    evm_config: &EvmConfig,
 ) -> EthResult<()>
 where
     DB: DatabaseRef,
@@ -189,7 +193,7 @@ where
         }
 
         tx.try_fill_tx_env(&mut evm.env.tx)?;
-        let res = evm.transact()?;
+        let res = evm_config.transact(&mut evm)?;
         evm.db.as_mut().expect("is set").commit(res.state)
     }
     Ok(())
@@ -484,7 +488,7 @@ impl CallFees {
 }
 
 /// Applies the given block overrides to the env
-fn apply_block_overrides(overrides: BlockOverrides, env: &mut BlockEnv) {
+pub(crate) fn apply_block_overrides(overrides: BlockOverrides, env: &mut BlockEnv) {
     let BlockOverrides {
         number,
         difficulty,
@@ -0,0 +1,338 @@
+//! Limits of tracers that run on blocking tasks.
+
+use crate::eth::{
+    error::{EthApiError, EthResult},
+    revm_utils::{inspect, inspect_and_return_db},
+};
+use reth_primitives::{Address, Bytes, B256, U256};
+use reth_revm::EvmConfig;
+use reth_rpc_types::trace::geth::{
+    GethDebugBuiltInTracerType, GethDebugTracerType, GethDebugTracingOptions,
+};
+use revm::{
+    interpreter::{CallInputs, CreateInputs, Gas, InstructionResult, Interpreter},
+    primitives::{Env, ResultAndState},
+    Database, EVMData, Inspector,
+};
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+use tokio::sync::OwnedSemaphorePermit;
+
+/// The default limit of the memory retained by the steps a tracer records, 1 GiB.
+pub(crate) const DEFAULT_TRACER_MEMORY_LIMIT: usize = 1024 * 1024 * 1024;
+
+/// The estimated memory of a recorded step, besides its stack and memory.
+const RECORDED_STEP_SIZE: usize = 128;
+
+/// The limits of a tracer that runs on a blocking task.
+///
+/// Dropping the future of the request doesn't stop the blocking task, so the tracer checks on
+/// every step whether it was cancelled with [TracingLimits::cancel_on_drop], e.g. because the
+/// request timed out. The tracer is also stopped once the steps it records exceed the memory
+/// limit.
+///
+/// The limits hold the tracing permit, which is released once the tracer exited.
+#[derive(Debug)]
+pub(crate) struct TracingLimits {
+    /// Set once the request is dropped.
+    cancelled: Arc<AtomicBool>,
+    /// The memory the recorded steps may retain, in bytes.
+    memory_limit: usize,
+    /// What the tracer records of every step, if it records the steps.
+    recorded_steps: Option<RecordedSteps>,
+    /// The permit to trace.
+    _permit: OwnedSemaphorePermit,
+}
+
+/// What a tracer records of every step.
+#[derive(Debug, Clone, Copy)]
+struct RecordedSteps {
+    stack: bool,
+    memory: bool,
+}
+
+impl TracingLimits {
+    /// Creates the limits of the tracer configured by the options.
+    pub(crate) fn new(opts: &GethDebugTracingOptions, permit: OwnedSemaphorePermit) -> Self {
+        // only the struct logger and the prestate tracer in default mode record the steps
+        let records_steps = match &opts.tracer {
+            None => true,
+            Some(GethDebugTracerType::BuiltInTracer(
+                GethDebugBuiltInTracerType::PreStateTracer,
+            )) => opts
+                .tracer_config
+                .clone()
+                .into_pre_state_config()
+                .map_or(false, |config| config.is_default_mode()),
+            Some(_) => false,
+        };
+        let recorded_steps = records_steps.then(|| RecordedSteps {
+            stack: !opts.config.disable_stack.unwrap_or_default(),
+            memory: opts.config.enable_memory.unwrap_or_default(),
+        });
+
+        Self {
+            cancelled: Arc::new(AtomicBool::new(false)),
+            memory_limit: DEFAULT_TRACER_MEMORY_LIMIT,
+            recorded_steps,
+            _permit: permit,
+        }
+    }
+
+    /// Returns a guard that stops the tracer once it's dropped.
+    pub(crate) fn cancel_on_drop(&self) -> CancelOnDrop {
+        CancelOnDrop(Arc::clone(&self.cancelled))
+    }
+
+    /// Same as [inspect], but stops the execution once a limit is exceeded.
+    pub(crate) fn inspect<DB, I>(
+        &self,
+        db: DB,
+        env: Env,
+        inspector: I,
+        evm_config: &EvmConfig,
+    ) -> EthResult<(ResultAndState, Env)>
+    where
+        DB: Database,
+        <DB as Database>::Error: Into<EthApiError>,
+        I: Inspector<DB>,
+    {
+        let mut inspector = LimitedInspector::new(inspector, self);
+        let res = inspect(db, env, &mut inspector, evm_config);
+        inspector.finish(res)
+    }
+
+    /// Same as [inspect_and_return_db], but stops the execution once a limit is exceeded.
+    pub(crate) fn inspect_and_return_db<DB, I>(
+        &self,
+        db: DB,
+        env: Env,
+        inspector: I,
+        evm_config: &EvmConfig,
+    ) -> EthResult<(ResultAndState, Env, DB)>
+    where
+        DB: Database,
+        <DB as Database>::Error: Into<EthApiError>,
+        I: Inspector<DB>,
+    {
+        let mut inspector = LimitedInspector::new(inspector, self);
+        let res = inspect_and_return_db(db, env, &mut inspector, evm_config);
+        inspector.finish(res)
+    }
+}
+
+/// Stops the tracer of a [TracingLimits] when dropped.
+#[derive(Debug)]
+pub(crate) struct CancelOnDrop(Arc<AtomicBool>);
+
+impl Drop for CancelOnDrop {
+    fn drop(&mut self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+}
+
+/// An inspector that halts the execution once a [TracingLimits] is exceeded.
+struct LimitedInspector<'a, I> {
+    inner: I,
+    limits: &'a TracingLimits,
+    /// The estimated memory retained by the recorded steps so far.
+    memory_used: usize,
+    /// The limit that was exceeded, if any.
+    exceeded: Option<EthApiError>,
+}
+
+impl<'a, I> LimitedInspector<'a, I> {
+    fn new(inner: I, limits: &'a TracingLimits) -> Self {
+        Self { inner, limits, memory_used: 0, exceeded: None }
+    }
+
+    /// Returns true if a limit is exceeded at this step.
+    fn exceeded(&mut self, interp: &Interpreter<'_>) -> bool {
+        if self.exceeded.is_some() {
+            return true
+        }
+
+        if self.limits.cancelled.load(Ordering::Relaxed) {
+            // nobody is waiting for the result anymore
+            self.exceeded = Some(EthApiError::InternalEthError);
+        } else if let Some(recorded) = self.limits.recorded_steps {
+            self.memory_used += RECORDED_STEP_SIZE;
+            if recorded.stack {
+                self.memory_used += interp.stack.len() * 32;
+            }
+            if recorded.memory {
+                self.memory_used += interp.shared_memory.len();
+            }
+            if self.memory_used > self.limits.memory_limit {
+                self.exceeded =
+                    Some(EthApiError::TracerMemoryLimitExceeded(self.limits.memory_limit));
+            }
+        }
+        self.exceeded.is_some()
+    }
+
+    /// Returns the result of the execution, or the error of the exceeded limit.
+    fn finish<T>(self, res: EthResult<T>) -> EthResult<T> {
+        match self.exceeded {
+            Some(err) => Err(err),
+            None => res,
+        }
+    }
+}
+
+impl<'a, DB: Database, I: Inspector<DB>> Inspector<DB> for LimitedInspector<'a, I> {
+    fn initialize_interp(&mut self, interp: &mut Interpreter<'_>, data: &mut EVMData<'_, DB>) {
+        self.inner.initialize_interp(interp, data)
+    }
+
+    fn step(&mut self, interp: &mut Interpreter<'_>, data: &mut EVMData<'_, DB>) {
+        if self.exceeded(interp) {
+            // the result is discarded, reverting every frame unwinds the execution
+            interp.instruction_result = InstructionResult::Revert;
+            return
+        }
+        self.inner.step(interp, data)
+    }
+
+    fn log(
+        &mut self,
+        evm_data: &mut EVMData<'_, DB>,
+        address: &Address,
+        topics: &[B256],
+        data: &Bytes,
+    ) {
+        if self.exceeded.is_none() {
+            self.inner.log(evm_data, address, topics, data)
+        }
+    }
+
+    fn step_end(&mut self, interp: &mut Interpreter<'_>, data: &mut EVMData<'_, DB>) {
+        if self.exceeded.is_none() {
+            self.inner.step_end(interp, data)
+        }
+    }
+
+    fn call(
+        &mut self,
+        data: &mut EVMData<'_, DB>,
+        inputs: &mut CallInputs,
+    ) -> (InstructionResult, Gas, Bytes) {
+        if self.exceeded.is_some() {
+            return (InstructionResult::Revert, Gas::new(0), Bytes::new())
+        }
+        self.inner.call(data, inputs)
+    }
+
+    fn call_end(
+        &mut self,
+        data: &mut EVMData<'_, DB>,
+        inputs: &CallInputs,
+        remaining_gas: Gas,
+        ret: InstructionResult,
+        out: Bytes,
+    ) -> (InstructionResult, Gas, Bytes) {
+        // the inner inspector didn't see the calls that were skipped
+        if self.exceeded.is_some() {
+            return (ret, remaining_gas, out)
+        }
+        self.inner.call_end(data, inputs, remaining_gas, ret, out)
+    }
+
+    fn create(
+        &mut self,
+        data: &mut EVMData<'_, DB>,
+        inputs: &mut CreateInputs,
+    ) -> (InstructionResult, Option<Address>, Gas, Bytes) {
+        if self.exceeded.is_some() {
+            return (InstructionResult::Revert, None, Gas::new(0), Bytes::new())
+        }
+        self.inner.create(data, inputs)
+    }
+
+    fn create_end(
+        &mut self,
+        data: &mut EVMData<'_, DB>,
+        inputs: &CreateInputs,
+        ret: InstructionResult,
+        address: Option<Address>,
+        remaining_gas: Gas,
+        out: Bytes,
+    ) -> (InstructionResult, Option<Address>, Gas, Bytes) {
+        if self.exceeded.is_some() {
+            return (ret, address, remaining_gas, out)
+        }
+        self.inner.create_end(data, inputs, ret, address, remaining_gas, out)
+    }
+
+    fn selfdestruct(&mut self, contract: Address, target: Address, value: U256) {
+        self.inner.selfdestruct(contract, target, value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_matches::assert_matches;
+    use reth_provider::test_utils::NoopProvider;
+    use reth_revm::{
+        database::StateProviderDatabase,
+        tracing::{TracingInspector, TracingInspectorConfig},
+    };
+    use revm::{
+        db::CacheDB,
+        primitives::{AccountInfo, Bytecode, TransactTo, TxEnv},
+    };
+    use tokio::sync::Semaphore;
+
+    fn limits() -> TracingLimits {
+        let permit = Arc::new(Semaphore::new(1)).try_acquire_owned().unwrap();
+        TracingLimits::new(&GethDebugTracingOptions::default(), permit)
+    }
+
+    /// Traces a call to a contract that loops until it runs out of gas.
+    fn trace_loop(limits: &TracingLimits) -> EthResult<(ResultAndState, Env)> {
+        let address = Address::random();
+        let mut db = CacheDB::new(StateProviderDatabase::new(NoopProvider::default()));
+        // JUMPDEST, PUSH1 0, JUMP
+        let code = Bytecode::new_raw(Bytes::from_static(&[0x5b, 0x60, 0x00, 0x56]));
+        db.insert_account_info(
+            address,
+            AccountInfo { code_hash: code.hash_slow(), code: Some(code), ..Default::default() },
+        );
+        let env = Env {
+            tx: TxEnv {
+                transact_to: TransactTo::Call(address),
+                gas_limit: 1_000_000,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let mut inspector = TracingInspector::new(TracingInspectorConfig::default_geth());
+        limits.inspect(db, env, &mut inspector, &EvmConfig::default())
+    }
+
+    #[test]
+    fn trace_within_limits() {
+        let (res, _) = trace_loop(&limits()).unwrap();
+        assert!(!res.result.is_success());
+    }
+
+    #[test]
+    fn stop_tracer_over_memory_limit() {
+        let mut limits = limits();
+        limits.memory_limit = 1024 * 1024;
+        assert_matches!(
+            trace_loop(&limits),
+            Err(EthApiError::TracerMemoryLimitExceeded(limit)) if limit == 1024 * 1024
+        );
+    }
+
+    #[test]
+    fn stop_cancelled_tracer() {
+        let limits = limits();
+        drop(limits.cancel_on_drop());
+        assert_matches!(trace_loop(&limits), Err(EthApiError::InternalEthError));
+    }
+}
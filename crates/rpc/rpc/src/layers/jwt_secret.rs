@@ -32,9 +32,9 @@ pub enum JwtError {
     #[error("provided signature is invalid")]
     InvalidSignature,
 
-    /// The "iat" (issued-at) claim in the JWT is not within the allowed ±60 seconds from the
-    /// current time.
-    #[error("IAT (issued-at) claim is not within ±60 seconds from the current time")]
+    /// The "iat" (issued-at) claim in the JWT is not within the allowed time window from the
+    /// current time, ±60 seconds by default.
+    #[error("IAT (issued-at) claim is not within the allowed time window from the current time")]
     InvalidIssuanceTimestamp,
 
     /// The Authorization header is missing or invalid in the context of JWT validation.
@@ -61,8 +61,8 @@ pub enum JwtError {
 /// we need 64 hexadecimal digits (256 bits ÷ 4 bits per digit = 64 digits).
 const JWT_SECRET_LEN: usize = 64;
 
-/// The JWT `iat` (issued-at) claim cannot exceed +-60 seconds from the current time.
-const JWT_MAX_IAT_DIFF: Duration = Duration::from_secs(60);
+/// The JWT `iat` (issued-at) claim cannot exceed +-60 seconds from the current time by default.
+pub const JWT_MAX_IAT_DIFF: Duration = Duration::from_secs(60);
 
 /// The execution layer client MUST support at least the following alg HMAC + SHA256 (HS256)
 const JWT_SIGNATURE_ALGO: Algorithm = Algorithm::HS256;
@@ -127,14 +127,25 @@ impl JwtSecret {
     ///
     /// See also: [JWT Claims - Engine API specs](https://github.com/ethereum/execution-apis/blob/main/src/engine/authentication.md#jwt-claims)
     pub fn validate(&self, jwt: String) -> Result<(), JwtError> {
+        self.validate_with_max_iat_diff(&jwt, JWT_MAX_IAT_DIFF)
+    }
+
+    /// Validates a JWT token like [`JwtSecret::validate`], but allows an `iat` (issued-at) claim
+    /// within `max_iat_diff` from the current time, to tolerate clock skew between the consensus
+    /// and the execution layer client.
+    pub fn validate_with_max_iat_diff(
+        &self,
+        jwt: &str,
+        max_iat_diff: Duration,
+    ) -> Result<(), JwtError> {
         let mut validation = Validation::new(JWT_SIGNATURE_ALGO);
         // ensure that the JWT has an `iat` claim
         validation.set_required_spec_claims(&["iat"]);
         let bytes = &self.0;
 
-        match decode::<Claims>(&jwt, &DecodingKey::from_secret(bytes), &validation) {
+        match decode::<Claims>(jwt, &DecodingKey::from_secret(bytes), &validation) {
             Ok(token) => {
-                if !token.claims.is_within_time_window() {
+                if !token.claims.is_within_time_window(max_iat_diff) {
                     Err(JwtError::InvalidIssuanceTimestamp)?
                 }
             }
@@ -210,10 +221,10 @@ pub struct Claims {
 }
 
 impl Claims {
-    fn is_within_time_window(&self) -> bool {
+    fn is_within_time_window(&self, max_iat_diff: Duration) -> bool {
         let now = SystemTime::now();
         let now_secs = now.duration_since(UNIX_EPOCH).unwrap().as_secs();
-        now_secs.abs_diff(self.iat) <= JWT_MAX_IAT_DIFF.as_secs()
+        now_secs.abs_diff(self.iat) <= max_iat_diff.as_secs()
     }
 }
 
@@ -315,6 +326,22 @@ mod tests {
         assert!(matches!(result, Err(JwtError::InvalidIssuanceTimestamp)));
     }
 
+    #[test]
+    fn validation_with_max_iat_diff() {
+        let secret = JwtSecret::random();
+
+        let offset = Duration::from_secs(JWT_MAX_IAT_DIFF.as_secs() + 10);
+        let out_of_window_time = SystemTime::now().checked_sub(offset).unwrap();
+        let claims = Claims { iat: to_u64(out_of_window_time), exp: Some(10000000000) };
+        let jwt: String = secret.encode(&claims).unwrap();
+
+        let result = secret.validate_with_max_iat_diff(&jwt, Duration::from_secs(120));
+        assert!(matches!(result, Ok(())));
+
+        let result = secret.validate_with_max_iat_diff(&jwt, Duration::from_secs(30));
+        assert!(matches!(result, Err(JwtError::InvalidIssuanceTimestamp)));
+    }
+
     #[test]
     fn validation_error_wrong_signature() {
         let secret_1 = JwtSecret::random();
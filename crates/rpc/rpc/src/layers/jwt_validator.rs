@@ -1,15 +1,21 @@
 use http::{header, HeaderMap, Response, StatusCode};
+use parking_lot::RwLock;
+use std::{sync::Arc, time::Duration};
 use tracing::error;
 
-use crate::{AuthValidator, JwtError, JwtSecret};
+use crate::{layers::jwt_secret::JWT_MAX_IAT_DIFF, AuthValidator, JwtError, JwtSecret};
 
 /// Implements JWT validation logics and integrates
 /// to an Http [`AuthLayer`][crate::layers::AuthLayer]
 /// by implementing the [`AuthValidator`] trait.
-#[derive(Clone)]
-#[allow(missing_debug_implementations)]
+///
+/// A token is accepted if it is valid for any of the configured secrets, which allows rotating the
+/// secret without downtime. The secrets are shared by all clones of the validator and can be
+/// replaced at runtime with [`JwtAuthValidator::set_secrets`].
+#[derive(Clone, Debug)]
 pub struct JwtAuthValidator {
-    secret: JwtSecret,
+    secrets: Arc<RwLock<Vec<JwtSecret>>>,
+    max_iat_diff: Duration,
 }
 
 impl JwtAuthValidator {
@@ -17,7 +23,46 @@ impl JwtAuthValidator {
     /// Validation logics are implemented by the `secret`
     /// argument (see [`JwtSecret`]).
     pub fn new(secret: JwtSecret) -> Self {
-        Self { secret }
+        Self::with_secrets(vec![secret])
+    }
+
+    /// Creates a new instance of [`JwtAuthValidator`] that accepts tokens of any of the given
+    /// secrets.
+    pub fn with_secrets(secrets: Vec<JwtSecret>) -> Self {
+        Self { secrets: Arc::new(RwLock::new(secrets)), max_iat_diff: JWT_MAX_IAT_DIFF }
+    }
+
+    /// Sets the maximum difference between the `iat` (issued-at) claim and the current time.
+    ///
+    /// Default is 60 seconds, as required by the engine API specs.
+    pub fn with_max_iat_diff(mut self, max_iat_diff: Duration) -> Self {
+        self.max_iat_diff = max_iat_diff;
+        self
+    }
+
+    /// Replaces the accepted secrets of this validator and all of its clones.
+    pub fn set_secrets(&self, secrets: Vec<JwtSecret>) {
+        *self.secrets.write() = secrets;
+    }
+
+    /// Validates the token against all secrets.
+    ///
+    /// If the token is invalid for all secrets, this returns the error of the first secret the
+    /// signature is valid for, or [`JwtError::InvalidSignature`].
+    fn validate_token(&self, jwt: &str) -> Result<(), JwtError> {
+        let mut result = Err(JwtError::InvalidSignature);
+        for secret in self.secrets.read().iter() {
+            match secret.validate_with_max_iat_diff(jwt, self.max_iat_diff) {
+                Ok(()) => return Ok(()),
+                Err(JwtError::InvalidSignature) => {}
+                Err(err) => {
+                    if matches!(result, Err(JwtError::InvalidSignature)) {
+                        result = Err(err);
+                    }
+                }
+            }
+        }
+        result
     }
 }
 
@@ -26,7 +71,7 @@ impl AuthValidator for JwtAuthValidator {
 
     fn validate(&self, headers: &HeaderMap) -> Result<(), Response<Self::ResponseBody>> {
         match get_bearer(headers) {
-            Some(jwt) => match self.secret.validate(jwt) {
+            Some(jwt) => match self.validate_token(&jwt) {
                 Ok(_) => Ok(()),
                 Err(e) => {
                     error!(target: "engine::jwt-validator", "Invalid JWT: {e}");
@@ -68,8 +113,29 @@ fn err_response(err: JwtError) -> Response<hyper::Body> {
 
 #[cfg(test)]
 mod tests {
-    use crate::layers::jwt_validator::get_bearer;
+    use crate::{
+        layers::jwt_validator::{get_bearer, JwtAuthValidator},
+        Claims, JwtError, JwtSecret,
+    };
     use http::{header, HeaderMap};
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    #[test]
+    fn validate_with_multiple_secrets() {
+        let (old, new) = (JwtSecret::random(), JwtSecret::random());
+        let iat = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+        let claims = Claims { iat, exp: None };
+        let (old_jwt, new_jwt) = (old.encode(&claims).unwrap(), new.encode(&claims).unwrap());
+
+        let validator = JwtAuthValidator::with_secrets(vec![new.clone(), old]);
+        assert!(validator.validate_token(&old_jwt).is_ok());
+        assert!(validator.validate_token(&new_jwt).is_ok());
+
+        // the secrets are replaced for all clones
+        validator.clone().set_secrets(vec![new]);
+        assert!(matches!(validator.validate_token(&old_jwt), Err(JwtError::InvalidSignature)));
+        assert!(validator.validate_token(&new_jwt).is_ok());
+    }
 
     #[test]
     fn auth_header_available() {
@@ -0,0 +1,306 @@
+use futures::{future, FutureExt};
+use http::Request;
+use jsonrpsee::{
+    core::server::{ConnectionId, MethodCallback, MethodResponse, Methods},
+    types::{ErrorObject, Id},
+};
+use parking_lot::Mutex;
+use reth_net_common::token_bucket::TokenBucket;
+use std::{
+    collections::HashMap,
+    num::NonZeroU32,
+    sync::Arc,
+    task::{Context, Poll},
+    time::Instant,
+};
+use tokio::task::futures::TaskLocalFuture;
+use tower::{Layer, Service};
+use tracing::debug;
+
+/// The JSON-RPC error code returned when a call exceeds the rate limit.
+pub const RATE_LIMIT_EXCEEDED_CODE: i32 = -32005;
+
+tokio::task_local! {
+    /// The per-connection bucket of the HTTP connection that is served by the current task, set
+    /// by the [RateLimitService].
+    ///
+    /// All HTTP connections have the same [ConnectionId], so their calls can't be told apart by the
+    /// id.
+    static HTTP_CONNECTION: Option<SharedTokenBucket>;
+}
+
+type SharedTokenBucket = Arc<Mutex<TokenBucket>>;
+
+/// The call rate limits of the RPC servers.
+///
+/// Every call is counted, including every call of a batch request and every message of a WS or
+/// IPC connection.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RateLimitConfig {
+    /// The maximum number of calls per second of all connections.
+    pub global: Option<NonZeroU32>,
+    /// The maximum number of calls per second of a single connection.
+    pub per_connection: Option<NonZeroU32>,
+}
+
+impl RateLimitConfig {
+    /// Returns true if no limit is configured.
+    pub fn is_unlimited(&self) -> bool {
+        self.global.is_none() && self.per_connection.is_none()
+    }
+}
+
+/// Rate limits the calls of the RPC servers according to a [RateLimitConfig].
+///
+/// The methods of every server are wrapped with [RateLimiter::rate_limit], so that every call takes
+/// a token from the global bucket, which is shared by all servers of the limiter, and from the
+/// bucket of its connection. Calls over the limit are answered with a JSON-RPC error with the
+/// [RATE_LIMIT_EXCEEDED_CODE]. Unsubscribing is never limited.
+///
+/// The per-connection limit of HTTP connections additionally requires the [RateLimitLayer] as HTTP
+/// middleware of the server.
+#[derive(Debug, Clone, Default)]
+pub struct RateLimiter {
+    global: Option<SharedTokenBucket>,
+    per_connection: Option<NonZeroU32>,
+}
+
+impl RateLimiter {
+    /// Creates a new [RateLimiter] with the given limits.
+    pub fn new(config: RateLimitConfig) -> Self {
+        Self {
+            global: config
+                .global
+                .map(|rate| Arc::new(Mutex::new(TokenBucket::new(rate.get().into())))),
+            per_connection: config.per_connection,
+        }
+    }
+
+    /// Returns true if no limit is configured.
+    pub fn is_unlimited(&self) -> bool {
+        self.global.is_none() && self.per_connection.is_none()
+    }
+
+    /// Returns the methods of a server, rate limited.
+    ///
+    /// The connection ids are only unique within a server, so the methods of every server must be
+    /// wrapped separately.
+    pub fn rate_limit(&self, methods: impl Into<Methods>) -> Methods {
+        let methods = methods.into();
+        if self.is_unlimited() {
+            return methods
+        }
+
+        let limiter = Arc::new(CallLimiter {
+            global: self.global.clone(),
+            per_connection: self.per_connection,
+            connections: Default::default(),
+        });
+        let mut limited = Methods::new();
+        for name in methods.method_names() {
+            let callback = methods.method(name).expect("method exists").clone();
+            limited
+                .verify_and_insert(name, limiter.wrap(callback))
+                .expect("method names are unique");
+        }
+        limited
+    }
+}
+
+/// Takes the tokens of the calls of a single server.
+#[derive(Debug)]
+struct CallLimiter {
+    global: Option<SharedTokenBucket>,
+    per_connection: Option<NonZeroU32>,
+    /// The per-connection buckets of the WS and IPC connections.
+    connections: Mutex<HashMap<ConnectionId, TokenBucket>>,
+}
+
+impl CallLimiter {
+    /// Takes a token from the bucket of the connection and the global bucket.
+    fn try_acquire(&self, conn_id: ConnectionId) -> bool {
+        let now = Instant::now();
+        let admitted = match HTTP_CONNECTION.try_with(Clone::clone) {
+            Ok(connection) => connection.map_or(true, |bucket| bucket.lock().try_acquire(now)),
+            Err(_) => self.per_connection.map_or(true, |rate| {
+                let mut connections = self.connections.lock();
+                if !connections.contains_key(&conn_id) {
+                    // full buckets didn't limit anything recently, such as the buckets of closed
+                    // connections, and are the same as new ones
+                    connections.retain(|_, bucket| !bucket.is_full(now));
+                }
+                connections
+                    .entry(conn_id)
+                    .or_insert_with(|| TokenBucket::new(rate.get().into()))
+                    .try_acquire(now)
+            }),
+        };
+        let admitted =
+            admitted && self.global.as_ref().map_or(true, |global| global.lock().try_acquire(now));
+        if !admitted {
+            debug!(target: "rpc::rate-limit", conn_id, "Rate limit exceeded");
+        }
+        admitted
+    }
+
+    /// Wraps the callback of a method, so that it's only called within the limits.
+    fn wrap(self: &Arc<Self>, callback: MethodCallback) -> MethodCallback {
+        let limiter = Arc::clone(self);
+        match callback {
+            // synchronous methods don't get the connection id, so they're called asynchronously
+            MethodCallback::Sync(method) => {
+                MethodCallback::Async(Arc::new(move |id, params, conn_id, max_response_size| {
+                    let response = if limiter.try_acquire(conn_id) {
+                        method(id, params, max_response_size)
+                    } else {
+                        rate_limit_exceeded(id)
+                    };
+                    future::ready(response).boxed()
+                }))
+            }
+            MethodCallback::Async(method) => {
+                MethodCallback::Async(Arc::new(move |id, params, conn_id, max_response_size| {
+                    if limiter.try_acquire(conn_id) {
+                        method(id, params, conn_id, max_response_size)
+                    } else {
+                        future::ready(rate_limit_exceeded(id)).boxed()
+                    }
+                }))
+            }
+            MethodCallback::Subscription(method) => {
+                MethodCallback::Subscription(Arc::new(move |id, params, sink, state| {
+                    if limiter.try_acquire(state.conn_id) {
+                        return method(id, params, sink, state)
+                    }
+                    // the response to a subscription call is sent by the callback
+                    let response = rate_limit_exceeded(id);
+                    async move {
+                        let _ = sink.send(response.result.clone()).await;
+                        Ok(response)
+                    }
+                    .boxed()
+                }))
+            }
+            unsubscription @ MethodCallback::Unsubscription(_) => unsubscription,
+        }
+    }
+}
+
+fn rate_limit_exceeded(id: Id<'_>) -> MethodResponse {
+    MethodResponse::error(
+        id,
+        ErrorObject::owned(RATE_LIMIT_EXCEEDED_CODE, "rate limit exceeded", None::<()>),
+    )
+}
+
+/// A HTTP middleware layer that tracks the per-connection limit of a [RateLimiter] for HTTP
+/// connections.
+///
+/// The server creates a service for every connection, which holds the bucket of the connection
+/// while the connection's requests are served.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RateLimitLayer {
+    per_connection: Option<NonZeroU32>,
+}
+
+impl RateLimitLayer {
+    /// Creates a new [RateLimitLayer] with the per-connection limit of the given limits.
+    pub fn new(config: RateLimitConfig) -> Self {
+        Self { per_connection: config.per_connection }
+    }
+}
+
+impl<S> Layer<S> for RateLimitLayer {
+    type Service = RateLimitService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        RateLimitService {
+            connection: self
+                .per_connection
+                .map(|rate| Arc::new(Mutex::new(TokenBucket::new(rate.get().into())))),
+            inner,
+        }
+    }
+}
+
+/// The service of the [RateLimitLayer] for a single connection.
+#[derive(Debug)]
+pub struct RateLimitService<S> {
+    connection: Option<SharedTokenBucket>,
+    inner: S,
+}
+
+impl<ReqBody, S> Service<Request<ReqBody>> for RateLimitService<S>
+where
+    S: Service<Request<ReqBody>>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = TaskLocalFuture<Option<SharedTokenBucket>, S::Future>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request<ReqBody>) -> Self::Future {
+        // WS connections are served by their own task once upgraded, so only calls of HTTP
+        // requests see the bucket
+        HTTP_CONNECTION.scope(self.connection.clone(), self.inner.call(req))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jsonrpsee::RpcModule;
+
+    fn limited_module(config: RateLimitConfig) -> Methods {
+        let mut module = RpcModule::new(());
+        module.register_method("sync_call", |_, _| "sync").unwrap();
+        module.register_async_method("async_call", |_, _| async { "async" }).unwrap();
+        RateLimiter::new(config).rate_limit(module)
+    }
+
+    fn is_rate_limited(response: &str) -> bool {
+        response.contains(&RATE_LIMIT_EXCEEDED_CODE.to_string())
+    }
+
+    #[tokio::test]
+    async fn limit_every_call() {
+        let methods =
+            limited_module(RateLimitConfig { global: NonZeroU32::new(3), per_connection: None });
+
+        // sync and async methods share the bucket
+        for method in ["sync_call", "async_call", "sync_call"] {
+            let (response, _) = methods.raw_json_request(&call(method), 1).await.unwrap();
+            assert!(!is_rate_limited(&response.result), "{response:?}");
+        }
+        let (response, _) = methods.raw_json_request(&call("async_call"), 1).await.unwrap();
+        assert!(is_rate_limited(&response.result), "{response:?}");
+    }
+
+    #[tokio::test]
+    async fn limit_per_connection() {
+        let limiter = Arc::new(CallLimiter {
+            global: None,
+            per_connection: NonZeroU32::new(1),
+            connections: Default::default(),
+        });
+        assert!(limiter.try_acquire(1));
+        assert!(!limiter.try_acquire(1));
+        assert!(limiter.try_acquire(2));
+
+        // the bucket of the HTTP connection takes precedence over the connection id
+        let connection = Arc::new(Mutex::new(TokenBucket::new(1)));
+        HTTP_CONNECTION
+            .scope(Some(connection), async {
+                assert!(limiter.try_acquire(1));
+                assert!(!limiter.try_acquire(1));
+            })
+            .await;
+    }
+
+    fn call(method: &str) -> String {
+        format!(r#"{{"jsonrpc":"2.0","id":1,"method":"{method}","params":[]}}"#)
+    }
+}
@@ -41,7 +41,10 @@ pub use blocking_pool::{BlockingTaskGuard, BlockingTaskPool};
 pub use debug::DebugApi;
 pub use engine::{EngineApi, EngineEthApi};
 pub use eth::{EthApi, EthApiSpec, EthFilter, EthPubSub, EthSubscriptionIdProvider};
-pub use layers::{AuthLayer, AuthValidator, Claims, JwtAuthValidator, JwtError, JwtSecret};
+pub use layers::{
+    AuthLayer, AuthValidator, Claims, JwtAuthValidator, JwtError, JwtSecret, RateLimitConfig,
+    RateLimitLayer, RateLimitService, RateLimiter, JWT_MAX_IAT_DIFF, RATE_LIMIT_EXCEEDED_CODE,
+};
 pub use net::NetApi;
 pub use otterscan::OtterscanApi;
 pub use reth::RethApi;
@@ -1,32 +1,166 @@
-use crate::result::internal_rpc_err;
+use crate::{
+    eth::{error::EthApiError, EthTransactions},
+    result::internal_rpc_err,
+};
 use async_trait::async_trait;
 use jsonrpsee::core::RpcResult;
-use reth_primitives::{Address, BlockId, BlockNumberOrTag, TxHash, B256};
+use reth_primitives::{
+    Address, BlockId, BlockNumberOrTag, Bytes, TxHash, B256, KECCAK_EMPTY, U256, U64,
+};
+use reth_provider::{ChangeSetReader, TransactionAddressIndexReader};
+use reth_revm::tracing::{
+    types::{CallKind, CallTraceNode},
+    TracingInspectorConfig,
+};
 use reth_rpc_api::{EthApiServer, OtterscanServer};
 use reth_rpc_types::{
-    BlockDetails, ContractCreator, InternalOperation, OtsBlockTransactions, TraceEntry,
-    Transaction, TransactionsWithReceipts,
+    BlockDetails, BlockTransactions, ContractCreator, InternalOperation, OperationType, OtsBlock,
+    OtsBlockTransactions, OtsTransactionReceipt, TraceEntry, Transaction, TransactionsWithReceipts,
 };
+use revm::primitives::ExecutionResult;
+use std::{collections::HashSet, ops::Range};
 
 const API_LEVEL: u64 = 8;
 
 /// Otterscan API.
 #[derive(Debug)]
-pub struct OtterscanApi<Eth> {
+pub struct OtterscanApi<Provider, Eth> {
+    provider: Provider,
     eth: Eth,
 }
 
-impl<Eth> OtterscanApi<Eth> {
+impl<Provider, Eth> OtterscanApi<Provider, Eth> {
     /// Creates a new instance of `Otterscan`.
-    pub fn new(eth: Eth) -> Self {
-        Self { eth }
+    pub fn new(provider: Provider, eth: Eth) -> Self {
+        Self { provider, eth }
+    }
+}
+
+// === impl OtterscanApi ===
+
+impl<Provider, Eth> OtterscanApi<Provider, Eth>
+where
+    Provider: TransactionAddressIndexReader + ChangeSetReader + 'static,
+    Eth: EthApiServer + EthTransactions,
+{
+    /// Returns the current best block number.
+    fn best_block_number(&self) -> RpcResult<u64> {
+        Ok(EthApiServer::block_number(&self.eth)?.saturating_to())
+    }
+
+    /// Returns the blocks of the given range, in ascending order, that the address can be
+    /// involved in.
+    ///
+    /// The blocks up to the checkpoint of the transaction address index are looked up in the
+    /// index. The blocks above it are not indexed yet, so all of them are returned.
+    fn candidate_blocks(&self, address: Address, range: Range<u64>) -> RpcResult<Vec<u64>> {
+        if range.is_empty() {
+            return Ok(Vec::new())
+        }
+
+        let checkpoint = self
+            .provider
+            .transaction_address_index_checkpoint()
+            .map_err(EthApiError::from)?
+            .ok_or_else(|| internal_rpc_err("the transaction address index is not enabled"))?;
+
+        let mut blocks = if checkpoint >= range.start {
+            self.provider
+                .blocks_with_transaction_address(
+                    address,
+                    range.start..=checkpoint.min(range.end - 1),
+                )
+                .map_err(EthApiError::from)?
+        } else {
+            Vec::new()
+        };
+        blocks.extend(checkpoint.saturating_add(1).max(range.start)..range.end);
+        Ok(blocks)
+    }
+
+    /// Returns all transactions of the given block that the address is involved in, together with
+    /// their receipts.
+    ///
+    /// A transaction matches if the address is its sender, its recipient or the contract it
+    /// created, or the caller, callee or refund target of any of its internal calls.
+    async fn search_block(
+        &self,
+        address: Address,
+        block_number: u64,
+    ) -> RpcResult<(Vec<Transaction>, Vec<OtsTransactionReceipt>)> {
+        let number = BlockNumberOrTag::Number(block_number);
+        let matches = self
+            .eth
+            .trace_block_with(
+                BlockId::Number(number),
+                TracingInspectorConfig::default_parity(),
+                move |tx_info, inspector, _, _, _| {
+                    let involved = inspector.into_traces().into_nodes().into_iter().any(
+                        |CallTraceNode { trace, .. }| {
+                            trace.caller == address ||
+                                trace.address == address ||
+                                trace.selfdestruct_refund_target == Some(address)
+                        },
+                    );
+                    Ok(involved.then_some(tx_info.index).flatten())
+                },
+            )
+            .await?
+            .unwrap_or_default();
+        let matches = matches.into_iter().flatten().collect::<HashSet<_>>();
+        if matches.is_empty() {
+            return Ok(Default::default())
+        }
+
+        let block = EthApiServer::block_by_number(&self.eth, number, true);
+        let receipts = EthApiServer::block_receipts(&self.eth, BlockId::Number(number));
+        let (block, receipts) = futures::try_join!(block, receipts)?;
+
+        let (Some(block), Some(receipts)) = (block, receipts) else {
+            return Ok(Default::default())
+        };
+        let BlockTransactions::Full(transactions) = block.inner.transactions else {
+            return Ok(Default::default())
+        };
+        let timestamp = u64::try_from(block.inner.header.timestamp).unwrap_or(u64::MAX);
+
+        let mut txs = Vec::new();
+        let mut ots_receipts = Vec::new();
+        for (index, (tx, receipt)) in transactions.into_iter().zip(receipts).enumerate() {
+            if matches.contains(&(index as u64)) {
+                txs.push(tx);
+                ots_receipts.push(OtsTransactionReceipt { receipt, timestamp });
+            }
+        }
+
+        Ok((txs, ots_receipts))
+    }
+
+    /// Returns the first block in `0..=best` for which the predicate holds, assuming that the
+    /// predicate is monotonic over the block range.
+    async fn find_first_block<F, Fut>(&self, best: u64, mut predicate: F) -> RpcResult<u64>
+    where
+        F: FnMut(u64) -> Fut,
+        Fut: std::future::Future<Output = RpcResult<bool>>,
+    {
+        let (mut low, mut high) = (0, best);
+        while low < high {
+            let mid = low + (high - low) / 2;
+            if predicate(mid).await? {
+                high = mid;
+            } else {
+                low = mid + 1;
+            }
+        }
+        Ok(low)
     }
 }
 
 #[async_trait]
-impl<Eth> OtterscanServer for OtterscanApi<Eth>
+impl<Provider, Eth> OtterscanServer for OtterscanApi<Provider, Eth>
 where
-    Eth: EthApiServer,
+    Provider: TransactionAddressIndexReader + ChangeSetReader + 'static,
+    Eth: EthApiServer + EthTransactions,
 {
     /// Handler for `ots_hasCode`
     async fn has_code(&self, address: Address, block_number: Option<BlockId>) -> RpcResult<bool> {
@@ -39,18 +173,99 @@ where
     }
 
     /// Handler for `ots_getInternalOperations`
-    async fn get_internal_operations(&self, _tx_hash: TxHash) -> RpcResult<Vec<InternalOperation>> {
-        Err(internal_rpc_err("unimplemented"))
+    async fn get_internal_operations(&self, tx_hash: TxHash) -> RpcResult<Vec<InternalOperation>> {
+        let operations = self
+            .eth
+            .spawn_trace_transaction_in_block(
+                tx_hash,
+                TracingInspectorConfig::default_parity(),
+                |_tx_info, inspector, _, _| {
+                    let mut operations = Vec::new();
+                    for CallTraceNode { trace, .. } in inspector.into_traces().into_nodes() {
+                        // the top level call is the transaction itself
+                        if trace.depth > 0 {
+                            let r#type = match trace.kind {
+                                CallKind::Create => Some(OperationType::OpCreate),
+                                CallKind::Create2 => Some(OperationType::OpCreate2),
+                                CallKind::Call if trace.value > U256::ZERO => {
+                                    Some(OperationType::OpTransfer)
+                                }
+                                _ => None,
+                            };
+                            if let Some(r#type) = r#type {
+                                operations.push(InternalOperation {
+                                    r#type,
+                                    from: trace.caller,
+                                    to: trace.address,
+                                    value: trace.value,
+                                });
+                            }
+                        }
+                        if let Some(refund_target) = trace.selfdestruct_refund_target {
+                            operations.push(InternalOperation {
+                                r#type: OperationType::OpSelfDestruct,
+                                from: trace.address,
+                                to: refund_target,
+                                value: trace.value,
+                            });
+                        }
+                    }
+                    Ok(operations)
+                },
+            )
+            .await?
+            .ok_or(EthApiError::TransactionNotFound)?;
+        Ok(operations)
     }
 
     /// Handler for `ots_getTransactionError`
-    async fn get_transaction_error(&self, _tx_hash: TxHash) -> RpcResult<String> {
-        Err(internal_rpc_err("unimplemented"))
+    async fn get_transaction_error(&self, tx_hash: TxHash) -> RpcResult<Bytes> {
+        let output = self
+            .eth
+            .spawn_trace_transaction_in_block(
+                tx_hash,
+                TracingInspectorConfig::default_parity(),
+                |_tx_info, _inspector, res, _| match res.result {
+                    ExecutionResult::Revert { output, .. } => Ok(output),
+                    _ => Ok(Bytes::default()),
+                },
+            )
+            .await?
+            .ok_or(EthApiError::TransactionNotFound)?;
+        Ok(output)
     }
 
     /// Handler for `ots_traceTransaction`
-    async fn trace_transaction(&self, _tx_hash: TxHash) -> RpcResult<TraceEntry> {
-        Err(internal_rpc_err("unimplemented"))
+    async fn trace_transaction(&self, tx_hash: TxHash) -> RpcResult<Vec<TraceEntry>> {
+        let traces = self
+            .eth
+            .spawn_trace_transaction_in_block(
+                tx_hash,
+                TracingInspectorConfig::default_parity(),
+                |_tx_info, inspector, _, _| {
+                    Ok(inspector
+                        .into_traces()
+                        .into_nodes()
+                        .into_iter()
+                        .map(|CallTraceNode { trace, .. }| TraceEntry {
+                            r#type: if trace.selfdestruct_refund_target.is_some() {
+                                "SELFDESTRUCT".to_string()
+                            } else {
+                                trace.kind.to_string()
+                            },
+                            depth: trace.depth as u32,
+                            from: trace.caller,
+                            to: trace.address,
+                            value: trace.value,
+                            input: trace.data,
+                            output: trace.output,
+                        })
+                        .collect::<Vec<_>>())
+                },
+            )
+            .await?
+            .ok_or(EthApiError::TransactionNotFound)?;
+        Ok(traces)
     }
 
     /// Handler for `ots_getBlockDetails`
@@ -71,44 +286,203 @@ where
     /// Handler for `getBlockTransactions`
     async fn get_block_transactions(
         &self,
-        _block_number: BlockNumberOrTag,
-        _page_number: usize,
-        _page_size: usize,
+        block_number: BlockNumberOrTag,
+        page_number: usize,
+        page_size: usize,
     ) -> RpcResult<OtsBlockTransactions> {
-        Err(internal_rpc_err("unimplemented"))
+        let block = EthApiServer::block_by_number(&self.eth, block_number, true);
+        let receipts = EthApiServer::block_receipts(&self.eth, BlockId::Number(block_number));
+        let (block, receipts) = futures::try_join!(block, receipts)?;
+
+        let mut block = block.ok_or(EthApiError::UnknownBlockNumber)?.inner;
+        let mut receipts = receipts.ok_or(EthApiError::UnknownBlockNumber)?;
+        let timestamp = u64::try_from(block.header.timestamp).unwrap_or(u64::MAX);
+
+        let BlockTransactions::Full(transactions) = &mut block.transactions else {
+            return Err(internal_rpc_err("block is not full"))
+        };
+        let transaction_count = transactions.len();
+        if transaction_count != receipts.len() {
+            return Err(internal_rpc_err(
+                "the number of transactions does not match the number of receipts",
+            ))
+        }
+
+        // pages are counted from the start of the block
+        let page_start = page_number.saturating_mul(page_size).min(transaction_count);
+        let page_end = page_start.saturating_add(page_size).min(transaction_count);
+        *transactions = transactions.drain(page_start..page_end).collect();
+
+        let receipts = receipts
+            .drain(page_start..page_end)
+            .map(|receipt| OtsTransactionReceipt { receipt, timestamp })
+            .collect();
+
+        Ok(OtsBlockTransactions { fullblock: OtsBlock { block, transaction_count }, receipts })
     }
 
     /// Handler for `searchTransactionsBefore`
     async fn search_transactions_before(
         &self,
-        _address: Address,
-        _block_number: BlockNumberOrTag,
-        _page_size: usize,
+        address: Address,
+        block_number: BlockNumberOrTag,
+        page_size: usize,
     ) -> RpcResult<TransactionsWithReceipts> {
-        Err(internal_rpc_err("unimplemented"))
+        let best = self.best_block_number()?;
+        // `0` is used by otterscan to request the most recent transactions
+        let (first_page, end) = match block_number {
+            BlockNumberOrTag::Number(0) | BlockNumberOrTag::Latest => (true, best + 1),
+            BlockNumberOrTag::Number(number) => (false, number.min(best + 1)),
+            _ => return Err(internal_rpc_err("unsupported block number")),
+        };
+
+        let mut blocks = self.candidate_blocks(address, 0..end)?;
+        let mut txs = Vec::new();
+        let mut receipts = Vec::new();
+        // walk the candidate blocks backwards and only stop at block boundaries, so the block of
+        // the last returned transaction is where the next page continues
+        while txs.len() < page_size {
+            let Some(block_number) = blocks.pop() else { break };
+            let (block_txs, block_receipts) = self.search_block(address, block_number).await?;
+            txs.extend(block_txs.into_iter().rev());
+            receipts.extend(block_receipts.into_iter().rev());
+        }
+
+        Ok(TransactionsWithReceipts { txs, receipts, first_page, last_page: blocks.is_empty() })
     }
 
     /// Handler for `searchTransactionsAfter`
     async fn search_transactions_after(
         &self,
-        _address: Address,
-        _block_number: BlockNumberOrTag,
-        _page_size: usize,
+        address: Address,
+        block_number: BlockNumberOrTag,
+        page_size: usize,
     ) -> RpcResult<TransactionsWithReceipts> {
-        Err(internal_rpc_err("unimplemented"))
+        let best = self.best_block_number()?;
+        // `0` is used by otterscan to request the oldest transactions
+        let (last_page, start) = match block_number {
+            BlockNumberOrTag::Number(0) | BlockNumberOrTag::Earliest => (true, 0),
+            BlockNumberOrTag::Number(number) => (false, number + 1),
+            _ => return Err(internal_rpc_err("unsupported block number")),
+        };
+
+        let mut blocks = self.candidate_blocks(address, start..best + 1)?.into_iter();
+        let mut txs = Vec::new();
+        let mut receipts = Vec::new();
+        // walk the candidate blocks forwards and only stop at block boundaries, so the block of
+        // the most recent returned transaction is where the next page continues
+        while txs.len() < page_size {
+            let Some(block_number) = blocks.next() else { break };
+            let (block_txs, block_receipts) = self.search_block(address, block_number).await?;
+            txs.extend(block_txs);
+            receipts.extend(block_receipts);
+        }
+
+        // results are always returned with the most recent transaction first
+        txs.reverse();
+        receipts.reverse();
+
+        let first_page = blocks.as_slice().is_empty();
+        Ok(TransactionsWithReceipts { txs, receipts, first_page, last_page })
     }
 
     /// Handler for `getTransactionBySenderAndNonce`
     async fn get_transaction_by_sender_and_nonce(
         &self,
-        _sender: Address,
-        _nonce: u64,
+        sender: Address,
+        nonce: u64,
     ) -> RpcResult<Option<Transaction>> {
-        Err(internal_rpc_err("unimplemented"))
+        let best = self.best_block_number()?;
+        let transaction_count = |number: u64| async move {
+            let count = EthApiServer::transaction_count(
+                &self.eth,
+                sender,
+                Some(BlockId::Number(BlockNumberOrTag::Number(number))),
+            )
+            .await?;
+            RpcResult::Ok(count.saturating_to::<u64>())
+        };
+
+        if transaction_count(best).await? <= nonce {
+            return Ok(None)
+        }
+
+        // find the block that increased the nonce of the sender past the requested nonce
+        let block_number = self
+            .find_first_block(best, |number| async move {
+                RpcResult::Ok(transaction_count(number).await? > nonce)
+            })
+            .await?;
+
+        let block =
+            EthApiServer::block_by_number(&self.eth, BlockNumberOrTag::Number(block_number), true)
+                .await?;
+        let Some(BlockTransactions::Full(transactions)) =
+            block.map(|block| block.inner.transactions)
+        else {
+            return Ok(None)
+        };
+
+        Ok(transactions.into_iter().find(|tx| tx.from == sender && tx.nonce == U64::from(nonce)))
     }
 
     /// Handler for `getContractCreator`
-    async fn get_contract_creator(&self, _address: Address) -> RpcResult<Option<ContractCreator>> {
-        Err(internal_rpc_err("unimplemented"))
+    async fn get_contract_creator(&self, address: Address) -> RpcResult<Option<ContractCreator>> {
+        if !self.has_code(address, None).await? {
+            return Ok(None)
+        }
+
+        // The current code is deployed by the most recent block that changed the account from an
+        // account without code. A contract that self-destructed and was deployed again has more
+        // than one such block, so the code history is not monotonic.
+        let best = self.best_block_number()?;
+        let mut deployment = None;
+        for block_number in self.candidate_blocks(address, 0..best + 1)?.into_iter().rev() {
+            let changeset =
+                self.provider.account_block_changeset(block_number).map_err(EthApiError::from)?;
+            let Some(before) = changeset.into_iter().find(|change| change.address == address)
+            else {
+                continue
+            };
+            let had_code = before
+                .info
+                .and_then(|account| account.bytecode_hash)
+                .map_or(false, |hash| hash != KECCAK_EMPTY);
+            if !had_code {
+                deployment = Some(block_number);
+                break
+            }
+        }
+        let Some(block_number) = deployment else { return Ok(None) };
+
+        let creations = self
+            .eth
+            .trace_block_with(
+                BlockId::Number(BlockNumberOrTag::Number(block_number)),
+                TracingInspectorConfig::default_parity(),
+                move |tx_info, inspector, _, _, _| {
+                    let creator = inspector.into_traces().into_nodes().into_iter().find_map(
+                        |CallTraceNode { trace, .. }| {
+                            (matches!(trace.kind, CallKind::Create | CallKind::Create2) &&
+                                trace.success &&
+                                trace.address == address)
+                                .then_some(trace.caller)
+                        },
+                    );
+                    Ok(creator.map(|creator| (tx_info.hash, creator)))
+                },
+            )
+            .await?
+            .unwrap_or_default();
+
+        // the code can be deployed more than once in the same block
+        let Some((Some(tx_hash), creator)) = creations.into_iter().flatten().last() else {
+            return Ok(None)
+        };
+        let Some(tx) = EthApiServer::transaction_by_hash(&self.eth, tx_hash).await? else {
+            return Ok(None)
+        };
+
+        Ok(Some(ContractCreator { tx, creator }))
     }
 }
@@ -1,5 +1,6 @@
 use crate::{
     eth::{
+        cache::TraceCache,
         error::{EthApiError, EthResult},
         revm_utils::{inspect, inspect_and_return_db, prepare_call_env, EvmOverrides},
         utils::recover_raw_transaction,
@@ -45,8 +46,13 @@ impl<Provider, Eth> TraceApi<Provider, Eth> {
     }
 
     /// Create a new instance of the [TraceApi]
-    pub fn new(provider: Provider, eth_api: Eth, blocking_task_guard: BlockingTaskGuard) -> Self {
-        let inner = Arc::new(TraceApiInner { provider, eth_api, blocking_task_guard });
+    pub fn new(
+        provider: Provider,
+        eth_api: Eth,
+        blocking_task_guard: BlockingTaskGuard,
+        trace_cache: TraceCache,
+    ) -> Self {
+        let inner = Arc::new(TraceApiInner { provider, eth_api, blocking_task_guard, trace_cache });
         Self { inner }
     }
 
@@ -72,10 +78,11 @@ where
         let overrides =
             EvmOverrides::new(trace_request.state_overrides, trace_request.block_overrides);
         let mut inspector = TracingInspector::new(config);
+        let evm_config = *self.inner.eth_api.evm_config();
         self.inner
             .eth_api
             .spawn_with_call_at(trace_request.call, at, overrides, move |db, env| {
-                let (res, _, db) = inspect_and_return_db(db, env, &mut inspector)?;
+                let (res, _, db) = inspect_and_return_db(db, env, &mut inspector, &evm_config)?;
                 let trace_res = inspector.into_parity_builder().into_trace_results_with_state(
                     &res,
                     &trace_request.trace_types,
@@ -130,6 +137,7 @@ where
         let (cfg, block_env, at) = self.inner.eth_api.evm_env_at(at).await?;
 
         let gas_limit = self.inner.eth_api.call_gas_limit();
+        let evm_config = *self.inner.eth_api.evm_config();
         // execute all transactions on top of each other and record the traces
         self.inner
             .eth_api
@@ -150,7 +158,7 @@ where
                     )?;
                     let config = TracingInspectorConfig::from_parity_config(&trace_types);
                     let mut inspector = TracingInspector::new(config);
-                    let (res, _) = inspect(&mut db, env, &mut inspector)?;
+                    let (res, _) = inspect(&mut db, env, &mut inspector, &evm_config)?;
 
                     let trace_res = inspector.into_parity_builder().into_trace_results_with_state(
                         &res,
@@ -240,7 +248,7 @@ where
         filter: TraceFilter,
     ) -> EthResult<Vec<LocalizedTransactionTrace>> {
         let matcher = filter.matcher();
-        let TraceFilter { from_block, to_block, after: _after, count: _count, .. } = filter;
+        let TraceFilter { from_block, to_block, after, count, .. } = filter;
         let start = from_block.unwrap_or(0);
         let end = if let Some(to_block) = to_block {
             to_block
@@ -248,6 +256,12 @@ where
             self.provider().best_block_number()?
         };
 
+        if start > end {
+            return Err(EthApiError::InvalidParams(
+                "invalid parameters: fromBlock cannot be greater than toBlock".to_string(),
+            ))
+        }
+
         // ensure that the range is not too large, since we need to fetch all blocks in the range
         let distance = end.saturating_sub(start);
         if distance > 100 {
@@ -307,6 +321,10 @@ where
             .into_iter()
             .flatten()
             .flat_map(|traces| traces.into_iter().flatten().flat_map(|traces| traces.into_iter()))
+            // apply the pagination of the filter: skip the first `after` traces and return at most
+            // `count` traces
+            .skip(after.unwrap_or_default() as usize)
+            .take(count.map(|count| count as usize).unwrap_or(usize::MAX))
             .collect();
 
         Ok(all_traces)
@@ -334,9 +352,43 @@ where
     }
 
     /// Returns traces created at given block.
+    ///
+    /// The traces of blocks that are requested by hash or number are cached by their hash, see
+    /// [TraceCache].
     pub async fn trace_block(
         &self,
         block_id: BlockId,
+    ) -> EthResult<Option<Vec<LocalizedTransactionTrace>>> {
+        let block_hash = if self.inner.trace_cache.is_enabled() {
+            match block_id {
+                BlockId::Hash(hash) => Some(hash.block_hash),
+                BlockId::Number(BlockNumberOrTag::Number(number)) => {
+                    self.provider().block_hash(number)?
+                }
+                _ => None,
+            }
+        } else {
+            None
+        };
+
+        let Some(block_hash) = block_hash else { return self.trace_block_uncached(block_id).await };
+
+        if let Some(traces) = self.inner.trace_cache.get_block_traces(block_hash) {
+            return Ok(Some(traces))
+        }
+
+        // trace the resolved block, in case the block of the number changes in the meantime
+        let maybe_traces = self.trace_block_uncached(block_hash.into()).await?;
+        if let Some(traces) = &maybe_traces {
+            self.inner.trace_cache.insert_block_traces(block_hash, traces.clone());
+        }
+        Ok(maybe_traces)
+    }
+
+    /// Returns traces created at given block, without looking up the [TraceCache].
+    async fn trace_block_uncached(
+        &self,
+        block_id: BlockId,
     ) -> EthResult<Option<Vec<LocalizedTransactionTrace>>> {
         let traces = self.inner.eth_api.trace_block_with(
             block_id,
@@ -547,6 +599,8 @@ struct TraceApiInner<Provider, Eth> {
     eth_api: Eth,
     // restrict the number of concurrent calls to `trace_*`
     blocking_task_guard: BlockingTaskGuard,
+    /// Cache for the results of `trace_block`
+    trace_cache: TraceCache,
 }
 
 /// Helper to construct a [`LocalizedTransactionTrace`] that describes a reward to the block
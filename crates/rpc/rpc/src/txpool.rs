@@ -31,6 +31,12 @@ where
     Pool: TransactionPool + 'static,
 {
     fn content(&self) -> TxpoolContent {
+        self.content_filtered(|_| true)
+    }
+
+    /// Returns the content of the pool, only including transactions of senders that match the
+    /// given filter.
+    fn content_filtered(&self, mut filter: impl FnMut(&Address) -> bool) -> TxpoolContent {
         #[inline]
         fn insert<T: PoolTransaction>(
             tx: &T,
@@ -46,10 +52,10 @@ where
         let AllPoolTransactions { pending, queued } = self.pool.all_transactions();
 
         let mut content = TxpoolContent::default();
-        for pending in pending {
+        for pending in pending.iter().filter(|tx| filter(&tx.sender())) {
             insert(&pending.transaction, &mut content.pending);
         }
-        for queued in queued {
+        for queued in queued.iter().filter(|tx| filter(&tx.sender())) {
             insert(&queued.transaction, &mut content.queued);
         }
 
@@ -126,16 +132,17 @@ where
     /// Handler for `txpool_contentFrom`
     async fn txpool_content_from(&self, from: Address) -> Result<TxpoolContentFrom> {
         trace!(target: "rpc::eth", ?from, "Serving txpool_contentFrom");
-        Ok(self.content().remove_from(&from))
+        // only convert the transactions of the requested sender
+        Ok(self.content_filtered(|sender| *sender == from).remove_from(&from))
     }
 
     /// Returns the details of all transactions currently pending for inclusion in the next
     /// block(s), as well as the ones that are being scheduled for future execution only.
     ///
     /// See [here](https://geth.ethereum.org/docs/rpc/ns-txpool#txpool_content) for more details
-    /// Handler for `txpool_inspect`
+    /// Handler for `txpool_content`
     async fn txpool_content(&self) -> Result<TxpoolContent> {
-        trace!(target: "rpc::eth", "Serving txpool_inspect");
+        trace!(target: "rpc::eth", "Serving txpool_content");
         Ok(self.content())
     }
 }
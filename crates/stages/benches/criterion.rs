@@ -51,7 +51,7 @@ fn senders(c: &mut Criterion) {
     group.sample_size(10);
 
     for batch in [1000usize, 10_000, 100_000, 250_000] {
-        let stage = SenderRecoveryStage { commit_threshold: DEFAULT_NUM_BLOCKS };
+        let stage = SenderRecoveryStage::new(DEFAULT_NUM_BLOCKS);
         let label = format!("SendersRecovery-batch-{batch}");
 
         measure_stage(&mut group, setup::stage_unwind, stage, 0..DEFAULT_NUM_BLOCKS, label);
@@ -95,7 +95,8 @@ fn merkle(c: &mut Criterion) {
     // don't need to run each stage for that many times
     group.sample_size(10);
 
-    let stage = MerkleStage::Both { clean_threshold: u64::MAX };
+    let stage =
+        MerkleStage::Both { clean_threshold: u64::MAX, trie_node_cache: Default::default() };
     measure_stage(
         &mut group,
         setup::unwind_hashes,
@@ -104,7 +105,7 @@ fn merkle(c: &mut Criterion) {
         "Merkle-incremental".to_string(),
     );
 
-    let stage = MerkleStage::Both { clean_threshold: 0 };
+    let stage = MerkleStage::Both { clean_threshold: 0, trie_node_cache: Default::default() };
     measure_stage(
         &mut group,
         setup::unwind_hashes,
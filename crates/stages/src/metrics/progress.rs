@@ -0,0 +1,54 @@
+use crate::{metrics::StageProgressMetrics, PipelineEvent, PipelineProgressStream, StageStatus};
+use futures_util::{ready, Stream};
+use pin_project::pin_project;
+use reth_primitives::stage::StageId;
+use std::{
+    collections::HashMap,
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+/// Metrics routine that records the updates of a [PipelineProgressStream], such as the estimated
+/// time until a stage reaches its target.
+#[pin_project]
+#[derive(Debug)]
+pub struct PipelineProgressMetrics<S> {
+    #[pin]
+    progress: PipelineProgressStream<S>,
+    stages: HashMap<StageId, StageProgressMetrics>,
+}
+
+impl<S> PipelineProgressMetrics<S> {
+    /// Creates a new [PipelineProgressMetrics] that records the updates of the given stream.
+    pub fn new(progress: PipelineProgressStream<S>) -> Self {
+        Self { progress, stages: HashMap::new() }
+    }
+}
+
+impl<S> Future for PipelineProgressMetrics<S>
+where
+    S: Stream<Item = PipelineEvent>,
+{
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut this = self.project();
+
+        loop {
+            let Some(progress) = ready!(this.progress.as_mut().poll_next(cx)) else {
+                // Stream has closed
+                return Poll::Ready(())
+            };
+
+            let metrics = this.stages.entry(progress.stage_id).or_insert_with(|| {
+                StageProgressMetrics::new_with_labels(&[("stage", progress.stage_id.to_string())])
+            });
+            let eta = match progress.status {
+                StageStatus::Running => progress.eta.unwrap_or_default(),
+                _ => Default::default(),
+            };
+            metrics.eta_seconds.set(eta.as_secs_f64());
+        }
+    }
+}
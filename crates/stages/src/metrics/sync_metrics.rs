@@ -28,6 +28,14 @@ pub(crate) struct StageMetrics {
     pub(crate) entities_total: Gauge,
 }
 
+/// Progress metrics of a stage, recorded from the pipeline progress stream.
+#[derive(Metrics)]
+#[metrics(scope = "sync")]
+pub(crate) struct StageProgressMetrics {
+    /// The estimated time in seconds until a running stage reaches its target, zero if unknown.
+    pub(crate) eta_seconds: Gauge,
+}
+
 /// Execution stage metrics.
 #[derive(Metrics)]
 #[metrics(scope = "sync.execution")]
@@ -21,12 +21,14 @@ mod ctrl;
 mod event;
 mod progress;
 mod set;
+mod status;
 
 pub use crate::pipeline::ctrl::ControlFlow;
 pub use builder::*;
 pub use event::*;
 use progress::*;
 pub use set::*;
+pub use status::*;
 
 /// A container for a queued stage.
 pub(crate) type BoxedStage<DB> = Box<dyn Stage<DB>>;
@@ -104,6 +106,11 @@ where
         self.listeners.new_listener()
     }
 
+    /// Listen for typed progress updates of the stages, derived from the [PipelineEvent]s.
+    pub fn progress(&mut self) -> PipelineProgressStream<UnboundedReceiverStream<PipelineEvent>> {
+        PipelineProgressStream::new(self.events())
+    }
+
     /// Registers progress metrics for each registered stage
     pub fn register_metrics(&mut self) -> Result<(), PipelineError> {
         let Some(metrics_tx) = &mut self.metrics_tx else { return Ok(()) };
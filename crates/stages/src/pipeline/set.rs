@@ -187,6 +187,21 @@ where
         self
     }
 
+    /// Disables all stages that are ordered before the given stage.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the stage is not in this set.
+    pub fn disable_all_before(mut self, stage_id: StageId) -> Self {
+        let index = self.index_of(stage_id);
+        for id in &self.order[..index] {
+            if let Some(entry) = self.stages.get_mut(id) {
+                entry.enabled = false;
+            }
+        }
+        self
+    }
+
     /// Disables the given stage if the given closure returns true.
     ///
     /// See [Self::disable]
@@ -0,0 +1,326 @@
+use super::{PipelineEvent, PipelineStagesProgress};
+use futures_util::{ready, Stream};
+use pin_project::pin_project;
+use reth_primitives::{
+    stage::{EntitiesCheckpoint, StageCheckpoint, StageId},
+    BlockNumber,
+};
+use std::{
+    fmt::{Display, Formatter},
+    pin::Pin,
+    task::{Context, Poll},
+    time::{Duration, Instant},
+};
+
+/// The status of a stage, as reported by a [PipelineProgressStream].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StageStatus {
+    /// The stage is running and has not reached its target yet.
+    Running,
+    /// The stage has reached its target.
+    Finished,
+    /// The stage is being unwound.
+    Unwinding,
+    /// The stage has been unwound.
+    Unwound,
+    /// The stage was skipped because its run conditions were not met.
+    Skipped,
+    /// The stage encountered an error either during execution or unwinding.
+    Failed,
+}
+
+/// A progress update of a single stage.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StageProgress {
+    /// The stage this update belongs to.
+    pub stage_id: StageId,
+    /// The status of the stage.
+    pub status: StageStatus,
+    /// The position of the stage in the pipeline, if the stage is executed.
+    pub stages_progress: Option<PipelineStagesProgress>,
+    /// The latest checkpoint of the stage, if known.
+    pub checkpoint: Option<StageCheckpoint>,
+    /// The block number the stage is running or unwinding to, if known.
+    pub target: Option<BlockNumber>,
+    /// The estimated time until the stage reaches its target, if known.
+    pub eta: Option<Duration>,
+}
+
+impl StageProgress {
+    /// Returns the number of processed and total entities of the stage, if the stage reports them.
+    pub fn entities(&self) -> Option<EntitiesCheckpoint> {
+        self.checkpoint?.entities()
+    }
+
+    /// Returns the number of executed and total transactions, if the stage is the execution stage.
+    pub fn transactions(&self) -> Option<EntitiesCheckpoint> {
+        Some(self.checkpoint?.execution_stage_checkpoint()?.transactions)
+    }
+}
+
+/// A container calculating the estimated time that a stage will complete in, based on stage
+/// checkpoints reported by the pipeline.
+///
+/// One `Eta` is only valid for a single stage.
+#[derive(Debug, Default, Copy, Clone)]
+pub struct Eta {
+    /// The last stage checkpoint
+    last_checkpoint: EntitiesCheckpoint,
+    /// The last time the stage reported its checkpoint
+    last_checkpoint_time: Option<Instant>,
+    /// The current ETA
+    eta: Option<Duration>,
+}
+
+impl Eta {
+    /// Update the ETA given the checkpoint, if possible.
+    pub fn update(&mut self, checkpoint: StageCheckpoint) {
+        let Some(current) = checkpoint.entities() else { return };
+
+        if let Some(last_checkpoint_time) = &self.last_checkpoint_time {
+            // the checkpoint can go backwards, e.g. after an unwind
+            let processed_since_last =
+                current.processed.saturating_sub(self.last_checkpoint.processed);
+            let elapsed = last_checkpoint_time.elapsed();
+            let per_second = processed_since_last as f64 / elapsed.as_secs_f64();
+
+            self.eta = Duration::try_from_secs_f64(
+                (current.total.saturating_sub(current.processed) as f64) / per_second,
+            )
+            .ok();
+        }
+
+        self.last_checkpoint = current;
+        self.last_checkpoint_time = Some(Instant::now());
+    }
+
+    /// Returns the remaining time until the stage completes, if it can be estimated.
+    pub fn remaining(&self) -> Option<Duration> {
+        let (eta, last_checkpoint_time) = self.eta.zip(self.last_checkpoint_time)?;
+        eta.checked_sub(last_checkpoint_time.elapsed())
+    }
+
+    /// Format ETA for a given stage.
+    ///
+    /// NOTE: Currently ETA is enabled only for the stages that have predictable progress.
+    /// It's not the case for network-dependent ([StageId::Headers] and [StageId::Bodies]) and
+    /// [StageId::Execution] stages.
+    pub fn fmt_for_stage(&self, stage: StageId) -> Option<String> {
+        if matches!(stage, StageId::Headers | StageId::Bodies | StageId::Execution) {
+            None
+        } else {
+            Some(self.to_string())
+        }
+    }
+}
+
+impl Display for Eta {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if let Some(remaining) = self.remaining() {
+            return write!(
+                f,
+                "{}",
+                humantime::format_duration(Duration::from_secs(remaining.as_secs()))
+            )
+        }
+
+        write!(f, "unknown")
+    }
+}
+
+/// The stage that is currently being executed or unwound.
+#[derive(Debug)]
+struct CurrentStage {
+    stage_id: StageId,
+    stages_progress: Option<PipelineStagesProgress>,
+    target: Option<BlockNumber>,
+    eta: Eta,
+}
+
+/// A stream of typed [StageProgress] updates, derived from a stream of [PipelineEvent]s.
+///
+/// Every event that changes the progress of a stage is turned into an update that contains the
+/// latest checkpoint, the target and the estimated time until the target is reached.
+#[pin_project]
+#[derive(Debug)]
+pub struct PipelineProgressStream<S> {
+    /// The pipeline events.
+    #[pin]
+    events: S,
+    /// The stage that is currently being executed or unwound.
+    current: Option<CurrentStage>,
+}
+
+impl<S> PipelineProgressStream<S> {
+    /// Creates a new progress stream from the given stream of [PipelineEvent]s.
+    pub fn new(events: S) -> Self {
+        Self { events, current: None }
+    }
+}
+
+impl CurrentStage {
+    fn new(
+        stage_id: StageId,
+        stages_progress: Option<PipelineStagesProgress>,
+        target: Option<BlockNumber>,
+    ) -> Self {
+        Self { stage_id, stages_progress, target, eta: Eta::default() }
+    }
+
+    fn progress(&self, status: StageStatus, checkpoint: Option<StageCheckpoint>) -> StageProgress {
+        StageProgress {
+            stage_id: self.stage_id,
+            status,
+            stages_progress: self.stages_progress.clone(),
+            checkpoint,
+            target: self.target,
+            eta: self.eta.remaining(),
+        }
+    }
+}
+
+/// Returns the current stage, replacing it with a new one if the event is for a different stage.
+fn current_stage(current: &mut Option<CurrentStage>, stage_id: StageId) -> &mut CurrentStage {
+    if current.as_ref().map_or(true, |stage| stage.stage_id != stage_id) {
+        *current = Some(CurrentStage::new(stage_id, None, None));
+    }
+    current.as_mut().expect("current stage is set")
+}
+
+/// Updates the current stage with the given event and returns the resulting progress update.
+fn on_event(current: &mut Option<CurrentStage>, event: PipelineEvent) -> StageProgress {
+    match event {
+        PipelineEvent::Run { pipeline_stages_progress, stage_id, checkpoint, target } => {
+            let stage = current_stage(current, stage_id);
+            stage.stages_progress = Some(pipeline_stages_progress);
+            stage.target = target;
+            if let Some(checkpoint) = checkpoint {
+                stage.eta.update(checkpoint);
+            }
+            stage.progress(StageStatus::Running, checkpoint)
+        }
+        PipelineEvent::Ran { pipeline_stages_progress, stage_id, result } => {
+            let stage = current_stage(current, stage_id);
+            stage.stages_progress = Some(pipeline_stages_progress);
+            stage.eta.update(result.checkpoint);
+            let status = if result.done { StageStatus::Finished } else { StageStatus::Running };
+            stage.progress(status, Some(result.checkpoint))
+        }
+        PipelineEvent::Unwind { stage_id, input } => {
+            let stage = current_stage(current, stage_id);
+            stage.stages_progress = None;
+            stage.target = Some(input.unwind_to);
+            stage.progress(StageStatus::Unwinding, Some(input.checkpoint))
+        }
+        PipelineEvent::Unwound { stage_id, result } => {
+            current_stage(current, stage_id).progress(StageStatus::Unwound, Some(result.checkpoint))
+        }
+        PipelineEvent::Error { stage_id } => {
+            CurrentStage::new(stage_id, None, None).progress(StageStatus::Failed, None)
+        }
+        PipelineEvent::Skipped { stage_id } => {
+            CurrentStage::new(stage_id, None, None).progress(StageStatus::Skipped, None)
+        }
+    }
+}
+
+impl<S> Stream for PipelineProgressStream<S>
+where
+    S: Stream<Item = PipelineEvent>,
+{
+    type Item = StageProgress;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.project();
+        let Some(event) = ready!(this.events.poll_next(cx)) else { return Poll::Ready(None) };
+        Poll::Ready(Some(on_event(this.current, event)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ExecOutput, UnwindInput, UnwindOutput};
+    use futures_util::StreamExt;
+
+    #[test]
+    fn eta_display_no_milliseconds() {
+        let eta = Eta {
+            last_checkpoint_time: Some(Instant::now()),
+            eta: Some(Duration::from_millis(
+                13 * 60 * 1000 + // Minutes
+                    37 * 1000 + // Seconds
+                    999, // Milliseconds
+            )),
+            ..Default::default()
+        }
+        .to_string();
+
+        assert_eq!(eta, "13m 37s");
+    }
+
+    #[test]
+    fn eta_checkpoint_going_backwards() {
+        let checkpoint = |processed, total| {
+            StageCheckpoint::new(0)
+                .with_entities_stage_checkpoint(EntitiesCheckpoint { processed, total })
+        };
+
+        let mut eta = Eta::default();
+        eta.update(checkpoint(100, 200));
+        // fewer entities processed than before, e.g. after an unwind
+        eta.update(checkpoint(50, 200));
+        assert_eq!(eta.eta, None);
+        // more entities processed than the total
+        eta.update(checkpoint(300, 200));
+        assert_eq!(eta.eta, Some(Duration::ZERO));
+    }
+
+    #[tokio::test]
+    async fn progress_from_events() {
+        let stages_progress = PipelineStagesProgress { current: 1, total: 2 };
+        let events = futures_util::stream::iter([
+            PipelineEvent::Run {
+                pipeline_stages_progress: stages_progress.clone(),
+                stage_id: StageId::Headers,
+                checkpoint: None,
+                target: Some(10),
+            },
+            PipelineEvent::Ran {
+                pipeline_stages_progress: stages_progress.clone(),
+                stage_id: StageId::Headers,
+                result: ExecOutput { checkpoint: StageCheckpoint::new(10), done: true },
+            },
+            PipelineEvent::Unwind {
+                stage_id: StageId::Headers,
+                input: UnwindInput {
+                    checkpoint: StageCheckpoint::new(10),
+                    unwind_to: 5,
+                    bad_block: None,
+                },
+            },
+            PipelineEvent::Unwound {
+                stage_id: StageId::Headers,
+                result: UnwindOutput { checkpoint: StageCheckpoint::new(5) },
+            },
+            PipelineEvent::Skipped { stage_id: StageId::Bodies },
+        ]);
+
+        let updates = PipelineProgressStream::new(events)
+            .map(|progress| {
+                (progress.stage_id, progress.status, progress.target, progress.checkpoint)
+            })
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(
+            updates,
+            vec![
+                (StageId::Headers, StageStatus::Running, Some(10), None),
+                (StageId::Headers, StageStatus::Finished, Some(10), Some(StageCheckpoint::new(10))),
+                (StageId::Headers, StageStatus::Unwinding, Some(5), Some(StageCheckpoint::new(10))),
+                (StageId::Headers, StageStatus::Unwound, Some(5), Some(StageCheckpoint::new(5))),
+                (StageId::Bodies, StageStatus::Skipped, None, None),
+            ]
+        );
+    }
+}
@@ -3,6 +3,7 @@ use crate::{
     MetricEvent, MetricEventsSender, Stage, StageError, UnwindInput, UnwindOutput,
 };
 use num_traits::Zero;
+use reth_codecs::Compact;
 use reth_db::{
     cursor::{DbCursorRO, DbCursorRW, DbDupCursorRO},
     database::Database,
@@ -13,13 +14,14 @@ use reth_db::{
 use reth_interfaces::db::DatabaseError;
 use reth_primitives::{
     stage::{
-        CheckpointBlockRange, EntitiesCheckpoint, ExecutionCheckpoint, StageCheckpoint, StageId,
+        CheckpointBlockRange, EntitiesCheckpoint, ExecutionCheckpoint, MerkleCheckpoint,
+        StageCheckpoint, StageId,
     },
     BlockNumber, Header, PruneModes, U256,
 };
 use reth_provider::{
     BlockReader, DatabaseProviderRW, ExecutorFactory, HeaderProvider, LatestStateProviderRef,
-    OriginalValuesKnown, ProviderError, TransactionVariant,
+    OriginalValuesKnown, ProviderError, StageCheckpointReader, TransactionVariant,
 };
 use std::{
     ops::RangeInclusive,
@@ -118,7 +120,17 @@ impl<EF: ExecutorFactory> ExecutionStage<EF> {
         }
 
         let start_block = input.next_block();
-        let max_block = input.target();
+        let mut max_block = input.target();
+        if let Some(rebuild_target) = interrupted_rebuild_target(provider)? {
+            let checkpoint = input.checkpoint().block_number;
+            if (checkpoint..max_block).contains(&rebuild_target) {
+                info!(target: "sync::stages::execution", checkpoint, rebuild_target, "Not executing past the target of an interrupted state rebuild");
+                if rebuild_target == checkpoint {
+                    return Ok(ExecOutput::done(input.checkpoint()))
+                }
+                max_block = rebuild_target;
+            }
+        }
         let prune_modes = self.adjust_prune_modes(provider, start_block, max_block)?;
 
         // Build executor
@@ -138,6 +150,7 @@ impl<EF: ExecutorFactory> ExecutionStage<EF> {
         // Execute block range
 
         let mut cumulative_gas = 0;
+        let batch_start = Instant::now();
 
         for block_number in start_block..=max_block {
             let time = Instant::now();
@@ -175,6 +188,7 @@ impl<EF: ExecutorFactory> ExecutionStage<EF> {
             stage_progress = block_number;
 
             stage_checkpoint.progress.processed += block.gas_used;
+            stage_checkpoint.transactions.processed += block.body.len() as u64;
 
             // Check if we should commit now
             let bundle_size_hint = executor.size_hint().unwrap_or_default() as u64;
@@ -182,6 +196,7 @@ impl<EF: ExecutorFactory> ExecutionStage<EF> {
                 block_number - start_block,
                 bundle_size_hint,
                 cumulative_gas,
+                batch_start.elapsed(),
             ) {
                 break
             }
@@ -243,42 +258,78 @@ impl<EF: ExecutorFactory> ExecutionStage<EF> {
     }
 }
 
+/// Returns the target block of an interrupted rebuild of the hashed state or the trie from
+/// scratch, if any.
+///
+/// The [`super::AccountHashingStage`], [`super::StorageHashingStage`] and [`super::MerkleStage`]
+/// save their progress within a rebuild, which is only valid as long as the block they're
+/// rebuilding up to doesn't change. Not executing past that block lets them resume where they were
+/// interrupted instead of starting over.
+fn interrupted_rebuild_target<DB: Database>(
+    provider: &DatabaseProviderRW<DB>,
+) -> Result<Option<BlockNumber>, StageError> {
+    let merkle =
+        provider.get_stage_checkpoint_progress(StageId::MerkleExecute)?.unwrap_or_default();
+    if !merkle.is_empty() {
+        let (checkpoint, _) = MerkleCheckpoint::from_compact(&merkle, merkle.len());
+        return Ok(Some(checkpoint.target_block))
+    }
+
+    let account_hashing = provider
+        .get_stage_checkpoint(StageId::AccountHashing)?
+        .and_then(|checkpoint| checkpoint.account_hashing_stage_checkpoint())
+        .filter(|checkpoint| checkpoint.address.is_some())
+        .map(|checkpoint| checkpoint.block_range.to);
+    let storage_hashing = provider
+        .get_stage_checkpoint(StageId::StorageHashing)?
+        .and_then(|checkpoint| checkpoint.storage_hashing_stage_checkpoint())
+        .filter(|checkpoint| checkpoint.address.is_some())
+        .map(|checkpoint| checkpoint.block_range.to);
+
+    Ok(account_hashing.into_iter().chain(storage_hashing).min())
+}
+
 fn execution_checkpoint<DB: Database>(
     provider: &DatabaseProviderRW<DB>,
     start_block: BlockNumber,
     max_block: BlockNumber,
     checkpoint: StageCheckpoint,
 ) -> Result<ExecutionCheckpoint, DatabaseError> {
+    let transactions = calculate_transactions_from_body_indices(provider, start_block, max_block)?;
+
     Ok(match checkpoint.execution_stage_checkpoint() {
         // If checkpoint block range fully matches our range,
         // we take the previously used stage checkpoint as-is.
         Some(stage_checkpoint @ ExecutionCheckpoint { block_range, .. })
             if block_range == CheckpointBlockRange::from(start_block..=max_block) =>
         {
-            stage_checkpoint
+            ExecutionCheckpoint { transactions, ..stage_checkpoint }
         }
         // If checkpoint block range precedes our range seamlessly, we take the previously used
         // stage checkpoint and add the amount of gas from our range to the checkpoint total.
         Some(ExecutionCheckpoint {
             block_range: CheckpointBlockRange { to, .. },
             progress: EntitiesCheckpoint { processed, total },
+            ..
         }) if to == start_block - 1 => ExecutionCheckpoint {
             block_range: CheckpointBlockRange { from: start_block, to: max_block },
             progress: EntitiesCheckpoint {
                 processed,
                 total: total + calculate_gas_used_from_headers(provider, start_block..=max_block)?,
             },
+            transactions,
         },
         // If checkpoint block range ends on the same block as our range, we take the previously
         // used stage checkpoint.
-        Some(ExecutionCheckpoint { block_range: CheckpointBlockRange { to, .. }, progress })
-            if to == max_block =>
-        {
-            ExecutionCheckpoint {
-                block_range: CheckpointBlockRange { from: start_block, to: max_block },
-                progress,
-            }
-        }
+        Some(ExecutionCheckpoint {
+            block_range: CheckpointBlockRange { to, .. },
+            progress,
+            ..
+        }) if to == max_block => ExecutionCheckpoint {
+            block_range: CheckpointBlockRange { from: start_block, to: max_block },
+            progress,
+            transactions,
+        },
         // If there's any other non-empty checkpoint, we calculate the remaining amount of total gas
         // to be processed not including the checkpoint range.
         Some(ExecutionCheckpoint { progress: EntitiesCheckpoint { processed, .. }, .. }) => {
@@ -291,6 +342,7 @@ fn execution_checkpoint<DB: Database>(
                     processed,
                     total: processed + after_checkpoint_block_number,
                 },
+                transactions,
             }
         }
         // Otherwise, we recalculate the whole stage checkpoint including the amount of gas
@@ -305,11 +357,34 @@ fn execution_checkpoint<DB: Database>(
                     total: processed +
                         calculate_gas_used_from_headers(provider, start_block..=max_block)?,
                 },
+                transactions,
             }
         }
     })
 }
 
+/// Returns the progress of the execution of the block range in transactions.
+///
+/// Unlike the gas, the transactions don't need to be summed up: the number of the first
+/// transaction of the start block is the number of transactions executed before the range.
+fn calculate_transactions_from_body_indices<DB: Database>(
+    provider: &DatabaseProviderRW<DB>,
+    start_block: BlockNumber,
+    max_block: BlockNumber,
+) -> Result<EntitiesCheckpoint, DatabaseError> {
+    let tx = provider.tx_ref();
+    let processed = tx
+        .get::<tables::BlockBodyIndices>(start_block)?
+        .map(|indices| indices.first_tx_num())
+        .unwrap_or_default();
+    let total = tx
+        .get::<tables::BlockBodyIndices>(max_block)?
+        .map(|indices| indices.next_tx_num())
+        .unwrap_or(processed);
+
+    Ok(EntitiesCheckpoint { processed, total })
+}
+
 fn calculate_gas_used_from_headers<DB: Database>(
     provider: &DatabaseProviderRW<DB>,
     range: RangeInclusive<BlockNumber>,
@@ -432,7 +507,9 @@ impl<EF: ExecutorFactory, DB: Database> Stage<DB> for ExecutionStage<EF> {
             }
         }
 
-        let checkpoint = if let Some(stage_checkpoint) = stage_checkpoint {
+        let checkpoint = if let Some(mut stage_checkpoint) = stage_checkpoint {
+            stage_checkpoint.transactions.processed = first_tx_num;
+
             StageCheckpoint::new(unwind_to).with_execution_stage_checkpoint(stage_checkpoint)
         } else {
             StageCheckpoint::new(unwind_to)
@@ -444,11 +521,8 @@ impl<EF: ExecutorFactory, DB: Database> Stage<DB> for ExecutionStage<EF> {
 
 /// The thresholds at which the execution stage writes state changes to the database.
 ///
-/// If either of the thresholds (`max_blocks` and `max_changes`) are hit, then the execution stage
-/// commits all pending changes to the database.
-///
-/// A third threshold, `max_changesets`, can be set to periodically write changesets to the
-/// current database transaction, which frees up memory.
+/// If either of the thresholds (`max_blocks`, `max_changes`, `max_cumulative_gas` and
+/// `max_duration`) are hit, then the execution stage commits all pending changes to the database.
 #[derive(Debug, Clone)]
 pub struct ExecutionStageThresholds {
     /// The maximum number of blocks to process before the execution stage commits.
@@ -457,6 +531,8 @@ pub struct ExecutionStageThresholds {
     pub max_changes: Option<u64>,
     /// The maximum amount of cumultive gas used in the batch.
     pub max_cumulative_gas: Option<u64>,
+    /// The maximum time spent on executing blocks in the batch.
+    pub max_duration: Option<Duration>,
 }
 
 impl Default for ExecutionStageThresholds {
@@ -466,6 +542,8 @@ impl Default for ExecutionStageThresholds {
             max_changes: Some(5_000_000),
             // 30M gas per block on 50k blocks
             max_cumulative_gas: Some(30_000_000 * 50_000),
+            // 10 minutes
+            max_duration: Some(Duration::from_secs(10 * 60)),
         }
     }
 }
@@ -478,10 +556,12 @@ impl ExecutionStageThresholds {
         blocks_processed: u64,
         changes_processed: u64,
         cumulative_gas_used: u64,
+        elapsed: Duration,
     ) -> bool {
         blocks_processed >= self.max_blocks.unwrap_or(u64::MAX) ||
             changes_processed >= self.max_changes.unwrap_or(u64::MAX) ||
-            cumulative_gas_used >= self.max_cumulative_gas.unwrap_or(u64::MAX)
+            cumulative_gas_used >= self.max_cumulative_gas.unwrap_or(u64::MAX) ||
+            elapsed >= self.max_duration.unwrap_or(Duration::MAX)
     }
 }
 
@@ -494,10 +574,16 @@ mod tests {
     use reth_db::{models::AccountBeforeTx, test_utils::create_test_rw_db};
     use reth_interfaces::executor::BlockValidationError;
     use reth_primitives::{
-        address, hex_literal::hex, keccak256, stage::StageUnitCheckpoint, Account, Bytecode,
-        ChainSpecBuilder, PruneModes, SealedBlock, StorageEntry, B256, MAINNET, U256,
+        address,
+        hex_literal::hex,
+        keccak256,
+        stage::{AccountHashingCheckpoint, StageUnitCheckpoint},
+        Account, Bytecode, ChainSpecBuilder, PruneModes, SealedBlock, StorageEntry, B256, MAINNET,
+        U256,
+    };
+    use reth_provider::{
+        AccountReader, BlockWriter, ProviderFactory, ReceiptProvider, StageCheckpointWriter,
     };
-    use reth_provider::{AccountReader, BlockWriter, ProviderFactory, ReceiptProvider};
     use reth_revm::EvmProcessorFactory;
     use std::sync::Arc;
 
@@ -511,12 +597,75 @@ mod tests {
                 max_blocks: Some(100),
                 max_changes: None,
                 max_cumulative_gas: None,
+                max_duration: None,
             },
             MERKLE_STAGE_DEFAULT_CLEAN_THRESHOLD,
             PruneModes::none(),
         )
     }
 
+    #[test]
+    fn thresholds_end_of_batch() {
+        let thresholds = ExecutionStageThresholds {
+            max_blocks: Some(10),
+            max_changes: Some(100),
+            max_cumulative_gas: Some(1_000),
+            max_duration: Some(Duration::from_secs(60)),
+        };
+        assert!(!thresholds.is_end_of_batch(9, 99, 999, Duration::from_secs(59)));
+        assert!(thresholds.is_end_of_batch(10, 0, 0, Duration::ZERO));
+        assert!(thresholds.is_end_of_batch(0, 100, 0, Duration::ZERO));
+        assert!(thresholds.is_end_of_batch(0, 0, 1_000, Duration::ZERO));
+        assert!(thresholds.is_end_of_batch(0, 0, 0, Duration::from_secs(60)));
+
+        let unbounded = ExecutionStageThresholds {
+            max_blocks: None,
+            max_changes: None,
+            max_cumulative_gas: None,
+            max_duration: None,
+        };
+        assert!(!unbounded.is_end_of_batch(
+            u64::MAX - 1,
+            u64::MAX - 1,
+            u64::MAX - 1,
+            Duration::from_secs(u32::MAX as u64),
+        ));
+    }
+
+    #[test]
+    fn execution_stops_at_interrupted_rebuild_target() {
+        let state_db = create_test_rw_db();
+        let factory = ProviderFactory::new(state_db.as_ref(), MAINNET.clone());
+        let provider = factory.provider_rw().unwrap();
+        assert_eq!(interrupted_rebuild_target(&provider).unwrap(), None);
+
+        provider
+            .save_stage_checkpoint(
+                StageId::AccountHashing,
+                StageCheckpoint::new(0).with_account_hashing_stage_checkpoint(
+                    AccountHashingCheckpoint {
+                        address: Some(address!("0000000000000000000000000000000000000001")),
+                        block_range: CheckpointBlockRange { from: 1, to: 2 },
+                        ..Default::default()
+                    },
+                ),
+            )
+            .unwrap();
+        assert_eq!(interrupted_rebuild_target(&provider).unwrap(), Some(2));
+
+        let mut buf = Vec::new();
+        MerkleCheckpoint::new(1, B256::ZERO, Vec::new(), Default::default()).to_compact(&mut buf);
+        provider.save_stage_checkpoint_progress(StageId::MerkleExecute, buf).unwrap();
+        assert_eq!(interrupted_rebuild_target(&provider).unwrap(), Some(1));
+
+        // the executed blocks up to the target of the rebuild are not changed
+        let input = ExecInput { target: Some(10), checkpoint: Some(StageCheckpoint::new(1)) };
+        assert_eq!(
+            stage().execute_inner(&provider, input).unwrap(),
+            ExecOutput::done(StageCheckpoint::new(1))
+        );
+    }
+
     #[test]
     fn execution_checkpoint_matches() {
         let state_db = create_test_rw_db();
@@ -526,6 +675,7 @@ mod tests {
         let previous_stage_checkpoint = ExecutionCheckpoint {
             block_range: CheckpointBlockRange { from: 0, to: 0 },
             progress: EntitiesCheckpoint { processed: 1, total: 2 },
+            transactions: EntitiesCheckpoint::default(),
         };
         let previous_checkpoint = StageCheckpoint {
             block_number: 0,
@@ -567,6 +717,7 @@ mod tests {
         let previous_stage_checkpoint = ExecutionCheckpoint {
             block_range: CheckpointBlockRange { from: 0, to: 0 },
             progress: EntitiesCheckpoint { processed: 1, total: 1 },
+            transactions: EntitiesCheckpoint { processed: 0, total: 0 },
         };
         let previous_checkpoint = StageCheckpoint {
             block_number: 1,
@@ -581,7 +732,8 @@ mod tests {
             progress: EntitiesCheckpoint {
                 processed,
                 total
-            }
+            },
+            transactions: EntitiesCheckpoint { processed: 0, total: 1 }
         }) if processed == previous_stage_checkpoint.progress.processed &&
             total == previous_stage_checkpoint.progress.total + block.gas_used);
     }
@@ -603,6 +755,7 @@ mod tests {
         let previous_stage_checkpoint = ExecutionCheckpoint {
             block_range: CheckpointBlockRange { from: 0, to: 0 },
             progress: EntitiesCheckpoint { processed: 1, total: 1 },
+            transactions: EntitiesCheckpoint { processed: 0, total: 0 },
         };
         let previous_checkpoint = StageCheckpoint {
             block_number: 1,
@@ -617,7 +770,8 @@ mod tests {
             progress: EntitiesCheckpoint {
                 processed,
                 total
-            }
+            },
+            transactions: EntitiesCheckpoint { processed: 0, total: 1 }
         }) if processed == previous_stage_checkpoint.progress.processed &&
             total == previous_stage_checkpoint.progress.total + block.gas_used);
     }
@@ -646,7 +800,8 @@ mod tests {
             progress: EntitiesCheckpoint {
                 processed: 0,
                 total
-            }
+            },
+            transactions: EntitiesCheckpoint { processed: 0, total: 1 }
         }) if total == block.gas_used);
     }
 
@@ -705,7 +860,8 @@ mod tests {
                     progress: EntitiesCheckpoint {
                         processed,
                         total
-                    }
+                    },
+                    transactions: EntitiesCheckpoint { processed: 1, total: 1 }
                 }))
             },
             done: true
@@ -816,7 +972,8 @@ mod tests {
                     progress: EntitiesCheckpoint {
                         processed: 0,
                         total
-                    }
+                    },
+                    transactions: EntitiesCheckpoint { processed: 0, total: 1 }
                 }))
             }
         } if total == block.gas_used);
@@ -0,0 +1,132 @@
+use crate::{ExecInput, ExecOutput, Stage, StageError, UnwindInput, UnwindOutput};
+use reth_db::database::Database;
+use reth_primitives::stage::{StageCheckpoint, StageId};
+use reth_provider::{DatabaseProviderRW, LogIndexWriter};
+
+/// Stage is indexing the addresses and topics of the logs in the receipts generated in
+/// [`ExecutionStage`][crate::stages::ExecutionStage], see [`reth_db::tables::LogAddressHistory`]
+/// and [`reth_db::tables::LogTopicHistory`].
+///
+/// The log index is optional and allows `eth_getLogs` to skip the blocks without matching logs.
+/// Blocks whose receipts are pruned are not indexed.
+#[derive(Debug)]
+pub struct IndexLogsStage {
+    /// Number of blocks after which the control
+    /// flow will be returned to the pipeline for commit.
+    pub commit_threshold: u64,
+}
+
+impl IndexLogsStage {
+    /// Create new instance of [IndexLogsStage].
+    pub fn new(commit_threshold: u64) -> Self {
+        Self { commit_threshold }
+    }
+}
+
+impl Default for IndexLogsStage {
+    fn default() -> Self {
+        Self { commit_threshold: 100_000 }
+    }
+}
+
+impl<DB: Database> Stage<DB> for IndexLogsStage {
+    /// Return the id of the stage
+    fn id(&self) -> StageId {
+        StageId::IndexLogs
+    }
+
+    /// Execute the stage.
+    fn execute(
+        &mut self,
+        provider: &DatabaseProviderRW<DB>,
+        input: ExecInput,
+    ) -> Result<ExecOutput, StageError> {
+        if input.target_reached() {
+            return Ok(ExecOutput::done(input.checkpoint()))
+        }
+
+        let (range, is_final_range) = input.next_block_range_with_threshold(self.commit_threshold);
+
+        provider.insert_log_index(range.clone())?;
+
+        Ok(ExecOutput { checkpoint: StageCheckpoint::new(*range.end()), done: is_final_range })
+    }
+
+    /// Unwind the stage.
+    fn unwind(
+        &mut self,
+        provider: &DatabaseProviderRW<DB>,
+        input: UnwindInput,
+    ) -> Result<UnwindOutput, StageError> {
+        let (range, unwind_progress, _) =
+            input.unwind_block_range_with_threshold(self.commit_threshold);
+
+        provider.unwind_log_index(range)?;
+
+        Ok(UnwindOutput { checkpoint: StageCheckpoint::new(unwind_progress) })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::TestStageDB;
+    use reth_interfaces::test_utils::{
+        generators,
+        generators::{random_block_range, random_log, random_receipt},
+    };
+    use reth_primitives::{Address, B256};
+    use reth_provider::LogIndexReader;
+
+    #[test]
+    fn execute_and_unwind() {
+        let db = TestStageDB::default();
+        let mut rng = generators::rng();
+
+        let blocks = random_block_range(&mut rng, 0..=3, B256::ZERO, 1..2);
+        db.insert_blocks(blocks.iter(), None).expect("insert blocks");
+
+        // block 1 has a log of the address, block 2 a log with the topic and block 3 both
+        let (address, topic) = (Address::random(), B256::random());
+        let receipts = blocks
+            .iter()
+            .enumerate()
+            .map(|(tx_num, block)| {
+                let mut receipt = random_receipt(&mut rng, &block.body[0], Some(0));
+                let mut log = random_log(&mut rng, None, Some(1));
+                if matches!(block.number, 1 | 3) {
+                    log.address = address;
+                }
+                if matches!(block.number, 2 | 3) {
+                    log.topics.push(topic);
+                }
+                receipt.logs.push(log);
+                (tx_num as u64, receipt)
+            })
+            .collect::<Vec<_>>();
+        db.insert_receipts(receipts).expect("insert receipts");
+
+        let mut stage = IndexLogsStage::default();
+        let provider = db.factory.provider_rw().unwrap();
+        let output = stage
+            .execute(&provider, ExecInput { target: Some(3), checkpoint: None })
+            .expect("execute");
+        assert_eq!(output, ExecOutput { checkpoint: StageCheckpoint::new(3), done: true });
+
+        assert_eq!(provider.blocks_with_log_address(address, 0..=3).unwrap(), vec![1, 3]);
+        assert_eq!(provider.blocks_with_log_address(address, 2..=3).unwrap(), vec![3]);
+        assert_eq!(provider.blocks_with_log_topic(topic, 0..=3).unwrap(), vec![2, 3]);
+        assert_eq!(provider.blocks_with_log_topic(topic, 0..=1).unwrap(), Vec::<u64>::new());
+
+        let output = stage
+            .unwind(
+                &provider,
+                UnwindInput { checkpoint: output.checkpoint, unwind_to: 1, bad_block: None },
+            )
+            .expect("unwind");
+        assert_eq!(output, UnwindOutput { checkpoint: StageCheckpoint::new(1) });
+
+        assert_eq!(provider.blocks_with_log_address(address, 0..=3).unwrap(), vec![1]);
+        assert_eq!(provider.blocks_with_log_topic(topic, 0..=3).unwrap(), Vec::<u64>::new());
+    }
+}
@@ -0,0 +1,117 @@
+use crate::{ExecInput, ExecOutput, Stage, StageError, UnwindInput, UnwindOutput};
+use reth_db::database::Database;
+use reth_primitives::stage::{StageCheckpoint, StageId};
+use reth_provider::{DatabaseProviderRW, TransactionAddressIndexWriter};
+
+/// Stage is indexing the senders, recipients and created contracts of the transactions in the
+/// bodies downloaded in [`BodyStage`][crate::stages::BodyStage], see
+/// [`reth_db::tables::TransactionAddressHistory`].
+///
+/// The transaction address index is optional. Together with the account history, it allows the
+/// `ots_` transaction search methods to skip the blocks the address is not involved in.
+#[derive(Debug)]
+pub struct IndexTransactionAddressesStage {
+    /// Number of blocks after which the control
+    /// flow will be returned to the pipeline for commit.
+    pub commit_threshold: u64,
+}
+
+impl IndexTransactionAddressesStage {
+    /// Create new instance of [IndexTransactionAddressesStage].
+    pub fn new(commit_threshold: u64) -> Self {
+        Self { commit_threshold }
+    }
+}
+
+impl Default for IndexTransactionAddressesStage {
+    fn default() -> Self {
+        Self { commit_threshold: 100_000 }
+    }
+}
+
+impl<DB: Database> Stage<DB> for IndexTransactionAddressesStage {
+    /// Return the id of the stage
+    fn id(&self) -> StageId {
+        StageId::IndexTransactionAddresses
+    }
+
+    /// Execute the stage.
+    fn execute(
+        &mut self,
+        provider: &DatabaseProviderRW<DB>,
+        input: ExecInput,
+    ) -> Result<ExecOutput, StageError> {
+        if input.target_reached() {
+            return Ok(ExecOutput::done(input.checkpoint()))
+        }
+
+        let (range, is_final_range) = input.next_block_range_with_threshold(self.commit_threshold);
+
+        provider.insert_transaction_address_index(range.clone())?;
+
+        Ok(ExecOutput { checkpoint: StageCheckpoint::new(*range.end()), done: is_final_range })
+    }
+
+    /// Unwind the stage.
+    fn unwind(
+        &mut self,
+        provider: &DatabaseProviderRW<DB>,
+        input: UnwindInput,
+    ) -> Result<UnwindOutput, StageError> {
+        let (range, unwind_progress, _) =
+            input.unwind_block_range_with_threshold(self.commit_threshold);
+
+        provider.unwind_transaction_address_index(range)?;
+
+        Ok(UnwindOutput { checkpoint: StageCheckpoint::new(unwind_progress) })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::TestStageDB;
+    use reth_interfaces::test_utils::{generators, generators::random_block_range};
+    use reth_primitives::B256;
+    use reth_provider::TransactionAddressIndexReader;
+
+    #[test]
+    fn execute_and_unwind() {
+        let db = TestStageDB::default();
+        let mut rng = generators::rng();
+
+        // the senders are not inserted, so the stage has to recover them
+        let blocks = random_block_range(&mut rng, 0..=3, B256::ZERO, 1..2);
+        db.insert_blocks(blocks.iter(), None).expect("insert blocks");
+        let sender = blocks[1].body[0].recover_signer().unwrap();
+        let recipient = blocks[3].body[0].to().unwrap();
+
+        let mut stage = IndexTransactionAddressesStage::default();
+        let provider = db.factory.provider_rw().unwrap();
+        let output = stage
+            .execute(&provider, ExecInput { target: Some(3), checkpoint: None })
+            .expect("execute");
+        assert_eq!(output, ExecOutput { checkpoint: StageCheckpoint::new(3), done: true });
+
+        assert_eq!(provider.blocks_with_transaction_address(sender, 0..=3).unwrap(), vec![1]);
+        assert_eq!(provider.blocks_with_transaction_address(recipient, 0..=3).unwrap(), vec![3]);
+        assert_eq!(
+            provider.blocks_with_transaction_address(recipient, 0..=2).unwrap(),
+            Vec::<u64>::new()
+        );
+
+        let output = stage
+            .unwind(
+                &provider,
+                UnwindInput { checkpoint: output.checkpoint, unwind_to: 2, bad_block: None },
+            )
+            .expect("unwind");
+        assert_eq!(output, UnwindOutput { checkpoint: StageCheckpoint::new(2) });
+
+        assert_eq!(provider.blocks_with_transaction_address(sender, 0..=3).unwrap(), vec![1]);
+        assert_eq!(
+            provider.blocks_with_transaction_address(recipient, 0..=3).unwrap(),
+            Vec::<u64>::new()
+        );
+    }
+}
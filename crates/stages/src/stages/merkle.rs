@@ -15,7 +15,10 @@ use reth_primitives::{
 use reth_provider::{
     DatabaseProviderRW, HeaderProvider, ProviderError, StageCheckpointReader, StageCheckpointWriter,
 };
-use reth_trie::{IntermediateStateRootState, StateRoot, StateRootProgress};
+use reth_trie::{
+    trie_cursor::{CachedTrieCursorFactory, TrieNodeCache},
+    IntermediateStateRootState, StateRoot, StateRootProgress,
+};
 use std::fmt::Debug;
 use tracing::*;
 
@@ -51,6 +54,8 @@ pub enum MerkleStage {
         /// The threshold (in number of blocks) for switching from incremental trie building
         /// of changes to whole rebuild.
         clean_threshold: u64,
+        /// The trie nodes shared between consecutive incremental trie updates.
+        trie_node_cache: TrieNodeCache,
     },
     /// The unwind portion of the merkle stage.
     Unwind,
@@ -60,13 +65,15 @@ pub enum MerkleStage {
         /// The threshold (in number of blocks) for switching from incremental trie building
         /// of changes to whole rebuild.
         clean_threshold: u64,
+        /// The trie nodes shared between consecutive incremental trie updates.
+        trie_node_cache: TrieNodeCache,
     },
 }
 
 impl MerkleStage {
     /// Stage default for the [MerkleStage::Execution].
     pub fn default_execution() -> Self {
-        Self::Execution { clean_threshold: MERKLE_STAGE_DEFAULT_CLEAN_THRESHOLD }
+        Self::new_execution(MERKLE_STAGE_DEFAULT_CLEAN_THRESHOLD)
     }
 
     /// Stage default for the [MerkleStage::Unwind].
@@ -76,7 +83,7 @@ impl MerkleStage {
 
     /// Create new instance of [MerkleStage::Execution].
     pub fn new_execution(clean_threshold: u64) -> Self {
-        Self::Execution { clean_threshold }
+        Self::Execution { clean_threshold, trie_node_cache: TrieNodeCache::default() }
     }
 
     /// Gets the hashing progress
@@ -132,14 +139,18 @@ impl<DB: Database> Stage<DB> for MerkleStage {
         provider: &DatabaseProviderRW<DB>,
         input: ExecInput,
     ) -> Result<ExecOutput, StageError> {
-        let threshold = match self {
+        let (threshold, trie_node_cache) = match self {
             MerkleStage::Unwind => {
                 info!(target: "sync::stages::merkle::unwind", "Stage is always skipped");
                 return Ok(ExecOutput::done(StageCheckpoint::new(input.target())))
             }
-            MerkleStage::Execution { clean_threshold } => *clean_threshold,
+            MerkleStage::Execution { clean_threshold, trie_node_cache } => {
+                (*clean_threshold, trie_node_cache.clone())
+            }
             #[cfg(any(test, feature = "test-utils"))]
-            MerkleStage::Both { clean_threshold } => *clean_threshold,
+            MerkleStage::Both { clean_threshold, trie_node_cache } => {
+                (*clean_threshold, trie_node_cache.clone())
+            }
         };
 
         let range = input.next_block_range();
@@ -179,6 +190,7 @@ impl<DB: Database> Stage<DB> for MerkleStage {
                 );
                 // Reset the checkpoint and clear trie tables
                 checkpoint = None;
+                trie_node_cache.clear();
                 self.save_execution_checkpoint(provider, None)?;
                 provider.tx_ref().clear::<tables::AccountsTrie>()?;
                 provider.tx_ref().clear::<tables::StoragesTrie>()?;
@@ -195,6 +207,7 @@ impl<DB: Database> Stage<DB> for MerkleStage {
             let tx = provider.tx_ref();
             let progress = StateRoot::from_tx(tx)
                 .with_intermediate_state(checkpoint.map(IntermediateStateRootState::from))
+                .with_parallel_storage_roots(true)
                 .root_with_progress()
                 .map_err(|e| StageError::Fatal(Box::new(e)))?;
             match progress {
@@ -228,10 +241,32 @@ impl<DB: Database> Stage<DB> for MerkleStage {
             }
         } else {
             debug!(target: "sync::stages::merkle::exec", current = ?current_block_number, target = ?to_block, "Updating trie");
-            let (root, updates) =
-                StateRoot::incremental_root_with_updates(provider.tx_ref(), range)
-                    .map_err(|e| StageError::Fatal(Box::new(e)))?;
-            updates.flush(provider.tx_ref())?;
+            let tx = provider.tx_ref();
+
+            // The cached trie nodes are only valid if they belong to the trie of the block
+            // preceding the range. The trie tables are also written outside of this stage, e.g. by
+            // the blockchain tree or on unwind, so this is keyed by the state root rather than the
+            // block number.
+            let parent_state_root = provider
+                .header_by_number(from_block - 1)?
+                .ok_or_else(|| ProviderError::HeaderNotFound((from_block - 1).into()))?
+                .state_root;
+            trie_node_cache.retain_if_state_root(parent_state_root);
+
+            let calculator = StateRoot::incremental_root_calculator(tx, range)
+                .map_err(|e| StageError::Fatal(Box::new(e)))?;
+            let account_prefixes = calculator.changed_account_prefixes.clone();
+            let storage_prefixes = calculator.changed_storage_prefixes.clone();
+            let destroyed_accounts = calculator.destroyed_accounts.clone();
+            let (root, updates) = calculator
+                .with_parallel_storage_roots(true)
+                .with_trie_cursor_factory(CachedTrieCursorFactory::new(tx, trie_node_cache.clone()))
+                .root_with_updates()
+                .map_err(|e| StageError::Fatal(Box::new(e)))?;
+            updates.flush(tx)?;
+
+            trie_node_cache.invalidate(&account_prefixes, &storage_prefixes, &destroyed_accounts);
+            trie_node_cache.set_state_root(root);
 
             let total_hashed_entries = (provider.tx_ref().entries::<tables::HashedAccount>()? +
                 provider.tx_ref().entries::<tables::HashedStorage>()?)
@@ -273,6 +308,11 @@ impl<DB: Database> Stage<DB> for MerkleStage {
             return Ok(UnwindOutput { checkpoint: StageCheckpoint::new(input.unwind_to) })
         }
 
+        #[cfg(any(test, feature = "test-utils"))]
+        if let MerkleStage::Both { trie_node_cache, .. } = self {
+            trie_node_cache.clear();
+        }
+
         let mut entities_checkpoint =
             input.checkpoint.entities_stage_checkpoint().unwrap_or(EntitiesCheckpoint {
                 processed: 0,
@@ -294,7 +334,10 @@ impl<DB: Database> Stage<DB> for MerkleStage {
 
         // Unwind trie only if there are transitions
         if !range.is_empty() {
-            let (block_root, updates) = StateRoot::incremental_root_with_updates(tx, range)
+            let (block_root, updates) = StateRoot::incremental_root_calculator(tx, range)
+                .and_then(|calculator| {
+                    calculator.with_parallel_storage_roots(true).root_with_updates()
+                })
                 .map_err(|e| StageError::Fatal(Box::new(e)))?;
 
             // Validate the calulated state root
@@ -462,7 +505,10 @@ mod tests {
         }
 
         fn stage(&self) -> Self::S {
-            Self::S::Both { clean_threshold: self.clean_threshold }
+            Self::S::Both {
+                clean_threshold: self.clean_threshold,
+                trie_node_cache: TrieNodeCache::default(),
+            }
         }
     }
 
@@ -12,8 +12,12 @@ mod hashing_storage;
 mod headers;
 /// Index history of account changes
 mod index_account_history;
+/// Index of the addresses and topics of logs
+mod index_logs;
 /// Index history of storage changes
 mod index_storage_history;
+/// Index of the addresses involved in transactions
+mod index_transaction_addresses;
 /// Stage for computing state root.
 mod merkle;
 /// The sender recovery stage.
@@ -30,7 +34,9 @@ pub use hashing_account::*;
 pub use hashing_storage::*;
 pub use headers::*;
 pub use index_account_history::*;
+pub use index_logs::*;
 pub use index_storage_history::*;
+pub use index_transaction_addresses::*;
 pub use merkle::*;
 pub use sender_recovery::*;
 pub use total_difficulty::*;
@@ -135,6 +141,7 @@ mod tests {
                     max_blocks: Some(100),
                     max_changes: None,
                     max_cumulative_gas: None,
+                    max_duration: None,
                 },
                 MERKLE_STAGE_DEFAULT_CLEAN_THRESHOLD,
                 prune_modes.clone(),
@@ -1,5 +1,6 @@
 use crate::{BlockErrorKind, ExecInput, ExecOutput, Stage, StageError, UnwindInput, UnwindOutput};
 use itertools::Itertools;
+use rayon::{ThreadPool, ThreadPoolBuilder};
 use reth_db::{
     cursor::{DbCursorRO, DbCursorRW},
     database::Database,
@@ -16,30 +17,76 @@ use reth_primitives::{
 use reth_provider::{
     BlockReader, DatabaseProviderRW, HeaderProvider, ProviderError, PruneCheckpointReader,
 };
-use std::{fmt::Debug, sync::mpsc};
+use std::{
+    fmt::Debug,
+    sync::{mpsc, Arc},
+};
 use thiserror::Error;
 use tracing::*;
 
 /// The sender recovery stage iterates over existing transactions,
 /// recovers the transaction signer and stores them
 /// in [`TxSenders`][reth_db::tables::TxSenders] table.
+///
+/// The transactions are split into batches that are recovered in parallel by a pool of workers,
+/// the recovered senders are written in the order of the transactions.
 #[derive(Clone, Debug)]
 pub struct SenderRecoveryStage {
     /// The size of inserted items after which the control
     /// flow will be returned to the pipeline for commit
     pub commit_threshold: u64,
+    /// The number of workers recovering senders, the global rayon thread pool is used if unset.
+    parallelism: Option<usize>,
+    /// The number of transactions recovered by a single worker job, the transactions are divided
+    /// evenly across the workers if unset.
+    batch_size: Option<usize>,
+    /// The dedicated thread pool of the workers, built on first use if the parallelism is set.
+    pool: Option<Arc<ThreadPool>>,
 }
 
 impl SenderRecoveryStage {
     /// Create new instance of [SenderRecoveryStage].
     pub fn new(commit_threshold: u64) -> Self {
-        Self { commit_threshold }
+        Self { commit_threshold, parallelism: None, batch_size: None, pool: None }
+    }
+
+    /// Set the number of workers recovering senders.
+    ///
+    /// If `None`, the global rayon thread pool is used.
+    pub fn with_parallelism(mut self, parallelism: Option<usize>) -> Self {
+        self.parallelism = parallelism;
+        self.pool = None;
+        self
+    }
+
+    /// Set the number of transactions recovered by a single worker job.
+    ///
+    /// If `None`, the transactions are divided evenly across the workers.
+    pub fn with_batch_size(mut self, batch_size: Option<usize>) -> Self {
+        self.batch_size = batch_size;
+        self
+    }
+
+    /// Returns the dedicated thread pool of the workers, if the parallelism is set.
+    fn thread_pool(&mut self) -> Result<Option<Arc<ThreadPool>>, StageError> {
+        let Some(parallelism) = self.parallelism else { return Ok(None) };
+
+        if self.pool.is_none() {
+            let pool = ThreadPoolBuilder::new()
+                .num_threads(parallelism)
+                .thread_name(|idx| format!("sender-recovery-{idx}"))
+                .build()
+                .map_err(|err| StageError::Fatal(Box::new(err)))?;
+            self.pool = Some(Arc::new(pool));
+        }
+
+        Ok(self.pool.clone())
     }
 }
 
 impl Default for SenderRecoveryStage {
     fn default() -> Self {
-        Self { commit_threshold: 5_000_000 }
+        Self::new(5_000_000)
     }
 }
 
@@ -94,17 +141,24 @@ impl<DB: Database> Stage<DB> for SenderRecoveryStage {
         // channels used to return result of sender recovery.
         let mut channels = Vec::new();
 
-        // Spawn recovery jobs onto the default rayon threadpool and send the result through the
-        // channel.
+        // Spawn recovery jobs onto the thread pool and send the result through the channel.
         //
-        // We try to evenly divide the transactions to recover across all threads in the threadpool.
-        // Chunks are submitted instead of individual transactions to reduce the overhead of work
-        // stealing in the threadpool workers.
-        let chunk_size = self.commit_threshold as usize / rayon::current_num_threads();
-        // prevents an edge case
-        // where the chunk size is either 0 or too small
-        // to gain anything from using more than 1 thread
-        let chunk_size = chunk_size.max(16);
+        // Unless a batch size is configured, we try to evenly divide the transactions to recover
+        // across all threads in the threadpool. Chunks are submitted instead of individual
+        // transactions to reduce the overhead of work stealing in the threadpool workers.
+        let pool = self.thread_pool()?;
+        let chunk_size = match self.batch_size {
+            Some(batch_size) => batch_size.max(1),
+            None => {
+                let threads = pool
+                    .as_ref()
+                    .map_or_else(rayon::current_num_threads, |pool| pool.current_num_threads());
+                // prevents an edge case
+                // where the chunk size is either 0 or too small
+                // to gain anything from using more than 1 thread
+                (self.commit_threshold as usize / threads).max(16)
+            }
+        };
 
         for chunk in &tx_walker.chunks(chunk_size) {
             // An _unordered_ channel to receive results from a rayon job
@@ -113,16 +167,20 @@ impl<DB: Database> Stage<DB> for SenderRecoveryStage {
             // Note: Unfortunate side-effect of how chunk is designed in itertools (it is not Send)
             let chunk: Vec<_> = chunk.collect();
 
-            // Spawn the sender recovery task onto the global rayon pool
+            // Spawn the sender recovery task onto the thread pool
             // This task will send the results through the channel after it recovered the senders.
-            rayon::spawn(move || {
+            let job = move || {
                 let mut rlp_buf = Vec::with_capacity(128);
                 for entry in chunk {
                     rlp_buf.clear();
                     let recovery_result = recover_sender(entry, &mut rlp_buf);
                     let _ = recovered_senders_tx.send(recovery_result);
                 }
-            });
+            };
+            match &pool {
+                Some(pool) => pool.spawn(job),
+                None => rayon::spawn(job),
+            }
         }
 
         // Iterate over channels and append the sender in the order that they are received.
@@ -382,6 +440,40 @@ mod tests {
         assert!(runner.validate_execution(first_input, result.ok()).is_ok(), "validation failed");
     }
 
+    /// Execute the stage with a dedicated thread pool and small batches
+    #[tokio::test]
+    async fn execute_parallel_batches() {
+        let (stage_progress, previous_stage) = (1000, 1100);
+        let mut rng = generators::rng();
+
+        let mut runner = SenderRecoveryTestRunner::default();
+        runner.set_threshold(u64::MAX);
+        runner.parallelism = Some(4);
+        runner.batch_size = Some(3);
+
+        let seed =
+            random_block_range(&mut rng, stage_progress + 1..=previous_stage, B256::ZERO, 0..4);
+        runner.db.insert_blocks(seed.iter(), None).expect("failed to seed execution");
+        let total_transactions = runner.db.table::<tables::Transactions>().unwrap().len() as u64;
+
+        let input = ExecInput {
+            target: Some(previous_stage),
+            checkpoint: Some(StageCheckpoint::new(stage_progress)),
+        };
+        let result = runner.execute(input).await.unwrap();
+        assert_eq!(
+            result.as_ref().unwrap(),
+            &ExecOutput {
+                checkpoint: StageCheckpoint::new(previous_stage).with_entities_stage_checkpoint(
+                    EntitiesCheckpoint { processed: total_transactions, total: total_transactions }
+                ),
+                done: true
+            }
+        );
+
+        assert!(runner.validate_execution(input, result.ok()).is_ok(), "validation failed");
+    }
+
     #[test]
     fn stage_checkpoint_pruned() {
         let db = TestStageDB::default();
@@ -440,11 +532,18 @@ mod tests {
     struct SenderRecoveryTestRunner {
         db: TestStageDB,
         threshold: u64,
+        parallelism: Option<usize>,
+        batch_size: Option<usize>,
     }
 
     impl Default for SenderRecoveryTestRunner {
         fn default() -> Self {
-            Self { threshold: 1000, db: TestStageDB::default() }
+            Self {
+                threshold: 1000,
+                parallelism: None,
+                batch_size: None,
+                db: TestStageDB::default(),
+            }
         }
     }
 
@@ -487,7 +586,9 @@ mod tests {
         }
 
         fn stage(&self) -> Self::S {
-            SenderRecoveryStage { commit_threshold: self.threshold }
+            SenderRecoveryStage::new(self.threshold)
+                .with_parallelism(self.parallelism)
+                .with_batch_size(self.batch_size)
         }
     }
 
@@ -63,6 +63,7 @@ fn generate_from_compact(fields: &FieldList, ident: &Ident, is_zstd: bool) -> To
         "AccessList",
         "Signature",
         "CheckpointBlockRange",
+        "EntitiesCheckpoint",
     ]);
 
     // let mut handle = FieldListHandler::new(fields);
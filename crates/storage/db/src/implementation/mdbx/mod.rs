@@ -14,8 +14,9 @@ use reth_interfaces::db::LogLevel;
 use reth_libmdbx::{
     DatabaseFlags, Environment, EnvironmentFlags, Geometry, Mode, PageSize, SyncMode, RO, RW,
 };
-use reth_tracing::tracing::error;
-use std::{ops::Deref, path::Path};
+use reth_tracing::tracing::{debug, error};
+use serde::{Deserialize, Serialize};
+use std::{fmt, ops::Deref, path::Path, str::FromStr};
 use tx::Tx;
 
 pub mod cursor;
@@ -32,6 +33,10 @@ const DEFAULT_MAX_READERS: u64 = 32_000;
 #[cfg(not(windows))]
 const MAX_SAFE_READER_SPACE: usize = 10 * GIGABYTE;
 
+/// Number of table handles on top of [Tables::ALL] for the schema version table and the tables
+/// that only exist while the database is migrated, see [crate::migration].
+const MIGRATION_TABLES: usize = 16;
+
 #[cfg(not(windows))]
 static PROCESS_ID: Lazy<u32> = Lazy::new(|| {
     #[cfg(unix)]
@@ -45,6 +50,106 @@ static PROCESS_ID: Lazy<u32> = Lazy::new(|| {
     }
 });
 
+/// Durability of the commits of read-write transactions, see [SyncMode] for the details.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum DatabaseSyncMode {
+    /// Data and metadata are flushed to disk on every commit.
+    #[default]
+    Durable,
+    /// Data is flushed to disk on every commit, but a system crash may undo the last commit.
+    NoMetaSync,
+    /// Nothing is flushed to disk on commit. A system crash may undo the commits since the last
+    /// flush, but can't corrupt the database.
+    SafeNoSync,
+    /// Nothing is flushed to disk on commit, and a system crash may corrupt the database.
+    ///
+    /// Only intended for syncing from scratch, where the database can be discarded on a crash.
+    UtterlyNoSync,
+}
+
+impl DatabaseSyncMode {
+    /// All sync modes.
+    pub const ALL: [DatabaseSyncMode; 4] = [
+        DatabaseSyncMode::Durable,
+        DatabaseSyncMode::NoMetaSync,
+        DatabaseSyncMode::SafeNoSync,
+        DatabaseSyncMode::UtterlyNoSync,
+    ];
+
+    /// Returns the name of the sync mode.
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            DatabaseSyncMode::Durable => "durable",
+            DatabaseSyncMode::NoMetaSync => "no-meta-sync",
+            DatabaseSyncMode::SafeNoSync => "safe-no-sync",
+            DatabaseSyncMode::UtterlyNoSync => "utterly-no-sync",
+        }
+    }
+}
+
+impl From<DatabaseSyncMode> for SyncMode {
+    fn from(mode: DatabaseSyncMode) -> Self {
+        match mode {
+            DatabaseSyncMode::Durable => SyncMode::Durable,
+            DatabaseSyncMode::NoMetaSync => SyncMode::NoMetaSync,
+            DatabaseSyncMode::SafeNoSync => SyncMode::SafeNoSync,
+            DatabaseSyncMode::UtterlyNoSync => SyncMode::UtterlyNoSync,
+        }
+    }
+}
+
+impl fmt::Display for DatabaseSyncMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for DatabaseSyncMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL.into_iter().find(|mode| mode.as_str() == s).ok_or_else(|| {
+            let modes = Self::ALL.map(|mode| mode.as_str()).join(", ");
+            format!("invalid sync mode {s}, expected one of: {modes}")
+        })
+    }
+}
+
+/// Tuning of the MDBX environment.
+///
+/// The geometry and the maximum number of readers are only applied by the process that opens the
+/// environment first, other processes use the settings of the running environment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DatabaseEnvConfig {
+    /// Maximum size of the database file in bytes.
+    pub max_size: usize,
+    /// Number of bytes the database file grows by when it's full.
+    pub growth_step: usize,
+    /// Maximum number of concurrent read transactions, up to 32767.
+    pub max_readers: u64,
+    /// Durability of the commits of read-write transactions.
+    pub sync_mode: DatabaseSyncMode,
+    /// Whether the OS reads ahead when pages are read.
+    ///
+    /// Improves the performance of linear scans, but worsens it for random access, which is the
+    /// access pattern outside of sync.
+    pub read_ahead: bool,
+}
+
+impl Default for DatabaseEnvConfig {
+    fn default() -> Self {
+        Self {
+            max_size: 4 * TERABYTE,
+            growth_step: 4 * GIGABYTE,
+            max_readers: DEFAULT_MAX_READERS,
+            sync_mode: DatabaseSyncMode::Durable,
+            read_ahead: false,
+        }
+    }
+}
+
 /// Environment used when opening a MDBX environment. RO/RW.
 #[derive(Debug)]
 pub enum DatabaseEnvKind {
@@ -147,6 +252,10 @@ impl DatabaseMetrics for DatabaseEnv {
             metrics.push(("db.freelist", freelist as f64, vec![]));
         }
 
+        if let Ok(info) = self.info().map_err(|error| error!(?error, "Failed to read db.info")) {
+            metrics.push(("db.readers", info.num_readers() as f64, vec![]));
+        }
+
         metrics
     }
 }
@@ -165,6 +274,19 @@ impl DatabaseEnv {
         path: &Path,
         kind: DatabaseEnvKind,
         log_level: Option<LogLevel>,
+    ) -> Result<DatabaseEnv, DatabaseError> {
+        Self::open_with_config(path, kind, log_level, DatabaseEnvConfig::default())
+    }
+
+    /// Opens the database at the specified path with the given `EnvKind`, tuned by the given
+    /// [DatabaseEnvConfig].
+    ///
+    /// It does not create the tables, for that call [`DatabaseEnv::create_tables`].
+    pub fn open_with_config(
+        path: &Path,
+        kind: DatabaseEnvKind,
+        log_level: Option<LogLevel>,
+        config: DatabaseEnvConfig,
     ) -> Result<DatabaseEnv, DatabaseError> {
         let mut inner_env = Environment::builder();
 
@@ -173,16 +295,16 @@ impl DatabaseEnv {
             DatabaseEnvKind::RW => {
                 // enable writemap mode in RW mode
                 inner_env.write_map();
-                Mode::ReadWrite { sync_mode: SyncMode::Durable }
+                Mode::ReadWrite { sync_mode: config.sync_mode.into() }
             }
         };
 
-        inner_env.set_max_dbs(Tables::ALL.len());
+        inner_env.set_max_dbs(Tables::ALL.len() + MIGRATION_TABLES);
         inner_env.set_geometry(Geometry {
-            // Maximum database size of 4 terabytes
-            size: Some(0..(4 * TERABYTE)),
-            // We grow the database in increments of 4 gigabytes
-            growth_step: Some(4 * GIGABYTE as isize),
+            // Maximum database size, 4 terabytes by default
+            size: Some(0..config.max_size),
+            // We grow the database in increments of 4 gigabytes by default
+            growth_step: Some(config.growth_step as isize),
             // The database never shrinks
             shrink_threshold: None,
             page_size: Some(PageSize::Set(default_page_size())),
@@ -215,14 +337,14 @@ impl DatabaseEnv {
         }
         inner_env.set_flags(EnvironmentFlags {
             mode,
-            // We disable readahead because it improves performance for linear scans, but
-            // worsens it for random access (which is our access pattern outside of sync)
-            no_rdahead: true,
+            // We disable readahead by default because it improves performance for linear scans,
+            // but worsens it for random access (which is our access pattern outside of sync)
+            no_rdahead: !config.read_ahead,
             coalesce: true,
             ..Default::default()
         });
         // Configure more readers
-        inner_env.set_max_readers(DEFAULT_MAX_READERS);
+        inner_env.set_max_readers(config.max_readers);
         // This parameter sets the maximum size of the "reclaimed list", and the unit of measurement
         // is "pages". Reclaimed list is the list of freed pages that's populated during the
         // lifetime of DB transaction, and through which MDBX searches when it needs to insert new
@@ -281,6 +403,17 @@ impl DatabaseEnv {
             with_metrics: false,
         };
 
+        // The reader table is shared by all processes that have the environment open, e.g. a
+        // running node and external tooling that opened it read-only. Readers of processes that
+        // were killed are only released by a reader check.
+        match env.reader_check() {
+            Ok(0) => {}
+            Ok(stale_readers) => {
+                debug!(target: "storage::db::mdbx", ?stale_readers, "Cleared stale reader slots")
+            }
+            Err(error) => error!(target: "storage::db::mdbx", ?error, "Failed to check readers"),
+        }
+
         Ok(env)
     }
 
@@ -1161,4 +1294,35 @@ mod tests {
             assert_eq!(list400, list);
         }
     }
+
+    #[test]
+    fn db_open_with_config() {
+        let path = TempDir::new().expect(ERROR_TEMPDIR).into_path();
+        let config = DatabaseEnvConfig {
+            max_size: 64 * 1024 * 1024,
+            growth_step: 1024 * 1024,
+            sync_mode: DatabaseSyncMode::SafeNoSync,
+            read_ahead: true,
+            ..Default::default()
+        };
+        let env = DatabaseEnv::open_with_config(&path, DatabaseEnvKind::RW, None, config)
+            .expect(ERROR_DB_CREATION);
+        env.create_tables().expect(ERROR_TABLE_CREATION);
+
+        let tx = env.tx_mut().expect(ERROR_INIT_TX);
+        tx.put::<CanonicalHeaders>(1, B256::ZERO).expect(ERROR_PUT);
+        tx.commit().expect(ERROR_COMMIT);
+        assert_eq!(
+            env.tx().unwrap().get::<CanonicalHeaders>(1).expect(ERROR_GET),
+            Some(B256::ZERO)
+        );
+    }
+
+    #[test]
+    fn db_sync_mode_from_str() {
+        for mode in DatabaseSyncMode::ALL {
+            assert_eq!(mode.to_string().parse::<DatabaseSyncMode>(), Ok(mode));
+        }
+        assert!("fast".parse::<DatabaseSyncMode>().is_err());
+    }
 }
@@ -0,0 +1,343 @@
+//! Cursors of the in-memory database.
+
+use super::{
+    first_dup, Entries, MemoryDatabase, Snapshot, TransactionKind, ERROR_KEY_EXISTS,
+    ERROR_KEY_MISMATCH, ERROR_NOT_FOUND, RW,
+};
+use crate::{
+    common::{PairResult, ValueOnlyResult},
+    cursor::{
+        DbCursorRO, DbCursorRW, DbDupCursorRO, DbDupCursorRW, DupWalker, RangeWalker,
+        ReverseWalker, Walker,
+    },
+    table::{Compress, DupSort, Encode, Table},
+    tables::{utils::*, TableType},
+    DatabaseError,
+};
+use parking_lot::RwLock;
+use reth_interfaces::db::{DatabaseWriteError, DatabaseWriteOperation};
+use std::{
+    borrow::Cow,
+    marker::PhantomData,
+    ops::{Bound, RangeBounds},
+    sync::Arc,
+};
+
+/// A `(key, value)` pair of a table.
+type Entry = (Vec<u8>, Vec<u8>);
+
+/// The position of a cursor.
+#[derive(Debug, Clone)]
+enum Position {
+    /// The cursor hasn't been positioned yet, or its last seek failed. Moving forward starts at
+    /// the first entry and moving backward at the last entry.
+    Unset,
+    /// The cursor is at the entry, which might have been deleted since.
+    At(Entry),
+    /// The cursor moved past the last entry.
+    End,
+}
+
+/// A cursor over table `T` of the in-memory database.
+#[derive(Debug)]
+pub struct Cursor<K: TransactionKind, T: Table> {
+    /// The tables of the transaction the cursor was created by.
+    state: Arc<RwLock<Snapshot>>,
+    /// The position of the cursor.
+    position: Position,
+    _kind: PhantomData<(K, T)>,
+}
+
+impl<K: TransactionKind, T: Table> Cursor<K, T> {
+    pub(crate) fn new(state: Arc<RwLock<Snapshot>>) -> Self {
+        Self { state, position: Position::Unset, _kind: PhantomData }
+    }
+
+    /// Looks up an entry of the table, given the current position of the cursor.
+    fn find<F>(&self, f: F) -> Option<Entry>
+    where
+        F: for<'a> FnOnce(&'a Entries, &Position) -> Option<&'a Entry>,
+    {
+        let state = self.state.read();
+        let empty = Entries::new();
+        f(state.table(T::NAME).unwrap_or(&empty), &self.position).cloned()
+    }
+
+    /// Moves the cursor to the entry, or to `otherwise` if there is none, returning the decoded
+    /// entry.
+    fn move_to(&mut self, entry: Option<Entry>, otherwise: Position) -> PairResult<T> {
+        match entry {
+            Some(entry) => {
+                self.position = Position::At(entry.clone());
+                decoder::<T>((Cow::Owned(entry.0), Cow::Owned(entry.1))).map(Some)
+            }
+            None => {
+                self.position = otherwise;
+                Ok(None)
+            }
+        }
+    }
+
+    /// Returns the key of the entry the cursor is at.
+    fn current_key(&self) -> Option<Vec<u8>> {
+        match &self.position {
+            Position::At((key, _)) => Some(key.clone()),
+            _ => None,
+        }
+    }
+
+    /// Positions the cursor at the first entry of the key whose value is greater than or equal to
+    /// the subkey.
+    fn seek_key_subkey(&mut self, key: &[u8], subkey: &[u8]) -> PairResult<T> {
+        let entry = self.find(|entries, _| {
+            entries.range((key.to_vec(), subkey.to_vec())..).next().filter(|(k, _)| k == key)
+        });
+        self.move_to(entry, Position::Unset)
+    }
+}
+
+impl<K: TransactionKind, T: Table> DbCursorRO<T> for Cursor<K, T> {
+    fn first(&mut self) -> PairResult<T> {
+        let entry = self.find(|entries, _| entries.get_min());
+        self.move_to(entry, Position::End)
+    }
+
+    fn seek_exact(&mut self, key: <T as Table>::Key) -> PairResult<T> {
+        let key = key.encode();
+        let entry = self.find(|entries, _| first_dup(entries, key.as_ref()));
+        self.move_to(entry, Position::Unset)
+    }
+
+    fn seek(&mut self, key: <T as Table>::Key) -> PairResult<T> {
+        let key: Vec<u8> = key.encode().into();
+        let entry = self.find(|entries, _| entries.range((key, Vec::new())..).next());
+        self.move_to(entry, Position::End)
+    }
+
+    fn next(&mut self) -> PairResult<T> {
+        let entry = self.find(|entries, position| match position {
+            Position::Unset => entries.get_min(),
+            Position::At(entry) => {
+                entries.range((Bound::Excluded(entry.clone()), Bound::Unbounded)).next()
+            }
+            Position::End => None,
+        });
+        self.move_to(entry, Position::End)
+    }
+
+    fn prev(&mut self) -> PairResult<T> {
+        let entry = self.find(|entries, position| match position {
+            Position::Unset | Position::End => entries.get_max(),
+            Position::At(entry) => entries.range(..entry.clone()).next_back(),
+        });
+        self.move_to(entry, Position::Unset)
+    }
+
+    fn last(&mut self) -> PairResult<T> {
+        let entry = self.find(|entries, _| entries.get_max());
+        self.move_to(entry, Position::Unset)
+    }
+
+    fn current(&mut self) -> PairResult<T> {
+        let entry = self.find(|entries, position| match position {
+            Position::At(entry) => entries.get(entry),
+            _ => None,
+        });
+        entry.map(|(k, v)| decoder::<T>((Cow::Owned(k), Cow::Owned(v)))).transpose()
+    }
+
+    fn walk(&mut self, start_key: Option<T::Key>) -> Result<Walker<'_, T, Self>, DatabaseError> {
+        let start = if let Some(start_key) = start_key {
+            self.seek(start_key).transpose()
+        } else {
+            self.first().transpose()
+        };
+
+        Ok(Walker::new(self, start))
+    }
+
+    fn walk_range(
+        &mut self,
+        range: impl RangeBounds<T::Key>,
+    ) -> Result<RangeWalker<'_, T, Self>, DatabaseError> {
+        let start = match range.start_bound().cloned() {
+            Bound::Included(key) => self.seek(key),
+            Bound::Excluded(_key) => {
+                unreachable!("Rust doesn't allow for Bound::Excluded in starting bounds");
+            }
+            Bound::Unbounded => self.first(),
+        }
+        .transpose();
+        Ok(RangeWalker::new(self, start, range.end_bound().cloned()))
+    }
+
+    fn walk_back(
+        &mut self,
+        start_key: Option<T::Key>,
+    ) -> Result<ReverseWalker<'_, T, Self>, DatabaseError> {
+        let start =
+            if let Some(start_key) = start_key { self.seek(start_key) } else { self.last() }
+                .transpose();
+
+        Ok(ReverseWalker::new(self, start))
+    }
+}
+
+impl<K: TransactionKind, T: DupSort> DbDupCursorRO<T> for Cursor<K, T> {
+    fn next_dup(&mut self) -> PairResult<T> {
+        let entry = self.find(|entries, position| match position {
+            Position::At(entry) => entries
+                .range((Bound::Excluded(entry.clone()), Bound::Unbounded))
+                .next()
+                .filter(|(k, _)| k == &entry.0),
+            _ => None,
+        });
+        // the cursor stays at the last duplicate if there is no next one
+        let position = self.position.clone();
+        self.move_to(entry, position)
+    }
+
+    fn next_no_dup(&mut self) -> PairResult<T> {
+        let entry = self.find(|entries, position| match position {
+            Position::Unset => entries.get_min(),
+            Position::At((key, _)) => entries
+                .range((Bound::Excluded((key.clone(), Vec::new())), Bound::Unbounded))
+                .find(|(k, _)| k != key),
+            Position::End => None,
+        });
+        self.move_to(entry, Position::End)
+    }
+
+    fn next_dup_val(&mut self) -> ValueOnlyResult<T> {
+        Ok(self.next_dup()?.map(|(_, value)| value))
+    }
+
+    fn seek_by_key_subkey(
+        &mut self,
+        key: <T as Table>::Key,
+        subkey: <T as DupSort>::SubKey,
+    ) -> ValueOnlyResult<T> {
+        Ok(self
+            .seek_key_subkey(key.encode().as_ref(), subkey.encode().as_ref())?
+            .map(|(_, value)| value))
+    }
+
+    /// Depending on its arguments, returns an iterator starting at:
+    /// - Some(key), Some(subkey): a `key` item whose data is >= than `subkey`
+    /// - Some(key), None: first item of a specified `key`
+    /// - None, Some(subkey): like first case, but in the first key
+    /// - None, None: first item in the table
+    /// of a DUPSORT table.
+    fn walk_dup(
+        &mut self,
+        key: Option<T::Key>,
+        subkey: Option<T::SubKey>,
+    ) -> Result<DupWalker<'_, T, Self>, DatabaseError> {
+        let start = match (key, subkey) {
+            (Some(key), Some(subkey)) => {
+                self.seek_key_subkey(key.encode().as_ref(), subkey.encode().as_ref())?.map(Ok)
+            }
+            (Some(key), None) => self.seek_exact(key)?.map(Ok),
+            (None, Some(subkey)) => {
+                if let Some((key, _)) = self.first()? {
+                    self.seek_key_subkey(key.encode().as_ref(), subkey.encode().as_ref())?.map(Ok)
+                } else {
+                    Some(Err(DatabaseError::Read(ERROR_NOT_FOUND)))
+                }
+            }
+            (None, None) => self.first().transpose(),
+        };
+
+        Ok(DupWalker::<'_, T, Self> { cursor: self, start })
+    }
+}
+
+impl<T: Table> Cursor<RW, T> {
+    /// Returns whether the table is a dup table.
+    fn is_dupsort() -> bool {
+        MemoryDatabase::table_type(T::NAME) == TableType::DupSort
+    }
+
+    /// Writes the entry if `check` accepts the current entries of the table, and moves the cursor
+    /// to it.
+    fn write<F>(
+        &mut self,
+        key: T::Key,
+        value: T::Value,
+        operation: DatabaseWriteOperation,
+        check: F,
+    ) -> Result<(), DatabaseError>
+    where
+        F: FnOnce(&Entries, &Entry) -> Result<(), i32>,
+    {
+        let entry: Entry = (key.encode().into(), value.compress().into());
+
+        let mut state = self.state.write();
+        let empty = Entries::new();
+        check(state.table(T::NAME).unwrap_or(&empty), &entry).map_err(|code| {
+            DatabaseWriteError { code, operation, table_name: T::NAME, key: entry.0.clone() }
+        })?;
+        state.put(T::NAME, entry.0.clone(), entry.1.clone());
+        drop(state);
+
+        self.position = Position::At(entry);
+        Ok(())
+    }
+}
+
+impl<T: Table> DbCursorRW<T> for Cursor<RW, T> {
+    /// For a DUPSORT table, `upsert` adds the value to the values of the key, as with MDBX.
+    fn upsert(&mut self, key: T::Key, value: T::Value) -> Result<(), DatabaseError> {
+        self.write(key, value, DatabaseWriteOperation::CursorUpsert, |_, _| Ok(()))
+    }
+
+    fn insert(&mut self, key: T::Key, value: T::Value) -> Result<(), DatabaseError> {
+        self.write(key, value, DatabaseWriteOperation::CursorInsert, |entries, (key, _)| {
+            match first_dup(entries, key) {
+                Some(_) => Err(ERROR_KEY_EXISTS),
+                None => Ok(()),
+            }
+        })
+    }
+
+    /// Appends the data to the end of the table. Consequently, the append operation
+    /// will fail if the inserted key is less than the last table key
+    fn append(&mut self, key: T::Key, value: T::Value) -> Result<(), DatabaseError> {
+        let is_dupsort = Self::is_dupsort();
+        self.write(key, value, DatabaseWriteOperation::CursorAppend, |entries, entry| match entries
+            .get_max()
+        {
+            Some(last) if is_dupsort && last >= entry => Err(ERROR_KEY_MISMATCH),
+            Some(last) if !is_dupsort && last.0 >= entry.0 => Err(ERROR_KEY_MISMATCH),
+            _ => Ok(()),
+        })
+    }
+
+    fn delete_current(&mut self) -> Result<(), DatabaseError> {
+        let Position::At(entry) = &self.position else {
+            return Err(DatabaseError::Delete(ERROR_NOT_FOUND))
+        };
+        self.state.write().remove(T::NAME, entry);
+        Ok(())
+    }
+}
+
+impl<T: DupSort> DbDupCursorRW<T> for Cursor<RW, T> {
+    fn delete_current_duplicates(&mut self) -> Result<(), DatabaseError> {
+        let key = self.current_key().ok_or(DatabaseError::Delete(ERROR_NOT_FOUND))?;
+        self.state.write().remove_dups(T::NAME, &key);
+        Ok(())
+    }
+
+    /// Appends the value to the values of the key, failing if it's less than the last one.
+    fn append_dup(&mut self, key: T::Key, value: T::Value) -> Result<(), DatabaseError> {
+        self.write(key, value, DatabaseWriteOperation::CursorAppendDup, |entries, (key, value)| {
+            let last =
+                entries.range((key.clone(), Vec::new())..).take_while(|(k, _)| k == key).last();
+            match last {
+                Some((_, last)) if last >= value => Err(ERROR_KEY_MISMATCH),
+                _ => Ok(()),
+            }
+        })
+    }
+}
@@ -0,0 +1,348 @@
+//! Persistence of the in-memory database.
+//!
+//! A persistent database lives in a directory with two files, both written with plain file I/O:
+//! - `tables`, a snapshot of all tables, which is replaced atomically by [`CommitLog::compact`].
+//! - `log`, the changes of every commit since the snapshot was taken, appended and synced on
+//!   commit.
+//!
+//! Both files start with a header that holds the generation of the snapshot, and contain a
+//! sequence of records, each a batch of [`Change`]s with its length and checksum. A log only
+//! applies to the snapshot of the same generation, so a crash during compaction never applies a
+//! log twice. A record that wasn't completely written when the node crashed is discarded on open.
+
+use super::Snapshot;
+use crate::{
+    table::Table,
+    tables::{TableViewer, Tables},
+};
+use std::{
+    convert::Infallible,
+    fs::{self, File, OpenOptions},
+    io::{self, BufReader, BufWriter, Read, Seek, SeekFrom, Write},
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+/// The magic bytes at the start of both files.
+const MAGIC: &[u8; 8] = b"rethmem1";
+/// The length of the header of both files, the magic bytes and the generation.
+const HEADER_LEN: u64 = 16;
+/// The length of the prefix of a record, its length and checksum.
+const RECORD_PREFIX_LEN: usize = 8;
+/// The maximum length of a record of the snapshot.
+const MAX_SNAPSHOT_RECORD_LEN: usize = 1024 * 1024;
+/// The minimum length of the log before it's compacted into the snapshot.
+const MIN_COMPACTION_LEN: u64 = 64 * 1024 * 1024;
+
+/// A change of a table, as recorded by a read-write transaction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Change {
+    /// The entry was inserted, replacing the value of the key unless the table is a dup table.
+    Put { table: &'static str, key: Vec<u8>, value: Vec<u8> },
+    /// The entry was removed.
+    Remove { table: &'static str, key: Vec<u8>, value: Vec<u8> },
+    /// All entries of the key were removed.
+    RemoveDups { table: &'static str, key: Vec<u8> },
+    /// All entries of the table were removed.
+    Clear { table: &'static str },
+}
+
+impl Change {
+    /// Applies the change to the snapshot.
+    fn apply(self, snapshot: &mut Snapshot) {
+        match self {
+            Self::Put { table, key, value } => snapshot.put(table, key, value),
+            Self::Remove { table, key, value } => {
+                snapshot.remove(table, &(key, value));
+            }
+            Self::RemoveDups { table, key } => {
+                snapshot.remove_dups(table, &key);
+            }
+            Self::Clear { table } => snapshot.clear(table),
+        }
+    }
+
+    fn encode(&self, buf: &mut Vec<u8>) {
+        let (tag, table, key, value) = match self {
+            Self::Put { table, key, value } => (0, table, Some(key), Some(value)),
+            Self::Remove { table, key, value } => (1, table, Some(key), Some(value)),
+            Self::RemoveDups { table, key } => (2, table, Some(key), None),
+            Self::Clear { table } => (3, table, None, None),
+        };
+        buf.push(tag);
+        buf.push(table.len() as u8);
+        buf.extend_from_slice(table.as_bytes());
+        for bytes in [key, value].into_iter().flatten() {
+            buf.extend_from_slice(&(bytes.len() as u32).to_le_bytes());
+            buf.extend_from_slice(bytes);
+        }
+    }
+
+    fn decode(buf: &mut &[u8]) -> io::Result<Self> {
+        let tag = take(buf, 1)?[0];
+        let table_len = take(buf, 1)?[0] as usize;
+        let table = std::str::from_utf8(take(buf, table_len)?)
+            .ok()
+            .and_then(|name| Tables::from_str(name).ok())
+            .ok_or_else(|| invalid_data("unknown table"))?;
+        let table = table.view(&TableName).unwrap_or_else(|never| match never {});
+        let mut bytes = || -> io::Result<Vec<u8>> {
+            let len = u32::from_le_bytes(take(buf, 4)?.try_into().expect("4 bytes")) as usize;
+            Ok(take(buf, len)?.to_vec())
+        };
+        Ok(match tag {
+            0 => Self::Put { table, key: bytes()?, value: bytes()? },
+            1 => Self::Remove { table, key: bytes()?, value: bytes()? },
+            2 => Self::RemoveDups { table, key: bytes()? },
+            3 => Self::Clear { table },
+            _ => return Err(invalid_data("unknown change")),
+        })
+    }
+}
+
+/// The files of a persistent in-memory database, see the [module-level documentation](self).
+#[derive(Debug)]
+pub(crate) struct CommitLog {
+    /// The directory of the database.
+    path: PathBuf,
+    /// The log, positioned at its end.
+    log: File,
+    /// The generation of the snapshot.
+    generation: u64,
+    /// The generation of the log, which differs from the generation of the snapshot only if a
+    /// compaction failed before the log was replaced.
+    log_generation: u64,
+    /// The length of the log.
+    log_len: u64,
+    /// The length of the snapshot.
+    snapshot_len: u64,
+}
+
+impl CommitLog {
+    /// Opens the database in the directory, creating it if it doesn't exist, and returns the
+    /// tables restored from its files.
+    pub(crate) fn open(path: &Path) -> io::Result<(Self, Snapshot)> {
+        fs::create_dir_all(path)?;
+
+        let mut snapshot = Snapshot::default();
+        let tables_path = path.join("tables");
+        let (generation, snapshot_len) = if tables_path.exists() {
+            let mut reader = BufReader::new(File::open(&tables_path)?);
+            let generation = read_header(&mut reader)?;
+            let mut len = HEADER_LEN;
+            // the snapshot is only ever replaced as a whole, so all of its records must be valid
+            while let Some(record) = read_record(&mut reader)? {
+                len += (RECORD_PREFIX_LEN + record.len()) as u64;
+                apply_record(&record, &mut snapshot)?;
+            }
+            (generation, len)
+        } else {
+            (0, 0)
+        };
+
+        let log_path = path.join("log");
+        let mut log_len = HEADER_LEN;
+        let mut log = if log_path.exists() {
+            let mut reader = BufReader::new(File::open(&log_path)?);
+            let log_generation = match read_header(&mut reader) {
+                Ok(log_generation) => Some(log_generation),
+                // the header of a new log wasn't completely written
+                Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => None,
+                Err(err) => return Err(err),
+            };
+            if log_generation == Some(generation) {
+                // a record that can't be read was torn by a crash while it was appended
+                while let Ok(Some(record)) = read_record(&mut reader) {
+                    apply_record(&record, &mut snapshot)?;
+                    log_len += (RECORD_PREFIX_LEN + record.len()) as u64;
+                }
+                let log = OpenOptions::new().write(true).open(&log_path)?;
+                log.set_len(log_len)?;
+                log.sync_all()?;
+                log
+            } else {
+                // the log was already compacted into the snapshot, or is empty
+                create_file(&log_path, generation)?
+            }
+        } else {
+            create_file(&log_path, generation)?
+        };
+        log.seek(SeekFrom::End(0))?;
+
+        let path = path.to_path_buf();
+        let log_generation = generation;
+        Ok((Self { path, log, generation, log_generation, log_len, snapshot_len }, snapshot))
+    }
+
+    /// Appends the changes of a commit to the log and syncs it.
+    pub(crate) fn append(&mut self, changes: &[Change]) -> io::Result<()> {
+        if self.log_generation != self.generation {
+            // the last compaction failed after the snapshot was replaced
+            self.reset_log()?;
+        }
+
+        let mut payload = Vec::new();
+        for change in changes {
+            change.encode(&mut payload);
+        }
+        let record = encode_record(&payload);
+        if let Err(err) = self.log.write_all(&record).and_then(|_| self.log.sync_data()) {
+            // the commit fails, so its partially written record must not be replayed
+            self.log.set_len(self.log_len)?;
+            self.log.seek(SeekFrom::End(0))?;
+            return Err(err)
+        }
+        self.log_len += record.len() as u64;
+        Ok(())
+    }
+
+    /// Returns whether the log grew large enough to be compacted into the snapshot.
+    pub(crate) fn should_compact(&self) -> bool {
+        self.log_len > MIN_COMPACTION_LEN.max(self.snapshot_len)
+    }
+
+    /// Replaces the snapshot with the given tables, which must include all changes of the log,
+    /// and starts a new, empty log.
+    pub(crate) fn compact(&mut self, snapshot: &Snapshot) -> io::Result<()> {
+        let generation = self.generation + 1;
+
+        let tables_path = self.path.join("tables");
+        let tmp_path = self.path.join("tables.tmp");
+        let mut writer = BufWriter::new(File::create(&tmp_path)?);
+        writer.write_all(&header(generation))?;
+        let mut snapshot_len = HEADER_LEN;
+        let mut payload = Vec::new();
+        let mut write_payload = |payload: &mut Vec<u8>, writer: &mut BufWriter<File>| {
+            let record = encode_record(payload);
+            snapshot_len += record.len() as u64;
+            payload.clear();
+            writer.write_all(&record)
+        };
+        for (&table, entries) in &snapshot.tables {
+            for (key, value) in entries {
+                let change = Change::Put { table, key: key.clone(), value: value.clone() };
+                change.encode(&mut payload);
+                if payload.len() >= MAX_SNAPSHOT_RECORD_LEN {
+                    write_payload(&mut payload, &mut writer)?;
+                }
+            }
+        }
+        if !payload.is_empty() {
+            write_payload(&mut payload, &mut writer)?;
+        }
+        writer.into_inner().map_err(io::IntoInnerError::into_error)?.sync_all()?;
+        // from here on, the log of the previous generation is ignored on open
+        fs::rename(&tmp_path, &tables_path)?;
+        self.generation = generation;
+        self.snapshot_len = snapshot_len;
+        sync_dir(&self.path)?;
+
+        self.reset_log()
+    }
+
+    /// Replaces the log with an empty log of the current generation.
+    fn reset_log(&mut self) -> io::Result<()> {
+        let log_path = self.path.join("log");
+        let tmp_path = self.path.join("log.tmp");
+        drop(create_file(&tmp_path, self.generation)?);
+        fs::rename(&tmp_path, &log_path)?;
+        sync_dir(&self.path)?;
+
+        self.log = OpenOptions::new().append(true).open(&log_path)?;
+        self.log_generation = self.generation;
+        self.log_len = HEADER_LEN;
+        Ok(())
+    }
+}
+
+/// Returns the name of a table with a `'static` lifetime.
+struct TableName;
+
+impl TableViewer<&'static str> for TableName {
+    type Error = Infallible;
+
+    fn view<T: Table>(&self) -> Result<&'static str, Self::Error> {
+        Ok(T::NAME)
+    }
+}
+
+fn header(generation: u64) -> [u8; HEADER_LEN as usize] {
+    let mut header = [0; HEADER_LEN as usize];
+    header[..8].copy_from_slice(MAGIC);
+    header[8..].copy_from_slice(&generation.to_le_bytes());
+    header
+}
+
+fn read_header(reader: &mut impl Read) -> io::Result<u64> {
+    let mut header = [0; HEADER_LEN as usize];
+    reader.read_exact(&mut header)?;
+    if &header[..8] != MAGIC {
+        return Err(invalid_data("not an in-memory database file"))
+    }
+    Ok(u64::from_le_bytes(header[8..].try_into().expect("8 bytes")))
+}
+
+/// Creates the file with only a header, and syncs it.
+fn create_file(path: &Path, generation: u64) -> io::Result<File> {
+    let mut file = File::create(path)?;
+    file.write_all(&header(generation))?;
+    file.sync_all()?;
+    Ok(file)
+}
+
+fn encode_record(payload: &[u8]) -> Vec<u8> {
+    let mut record = Vec::with_capacity(RECORD_PREFIX_LEN + payload.len());
+    record.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+    record.extend_from_slice(&crc32fast::hash(payload).to_le_bytes());
+    record.extend_from_slice(payload);
+    record
+}
+
+/// Reads the next record, returning `None` at the end of the file.
+fn read_record(reader: &mut impl Read) -> io::Result<Option<Vec<u8>>> {
+    let mut prefix = [0; RECORD_PREFIX_LEN];
+    let read = reader.read(&mut prefix[..1])?;
+    if read == 0 {
+        return Ok(None)
+    }
+    reader.read_exact(&mut prefix[1..])?;
+    let len = u32::from_le_bytes(prefix[..4].try_into().expect("4 bytes")) as usize;
+    let checksum = u32::from_le_bytes(prefix[4..].try_into().expect("4 bytes"));
+
+    let mut payload = Vec::new();
+    reader.take(len as u64).read_to_end(&mut payload)?;
+    if payload.len() != len || crc32fast::hash(&payload) != checksum {
+        return Err(invalid_data("invalid record"))
+    }
+    Ok(Some(payload))
+}
+
+fn apply_record(record: &[u8], snapshot: &mut Snapshot) -> io::Result<()> {
+    let mut buf = record;
+    while !buf.is_empty() {
+        Change::decode(&mut buf)?.apply(snapshot);
+    }
+    Ok(())
+}
+
+fn take<'a>(buf: &mut &'a [u8], len: usize) -> io::Result<&'a [u8]> {
+    if buf.len() < len {
+        return Err(invalid_data("truncated change"))
+    }
+    let (taken, rest) = buf.split_at(len);
+    *buf = rest;
+    Ok(taken)
+}
+
+fn invalid_data(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg.to_string())
+}
+
+/// Syncs the directory, so renames of its files are durable.
+fn sync_dir(path: &Path) -> io::Result<()> {
+    #[cfg(unix)]
+    File::open(path)?.sync_all()?;
+    #[cfg(not(unix))]
+    let _ = path;
+    Ok(())
+}
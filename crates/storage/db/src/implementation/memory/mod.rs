@@ -0,0 +1,575 @@
+//! An in-memory database backend.
+//!
+//! The backend keeps every table in an ordered map and never maps files into memory, which makes
+//! it usable in environments with no (or broken) support for memory-mapped files. The database is
+//! either volatile, or persisted to a directory with plain file I/O, as a snapshot of all tables
+//! and a log of the commits since. It follows the
+//! semantics of the MDBX backend, so it also serves as a reference for implementing other backends
+//! behind the [`Database`] abstraction.
+
+use self::log::{Change, CommitLog};
+use crate::{
+    abstraction::database_metrics::{DatabaseMetadata, DatabaseMetadataValue, DatabaseMetrics},
+    database::Database,
+    tables::{TableType, Tables},
+    DatabaseError,
+};
+use imbl::OrdSet;
+use metrics::Label;
+use parking_lot::RwLock;
+use std::{
+    collections::HashMap,
+    fmt, io,
+    path::Path,
+    str::FromStr,
+    sync::{Arc, Condvar, Mutex},
+};
+
+pub mod cursor;
+mod log;
+pub mod tx;
+
+pub use cursor::Cursor;
+pub use tx::Tx;
+
+/// The error code of a missing entry, same as in MDBX.
+pub(crate) const ERROR_NOT_FOUND: i32 = -30798;
+/// The error code of a write to a key that already exists, same as in MDBX.
+pub(crate) const ERROR_KEY_EXISTS: i32 = -30799;
+/// The error code of an append of a key that isn't the greatest in the table, same as in MDBX.
+pub(crate) const ERROR_KEY_MISMATCH: i32 = -30419;
+
+/// The kind of a transaction, either read-only or read-write.
+pub trait TransactionKind: private::Sealed + Send + Sync + fmt::Debug + 'static {
+    /// Whether the transaction is read-only.
+    const IS_READ_ONLY: bool;
+}
+
+/// Read-only transaction kind.
+#[derive(Debug)]
+pub struct RO;
+
+/// Read-write transaction kind.
+#[derive(Debug)]
+pub struct RW;
+
+impl TransactionKind for RO {
+    const IS_READ_ONLY: bool = true;
+}
+
+impl TransactionKind for RW {
+    const IS_READ_ONLY: bool = false;
+}
+
+mod private {
+    pub trait Sealed {}
+
+    impl Sealed for super::RO {}
+    impl Sealed for super::RW {}
+}
+
+/// The entries of a table as `(key, value)` pairs, ordered by key first and by value second, the
+/// same way as MDBX orders the duplicate values of dup tables.
+///
+/// The set is persistent, so copies share all nodes they haven't written to, and copying it is
+/// cheap regardless of the size of the table.
+pub(crate) type Entries = OrdSet<(Vec<u8>, Vec<u8>)>;
+
+/// A consistent view of all tables.
+///
+/// Writes are recorded in the journal of the snapshot, if it has one, to persist them on commit.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Snapshot {
+    tables: HashMap<&'static str, Entries>,
+    /// The changes since the snapshot was created.
+    journal: Option<Vec<Change>>,
+}
+
+impl Snapshot {
+    /// Returns the entries of the table, if it has been written to.
+    pub(crate) fn table(&self, name: &str) -> Option<&Entries> {
+        self.tables.get(name)
+    }
+
+    /// Inserts the entry, replacing the value of the key unless the table is a dup table.
+    pub(crate) fn put(&mut self, table: &'static str, key: Vec<u8>, value: Vec<u8>) {
+        let entries = self.tables.entry(table).or_default();
+        if MemoryDatabase::table_type(table) == TableType::Table {
+            remove_dups(entries, &key);
+        }
+        if let Some(journal) = &mut self.journal {
+            journal.push(Change::Put { table, key: key.clone(), value: value.clone() });
+        }
+        entries.insert((key, value));
+    }
+
+    /// Removes the entry, returning whether it existed.
+    pub(crate) fn remove(&mut self, table: &'static str, entry: &(Vec<u8>, Vec<u8>)) -> bool {
+        let Some(entries) = self.tables.get_mut(table) else { return false };
+        let removed = entries.remove(entry).is_some();
+        if let (true, Some(journal)) = (removed, &mut self.journal) {
+            let (key, value) = entry.clone();
+            journal.push(Change::Remove { table, key, value });
+        }
+        removed
+    }
+
+    /// Removes all entries of the key, returning whether there were any.
+    pub(crate) fn remove_dups(&mut self, table: &'static str, key: &[u8]) -> bool {
+        let Some(entries) = self.tables.get_mut(table) else { return false };
+        let removed = remove_dups(entries, key);
+        if let (true, Some(journal)) = (removed, &mut self.journal) {
+            journal.push(Change::RemoveDups { table, key: key.to_vec() });
+        }
+        removed
+    }
+
+    /// Removes all entries of the table.
+    pub(crate) fn clear(&mut self, table: &'static str) {
+        if self.tables.remove(table).is_some() {
+            if let Some(journal) = &mut self.journal {
+                journal.push(Change::Clear { table });
+            }
+        }
+    }
+}
+
+/// Returns the first entry of the key, which is its only entry in tables that aren't dup tables.
+pub(crate) fn first_dup<'a>(entries: &'a Entries, key: &[u8]) -> Option<&'a (Vec<u8>, Vec<u8>)> {
+    entries.range((key.to_vec(), Vec::new())..).next().filter(|(k, _)| k == key)
+}
+
+/// Removes all entries of the key, returning whether there were any.
+fn remove_dups(entries: &mut Entries, key: &[u8]) -> bool {
+    let dups = entries
+        .range((key.to_vec(), Vec::new())..)
+        .take_while(|(k, _)| k == key)
+        .cloned()
+        .collect::<Vec<_>>();
+    for dup in &dups {
+        entries.remove(dup);
+    }
+    !dups.is_empty()
+}
+
+/// State shared by the database handle and its transactions.
+#[derive(Debug, Default)]
+pub(crate) struct Shared {
+    /// The state written by the last committed read-write transaction.
+    pub(crate) committed: RwLock<Snapshot>,
+    /// The files the commits are persisted to, if the database is persistent.
+    log: Option<parking_lot::Mutex<CommitLog>>,
+    /// Whether a read-write transaction is open.
+    writer: Mutex<bool>,
+    /// Notified when the open read-write transaction is closed.
+    writer_closed: Condvar,
+}
+
+impl Shared {
+    /// Blocks until no other read-write transaction is open, like MDBX does.
+    fn acquire_writer(&self) {
+        let mut writer = self.writer.lock().unwrap_or_else(|err| err.into_inner());
+        while *writer {
+            writer = self.writer_closed.wait(writer).unwrap_or_else(|err| err.into_inner());
+        }
+        *writer = true;
+    }
+
+    /// Releases the writer acquired by [`Shared::acquire_writer`].
+    pub(crate) fn release_writer(&self) {
+        *self.writer.lock().unwrap_or_else(|err| err.into_inner()) = false;
+        self.writer_closed.notify_one();
+    }
+
+    /// Returns a snapshot of the committed state for a new transaction, which journals its writes
+    /// if the database is persistent.
+    pub(crate) fn begin(&self) -> Snapshot {
+        let mut snapshot = self.committed.read().clone();
+        if self.log.is_some() {
+            snapshot.journal = Some(Vec::new());
+        }
+        snapshot
+    }
+
+    /// Publishes the state of a read-write transaction, after persisting its changes.
+    pub(crate) fn commit(&self, mut snapshot: Snapshot) -> io::Result<()> {
+        let journal = snapshot.journal.take().unwrap_or_default();
+        if let (Some(log), false) = (&self.log, journal.is_empty()) {
+            let mut log = log.lock();
+            log.append(&journal)?;
+            if log.should_compact() {
+                // the commit is already durable, so a failed compaction only leaves a longer log
+                if let Err(err) = log.compact(&snapshot) {
+                    reth_tracing::tracing::warn!(
+                        target: "storage::db::memory",
+                        ?err,
+                        "Failed to compact log"
+                    );
+                }
+            }
+        }
+        *self.committed.write() = snapshot;
+        Ok(())
+    }
+}
+
+/// An in-memory database, see the [module-level documentation](self).
+///
+/// Read-only transactions see the state of the last commit at the time they were opened, and only
+/// one read-write transaction can be open at a time. Unless the database was opened with
+/// [`MemoryDatabase::open`], the data is lost when the last handle to the database is dropped.
+#[derive(Debug, Clone, Default)]
+pub struct MemoryDatabase {
+    shared: Arc<Shared>,
+}
+
+impl MemoryDatabase {
+    /// Creates a new, empty database.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Opens the persistent database in the directory, creating it if it doesn't exist.
+    ///
+    /// All tables are loaded into memory, and every commit is appended to a log in the directory
+    /// and synced before it's visible to other transactions. Once the log grows larger than the
+    /// tables, it's compacted into a snapshot of them.
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let (log, committed) = CommitLog::open(path.as_ref())?;
+        let shared = Shared {
+            committed: RwLock::new(committed),
+            log: Some(parking_lot::Mutex::new(log)),
+            ..Default::default()
+        };
+        Ok(Self { shared: Arc::new(shared) })
+    }
+
+    /// Returns the type of the table with the given name.
+    pub(crate) fn table_type(name: &str) -> TableType {
+        Tables::from_str(name).expect("Requested table should be part of `Tables`.").table_type()
+    }
+}
+
+impl Database for MemoryDatabase {
+    type TX = Tx<RO>;
+    type TXMut = Tx<RW>;
+
+    fn tx(&self) -> Result<Self::TX, DatabaseError> {
+        Ok(Tx::new(self.shared.clone()))
+    }
+
+    fn tx_mut(&self) -> Result<Self::TXMut, DatabaseError> {
+        self.shared.acquire_writer();
+        Ok(Tx::new(self.shared.clone()))
+    }
+}
+
+impl DatabaseMetrics for MemoryDatabase {
+    fn gauge_metrics(&self) -> Vec<(&'static str, f64, Vec<Label>)> {
+        let committed = self.shared.committed.read();
+        Tables::ALL
+            .iter()
+            .map(|table| {
+                let entries = committed.table(table.name()).map_or(0, |entries| entries.len());
+                ("db.table_entries", entries as f64, vec![Label::new("table", table.name())])
+            })
+            .collect()
+    }
+}
+
+impl DatabaseMetadata for MemoryDatabase {
+    fn metadata(&self) -> DatabaseMetadataValue {
+        DatabaseMetadataValue::new(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        cursor::{DbCursorRO, DbCursorRW, DbDupCursorRO, DbDupCursorRW},
+        models::{AccountBeforeTx, ShardedKey},
+        table::{Encode, Table},
+        tables::{AccountChangeSet, AccountHistory, CanonicalHeaders, PlainStorageState},
+        transaction::{DbTx, DbTxMut},
+    };
+    use reth_interfaces::db::{DatabaseWriteError, DatabaseWriteOperation};
+    use reth_primitives::{Address, IntegerList, StorageEntry, B256, U256};
+    use std::{thread, time::Duration};
+
+    #[test]
+    fn memory_put_get_commit() {
+        let db = MemoryDatabase::new();
+
+        let tx = db.tx_mut().unwrap();
+        tx.put::<CanonicalHeaders>(1, B256::with_last_byte(1)).unwrap();
+        // uncommitted writes are only visible to the writer
+        assert_eq!(db.tx().unwrap().get::<CanonicalHeaders>(1), Ok(None));
+        assert_eq!(tx.get::<CanonicalHeaders>(1), Ok(Some(B256::with_last_byte(1))));
+
+        let reader = db.tx().unwrap();
+        tx.commit().unwrap();
+        // readers keep the view from when they were opened
+        assert_eq!(reader.get::<CanonicalHeaders>(1), Ok(None));
+        assert_eq!(db.tx().unwrap().get::<CanonicalHeaders>(1), Ok(Some(B256::with_last_byte(1))));
+
+        // aborted writes are discarded
+        let tx = db.tx_mut().unwrap();
+        tx.put::<CanonicalHeaders>(2, B256::with_last_byte(2)).unwrap();
+        tx.abort();
+        assert_eq!(db.tx().unwrap().entries::<CanonicalHeaders>(), Ok(1));
+    }
+
+    #[test]
+    fn memory_single_writer() {
+        let db = MemoryDatabase::new();
+        let tx = db.tx_mut().unwrap();
+
+        let handle = {
+            let db = db.clone();
+            thread::spawn(move || {
+                let tx = db.tx_mut().unwrap();
+                // the first writer committed before this one could open
+                assert_eq!(tx.get::<CanonicalHeaders>(1), Ok(Some(B256::ZERO)));
+            })
+        };
+
+        thread::sleep(Duration::from_millis(50));
+        tx.put::<CanonicalHeaders>(1, B256::ZERO).unwrap();
+        tx.commit().unwrap();
+        handle.join().unwrap();
+    }
+
+    #[test]
+    fn memory_cursor_walk() {
+        let db = MemoryDatabase::new();
+        let tx = db.tx_mut().unwrap();
+        let mut cursor = tx.cursor_write::<CanonicalHeaders>().unwrap();
+        for block in [0, 1, 2, 4] {
+            cursor.append(block, B256::with_last_byte(block as u8)).unwrap();
+        }
+
+        // appended keys must be increasing
+        assert_eq!(
+            cursor.append(3, B256::ZERO),
+            Err(DatabaseWriteError {
+                code: ERROR_KEY_MISMATCH,
+                operation: DatabaseWriteOperation::CursorAppend,
+                table_name: CanonicalHeaders::NAME,
+                key: 3u64.encode().into(),
+            }
+            .into())
+        );
+        assert!(cursor.insert(4, B256::ZERO).is_err());
+        cursor.upsert(4, B256::ZERO).unwrap();
+        drop(cursor);
+        tx.commit().unwrap();
+
+        let tx = db.tx().unwrap();
+        let mut cursor = tx.cursor_read::<CanonicalHeaders>().unwrap();
+        assert_eq!(cursor.seek(3), Ok(Some((4, B256::ZERO))));
+        assert_eq!(cursor.prev(), Ok(Some((2, B256::with_last_byte(2)))));
+        assert_eq!(cursor.seek_exact(3), Ok(None));
+        assert_eq!(cursor.last(), Ok(Some((4, B256::ZERO))));
+        assert_eq!(cursor.next(), Ok(None));
+
+        let keys = cursor.walk_range(1..4).unwrap().map(|row| row.unwrap().0).collect::<Vec<_>>();
+        assert_eq!(keys, vec![1, 2]);
+        let keys = cursor.walk_back(Some(2)).unwrap().map(|row| row.unwrap().0).collect::<Vec<_>>();
+        assert_eq!(keys, vec![2, 1, 0]);
+    }
+
+    #[test]
+    fn memory_cursor_delete_current() {
+        let db = MemoryDatabase::new();
+        let tx = db.tx_mut().unwrap();
+        for block in 0..5 {
+            tx.put::<CanonicalHeaders>(block, B256::ZERO).unwrap();
+        }
+
+        let mut cursor = tx.cursor_write::<CanonicalHeaders>().unwrap();
+        let mut walker = cursor.walk(Some(1)).unwrap();
+        while let Some(Ok((block, _))) = walker.next() {
+            if block % 2 == 1 {
+                walker.delete_current().unwrap();
+            }
+        }
+
+        let keys = cursor.walk(None).unwrap().map(|row| row.unwrap().0).collect::<Vec<_>>();
+        assert_eq!(keys, vec![0, 2, 4]);
+    }
+
+    #[test]
+    fn memory_dup_cursor() {
+        let db = MemoryDatabase::new();
+        let address = Address::with_last_byte(1);
+        let entry = |key: u8, value: u64| StorageEntry {
+            key: B256::with_last_byte(key),
+            value: U256::from(value),
+        };
+
+        let tx = db.tx_mut().unwrap();
+        // dup values are sorted, regardless of the insertion order
+        tx.put::<PlainStorageState>(address, entry(3, 3)).unwrap();
+        tx.put::<PlainStorageState>(address, entry(1, 1)).unwrap();
+        tx.put::<PlainStorageState>(Address::with_last_byte(2), entry(1, 1)).unwrap();
+
+        let mut cursor = tx.cursor_dup_write::<PlainStorageState>().unwrap();
+        assert_eq!(
+            cursor.seek_by_key_subkey(address, B256::with_last_byte(2)),
+            Ok(Some(entry(3, 3)))
+        );
+        assert_eq!(cursor.next_dup(), Ok(None));
+        assert_eq!(cursor.seek_exact(address), Ok(Some((address, entry(1, 1)))));
+        assert_eq!(cursor.next_dup_val(), Ok(Some(entry(3, 3))));
+        assert_eq!(cursor.seek_exact(address), Ok(Some((address, entry(1, 1)))));
+        assert_eq!(cursor.next_no_dup(), Ok(Some((Address::with_last_byte(2), entry(1, 1)))));
+
+        // a dup value is only removed with its exact value
+        assert_eq!(tx.delete::<PlainStorageState>(address, Some(entry(1, 2))), Ok(false));
+        assert_eq!(tx.delete::<PlainStorageState>(address, Some(entry(1, 1))), Ok(true));
+        let values = cursor
+            .walk_dup(Some(address), None)
+            .unwrap()
+            .map(|row| row.unwrap().1)
+            .collect::<Vec<_>>();
+        assert_eq!(values, vec![entry(3, 3)]);
+
+        cursor.seek_exact(address).unwrap();
+        cursor.delete_current_duplicates().unwrap();
+        assert_eq!(tx.entries::<PlainStorageState>(), Ok(1));
+    }
+
+    #[test]
+    fn memory_append_dup() {
+        let db = MemoryDatabase::new();
+        let tx = db.tx_mut().unwrap();
+        let account =
+            |address: u8| AccountBeforeTx { address: Address::with_last_byte(address), info: None };
+
+        let mut cursor = tx.cursor_dup_write::<AccountChangeSet>().unwrap();
+        cursor.append_dup(1, account(1)).unwrap();
+        cursor.append_dup(1, account(2)).unwrap();
+        cursor.append_dup(2, account(1)).unwrap();
+        // values must be increasing per key
+        assert!(cursor.append_dup(2, account(0)).is_err());
+        cursor.append_dup(1, account(3)).unwrap();
+
+        assert_eq!(cursor.walk_dup(None, None).unwrap().count(), 4);
+        tx.clear::<AccountChangeSet>().unwrap();
+        assert_eq!(tx.entries::<AccountChangeSet>(), Ok(0));
+
+        // tables are only copied on write, and the written tables are independent
+        tx.put::<AccountHistory>(
+            ShardedKey::new(Address::ZERO, u64::MAX),
+            IntegerList::new_pre_sorted([1]),
+        )
+        .unwrap();
+        tx.commit().unwrap();
+        assert_eq!(db.tx().unwrap().entries::<AccountHistory>(), Ok(1));
+        assert_eq!(db.tx().unwrap().entries::<AccountChangeSet>(), Ok(0));
+    }
+
+    #[test]
+    fn memory_persistent_reopen() {
+        let dir = tempfile::tempdir().unwrap();
+        let address = Address::with_last_byte(1);
+        let entry = |key: u8| StorageEntry { key: B256::with_last_byte(key), value: U256::from(1) };
+
+        let db = MemoryDatabase::open(dir.path()).unwrap();
+        let tx = db.tx_mut().unwrap();
+        for block in 0..3 {
+            tx.put::<CanonicalHeaders>(block, B256::with_last_byte(block as u8)).unwrap();
+        }
+        tx.put::<PlainStorageState>(address, entry(1)).unwrap();
+        tx.put::<PlainStorageState>(address, entry(2)).unwrap();
+        tx.commit().unwrap();
+
+        let tx = db.tx_mut().unwrap();
+        tx.delete::<CanonicalHeaders>(0, None).unwrap();
+        tx.put::<CanonicalHeaders>(1, B256::ZERO).unwrap();
+        tx.delete::<PlainStorageState>(address, Some(entry(1))).unwrap();
+        tx.put::<AccountHistory>(
+            ShardedKey::new(Address::ZERO, u64::MAX),
+            IntegerList::new_pre_sorted([1]),
+        )
+        .unwrap();
+        tx.clear::<AccountHistory>().unwrap();
+        tx.commit().unwrap();
+
+        // aborted writes aren't persisted
+        let tx = db.tx_mut().unwrap();
+        tx.put::<CanonicalHeaders>(3, B256::ZERO).unwrap();
+        tx.abort();
+        drop(db);
+
+        let db = MemoryDatabase::open(dir.path()).unwrap();
+        let tx = db.tx().unwrap();
+        let mut cursor = tx.cursor_read::<CanonicalHeaders>().unwrap();
+        let headers = cursor.walk(None).unwrap().collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(headers, vec![(1, B256::ZERO), (2, B256::with_last_byte(2))]);
+        let mut cursor = tx.cursor_dup_read::<PlainStorageState>().unwrap();
+        let storage = cursor.walk(None).unwrap().collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(storage, vec![(address, entry(2))]);
+        assert_eq!(tx.entries::<AccountHistory>(), Ok(0));
+    }
+
+    #[test]
+    fn memory_persistent_torn_log() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let db = MemoryDatabase::open(dir.path()).unwrap();
+        let tx = db.tx_mut().unwrap();
+        tx.put::<CanonicalHeaders>(1, B256::ZERO).unwrap();
+        tx.commit().unwrap();
+        drop(db);
+
+        // a crash while appending a commit leaves a partial record at the end of the log
+        let mut log =
+            std::fs::OpenOptions::new().append(true).open(dir.path().join("log")).unwrap();
+        std::io::Write::write_all(&mut log, &[42, 0, 0, 0, 1, 2]).unwrap();
+        drop(log);
+
+        let db = MemoryDatabase::open(dir.path()).unwrap();
+        assert_eq!(db.tx().unwrap().entries::<CanonicalHeaders>(), Ok(1));
+        // the partial record is discarded, so later commits are replayed
+        let tx = db.tx_mut().unwrap();
+        tx.put::<CanonicalHeaders>(2, B256::ZERO).unwrap();
+        tx.commit().unwrap();
+        drop(db);
+
+        let db = MemoryDatabase::open(dir.path()).unwrap();
+        assert_eq!(db.tx().unwrap().entries::<CanonicalHeaders>(), Ok(2));
+    }
+
+    #[test]
+    fn memory_persistent_compaction() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let db = MemoryDatabase::open(dir.path()).unwrap();
+        for block in 0..10 {
+            let tx = db.tx_mut().unwrap();
+            tx.put::<CanonicalHeaders>(block, B256::with_last_byte(block as u8)).unwrap();
+            tx.delete::<CanonicalHeaders>(block.saturating_sub(5), None).unwrap();
+            tx.commit().unwrap();
+        }
+        let log_len = || std::fs::metadata(dir.path().join("log")).unwrap().len();
+        let uncompacted = log_len();
+
+        let log = db.shared.log.as_ref().unwrap();
+        log.lock().compact(&db.shared.committed.read()).unwrap();
+        assert!(log_len() < uncompacted);
+
+        let tx = db.tx_mut().unwrap();
+        tx.put::<CanonicalHeaders>(10, B256::ZERO).unwrap();
+        tx.commit().unwrap();
+        drop(db);
+
+        // the snapshot and the commits after it are restored
+        let db = MemoryDatabase::open(dir.path()).unwrap();
+        let tx = db.tx().unwrap();
+        let mut cursor = tx.cursor_read::<CanonicalHeaders>().unwrap();
+        let keys = cursor.walk(None).unwrap().map(|row| row.unwrap().0).collect::<Vec<_>>();
+        assert_eq!(keys, (5..=10).collect::<Vec<_>>());
+    }
+}
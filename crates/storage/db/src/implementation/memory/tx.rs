@@ -0,0 +1,124 @@
+//! Transactions of the in-memory database.
+
+use super::{cursor::Cursor, first_dup, Shared, Snapshot, TransactionKind, RW};
+use crate::{
+    table::{Compress, DupSort, Encode, Table, TableImporter},
+    tables::utils::decode_one,
+    transaction::{DbTx, DbTxMut},
+    DatabaseError,
+};
+use parking_lot::RwLock;
+use std::{borrow::Cow, marker::PhantomData, sync::Arc};
+
+/// A transaction of the in-memory database.
+///
+/// The transaction works on its own copy of the tables, which read-write transactions publish to
+/// the database on commit. Copying the tables is cheap, since the copy shares all entries with the
+/// committed tables until they are written to.
+#[derive(Debug)]
+pub struct Tx<K: TransactionKind> {
+    /// The tables as seen by this transaction, shared with its cursors.
+    state: Arc<RwLock<Snapshot>>,
+    /// The database the transaction was opened on.
+    shared: Arc<Shared>,
+    _kind: PhantomData<K>,
+}
+
+impl<K: TransactionKind> Tx<K> {
+    /// Creates a transaction on the last committed state of the database.
+    ///
+    /// For read-write transactions, the writer must have been acquired beforehand.
+    pub(crate) fn new(shared: Arc<Shared>) -> Self {
+        let state = Arc::new(RwLock::new(shared.begin()));
+        Self { state, shared, _kind: PhantomData }
+    }
+
+    /// Creates a cursor over the tables of the transaction.
+    fn new_cursor<T: Table>(&self) -> Cursor<K, T> {
+        Cursor::new(self.state.clone())
+    }
+}
+
+impl<K: TransactionKind> Drop for Tx<K> {
+    fn drop(&mut self) {
+        if !K::IS_READ_ONLY {
+            self.shared.release_writer();
+        }
+    }
+}
+
+impl<K: TransactionKind> DbTx for Tx<K> {
+    type Cursor<T: Table> = Cursor<K, T>;
+    type DupCursor<T: DupSort> = Cursor<K, T>;
+
+    fn get<T: Table>(&self, key: T::Key) -> Result<Option<<T as Table>::Value>, DatabaseError> {
+        let state = self.state.read();
+        let Some(entries) = state.table(T::NAME) else { return Ok(None) };
+        first_dup(entries, key.encode().as_ref())
+            .map(|(_, value)| decode_one::<T>(Cow::Borrowed(value.as_slice())))
+            .transpose()
+    }
+
+    fn commit(self) -> Result<bool, DatabaseError> {
+        if !K::IS_READ_ONLY {
+            let mut state = self.state.write();
+            let snapshot = Snapshot { tables: state.tables.clone(), journal: state.journal.take() };
+            self.shared
+                .commit(snapshot)
+                .map_err(|err| DatabaseError::Commit(err.raw_os_error().unwrap_or(-1)))?;
+        }
+        Ok(false)
+    }
+
+    fn abort(self) {}
+
+    fn cursor_read<T: Table>(&self) -> Result<Self::Cursor<T>, DatabaseError> {
+        Ok(self.new_cursor())
+    }
+
+    fn cursor_dup_read<T: DupSort>(&self) -> Result<Self::DupCursor<T>, DatabaseError> {
+        Ok(self.new_cursor())
+    }
+
+    fn entries<T: Table>(&self) -> Result<usize, DatabaseError> {
+        Ok(self.state.read().table(T::NAME).map_or(0, |entries| entries.len()))
+    }
+}
+
+impl DbTxMut for Tx<RW> {
+    type CursorMut<T: Table> = Cursor<RW, T>;
+    type DupCursorMut<T: DupSort> = Cursor<RW, T>;
+
+    fn put<T: Table>(&self, key: T::Key, value: T::Value) -> Result<(), DatabaseError> {
+        self.state.write().put(T::NAME, key.encode().into(), value.compress().into());
+        Ok(())
+    }
+
+    fn delete<T: Table>(
+        &self,
+        key: T::Key,
+        value: Option<T::Value>,
+    ) -> Result<bool, DatabaseError> {
+        let mut state = self.state.write();
+        let key: Vec<u8> = key.encode().into();
+        Ok(match value {
+            Some(value) => state.remove(T::NAME, &(key, value.compress().into())),
+            None => state.remove_dups(T::NAME, &key),
+        })
+    }
+
+    fn clear<T: Table>(&self) -> Result<(), DatabaseError> {
+        self.state.write().clear(T::NAME);
+        Ok(())
+    }
+
+    fn cursor_write<T: Table>(&self) -> Result<Self::CursorMut<T>, DatabaseError> {
+        Ok(self.new_cursor())
+    }
+
+    fn cursor_dup_write<T: DupSort>(&self) -> Result<Self::DupCursorMut<T>, DatabaseError> {
+        Ok(self.new_cursor())
+    }
+}
+
+impl TableImporter for Tx<RW> {}
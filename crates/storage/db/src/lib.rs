@@ -67,6 +67,8 @@ pub mod abstraction;
 
 mod implementation;
 mod metrics;
+#[cfg(feature = "mdbx")]
+pub mod migration;
 pub mod snapshot;
 pub mod tables;
 mod utils;
@@ -79,13 +81,19 @@ pub mod mdbx {
     pub use reth_libmdbx::*;
 }
 
+/// An in-memory database backend, for environments where MDBX can't be used.
+pub mod memory {
+    pub use crate::implementation::memory::*;
+}
+
 pub use abstraction::*;
+pub use memory::MemoryDatabase;
 pub use reth_interfaces::db::{DatabaseError, DatabaseWriteOperation};
 pub use tables::*;
 pub use utils::is_database_empty;
 
 #[cfg(feature = "mdbx")]
-pub use mdbx::{DatabaseEnv, DatabaseEnvKind};
+pub use mdbx::{DatabaseEnv, DatabaseEnvConfig, DatabaseEnvKind, DatabaseSyncMode};
 
 use eyre::WrapErr;
 use reth_interfaces::db::LogLevel;
@@ -94,9 +102,22 @@ use std::path::Path;
 /// Opens up an existing database or creates a new one at the specified path. Creates tables if
 /// necessary. Read/Write mode.
 pub fn init_db<P: AsRef<Path>>(path: P, log_level: Option<LogLevel>) -> eyre::Result<DatabaseEnv> {
-    use crate::version::{check_db_version_file, create_db_version_file, DatabaseVersionError};
+    init_db_with_config(path, log_level, DatabaseEnvConfig::default())
+}
+
+/// Opens up an existing database or creates a new one at the specified path, tuned by the given
+/// [DatabaseEnvConfig]. Creates tables if necessary. Read/Write mode.
+pub fn init_db_with_config<P: AsRef<Path>>(
+    path: P,
+    log_level: Option<LogLevel>,
+    config: DatabaseEnvConfig,
+) -> eyre::Result<DatabaseEnv> {
+    use crate::version::{
+        check_db_version_file, create_db_version_file, DatabaseVersionError, DB_VERSION,
+    };
 
     let rpath = path.as_ref();
+    let mut outdated = false;
     if is_database_empty(rpath) {
         std::fs::create_dir_all(rpath)
             .wrap_err_with(|| format!("Could not create database directory {}", rpath.display()))?;
@@ -105,12 +126,18 @@ pub fn init_db<P: AsRef<Path>>(path: P, log_level: Option<LogLevel>) -> eyre::Re
         match check_db_version_file(rpath) {
             Ok(_) => (),
             Err(DatabaseVersionError::MissingFile) => create_db_version_file(rpath)?,
+            Err(DatabaseVersionError::VersionMismatch { version }) if version < DB_VERSION => {
+                outdated = true
+            }
             Err(err) => return Err(err.into()),
         }
     }
     #[cfg(feature = "mdbx")]
     {
-        let db = DatabaseEnv::open(rpath, DatabaseEnvKind::RW, log_level)?;
+        let db = DatabaseEnv::open_with_config(rpath, DatabaseEnvKind::RW, log_level, config)?;
+        if outdated {
+            migration::Migrator::new(&db, rpath).run()?;
+        }
         db.create_tables()?;
         Ok(db)
     }
@@ -136,9 +163,19 @@ pub fn open_db_read_only(path: &Path, log_level: Option<LogLevel>) -> eyre::Resu
 /// Opens up an existing database. Read/Write mode with WriteMap enabled. It doesn't create it or
 /// create tables if missing.
 pub fn open_db(path: &Path, log_level: Option<LogLevel>) -> eyre::Result<DatabaseEnv> {
+    open_db_with_config(path, log_level, DatabaseEnvConfig::default())
+}
+
+/// Opens up an existing database, tuned by the given [DatabaseEnvConfig]. Read/Write mode with
+/// WriteMap enabled. It doesn't create it or create tables if missing.
+pub fn open_db_with_config(
+    path: &Path,
+    log_level: Option<LogLevel>,
+    config: DatabaseEnvConfig,
+) -> eyre::Result<DatabaseEnv> {
     #[cfg(feature = "mdbx")]
     {
-        DatabaseEnv::open(path, DatabaseEnvKind::RW, log_level)
+        DatabaseEnv::open_with_config(path, DatabaseEnvKind::RW, log_level, config)
             .with_context(|| format!("Could not open database at path: {}", path.display()))
     }
     #[cfg(not(feature = "mdbx"))]
@@ -0,0 +1,847 @@
+//! Database schema migrations.
+//!
+//! A change of the table layout bumps [DB_VERSION] and registers a [Migration] to that version in
+//! [migrations]. On startup, the [Migrator] applies the migrations between the version of the
+//! database and [DB_VERSION] in order, so that existing databases are upgraded in place instead of
+//! being resynced.
+//!
+//! The version is stored in the [SCHEMA_VERSION_TABLE] together with the progress of the migration
+//! that is applied, and is written in the same transaction as the changes of the migration. An
+//! interrupted migration is resumed where it stopped. The database version file is only updated
+//! afterwards, for the version checks of the node.
+
+use crate::{
+    mdbx::{DatabaseEnv, DatabaseFlags, Transaction, WriteFlags, RW},
+    table::Table,
+    tables::{self, TableType},
+    version::{get_db_version, write_db_version_file, DatabaseVersionError, DB_VERSION},
+    DatabaseError, DatabaseWriteOperation,
+};
+use reth_codecs::Compact;
+use reth_interfaces::db::DatabaseWriteError;
+use reth_primitives::stage::{
+    EntitiesCheckpoint, ExecutionCheckpoint, StageCheckpoint, StageId, StageUnitCheckpoint,
+};
+use reth_tracing::tracing::info;
+use std::{io, path::Path};
+
+/// The name of the table with the schema version of the database and the progress of the
+/// migration that is applied.
+pub const SCHEMA_VERSION_TABLE: &str = "SchemaVersion";
+
+/// Key of the schema version.
+const VERSION_KEY: &[u8] = b"version";
+/// Key of the index of the next step of the migration that is applied.
+const STEP_KEY: &[u8] = b"step";
+/// Key of the key of the last entry of a table that was re-encoded into the temporary table.
+const REENCODED_KEY: &[u8] = b"reencoded.key";
+/// Key of the value of the last entry of a table that was re-encoded into the temporary table.
+const REENCODED_VALUE: &[u8] = b"reencoded.value";
+/// Key of the marker that a re-encoded table is moved back from the temporary table.
+const MOVING_KEY: &[u8] = b"moving";
+
+/// Number of entries of a table that are re-encoded or moved in one transaction.
+const REENCODE_BATCH_SIZE: usize = 100_000;
+
+/// Re-encodes a `(key, value)` pair of a table into the new layout of the table.
+pub type ReencodeFn = fn(&[u8], &[u8]) -> Result<(Vec<u8>, Vec<u8>), DatabaseError>;
+
+/// A single change of the table layout.
+#[derive(Debug, Clone)]
+pub enum MigrationStep {
+    /// Creates a new empty table. Tables that already exist are kept.
+    CreateTable {
+        /// The name of the table.
+        name: &'static str,
+        /// The type of the table.
+        table_type: TableType,
+    },
+    /// Drops a table with all of its entries. Tables that don't exist are ignored.
+    DropTable {
+        /// The name of the table.
+        name: &'static str,
+    },
+    /// Rewrites every entry of a table with the given function.
+    ///
+    /// The table is re-encoded in batches, each committed in its own transaction.
+    ReencodeTable {
+        /// The name of the table.
+        name: &'static str,
+        /// The function that maps the old encoding of an entry to the new one.
+        reencode: ReencodeFn,
+    },
+}
+
+impl MigrationStep {
+    /// Applies the step in the transaction of the migration.
+    fn apply(&self, tx: &mut MigrationTx<'_>) -> Result<(), DatabaseError> {
+        match self {
+            MigrationStep::CreateTable { name, table_type } => {
+                let flags = match table_type {
+                    TableType::Table => DatabaseFlags::default(),
+                    TableType::DupSort => DatabaseFlags::DUP_SORT,
+                };
+                tx.tx()
+                    .create_db(Some(*name), flags)
+                    .map_err(|e| DatabaseError::CreateTable(e.into()))?;
+            }
+            MigrationStep::DropTable { name } => {
+                let table = match tx.tx().open_db(Some(*name)) {
+                    Ok(table) => table,
+                    Err(reth_libmdbx::Error::NotFound) => return Ok(()),
+                    Err(e) => return Err(DatabaseError::Open(e.into())),
+                };
+                // SAFETY: the handle isn't shared with any other cursor or transaction
+                unsafe { tx.tx().drop_db(table) }.map_err(|e| DatabaseError::Delete(e.into()))?;
+            }
+            MigrationStep::ReencodeTable { name, reencode } => reencode_table(tx, name, *reencode)?,
+        }
+        Ok(())
+    }
+}
+
+/// The read-write transaction of the [Migrator], which is committed after every migration and
+/// every batch of a re-encoded table.
+///
+/// On a dry run, all changes are kept in one transaction that is aborted afterwards.
+struct MigrationTx<'a> {
+    db: &'a DatabaseEnv,
+    tx: Option<Transaction<RW>>,
+    dry_run: bool,
+    /// Number of entries of a table that are re-encoded or moved in one transaction.
+    batch_size: usize,
+}
+
+impl<'a> MigrationTx<'a> {
+    fn new(db: &'a DatabaseEnv, dry_run: bool, batch_size: usize) -> Result<Self, DatabaseError> {
+        let tx = db.begin_rw_txn().map_err(|e| DatabaseError::InitTx(e.into()))?;
+        Ok(Self { db, tx: Some(tx), dry_run, batch_size })
+    }
+
+    fn tx(&self) -> &Transaction<RW> {
+        self.tx.as_ref().expect("transaction is open")
+    }
+
+    /// Commits the changes so far and opens a new transaction, unless it's a dry run.
+    fn commit(&mut self) -> Result<(), DatabaseError> {
+        if self.dry_run {
+            return Ok(())
+        }
+        let tx = self.tx.take().expect("transaction is open");
+        tx.commit().map_err(|e| DatabaseError::Commit(e.into()))?;
+        self.tx = Some(self.db.begin_rw_txn().map_err(|e| DatabaseError::InitTx(e.into()))?);
+        Ok(())
+    }
+
+    /// Returns the value of the key in the [SCHEMA_VERSION_TABLE].
+    fn get(&self, key: &[u8]) -> Result<Option<Vec<u8>>, DatabaseError> {
+        read_schema_value(self.tx(), key)
+    }
+
+    /// Sets the value of the key in the [SCHEMA_VERSION_TABLE].
+    fn put(&self, key: &[u8], value: &[u8]) -> Result<(), DatabaseError> {
+        let table = self
+            .tx()
+            .create_db(Some(SCHEMA_VERSION_TABLE), DatabaseFlags::default())
+            .map_err(|e| DatabaseError::CreateTable(e.into()))?;
+        put(self.tx(), &table, SCHEMA_VERSION_TABLE, key.to_vec(), value)
+    }
+
+    /// Removes the key from the [SCHEMA_VERSION_TABLE].
+    fn delete(&self, key: &[u8]) -> Result<(), DatabaseError> {
+        if let Ok(table) = self.tx().open_db(Some(SCHEMA_VERSION_TABLE)) {
+            self.tx().del(table.dbi(), key, None).map_err(|e| DatabaseError::Delete(e.into()))?;
+        }
+        Ok(())
+    }
+}
+
+/// Returns the value of the key in the [SCHEMA_VERSION_TABLE], if the table exists.
+fn read_schema_value<K: reth_libmdbx::TransactionKind>(
+    tx: &Transaction<K>,
+    key: &[u8],
+) -> Result<Option<Vec<u8>>, DatabaseError> {
+    let table = match tx.open_db(Some(SCHEMA_VERSION_TABLE)) {
+        Ok(table) => table,
+        Err(reth_libmdbx::Error::NotFound) => return Ok(None),
+        Err(e) => return Err(DatabaseError::Open(e.into())),
+    };
+    tx.get(table.dbi(), key).map_err(|e| DatabaseError::Read(e.into()))
+}
+
+/// Decodes a big-endian number of the [SCHEMA_VERSION_TABLE].
+fn decode_u64(value: Option<Vec<u8>>) -> Result<Option<u64>, DatabaseError> {
+    value
+        .map(|value| value.try_into().map(u64::from_be_bytes).map_err(|_| DatabaseError::Decode))
+        .transpose()
+}
+
+/// Writes the entry into the table.
+fn put(
+    tx: &Transaction<RW>,
+    table: &reth_libmdbx::Database,
+    name: &'static str,
+    key: Vec<u8>,
+    value: &[u8],
+) -> Result<(), DatabaseError> {
+    tx.put(table.dbi(), &key, value, WriteFlags::UPSERT).map_err(|e| {
+        DatabaseWriteError {
+            code: e.into(),
+            operation: DatabaseWriteOperation::Put,
+            table_name: name,
+            key,
+        }
+        .into()
+    })
+}
+
+/// Re-encodes all entries of the table into a temporary table, and then moves them back.
+///
+/// MDBX tables can't be renamed, so the entries are copied twice. Both copies are done in batches,
+/// and the progress is committed with every batch: the last re-encoded entry of the table while
+/// it's re-encoded, and the remaining entries of the temporary table, which are deleted as they're
+/// moved back.
+fn reencode_table(
+    tx: &mut MigrationTx<'_>,
+    name: &'static str,
+    reencode: ReencodeFn,
+) -> Result<(), DatabaseError> {
+    let temp_name = format!("{name}.migration");
+
+    if tx.get(MOVING_KEY)?.is_none() {
+        let (flags, total) = {
+            let table = tx.tx().open_db(Some(name)).map_err(|e| DatabaseError::Open(e.into()))?;
+            let flags = tx.tx().db_flags(&table).map_err(|e| DatabaseError::Stats(e.into()))?;
+            let stat = tx.tx().db_stat(&table).map_err(|e| DatabaseError::Stats(e.into()))?;
+            (flags, stat.entries())
+        };
+        let mut done = 0;
+
+        loop {
+            let table = tx.tx().open_db(Some(name)).map_err(|e| DatabaseError::Open(e.into()))?;
+            let temp = tx
+                .tx()
+                .create_db(Some(temp_name.as_str()), flags)
+                .map_err(|e| DatabaseError::CreateTable(e.into()))?;
+            let mut cursor =
+                tx.tx().cursor(&table).map_err(|e| DatabaseError::InitCursor(e.into()))?;
+
+            // resume after the last re-encoded entry
+            let mut entry = match (tx.get(REENCODED_KEY)?, tx.get(REENCODED_VALUE)?) {
+                (Some(key), Some(value)) => {
+                    if flags.contains(DatabaseFlags::DUP_SORT) {
+                        cursor.get_both::<()>(&key, &value)
+                    } else {
+                        cursor.set::<()>(&key)
+                    }
+                    .map_err(|e| DatabaseError::Read(e.into()))?;
+                    cursor.next::<Vec<u8>, Vec<u8>>()
+                }
+                _ => cursor.first::<Vec<u8>, Vec<u8>>(),
+            }
+            .map_err(|e| DatabaseError::Read(e.into()))?;
+
+            let mut last = None;
+            let mut batch = 0;
+            while let Some((key, value)) = entry {
+                let (new_key, new_value) = reencode(&key, &value)?;
+                put(tx.tx(), &temp, name, new_key, &new_value)?;
+                last = Some((key, value));
+                batch += 1;
+                if batch == tx.batch_size {
+                    break
+                }
+                entry = cursor.next().map_err(|e| DatabaseError::Read(e.into()))?;
+            }
+            drop(cursor);
+
+            let Some((key, value)) = last else { break };
+            tx.put(REENCODED_KEY, &key)?;
+            tx.put(REENCODED_VALUE, &value)?;
+            tx.commit()?;
+
+            done += batch;
+            info!(
+                target: "storage::db::migration",
+                table = name,
+                progress = %format!("{:.2}%", done as f64 / total.max(1) as f64 * 100.0),
+                "Re-encoding table"
+            );
+            if batch < tx.batch_size {
+                break
+            }
+        }
+
+        // the temporary table is complete, replace the entries of the table
+        let table = tx.tx().open_db(Some(name)).map_err(|e| DatabaseError::Open(e.into()))?;
+        tx.tx().clear_db(table.dbi()).map_err(|e| DatabaseError::Delete(e.into()))?;
+        tx.delete(REENCODED_KEY)?;
+        tx.delete(REENCODED_VALUE)?;
+        tx.put(MOVING_KEY, &[])?;
+        tx.commit()?;
+    }
+
+    loop {
+        let table = tx.tx().open_db(Some(name)).map_err(|e| DatabaseError::Open(e.into()))?;
+        let temp =
+            tx.tx().open_db(Some(temp_name.as_str())).map_err(|e| DatabaseError::Open(e.into()))?;
+
+        let entries = {
+            let mut cursor =
+                tx.tx().cursor(&temp).map_err(|e| DatabaseError::InitCursor(e.into()))?;
+            cursor
+                .iter_start::<Vec<u8>, Vec<u8>>()
+                .take(tx.batch_size)
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|e| DatabaseError::Read(e.into()))?
+        };
+        if entries.is_empty() {
+            // SAFETY: the cursors over the temporary table were dropped
+            unsafe { tx.tx().drop_db(temp) }.map_err(|e| DatabaseError::Delete(e.into()))?;
+            tx.delete(MOVING_KEY)?;
+            return Ok(())
+        }
+
+        for (key, value) in entries {
+            tx.tx()
+                .del(temp.dbi(), &key, Some(&value))
+                .map_err(|e| DatabaseError::Delete(e.into()))?;
+            put(tx.tx(), &table, name, key, &value)?;
+        }
+        tx.commit()?;
+    }
+}
+
+/// An ordered set of table layout changes that upgrades the database to [Migration::version].
+#[derive(Debug, Clone)]
+pub struct Migration {
+    /// The database version after the migration is applied.
+    pub version: u64,
+    /// A human-readable description of the migration.
+    pub description: &'static str,
+    /// The steps of the migration, applied in order.
+    pub steps: Vec<MigrationStep>,
+}
+
+/// Returns the migrations of the database, one to each version above the first one up to
+/// [DB_VERSION].
+pub fn migrations() -> Vec<Migration> {
+    vec![
+        Migration {
+            version: 2,
+            description: "create the log index tables",
+            steps: vec![
+                MigrationStep::CreateTable {
+                    name: tables::LogAddressHistory::NAME,
+                    table_type: TableType::Table,
+                },
+                MigrationStep::CreateTable {
+                    name: tables::LogTopicHistory::NAME,
+                    table_type: TableType::Table,
+                },
+            ],
+        },
+        Migration {
+            version: 3,
+            description: "add the progress in transactions to the execution stage checkpoint",
+            steps: vec![MigrationStep::ReencodeTable {
+                name: tables::SyncStage::NAME,
+                reencode: reencode_execution_checkpoint,
+            }],
+        },
+    ]
+}
+
+/// Re-encodes the checkpoint of the execution stage with the progress in transactions, see
+/// [ExecutionCheckpoint::transactions]. The progress is left empty, the stage counts the
+/// transactions on its next run.
+fn reencode_execution_checkpoint(
+    key: &[u8],
+    value: &[u8],
+) -> Result<(Vec<u8>, Vec<u8>), DatabaseError> {
+    if key != StageId::Execution.to_string().as_bytes() {
+        return Ok((key.to_vec(), value.to_vec()))
+    }
+
+    let (checkpoint, _) = legacy::StageCheckpoint::from_compact(value, value.len());
+    let stage_checkpoint = match checkpoint.stage_checkpoint {
+        Some(legacy::StageUnitCheckpoint { variant: legacy::EXECUTION_VARIANT, data }) => {
+            let (execution, _) = legacy::ExecutionCheckpoint::from_compact(&data, data.len());
+            Some(StageUnitCheckpoint::Execution(ExecutionCheckpoint {
+                block_range: execution.block_range,
+                progress: execution.progress,
+                transactions: EntitiesCheckpoint::default(),
+            }))
+        }
+        // only the execution stage checkpoint changed its layout
+        Some(_) => return Ok((key.to_vec(), value.to_vec())),
+        None => None,
+    };
+
+    let mut buf = Vec::new();
+    StageCheckpoint { block_number: checkpoint.block_number, stage_checkpoint }
+        .to_compact(&mut buf);
+    Ok((key.to_vec(), buf))
+}
+
+/// Layouts of the tables before they were migrated.
+mod legacy {
+    use reth_codecs::Compact;
+    use reth_primitives::{
+        stage::{CheckpointBlockRange, EntitiesCheckpoint},
+        BlockNumber,
+    };
+
+    /// The variant of the execution stage checkpoint in the encoding of a stage checkpoint.
+    pub(super) const EXECUTION_VARIANT: u8 = 3;
+
+    /// A [reth_primitives::stage::StageCheckpoint] before database version 3.
+    #[derive(Debug, Compact)]
+    pub(super) struct StageCheckpoint {
+        pub(super) block_number: BlockNumber,
+        pub(super) stage_checkpoint: Option<StageUnitCheckpoint>,
+    }
+
+    /// An encoded [reth_primitives::stage::StageUnitCheckpoint] before database version 3.
+    #[derive(Debug)]
+    pub(super) struct StageUnitCheckpoint {
+        pub(super) variant: u8,
+        pub(super) data: Vec<u8>,
+    }
+
+    impl Compact for StageUnitCheckpoint {
+        fn to_compact<B>(self, buf: &mut B) -> usize
+        where
+            B: bytes::BufMut + AsMut<[u8]>,
+        {
+            buf.put_u8(self.variant);
+            buf.put_slice(&self.data);
+            1 + self.data.len()
+        }
+
+        fn from_compact(buf: &[u8], len: usize) -> (Self, &[u8]) {
+            let this = Self { variant: buf[0], data: buf[1..len].to_vec() };
+            (this, &buf[len..])
+        }
+    }
+
+    /// A [reth_primitives::stage::ExecutionCheckpoint] before database version 3.
+    #[derive(Debug, Compact)]
+    pub(super) struct ExecutionCheckpoint {
+        pub(super) block_range: CheckpointBlockRange,
+        pub(super) progress: EntitiesCheckpoint,
+    }
+}
+
+/// Error when migrating the database with the [Migrator].
+#[derive(thiserror::Error, Debug)]
+pub enum MigrationError {
+    /// Failed to apply a migration.
+    #[error(transparent)]
+    Database(#[from] DatabaseError),
+    /// Failed to read the version of the database.
+    #[error(transparent)]
+    Version(#[from] DatabaseVersionError),
+    /// Failed to write the version of the database.
+    #[error("failed to write the database version file: {0}")]
+    Io(#[from] io::Error),
+    /// There is no migration to a version between the database version and the target version.
+    #[error("no migration to database version v{version} is available")]
+    MissingMigration {
+        /// The version without a migration.
+        version: u64,
+    },
+    /// The database version is newer than the target version.
+    #[error("database version v{version} is newer than the target version v{target}")]
+    NewerVersion {
+        /// The version of the database.
+        version: u64,
+        /// The target version.
+        target: u64,
+    },
+}
+
+/// The outcome of [Migrator::run].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MigrationOutcome {
+    /// The database version before the migrations.
+    pub from_version: u64,
+    /// The database version after the migrations, which is unchanged on a dry run.
+    pub to_version: u64,
+    /// The versions of the applied migrations.
+    pub applied: Vec<u64>,
+}
+
+/// Applies the pending [Migration]s to a database.
+#[derive(Debug)]
+pub struct Migrator<'a> {
+    /// The database to migrate.
+    db: &'a DatabaseEnv,
+    /// The path of the database, containing the database version file.
+    db_path: &'a Path,
+    /// The known migrations.
+    migrations: Vec<Migration>,
+    /// The version to migrate the database to.
+    target_version: u64,
+    /// Whether the migrations are rolled back after they were applied.
+    dry_run: bool,
+    /// Number of entries of a table that are re-encoded or moved in one transaction.
+    batch_size: usize,
+}
+
+impl<'a> Migrator<'a> {
+    /// Creates a new migrator that applies the [migrations] up to [DB_VERSION].
+    pub fn new(db: &'a DatabaseEnv, db_path: &'a Path) -> Self {
+        Self {
+            db,
+            db_path,
+            migrations: migrations(),
+            target_version: DB_VERSION,
+            dry_run: false,
+            batch_size: REENCODE_BATCH_SIZE,
+        }
+    }
+
+    /// Sets the known migrations.
+    pub fn with_migrations(mut self, migrations: Vec<Migration>) -> Self {
+        self.migrations = migrations;
+        self
+    }
+
+    /// Sets the version to migrate the database to.
+    pub fn with_target_version(mut self, target_version: u64) -> Self {
+        self.target_version = target_version;
+        self
+    }
+
+    /// Sets whether the migrations are only tried, and rolled back afterwards.
+    pub fn with_dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    /// Returns the version of the database.
+    ///
+    /// The version is read from the [SCHEMA_VERSION_TABLE], or from the database version file if
+    /// the database was never migrated.
+    pub fn version(&self) -> Result<u64, MigrationError> {
+        let tx = self.db.begin_ro_txn().map_err(|e| DatabaseError::InitTx(e.into()))?;
+        match decode_u64(read_schema_value(&tx, VERSION_KEY)?)? {
+            Some(version) => Ok(version),
+            None => Ok(get_db_version(self.db_path)?),
+        }
+    }
+
+    /// Returns the migrations that are pending for the database, in the order they are applied.
+    pub fn pending(&self) -> Result<Vec<&Migration>, MigrationError> {
+        self.pending_from(self.version()?)
+    }
+
+    fn pending_from(&self, version: u64) -> Result<Vec<&Migration>, MigrationError> {
+        if version > self.target_version {
+            return Err(MigrationError::NewerVersion { version, target: self.target_version })
+        }
+
+        (version + 1..=self.target_version)
+            .map(|version| {
+                self.migrations
+                    .iter()
+                    .find(|migration| migration.version == version)
+                    .ok_or(MigrationError::MissingMigration { version })
+            })
+            .collect()
+    }
+
+    /// Applies the pending migrations.
+    ///
+    /// Every migration is committed together with the new version, and the progress of its steps
+    /// is committed along with their changes, so an interrupted run resumes at the step that
+    /// wasn't finished. The database version file is updated once the migrations are committed.
+    /// On a dry run, all migrations are applied in one transaction that is aborted afterwards.
+    pub fn run(&self) -> Result<MigrationOutcome, MigrationError> {
+        let from_version = self.version()?;
+        let pending = self.pending_from(from_version)?;
+        let mut outcome =
+            MigrationOutcome { from_version, to_version: from_version, applied: Vec::new() };
+        if pending.is_empty() {
+            // the run was interrupted before the version file was written
+            self.write_version_file(from_version)?;
+            return Ok(outcome)
+        }
+
+        info!(
+            target: "storage::db::migration",
+            from_version,
+            to_version = self.target_version,
+            migrations = pending.len(),
+            dry_run = self.dry_run,
+            "Migrating database"
+        );
+
+        let mut tx = MigrationTx::new(self.db, self.dry_run, self.batch_size)?;
+        for migration in pending {
+            info!(
+                target: "storage::db::migration",
+                version = migration.version,
+                description = migration.description,
+                "Applying database migration"
+            );
+
+            let first_step = decode_u64(tx.get(STEP_KEY)?)?.unwrap_or_default() as usize;
+            for (idx, step) in migration.steps.iter().enumerate().skip(first_step) {
+                step.apply(&mut tx)?;
+                tx.put(STEP_KEY, &(idx as u64 + 1).to_be_bytes())?;
+            }
+            tx.delete(STEP_KEY)?;
+            tx.put(VERSION_KEY, &migration.version.to_be_bytes())?;
+            tx.commit()?;
+
+            if !self.dry_run {
+                outcome.to_version = migration.version;
+            }
+            outcome.applied.push(migration.version);
+        }
+
+        // the transaction of a dry run is aborted on drop
+        drop(tx);
+        self.write_version_file(outcome.to_version)?;
+
+        info!(
+            target: "storage::db::migration",
+            version = outcome.to_version,
+            dry_run = self.dry_run,
+            "Database migrated"
+        );
+
+        Ok(outcome)
+    }
+
+    /// Writes the version to the database version file if it's outdated, unless it's a dry run.
+    fn write_version_file(&self, version: u64) -> Result<(), MigrationError> {
+        if !self.dry_run && get_db_version(self.db_path).ok() != Some(version) {
+            write_db_version_file(self.db_path, version)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{mdbx::DatabaseEnvKind, version::create_db_version_file};
+    use assert_matches::assert_matches;
+    use reth_primitives::stage::CheckpointBlockRange;
+    use tempfile::tempdir;
+
+    fn swap(key: &[u8], value: &[u8]) -> Result<(Vec<u8>, Vec<u8>), DatabaseError> {
+        Ok((value.to_vec(), key.to_vec()))
+    }
+
+    fn swap_until_b(key: &[u8], value: &[u8]) -> Result<(Vec<u8>, Vec<u8>), DatabaseError> {
+        if key == b"b" {
+            return Err(DatabaseError::Decode)
+        }
+        swap(key, value)
+    }
+
+    fn test_migrations(reencode: ReencodeFn) -> Vec<Migration> {
+        vec![
+            Migration {
+                version: 1,
+                description: "create tables",
+                steps: vec![
+                    MigrationStep::CreateTable { name: "Old", table_type: TableType::Table },
+                    MigrationStep::CreateTable { name: "Swapped", table_type: TableType::Table },
+                ],
+            },
+            Migration {
+                version: 2,
+                description: "swap keys and values, drop the old table",
+                steps: vec![
+                    MigrationStep::ReencodeTable { name: "Swapped", reencode },
+                    MigrationStep::DropTable { name: "Old" },
+                    MigrationStep::DropTable { name: "Missing" },
+                ],
+            },
+        ]
+    }
+
+    fn entries(db: &DatabaseEnv, name: &str) -> Option<Vec<(Vec<u8>, Vec<u8>)>> {
+        let tx = db.begin_ro_txn().unwrap();
+        let table = tx.open_db(Some(name)).ok()?;
+        let mut cursor = tx.cursor(&table).unwrap();
+        let entries = cursor.iter_start().collect::<Result<Vec<_>, _>>().unwrap();
+        Some(entries)
+    }
+
+    #[test]
+    fn migrate() {
+        let dir = tempdir().unwrap();
+        write_db_version_file(&dir, 0).unwrap();
+        let db = DatabaseEnv::open(dir.path(), DatabaseEnvKind::RW, None).unwrap();
+        let migrator = Migrator::new(&db, dir.path())
+            .with_migrations(test_migrations(swap))
+            .with_target_version(1);
+
+        let outcome = migrator.run().unwrap();
+        assert_eq!(outcome, MigrationOutcome { from_version: 0, to_version: 1, applied: vec![1] });
+        assert_eq!(get_db_version(&dir).unwrap(), 1);
+        assert_eq!(entries(&db, "Old"), Some(vec![]));
+
+        let tx = db.begin_rw_txn().unwrap();
+        let table = tx.open_db(Some("Swapped")).unwrap();
+        tx.put(table.dbi(), b"a", b"2", WriteFlags::UPSERT).unwrap();
+        tx.put(table.dbi(), b"b", b"1", WriteFlags::UPSERT).unwrap();
+        tx.commit().unwrap();
+
+        let outcome = migrator.with_target_version(2).run().unwrap();
+        assert_eq!(outcome, MigrationOutcome { from_version: 1, to_version: 2, applied: vec![2] });
+        assert_eq!(get_db_version(&dir).unwrap(), 2);
+        assert_eq!(entries(&db, "Old"), None);
+        assert_eq!(entries(&db, "Swapped.migration"), None);
+        assert_eq!(
+            entries(&db, "Swapped"),
+            Some(vec![(b"1".to_vec(), b"b".to_vec()), (b"2".to_vec(), b"a".to_vec())])
+        );
+    }
+
+    #[test]
+    fn dry_run() {
+        let dir = tempdir().unwrap();
+        write_db_version_file(&dir, 0).unwrap();
+        let db = DatabaseEnv::open(dir.path(), DatabaseEnvKind::RW, None).unwrap();
+
+        let outcome = Migrator::new(&db, dir.path())
+            .with_migrations(test_migrations(swap))
+            .with_target_version(2)
+            .with_dry_run(true)
+            .run()
+            .unwrap();
+        assert_eq!(
+            outcome,
+            MigrationOutcome { from_version: 0, to_version: 0, applied: vec![1, 2] }
+        );
+        assert_eq!(get_db_version(&dir).unwrap(), 0);
+        assert_eq!(entries(&db, "Swapped"), None);
+    }
+
+    #[test]
+    fn invalid_versions() {
+        let dir = tempdir().unwrap();
+        write_db_version_file(&dir, 0).unwrap();
+        let db = DatabaseEnv::open(dir.path(), DatabaseEnvKind::RW, None).unwrap();
+
+        let migrator = Migrator::new(&db, dir.path())
+            .with_migrations(test_migrations(swap))
+            .with_target_version(3);
+        assert_matches!(migrator.pending(), Err(MigrationError::MissingMigration { version: 3 }));
+        assert_matches!(migrator.run(), Err(MigrationError::MissingMigration { version: 3 }));
+        assert_eq!(get_db_version(&dir).unwrap(), 0);
+
+        create_db_version_file(&dir).unwrap();
+        let migrator = Migrator::new(&db, dir.path()).with_target_version(0);
+        assert_matches!(
+            migrator.pending(),
+            Err(MigrationError::NewerVersion { version: DB_VERSION, target: 0 })
+        );
+    }
+
+    #[test]
+    fn resume_version_from_db() {
+        let dir = tempdir().unwrap();
+        write_db_version_file(&dir, 0).unwrap();
+        let db = DatabaseEnv::open(dir.path(), DatabaseEnvKind::RW, None).unwrap();
+        let migrator = Migrator::new(&db, dir.path())
+            .with_migrations(test_migrations(swap))
+            .with_target_version(1);
+        migrator.run().unwrap();
+
+        // the node stopped before the version file was written
+        write_db_version_file(&dir, 0).unwrap();
+        assert_eq!(migrator.version().unwrap(), 1);
+        assert!(migrator.pending().unwrap().is_empty());
+
+        let outcome = migrator.run().unwrap();
+        assert_eq!(outcome, MigrationOutcome { from_version: 1, to_version: 1, applied: vec![] });
+        assert_eq!(get_db_version(&dir).unwrap(), 1);
+    }
+
+    #[test]
+    fn resume_reencode() {
+        let dir = tempdir().unwrap();
+        write_db_version_file(&dir, 0).unwrap();
+        let db = DatabaseEnv::open(dir.path(), DatabaseEnvKind::RW, None).unwrap();
+        Migrator::new(&db, dir.path())
+            .with_migrations(test_migrations(swap))
+            .with_target_version(1)
+            .run()
+            .unwrap();
+
+        let tx = db.begin_rw_txn().unwrap();
+        let table = tx.open_db(Some("Swapped")).unwrap();
+        tx.put(table.dbi(), b"a", b"2", WriteFlags::UPSERT).unwrap();
+        tx.put(table.dbi(), b"b", b"1", WriteFlags::UPSERT).unwrap();
+        tx.put(table.dbi(), b"c", b"3", WriteFlags::UPSERT).unwrap();
+        tx.commit().unwrap();
+
+        // the re-encoding fails after the first batch was committed
+        let mut migrator = Migrator::new(&db, dir.path())
+            .with_migrations(test_migrations(swap_until_b))
+            .with_target_version(2);
+        migrator.batch_size = 1;
+        assert_matches!(migrator.run(), Err(MigrationError::Database(DatabaseError::Decode)));
+        assert_eq!(migrator.version().unwrap(), 1);
+        assert_eq!(entries(&db, "Swapped.migration"), Some(vec![(b"2".to_vec(), b"a".to_vec())]));
+
+        // the migration resumes after the committed batch
+        let mut migrator = migrator.with_migrations(test_migrations(swap));
+        migrator.batch_size = 1;
+        let outcome = migrator.run().unwrap();
+        assert_eq!(outcome, MigrationOutcome { from_version: 1, to_version: 2, applied: vec![2] });
+        assert_eq!(entries(&db, "Swapped.migration"), None);
+        assert_eq!(
+            entries(&db, "Swapped"),
+            Some(vec![
+                (b"1".to_vec(), b"b".to_vec()),
+                (b"2".to_vec(), b"a".to_vec()),
+                (b"3".to_vec(), b"c".to_vec())
+            ])
+        );
+    }
+
+    #[test]
+    fn reencode_legacy_execution_checkpoint() {
+        let execution = legacy::ExecutionCheckpoint {
+            block_range: CheckpointBlockRange { from: 1, to: 100 },
+            progress: EntitiesCheckpoint { processed: 21_000, total: 42_000 },
+        };
+        let mut data = Vec::new();
+        execution.to_compact(&mut data);
+        let checkpoint = legacy::StageCheckpoint {
+            block_number: 50,
+            stage_checkpoint: Some(legacy::StageUnitCheckpoint {
+                variant: legacy::EXECUTION_VARIANT,
+                data,
+            }),
+        };
+        let mut value = Vec::new();
+        checkpoint.to_compact(&mut value);
+
+        let key = StageId::Execution.to_string();
+        let (new_key, new_value) = reencode_execution_checkpoint(key.as_bytes(), &value).unwrap();
+        assert_eq!(new_key, key.as_bytes());
+        let (checkpoint, _) = StageCheckpoint::from_compact(&new_value, new_value.len());
+        assert_eq!(
+            checkpoint,
+            StageCheckpoint::new(50).with_execution_stage_checkpoint(ExecutionCheckpoint {
+                block_range: CheckpointBlockRange { from: 1, to: 100 },
+                progress: EntitiesCheckpoint { processed: 21_000, total: 42_000 },
+                transactions: EntitiesCheckpoint::default(),
+            })
+        );
+
+        // the checkpoints of the other stages are kept
+        let (_, other) = reencode_execution_checkpoint(b"Headers", &value).unwrap();
+        assert_eq!(other, value);
+    }
+}
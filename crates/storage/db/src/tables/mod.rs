@@ -51,7 +51,7 @@ pub enum TableType {
 }
 
 /// Number of tables that should be present inside database.
-pub const NUM_TABLES: usize = 26;
+pub const NUM_TABLES: usize = 29;
 
 /// The general purpose of this is to use with a combination of Tables enum,
 /// by implementing a `TableViewer` trait you can operate on db tables in an abstract way.
@@ -210,6 +210,9 @@ tables!([
             Bytecodes,
             AccountHistory,
             StorageHistory,
+            LogAddressHistory,
+            LogTopicHistory,
+            TransactionAddressHistory,
             HashedAccount,
             AccountsTrie,
             TxSenders,
@@ -389,6 +392,31 @@ table!(
     ( StorageHistory ) StorageShardedKey | BlockNumberList
 );
 
+table!(
+    /// Stores pointers to the blocks with logs emitted by each address.
+    ///
+    /// The shards are laid out like the shards of [`AccountHistory`]. This table is only populated
+    /// if the optional log index is enabled.
+    ( LogAddressHistory ) ShardedKey<Address> | BlockNumberList
+);
+
+table!(
+    /// Stores pointers to the blocks with logs that contain each topic, in any position.
+    ///
+    /// The shards are laid out like the shards of [`AccountHistory`]. This table is only populated
+    /// if the optional log index is enabled.
+    ( LogTopicHistory ) ShardedKey<B256> | BlockNumberList
+);
+
+table!(
+    /// Stores pointers to the blocks with transactions sent from or to each address, or creating
+    /// it.
+    ///
+    /// The shards are laid out like the shards of [`AccountHistory`]. This table is only populated
+    /// if the optional transaction address index is enabled.
+    ( TransactionAddressHistory ) ShardedKey<Address> | BlockNumberList
+);
+
 dupsort!(
     /// Stores the state of an account before a certain transaction changed it.
     /// Change on state can be: account is created, selfdestructed, touched while empty
@@ -479,6 +507,9 @@ mod tests {
         (TableType::Table, Bytecodes::NAME),
         (TableType::Table, AccountHistory::NAME),
         (TableType::Table, StorageHistory::NAME),
+        (TableType::Table, LogAddressHistory::NAME),
+        (TableType::Table, LogTopicHistory::NAME),
+        (TableType::Table, TransactionAddressHistory::NAME),
         (TableType::Table, HashedAccount::NAME),
         (TableType::Table, AccountsTrie::NAME),
         (TableType::Table, TxSenders::NAME),
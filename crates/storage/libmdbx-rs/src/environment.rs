@@ -151,6 +151,36 @@ impl Environment {
         mdbx_result(unsafe { ffi::mdbx_env_sync_ex(self.env_ptr(), force, false) })
     }
 
+    /// Clears the reader slots of processes that exited without ending their read transactions,
+    /// and returns the number of cleared slots.
+    ///
+    /// Stale reader slots count against the maximum number of readers, and keep the pages of the
+    /// snapshot they were reading from being reused.
+    pub fn reader_check(&self) -> Result<usize> {
+        let mut dead = 0;
+        mdbx_result(unsafe { ffi::mdbx_reader_check(self.env_ptr(), &mut dead) })?;
+        Ok(dead as usize)
+    }
+
+    /// Copies the environment to a new file at the given path.
+    ///
+    /// The copy is made from a read-only transaction, so it is a consistent snapshot and the
+    /// environment can still be written to while copying. The destination file must not exist.
+    ///
+    /// If `compact` is set, free pages are omitted and all pages are renumbered sequentially.
+    pub fn copy(&self, dest: &Path, compact: bool) -> Result<()> {
+        let dest = CString::new(path_to_bytes(dest)).map_err(|_| Error::Invalid)?;
+        let flags = if compact { ffi::MDBX_CP_COMPACT } else { ffi::MDBX_CP_DEFAULTS };
+        mdbx_result(unsafe {
+            ffi::mdbx_env_copy(
+                self.env_ptr(),
+                dest.as_ptr(),
+                flags | ffi::MDBX_CP_FORCE_DYNAMIC_SIZE,
+            )
+        })?;
+        Ok(())
+    }
+
     /// Retrieves statistics about this environment.
     pub fn stat(&self) -> Result<Stat> {
         unsafe {
@@ -685,20 +715,6 @@ impl EnvironmentBuilder {
                     ))?;
                 }
 
-                #[cfg(unix)]
-                fn path_to_bytes<P: AsRef<Path>>(path: P) -> Vec<u8> {
-                    use std::os::unix::ffi::OsStrExt;
-                    path.as_ref().as_os_str().as_bytes().to_vec()
-                }
-
-                #[cfg(windows)]
-                fn path_to_bytes<P: AsRef<Path>>(path: P) -> Vec<u8> {
-                    // On Windows, could use std::os::windows::ffi::OsStrExt to encode_wide(),
-                    // but we end up with a Vec<u16> instead of a Vec<u8>, so that doesn't
-                    // really help.
-                    path.as_ref().to_string_lossy().to_string().into_bytes()
-                }
-
                 let path = match CString::new(path_to_bytes(path)) {
                     Ok(path) => path,
                     Err(_) => return Err(Error::Invalid),
@@ -911,6 +927,20 @@ unsafe fn handle_slow_readers_callback(callback: HandleSlowReadersCallback) -> f
     Some(std::mem::transmute(closure_ptr))
 }
 
+#[cfg(unix)]
+fn path_to_bytes<P: AsRef<Path>>(path: P) -> Vec<u8> {
+    use std::os::unix::ffi::OsStrExt;
+    path.as_ref().as_os_str().as_bytes().to_vec()
+}
+
+#[cfg(windows)]
+fn path_to_bytes<P: AsRef<Path>>(path: P) -> Vec<u8> {
+    // On Windows, could use std::os::windows::ffi::OsStrExt to encode_wide(),
+    // but we end up with a Vec<u16> instead of a Vec<u8>, so that doesn't
+    // really help.
+    path.as_ref().to_string_lossy().to_string().into_bytes()
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{Environment, Error, Geometry, HandleSlowReadersReturnCode, PageSize, WriteFlags};
@@ -154,3 +154,25 @@ fn test_freelist() {
     freelist = env.freelist().unwrap();
     assert!(freelist > 0);
 }
+
+#[test]
+fn test_copy() {
+    let dir = tempdir().unwrap();
+    let env = Environment::builder().open(dir.path()).unwrap();
+
+    let tx = env.begin_rw_txn().unwrap();
+    tx.put(tx.open_db(None).unwrap().dbi(), b"key", b"val", WriteFlags::default()).unwrap();
+    tx.commit().unwrap();
+
+    for compact in [false, true] {
+        let backup = tempdir().unwrap();
+        env.copy(&backup.path().join("mdbx.dat"), compact).unwrap();
+
+        // copying to an existing file should fail
+        assert!(env.copy(&backup.path().join("mdbx.dat"), compact).is_err());
+
+        let copy = Environment::builder().open(backup.path()).unwrap();
+        let tx = copy.begin_ro_txn().unwrap();
+        assert_eq!(tx.get(tx.open_db(None).unwrap().dbi(), b"key").unwrap(), Some(*b"val"));
+    }
+}
@@ -1,23 +1,25 @@
 use crate::{
     providers::{
         state::{historical::HistoricalStateProvider, latest::LatestStateProvider},
-        SnapshotProvider,
+        SnapshotProvider, StateCache,
     },
     traits::{BlockSource, ReceiptProvider},
     BlockHashReader, BlockNumReader, BlockReader, ChainSpecProvider, EvmEnvProvider,
-    HeaderProvider, HeaderSyncGap, HeaderSyncGapProvider, HeaderSyncMode, ProviderError,
-    PruneCheckpointReader, StageCheckpointReader, StateProviderBox, TransactionVariant,
-    TransactionsProvider, WithdrawalsProvider,
+    HeaderProvider, HeaderSyncGap, HeaderSyncGapProvider, HeaderSyncMode, LogIndexReader,
+    ProviderError, PruneCheckpointReader, SnapStateReader, StageCheckpointReader, StateProviderBox,
+    TransactionAddressIndexReader, TransactionVariant, TransactionsProvider, WithdrawalsProvider,
+};
+use reth_db::{
+    database::Database, init_db, models::StoredBlockBodyIndices, open_db_read_only, DatabaseEnv,
 };
-use reth_db::{database::Database, init_db, models::StoredBlockBodyIndices, DatabaseEnv};
 use reth_interfaces::{db::LogLevel, provider::ProviderResult, RethError, RethResult};
 use reth_primitives::{
     snapshot::HighestSnapshots,
     stage::{StageCheckpoint, StageId},
-    Address, Block, BlockHash, BlockHashOrNumber, BlockNumber, BlockWithSenders, ChainInfo,
-    ChainSpec, Header, PruneCheckpoint, PruneSegment, Receipt, SealedBlock, SealedBlockWithSenders,
-    SealedHeader, TransactionMeta, TransactionSigned, TransactionSignedNoHash, TxHash, TxNumber,
-    Withdrawal, B256, U256,
+    Account, Address, Block, BlockHash, BlockHashOrNumber, BlockNumber, BlockWithSenders, Bytecode,
+    Bytes, ChainInfo, ChainSpec, Header, PruneCheckpoint, PruneSegment, Receipt, SealedBlock,
+    SealedBlockWithSenders, SealedHeader, TransactionMeta, TransactionSigned,
+    TransactionSignedNoHash, TxHash, TxNumber, Withdrawal, B256, U256,
 };
 use revm::primitives::{BlockEnv, CfgEnv};
 use std::{
@@ -44,6 +46,8 @@ pub struct ProviderFactory<DB> {
     chain_spec: Arc<ChainSpec>,
     /// Snapshot Provider
     snapshot_provider: Option<Arc<SnapshotProvider>>,
+    /// Cache of the latest state
+    state_cache: Option<StateCache>,
 }
 
 impl<DB: Clone> Clone for ProviderFactory<DB> {
@@ -52,6 +56,7 @@ impl<DB: Clone> Clone for ProviderFactory<DB> {
             db: self.db.clone(),
             chain_spec: Arc::clone(&self.chain_spec),
             snapshot_provider: self.snapshot_provider.clone(),
+            state_cache: self.state_cache.clone(),
         }
     }
 }
@@ -61,7 +66,7 @@ impl<DB: Database> ProviderFactory<DB> {}
 impl<DB> ProviderFactory<DB> {
     /// Create new database provider factory.
     pub fn new(db: DB, chain_spec: Arc<ChainSpec>) -> Self {
-        Self { db, chain_spec, snapshot_provider: None }
+        Self { db, chain_spec, snapshot_provider: None, state_cache: None }
     }
 
     /// Create new database provider by passing a path. [`ProviderFactory`] will own the database
@@ -75,9 +80,40 @@ impl<DB> ProviderFactory<DB> {
             db: init_db(path, log_level).map_err(|e| RethError::Custom(e.to_string()))?,
             chain_spec,
             snapshot_provider: None,
+            state_cache: None,
+        })
+    }
+
+    /// Create new database provider that opens the database at the given path in read-only mode.
+    ///
+    /// The database can be opened while another process, e.g. a running node, writes to it.
+    /// Snapshots are read from the given directory if provided, new snapshots of the writer are
+    /// picked up with [SnapshotProvider::update_index].
+    ///
+    /// Read transactions should be short-lived, because the writer can't reuse the pages of
+    /// the snapshot a transaction reads from until it ends, which grows the database file.
+    pub fn new_with_database_path_read_only<P: AsRef<Path>>(
+        path: P,
+        chain_spec: Arc<ChainSpec>,
+        snapshots_path: Option<PathBuf>,
+        log_level: Option<LogLevel>,
+    ) -> RethResult<ProviderFactory<DatabaseEnv>> {
+        let snapshot_provider =
+            snapshots_path.map(|path| SnapshotProvider::new(path).map(Arc::new)).transpose()?;
+        Ok(ProviderFactory::<DatabaseEnv> {
+            db: open_db_read_only(path.as_ref(), log_level)
+                .map_err(|e| RethError::Custom(e.to_string()))?,
+            chain_spec,
+            snapshot_provider,
+            state_cache: None,
         })
     }
 
+    /// Returns the snapshot provider, if any.
+    pub fn snapshot_provider(&self) -> Option<&Arc<SnapshotProvider>> {
+        self.snapshot_provider.as_ref()
+    }
+
     /// Database provider that comes with a shared snapshot provider.
     pub fn with_snapshots(
         mut self,
@@ -91,6 +127,21 @@ impl<DB> ProviderFactory<DB> {
         Ok(self)
     }
 
+    /// Database provider that comes with the given shared snapshot provider.
+    pub fn with_snapshot_provider(mut self, snapshot_provider: Arc<SnapshotProvider>) -> Self {
+        self.snapshot_provider = Some(snapshot_provider);
+        self
+    }
+
+    /// Reads the latest state through the given cache.
+    ///
+    /// The cache is only used for the state at its tip, so it should be kept up to date with the
+    /// canonical chain, see [StateCache::update_on_notifications].
+    pub fn with_state_cache(mut self, state_cache: StateCache) -> Self {
+        self.state_cache = Some(state_cache);
+        self
+    }
+
     /// Returns reference to the underlying database.
     pub fn db_ref(&self) -> &DB {
         &self.db
@@ -131,9 +182,30 @@ impl<DB: Database> ProviderFactory<DB> {
     #[track_caller]
     pub fn latest(&self) -> ProviderResult<StateProviderBox> {
         trace!(target: "providers::db", "Returning latest state provider");
+        if self.state_cache.is_some() {
+            let provider = self.provider()?;
+            let block_number = provider.best_block_number()?;
+            return self.latest_with_cache(provider, block_number)
+        }
         Ok(Box::new(LatestStateProvider::new(self.db.tx()?)))
     }
 
+    /// Returns the latest state provider, reading through the state cache if the hash of the
+    /// latest block is known.
+    fn latest_with_cache(
+        &self,
+        provider: DatabaseProviderRO<DB>,
+        block_number: BlockNumber,
+    ) -> ProviderResult<StateProviderBox> {
+        if let Some(state_cache) = &self.state_cache {
+            if let Some(block_hash) = provider.block_hash(block_number)? {
+                let state = LatestStateProvider::new(provider.into_tx());
+                return Ok(Box::new(state_cache.provider(state, block_hash)))
+            }
+        }
+        Ok(Box::new(LatestStateProvider::new(provider.into_tx())))
+    }
+
     /// Storage provider for state at that given block
     fn state_provider_by_block_number(
         &self,
@@ -144,7 +216,7 @@ impl<DB: Database> ProviderFactory<DB> {
         if block_number == provider.best_block_number().unwrap_or_default() &&
             block_number == provider.last_block_number().unwrap_or_default()
         {
-            return Ok(Box::new(LatestStateProvider::new(provider.into_tx())))
+            return self.latest_with_cache(provider, block_number)
         }
 
         // +1 as the changeset that we want is the one that was applied after this block.
@@ -283,6 +355,49 @@ impl<DB: Database> BlockNumReader for ProviderFactory<DB> {
     }
 }
 
+impl<DB: Database> SnapStateReader for ProviderFactory<DB> {
+    fn snap_state_root(&self) -> ProviderResult<B256> {
+        self.provider()?.snap_state_root()
+    }
+
+    fn hashed_account_range(
+        &self,
+        start: B256,
+        limit: usize,
+    ) -> ProviderResult<Vec<(B256, Account)>> {
+        self.provider()?.hashed_account_range(start, limit)
+    }
+
+    fn hashed_storage_range(
+        &self,
+        hashed_address: B256,
+        start: B256,
+        limit: usize,
+    ) -> ProviderResult<Vec<(B256, U256)>> {
+        self.provider()?.hashed_storage_range(hashed_address, start, limit)
+    }
+
+    fn hashed_storage_root(&self, hashed_address: B256) -> ProviderResult<B256> {
+        self.provider()?.hashed_storage_root(hashed_address)
+    }
+
+    fn code_by_hash(&self, code_hash: B256) -> ProviderResult<Option<Bytecode>> {
+        self.provider()?.code_by_hash(code_hash)
+    }
+
+    fn hashed_account_multiproof(&self, targets: &[B256]) -> ProviderResult<Vec<Bytes>> {
+        self.provider()?.hashed_account_multiproof(targets)
+    }
+
+    fn hashed_storage_multiproof(
+        &self,
+        hashed_address: B256,
+        targets: &[B256],
+    ) -> ProviderResult<Vec<Bytes>> {
+        self.provider()?.hashed_storage_multiproof(hashed_address, targets)
+    }
+}
+
 impl<DB: Database> BlockReader for ProviderFactory<DB> {
     fn find_block_by_hash(&self, hash: B256, source: BlockSource) -> ProviderResult<Option<Block>> {
         self.provider()?.find_block_by_hash(hash, source)
@@ -437,6 +552,42 @@ impl<DB: Database> StageCheckpointReader for ProviderFactory<DB> {
     }
 }
 
+impl<DB: Database> LogIndexReader for ProviderFactory<DB> {
+    fn log_index_checkpoint(&self) -> ProviderResult<Option<BlockNumber>> {
+        self.provider()?.log_index_checkpoint()
+    }
+
+    fn blocks_with_log_address(
+        &self,
+        address: Address,
+        range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<Vec<BlockNumber>> {
+        self.provider()?.blocks_with_log_address(address, range)
+    }
+
+    fn blocks_with_log_topic(
+        &self,
+        topic: B256,
+        range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<Vec<BlockNumber>> {
+        self.provider()?.blocks_with_log_topic(topic, range)
+    }
+}
+
+impl<DB: Database> TransactionAddressIndexReader for ProviderFactory<DB> {
+    fn transaction_address_index_checkpoint(&self) -> ProviderResult<Option<BlockNumber>> {
+        self.provider()?.transaction_address_index_checkpoint()
+    }
+
+    fn blocks_with_transaction_address(
+        &self,
+        address: Address,
+        range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<Vec<BlockNumber>> {
+        self.provider()?.blocks_with_transaction_address(address, range)
+    }
+}
+
 impl<DB: Database> EvmEnvProvider for ProviderFactory<DB> {
     fn fill_env_at(
         &self,
@@ -567,6 +718,27 @@ mod tests {
         provider.block_hash(0).unwrap();
     }
 
+    #[test]
+    fn provider_factory_read_only_while_writing() {
+        let chain_spec = Arc::new(ChainSpecBuilder::mainnet().build());
+        let path = tempfile::TempDir::new().expect(ERROR_TEMPDIR).into_path();
+        let factory =
+            ProviderFactory::<DatabaseEnv>::new_with_database_path(&path, chain_spec.clone(), None)
+                .unwrap();
+        let read_only = ProviderFactory::<DatabaseEnv>::new_with_database_path_read_only(
+            &path, chain_spec, None, None,
+        )
+        .unwrap();
+
+        let provider_rw = factory.provider_rw().unwrap();
+        provider_rw.tx_ref().put::<tables::CanonicalHeaders>(0, B256::with_last_byte(1)).unwrap();
+        provider_rw.commit().unwrap();
+
+        let provider = read_only.provider().unwrap();
+        assert_eq!(provider.block_hash(0).unwrap(), Some(B256::with_last_byte(1)));
+        assert!(read_only.provider_rw().is_err());
+    }
+
     #[test]
     fn insert_block_with_prune_modes() {
         let factory = create_test_provider_factory();
@@ -7,9 +7,11 @@ use crate::{
     },
     AccountReader, BlockExecutionWriter, BlockHashReader, BlockNumReader, BlockReader, BlockWriter,
     Chain, EvmEnvProvider, HashingWriter, HeaderProvider, HeaderSyncGap, HeaderSyncGapProvider,
-    HeaderSyncMode, HistoryWriter, OriginalValuesKnown, ProviderError, PruneCheckpointReader,
-    PruneCheckpointWriter, StageCheckpointReader, StorageReader, TransactionVariant,
-    TransactionsProvider, TransactionsProviderExt, WithdrawalsProvider,
+    HeaderSyncMode, HistoryWriter, LogIndexReader, LogIndexWriter, OriginalValuesKnown,
+    ProviderError, PruneCheckpointReader, PruneCheckpointWriter, SnapStateReader,
+    StageCheckpointReader, StorageReader, TransactionAddressIndexReader,
+    TransactionAddressIndexWriter, TransactionVariant, TransactionsProvider,
+    TransactionsProviderExt, WithdrawalsProvider,
 };
 use ahash::{AHashMap, AHashSet};
 use itertools::{izip, Itertools};
@@ -39,13 +41,16 @@ use reth_primitives::{
     },
     stage::{StageCheckpoint, StageId},
     trie::Nibbles,
-    Account, Address, Block, BlockHash, BlockHashOrNumber, BlockNumber, BlockWithSenders,
-    ChainInfo, ChainSpec, GotExpected, Hardfork, Head, Header, PruneCheckpoint, PruneModes,
+    Account, Address, Block, BlockHash, BlockHashOrNumber, BlockNumber, BlockWithSenders, Bytecode,
+    Bytes, ChainInfo, ChainSpec, GotExpected, Hardfork, Head, Header, PruneCheckpoint, PruneModes,
     PruneSegment, Receipt, SealedBlock, SealedBlockWithSenders, SealedHeader, SnapshotSegment,
     StorageEntry, TransactionMeta, TransactionSigned, TransactionSignedEcRecovered,
     TransactionSignedNoHash, TxHash, TxNumber, Withdrawal, B256, U256,
 };
-use reth_trie::{prefix_set::PrefixSetMut, updates::TrieUpdates, HashedPostState, StateRoot};
+use reth_trie::{
+    prefix_set::PrefixSetMut, proof::Proof, updates::TrieUpdates, HashedPostState, StateRoot,
+    StateRootError, StorageRoot,
+};
 use revm::primitives::{BlockEnv, CfgEnv, SpecId};
 use std::{
     collections::{hash_map, BTreeMap, BTreeSet, HashMap},
@@ -208,6 +213,27 @@ where
     Ok(Vec::new())
 }
 
+/// Groups the receipts of the range of blocks by block.
+fn block_receipts(
+    block_bodies: Vec<(BlockNumber, StoredBlockBodyIndices)>,
+    receipts: Vec<(TxNumber, Receipt)>,
+) -> Vec<Vec<Option<Receipt>>> {
+    let mut receipt_iter = receipts.into_iter();
+
+    let mut receipts = Vec::new();
+    // loop break if we are at the end of the blocks.
+    for (_, block_body) in block_bodies.into_iter() {
+        let mut block_receipts = Vec::with_capacity(block_body.tx_count as usize);
+        for _ in block_body.tx_num_range() {
+            if let Some((_, receipt)) = receipt_iter.next() {
+                block_receipts.push(Some(receipt));
+            }
+        }
+        receipts.push(block_receipts);
+    }
+    receipts
+}
+
 impl<TX: DbTx> DatabaseProvider<TX> {
     /// Creates a provider with an inner read-only transaction.
     pub fn new(tx: TX, chain_spec: Arc<ChainSpec>) -> Self {
@@ -220,6 +246,155 @@ impl<TX: DbTx> DatabaseProvider<TX> {
         self
     }
 
+    /// Returns the canonical blocks in the range and their execution outcome.
+    ///
+    /// This is the read-only counterpart of
+    /// [BlockExecutionWriter::get_block_and_execution_range], and like it only returns the correct
+    /// state for ranges that end at the tip of the plain state.
+    pub fn get_committed_chain(&self, range: RangeInclusive<BlockNumber>) -> ProviderResult<Chain> {
+        let mut blocks = Vec::new();
+        for number in range.clone() {
+            let hash = self
+                .block_hash(number)?
+                .ok_or_else(|| ProviderError::HeaderNotFound(number.into()))?;
+            let block = self
+                .block_with_senders(number.into(), TransactionVariant::WithHash)?
+                .ok_or_else(|| ProviderError::BlockNotFound(number.into()))?;
+            blocks.push(block.seal(hash));
+        }
+        let execution_state = self.peek_state(range)?;
+
+        Ok(Chain::new(blocks, execution_state, None))
+    }
+
+    /// Reads the last N blocks of state recreating the [`BundleStateWithReceipts`], see
+    /// [`DatabaseProvider::unwind_or_peek_state`].
+    fn peek_state(
+        &self,
+        range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<BundleStateWithReceipts> {
+        if range.is_empty() {
+            return Ok(BundleStateWithReceipts::default())
+        }
+        let start_block_number = *range.start();
+
+        let block_bodies = self
+            .tx
+            .cursor_read::<tables::BlockBodyIndices>()?
+            .walk_range(range.clone())?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // get transaction receipts
+        let from_transaction_num =
+            block_bodies.first().expect("already checked if there are blocks").1.first_tx_num();
+        let to_transaction_num =
+            block_bodies.last().expect("already checked if there are blocks").1.last_tx_num();
+
+        let storage_changeset = self
+            .tx
+            .cursor_read::<tables::StorageChangeSet>()?
+            .walk_range(BlockNumberAddress::range(range.clone()))?
+            .collect::<Result<Vec<_>, _>>()?;
+        let account_changeset = self
+            .tx
+            .cursor_read::<tables::AccountChangeSet>()?
+            .walk_range(range)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let (state, reverts) = self.populate_bundle_state(account_changeset, storage_changeset)?;
+
+        let receipts = self
+            .tx
+            .cursor_read::<tables::Receipts>()?
+            .walk_range(from_transaction_num..=to_transaction_num)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(BundleStateWithReceipts::new_init(
+            state,
+            reverts,
+            Vec::new(),
+            reth_primitives::Receipts::from_vec(block_receipts(block_bodies, receipts)),
+            start_block_number,
+        ))
+    }
+
+    /// Populates the state before and after the changesets, and the reverts of each block, from
+    /// the changesets and the plain state.
+    fn populate_bundle_state(
+        &self,
+        account_changeset: Vec<(BlockNumber, AccountBeforeTx)>,
+        storage_changeset: Vec<(BlockNumberAddress, StorageEntry)>,
+    ) -> ProviderResult<(BundleStateInit, RevertsInit)> {
+        // iterate previous value and get plain state value to create changeset
+        // Double option around Account represent if Account state is know (first option) and
+        // account is removed (Second Option)
+
+        let mut state: BundleStateInit = HashMap::new();
+
+        // This is not working for blocks that are not at tip. as plain state is not the last
+        // state of end range. We should rename the functions or add support to access
+        // History state. Accessing history state can be tricky but we are not gaining
+        // anything.
+        let mut plain_accounts_cursor = self.tx.cursor_read::<tables::PlainAccountState>()?;
+        let mut plain_storage_cursor = self.tx.cursor_dup_read::<tables::PlainStorageState>()?;
+
+        let mut reverts: RevertsInit = HashMap::new();
+
+        // add account changeset changes
+        for (block_number, account_before) in account_changeset.into_iter().rev() {
+            let AccountBeforeTx { info: old_info, address } = account_before;
+            match state.entry(address) {
+                hash_map::Entry::Vacant(entry) => {
+                    let new_info = plain_accounts_cursor.seek_exact(address)?.map(|kv| kv.1);
+                    entry.insert((old_info, new_info, HashMap::new()));
+                }
+                hash_map::Entry::Occupied(mut entry) => {
+                    // overwrite old account state.
+                    entry.get_mut().0 = old_info;
+                }
+            }
+            // insert old info into reverts.
+            reverts.entry(block_number).or_default().entry(address).or_default().0 = Some(old_info);
+        }
+
+        // add storage changeset changes
+        for (block_and_address, old_storage) in storage_changeset.into_iter().rev() {
+            let BlockNumberAddress((block_number, address)) = block_and_address;
+            // get account state or insert from plain state.
+            let account_state = match state.entry(address) {
+                hash_map::Entry::Vacant(entry) => {
+                    let present_info = plain_accounts_cursor.seek_exact(address)?.map(|kv| kv.1);
+                    entry.insert((present_info, present_info, HashMap::new()))
+                }
+                hash_map::Entry::Occupied(entry) => entry.into_mut(),
+            };
+
+            // match storage.
+            match account_state.2.entry(old_storage.key) {
+                hash_map::Entry::Vacant(entry) => {
+                    let new_storage = plain_storage_cursor
+                        .seek_by_key_subkey(address, old_storage.key)?
+                        .filter(|storage| storage.key == old_storage.key)
+                        .unwrap_or_default();
+                    entry.insert((old_storage.value, new_storage.value));
+                }
+                hash_map::Entry::Occupied(mut entry) => {
+                    entry.get_mut().0 = old_storage.value;
+                }
+            };
+
+            reverts
+                .entry(block_number)
+                .or_default()
+                .entry(address)
+                .or_default()
+                .1
+                .push(old_storage);
+        }
+
+        Ok((state, reverts))
+    }
+
     /// Consume `DbTx` or `DbTxMut`.
     pub fn into_tx(self) -> TX {
         self.tx
@@ -235,6 +410,110 @@ impl<TX: DbTx> DatabaseProvider<TX> {
         &self.tx
     }
 
+    /// Returns the addresses and topics of the logs in the given range of blocks, each with the
+    /// blocks they appear in.
+    ///
+    /// The receipts are read from the snapshots, if they are no longer in the database.
+    fn log_addresses_and_topics_with_range(
+        &self,
+        range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<(BTreeMap<Address, Vec<u64>>, BTreeMap<B256, Vec<u64>>)> {
+        let mut addresses = BTreeMap::<Address, Vec<u64>>::new();
+        let mut topics = BTreeMap::<B256, Vec<u64>>::new();
+
+        for entry in self.tx.cursor_read::<tables::BlockBodyIndices>()?.walk_range(range)? {
+            let (block_number, body) = entry?;
+            let insert = |blocks: &mut Vec<u64>| {
+                if blocks.last() != Some(&block_number) {
+                    blocks.push(block_number);
+                }
+            };
+            for receipt in self.receipts_by_tx_range(body.tx_num_range())? {
+                for log in receipt.logs {
+                    insert(addresses.entry(log.address).or_default());
+                    for topic in log.topics {
+                        insert(topics.entry(topic).or_default());
+                    }
+                }
+            }
+        }
+
+        Ok((addresses, topics))
+    }
+
+    /// Returns the senders, recipients and created contracts of the transactions in the given
+    /// range of blocks, each with the blocks they appear in.
+    ///
+    /// The senders are recovered from the transactions, if they are pruned.
+    fn transaction_addresses_with_range(
+        &self,
+        range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<BTreeMap<Address, Vec<u64>>> {
+        let mut addresses = BTreeMap::<Address, Vec<u64>>::new();
+
+        for entry in self.tx.cursor_read::<tables::BlockBodyIndices>()?.walk_range(range)? {
+            let (block_number, body) = entry?;
+            let mut insert = |address: Address| {
+                let blocks = addresses.entry(address).or_default();
+                if blocks.last() != Some(&block_number) {
+                    blocks.push(block_number);
+                }
+            };
+
+            let transactions = self.transactions_by_tx_range(body.tx_num_range())?;
+            let mut senders = self.senders_by_tx_range(body.tx_num_range())?;
+            if senders.len() != transactions.len() {
+                senders =
+                    TransactionSignedNoHash::recover_signers(&transactions, transactions.len())
+                        .ok_or(ProviderError::SenderRecoveryError)?;
+            }
+
+            for (transaction, sender) in transactions.iter().zip(senders) {
+                insert(sender);
+                match transaction.transaction.to() {
+                    Some(to) => insert(to),
+                    None => insert(sender.create(transaction.transaction.nonce())),
+                }
+            }
+        }
+
+        Ok(addresses)
+    }
+
+    /// Returns the blocks of the given range from the shards of the given key in a history index
+    /// table.
+    fn history_index_blocks<K, T>(
+        &self,
+        key: K,
+        range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<Vec<BlockNumber>>
+    where
+        K: Clone + PartialEq,
+        T: Table<Key = ShardedKey<K>, Value = BlockNumberList>,
+    {
+        let mut blocks = Vec::new();
+        let mut cursor = self.tx.cursor_read::<T>()?;
+        // The first shard with a highest block number above the start of the range is the first
+        // shard that can contain blocks of the range.
+        let mut item = cursor.seek(ShardedKey::new(key.clone(), *range.start()))?;
+        while let Some((sharded_key, list)) = item {
+            if sharded_key.key != key {
+                break
+            }
+            blocks.extend(
+                list.iter(0)
+                    .map(|block| block as u64)
+                    .skip_while(|block| block < range.start())
+                    .take_while(|block| block <= range.end()),
+            );
+            if sharded_key.highest_block_number >= *range.end() {
+                break
+            }
+            item = cursor.next()?;
+        }
+        Ok(blocks)
+    }
+
     /// Return full table as Vec
     pub fn table<T: Table>(&self) -> Result<Vec<KeyValue<T>>, DatabaseError>
     where
@@ -390,6 +669,9 @@ impl<TX: DbTxMut + DbTx> DatabaseProvider<TX> {
         &self,
         range: RangeInclusive<BlockNumber>,
     ) -> ProviderResult<BundleStateWithReceipts> {
+        if !UNWIND {
+            return self.peek_state(range)
+        }
         if range.is_empty() {
             return Ok(BundleStateWithReceipts::default())
         }
@@ -410,130 +692,52 @@ impl<TX: DbTxMut + DbTx> DatabaseProvider<TX> {
             self.get_or_take::<tables::StorageChangeSet, UNWIND>(storage_range)?;
         let account_changeset = self.get_or_take::<tables::AccountChangeSet, UNWIND>(range)?;
 
-        // iterate previous value and get plain state value to create changeset
-        // Double option around Account represent if Account state is know (first option) and
-        // account is removed (Second Option)
+        let (state, reverts) = self.populate_bundle_state(account_changeset, storage_changeset)?;
 
-        let mut state: BundleStateInit = HashMap::new();
-
-        // This is not working for blocks that are not at tip. as plain state is not the last
-        // state of end range. We should rename the functions or add support to access
-        // History state. Accessing history state can be tricky but we are not gaining
-        // anything.
         let mut plain_accounts_cursor = self.tx.cursor_write::<tables::PlainAccountState>()?;
         let mut plain_storage_cursor = self.tx.cursor_dup_write::<tables::PlainStorageState>()?;
 
-        let mut reverts: RevertsInit = HashMap::new();
-
-        // add account changeset changes
-        for (block_number, account_before) in account_changeset.into_iter().rev() {
-            let AccountBeforeTx { info: old_info, address } = account_before;
-            match state.entry(address) {
-                hash_map::Entry::Vacant(entry) => {
-                    let new_info = plain_accounts_cursor.seek_exact(address)?.map(|kv| kv.1);
-                    entry.insert((old_info, new_info, HashMap::new()));
-                }
-                hash_map::Entry::Occupied(mut entry) => {
-                    // overwrite old account state.
-                    entry.get_mut().0 = old_info;
+        // iterate over local plain state remove all account and all storages.
+        for (address, (old_account, new_account, storage)) in state.iter() {
+            // revert account if needed.
+            if old_account != new_account {
+                let existing_entry = plain_accounts_cursor.seek_exact(*address)?;
+                if let Some(account) = old_account {
+                    plain_accounts_cursor.upsert(*address, *account)?;
+                } else if existing_entry.is_some() {
+                    plain_accounts_cursor.delete_current()?;
                 }
             }
-            // insert old info into reverts.
-            reverts.entry(block_number).or_default().entry(address).or_default().0 = Some(old_info);
-        }
-
-        // add storage changeset changes
-        for (block_and_address, old_storage) in storage_changeset.into_iter().rev() {
-            let BlockNumberAddress((block_number, address)) = block_and_address;
-            // get account state or insert from plain state.
-            let account_state = match state.entry(address) {
-                hash_map::Entry::Vacant(entry) => {
-                    let present_info = plain_accounts_cursor.seek_exact(address)?.map(|kv| kv.1);
-                    entry.insert((present_info, present_info, HashMap::new()))
-                }
-                hash_map::Entry::Occupied(entry) => entry.into_mut(),
-            };
-
-            // match storage.
-            match account_state.2.entry(old_storage.key) {
-                hash_map::Entry::Vacant(entry) => {
-                    let new_storage = plain_storage_cursor
-                        .seek_by_key_subkey(address, old_storage.key)?
-                        .filter(|storage| storage.key == old_storage.key)
-                        .unwrap_or_default();
-                    entry.insert((old_storage.value, new_storage.value));
-                }
-                hash_map::Entry::Occupied(mut entry) => {
-                    entry.get_mut().0 = old_storage.value;
-                }
-            };
 
-            reverts
-                .entry(block_number)
-                .or_default()
-                .entry(address)
-                .or_default()
-                .1
-                .push(old_storage);
-        }
-
-        if UNWIND {
-            // iterate over local plain state remove all account and all storages.
-            for (address, (old_account, new_account, storage)) in state.iter() {
-                // revert account if needed.
-                if old_account != new_account {
-                    let existing_entry = plain_accounts_cursor.seek_exact(*address)?;
-                    if let Some(account) = old_account {
-                        plain_accounts_cursor.upsert(*address, *account)?;
-                    } else if existing_entry.is_some() {
-                        plain_accounts_cursor.delete_current()?;
-                    }
+            // revert storages
+            for (storage_key, (old_storage_value, _new_storage_value)) in storage {
+                let storage_entry = StorageEntry { key: *storage_key, value: *old_storage_value };
+                // delete previous value
+                // TODO: This does not use dupsort features
+                if plain_storage_cursor
+                    .seek_by_key_subkey(*address, *storage_key)?
+                    .filter(|s| s.key == *storage_key)
+                    .is_some()
+                {
+                    plain_storage_cursor.delete_current()?
                 }
 
-                // revert storages
-                for (storage_key, (old_storage_value, _new_storage_value)) in storage {
-                    let storage_entry =
-                        StorageEntry { key: *storage_key, value: *old_storage_value };
-                    // delete previous value
-                    // TODO: This does not use dupsort features
-                    if plain_storage_cursor
-                        .seek_by_key_subkey(*address, *storage_key)?
-                        .filter(|s| s.key == *storage_key)
-                        .is_some()
-                    {
-                        plain_storage_cursor.delete_current()?
-                    }
-
-                    // insert value if needed
-                    if *old_storage_value != U256::ZERO {
-                        plain_storage_cursor.upsert(*address, storage_entry)?;
-                    }
+                // insert value if needed
+                if *old_storage_value != U256::ZERO {
+                    plain_storage_cursor.upsert(*address, storage_entry)?;
                 }
             }
         }
 
         // iterate over block body and create ExecutionResult
-        let mut receipt_iter = self
-            .get_or_take::<tables::Receipts, UNWIND>(from_transaction_num..=to_transaction_num)?
-            .into_iter();
-
-        let mut receipts = Vec::new();
-        // loop break if we are at the end of the blocks.
-        for (_, block_body) in block_bodies.into_iter() {
-            let mut block_receipts = Vec::with_capacity(block_body.tx_count as usize);
-            for _ in block_body.tx_num_range() {
-                if let Some((_, receipt)) = receipt_iter.next() {
-                    block_receipts.push(Some(receipt));
-                }
-            }
-            receipts.push(block_receipts);
-        }
+        let receipts = self
+            .get_or_take::<tables::Receipts, UNWIND>(from_transaction_num..=to_transaction_num)?;
 
         Ok(BundleStateWithReceipts::new_init(
             state,
             reverts,
             Vec::new(),
-            reth_primitives::Receipts::from_vec(receipts),
+            reth_primitives::Receipts::from_vec(block_receipts(block_bodies, receipts)),
             start_block_number,
         ))
     }
@@ -958,6 +1162,38 @@ impl<TX: DbTxMut + DbTx> DatabaseProvider<TX> {
         }
         Ok(())
     }
+
+    /// Removes all blocks from the given block number onwards from the shards of the given keys in
+    /// a history index table.
+    fn unwind_history_index_shards<K, T>(
+        &self,
+        keys: impl IntoIterator<Item = K>,
+        block_number: BlockNumber,
+    ) -> ProviderResult<()>
+    where
+        K: Clone + PartialEq,
+        T: Table<Key = ShardedKey<K>, Value = BlockNumberList>,
+    {
+        let mut cursor = self.tx.cursor_write::<T>()?;
+        for key in keys {
+            let partial_shard = unwind_history_shards::<_, T, _>(
+                &mut cursor,
+                ShardedKey::last(key.clone()),
+                block_number,
+                |sharded_key| sharded_key.key == key,
+            )?;
+
+            // Check the last returned partial shard.
+            // If it's not empty, the shard needs to be reinserted.
+            if !partial_shard.is_empty() {
+                cursor.insert(
+                    ShardedKey::last(key),
+                    BlockNumberList::new_pre_sorted(partial_shard),
+                )?;
+            }
+        }
+        Ok(())
+    }
 }
 
 impl<TX: DbTx> AccountReader for DatabaseProvider<TX> {
@@ -1907,6 +2143,67 @@ impl<TX: DbTx> StorageReader for DatabaseProvider<TX> {
     }
 }
 
+impl<TX: DbTx> SnapStateReader for DatabaseProvider<TX> {
+    fn snap_state_root(&self) -> ProviderResult<B256> {
+        let number = self.best_block_number()?;
+        let header =
+            self.header_by_number(number)?.ok_or(ProviderError::HeaderNotFound(number.into()))?;
+        Ok(header.state_root)
+    }
+
+    fn hashed_account_range(
+        &self,
+        start: B256,
+        limit: usize,
+    ) -> ProviderResult<Vec<(B256, Account)>> {
+        let mut cursor = self.tx.cursor_read::<tables::HashedAccount>()?;
+        Ok(cursor.walk(Some(start))?.take(limit).collect::<Result<Vec<_>, _>>()?)
+    }
+
+    fn hashed_storage_range(
+        &self,
+        hashed_address: B256,
+        start: B256,
+        limit: usize,
+    ) -> ProviderResult<Vec<(B256, U256)>> {
+        let mut cursor = self.tx.cursor_dup_read::<tables::HashedStorage>()?;
+        let mut slots = Vec::new();
+        let mut entry = cursor.seek_by_key_subkey(hashed_address, start)?;
+        while let Some(StorageEntry { key, value }) = entry {
+            if slots.len() >= limit {
+                break
+            }
+            slots.push((key, value));
+            entry = cursor.next_dup_val()?;
+        }
+        Ok(slots)
+    }
+
+    fn hashed_storage_root(&self, hashed_address: B256) -> ProviderResult<B256> {
+        Ok(StorageRoot::from_tx_hashed(&self.tx, hashed_address)
+            .root()
+            .map_err(|err| Into::<DatabaseError>::into(StateRootError::from(err)))?)
+    }
+
+    fn code_by_hash(&self, code_hash: B256) -> ProviderResult<Option<Bytecode>> {
+        Ok(self.tx.get::<tables::Bytecodes>(code_hash)?)
+    }
+
+    fn hashed_account_multiproof(&self, targets: &[B256]) -> ProviderResult<Vec<Bytes>> {
+        Ok(Proof::new(&self.tx).account_multiproof(targets).map_err(Into::<DatabaseError>::into)?)
+    }
+
+    fn hashed_storage_multiproof(
+        &self,
+        hashed_address: B256,
+        targets: &[B256],
+    ) -> ProviderResult<Vec<Bytes>> {
+        Ok(Proof::new(&self.tx)
+            .storage_multiproof(hashed_address, targets)
+            .map_err(|err| Into::<DatabaseError>::into(StateRootError::from(err)))?)
+    }
+}
+
 impl<TX: DbTxMut + DbTx> HashingWriter for DatabaseProvider<TX> {
     fn unwind_account_hashing(
         &self,
@@ -2221,6 +2518,104 @@ impl<TX: DbTxMut + DbTx> HistoryWriter for DatabaseProvider<TX> {
     }
 }
 
+impl<TX: DbTx> LogIndexReader for DatabaseProvider<TX> {
+    fn log_index_checkpoint(&self) -> ProviderResult<Option<BlockNumber>> {
+        Ok(self.get_stage_checkpoint(StageId::IndexLogs)?.map(|checkpoint| checkpoint.block_number))
+    }
+
+    fn blocks_with_log_address(
+        &self,
+        address: Address,
+        range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<Vec<BlockNumber>> {
+        self.history_index_blocks::<_, tables::LogAddressHistory>(address, range)
+    }
+
+    fn blocks_with_log_topic(
+        &self,
+        topic: B256,
+        range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<Vec<BlockNumber>> {
+        self.history_index_blocks::<_, tables::LogTopicHistory>(topic, range)
+    }
+}
+
+impl<TX: DbTxMut + DbTx> LogIndexWriter for DatabaseProvider<TX> {
+    fn insert_log_index(&self, range: RangeInclusive<BlockNumber>) -> ProviderResult<()> {
+        let (addresses, topics) = self.log_addresses_and_topics_with_range(range)?;
+        self.append_history_index::<_, tables::LogAddressHistory>(addresses, ShardedKey::new)?;
+        self.append_history_index::<_, tables::LogTopicHistory>(topics, ShardedKey::new)
+    }
+
+    fn unwind_log_index(&self, range: RangeInclusive<BlockNumber>) -> ProviderResult<usize> {
+        let block_number = *range.start();
+        let (addresses, topics) = self.log_addresses_and_topics_with_range(range)?;
+        let keys = addresses.len() + topics.len();
+        self.unwind_history_index_shards::<_, tables::LogAddressHistory>(
+            addresses.into_keys(),
+            block_number,
+        )?;
+        self.unwind_history_index_shards::<_, tables::LogTopicHistory>(
+            topics.into_keys(),
+            block_number,
+        )?;
+        Ok(keys)
+    }
+}
+
+impl<TX: DbTx> TransactionAddressIndexReader for DatabaseProvider<TX> {
+    fn transaction_address_index_checkpoint(&self) -> ProviderResult<Option<BlockNumber>> {
+        let Some(index) = self.get_stage_checkpoint(StageId::IndexTransactionAddresses)? else {
+            return Ok(None)
+        };
+        let account_history = self
+            .get_stage_checkpoint(StageId::IndexAccountHistory)?
+            .map(|checkpoint| checkpoint.block_number)
+            .unwrap_or_default();
+        Ok(Some(index.block_number.min(account_history)))
+    }
+
+    fn blocks_with_transaction_address(
+        &self,
+        address: Address,
+        range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<Vec<BlockNumber>> {
+        let mut blocks = self
+            .history_index_blocks::<_, tables::TransactionAddressHistory>(address, range.clone())?;
+        blocks.extend(self.history_index_blocks::<_, tables::AccountHistory>(address, range)?);
+        blocks.sort_unstable();
+        blocks.dedup();
+        Ok(blocks)
+    }
+}
+
+impl<TX: DbTxMut + DbTx> TransactionAddressIndexWriter for DatabaseProvider<TX> {
+    fn insert_transaction_address_index(
+        &self,
+        range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<()> {
+        let addresses = self.transaction_addresses_with_range(range)?;
+        self.append_history_index::<_, tables::TransactionAddressHistory>(
+            addresses,
+            ShardedKey::new,
+        )
+    }
+
+    fn unwind_transaction_address_index(
+        &self,
+        range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<usize> {
+        let block_number = *range.start();
+        let addresses = self.transaction_addresses_with_range(range)?;
+        let keys = addresses.len();
+        self.unwind_history_index_shards::<_, tables::TransactionAddressHistory>(
+            addresses.into_keys(),
+            block_number,
+        )?;
+        Ok(keys)
+    }
+}
+
 impl<TX: DbTxMut + DbTx> BlockExecutionWriter for DatabaseProvider<TX> {
     /// Return range of blocks and its execution result
     fn get_or_take_block_and_execution_range<const TAKE: bool>(
@@ -2229,6 +2624,32 @@ impl<TX: DbTxMut + DbTx> BlockExecutionWriter for DatabaseProvider<TX> {
         range: RangeInclusive<BlockNumber>,
     ) -> ProviderResult<Chain> {
         if TAKE {
+            // Unwind the optional log index before the receipts are removed.
+            if let Some(checkpoint) = self.log_index_checkpoint()? {
+                if checkpoint >= *range.start() {
+                    self.unwind_log_index(*range.start()..=checkpoint)?;
+                    self.save_stage_checkpoint(
+                        StageId::IndexLogs,
+                        StageCheckpoint::new(range.start().saturating_sub(1)),
+                    )?;
+                }
+            }
+
+            // Unwind the optional transaction address index before the transactions are removed.
+            if let Some(checkpoint) =
+                self.get_stage_checkpoint(StageId::IndexTransactionAddresses)?
+            {
+                if checkpoint.block_number >= *range.start() {
+                    self.unwind_transaction_address_index(
+                        *range.start()..=checkpoint.block_number,
+                    )?;
+                    self.save_stage_checkpoint(
+                        StageId::IndexTransactionAddresses,
+                        StageCheckpoint::new(range.start().saturating_sub(1)),
+                    )?;
+                }
+            }
+
             let storage_range = BlockNumberAddress::range(range.clone());
 
             // Initialize prefix sets.
@@ -2492,6 +2913,26 @@ impl<TX: DbTxMut + DbTx> BlockWriter for DatabaseProvider<TX> {
         self.update_history_indices(first_number..=last_block_number)?;
         durations_recorder.record_relative(metrics::Action::InsertHistoryIndices);
 
+        // Extend the optional log index if it is up to date. Otherwise, the blocks are indexed by
+        // the next pipeline run.
+        if self.log_index_checkpoint()?.map(|checkpoint| checkpoint + 1) == Some(first_number) {
+            self.insert_log_index(first_number..=last_block_number)?;
+            self.save_stage_checkpoint(
+                StageId::IndexLogs,
+                StageCheckpoint::new(last_block_number),
+            )?;
+        }
+        let transaction_address_index = self
+            .get_stage_checkpoint(StageId::IndexTransactionAddresses)?
+            .map(|checkpoint| checkpoint.block_number);
+        if transaction_address_index.map(|checkpoint| checkpoint + 1) == Some(first_number) {
+            self.insert_transaction_address_index(first_number..=last_block_number)?;
+            self.save_stage_checkpoint(
+                StageId::IndexTransactionAddresses,
+                StageCheckpoint::new(last_block_number),
+            )?;
+        }
+
         // Update pipeline progress
         self.update_pipeline_stages(last_block_number, false)?;
         durations_recorder.record_relative(metrics::Action::UpdatePipelineStages);
@@ -2,9 +2,10 @@ use crate::{
     AccountReader, BlockHashReader, BlockIdReader, BlockNumReader, BlockReader, BlockReaderIdExt,
     BlockchainTreePendingStateProvider, BundleStateDataProvider, CanonChainTracker,
     CanonStateNotifications, CanonStateSubscriptions, ChainSpecProvider, ChangeSetReader,
-    EvmEnvProvider, HeaderProvider, ProviderError, PruneCheckpointReader, ReceiptProvider,
-    ReceiptProviderIdExt, StageCheckpointReader, StateProviderBox, StateProviderFactory,
-    TransactionVariant, TransactionsProvider, WithdrawalsProvider,
+    EvmEnvProvider, HeaderProvider, LogIndexReader, ProviderError, PruneCheckpointReader,
+    ReceiptProvider, ReceiptProviderIdExt, SnapStateReader, StageCheckpointReader,
+    StateProviderBox, StateProviderFactory, TransactionAddressIndexReader, TransactionVariant,
+    TransactionsProvider, WithdrawalsProvider,
 };
 use reth_db::{database::Database, models::StoredBlockBodyIndices};
 use reth_interfaces::{
@@ -16,9 +17,10 @@ use reth_interfaces::{
 use reth_primitives::{
     stage::{StageCheckpoint, StageId},
     Account, Address, Block, BlockHash, BlockHashOrNumber, BlockId, BlockNumHash, BlockNumber,
-    BlockNumberOrTag, BlockWithSenders, ChainInfo, ChainSpec, Header, PruneCheckpoint,
-    PruneSegment, Receipt, SealedBlock, SealedBlockWithSenders, SealedHeader, TransactionMeta,
-    TransactionSigned, TransactionSignedNoHash, TxHash, TxNumber, Withdrawal, B256, U256,
+    BlockNumberOrTag, BlockWithSenders, Bytecode, Bytes, ChainInfo, ChainSpec, Header,
+    PruneCheckpoint, PruneSegment, Receipt, SealedBlock, SealedBlockWithSenders, SealedHeader,
+    TransactionMeta, TransactionSigned, TransactionSignedNoHash, TxHash, TxNumber, Withdrawal,
+    B256, U256,
 };
 use revm::primitives::{BlockEnv, CfgEnv};
 use std::{
@@ -30,6 +32,7 @@ use std::{
 use tracing::trace;
 
 pub use state::{
+    cached::{CachedStateProvider, StateCache, StateCacheConfig},
     historical::{HistoricalStateProvider, HistoricalStateProviderRef},
     latest::{LatestStateProvider, LatestStateProviderRef},
 };
@@ -38,7 +41,11 @@ mod bundle_state_provider;
 mod chain_info;
 mod database;
 mod snapshot;
-pub use snapshot::{SnapshotJarProvider, SnapshotProvider};
+pub use snapshot::{
+    FsSnapshotStore, RemoteSnapshots, SnapshotJarProvider, SnapshotProvider, SnapshotStore,
+};
+#[cfg(feature = "s3")]
+pub use snapshot::{S3Config, S3SnapshotStore};
 mod state;
 use crate::{providers::chain_info::ChainInfoTracker, traits::BlockSource};
 pub use bundle_state_provider::BundleStateProvider;
@@ -442,6 +449,50 @@ where
     }
 }
 
+impl<DB, Tree> LogIndexReader for BlockchainProvider<DB, Tree>
+where
+    DB: Database,
+    Tree: Send + Sync,
+{
+    fn log_index_checkpoint(&self) -> ProviderResult<Option<BlockNumber>> {
+        self.database.provider()?.log_index_checkpoint()
+    }
+
+    fn blocks_with_log_address(
+        &self,
+        address: Address,
+        range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<Vec<BlockNumber>> {
+        self.database.provider()?.blocks_with_log_address(address, range)
+    }
+
+    fn blocks_with_log_topic(
+        &self,
+        topic: B256,
+        range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<Vec<BlockNumber>> {
+        self.database.provider()?.blocks_with_log_topic(topic, range)
+    }
+}
+
+impl<DB, Tree> TransactionAddressIndexReader for BlockchainProvider<DB, Tree>
+where
+    DB: Database,
+    Tree: Send + Sync,
+{
+    fn transaction_address_index_checkpoint(&self) -> ProviderResult<Option<BlockNumber>> {
+        self.database.provider()?.transaction_address_index_checkpoint()
+    }
+
+    fn blocks_with_transaction_address(
+        &self,
+        address: Address,
+        range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<Vec<BlockNumber>> {
+        self.database.provider()?.blocks_with_transaction_address(address, range)
+    }
+}
+
 impl<DB, Tree> EvmEnvProvider for BlockchainProvider<DB, Tree>
 where
     DB: Database,
@@ -859,3 +910,50 @@ where
         self.database.provider()?.basic_account(address)
     }
 }
+
+impl<DB, Tree> SnapStateReader for BlockchainProvider<DB, Tree>
+where
+    DB: Database,
+    Tree: Sync + Send,
+{
+    fn snap_state_root(&self) -> ProviderResult<B256> {
+        self.database.snap_state_root()
+    }
+
+    fn hashed_account_range(
+        &self,
+        start: B256,
+        limit: usize,
+    ) -> ProviderResult<Vec<(B256, Account)>> {
+        self.database.hashed_account_range(start, limit)
+    }
+
+    fn hashed_storage_range(
+        &self,
+        hashed_address: B256,
+        start: B256,
+        limit: usize,
+    ) -> ProviderResult<Vec<(B256, U256)>> {
+        self.database.hashed_storage_range(hashed_address, start, limit)
+    }
+
+    fn hashed_storage_root(&self, hashed_address: B256) -> ProviderResult<B256> {
+        self.database.hashed_storage_root(hashed_address)
+    }
+
+    fn code_by_hash(&self, code_hash: B256) -> ProviderResult<Option<Bytecode>> {
+        self.database.code_by_hash(code_hash)
+    }
+
+    fn hashed_account_multiproof(&self, targets: &[B256]) -> ProviderResult<Vec<Bytes>> {
+        self.database.hashed_account_multiproof(targets)
+    }
+
+    fn hashed_storage_multiproof(
+        &self,
+        hashed_address: B256,
+        targets: &[B256],
+    ) -> ProviderResult<Vec<Bytes>> {
+        self.database.hashed_storage_multiproof(hashed_address, targets)
+    }
+}
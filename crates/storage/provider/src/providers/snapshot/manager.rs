@@ -1,4 +1,4 @@
-use super::{LoadedJar, SnapshotJarProvider};
+use super::{remote::segment_files, LoadedJar, RemoteSnapshots, SnapshotJarProvider};
 use crate::{
     to_range, BlockHashReader, BlockNumReader, BlockReader, BlockSource, HeaderProvider,
     ReceiptProvider, TransactionVariant, TransactionsProvider, TransactionsProviderExt,
@@ -21,10 +21,12 @@ use reth_primitives::{
 };
 use std::{
     collections::{hash_map::Entry, BTreeMap, HashMap},
+    ffi::OsStr,
     ops::{Range, RangeBounds, RangeInclusive},
     path::{Path, PathBuf},
 };
 use tokio::sync::watch;
+use tracing::info;
 
 /// Alias type for a map that can be queried for transaction/block ranges from a block/transaction
 /// segment respectively. It uses `BlockNumber` to represent the block end of a snapshot range or
@@ -52,6 +54,8 @@ pub struct SnapshotProvider {
     /// Whether [`SnapshotJarProvider`] loads filters into memory. If not, `by_hash` queries won't
     /// be able to be queried directly.
     load_filters: bool,
+    /// Remote store of the offloaded segments, if any.
+    remote: Option<RemoteSnapshots>,
 }
 
 impl SnapshotProvider {
@@ -64,6 +68,7 @@ impl SnapshotProvider {
             highest_tracker: None,
             path: path.as_ref().to_path_buf(),
             load_filters: false,
+            remote: None,
         };
 
         provider.update_index()?;
@@ -76,6 +81,19 @@ impl SnapshotProvider {
         self
     }
 
+    /// Fetches the segments that are missing locally from the given remote store, and indexes the
+    /// segments offloaded to it.
+    pub fn with_remote(mut self, remote: RemoteSnapshots) -> ProviderResult<Self> {
+        self.remote = Some(remote);
+        self.update_index()?;
+        Ok(self)
+    }
+
+    /// Returns the remote store of the offloaded segments, if any.
+    pub fn remote(&self) -> Option<&RemoteSnapshots> {
+        self.remote.as_ref()
+    }
+
     /// Adds a highest snapshot tracker to the provider
     pub fn with_highest_tracker(
         mut self,
@@ -157,10 +175,26 @@ impl SnapshotProvider {
     ) -> ProviderResult<SnapshotJarProvider<'_>> {
         let key = (*block_range.end(), segment);
         if let Some(jar) = self.map.get(&key) {
+            if let Some(remote) = &self.remote {
+                remote.touch(&segment.filename(block_range, tx_range));
+            }
             Ok(jar.into())
         } else {
-            let jar = NippyJar::load(&self.path.join(segment.filename(block_range, tx_range)))
-                .map(|jar| {
+            let filename = segment.filename(block_range, tx_range);
+            let mut path = self.path.join(&filename);
+            if let Some(remote) = self.remote.as_ref().filter(|_| !path.exists()) {
+                let (cached, evicted) = remote.fetch(&filename)?;
+                for filename in evicted {
+                    if let Some((segment, block_range, _)) =
+                        SnapshotSegment::parse_filename(OsStr::new(&filename))
+                    {
+                        self.map.remove(&(*block_range.end(), segment));
+                    }
+                }
+                path = cached;
+            }
+
+            let jar = NippyJar::load(&path).map(|jar| {
                 if self.load_filters {
                     return jar.load_filters()
                 }
@@ -234,7 +268,14 @@ impl SnapshotProvider {
         let mut block_index = self.snapshots_block_index.write();
         let mut tx_index = self.snapshots_tx_index.write();
 
-        for (segment, ranges) in iter_snapshots(&self.path)? {
+        let mut snapshots = iter_snapshots(&self.path)?;
+        if let Some(remote) = &self.remote {
+            for (segment, block_range, tx_range) in remote.snapshots() {
+                snapshots.entry(segment).or_default().push((block_range, tx_range));
+            }
+        }
+
+        for (segment, ranges) in snapshots {
             for (block_range, tx_range) in ranges {
                 let block_end = *block_range.end();
                 let tx_end = *tx_range.end();
@@ -262,6 +303,53 @@ impl SnapshotProvider {
         Ok(())
     }
 
+    /// Offloads the local segments to the remote store, keeping the segments that end less than
+    /// [RemoteSnapshots::keep_local_blocks] blocks below the highest snapshotted block of their
+    /// segment. The highest segment is always kept.
+    ///
+    /// The segments that were snapshotted since the index was last updated are picked up first.
+    /// Does nothing if there's no remote store or it doesn't keep a number of blocks locally.
+    /// Returns the number of offloaded segments.
+    pub fn offload_snapshots(&self) -> ProviderResult<usize> {
+        let Some(remote) = &self.remote else { return Ok(0) };
+        let Some(keep_local_blocks) = remote.keep_local_blocks() else { return Ok(0) };
+        self.update_index()?;
+
+        let snapshots = self.snapshots_block_index.read().clone();
+        let mut offloaded = 0;
+        for (segment, ranges) in snapshots {
+            let Some((&highest, _)) = ranges.last_key_value() else { continue };
+
+            let mut block_start = 0;
+            for (block_end, tx_range) in ranges {
+                let block_range = block_start..=block_end;
+                block_start = block_end + 1;
+                if block_end == highest || highest - block_end < keep_local_blocks {
+                    continue
+                }
+
+                let filename = segment.filename(&block_range, &tx_range);
+                if !self.path.join(&filename).exists() {
+                    continue
+                }
+
+                remote.upload(&self.path, &filename)?;
+                self.map.remove(&(block_end, segment));
+                for file in segment_files(&filename) {
+                    let path = self.path.join(file);
+                    if path.exists() {
+                        reth_primitives::fs::remove_file(path)?;
+                    }
+                }
+
+                info!(target: "provider::snapshot", %segment, ?block_range, "Offloaded snapshot");
+                offloaded += 1;
+            }
+        }
+
+        Ok(offloaded)
+    }
+
     /// Gets the highest snapshot block if it exists for a snapshot segment.
     pub fn get_highest_snapshot_block(&self, segment: SnapshotSegment) -> Option<BlockNumber> {
         self.snapshots_block_index
@@ -4,6 +4,14 @@ pub use manager::SnapshotProvider;
 mod jar;
 pub use jar::SnapshotJarProvider;
 
+mod remote;
+pub use remote::{FsSnapshotStore, RemoteSnapshots, SnapshotStore};
+
+#[cfg(feature = "s3")]
+mod s3;
+#[cfg(feature = "s3")]
+pub use self::s3::{S3Config, S3SnapshotStore};
+
 use reth_interfaces::provider::ProviderResult;
 use reth_nippy_jar::NippyJar;
 use reth_primitives::{snapshot::SegmentHeader, SnapshotSegment};
@@ -0,0 +1,407 @@
+use parking_lot::{Mutex, RwLock};
+use reth_interfaces::provider::{ProviderError, ProviderResult};
+use reth_primitives::{BlockNumber, SnapshotSegment, TxNumber};
+use schnellru::{LruMap, Unlimited};
+use std::{
+    collections::{HashMap, HashSet},
+    ffi::OsStr,
+    fmt, fs, io,
+    ops::RangeInclusive,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use tokio::runtime::{Handle, RuntimeFlavor};
+use tracing::{debug, warn};
+
+/// Suffix of the files that are still being downloaded into the cache.
+const PARTIAL_FILE_SUFFIX: &str = "part";
+
+/// A remote store of snapshot files, such as an S3-compatible bucket.
+///
+/// Files are addressed by their file name in the snapshots directory.
+pub trait SnapshotStore: Send + Sync + fmt::Debug {
+    /// Returns the names of all files in the store.
+    fn list(&self) -> io::Result<Vec<String>>;
+
+    /// Downloads the file with the given name to the given path.
+    fn download(&self, name: &str, to: &Path) -> io::Result<()>;
+
+    /// Uploads the file at the given path under the given name.
+    fn upload(&self, name: &str, from: &Path) -> io::Result<()>;
+}
+
+/// A [SnapshotStore] in a local directory, e.g. a mounted network filesystem.
+#[derive(Debug, Clone)]
+pub struct FsSnapshotStore {
+    path: PathBuf,
+}
+
+impl FsSnapshotStore {
+    /// Creates a new store in the given directory.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+impl SnapshotStore for FsSnapshotStore {
+    fn list(&self) -> io::Result<Vec<String>> {
+        if !self.path.exists() {
+            return Ok(Vec::new())
+        }
+
+        let mut names = Vec::new();
+        for entry in fs::read_dir(&self.path)? {
+            let entry = entry?;
+            if entry.file_type()?.is_file() {
+                names.extend(entry.file_name().to_str().map(str::to_string));
+            }
+        }
+        Ok(names)
+    }
+
+    fn download(&self, name: &str, to: &Path) -> io::Result<()> {
+        fs::copy(self.path.join(name), to).map(|_| ())
+    }
+
+    fn upload(&self, name: &str, from: &Path) -> io::Result<()> {
+        fs::create_dir_all(&self.path)?;
+        fs::copy(from, self.path.join(name)).map(|_| ())
+    }
+}
+
+/// Snapshot segments that were offloaded to a [SnapshotStore].
+///
+/// Offloaded segments are fetched into a local cache directory on access. The cache is bounded by
+/// size, evicting the least recently used segments.
+pub struct RemoteSnapshots {
+    /// The store the segments are offloaded to.
+    store: Arc<dyn SnapshotStore>,
+    /// Directory the fetched segments are cached in.
+    cache_path: PathBuf,
+    /// Maximum size of the cached segments in bytes.
+    max_cache_size: u64,
+    /// Number of the most recent snapshotted blocks whose segments are kept locally when
+    /// offloading, or [None] if segments aren't offloaded.
+    keep_local_blocks: Option<u64>,
+    /// Names of the files in the store.
+    files: RwLock<HashSet<String>>,
+    /// The cached segments.
+    cache: Mutex<SegmentCache>,
+    /// Locks of the segments that are being fetched, by segment file name, so a segment is only
+    /// fetched once while other segments are fetched concurrently.
+    downloads: Mutex<HashMap<String, Arc<Mutex<()>>>>,
+}
+
+impl fmt::Debug for RemoteSnapshots {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RemoteSnapshots")
+            .field("store", &self.store)
+            .field("cache_path", &self.cache_path)
+            .field("max_cache_size", &self.max_cache_size)
+            .field("keep_local_blocks", &self.keep_local_blocks)
+            .field("files", &self.files.read().len())
+            .field("cache", &self.cache.lock())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Segments in the cache directory, by segment file name, with the size of their files.
+struct SegmentCache {
+    segments: LruMap<String, u64, Unlimited>,
+    size: u64,
+}
+
+impl fmt::Debug for SegmentCache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SegmentCache")
+            .field("segments", &self.segments.len())
+            .field("size", &self.size)
+            .finish()
+    }
+}
+
+impl RemoteSnapshots {
+    /// Creates a new remote for the given store, caching up to `max_cache_size` bytes of fetched
+    /// segments in `cache_path`.
+    ///
+    /// Lists the files of the store, and picks up the segments cached by previous runs.
+    pub fn new(
+        store: Arc<dyn SnapshotStore>,
+        cache_path: impl Into<PathBuf>,
+        max_cache_size: u64,
+    ) -> ProviderResult<Self> {
+        let cache_path = cache_path.into();
+        reth_primitives::fs::create_dir_all(&cache_path)?;
+
+        let mut cache = SegmentCache { segments: LruMap::new(Unlimited), size: 0 };
+        for entry in fs::read_dir(&cache_path).map_err(remote_error)? {
+            let entry = entry.map_err(remote_error)?;
+            let path = entry.path();
+            if path.extension() == Some(OsStr::new(PARTIAL_FILE_SUFFIX)) {
+                // interrupted download
+                reth_primitives::fs::remove_file(&path)?;
+                continue
+            }
+            if SnapshotSegment::parse_filename(&entry.file_name()).is_some() {
+                let name = entry.file_name().to_string_lossy().into_owned();
+                let size = segment_files(&name)
+                    .filter_map(|file| fs::metadata(cache_path.join(file)).ok())
+                    .map(|metadata| metadata.len())
+                    .sum();
+                cache.segments.insert(name, size);
+                cache.size += size;
+            }
+        }
+
+        let files = store.list().map_err(remote_error)?.into_iter().collect();
+
+        Ok(Self {
+            store,
+            cache_path,
+            max_cache_size,
+            keep_local_blocks: None,
+            files: RwLock::new(files),
+            cache: Mutex::new(cache),
+            downloads: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// Offloads the local segments that end at least `blocks` blocks below the highest snapshotted
+    /// block of their segment, see [SnapshotProvider::offload_snapshots].
+    ///
+    /// [SnapshotProvider::offload_snapshots]: crate::providers::SnapshotProvider::offload_snapshots
+    pub fn with_keep_local_blocks(mut self, blocks: u64) -> Self {
+        self.keep_local_blocks = Some(blocks);
+        self
+    }
+
+    /// Returns the number of the most recent snapshotted blocks whose segments are kept locally.
+    pub fn keep_local_blocks(&self) -> Option<u64> {
+        self.keep_local_blocks
+    }
+
+    /// Returns the ranges of all segments in the store.
+    pub fn snapshots(
+        &self,
+    ) -> Vec<(SnapshotSegment, RangeInclusive<BlockNumber>, RangeInclusive<TxNumber>)> {
+        self.files
+            .read()
+            .iter()
+            .filter_map(|name| SnapshotSegment::parse_filename(OsStr::new(name)))
+            .collect()
+    }
+
+    /// Marks the cached segment as recently used.
+    pub(crate) fn touch(&self, filename: &str) {
+        self.cache.lock().segments.get(filename);
+    }
+
+    /// Returns the path of the segment in the cache, downloading it from the store if it's not
+    /// cached yet, together with the file names of the segments evicted from the cache to make
+    /// room for it.
+    ///
+    /// Downloads block the current thread. On a worker thread of a multi-threaded tokio runtime,
+    /// its other tasks are moved to another thread first, see [tokio::task::block_in_place].
+    pub(crate) fn fetch(&self, filename: &str) -> ProviderResult<(PathBuf, Vec<String>)> {
+        let path = self.cache_path.join(filename);
+        if self.cache.lock().segments.get(filename).is_some() {
+            return Ok((path, Vec::new()))
+        }
+
+        let lock = self.downloads.lock().entry(filename.to_string()).or_default().clone();
+        let result = {
+            let _download = lock.lock();
+            match Handle::try_current() {
+                Ok(handle) if handle.runtime_flavor() == RuntimeFlavor::MultiThread => {
+                    tokio::task::block_in_place(|| self.download(filename))
+                }
+                _ => self.download(filename),
+            }
+        };
+
+        // the lock is only shared by the fetches that are still waiting for it
+        let mut downloads = self.downloads.lock();
+        if Arc::strong_count(&lock) == 2 {
+            downloads.remove(filename);
+        }
+
+        result.map(|evicted| (path, evicted))
+    }
+
+    /// Downloads the segment into the cache if it's not cached yet, and returns the file names of
+    /// the segments evicted from the cache to make room for it.
+    fn download(&self, filename: &str) -> ProviderResult<Vec<String>> {
+        // the segment may have been downloaded while waiting for its lock
+        if self.cache.lock().segments.get(filename).is_some() {
+            return Ok(Vec::new())
+        }
+
+        // the data file is downloaded last, so it only exists if the segment is complete
+        let files = {
+            let remote_files = self.files.read();
+            if !remote_files.contains(filename) {
+                return Err(ProviderError::RemoteSnapshot(format!("{filename} is not offloaded")))
+            }
+            let mut files = segment_files(filename)
+                .filter(|file| remote_files.contains(file))
+                .collect::<Vec<_>>();
+            files.rotate_left(1);
+            files
+        };
+
+        debug!(target: "provider::snapshot", filename, "Fetching offloaded snapshot");
+        let mut size = 0;
+        for file in files {
+            let destination = self.cache_path.join(&file);
+            let partial = destination.with_file_name(format!("{file}.{PARTIAL_FILE_SUFFIX}"));
+            self.store.download(&file, &partial).map_err(remote_error)?;
+            size += fs::metadata(&partial).map_err(remote_error)?.len();
+            reth_primitives::fs::rename(&partial, &destination)?;
+        }
+
+        let mut cache = self.cache.lock();
+        cache.segments.insert(filename.to_string(), size);
+        cache.size += size;
+
+        let mut evicted = Vec::new();
+        while cache.size > self.max_cache_size && cache.segments.len() > 1 {
+            let Some((name, size)) = cache.segments.pop_oldest() else { break };
+            cache.size -= size;
+            for file in segment_files(&name) {
+                let path = self.cache_path.join(file);
+                if path.exists() {
+                    if let Err(err) = reth_primitives::fs::remove_file(&path) {
+                        warn!(target: "provider::snapshot", %err, "Failed to evict snapshot");
+                    }
+                }
+            }
+            evicted.push(name);
+        }
+
+        Ok(evicted)
+    }
+
+    /// Uploads the files of the segment in the given snapshots directory to the store.
+    pub(crate) fn upload(&self, snapshots_path: &Path, filename: &str) -> ProviderResult<()> {
+        // the data file is uploaded last, so the segment is only listed once it's complete
+        let mut files = segment_files(filename).collect::<Vec<_>>();
+        files.rotate_left(1);
+
+        for file in files {
+            let path = snapshots_path.join(&file);
+            if path.exists() {
+                self.store.upload(&file, &path).map_err(remote_error)?;
+                self.files.write().insert(file);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Returns the names of the files of a segment, the data file first.
+pub(crate) fn segment_files(filename: &str) -> impl Iterator<Item = String> + '_ {
+    std::iter::once(filename.to_string()).chain(
+        ["conf", "off", "idx"].into_iter().map(move |extension| format!("{filename}.{extension}")),
+    )
+}
+
+fn remote_error(err: io::Error) -> ProviderError {
+    ProviderError::RemoteSnapshot(err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_segment(path: &Path, filename: &str, len: usize) {
+        for file in segment_files(filename) {
+            fs::write(path.join(file), vec![0; len]).unwrap();
+        }
+    }
+
+    /// A [FsSnapshotStore] that counts the downloaded files.
+    #[derive(Debug)]
+    struct CountingStore {
+        store: FsSnapshotStore,
+        downloads: Mutex<usize>,
+    }
+
+    impl SnapshotStore for CountingStore {
+        fn list(&self) -> io::Result<Vec<String>> {
+            self.store.list()
+        }
+
+        fn download(&self, name: &str, to: &Path) -> io::Result<()> {
+            *self.downloads.lock() += 1;
+            self.store.download(name, to)
+        }
+
+        fn upload(&self, name: &str, from: &Path) -> io::Result<()> {
+            self.store.upload(name, from)
+        }
+    }
+
+    #[test]
+    fn fetch_concurrently() {
+        let local = tempfile::tempdir().unwrap();
+        let store = tempfile::tempdir().unwrap();
+        let cache = tempfile::tempdir().unwrap();
+
+        let filename = SnapshotSegment::Headers.filename(&(0..=9), &(0..=9));
+        write_segment(local.path(), &filename, 10);
+        let store = Arc::new(CountingStore {
+            store: FsSnapshotStore::new(store.path()),
+            downloads: Mutex::new(0),
+        });
+        let remote = RemoteSnapshots::new(store.clone(), cache.path(), 1024).unwrap();
+        remote.upload(local.path(), &filename).unwrap();
+
+        // the segment is only downloaded once
+        std::thread::scope(|scope| {
+            for _ in 0..4 {
+                scope.spawn(|| {
+                    assert_eq!(remote.fetch(&filename).unwrap().0, cache.path().join(&filename))
+                });
+            }
+        });
+        assert_eq!(*store.downloads.lock(), segment_files(&filename).count());
+        assert!(remote.downloads.lock().is_empty());
+    }
+
+    #[test]
+    fn fetch_and_evict() {
+        let local = tempfile::tempdir().unwrap();
+        let store = tempfile::tempdir().unwrap();
+        let cache = tempfile::tempdir().unwrap();
+
+        let first = SnapshotSegment::Headers.filename(&(0..=9), &(0..=9));
+        let second = SnapshotSegment::Headers.filename(&(10..=19), &(10..=19));
+        write_segment(local.path(), &first, 10);
+        write_segment(local.path(), &second, 10);
+
+        let remote =
+            RemoteSnapshots::new(Arc::new(FsSnapshotStore::new(store.path())), cache.path(), 60)
+                .unwrap();
+        remote.upload(local.path(), &first).unwrap();
+        remote.upload(local.path(), &second).unwrap();
+        assert_eq!(remote.snapshots().len(), 2);
+
+        let (path, evicted) = remote.fetch(&first).unwrap();
+        assert_eq!(path, cache.path().join(&first));
+        assert!(path.exists());
+        assert!(evicted.is_empty());
+
+        // the cache fits only one segment
+        let (path, evicted) = remote.fetch(&second).unwrap();
+        assert!(path.exists());
+        assert_eq!(evicted, vec![first.clone()]);
+        assert!(!cache.path().join(&first).exists());
+
+        // cached segments are picked up again
+        let remote =
+            RemoteSnapshots::new(Arc::new(FsSnapshotStore::new(store.path())), cache.path(), 60)
+                .unwrap();
+        assert_eq!(remote.cache.lock().size, 40);
+        assert_eq!(remote.fetch(&second).unwrap(), (cache.path().join(&second), Vec::new()));
+    }
+}
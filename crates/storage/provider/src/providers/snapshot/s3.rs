@@ -0,0 +1,132 @@
+use super::SnapshotStore;
+use s3::{creds::Credentials, Bucket, Region};
+use std::{fmt, fs::File, io, path::Path};
+
+/// Configuration of a [S3SnapshotStore].
+#[derive(Clone, PartialEq, Eq)]
+pub struct S3Config {
+    /// The endpoint of the service, e.g. `https://s3.us-east-1.amazonaws.com`.
+    pub endpoint: String,
+    /// The region of the bucket.
+    pub region: String,
+    /// The bucket the snapshots are stored in.
+    pub bucket: String,
+    /// The prefix of the object keys, e.g. `mainnet/`.
+    pub prefix: String,
+    /// The access key ID of the credentials.
+    pub access_key_id: String,
+    /// The secret access key of the credentials.
+    pub secret_access_key: String,
+}
+
+impl fmt::Debug for S3Config {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // don't leak the secret access key
+        f.debug_struct("S3Config")
+            .field("endpoint", &self.endpoint)
+            .field("region", &self.region)
+            .field("bucket", &self.bucket)
+            .field("prefix", &self.prefix)
+            .field("access_key_id", &self.access_key_id)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A [SnapshotStore] in a bucket of an S3-compatible object storage.
+///
+/// The bucket is addressed path-style, which all S3-compatible services support. Files are
+/// uploaded in parts if they're too large for a single request.
+pub struct S3SnapshotStore {
+    bucket: Bucket,
+    prefix: String,
+}
+
+impl fmt::Debug for S3SnapshotStore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // don't leak the credentials
+        f.debug_struct("S3SnapshotStore")
+            .field("bucket", &self.bucket.name)
+            .field("region", &self.bucket.region)
+            .field("prefix", &self.prefix)
+            .finish_non_exhaustive()
+    }
+}
+
+impl S3SnapshotStore {
+    /// Creates a new store for the bucket of the configuration.
+    pub fn new(config: S3Config) -> io::Result<Self> {
+        let region = Region::Custom { region: config.region, endpoint: config.endpoint };
+        let credentials = Credentials::new(
+            Some(&config.access_key_id),
+            Some(&config.secret_access_key),
+            None,
+            None,
+            None,
+        )
+        .map_err(io::Error::other)?;
+        let mut bucket =
+            Bucket::new(&config.bucket, region, credentials).map_err(io::Error::other)?;
+        bucket = bucket.with_path_style();
+        // snapshot segments are gigabytes large
+        bucket.request_timeout = None;
+
+        Ok(Self { bucket, prefix: config.prefix })
+    }
+
+    /// Returns the key of the object of the file with the given name.
+    fn key(&self, name: &str) -> String {
+        format!("{}{name}", self.prefix)
+    }
+}
+
+impl SnapshotStore for S3SnapshotStore {
+    fn list(&self) -> io::Result<Vec<String>> {
+        // objects in nested directories aren't snapshot files, and are grouped by the delimiter
+        let results = self
+            .bucket
+            .list(self.prefix.clone(), Some("/".to_string()))
+            .map_err(io::Error::other)?;
+        Ok(results
+            .into_iter()
+            .flat_map(|result| result.contents)
+            .filter_map(|object| object.key.strip_prefix(self.prefix.as_str()).map(Into::into))
+            .collect())
+    }
+
+    fn download(&self, name: &str, to: &Path) -> io::Result<()> {
+        let mut file = File::create(to)?;
+        self.bucket.get_object_to_writer(self.key(name), &mut file).map_err(io::Error::other)?;
+        file.sync_all()
+    }
+
+    fn upload(&self, name: &str, from: &Path) -> io::Result<()> {
+        let mut file = File::open(from)?;
+        self.bucket.put_object_stream(&mut file, self.key(name)).map_err(io::Error::other)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn s3_store_keys() {
+        let store = S3SnapshotStore::new(S3Config {
+            endpoint: "http://localhost:9000".to_string(),
+            region: "us-east-1".to_string(),
+            bucket: "snapshots".to_string(),
+            prefix: "mainnet/".to_string(),
+            access_key_id: "access".to_string(),
+            secret_access_key: "secret".to_string(),
+        })
+        .unwrap();
+        assert_eq!(
+            store.key("snapshot_headers_0_499999_0_0"),
+            "mainnet/snapshot_headers_0_499999_0_0"
+        );
+
+        // the credentials are not part of the debug output
+        assert!(!format!("{store:?}").contains("secret"));
+    }
+}
@@ -0,0 +1,447 @@
+use crate::{
+    AccountReader, BlockHashReader, BundleStateWithReceipts, CanonStateNotification,
+    CanonStateNotifications, StateProvider, StateRootProvider,
+};
+use metrics::Counter;
+use parking_lot::Mutex;
+use reth_interfaces::provider::ProviderResult;
+use reth_metrics::Metrics;
+use reth_primitives::{
+    revm::compat::into_reth_acc, trie::AccountProof, Account, Address, BlockNumber, Bytecode,
+    StorageKey, StorageValue, B256,
+};
+use reth_trie::updates::TrieUpdates;
+use schnellru::{ByLength, LruMap};
+use std::{fmt, sync::Arc};
+use tokio::sync::broadcast::error::RecvError;
+use tracing::trace;
+
+/// Configuration of a [StateCache].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StateCacheConfig {
+    /// The maximum number of accounts to cache.
+    pub max_accounts: u32,
+    /// The maximum number of accounts to cache storage slots for.
+    pub max_storage_accounts: u32,
+    /// The maximum number of storage slots to cache per account.
+    ///
+    /// Once the limit is reached, the least recently used slot of the account is evicted.
+    pub max_storage_slots_per_account: u32,
+    /// The maximum number of bytecodes to cache.
+    pub max_bytecodes: u32,
+}
+
+impl Default for StateCacheConfig {
+    fn default() -> Self {
+        Self {
+            max_accounts: 1_000_000,
+            max_storage_accounts: 100_000,
+            max_storage_slots_per_account: 1_000,
+            max_bytecodes: 10_000,
+        }
+    }
+}
+
+/// A shared cache of accounts, storage slots and bytecodes of the latest state.
+///
+/// The cache belongs to the state after a single block, its tip. Only providers for the state at
+/// the tip read from and write to the cache, so it can be shared by all providers. Once the
+/// canonical chain is extended, the cache is updated with the state changes of the new blocks
+/// and moves to the new tip, which keeps it warm across blocks. If the cache can't be updated,
+/// e.g. on reorgs, it is cleared instead.
+///
+/// Bytecodes are immutable, so they are shared by providers for any state.
+#[derive(Clone, Debug)]
+pub struct StateCache {
+    inner: Arc<Mutex<StateCacheInner>>,
+    metrics: StateCacheMetrics,
+}
+
+struct StateCacheInner {
+    /// The block hash of the state the cached accounts and storage slots belong to.
+    tip: Option<B256>,
+    accounts: LruMap<Address, Option<Account>>,
+    storage: LruMap<Address, LruMap<StorageKey, Option<StorageValue>>>,
+    max_storage_slots_per_account: u32,
+    bytecodes: LruMap<B256, Bytecode>,
+}
+
+impl fmt::Debug for StateCacheInner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StateCacheInner")
+            .field("tip", &self.tip)
+            .field("accounts", &self.accounts.len())
+            .field("storage", &self.storage.len())
+            .field("bytecodes", &self.bytecodes.len())
+            .finish()
+    }
+}
+
+impl StateCacheInner {
+    fn clear(&mut self, tip: Option<B256>) {
+        self.tip = tip;
+        self.accounts.clear();
+        self.storage.clear();
+    }
+}
+
+// === impl StateCache ===
+
+impl StateCache {
+    /// Creates a new, empty cache with the given limits.
+    pub fn new(config: StateCacheConfig) -> Self {
+        let inner = StateCacheInner {
+            tip: None,
+            accounts: LruMap::new(ByLength::new(config.max_accounts)),
+            storage: LruMap::new(ByLength::new(config.max_storage_accounts)),
+            max_storage_slots_per_account: config.max_storage_slots_per_account,
+            bytecodes: LruMap::new(ByLength::new(config.max_bytecodes)),
+        };
+        Self { inner: Arc::new(Mutex::new(inner)), metrics: StateCacheMetrics::default() }
+    }
+
+    /// Returns the block hash of the state the cache belongs to, if any.
+    pub fn tip(&self) -> Option<B256> {
+        self.inner.lock().tip
+    }
+
+    /// Wraps the given provider for the state after the given block in a cached provider.
+    pub fn provider<SP: StateProvider>(
+        &self,
+        provider: SP,
+        block_hash: B256,
+    ) -> CachedStateProvider<SP> {
+        CachedStateProvider { provider, cache: self.clone(), block_hash }
+    }
+
+    /// Moves the cache from the `parent` state to the `tip` state, applying the given state
+    /// changes in between.
+    ///
+    /// If the cache does not belong to the `parent` state, it is cleared instead.
+    pub fn update(&self, parent: B256, tip: B256, changes: &BundleStateWithReceipts) {
+        let mut inner = self.inner.lock();
+        if inner.tip != Some(parent) {
+            trace!(target: "providers::state_cache", ?parent, ?tip, "Clearing state cache");
+            inner.clear(Some(tip));
+            self.metrics.cleared.increment(1);
+            return
+        }
+
+        for (address, account) in changes.bundle_accounts_iter() {
+            inner.accounts.insert(address, account.info.clone().map(into_reth_acc));
+            if account.status.was_destroyed() {
+                inner.storage.remove(&address);
+            }
+            if let Some(slots) = inner.storage.get(&address) {
+                for (key, slot) in &account.storage {
+                    let value = slot.present_value;
+                    slots.insert(
+                        B256::from(key.to_be_bytes()),
+                        (value != StorageValue::ZERO).then_some(value),
+                    );
+                }
+            }
+        }
+        inner.tip = Some(tip);
+        self.metrics.reused.increment(1);
+    }
+
+    /// Clears the cache and moves it to the given tip.
+    pub fn clear(&self, tip: Option<B256>) {
+        self.inner.lock().clear(tip);
+        self.metrics.cleared.increment(1);
+    }
+
+    /// Updates the cache with the given notification of the canonical chain.
+    pub fn on_canon_state_notification(&self, notification: &CanonStateNotification) {
+        match notification {
+            CanonStateNotification::Commit { new } => {
+                self.update(new.first().parent_hash, new.tip().hash, new.state())
+            }
+            CanonStateNotification::Reorg { new, .. } => self.clear(Some(new.tip().hash)),
+        }
+    }
+
+    /// Keeps the cache up to date with the canonical chain until the notification channel is
+    /// closed.
+    pub async fn update_on_notifications(self, mut notifications: CanonStateNotifications) {
+        loop {
+            match notifications.recv().await {
+                Ok(notification) => self.on_canon_state_notification(&notification),
+                // some changes were missed, so the cache can't be updated
+                Err(RecvError::Lagged(_)) => self.clear(None),
+                Err(RecvError::Closed) => break,
+            }
+        }
+    }
+
+    fn account(&self, block_hash: B256, address: Address) -> Option<Option<Account>> {
+        let mut inner = self.inner.lock();
+        if inner.tip != Some(block_hash) {
+            return None
+        }
+        let account = inner.accounts.get(&address).copied();
+        self.metrics.record_account(account.is_some());
+        account
+    }
+
+    fn insert_account(&self, block_hash: B256, address: Address, account: Option<Account>) {
+        let mut inner = self.inner.lock();
+        if inner.tip == Some(block_hash) {
+            inner.accounts.insert(address, account);
+        }
+    }
+
+    fn storage(
+        &self,
+        block_hash: B256,
+        address: Address,
+        key: StorageKey,
+    ) -> Option<Option<StorageValue>> {
+        let mut inner = self.inner.lock();
+        if inner.tip != Some(block_hash) {
+            return None
+        }
+        let value = inner.storage.get(&address).and_then(|slots| slots.get(&key).copied());
+        self.metrics.record_storage(value.is_some());
+        value
+    }
+
+    fn insert_storage(
+        &self,
+        block_hash: B256,
+        address: Address,
+        key: StorageKey,
+        value: Option<StorageValue>,
+    ) {
+        let mut inner = self.inner.lock();
+        if inner.tip != Some(block_hash) {
+            return
+        }
+        let max_slots = inner.max_storage_slots_per_account;
+        if let Some(slots) =
+            inner.storage.get_or_insert(address, || LruMap::new(ByLength::new(max_slots)))
+        {
+            slots.insert(key, value);
+        }
+    }
+
+    fn bytecode(&self, code_hash: B256) -> Option<Bytecode> {
+        let bytecode = self.inner.lock().bytecodes.get(&code_hash).cloned();
+        self.metrics.record_bytecode(bytecode.is_some());
+        bytecode
+    }
+
+    fn insert_bytecode(&self, code_hash: B256, bytecode: Bytecode) {
+        self.inner.lock().bytecodes.insert(code_hash, bytecode);
+    }
+}
+
+/// A [StateProvider] that reads the state through a [StateCache].
+#[derive(Debug)]
+pub struct CachedStateProvider<SP> {
+    /// The provider for the state after `block_hash`.
+    provider: SP,
+    cache: StateCache,
+    block_hash: B256,
+}
+
+impl<SP: StateProvider> AccountReader for CachedStateProvider<SP> {
+    fn basic_account(&self, address: Address) -> ProviderResult<Option<Account>> {
+        if let Some(account) = self.cache.account(self.block_hash, address) {
+            return Ok(account)
+        }
+        let account = self.provider.basic_account(address)?;
+        self.cache.insert_account(self.block_hash, address, account);
+        Ok(account)
+    }
+}
+
+impl<SP: StateProvider> BlockHashReader for CachedStateProvider<SP> {
+    fn block_hash(&self, number: u64) -> ProviderResult<Option<B256>> {
+        self.provider.block_hash(number)
+    }
+
+    fn canonical_hashes_range(
+        &self,
+        start: BlockNumber,
+        end: BlockNumber,
+    ) -> ProviderResult<Vec<B256>> {
+        self.provider.canonical_hashes_range(start, end)
+    }
+}
+
+impl<SP: StateProvider> StateRootProvider for CachedStateProvider<SP> {
+    fn state_root(&self, bundle_state: &BundleStateWithReceipts) -> ProviderResult<B256> {
+        self.provider.state_root(bundle_state)
+    }
+
+    fn state_root_with_updates(
+        &self,
+        bundle_state: &BundleStateWithReceipts,
+    ) -> ProviderResult<(B256, TrieUpdates)> {
+        self.provider.state_root_with_updates(bundle_state)
+    }
+}
+
+impl<SP: StateProvider> StateProvider for CachedStateProvider<SP> {
+    fn storage(
+        &self,
+        account: Address,
+        storage_key: StorageKey,
+    ) -> ProviderResult<Option<StorageValue>> {
+        if let Some(value) = self.cache.storage(self.block_hash, account, storage_key) {
+            return Ok(value)
+        }
+        let value = self.provider.storage(account, storage_key)?;
+        self.cache.insert_storage(self.block_hash, account, storage_key, value);
+        Ok(value)
+    }
+
+    fn bytecode_by_hash(&self, code_hash: B256) -> ProviderResult<Option<Bytecode>> {
+        if let Some(bytecode) = self.cache.bytecode(code_hash) {
+            return Ok(Some(bytecode))
+        }
+        // missing bytecodes are not cached, since they may be added by later blocks
+        let bytecode = self.provider.bytecode_by_hash(code_hash)?;
+        if let Some(bytecode) = &bytecode {
+            self.cache.insert_bytecode(code_hash, bytecode.clone());
+        }
+        Ok(bytecode)
+    }
+
+    fn proof(&self, address: Address, keys: &[B256]) -> ProviderResult<AccountProof> {
+        self.provider.proof(address, keys)
+    }
+
+    fn proofs(&self, targets: &[(Address, Vec<B256>)]) -> ProviderResult<Vec<AccountProof>> {
+        self.provider.proofs(targets)
+    }
+}
+
+/// Metrics of the [StateCache].
+#[derive(Metrics, Clone)]
+#[metrics(scope = "providers.state_cache")]
+struct StateCacheMetrics {
+    /// Number of account reads served from the cache.
+    account_hits: Counter,
+    /// Number of account reads that missed the cache.
+    account_misses: Counter,
+    /// Number of storage reads served from the cache.
+    storage_hits: Counter,
+    /// Number of storage reads that missed the cache.
+    storage_misses: Counter,
+    /// Number of bytecode reads served from the cache.
+    bytecode_hits: Counter,
+    /// Number of bytecode reads that missed the cache.
+    bytecode_misses: Counter,
+    /// Number of times the cache was carried over to a new block.
+    reused: Counter,
+    /// Number of times the cache was cleared.
+    cleared: Counter,
+}
+
+impl StateCacheMetrics {
+    fn record_account(&self, hit: bool) {
+        let counter = if hit { &self.account_hits } else { &self.account_misses };
+        counter.increment(1);
+    }
+
+    fn record_storage(&self, hit: bool) {
+        let counter = if hit { &self.storage_hits } else { &self.storage_misses };
+        counter.increment(1);
+    }
+
+    fn record_bytecode(&self, hit: bool) {
+        let counter = if hit { &self.bytecode_hits } else { &self.bytecode_misses };
+        counter.increment(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{ExtendedAccount, MockEthProvider};
+    use reth_primitives::{Receipts, U256};
+    use revm::{db::BundleState, primitives::AccountInfo};
+    use std::collections::HashMap;
+
+    #[test]
+    fn cache_follows_tip() {
+        let provider = MockEthProvider::default();
+        let address = Address::random();
+        let key = B256::with_last_byte(1);
+        provider.add_account(
+            address,
+            ExtendedAccount::new(0, U256::from(1)).extend_storage([(key, U256::from(2))]),
+        );
+
+        let (parent, tip) = (B256::random(), B256::random());
+        let cache = StateCache::new(StateCacheConfig::default());
+        cache.clear(Some(parent));
+
+        let state = cache.provider(provider.clone(), parent);
+        assert_eq!(state.basic_account(address).unwrap().unwrap().balance, U256::from(1));
+        assert_eq!(state.storage(address, key).unwrap(), Some(U256::from(2)));
+        assert_eq!(cache.account(parent, address).unwrap().unwrap().balance, U256::from(1));
+        assert_eq!(cache.storage(parent, address, key), Some(Some(U256::from(2))));
+
+        // providers for other states don't use the cache
+        assert_eq!(cache.account(tip, address), None);
+        cache.provider(provider, tip).basic_account(Address::random()).unwrap();
+        assert_eq!(cache.inner.lock().accounts.len(), 1);
+
+        // changes are applied when moving to the next block
+        let changes = BundleStateWithReceipts::new(
+            BundleState::new(
+                [(
+                    address,
+                    None,
+                    Some(AccountInfo { balance: U256::from(3), ..Default::default() }),
+                    HashMap::from([(U256::from(1), (U256::from(2), U256::ZERO))]),
+                )],
+                vec![vec![(address, None, vec![])]],
+                vec![],
+            ),
+            Receipts::from_vec(vec![vec![]]),
+            1,
+        );
+        cache.update(parent, tip, &changes);
+        assert_eq!(cache.tip(), Some(tip));
+        assert_eq!(cache.account(tip, address).unwrap().unwrap().balance, U256::from(3));
+        assert_eq!(cache.storage(tip, address, key), Some(None));
+
+        // the cache is cleared if it can't be updated
+        cache.update(parent, B256::random(), &changes);
+        assert_eq!(cache.account(tip, address), None);
+        assert!(cache.inner.lock().accounts.is_empty());
+    }
+
+    #[test]
+    fn limit_storage_slots_per_account() {
+        let provider = MockEthProvider::default();
+        let address = Address::random();
+        let keys = [B256::with_last_byte(1), B256::with_last_byte(2), B256::with_last_byte(3)];
+        provider.add_account(
+            address,
+            ExtendedAccount::new(0, U256::ZERO)
+                .extend_storage(keys.iter().map(|key| (*key, U256::from(1)))),
+        );
+
+        let tip = B256::random();
+        let cache = StateCache::new(StateCacheConfig {
+            max_storage_slots_per_account: 2,
+            ..Default::default()
+        });
+        cache.clear(Some(tip));
+
+        let state = cache.provider(provider, tip);
+        for key in keys {
+            assert_eq!(state.storage(address, key).unwrap(), Some(U256::from(1)));
+        }
+        // the least recently used slot is evicted
+        assert_eq!(cache.storage(tip, address, keys[0]), None);
+        assert_eq!(cache.storage(tip, address, keys[1]), Some(Some(U256::from(1))));
+        assert_eq!(cache.storage(tip, address, keys[2]), Some(Some(U256::from(1))));
+    }
+}
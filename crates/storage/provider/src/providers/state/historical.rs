@@ -12,9 +12,10 @@ use reth_db::{
 };
 use reth_interfaces::provider::ProviderResult;
 use reth_primitives::{
-    trie::AccountProof, Account, Address, BlockNumber, Bytecode, StorageKey, StorageValue, B256,
+    stage::StageId, trie::AccountProof, Account, Address, BlockNumber, Bytecode, StorageKey,
+    StorageValue, B256,
 };
-use reth_trie::updates::TrieUpdates;
+use reth_trie::{updates::TrieUpdates, HashedPostState};
 
 /// State provider for a given block number which takes a tx reference.
 ///
@@ -150,6 +151,27 @@ impl<'b, TX: DbTx> HistoricalStateProviderRef<'b, TX> {
             Ok(HistoryInfo::NotYetWritten)
         }
     }
+
+    /// Returns the changes of all blocks since the historical block, which revert the current
+    /// hashed state to the historical state.
+    fn revert_state(&self) -> ProviderResult<HashedPostState> {
+        if !self.lowest_available_blocks.is_account_history_available(self.block_number) ||
+            !self.lowest_available_blocks.is_storage_history_available(self.block_number)
+        {
+            return Err(ProviderError::StateAtBlockPruned(self.block_number))
+        }
+
+        // the hashed state and the trie are written by the stages following the execution, which
+        // also writes the changesets, so neither can be ahead of the execution checkpoint. The
+        // canonical headers can be ahead of both while the pipeline is syncing.
+        let mut tip = 0;
+        for stage in [StageId::Execution, StageId::MerkleExecute] {
+            if let Some(checkpoint) = self.tx.get::<tables::SyncStage>(stage.to_string())? {
+                tip = tip.max(checkpoint.block_number);
+            }
+        }
+        Ok(HashedPostState::from_revert_range(self.tx, self.block_number..=tip)?)
+    }
 }
 
 impl<'b, TX: DbTx> AccountReader for HistoricalStateProviderRef<'b, TX> {
@@ -248,8 +270,29 @@ impl<'b, TX: DbTx> StateProvider for HistoricalStateProviderRef<'b, TX> {
     }
 
     /// Get account and storage proofs.
-    fn proof(&self, _address: Address, _keys: &[B256]) -> ProviderResult<AccountProof> {
-        Err(ProviderError::StateRootNotAvailableForHistoricalBlock)
+    ///
+    /// The proofs are generated by reverting the changes of all blocks since the historical block
+    /// on top of the current hashed state and trie.
+    fn proof(&self, address: Address, keys: &[B256]) -> ProviderResult<AccountProof> {
+        let revert_state = self.revert_state()?;
+        Ok(revert_state
+            .account_proof(self.tx, address, keys)
+            .map_err(Into::<reth_db::DatabaseError>::into)?)
+    }
+
+    /// Get account and storage proofs of multiple accounts.
+    ///
+    /// The changes since the historical block are reverted once for all proofs.
+    fn proofs(&self, targets: &[(Address, Vec<B256>)]) -> ProviderResult<Vec<AccountProof>> {
+        let revert_state = self.revert_state()?;
+        targets
+            .iter()
+            .map(|(address, keys)| {
+                Ok(revert_state
+                    .account_proof(self.tx, *address, keys)
+                    .map_err(Into::<reth_db::DatabaseError>::into)?)
+            })
+            .collect()
     }
 }
 
@@ -346,7 +389,12 @@ mod tests {
         BlockNumberList,
     };
     use reth_interfaces::provider::ProviderError;
-    use reth_primitives::{address, b256, Account, Address, StorageEntry, B256, U256};
+    use reth_primitives::{
+        address, b256,
+        stage::{StageCheckpoint, StageId},
+        Account, Address, Header, StorageEntry, B256, U256,
+    };
+    use reth_trie::HashedPostState;
 
     const ADDRESS: Address = address!("0000000000000000000000000000000000000001");
     const HIGHER_ADDRESS: Address = address!("0000000000000000000000000000000000000005");
@@ -613,4 +661,31 @@ mod tests {
             Ok(HistoryInfo::MaybeInPlainState)
         );
     }
+
+    #[test]
+    fn history_provider_revert_state_up_to_execution() {
+        let db = create_test_rw_db();
+        let tx = db.tx_mut().unwrap();
+
+        // the headers are ahead of the execution
+        for number in 0..=5 {
+            tx.put::<tables::CanonicalHeaders>(number, Header::default().hash_slow()).unwrap();
+        }
+        tx.put::<tables::SyncStage>(StageId::Execution.to_string(), StageCheckpoint::new(2))
+            .unwrap();
+        tx.put::<tables::AccountChangeSet>(1, AccountBeforeTx { address: ADDRESS, info: None })
+            .unwrap();
+        tx.put::<tables::AccountChangeSet>(
+            3,
+            AccountBeforeTx { address: HIGHER_ADDRESS, info: None },
+        )
+        .unwrap();
+        tx.commit().unwrap();
+
+        let tx = db.tx().unwrap();
+        assert_eq!(
+            HistoricalStateProviderRef::new(&tx, 1).revert_state().unwrap(),
+            HashedPostState::from_revert_range(&tx, 1..=2).unwrap()
+        );
+    }
 }
@@ -2,17 +2,17 @@ use crate::{
     bundle_state::BundleStateWithReceipts,
     traits::{BlockSource, ReceiptProvider},
     AccountReader, BlockHashReader, BlockIdReader, BlockNumReader, BlockReader, BlockReaderIdExt,
-    ChainSpecProvider, ChangeSetReader, EvmEnvProvider, HeaderProvider, PruneCheckpointReader,
-    ReceiptProviderIdExt, StageCheckpointReader, StateProvider, StateProviderBox,
-    StateProviderFactory, StateRootProvider, TransactionVariant, TransactionsProvider,
-    WithdrawalsProvider,
+    ChainSpecProvider, ChangeSetReader, EvmEnvProvider, HeaderProvider, LogIndexReader,
+    PruneCheckpointReader, ReceiptProviderIdExt, SnapStateReader, StageCheckpointReader,
+    StateProvider, StateProviderBox, StateProviderFactory, StateRootProvider,
+    TransactionAddressIndexReader, TransactionVariant, TransactionsProvider, WithdrawalsProvider,
 };
 use reth_db::models::{AccountBeforeTx, StoredBlockBodyIndices};
 use reth_interfaces::provider::ProviderResult;
 use reth_primitives::{
     stage::{StageCheckpoint, StageId},
     trie::AccountProof,
-    Account, Address, Block, BlockHash, BlockHashOrNumber, BlockId, BlockNumber, Bytecode,
+    Account, Address, Block, BlockHash, BlockHashOrNumber, BlockId, BlockNumber, Bytecode, Bytes,
     ChainInfo, ChainSpec, Header, PruneCheckpoint, PruneSegment, Receipt, SealedBlock,
     SealedBlockWithSenders, SealedHeader, StorageKey, StorageValue, TransactionMeta,
     TransactionSigned, TransactionSignedNoHash, TxHash, TxNumber, B256, MAINNET, U256,
@@ -313,6 +313,49 @@ impl StateProvider for NoopProvider {
     }
 }
 
+impl SnapStateReader for NoopProvider {
+    fn snap_state_root(&self) -> ProviderResult<B256> {
+        Ok(B256::default())
+    }
+
+    fn hashed_account_range(
+        &self,
+        _start: B256,
+        _limit: usize,
+    ) -> ProviderResult<Vec<(B256, Account)>> {
+        Ok(Vec::default())
+    }
+
+    fn hashed_storage_range(
+        &self,
+        _hashed_address: B256,
+        _start: B256,
+        _limit: usize,
+    ) -> ProviderResult<Vec<(B256, U256)>> {
+        Ok(Vec::default())
+    }
+
+    fn hashed_storage_root(&self, _hashed_address: B256) -> ProviderResult<B256> {
+        Ok(B256::default())
+    }
+
+    fn code_by_hash(&self, _code_hash: B256) -> ProviderResult<Option<Bytecode>> {
+        Ok(None)
+    }
+
+    fn hashed_account_multiproof(&self, _targets: &[B256]) -> ProviderResult<Vec<Bytes>> {
+        Ok(Vec::default())
+    }
+
+    fn hashed_storage_multiproof(
+        &self,
+        _hashed_address: B256,
+        _targets: &[B256],
+    ) -> ProviderResult<Vec<Bytes>> {
+        Ok(Vec::default())
+    }
+}
+
 impl EvmEnvProvider for NoopProvider {
     fn fill_env_at(
         &self,
@@ -400,6 +443,42 @@ impl StageCheckpointReader for NoopProvider {
     }
 }
 
+impl LogIndexReader for NoopProvider {
+    fn log_index_checkpoint(&self) -> ProviderResult<Option<BlockNumber>> {
+        Ok(None)
+    }
+
+    fn blocks_with_log_address(
+        &self,
+        _address: Address,
+        _range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<Vec<BlockNumber>> {
+        Ok(Vec::new())
+    }
+
+    fn blocks_with_log_topic(
+        &self,
+        _topic: B256,
+        _range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<Vec<BlockNumber>> {
+        Ok(Vec::new())
+    }
+}
+
+impl TransactionAddressIndexReader for NoopProvider {
+    fn transaction_address_index_checkpoint(&self) -> ProviderResult<Option<BlockNumber>> {
+        Ok(None)
+    }
+
+    fn blocks_with_transaction_address(
+        &self,
+        _address: Address,
+        _range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<Vec<BlockNumber>> {
+        Ok(Vec::new())
+    }
+}
+
 impl WithdrawalsProvider for NoopProvider {
     fn latest_withdrawal(&self) -> ProviderResult<Option<reth_primitives::Withdrawal>> {
         Ok(None)
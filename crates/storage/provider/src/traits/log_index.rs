@@ -0,0 +1,44 @@
+use reth_interfaces::provider::ProviderResult;
+use reth_primitives::{Address, BlockNumber, B256};
+use std::ops::RangeInclusive;
+
+/// The trait for looking up the blocks with logs of an address or topic in the log index.
+///
+/// The log index is optional. It is built by the `IndexLogs` stage and covers all blocks up to
+/// its checkpoint, see [`LogIndexReader::log_index_checkpoint`].
+#[auto_impl::auto_impl(&, Arc)]
+pub trait LogIndexReader: Send + Sync {
+    /// Returns the highest block covered by the log index, or `None` if the log index is not
+    /// maintained.
+    fn log_index_checkpoint(&self) -> ProviderResult<Option<BlockNumber>>;
+
+    /// Returns the blocks in the given range, in ascending order, with logs emitted by the given
+    /// address.
+    fn blocks_with_log_address(
+        &self,
+        address: Address,
+        range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<Vec<BlockNumber>>;
+
+    /// Returns the blocks in the given range, in ascending order, with logs that contain the given
+    /// topic in any position.
+    fn blocks_with_log_topic(
+        &self,
+        topic: B256,
+        range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<Vec<BlockNumber>>;
+}
+
+/// The trait for maintaining the log index.
+#[auto_impl::auto_impl(&, Arc, Box)]
+pub trait LogIndexWriter: Send + Sync {
+    /// Indexes the logs of the receipts of the given range of blocks.
+    fn insert_log_index(&self, range: RangeInclusive<BlockNumber>) -> ProviderResult<()>;
+
+    /// Removes the given range of blocks from the log index.
+    ///
+    /// This reads the receipts of the range, so it must be called before they are removed.
+    ///
+    /// Returns the number of unwound index keys.
+    fn unwind_log_index(&self, range: RangeInclusive<BlockNumber>) -> ProviderResult<usize>;
+}
@@ -6,6 +6,9 @@ pub use account::{AccountExtReader, AccountReader, ChangeSetReader};
 mod storage;
 pub use storage::StorageReader;
 
+mod snap;
+pub use snap::SnapStateReader;
+
 mod block;
 pub use block::{
     BlockExecutionWriter, BlockReader, BlockReaderIdExt, BlockSource, BlockWriter,
@@ -66,5 +69,11 @@ pub use hashing::HashingWriter;
 mod history;
 pub use history::HistoryWriter;
 
+mod log_index;
+pub use log_index::{LogIndexReader, LogIndexWriter};
+
 mod prune_checkpoint;
 pub use prune_checkpoint::{PruneCheckpointReader, PruneCheckpointWriter};
+
+mod transaction_address_index;
+pub use transaction_address_index::{TransactionAddressIndexReader, TransactionAddressIndexWriter};
@@ -0,0 +1,49 @@
+use auto_impl::auto_impl;
+use reth_interfaces::provider::ProviderResult;
+use reth_primitives::{Account, Bytecode, Bytes, B256, U256};
+
+/// Provides access to the hashed state and the state tries of the latest persisted block.
+///
+/// This is used to serve `snap` protocol requests: all ranges are ordered by hash and the proofs
+/// are generated against the root returned by [SnapStateReader::snap_state_root].
+///
+/// Note: implementations that open a new database transaction per call do not guarantee that
+/// consecutive calls observe the same state.
+#[auto_impl(&, Arc, Box)]
+pub trait SnapStateReader: Send + Sync {
+    /// Returns the state root of the latest persisted block.
+    fn snap_state_root(&self) -> ProviderResult<B256>;
+
+    /// Returns at most `limit` hashed accounts in ascending order, starting at `start`
+    /// (inclusive).
+    fn hashed_account_range(
+        &self,
+        start: B256,
+        limit: usize,
+    ) -> ProviderResult<Vec<(B256, Account)>>;
+
+    /// Returns at most `limit` hashed storage slots of the account in ascending order, starting at
+    /// `start` (inclusive).
+    fn hashed_storage_range(
+        &self,
+        hashed_address: B256,
+        start: B256,
+        limit: usize,
+    ) -> ProviderResult<Vec<(B256, U256)>>;
+
+    /// Returns the storage root of the account.
+    fn hashed_storage_root(&self, hashed_address: B256) -> ProviderResult<B256>;
+
+    /// Returns the bytecode with the given code hash.
+    fn code_by_hash(&self, code_hash: B256) -> ProviderResult<Option<Bytecode>>;
+
+    /// Returns the account trie nodes proving the given hashed addresses.
+    fn hashed_account_multiproof(&self, targets: &[B256]) -> ProviderResult<Vec<Bytes>>;
+
+    /// Returns the storage trie nodes of the account proving the given hashed slots.
+    fn hashed_storage_multiproof(
+        &self,
+        hashed_address: B256,
+        targets: &[B256],
+    ) -> ProviderResult<Vec<Bytes>>;
+}
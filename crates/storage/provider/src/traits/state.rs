@@ -27,6 +27,13 @@ pub trait StateProvider: BlockHashReader + AccountReader + StateRootProvider + S
     /// Get account and storage proofs.
     fn proof(&self, address: Address, keys: &[B256]) -> ProviderResult<AccountProof>;
 
+    /// Get account and storage proofs of multiple accounts, in the order of the targets.
+    ///
+    /// Providers of historical state override this to revert the state only once for all proofs.
+    fn proofs(&self, targets: &[(Address, Vec<B256>)]) -> ProviderResult<Vec<AccountProof>> {
+        targets.iter().map(|(address, keys)| self.proof(*address, keys)).collect()
+    }
+
     /// Get account code by its address.
     ///
     /// Returns `None` if the account doesn't exist or account is not a contract
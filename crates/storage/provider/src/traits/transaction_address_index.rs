@@ -0,0 +1,48 @@
+use reth_interfaces::provider::ProviderResult;
+use reth_primitives::{Address, BlockNumber};
+use std::ops::RangeInclusive;
+
+/// The trait for looking up the blocks with transactions an address is involved in.
+///
+/// The transaction address index is optional. It is built by the `IndexTransactionAddresses`
+/// stage and covers all blocks up to its checkpoint, see
+/// [`TransactionAddressIndexReader::transaction_address_index_checkpoint`].
+#[auto_impl::auto_impl(&, Arc)]
+pub trait TransactionAddressIndexReader: Send + Sync {
+    /// Returns the highest block covered by the transaction address index and the account
+    /// history, or `None` if the transaction address index is not maintained.
+    fn transaction_address_index_checkpoint(&self) -> ProviderResult<Option<BlockNumber>>;
+
+    /// Returns the blocks in the given range, in ascending order, that the given address is
+    /// involved in.
+    ///
+    /// These are the blocks with a transaction sent from or to the address or creating it, and the
+    /// blocks that changed the account, e.g. with an internal transfer or a self-destruct. Blocks
+    /// with account history that is pruned are missing.
+    fn blocks_with_transaction_address(
+        &self,
+        address: Address,
+        range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<Vec<BlockNumber>>;
+}
+
+/// The trait for maintaining the transaction address index.
+#[auto_impl::auto_impl(&, Arc, Box)]
+pub trait TransactionAddressIndexWriter: Send + Sync {
+    /// Indexes the senders, recipients and created contracts of the transactions of the given
+    /// range of blocks.
+    fn insert_transaction_address_index(
+        &self,
+        range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<()>;
+
+    /// Removes the given range of blocks from the transaction address index.
+    ///
+    /// This reads the transactions of the range, so it must be called before they are removed.
+    ///
+    /// Returns the number of unwound index keys.
+    fn unwind_transaction_address_index(
+        &self,
+        range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<usize>;
+}
@@ -0,0 +1,358 @@
+//! Storage for MEV bundles that are submitted via `eth_sendBundle`.
+//!
+//! A bundle is an ordered list of transactions that must be included atomically at the top of a
+//! block. Bundles are not subject to the regular transaction pool rules: they are kept separate
+//! from the pool's transactions and are only consulted by the payload builder when it builds a
+//! block for the bundle's target block number.
+
+use parking_lot::RwLock;
+use reth_primitives::{keccak256, TransactionSignedEcRecovered, TxHash, B256};
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::Arc,
+};
+
+/// The default maximum number of bundles kept in the [BundlePool].
+pub const DEFAULT_MAX_BUNDLES: usize = 1_000;
+
+/// A bundle of transactions that should be included atomically in a specific block.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MevBundle {
+    /// Hash of the bundle, derived from the hashes of its transactions.
+    hash: B256,
+    /// The ordered list of recovered transactions.
+    transactions: Vec<TransactionSignedEcRecovered>,
+    /// The block number this bundle targets.
+    block_number: u64,
+    /// Minimum timestamp of the block the bundle can be included in.
+    min_timestamp: Option<u64>,
+    /// Maximum timestamp of the block the bundle can be included in.
+    max_timestamp: Option<u64>,
+    /// Hashes of transactions that are allowed to revert.
+    reverting_tx_hashes: Vec<TxHash>,
+    /// An identifier that can be used to replace or cancel the bundle.
+    replacement_uuid: Option<String>,
+}
+
+// === impl MevBundle ===
+
+impl MevBundle {
+    /// Creates a new bundle for the given block with the given transactions.
+    ///
+    /// The bundle hash is the keccak256 hash of the concatenated transaction hashes.
+    pub fn new(transactions: Vec<TransactionSignedEcRecovered>, block_number: u64) -> Self {
+        let mut concatenated = Vec::with_capacity(transactions.len() * 32);
+        for tx in &transactions {
+            concatenated.extend_from_slice(tx.hash().as_slice());
+        }
+        Self {
+            hash: keccak256(concatenated),
+            transactions,
+            block_number,
+            min_timestamp: None,
+            max_timestamp: None,
+            reverting_tx_hashes: Vec::new(),
+            replacement_uuid: None,
+        }
+    }
+
+    /// Sets the minimum timestamp of the block the bundle can be included in.
+    pub fn with_min_timestamp(mut self, min_timestamp: Option<u64>) -> Self {
+        self.min_timestamp = min_timestamp;
+        self
+    }
+
+    /// Sets the maximum timestamp of the block the bundle can be included in.
+    pub fn with_max_timestamp(mut self, max_timestamp: Option<u64>) -> Self {
+        self.max_timestamp = max_timestamp;
+        self
+    }
+
+    /// Sets the hashes of transactions that are allowed to revert.
+    pub fn with_reverting_tx_hashes(mut self, reverting_tx_hashes: Vec<TxHash>) -> Self {
+        self.reverting_tx_hashes = reverting_tx_hashes;
+        self
+    }
+
+    /// Sets the replacement uuid of the bundle.
+    pub fn with_replacement_uuid(mut self, replacement_uuid: Option<String>) -> Self {
+        self.replacement_uuid = replacement_uuid;
+        self
+    }
+
+    /// Returns the hash of the bundle.
+    pub fn hash(&self) -> B256 {
+        self.hash
+    }
+
+    /// Returns the transactions of the bundle in order.
+    pub fn transactions(&self) -> &[TransactionSignedEcRecovered] {
+        &self.transactions
+    }
+
+    /// Returns the block number this bundle targets.
+    pub fn block_number(&self) -> u64 {
+        self.block_number
+    }
+
+    /// Returns the replacement uuid of the bundle, if any.
+    pub fn replacement_uuid(&self) -> Option<&str> {
+        self.replacement_uuid.as_deref()
+    }
+
+    /// Returns true if the transaction with the given hash is allowed to revert.
+    pub fn may_revert(&self, hash: &TxHash) -> bool {
+        self.reverting_tx_hashes.contains(hash)
+    }
+
+    /// Returns true if the bundle can be included in a block with the given number and timestamp.
+    pub fn is_valid_at(&self, block_number: u64, timestamp: u64) -> bool {
+        self.block_number == block_number &&
+            self.min_timestamp.map_or(true, |min| timestamp >= min) &&
+            self.max_timestamp.map_or(true, |max| max == 0 || timestamp <= max)
+    }
+}
+
+/// Errors that can occur when adding a bundle to the [BundlePool].
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum BundleError {
+    /// Thrown if the bundle has no transactions.
+    #[error("bundle has no transactions")]
+    Empty,
+    /// Thrown if the bundle contains an EIP-4844 transaction.
+    #[error("bundle contains blob transaction {0}")]
+    BlobTransaction(TxHash),
+    /// Thrown if the bundle targets a block that is already mined.
+    #[error("bundle targets block {bundle} but the current block is {current}")]
+    Outdated {
+        /// The block the bundle targets.
+        bundle: u64,
+        /// The currently tracked block.
+        current: u64,
+    },
+    /// Thrown if the pool already holds the maximum number of bundles.
+    #[error("bundle pool is full")]
+    PoolFull,
+    /// Thrown if the pool does not support bundles.
+    #[error("bundles are not supported")]
+    Unsupported,
+}
+
+/// A pool of [MevBundle]s, ordered by the time they were received.
+#[derive(Debug)]
+pub struct BundlePool {
+    inner: RwLock<BundlePoolInner>,
+}
+
+// === impl BundlePool ===
+
+impl BundlePool {
+    /// Creates a new pool that holds at most `max_bundles` bundles.
+    pub fn new(max_bundles: usize) -> Self {
+        Self { inner: RwLock::new(BundlePoolInner { max_bundles, ..Default::default() }) }
+    }
+
+    /// Adds a bundle to the pool.
+    ///
+    /// If a bundle with the same replacement uuid exists, it is replaced by the new bundle.
+    pub fn add_bundle(&self, bundle: MevBundle) -> Result<B256, BundleError> {
+        if bundle.transactions.is_empty() {
+            return Err(BundleError::Empty)
+        }
+        if let Some(tx) = bundle.transactions.iter().find(|tx| tx.is_eip4844()) {
+            return Err(BundleError::BlobTransaction(tx.hash()))
+        }
+
+        let mut inner = self.inner.write();
+        if bundle.block_number <= inner.last_mined_block {
+            return Err(BundleError::Outdated {
+                bundle: bundle.block_number,
+                current: inner.last_mined_block,
+            })
+        }
+
+        if let Some(uuid) = bundle.replacement_uuid() {
+            if let Some(hash) = inner.by_uuid.get(uuid).copied() {
+                inner.remove(&hash);
+            }
+        }
+
+        let hash = bundle.hash;
+        if inner.by_hash.contains_key(&hash) {
+            return Ok(hash)
+        }
+        if inner.by_hash.len() >= inner.max_bundles {
+            return Err(BundleError::PoolFull)
+        }
+
+        let id = inner.next_id;
+        inner.next_id += 1;
+        if let Some(uuid) = bundle.replacement_uuid.clone() {
+            inner.by_uuid.insert(uuid, hash);
+        }
+        inner.by_id.insert(id, hash);
+        inner.by_hash.insert(hash, (id, Arc::new(bundle)));
+        Ok(hash)
+    }
+
+    /// Returns the bundle with the given hash, if it exists.
+    pub fn get(&self, hash: &B256) -> Option<Arc<MevBundle>> {
+        self.inner.read().by_hash.get(hash).map(|(_, bundle)| Arc::clone(bundle))
+    }
+
+    /// Removes the bundle with the given hash.
+    pub fn remove_bundle(&self, hash: &B256) -> Option<Arc<MevBundle>> {
+        self.inner.write().remove(hash)
+    }
+
+    /// Removes the bundle with the given replacement uuid.
+    pub fn remove_bundle_by_replacement_uuid(&self, uuid: &str) -> Option<Arc<MevBundle>> {
+        let mut inner = self.inner.write();
+        let hash = inner.by_uuid.get(uuid).copied()?;
+        inner.remove(&hash)
+    }
+
+    /// Returns all bundles that can be included in a block with the given number and timestamp,
+    /// in the order they were received.
+    pub fn bundles_for_block(&self, block_number: u64, timestamp: u64) -> Vec<Arc<MevBundle>> {
+        let inner = self.inner.read();
+        inner
+            .by_id
+            .values()
+            .filter_map(|hash| inner.by_hash.get(hash))
+            .filter(|(_, bundle)| bundle.is_valid_at(block_number, timestamp))
+            .map(|(_, bundle)| Arc::clone(bundle))
+            .collect()
+    }
+
+    /// Removes all bundles that target the given block or an earlier one.
+    ///
+    /// This should be called with the new canonical tip on every canonical state change. After a
+    /// reorg the tip can be lower than before, bundles for the blocks above it are accepted again.
+    pub fn on_new_block(&self, block_number: u64) {
+        let mut inner = self.inner.write();
+        inner.last_mined_block = block_number;
+        let outdated = inner
+            .by_hash
+            .iter()
+            .filter(|(_, (_, bundle))| bundle.block_number <= block_number)
+            .map(|(hash, _)| *hash)
+            .collect::<Vec<_>>();
+        for hash in outdated {
+            inner.remove(&hash);
+        }
+    }
+
+    /// Returns the number of bundles in the pool.
+    pub fn len(&self) -> usize {
+        self.inner.read().by_hash.len()
+    }
+
+    /// Returns true if the pool holds no bundles.
+    pub fn is_empty(&self) -> bool {
+        self.inner.read().by_hash.is_empty()
+    }
+}
+
+impl Default for BundlePool {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_BUNDLES)
+    }
+}
+
+#[derive(Debug, Default)]
+struct BundlePoolInner {
+    /// All bundles by hash, with their insertion id.
+    by_hash: HashMap<B256, (u64, Arc<MevBundle>)>,
+    /// Bundle hashes in insertion order.
+    by_id: BTreeMap<u64, B256>,
+    /// Bundle hashes by replacement uuid.
+    by_uuid: HashMap<String, B256>,
+    /// The id assigned to the next bundle.
+    next_id: u64,
+    /// The number of the canonical tip.
+    last_mined_block: u64,
+    /// Maximum number of bundles.
+    max_bundles: usize,
+}
+
+impl BundlePoolInner {
+    fn remove(&mut self, hash: &B256) -> Option<Arc<MevBundle>> {
+        let (id, bundle) = self.by_hash.remove(hash)?;
+        self.by_id.remove(&id);
+        if let Some(uuid) = bundle.replacement_uuid() {
+            if self.by_uuid.get(uuid) == Some(hash) {
+                self.by_uuid.remove(uuid);
+            }
+        }
+        Some(bundle)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::{Signature, Transaction, TransactionSigned, TxLegacy};
+
+    fn tx(nonce: u64) -> TransactionSignedEcRecovered {
+        let tx = Transaction::Legacy(TxLegacy { nonce, ..Default::default() });
+        TransactionSigned::from_transaction_and_signature(tx, Signature::default())
+            .with_signer(Default::default())
+    }
+
+    #[test]
+    fn add_and_replace_bundles() {
+        let pool = BundlePool::new(2);
+        assert_eq!(pool.add_bundle(MevBundle::new(vec![], 1)), Err(BundleError::Empty));
+
+        let first = MevBundle::new(vec![tx(0)], 1).with_replacement_uuid(Some("a".to_string()));
+        let first_hash = pool.add_bundle(first).unwrap();
+        let second = MevBundle::new(vec![tx(1)], 1);
+        pool.add_bundle(second).unwrap();
+        assert_eq!(pool.add_bundle(MevBundle::new(vec![tx(2)], 1)), Err(BundleError::PoolFull));
+
+        // replaces the first bundle
+        let replacement =
+            MevBundle::new(vec![tx(3)], 1).with_replacement_uuid(Some("a".to_string()));
+        let replacement_hash = pool.add_bundle(replacement).unwrap();
+        assert!(pool.get(&first_hash).is_none());
+        assert_eq!(pool.len(), 2);
+
+        let bundles = pool.bundles_for_block(1, 0);
+        assert_eq!(bundles.len(), 2);
+        assert_eq!(bundles[1].hash(), replacement_hash);
+        assert!(pool.bundles_for_block(2, 0).is_empty());
+
+        assert!(pool.remove_bundle_by_replacement_uuid("a").is_some());
+        assert_eq!(pool.len(), 1);
+    }
+
+    #[test]
+    fn prunes_outdated_bundles() {
+        let pool = BundlePool::default();
+        pool.add_bundle(MevBundle::new(vec![tx(0)], 1)).unwrap();
+        pool.add_bundle(MevBundle::new(vec![tx(1)], 2).with_min_timestamp(Some(10))).unwrap();
+
+        pool.on_new_block(1);
+        assert_eq!(pool.len(), 1);
+        assert!(pool.bundles_for_block(2, 9).is_empty());
+        assert_eq!(pool.bundles_for_block(2, 10).len(), 1);
+
+        assert_eq!(
+            pool.add_bundle(MevBundle::new(vec![tx(2)], 1)),
+            Err(BundleError::Outdated { bundle: 1, current: 1 })
+        );
+    }
+
+    #[test]
+    fn resets_tip_on_reorg() {
+        let pool = BundlePool::default();
+        pool.on_new_block(5);
+        assert!(pool.add_bundle(MevBundle::new(vec![tx(0)], 4)).is_err());
+
+        // the reorg moved the canonical tip back to block 3
+        pool.on_new_block(3);
+        pool.add_bundle(MevBundle::new(vec![tx(0)], 4)).unwrap();
+        assert_eq!(pool.bundles_for_block(4, 0).len(), 1);
+    }
+}
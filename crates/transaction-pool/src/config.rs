@@ -1,4 +1,4 @@
-use crate::TransactionOrigin;
+use crate::{bundle::DEFAULT_MAX_BUNDLES, TransactionOrigin};
 use reth_primitives::{Address, EIP4844_TX_TYPE_ID};
 use std::collections::HashSet;
 /// Guarantees max transactions for one sender, compatible with geth/erigon
@@ -36,6 +36,8 @@ pub struct PoolConfig {
     /// How to handle locally received transactions:
     /// [TransactionOrigin::Local](crate::TransactionOrigin).
     pub local_transactions_config: LocalTransactionConfig,
+    /// Max number of bundles kept in the bundle pool.
+    pub max_bundles: usize,
 }
 
 impl Default for PoolConfig {
@@ -48,10 +50,51 @@ impl Default for PoolConfig {
             max_account_slots: TXPOOL_MAX_ACCOUNT_SLOTS_PER_SENDER,
             price_bumps: Default::default(),
             local_transactions_config: Default::default(),
+            max_bundles: DEFAULT_MAX_BUNDLES,
         }
     }
 }
 
+impl PoolConfig {
+    /// Sets the size limits of the pending sub-pool.
+    pub fn with_pending_limit(mut self, limit: SubPoolLimit) -> Self {
+        self.pending_limit = limit;
+        self
+    }
+
+    /// Sets the size limits of the basefee sub-pool.
+    pub fn with_basefee_limit(mut self, limit: SubPoolLimit) -> Self {
+        self.basefee_limit = limit;
+        self
+    }
+
+    /// Sets the size limits of the queued sub-pool.
+    pub fn with_queued_limit(mut self, limit: SubPoolLimit) -> Self {
+        self.queued_limit = limit;
+        self
+    }
+
+    /// Sets the size limits of the blob sub-pool.
+    pub fn with_blob_limit(mut self, limit: SubPoolLimit) -> Self {
+        self.blob_limit = limit;
+        self
+    }
+
+    /// Sets the max number of executable transaction slots guaranteed per account.
+    ///
+    /// Non-local senders can't have more transactions in the pool.
+    pub fn with_max_account_slots(mut self, max_account_slots: usize) -> Self {
+        self.max_account_slots = max_account_slots;
+        self
+    }
+
+    /// Sets the price bumps (in %) required to replace a transaction.
+    pub fn with_price_bumps(mut self, price_bumps: PriceBumpConfig) -> Self {
+        self.price_bumps = price_bumps;
+        self
+    }
+}
+
 /// Size limits for a sub-pool.
 #[derive(Debug, Clone)]
 pub struct SubPoolLimit {
@@ -62,6 +105,11 @@ pub struct SubPoolLimit {
 }
 
 impl SubPoolLimit {
+    /// Creates a new limit of the given number of transactions and combined size in bytes.
+    pub const fn new(max_txs: usize, max_size: usize) -> Self {
+        Self { max_txs, max_size }
+    }
+
     /// Returns whether the size or amount constraint is violated.
     #[inline]
     pub fn is_exceeded(&self, txs: usize, size: usize) -> bool {
@@ -110,7 +158,7 @@ impl Default for PriceBumpConfig {
 
 /// Configuration options for the locally received transactions:
 /// [TransactionOrigin::Local](crate::TransactionOrigin)
-#[derive(Debug, Clone, Eq, PartialEq, Default)]
+#[derive(Debug, Clone, Eq, PartialEq)]
 pub struct LocalTransactionConfig {
     /// Apply no exemptions to the locally received transactions.
     ///
@@ -121,6 +169,21 @@ pub struct LocalTransactionConfig {
     pub no_exemptions: bool,
     /// Addresses that will be considered as local . Above exemptions apply
     pub local_addresses: HashSet<Address>,
+    /// Flag indicating whether local transactions should be propagated to the network.
+    ///
+    /// If disabled, local transactions are never gossiped and are not returned on
+    /// `GetPooledTransactions` requests from peers.
+    pub propagate_local_transactions: bool,
+}
+
+impl Default for LocalTransactionConfig {
+    fn default() -> Self {
+        Self {
+            no_exemptions: false,
+            local_addresses: HashSet::default(),
+            propagate_local_transactions: true,
+        }
+    }
 }
 
 impl LocalTransactionConfig {
@@ -146,4 +209,11 @@ impl LocalTransactionConfig {
         }
         origin.is_local() || self.contains_local_address(sender)
     }
+
+    /// Sets toggle to propagate transactions received locally by this client (e.g
+    /// transactions from eth_sendTransaction to this nodes' RPC server)
+    pub fn with_propagate_local_transactions(mut self, propagate_local_txs: bool) -> Self {
+        self.propagate_local_transactions = propagate_local_txs;
+        self
+    }
 }
@@ -150,7 +150,9 @@
 
 use crate::{identifier::TransactionId, pool::PoolInner};
 use aquamarine as _;
-use reth_primitives::{Address, BlobTransactionSidecar, PooledTransactionsElement, TxHash, U256};
+use reth_primitives::{
+    Address, BlobTransactionSidecar, PooledTransactionsElement, TxHash, B256, U256,
+};
 use reth_provider::StateProviderFactory;
 use std::{collections::HashSet, sync::Arc};
 use tokio::sync::mpsc::Receiver;
@@ -158,16 +160,17 @@ use tracing::{instrument, trace};
 
 pub use crate::{
     blobstore::{BlobStore, BlobStoreError},
+    bundle::{BundleError, BundlePool, MevBundle, DEFAULT_MAX_BUNDLES},
     config::{
         LocalTransactionConfig, PoolConfig, PriceBumpConfig, SubPoolLimit, DEFAULT_PRICE_BUMP,
         REPLACE_BLOB_PRICE_BUMP, TXPOOL_MAX_ACCOUNT_SLOTS_PER_SENDER,
         TXPOOL_SUBPOOL_MAX_SIZE_MB_DEFAULT, TXPOOL_SUBPOOL_MAX_TXS_DEFAULT,
     },
     error::PoolResult,
-    ordering::{CoinbaseTipOrdering, Priority, TransactionOrdering},
+    ordering::{CoinbaseTipOrdering, FnOrdering, Priority, TransactionOrdering},
     pool::{
-        blob_tx_priority, fee_delta, state::SubPool, AllTransactionsEvents, FullTransactionEvent,
-        TransactionEvent, TransactionEvents,
+        blob_tx_priority, fee_delta, state::SubPool, AllTransactionsEvents, DiscardReason,
+        FullTransactionEvent, TransactionEvent, TransactionEvents,
     },
     traits::*,
     validate::{
@@ -184,6 +187,7 @@ pub mod pool;
 pub mod validate;
 
 pub mod blobstore;
+pub mod bundle;
 mod config;
 mod identifier;
 mod ordering;
@@ -515,6 +519,22 @@ where
     ) -> Result<Vec<BlobTransactionSidecar>, BlobStoreError> {
         self.pool.blob_store().get_exact(tx_hashes)
     }
+
+    fn add_bundle(&self, bundle: MevBundle) -> Result<B256, BundleError> {
+        self.pool.bundles().add_bundle(bundle)
+    }
+
+    fn remove_bundle(&self, bundle_hash: B256) -> Option<Arc<MevBundle>> {
+        self.pool.bundles().remove_bundle(&bundle_hash)
+    }
+
+    fn remove_bundle_by_replacement_uuid(&self, uuid: &str) -> Option<Arc<MevBundle>> {
+        self.pool.bundles().remove_bundle_by_replacement_uuid(uuid)
+    }
+
+    fn bundles_for_block(&self, block_number: u64, timestamp: u64) -> Vec<Arc<MevBundle>> {
+        self.pool.bundles().bundles_for_block(block_number, timestamp)
+    }
 }
 
 impl<V: TransactionValidator, T: TransactionOrdering, S> TransactionPoolExt for Pool<V, T, S>
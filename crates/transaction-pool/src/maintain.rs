@@ -5,7 +5,7 @@ use crate::{
     error::PoolError,
     metrics::MaintainPoolMetrics,
     traits::{CanonicalStateUpdate, ChangedAccount, TransactionPool, TransactionPoolExt},
-    BlockInfo,
+    BlockInfo, PoolTransaction, TransactionOrigin,
 };
 use futures_util::{
     future::{BoxFuture, Fuse, FusedFuture},
@@ -26,6 +26,7 @@ use std::{
     collections::HashSet,
     hash::{Hash, Hasher},
     path::{Path, PathBuf},
+    time::Duration,
 };
 use tokio::sync::oneshot;
 use tracing::{debug, error, info, trace, warn};
@@ -50,17 +51,40 @@ impl Default for MaintainPoolConfig {
     }
 }
 
+/// The default interval at which the transactions backup is written.
+pub const DEFAULT_TXS_BACKUP_INTERVAL: Duration = Duration::from_secs(60 * 5);
+
 /// Settings for local transaction backup task
 #[derive(Debug, Clone, Default)]
 pub struct LocalTransactionBackupConfig {
     /// Path to transactions backup file
     pub transactions_path: Option<PathBuf>,
+    /// Path to the backup file for pending transactions that were received from the network.
+    ///
+    /// If not set, only local transactions are persisted.
+    pub pending_transactions_path: Option<PathBuf>,
+    /// How often the backup is written while the node is running.
+    ///
+    /// If not set, the backup is only written on shutdown.
+    pub backup_interval: Option<Duration>,
 }
 
 impl LocalTransactionBackupConfig {
     /// Receive path to transactions backup and return initialized config
     pub fn with_local_txs_backup(transactions_path: PathBuf) -> Self {
-        Self { transactions_path: Some(transactions_path) }
+        Self { transactions_path: Some(transactions_path), ..Default::default() }
+    }
+
+    /// Also persists pending transactions from the network to the given file.
+    pub fn with_pending_txs_backup(mut self, pending_transactions_path: PathBuf) -> Self {
+        self.pending_transactions_path = Some(pending_transactions_path);
+        self
+    }
+
+    /// Sets the interval at which the backup is written while the node is running.
+    pub fn with_backup_interval(mut self, interval: Duration) -> Self {
+        self.backup_interval = Some(interval);
+        self
     }
 }
 
@@ -553,11 +577,12 @@ fn changed_accounts_iter(
 }
 
 /// Loads transactions from a file, decodes them from the RLP format, and inserts them
-/// into the transaction pool on node boot up.
+/// into the transaction pool with the given origin on node boot up.
 /// The file is removed after the transactions have been successfully processed.
 async fn load_and_reinsert_transactions<P>(
     pool: P,
     file_path: &Path,
+    origin: TransactionOrigin,
 ) -> Result<(), TransactionsBackupError>
 where
     P: TransactionPool,
@@ -579,9 +604,9 @@ where
         .into_iter()
         .filter_map(|tx| tx.try_ecrecovered().map(<P::Transaction>::from_recovered_transaction))
         .collect::<Vec<_>>();
-    let outcome = pool.add_transactions(crate::TransactionOrigin::Local, pool_transactions).await?;
+    let outcome = pool.add_transactions(origin, pool_transactions).await?;
 
-    info!(target: "txpool", txs_file =?file_path, num_txs=%outcome.len(), "Successfully reinserted local transactions from file");
+    info!(target: "txpool", txs_file =?file_path, num_txs=%outcome.len(), ?origin, "Successfully reinserted transactions from file");
     reth_primitives::fs::remove_file(file_path)?;
     Ok(())
 }
@@ -591,28 +616,58 @@ where
     P: TransactionPool,
 {
     let local_transactions = pool.get_local_transactions();
-    if local_transactions.is_empty() {
-        trace!(target: "txpool", "no local transactions to save");
+    save_txs_backup(
+        local_transactions.into_iter().map(|tx| tx.to_recovered_transaction()),
+        file_path,
+    )
+}
+
+/// Saves all pending transactions that were received from the network.
+///
+/// Local transactions are persisted separately and private transactions are never persisted, so
+/// that they can't be propagated after a restart.
+fn save_pending_txs_backup<P>(pool: P, file_path: &Path)
+where
+    P: TransactionPool,
+{
+    let pending_transactions = pool.pending_transactions();
+    save_txs_backup(
+        pending_transactions
+            .into_iter()
+            .filter(|tx| tx.origin == TransactionOrigin::External && !tx.transaction.is_eip4844())
+            .map(|tx| tx.to_recovered_transaction()),
+        file_path,
+    )
+}
+
+fn save_txs_backup(
+    transactions: impl Iterator<Item = reth_primitives::TransactionSignedEcRecovered>,
+    file_path: &Path,
+) {
+    let transactions = transactions.map(|tx| tx.into_signed()).collect::<Vec<_>>();
+    if transactions.is_empty() {
+        trace!(target: "txpool", txs_file=?file_path, "no transactions to save");
+        // remove outdated backups from previous runs
+        if file_path.exists() {
+            if let Err(err) = reth_primitives::fs::remove_file(file_path) {
+                warn!(target: "txpool", %err, txs_file=?file_path, "Failed to remove transactions backup");
+            }
+        }
         return
     }
 
-    let local_transactions = local_transactions
-        .into_iter()
-        .map(|tx| tx.to_recovered_transaction().into_signed())
-        .collect::<Vec<_>>();
-
-    let num_txs = local_transactions.len();
+    let num_txs = transactions.len();
     let mut buf = alloy_rlp::BytesMut::new();
-    alloy_rlp::encode_list(&local_transactions, &mut buf);
-    info!(target: "txpool", txs_file =?file_path, num_txs=%num_txs, "Saving current local transactions");
+    alloy_rlp::encode_list(&transactions, &mut buf);
+    info!(target: "txpool", txs_file =?file_path, num_txs=%num_txs, "Saving current transactions");
     let parent_dir = file_path.parent().map(std::fs::create_dir_all).transpose();
 
     match parent_dir.map(|_| reth_primitives::fs::write(file_path, buf)) {
         Ok(_) => {
-            info!(target: "txpool", txs_file=?file_path, "Wrote local transactions to file");
+            info!(target: "txpool", txs_file=?file_path, "Wrote transactions to file");
         }
         Err(err) => {
-            warn!(target: "txpool", %err, txs_file=?file_path, "Failed to write local transactions to file");
+            warn!(target: "txpool", %err, txs_file=?file_path, "Failed to write transactions to file");
         }
     }
 }
@@ -633,6 +688,9 @@ pub enum TransactionsBackupError {
 
 /// Task which manages saving local transactions to the persistent file in case of shutdown.
 /// Reloads the transactions from the file on the boot up and inserts them into the pool.
+///
+/// If configured, this also persists pending transactions from the network and writes the backup
+/// periodically, so that transactions are not lost if the node is not shut down gracefully.
 pub async fn backup_local_transactions_task<P>(
     shutdown: reth_tasks::shutdown::GracefulShutdown,
     pool: P,
@@ -640,19 +698,59 @@ pub async fn backup_local_transactions_task<P>(
 ) where
     P: TransactionPool + Clone,
 {
-    let Some(transactions_path) = config.transactions_path else {
+    let LocalTransactionBackupConfig {
+        transactions_path,
+        pending_transactions_path,
+        backup_interval,
+    } = config;
+    let Some(transactions_path) = transactions_path else {
         // nothing to do
         return
     };
 
-    if let Err(err) = load_and_reinsert_transactions(pool.clone(), &transactions_path).await {
+    if let Err(err) =
+        load_and_reinsert_transactions(pool.clone(), &transactions_path, TransactionOrigin::Local)
+            .await
+    {
         error!(target: "txpool", "{}", err)
     }
+    if let Some(pending_transactions_path) = pending_transactions_path.as_deref() {
+        if let Err(err) = load_and_reinsert_transactions(
+            pool.clone(),
+            pending_transactions_path,
+            TransactionOrigin::External,
+        )
+        .await
+        {
+            error!(target: "txpool", "{}", err)
+        }
+    }
+
+    let save_backup = |pool: &P| {
+        save_local_txs_backup(pool.clone(), &transactions_path);
+        if let Some(pending_transactions_path) = pending_transactions_path.as_deref() {
+            save_pending_txs_backup(pool.clone(), pending_transactions_path);
+        }
+    };
 
-    let graceful_guard = shutdown.await;
+    let graceful_guard = if let Some(backup_interval) = backup_interval {
+        let mut interval = tokio::time::interval_at(
+            tokio::time::Instant::now() + backup_interval,
+            backup_interval,
+        );
+        let mut shutdown = std::pin::pin!(shutdown);
+        loop {
+            tokio::select! {
+                guard = &mut shutdown => break guard,
+                _ = interval.tick() => save_backup(&pool),
+            }
+        }
+    } else {
+        shutdown.await
+    };
 
     // write transactions to disk
-    save_local_txs_backup(pool, &transactions_path);
+    save_backup(&pool);
 
     drop(graceful_guard)
 }
@@ -731,4 +829,56 @@ mod tests {
 
         temp_dir.close().unwrap();
     }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_periodic_pending_txs_backup() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let transactions_path = temp_dir.path().join(FILENAME).with_extension(EXTENSION);
+        let pending_transactions_path =
+            temp_dir.path().join(format!("{FILENAME}_pending")).with_extension(EXTENSION);
+        let tx_bytes = hex!("02f87201830655c2808505ef61f08482565f94388c818ca8b9251b393131c08a736a67ccb192978801049e39c4b5b1f580c001a01764ace353514e8abdfb92446de356b260e3c1225b73fc4c8876a6258d12a129a04f02294aa61ca7676061cd99f29275491218b4754b46a0248e5e42bc5091f507");
+        let tx = PooledTransactionsElement::decode_enveloped(tx_bytes.into()).unwrap();
+        let provider = MockEthProvider::default();
+        let transaction = EthPooledTransaction::from_recovered_pooled_transaction(
+            tx.try_into_ecrecovered().unwrap(),
+        );
+        let sender = hex!("1f9090aaE28b8a3dCeaDf281B0F12828e676c326").into();
+        provider.add_account(sender, ExtendedAccount::new(42, U256::MAX));
+        let blob_store = InMemoryBlobStore::default();
+        let validator = EthTransactionValidatorBuilder::new(MAINNET.clone())
+            .build(provider, blob_store.clone());
+
+        let txpool = Pool::new(
+            validator.clone(),
+            CoinbaseTipOrdering::default(),
+            blob_store.clone(),
+            Default::default(),
+        );
+
+        txpool.add_transaction(TransactionOrigin::External, transaction.clone()).await.unwrap();
+
+        let handle = tokio::runtime::Handle::current();
+        let manager = TaskManager::new(handle);
+        let config = LocalTransactionBackupConfig::with_local_txs_backup(transactions_path.clone())
+            .with_pending_txs_backup(pending_transactions_path.clone())
+            .with_backup_interval(Duration::from_millis(50));
+        manager.executor().spawn_critical_with_graceful_shutdown_signal("test task", |shutdown| {
+            backup_local_transactions_task(shutdown, txpool.clone(), config)
+        });
+
+        // the backup is written before the node shuts down
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        let data = fs::read(&pending_transactions_path).unwrap();
+        let txs: Vec<TransactionSigned> =
+            alloy_rlp::Decodable::decode(&mut data.as_slice()).unwrap();
+        assert_eq!(txs.len(), 1);
+        assert_eq!(txs[0].hash(), *transaction.hash());
+
+        // there are no local transactions
+        assert!(!transactions_path.exists());
+
+        manager.graceful_shutdown();
+
+        temp_dir.close().unwrap();
+    }
 }
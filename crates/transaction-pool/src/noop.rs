@@ -5,6 +5,7 @@
 
 use crate::{
     blobstore::BlobStoreError,
+    bundle::{BundleError, MevBundle},
     error::PoolError,
     traits::{
         BestTransactionsAttributes, GetPooledTransactionLimit, NewBlobSidecar,
@@ -16,7 +17,7 @@ use crate::{
     PropagatedTransactions, TransactionEvents, TransactionOrigin, TransactionPool,
     TransactionValidationOutcome, TransactionValidator, ValidPoolTransaction,
 };
-use reth_primitives::{Address, BlobTransactionSidecar, TxHash};
+use reth_primitives::{Address, BlobTransactionSidecar, TxHash, B256};
 use std::{collections::HashSet, marker::PhantomData, sync::Arc};
 use tokio::sync::{mpsc, mpsc::Receiver};
 
@@ -231,6 +232,22 @@ impl TransactionPool for NoopTransactionPool {
     ) -> Vec<Arc<ValidPoolTransaction<Self::Transaction>>> {
         vec![]
     }
+
+    fn add_bundle(&self, _bundle: MevBundle) -> Result<B256, BundleError> {
+        Err(BundleError::Unsupported)
+    }
+
+    fn remove_bundle(&self, _bundle_hash: B256) -> Option<Arc<MevBundle>> {
+        None
+    }
+
+    fn remove_bundle_by_replacement_uuid(&self, _uuid: &str) -> Option<Arc<MevBundle>> {
+        None
+    }
+
+    fn bundles_for_block(&self, _block_number: u64, _timestamp: u64) -> Vec<Arc<MevBundle>> {
+        vec![]
+    }
 }
 
 /// A [`TransactionValidator`] that does nothing.
@@ -1,6 +1,6 @@
 use crate::traits::PoolTransaction;
 use reth_primitives::U256;
-use std::{fmt, marker::PhantomData};
+use std::{fmt, marker::PhantomData, sync::Arc};
 
 /// Priority of the transaction that can be missing.
 ///
@@ -82,3 +82,82 @@ impl<T> Clone for CoinbaseTipOrdering<T> {
         Self::default()
     }
 }
+
+/// An ordering that ranks transactions by the score returned by a custom function.
+///
+/// The function receives the transaction and the current base fee and returns the score of the
+/// transaction, or `None` if the transaction has no valid priority. This makes it possible to rank
+/// transactions by something other than the effective tip without implementing
+/// [TransactionOrdering] for a new type.
+///
+/// ```
+/// use reth_primitives::U256;
+/// use reth_transaction_pool::{EthPooledTransaction, FnOrdering, PoolTransaction};
+///
+/// // rank transactions by their max fee per gas
+/// let ordering = FnOrdering::<EthPooledTransaction, _>::new(|tx: &EthPooledTransaction, _| {
+///     Some(U256::from(tx.max_fee_per_gas()))
+/// });
+/// ```
+pub struct FnOrdering<T, F> {
+    score: Arc<F>,
+    _marker: PhantomData<fn(&T)>,
+}
+
+impl<T, F> FnOrdering<T, F>
+where
+    F: Fn(&T, u64) -> Option<U256>,
+{
+    /// Creates a new ordering that uses the given scoring function.
+    pub fn new(score: F) -> Self {
+        Self { score: Arc::new(score), _marker: PhantomData }
+    }
+}
+
+impl<T, F> TransactionOrdering for FnOrdering<T, F>
+where
+    T: PoolTransaction + 'static,
+    F: Fn(&T, u64) -> Option<U256> + Send + Sync + 'static,
+{
+    type PriorityValue = U256;
+    type Transaction = T;
+
+    fn priority(
+        &self,
+        transaction: &Self::Transaction,
+        base_fee: u64,
+    ) -> Priority<Self::PriorityValue> {
+        (self.score)(transaction, base_fee).into()
+    }
+}
+
+impl<T, F> Clone for FnOrdering<T, F> {
+    fn clone(&self) -> Self {
+        Self { score: Arc::clone(&self.score), _marker: PhantomData }
+    }
+}
+
+impl<T, F> fmt::Debug for FnOrdering<T, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FnOrdering").finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::MockTransaction;
+
+    #[test]
+    fn fn_ordering_uses_score() {
+        // rank by max fee regardless of the base fee
+        let ordering =
+            FnOrdering::new(|tx: &MockTransaction, _| Some(U256::from(tx.max_fee_per_gas())));
+        let low = MockTransaction::eip1559().with_max_fee(10);
+        let high = MockTransaction::eip1559().with_max_fee(100);
+        assert!(ordering.priority(&high, 1_000) > ordering.priority(&low, 1_000));
+
+        let ordering = FnOrdering::new(|_: &MockTransaction, _| None);
+        assert_eq!(ordering.priority(&low, 0), Priority::None);
+    }
+}
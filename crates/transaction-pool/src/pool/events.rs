@@ -28,8 +28,13 @@ pub enum FullTransactionEvent<T: PoolTransaction> {
         /// The transaction that replaced the event subject.
         replaced_by: TxHash,
     },
-    /// Transaction was dropped due to configured limits.
-    Discarded(TxHash),
+    /// Transaction was dropped from the pool.
+    Discarded {
+        /// The hash of the dropped transaction.
+        tx_hash: TxHash,
+        /// Why the transaction was dropped.
+        reason: DiscardReason,
+    },
     /// Transaction became invalid indefinitely.
     Invalid(TxHash),
     /// Transaction was propagated to peers.
@@ -47,7 +52,9 @@ impl<T: PoolTransaction> Clone for FullTransactionEvent<T> {
             Self::Replaced { transaction, replaced_by } => {
                 Self::Replaced { transaction: Arc::clone(transaction), replaced_by: *replaced_by }
             }
-            Self::Discarded(hash) => Self::Discarded(*hash),
+            Self::Discarded { tx_hash, reason } => {
+                Self::Discarded { tx_hash: *tx_hash, reason: *reason }
+            }
             Self::Invalid(hash) => Self::Invalid(*hash),
             Self::Propagated(propagated) => Self::Propagated(Arc::clone(propagated)),
         }
@@ -68,8 +75,8 @@ pub enum TransactionEvent {
     ///
     /// E.g. same (sender + nonce) pair
     Replaced(TxHash),
-    /// Transaction was dropped due to configured limits.
-    Discarded,
+    /// Transaction was dropped from the pool for the given reason.
+    Discarded(DiscardReason),
     /// Transaction became invalid indefinitely.
     Invalid,
     /// Transaction was propagated to peers.
@@ -84,7 +91,24 @@ impl TransactionEvent {
             self,
             TransactionEvent::Replaced(_) |
                 TransactionEvent::Mined(_) |
-                TransactionEvent::Discarded
+                TransactionEvent::Discarded(_)
         )
     }
 }
+
+/// The reason a transaction was dropped from the pool.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum DiscardReason {
+    /// The transaction was dropped to respect the size limits of the pool.
+    PoolLimits,
+    /// The transaction failed validation and was never added to the pool.
+    Invalid,
+    /// The transaction couldn't be validated because of an internal error, e.g. a database error.
+    ValidationError,
+    /// The transaction can't be executed anymore after the state of its sender changed, e.g.
+    /// because another transaction with the same nonce was mined.
+    StateChange,
+    /// The transaction was removed via the pool API.
+    Removed,
+}
@@ -66,6 +66,7 @@
 //!    category (2.) and become pending.
 
 use crate::{
+    bundle::BundlePool,
     error::{PoolError, PoolErrorKind, PoolResult},
     identifier::{SenderId, SenderIdentifiers, TransactionId},
     pool::{
@@ -95,7 +96,7 @@ use std::{
 use tokio::sync::mpsc;
 use tracing::{debug, trace, warn};
 mod events;
-pub use events::{FullTransactionEvent, TransactionEvent};
+pub use events::{DiscardReason, FullTransactionEvent, TransactionEvent};
 
 mod listener;
 use crate::{
@@ -149,6 +150,8 @@ where
     blob_transaction_sidecar_listener: Mutex<Vec<BlobTransactionSidecarListener>>,
     /// Metrics for the blob store
     blob_store_metrics: BlobStoreMetrics,
+    /// Bundles submitted via `eth_sendBundle`.
+    bundles: BundlePool,
 }
 
 // === impl PoolInner ===
@@ -166,6 +169,7 @@ where
             validator,
             event_listener: Default::default(),
             pool: RwLock::new(TxPool::new(ordering, config.clone())),
+            bundles: BundlePool::new(config.max_bundles),
             pending_transaction_listener: Default::default(),
             transaction_listener: Default::default(),
             blob_transaction_sidecar_listener: Default::default(),
@@ -180,6 +184,11 @@ where
         &self.blob_store
     }
 
+    /// Returns the pool of submitted bundles.
+    pub(crate) fn bundles(&self) -> &BundlePool {
+        &self.bundles
+    }
+
     /// Returns stats about the size of the pool.
     pub(crate) fn size(&self) -> PoolSize {
         self.get_pool_data().size()
@@ -317,7 +326,8 @@ where
         let transactions = self.get_all(tx_hashes);
         let mut elements = Vec::with_capacity(transactions.len());
         let mut size = 0;
-        for transaction in transactions {
+        // transactions that must not be propagated are never handed out to peers
+        for transaction in transactions.into_iter().filter(|tx| tx.propagate) {
             let tx = transaction.to_recovered_transaction().into_signed();
             let pooled = if tx.is_eip4844() {
                 if let Some(blob) = self.get_blob_transaction(tx) {
@@ -345,6 +355,7 @@ where
         trace!(target: "txpool", %update, "updating pool on canonical state change");
 
         let block_info = update.block_info();
+        self.bundles.on_new_block(update.number());
         let CanonicalStateUpdate { new_tip, changed_accounts, mined_transactions, .. } = update;
         self.validator.on_new_head_block(new_tip);
 
@@ -374,7 +385,7 @@ where
         let mut listener = self.event_listener.write();
 
         promoted.iter().for_each(|tx| listener.pending(tx.hash(), None));
-        discarded.iter().for_each(|tx| listener.discarded(tx.hash()));
+        discarded.iter().for_each(|tx| listener.discarded(tx.hash(), DiscardReason::StateChange));
 
         // This deletes outdated blob txs from the blob store, based on the account's nonce. This is
         // called during txpool maintenance when the pool drifted.
@@ -456,12 +467,12 @@ where
             }
             TransactionValidationOutcome::Invalid(tx, err) => {
                 let mut listener = self.event_listener.write();
-                listener.discarded(tx.hash());
+                listener.discarded(tx.hash(), DiscardReason::Invalid);
                 Err(PoolError::new(*tx.hash(), err))
             }
             TransactionValidationOutcome::Error(tx_hash, err) => {
                 let mut listener = self.event_listener.write();
-                listener.discarded(&tx_hash);
+                listener.discarded(&tx_hash, DiscardReason::ValidationError);
                 Err(PoolError::other(tx_hash, err))
             }
         }
@@ -498,7 +509,7 @@ where
         }
 
         let mut listener = self.event_listener.write();
-        discarded.iter().for_each(|tx| listener.discarded(tx));
+        discarded.iter().for_each(|tx| listener.discarded(tx, DiscardReason::PoolLimits));
 
         // It may happen that a newly added transaction is immediately discarded, so we need to
         // adjust the result here
@@ -591,7 +602,7 @@ where
 
         mined.iter().for_each(|tx| listener.mined(tx, block_hash));
         promoted.iter().for_each(|tx| listener.pending(tx.hash(), None));
-        discarded.iter().for_each(|tx| listener.discarded(tx.hash()));
+        discarded.iter().for_each(|tx| listener.discarded(tx.hash(), DiscardReason::StateChange));
     }
 
     /// Fire events for the newly added transaction if there are any.
@@ -604,7 +615,9 @@ where
 
                 listener.pending(transaction.hash(), replaced.clone());
                 promoted.iter().for_each(|tx| listener.pending(tx.hash(), None));
-                discarded.iter().for_each(|tx| listener.discarded(tx.hash()));
+                discarded
+                    .iter()
+                    .for_each(|tx| listener.discarded(tx.hash(), DiscardReason::StateChange));
             }
             AddedTransaction::Parked { transaction, replaced, .. } => {
                 listener.queued(transaction.hash());
@@ -671,7 +684,7 @@ where
 
         let mut listener = self.event_listener.write();
 
-        removed.iter().for_each(|tx| listener.discarded(tx.hash()));
+        removed.iter().for_each(|tx| listener.discarded(tx.hash(), DiscardReason::Removed));
 
         removed
     }
@@ -1,4 +1,5 @@
 use crate::{
+    bundle::{BundleError, MevBundle},
     error::PoolResult,
     pool::{state::SubPool, TransactionEvents},
     validate::ValidPoolTransaction,
@@ -359,6 +360,29 @@ pub trait TransactionPool: Send + Sync + Clone {
         &self,
         tx_hashes: Vec<TxHash>,
     ) -> Result<Vec<BlobTransactionSidecar>, BlobStoreError>;
+
+    /// Adds a [MevBundle] to the bundle pool and returns its hash.
+    ///
+    /// If a bundle with the same replacement uuid is already tracked, it is replaced.
+    ///
+    /// Consumer: RPC
+    fn add_bundle(&self, bundle: MevBundle) -> Result<B256, BundleError>;
+
+    /// Removes the bundle with the given hash from the bundle pool.
+    ///
+    /// Consumer: RPC
+    fn remove_bundle(&self, bundle_hash: B256) -> Option<Arc<MevBundle>>;
+
+    /// Removes the bundle with the given replacement uuid from the bundle pool.
+    ///
+    /// Consumer: RPC
+    fn remove_bundle_by_replacement_uuid(&self, uuid: &str) -> Option<Arc<MevBundle>>;
+
+    /// Returns all bundles that can be included in a block with the given number and timestamp,
+    /// in the order they were received.
+    ///
+    /// Consumer: Block production
+    fn bundles_for_block(&self, block_number: u64, timestamp: u64) -> Vec<Arc<MevBundle>>;
 }
 
 /// Extension for [TransactionPool] trait that allows to set the current block info.
@@ -656,6 +680,24 @@ pub trait BestTransactions: Iterator + Send {
     fn set_skip_blobs(&mut self, skip_blobs: bool);
 }
 
+impl<T: BestTransactions + ?Sized> BestTransactions for Box<T> {
+    fn mark_invalid(&mut self, transaction: &Self::Item) {
+        (**self).mark_invalid(transaction)
+    }
+
+    fn no_updates(&mut self) {
+        (**self).no_updates()
+    }
+
+    fn skip_blobs(&mut self) {
+        (**self).skip_blobs()
+    }
+
+    fn set_skip_blobs(&mut self, skip_blobs: bool) {
+        (**self).set_skip_blobs(skip_blobs)
+    }
+}
+
 /// A no-op implementation that yields no transactions.
 impl<T> BestTransactions for std::iter::Empty<T> {
     fn mark_invalid(&mut self, _tx: &T) {}
@@ -525,10 +525,15 @@ impl EthTransactionValidatorBuilder {
     }
 
     /// Whether to allow exemptions for local transaction exemptions.
+    ///
+    /// This also applies the config's
+    /// [propagate_local_transactions](LocalTransactionConfig::propagate_local_transactions)
+    /// toggle, see also [Self::set_propagate_local_transactions].
     pub fn set_local_transactions_config(
         mut self,
         local_transactions_config: LocalTransactionConfig,
     ) -> Self {
+        self.propagate_local_transactions = local_transactions_config.propagate_local_transactions;
         self.local_transactions_config = local_transactions_config;
         self
     }
@@ -2,8 +2,8 @@ use assert_matches::assert_matches;
 use reth_transaction_pool::{
     noop::MockTransactionValidator,
     test_utils::{testing_pool, testing_pool_with_validator, MockTransactionFactory},
-    FullTransactionEvent, TransactionEvent, TransactionListenerKind, TransactionOrigin,
-    TransactionPool,
+    DiscardReason, FullTransactionEvent, GetPooledTransactionLimit, TransactionEvent,
+    TransactionListenerKind, TransactionOrigin, TransactionPool,
 };
 use std::{future::poll_fn, task::Poll};
 use tokio_stream::StreamExt;
@@ -41,6 +41,30 @@ async fn txpool_listener_all() {
     );
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn txpool_listener_discarded_reason() {
+    let txpool = testing_pool();
+    let mut mock_tx_factory = MockTransactionFactory::default();
+    let transaction = mock_tx_factory.create_eip1559();
+    let hash = *transaction.hash();
+
+    let mut all_tx_events = txpool.all_transactions_event_listener();
+    let mut events = txpool
+        .add_transaction_and_subscribe(TransactionOrigin::External, transaction.transaction.clone())
+        .await
+        .unwrap();
+    assert_matches!(events.next().await, Some(TransactionEvent::Pending));
+    assert_matches!(all_tx_events.next().await, Some(FullTransactionEvent::Pending(_)));
+
+    txpool.remove_transactions(vec![hash]);
+    assert_matches!(events.next().await, Some(TransactionEvent::Discarded(DiscardReason::Removed)));
+    assert_matches!(
+        all_tx_events.next().await,
+        Some(FullTransactionEvent::Discarded { tx_hash, reason: DiscardReason::Removed })
+            if tx_hash == hash
+    );
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn txpool_listener_propagate_only() {
     let txpool = testing_pool_with_validator(MockTransactionValidator::no_propagate_local());
@@ -87,3 +111,21 @@ async fn txpool_listener_new_propagate_only() {
     })
     .await;
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn txpool_no_propagate_local_not_served() {
+    let txpool = testing_pool_with_validator(MockTransactionValidator::no_propagate_local());
+    let mut mock_tx_factory = MockTransactionFactory::default();
+    let transaction = mock_tx_factory.create_eip1559();
+    let hash = *transaction.hash();
+    let result =
+        txpool.add_transaction(TransactionOrigin::Local, transaction.transaction.clone()).await;
+    assert!(result.is_ok());
+    assert!(txpool.contains(&hash));
+
+    // local transactions must not be handed out to peers
+    assert!(txpool.pooled_transaction_hashes().is_empty());
+    assert!(txpool
+        .get_pooled_transaction_elements(vec![hash], GetPooledTransactionLimit::None)
+        .is_empty());
+}
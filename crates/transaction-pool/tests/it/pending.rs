@@ -1,7 +1,10 @@
 use assert_matches::assert_matches;
+use reth_primitives::U256;
 use reth_transaction_pool::{
-    test_utils::{testing_pool, MockTransactionFactory},
-    TransactionOrigin, TransactionPool,
+    blobstore::InMemoryBlobStore,
+    noop::MockTransactionValidator,
+    test_utils::{testing_pool, MockTransaction, MockTransactionFactory},
+    FnOrdering, Pool, PoolTransaction, TransactionOrigin, TransactionPool,
 };
 
 #[tokio::test(flavor = "multi_thread")]
@@ -23,3 +26,26 @@ async fn txpool_new_pending_txs() {
     assert_matches!(added_result, Ok(hash) if hash == transaction.transaction.get_hash());
     assert_matches!(best_txns.next(), Some(tx) if tx.transaction.get_hash() == transaction.transaction.get_hash());
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn txpool_custom_ordering() {
+    // rank the transactions with the lowest max fee first, unlike the coinbase tip ordering
+    let ordering = FnOrdering::new(|tx: &MockTransaction, _| {
+        Some(U256::MAX - U256::from(tx.max_fee_per_gas()))
+    });
+    let txpool = Pool::new(
+        MockTransactionValidator::default(),
+        ordering,
+        InMemoryBlobStore::default(),
+        Default::default(),
+    );
+
+    let cheap = MockTransaction::eip1559().with_max_fee(1_000).with_priority_fee(1);
+    let expensive = MockTransaction::eip1559().with_max_fee(2_000).with_priority_fee(1_000);
+    for transaction in [expensive.clone(), cheap.clone()] {
+        txpool.add_transaction(TransactionOrigin::External, transaction).await.unwrap();
+    }
+
+    let best = txpool.best_transactions().map(|tx| *tx.hash()).collect::<Vec<_>>();
+    assert_eq!(best, vec![*cheap.hash(), *expensive.hash()]);
+}
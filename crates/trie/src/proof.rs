@@ -6,13 +6,14 @@ use crate::{
     walker::TrieWalker,
     StateRootError, StorageRootError,
 };
+use ahash::AHashMap;
 use alloy_rlp::{BufMut, Encodable};
 use reth_db::{tables, transaction::DbTx};
 use reth_primitives::{
     constants::EMPTY_ROOT_HASH,
     keccak256,
     trie::{AccountProof, HashBuilder, Nibbles, StorageProof, TrieAccount},
-    Address, B256,
+    Address, Bytes, B256,
 };
 
 /// A struct for generating merkle proofs.
@@ -26,12 +27,49 @@ pub struct Proof<'a, TX, H> {
     tx: &'a TX,
     /// The factory for hashed cursors.
     hashed_cursor_factory: H,
+    /// A set of account prefixes that have changed compared to the trie in the database.
+    changed_account_prefixes: PrefixSetMut,
+    /// A map containing storage changes with the hashed address as key and a set of storage key
+    /// prefixes as the value.
+    changed_storage_prefixes: AHashMap<B256, PrefixSetMut>,
 }
 
 impl<'a, TX> Proof<'a, TX, &'a TX> {
     /// Create a new [Proof] instance.
     pub fn new(tx: &'a TX) -> Self {
-        Self { tx, hashed_cursor_factory: tx }
+        Self {
+            tx,
+            hashed_cursor_factory: tx,
+            changed_account_prefixes: PrefixSetMut::default(),
+            changed_storage_prefixes: AHashMap::default(),
+        }
+    }
+}
+
+impl<'a, TX, H> Proof<'a, TX, H> {
+    /// Set the changed account prefixes.
+    ///
+    /// The trie nodes of these prefixes are not read from the database but recomputed from the
+    /// hashed state.
+    pub fn with_changed_account_prefixes(mut self, prefixes: PrefixSetMut) -> Self {
+        self.changed_account_prefixes = prefixes;
+        self
+    }
+
+    /// Set the changed storage prefixes.
+    pub fn with_changed_storage_prefixes(mut self, prefixes: AHashMap<B256, PrefixSetMut>) -> Self {
+        self.changed_storage_prefixes = prefixes;
+        self
+    }
+
+    /// Set the hashed cursor factory.
+    pub fn with_hashed_cursor_factory<HF>(self, hashed_cursor_factory: HF) -> Proof<'a, TX, HF> {
+        Proof {
+            tx: self.tx,
+            hashed_cursor_factory,
+            changed_account_prefixes: self.changed_account_prefixes,
+            changed_storage_prefixes: self.changed_storage_prefixes,
+        }
     }
 }
 
@@ -55,7 +93,7 @@ where
             DatabaseAccountTrieCursor::new(self.tx.cursor_read::<tables::AccountsTrie>()?);
 
         // Create the walker.
-        let mut prefix_set = PrefixSetMut::default();
+        let mut prefix_set = self.changed_account_prefixes.clone();
         prefix_set.insert(target_nibbles.clone());
         let walker = TrieWalker::new(trie_cursor, prefix_set.freeze());
 
@@ -119,12 +157,16 @@ where
         }
 
         let target_nibbles = proofs.iter().map(|p| p.nibbles.clone()).collect::<Vec<_>>();
-        let prefix_set = PrefixSetMut::from(target_nibbles.clone()).freeze();
+        let mut prefix_set =
+            self.changed_storage_prefixes.get(&hashed_address).cloned().unwrap_or_default();
+        for nibbles in &target_nibbles {
+            prefix_set.insert(nibbles.clone());
+        }
         let trie_cursor = DatabaseStorageTrieCursor::new(
             self.tx.cursor_dup_read::<tables::StoragesTrie>()?,
             hashed_address,
         );
-        let walker = TrieWalker::new(trie_cursor, prefix_set);
+        let walker = TrieWalker::new(trie_cursor, prefix_set.freeze());
 
         let mut hash_builder = HashBuilder::default().with_proof_retainer(target_nibbles);
         let mut storage_node_iter =
@@ -159,19 +201,104 @@ where
 
         Ok((root, proofs))
     }
+
+    /// Generate the proof nodes of the account trie for the given hashed addresses.
+    ///
+    /// The returned nodes are deduplicated and ordered by their path in the trie.
+    pub fn account_multiproof(&self, targets: &[B256]) -> Result<Vec<Bytes>, StateRootError> {
+        let target_nibbles = targets.iter().map(Nibbles::unpack).collect::<Vec<_>>();
+
+        let hashed_account_cursor = self.hashed_cursor_factory.hashed_account_cursor()?;
+        let trie_cursor =
+            DatabaseAccountTrieCursor::new(self.tx.cursor_read::<tables::AccountsTrie>()?);
+        let mut prefix_set = self.changed_account_prefixes.clone();
+        for nibbles in &target_nibbles {
+            prefix_set.insert(nibbles.clone());
+        }
+        let walker = TrieWalker::new(trie_cursor, prefix_set.freeze());
+
+        let mut hash_builder = HashBuilder::default().with_proof_retainer(target_nibbles);
+
+        let mut account_rlp = Vec::with_capacity(128);
+        let mut account_node_iter = AccountNodeIter::new(walker, hashed_account_cursor);
+        while let Some(account_node) = account_node_iter.try_next()? {
+            match account_node {
+                AccountNode::Branch(node) => {
+                    hash_builder.add_branch(node.key, node.value, node.children_are_in_trie);
+                }
+                AccountNode::Leaf(hashed_address, account) => {
+                    let storage_root = self.storage_root(hashed_address)?;
+
+                    account_rlp.clear();
+                    let account = TrieAccount::from((account, storage_root));
+                    account.encode(&mut account_rlp as &mut dyn BufMut);
+
+                    hash_builder.add_leaf(Nibbles::unpack(hashed_address), &account_rlp);
+                }
+            }
+        }
+
+        let _ = hash_builder.root();
+
+        Ok(hash_builder.take_proofs().into_values().collect())
+    }
+
+    /// Generate the proof nodes of the storage trie of the given account for the given hashed
+    /// slots.
+    ///
+    /// The returned nodes are deduplicated and ordered by their path in the trie.
+    pub fn storage_multiproof(
+        &self,
+        hashed_address: B256,
+        targets: &[B256],
+    ) -> Result<Vec<Bytes>, StorageRootError> {
+        let hashed_storage_cursor = self.hashed_cursor_factory.hashed_storage_cursor()?;
+        let target_nibbles = targets.iter().map(Nibbles::unpack).collect::<Vec<_>>();
+        let mut prefix_set =
+            self.changed_storage_prefixes.get(&hashed_address).cloned().unwrap_or_default();
+        for nibbles in &target_nibbles {
+            prefix_set.insert(nibbles.clone());
+        }
+        let trie_cursor = DatabaseStorageTrieCursor::new(
+            self.tx.cursor_dup_read::<tables::StoragesTrie>()?,
+            hashed_address,
+        );
+        let walker = TrieWalker::new(trie_cursor, prefix_set.freeze());
+
+        let mut hash_builder = HashBuilder::default().with_proof_retainer(target_nibbles);
+        let mut storage_node_iter =
+            StorageNodeIter::new(walker, hashed_storage_cursor, hashed_address);
+        while let Some(node) = storage_node_iter.try_next()? {
+            match node {
+                StorageNode::Branch(node) => {
+                    hash_builder.add_branch(node.key, node.value, node.children_are_in_trie);
+                }
+                StorageNode::Leaf(hashed_slot, value) => {
+                    hash_builder.add_leaf(
+                        Nibbles::unpack(hashed_slot),
+                        alloy_rlp::encode_fixed_size(&value).as_ref(),
+                    );
+                }
+            }
+        }
+
+        let _ = hash_builder.root();
+
+        Ok(hash_builder.take_proofs().into_values().collect())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::StateRoot;
+    use crate::{HashedPostState, StateRoot};
     use alloy_chains::Chain;
     use once_cell::sync::Lazy;
-    use reth_db::database::Database;
+    use reth_db::{database::Database, models::AccountBeforeTx, transaction::DbTxMut};
     use reth_interfaces::RethResult;
     use reth_primitives::{Account, Bytes, ChainSpec, StorageEntry, HOLESKY, MAINNET, U256};
     use reth_provider::{test_utils::create_test_provider_factory, HashingWriter, ProviderFactory};
-    use std::{str::FromStr, sync::Arc};
+    use std::{collections::BTreeSet, str::FromStr, sync::Arc};
 
     /*
         World State (sampled from <https://ethereum.stackexchange.com/questions/268/ethereum-block-architecture/6413#6413>)
@@ -290,6 +417,69 @@ mod tests {
         }
     }
 
+    #[test]
+    fn testspec_reverted_state_proofs() {
+        let factory = create_test_provider_factory();
+        insert_genesis(&factory, TEST_SPEC.clone()).unwrap();
+
+        let target = Address::from_str("0x1ed9b1dd266b607ee278726d324b855a093394a6").unwrap();
+        let genesis_account: Account = TEST_SPEC.genesis().alloc[&target].clone().into();
+
+        let provider = factory.provider().unwrap();
+        let expected = Proof::new(provider.tx_ref()).account_proof(target, &[]).unwrap();
+        drop(provider);
+
+        // change the balance of the target in block 1 and rebuild the trie
+        let mut provider = factory.provider_rw().unwrap();
+        let changed_account = Account { balance: U256::from(1), ..genesis_account };
+        provider.insert_account_for_hashing([(target, Some(changed_account))]).unwrap();
+        provider
+            .tx_ref()
+            .put::<tables::AccountChangeSet>(
+                1,
+                AccountBeforeTx { address: target, info: Some(genesis_account) },
+            )
+            .unwrap();
+        provider.tx_ref().clear::<tables::AccountsTrie>().unwrap();
+        let (_, updates) = StateRoot::from_tx(provider.tx_ref()).root_with_updates().unwrap();
+        updates.flush(provider.tx_mut()).unwrap();
+        provider.commit().unwrap();
+
+        let provider = factory.provider().unwrap();
+        let latest = Proof::new(provider.tx_ref()).account_proof(target, &[]).unwrap();
+        assert_ne!(latest.proof, expected.proof);
+
+        let reverted = HashedPostState::from_revert_range(provider.tx_ref(), 1..=1)
+            .unwrap()
+            .account_proof(provider.tx_ref(), target, &[])
+            .unwrap();
+        pretty_assertions::assert_eq!(reverted, expected);
+    }
+
+    #[test]
+    fn testspec_multiproof_matches_account_proofs() {
+        // Create test database and insert genesis accounts.
+        let factory = create_test_provider_factory();
+        insert_genesis(&factory, TEST_SPEC.clone()).unwrap();
+
+        let provider = factory.provider().unwrap();
+        let proof = Proof::new(provider.tx_ref());
+        let targets = [
+            Address::from_str("0x2031f89b3ea8014eb51a78c316e42af3e0d7695f").unwrap(),
+            Address::from_str("0x33f0fc440b8477fcfbe9d0bf8649e7dea9baedb2").unwrap(),
+        ];
+
+        let mut expected = BTreeSet::new();
+        for target in targets {
+            expected.extend(proof.account_proof(target, &[]).unwrap().proof);
+        }
+
+        let hashed_targets = targets.map(keccak256);
+        let multiproof = proof.account_multiproof(&hashed_targets).unwrap();
+        assert_eq!(multiproof.len(), expected.len());
+        assert_eq!(multiproof.into_iter().collect::<BTreeSet<_>>(), expected);
+    }
+
     #[test]
     fn testspec_empty_storage_proof() {
         // Create test database and insert genesis accounts.
@@ -1,15 +1,26 @@
 use crate::{
     hashed_cursor::HashedPostStateCursorFactory,
     prefix_set::{PrefixSet, PrefixSetMut},
+    proof::Proof,
     updates::TrieUpdates,
     StateRoot, StateRootError,
 };
 use ahash::{AHashMap, AHashSet};
-use reth_db::transaction::DbTx;
+use reth_db::{
+    cursor::DbCursorRO,
+    models::{AccountBeforeTx, BlockNumberAddress},
+    tables,
+    transaction::DbTx,
+    DatabaseError,
+};
 use reth_primitives::{
-    keccak256, revm::compat::into_reth_acc, trie::Nibbles, Account, Address, B256, U256,
+    keccak256,
+    revm::compat::into_reth_acc,
+    trie::{AccountProof, Nibbles},
+    Account, Address, BlockNumber, StorageEntry, B256, U256,
 };
 use revm::db::BundleAccount;
+use std::{collections::hash_map, ops::RangeInclusive};
 
 /// The post state with hashed addresses as keys.
 #[derive(Debug, Clone, Eq, PartialEq)]
@@ -64,6 +75,54 @@ impl HashedPostState {
         hashed_state.sorted()
     }
 
+    /// Initialize [HashedPostState] from the account and storage changesets of the given block
+    /// range.
+    ///
+    /// The resulting state contains the values of all accounts and storage slots that were changed
+    /// within the range as they were _before_ the first block of the range. Applied on top of the
+    /// current hashed state, this reverts the state to the beginning of the range.
+    pub fn from_revert_range<TX: DbTx>(
+        tx: &TX,
+        range: RangeInclusive<BlockNumber>,
+    ) -> Result<Self, DatabaseError> {
+        // Only the first change of every account and slot is relevant, it contains the value
+        // before the range.
+        let mut accounts = AHashMap::<Address, Option<Account>>::default();
+        let mut account_changeset_cursor = tx.cursor_read::<tables::AccountChangeSet>()?;
+        for entry in account_changeset_cursor.walk_range(range.clone())? {
+            let (_, AccountBeforeTx { address, info }) = entry?;
+            accounts.entry(address).or_insert(info);
+        }
+
+        let mut storages = AHashMap::<Address, AHashMap<B256, U256>>::default();
+        let mut storage_changeset_cursor = tx.cursor_dup_read::<tables::StorageChangeSet>()?;
+        for entry in storage_changeset_cursor.walk_range(BlockNumberAddress::range(range))? {
+            let (BlockNumberAddress((_, address)), StorageEntry { key, value }) = entry?;
+            if let hash_map::Entry::Vacant(entry) = storages.entry(address).or_default().entry(key)
+            {
+                entry.insert(value);
+            }
+        }
+
+        let mut hashed_state = Self::default();
+        for (address, account) in accounts {
+            let hashed_address = keccak256(address);
+            match account {
+                Some(account) => hashed_state.insert_account(hashed_address, account),
+                None => hashed_state.insert_destroyed_account(hashed_address),
+            }
+        }
+        for (address, storage) in storages {
+            let mut hashed_storage = HashedStorage::new(false);
+            for (slot, value) in storage {
+                hashed_storage.insert_storage(keccak256(slot), value);
+            }
+            hashed_state.insert_hashed_storage(keccak256(address), hashed_storage);
+        }
+
+        Ok(hashed_state.sorted())
+    }
+
     /// Sort and return self.
     pub fn sorted(mut self) -> Self {
         self.sort();
@@ -120,6 +179,15 @@ impl HashedPostState {
     /// The prefix sets contain the hashed account and storage keys that have been changed in the
     /// post state.
     pub fn construct_prefix_sets(&self) -> (PrefixSet, AHashMap<B256, PrefixSet>) {
+        let (account_prefix_set, storage_prefix_set) = self.construct_prefix_sets_mut();
+        (
+            account_prefix_set.freeze(),
+            storage_prefix_set.into_iter().map(|(k, v)| (k, v.freeze())).collect(),
+        )
+    }
+
+    /// Same as [Self::construct_prefix_sets] but returns the unfrozen prefix sets.
+    fn construct_prefix_sets_mut(&self) -> (PrefixSetMut, AHashMap<B256, PrefixSetMut>) {
         // Initialize prefix sets.
         let mut account_prefix_set = PrefixSetMut::default();
         let mut storage_prefix_set: AHashMap<B256, PrefixSetMut> = AHashMap::default();
@@ -145,10 +213,7 @@ impl HashedPostState {
             }
         }
 
-        (
-            account_prefix_set.freeze(),
-            storage_prefix_set.into_iter().map(|(k, v)| (k, v.freeze())).collect(),
-        )
+        (account_prefix_set, storage_prefix_set)
     }
 
     /// Returns [StateRoot] calculator based on database and in-memory state.
@@ -207,6 +272,22 @@ impl HashedPostState {
     ) -> Result<(B256, TrieUpdates), StateRootError> {
         self.state_root_calculator(tx).root_with_updates()
     }
+
+    /// Generates the account and storage proofs for the state of the database with this
+    /// [HashedPostState] applied on top.
+    pub fn account_proof<TX: DbTx>(
+        &self,
+        tx: &TX,
+        address: Address,
+        slots: &[B256],
+    ) -> Result<AccountProof, StateRootError> {
+        let (account_prefix_set, storage_prefix_set) = self.construct_prefix_sets_mut();
+        Proof::new(tx)
+            .with_hashed_cursor_factory(HashedPostStateCursorFactory::new(tx, self))
+            .with_changed_account_prefixes(account_prefix_set)
+            .with_changed_storage_prefixes(storage_prefix_set)
+            .account_proof(address, slots)
+    }
 }
 
 /// The post state account storage with hashed slots.
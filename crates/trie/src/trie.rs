@@ -10,6 +10,7 @@ use crate::{
 };
 use ahash::{AHashMap, AHashSet};
 use alloy_rlp::{BufMut, Encodable};
+use rayon::prelude::*;
 use reth_db::transaction::DbTx;
 use reth_primitives::{
     constants::EMPTY_ROOT_HASH,
@@ -20,6 +21,10 @@ use reth_primitives::{
 use std::ops::RangeInclusive;
 use tracing::{debug, trace};
 
+/// The number of account leaves whose storage roots are computed together when storage roots are
+/// computed in parallel.
+const PARALLEL_STORAGE_ROOTS_BATCH_SIZE: usize = 1_000;
+
 /// StateRoot is used to compute the root node of a state trie.
 #[derive(Debug)]
 pub struct StateRoot<T, H> {
@@ -38,6 +43,8 @@ pub struct StateRoot<T, H> {
     previous_state: Option<IntermediateStateRootState>,
     /// The number of updates after which the intermediate progress should be returned.
     threshold: u64,
+    /// Whether the storage roots of different accounts should be computed in parallel.
+    parallel_storage_roots: bool,
 }
 
 impl<T, H> StateRoot<T, H> {
@@ -77,6 +84,12 @@ impl<T, H> StateRoot<T, H> {
         self
     }
 
+    /// Compute the storage roots of different accounts in parallel.
+    pub fn with_parallel_storage_roots(mut self, parallel: bool) -> Self {
+        self.parallel_storage_roots = parallel;
+        self
+    }
+
     /// Set the hashed cursor factory.
     pub fn with_hashed_cursor_factory<HF>(self, hashed_cursor_factory: HF) -> StateRoot<T, HF> {
         StateRoot {
@@ -87,6 +100,7 @@ impl<T, H> StateRoot<T, H> {
             destroyed_accounts: self.destroyed_accounts,
             threshold: self.threshold,
             previous_state: self.previous_state,
+            parallel_storage_roots: self.parallel_storage_roots,
         }
     }
 
@@ -100,6 +114,7 @@ impl<T, H> StateRoot<T, H> {
             destroyed_accounts: self.destroyed_accounts,
             threshold: self.threshold,
             previous_state: self.previous_state,
+            parallel_storage_roots: self.parallel_storage_roots,
         }
     }
 }
@@ -115,6 +130,7 @@ impl<'a, TX: DbTx> StateRoot<&'a TX, &'a TX> {
             destroyed_accounts: AHashSet::default(),
             previous_state: None,
             threshold: 100_000,
+            parallel_storage_roots: false,
         }
     }
 
@@ -188,8 +204,8 @@ impl<'a, TX: DbTx> StateRoot<&'a TX, &'a TX> {
 
 impl<T, H> StateRoot<T, H>
 where
-    T: TrieCursorFactory + Clone,
-    H: HashedCursorFactory + Clone,
+    T: TrieCursorFactory + Clone + Send + Sync,
+    H: HashedCursorFactory + Clone + Send + Sync,
 {
     /// Walks the intermediate nodes of existing state trie (if any) and hashed entries. Feeds the
     /// nodes into the hash builder. Collects the updates in the process.
@@ -258,77 +274,111 @@ where
         account_node_iter.walker.set_updates(retain_updates);
         hash_builder.set_updates(retain_updates);
 
+        // Computes the storage root of an account along with the number of walked storage slots
+        // and the storage trie updates.
+        let storage_root = |hashed_address: B256| {
+            StorageRoot::new_hashed(
+                self.trie_cursor_factory.clone(),
+                self.hashed_cursor_factory.clone(),
+                hashed_address,
+            )
+            .with_changed_prefixes(
+                self.changed_storage_prefixes.get(&hashed_address).cloned().unwrap_or_default(),
+            )
+            .calculate(retain_updates)
+        };
+
+        // Account leaves are buffered together with the branch nodes in between them, so that
+        // the storage roots of a batch can be computed in parallel before the nodes are fed into
+        // the hash builder in their original order.
+        let batch_size =
+            if self.parallel_storage_roots { PARALLEL_STORAGE_ROOTS_BATCH_SIZE } else { 1 };
+        let mut pending_nodes = Vec::with_capacity(batch_size);
+        let mut pending_leaves = Vec::with_capacity(batch_size);
+
         let mut account_rlp = Vec::with_capacity(128);
         let mut hashed_entries_walked = 0;
-        while let Some(node) = account_node_iter.try_next()? {
-            match node {
-                AccountNode::Branch(node) => {
-                    hash_builder.add_branch(node.key, node.value, node.children_are_in_trie);
+        loop {
+            let next = account_node_iter.try_next()?;
+            let done = next.is_none();
+            if let Some(node) = next {
+                if let AccountNode::Leaf(hashed_address, _) = &node {
+                    pending_leaves.push(*hashed_address);
                 }
-                AccountNode::Leaf(hashed_address, account) => {
-                    hashed_entries_walked += 1;
-
-                    // We assume we can always calculate a storage root without
-                    // OOMing. This opens us up to a potential DOS vector if
-                    // a contract had too many storage entries and they were
-                    // all buffered w/o us returning and committing our intermediate
-                    // progress.
-                    // TODO: We can consider introducing the TrieProgress::Progress/Complete
-                    // abstraction inside StorageRoot, but let's give it a try as-is for now.
-                    let storage_root_calculator = StorageRoot::new_hashed(
-                        self.trie_cursor_factory.clone(),
-                        self.hashed_cursor_factory.clone(),
-                        hashed_address,
-                    )
-                    .with_changed_prefixes(
-                        self.changed_storage_prefixes
-                            .get(&hashed_address)
-                            .cloned()
-                            .unwrap_or_default(),
-                    );
-
-                    let storage_root = if retain_updates {
-                        let (root, storage_slots_walked, updates) =
-                            storage_root_calculator.root_with_updates()?;
-                        hashed_entries_walked += storage_slots_walked;
-                        trie_updates.extend(updates.into_iter());
-                        root
-                    } else {
-                        storage_root_calculator.root()?
-                    };
-
-                    let account = TrieAccount::from((account, storage_root));
-
-                    account_rlp.clear();
-                    account.encode(&mut account_rlp as &mut dyn BufMut);
-
-                    hash_builder.add_leaf(Nibbles::unpack(hashed_address), &account_rlp);
+                pending_nodes.push(node);
+                if pending_leaves.len() < batch_size {
+                    continue
+                }
+            }
 
-                    // Decide if we need to return intermediate progress.
-                    let total_updates_len = trie_updates.len() +
-                        account_node_iter.walker.updates_len() +
-                        hash_builder.updates_len();
-                    if retain_updates && total_updates_len as u64 >= self.threshold {
-                        let (walker_stack, walker_updates) = account_node_iter.walker.split();
-                        let (hash_builder, hash_builder_updates) = hash_builder.split();
+            // We assume we can always calculate a storage root without
+            // OOMing. This opens us up to a potential DOS vector if
+            // a contract had too many storage entries and they were
+            // all buffered w/o us returning and committing our intermediate
+            // progress.
+            // TODO: We can consider introducing the TrieProgress::Progress/Complete
+            // abstraction inside StorageRoot, but let's give it a try as-is for now.
+            let leaves = std::mem::take(&mut pending_leaves);
+            let storage_roots = if self.parallel_storage_roots {
+                leaves.into_par_iter().map(&storage_root).collect::<Result<Vec<_>, _>>()?
+            } else {
+                leaves.into_iter().map(&storage_root).collect::<Result<Vec<_>, _>>()?
+            };
+
+            let mut storage_roots = storage_roots.into_iter();
+            let mut last_account_key = None;
+            for node in pending_nodes.drain(..) {
+                match node {
+                    AccountNode::Branch(node) => {
+                        hash_builder.add_branch(node.key, node.value, node.children_are_in_trie);
+                    }
+                    AccountNode::Leaf(hashed_address, account) => {
+                        hashed_entries_walked += 1;
+
+                        let (storage_root, storage_slots_walked, updates) =
+                            storage_roots.next().expect("storage root for every leaf");
+                        if retain_updates {
+                            hashed_entries_walked += storage_slots_walked;
+                            trie_updates.extend(updates.into_iter());
+                        }
 
-                        let state = IntermediateStateRootState {
-                            hash_builder,
-                            walker_stack,
-                            last_account_key: hashed_address,
-                        };
+                        let account = TrieAccount::from((account, storage_root));
 
-                        trie_updates.extend(walker_updates.into_iter());
-                        trie_updates.extend_with_account_updates(hash_builder_updates);
+                        account_rlp.clear();
+                        account.encode(&mut account_rlp as &mut dyn BufMut);
 
-                        return Ok(StateRootProgress::Progress(
-                            Box::new(state),
-                            hashed_entries_walked,
-                            trie_updates,
-                        ))
+                        hash_builder.add_leaf(Nibbles::unpack(hashed_address), &account_rlp);
+                        last_account_key = Some(hashed_address);
                     }
                 }
             }
+
+            if done {
+                break
+            }
+
+            // Decide if we need to return intermediate progress. A batch is only flushed right
+            // after an account leaf, so the walker is positioned at the last account key.
+            let Some(last_account_key) = last_account_key else { continue };
+            let total_updates_len = trie_updates.len() +
+                account_node_iter.walker.updates_len() +
+                hash_builder.updates_len();
+            if retain_updates && total_updates_len as u64 >= self.threshold {
+                let (walker_stack, walker_updates) = account_node_iter.walker.split();
+                let (hash_builder, hash_builder_updates) = hash_builder.split();
+
+                let state =
+                    IntermediateStateRootState { hash_builder, walker_stack, last_account_key };
+
+                trie_updates.extend(walker_updates.into_iter());
+                trie_updates.extend_with_account_updates(hash_builder_updates);
+
+                return Ok(StateRootProgress::Progress(
+                    Box::new(state),
+                    hashed_entries_walked,
+                    trie_updates,
+                ))
+            }
         }
 
         let root = hash_builder.root();
@@ -767,6 +817,33 @@ mod tests {
         );
     }
 
+    #[test]
+    fn arbitrary_parallel_state_root() {
+        proptest!(
+            ProptestConfig::with_cases(10), | (state: State) | {
+                let factory = create_test_provider_factory();
+                let tx = factory.provider_rw().unwrap();
+
+                for (address, (account, storage)) in &state {
+                    insert_account(tx.tx_ref(), *address, *account, storage)
+                }
+                tx.commit().unwrap();
+                let expected = state_root(state.into_iter());
+
+                let tx = factory.provider_rw().unwrap();
+                let (sequential_root, sequential_updates) =
+                    StateRoot::from_tx(tx.tx_ref()).root_with_updates().unwrap();
+                let (parallel_root, parallel_updates) = StateRoot::from_tx(tx.tx_ref())
+                    .with_parallel_storage_roots(true)
+                    .root_with_updates()
+                    .unwrap();
+                assert_eq!(expected, sequential_root);
+                assert_eq!(expected, parallel_root);
+                assert_eq!(sequential_updates, parallel_updates);
+            }
+        );
+    }
+
     fn test_state_root_with_state(state: State) {
         let factory = create_test_provider_factory();
         let tx = factory.provider_rw().unwrap();
@@ -0,0 +1,436 @@
+use super::{TrieCursor, TrieCursorFactory};
+use crate::{prefix_set::PrefixSet, updates::TrieKey};
+use ahash::{AHashMap, AHashSet};
+use parking_lot::Mutex;
+use reth_db::DatabaseError;
+use reth_primitives::{
+    trie::{BranchNodeCompact, Nibbles, StoredNibbles, StoredNibblesSubKey},
+    B256,
+};
+use schnellru::{ByLength, LruMap};
+use std::{fmt, sync::Arc};
+
+/// The default maximum number of entries held by the [TrieNodeCache].
+pub const DEFAULT_TRIE_NODE_CACHE_SIZE: u32 = 100_000;
+
+/// An in-memory LRU cache of intermediate trie nodes keyed by their path.
+///
+/// The cache is meant to be shared between consecutive state root computations: most of the trie
+/// does not change from one block to the next, so the nodes read during the previous computation
+/// can be reused instead of reading them from the database again. Paths that are known to have no
+/// node are cached as well.
+///
+/// The cache does not observe writes to the trie tables. Once trie updates are written to the
+/// database, the nodes on the paths of the changed keys must be evicted with
+/// [TrieNodeCache::invalidate], using the same prefix sets the updates were computed with.
+///
+/// The cached nodes are tagged with the state root of the trie they were read from, see
+/// [TrieNodeCache::state_root]. Since the trie is uniquely determined by its root, the cache can be
+/// reused whenever the trie tables are known to hold the trie of that root, regardless of which
+/// writers touched the tables in between.
+#[derive(Clone)]
+pub struct TrieNodeCache {
+    inner: Arc<Mutex<TrieNodeCacheInner>>,
+}
+
+struct TrieNodeCacheInner {
+    /// The cached nodes, `None` if there is no node at the path.
+    nodes: LruMap<TrieKey, Option<BranchNodeCompact>>,
+    /// The state root of the trie the cached nodes belong to.
+    state_root: Option<B256>,
+}
+
+impl TrieNodeCache {
+    /// Creates a new cache holding at most `max_entries` entries.
+    pub fn new(max_entries: u32) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(TrieNodeCacheInner {
+                nodes: LruMap::new(ByLength::new(max_entries)),
+                state_root: None,
+            })),
+        }
+    }
+
+    /// Returns the number of cached entries.
+    pub fn len(&self) -> usize {
+        self.inner.lock().nodes.len()
+    }
+
+    /// Returns `true` if the cache is empty.
+    pub fn is_empty(&self) -> bool {
+        self.inner.lock().nodes.is_empty()
+    }
+
+    /// Returns the state root of the trie the cached nodes belong to, if any.
+    pub fn state_root(&self) -> Option<B256> {
+        self.inner.lock().state_root
+    }
+
+    /// Sets the state root of the trie the cached nodes belong to.
+    pub fn set_state_root(&self, state_root: B256) {
+        self.inner.lock().state_root = Some(state_root);
+    }
+
+    /// Clears the cache if the cached nodes do not belong to the trie with the given root.
+    pub fn retain_if_state_root(&self, state_root: B256) {
+        let mut inner = self.inner.lock();
+        if inner.state_root != Some(state_root) {
+            inner.nodes.clear();
+            inner.state_root = None;
+        }
+    }
+
+    /// Removes all entries from the cache.
+    pub fn clear(&self) {
+        let mut inner = self.inner.lock();
+        inner.nodes.clear();
+        inner.state_root = None;
+    }
+
+    /// Returns the cached entry for the given path.
+    ///
+    /// `Some(None)` means that there is no node at the path.
+    pub fn get(&self, key: &TrieKey) -> Option<Option<BranchNodeCompact>> {
+        self.inner.lock().nodes.get(key).cloned()
+    }
+
+    /// Caches the node at the given path.
+    pub fn insert(&self, key: TrieKey, node: Option<BranchNodeCompact>) {
+        self.inner.lock().nodes.insert(key, node);
+    }
+
+    /// Evicts all nodes that may have been changed by trie updates computed with the given prefix
+    /// sets.
+    ///
+    /// A node may change if its path is a prefix of one of the changed keys, see
+    /// [PrefixSet::contains]. The storage tries of destroyed accounts are evicted entirely.
+    pub fn invalidate(
+        &self,
+        account_prefixes: &PrefixSet,
+        storage_prefixes: &AHashMap<B256, PrefixSet>,
+        destroyed_accounts: &AHashSet<B256>,
+    ) {
+        let mut inner = self.inner.lock();
+
+        for key in account_prefixes.iter() {
+            for len in 0..=key.len() {
+                let path = Nibbles::from_nibbles_unchecked(&key.as_slice()[..len]);
+                inner.nodes.remove(&TrieKey::AccountNode(StoredNibbles(path)));
+            }
+        }
+
+        for (hashed_address, prefixes) in storage_prefixes {
+            for key in prefixes.iter() {
+                for len in 0..=key.len() {
+                    let path = Nibbles::from_nibbles_unchecked(&key.as_slice()[..len]);
+                    inner
+                        .nodes
+                        .remove(&TrieKey::StorageNode(*hashed_address, StoredNibblesSubKey(path)));
+                }
+            }
+        }
+
+        if !destroyed_accounts.is_empty() {
+            let destroyed = inner
+                .nodes
+                .iter()
+                .filter_map(|(key, _)| match key {
+                    TrieKey::StorageNode(hashed_address, _)
+                        if destroyed_accounts.contains(hashed_address) =>
+                    {
+                        Some(key.clone())
+                    }
+                    _ => None,
+                })
+                .collect::<Vec<_>>();
+            for key in destroyed {
+                inner.nodes.remove(&key);
+            }
+        }
+    }
+}
+
+impl Default for TrieNodeCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_TRIE_NODE_CACHE_SIZE)
+    }
+}
+
+impl fmt::Debug for TrieNodeCache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let inner = self.inner.lock();
+        f.debug_struct("TrieNodeCache")
+            .field("len", &inner.nodes.len())
+            .field("state_root", &inner.state_root)
+            .finish()
+    }
+}
+
+/// A trie cursor factory that serves the trie nodes from a [TrieNodeCache] and falls back to the
+/// underlying factory on cache misses.
+#[derive(Debug, Clone)]
+pub struct CachedTrieCursorFactory<F> {
+    /// The underlying cursor factory.
+    factory: F,
+    /// The shared node cache.
+    cache: TrieNodeCache,
+}
+
+impl<F> CachedTrieCursorFactory<F> {
+    /// Create a new cached trie cursor factory.
+    pub fn new(factory: F, cache: TrieNodeCache) -> Self {
+        Self { factory, cache }
+    }
+}
+
+impl<F: TrieCursorFactory> TrieCursorFactory for CachedTrieCursorFactory<F> {
+    fn account_trie_cursor(
+        &self,
+    ) -> Result<Box<dyn TrieCursor<Key = StoredNibbles> + '_>, DatabaseError> {
+        Ok(Box::new(CachedAccountTrieCursor::new(
+            self.factory.account_trie_cursor()?,
+            self.cache.clone(),
+        )))
+    }
+
+    fn storage_tries_cursor(
+        &self,
+        hashed_address: B256,
+    ) -> Result<Box<dyn TrieCursor<Key = StoredNibblesSubKey> + '_>, DatabaseError> {
+        Ok(Box::new(CachedStorageTrieCursor::new(
+            self.factory.storage_tries_cursor(hashed_address)?,
+            self.cache.clone(),
+            hashed_address,
+        )))
+    }
+}
+
+/// An account trie cursor backed by a [TrieNodeCache].
+#[derive(Debug)]
+pub struct CachedAccountTrieCursor<C> {
+    /// The underlying cursor.
+    cursor: C,
+    /// The shared node cache.
+    cache: TrieNodeCache,
+    /// The key of the last returned node.
+    current: Option<TrieKey>,
+}
+
+impl<C> CachedAccountTrieCursor<C> {
+    /// Create a new cached account trie cursor.
+    pub fn new(cursor: C, cache: TrieNodeCache) -> Self {
+        Self { cursor, cache, current: None }
+    }
+}
+
+impl<C> TrieCursor for CachedAccountTrieCursor<C>
+where
+    C: TrieCursor<Key = StoredNibbles>,
+{
+    type Key = StoredNibbles;
+
+    fn seek_exact(
+        &mut self,
+        key: Self::Key,
+    ) -> Result<Option<(Vec<u8>, BranchNodeCompact)>, DatabaseError> {
+        let cache_key = TrieKey::AccountNode(key.clone());
+        let entry = match self.cache.get(&cache_key) {
+            Some(node) => node.map(|node| (key.0.to_vec(), node)),
+            None => {
+                let entry = self.cursor.seek_exact(key)?;
+                self.cache.insert(cache_key, entry.as_ref().map(|(_, node)| node.clone()));
+                entry
+            }
+        };
+        self.current = entry.as_ref().map(|(path, _)| TrieKey::AccountNode(path.clone().into()));
+        Ok(entry)
+    }
+
+    fn seek(
+        &mut self,
+        key: Self::Key,
+    ) -> Result<Option<(Vec<u8>, BranchNodeCompact)>, DatabaseError> {
+        // Only exact matches can be served from the cache, the next node after a missing path is
+        // always looked up in the database.
+        let cache_key = TrieKey::AccountNode(key.clone());
+        let entry = match self.cache.get(&cache_key) {
+            Some(Some(node)) => Some((key.0.to_vec(), node)),
+            _ => {
+                let entry = self.cursor.seek(key)?;
+                if let Some((path, node)) = &entry {
+                    self.cache
+                        .insert(TrieKey::AccountNode(path.clone().into()), Some(node.clone()));
+                }
+                entry
+            }
+        };
+        self.current = entry.as_ref().map(|(path, _)| TrieKey::AccountNode(path.clone().into()));
+        Ok(entry)
+    }
+
+    fn current(&mut self) -> Result<Option<TrieKey>, DatabaseError> {
+        Ok(self.current.clone())
+    }
+}
+
+/// A storage trie cursor backed by a [TrieNodeCache].
+#[derive(Debug)]
+pub struct CachedStorageTrieCursor<C> {
+    /// The underlying cursor.
+    cursor: C,
+    /// The shared node cache.
+    cache: TrieNodeCache,
+    /// Hashed address of the storage trie.
+    hashed_address: B256,
+    /// The key of the last returned node.
+    current: Option<TrieKey>,
+}
+
+impl<C> CachedStorageTrieCursor<C> {
+    /// Create a new cached storage trie cursor.
+    pub fn new(cursor: C, cache: TrieNodeCache, hashed_address: B256) -> Self {
+        Self { cursor, cache, hashed_address, current: None }
+    }
+
+    fn trie_key(&self, path: Vec<u8>) -> TrieKey {
+        TrieKey::StorageNode(self.hashed_address, path.into())
+    }
+}
+
+impl<C> TrieCursor for CachedStorageTrieCursor<C>
+where
+    C: TrieCursor<Key = StoredNibblesSubKey>,
+{
+    type Key = StoredNibblesSubKey;
+
+    fn seek_exact(
+        &mut self,
+        key: Self::Key,
+    ) -> Result<Option<(Vec<u8>, BranchNodeCompact)>, DatabaseError> {
+        let cache_key = self.trie_key(key.0.to_vec());
+        let entry = match self.cache.get(&cache_key) {
+            Some(node) => node.map(|node| (key.0.to_vec(), node)),
+            None => {
+                let entry = self.cursor.seek_exact(key)?;
+                self.cache.insert(cache_key, entry.as_ref().map(|(_, node)| node.clone()));
+                entry
+            }
+        };
+        self.current = entry.as_ref().map(|(path, _)| self.trie_key(path.clone()));
+        Ok(entry)
+    }
+
+    fn seek(
+        &mut self,
+        key: Self::Key,
+    ) -> Result<Option<(Vec<u8>, BranchNodeCompact)>, DatabaseError> {
+        // Only exact matches can be served from the cache, the next node after a missing path is
+        // always looked up in the database.
+        let cache_key = self.trie_key(key.0.to_vec());
+        let entry = match self.cache.get(&cache_key) {
+            Some(Some(node)) => Some((key.0.to_vec(), node)),
+            _ => {
+                let entry = self.cursor.seek(key)?;
+                if let Some((path, node)) = &entry {
+                    self.cache.insert(self.trie_key(path.clone()), Some(node.clone()));
+                }
+                entry
+            }
+        };
+        self.current = entry.as_ref().map(|(path, _)| self.trie_key(path.clone()));
+        Ok(entry)
+    }
+
+    fn current(&mut self) -> Result<Option<TrieKey>, DatabaseError> {
+        Ok(self.current.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{prefix_set::PrefixSetMut, trie_cursor::DatabaseAccountTrieCursor};
+    use reth_db::{
+        cursor::DbCursorRW,
+        tables,
+        transaction::{DbTx, DbTxMut},
+    };
+    use reth_primitives::{hex_literal::hex, trie::StoredBranchNode};
+    use reth_provider::test_utils::create_test_provider_factory;
+
+    fn branch_node(hash: B256) -> BranchNodeCompact {
+        BranchNodeCompact::new(0b11, 0, 0b11, vec![hash, hash], None)
+    }
+
+    #[test]
+    fn serves_account_nodes_from_cache() {
+        let factory = create_test_provider_factory();
+        let provider = factory.provider_rw().unwrap();
+        let tx = provider.tx_ref();
+
+        let path = hex!("0102").to_vec();
+        let node = branch_node(B256::with_last_byte(1));
+        tx.cursor_write::<tables::AccountsTrie>()
+            .unwrap()
+            .upsert(path.clone().into(), StoredBranchNode(node.clone()))
+            .unwrap();
+
+        let cache = TrieNodeCache::default();
+        let mut cursor = CachedAccountTrieCursor::new(
+            DatabaseAccountTrieCursor::new(tx.cursor_read::<tables::AccountsTrie>().unwrap()),
+            cache.clone(),
+        );
+        assert_eq!(cursor.seek(path.clone().into()).unwrap(), Some((path.clone(), node.clone())));
+        assert_eq!(cursor.seek_exact(hex!("03").to_vec().into()).unwrap(), None);
+        assert_eq!(cache.len(), 2);
+
+        // Change the node in the database, the stale node is still served from the cache.
+        let updated = branch_node(B256::with_last_byte(2));
+        tx.cursor_write::<tables::AccountsTrie>()
+            .unwrap()
+            .upsert(path.clone().into(), StoredBranchNode(updated.clone()))
+            .unwrap();
+        assert_eq!(cursor.seek_exact(path.clone().into()).unwrap(), Some((path.clone(), node)));
+        assert_eq!(cursor.current().unwrap(), Some(TrieKey::AccountNode(path.clone().into())));
+
+        // Invalidating with a changed key below the node evicts it.
+        let mut prefixes = PrefixSetMut::default();
+        prefixes.insert(Nibbles::from_nibbles_unchecked(hex!("010203")));
+        cache.invalidate(&prefixes.freeze(), &AHashMap::default(), &AHashSet::default());
+        assert_eq!(cache.len(), 1);
+        assert_eq!(cursor.seek_exact(path.clone().into()).unwrap(), Some((path, updated)));
+    }
+
+    #[test]
+    fn invalidates_destroyed_storage_tries() {
+        let cache = TrieNodeCache::default();
+        let destroyed = B256::with_last_byte(1);
+        let retained = B256::with_last_byte(2);
+        for hashed_address in [destroyed, retained] {
+            cache.insert(TrieKey::StorageNode(hashed_address, vec![0x1].into()), None);
+        }
+
+        cache.invalidate(
+            &PrefixSetMut::default().freeze(),
+            &AHashMap::default(),
+            &AHashSet::from_iter([destroyed]),
+        );
+        assert_eq!(cache.len(), 1);
+        assert!(cache.get(&TrieKey::StorageNode(retained, vec![0x1].into())).is_some());
+    }
+
+    #[test]
+    fn clears_nodes_of_other_state_root() {
+        let cache = TrieNodeCache::default();
+        let root = B256::with_last_byte(1);
+        cache.insert(TrieKey::AccountNode(vec![0x1].into()), None);
+        cache.set_state_root(root);
+
+        cache.retain_if_state_root(root);
+        assert_eq!(cache.len(), 1);
+
+        cache.retain_if_state_root(B256::with_last_byte(2));
+        assert!(cache.is_empty());
+        assert_eq!(cache.state_root(), None);
+    }
+}
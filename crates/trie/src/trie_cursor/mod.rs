@@ -5,6 +5,7 @@ use reth_primitives::{
     B256,
 };
 
+mod cache;
 mod database_cursors;
 mod subnode;
 
@@ -12,6 +13,10 @@ mod subnode;
 pub mod noop;
 
 pub use self::{
+    cache::{
+        CachedAccountTrieCursor, CachedStorageTrieCursor, CachedTrieCursorFactory, TrieNodeCache,
+        DEFAULT_TRIE_NODE_CACHE_SIZE,
+    },
     database_cursors::{DatabaseAccountTrieCursor, DatabaseStorageTrieCursor},
     subnode::CursorSubNode,
 };
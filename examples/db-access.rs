@@ -1,4 +1,4 @@
-use reth_db::open_db_read_only;
+use reth_db::DatabaseEnv;
 use reth_primitives::{Address, ChainSpecBuilder, B256};
 use reth_provider::{
     AccountReader, BlockReader, BlockSource, HeaderProvider, ProviderFactory, ReceiptProvider,
@@ -15,15 +15,16 @@ use std::path::Path;
 // These abstractions do not include any caching and the user is responsible for doing that.
 // Other parts of the code which include caching are parts of the `EthApi` abstraction.
 fn main() -> eyre::Result<()> {
-    // Opens a RO handle to the database file.
-    // TODO: Should be able to do `ProviderFactory::new_with_db_path_ro(...)` instead of
-    // doing in 2 steps.
-    let db = open_db_read_only(Path::new(&std::env::var("RETH_DB_PATH")?), None)?;
-
-    // Instantiate a provider factory for Ethereum mainnet using the provided DB.
+    // Instantiate a provider factory for Ethereum mainnet that opens a RO handle to the database
+    // file, which can be done while the node is running.
     // TODO: Should the DB version include the spec so that you do not need to specify it here?
     let spec = ChainSpecBuilder::mainnet().build();
-    let factory = ProviderFactory::new(db, spec.into());
+    let factory = ProviderFactory::<DatabaseEnv>::new_with_database_path_read_only(
+        Path::new(&std::env::var("RETH_DB_PATH")?),
+        spec.into(),
+        None,
+        None,
+    )?;
 
     // This call opens a RO transaction on the database. To write to the DB you'd need to call
     // the `provider_rw` function and look for the `Writer` variants of the traits.
@@ -13,7 +13,8 @@ use once_cell::sync::Lazy;
 use reth_discv4::{DiscoveryUpdate, Discv4, Discv4ConfigBuilder, DEFAULT_DISCOVERY_ADDRESS};
 use reth_ecies::{stream::ECIESStream, util::pk2id};
 use reth_eth_wire::{
-    EthMessage, EthStream, HelloMessage, P2PStream, Status, UnauthedEthStream, UnauthedP2PStream,
+    BlockRangeUpdate, EthMessage, EthStream, HelloMessage, P2PStream, Status, UnauthedEthStream,
+    UnauthedP2PStream,
 };
 use reth_network::config::rng_secret_key;
 use reth_primitives::{
@@ -107,7 +108,9 @@ async fn handshake_eth(p2p_stream: AuthedP2PStream) -> eyre::Result<(AuthedEthSt
 
     let status = Status { version: p2p_stream.shared_capabilities().eth()?.version(), ..status };
     let eth_unauthed = UnauthedEthStream::new(p2p_stream);
-    Ok(eth_unauthed.handshake(status, fork_filter).await?)
+    // like the status, the block range only announces the genesis block
+    let block_range = BlockRangeUpdate { latest_hash: status.blockhash, ..Default::default() };
+    Ok(eth_unauthed.handshake(status, block_range, fork_filter).await?)
 }
 
 // Snoop by greedily capturing all broadcasts that the peer emits